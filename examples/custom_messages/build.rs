@@ -0,0 +1,23 @@
+use std::env;
+use std::path::PathBuf;
+
+use protocol_codegen::CustomOptions;
+
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    // Each option follows the feature of this crate of the same name
+    let feature = |name: &str| env::var_os(format!("CARGO_FEATURE_{}", name)).is_some();
+    let options = CustomOptions {
+        serde: feature("SERDE"),
+        json: feature("JSON"),
+        unstable_apis: feature("UNSTABLE_APIS"),
+    };
+    if let Err(e) = protocol_codegen::generate_custom(
+        "schemas".as_ref(),
+        &out_dir.join("custom_messages.rs"),
+        &options,
+    ) {
+        panic!("failed to generate messages: {}", e);
+    }
+    println!("cargo:rerun-if-changed=schemas");
+}
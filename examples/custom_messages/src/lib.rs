@@ -0,0 +1,9 @@
+//! Messages of two APIs added by a fork of Kafka, generated from the schemas in `schemas` by the
+//! build script, and usable with everything in `kafka-protocol` expecting a [`Request`].
+//!
+//! [`Request`]: kafka_protocol::protocol::Request
+
+/// The generated messages.
+pub mod messages {
+    include!(concat!(env!("OUT_DIR"), "/custom_messages.rs"));
+}
@@ -0,0 +1,97 @@
+use bytes::BytesMut;
+use custom_messages::messages::describe_tiering_request::DescribeTieringTopic;
+use custom_messages::messages::describe_tiering_response::{
+    DescribeTieringPartitionResult, DescribeTieringTopicResult,
+};
+use custom_messages::messages::{
+    DescribeTieringRequest, DescribeTieringResponse, PauseTieringRequest, TopicName,
+};
+use kafka_protocol::protocol::{
+    Decodable, Encodable, EncodeErrorKind, HeaderVersion, Message, Request, StrBytes,
+};
+
+fn round_trip<T: Encodable + Decodable>(message: &T, version: i16) -> T {
+    let mut buf = BytesMut::new();
+    message.encode(&mut buf, version).unwrap();
+    assert_eq!(buf.len(), message.compute_size(version).unwrap());
+    T::decode(&mut buf.freeze(), version).unwrap()
+}
+
+fn topic_name(name: &'static str) -> TopicName {
+    TopicName(StrBytes::from_str(name))
+}
+
+#[test]
+fn request_metadata() {
+    assert_eq!(DescribeTieringRequest::KEY, 1000);
+    assert_eq!(PauseTieringRequest::KEY, 1001);
+    assert_eq!(DescribeTieringRequest::VERSIONS.max, 2);
+    assert_eq!(DescribeTieringRequest::header_version(0), 1);
+    assert_eq!(DescribeTieringRequest::header_version(1), 2);
+    assert_eq!(DescribeTieringResponse::header_version(1), 1);
+    assert_eq!(PauseTieringRequest::header_version(0), 2);
+}
+
+#[test]
+fn request_round_trip() {
+    let mut topic = DescribeTieringTopic::default();
+    topic.name = topic_name("events");
+    topic.partitions = vec![0, 1, 2];
+    let mut request = DescribeTieringRequest::default();
+    request.topics.push(topic);
+
+    assert_eq!(round_trip(&request, 0), request);
+
+    request.include_segments = true;
+    assert_eq!(round_trip(&request, 1), request);
+    // The tagged field only exists from version 1
+    assert!(!round_trip(&request, 0).include_segments);
+}
+
+#[test]
+fn response_round_trip() {
+    let mut partition = DescribeTieringPartitionResult::default();
+    partition.partition_index = 3;
+    partition.remote_bytes = 1 << 40;
+    let mut topic = DescribeTieringTopicResult::default();
+    topic.partitions.push(partition);
+    let mut response = DescribeTieringResponse::default();
+    response.throttle_time_ms = 10;
+    response.topics.insert(topic_name("events"), topic);
+
+    for version in 0..=1 {
+        assert_eq!(round_trip(&response, version), response);
+    }
+}
+
+#[test]
+fn null_only_encodes_in_nullable_versions() {
+    let mut request = DescribeTieringRequest::default();
+    assert_eq!(request.rack_id, Some(StrBytes::from_str("")));
+    request.rack_id = None;
+    assert_eq!(round_trip(&request, 1), request);
+    let err = request.encode(&mut BytesMut::new(), 0).unwrap_err();
+    assert_eq!(err.kind(), EncodeErrorKind::NullNotValidAtVersion);
+}
+
+#[test]
+fn null_only_encodes_in_nullable_versions_of_tagged_fields() {
+    let mut request = DescribeTieringRequest::default();
+    assert_eq!(request.segment_prefix, Some(StrBytes::from_str("")));
+    request.segment_prefix = None;
+    assert_eq!(round_trip(&request, 2), request);
+    let err = request.encode(&mut BytesMut::new(), 1).unwrap_err();
+    assert_eq!(err.kind(), EncodeErrorKind::NullNotValidAtVersion);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_round_trip() {
+    let mut request = DescribeTieringRequest::default();
+    request.include_segments = true;
+    let json = request.to_json(1);
+    assert_eq!(
+        DescribeTieringRequest::from_json(&json, 1).unwrap(),
+        request
+    );
+}
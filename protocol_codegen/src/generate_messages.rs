@@ -1,29 +1,101 @@
 use std::collections::{BTreeMap, BTreeSet};
-use std::fs::{self, File};
+use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use failure::Error;
-use git2::{Oid, Repository};
 
 use std::path::MAIN_SEPARATOR;
 
+mod api_families;
+mod check;
 mod code_writer;
+pub mod compat;
+pub mod custom;
+mod error_response;
 pub mod expr;
 mod generate;
+mod internal_topics;
+mod metadata;
 mod parse;
 mod spec;
 
-use spec::SpecType;
+use generate::Target;
+use spec::{ListenerSpec, PrimitiveType, Spec, SpecType, VersionSpec};
 
-pub fn run() -> Result<(), Error> {
-    let mut dir = std::fs::canonicalize(std::file!().rsplit_once(MAIN_SEPARATOR).unwrap().0)?;
-    dir.push(format!("..{separator}..{separator}src{separator}messages", separator = MAIN_SEPARATOR));
-    let output_path = std::fs::canonicalize(dir)?;
-    let output_path = output_path.to_str().unwrap();
+/// The Kafka release the checked-in messages are generated from.
+///
+/// https://github.com/apache/kafka/releases/tag/3.6.0
+/// checking out a tag with git2 is annoying -- we pin to the tag's commit sha instead
+pub const KAFKA_RELEASE_COMMIT: &str = "60e845626d8a465a8cfe68bb2d7d4b88d622634e";
+
+/// Path of the client message schemas within the Kafka repo.
+pub const MESSAGE_SCHEMA_PATH: &str = "clients/src/main/resources/common/message";
+
+/// Path of the KRaft cluster metadata record schemas within the Kafka repo.
+pub const METADATA_SCHEMA_PATH: &str = "metadata/src/main/resources/common/metadata";
+
+/// The directories holding each kind of JSON schema.
+#[derive(Debug, Clone)]
+pub struct SchemaDirs {
+    /// The `*.json` message schemas, e.g. a vendored copy of [`MESSAGE_SCHEMA_PATH`] from the Kafka repo.
+    pub messages: PathBuf,
+    /// The `*.json` cluster metadata record schemas ([`METADATA_SCHEMA_PATH`]). The `metadata` module is left
+    /// untouched if this is not given.
+    pub metadata: Option<PathBuf>,
+    /// The `*.json` schemas of the `__consumer_offsets` records. The `consumer_offsets` module is left
+    /// untouched if this is not given.
+    pub consumer_offsets: Option<PathBuf>,
+    /// The `*.json` schemas of the `__transaction_state` records. The `transaction_state` module is left
+    /// untouched if this is not given.
+    pub transaction_state: Option<PathBuf>,
+}
+
+/// Where the JSON schemas are read from.
+#[derive(Debug, Clone)]
+pub enum SchemaSource {
+    /// Local directories containing the schemas.
+    Dir(SchemaDirs),
+    /// Clone (or fetch) the Kafka repo into `repo_dir` and check out `commit`.
+    #[cfg(feature = "git")]
+    Git {
+        /// Directory the Kafka repo is cloned into.
+        repo_dir: PathBuf,
+        /// Commit to check out.
+        commit: String,
+    },
+}
+
+impl SchemaSource {
+    /// Resolve this source to directories of schemas, downloading them if necessary.
+    pub fn resolve(&self) -> Result<SchemaDirs, Error> {
+        match self {
+            SchemaSource::Dir(dirs) => Ok(dirs.clone()),
+            #[cfg(feature = "git")]
+            SchemaSource::Git { repo_dir, commit } => {
+                checkout_kafka_repo(repo_dir, commit)?;
+                let repo_path =
+                    |path: &str| repo_dir.join(path.replace('/', std::path::MAIN_SEPARATOR_STR));
+                Ok(SchemaDirs {
+                    messages: repo_path(MESSAGE_SCHEMA_PATH),
+                    metadata: Some(repo_path(METADATA_SCHEMA_PATH)),
+                    consumer_offsets: Some(repo_path(
+                        internal_topics::CONSUMER_OFFSETS.schema_path,
+                    )),
+                    transaction_state: Some(repo_path(
+                        internal_topics::TRANSACTION_STATE.schema_path,
+                    )),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(feature = "git")]
+fn checkout_kafka_repo(kafka_repo: &Path, release_commit: &str) -> Result<(), Error> {
+    use git2::{Oid, Repository};
 
     // Download messages from head of Kafka repo
-    let kafka_repo = Path::new("kafka_repo");
     let repo = if kafka_repo.exists() {
         println!("Fetching latest kafka repo");
         let repo = Repository::open(kafka_repo)?;
@@ -41,32 +113,102 @@ pub fn run() -> Result<(), Error> {
     };
 
     // Checkout the release commit
-    // https://github.com/apache/kafka/releases/tag/3.6.0
-    // checking out a tag with git2 is annoying -- we pin to the tag's commit sha instead
-    let release_commit = "60e845626d8a465a8cfe68bb2d7d4b88d622634e";
     println!("Checking out release {}", release_commit);
-    let oid = Oid::from_str(release_commit).unwrap();
+    let oid = Oid::from_str(release_commit)?;
     let commit = repo
         .find_commit(oid)
         .expect("Could not find release commit!")
         .into_object();
     repo.checkout_tree(&commit, None).unwrap();
     repo.set_head_detached(commit.id()).unwrap();
+    Ok(())
+}
 
-    // Clear output directory
-    for file in fs::read_dir(output_path)? {
-        let file = file?;
-        if file.file_type()?.is_file() {
-            let path = file.path();
-            if path.extension() == Some("rs".as_ref()) {
-                fs::remove_file(path)?;
+/// The crate's `src` directory, which the generated messages are written into by default.
+pub fn default_output_dir() -> Result<PathBuf, Error> {
+    let mut dir = std::fs::canonicalize(std::file!().rsplit_once(MAIN_SEPARATOR).unwrap().0)?;
+    dir.push(format!(
+        "..{separator}..{separator}src",
+        separator = MAIN_SEPARATOR
+    ));
+    Ok(std::fs::canonicalize(dir)?)
+}
+
+/// Generate the messages from `source` into `output_dir`.
+///
+/// `output_dir` receives `messages.rs` and a `messages` directory holding one module per schema.
+pub fn run(source: &SchemaSource, output_dir: &Path) -> Result<(), Error> {
+    let files = generate_files(source)?;
+    write_files(&files, output_dir)
+}
+
+/// Check that the messages in `output_dir` match what would be generated from `source`.
+///
+/// Prints a summary of every file that differs and returns whether everything was up to date.
+pub fn check(source: &SchemaSource, output_dir: &Path) -> Result<bool, Error> {
+    let files = generate_files(source)?;
+    check::check_files(&files, output_dir)
+}
+
+/// The `*.rs` files currently in the directories of `output_dir` that `files` are generated into.
+fn existing_generated_files(
+    files: &BTreeMap<PathBuf, Vec<u8>>,
+    output_dir: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    let dirs: BTreeSet<_> = files
+        .keys()
+        .filter_map(|path| path.parent())
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .collect();
+    let mut paths = Vec::new();
+    for dir in dirs {
+        let dir = output_dir.join(dir);
+        if !dir.exists() {
+            continue;
+        }
+        for file in fs::read_dir(dir)? {
+            let file = file?;
+            if file.file_type()?.is_file() {
+                let path = file.path();
+                if path.extension() == Some("rs".as_ref()) {
+                    paths.push(path);
+                }
             }
         }
     }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Write generated files into `output_dir`, replacing any previously generated modules.
+pub fn write_files(files: &BTreeMap<PathBuf, Vec<u8>>, output_dir: &Path) -> Result<(), Error> {
+    // Clear output directory
+    for path in existing_generated_files(files, output_dir)? {
+        fs::remove_file(path)?;
+    }
+
+    for (path, contents) in files {
+        let path = output_dir.join(path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, contents)?;
+    }
+    Ok(())
+}
+
+/// Parse every `*.json` schema in `dir`, in file name order.
+fn read_specs(dir: &Path) -> Result<Vec<Spec>, Error> {
+    if !dir.is_dir() {
+        return Err(failure::format_err!(
+            "schema directory {} does not exist",
+            dir.display()
+        ));
+    }
 
     // Find input files
     let mut input_file_paths = Vec::new();
-    for file in fs::read_dir(kafka_repo.join(format!("clients{separator}src{separator}main{separator}resources{separator}common{separator}message", separator = MAIN_SEPARATOR)))? {
+    for file in fs::read_dir(dir)? {
         let file = file?;
         if file.file_type()?.is_file() {
             let path = file.path();
@@ -76,16 +218,43 @@ pub fn run() -> Result<(), Error> {
         }
     }
     input_file_paths.sort();
+    if input_file_paths.is_empty() {
+        return Err(failure::format_err!(
+            "no schemas found in {}",
+            dir.display()
+        ));
+    }
+
+    input_file_paths
+        .iter()
+        .map(|path| parse::parse(path))
+        .collect()
+}
+
+/// Generate the messages from `source` in memory.
+///
+/// The returned paths are relative to the output directory: `messages.rs` and `messages/<module>.rs`,
+/// plus `metadata.rs`, `consumer_offsets.rs` and `transaction_state.rs` and their directories of modules if
+/// the respective schemas are available.
+pub fn generate_files(source: &SchemaSource) -> Result<BTreeMap<PathBuf, Vec<u8>>, Error> {
+    let schema_dirs = source.resolve()?;
+    let specs = read_specs(&schema_dirs.messages)?;
+
     let mut entity_types = BTreeSet::new();
     let mut request_types = BTreeMap::new();
     let mut response_types = BTreeMap::new();
+    let mut request_specs = BTreeMap::new();
 
-    let module_path = format!("{}.rs", output_path);
-    let mut module_file = File::create(&module_path)?;
+    let mut files = BTreeMap::new();
+    let mut module_file = Vec::new();
 
     writeln!(module_file, "//! Messages used by the Kafka protocol.")?;
     writeln!(module_file, "//!")?;
     writeln!(module_file, "//! These messages are generated programmatically. See the [Kafka's protocol documentation](https://kafka.apache.org/protocol.html) for more information about a given message type.")?;
+    writeln!(module_file, "//!")?;
+    writeln!(module_file, "//! The requests and responses of each API family are compiled behind a cargo feature: `client-core`, `admin`,")?;
+    writeln!(module_file, "//! `consumer-group`, `transactions`, `broker-internal` and `raft`, all of which are enabled by default.")?;
+    writeln!(module_file, "//! `ApiVersions` is always compiled.")?;
     writeln!(
         module_file,
         "// WARNING: the items of this module are generated and should not be edited directly."
@@ -93,16 +262,70 @@ pub fn run() -> Result<(), Error> {
     writeln!(module_file)?;
     writeln!(
         module_file,
-        "use crate::protocol::{{NewType, Request, StrBytes, HeaderVersion}};"
+        "use crate::protocol::{{NewType, Request, StrBytes, HeaderVersion, Message, VersionRange, ListenerType}};"
     )?;
     writeln!(module_file, "use std::convert::TryFrom;")?;
     writeln!(module_file)?;
 
-    for input_file_path in &input_file_paths {
-        let spec = parse::parse(input_file_path)?;
+    let response_specs: BTreeMap<_, _> = specs
+        .iter()
+        .filter(|spec| spec.type_ == SpecType::Response)
+        .filter_map(|spec| spec.api_key.map(|k| (k, spec.clone())))
+        .collect();
+    let features: BTreeMap<_, _> = specs
+        .iter()
+        .filter(|spec| spec.type_ == SpecType::Request)
+        .filter_map(|spec| {
+            let name = spec.name.trim_end_matches("Request");
+            let listeners = spec.listeners.as_deref().unwrap_or_default();
+            spec.api_key
+                .map(|k| Ok((k, api_families::feature_for(name, listeners)?)))
+        })
+        .collect::<Result<_, Error>>()?;
+    let feature = |api_key: &i16| features.get(api_key).copied().flatten();
+
+    let unstable_api_keys: BTreeSet<_> = specs
+        .iter()
+        .filter(|spec| {
+            spec.type_ == SpecType::Request && spec.latest_version_unstable == Some(true)
+        })
+        .filter_map(|spec| spec.api_key)
+        .collect();
+
+    for mut spec in specs {
         let spec_meta = (spec.type_, spec.api_key);
-        let (module_name, struct_name) = generate::generate(output_path, spec, &mut entity_types)?;
-		
+        // Only requests are marked as unstable in the schemas, but their responses share versions
+        if let (SpecType::Response, Some(k)) = spec_meta {
+            if unstable_api_keys.contains(&k) {
+                spec.latest_version_unstable = Some(true);
+            }
+        }
+        if let (SpecType::Request, Some(k)) = spec_meta {
+            request_specs.insert(
+                k,
+                (
+                    spec.valid_versions,
+                    spec.flexible_versions.unwrap_or_default(),
+                    spec.listeners.clone().unwrap_or_default(),
+                ),
+            );
+        }
+        let response_spec = match spec_meta {
+            (SpecType::Request, Some(k)) => response_specs.get(&k),
+            _ => None,
+        };
+        let (module_name, struct_name, contents) = generate::generate(
+            spec,
+            response_spec,
+            MESSAGE_SCHEMA_PATH,
+            Target::Runtime,
+            &mut entity_types,
+        )?;
+        files.insert(
+            Path::new("messages").join(format!("{}.rs", module_name)),
+            contents,
+        );
+
         match spec_meta {
             (SpecType::Request, Some(k)) => {
                 request_types.insert(k, struct_name.clone());
@@ -113,15 +336,26 @@ pub fn run() -> Result<(), Error> {
             _ => {}
         }
 
+        let module_feature = match spec_meta {
+            (SpecType::Request | SpecType::Response, Some(k)) => feature(&k),
+            _ => None,
+        };
+        api_families::write_cfg(&mut module_file, module_feature, "")?;
         writeln!(module_file, "pub mod {};", module_name)?;
+        api_families::write_cfg(&mut module_file, module_feature, "")?;
         writeln!(module_file, "pub use {}::{};", module_name, struct_name)?;
         writeln!(module_file)?;
     }
 
     for (api_key, request_type) in request_types.iter() {
-        let response_type = response_types
-            .get(api_key)
-            .expect("Every request type has a response type");
+        let response_type = response_types.get(api_key).ok_or_else(|| {
+            failure::format_err!(
+                "{}.json has no response schema with apiKey {}",
+                request_type,
+                api_key
+            )
+        })?;
+        api_families::write_cfg(&mut module_file, feature(api_key), "")?;
         writeln!(module_file, "impl Request for {} {{", request_type)?;
         writeln!(module_file, "    const KEY: i16 = {};", api_key)?;
         writeln!(module_file, "    type Response = {};", response_type)?;
@@ -130,10 +364,18 @@ pub fn run() -> Result<(), Error> {
     }
 
     writeln!(module_file, "/// Valid API keys in the Kafka protocol.")?;
-    writeln!(module_file, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]")?;
+    writeln!(
+        module_file,
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]"
+    )?;
+    writeln!(
+        module_file,
+        "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]"
+    )?;
     writeln!(module_file, "pub enum ApiKey {{")?;
     for (api_key, request_type) in request_types.iter() {
         writeln!(module_file, "    /// API key for request {}", request_type)?;
+        api_families::write_cfg(&mut module_file, feature(api_key), "    ")?;
         writeln!(
             module_file,
             "    {} = {},",
@@ -154,7 +396,8 @@ pub fn run() -> Result<(), Error> {
         "    pub fn request_header_version(&self, version: i16) -> i16 {{"
     )?;
     writeln!(module_file, "        match self {{")?;
-    for request_type in request_types.values() {
+    for (api_key, request_type) in request_types.iter() {
+        api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
         writeln!(
             module_file,
             "            ApiKey::{} => {}::header_version(version),",
@@ -174,7 +417,8 @@ pub fn run() -> Result<(), Error> {
         "    pub fn response_header_version(&self, version: i16) -> i16 {{"
     )?;
     writeln!(module_file, "        match self {{")?;
-    for response_type in response_types.values() {
+    for (api_key, response_type) in response_types.iter() {
+        api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
         writeln!(
             module_file,
             "            ApiKey::{} => {}::header_version(version),",
@@ -184,6 +428,219 @@ pub fn run() -> Result<(), Error> {
     }
     writeln!(module_file, "        }}")?;
     writeln!(module_file, "    }}")?;
+    writeln!(module_file)?;
+
+    writeln!(
+        module_file,
+        "    /// All API keys known to this crate, in ascending order."
+    )?;
+    writeln!(module_file, "    pub fn all() -> &'static [ApiKey] {{")?;
+    writeln!(module_file, "        &[")?;
+    for (api_key, request_type) in request_types.iter() {
+        api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
+        writeln!(
+            module_file,
+            "            ApiKey::{},",
+            request_type.replace("Request", "Key")
+        )?;
+    }
+    writeln!(module_file, "        ]")?;
+    writeln!(module_file, "    }}")?;
+    writeln!(module_file)?;
+
+    writeln!(
+        module_file,
+        "    /// The name of this API as used by Kafka, e.g. `Produce`."
+    )?;
+    writeln!(module_file, "    pub fn name(&self) -> &'static str {{")?;
+    writeln!(module_file, "        match self {{")?;
+    for (api_key, request_type) in request_types.iter() {
+        api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
+        writeln!(
+            module_file,
+            "            ApiKey::{} => \"{}\",",
+            request_type.replace("Request", "Key"),
+            request_type.trim_end_matches("Request")
+        )?;
+    }
+    writeln!(module_file, "        }}")?;
+    writeln!(module_file, "    }}")?;
+    writeln!(module_file)?;
+
+    writeln!(
+        module_file,
+        "    /// The versions of this API supported by this crate."
+    )?;
+    writeln!(
+        module_file,
+        "    pub fn valid_versions(&self) -> VersionRange {{"
+    )?;
+    writeln!(module_file, "        match self {{")?;
+    for (api_key, request_type) in request_types.iter() {
+        api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
+        writeln!(
+            module_file,
+            "            ApiKey::{} => {}::VERSIONS,",
+            request_type.replace("Request", "Key"),
+            request_type
+        )?;
+    }
+    writeln!(module_file, "        }}")?;
+    writeln!(module_file, "    }}")?;
+    writeln!(module_file)?;
+
+    writeln!(
+        module_file,
+        "    /// The versions of this API that use the flexible encoding (compact types and tagged fields)."
+    )?;
+    writeln!(module_file, "    ///")?;
+    writeln!(
+        module_file,
+        "    /// An API with no flexible versions returns an empty range. Like [`ApiKey::valid_versions`], the"
+    )?;
+    writeln!(
+        module_file,
+        "    /// range only includes unstable versions if the `unstable-apis` feature is enabled."
+    )?;
+    writeln!(
+        module_file,
+        "    pub fn flexible_versions(&self) -> VersionRange {{"
+    )?;
+    writeln!(module_file, "        match self {{")?;
+    for (api_key, request_type) in request_types.iter() {
+        let (valid_versions, flexible_versions, _) = &request_specs[api_key];
+        let key_name = request_type.replace("Request", "Key");
+        let flexible_range =
+            |valid_versions: VersionSpec| match flexible_versions.intersect(valid_versions) {
+                VersionSpec::None => (0, -1),
+                VersionSpec::Exact(v) => (v, v),
+                VersionSpec::Range(a, b) => (a, b),
+                VersionSpec::Since(_) => panic!("valid versions of {} are unbounded", request_type),
+            };
+        let (min, max) = flexible_range(*valid_versions);
+        let stable_range = match valid_versions.range() {
+            Some(range) if unstable_api_keys.contains(api_key) => {
+                let stable_versions = if range.start() < range.end() {
+                    VersionSpec::Range(*range.start(), range.end() - 1)
+                } else {
+                    VersionSpec::None
+                };
+                Some(flexible_range(stable_versions))
+            }
+            _ => None,
+        };
+        match stable_range {
+            // The unstable version is only valid with the `unstable-apis` feature, so must only be
+            // reported as flexible with it
+            Some((stable_min, stable_max)) if (stable_min, stable_max) != (min, max) => {
+                let features =
+                    feature(api_key).map_or(String::new(), |f| format!("feature = \"{}\", ", f));
+                writeln!(
+                    module_file,
+                    "            #[cfg(all({}feature = \"unstable-apis\"))]",
+                    features
+                )?;
+                writeln!(
+                    module_file,
+                    "            ApiKey::{} => VersionRange {{ min: {}, max: {} }},",
+                    key_name, min, max
+                )?;
+                writeln!(
+                    module_file,
+                    "            #[cfg(all({}not(feature = \"unstable-apis\")))]",
+                    features
+                )?;
+                writeln!(
+                    module_file,
+                    "            ApiKey::{} => VersionRange {{ min: {}, max: {} }},",
+                    key_name, stable_min, stable_max
+                )?;
+            }
+            _ => {
+                api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
+                writeln!(
+                    module_file,
+                    "            ApiKey::{} => VersionRange {{ min: {}, max: {} }},",
+                    key_name, min, max
+                )?;
+            }
+        }
+    }
+    writeln!(module_file, "        }}")?;
+    writeln!(module_file, "    }}")?;
+    writeln!(module_file)?;
+
+    writeln!(
+        module_file,
+        "    /// The versions of this API that are unstable, i.e. still under development upstream and subject"
+    )?;
+    writeln!(
+        module_file,
+        "    /// to incompatible changes. They are only part of [`ApiKey::valid_versions`] if the `unstable-apis`"
+    )?;
+    writeln!(module_file, "    /// feature is enabled.")?;
+    writeln!(
+        module_file,
+        "    pub fn unstable_versions(&self) -> Option<VersionRange> {{"
+    )?;
+    writeln!(module_file, "        match self {{")?;
+    for (api_key, request_type) in request_types.iter() {
+        api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
+        writeln!(
+            module_file,
+            "            ApiKey::{} => {}::UNSTABLE_VERSIONS,",
+            request_type.replace("Request", "Key"),
+            request_type
+        )?;
+    }
+    writeln!(module_file, "        }}")?;
+    writeln!(module_file, "    }}")?;
+    writeln!(module_file)?;
+
+    writeln!(
+        module_file,
+        "    /// Whether the given version of this API is unstable, see [`ApiKey::unstable_versions`]."
+    )?;
+    writeln!(
+        module_file,
+        "    pub fn is_unstable(&self, version: i16) -> bool {{"
+    )?;
+    writeln!(
+        module_file,
+        "        self.unstable_versions().map_or(false, |versions| versions.contains(version))"
+    )?;
+    writeln!(module_file, "    }}")?;
+    writeln!(module_file)?;
+
+    writeln!(
+        module_file,
+        "    /// The listeners on which this API is exposed by a Kafka node."
+    )?;
+    writeln!(
+        module_file,
+        "    pub fn listeners(&self) -> &'static [ListenerType] {{"
+    )?;
+    writeln!(module_file, "        match self {{")?;
+    for (api_key, request_type) in request_types.iter() {
+        let (_, _, listeners) = &request_specs[api_key];
+        let listeners: Vec<_> = listeners
+            .iter()
+            .map(|l| match l {
+                ListenerSpec::ZkBroker => "ListenerType::ZkBroker",
+                ListenerSpec::Broker => "ListenerType::Broker",
+                ListenerSpec::Controller => "ListenerType::Controller",
+            })
+            .collect();
+        api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
+        writeln!(
+            module_file,
+            "            ApiKey::{} => &[{}],",
+            request_type.replace("Request", "Key"),
+            listeners.join(", ")
+        )?;
+    }
+    writeln!(module_file, "        }}")?;
+    writeln!(module_file, "    }}")?;
     writeln!(module_file, "}}")?;
 
     writeln!(module_file, "impl TryFrom<i16> for ApiKey {{")?;
@@ -194,8 +651,9 @@ pub fn run() -> Result<(), Error> {
         "    fn try_from(v: i16) -> Result<Self, Self::Error> {{"
     )?;
     writeln!(module_file, "        match v {{")?;
-    for (_, request_type) in request_types.iter() {
+    for (api_key, request_type) in request_types.iter() {
         let key = request_type.replace("Request", "Key");
+        api_families::write_cfg(&mut module_file, feature(api_key), "            ")?;
         writeln!(
             module_file,
             "            x if x == ApiKey::{} as i16 => Ok(ApiKey::{}),",
@@ -214,9 +672,14 @@ pub fn run() -> Result<(), Error> {
     )?;
     writeln!(module_file, "#[non_exhaustive]")?;
     writeln!(module_file, "#[derive(Debug, Clone, PartialEq)]")?;
+    writeln!(
+        module_file,
+        "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]"
+    )?;
     writeln!(module_file, "pub enum RequestKind {{")?;
-    for (_, request_type) in request_types.iter() {
+    for (api_key, request_type) in request_types.iter() {
         writeln!(module_file, "    /// {},", request_type)?;
+        api_families::write_cfg(&mut module_file, feature(api_key), "    ")?;
         writeln!(module_file, "    {}({}),", request_type, request_type)?;
     }
     writeln!(module_file, "}}")?;
@@ -228,14 +691,53 @@ pub fn run() -> Result<(), Error> {
     )?;
     writeln!(module_file, "#[non_exhaustive]")?;
     writeln!(module_file, "#[derive(Debug, Clone, PartialEq)]")?;
+    writeln!(
+        module_file,
+        "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]"
+    )?;
     writeln!(module_file, "pub enum ResponseKind {{")?;
-    for (_, response_type) in response_types.iter() {
+    for (api_key, response_type) in response_types.iter() {
         writeln!(module_file, "    /// {},", response_type)?;
+        api_families::write_cfg(&mut module_file, feature(api_key), "    ")?;
         writeln!(module_file, "    {}({}),", response_type, response_type)?;
     }
     writeln!(module_file, "}}")?;
     writeln!(module_file)?;
 
+    if let Some(metadata_dir) = &schema_dirs.metadata {
+        let metadata_entity_types =
+            metadata::generate_metadata(read_specs(metadata_dir)?, &mut files)?;
+        entity_types.extend(metadata_entity_types);
+    }
+    for (dir, topic) in [
+        (
+            &schema_dirs.consumer_offsets,
+            &internal_topics::CONSUMER_OFFSETS,
+        ),
+        (
+            &schema_dirs.transaction_state,
+            &internal_topics::TRANSACTION_STATE,
+        ),
+    ] {
+        if let Some(dir) = dir {
+            let topic_entity_types =
+                internal_topics::generate_internal_topic(topic, read_specs(dir)?, &mut files)?;
+            entity_types.extend(topic_entity_types);
+        }
+    }
+
+    write_entity_types(&mut module_file, &entity_types, Target::Runtime)?;
+
+    files.insert(PathBuf::from("messages.rs"), module_file);
+    Ok(files)
+}
+
+/// Write the newtype definitions of `entity_types`, to be compiled as part of `target`.
+fn write_entity_types<W: Write>(
+    w: &mut W,
+    entity_types: &BTreeSet<generate::EntityType>,
+    target: Target<'_>,
+) -> Result<(), Error> {
     for entity_type in entity_types {
         let mut derives = vec![
             "Debug",
@@ -253,85 +755,98 @@ pub fn run() -> Result<(), Error> {
 
         let rust_name = entity_type.inner.rust_name();
 
-        writeln!(module_file, "/// {}", entity_type.doc)?;
-        writeln!(module_file, "#[derive({})]", derives.join(", "))?;
-        writeln!(
-            module_file,
-            "pub struct {}(pub {});\n",
-            entity_type.name, rust_name
-        )?;
+        writeln!(w, "/// {}", entity_type.doc)?;
+        writeln!(w, "#[derive({})]", derives.join(", "))?;
+        target.write_serde_derive(w)?;
+        if let Some(attr) = target.cfg_attr("serde", "serde(transparent)")? {
+            writeln!(w, "{}", attr)?;
+        }
+        let serde_with = if entity_type.inner == PrimitiveType::String {
+            let with = format!(
+                "serde(with = \"{}::serde_str_bytes\")",
+                target.internals("protocol")
+            );
+            target
+                .cfg_attr("serde", &with)?
+                .map_or(String::new(), |attr| attr + " ")
+        } else {
+            String::new()
+        };
         writeln!(
-            module_file,
-            "impl From<{}> for {} {{",
-            rust_name, entity_type.name
+            w,
+            "pub struct {}({}pub {});\n",
+            entity_type.name, serde_with, rust_name
         )?;
+        writeln!(w, "impl From<{}> for {} {{", rust_name, entity_type.name)?;
         writeln!(
-            module_file,
+            w,
             "    fn from(other: {}) -> Self {{ Self(other) }}",
             rust_name
         )?;
-        writeln!(module_file, "}}")?;
-        writeln!(
-            module_file,
-            "impl From<{}> for {} {{",
-            entity_type.name, rust_name
-        )?;
+        writeln!(w, "}}")?;
+        writeln!(w, "impl From<{}> for {} {{", entity_type.name, rust_name)?;
         writeln!(
-            module_file,
+            w,
             "    fn from(other: {}) -> Self {{ other.0 }}",
             entity_type.name
         )?;
-        writeln!(module_file, "}}")?;
+        writeln!(w, "}}")?;
         writeln!(
-            module_file,
+            w,
             "impl std::borrow::Borrow<{}> for {} {{",
             rust_name, entity_type.name
         )?;
+        writeln!(w, "    fn borrow(&self) -> &{} {{ &self.0 }}", rust_name)?;
+        writeln!(w, "}}")?;
+        writeln!(w, "impl std::ops::Deref for {} {{", entity_type.name)?;
+        writeln!(w, "    type Target = {};", rust_name)?;
+        writeln!(w, "    fn deref(&self) -> &Self::Target {{ &self.0 }}")?;
+        writeln!(w, "}}")?;
         writeln!(
-            module_file,
-            "    fn borrow(&self) -> &{} {{ &self.0 }}",
-            rust_name
-        )?;
-        writeln!(module_file, "}}")?;
-        writeln!(
-            module_file,
-            "impl std::ops::Deref for {} {{",
-            entity_type.name
-        )?;
-        writeln!(module_file, "    type Target = {};", rust_name)?;
-        writeln!(
-            module_file,
-            "    fn deref(&self) -> &Self::Target {{ &self.0 }}"
-        )?;
-        writeln!(module_file, "}}")?;
-        writeln!(
-            module_file,
+            w,
             "impl std::cmp::PartialEq<{}> for {} {{",
             rust_name, entity_type.name
         )?;
         writeln!(
-            module_file,
+            w,
             "    fn eq(&self, other: &{}) -> bool {{ &self.0 == other }}",
             rust_name
         )?;
-        writeln!(module_file, "}}")?;
+        writeln!(w, "}}")?;
         writeln!(
-            module_file,
+            w,
             "impl std::cmp::PartialEq<{}> for {} {{",
             entity_type.name, rust_name
         )?;
         writeln!(
-            module_file,
+            w,
             "    fn eq(&self, other: &{}) -> bool {{ self == &other.0 }}",
             entity_type.name
         )?;
-        writeln!(module_file, "}}")?;
+        writeln!(w, "}}")?;
         writeln!(
-            module_file,
+            w,
             "impl NewType<{}> for {} {{}}",
             rust_name, entity_type.name
         )?;
-        writeln!(module_file)?;
+        if target.write_cfg(w, "json", true)? {
+            let json = target.internals("json");
+            writeln!(w, "impl {}::JsonValue for {} {{", json, entity_type.name)?;
+            writeln!(
+                w,
+                "    fn to_json(&self) -> serde_json::Value {{ {}::JsonValue::to_json(&self.0) }}",
+                json
+            )?;
+            writeln!(
+                w,
+                "    fn from_json(node: &serde_json::Value, about: &str) -> Result<Self, {}::json::JsonError> {{ <{} as {json}::JsonValue>::from_json(node, about).map(Self) }}",
+                target.runtime(),
+                rust_name,
+                json = json
+            )?;
+            writeln!(w, "}}")?;
+        }
+        writeln!(w)?;
     }
 
     Ok(())
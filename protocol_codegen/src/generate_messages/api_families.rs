@@ -0,0 +1,166 @@
+use std::io::Write;
+
+use failure::{format_err, Error};
+
+use super::spec::ListenerSpec;
+
+/// The feature of the APIs a broker exposes to clients, unless an [`Override`] assigns them to
+/// a narrower family.
+pub const ADMIN: &str = "admin";
+
+/// The feature of the APIs a broker does not expose to clients, which are only sent by other
+/// Kafka nodes.
+pub const BROKER_INTERNAL: &str = "broker-internal";
+
+/// APIs which are compiled regardless of the enabled features, as every connection needs them to
+/// negotiate versions.
+pub const ALWAYS_ENABLED: &[&str] = &["ApiVersions"];
+
+/// A family of APIs which the listeners cannot tell apart from the other APIs exposed on the same
+/// listener, compiled behind its own cargo feature.
+pub struct Override {
+    /// The cargo feature enabling the family.
+    pub feature: &'static str,
+    /// The listener every API of the family is exposed on.
+    pub listener: ListenerSpec,
+    /// The names of the APIs in the family, e.g. `Produce`.
+    pub apis: &'static [&'static str],
+}
+
+/// The APIs producers and consumers need outside of a group: these share the broker listener with
+/// every admin API.
+const CLIENT_CORE: Override = Override {
+    feature: "client-core",
+    listener: ListenerSpec::Broker,
+    apis: &[
+        "Produce",
+        "Fetch",
+        "ListOffsets",
+        "Metadata",
+        "OffsetForLeaderEpoch",
+        "FindCoordinator",
+        "SaslHandshake",
+        "SaslAuthenticate",
+    ],
+};
+
+/// The APIs of the group coordinator, which a broker exposes on the same listener as the admin
+/// APIs.
+const CONSUMER_GROUP: Override = Override {
+    feature: "consumer-group",
+    listener: ListenerSpec::Broker,
+    apis: &[
+        "OffsetCommit",
+        "OffsetFetch",
+        "JoinGroup",
+        "Heartbeat",
+        "LeaveGroup",
+        "SyncGroup",
+        "DescribeGroups",
+        "ListGroups",
+        "DeleteGroups",
+        "OffsetDelete",
+        "ConsumerGroupHeartbeat",
+        "ConsumerGroupDescribe",
+    ],
+};
+
+/// The APIs of the transaction coordinator. Even `WriteTxnMarkers`, which only the coordinator
+/// sends to partition leaders, is exposed on the broker listener.
+const TRANSACTIONS: Override = Override {
+    feature: "transactions",
+    listener: ListenerSpec::Broker,
+    apis: &[
+        "InitProducerId",
+        "AddPartitionsToTxn",
+        "AddOffsetsToTxn",
+        "EndTxn",
+        "WriteTxnMarkers",
+        "TxnOffsetCommit",
+        "DescribeTransactions",
+        "ListTransactions",
+        "DescribeProducers",
+    ],
+};
+
+/// The APIs of the KRaft quorum. They are exposed on the controller listener only, like the APIs
+/// brokers use to register with the controller, except `DescribeQuorum`, which brokers forward
+/// to the controller like an admin API.
+const RAFT: Override = Override {
+    feature: "raft",
+    listener: ListenerSpec::Controller,
+    apis: &[
+        "Vote",
+        "BeginQuorumEpoch",
+        "EndQuorumEpoch",
+        "DescribeQuorum",
+        "FetchSnapshot",
+    ],
+};
+
+/// The families assigned by name, where the listeners cannot tell them apart.
+pub const OVERRIDES: &[Override] = &[CLIENT_CORE, CONSUMER_GROUP, TRANSACTIONS, RAFT];
+
+/// Returns the cargo feature the API `name` is compiled behind, or `None` if it is always compiled.
+///
+/// The family is derived from the listeners the API is exposed on: those a broker exposes to
+/// clients are admin APIs, and the rest are broker internal. [`OVERRIDES`] then narrow these
+/// down, failing if the API is not exposed on the listener of its override.
+pub fn feature_for(name: &str, listeners: &[ListenerSpec]) -> Result<Option<&'static str>, Error> {
+    if ALWAYS_ENABLED.contains(&name) {
+        return Ok(None);
+    }
+    let family = OVERRIDES.iter().find(|family| family.apis.contains(&name));
+    match family {
+        Some(family) if listeners.contains(&family.listener) => Ok(Some(family.feature)),
+        Some(family) => Err(format_err!(
+            "{} is in the {} family, but is not exposed on the {:?} listener",
+            name,
+            family.feature,
+            family.listener
+        )),
+        None if listeners.contains(&ListenerSpec::Broker) => Ok(Some(ADMIN)),
+        None => Ok(Some(BROKER_INTERNAL)),
+    }
+}
+
+/// Write the `#[cfg]` attribute gating an item behind `feature`, if any, at the given indentation.
+pub fn write_cfg<W: Write>(w: &mut W, feature: Option<&str>, indent: &str) -> Result<(), Error> {
+    if let Some(feature) = feature {
+        writeln!(w, "{}#[cfg(feature = \"{}\")]", indent, feature)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_families_from_listeners() {
+        use ListenerSpec::*;
+        let feature = |name, listeners| feature_for(name, listeners).unwrap();
+        assert_eq!(
+            feature("ApiVersions", &[ZkBroker, Broker, Controller]),
+            None
+        );
+        assert_eq!(feature("Produce", &[ZkBroker, Broker]), Some("client-core"));
+        assert_eq!(feature("Vote", &[Controller]), Some("raft"));
+        assert_eq!(
+            feature("CreateTopics", &[ZkBroker, Broker, Controller]),
+            Some(ADMIN)
+        );
+        assert_eq!(feature("LeaderAndIsr", &[ZkBroker]), Some(BROKER_INTERNAL));
+        assert_eq!(
+            feature("BrokerRegistration", &[Controller]),
+            Some(BROKER_INTERNAL)
+        );
+    }
+
+    #[test]
+    fn overrides_must_match_the_listeners() {
+        use ListenerSpec::*;
+        assert!(feature_for("Produce", &[Controller]).is_err());
+        assert!(feature_for("Vote", &[Broker]).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use failure::Error;
+use similar::TextDiff;
+
+use super::existing_generated_files;
+
+/// The most diff lines printed for a single file; the rest are summarised.
+const MAX_DIFF_LINES: usize = 60;
+
+/// Compare generated `files` against the contents of `output_dir`, printing a per-file summary of
+/// anything that differs. Returns `true` if every file is up to date.
+pub fn check_files(files: &BTreeMap<PathBuf, Vec<u8>>, output_dir: &Path) -> Result<bool, Error> {
+    let mut out_of_date = 0;
+
+    for (path, generated) in files {
+        let full_path = output_dir.join(path);
+        let existing = match fs::read(&full_path) {
+            Ok(existing) => existing,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                println!("{}: missing", full_path.display());
+                out_of_date += 1;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if existing == *generated {
+            continue;
+        }
+
+        out_of_date += 1;
+        let existing = String::from_utf8_lossy(&existing);
+        let generated = String::from_utf8_lossy(generated);
+        print_diff(&full_path, &existing, &generated);
+    }
+
+    let generated_paths: BTreeSet<_> = files.keys().map(|path| output_dir.join(path)).collect();
+    for path in existing_generated_files(files, output_dir)? {
+        if !generated_paths.contains(&path) {
+            println!("{}: not produced by any schema", path.display());
+            out_of_date += 1;
+        }
+    }
+
+    if out_of_date == 0 {
+        println!("{} generated files are up to date", files.len());
+    } else {
+        println!();
+        println!(
+            "{} file(s) differ from the generator output; rerun protocol_codegen without --check to update them",
+            out_of_date
+        );
+    }
+    Ok(out_of_date == 0)
+}
+
+fn print_diff(path: &Path, existing: &str, generated: &str) {
+    let diff = TextDiff::from_lines(existing, generated);
+    let (mut inserted, mut deleted) = (0, 0);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            similar::ChangeTag::Insert => inserted += 1,
+            similar::ChangeTag::Delete => deleted += 1,
+            similar::ChangeTag::Equal => {}
+        }
+    }
+    println!(
+        "{}: modified (+{} -{} lines against the generator output)",
+        path.display(),
+        inserted,
+        deleted
+    );
+
+    let display_path = path.display().to_string();
+    let unified = diff
+        .unified_diff()
+        .context_radius(3)
+        .header(&display_path, "generated")
+        .to_string();
+    let lines: Vec<_> = unified.lines().collect();
+    for line in lines.iter().take(MAX_DIFF_LINES) {
+        println!("    {}", line);
+    }
+    if lines.len() > MAX_DIFF_LINES {
+        println!("    ... {} more diff lines", lines.len() - MAX_DIFF_LINES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_modified_missing_and_stray_files() -> Result<(), Error> {
+        let dir =
+            std::env::temp_dir().join(format!("protocol_codegen_check_{}", std::process::id()));
+        fs::create_dir_all(dir.join("messages"))?;
+
+        let mut files = BTreeMap::new();
+        files.insert(PathBuf::from("messages.rs"), b"pub mod a;\n".to_vec());
+        files.insert(
+            PathBuf::from("messages").join("a.rs"),
+            b"struct A;\n".to_vec(),
+        );
+
+        fs::write(dir.join("messages.rs"), b"pub mod a;\n")?;
+        fs::write(dir.join("messages").join("a.rs"), b"struct A;\n")?;
+        assert!(check_files(&files, &dir)?);
+
+        fs::write(dir.join("messages").join("a.rs"), b"struct B;\n")?;
+        assert!(!check_files(&files, &dir)?);
+
+        fs::remove_file(dir.join("messages").join("a.rs"))?;
+        assert!(!check_files(&files, &dir)?);
+
+        fs::write(dir.join("messages").join("a.rs"), b"struct A;\n")?;
+        fs::write(dir.join("messages").join("b.rs"), b"struct B;\n")?;
+        assert!(!check_files(&files, &dir)?);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
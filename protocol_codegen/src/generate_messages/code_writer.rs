@@ -31,7 +31,12 @@ impl<W: Write> CodeWriter<W> {
         write!(self, "}}")?;
         res
     }
-    #[allow(dead_code)]
+    pub fn end_line(&mut self) -> Result<(), Error> {
+        if !self.new_line {
+            writeln!(self)?;
+        }
+        Ok(())
+    }
     pub fn into_inner(self) -> W {
         self.inner
     }
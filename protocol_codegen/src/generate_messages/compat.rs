@@ -0,0 +1,691 @@
+//! Compares two directories of schemas, such as those of two Kafka releases, reporting what an
+//! upgrade changes in the generated messages.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use failure::Error;
+use serde::Serialize;
+
+use super::api_families;
+use super::read_specs;
+use super::spec::{FieldSpec, Spec, SpecType, VersionSpec};
+
+/// The kind of a [`Change`].
+#[derive(Debug, Copy, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangeKind {
+    /// A schema only present in the new directory.
+    SchemaAdded,
+    /// A schema only present in the old directory.
+    SchemaRemoved,
+    /// Stable versions were added.
+    VersionsAdded,
+    /// Stable versions were removed.
+    VersionsRemoved,
+    /// The version which requires the `unstable-apis` feature changed.
+    UnstableVersionChanged,
+    /// The versions using the flexible encoding changed.
+    FlexibleVersionsChanged,
+    /// The deprecated versions changed.
+    DeprecatedVersionsChanged,
+    /// The API moved to another cargo feature.
+    FeatureChanged,
+    /// A common struct only present in the new schema.
+    CommonStructAdded,
+    /// A common struct only present in the old schema.
+    CommonStructRemoved,
+    /// A field only present in the new schema.
+    FieldAdded,
+    /// A tagged field only present in the new schema.
+    TaggedFieldAdded,
+    /// A field only present in the old schema.
+    FieldRemoved,
+    /// The type of a field changed.
+    TypeChanged,
+    /// The versions a field is present in changed.
+    FieldVersionsChanged,
+    /// A field which was never null became nullable.
+    NowNullable,
+    /// A nullable field became non-nullable.
+    NoLongerNullable,
+    /// The versions a nullable field can be null in changed.
+    NullableVersionsChanged,
+    /// The tag of a field changed, or a field became tagged or untagged.
+    TagChanged,
+    /// A field became or stopped being the key of its map.
+    MapKeyChanged,
+    /// The entity type of a field changed.
+    EntityTypeChanged,
+    /// The default value of a field changed.
+    DefaultChanged,
+}
+
+/// A difference between two versions of a schema.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+    /// The name of the schema, e.g. `FetchRequest`.
+    pub schema: String,
+    /// The path of the field within the schema, e.g. `Topics.Partitions`, for changes to a field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// The kind of change.
+    pub kind: ChangeKind,
+    /// Whether the change breaks code using the generated messages, e.g. by changing the type of a
+    /// field or by removing a version it may be encoding.
+    pub breaking: bool,
+    /// A description of the change.
+    pub description: String,
+}
+
+/// The differences between two directories of schemas, ordered by schema name.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Every difference found.
+    pub changes: Vec<Change>,
+}
+
+impl Report {
+    /// The changes breaking code using the generated messages.
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.breaking)
+    }
+
+    /// The report as JSON, for tools consuming it.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "breakingChanges": self.breaking_changes().count(),
+            "changes": self.changes,
+        })
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut schema = None;
+        for change in &self.changes {
+            if schema != Some(&change.schema) {
+                writeln!(f, "{}", change.schema)?;
+                schema = Some(&change.schema);
+            }
+            write!(f, "    {}", if change.breaking { "BREAKING " } else { "" })?;
+            if let Some(field) = &change.field {
+                write!(f, "field `{}`: ", field)?;
+            }
+            writeln!(f, "{}", change.description)?;
+        }
+        let schemas: BTreeSet<_> = self.changes.iter().map(|change| &change.schema).collect();
+        if !self.changes.is_empty() {
+            writeln!(f)?;
+        }
+        writeln!(
+            f,
+            "{} change(s) to {} schema(s), {} of them breaking",
+            self.changes.len(),
+            schemas.len(),
+            self.breaking_changes().count()
+        )
+    }
+}
+
+/// Compare the schemas in `old_dir` with those in `new_dir`.
+pub fn compare_dirs(old_dir: &Path, new_dir: &Path) -> Result<Report, Error> {
+    compare_specs(&read_specs(old_dir)?, &read_specs(new_dir)?)
+}
+
+fn compare_specs(old_specs: &[Spec], new_specs: &[Spec]) -> Result<Report, Error> {
+    let old = Specs::new(old_specs);
+    let new = Specs::new(new_specs);
+    let mut report = Report::default();
+
+    let names: BTreeSet<_> = old.by_name.keys().chain(new.by_name.keys()).collect();
+    for name in names {
+        let mut changes = Changes {
+            schema: name,
+            changes: &mut report.changes,
+        };
+        match (old.by_name.get(name), new.by_name.get(name)) {
+            (Some(old_spec), Some(new_spec)) => {
+                compare_spec(&mut changes, (&old, old_spec), (&new, new_spec))?
+            }
+            (None, Some(new_spec)) => {
+                let (description, breaking) = match (new_spec.type_, new_spec.api_key) {
+                    (SpecType::Request, Some(api_key)) => (
+                        format!(
+                            "new request schema with apiKey {}, adding a variant to `ApiKey`",
+                            api_key
+                        ),
+                        true,
+                    ),
+                    _ => (format!("new {} schema", type_name(new_spec.type_)), false),
+                };
+                changes.push(None, ChangeKind::SchemaAdded, breaking, description);
+            }
+            (Some(_), None) => {
+                changes.push(
+                    None,
+                    ChangeKind::SchemaRemoved,
+                    true,
+                    "schema removed".into(),
+                );
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+    Ok(report)
+}
+
+/// The specs of one directory.
+struct Specs<'a> {
+    by_name: BTreeMap<&'a str, &'a Spec>,
+    /// The API keys whose latest version is unstable, which applies to their responses too.
+    unstable_api_keys: BTreeSet<i16>,
+}
+
+impl<'a> Specs<'a> {
+    fn new(specs: &'a [Spec]) -> Self {
+        Self {
+            by_name: specs
+                .iter()
+                .map(|spec| (spec.name.as_str(), spec))
+                .collect(),
+            unstable_api_keys: specs
+                .iter()
+                .filter(|spec| {
+                    spec.type_ == SpecType::Request && spec.latest_version_unstable == Some(true)
+                })
+                .filter_map(|spec| spec.api_key)
+                .collect(),
+        }
+    }
+
+    fn unstable_version(&self, spec: &Spec) -> Option<i16> {
+        let unstable = match (spec.type_, spec.api_key) {
+            (SpecType::Request | SpecType::Response, Some(api_key)) => {
+                self.unstable_api_keys.contains(&api_key)
+            }
+            _ => spec.latest_version_unstable == Some(true),
+        };
+        if unstable {
+            spec.valid_versions.range().map(|range| *range.end())
+        } else {
+            None
+        }
+    }
+
+    /// The versions available without the `unstable-apis` feature.
+    fn stable_versions(&self, spec: &Spec) -> Option<RangeInclusive<i16>> {
+        let range = spec.valid_versions.range()?;
+        match self.unstable_version(spec) {
+            Some(_) if range.start() == range.end() => None,
+            Some(unstable) => Some(*range.start()..=unstable - 1),
+            None => Some(range),
+        }
+    }
+}
+
+/// Collects the changes to a single schema.
+struct Changes<'a> {
+    schema: &'a str,
+    changes: &'a mut Vec<Change>,
+}
+
+impl Changes<'_> {
+    fn push(&mut self, field: Option<&str>, kind: ChangeKind, breaking: bool, description: String) {
+        self.changes.push(Change {
+            schema: self.schema.to_string(),
+            field: field.map(str::to_string),
+            kind,
+            breaking,
+            description,
+        });
+    }
+}
+
+fn compare_spec(
+    changes: &mut Changes,
+    (old, old_spec): (&Specs, &Spec),
+    (new, new_spec): (&Specs, &Spec),
+) -> Result<(), Error> {
+    let old_versions = old.stable_versions(old_spec);
+    let new_versions = new.stable_versions(new_spec);
+    for added in difference(&new_versions, &old_versions) {
+        changes.push(
+            None,
+            ChangeKind::VersionsAdded,
+            false,
+            format!("{} added", versions_text(&added)),
+        );
+    }
+    for removed in difference(&old_versions, &new_versions) {
+        changes.push(
+            None,
+            ChangeKind::VersionsRemoved,
+            true,
+            format!("{} removed", versions_text(&removed)),
+        );
+    }
+
+    let old_unstable = old.unstable_version(old_spec);
+    let new_unstable = new.unstable_version(new_spec);
+    if old_unstable != new_unstable {
+        let description = match new_unstable {
+            Some(version) => format!(
+                "version {} is unstable and requires the `unstable-apis` feature",
+                version
+            ),
+            None => "no version is unstable".into(),
+        };
+        changes.push(None, ChangeKind::UnstableVersionChanged, false, description);
+    }
+
+    let flexible = |spec: &Spec| spec.flexible_versions.unwrap_or_default();
+    if flexible(old_spec) != flexible(new_spec) {
+        changes.push(
+            None,
+            ChangeKind::FlexibleVersionsChanged,
+            false,
+            format!(
+                "flexibleVersions changed from {} to {}",
+                flexible(old_spec),
+                flexible(new_spec)
+            ),
+        );
+    }
+
+    let deprecated = |spec: &Spec| spec.deprecated_versions.unwrap_or_default();
+    if deprecated(old_spec) != deprecated(new_spec) {
+        changes.push(
+            None,
+            ChangeKind::DeprecatedVersionsChanged,
+            false,
+            format!(
+                "deprecatedVersions changed from {} to {}",
+                deprecated(old_spec),
+                deprecated(new_spec)
+            ),
+        );
+    }
+
+    if new_spec.type_ == SpecType::Request {
+        let api_name = new_spec.name.trim_end_matches("Request");
+        let feature = |spec: &Spec| {
+            api_families::feature_for(api_name, spec.listeners.as_deref().unwrap_or_default())
+        };
+        let (old_feature, new_feature) = (feature(old_spec)?, feature(new_spec)?);
+        if old_feature != new_feature {
+            changes.push(
+                None,
+                ChangeKind::FeatureChanged,
+                true,
+                format!(
+                    "moved from the {} feature to the {} feature",
+                    feature_text(old_feature),
+                    feature_text(new_feature)
+                ),
+            );
+        }
+    }
+
+    for new_struct in &new_spec.common_structs {
+        match old_spec
+            .common_structs
+            .iter()
+            .find(|old_struct| old_struct.name == new_struct.name)
+        {
+            Some(old_struct) => compare_fields(
+                changes,
+                &new_struct.name,
+                &old_struct.fields,
+                &new_struct.fields,
+            ),
+            None => changes.push(
+                Some(&new_struct.name),
+                ChangeKind::CommonStructAdded,
+                false,
+                "common struct added".into(),
+            ),
+        }
+    }
+    for old_struct in &old_spec.common_structs {
+        if !new_spec
+            .common_structs
+            .iter()
+            .any(|new_struct| new_struct.name == old_struct.name)
+        {
+            changes.push(
+                Some(&old_struct.name),
+                ChangeKind::CommonStructRemoved,
+                true,
+                "common struct removed".into(),
+            );
+        }
+    }
+    compare_fields(changes, "", &old_spec.fields, &new_spec.fields);
+    Ok(())
+}
+
+fn compare_fields(
+    changes: &mut Changes,
+    parent: &str,
+    old_fields: &[FieldSpec],
+    new_fields: &[FieldSpec],
+) {
+    let path = |field: &FieldSpec| {
+        if parent.is_empty() {
+            field.name.clone()
+        } else {
+            format!("{}.{}", parent, field.name)
+        }
+    };
+
+    for new_field in new_fields {
+        let path = path(new_field);
+        match old_fields
+            .iter()
+            .find(|old_field| old_field.name == new_field.name)
+        {
+            Some(old_field) => {
+                compare_field(changes, &path, old_field, new_field);
+                if let (Some(old_children), Some(new_children)) =
+                    (&old_field.fields, &new_field.fields)
+                {
+                    compare_fields(changes, &path, old_children, new_children);
+                }
+            }
+            None => {
+                let (kind, description) = match new_field.tag {
+                    Some(tag) => (
+                        ChangeKind::TaggedFieldAdded,
+                        format!(
+                            "tagged field added with tag {} in versions {}",
+                            tag, new_field.versions
+                        ),
+                    ),
+                    None => (
+                        ChangeKind::FieldAdded,
+                        format!("field added in versions {}", new_field.versions),
+                    ),
+                };
+                changes.push(Some(&path), kind, false, description);
+            }
+        }
+    }
+
+    for old_field in old_fields {
+        if !new_fields
+            .iter()
+            .any(|new_field| new_field.name == old_field.name)
+        {
+            changes.push(
+                Some(&path(old_field)),
+                ChangeKind::FieldRemoved,
+                true,
+                "field removed".into(),
+            );
+        }
+    }
+}
+
+fn compare_field(changes: &mut Changes, path: &str, old: &FieldSpec, new: &FieldSpec) {
+    let mut push =
+        |kind, breaking, description| changes.push(Some(path), kind, breaking, description);
+
+    if old.type_.to_string() != new.type_.to_string() {
+        push(
+            ChangeKind::TypeChanged,
+            true,
+            format!("type changed from {} to {}", old.type_, new.type_),
+        );
+    }
+    if old.versions != new.versions {
+        push(
+            ChangeKind::FieldVersionsChanged,
+            false,
+            format!("versions changed from {} to {}", old.versions, new.versions),
+        );
+    }
+
+    // Nullable fields are generated as an `Option` in every version
+    match (
+        old.nullable_versions.is_none(),
+        new.nullable_versions.is_none(),
+    ) {
+        (true, false) => push(
+            ChangeKind::NowNullable,
+            true,
+            format!(
+                "nullable in versions {}, which makes it an `Option`",
+                new.nullable_versions
+            ),
+        ),
+        (false, true) => push(
+            ChangeKind::NoLongerNullable,
+            true,
+            "no longer nullable, which makes it no longer an `Option`".into(),
+        ),
+        _ if old.nullable_versions != new.nullable_versions => push(
+            ChangeKind::NullableVersionsChanged,
+            false,
+            format!(
+                "nullableVersions changed from {} to {}",
+                old.nullable_versions, new.nullable_versions
+            ),
+        ),
+        _ => {}
+    }
+
+    if old.tag != new.tag || old.tagged_versions != new.tagged_versions {
+        let tag_text = |field: &FieldSpec| match field.tag {
+            Some(tag) => format!("tag {} in versions {}", tag, field.tagged_versions),
+            None => "untagged".to_string(),
+        };
+        push(
+            ChangeKind::TagChanged,
+            false,
+            format!("changed from {} to {}", tag_text(old), tag_text(new)),
+        );
+    }
+
+    if old.map_key != new.map_key {
+        let description = if new.map_key {
+            "now the key of its map, which changes the type of the collection holding it"
+        } else {
+            "no longer the key of its map, which changes the type of the collection holding it"
+        };
+        push(ChangeKind::MapKeyChanged, true, description.into());
+    }
+
+    if old.entity_type != new.entity_type {
+        let entity_text =
+            |field: &FieldSpec| field.entity_type.clone().unwrap_or_else(|| "none".into());
+        push(
+            ChangeKind::EntityTypeChanged,
+            true,
+            format!(
+                "entityType changed from {} to {}",
+                entity_text(old),
+                entity_text(new)
+            ),
+        );
+    }
+
+    if old.default != new.default {
+        let default_text = |field: &FieldSpec| match &field.default {
+            Some(serde_json::Value::String(default)) => default.clone(),
+            Some(default) => default.to_string(),
+            None => "none".into(),
+        };
+        push(
+            ChangeKind::DefaultChanged,
+            false,
+            format!(
+                "default changed from {} to {}",
+                default_text(old),
+                default_text(new)
+            ),
+        );
+    }
+}
+
+/// The versions of `a` which are not in `b`, as contiguous ranges.
+fn difference(
+    a: &Option<RangeInclusive<i16>>,
+    b: &Option<RangeInclusive<i16>>,
+) -> Vec<RangeInclusive<i16>> {
+    let mut ranges: Vec<RangeInclusive<i16>> = Vec::new();
+    for version in a.clone().into_iter().flatten() {
+        if matches!(b, Some(b) if b.contains(&version)) {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(last) if *last.end() == version - 1 => *last = *last.start()..=version,
+            _ => ranges.push(version..=version),
+        }
+    }
+    ranges
+}
+
+fn versions_text(versions: &RangeInclusive<i16>) -> String {
+    if versions.start() == versions.end() {
+        format!("version {}", versions.start())
+    } else {
+        format!(
+            "versions {}",
+            VersionSpec::Range(*versions.start(), *versions.end())
+        )
+    }
+}
+
+fn feature_text(feature: Option<&str>) -> String {
+    match feature {
+        Some(feature) => format!("`{}`", feature),
+        None => "always enabled".into(),
+    }
+}
+
+fn type_name(type_: SpecType) -> String {
+    serde_json::to_value(type_)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(json: &str) -> Spec {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn kinds(report: &Report) -> Vec<(Option<&str>, ChangeKind, bool)> {
+        report
+            .changes
+            .iter()
+            .map(|change| (change.field.as_deref(), change.kind, change.breaking))
+            .collect()
+    }
+
+    #[test]
+    fn reports_version_and_field_changes() {
+        let old = spec(
+            r#"{ "apiKey": 1, "type": "request", "name": "FetchRequest", "listeners": ["broker"],
+                "validVersions": "0-3", "flexibleVersions": "3+", "fields": [
+                { "name": "Topic", "type": "string", "versions": "0+" },
+                { "name": "Partitions", "type": "[]Partition", "versions": "0+", "fields": [
+                    { "name": "Index", "type": "int32", "versions": "0+", "mapKey": true },
+                    { "name": "MaxBytes", "type": "int32", "versions": "0+" }
+                ]}
+            ]}"#,
+        );
+        let new = spec(
+            r#"{ "apiKey": 1, "type": "request", "name": "FetchRequest", "listeners": ["broker"],
+                "validVersions": "1-5", "flexibleVersions": "3+", "latestVersionUnstable": true, "fields": [
+                { "name": "Topic", "type": "string", "versions": "0+", "nullableVersions": "4+" },
+                { "name": "Partitions", "type": "[]Partition", "versions": "0+", "fields": [
+                    { "name": "Index", "type": "int32", "versions": "0+", "mapKey": true },
+                    { "name": "Rack", "type": "string", "versions": "4+", "taggedVersions": "4+", "tag": 0 }
+                ]}
+            ]}"#,
+        );
+        let report = compare_specs(&[old], &[new]).unwrap();
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (None, ChangeKind::VersionsAdded, false),
+                (None, ChangeKind::VersionsRemoved, true),
+                (None, ChangeKind::UnstableVersionChanged, false),
+                (Some("Topic"), ChangeKind::NowNullable, true),
+                (Some("Partitions.Rack"), ChangeKind::TaggedFieldAdded, false),
+                (Some("Partitions.MaxBytes"), ChangeKind::FieldRemoved, true),
+            ]
+        );
+        assert_eq!(report.changes[0].description, "version 4 added");
+        assert_eq!(report.changes[1].description, "version 0 removed");
+        assert_eq!(report.breaking_changes().count(), 3);
+    }
+
+    #[test]
+    fn reports_added_and_removed_common_structs() {
+        let old = spec(
+            r#"{ "apiKey": 68, "type": "request", "name": "ConsumerGroupHeartbeatRequest", "listeners": ["broker"],
+                "validVersions": "0", "flexibleVersions": "0+", "fields": [],
+                "commonStructs": [
+                    { "name": "Assignor", "versions": "0+", "fields": [
+                        { "name": "Name", "type": "string", "versions": "0+" }
+                    ]}
+                ]}"#,
+        );
+        let new = spec(
+            r#"{ "apiKey": 68, "type": "request", "name": "ConsumerGroupHeartbeatRequest", "listeners": ["broker"],
+                "validVersions": "0", "flexibleVersions": "0+", "fields": [],
+                "commonStructs": [
+                    { "name": "TopicPartitions", "versions": "0+", "fields": [
+                        { "name": "TopicId", "type": "uuid", "versions": "0+" }
+                    ]}
+                ]}"#,
+        );
+        let report = compare_specs(&[old], &[new]).unwrap();
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (
+                    Some("TopicPartitions"),
+                    ChangeKind::CommonStructAdded,
+                    false
+                ),
+                (Some("Assignor"), ChangeKind::CommonStructRemoved, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_added_and_removed_schemas() {
+        let header = spec(
+            r#"{ "type": "header", "name": "RequestHeader", "validVersions": "0-2", "fields": [] }"#,
+        );
+        let request = spec(
+            r#"{ "apiKey": 80, "type": "request", "name": "PushTelemetryRequest", "listeners": ["broker"],
+                "validVersions": "0", "flexibleVersions": "0+", "fields": [] }"#,
+        );
+        let report = compare_specs(&[header], &[request]).unwrap();
+        assert_eq!(
+            kinds(&report),
+            vec![
+                (None, ChangeKind::SchemaAdded, true),
+                (None, ChangeKind::SchemaRemoved, true),
+            ]
+        );
+
+        let text = report.to_string();
+        assert!(text.starts_with(
+            "PushTelemetryRequest\n    BREAKING new request schema with apiKey 80, adding a variant to `ApiKey`\n"
+        ));
+        assert!(text.ends_with("2 change(s) to 2 schema(s), 2 of them breaking\n"));
+        assert_eq!(report.to_json()["changes"][1]["kind"], "schema-removed");
+    }
+}
@@ -0,0 +1,167 @@
+//! Generates messages from user-supplied schemas into a single file, for crates which use
+//! `kafka-protocol` rather than being part of it.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use failure::{format_err, Error};
+
+use super::generate::{self, write_dependency_aliases, Target};
+use super::spec::{Spec, SpecType};
+use super::{read_specs, write_entity_types};
+
+/// The optional items of the messages generated by [`generate_custom`], which are resolved when
+/// generating rather than gated on the features of the crate including them.
+///
+/// Each option mirrors the feature of `kafka-protocol` of the same name, which must be enabled for
+/// the generated code to compile if it is set.
+#[derive(Debug, Clone, Default)]
+pub struct CustomOptions {
+    /// Derive `Serialize` and `Deserialize` for the messages.
+    pub serde: bool,
+    /// Generate `to_json` and `from_json`, converting the messages to and from the JSON
+    /// representation written by Kafka's `JsonConverter`.
+    pub json: bool,
+    /// Include the versions of APIs whose schemas mark their latest version as unstable in
+    /// `Message::VERSIONS`.
+    pub unstable_apis: bool,
+}
+
+impl CustomOptions {
+    /// Whether the generated code includes the items gated on `feature` of `kafka-protocol`, or an
+    /// error if no option mirrors `feature`.
+    pub(crate) fn enables(&self, feature: &str) -> Result<bool, Error> {
+        Ok(match feature {
+            "serde" => self.serde,
+            "json" => self.json,
+            "unstable-apis" => self.unstable_apis,
+            _ => return Err(format_err!("no option mirrors the feature `{}`", feature)),
+        })
+    }
+}
+
+/// Generate the messages described by the `*.json` schemas in `schema_dir` into `output_file`,
+/// which is only written if its contents change.
+///
+/// The schemas use Kafka's format. Every message becomes a module of the output holding a struct
+/// implementing `Encodable`, `Decodable` and `Message`, and requests and responses also implement
+/// `HeaderVersion`. Requests are paired with the response sharing their `apiKey` to implement
+/// `Request`. The output only depends on `kafka-protocol`, and has no inner attributes, so it can
+/// be included from a build script:
+///
+/// ```ignore
+/// // build.rs
+/// let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+/// let options = protocol_codegen::CustomOptions::default();
+/// protocol_codegen::generate_custom("schemas".as_ref(), &out_dir.join("custom_messages.rs"), &options).unwrap();
+/// println!("cargo:rerun-if-changed=schemas");
+///
+/// // lib.rs
+/// pub mod custom_messages {
+///     include!(concat!(env!("OUT_DIR"), "/custom_messages.rs"));
+/// }
+/// ```
+pub fn generate_custom(
+    schema_dir: &Path,
+    output_file: &Path,
+    options: &CustomOptions,
+) -> Result<(), Error> {
+    let contents = generate_custom_source(read_specs(schema_dir)?, Target::Custom(options))?;
+    if fs::read(output_file).ok().as_deref() != Some(&contents[..]) {
+        fs::write(output_file, contents)
+            .map_err(|e| format_err!("{}: {}", output_file.display(), e))?;
+    }
+    Ok(())
+}
+
+fn generate_custom_source(specs: Vec<Spec>, target: Target<'_>) -> Result<Vec<u8>, Error> {
+    let response_specs: BTreeMap<_, _> = specs
+        .iter()
+        .filter(|spec| spec.type_ == SpecType::Response)
+        .filter_map(|spec| spec.api_key.map(|k| (k, spec.clone())))
+        .collect();
+    let unstable_api_keys: BTreeSet<_> = specs
+        .iter()
+        .filter(|spec| {
+            spec.type_ == SpecType::Request && spec.latest_version_unstable == Some(true)
+        })
+        .filter_map(|spec| spec.api_key)
+        .collect();
+
+    let mut entity_types = BTreeSet::new();
+    let mut request_types = BTreeMap::new();
+    let mut response_types = BTreeMap::new();
+
+    let mut file = Vec::new();
+    writeln!(
+        file,
+        "// Messages generated by protocol_codegen from custom schemas."
+    )?;
+    writeln!(
+        file,
+        "// WARNING: the items of this file are generated and should not be edited directly."
+    )?;
+    writeln!(file)?;
+    writeln!(file, "#[allow(unused_imports)]")?;
+    writeln!(
+        file,
+        "use {}::protocol::{{Request, StrBytes}};",
+        target.runtime()
+    )?;
+    writeln!(file, "#[allow(unused_imports)]")?;
+    writeln!(file, "use {}::NewType;", target.internals("protocol"))?;
+    write_dependency_aliases(&mut file, target)?;
+    writeln!(file)?;
+
+    for mut spec in specs {
+        let spec_meta = (spec.type_, spec.api_key);
+        if let (SpecType::Response, Some(k)) = spec_meta {
+            if unstable_api_keys.contains(&k) {
+                spec.latest_version_unstable = Some(true);
+            }
+        }
+        let response_spec = match spec_meta {
+            (SpecType::Request, Some(k)) => response_specs.get(&k),
+            _ => None,
+        };
+        let (module_name, struct_name, contents) =
+            generate::generate(spec, response_spec, "", target, &mut entity_types)?;
+
+        match spec_meta {
+            (SpecType::Request, Some(k)) => {
+                request_types.insert(k, struct_name.clone());
+            }
+            (SpecType::Response, Some(k)) => {
+                response_types.insert(k, struct_name.clone());
+            }
+            _ => {}
+        }
+
+        writeln!(file, "#[allow(clippy::all)]")?;
+        writeln!(file, "pub mod {} {{", module_name)?;
+        file.extend_from_slice(&contents);
+        writeln!(file, "}}")?;
+        writeln!(file, "pub use {}::{};", module_name, struct_name)?;
+        writeln!(file)?;
+    }
+
+    for (api_key, request_type) in &request_types {
+        let response_type = response_types.get(api_key).ok_or_else(|| {
+            format_err!(
+                "{}.json has no response schema with apiKey {}",
+                request_type,
+                api_key
+            )
+        })?;
+        writeln!(file, "impl Request for {} {{", request_type)?;
+        writeln!(file, "    const KEY: i16 = {};", api_key)?;
+        writeln!(file, "    type Response = {};", response_type)?;
+        writeln!(file, "}}")?;
+        writeln!(file)?;
+    }
+
+    write_entity_types(&mut file, &entity_types, target)?;
+    Ok(file)
+}
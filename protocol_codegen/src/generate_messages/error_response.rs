@@ -0,0 +1,597 @@
+//! Generates `error_response` methods, which answer a request with an error for every
+//! resource it mentions.
+//!
+//! Request and response schemas are not linked to each other, so the mirroring is inferred: a
+//! response array is populated from the request array whose elements share the most identifying
+//! fields (topic, partition, group, ...) with it, and every `ErrorCode` field is set to the
+//! provided error.
+
+use std::cmp::Reverse;
+use std::io::Write;
+
+use failure::{format_err, Error};
+use inflector::Inflector;
+
+use super::code_writer::CodeWriter;
+use super::generate::{rust_field_name, version_cond, write_version_cond};
+use super::spec::{FieldSpec, PrimitiveType, Spec, TypeSpec, VersionSpec};
+
+/// Fields which identify a resource, and are therefore copied from the request.
+const IDENTITY_FIELDS: &[&str] = &[
+    "Name",
+    "Topic",
+    "TopicId",
+    "TopicName",
+    "Partition",
+    "PartitionIndex",
+    "PartitionId",
+    "Index",
+    "Key",
+    "GroupId",
+    "MemberId",
+    "GroupInstanceId",
+    "ResourceType",
+    "ResourceName",
+    "Path",
+];
+
+/// The fields of a request struct being mirrored.
+#[derive(Clone, Copy)]
+struct Source<'a> {
+    fields: &'a [FieldSpec],
+    /// The expression of the struct being mirrored.
+    expr: &'a str,
+    /// The map key of the struct being mirrored and the variable holding it.
+    key: Option<(&'a FieldSpec, &'a str)>,
+}
+
+struct Context<'a> {
+    request: &'a Spec,
+    response: &'a Spec,
+    response_module: String,
+}
+
+impl<'a> Context<'a> {
+    /// An error about `field` of `spec`, which names the schema file unless it is the request's,
+    /// as errors about the request are already reported against its file.
+    fn field_error(&self, spec: &Spec, field: &FieldSpec, message: String) -> Error {
+        if std::ptr::eq(spec, self.request) {
+            format_err!("field `{}`: {}", field.name, message)
+        } else {
+            format_err!("{}.json: field `{}`: {}", spec.name, field.name, message)
+        }
+    }
+
+    fn struct_fields(
+        &self,
+        spec: &'a Spec,
+        field: &'a FieldSpec,
+    ) -> Result<&'a [FieldSpec], Error> {
+        if let Some(fields) = &field.fields {
+            return Ok(fields);
+        }
+        let name = struct_name(&field.type_).ok_or_else(|| {
+            self.field_error(
+                spec,
+                field,
+                format!("expected a struct, got {}", field.type_),
+            )
+        })?;
+        spec.common_structs
+            .iter()
+            .find(|common_struct| common_struct.name == name)
+            .map(|common_struct| common_struct.fields.as_slice())
+            .ok_or_else(|| self.field_error(spec, field, format!("unknown struct `{}`", name)))
+    }
+
+    /// Whether a response struct reports errors, directly or in one of its nested structs.
+    fn has_error_code(&self, fields: &'a [FieldSpec]) -> Result<bool, Error> {
+        for field in fields {
+            if is_error_code(field)
+                || (struct_name(&field.type_).is_some()
+                    && self.has_error_code(self.struct_fields(self.response, field)?)?)
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+fn element_type(type_: &TypeSpec) -> &TypeSpec {
+    match type_ {
+        TypeSpec::Array(inner) => inner,
+        other => other,
+    }
+}
+
+fn struct_name(type_: &TypeSpec) -> Option<&str> {
+    match element_type(type_) {
+        TypeSpec::Struct(name) => Some(name),
+        _ => None,
+    }
+}
+
+fn primitive(type_: &TypeSpec) -> Option<PrimitiveType> {
+    match element_type(type_) {
+        TypeSpec::Primitive(prim) => Some(*prim),
+        _ => None,
+    }
+}
+
+fn map_key(fields: &[FieldSpec]) -> Option<&FieldSpec> {
+    let mut keys = fields.iter().filter(|field| field.map_key);
+    match (keys.next(), keys.next()) {
+        (Some(key), None) => Some(key),
+        _ => None,
+    }
+}
+
+fn is_optional(field: &FieldSpec) -> bool {
+    !field.nullable_versions.is_none()
+}
+
+fn is_error_code(field: &FieldSpec) -> bool {
+    matches!(field.type_, TypeSpec::Primitive(PrimitiveType::Int16))
+        && field.name.ends_with("ErrorCode")
+}
+
+fn is_identity(field: &FieldSpec) -> bool {
+    field.map_key || field.entity_type.is_some() || IDENTITY_FIELDS.contains(&field.name.as_str())
+}
+
+/// Normalizes a field name so that e.g. `Partition` and `PartitionIndex` compare equal. Array
+/// fields are singularized first.
+fn canonical_name(field: &FieldSpec) -> String {
+    if let Some(entity_type) = &field.entity_type {
+        return format!("entity:{}", entity_type);
+    }
+    let mut name = field.name.to_lowercase();
+    if let TypeSpec::Array(_) = field.type_ {
+        if let Some(stripped) = name.strip_suffix("indexes") {
+            name = format!("{}index", stripped);
+        } else if let Some(stripped) = name.strip_suffix('s') {
+            name = stripped.to_string();
+        }
+    }
+    match name.as_str() {
+        "partition" | "partitionindex" | "partitionid" | "index" => "partition".into(),
+        "topic" | "topicname" => "entity:topicName".into(),
+        _ => name,
+    }
+}
+
+fn rust_type(field: &FieldSpec) -> String {
+    match &field.entity_type {
+        Some(entity_type) => entity_type.to_pascal_case(),
+        None => primitive(&field.type_).unwrap().rust_name().to_string(),
+    }
+}
+
+/// Converts the value of a request field into the value of a response field. `by_ref` is true
+/// when `expr` is a reference rather than a field access.
+fn convert(expr: &str, by_ref: bool, from: &FieldSpec, to: &FieldSpec) -> String {
+    let copy = primitive(&from.type_).unwrap().is_copy();
+    let value = match (copy, by_ref) {
+        (true, true) => format!("*{}", expr),
+        (true, false) => expr.to_string(),
+        (false, _) => format!("{}.clone()", expr),
+    };
+    let needs_into = rust_type(from) != rust_type(to);
+    match (is_optional(from), is_optional(to), needs_into) {
+        (false, false, false) => value,
+        (false, false, true) => format!("{}.into()", value),
+        (false, true, false) => format!("Some({})", value),
+        (false, true, true) => format!("Some({}.into())", value),
+        (true, false, false) => format!("{}.unwrap_or_default()", value),
+        (true, false, true) => format!("{}.unwrap_or_default().into()", value),
+        (true, true, false) => value,
+        (true, true, true) => format!("{}.map(Into::into)", value),
+    }
+}
+
+/// Finds the request field (or key) holding the value of the response field `target`.
+fn find_scalar(source: Source, target: &FieldSpec) -> Option<String> {
+    let prim = primitive(&target.type_)?;
+    let canonical = canonical_name(target);
+    let matches = |field: &FieldSpec| {
+        primitive(&field.type_) == Some(prim) && canonical_name(field) == canonical
+    };
+    if let Some((key, var)) = source.key {
+        if matches(key) {
+            return Some(convert(var, true, key, target));
+        }
+    }
+    source
+        .fields
+        .iter()
+        .filter(|field| !field.map_key && matches!(field.type_, TypeSpec::Primitive(_)))
+        .find(|field| matches(field))
+        .map(|field| {
+            let expr = format!("{}.{}", source.expr, rust_field_name(field));
+            convert(&expr, false, field, target)
+        })
+}
+
+enum Candidate<'a> {
+    /// A request array of structs, mirrored element by element.
+    Struct(&'a FieldSpec, &'a [FieldSpec]),
+    /// A request array of primitives, each element populating the field `.1` of the response.
+    Primitive(&'a FieldSpec, &'a FieldSpec),
+}
+
+impl<'a> Candidate<'a> {
+    fn field(&self) -> &'a FieldSpec {
+        match self {
+            Candidate::Struct(field, _) | Candidate::Primitive(field, _) => field,
+        }
+    }
+}
+
+/// Finds the request arrays which the response array with elements `target_fields` can mirror,
+/// best matches first.
+fn find_candidates<'a>(
+    ctx: &Context<'a>,
+    source: Source<'a>,
+    target_fields: &'a [FieldSpec],
+) -> Result<Vec<Candidate<'a>>, Error> {
+    let target_key = map_key(target_fields);
+    let identities: Vec<_> = target_fields
+        .iter()
+        .filter(|field| is_identity(field) && primitive(&field.type_).is_some())
+        .filter(|field| !matches!(field.type_, TypeSpec::Array(_)))
+        .collect();
+
+    let mut candidates = Vec::new();
+    for field in source.fields {
+        if !matches!(field.type_, TypeSpec::Array(_)) {
+            continue;
+        }
+        if struct_name(&field.type_).is_some() {
+            let fields = ctx.struct_fields(ctx.request, field)?;
+            let element = Source {
+                fields,
+                expr: "",
+                key: map_key(fields).map(|key| (key, "")),
+            };
+            if let Some(key) = target_key {
+                if find_scalar(element, key).is_none() {
+                    continue;
+                }
+            }
+            let score = identities
+                .iter()
+                .filter(|target| find_scalar(element, target).is_some())
+                .count();
+            if score > 0 {
+                candidates.push((score, Candidate::Struct(field, fields)));
+            }
+        } else if let Some(prim) = primitive(&field.type_) {
+            let canonical = canonical_name(field);
+            let target = identities.iter().find(|target| {
+                primitive(&target.type_) == Some(prim)
+                    && (target.entity_type.is_some() || field.entity_type.is_none())
+                    && canonical.ends_with(&canonical_name(target))
+            });
+            match (target, target_key) {
+                (Some(target), Some(key)) if target.name != key.name => {}
+                (Some(target), _) => candidates.push((1, Candidate::Primitive(field, target))),
+                (None, _) => {}
+            }
+        }
+    }
+    candidates.sort_by_key(|(score, _)| Reverse(*score));
+    Ok(candidates
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect())
+}
+
+/// Writes `f` guarded by a check that `version` is within `versions`.
+fn write_guarded<W: Write, F: FnOnce(&mut CodeWriter<W>) -> Result<(), Error>>(
+    w: &mut CodeWriter<W>,
+    valid_versions: VersionSpec,
+    versions: VersionSpec,
+    f: F,
+) -> Result<(), Error> {
+    write_version_cond(w, valid_versions, versions, f, |_| Ok(()), false, true)?;
+    w.end_line()
+}
+
+fn write_fields<W: Write>(
+    w: &mut CodeWriter<W>,
+    ctx: &Context,
+    valid_versions: VersionSpec,
+    target: &str,
+    fields: &[FieldSpec],
+    source: Option<Source>,
+    depth: usize,
+) -> Result<(), Error> {
+    for field in fields {
+        if field.map_key && map_key(fields).is_some() {
+            continue;
+        }
+        let name = rust_field_name(field);
+        if is_error_code(field) {
+            write_guarded(w, valid_versions, field.versions, |w| {
+                writeln!(w, "{}.{} = error.code();", target, name)?;
+                Ok(())
+            })?;
+            continue;
+        }
+        let source = match source {
+            Some(source) => source,
+            None => continue,
+        };
+        if let TypeSpec::Array(_) = field.type_ {
+            if struct_name(&field.type_).is_none() {
+                continue;
+            }
+            let element_fields = ctx.struct_fields(ctx.response, field)?;
+            if !ctx.has_error_code(element_fields)? {
+                continue;
+            }
+            let candidates = find_candidates(ctx, source, element_fields)?;
+            if candidates.is_empty() {
+                continue;
+            }
+            write_guarded(w, valid_versions, field.versions, |w| {
+                write_array(
+                    w,
+                    ctx,
+                    valid_versions.intersect(field.versions),
+                    target,
+                    field,
+                    element_fields,
+                    source,
+                    &candidates,
+                    depth,
+                )
+            })?;
+        } else if is_identity(field) {
+            if let Some(value) = find_scalar(source, field) {
+                write_guarded(w, valid_versions, field.versions, |w| {
+                    writeln!(w, "{}.{} = {};", target, name, value)?;
+                    Ok(())
+                })?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_array<W: Write>(
+    w: &mut CodeWriter<W>,
+    ctx: &Context,
+    valid_versions: VersionSpec,
+    target: &str,
+    field: &FieldSpec,
+    element_fields: &[FieldSpec],
+    source: Source,
+    candidates: &[Candidate],
+    depth: usize,
+) -> Result<(), Error> {
+    let items = format!("items{}", depth);
+    let target_key = map_key(element_fields);
+    if target_key.is_some() {
+        writeln!(w, "let mut {} = indexmap::IndexMap::new();", items)?;
+    } else {
+        writeln!(w, "let mut {} = Vec::new();", items)?;
+    }
+
+    let mut first = true;
+    for candidate in candidates {
+        let source_field = candidate.field();
+        let cond = if source_field.versions.contains(valid_versions) {
+            None
+        } else {
+            match version_cond(valid_versions, source_field.versions) {
+                Some(cond) => Some(cond),
+                None => continue,
+            }
+        };
+        match (&cond, first) {
+            (Some(cond), true) => write!(w, "if {} ", cond)?,
+            (Some(cond), false) => write!(w, " else if {} ", cond)?,
+            (None, true) => {}
+            (None, false) => write!(w, " else ")?,
+        }
+        let write_loop = |w: &mut CodeWriter<W>| {
+            write_loop(
+                w,
+                ctx,
+                valid_versions.intersect(source_field.versions),
+                &items,
+                field,
+                element_fields,
+                source,
+                candidate,
+                depth,
+            )
+        };
+        if first && cond.is_none() {
+            write_loop(w)?;
+        } else {
+            w.block(write_loop)?;
+        }
+        first = false;
+        if cond.is_none() {
+            break;
+        }
+    }
+    w.end_line()?;
+
+    let name = rust_field_name(field);
+    if is_optional(field) {
+        writeln!(w, "{}.{} = Some({});", target, name, items)?;
+    } else {
+        writeln!(w, "{}.{} = {};", target, name, items)?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_loop<W: Write>(
+    w: &mut CodeWriter<W>,
+    ctx: &Context,
+    valid_versions: VersionSpec,
+    items: &str,
+    field: &FieldSpec,
+    element_fields: &[FieldSpec],
+    source: Source,
+    candidate: &Candidate,
+    depth: usize,
+) -> Result<(), Error> {
+    let source_field = candidate.field();
+    let element_struct = struct_name(&field.type_).ok_or_else(|| {
+        ctx.field_error(ctx.response, field, "expected an array of structs".into())
+    })?;
+    let element = format!("request{}", depth);
+    let key = format!("key{}", depth);
+    let value = format!("response{}", depth);
+
+    let mut collection = format!("{}.{}", source.expr, rust_field_name(source_field));
+    if is_optional(source_field) {
+        collection = format!("{}.iter().flatten()", collection);
+    } else {
+        collection = format!("&{}", collection);
+    }
+    let source_key = match candidate {
+        Candidate::Struct(_, fields) => map_key(fields),
+        Candidate::Primitive(_, _) => None,
+    };
+    if source_key.is_some() {
+        write!(w, "for ({}, {}) in {} ", key, element, collection)?;
+    } else {
+        write!(w, "for {} in {} ", element, collection)?;
+    }
+    w.block(|w| {
+        writeln!(
+            w,
+            "let mut {} = super::{}::{}::default();",
+            value, ctx.response_module, element_struct
+        )?;
+        let target_key = map_key(element_fields);
+        let key_value = match candidate {
+            Candidate::Struct(_, fields) => {
+                let element_source = Source {
+                    fields,
+                    expr: &element,
+                    key: source_key.map(|source_key| (source_key, key.as_str())),
+                };
+                write_fields(
+                    w,
+                    ctx,
+                    valid_versions,
+                    &value,
+                    element_fields,
+                    Some(element_source),
+                    depth + 1,
+                )?;
+                target_key
+                    .map(|target_key| {
+                        find_scalar(element_source, target_key).ok_or_else(|| {
+                            ctx.field_error(
+                                ctx.request,
+                                source_field,
+                                format!("no field to key `{}` by", target_key.name),
+                            )
+                        })
+                    })
+                    .transpose()?
+            }
+            Candidate::Primitive(source_field, target) => {
+                let converted = convert(&element, true, source_field, target);
+                if target.map_key {
+                    write_fields(
+                        w,
+                        ctx,
+                        valid_versions,
+                        &value,
+                        element_fields,
+                        None,
+                        depth + 1,
+                    )?;
+                    Some(converted)
+                } else {
+                    writeln!(w, "{}.{} = {};", value, rust_field_name(target), converted)?;
+                    write_fields(
+                        w,
+                        ctx,
+                        valid_versions,
+                        &value,
+                        element_fields,
+                        None,
+                        depth + 1,
+                    )?;
+                    None
+                }
+            }
+        };
+        match key_value {
+            Some(key_value) => writeln!(w, "{}.insert({}, {});", items, key_value, value)?,
+            None => writeln!(w, "{}.push({});", items, value)?,
+        }
+        Ok(())
+    })
+}
+
+/// Writes the `error_response` method of `request`, building the matching `response`.
+pub fn write_error_response<W: Write>(
+    w: &mut CodeWriter<W>,
+    request: &Spec,
+    response: &Spec,
+) -> Result<(), Error> {
+    let ctx = Context {
+        request,
+        response,
+        response_module: response.name.to_snake_case(),
+    };
+    write!(w, "impl {} ", request.name)?;
+    w.block(|w| {
+        writeln!(
+            w,
+            "/// Builds a response reporting `error` for this request and every resource it contains."
+        )?;
+        writeln!(w, "///")?;
+        writeln!(
+            w,
+            "/// Topics, partitions, groups and similar resources are mirrored from the request, and every"
+        )?;
+        writeln!(
+            w,
+            "/// error code available in `version` is set. Other fields are left at their default values."
+        )?;
+        writeln!(w, "#[allow(clippy::field_reassign_with_default)]")?;
+        write!(
+            w,
+            "pub fn error_response(&self, version: i16, error: ResponseError) -> super::{} ",
+            response.name
+        )?;
+        w.block(|w| {
+            writeln!(w, "let mut response = super::{}::default();", response.name)?;
+            let source = Source {
+                fields: &request.fields,
+                expr: "self",
+                key: None,
+            };
+            write_fields(
+                w,
+                &ctx,
+                response.valid_versions,
+                "response",
+                &response.fields,
+                Some(source),
+                0,
+            )?;
+            write!(w, "response")?;
+            Ok(())
+        })?;
+        writeln!(w)?;
+        Ok(())
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+    Ok(())
+}
@@ -1,17 +1,101 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Display;
-use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::Write;
 
-use failure::Error;
+use failure::{format_err, Error};
 use inflector::Inflector;
 
 use super::code_writer::CodeWriter;
+use super::custom::CustomOptions;
+use super::error_response::write_error_response;
 use super::expr::{CmpType, Expr};
 use super::spec::{FieldSpec, PrimitiveType, Spec, SpecType, TypeSpec, VersionSpec};
 use std::cmp::Ordering;
 
-use std::path::MAIN_SEPARATOR;
+/// The APIs for which borrowed views are generated: Produce, Fetch, ListOffsets and Metadata.
+const VIEW_API_KEYS: &[i16] = &[0, 1, 2, 3];
+
+/// The messages for which a head is generated, decoding the top-level fields which precede their
+/// bulk, and only the keys of their maps.
+const HEAD_MESSAGES: &[&str] = &["ProduceRequest"];
+
+/// The crate the generated code is compiled as part of.
+#[derive(Debug, Clone, Copy)]
+pub enum Target<'a> {
+    /// The `kafka-protocol` crate itself, whose optional items are gated on its features.
+    Runtime,
+    /// A crate depending on `kafka-protocol`, which reaches the dependencies and internals of
+    /// `kafka-protocol` through `kafka_protocol::__private`, and whose optional items are included
+    /// according to `options`.
+    Custom(&'a CustomOptions),
+}
+
+impl Target<'_> {
+    /// The path of the `kafka-protocol` crate.
+    pub fn runtime(self) -> &'static str {
+        match self {
+            Target::Runtime => "crate",
+            Target::Custom(_) => "kafka_protocol",
+        }
+    }
+
+    /// The path of the internals of `kafka-protocol` which live in `module` within it.
+    pub fn internals(self, module: &str) -> String {
+        match self {
+            Target::Runtime => format!("crate::{}", module),
+            Target::Custom(_) => "kafka_protocol::__private".into(),
+        }
+    }
+
+    /// The path of the crate `name` which `kafka-protocol` depends on.
+    pub fn dependency(self, name: &str) -> String {
+        match self {
+            Target::Runtime => name.into(),
+            Target::Custom(_) => format!("kafka_protocol::__private::{}", name),
+        }
+    }
+
+    /// Writes the attribute compiling the next item only if `feature` is enabled, or disabled if
+    /// not `enabled`, returning whether the item should be written at all.
+    pub fn write_cfg<W: Write>(
+        self,
+        w: &mut W,
+        feature: &str,
+        enabled: bool,
+    ) -> Result<bool, Error> {
+        match self {
+            Target::Runtime if enabled => writeln!(w, "#[cfg(feature = \"{}\")]", feature)?,
+            Target::Runtime => writeln!(w, "#[cfg(not(feature = \"{}\"))]", feature)?,
+            Target::Custom(options) => return Ok(options.enables(feature)? == enabled),
+        }
+        Ok(true)
+    }
+
+    /// `attr` as an attribute only applied if `feature` is enabled, or `None` if it never is.
+    pub fn cfg_attr(self, feature: &str, attr: &str) -> Result<Option<String>, Error> {
+        Ok(match self {
+            Target::Runtime => Some(format!("#[cfg_attr(feature = \"{}\", {})]", feature, attr)),
+            Target::Custom(options) if options.enables(feature)? => Some(format!("#[{}]", attr)),
+            Target::Custom(_) => None,
+        })
+    }
+
+    /// Writes the attributes deriving `Serialize` and `Deserialize` for the next item, applied if
+    /// the `serde` feature is enabled.
+    pub fn write_serde_derive<W: Write>(self, w: &mut W) -> Result<(), Error> {
+        if let Some(attr) =
+            self.cfg_attr("serde", "derive(serde::Serialize, serde::Deserialize)")?
+        {
+            writeln!(w, "{}", attr)?;
+        }
+        if let Target::Custom(options) = self {
+            if options.serde {
+                writeln!(w, "#[serde(crate = \"{}\")]", self.dependency("serde"))?;
+            }
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct WrittenStruct {
@@ -28,7 +112,7 @@ pub struct EntityType {
 
 impl PartialOrd for EntityType {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.name.cmp(&other.name))
+        Some(self.cmp(other))
     }
 }
 
@@ -50,7 +134,7 @@ fn primitive_default(prim: PrimitiveType) -> PreparedDefault {
     use PrimitiveType::*;
     match prim {
         Bool => PreparedDefault::Boolean(false),
-        Int8 | Int16 | Uint16 | Int32 | Int64 => PreparedDefault::Numeric("0".into()),
+        Int8 | Int16 | Uint16 | Uint32 | Int32 | Int64 => PreparedDefault::Numeric("0".into()),
         Float64 => PreparedDefault::Numeric("0.0".into()),
         String | Bytes | Records => PreparedDefault::Empty,
         Uuid => PreparedDefault::Uuid,
@@ -61,17 +145,21 @@ fn parse_primitive_default(
     prim: PrimitiveType,
     default_str: String,
     type_: &PreparedType,
-) -> PreparedDefault {
+) -> Result<PreparedDefault, Error> {
     use PrimitiveType::*;
-    match prim {
-        Int8 | Int16 | Int32 | Int64 | Float64 => PreparedDefault::Numeric(default_str),
+    Ok(match prim {
+        Int8 | Int16 | Uint16 | Uint32 | Int32 | Int64 | Float64 => {
+            PreparedDefault::Numeric(default_str)
+        }
         String => PreparedDefault::String(default_str),
-        _ => panic!(
-            "Unexpected default value {:?} for {}",
-            default_str,
-            type_.rust_name()
-        ),
-    }
+        _ => {
+            return Err(format_err!(
+                "unsupported default value {:?} for {}",
+                default_str,
+                type_.rust_name()
+            ))
+        }
+    })
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -93,23 +181,42 @@ impl PreparedType {
             Self::Map(key, value) => format!("indexmap::IndexMap<{}, {}>", key.rust_name(), value),
         }
     }
+    /// Whether serde must be told how to handle the `StrBytes` contained in this type.
+    fn contains_str_bytes(&self) -> bool {
+        match self {
+            Self::Primitive(PrimitiveType::String) => true,
+            Self::Array(inner) | Self::Map(inner, _) => inner.contains_str_bytes(),
+            _ => false,
+        }
+    }
     fn name(&self, flexible: bool) -> String {
+        self.codec_name(flexible, "types::Struct { version }")
+    }
+    /// The name of the encoder of this type, which caches the sizes of the tagged fields of its
+    /// structs in `cache`.
+    fn encoder_name(&self, flexible: bool) -> String {
+        self.codec_name(flexible, "CachedStruct { version, cache }")
+    }
+    fn codec_name(&self, flexible: bool, struct_codec: &str) -> String {
         match self {
             Self::Primitive(prim) => prim.name(flexible).into(),
             Self::Entity(entity_type) => entity_type.inner.name(flexible).into(),
-            Self::Struct(_) => "types::Struct { version }".into(),
+            Self::Struct(_) => struct_codec.into(),
             Self::Array(inner) => {
                 if flexible {
-                    format!("types::CompactArray({})", inner.name(flexible))
+                    format!(
+                        "types::CompactArray({})",
+                        inner.codec_name(flexible, struct_codec)
+                    )
                 } else {
-                    format!("types::Array({})", inner.name(flexible))
+                    format!("types::Array({})", inner.codec_name(flexible, struct_codec))
                 }
             }
             Self::Map(_, _) => {
                 if flexible {
-                    "types::CompactArray(types::Struct { version })".into()
+                    format!("types::CompactArray({})", struct_codec)
                 } else {
-                    "types::Array(types::Struct { version })".into()
+                    format!("types::Array({})", struct_codec)
                 }
             }
         }
@@ -204,12 +311,13 @@ impl PreparedDefault {
 
 struct PreparedField {
     name: String,
+    json_name: String,
     optional: bool,
     type_: PreparedType,
     versions: VersionSpec,
     tag: Option<i32>,
     tagged_versions: VersionSpec,
-    _nullable_versions: VersionSpec,
+    nullable_versions: VersionSpec,
     default: PreparedDefault,
     ignorable: bool,
     _entity_type: Option<String>,
@@ -228,14 +336,18 @@ impl PreparedField {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn prepare_field_type<W: Write>(
     w: &mut CodeWriter<W>,
     type_: &TypeSpec,
     field: &FieldSpec,
-    common_structs_map_keys: &HashMap<String, PreparedType>, 
+    common_structs_map_keys: &HashMap<String, PreparedType>,
     entity_types: &mut BTreeSet<EntityType>,
     valid_versions: VersionSpec,
     flexible_msg_versions: VersionSpec,
+    views: bool,
+    target: Target<'_>,
+    latest_version_unstable: bool,
 ) -> Result<PreparedType, Error> {
     Ok(match type_ {
         TypeSpec::Primitive(prim) => {
@@ -261,6 +373,11 @@ fn prepare_field_type<W: Write>(
                     entity_types,
                     valid_versions,
                     flexible_msg_versions,
+                    false,
+                    views,
+                    target,
+                    None,
+                    latest_version_unstable,
                 )?;
                 PreparedType::Struct(written_struct)
             } else {
@@ -279,6 +396,9 @@ fn prepare_field_type<W: Write>(
                 entity_types,
                 valid_versions,
                 flexible_msg_versions,
+                views,
+                target,
+                latest_version_unstable,
             )?;
             match prepared_elem {
                 PreparedType::Struct(WrittenStruct {
@@ -290,15 +410,22 @@ fn prepare_field_type<W: Write>(
                     map_key: None,
                 }) if common_structs_map_keys.contains_key(&name) => {
                     // raw unwrap here because it's guaranteed by match guard
-                    PreparedType::Map(Box::new(common_structs_map_keys.get(&name).unwrap().clone()), name.clone())
-                },
+                    PreparedType::Map(
+                        Box::new(common_structs_map_keys.get(&name).unwrap().clone()),
+                        name.clone(),
+                    )
+                }
                 other => PreparedType::Array(Box::new(other)),
             }
         }
     })
 }
 
-fn prepare_common_struct_key_type(type_: &TypeSpec, field: &FieldSpec, entity_types: &mut BTreeSet<EntityType>) -> Result<PreparedType, Error> {
+fn prepare_common_struct_key_type(
+    type_: &TypeSpec,
+    field: &FieldSpec,
+    entity_types: &mut BTreeSet<EntityType>,
+) -> Result<PreparedType, Error> {
     Ok(match type_ {
         TypeSpec::Primitive(prim) => {
             if let Some(entity_type) = &field.entity_type {
@@ -313,11 +440,16 @@ fn prepare_common_struct_key_type(type_: &TypeSpec, field: &FieldSpec, entity_ty
                 PreparedType::Primitive(*prim)
             }
         }
-        _ => panic!("Found key field in common structs that is not primitive or entity type (unimplemented use-case)"),
+        _ => {
+            return Err(format_err!(
+                "map key `{}` of a common struct must be a primitive or entity type",
+                field.name
+            ))
+        }
     })
 }
 
-fn write_version_cond<
+pub(super) fn write_version_cond<
     W: Write,
     FT: FnOnce(&mut CodeWriter<W>) -> Result<(), Error>,
     FF: FnOnce(&mut CodeWriter<W>) -> Result<(), Error>,
@@ -357,16 +489,9 @@ fn write_version_cond<
     } else if always_true {
         if_true(w)?;
     } else {
-        match condition {
-            VersionSpec::None => return if_false(w),
-            VersionSpec::Exact(version) if version == max => {
-                write!(w, "if version >= {} ", version)?
-            }
-            VersionSpec::Exact(version) => write!(w, "if version == {} ", version)?,
-            VersionSpec::Since(version) => write!(w, "if version >= {} ", version)?,
-            VersionSpec::Range(a, b) if a == min => write!(w, "if version <= {} ", b)?,
-            VersionSpec::Range(a, b) if b == max => write!(w, "if version >= {} ", a)?,
-            VersionSpec::Range(a, b) => write!(w, "if version >= {} && version <= {} ", a, b)?,
+        match version_cond(valid_versions, condition) {
+            Some(cond) => write!(w, "if {} ", cond)?,
+            None => return if_false(w),
         }
         w.block(if_true)?;
         if !skip_false {
@@ -377,22 +502,98 @@ fn write_version_cond<
     Ok(())
 }
 
+/// Returns the expression testing whether `version` satisfies `condition`, or `None` if no valid
+/// version does. Callers are expected to handle the always true case themselves.
+pub(super) fn version_cond(valid_versions: VersionSpec, condition: VersionSpec) -> Option<String> {
+    let (min, max) = match valid_versions {
+        VersionSpec::None => return None,
+        VersionSpec::Exact(version) => (version, version),
+        VersionSpec::Since(_) => panic!("Valid version range should be bounded"),
+        VersionSpec::Range(a, b) => (a, b),
+    };
+
+    Some(match condition.intersect(valid_versions) {
+        VersionSpec::None => return None,
+        VersionSpec::Exact(version) if version == max => format!("version >= {}", version),
+        VersionSpec::Exact(version) => format!("version == {}", version),
+        VersionSpec::Since(version) => format!("version >= {}", version),
+        VersionSpec::Range(a, b) if a == min => format!("version <= {}", b),
+        VersionSpec::Range(a, b) if b == max => format!("version >= {}", a),
+        VersionSpec::Range(a, b) => format!("version >= {} && version <= {}", a, b),
+    })
+}
+
+/// The name of the struct field generated for a schema field.
+pub(super) fn rust_field_name(field: &FieldSpec) -> String {
+    let name = field.name.to_snake_case();
+    match name.as_str() {
+        "type" => "_type".to_string(),
+        "match" => "_match".to_string(),
+        _ => name,
+    }
+}
+
+/// The name of a schema field in the JSON representation of Kafka's `JsonConverter`.
+fn json_field_name(field: &FieldSpec) -> String {
+    let mut chars = field.name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The `map_err` adapter recording which field of `struct_name` failed to encode.
+fn encode_context(struct_name: &str, field: &str) -> String {
+    format!(
+        ".map_err(|e| e.with_field({:?}, {:?}, version))",
+        struct_name, field
+    )
+}
+
+/// The `map_err` adapter recording which field of `struct_name` failed to decode, and where.
+fn decode_context(struct_name: &str, field: &str) -> String {
+    format!(
+        ".map_err(|e| e.with_field({:?}, {:?}, version, start - buf.remaining()))",
+        struct_name, field
+    )
+}
+
+fn encode_error(struct_name: &str, field: &str, kind: &str) -> String {
+    format!(
+        "EncodeError::new(EncodeErrorKind::{}).with_field({:?}, {:?}, version)",
+        kind, struct_name, field
+    )
+}
+
+fn decode_error(struct_name: &str, field: &str, kind: &str) -> String {
+    format!(
+        "DecodeError::new(DecodeErrorKind::{}).with_field({:?}, {:?}, version, start - buf.remaining())",
+        kind, struct_name, field
+    )
+}
+
 fn write_encode_or_compute<W: Write, T: Display>(
     w: &mut CodeWriter<W>,
     type_: &str,
     var_name: T,
     compute_size: bool,
+    context: &str,
 ) -> Result<(), Error> {
     if compute_size {
-        write!(w, "total_size += {}.compute_size({})?;", type_, var_name)?;
+        write!(
+            w,
+            "total_size += {}.compute_size({}){}?;",
+            type_, var_name, context
+        )?;
     } else {
-        write!(w, "{}.encode(buf, {})?;", type_, var_name)?;
+        write!(w, "{}.encode(buf, {}){}?;", type_, var_name, context)?;
     }
     Ok(())
 }
 
 fn write_encode_field<W: Write>(
     w: &mut CodeWriter<W>,
+    struct_name: &str,
     field: &PreparedField,
     valid_versions: VersionSpec,
     compute_size: bool,
@@ -415,9 +616,9 @@ fn write_encode_field<W: Write>(
                 valid_versions,
                 field.versions,
                 // field is used in this version, encode it
-                |w| write_encode_field_inner(w, field, valid_versions, compute_size),
+                |w| write_encode_field_inner(w, struct_name, field, valid_versions, compute_size),
                 // field is not present in this version, ensure that the default value is used
-                |w| write_default_check(w, field),
+                |w| write_default_check(w, struct_name, field),
                 false,
                 field.ignorable,
             )
@@ -431,30 +632,92 @@ fn write_encode_field<W: Write>(
 
 fn write_encode_field_inner<W: Write>(
     w: &mut CodeWriter<W>,
+    struct_name: &str,
     field: &PreparedField,
     valid_versions: VersionSpec,
     compute_size: bool,
 ) -> Result<(), Error> {
     let var_name = field.var_name();
+    let context = encode_context(struct_name, &field.name);
 
     let valid_versions = valid_versions.intersect(field.versions);
+    if !compute_size {
+        write_null_check(w, struct_name, field, valid_versions)?;
+    }
     if !field.type_.has_compact_form() {
-        write_encode_or_compute(w, &field.type_.name(false), &var_name, compute_size)
+        write_encode_or_compute(
+            w,
+            &field.type_.encoder_name(false),
+            &var_name,
+            compute_size,
+            &context,
+        )
     } else {
         write_version_cond(
             w,
             valid_versions,
             field.flexible_versions,
-            |w| write_encode_or_compute(w, &field.type_.name(true), &var_name, compute_size),
-            |w| write_encode_or_compute(w, &field.type_.name(false), &var_name, compute_size),
+            |w| {
+                write_encode_or_compute(
+                    w,
+                    &field.type_.encoder_name(true),
+                    &var_name,
+                    compute_size,
+                    &context,
+                )
+            },
+            |w| {
+                write_encode_or_compute(
+                    w,
+                    &field.type_.encoder_name(false),
+                    &var_name,
+                    compute_size,
+                    &context,
+                )
+            },
             false,
             false,
         )
     }
 }
 
+/// Writes the check rejecting `None` for `field` in the `valid_versions` in which it is not
+/// nullable.
+fn write_null_check<W: Write>(
+    w: &mut CodeWriter<W>,
+    struct_name: &str,
+    field: &PreparedField,
+    valid_versions: VersionSpec,
+) -> Result<(), Error> {
+    if !field.optional || field.nullable_versions.contains(valid_versions) {
+        return Ok(());
+    }
+    write_version_cond(
+        w,
+        valid_versions,
+        field.nullable_versions,
+        |_| Ok(()),
+        |w| {
+            write!(w, "if {} ", field.var_name().method("is_none", ""))?;
+            w.block(|w| {
+                write!(
+                    w,
+                    "return Err({});",
+                    encode_error(struct_name, &field.name, "NullNotValidAtVersion")
+                )?;
+                Ok(())
+            })
+        },
+        true,
+        false,
+    )?;
+    writeln!(w)?;
+    Ok(())
+}
+
 fn write_default_check<W: Write>(
     w: &mut CodeWriter<W>,
+    struct_name: &str,
     field: &PreparedField,
 ) -> Result<(), Error> {
     let var_name = field.var_name();
@@ -465,7 +728,11 @@ fn write_default_check<W: Write>(
         .not();
     write!(w, "if {is_default} ")?;
     w.block(|w| {
-        write!(w, "return Err(EncodeError)")?;
+        write!(
+            w,
+            "return Err({});",
+            encode_error(struct_name, &field.name, "FieldNotValidAtVersion")
+        )?;
         Ok(())
     })
 }
@@ -474,17 +741,17 @@ fn write_size_check<W: Write, T: Display>(
     w: &mut CodeWriter<W>,
     expr: T,
     t: &str,
-    msg: &str,
+    err: &str,
 ) -> Result<(), Error> {
     writeln!(w, "if {} > std::{}::MAX as usize {{", expr, t)?;
-    writeln!(w, "    error!({:?}, {});", msg, expr)?;
-    writeln!(w, "    return Err(EncodeError);")?;
+    writeln!(w, "    return Err({});", err)?;
     writeln!(w, "}}")?;
     Ok(())
 }
 
 fn write_encode_tag_buffer<W: Write>(
     w: &mut CodeWriter<W>,
+    struct_name: &str,
     prepared_fields: &[PreparedField],
     valid_versions: VersionSpec,
     flexible_msg_versions: VersionSpec,
@@ -553,13 +820,14 @@ fn write_encode_tag_buffer<W: Write>(
                 w,
                 "num_tagged_fields",
                 "u32",
-                "Too many tagged fields to encode ({} fields)",
+                &encode_error(struct_name, "unknown_tagged_fields", "ValueTooLarge"),
             )?;
             write_encode_or_compute(
                 w,
                 "types::UnsignedVarInt",
                 "num_tagged_fields as u32",
                 compute_size,
+                &encode_context(struct_name, "unknown_tagged_fields"),
             )?;
             writeln!(w)?;
 
@@ -569,7 +837,7 @@ fn write_encode_tag_buffer<W: Write>(
                 current_tag += 1;
                 if k != current_tag {
                     if !compute_size {
-                        writeln!(w, "write_unknown_tagged_fields(buf, {}..{}, &self.unknown_tagged_fields)?;", current_tag, k)?;
+                        writeln!(w, "write_unknown_tagged_fields(buf, {}..{}, &self.unknown_tagged_fields){}?;", current_tag, k, encode_context(struct_name, "unknown_tagged_fields"))?;
                     }
                     current_tag = k;
                 }
@@ -587,13 +855,14 @@ fn write_encode_tag_buffer<W: Write>(
                             |w| {
                                 write_encode_tag_buffer_inner(
                                     w,
+                                    struct_name,
                                     field,
                                     k,
                                     valid_versions,
                                     compute_size,
                                 )
                             },
-                            |w| write_default_check(w, field),
+                            |w| write_default_check(w, struct_name, field),
                             false,
                             field.ignorable,
                         )?;
@@ -608,12 +877,13 @@ fn write_encode_tag_buffer<W: Write>(
             writeln!(w)?;
 
             if compute_size {
-                write!(w, "total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;")?;
+                write!(w, "total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields){}?;", encode_context(struct_name, "unknown_tagged_fields"))?;
             } else {
                 write!(
                     w,
-                    "write_unknown_tagged_fields(buf, {}.., &self.unknown_tagged_fields)?;",
-                    current_tag + 1
+                    "write_unknown_tagged_fields(buf, {}.., &self.unknown_tagged_fields){}?;",
+                    current_tag + 1,
+                    encode_context(struct_name, "unknown_tagged_fields")
                 )?;
             }
 
@@ -629,42 +899,47 @@ fn write_encode_tag_buffer<W: Write>(
 
 fn write_encode_tag_buffer_inner<W: Write>(
     w: &mut CodeWriter<W>,
+    struct_name: &str,
     field: &PreparedField,
     k: i32,
     valid_versions: VersionSpec,
     compute_size: bool,
 ) -> Result<(), Error> {
     let var_name = &field.var_name();
+    let context = encode_context(struct_name, &field.name);
     let valid_versions = valid_versions.intersect(field.versions);
+    if !compute_size {
+        write_null_check(w, struct_name, field, valid_versions)?;
+    }
     let is_default = field.default.gen_is_default(var_name, field.optional).not();
     write!(w, "if {is_default} ")?;
     w.block(|w| {
+        // The size of fields holding structs is cached, as it is computed again when encoding
+        // every tagged field they are nested in otherwise
+        let cached = !matches!(NestedStructs::of(&field.type_), NestedStructs::None);
+        let write_computed_size = |w: &mut CodeWriter<W>, flexible: bool| -> Result<(), Error> {
+            let encoder = field.type_.encoder_name(flexible);
+            if cached {
+                write!(
+                    w,
+                    "cache.size_of({}, || {}.compute_size({})){}?",
+                    var_name, encoder, var_name, context
+                )?;
+            } else {
+                write!(w, "{}.compute_size({}){}?", encoder, var_name, context)?;
+            }
+            Ok(())
+        };
         write!(w, "let computed_size = ")?;
         if !field.type_.has_compact_form() {
-            write!(
-                w,
-                "{}.compute_size({})?",
-                &field.type_.name(false),
-                var_name
-            )?;
+            write_computed_size(w, false)?;
         } else {
             write_version_cond(
                 w,
                 valid_versions,
                 field.flexible_versions,
-                |w| {
-                    write!(w, "{}.compute_size({})?", &field.type_.name(true), var_name)?;
-                    Ok(())
-                },
-                |w| {
-                    write!(
-                        w,
-                        "{}.compute_size({})?",
-                        &field.type_.name(false),
-                        var_name
-                    )?;
-                    Ok(())
-                },
+                |w| write_computed_size(w, true),
+                |w| write_computed_size(w, false),
                 false,
                 false,
             )?;
@@ -674,29 +949,52 @@ fn write_encode_tag_buffer_inner<W: Write>(
             w,
             "computed_size",
             "u32",
-            "Tagged field is too large to encode ({} bytes)",
+            &encode_error(struct_name, &field.name, "ValueTooLarge"),
         )?;
-        write_encode_or_compute(w, "types::UnsignedVarInt", k, compute_size)?;
+        write_encode_or_compute(w, "types::UnsignedVarInt", k, compute_size, &context)?;
         writeln!(w)?;
         write_encode_or_compute(
             w,
             "types::UnsignedVarInt",
             "computed_size as u32",
             compute_size,
+            &context,
         )?;
         writeln!(w)?;
         if compute_size {
             writeln!(w, "total_size += computed_size;")?;
             Ok(())
         } else if !field.type_.has_compact_form() {
-            write_encode_or_compute(w, &field.type_.name(false), var_name, compute_size)
+            write_encode_or_compute(
+                w,
+                &field.type_.encoder_name(false),
+                var_name,
+                compute_size,
+                &context,
+            )
         } else {
             write_version_cond(
                 w,
                 valid_versions,
                 field.flexible_versions,
-                |w| write_encode_or_compute(w, &field.type_.name(true), var_name, compute_size),
-                |w| write_encode_or_compute(w, &field.type_.name(false), var_name, compute_size),
+                |w| {
+                    write_encode_or_compute(
+                        w,
+                        &field.type_.encoder_name(true),
+                        var_name,
+                        compute_size,
+                        &context,
+                    )
+                },
+                |w| {
+                    write_encode_or_compute(
+                        w,
+                        &field.type_.encoder_name(false),
+                        var_name,
+                        compute_size,
+                        &context,
+                    )
+                },
                 false,
                 false,
             )
@@ -704,11 +1002,16 @@ fn write_encode_tag_buffer_inner<W: Write>(
     })
 }
 
+/// Writes the declaration of the variable decoding `field`, whose value is read by the expression
+/// returned by `decode` for the flexible or non-flexible encoding.
 fn write_decode_field<W: Write>(
     w: &mut CodeWriter<W>,
+    struct_name: &str,
     field: &PreparedField,
     valid_versions: VersionSpec,
+    decode: &dyn Fn(bool) -> String,
 ) -> Result<(), Error> {
+    let context = decode_context(struct_name, &field.name);
     let var_name = if field.map_key {
         "key_field"
     } else {
@@ -757,18 +1060,18 @@ fn write_decode_field<W: Write>(
                 |w| {
                     let valid_versions = valid_versions.intersect(field.versions);
                     if !field.type_.has_compact_form() {
-                        write!(w, "{}.decode(buf)?", field.type_.name(false))?;
+                        write!(w, "{}{}?", decode(false), context)?;
                     } else {
                         write_version_cond(
                             w,
                             valid_versions,
                             field.flexible_versions,
                             |w| {
-                                write!(w, "{}.decode(buf)?", field.type_.name(true))?;
+                                write!(w, "{}{}?", decode(true), context)?;
                                 Ok(())
                             },
                             |w| {
-                                write!(w, "{}.decode(buf)?", field.type_.name(false))?;
+                                write!(w, "{}{}?", decode(false), context)?;
                                 Ok(())
                             },
                             false,
@@ -801,6 +1104,7 @@ fn write_decode_field<W: Write>(
 
 fn write_decode_tag_buffer<W: Write>(
     w: &mut CodeWriter<W>,
+    struct_name: &str,
     prepared_fields: &[PreparedField],
     valid_versions: VersionSpec,
     flexible_msg_versions: VersionSpec,
@@ -817,18 +1121,19 @@ fn write_decode_tag_buffer<W: Write>(
                 .filter_map(|field| Some((field.tag?, field)))
                 .collect();
 
+            let unknown_context = decode_context(struct_name, "unknown_tagged_fields");
             writeln!(
                 w,
-                "let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;"
+                "let num_tagged_fields = types::UnsignedVarInt.decode(buf){}?;",
+                unknown_context
             )?;
             write!(w, "for _ in 0..num_tagged_fields ")?;
             w.block(|w| {
-                writeln!(w, "let tag: u32 = types::UnsignedVarInt.decode(buf)?;")?;
-                writeln!(w, "let size: u32 = types::UnsignedVarInt.decode(buf)?;")?;
+                writeln!(w, "let tag: u32 = types::UnsignedVarInt.decode(buf){}?;", unknown_context)?;
+                writeln!(w, "let size: u32 = types::UnsignedVarInt.decode(buf){}?;", unknown_context)?;
 
                 if sorted_tagged_fields.is_empty() {
-                    writeln!(w, "let mut unknown_value = vec![0; size as usize];")?;
-                    writeln!(w, "buf.try_copy_to_slice(&mut unknown_value)?;")?;
+                    writeln!(w, "let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from){}?;", unknown_context)?;
                     write!(
                         w,
                         "unknown_tagged_fields.insert(tag as i32, unknown_value);"
@@ -843,6 +1148,7 @@ fn write_decode_tag_buffer<W: Write>(
                             } else {
                                 &field.name
                             };
+                            let context = decode_context(struct_name, &field.name);
                             write!(w, "{} => ", k)?;
                             w.block(|w| {
                                 let tagged_field_versions =
@@ -857,9 +1163,10 @@ fn write_decode_tag_buffer<W: Write>(
                                         if !field.type_.has_compact_form() {
                                             write!(
                                                 w,
-                                                "{} = {}.decode(buf)?;",
+                                                "{} = {}.decode(buf){}?;",
                                                 var_name,
-                                                field.type_.name(false)
+                                                field.type_.name(false),
+                                                context
                                             )?;
                                         } else {
                                             write_version_cond(
@@ -869,18 +1176,20 @@ fn write_decode_tag_buffer<W: Write>(
                                                 |w| {
                                                     write!(
                                                         w,
-                                                        "{} = {}.decode(buf)?;",
+                                                        "{} = {}.decode(buf){}?;",
                                                         var_name,
-                                                        field.type_.name(true)
+                                                        field.type_.name(true),
+                                                        context
                                                     )?;
                                                     Ok(())
                                                 },
                                                 |w| {
                                                     write!(
                                                         w,
-                                                        "{} = {}.decode(buf)?;",
+                                                        "{} = {}.decode(buf){}?;",
                                                         var_name,
-                                                        field.type_.name(false)
+                                                        field.type_.name(false),
+                                                        context
                                                     )?;
                                                     Ok(())
                                                 },
@@ -891,12 +1200,11 @@ fn write_decode_tag_buffer<W: Write>(
                                         Ok(())
                                     },
                                     |w| {
-                                        writeln!(
+                                        write!(
                                             w,
-                                            "error!({:?}, tag, version);",
-                                            "Tag {} is not valid for version {}"
+                                            "return Err({});",
+                                            decode_error(struct_name, &field.name, "FieldNotValidAtVersion")
                                         )?;
-                                        write!(w, "return Err(DecodeError);")?;
                                         Ok(())
                                     },
                                     false,
@@ -907,8 +1215,7 @@ fn write_decode_tag_buffer<W: Write>(
                         }
                         write!(w, "_ => ")?;
                         w.block(|w| {
-                            writeln!(w, "let mut unknown_value = vec![0; size as usize];")?;
-                            writeln!(w, "buf.try_copy_to_slice(&mut unknown_value)?;")?;
+                            writeln!(w, "let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from){}?;", unknown_context)?;
                             write!(
                                 w,
                                 "unknown_tagged_fields.insert(tag as i32, unknown_value);"
@@ -927,14 +1234,42 @@ fn write_decode_tag_buffer<W: Write>(
     Ok(())
 }
 
+/// Writes a guard rejecting versions outside of the valid range of `message`, a type implementing
+/// `Message`.
+fn write_version_check<W: Write>(
+    w: &mut CodeWriter<W>,
+    name: &str,
+    message: &str,
+    error: &str,
+    kind: &str,
+) -> Result<(), Error> {
+    write!(w, "if !{}::VERSIONS.contains(version) ", message)?;
+    w.block(|w| {
+        write!(
+            w,
+            "return Err({}::new({}::UnsupportedVersion).with_message({:?}, version));",
+            error, kind, name
+        )?;
+        Ok(())
+    })?;
+    writeln!(w)?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn write_struct_def<W: Write>(
     w: &mut CodeWriter<W>,
     name: &str,
     fields: &[FieldSpec],
-    common_structs_map_keys: &HashMap<String, PreparedType>, 
+    common_structs_map_keys: &HashMap<String, PreparedType>,
     entity_types: &mut BTreeSet<EntityType>,
     valid_versions: VersionSpec,
     flexible_msg_versions: VersionSpec,
+    top_level: bool,
+    views: bool,
+    target: Target<'_>,
+    deprecated_versions: Option<VersionSpec>,
+    latest_version_unstable: bool,
 ) -> Result<WrittenStruct, Error> {
     let mut prepared_fields = Vec::new();
     let mut map_key = None;
@@ -942,6 +1277,7 @@ fn write_struct_def<W: Write>(
     let num_map_keys = fields.iter().filter(|field| field.map_key).count();
 
     for field in fields {
+        let field_err = |message: String| format_err!("field `{}`: {}", field.name, message);
         let type_ = prepare_field_type(
             w,
             &field.type_,
@@ -950,19 +1286,17 @@ fn write_struct_def<W: Write>(
             entity_types,
             valid_versions,
             flexible_msg_versions,
-        )?;
+            views,
+            target,
+            latest_version_unstable,
+        )
+        .map_err(|e| field_err(e.to_string()))?;
 
         if field.map_key && num_map_keys == 1 {
             map_key = Some(Box::new(type_.clone()))
         }
 
-        let mut name = field.name.to_snake_case();
-        if name == "type" {
-            name = "_type".to_string();
-        }
-        if name == "match" {
-            name = "_match".to_string();
-        }
+        let name = rust_field_name(field);
 
         let optional = !field.nullable_versions.is_none();
         let versions = field.versions.intersect(valid_versions);
@@ -970,8 +1304,18 @@ fn write_struct_def<W: Write>(
         let tagged_versions = field.tagged_versions;
         let nullable_versions = field.nullable_versions;
 
-        assert!(flexible_msg_versions.contains(flexible_versions));
-        assert!(flexible_versions.contains(tagged_versions));
+        if !flexible_msg_versions.contains(flexible_versions) {
+            return Err(field_err(format!(
+                "flexibleVersions {} are not flexible versions of the message",
+                flexible_versions
+            )));
+        }
+        if !flexible_versions.contains(tagged_versions) {
+            return Err(field_err(format!(
+                "taggedVersions {} are not all flexible",
+                tagged_versions
+            )));
+        }
 
         let default = if let Some(default) = &field.default {
             let default_str = match default {
@@ -981,11 +1325,20 @@ fn write_struct_def<W: Write>(
 
             match default_str.as_str() {
                 "null" => {
-                    assert!(nullable_versions.contains(field.versions));
+                    if !nullable_versions.contains(field.versions) {
+                        return Err(field_err(
+                            "default is null but the field is not nullable in all versions".into(),
+                        ));
+                    }
                     PreparedDefault::Null
                 }
                 "true" | "false" => {
-                    assert!(type_ == PreparedType::Primitive(PrimitiveType::Bool));
+                    if type_ != PreparedType::Primitive(PrimitiveType::Bool) {
+                        return Err(field_err(format!(
+                            "boolean default for {}",
+                            type_.rust_name()
+                        )));
+                    }
                     PreparedDefault::Boolean(default_str == "true")
                 }
                 _ => match &type_ {
@@ -995,12 +1348,13 @@ fn write_struct_def<W: Write>(
                     PreparedType::Entity(entity_type) => {
                         parse_primitive_default(entity_type.inner, default_str, &type_)
                     }
-                    _ => panic!(
-                        "Unexpected default value {:?} for {}",
+                    _ => Err(format_err!(
+                        "unsupported default value {:?} for {}",
                         default_str,
                         type_.rust_name()
-                    ),
-                },
+                    )),
+                }
+                .map_err(|e| field_err(e.to_string()))?,
             }
         } else {
             type_.default()
@@ -1008,12 +1362,13 @@ fn write_struct_def<W: Write>(
 
         prepared_fields.push(PreparedField {
             name,
+            json_name: json_field_name(field),
             optional,
             type_,
             versions,
             tag: field.tag,
             tagged_versions,
-            _nullable_versions: field.nullable_versions,
+            nullable_versions,
             default,
             ignorable: field.ignorable.unwrap_or(false),
             _entity_type: field.entity_type.clone(),
@@ -1024,11 +1379,52 @@ fn write_struct_def<W: Write>(
     }
 
     writeln!(w, "/// Valid versions: {}", valid_versions)?;
+    let deprecated_range = match deprecated_versions {
+        Some(deprecated_versions) => {
+            let range = deprecated_versions
+                .intersect(valid_versions)
+                .range()
+                .ok_or_else(|| {
+                    format_err!(
+                        "deprecatedVersions {} are not a bounded range of valid versions",
+                        deprecated_versions
+                    )
+                })?;
+            writeln!(w, "///")?;
+            writeln!(w, "/// Deprecated versions: {}", deprecated_versions)?;
+            Some(range)
+        }
+        None => None,
+    };
+    let unstable_version = match valid_versions.range() {
+        Some(range) if latest_version_unstable => {
+            let note = match target {
+                Target::Runtime => "requires the `unstable-apis` feature",
+                Target::Custom(options) if options.unstable_apis => "included in `VERSIONS`",
+                Target::Custom(_) => "not included in `VERSIONS`",
+            };
+            writeln!(w, "///")?;
+            writeln!(w, "/// Unstable versions: {} ({})", range.end(), note)?;
+            Some(*range.end())
+        }
+        _ => None,
+    };
     writeln!(w, "#[non_exhaustive]")?;
     writeln!(
         w,
         "#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]"
     )?;
+    target.write_serde_derive(w)?;
+    if let Some(attr) = target.cfg_attr("serde", "serde(default)")? {
+        writeln!(w, "{}", attr)?;
+    }
+    if let Target::Custom(_) = target {
+        writeln!(
+            w,
+            "#[builder(crate = \"{}\")]",
+            target.dependency("derive_builder")
+        )?;
+    }
     writeln!(w, "#[builder(default)]")?;
     write!(w, "pub struct {} ", name)?;
     w.block(|w| {
@@ -1039,6 +1435,24 @@ fn write_struct_def<W: Write>(
             writeln!(w, "/// {}", prepared_field.about)?;
             writeln!(w, "/// ")?;
             writeln!(w, "/// Supported API versions: {}", prepared_field.versions)?;
+            if prepared_field.optional {
+                writeln!(
+                    w,
+                    "/// May be `None` in versions: {}",
+                    prepared_field
+                        .nullable_versions
+                        .intersect(prepared_field.versions)
+                )?;
+            }
+            if prepared_field.type_.contains_str_bytes() {
+                let with = format!(
+                    "serde(with = \"{}::serde_str_bytes\")",
+                    target.internals("protocol")
+                );
+                if let Some(attr) = target.cfg_attr("serde", &with)? {
+                    writeln!(w, "{}", attr)?;
+                }
+            }
             if prepared_field.optional {
                 writeln!(
                     w,
@@ -1059,7 +1473,7 @@ fn write_struct_def<W: Write>(
 
         if !flexible_msg_versions.is_none() {
             writeln!(w, "/// Other tagged fields")?;
-            writeln!(w, "pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,")?;
+            writeln!(w, "pub unknown_tagged_fields: UnknownTaggedFields,")?;
         }
 
         Ok(())
@@ -1090,30 +1504,48 @@ fn write_struct_def<W: Write>(
     w.block(|w| {
         if let Some(key) = &map_key {
             writeln!(w, "type Key = {};", key.rust_name())?;
-            write!(w, "fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> ")?;
+            writeln!(w, "fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {{")?;
+            writeln!(w, "    self.encode_cached(key, buf, version, &SizeCache::default())")?;
+            writeln!(w, "}}")?;
+            writeln!(w, "fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {{")?;
+            writeln!(w, "    self.compute_size_cached(key, version, &SizeCache::default())")?;
+            writeln!(w, "}}")?;
+            write!(w, "fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> ")?;
         } else {
-            write!(w, "fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> ")?;
+            writeln!(w, "fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {{")?;
+            writeln!(w, "    self.encode_cached(buf, version, &SizeCache::default())")?;
+            writeln!(w, "}}")?;
+            writeln!(w, "fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {{")?;
+            writeln!(w, "    self.compute_size_cached(version, &SizeCache::default())")?;
+            writeln!(w, "}}")?;
+            write!(w, "fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> ")?;
         }
         w.block(|w| {
+            if top_level {
+                write_version_check(w, name, "Self", "EncodeError", "EncodeErrorKind")?;
+            }
             for prepared_field in &prepared_fields {
-                write_encode_field(w, prepared_field, valid_versions, false)?;
+                write_encode_field(w, name, prepared_field, valid_versions, false)?;
             }
-            write_encode_tag_buffer(w, &prepared_fields, valid_versions, flexible_msg_versions, false)?;
+            write_encode_tag_buffer(w, name, &prepared_fields, valid_versions, flexible_msg_versions, false)?;
             write!(w, "Ok(())")?;
             Ok(())
         })?;
         writeln!(w)?;
         if map_key.is_some() {
-            write!(w, "fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> ")?;
+            write!(w, "fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> ")?;
         } else {
-            write!(w, "fn compute_size(&self, version: i16) -> Result<usize, EncodeError> ")?;
+            write!(w, "fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> ")?;
         }
         w.block(|w| {
+            if top_level {
+                write_version_check(w, name, "Self", "EncodeError", "EncodeErrorKind")?;
+            }
             writeln!(w, "let mut total_size = 0;")?;
             for prepared_field in &prepared_fields {
-                write_encode_field(w, prepared_field, valid_versions, true)?;
+                write_encode_field(w, name, prepared_field, valid_versions, true)?;
             }
-            write_encode_tag_buffer(w, &prepared_fields, valid_versions, flexible_msg_versions, true)?;
+            write_encode_tag_buffer(w, name, &prepared_fields, valid_versions, flexible_msg_versions, true)?;
             write!(w, "Ok(total_size)")?;
             Ok(())
         })?;
@@ -1135,12 +1567,18 @@ fn write_struct_def<W: Write>(
             write!(w, "fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> ")?;
         }
         w.block(|w| {
+            if top_level {
+                write_version_check(w, name, "Self", "DecodeError", "DecodeErrorKind")?;
+            }
+            writeln!(w, "let start = buf.remaining();")?;
             for prepared_field in &prepared_fields {
-                write_decode_field(w, prepared_field, valid_versions)?;
+                write_decode_field(w, name, prepared_field, valid_versions, &|flexible| {
+                    format!("{}.decode(buf)", prepared_field.type_.name(flexible))
+                })?;
             }
             if !flexible_msg_versions.is_none() {
-                writeln!(w, "let mut unknown_tagged_fields = BTreeMap::new();")?;
-                write_decode_tag_buffer(w, &prepared_fields, valid_versions, flexible_msg_versions)?;
+                writeln!(w, "let mut unknown_tagged_fields = UnknownTaggedFields::new();")?;
+                write_decode_tag_buffer(w, name, &prepared_fields, valid_versions, flexible_msg_versions)?;
             }
             if map_key.is_some() {
                 write!(w, "Ok((key_field, Self ")?;
@@ -1192,7 +1630,7 @@ fn write_struct_def<W: Write>(
                 }
 
                 if !flexible_msg_versions.is_none() {
-                    writeln!(w, "unknown_tagged_fields: BTreeMap::new(),")?;
+                    writeln!(w, "unknown_tagged_fields: UnknownTaggedFields::new(),")?;
                 }
 
                 Ok(())
@@ -1207,73 +1645,1236 @@ fn write_struct_def<W: Write>(
         let range = valid_versions
             .range()
             .expect("Valid versions should be bounded.");
-        writeln!(
-            w,
-            "const VERSIONS: VersionRange = VersionRange {{ min: {}, max: {} }};",
-            range.start(),
-            range.end()
-        )?;
+        if let Some(unstable_version) = unstable_version {
+            if target.write_cfg(w, "unstable-apis", true)? {
+                writeln!(
+                    w,
+                    "const VERSIONS: VersionRange = VersionRange {{ min: {}, max: {} }};",
+                    range.start(),
+                    range.end()
+                )?;
+            }
+            if target.write_cfg(w, "unstable-apis", false)? {
+                writeln!(
+                    w,
+                    "const VERSIONS: VersionRange = VersionRange {{ min: {}, max: {} }};",
+                    range.start(),
+                    unstable_version - 1
+                )?;
+            }
+            writeln!(
+                w,
+                "const UNSTABLE_VERSIONS: Option<VersionRange> = Some(VersionRange {{ min: {}, max: {} }});",
+                unstable_version,
+                unstable_version
+            )?;
+        } else {
+            writeln!(
+                w,
+                "const VERSIONS: VersionRange = VersionRange {{ min: {}, max: {} }};",
+                range.start(),
+                range.end()
+            )?;
+        }
+        if let Some(deprecated_range) = &deprecated_range {
+            writeln!(
+                w,
+                "const DEPRECATED_VERSIONS: Option<VersionRange> = Some(VersionRange {{ min: {}, max: {} }});",
+                deprecated_range.start(),
+                deprecated_range.end()
+            )?;
+        }
         Ok(())
     })?;
     writeln!(w)?;
     writeln!(w)?;
 
+    if top_level && map_key.is_none() {
+        write_streaming_encode_impl(
+            w,
+            name,
+            &prepared_fields,
+            valid_versions,
+            flexible_msg_versions,
+        )?;
+    }
+    write_validate_impl(w, name, &prepared_fields, valid_versions)?;
+    write_json_impl(
+        w,
+        name,
+        &prepared_fields,
+        valid_versions,
+        map_key.as_deref(),
+        target,
+    )?;
+    if views {
+        write_view_def(
+            w,
+            name,
+            &prepared_fields,
+            valid_versions,
+            flexible_msg_versions,
+            top_level,
+        )?;
+        if top_level && HEAD_MESSAGES.contains(&name) {
+            write_head_def(w, name, &prepared_fields, valid_versions)?;
+        }
+    }
+
     Ok(WrittenStruct {
         name: name.into(),
         map_key,
     })
 }
 
-fn write_file_header<W: Write>(w: &mut CodeWriter<W>, name: &str) -> Result<(), Error> {
-    writeln!(w, "//! {}", name)?;
-    writeln!(w, "//!")?;
-    writeln!(w, "//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/clients/src/main/resources/common/message/{}.json).", name)?;
-    writeln!(
-        w,
-        "// WARNING: the items of this module are generated and should not be edited directly"
-    )?;
-    writeln!(w, "#![allow(unused)]")?;
-    writeln!(w)?;
-    writeln!(w, "use std::borrow::Borrow;")?;
-    writeln!(w, "use std::collections::BTreeMap;")?;
-    writeln!(w)?;
-    writeln!(w, "use bytes::Bytes;")?;
-    writeln!(w, "use log::error;")?;
-    writeln!(w, "use uuid::Uuid;")?;
-    writeln!(w)?;
-    writeln!(w, "use crate::protocol::{{")?;
-    writeln!(w, "    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,")?;
-    writeln!(w, "    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{{ByteBuf, ByteBufMut}}, Builder")?;
-    writeln!(w, "}};")?;
-    writeln!(w)?;
-    writeln!(w)?;
-    Ok(())
+/// How a field nests other generated structs, for recursing into them.
+enum NestedStructs {
+    None,
+    Single,
+    Array,
+    Map,
 }
 
-pub fn generate(
-    output_path: &str,
-    spec: Spec,
-    entity_types: &mut BTreeSet<EntityType>,
-) -> Result<(String, String), Error> {
-    let struct_name = spec.name.clone();
-    let module_name = struct_name.to_snake_case();
-
-    let file_name = format!("{}{}{}.rs", output_path, MAIN_SEPARATOR, module_name);
-    let mut file = CodeWriter::new(BufWriter::new(File::create(file_name)?));
+impl NestedStructs {
+    fn of(type_: &PreparedType) -> Self {
+        match type_ {
+            PreparedType::Struct(_) => Self::Single,
+            PreparedType::Array(inner) if matches!(**inner, PreparedType::Struct(_)) => Self::Array,
+            PreparedType::Map(_, _) => Self::Map,
+            _ => Self::None,
+        }
+    }
+}
 
-    let valid_versions = spec.valid_versions;
-    let flexible_msg_versions = spec.flexible_versions.unwrap_or_default();
+/// The type of the elements of an array field which can be streamed, and the wrapper streaming
+/// them into its encoder.
+fn streamed_element(field: &PreparedField) -> Option<(String, &'static str)> {
+    if !field.tagged_versions.intersect(field.versions).is_none() {
+        return None;
+    }
+    match &field.type_ {
+        PreparedType::Array(inner) => Some((inner.rust_name(), "types::Streamed")),
+        PreparedType::Map(key, value) => Some((
+            format!("({}, {})", key.rust_name(), value),
+            "types::StreamedMap",
+        )),
+        _ => None,
+    }
+}
 
-    write_file_header(&mut file, &struct_name)?;
+fn write_streaming_encode_impl<W: Write>(
+    w: &mut CodeWriter<W>,
+    name: &str,
+    prepared_fields: &[PreparedField],
+    valid_versions: VersionSpec,
+    flexible_msg_versions: VersionSpec,
+) -> Result<(), Error> {
+    let streamed_fields: Vec<_> = prepared_fields
+        .iter()
+        .filter_map(|field| streamed_element(field).map(|element| (field, element)))
+        .collect();
+    if streamed_fields.is_empty() {
+        return Ok(());
+    }
+
+    write!(w, "impl {} ", name)?;
+    w.block(|w| {
+        for (i, (streamed, (element, wrapper))) in streamed_fields.iter().enumerate() {
+            if i > 0 {
+                writeln!(w)?;
+            }
+            writeln!(w, "/// Encode the message with the elements of `{}` produced by an iterator of known length, in place of", streamed.name)?;
+            writeln!(w, "/// the `{}` field, which is ignored. Elements are encoded as they are produced, so the array never", streamed.name)?;
+            writeln!(w, "/// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are")?;
+            writeln!(w, "/// encoded from the elements as usual.")?;
+            writeln!(w, "///")?;
+            writeln!(w, "/// If the iterator produces a different number of elements than its length, an error is returned after")?;
+            writeln!(w, "/// part of the message was written to `buf`, which should then be discarded.")?;
+            writeln!(w, "pub fn encode_with_{}<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, {}: I) -> Result<(), EncodeError>", streamed.name, streamed.name)?;
+            writeln!(w, "where")?;
+            writeln!(w, "    I: IntoIterator<Item = {}>,", element)?;
+            writeln!(w, "    I::IntoIter: ExactSizeIterator,")?;
+            w.block(|w| {
+                write_version_check(w, name, "Self", "EncodeError", "EncodeErrorKind")?;
+                writeln!(w, "let cache = &SizeCache::default();")?;
+                writeln!(w, "let {} = {}.into_iter();", streamed.name, streamed.name)?;
+                for prepared_field in prepared_fields {
+                    if prepared_field.name != streamed.name {
+                        write_encode_field(w, name, prepared_field, valid_versions, false)?;
+                        continue;
+                    }
+                    let context = encode_context(name, &streamed.name);
+                    let value = format!("{}({})", wrapper, streamed.name);
+                    write_version_cond(
+                        w,
+                        valid_versions,
+                        streamed.versions,
+                        |w| {
+                            write_version_cond(
+                                w,
+                                valid_versions.intersect(streamed.versions),
+                                streamed.flexible_versions,
+                                |w| write_encode_or_compute(w, &streamed.type_.encoder_name(true), &value, false, &context),
+                                |w| write_encode_or_compute(w, &streamed.type_.encoder_name(false), &value, false, &context),
+                                false,
+                                false,
+                            )
+                        },
+                        |w| {
+                            write!(w, "if {}.len() != 0 ", streamed.name)?;
+                            w.block(|w| {
+                                write!(
+                                    w,
+                                    "return Err({});",
+                                    encode_error(name, &streamed.name, "FieldNotValidAtVersion")
+                                )?;
+                                Ok(())
+                            })
+                        },
+                        false,
+                        streamed.ignorable,
+                    )?;
+                    writeln!(w)?;
+                }
+                write_encode_tag_buffer(w, name, prepared_fields, valid_versions, flexible_msg_versions, false)?;
+                write!(w, "Ok(())")?;
+                Ok(())
+            })?;
+            writeln!(w)?;
+        }
+        Ok(())
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+/// Writes `body` for every struct nested in `field`, which is bound to `item`. When not `mutable`,
+/// the position of `item` is bound to `i`.
+fn write_for_each_nested<W: Write, F: Fn(&mut CodeWriter<W>, &str) -> Result<(), Error>>(
+    w: &mut CodeWriter<W>,
+    field: &PreparedField,
+    mutable: bool,
+    body: F,
+) -> Result<(), Error> {
+    let nested = NestedStructs::of(&field.type_);
+    let reference = if mutable { "&mut " } else { "&" };
+    let write_nested = |w: &mut CodeWriter<W>, collection: &str| -> Result<(), Error> {
+        let iter = match (&nested, mutable) {
+            (NestedStructs::None, _) => return Ok(()),
+            (NestedStructs::Single, _) => return body(w, collection),
+            (NestedStructs::Array, false) => "iter",
+            (NestedStructs::Array, true) => "iter_mut",
+            (NestedStructs::Map, false) => "values",
+            (NestedStructs::Map, true) => "values_mut",
+        };
+        if mutable {
+            write!(w, "for item in {}.{}() ", collection, iter)?;
+        } else {
+            write!(w, "for (i, item) in {}.{}().enumerate() ", collection, iter)?;
+        }
+        w.block(|w| body(w, "item"))
+    };
+    if field.optional {
+        let binding = match nested {
+            NestedStructs::Single => "item",
+            _ => "items",
+        };
+        write!(
+            w,
+            "if let Some({}) = {}self.{} ",
+            binding, reference, field.name
+        )?;
+        w.block(|w| write_nested(w, binding))
+    } else {
+        write_nested(w, &format!("self.{}", field.name))
+    }
+}
+
+fn write_validate_impl<W: Write>(
+    w: &mut CodeWriter<W>,
+    name: &str,
+    prepared_fields: &[PreparedField],
+    valid_versions: VersionSpec,
+) -> Result<(), Error> {
+    // Map keys identify their entries, and Kafka's schemas make them valid wherever their map is,
+    // so they are neither reported nor reset
+    let fields: Vec<&PreparedField> = prepared_fields.iter().filter(|f| !f.map_key).collect();
+
+    write!(w, "impl {} ", name)?;
+    w.block(|w| {
+        writeln!(w, "/// Lists the fields set to non-default values which are not valid in `version`.")?;
+        writeln!(w, "///")?;
+        writeln!(w, "/// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported")?;
+        writeln!(w, "/// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.")?;
+        write!(w, "pub fn validate(&self, version: i16) -> Vec<String> ")?;
+        w.block(|w| {
+            writeln!(w, "let mut invalid = Vec::new();")?;
+            writeln!(w, "self.validate_into(version, \"\", &mut invalid);")?;
+            write!(w, "invalid")?;
+            Ok(())
+        })?;
+        writeln!(w)?;
+        writeln!(w)?;
+
+        writeln!(w, "/// Resets every field which is not valid in `version` to its default value, so that the")?;
+        writeln!(w, "/// message encodes at `version` without any of its fields being dropped or rejected.")?;
+        write!(w, "pub fn normalize_to(&mut self, version: i16) ")?;
+        w.block(|w| {
+            for field in &fields {
+                let nested = NestedStructs::of(&field.type_);
+                if matches!(nested, NestedStructs::None) && field.versions.contains(valid_versions) {
+                    continue;
+                }
+                write_version_cond(
+                    w,
+                    valid_versions,
+                    field.versions,
+                    |w| write_for_each_nested(w, field, true, |w, item| {
+                        write!(w, "{}.normalize_to(version);", item)?;
+                        Ok(())
+                    }),
+                    |w| {
+                        write!(
+                            w,
+                            "self.{} = {};",
+                            field.name,
+                            field.default.gen_default(field.optional, field.type_.is_entity())
+                        )?;
+                        Ok(())
+                    },
+                    matches!(nested, NestedStructs::None),
+                    false,
+                )?;
+                w.end_line()?;
+            }
+            Ok(())
+        })?;
+        writeln!(w)?;
+        writeln!(w)?;
+
+        write!(w, "fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) ")?;
+        w.block(|w| {
+            for field in &fields {
+                let nested = NestedStructs::of(&field.type_);
+                if matches!(nested, NestedStructs::None) && field.versions.contains(valid_versions) {
+                    continue;
+                }
+                let path = match nested {
+                    NestedStructs::Single => format!("&format!(\"{{}}{}.\", path)", field.name),
+                    _ => format!("&format!(\"{{}}{}[{{}}].\", path, i)", field.name),
+                };
+                write_version_cond(
+                    w,
+                    valid_versions,
+                    field.versions,
+                    |w| write_for_each_nested(w, field, false, |w, item| {
+                        write!(w, "{}.validate_into(version, {}, invalid);", item, path)?;
+                        Ok(())
+                    }),
+                    |w| {
+                        let is_default = field
+                            .default
+                            .gen_is_default(&field.var_name(), field.optional)
+                            .not();
+                        write!(w, "if {} ", is_default)?;
+                        w.block(|w| {
+                            write!(w, "invalid.push(format!(\"{{}}{}\", path));", field.name)?;
+                            Ok(())
+                        })
+                    },
+                    matches!(nested, NestedStructs::None),
+                    false,
+                )?;
+                w.end_line()?;
+            }
+            Ok(())
+        })
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+/// The expression converting `field` of `self` to its JSON representation.
+fn json_write_expr(field: &PreparedField) -> String {
+    let nested = NestedStructs::of(&field.type_);
+    let binding = match nested {
+        NestedStructs::None => return format!("JsonValue::to_json({})", field.var_name()),
+        NestedStructs::Single => "item",
+        NestedStructs::Array | NestedStructs::Map => "items",
+    };
+    let source = if field.optional {
+        binding.to_string()
+    } else {
+        format!("self.{}", field.name)
+    };
+    let value = match nested {
+        NestedStructs::Map => format!(
+            "serde_json::Value::Array({}.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect())",
+            source
+        ),
+        NestedStructs::Array => format!(
+            "serde_json::Value::Array({}.iter().map(|item| item.to_json_with(version, records)).collect())",
+            source
+        ),
+        _ => format!("{}.to_json_with(version, records)", source),
+    };
+    if field.optional {
+        format!(
+            "self.{}.as_ref().map(|{}| {}).unwrap_or(serde_json::Value::Null)",
+            field.name, binding, value
+        )
+    } else {
+        value
+    }
+}
+
+/// The expression reading `field` of `struct_name` from the JSON `value`.
+fn json_read_expr(struct_name: &str, field: &PreparedField, target: Target<'_>) -> String {
+    let about = format!("{}.{}", struct_name, field.json_name);
+    let value = match &field.type_ {
+        PreparedType::Struct(inner) => format!("{}::from_json(value, version)?", inner.name),
+        PreparedType::Array(inner) if matches!(**inner, PreparedType::Struct(_)) => format!(
+            "{}::array(value, {:?})?.iter().map(|item| {}::from_json(item, version)).collect::<Result<_, _>>()?",
+            target.internals("json"),
+            about,
+            inner.rust_name()
+        ),
+        PreparedType::Map(_, inner) => format!(
+            "{}::array(value, {:?})?.iter().map(|item| {}::from_json(item, version)).collect::<Result<_, _>>()?",
+            target.internals("json"),
+            about,
+            inner
+        ),
+        _ => return format!("JsonValue::from_json(value, {:?})?", about),
+    };
+    if field.optional {
+        format!("if value.is_null() {{ None }} else {{ Some({}) }}", value)
+    } else {
+        value
+    }
+}
+
+fn write_json_impl<W: Write>(
+    w: &mut CodeWriter<W>,
+    name: &str,
+    prepared_fields: &[PreparedField],
+    valid_versions: VersionSpec,
+    map_key: Option<&PreparedType>,
+    target: Target<'_>,
+) -> Result<(), Error> {
+    if !target.write_cfg(w, "json", true)? {
+        return Ok(());
+    }
+    write!(w, "impl {} ", name)?;
+    w.block(|w| {
+        let (key_param, key_arg) = match map_key {
+            Some(key) => (format!("key: &{}, ", key.rust_name()), "key, "),
+            None => (String::new(), ""),
+        };
+        writeln!(w, "/// Converts this message to the JSON representation written by Kafka's `JsonConverter` for")?;
+        writeln!(w, "/// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].")?;
+        writeln!(w, "///")?;
+        writeln!(w, "/// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.")?;
+        write!(w, "pub fn to_json(&self, {}version: i16) -> serde_json::Value ", key_param)?;
+        w.block(|w| {
+            write!(w, "self.to_json_with({}version, RecordsJson::SizeInBytes)", key_arg)?;
+            Ok(())
+        })?;
+        writeln!(w)?;
+        writeln!(w)?;
+
+        writeln!(w, "/// Converts this message to its JSON representation for `version` like [`Self::to_json`],")?;
+        writeln!(w, "/// writing records as `records` says.")?;
+        write!(w, "pub fn to_json_with(&self, {}version: i16, records: RecordsJson) -> serde_json::Value ", key_param)?;
+        w.block(|w| {
+            writeln!(w, "let mut node = serde_json::Map::new();")?;
+            for field in prepared_fields {
+                let insert = |w: &mut CodeWriter<W>| -> Result<(), Error> {
+                    if let PreparedType::Primitive(PrimitiveType::Records) = field.type_ {
+                        let value = if field.optional {
+                            format!("self.{}.as_ref()", field.name)
+                        } else {
+                            format!("Some(&self.{})", field.name)
+                        };
+                        write!(
+                            w,
+                            "{}::insert_records(&mut node, {:?}, {}, records);",
+                            target.internals("json"),
+                            field.json_name,
+                            value
+                        )?;
+                        return Ok(());
+                    }
+                    write!(w, "node.insert({:?}.into(), {});", field.json_name, json_write_expr(field))?;
+                    Ok(())
+                };
+                write_version_cond(
+                    w,
+                    valid_versions,
+                    field.versions,
+                    |w| {
+                        if field.tag.is_none() {
+                            return insert(w);
+                        }
+                        write_version_cond(
+                            w,
+                            field.versions.intersect(valid_versions),
+                            field.tagged_versions,
+                            |w| {
+                                let is_default = field
+                                    .default
+                                    .gen_is_default(&field.var_name(), field.optional)
+                                    .not();
+                                write!(w, "if {} ", is_default)?;
+                                w.block(insert)
+                            },
+                            insert,
+                            false,
+                            false,
+                        )
+                    },
+                    |_| Ok(()),
+                    false,
+                    true,
+                )?;
+                w.end_line()?;
+            }
+            write!(w, "serde_json::Value::Object(node)")?;
+            Ok(())
+        })?;
+        writeln!(w)?;
+        writeln!(w)?;
+
+        writeln!(w, "/// Reads this message from the JSON representation written by Kafka's `JsonConverter` for")?;
+        writeln!(w, "/// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.")?;
+        if let Some(key) = map_key {
+            write!(w, "pub fn from_json(node: &serde_json::Value, version: i16) -> Result<({}, Self), JsonError> ", key.rust_name())?;
+        } else {
+            write!(w, "pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> ")?;
+        }
+        w.block(|w| {
+            writeln!(w, "let object = {}::object(node, {:?})?;", target.internals("json"), name)?;
+            writeln!(w, "let mut this = Self::default();")?;
+            if let Some(key) = map_key {
+                writeln!(w, "let mut key = {}::default();", key.rust_name())?;
+            }
+            for field in prepared_fields {
+                let target_field = if field.map_key {
+                    "key".to_string()
+                } else {
+                    format!("this.{}", field.name)
+                };
+                write!(w, "if let Some(value) = object.get({:?}) ", field.json_name)?;
+                w.block(|w| {
+                    write!(w, "{} = {};", target_field, json_read_expr(name, field, target))?;
+                    Ok(())
+                })?;
+                let missing = |w: &mut CodeWriter<W>| -> Result<(), Error> {
+                    write!(
+                        w,
+                        "return Err({}::missing({:?}, {:?}, version));",
+                        target.internals("json"),
+                        name,
+                        field.json_name
+                    )?;
+                    Ok(())
+                };
+                if field.tag.is_none() {
+                    if field.versions.contains(valid_versions) {
+                        write!(w, " else ")?;
+                        w.block(missing)?;
+                    } else if let Some(cond) = version_cond(valid_versions, field.versions) {
+                        write!(w, " else if {} ", cond)?;
+                        w.block(missing)?;
+                    }
+                }
+                w.end_line()?;
+            }
+            if map_key.is_some() {
+                write!(w, "Ok((key, this))")?;
+            } else {
+                write!(w, "Ok(this)")?;
+            }
+            Ok(())
+        })
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+/// The type of `field` in the view of its struct.
+fn view_type(type_: &PreparedType) -> String {
+    match type_ {
+        PreparedType::Primitive(_) | PreparedType::Entity(_) => type_.rust_name(),
+        PreparedType::Struct(inner) => format!("{}View", inner.name),
+        PreparedType::Array(inner) => format!("ArrayView<{}>", view_type(inner)),
+        PreparedType::Map(_, inner) => format!("ArrayView<{}View>", inner),
+    }
+}
+
+/// The expression skipping over a value of a primitive type in `buf`, without decoding it.
+fn primitive_skip_expr(prim: PrimitiveType, buf: &str, flexible: bool) -> String {
+    let size = match prim {
+        PrimitiveType::Bool | PrimitiveType::Int8 => 1,
+        PrimitiveType::Int16 | PrimitiveType::Uint16 => 2,
+        PrimitiveType::Int32 | PrimitiveType::Uint32 => 4,
+        PrimitiveType::Int64 | PrimitiveType::Float64 => 8,
+        PrimitiveType::Uuid => 16,
+        PrimitiveType::String => return format!("view::skip_string({}, {})", buf, flexible),
+        PrimitiveType::Bytes | PrimitiveType::Records => {
+            return format!("view::skip_bytes({}, {})", buf, flexible)
+        }
+    };
+    format!("view::skip_fixed({}, {})", buf, size)
+}
+
+/// The function skipping over one element of an array of `type_`.
+fn view_skip_fn(type_: &PreparedType, flexible: bool) -> String {
+    match type_ {
+        PreparedType::Primitive(prim) => {
+            format!("|buf, _| {}", primitive_skip_expr(*prim, "buf", flexible))
+        }
+        PreparedType::Entity(entity_type) => {
+            format!(
+                "|buf, _| {}",
+                primitive_skip_expr(entity_type.inner, "buf", flexible)
+            )
+        }
+        PreparedType::Struct(inner) => format!("{}View::skip", inner.name),
+        PreparedType::Map(_, inner) => format!("{}View::skip", inner),
+        PreparedType::Array(_) => panic!("Arrays of arrays are not supported"),
+    }
+}
+
+/// The expression skipping over `field` in `buf`, reading no more than the lengths it contains.
+fn view_skip_expr(field: &PreparedField, buf: &str, flexible: bool) -> String {
+    match &field.type_ {
+        PreparedType::Primitive(prim) => primitive_skip_expr(*prim, buf, flexible),
+        PreparedType::Entity(entity_type) => primitive_skip_expr(entity_type.inner, buf, flexible),
+        PreparedType::Struct(inner) if field.optional => {
+            format!(
+                "view::skip_nullable_struct({}, version, {}View::skip)",
+                buf, inner.name
+            )
+        }
+        PreparedType::Struct(inner) => format!("{}View::skip({}, version)", inner.name, buf),
+        PreparedType::Array(inner) => format!(
+            "view::skip_array({}, version, {}, {})",
+            buf,
+            flexible,
+            view_skip_fn(inner, flexible)
+        ),
+        PreparedType::Map(_, _) => format!(
+            "view::skip_array({}, version, {}, {})",
+            buf,
+            flexible,
+            view_skip_fn(&field.type_, flexible)
+        ),
+    }
+}
+
+/// Writes the statement skipping over `field` in `buf`, in the versions it is present and not
+/// tagged in.
+fn write_skip_field<W: Write>(
+    w: &mut CodeWriter<W>,
+    struct_name: &str,
+    field: &PreparedField,
+    valid_versions: VersionSpec,
+) -> Result<(), Error> {
+    let context = decode_context(struct_name, &field.name);
+    write_version_cond(
+        w,
+        valid_versions,
+        field.tagged_versions,
+        |_| Ok(()),
+        |w| {
+            write_version_cond(
+                w,
+                valid_versions,
+                field.versions,
+                |w| {
+                    let valid_versions = valid_versions.intersect(field.versions);
+                    if !field.type_.has_compact_form() {
+                        write!(w, "{}{}?;", view_skip_expr(field, "buf", false), context)?;
+                        Ok(())
+                    } else {
+                        write_version_cond(
+                            w,
+                            valid_versions,
+                            field.flexible_versions,
+                            |w| {
+                                write!(w, "{}{}?;", view_skip_expr(field, "buf", true), context)?;
+                                Ok(())
+                            },
+                            |w| {
+                                write!(w, "{}{}?;", view_skip_expr(field, "buf", false), context)?;
+                                Ok(())
+                            },
+                            false,
+                            false,
+                        )
+                    }
+                },
+                |_| Ok(()),
+                false,
+                true,
+            )
+        },
+        true,
+        false,
+    )?;
+    w.end_line()
+}
+
+/// Writes the statement skipping over the tagged fields at the end of a struct, including the
+/// known ones.
+fn write_skip_tag_buffer<W: Write>(
+    w: &mut CodeWriter<W>,
+    struct_name: &str,
+    valid_versions: VersionSpec,
+    flexible_msg_versions: VersionSpec,
+) -> Result<(), Error> {
+    write_version_cond(
+        w,
+        valid_versions,
+        flexible_msg_versions,
+        |w| {
+            write!(
+                w,
+                "view::skip_tagged_fields(buf, version){}?;",
+                decode_context(struct_name, "tagged_fields")
+            )?;
+            Ok(())
+        },
+        |_| Ok(()),
+        false,
+        true,
+    )?;
+    w.end_line()
+}
+
+/// The expression decoding `field` from `buf` in the view of its struct.
+fn view_decode_expr(field: &PreparedField, buf: &str, flexible: bool) -> String {
+    let element = |type_: &PreparedType| match type_ {
+        PreparedType::Primitive(_) | PreparedType::Entity(_) => {
+            format!("|buf, _| {}.decode(buf)", type_.name(flexible))
+        }
+        PreparedType::Struct(inner) => format!("{}View::decode", inner.name),
+        PreparedType::Array(_) | PreparedType::Map(_, _) => {
+            panic!("Arrays of arrays are not supported")
+        }
+    };
+    match &field.type_ {
+        PreparedType::Primitive(_) | PreparedType::Entity(_) => {
+            format!("{}.decode({})", field.type_.name(flexible), buf)
+        }
+        PreparedType::Struct(inner) if field.optional => format!(
+            "types::Int8.decode({buf}).and_then(|present| if present == -1 {{ Ok(None) }} else {{ {}View::decode({buf}, version).map(Some) }})",
+            inner.name,
+            buf = buf
+        ),
+        PreparedType::Struct(inner) => format!("{}View::decode({}, version)", inner.name, buf),
+        PreparedType::Array(_) | PreparedType::Map(_, _) => {
+            let (decode_fn, skip_fn) = match &field.type_ {
+                PreparedType::Array(inner) => (element(inner), view_skip_fn(inner, flexible)),
+                PreparedType::Map(_, inner) => (
+                    format!("{}View::decode", inner),
+                    view_skip_fn(&field.type_, flexible),
+                ),
+                _ => unreachable!(),
+            };
+            let method = if field.optional { "decode_nullable" } else { "decode" };
+            format!(
+                "ArrayView::{}({}, version, {}, {}, {})",
+                method, buf, flexible, decode_fn, skip_fn
+            )
+        }
+    }
+}
+
+fn write_view_def<W: Write>(
+    w: &mut CodeWriter<W>,
+    name: &str,
+    prepared_fields: &[PreparedField],
+    valid_versions: VersionSpec,
+    flexible_msg_versions: VersionSpec,
+    top_level: bool,
+) -> Result<(), Error> {
+    let view_name = format!("{}View", name);
+    let field_type = |field: &PreparedField| {
+        if field.optional {
+            format!("Option<{}>", view_type(&field.type_))
+        } else {
+            view_type(&field.type_)
+        }
+    };
+    let field_default = |field: &PreparedField| {
+        field
+            .default
+            .gen_default(field.optional, field.type_.is_entity())
+    };
+
+    writeln!(
+        w,
+        "/// A view of [`{}`], decoded without allocating from the buffer it borrows.",
+        name
+    )?;
+    writeln!(w, "///")?;
+    writeln!(w, "/// Valid versions: {}", valid_versions)?;
+    writeln!(w, "#[non_exhaustive]")?;
+    writeln!(w, "#[derive(Debug, Clone, PartialEq)]")?;
+    write!(w, "pub struct {} ", view_name)?;
+    w.block(|w| {
+        for field in prepared_fields {
+            writeln!(w, "/// {}", field.about)?;
+            writeln!(w, "/// ")?;
+            writeln!(w, "/// Supported API versions: {}", field.versions)?;
+            writeln!(w, "pub {}: {},", field.name, field_type(field))?;
+            writeln!(w)?;
+        }
+        if !flexible_msg_versions.is_none() {
+            writeln!(w, "/// Every tagged field as its tag and encoded value, including the known ones decoded above.")?;
+            writeln!(w, "pub tagged_fields: ArrayView<(i32, Bytes)>,")?;
+        }
+        Ok(())
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+
+    write!(w, "impl {} ", view_name)?;
+    w.block(|w| {
+        writeln!(w, "/// Decodes a view of the struct at `version`, borrowing from `buf`.")?;
+        write!(w, "pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> ")?;
+        w.block(|w| {
+            if top_level {
+                write_version_check(w, name, name, "DecodeError", "DecodeErrorKind")?;
+            }
+            writeln!(w, "let start = buf.remaining();")?;
+            for field in prepared_fields {
+                write_decode_field(w, name, field, valid_versions, &|flexible| {
+                    view_decode_expr(field, "buf", flexible)
+                })?;
+            }
+            if !flexible_msg_versions.is_none() {
+                write_view_decode_tag_buffer(w, name, prepared_fields, valid_versions, flexible_msg_versions)?;
+            }
+            write!(w, "Ok(Self ")?;
+            w.block(|w| {
+                for field in prepared_fields {
+                    if field.map_key {
+                        writeln!(w, "{}: key_field,", field.name)?;
+                    } else {
+                        writeln!(w, "{},", field.name)?;
+                    }
+                }
+                if !flexible_msg_versions.is_none() {
+                    writeln!(w, "tagged_fields,")?;
+                }
+                Ok(())
+            })?;
+            write!(w, ")")?;
+            Ok(())
+        })?;
+        if top_level {
+            return Ok(());
+        }
+        writeln!(w)?;
+        writeln!(w)?;
+
+        writeln!(w, "/// Skips over the struct at `version`, reading no more than the lengths of its fields.")?;
+        write!(w, "pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> ")?;
+        w.block(|w| {
+            writeln!(w, "let start = buf.remaining();")?;
+            for field in prepared_fields {
+                write_skip_field(w, name, field, valid_versions)?;
+            }
+            write_skip_tag_buffer(w, name, valid_versions, flexible_msg_versions)?;
+            write!(w, "Ok(())")?;
+            Ok(())
+        })?;
+
+        if let Some(key) = prepared_fields.iter().find(|field| field.map_key) {
+            writeln!(w)?;
+            writeln!(w)?;
+            writeln!(w, "/// Decodes the `{}` of the struct at `version`, skipping over its other fields.", key.name)?;
+            write!(
+                w,
+                "pub fn decode_key<B: ByteBuf>(buf: &mut B, version: i16) -> Result<{}, DecodeError> ",
+                field_type(key)
+            )?;
+            w.block(|w| {
+                writeln!(w, "let start = buf.remaining();")?;
+                for field in prepared_fields {
+                    if field.map_key {
+                        write_decode_field(w, name, field, valid_versions, &|flexible| {
+                            view_decode_expr(field, "buf", flexible)
+                        })?;
+                    } else {
+                        write_skip_field(w, name, field, valid_versions)?;
+                    }
+                }
+                write_skip_tag_buffer(w, name, valid_versions, flexible_msg_versions)?;
+                write!(w, "Ok(key_field)")?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+
+    write!(w, "impl Default for {} ", view_name)?;
+    w.block(|w| {
+        write!(w, "fn default() -> Self ")?;
+        w.block(|w| {
+            write!(w, "Self ")?;
+            w.block(|w| {
+                for field in prepared_fields {
+                    writeln!(w, "{}: {},", field.name, field_default(field))?;
+                }
+                if !flexible_msg_versions.is_none() {
+                    writeln!(w, "tagged_fields: Default::default(),")?;
+                }
+                Ok(())
+            })
+        })
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+/// Writes the head of the message `name`: its top-level fields, with only the keys of its maps,
+/// decoded from a buffer which may hold no more than the start of the message.
+fn write_head_def<W: Write>(
+    w: &mut CodeWriter<W>,
+    name: &str,
+    prepared_fields: &[PreparedField],
+    valid_versions: VersionSpec,
+) -> Result<(), Error> {
+    let head_name = format!("{}Head", name);
+    let mut field_types = Vec::new();
+    for field in prepared_fields {
+        let type_ = match &field.type_ {
+            PreparedType::Primitive(_) | PreparedType::Entity(_)
+                if !field.tagged_versions.is_none() =>
+            {
+                None
+            }
+            PreparedType::Primitive(_) | PreparedType::Entity(_) if field.optional => {
+                Some(format!("Option<{}>", field.type_.rust_name()))
+            }
+            PreparedType::Primitive(_) | PreparedType::Entity(_) => Some(field.type_.rust_name()),
+            PreparedType::Map(key, _) if !field.optional && field.tagged_versions.is_none() => {
+                Some(format!("Vec<{}>", key.rust_name()))
+            }
+            _ => None,
+        };
+        field_types.push(type_.ok_or_else(|| {
+            format_err!(
+                "Field {} of {} can't be decoded in its head",
+                field.name,
+                name
+            )
+        })?);
+    }
+
+    writeln!(w, "/// The top-level fields of a [`{}`], decoded with only the keys of its maps, skipping over the", name)?;
+    writeln!(w, "/// rest of their entries.")?;
+    writeln!(w, "///")?;
+    writeln!(w, "/// Skipping an entry still walks it, including the records it holds, so each key is only decoded once")?;
+    writeln!(w, "/// the entries before it have been received in full.")?;
+    writeln!(w, "///")?;
+    writeln!(w, "/// Valid versions: {}", valid_versions)?;
+    writeln!(w, "#[non_exhaustive]")?;
+    writeln!(w, "#[derive(Debug, Clone, PartialEq)]")?;
+    write!(w, "pub struct {} ", head_name)?;
+    w.block(|w| {
+        for (field, type_) in prepared_fields.iter().zip(&field_types) {
+            writeln!(w, "/// {}", field.about)?;
+            writeln!(w, "/// ")?;
+            writeln!(w, "/// Supported API versions: {}", field.versions)?;
+            if let PreparedType::Map(_, _) = field.type_ {
+                writeln!(w, "/// ")?;
+                writeln!(w, "/// Only the key of each entry is decoded.")?;
+            }
+            writeln!(w, "pub {}: {},", field.name, type_)?;
+            writeln!(w)?;
+        }
+        Ok(())
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+
+    write!(w, "impl {} ", head_name)?;
+    w.block(|w| {
+        writeln!(w, "/// Decodes the head of the message at `version`, reading no further than the keys of its last map.")?;
+        write!(w, "pub fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> ")?;
+        w.block(|w| {
+            write_version_check(w, name, name, "DecodeError", "DecodeErrorKind")?;
+            writeln!(w, "let start = buf.remaining();")?;
+            for field in prepared_fields {
+                write_decode_field(w, name, field, valid_versions, &|flexible| match &field.type_ {
+                    PreparedType::Map(_, inner) => format!(
+                        "view::decode_keys(buf, version, {}, {}View::decode_key)",
+                        flexible, inner
+                    ),
+                    _ => view_decode_expr(field, "buf", flexible),
+                })?;
+            }
+            write!(w, "Ok(Self ")?;
+            w.block(|w| {
+                for field in prepared_fields {
+                    writeln!(w, "{},", field.name)?;
+                }
+                Ok(())
+            })?;
+            write!(w, ")")?;
+            Ok(())
+        })
+    })?;
+    writeln!(w)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+fn write_view_decode_tag_buffer<W: Write>(
+    w: &mut CodeWriter<W>,
+    struct_name: &str,
+    prepared_fields: &[PreparedField],
+    valid_versions: VersionSpec,
+    flexible_msg_versions: VersionSpec,
+) -> Result<(), Error> {
+    writeln!(w, "let mut tagged_fields = ArrayView::default();")?;
+    write_version_cond(
+        w,
+        valid_versions,
+        flexible_msg_versions,
+        |w| {
+            let valid_versions = valid_versions.intersect(flexible_msg_versions);
+            let sorted_tagged_fields: BTreeMap<i32, &PreparedField> = prepared_fields
+                .iter()
+                .filter_map(|field| Some((field.tag?, field)))
+                .collect();
+
+            let unknown_context = decode_context(struct_name, "tagged_fields");
+            writeln!(
+                w,
+                "let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf){}?;",
+                unknown_context
+            )?;
+            write!(
+                w,
+                "tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field){}?;",
+                unknown_context
+            )?;
+            if sorted_tagged_fields.is_empty() {
+                return Ok(());
+            }
+            writeln!(w)?;
+            write!(w, "for tagged_field in &tagged_fields ")?;
+            w.block(|w| {
+                writeln!(w, "let (tag, mut value) = tagged_field{}?;", unknown_context)?;
+                write!(w, "match tag ")?;
+                w.block(|w| {
+                    for (&k, field) in &sorted_tagged_fields {
+                        let var_name = if field.map_key { "key_field" } else { &field.name };
+                        let context = decode_context(struct_name, &field.name);
+                        write!(w, "{} => ", k)?;
+                        w.block(|w| {
+                            let tagged_field_versions = field.tagged_versions.intersect(field.versions);
+                            write_version_cond(
+                                w,
+                                valid_versions,
+                                tagged_field_versions,
+                                |w| {
+                                    let valid_versions = valid_versions.intersect(tagged_field_versions);
+                                    let assign = |w: &mut CodeWriter<W>, flexible: bool| -> Result<(), Error> {
+                                        write!(
+                                            w,
+                                            "{} = {}{}?;",
+                                            var_name,
+                                            view_decode_expr(field, "&mut value", flexible),
+                                            context
+                                        )?;
+                                        Ok(())
+                                    };
+                                    if !field.type_.has_compact_form() {
+                                        assign(w, false)
+                                    } else {
+                                        write_version_cond(
+                                            w,
+                                            valid_versions,
+                                            field.flexible_versions,
+                                            |w| assign(w, true),
+                                            |w| assign(w, false),
+                                            false,
+                                            false,
+                                        )
+                                    }
+                                },
+                                |w| {
+                                    write!(
+                                        w,
+                                        "return Err({});",
+                                        decode_error(struct_name, &field.name, "FieldNotValidAtVersion")
+                                    )?;
+                                    Ok(())
+                                },
+                                false,
+                                false,
+                            )
+                        })?;
+                        writeln!(w, ",")?;
+                    }
+                    write!(w, "_ => ")?;
+                    w.block(|_| Ok(()))
+                })
+            })
+        },
+        |_| Ok(()),
+        false,
+        true,
+    )?;
+    writeln!(w)?;
+    Ok(())
+}
+
+/// Writes the imports of the dependencies of `kafka-protocol` which the generated code refers to
+/// by their crate name, if they are not dependencies of the crate it is compiled as part of.
+pub fn write_dependency_aliases<W: Write>(w: &mut W, target: Target<'_>) -> Result<(), Error> {
+    if let Target::Custom(options) = target {
+        let mut dependencies = vec!["derive_builder", "indexmap"];
+        if options.serde {
+            dependencies.push("serde");
+        }
+        if options.json {
+            dependencies.push("serde_json");
+        }
+        writeln!(w, "#[allow(unused_imports)]")?;
+        writeln!(
+            w,
+            "use {}::__private::{{{}}};",
+            target.runtime(),
+            dependencies.join(", ")
+        )?;
+    }
+    Ok(())
+}
+
+fn write_file_header<W: Write>(
+    w: &mut CodeWriter<W>,
+    name: &str,
+    schema_path: &str,
+    views: bool,
+    target: Target<'_>,
+) -> Result<(), Error> {
+    writeln!(w, "//! {}", name)?;
+    if !schema_path.is_empty() {
+        writeln!(w, "//!")?;
+        writeln!(w, "//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/{}/{}.json).", schema_path, name)?;
+    }
+    writeln!(
+        w,
+        "// WARNING: the items of this module are generated and should not be edited directly"
+    )?;
+    writeln!(w, "#![allow(unused)]")?;
+    writeln!(w)?;
+    writeln!(w, "use std::borrow::Borrow;")?;
+    writeln!(w)?;
+    writeln!(w, "use {}::Bytes;", target.dependency("bytes"))?;
+    writeln!(w, "use {}::Uuid;", target.dependency("uuid"))?;
+    write_dependency_aliases(w, target)?;
+    writeln!(w)?;
+    writeln!(w, "use {}::protocol::{{", target.runtime())?;
+    writeln!(w, "    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,")?;
+    writeln!(w, "    types, StrBytes, buf::{{ByteBuf, ByteBufMut}}, Builder, tagged_fields::UnknownTaggedFields")?;
+    writeln!(w, "}};")?;
+    writeln!(
+        w,
+        "use {}::{{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size}};",
+        target.internals("protocol")
+    )?;
+    writeln!(w, "use {}::error::ResponseError;", target.runtime())?;
+    if views {
+        writeln!(w, "use {}::Buf;", target.dependency("bytes"))?;
+        writeln!(
+            w,
+            "use {}::protocol::view::{{self, ArrayView}};",
+            target.runtime()
+        )?;
+    }
+    if target.write_cfg(w, "json", true)? {
+        writeln!(
+            w,
+            "use {}::json::{{JsonError, RecordsJson}};",
+            target.runtime()
+        )?;
+    }
+    if target.write_cfg(w, "json", true)? {
+        writeln!(w, "use {}::JsonValue;", target.internals("json"))?;
+    }
+    writeln!(w)?;
+    writeln!(w)?;
+    Ok(())
+}
+
+/// Generate the module for `spec`, returning the module name, the struct name and the module's source.
+///
+/// `schema_path` is the directory of the schema within the Kafka repo, used to link to it unless it
+/// is empty. `target` is the crate the generated code is compiled as part of.
+pub fn generate(
+    spec: Spec,
+    response: Option<&Spec>,
+    schema_path: &str,
+    target: Target<'_>,
+    entity_types: &mut BTreeSet<EntityType>,
+) -> Result<(String, String, Vec<u8>), Error> {
+    let name = spec.name.clone();
+    generate_spec(spec, response, schema_path, target, entity_types)
+        .map_err(|e| format_err!("{}.json: {}", name, e))
+}
+
+fn generate_spec(
+    spec: Spec,
+    response: Option<&Spec>,
+    schema_path: &str,
+    target: Target<'_>,
+    entity_types: &mut BTreeSet<EntityType>,
+) -> Result<(String, String, Vec<u8>), Error> {
+    let struct_name = spec.name.clone();
+    let module_name = struct_name.to_snake_case();
+
+    let mut file = CodeWriter::new(Vec::new());
+
+    let valid_versions = spec.valid_versions;
+    let flexible_msg_versions = spec.flexible_versions.unwrap_or_default();
+
+    let views = matches!(spec.type_, SpecType::Request | SpecType::Response)
+        && spec
+            .api_key
+            .is_some_and(|api_key| VIEW_API_KEYS.contains(&api_key));
+
+    write_file_header(&mut file, &struct_name, schema_path, views, target)?;
 
     let mut common_structs_map_keys: HashMap<String, PreparedType> = HashMap::new();
 
     for common_struct in &spec.common_structs {
-        let num_map_keys = common_struct.fields.iter().filter(|&field| field.map_key).count();
+        let num_map_keys = common_struct
+            .fields
+            .iter()
+            .filter(|&field| field.map_key)
+            .count();
 
         for field in &common_struct.fields {
             if field.map_key && num_map_keys == 1 {
-                common_structs_map_keys.insert(common_struct.name.clone(), prepare_common_struct_key_type(&field.type_, field, entity_types)?);
+                common_structs_map_keys.insert(
+                    common_struct.name.clone(),
+                    prepare_common_struct_key_type(&field.type_, field, entity_types)?,
+                );
                 break;
             };
         }
@@ -1288,6 +2889,11 @@ pub fn generate(
             entity_types,
             valid_versions,
             flexible_msg_versions,
+            false,
+            views,
+            target,
+            None,
+            spec.latest_version_unstable.unwrap_or(false),
         )?;
     }
     write_struct_def(
@@ -1298,9 +2904,14 @@ pub fn generate(
         entity_types,
         valid_versions,
         flexible_msg_versions,
+        spec.api_key.is_some(),
+        views,
+        target,
+        spec.deprecated_versions,
+        spec.latest_version_unstable.unwrap_or(false),
     )?;
 
-    if let Some(api_key) = spec.api_key {
+    if let (Some(api_key), SpecType::Request | SpecType::Response) = (spec.api_key, spec.type_) {
         write!(&mut file, "impl HeaderVersion for {} ", struct_name)?;
         file.block(|w| {
             write!(w, "fn header_version(version: i16) -> i16 ")?;
@@ -1361,7 +2972,11 @@ pub fn generate(
         writeln!(&mut file)?;
     }
 
-    Ok((module_name, struct_name))
+    if let Some(response) = response {
+        write_error_response(&mut file, &spec, response)?;
+    }
+
+    Ok((module_name, struct_name, file.into_inner()))
 }
 
 #[cfg(test)]
@@ -1396,4 +3011,59 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn invalid_field_is_an_error() {
+        let spec: Spec = serde_json::from_str(
+            r#"{ "type": "data", "name": "Test", "validVersions": "0-1", "flexibleVersions": "none", "fields": [
+                { "name": "Name", "type": "string", "versions": "0+", "default": "null" }
+            ]}"#,
+        )
+        .unwrap();
+        let err = generate(spec, None, "", Target::Runtime, &mut BTreeSet::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Test.json: field `Name`: default is null but the field is not nullable in all versions"
+        );
+    }
+
+    #[test]
+    fn invalid_response_struct_is_an_error() {
+        let request: Spec = serde_json::from_str(
+            r#"{ "type": "request", "name": "TestRequest", "apiKey": 1000, "validVersions": "0", "flexibleVersions": "none", "fields": [
+                { "name": "Topics", "type": "[]string", "versions": "0+" }
+            ]}"#,
+        )
+        .unwrap();
+        let response: Spec = serde_json::from_str(
+            r#"{ "type": "response", "name": "TestResponse", "apiKey": 1000, "validVersions": "0", "flexibleVersions": "none", "fields": [
+                { "name": "Topics", "type": "[]TopicResult", "versions": "0+" }
+            ]}"#,
+        )
+        .unwrap();
+        let err = generate(
+            request,
+            Some(&response),
+            "",
+            Target::Runtime,
+            &mut BTreeSet::new(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TestRequest.json: TestResponse.json: field `Topics`: unknown struct `TopicResult`"
+        );
+    }
+
+    #[test]
+    fn unknown_feature_is_an_error() {
+        let options = CustomOptions::default();
+        let err = Target::Custom(&options)
+            .write_cfg(&mut Vec::new(), "no-such-feature", true)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "no option mirrors the feature `no-such-feature`"
+        );
+    }
 }
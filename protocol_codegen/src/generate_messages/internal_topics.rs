@@ -0,0 +1,346 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use failure::Error;
+
+use super::generate::{self, EntityType, Target};
+use super::spec::{Spec, SpecType, VersionSpec};
+
+/// An internal topic whose records are generated from a directory of key and value schemas.
+pub struct InternalTopic {
+    /// The name of the topic, e.g. `__consumer_offsets`.
+    pub topic: &'static str,
+    /// The module the records are generated into.
+    pub module_name: &'static str,
+    /// The enum wrapping a decoded key and value.
+    pub enum_name: &'static str,
+    /// Path of the schemas within the Kafka repo.
+    pub schema_path: &'static str,
+}
+
+/// The records of the group coordinator's `__consumer_offsets` topic.
+pub const CONSUMER_OFFSETS: InternalTopic = InternalTopic {
+    topic: "__consumer_offsets",
+    module_name: "consumer_offsets",
+    enum_name: "ConsumerOffsetsRecord",
+    schema_path: "group-coordinator/src/main/resources/common/message",
+};
+
+/// The records of the transaction coordinator's `__transaction_state` topic.
+pub const TRANSACTION_STATE: InternalTopic = InternalTopic {
+    topic: "__transaction_state",
+    module_name: "transaction_state",
+    enum_name: "TransactionStateRecord",
+    schema_path: "core/src/main/resources/common/message",
+};
+
+/// A key schema paired with the schema of its values.
+struct RecordType {
+    variant: String,
+    key: String,
+    value: String,
+    /// The values of the int16 prefix of the key that select this record type.
+    key_ids: VersionSpec,
+    /// The version the key is decoded with, if it is not the prefix itself.
+    key_version: Option<i16>,
+}
+
+/// Generate the module for `topic` from its key and value schemas, adding its files to `files`.
+/// Returns the entity types used by the records, which are defined in the `messages` module.
+///
+/// Every `<Name>Key` schema must be accompanied by a `<Name>Value` schema. The key of a `data`
+/// schema is prefixed with its version, so the valid versions of those keys must not overlap. The
+/// key of a `coordinator-key` schema is prefixed with its `apiKey` instead, and decoded with its
+/// lowest valid version.
+pub fn generate_internal_topic(
+    topic: &InternalTopic,
+    specs: Vec<Spec>,
+    files: &mut BTreeMap<PathBuf, Vec<u8>>,
+) -> Result<BTreeSet<EntityType>, Error> {
+    let names: BTreeSet<_> = specs.iter().map(|spec| spec.name.clone()).collect();
+    let mut record_types = Vec::new();
+    for spec in &specs {
+        if !matches!(
+            spec.type_,
+            SpecType::Data | SpecType::CoordinatorKey | SpecType::CoordinatorValue
+        ) {
+            return Err(failure::format_err!(
+                "{}.json is not a data or coordinator record schema",
+                spec.name
+            ));
+        }
+        if let Some(variant) = spec.name.strip_suffix("Key") {
+            let (key_ids, key_version) = match (spec.type_, spec.api_key) {
+                (SpecType::CoordinatorKey, Some(api_key)) => {
+                    let version =
+                        spec.valid_versions
+                            .range()
+                            .map(|r| *r.start())
+                            .ok_or_else(|| {
+                                failure::format_err!("{}.json has no valid versions", spec.name)
+                            })?;
+                    (VersionSpec::Exact(api_key), Some(version))
+                }
+                (SpecType::CoordinatorKey, None) => {
+                    return Err(failure::format_err!(
+                        "{}.json is a coordinator key without an apiKey",
+                        spec.name
+                    ))
+                }
+                _ => (spec.valid_versions, None),
+            };
+            let value = format!("{}Value", variant);
+            if !names.contains(&value) {
+                return Err(failure::format_err!(
+                    "{} has no matching {} schema",
+                    spec.name,
+                    value
+                ));
+            }
+            if let Some(other) = record_types
+                .iter()
+                .find(|other: &&RecordType| other.key_ids.intersect(key_ids) != VersionSpec::None)
+            {
+                return Err(failure::format_err!(
+                    "the key prefixes of {} and {} overlap",
+                    other.key,
+                    spec.name
+                ));
+            }
+            record_types.push(RecordType {
+                variant: variant.to_string(),
+                key: spec.name.clone(),
+                value,
+                key_ids,
+                key_version,
+            });
+        }
+    }
+    record_types.sort_by_key(|record_type| record_type.key_ids.range().map(|r| *r.start()));
+
+    let mut entity_types = BTreeSet::new();
+    let mut module_names = Vec::new();
+    for spec in specs {
+        let (module_name, struct_name, contents) = generate::generate(
+            spec,
+            None,
+            topic.schema_path,
+            Target::Runtime,
+            &mut entity_types,
+        )?;
+        files.insert(
+            Path::new(topic.module_name).join(format!("{}.rs", module_name)),
+            contents,
+        );
+        module_names.push((module_name, struct_name));
+    }
+
+    let mut module_file = Vec::new();
+    let w = &mut module_file;
+
+    writeln!(w, "//! Records of the internal `{}` topic.", topic.topic)?;
+    writeln!(w, "//!")?;
+    writeln!(w, "//! These records are generated programmatically. See [Kafka's schemas](https://github.com/apache/kafka/tree/trunk/{}) for more information about a given record type.", topic.schema_path)?;
+    writeln!(w, "//!")?;
+    writeln!(w, "//! The key and value of each record are prefixed with the version they are encoded with, and the version of the key")?;
+    writeln!(w, "//! determines the type of the record. [`{}`] decodes both from a [`Record`](crate::records::Record) read from the topic.", topic.enum_name)?;
+    writeln!(
+        w,
+        "// WARNING: the items of this module are generated and should not be edited directly."
+    )?;
+    writeln!(w)?;
+    writeln!(w, "use bytes::Bytes;")?;
+    writeln!(w, "use crate::protocol::{{Decodable, DecodeError, DecodeErrorKind, Message, types, Decoder}};")?;
+    writeln!(w)?;
+    if !entity_types.is_empty() {
+        let names: Vec<_> = entity_types.iter().map(|e| e.name.as_str()).collect();
+        writeln!(w, "pub use crate::messages::{{{}}};", names.join(", "))?;
+        writeln!(w)?;
+    }
+
+    for (module_name, struct_name) in &module_names {
+        writeln!(w, "pub mod {};", module_name)?;
+        writeln!(w, "pub use {}::{};", module_name, struct_name)?;
+        writeln!(w)?;
+    }
+
+    writeln!(w, "/// A record of the `{}` topic.", topic.topic)?;
+    writeln!(w, "///")?;
+    writeln!(w, "/// The value is `None` for a tombstone, which deletes the key when the topic is compacted.")?;
+    writeln!(w, "#[non_exhaustive]")?;
+    writeln!(w, "#[derive(Debug, Clone, PartialEq)]")?;
+    writeln!(w, "pub enum {} {{", topic.enum_name)?;
+    for record_type in &record_types {
+        writeln!(
+            w,
+            "    /// [`{}`] and [`{}`] records.",
+            record_type.key, record_type.value
+        )?;
+        writeln!(w, "    {} {{", record_type.variant)?;
+        writeln!(w, "        /// The key.")?;
+        writeln!(w, "        key: {},", record_type.key)?;
+        writeln!(w, "        /// The value, or `None` for a tombstone.")?;
+        writeln!(w, "        value: Option<{}>,", record_type.value)?;
+        writeln!(w, "    }},")?;
+    }
+    writeln!(
+        w,
+        "    /// A record whose key version is not known to this crate."
+    )?;
+    writeln!(w, "    Unknown {{")?;
+    writeln!(w, "        /// The version of the key.")?;
+    writeln!(w, "        version: i16,")?;
+    writeln!(w, "        /// The key, following its version.")?;
+    writeln!(w, "        key: Bytes,")?;
+    writeln!(
+        w,
+        "        /// The value including its version, or `None` for a tombstone."
+    )?;
+    writeln!(w, "        value: Option<Bytes>,")?;
+    writeln!(w, "    }},")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+
+    writeln!(w, "impl {} {{", topic.enum_name)?;
+    writeln!(
+        w,
+        "    /// Decode the key and value of a record of the `{}` topic.",
+        topic.topic
+    )?;
+    writeln!(
+        w,
+        "    pub fn decode(mut key: Bytes, value: Option<Bytes>) -> Result<Self, DecodeError> {{"
+    )?;
+    writeln!(
+        w,
+        "        let version: i16 = types::Int16.decode(&mut key)?;"
+    )?;
+    writeln!(w, "        match version {{")?;
+    for record_type in &record_types {
+        let pattern = match record_type.key_ids {
+            VersionSpec::Exact(v) => format!("{}", v),
+            VersionSpec::Range(a, b) => format!("{}..={}", a, b),
+            VersionSpec::Since(a) => format!("{}..", a),
+            VersionSpec::None => {
+                return Err(failure::format_err!(
+                    "{} has no valid versions",
+                    record_type.key
+                ))
+            }
+        };
+        writeln!(w, "            {} => {{", pattern)?;
+        match record_type.key_version {
+            Some(key_version) => writeln!(
+                w,
+                "                let key = {}::decode(&mut key, {})?;",
+                record_type.key, key_version
+            )?,
+            None => writeln!(
+                w,
+                "                let key = {}::decode(&mut key, version)?;",
+                record_type.key
+            )?,
+        }
+        writeln!(w, "                let value = match value {{")?;
+        writeln!(w, "                    Some(mut value) => {{")?;
+        writeln!(
+            w,
+            "                        let version: i16 = types::Int16.decode(&mut value)?;"
+        )?;
+        writeln!(
+            w,
+            "                        check_version::<{}>(version)?;",
+            record_type.value
+        )?;
+        writeln!(
+            w,
+            "                        Some({}::decode(&mut value, version)?)",
+            record_type.value
+        )?;
+        writeln!(w, "                    }}")?;
+        writeln!(w, "                    None => None,")?;
+        writeln!(w, "                }};")?;
+        writeln!(
+            w,
+            "                Ok({}::{} {{ key, value }})",
+            topic.enum_name, record_type.variant
+        )?;
+        writeln!(w, "            }}")?;
+    }
+    writeln!(
+        w,
+        "            _ => Ok({}::Unknown {{ version, key, value }}),",
+        topic.enum_name
+    )?;
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "    /// Decode a record read from the `{}` topic.",
+        topic.topic
+    )?;
+    writeln!(
+        w,
+        "    pub fn decode_record(record: &crate::records::Record) -> Result<Self, DecodeError> {{"
+    )?;
+    writeln!(w, "        let key = record.key.clone().ok_or_else(|| {{")?;
+    writeln!(
+        w,
+        "            error!(\"Record at offset {{}} of {} has no key\", record.offset);",
+        topic.topic
+    )?;
+    writeln!(
+        w,
+        "            DecodeError::new(DecodeErrorKind::InvalidValue)"
+    )?;
+    writeln!(w, "        }})?;")?;
+    writeln!(w, "        Self::decode(key, record.value.clone())")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "    /// Whether this record is a tombstone, deleting its key."
+    )?;
+    writeln!(w, "    pub fn is_tombstone(&self) -> bool {{")?;
+    writeln!(w, "        match self {{")?;
+    for record_type in &record_types {
+        writeln!(
+            w,
+            "            {}::{} {{ value, .. }} => value.is_none(),",
+            topic.enum_name, record_type.variant
+        )?;
+    }
+    writeln!(
+        w,
+        "            {}::Unknown {{ value, .. }} => value.is_none(),",
+        topic.enum_name
+    )?;
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+
+    writeln!(
+        w,
+        "fn check_version<M: Message>(version: i16) -> Result<(), DecodeError> {{"
+    )?;
+    writeln!(w, "    if M::VERSIONS.contains(version) {{")?;
+    writeln!(w, "        Ok(())")?;
+    writeln!(w, "    }} else {{")?;
+    writeln!(w, "        error!(\"Unsupported version {{}} of {{}}\", version, std::any::type_name::<M>());")?;
+    writeln!(
+        w,
+        "        Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion))"
+    )?;
+    writeln!(w, "    }}")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+
+    files.insert(
+        PathBuf::from(format!("{}.rs", topic.module_name)),
+        module_file,
+    );
+    Ok(entity_types)
+}
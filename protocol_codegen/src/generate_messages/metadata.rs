@@ -0,0 +1,414 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use failure::Error;
+
+use super::generate::{self, EntityType, Target};
+use super::spec::{Spec, SpecType};
+use super::METADATA_SCHEMA_PATH;
+
+/// Generate the `metadata` module from the cluster metadata record schemas, adding its files to
+/// `files`. Returns the entity types used by the records, which are defined in the `messages` module.
+pub fn generate_metadata(
+    specs: Vec<Spec>,
+    files: &mut BTreeMap<PathBuf, Vec<u8>>,
+) -> Result<BTreeSet<EntityType>, Error> {
+    let mut entity_types = BTreeSet::new();
+    let mut record_types = BTreeMap::new();
+    let mut module_names = Vec::new();
+
+    for spec in specs {
+        let api_key = match (spec.type_, spec.api_key) {
+            (SpecType::Metadata, Some(api_key)) => api_key,
+            _ => {
+                return Err(failure::format_err!(
+                    "{} is not a metadata record schema",
+                    spec.name
+                ))
+            }
+        };
+        let (module_name, struct_name, contents) = generate::generate(
+            spec,
+            None,
+            METADATA_SCHEMA_PATH,
+            Target::Runtime,
+            &mut entity_types,
+        )?;
+        files.insert(
+            Path::new("metadata").join(format!("{}.rs", module_name)),
+            contents,
+        );
+        if let Some(previous) = record_types.insert(api_key, struct_name.clone()) {
+            return Err(failure::format_err!(
+                "{} and {} have the same api key {}",
+                previous,
+                struct_name,
+                api_key
+            ));
+        }
+        module_names.push((module_name, struct_name));
+    }
+
+    let mut module_file = Vec::new();
+    let w = &mut module_file;
+
+    writeln!(w, "//! KRaft cluster metadata records, as stored in the `__cluster_metadata` log and its snapshots.")?;
+    writeln!(w, "//!")?;
+    writeln!(w, "//! These records are generated programmatically. See [Kafka's metadata schemas](https://github.com/apache/kafka/tree/trunk/{}) for more information about a given record type.", METADATA_SCHEMA_PATH)?;
+    writeln!(w, "//!")?;
+    writeln!(w, "//! Each record in the log is framed as an [`ApiMessageAndVersion`], which can be decoded from the value of a")?;
+    writeln!(
+        w,
+        "//! [`Record`](crate::records::Record) read from the log."
+    )?;
+    writeln!(
+        w,
+        "// WARNING: the items of this module are generated and should not be edited directly."
+    )?;
+    writeln!(w)?;
+    writeln!(w, "use bytes::Buf;")?;
+    writeln!(w, "use crate::protocol::{{Decodable, DecodeError, DecodeErrorKind, Encodable, EncodeError, EncodeErrorKind, Message, VersionRange, buf::{{ByteBuf, ByteBufMut}}, types, Decoder, Encoder}};")?;
+    writeln!(w, "use std::convert::TryFrom;")?;
+    writeln!(w)?;
+    if !entity_types.is_empty() {
+        let names: Vec<_> = entity_types.iter().map(|e| e.name.as_str()).collect();
+        writeln!(w, "pub use crate::messages::{{{}}};", names.join(", "))?;
+        writeln!(w)?;
+    }
+
+    for (module_name, struct_name) in &module_names {
+        writeln!(w, "pub mod {};", module_name)?;
+        writeln!(w, "pub use {}::{};", module_name, struct_name)?;
+        writeln!(w)?;
+    }
+
+    writeln!(
+        w,
+        "/// The type of a cluster metadata record, identified by its api key."
+    )?;
+    writeln!(w, "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]")?;
+    writeln!(
+        w,
+        "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]"
+    )?;
+    writeln!(w, "pub enum MetadataRecordType {{")?;
+    for (api_key, record_type) in &record_types {
+        writeln!(w, "    /// The type of [`{}`]", record_type)?;
+        writeln!(w, "    {} = {},", record_type, api_key)?;
+    }
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+
+    writeln!(w, "impl MetadataRecordType {{")?;
+    writeln!(
+        w,
+        "    /// All record types known to this crate, in ascending order."
+    )?;
+    writeln!(w, "    pub fn all() -> &'static [MetadataRecordType] {{")?;
+    writeln!(w, "        &[")?;
+    for record_type in record_types.values() {
+        writeln!(w, "            MetadataRecordType::{},", record_type)?;
+    }
+    writeln!(w, "        ]")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "    /// The name of this record type as used by Kafka, e.g. `TopicRecord`."
+    )?;
+    writeln!(w, "    pub fn name(&self) -> &'static str {{")?;
+    writeln!(w, "        match self {{")?;
+    for record_type in record_types.values() {
+        writeln!(
+            w,
+            "            MetadataRecordType::{} => \"{}\",",
+            record_type, record_type
+        )?;
+    }
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "    /// The versions of this record type supported by this crate."
+    )?;
+    writeln!(w, "    pub fn valid_versions(&self) -> VersionRange {{")?;
+    writeln!(w, "        match self {{")?;
+    for record_type in record_types.values() {
+        writeln!(
+            w,
+            "            MetadataRecordType::{} => {}::VERSIONS,",
+            record_type, record_type
+        )?;
+    }
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+
+    writeln!(w, "impl TryFrom<i16> for MetadataRecordType {{")?;
+    writeln!(w, "    type Error = ();")?;
+    writeln!(w)?;
+    writeln!(w, "    fn try_from(v: i16) -> Result<Self, Self::Error> {{")?;
+    writeln!(w, "        match v {{")?;
+    for record_type in record_types.values() {
+        writeln!(
+            w,
+            "            x if x == MetadataRecordType::{} as i16 => Ok(MetadataRecordType::{}),",
+            record_type, record_type
+        )?;
+    }
+    writeln!(w, "            _ => Err(()),")?;
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+
+    writeln!(w, "/// Wrapping enum for all cluster metadata records.")?;
+    writeln!(w, "#[non_exhaustive]")?;
+    writeln!(w, "#[derive(Debug, Clone, PartialEq)]")?;
+    writeln!(
+        w,
+        "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]"
+    )?;
+    writeln!(w, "pub enum MetadataRecord {{")?;
+    for record_type in record_types.values() {
+        writeln!(w, "    /// {},", record_type)?;
+        writeln!(w, "    {}({}),", record_type, record_type)?;
+    }
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+
+    writeln!(w, "impl MetadataRecord {{")?;
+    writeln!(w, "    /// The type of this record.")?;
+    writeln!(w, "    pub fn record_type(&self) -> MetadataRecordType {{")?;
+    writeln!(w, "        match self {{")?;
+    for record_type in record_types.values() {
+        writeln!(
+            w,
+            "            MetadataRecord::{}(_) => MetadataRecordType::{},",
+            record_type, record_type
+        )?;
+    }
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(w, "    /// Decode a record of the given type and version.")?;
+    writeln!(w, "    pub fn decode<B: ByteBuf>(record_type: MetadataRecordType, buf: &mut B, version: i16) -> Result<Self, DecodeError> {{")?;
+    writeln!(w, "        match record_type {{")?;
+    for record_type in record_types.values() {
+        writeln!(
+            w,
+            "            MetadataRecordType::{} => {}::decode(buf, version).map(MetadataRecord::{}),",
+            record_type, record_type, record_type
+        )?;
+    }
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(w, "    /// Encode this record with the given version.")?;
+    writeln!(w, "    pub fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {{")?;
+    writeln!(w, "        match self {{")?;
+    for record_type in record_types.values() {
+        writeln!(
+            w,
+            "            MetadataRecord::{}(record) => record.encode(buf, version),",
+            record_type
+        )?;
+    }
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "    /// Compute the size of this record when encoded with the given version."
+    )?;
+    writeln!(
+        w,
+        "    pub fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {{"
+    )?;
+    writeln!(w, "        match self {{")?;
+    for record_type in record_types.values() {
+        writeln!(
+            w,
+            "            MetadataRecord::{}(record) => record.compute_size(version),",
+            record_type
+        )?;
+    }
+    writeln!(w, "        }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+
+    for record_type in record_types.values() {
+        writeln!(w, "impl From<{}> for MetadataRecord {{", record_type)?;
+        writeln!(
+            w,
+            "    fn from(record: {}) -> Self {{ MetadataRecord::{}(record) }}",
+            record_type, record_type
+        )?;
+        writeln!(w, "}}")?;
+        writeln!(w)?;
+    }
+
+    write_api_message_and_version(w)?;
+
+    files.insert(PathBuf::from("metadata.rs"), module_file);
+    Ok(entity_types)
+}
+
+/// The frame Kafka's `MetadataRecordSerde` wraps every record in: a frame version, the record's api
+/// key and version, each an unsigned varint, followed by the record itself.
+fn write_api_message_and_version<W: Write>(w: &mut W) -> Result<(), Error> {
+    writeln!(
+        w,
+        "/// A metadata record together with the version it is encoded with, as framed in the"
+    )?;
+    writeln!(w, "/// `__cluster_metadata` log and snapshots.")?;
+    writeln!(w, "///")?;
+    writeln!(w, "/// The frame consists of the frame version, the record's api key and the record's version, each an")?;
+    writeln!(w, "/// unsigned varint, followed by the record itself.")?;
+    writeln!(w, "#[derive(Debug, Clone, PartialEq)]")?;
+    writeln!(
+        w,
+        "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]"
+    )?;
+    writeln!(w, "pub struct ApiMessageAndVersion {{")?;
+    writeln!(w, "    /// The record.")?;
+    writeln!(w, "    pub message: MetadataRecord,")?;
+    writeln!(w, "    /// The version the record is encoded with.")?;
+    writeln!(w, "    pub version: i16,")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    writeln!(w, "impl ApiMessageAndVersion {{")?;
+    writeln!(w, "    /// The only frame version written by Kafka.")?;
+    writeln!(w, "    pub const FRAME_VERSION: u32 = 1;")?;
+    writeln!(w)?;
+    writeln!(
+        w,
+        "    /// Create a frame for `message` encoded with `version`."
+    )?;
+    writeln!(
+        w,
+        "    pub fn new<M: Into<MetadataRecord>>(message: M, version: i16) -> Self {{"
+    )?;
+    writeln!(w, "        Self {{ message: message.into(), version }}")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(w, "    /// Decode a framed record, e.g. the value of a record in the `__cluster_metadata` log.")?;
+    writeln!(
+        w,
+        "    pub fn decode<B: ByteBuf>(buf: &mut B) -> Result<Self, DecodeError> {{"
+    )?;
+    writeln!(
+        w,
+        "        let frame_version: u32 = types::UnsignedVarInt.decode(buf)?;"
+    )?;
+    writeln!(w, "        if frame_version != Self::FRAME_VERSION {{")?;
+    writeln!(
+        w,
+        "            error!(\"Unknown metadata record frame version {{}}\", frame_version);"
+    )?;
+    writeln!(
+        w,
+        "            return Err(DecodeError::new(DecodeErrorKind::InvalidValue));"
+    )?;
+    writeln!(w, "        }}")?;
+    writeln!(
+        w,
+        "        let api_key: u32 = types::UnsignedVarInt.decode(buf)?;"
+    )?;
+    writeln!(w, "        let record_type = i16::try_from(api_key).ok().and_then(|k| MetadataRecordType::try_from(k).ok()).ok_or_else(|| {{")?;
+    writeln!(
+        w,
+        "            error!(\"Unknown metadata record type {{}}\", api_key);"
+    )?;
+    writeln!(
+        w,
+        "            DecodeError::new(DecodeErrorKind::InvalidValue)"
+    )?;
+    writeln!(w, "        }})?;")?;
+    writeln!(
+        w,
+        "        let version: u32 = types::UnsignedVarInt.decode(buf)?;"
+    )?;
+    writeln!(w, "        let version = i16::try_from(version).map_err(|_| DecodeError::new(DecodeErrorKind::InvalidValue))?;")?;
+    writeln!(
+        w,
+        "        let message = MetadataRecord::decode(record_type, buf, version)?;"
+    )?;
+    writeln!(w, "        Ok(Self {{ message, version }})")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(w, "    /// Decode the framed record held in the value of a record read from the `__cluster_metadata` log.")?;
+    writeln!(
+        w,
+        "    pub fn decode_record(record: &crate::records::Record) -> Result<Self, DecodeError> {{"
+    )?;
+    writeln!(
+        w,
+        "        let mut value = record.value.clone().ok_or_else(|| {{"
+    )?;
+    writeln!(
+        w,
+        "            error!(\"Metadata record at offset {{}} has no value\", record.offset);"
+    )?;
+    writeln!(
+        w,
+        "            DecodeError::new(DecodeErrorKind::InvalidValue)"
+    )?;
+    writeln!(w, "        }})?;")?;
+    writeln!(w, "        let frame = Self::decode(&mut value)?;")?;
+    writeln!(w, "        if value.has_remaining() {{")?;
+    writeln!(w, "            error!(\"Metadata record at offset {{}} has {{}} trailing bytes\", record.offset, value.remaining());")?;
+    writeln!(
+        w,
+        "            return Err(DecodeError::new(DecodeErrorKind::InvalidValue));"
+    )?;
+    writeln!(w, "        }}")?;
+    writeln!(w, "        Ok(frame)")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(w, "    /// Encode this frame.")?;
+    writeln!(
+        w,
+        "    pub fn encode<B: ByteBufMut>(&self, buf: &mut B) -> Result<(), EncodeError> {{"
+    )?;
+    writeln!(
+        w,
+        "        types::UnsignedVarInt.encode(buf, Self::FRAME_VERSION)?;"
+    )?;
+    writeln!(
+        w,
+        "        types::UnsignedVarInt.encode(buf, self.message.record_type() as u32)?;"
+    )?;
+    writeln!(w, "        let version = u32::try_from(self.version).map_err(|_| EncodeError::new(EncodeErrorKind::InvalidValue))?;")?;
+    writeln!(w, "        types::UnsignedVarInt.encode(buf, version)?;")?;
+    writeln!(w, "        self.message.encode(buf, self.version)")?;
+    writeln!(w, "    }}")?;
+    writeln!(w)?;
+    writeln!(w, "    /// Compute the size of this frame when encoded.")?;
+    writeln!(
+        w,
+        "    pub fn compute_size(&self) -> Result<usize, EncodeError> {{"
+    )?;
+    writeln!(w, "        let version = u32::try_from(self.version).map_err(|_| EncodeError::new(EncodeErrorKind::InvalidValue))?;")?;
+    writeln!(
+        w,
+        "        Ok(types::UnsignedVarInt.compute_size(Self::FRAME_VERSION)?"
+    )?;
+    writeln!(
+        w,
+        "            + types::UnsignedVarInt.compute_size(self.message.record_type() as u32)?"
+    )?;
+    writeln!(
+        w,
+        "            + types::UnsignedVarInt.compute_size(version)?"
+    )?;
+    writeln!(w, "            + self.message.compute_size(self.version)?)")?;
+    writeln!(w, "    }}")?;
+    writeln!(w, "}}")?;
+    writeln!(w)?;
+    Ok(())
+}
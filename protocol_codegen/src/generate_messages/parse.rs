@@ -1,20 +1,269 @@
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 
-use assert_json_diff::assert_json_eq;
-use failure::Error;
+use failure::{format_err, Error};
 use json_comments::StripComments;
+use serde_json::Value;
 
-use super::spec::Spec;
+use super::spec::{FieldSpec, Spec, TypeSpec, VersionSpec};
 
 pub fn parse(path: &Path) -> Result<Spec, Error> {
     let buf = fs::read(path)?;
-    let stripped = StripComments::new(buf.as_slice());
-    let original_json: serde_json::Value = serde_json::from_reader(stripped)?;
+    parse_spec(&buf).map_err(|e| format_err!("{}: {}", path.display(), e))
+}
+
+/// Parse a schema, rejecting anything the generator does not understand with an error naming the
+/// offending field.
+fn parse_spec(buf: &[u8]) -> Result<Spec, Error> {
+    let stripped = StripComments::new(buf);
+    let original_json: Value = serde_json::from_reader(stripped)?;
+
+    // Deserialize each field on its own first, so that errors name the field
+    if let Some(Value::Array(fields)) = original_json.get("fields") {
+        check_fields(fields, "")?;
+    }
+    if let Some(Value::Array(common_structs)) = original_json.get("commonStructs") {
+        for common_struct in common_structs {
+            if let Some(Value::Array(fields)) = common_struct.get("fields") {
+                check_fields(fields, &field_path("", common_struct))?;
+            }
+        }
+    }
     let spec: Spec = serde_json::from_value(original_json.clone())?;
-    let parsed_json = serde_json::to_value(&spec)?;
+    validate(&spec)?;
 
-    assert_json_eq!(original_json, parsed_json);
+    // Anything that does not survive a round trip is a property the generator would silently ignore
+    let parsed_json = serde_json::to_value(&spec)?;
+    if let Some(problem) = compare(&original_json, &parsed_json, "") {
+        return Err(format_err!("{}", problem));
+    }
 
     Ok(spec)
 }
+
+fn field_path(parent: &str, field: &Value) -> String {
+    let name = field
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("<unnamed>");
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+fn check_fields(fields: &[Value], parent: &str) -> Result<(), Error> {
+    for field in fields {
+        let path = field_path(parent, field);
+        if let Some(Value::Array(children)) = field.get("fields") {
+            check_fields(children, &path)?;
+        }
+        serde_json::from_value::<FieldSpec>(field.clone())
+            .map_err(|e| format_err!("field `{}`: {}", path, e))?;
+    }
+    Ok(())
+}
+
+fn validate(spec: &Spec) -> Result<(), Error> {
+    if spec.valid_versions.range().is_none() {
+        return Err(format_err!(
+            "validVersions must be a bounded range, not `{}`",
+            spec.valid_versions
+        ));
+    }
+    for common_struct in &spec.common_structs {
+        validate_fields(&common_struct.fields, &common_struct.name)?;
+    }
+    validate_fields(&spec.fields, "")
+}
+
+fn validate_fields(fields: &[FieldSpec], parent: &str) -> Result<(), Error> {
+    let mut tags = BTreeSet::new();
+    for field in fields {
+        let path = if parent.is_empty() {
+            field.name.clone()
+        } else {
+            format!("{}.{}", parent, field.name)
+        };
+        let err = |message: String| format_err!("field `{}`: {}", path, message);
+
+        let mut type_ = &field.type_;
+        while let TypeSpec::Array(inner) = type_ {
+            type_ = inner;
+        }
+        if let TypeSpec::Struct(name) = type_ {
+            if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+                return Err(err(format!("unknown type `{}`", field.type_)));
+            }
+        } else if field.fields.is_some() {
+            return Err(err(format!("type `{}` cannot have fields", field.type_)));
+        }
+
+        match (field.tag, field.tagged_versions) {
+            (Some(_), VersionSpec::None) => {
+                return Err(err("has a tag but no taggedVersions".into()))
+            }
+            (None, tagged) if !tagged.is_none() => {
+                return Err(err("has taggedVersions but no tag".into()))
+            }
+            (Some(tag), _) if !tags.insert(tag) => {
+                return Err(err(format!("tag {} is used by another field", tag)))
+            }
+            _ => {}
+        }
+
+        if let Some(children) = &field.fields {
+            validate_fields(children, &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Find the first property of `original` that differs in `parsed`.
+fn compare(original: &Value, parsed: &Value, path: &str) -> Option<String> {
+    let location = |path: &str| {
+        if path.is_empty() {
+            "schema".to_string()
+        } else {
+            format!("field `{}`", path)
+        }
+    };
+    match (original, parsed) {
+        (Value::Object(original), Value::Object(parsed)) => {
+            for (key, value) in original {
+                let parsed_value = match parsed.get(key) {
+                    Some(parsed_value) => parsed_value,
+                    None => {
+                        return Some(format!(
+                            "{}: unsupported property `{}`",
+                            location(path),
+                            key
+                        ))
+                    }
+                };
+                let problem = match (key.as_str(), value, parsed_value) {
+                    (
+                        "fields" | "commonStructs",
+                        Value::Array(values),
+                        Value::Array(parsed_values),
+                    ) if values.len() == parsed_values.len() => values
+                        .iter()
+                        .zip(parsed_values)
+                        .find_map(|(value, parsed_value)| {
+                            compare(value, parsed_value, &field_path(path, value))
+                        }),
+                    _ if value == parsed_value => None,
+                    _ => Some(format!(
+                        "{}: unsupported value {} for `{}`",
+                        location(path),
+                        value,
+                        key
+                    )),
+                };
+                if problem.is_some() {
+                    return problem;
+                }
+            }
+            None
+        }
+        _ if original == parsed => None,
+        _ => Some(format!(
+            "{}: unsupported value {}",
+            location(path),
+            original
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(schema: &str) -> String {
+        parse_spec(schema.as_bytes()).unwrap_err().to_string()
+    }
+
+    const HEADER: &str =
+        r#""type": "data", "name": "Test", "validVersions": "0-1", "flexibleVersions": "1+""#;
+
+    #[test]
+    fn parses_newer_constructs() {
+        let spec = parse_spec(
+            format!(
+                r#"{{ {}, "deprecatedVersions": "0", "fields": [
+                    {{ "name": "Count", "type": "uint32", "versions": "0+" }},
+                    {{ "name": "Ratio", "type": "float64", "versions": "0+", "default": "0.5" }},
+                    {{ "name": "Records", "type": "records", "versions": "0+", "nullableVersions": "0+", "zeroCopy": true }},
+                    {{ "name": "Extra", "type": "Extra", "versions": "1+", "taggedVersions": "1+", "tag": 0, "fields": [
+                        {{ "name": "Value", "type": "int32", "versions": "1+", "taggedVersions": "1+", "tag": 0 }}
+                    ]}}
+                ]}}"#,
+                HEADER
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(spec.deprecated_versions, Some(VersionSpec::Exact(0)));
+    }
+
+    #[test]
+    fn names_the_offending_field() {
+        let unknown_type = format!(
+            r#"{{ {}, "fields": [
+                {{ "name": "Topics", "type": "[]Topic", "versions": "0+", "fields": [
+                    {{ "name": "Size", "type": "uint64", "versions": "0+" }}
+                ]}}
+            ]}}"#,
+            HEADER
+        );
+        assert_eq!(
+            error(&unknown_type),
+            "field `Topics.Size`: unknown type `uint64`"
+        );
+
+        let unknown_property = format!(
+            r#"{{ {}, "fields": [
+                {{ "name": "Name", "type": "string", "versions": "0+", "compressed": true }}
+            ]}}"#,
+            HEADER
+        );
+        assert_eq!(
+            error(&unknown_property),
+            "field `Name`: unsupported property `compressed`"
+        );
+
+        let bad_versions = format!(
+            r#"{{ {}, "fields": [
+                {{ "name": "Name", "type": "string", "versions": "1-" }}
+            ]}}"#,
+            HEADER
+        );
+        assert!(error(&bad_versions).starts_with("field `Name`: "));
+
+        let missing_tag = format!(
+            r#"{{ {}, "fields": [
+                {{ "name": "Name", "type": "string", "versions": "1+", "taggedVersions": "1+" }}
+            ]}}"#,
+            HEADER
+        );
+        assert_eq!(
+            error(&missing_tag),
+            "field `Name`: has taggedVersions but no tag"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_schema_properties() {
+        let schema = format!(r#"{{ {}, "stability": "evolving", "fields": [] }}"#, HEADER);
+        assert_eq!(error(&schema), "schema: unsupported property `stability`");
+
+        let unbounded =
+            r#"{ "type": "data", "name": "Test", "validVersions": "0+", "fields": [] }"#;
+        assert_eq!(
+            error(unbounded),
+            "validVersions must be a bounded range, not `0+`"
+        );
+    }
+}
@@ -17,11 +17,13 @@ pub struct Spec {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub listeners: Option<Vec<ListenerSpec>>,
     pub name: String,
-	#[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub latest_version_unstable: Option<bool>,
     pub valid_versions: VersionSpec,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub flexible_versions: Option<VersionSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated_versions: Option<VersionSpec>,
     pub fields: Vec<FieldSpec>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub common_structs: Vec<StructSpec>,
@@ -81,6 +83,11 @@ pub enum SpecType {
     Request,
     Response,
     Data,
+    Metadata,
+    #[serde(rename = "coordinator-key")]
+    CoordinatorKey,
+    #[serde(rename = "coordinator-value")]
+    CoordinatorValue,
 }
 
 #[derive(Debug, Copy, Clone, Display, FromStr, Eq, PartialEq, Default)]
@@ -138,6 +145,7 @@ pub enum PrimitiveType {
     Int8,
     Int16,
     Uint16,
+    Uint32,
     Int32,
     Int64,
     Float64,
@@ -156,6 +164,7 @@ impl PrimitiveType {
             Self::Int8 => "i8",
             Self::Int16 => "i16",
             Self::Uint16 => "u16",
+            Self::Uint32 => "u32",
             Self::Int32 => "i32",
             Self::Int64 => "i64",
             Self::Float64 => "f64",
@@ -170,6 +179,7 @@ impl PrimitiveType {
             Self::Int8 => "types::Int8",
             Self::Int16 => "types::Int16",
             Self::Uint16 => "types::UInt16",
+            Self::Uint32 => "types::UInt32",
             Self::Int32 => "types::Int32",
             Self::Int64 => "types::Int64",
             Self::Float64 => "types::Float64",
@@ -0,0 +1,9 @@
+//! Generates the messages of the `kafka-protocol` crate from Kafka's JSON schemas.
+//!
+//! Besides regenerating `kafka-protocol` itself, [`generate_custom`] generates messages from
+//! schemas of your own, such as APIs added by a fork of Kafka, for use with `kafka-protocol` from
+//! a build script.
+
+pub mod generate_messages;
+
+pub use generate_messages::custom::{generate_custom, CustomOptions};
@@ -1,7 +1,215 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
 use failure::Error;
 
-pub mod generate_messages;
+use protocol_codegen::generate_messages::{self, SchemaDirs, SchemaSource};
+
+const USAGE: &str = "\
+Usage: protocol_codegen [OPTIONS]
+       protocol_codegen compat [--json] <OLD_SCHEMA_DIR> <NEW_SCHEMA_DIR>
+
+Commands:
+    compat               Report the changes between two directories of schemas, such as those of
+                         two Kafka releases, flagging the ones breaking code using the generated
+                         messages; --json prints the report as JSON
+
+Options:
+    --schema-dir <DIR>   Read the JSON message schemas from a local directory
+    --metadata-schema-dir <DIR>
+                         Read the KRaft metadata record schemas from a local directory; without it
+                         the metadata module is not regenerated when using --schema-dir
+    --consumer-offsets-schema-dir <DIR>
+                         Read the __consumer_offsets record schemas from a local directory
+    --transaction-state-schema-dir <DIR>
+                         Read the __transaction_state record schemas from a local directory
+    --git                Download the schemas from the Kafka repo (default if --schema-dir is not given)
+    --kafka-repo <DIR>   Directory the Kafka repo is cloned into [default: kafka_repo]
+    --commit <SHA>       Kafka commit to check out [default: the pinned release]
+    --output-dir <DIR>   Directory receiving messages.rs and messages/ [default: this crate's src]
+    --check              Don't write anything; exit non-zero if the output directory differs from
+                         what would be generated
+    -h, --help           Print this message
+";
+
+struct Args {
+    schema_dir: Option<PathBuf>,
+    metadata_schema_dir: Option<PathBuf>,
+    consumer_offsets_schema_dir: Option<PathBuf>,
+    transaction_state_schema_dir: Option<PathBuf>,
+    git: bool,
+    kafka_repo: PathBuf,
+    commit: Option<String>,
+    output_dir: Option<PathBuf>,
+    check: bool,
+}
+
+fn parse_args() -> Result<Args, Error> {
+    let mut args = Args {
+        schema_dir: None,
+        metadata_schema_dir: None,
+        consumer_offsets_schema_dir: None,
+        transaction_state_schema_dir: None,
+        git: false,
+        kafka_repo: PathBuf::from("kafka_repo"),
+        commit: None,
+        output_dir: None,
+        check: false,
+    };
+    let mut iter = std::env::args_os().skip(1);
+    while let Some(arg) = iter.next() {
+        let arg = arg
+            .into_string()
+            .map_err(|arg| failure::format_err!("invalid argument {:?}", arg))?;
+        let mut value = || {
+            iter.next()
+                .ok_or_else(|| failure::format_err!("{} expects a value\n\n{}", arg, USAGE))
+        };
+        match arg.as_str() {
+            "--schema-dir" => args.schema_dir = Some(value()?.into()),
+            "--metadata-schema-dir" => args.metadata_schema_dir = Some(value()?.into()),
+            "--consumer-offsets-schema-dir" => {
+                args.consumer_offsets_schema_dir = Some(value()?.into())
+            }
+            "--transaction-state-schema-dir" => {
+                args.transaction_state_schema_dir = Some(value()?.into())
+            }
+            "--git" => args.git = true,
+            "--kafka-repo" => args.kafka_repo = value()?.into(),
+            "--commit" => args.commit = Some(value()?.into_string().unwrap_or_default()),
+            "--output-dir" => args.output_dir = Some(value()?.into()),
+            "--check" => args.check = true,
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ => {
+                return Err(failure::format_err!(
+                    "unknown argument {}\n\n{}",
+                    arg,
+                    USAGE
+                ))
+            }
+        }
+    }
+    if args.git && args.schema_dir.is_some() {
+        return Err(failure::format_err!(
+            "--git and --schema-dir are mutually exclusive"
+        ));
+    }
+    let extra_dirs = [
+        &args.metadata_schema_dir,
+        &args.consumer_offsets_schema_dir,
+        &args.transaction_state_schema_dir,
+    ];
+    if extra_dirs.iter().any(|dir| dir.is_some()) && args.schema_dir.is_none() {
+        return Err(failure::format_err!(
+            "the --*-schema-dir options require --schema-dir"
+        ));
+    }
+    Ok(args)
+}
+
+fn schema_source(args: &Args) -> Result<SchemaSource, Error> {
+    if let Some(dir) = &args.schema_dir {
+        return Ok(SchemaSource::Dir(SchemaDirs {
+            messages: dir.clone(),
+            metadata: args.metadata_schema_dir.clone(),
+            consumer_offsets: args.consumer_offsets_schema_dir.clone(),
+            transaction_state: args.transaction_state_schema_dir.clone(),
+        }));
+    }
+    #[cfg(feature = "git")]
+    {
+        Ok(SchemaSource::Git {
+            repo_dir: args.kafka_repo.clone(),
+            commit: args
+                .commit
+                .clone()
+                .unwrap_or_else(|| generate_messages::KAFKA_RELEASE_COMMIT.to_string()),
+        })
+    }
+    #[cfg(not(feature = "git"))]
+    {
+        Err(failure::format_err!(
+            "built without the `git` feature, pass --schema-dir\n\n{}",
+            USAGE
+        ))
+    }
+}
+
+/// Prints the compatibility report between the schema directories given to `compat`.
+fn compat(args: impl Iterator<Item = OsString>) -> Result<(), Error> {
+    let mut json = false;
+    let mut dirs = Vec::new();
+    for arg in args {
+        let arg = arg
+            .into_string()
+            .map_err(|arg| failure::format_err!("invalid argument {:?}", arg))?;
+        match arg.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => {
+                return Err(failure::format_err!(
+                    "unknown argument {}\n\n{}",
+                    arg,
+                    USAGE
+                ))
+            }
+            _ => dirs.push(PathBuf::from(arg)),
+        }
+    }
+    let (old_dir, new_dir) = match &dirs[..] {
+        [old_dir, new_dir] => (old_dir, new_dir),
+        _ => {
+            return Err(failure::format_err!(
+                "compat expects two schema directories\n\n{}",
+                USAGE
+            ))
+        }
+    };
+    let report = generate_messages::compat::compare_dirs(old_dir, new_dir)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report.to_json())?);
+    } else {
+        print!("{}", report);
+    }
+    Ok(())
+}
+
+/// Runs the generator, returning whether the output is up to date in `--check` mode.
+fn run() -> Result<bool, Error> {
+    if std::env::args_os()
+        .nth(1)
+        .is_some_and(|arg| arg == "compat")
+    {
+        compat(std::env::args_os().skip(2))?;
+        return Ok(true);
+    }
+    let args = parse_args()?;
+    let source = schema_source(&args)?;
+    let output_dir = match &args.output_dir {
+        Some(dir) => dir.clone(),
+        None => generate_messages::default_output_dir()?,
+    };
+    if args.check {
+        generate_messages::check(&source, &output_dir)
+    } else {
+        generate_messages::run(&source, &output_dir)?;
+        Ok(true)
+    }
+}
 
-fn main() -> Result<(), Error> {
-    generate_messages::run()
+fn main() {
+    match run() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,179 @@
+//! Provides a builder for the [`ApiVersionsResponse`] a server sends to its clients.
+//!
+//! The advertised APIs and version ranges are derived from the messages generated into this
+//! crate, so a server only has to describe which listener it is answering on, and optionally
+//! which APIs to leave out.
+//!
+//! ```rust
+//! # #[cfg(all(feature = "client-core", feature = "raft"))] {
+//! use kafka_protocol::api_versions::ApiVersionsBuilder;
+//! use kafka_protocol::messages::ApiKey;
+//! use kafka_protocol::protocol::ListenerType;
+//!
+//! let response = ApiVersionsBuilder::new(ListenerType::Broker)
+//!     .deny([ApiKey::DescribeQuorumKey])
+//!     .build(3);
+//! assert!(response.api_keys.contains_key(&(ApiKey::ProduceKey as i16)));
+//! assert!(!response.api_keys.contains_key(&(ApiKey::DescribeQuorumKey as i16)));
+//! # }
+//! ```
+
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+
+use crate::messages::api_versions_response::{
+    ApiVersion, FinalizedFeatureKey, SupportedFeatureKey,
+};
+use crate::messages::{ApiKey, ApiVersionsResponse};
+use crate::protocol::{ListenerType, StrBytes};
+
+/// Builds an [`ApiVersionsResponse`] advertising the APIs supported by this crate.
+#[derive(Debug, Clone)]
+pub struct ApiVersionsBuilder {
+    listener: ListenerType,
+    allow: Option<HashSet<ApiKey>>,
+    deny: HashSet<ApiKey>,
+    enable_unstable: bool,
+    throttle_time_ms: i32,
+    supported_features: IndexMap<StrBytes, SupportedFeatureKey>,
+    finalized_features_epoch: i64,
+    finalized_features: IndexMap<StrBytes, FinalizedFeatureKey>,
+    zk_migration_ready: bool,
+}
+
+impl ApiVersionsBuilder {
+    /// Create a builder advertising every API exposed on the provided listener.
+    pub fn new(listener: ListenerType) -> Self {
+        Self {
+            listener,
+            allow: None,
+            deny: HashSet::new(),
+            enable_unstable: false,
+            throttle_time_ms: 0,
+            supported_features: IndexMap::new(),
+            finalized_features_epoch: -1,
+            finalized_features: IndexMap::new(),
+            zk_migration_ready: false,
+        }
+    }
+
+    /// Only advertise the provided APIs. May be called multiple times to extend the list.
+    pub fn allow<I: IntoIterator<Item = ApiKey>>(mut self, keys: I) -> Self {
+        self.allow.get_or_insert_with(HashSet::new).extend(keys);
+        self
+    }
+
+    /// Never advertise the provided APIs, even if they are allowed.
+    pub fn deny<I: IntoIterator<Item = ApiKey>>(mut self, keys: I) -> Self {
+        self.deny.extend(keys);
+        self
+    }
+
+    /// Whether to advertise API versions that are still unstable upstream. Defaults to `false`.
+    ///
+    /// Unstable versions are only known to this crate if the `unstable-apis` feature is enabled, so
+    /// this has no effect otherwise.
+    pub fn enable_unstable(mut self, enable: bool) -> Self {
+        self.enable_unstable = enable;
+        self
+    }
+
+    /// Set the throttle time returned in the response.
+    pub fn throttle_time_ms(mut self, throttle_time_ms: i32) -> Self {
+        self.throttle_time_ms = throttle_time_ms;
+        self
+    }
+
+    /// Advertise a feature supported by this node.
+    pub fn supported_feature(mut self, name: StrBytes, min_version: i16, max_version: i16) -> Self {
+        let feature = SupportedFeatureKey {
+            min_version,
+            max_version,
+            ..Default::default()
+        };
+        self.supported_features.insert(name, feature);
+        self
+    }
+
+    /// Set the epoch of the finalized features information.
+    pub fn finalized_features_epoch(mut self, epoch: i64) -> Self {
+        self.finalized_features_epoch = epoch;
+        self
+    }
+
+    /// Advertise a cluster-wide finalized feature.
+    pub fn finalized_feature(
+        mut self,
+        name: StrBytes,
+        min_version_level: i16,
+        max_version_level: i16,
+    ) -> Self {
+        let feature = FinalizedFeatureKey {
+            min_version_level,
+            max_version_level,
+            ..Default::default()
+        };
+        self.finalized_features.insert(name, feature);
+        self
+    }
+
+    /// Set whether a KRaft controller is ready for ZK migration.
+    pub fn zk_migration_ready(mut self, ready: bool) -> Self {
+        self.zk_migration_ready = ready;
+        self
+    }
+
+    /// Whether the provided API is advertised by this builder.
+    pub fn is_advertised(&self, key: ApiKey) -> bool {
+        self.version_range(key).is_some()
+    }
+
+    fn version_range(&self, key: ApiKey) -> Option<(i16, i16)> {
+        if !key.listeners().contains(&self.listener) || self.deny.contains(&key) {
+            return None;
+        }
+        if let Some(allow) = &self.allow {
+            if !allow.contains(&key) {
+                return None;
+            }
+        }
+        let versions = key.valid_versions();
+        let mut max = versions.max;
+        if let Some(unstable) = key.unstable_versions().filter(|_| !self.enable_unstable) {
+            max = max.min(unstable.min - 1);
+        }
+        if max < versions.min {
+            None
+        } else {
+            Some((versions.min, max))
+        }
+    }
+
+    /// Build the response to be encoded at the provided `ApiVersionsResponse` version.
+    ///
+    /// Fields which are not part of the provided version are left at their default values.
+    pub fn build(&self, version: i16) -> ApiVersionsResponse {
+        let mut response = ApiVersionsResponse::default();
+        for &key in ApiKey::all() {
+            if let Some((min_version, max_version)) = self.version_range(key) {
+                let api_version = ApiVersion {
+                    min_version,
+                    max_version,
+                    ..Default::default()
+                };
+                response.api_keys.insert(key as i16, api_version);
+            }
+        }
+        if version >= 1 {
+            response.throttle_time_ms = self.throttle_time_ms;
+        }
+        if version >= 3 {
+            response.supported_features = self.supported_features.clone();
+            response.finalized_features_epoch = self.finalized_features_epoch;
+            response.finalized_features = self.finalized_features.clone();
+            response.zk_migration_ready = self.zk_migration_ready;
+        }
+        response
+    }
+}
@@ -29,6 +29,9 @@ pub trait Decompressor<B: ByteBuf> {
     /// Target buffer type for decompression.
     type Buf: ByteBuf;
     /// Decompress records from `B` mapped using `F` into `R`.
+    ///
+    /// Implementations check the bytes they decompress with [`ByteBuf::check_allocation`] before
+    /// allocating for them.
     fn decompress<R, F>(buf: &mut B, f: F) -> Result<R, DecodeError>
     where
         F: FnOnce(&mut Self::Buf) -> Result<R, DecodeError>;
@@ -1,13 +1,14 @@
-use std::io::Write;
+use std::io::{Read, Write};
 
 use bytes::buf::BufMut;
 use bytes::{Bytes, BytesMut};
-use flate2::write::{GzDecoder, GzEncoder};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use flate2::Compression;
 use log::error;
 
 use crate::protocol::buf::{ByteBuf, ByteBufMut};
-use crate::protocol::{DecodeError, EncodeError};
+use crate::protocol::{DecodeError, DecodeErrorKind, EncodeError, EncodeErrorKind};
 
 use super::{Compressor, Decompressor};
 
@@ -15,14 +16,17 @@ use super::{Compressor, Decompressor};
 /// for more information.
 pub struct Gzip;
 
+/// The number of bytes decompressed between checks of the allocation limit.
+const CHUNK_SIZE: u64 = 32 * 1024;
+
 fn compression_err(e: std::io::Error) -> EncodeError {
     error!("Error whilst compressing data: {}", e);
-    EncodeError
+    EncodeError::new(EncodeErrorKind::Compression)
 }
 
 fn decompression_err(e: std::io::Error) -> DecodeError {
     error!("Error whilst decompressing data: {}", e);
-    DecodeError
+    DecodeError::new(DecodeErrorKind::Compression)
 }
 
 impl<B: ByteBufMut> Compressor<B> for Gzip {
@@ -50,13 +54,21 @@ impl<B: ByteBuf> Decompressor<B> for Gzip {
     where
         F: FnOnce(&mut Self::Buf) -> Result<R, DecodeError>,
     {
-        let mut tmp = BytesMut::new();
+        let compressed = buf.copy_to_bytes(buf.remaining());
+        let mut tmp = Vec::new();
 
-        // Decompress directly from the input buffer
-        let mut d = GzDecoder::new((&mut tmp).writer());
-        d.write_all(&buf.copy_to_bytes(buf.remaining()))
-            .map_err(decompression_err)?;
-        d.finish().map_err(decompression_err)?;
+        // Decompress a chunk at a time, checking each one is allowed before reading the next
+        let mut d = GzDecoder::new(&compressed[..]);
+        loop {
+            let len = (&mut d)
+                .take(CHUNK_SIZE)
+                .read_to_end(&mut tmp)
+                .map_err(decompression_err)?;
+            if len == 0 {
+                break;
+            }
+            buf.check_allocation(len)?;
+        }
 
         f(&mut tmp.into())
     }
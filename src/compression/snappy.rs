@@ -6,7 +6,7 @@ use log::error;
 use snap::raw::*;
 
 use crate::protocol::buf::{ByteBuf, ByteBufMut};
-use crate::protocol::{DecodeError, EncodeError};
+use crate::protocol::{DecodeError, DecodeErrorKind, EncodeError, EncodeErrorKind};
 
 use super::{Compressor, Decompressor};
 
@@ -57,7 +57,7 @@ impl<B: ByteBufMut> Compressor<B> for Snappy {
 
             let actual_len = encoder.compress(chunk, &mut chunk_buffer).map_err(|e| {
                 error!("Failed to compress buffer: {}", e);
-                EncodeError
+                EncodeError::new(EncodeErrorKind::Compression)
             })?;
             buf.put_u32(actual_len as u32);
             buf.put_slice(&chunk_buffer[..actual_len]);
@@ -78,23 +78,23 @@ impl<B: ByteBuf> Decompressor<B> for Snappy {
         F: FnOnce(&mut Self::Buf) -> Result<R, DecodeError>,
     {
         // Allocate a temporary buffer to hold the uncompressed bytes
-        let buf = buf.copy_to_bytes(buf.remaining());
+        let compressed = buf.copy_to_bytes(buf.remaining());
 
         // detecting xerial header to also support normal snappy just-in-case
-        let is_from_xerial = buf.len() >= 16 && buf[0] == 130 && from_utf8(&buf[1..7]).unwrap() == "SNAPPY";
+        let is_from_xerial = compressed.len() >= 16 && compressed[0] == 130 && from_utf8(&compressed[1..7]).unwrap() == "SNAPPY";
         if is_from_xerial {
             let mut res: Vec<u8> = Vec::new();
             let mut decoder = Decoder::new();
 
             let mut pos = 0;
-            let max = buf.len();
+            let max = compressed.len();
             // xerial special header is 16 bytes
             pos += 16;
 
             while (pos + 4) <= max {
-                let size = (&buf[pos..(pos + 4)]).try_get_u32().map_err(|e| {
+                let size = (&compressed[pos..(pos + 4)]).try_get_u32().map_err(|e| {
                     error!("Failed to decompress buffer: {}", e);
-                    DecodeError
+                    DecodeError::new(DecodeErrorKind::Compression)
                 })?;
                 pos += 4;
 
@@ -104,14 +104,15 @@ impl<B: ByteBuf> Decompressor<B> for Snappy {
                 if next_pos < pos || next_pos > max {
                     error!("Frame cursor overflow");
 
-                    return Err(DecodeError);
+                    return Err(DecodeError::new(DecodeErrorKind::Compression));
                 };
 
-                let chunk = &buf[pos..next_pos];
+                let chunk = &compressed[pos..next_pos];
                 let len = decompress_len(chunk).map_err(|e| {
                     error!("Failed to decompress buffer: {}", e);
-                    DecodeError
+                    DecodeError::new(DecodeErrorKind::Compression)
                 })?;
+                buf.check_allocation(len)?;
                 let mut chunk_buffer: Vec<u8> = vec![0; len];
 
                 let _ = decoder.decompress(chunk, &mut chunk_buffer);
@@ -122,17 +123,18 @@ impl<B: ByteBuf> Decompressor<B> for Snappy {
 
             f(&mut res.into())
         } else {
-            let actual_len = decompress_len(&buf).map_err(|e| {
+            let actual_len = decompress_len(&compressed).map_err(|e| {
                 error!("Failed to decompress buffer: {}", e);
-                DecodeError
+                DecodeError::new(DecodeErrorKind::Compression)
             })?;
+            buf.check_allocation(actual_len)?;
             let mut tmp = BytesMut::new();
             tmp.resize(actual_len, 0);
             
             // Decompress directly from the input buffer
-            Decoder::new().decompress(&buf, &mut tmp).map_err(|e| {
+            Decoder::new().decompress(&compressed, &mut tmp).map_err(|e| {
                 error!("Failed to decompress buffer: {}", e);
-                DecodeError
+                DecodeError::new(DecodeErrorKind::Compression)
             })?;
 
             f(&mut tmp.into())
@@ -0,0 +1,115 @@
+//! Records of the internal `__consumer_offsets` topic.
+//!
+//! These records are generated programmatically. See [Kafka's schemas](https://github.com/apache/kafka/tree/trunk/group-coordinator/src/main/resources/common/message) for more information about a given record type.
+//!
+//! The key and value of each record are prefixed with the version they are encoded with, and the version of the key
+//! determines the type of the record. [`ConsumerOffsetsRecord`] decodes both from a [`Record`](crate::records::Record) read from the topic.
+// WARNING: the items of this module are generated and should not be edited directly.
+
+use bytes::Bytes;
+use crate::protocol::{Decodable, DecodeError, DecodeErrorKind, Message, types, Decoder};
+
+pub mod group_metadata_key;
+pub use group_metadata_key::GroupMetadataKey;
+
+pub mod group_metadata_value;
+pub use group_metadata_value::GroupMetadataValue;
+
+pub mod offset_commit_key;
+pub use offset_commit_key::OffsetCommitKey;
+
+pub mod offset_commit_value;
+pub use offset_commit_value::OffsetCommitValue;
+
+/// A record of the `__consumer_offsets` topic.
+///
+/// The value is `None` for a tombstone, which deletes the key when the topic is compacted.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsumerOffsetsRecord {
+    /// [`OffsetCommitKey`] and [`OffsetCommitValue`] records.
+    OffsetCommit {
+        /// The key.
+        key: OffsetCommitKey,
+        /// The value, or `None` for a tombstone.
+        value: Option<OffsetCommitValue>,
+    },
+    /// [`GroupMetadataKey`] and [`GroupMetadataValue`] records.
+    GroupMetadata {
+        /// The key.
+        key: GroupMetadataKey,
+        /// The value, or `None` for a tombstone.
+        value: Option<GroupMetadataValue>,
+    },
+    /// A record whose key version is not known to this crate.
+    Unknown {
+        /// The version of the key.
+        version: i16,
+        /// The key, following its version.
+        key: Bytes,
+        /// The value including its version, or `None` for a tombstone.
+        value: Option<Bytes>,
+    },
+}
+
+impl ConsumerOffsetsRecord {
+    /// Decode the key and value of a record of the `__consumer_offsets` topic.
+    pub fn decode(mut key: Bytes, value: Option<Bytes>) -> Result<Self, DecodeError> {
+        let version: i16 = types::Int16.decode(&mut key)?;
+        match version {
+            0..=1 => {
+                let key = OffsetCommitKey::decode(&mut key, version)?;
+                let value = match value {
+                    Some(mut value) => {
+                        let version: i16 = types::Int16.decode(&mut value)?;
+                        check_version::<OffsetCommitValue>(version)?;
+                        Some(OffsetCommitValue::decode(&mut value, version)?)
+                    }
+                    None => None,
+                };
+                Ok(ConsumerOffsetsRecord::OffsetCommit { key, value })
+            }
+            2 => {
+                let key = GroupMetadataKey::decode(&mut key, version)?;
+                let value = match value {
+                    Some(mut value) => {
+                        let version: i16 = types::Int16.decode(&mut value)?;
+                        check_version::<GroupMetadataValue>(version)?;
+                        Some(GroupMetadataValue::decode(&mut value, version)?)
+                    }
+                    None => None,
+                };
+                Ok(ConsumerOffsetsRecord::GroupMetadata { key, value })
+            }
+            _ => Ok(ConsumerOffsetsRecord::Unknown { version, key, value }),
+        }
+    }
+
+    /// Decode a record read from the `__consumer_offsets` topic.
+    pub fn decode_record(record: &crate::records::Record) -> Result<Self, DecodeError> {
+        let key = record.key.clone().ok_or_else(|| {
+            error!("Record at offset {} of __consumer_offsets has no key", record.offset);
+            DecodeError::new(DecodeErrorKind::InvalidValue)
+        })?;
+        Self::decode(key, record.value.clone())
+    }
+
+    /// Whether this record is a tombstone, deleting its key.
+    pub fn is_tombstone(&self) -> bool {
+        match self {
+            ConsumerOffsetsRecord::OffsetCommit { value, .. } => value.is_none(),
+            ConsumerOffsetsRecord::GroupMetadata { value, .. } => value.is_none(),
+            ConsumerOffsetsRecord::Unknown { value, .. } => value.is_none(),
+        }
+    }
+}
+
+fn check_version<M: Message>(version: i16) -> Result<(), DecodeError> {
+    if M::VERSIONS.contains(version) {
+        Ok(())
+    } else {
+        error!("Unsupported version {} of {}", version, std::any::type_name::<M>());
+        Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion))
+    }
+}
+
@@ -0,0 +1,140 @@
+//! GroupMetadataKey
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/group-coordinator/src/main/resources/common/message/GroupMetadataKey.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 2
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct GroupMetadataKey {
+    /// 
+    /// 
+    /// Supported API versions: 2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub group: StrBytes,
+
+}
+
+impl Builder for GroupMetadataKey {
+    type Builder = GroupMetadataKeyBuilder;
+
+    fn builder() -> Self::Builder{
+        GroupMetadataKeyBuilder::default()
+    }
+}
+
+impl Encodable for GroupMetadataKey {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::String.encode(buf, &self.group).map_err(|e| e.with_field("GroupMetadataKey", "group", version))?;
+
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        total_size += types::String.compute_size(&self.group).map_err(|e| e.with_field("GroupMetadataKey", "group", version))?;
+
+        Ok(total_size)
+    }
+}
+
+impl Decodable for GroupMetadataKey {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let group = types::String.decode(buf).map_err(|e| e.with_field("GroupMetadataKey", "group", version, start - buf.remaining()))?;
+        Ok(Self {
+            group,
+        })
+    }
+}
+
+impl Default for GroupMetadataKey {
+    fn default() -> Self {
+        Self {
+            group: Default::default(),
+        }
+    }
+}
+
+impl Message for GroupMetadataKey {
+    const VERSIONS: VersionRange = VersionRange { min: 2, max: 2 };
+}
+
+impl GroupMetadataKey {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl GroupMetadataKey {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("group".into(), JsonValue::to_json(&self.group));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "GroupMetadataKey")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("group") {
+            this.group = JsonValue::from_json(value, "GroupMetadataKey.group")?;
+        } else {
+            return Err(crate::json::missing("GroupMetadataKey", "group", version));
+        }
+        Ok(this)
+    }
+}
+
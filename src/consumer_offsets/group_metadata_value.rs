@@ -0,0 +1,697 @@
+//! GroupMetadataValue
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/group-coordinator/src/main/resources/common/message/GroupMetadataValue.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0-4
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct MemberMetadata {
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub member_id: StrBytes,
+
+    /// 
+    /// 
+    /// Supported API versions: 3-4
+    /// May be `None` in versions: 3-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub group_instance_id: Option<StrBytes>,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub client_id: StrBytes,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub client_host: StrBytes,
+
+    /// 
+    /// 
+    /// Supported API versions: 1-4
+    pub rebalance_timeout: i32,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    pub session_timeout: i32,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    pub subscription: Bytes,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    pub assignment: Bytes,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for MemberMetadata {
+    type Builder = MemberMetadataBuilder;
+
+    fn builder() -> Self::Builder{
+        MemberMetadataBuilder::default()
+    }
+}
+
+impl Encodable for MemberMetadata {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("MemberMetadata", "member_id", version))?;
+        } else {
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("MemberMetadata", "member_id", version))?;
+        }
+        if version >= 3 {
+            if version >= 4 {
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("MemberMetadata", "group_instance_id", version))?;
+            } else {
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("MemberMetadata", "group_instance_id", version))?;
+            }
+        }
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.client_id).map_err(|e| e.with_field("MemberMetadata", "client_id", version))?;
+        } else {
+            types::String.encode(buf, &self.client_id).map_err(|e| e.with_field("MemberMetadata", "client_id", version))?;
+        }
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.client_host).map_err(|e| e.with_field("MemberMetadata", "client_host", version))?;
+        } else {
+            types::String.encode(buf, &self.client_host).map_err(|e| e.with_field("MemberMetadata", "client_host", version))?;
+        }
+        if version >= 1 {
+            types::Int32.encode(buf, &self.rebalance_timeout).map_err(|e| e.with_field("MemberMetadata", "rebalance_timeout", version))?;
+        }
+        types::Int32.encode(buf, &self.session_timeout).map_err(|e| e.with_field("MemberMetadata", "session_timeout", version))?;
+        if version >= 4 {
+            types::CompactBytes.encode(buf, &self.subscription).map_err(|e| e.with_field("MemberMetadata", "subscription", version))?;
+        } else {
+            types::Bytes.encode(buf, &self.subscription).map_err(|e| e.with_field("MemberMetadata", "subscription", version))?;
+        }
+        if version >= 4 {
+            types::CompactBytes.encode(buf, &self.assignment).map_err(|e| e.with_field("MemberMetadata", "assignment", version))?;
+        } else {
+            types::Bytes.encode(buf, &self.assignment).map_err(|e| e.with_field("MemberMetadata", "assignment", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MemberMetadata", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MemberMetadata", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MemberMetadata", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        if version >= 4 {
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("MemberMetadata", "member_id", version))?;
+        } else {
+            total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("MemberMetadata", "member_id", version))?;
+        }
+        if version >= 3 {
+            if version >= 4 {
+                total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("MemberMetadata", "group_instance_id", version))?;
+            } else {
+                total_size += types::String.compute_size(&self.group_instance_id).map_err(|e| e.with_field("MemberMetadata", "group_instance_id", version))?;
+            }
+        }
+        if version >= 4 {
+            total_size += types::CompactString.compute_size(&self.client_id).map_err(|e| e.with_field("MemberMetadata", "client_id", version))?;
+        } else {
+            total_size += types::String.compute_size(&self.client_id).map_err(|e| e.with_field("MemberMetadata", "client_id", version))?;
+        }
+        if version >= 4 {
+            total_size += types::CompactString.compute_size(&self.client_host).map_err(|e| e.with_field("MemberMetadata", "client_host", version))?;
+        } else {
+            total_size += types::String.compute_size(&self.client_host).map_err(|e| e.with_field("MemberMetadata", "client_host", version))?;
+        }
+        if version >= 1 {
+            total_size += types::Int32.compute_size(&self.rebalance_timeout).map_err(|e| e.with_field("MemberMetadata", "rebalance_timeout", version))?;
+        }
+        total_size += types::Int32.compute_size(&self.session_timeout).map_err(|e| e.with_field("MemberMetadata", "session_timeout", version))?;
+        if version >= 4 {
+            total_size += types::CompactBytes.compute_size(&self.subscription).map_err(|e| e.with_field("MemberMetadata", "subscription", version))?;
+        } else {
+            total_size += types::Bytes.compute_size(&self.subscription).map_err(|e| e.with_field("MemberMetadata", "subscription", version))?;
+        }
+        if version >= 4 {
+            total_size += types::CompactBytes.compute_size(&self.assignment).map_err(|e| e.with_field("MemberMetadata", "assignment", version))?;
+        } else {
+            total_size += types::Bytes.compute_size(&self.assignment).map_err(|e| e.with_field("MemberMetadata", "assignment", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MemberMetadata", "unknown_tagged_fields", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("MemberMetadata", "unknown_tagged_fields", version))?;
+
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("MemberMetadata", "unknown_tagged_fields", version))?;
+        }
+        Ok(total_size)
+    }
+}
+
+impl Decodable for MemberMetadata {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let member_id = if version >= 4 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MemberMetadata", "member_id", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("MemberMetadata", "member_id", version, start - buf.remaining()))?
+        };
+        let group_instance_id = if version >= 3 {
+            if version >= 4 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("MemberMetadata", "group_instance_id", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("MemberMetadata", "group_instance_id", version, start - buf.remaining()))?
+            }
+        } else {
+            None
+        };
+        let client_id = if version >= 4 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MemberMetadata", "client_id", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("MemberMetadata", "client_id", version, start - buf.remaining()))?
+        };
+        let client_host = if version >= 4 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MemberMetadata", "client_host", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("MemberMetadata", "client_host", version, start - buf.remaining()))?
+        };
+        let rebalance_timeout = if version >= 1 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("MemberMetadata", "rebalance_timeout", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let session_timeout = types::Int32.decode(buf).map_err(|e| e.with_field("MemberMetadata", "session_timeout", version, start - buf.remaining()))?;
+        let subscription = if version >= 4 {
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("MemberMetadata", "subscription", version, start - buf.remaining()))?
+        } else {
+            types::Bytes.decode(buf).map_err(|e| e.with_field("MemberMetadata", "subscription", version, start - buf.remaining()))?
+        };
+        let assignment = if version >= 4 {
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("MemberMetadata", "assignment", version, start - buf.remaining()))?
+        } else {
+            types::Bytes.decode(buf).map_err(|e| e.with_field("MemberMetadata", "assignment", version, start - buf.remaining()))?
+        };
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        if version >= 4 {
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MemberMetadata", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            for _ in 0..num_tagged_fields {
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MemberMetadata", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MemberMetadata", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("MemberMetadata", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                unknown_tagged_fields.insert(tag as i32, unknown_value);
+            }
+        }
+        Ok(Self {
+            member_id,
+            group_instance_id,
+            client_id,
+            client_host,
+            rebalance_timeout,
+            session_timeout,
+            subscription,
+            assignment,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for MemberMetadata {
+    fn default() -> Self {
+        Self {
+            member_id: Default::default(),
+            group_instance_id: None,
+            client_id: Default::default(),
+            client_host: Default::default(),
+            rebalance_timeout: 0,
+            session_timeout: 0,
+            subscription: Default::default(),
+            assignment: Default::default(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for MemberMetadata {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
+}
+
+impl MemberMetadata {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.group_instance_id = None;
+        }
+        if version < 1 {
+            self.rebalance_timeout = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.group_instance_id.is_none() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+        if version < 1 {
+            if self.rebalance_timeout != 0 {
+                invalid.push(format!("{}rebalance_timeout", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MemberMetadata {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        if version >= 3 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        node.insert("clientId".into(), JsonValue::to_json(&self.client_id));
+        node.insert("clientHost".into(), JsonValue::to_json(&self.client_host));
+        if version >= 1 {
+            node.insert("rebalanceTimeout".into(), JsonValue::to_json(&self.rebalance_timeout));
+        }
+        node.insert("sessionTimeout".into(), JsonValue::to_json(&self.session_timeout));
+        node.insert("subscription".into(), JsonValue::to_json(&self.subscription));
+        node.insert("assignment".into(), JsonValue::to_json(&self.assignment));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "MemberMetadata")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "MemberMetadata.memberId")?;
+        } else {
+            return Err(crate::json::missing("MemberMetadata", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "MemberMetadata.groupInstanceId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("MemberMetadata", "groupInstanceId", version));
+        }
+        if let Some(value) = object.get("clientId") {
+            this.client_id = JsonValue::from_json(value, "MemberMetadata.clientId")?;
+        } else {
+            return Err(crate::json::missing("MemberMetadata", "clientId", version));
+        }
+        if let Some(value) = object.get("clientHost") {
+            this.client_host = JsonValue::from_json(value, "MemberMetadata.clientHost")?;
+        } else {
+            return Err(crate::json::missing("MemberMetadata", "clientHost", version));
+        }
+        if let Some(value) = object.get("rebalanceTimeout") {
+            this.rebalance_timeout = JsonValue::from_json(value, "MemberMetadata.rebalanceTimeout")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("MemberMetadata", "rebalanceTimeout", version));
+        }
+        if let Some(value) = object.get("sessionTimeout") {
+            this.session_timeout = JsonValue::from_json(value, "MemberMetadata.sessionTimeout")?;
+        } else {
+            return Err(crate::json::missing("MemberMetadata", "sessionTimeout", version));
+        }
+        if let Some(value) = object.get("subscription") {
+            this.subscription = JsonValue::from_json(value, "MemberMetadata.subscription")?;
+        } else {
+            return Err(crate::json::missing("MemberMetadata", "subscription", version));
+        }
+        if let Some(value) = object.get("assignment") {
+            this.assignment = JsonValue::from_json(value, "MemberMetadata.assignment")?;
+        } else {
+            return Err(crate::json::missing("MemberMetadata", "assignment", version));
+        }
+        Ok(this)
+    }
+}
+
+/// Valid versions: 0-4
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct GroupMetadataValue {
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub protocol_type: StrBytes,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    pub generation: i32,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    /// May be `None` in versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub protocol: Option<StrBytes>,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    /// May be `None` in versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub leader: Option<StrBytes>,
+
+    /// 
+    /// 
+    /// Supported API versions: 2-4
+    pub current_state_timestamp: i64,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    pub members: Vec<MemberMetadata>,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for GroupMetadataValue {
+    type Builder = GroupMetadataValueBuilder;
+
+    fn builder() -> Self::Builder{
+        GroupMetadataValueBuilder::default()
+    }
+}
+
+impl Encodable for GroupMetadataValue {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("GroupMetadataValue", "protocol_type", version))?;
+        } else {
+            types::String.encode(buf, &self.protocol_type).map_err(|e| e.with_field("GroupMetadataValue", "protocol_type", version))?;
+        }
+        types::Int32.encode(buf, &self.generation).map_err(|e| e.with_field("GroupMetadataValue", "generation", version))?;
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.protocol).map_err(|e| e.with_field("GroupMetadataValue", "protocol", version))?;
+        } else {
+            types::String.encode(buf, &self.protocol).map_err(|e| e.with_field("GroupMetadataValue", "protocol", version))?;
+        }
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.leader).map_err(|e| e.with_field("GroupMetadataValue", "leader", version))?;
+        } else {
+            types::String.encode(buf, &self.leader).map_err(|e| e.with_field("GroupMetadataValue", "leader", version))?;
+        }
+        if version >= 2 {
+            types::Int64.encode(buf, &self.current_state_timestamp).map_err(|e| e.with_field("GroupMetadataValue", "current_state_timestamp", version))?;
+        }
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.members).map_err(|e| e.with_field("GroupMetadataValue", "members", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.members).map_err(|e| e.with_field("GroupMetadataValue", "members", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("GroupMetadataValue", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("GroupMetadataValue", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("GroupMetadataValue", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        if version >= 4 {
+            total_size += types::CompactString.compute_size(&self.protocol_type).map_err(|e| e.with_field("GroupMetadataValue", "protocol_type", version))?;
+        } else {
+            total_size += types::String.compute_size(&self.protocol_type).map_err(|e| e.with_field("GroupMetadataValue", "protocol_type", version))?;
+        }
+        total_size += types::Int32.compute_size(&self.generation).map_err(|e| e.with_field("GroupMetadataValue", "generation", version))?;
+        if version >= 4 {
+            total_size += types::CompactString.compute_size(&self.protocol).map_err(|e| e.with_field("GroupMetadataValue", "protocol", version))?;
+        } else {
+            total_size += types::String.compute_size(&self.protocol).map_err(|e| e.with_field("GroupMetadataValue", "protocol", version))?;
+        }
+        if version >= 4 {
+            total_size += types::CompactString.compute_size(&self.leader).map_err(|e| e.with_field("GroupMetadataValue", "leader", version))?;
+        } else {
+            total_size += types::String.compute_size(&self.leader).map_err(|e| e.with_field("GroupMetadataValue", "leader", version))?;
+        }
+        if version >= 2 {
+            total_size += types::Int64.compute_size(&self.current_state_timestamp).map_err(|e| e.with_field("GroupMetadataValue", "current_state_timestamp", version))?;
+        }
+        if version >= 4 {
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.members).map_err(|e| e.with_field("GroupMetadataValue", "members", version))?;
+        } else {
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.members).map_err(|e| e.with_field("GroupMetadataValue", "members", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("GroupMetadataValue", "unknown_tagged_fields", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("GroupMetadataValue", "unknown_tagged_fields", version))?;
+
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("GroupMetadataValue", "unknown_tagged_fields", version))?;
+        }
+        Ok(total_size)
+    }
+}
+
+impl Decodable for GroupMetadataValue {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let protocol_type = if version >= 4 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "protocol_type", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "protocol_type", version, start - buf.remaining()))?
+        };
+        let generation = types::Int32.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "generation", version, start - buf.remaining()))?;
+        let protocol = if version >= 4 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "protocol", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "protocol", version, start - buf.remaining()))?
+        };
+        let leader = if version >= 4 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "leader", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "leader", version, start - buf.remaining()))?
+        };
+        let current_state_timestamp = if version >= 2 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "current_state_timestamp", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let members = if version >= 4 {
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "members", version, start - buf.remaining()))?
+        } else {
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "members", version, start - buf.remaining()))?
+        };
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        if version >= 4 {
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            for _ in 0..num_tagged_fields {
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("GroupMetadataValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("GroupMetadataValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                unknown_tagged_fields.insert(tag as i32, unknown_value);
+            }
+        }
+        Ok(Self {
+            protocol_type,
+            generation,
+            protocol,
+            leader,
+            current_state_timestamp,
+            members,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for GroupMetadataValue {
+    fn default() -> Self {
+        Self {
+            protocol_type: Default::default(),
+            generation: 0,
+            protocol: Some(Default::default()),
+            leader: Some(Default::default()),
+            current_state_timestamp: -1,
+            members: Default::default(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for GroupMetadataValue {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
+}
+
+impl GroupMetadataValue {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.current_state_timestamp = -1;
+        }
+        for item in self.members.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if self.current_state_timestamp != -1 {
+                invalid.push(format!("{}current_state_timestamp", path));
+            }
+        }
+        for (i, item) in self.members.iter().enumerate() {
+            item.validate_into(version, &format!("{}members[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl GroupMetadataValue {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("protocolType".into(), JsonValue::to_json(&self.protocol_type));
+        node.insert("generation".into(), JsonValue::to_json(&self.generation));
+        node.insert("protocol".into(), JsonValue::to_json(&self.protocol));
+        node.insert("leader".into(), JsonValue::to_json(&self.leader));
+        if version >= 2 {
+            node.insert("currentStateTimestamp".into(), JsonValue::to_json(&self.current_state_timestamp));
+        }
+        node.insert("members".into(), serde_json::Value::Array(self.members.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "GroupMetadataValue")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("protocolType") {
+            this.protocol_type = JsonValue::from_json(value, "GroupMetadataValue.protocolType")?;
+        } else {
+            return Err(crate::json::missing("GroupMetadataValue", "protocolType", version));
+        }
+        if let Some(value) = object.get("generation") {
+            this.generation = JsonValue::from_json(value, "GroupMetadataValue.generation")?;
+        } else {
+            return Err(crate::json::missing("GroupMetadataValue", "generation", version));
+        }
+        if let Some(value) = object.get("protocol") {
+            this.protocol = JsonValue::from_json(value, "GroupMetadataValue.protocol")?;
+        } else {
+            return Err(crate::json::missing("GroupMetadataValue", "protocol", version));
+        }
+        if let Some(value) = object.get("leader") {
+            this.leader = JsonValue::from_json(value, "GroupMetadataValue.leader")?;
+        } else {
+            return Err(crate::json::missing("GroupMetadataValue", "leader", version));
+        }
+        if let Some(value) = object.get("currentStateTimestamp") {
+            this.current_state_timestamp = JsonValue::from_json(value, "GroupMetadataValue.currentStateTimestamp")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("GroupMetadataValue", "currentStateTimestamp", version));
+        }
+        if let Some(value) = object.get("members") {
+            this.members = crate::json::array(value, "GroupMetadataValue.members")?.iter().map(|item| MemberMetadata::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("GroupMetadataValue", "members", version));
+        }
+        Ok(this)
+    }
+}
+
@@ -0,0 +1,282 @@
+//! OffsetCommitValue
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/group-coordinator/src/main/resources/common/message/OffsetCommitValue.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0-4
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct OffsetCommitValue {
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    pub offset: i64,
+
+    /// 
+    /// 
+    /// Supported API versions: 3-4
+    pub leader_epoch: i32,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub metadata: StrBytes,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-4
+    pub commit_timestamp: i64,
+
+    /// 
+    /// 
+    /// Supported API versions: 1
+    pub expire_timestamp: i64,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for OffsetCommitValue {
+    type Builder = OffsetCommitValueBuilder;
+
+    fn builder() -> Self::Builder{
+        OffsetCommitValueBuilder::default()
+    }
+}
+
+impl Encodable for OffsetCommitValue {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int64.encode(buf, &self.offset).map_err(|e| e.with_field("OffsetCommitValue", "offset", version))?;
+        if version >= 3 {
+            types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("OffsetCommitValue", "leader_epoch", version))?;
+        }
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.metadata).map_err(|e| e.with_field("OffsetCommitValue", "metadata", version))?;
+        } else {
+            types::String.encode(buf, &self.metadata).map_err(|e| e.with_field("OffsetCommitValue", "metadata", version))?;
+        }
+        types::Int64.encode(buf, &self.commit_timestamp).map_err(|e| e.with_field("OffsetCommitValue", "commit_timestamp", version))?;
+        if version == 1 {
+            types::Int64.encode(buf, &self.expire_timestamp).map_err(|e| e.with_field("OffsetCommitValue", "expire_timestamp", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitValue", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitValue", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitValue", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        total_size += types::Int64.compute_size(&self.offset).map_err(|e| e.with_field("OffsetCommitValue", "offset", version))?;
+        if version >= 3 {
+            total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("OffsetCommitValue", "leader_epoch", version))?;
+        }
+        if version >= 4 {
+            total_size += types::CompactString.compute_size(&self.metadata).map_err(|e| e.with_field("OffsetCommitValue", "metadata", version))?;
+        } else {
+            total_size += types::String.compute_size(&self.metadata).map_err(|e| e.with_field("OffsetCommitValue", "metadata", version))?;
+        }
+        total_size += types::Int64.compute_size(&self.commit_timestamp).map_err(|e| e.with_field("OffsetCommitValue", "commit_timestamp", version))?;
+        if version == 1 {
+            total_size += types::Int64.compute_size(&self.expire_timestamp).map_err(|e| e.with_field("OffsetCommitValue", "expire_timestamp", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitValue", "unknown_tagged_fields", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitValue", "unknown_tagged_fields", version))?;
+
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitValue", "unknown_tagged_fields", version))?;
+        }
+        Ok(total_size)
+    }
+}
+
+impl Decodable for OffsetCommitValue {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let offset = types::Int64.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "offset", version, start - buf.remaining()))?;
+        let leader_epoch = if version >= 3 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "leader_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let metadata = if version >= 4 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "metadata", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "metadata", version, start - buf.remaining()))?
+        };
+        let commit_timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "commit_timestamp", version, start - buf.remaining()))?;
+        let expire_timestamp = if version == 1 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "expire_timestamp", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        if version >= 4 {
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            for _ in 0..num_tagged_fields {
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetCommitValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                unknown_tagged_fields.insert(tag as i32, unknown_value);
+            }
+        }
+        Ok(Self {
+            offset,
+            leader_epoch,
+            metadata,
+            commit_timestamp,
+            expire_timestamp,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for OffsetCommitValue {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            leader_epoch: -1,
+            metadata: Default::default(),
+            commit_timestamp: 0,
+            expire_timestamp: -1,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for OffsetCommitValue {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
+}
+
+impl OffsetCommitValue {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.leader_epoch = -1;
+        }
+        if version != 1 {
+            self.expire_timestamp = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.leader_epoch != -1 {
+                invalid.push(format!("{}leader_epoch", path));
+            }
+        }
+        if version != 1 {
+            if self.expire_timestamp != -1 {
+                invalid.push(format!("{}expire_timestamp", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetCommitValue {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("offset".into(), JsonValue::to_json(&self.offset));
+        if version >= 3 {
+            node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        }
+        node.insert("metadata".into(), JsonValue::to_json(&self.metadata));
+        node.insert("commitTimestamp".into(), JsonValue::to_json(&self.commit_timestamp));
+        if version == 1 {
+            node.insert("expireTimestamp".into(), JsonValue::to_json(&self.expire_timestamp));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetCommitValue")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("offset") {
+            this.offset = JsonValue::from_json(value, "OffsetCommitValue.offset")?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitValue", "offset", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "OffsetCommitValue.leaderEpoch")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("OffsetCommitValue", "leaderEpoch", version));
+        }
+        if let Some(value) = object.get("metadata") {
+            this.metadata = JsonValue::from_json(value, "OffsetCommitValue.metadata")?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitValue", "metadata", version));
+        }
+        if let Some(value) = object.get("commitTimestamp") {
+            this.commit_timestamp = JsonValue::from_json(value, "OffsetCommitValue.commitTimestamp")?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitValue", "commitTimestamp", version));
+        }
+        if let Some(value) = object.get("expireTimestamp") {
+            this.expire_timestamp = JsonValue::from_json(value, "OffsetCommitValue.expireTimestamp")?;
+        } else if version == 1 {
+            return Err(crate::json::missing("OffsetCommitValue", "expireTimestamp", version));
+        }
+        Ok(this)
+    }
+}
+
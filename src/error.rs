@@ -108,6 +108,7 @@ macro_rules! define_errors {
             /// Read [Kafka Protocol Guide](https://kafka.apache.org/protocol#protocol_error_codes) and
             /// [Errors.java](https://github.com/apache/kafka/blob/6d7723f073/clients/src/main/java/org/apache/kafka/common/protocol/Errors.java#L135-L147) for more details.
             #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             pub enum $name {
 
                 /// Client-side unknown error code.
@@ -0,0 +1,387 @@
+//! Reading, writing and incremental decoding of the size-delimited frames Kafka sends over a
+//! socket.
+//!
+//! Decoding a message requires all of its bytes, so a [`RequestDecoder`] is fed the bytes read
+//! from the socket as they arrive, and reports how many more bytes it needs before the next part
+//! of the request can be decoded: the size of the frame, its header, the top-level fields of its
+//! body, and finally the whole frame.
+//!
+//! ```rust
+//! # #[cfg(feature = "client-core")] {
+//! use bytes::BytesMut;
+//! use kafka_protocol::frame::{Decoded, ProduceRequestHead, RequestDecoder};
+//! use kafka_protocol::messages::{ApiKey, ProduceRequest, RequestHeader};
+//! use kafka_protocol::protocol::{Encodable, HeaderVersion};
+//!
+//! # let mut header = RequestHeader::default();
+//! # header.request_api_key = ApiKey::ProduceKey as i16;
+//! # header.request_api_version = 9;
+//! # let mut body = BytesMut::new();
+//! # header.encode(&mut body, ProduceRequest::header_version(9)).unwrap();
+//! # ProduceRequest::default().encode(&mut body, 9).unwrap();
+//! # let mut frame = BytesMut::new();
+//! # frame.extend_from_slice(&(body.len() as i32).to_be_bytes());
+//! # frame.extend_from_slice(&body);
+//! let mut decoder = RequestDecoder::new();
+//! let mut src = BytesMut::new();
+//! src.extend_from_slice(&frame[..5]);
+//! assert!(matches!(decoder.decode_header(&src), Ok(Decoded::Incomplete(_))));
+//!
+//! src.extend_from_slice(&frame[5..]);
+//! let api_key = match decoder.decode_header(&src).unwrap() {
+//!     Decoded::Complete(header) => header.request_api_key,
+//!     Decoded::Incomplete(_) => unreachable!(),
+//! };
+//! assert_eq!(api_key, ApiKey::ProduceKey as i16);
+//! let head = decoder
+//!     .decode_prefix(&src, |buf, version| ProduceRequestHead::decode(buf, version))
+//!     .unwrap();
+//! assert!(matches!(head, Decoded::Complete(ProduceRequestHead { acks: 0, .. })));
+//! assert!(matches!(decoder.decode(&mut src), Ok(Decoded::Complete(_))));
+//! assert!(src.is_empty());
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+
+use bytes::{Buf, Bytes, BytesMut};
+
+#[cfg(feature = "client-core")]
+pub use crate::messages::produce_request::ProduceRequestHead;
+use crate::messages::{ApiKey, RequestHeader};
+use crate::protocol::buf::{ByteBuf, NotEnoughBytesError};
+use crate::protocol::{
+    types, Decodable, DecodeError, DecodeErrorKind, Decoder, EncodeError, EncodeErrorKind,
+};
+
+/// The size of the length prefix of a frame.
+const SIZE_LEN: usize = 4;
+
+/// The default largest frame a [`RequestDecoder`] accepts, which is the default of the
+/// `socket.request.max.bytes` setting of Kafka brokers.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 100 * 1024 * 1024;
+
+/// The result of decoding from a buffer which may not hold all the bytes needed yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoded<T> {
+    /// The value was decoded.
+    Complete(T),
+    /// At least this many more bytes must be received before the value can be decoded. Exact
+    /// for frames, and a lower bound for the values they contain.
+    Incomplete(usize),
+}
+
+impl<T> Decoded<T> {
+    /// Map the decoded value.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Decoded<U> {
+        match self {
+            Decoded::Complete(value) => Decoded::Complete(f(value)),
+            Decoded::Incomplete(needed) => Decoded::Incomplete(needed),
+        }
+    }
+
+    /// The decoded value, if complete.
+    pub fn complete(self) -> Option<T> {
+        match self {
+            Decoded::Complete(value) => Some(value),
+            Decoded::Incomplete(_) => None,
+        }
+    }
+}
+
+/// The size of the frame at the start of `src`, excluding its length prefix.
+pub fn frame_size(src: &[u8]) -> Result<Decoded<usize>, DecodeError> {
+    if src.len() < SIZE_LEN {
+        return Ok(Decoded::Incomplete(SIZE_LEN - src.len()));
+    }
+    match (&src[..SIZE_LEN]).get_i32() {
+        size if size >= 0 => Ok(Decoded::Complete(size as usize)),
+        size => {
+            error!("Frame size is negative ({})", size);
+            Err(DecodeError::new(DecodeErrorKind::NegativeLength))
+        }
+    }
+}
+
+/// Split the frame at the start of `src` off it, without its length prefix, once it has been
+/// received in full.
+pub fn decode_frame(src: &mut BytesMut) -> Result<Decoded<Bytes>, DecodeError> {
+    let size = match frame_size(src)? {
+        Decoded::Complete(size) => size,
+        Decoded::Incomplete(needed) => return Ok(Decoded::Incomplete(needed)),
+    };
+    if src.len() < SIZE_LEN + size {
+        return Ok(Decoded::Incomplete(SIZE_LEN + size - src.len()));
+    }
+    src.advance(SIZE_LEN);
+    Ok(Decoded::Complete(src.split_to(size).freeze()))
+}
+
+/// Read a frame from a reader, such as a `TcpStream`, returning it without its length prefix.
+///
+/// Frames larger than `max_size` are rejected with an [`io::ErrorKind::InvalidData`] error
+/// before allocating for them.
+pub fn read_frame<R: Read>(reader: &mut R, max_size: usize) -> io::Result<Bytes> {
+    let mut size = [0; SIZE_LEN];
+    reader.read_exact(&mut size)?;
+    let size = match frame_size(&size).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))? {
+        Decoded::Complete(size) => size,
+        Decoded::Incomplete(_) => unreachable!("the length prefix has been read"),
+    };
+    if size > max_size {
+        error!(
+            "Frame size {} exceeds the limit of {} bytes",
+            size, max_size
+        );
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            DecodeError::new(DecodeErrorKind::LimitExceeded),
+        ));
+    }
+    let mut frame = BytesMut::zeroed(size);
+    reader.read_exact(&mut frame)?;
+    Ok(frame.freeze())
+}
+
+/// Write a frame to a writer, prefixed with its length.
+pub fn write_frame<W: Write>(writer: &mut W, frame: &[u8]) -> io::Result<()> {
+    let size = i32::try_from(frame.len()).map_err(|_| {
+        error!("Frame is too long to encode ({} bytes)", frame.len());
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            EncodeError::new(EncodeErrorKind::ValueTooLarge),
+        )
+    })?;
+    writer.write_all(&size.to_be_bytes())?;
+    writer.write_all(frame)
+}
+
+/// The received part of a frame, which records how many more bytes were needed by the read which
+/// ran out of them.
+#[derive(Debug, Clone, Copy)]
+pub struct PartialBuf<'a> {
+    buf: &'a [u8],
+    needed: usize,
+}
+
+impl<'a> PartialBuf<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, needed: 0 }
+    }
+
+    fn need(&mut self, len: usize) -> Result<(), NotEnoughBytesError> {
+        if self.buf.len() < len {
+            self.needed = len - self.buf.len();
+            Err(NotEnoughBytesError)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Buf for PartialBuf<'_> {
+    fn remaining(&self) -> usize {
+        self.buf.remaining()
+    }
+    fn chunk(&self) -> &[u8] {
+        self.buf.chunk()
+    }
+    fn advance(&mut self, cnt: usize) {
+        self.buf.advance(cnt)
+    }
+}
+
+macro_rules! partial_try_get {
+    ($($name:ident => $get:ident($t:ty)),*$(,)*) => {
+        $(
+            fn $name(&mut self) -> Result<$t, NotEnoughBytesError> {
+                self.need(std::mem::size_of::<$t>())?;
+                Ok(self.buf.$get())
+            }
+        )*
+    };
+}
+
+impl ByteBuf for PartialBuf<'_> {
+    fn peek_bytes(&mut self, r: Range<usize>) -> Bytes {
+        self.buf.peek_bytes(r)
+    }
+    fn get_bytes(&mut self, size: usize) -> Bytes {
+        self.buf.get_bytes(size)
+    }
+    fn try_peek_bytes(&mut self, r: Range<usize>) -> Result<Bytes, NotEnoughBytesError> {
+        self.need(r.end)?;
+        Ok(self.peek_bytes(r))
+    }
+    fn try_get_bytes(&mut self, size: usize) -> Result<Bytes, NotEnoughBytesError> {
+        self.need(size)?;
+        Ok(self.get_bytes(size))
+    }
+    fn try_advance(&mut self, cnt: usize) -> Result<(), NotEnoughBytesError> {
+        self.need(cnt)?;
+        self.advance(cnt);
+        Ok(())
+    }
+    fn try_copy_to_slice(&mut self, dst: &mut [u8]) -> Result<(), NotEnoughBytesError> {
+        self.need(dst.len())?;
+        self.copy_to_slice(dst);
+        Ok(())
+    }
+    fn check_bytes_len(&mut self, len: usize) -> Result<(), DecodeError> {
+        Ok(self.need(len)?)
+    }
+    partial_try_get! {
+        try_get_u8 => get_u8(u8),
+        try_get_u16 => get_u16(u16),
+        try_get_u32 => get_u32(u32),
+        try_get_i8 => get_i8(i8),
+        try_get_i16 => get_i16(i16),
+        try_get_i32 => get_i32(i32),
+        try_get_i64 => get_i64(i64),
+        try_get_f64 => get_f64(f64),
+    }
+}
+
+/// Decode a value from the start of a frame body which may have only been partially received.
+///
+/// Running out of bytes before the frame ends means more are needed: at least the rest of the
+/// value being read when they ran out, up to the end of the frame. Running out at the end of the
+/// frame is an error.
+fn decode_partial<T, F>(body: &[u8], size: usize, decode: F) -> Result<Decoded<T>, DecodeError>
+where
+    F: FnOnce(&mut PartialBuf<'_>) -> Result<T, DecodeError>,
+{
+    let received = body.len().min(size);
+    let mut buf = PartialBuf::new(&body[..received]);
+    match decode(&mut buf) {
+        Ok(value) => Ok(Decoded::Complete(value)),
+        Err(e) if e.kind() == DecodeErrorKind::NotEnoughBytes && received < size => {
+            Ok(Decoded::Incomplete(buf.needed.clamp(1, size - received)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Decodes requests incrementally, from a buffer the bytes read from the socket are appended to.
+///
+/// The header and top-level fields of a request can be decoded as soon as they have been
+/// received, without waiting for the rest of the frame, so that a proxy can route large requests
+/// early. [`RequestDecoder::decode`] then splits the complete frame off the buffer, and resets the
+/// decoder for the next request.
+///
+/// Requests for APIs left out by the enabled features fail with
+/// [`DecodeErrorKind::UnsupportedApiKey`], as soon as their API key has been received.
+#[derive(Debug, Clone)]
+pub struct RequestDecoder {
+    header: Option<(RequestHeader, usize)>,
+    max_frame_size: usize,
+}
+
+impl Default for RequestDecoder {
+    fn default() -> Self {
+        Self::with_max_frame_size(DEFAULT_MAX_FRAME_SIZE)
+    }
+}
+
+impl RequestDecoder {
+    /// Create a decoder, expecting the buffer to start with the length prefix of a request, which
+    /// accepts frames of up to [`DEFAULT_MAX_FRAME_SIZE`] bytes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a decoder accepting frames of up to `max_frame_size` bytes, excluding their length
+    /// prefix. Larger frames are rejected with [`DecodeErrorKind::LimitExceeded`] as soon as
+    /// their length prefix has been received, before the buffer grows to hold them.
+    pub fn with_max_frame_size(max_frame_size: usize) -> Self {
+        Self {
+            header: None,
+            max_frame_size,
+        }
+    }
+
+    /// Decode the header of the request at the start of `src`.
+    pub fn decode_header(
+        &mut self,
+        src: &BytesMut,
+    ) -> Result<Decoded<&RequestHeader>, DecodeError> {
+        if self.header.is_none() {
+            let size = match frame_size(src)? {
+                Decoded::Complete(size) => size,
+                Decoded::Incomplete(needed) => return Ok(Decoded::Incomplete(needed)),
+            };
+            if size > self.max_frame_size {
+                error!(
+                    "Frame size {} exceeds the limit of {} bytes",
+                    size, self.max_frame_size
+                );
+                return Err(DecodeError::new(DecodeErrorKind::LimitExceeded));
+            }
+            let body = &src[SIZE_LEN..];
+            let decoded = decode_partial(body, size, |buf| {
+                let mut peek = *buf;
+                let api_key: i16 = types::Int16.decode(&mut peek)?;
+                let api_version: i16 = types::Int16.decode(&mut peek)?;
+                let api_key = ApiKey::try_from(api_key).map_err(|_| {
+                    error!("Unsupported API key {}", api_key);
+                    DecodeError::new(DecodeErrorKind::UnsupportedApiKey)
+                })?;
+                let start = buf.remaining();
+                let header =
+                    RequestHeader::decode(buf, api_key.request_header_version(api_version))?;
+                Ok((header, start - buf.remaining()))
+            })?;
+            match decoded {
+                Decoded::Complete(header) => self.header = Some(header),
+                Decoded::Incomplete(needed) => return Ok(Decoded::Incomplete(needed)),
+            }
+        }
+        Ok(Decoded::Complete(&self.header.as_ref().unwrap().0))
+    }
+
+    /// Decode a prefix of the body of the request at the start of `src` with `decode`, such as
+    /// [`ProduceRequestHead::decode`], once its header has been decoded.
+    ///
+    /// The body is decoded from the start every time, so this is meant for the small fields at
+    /// the start of the body, or for heads which only skip over the bulk of it. Skipping still
+    /// needs the bytes skipped over: a [`ProduceRequestHead`] walks the records of each topic to
+    /// reach the name of the next one, so it is only complete once all of its topics have been
+    /// received, records included.
+    pub fn decode_prefix<T, F>(
+        &mut self,
+        src: &BytesMut,
+        decode: F,
+    ) -> Result<Decoded<T>, DecodeError>
+    where
+        F: FnOnce(&mut PartialBuf<'_>, i16) -> Result<T, DecodeError>,
+    {
+        let version = match self.decode_header(src)? {
+            Decoded::Complete(header) => header.request_api_version,
+            Decoded::Incomplete(needed) => return Ok(Decoded::Incomplete(needed)),
+        };
+        let size = frame_size(src)?.complete().unwrap();
+        let header_len = self.header.as_ref().unwrap().1;
+        let body = &src[(SIZE_LEN + header_len)..];
+        decode_partial(body, size - header_len, |buf| decode(buf, version))
+    }
+
+    /// Split the request at the start of `src` off it once it has been received in full, returning
+    /// its header and the encoded body.
+    pub fn decode(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<Decoded<(RequestHeader, Bytes)>, DecodeError> {
+        if let Decoded::Incomplete(needed) = self.decode_header(src)? {
+            return Ok(Decoded::Incomplete(needed));
+        }
+        let mut frame = match decode_frame(src)? {
+            Decoded::Complete(frame) => frame,
+            Decoded::Incomplete(needed) => return Ok(Decoded::Incomplete(needed)),
+        };
+        let (header, header_len) = self.header.take().unwrap();
+        frame.advance(header_len);
+        Ok(Decoded::Complete((header, frame)))
+    }
+}
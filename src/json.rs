@@ -0,0 +1,71 @@
+//! Provides the JSON representation of messages used by Kafka's generated `JsonConverter` classes.
+//!
+//! Kafka tools such as the broker request log, `kafka-dump-log` and the metadata shell print
+//! messages in this representation. Every generated message provides `to_json` and `from_json`
+//! methods converting to and from it, for a given message version:
+//!
+//! - fields are named in camel case after the schema, e.g. `throttleTimeMs`;
+//! - only fields present in the version are written, tagged fields only when not default;
+//! - bytes are written as base64 strings, UUIDs in Kafka's URL-safe base64 form;
+//! - records are written as their size, in a `<field>SizeInBytes` field, see [`RecordsJson`] for
+//!   the alternatives;
+//! - maps keyed by a field of their entries are written as arrays of entries.
+//!
+//! ```rust
+//! use kafka_protocol::messages::ApiVersionsRequest;
+//! use kafka_protocol::protocol::StrBytes;
+//!
+//! let mut request = ApiVersionsRequest::default();
+//! request.client_software_name = StrBytes::from_str("my-client");
+//! let json = request.to_json(3);
+//! assert_eq!(json["clientSoftwareName"], "my-client");
+//! assert_eq!(ApiVersionsRequest::from_json(&json, 3).unwrap(), request);
+//! ```
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+pub(crate) mod value;
+
+pub(crate) use value::{array, insert_records, missing, object, JsonValue};
+
+/// How the records fields of messages are written by `to_json_with`.
+///
+/// Kafka's `JsonConverter` either writes the size of the records or, when asked to serialize
+/// them, an empty string in their place. `from_json` reads records as base64 strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecordsJson {
+    /// Writes `<field>SizeInBytes` holding the size of the records in place of `<field>`, as
+    /// `JsonConverter` does for the broker's request log. Used by `to_json`.
+    #[default]
+    SizeInBytes,
+    /// Writes an empty string in place of the records, as `JsonConverter` does when serializing
+    /// records.
+    Empty,
+    /// Writes the records as a base64 string, so that `from_json` can read them back. This
+    /// deviates from `JsonConverter`, which never writes the contents of records.
+    Base64,
+}
+
+/// An error converting a message from its JSON representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError(String);
+
+impl JsonError {
+    pub(crate) fn new(message: String) -> Self {
+        JsonError(message)
+    }
+
+    /// A description of the fault, naming the struct and field at fault.
+    pub fn message(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for JsonError {}
@@ -0,0 +1,214 @@
+//! The JSON representation of the values making up messages, and the helpers generated messages
+//! convert their fields with.
+//!
+//! They are public only so that messages generated from custom schemas outside this crate can use
+//! them, through [`crate::__private`], and are not meant to be used directly.
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use bytes::Bytes;
+use serde_json::{Map, Value};
+use string::TryFrom;
+use uuid::Uuid;
+
+use super::{JsonError, RecordsJson};
+use crate::protocol::StrBytes;
+
+/// A value with a JSON representation independent of the message version.
+pub trait JsonValue: Sized {
+    /// The JSON representation of the value.
+    fn to_json(&self) -> Value;
+    /// Reads the value from its JSON representation, naming the field `about` in errors.
+    fn from_json(node: &Value, about: &str) -> Result<Self, JsonError>;
+}
+
+fn type_name(node: &Value) -> &'static str {
+    match node {
+        Value::Null => "NULL",
+        Value::Bool(_) => "BOOLEAN",
+        Value::Number(_) => "NUMBER",
+        Value::String(_) => "STRING",
+        Value::Array(_) => "ARRAY",
+        Value::Object(_) => "OBJECT",
+    }
+}
+
+fn unexpected(about: &str, expected: &str, node: &Value) -> JsonError {
+    JsonError::new(format!(
+        "{}: expected {}, but got {}",
+        about,
+        expected,
+        type_name(node)
+    ))
+}
+
+fn integer(node: &Value, about: &str) -> Result<i64, JsonError> {
+    match node {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| unexpected(about, "an integer", node))
+}
+
+macro_rules! define_integer_json {
+    ($($t:ty => $bits:literal),*) => {
+        $(
+            impl JsonValue for $t {
+                fn to_json(&self) -> Value {
+                    Value::from(*self)
+                }
+                fn from_json(node: &Value, about: &str) -> Result<Self, JsonError> {
+                    let value = integer(node, about)?;
+                    <$t as std::convert::TryFrom<i64>>::try_from(value).map_err(|_| {
+                        JsonError::new(format!(
+                            "{}: value {} does not fit in a {}-bit integer.",
+                            about, value, $bits
+                        ))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+define_integer_json!(i8 => 8, i16 => 16, u16 => 16, i32 => 32, u32 => 32, i64 => 64);
+
+impl JsonValue for bool {
+    fn to_json(&self) -> Value {
+        Value::Bool(*self)
+    }
+    fn from_json(node: &Value, about: &str) -> Result<Self, JsonError> {
+        node.as_bool()
+            .ok_or_else(|| unexpected(about, "Boolean type", node))
+    }
+}
+
+impl JsonValue for f64 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+    fn from_json(node: &Value, about: &str) -> Result<Self, JsonError> {
+        match node {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| unexpected(about, "a floating point number", node))
+    }
+}
+
+impl JsonValue for StrBytes {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+    fn from_json(node: &Value, about: &str) -> Result<Self, JsonError> {
+        let s = node
+            .as_str()
+            .ok_or_else(|| unexpected(about, "a string type", node))?;
+        StrBytes::try_from(Bytes::copy_from_slice(s.as_bytes()))
+            .map_err(|e| JsonError::new(format!("{}: {}", about, e)))
+    }
+}
+
+impl JsonValue for Bytes {
+    fn to_json(&self) -> Value {
+        Value::String(STANDARD.encode(self))
+    }
+    fn from_json(node: &Value, about: &str) -> Result<Self, JsonError> {
+        let s = node
+            .as_str()
+            .ok_or_else(|| unexpected(about, "Base64-encoded bytes", node))?;
+        STANDARD
+            .decode(s)
+            .map(Bytes::from)
+            .map_err(|e| JsonError::new(format!("{}: {}", about, e)))
+    }
+}
+
+impl JsonValue for Uuid {
+    fn to_json(&self) -> Value {
+        Value::String(URL_SAFE_NO_PAD.encode(self.as_bytes()))
+    }
+    fn from_json(node: &Value, about: &str) -> Result<Self, JsonError> {
+        let s = node
+            .as_str()
+            .ok_or_else(|| unexpected(about, "a JSON string type", node))?;
+        URL_SAFE_NO_PAD
+            .decode(s)
+            .ok()
+            .and_then(|bytes| Uuid::from_slice(&bytes).ok())
+            .ok_or_else(|| JsonError::new(format!("{}: invalid UUID {:?}", about, s)))
+    }
+}
+
+impl<T: JsonValue> JsonValue for Option<T> {
+    fn to_json(&self) -> Value {
+        match self {
+            Some(value) => value.to_json(),
+            None => Value::Null,
+        }
+    }
+    fn from_json(node: &Value, about: &str) -> Result<Self, JsonError> {
+        match node {
+            Value::Null => Ok(None),
+            node => T::from_json(node, about).map(Some),
+        }
+    }
+}
+
+impl<T: JsonValue> JsonValue for Vec<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(JsonValue::to_json).collect())
+    }
+    fn from_json(node: &Value, about: &str) -> Result<Self, JsonError> {
+        array(node, about)?
+            .iter()
+            .map(|item| T::from_json(item, about))
+            .collect()
+    }
+}
+
+/// Reads `node` as a JSON object representing the struct `about`.
+pub fn object<'a>(node: &'a Value, about: &str) -> Result<&'a Map<String, Value>, JsonError> {
+    node.as_object()
+        .ok_or_else(|| unexpected(about, "a JSON object", node))
+}
+
+/// Reads `node` as a JSON array of the field `about`.
+pub fn array<'a>(node: &'a Value, about: &str) -> Result<&'a Vec<Value>, JsonError> {
+    node.as_array()
+        .ok_or_else(|| unexpected(about, "a JSON array", node))
+}
+
+/// Inserts the records field `name` into `node`, written as `how` says. Null records are written
+/// as null whatever `how` says.
+pub fn insert_records(
+    node: &mut Map<String, Value>,
+    name: &str,
+    records: Option<&Bytes>,
+    how: RecordsJson,
+) {
+    match (records, how) {
+        (None, _) => {
+            node.insert(name.into(), Value::Null);
+        }
+        (Some(records), RecordsJson::SizeInBytes) => {
+            node.insert(format!("{}SizeInBytes", name), records.len().into());
+        }
+        (Some(_), RecordsJson::Empty) => {
+            node.insert(name.into(), Value::String(String::new()));
+        }
+        (Some(records), RecordsJson::Base64) => {
+            node.insert(name.into(), records.to_json());
+        }
+    }
+}
+
+/// The error for a field missing from the JSON representation of a version requiring it.
+pub fn missing(struct_name: &str, field: &str, version: i16) -> JsonError {
+    JsonError::new(format!(
+        "{}: unable to locate field '{}', which is mandatory in version {}",
+        struct_name, field, version
+    ))
+}
@@ -27,9 +27,35 @@
 //! Note that every message implementation of [`Encodable::encode`](crate::protocol::Encodable::encode)
 //! and [`Decodable::decode`](crate::protocol::Decodable::decode) requires a version to be provided
 //! explicitly. This is because every  message contains *all* the fields that are valid for every
-//! version. These fields are *not* marked [`Option`], which would represent nullability according
-//! to a message's schema, but rather have a default value that represents a nil value. It is the
-//! user's responsibility to ensure that only valid fields of the decoded message version are used.
+//! version. Fields missing from some versions are *not* marked [`Option`], but rather have a
+//! default value standing in for them in those versions. It is the user's responsibility to ensure
+//! that only valid fields of the decoded message version are used.
+//!
+//! [`Option`] is reserved for the fields a message's schema marks as nullable, which are optional
+//! in every version and document the versions in which they may be `None`. Encoding `None` in any
+//! other version fails with
+//! [`EncodeErrorKind::NullNotValidAtVersion`](crate::protocol::EncodeErrorKind::NullNotValidAtVersion)
+//! rather than writing a null the receiver does not expect.
+//!
+//! Every message provides a `validate` method listing the fields set to non-default values which
+//! are not valid in a given version, and a `normalize_to` method resetting those fields, so that
+//! downgrading a message to an older version is explicit:
+//!
+//! ```rust
+//! # #[cfg(feature = "client-core")] {
+//! use kafka_protocol::messages::FetchResponse;
+//!
+//! let mut response = FetchResponse::default();
+//! response.session_id = 5;
+//! assert_eq!(response.validate(6), vec!["session_id".to_string()]);
+//! response.normalize_to(6);
+//! assert!(response.validate(6).is_empty());
+//! # }
+//! ```
+//!
+//! The keys of maps, such as the topic names keying `CreateTopicsRequest::topics`, are neither
+//! reported nor reset, as they identify their entries rather than being optional. Kafka's schemas
+//! make every key valid in all the versions of its map.
 //!
 //! ## Sending a Request
 //!
@@ -107,11 +133,51 @@
 #[macro_use]
 extern crate log;
 
+pub mod api_versions;
 pub mod compression;
+#[allow(clippy::all)]
+pub mod consumer_offsets;
 pub mod error;
+pub mod frame;
+#[cfg(feature = "json")]
+pub mod json;
 #[allow(clippy::all)]
 pub mod messages;
+#[allow(clippy::all)]
+pub mod metadata;
 pub mod protocol;
 pub mod records;
+#[allow(clippy::all)]
+pub mod transaction_state;
 
 pub use error::ResponseError;
+
+/// The dependencies and internals messages generated by `protocol_codegen` from custom schemas
+/// refer to, so that the crates including them only need to depend on this one.
+///
+/// Not part of the public API: the items may change in any release.
+#[doc(hidden)]
+pub mod __private {
+    pub use bytes;
+    pub use derive_builder;
+    pub use indexmap;
+    #[cfg(feature = "serde")]
+    pub use serde;
+    #[cfg(feature = "json")]
+    pub use serde_json;
+    pub use uuid;
+
+    pub use crate::protocol::codec::{
+        compute_unknown_tagged_fields_size, write_unknown_tagged_fields, CachedStruct, Decoder,
+        Encoder, MapDecodable, MapEncodable, NewType, SizeCache,
+    };
+
+    #[cfg(feature = "json")]
+    pub use crate::json::value::{array, insert_records, missing, object, JsonValue};
+
+    /// Serde support for the `StrBytes` fields of generated messages.
+    #[cfg(feature = "serde")]
+    pub mod serde_str_bytes {
+        pub use crate::protocol::serde_str_bytes::{deserialize, serialize};
+    }
+}
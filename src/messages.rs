@@ -1,63 +1,103 @@
 //! Messages used by the Kafka protocol.
 //!
 //! These messages are generated programmatically. See the [Kafka's protocol documentation](https://kafka.apache.org/protocol.html) for more information about a given message type.
+//!
+//! The requests and responses of each API family are compiled behind a cargo feature: `client-core`, `admin`,
+//! `consumer-group`, `transactions`, `broker-internal` and `raft`, all of which are enabled by default.
+//! `ApiVersions` is always compiled.
 // WARNING: the items of this module are generated and should not be edited directly.
 
-use crate::protocol::{NewType, Request, StrBytes, HeaderVersion};
+use crate::protocol::{NewType, Request, StrBytes, HeaderVersion, Message, VersionRange, ListenerType};
 use std::convert::TryFrom;
 
+#[cfg(feature = "transactions")]
 pub mod add_offsets_to_txn_request;
+#[cfg(feature = "transactions")]
 pub use add_offsets_to_txn_request::AddOffsetsToTxnRequest;
 
+#[cfg(feature = "transactions")]
 pub mod add_offsets_to_txn_response;
+#[cfg(feature = "transactions")]
 pub use add_offsets_to_txn_response::AddOffsetsToTxnResponse;
 
+#[cfg(feature = "transactions")]
 pub mod add_partitions_to_txn_request;
+#[cfg(feature = "transactions")]
 pub use add_partitions_to_txn_request::AddPartitionsToTxnRequest;
 
+#[cfg(feature = "transactions")]
 pub mod add_partitions_to_txn_response;
+#[cfg(feature = "transactions")]
 pub use add_partitions_to_txn_response::AddPartitionsToTxnResponse;
 
+#[cfg(feature = "broker-internal")]
 pub mod allocate_producer_ids_request;
+#[cfg(feature = "broker-internal")]
 pub use allocate_producer_ids_request::AllocateProducerIdsRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod allocate_producer_ids_response;
+#[cfg(feature = "broker-internal")]
 pub use allocate_producer_ids_response::AllocateProducerIdsResponse;
 
+#[cfg(feature = "admin")]
 pub mod alter_client_quotas_request;
+#[cfg(feature = "admin")]
 pub use alter_client_quotas_request::AlterClientQuotasRequest;
 
+#[cfg(feature = "admin")]
 pub mod alter_client_quotas_response;
+#[cfg(feature = "admin")]
 pub use alter_client_quotas_response::AlterClientQuotasResponse;
 
+#[cfg(feature = "admin")]
 pub mod alter_configs_request;
+#[cfg(feature = "admin")]
 pub use alter_configs_request::AlterConfigsRequest;
 
+#[cfg(feature = "admin")]
 pub mod alter_configs_response;
+#[cfg(feature = "admin")]
 pub use alter_configs_response::AlterConfigsResponse;
 
+#[cfg(feature = "admin")]
 pub mod alter_partition_reassignments_request;
+#[cfg(feature = "admin")]
 pub use alter_partition_reassignments_request::AlterPartitionReassignmentsRequest;
 
+#[cfg(feature = "admin")]
 pub mod alter_partition_reassignments_response;
+#[cfg(feature = "admin")]
 pub use alter_partition_reassignments_response::AlterPartitionReassignmentsResponse;
 
+#[cfg(feature = "broker-internal")]
 pub mod alter_partition_request;
+#[cfg(feature = "broker-internal")]
 pub use alter_partition_request::AlterPartitionRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod alter_partition_response;
+#[cfg(feature = "broker-internal")]
 pub use alter_partition_response::AlterPartitionResponse;
 
+#[cfg(feature = "admin")]
 pub mod alter_replica_log_dirs_request;
+#[cfg(feature = "admin")]
 pub use alter_replica_log_dirs_request::AlterReplicaLogDirsRequest;
 
+#[cfg(feature = "admin")]
 pub mod alter_replica_log_dirs_response;
+#[cfg(feature = "admin")]
 pub use alter_replica_log_dirs_response::AlterReplicaLogDirsResponse;
 
+#[cfg(feature = "admin")]
 pub mod alter_user_scram_credentials_request;
+#[cfg(feature = "admin")]
 pub use alter_user_scram_credentials_request::AlterUserScramCredentialsRequest;
 
+#[cfg(feature = "admin")]
 pub mod alter_user_scram_credentials_response;
+#[cfg(feature = "admin")]
 pub use alter_user_scram_credentials_response::AlterUserScramCredentialsResponse;
 
 pub mod api_versions_request;
@@ -66,28 +106,44 @@ pub use api_versions_request::ApiVersionsRequest;
 pub mod api_versions_response;
 pub use api_versions_response::ApiVersionsResponse;
 
+#[cfg(feature = "raft")]
 pub mod begin_quorum_epoch_request;
+#[cfg(feature = "raft")]
 pub use begin_quorum_epoch_request::BeginQuorumEpochRequest;
 
+#[cfg(feature = "raft")]
 pub mod begin_quorum_epoch_response;
+#[cfg(feature = "raft")]
 pub use begin_quorum_epoch_response::BeginQuorumEpochResponse;
 
+#[cfg(feature = "broker-internal")]
 pub mod broker_heartbeat_request;
+#[cfg(feature = "broker-internal")]
 pub use broker_heartbeat_request::BrokerHeartbeatRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod broker_heartbeat_response;
+#[cfg(feature = "broker-internal")]
 pub use broker_heartbeat_response::BrokerHeartbeatResponse;
 
+#[cfg(feature = "broker-internal")]
 pub mod broker_registration_request;
+#[cfg(feature = "broker-internal")]
 pub use broker_registration_request::BrokerRegistrationRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod broker_registration_response;
+#[cfg(feature = "broker-internal")]
 pub use broker_registration_response::BrokerRegistrationResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod consumer_group_heartbeat_request;
+#[cfg(feature = "consumer-group")]
 pub use consumer_group_heartbeat_request::ConsumerGroupHeartbeatRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod consumer_group_heartbeat_response;
+#[cfg(feature = "consumer-group")]
 pub use consumer_group_heartbeat_response::ConsumerGroupHeartbeatResponse;
 
 pub mod consumer_protocol_assignment;
@@ -96,280 +152,460 @@ pub use consumer_protocol_assignment::ConsumerProtocolAssignment;
 pub mod consumer_protocol_subscription;
 pub use consumer_protocol_subscription::ConsumerProtocolSubscription;
 
+#[cfg(feature = "broker-internal")]
 pub mod controlled_shutdown_request;
+#[cfg(feature = "broker-internal")]
 pub use controlled_shutdown_request::ControlledShutdownRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod controlled_shutdown_response;
+#[cfg(feature = "broker-internal")]
 pub use controlled_shutdown_response::ControlledShutdownResponse;
 
+#[cfg(feature = "admin")]
 pub mod create_acls_request;
+#[cfg(feature = "admin")]
 pub use create_acls_request::CreateAclsRequest;
 
+#[cfg(feature = "admin")]
 pub mod create_acls_response;
+#[cfg(feature = "admin")]
 pub use create_acls_response::CreateAclsResponse;
 
+#[cfg(feature = "admin")]
 pub mod create_delegation_token_request;
+#[cfg(feature = "admin")]
 pub use create_delegation_token_request::CreateDelegationTokenRequest;
 
+#[cfg(feature = "admin")]
 pub mod create_delegation_token_response;
+#[cfg(feature = "admin")]
 pub use create_delegation_token_response::CreateDelegationTokenResponse;
 
+#[cfg(feature = "admin")]
 pub mod create_partitions_request;
+#[cfg(feature = "admin")]
 pub use create_partitions_request::CreatePartitionsRequest;
 
+#[cfg(feature = "admin")]
 pub mod create_partitions_response;
+#[cfg(feature = "admin")]
 pub use create_partitions_response::CreatePartitionsResponse;
 
+#[cfg(feature = "admin")]
 pub mod create_topics_request;
+#[cfg(feature = "admin")]
 pub use create_topics_request::CreateTopicsRequest;
 
+#[cfg(feature = "admin")]
 pub mod create_topics_response;
+#[cfg(feature = "admin")]
 pub use create_topics_response::CreateTopicsResponse;
 
 pub mod default_principal_data;
 pub use default_principal_data::DefaultPrincipalData;
 
+#[cfg(feature = "admin")]
 pub mod delete_acls_request;
+#[cfg(feature = "admin")]
 pub use delete_acls_request::DeleteAclsRequest;
 
+#[cfg(feature = "admin")]
 pub mod delete_acls_response;
+#[cfg(feature = "admin")]
 pub use delete_acls_response::DeleteAclsResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod delete_groups_request;
+#[cfg(feature = "consumer-group")]
 pub use delete_groups_request::DeleteGroupsRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod delete_groups_response;
+#[cfg(feature = "consumer-group")]
 pub use delete_groups_response::DeleteGroupsResponse;
 
+#[cfg(feature = "admin")]
 pub mod delete_records_request;
+#[cfg(feature = "admin")]
 pub use delete_records_request::DeleteRecordsRequest;
 
+#[cfg(feature = "admin")]
 pub mod delete_records_response;
+#[cfg(feature = "admin")]
 pub use delete_records_response::DeleteRecordsResponse;
 
+#[cfg(feature = "admin")]
 pub mod delete_topics_request;
+#[cfg(feature = "admin")]
 pub use delete_topics_request::DeleteTopicsRequest;
 
+#[cfg(feature = "admin")]
 pub mod delete_topics_response;
+#[cfg(feature = "admin")]
 pub use delete_topics_response::DeleteTopicsResponse;
 
+#[cfg(feature = "admin")]
 pub mod describe_acls_request;
+#[cfg(feature = "admin")]
 pub use describe_acls_request::DescribeAclsRequest;
 
+#[cfg(feature = "admin")]
 pub mod describe_acls_response;
+#[cfg(feature = "admin")]
 pub use describe_acls_response::DescribeAclsResponse;
 
+#[cfg(feature = "admin")]
 pub mod describe_client_quotas_request;
+#[cfg(feature = "admin")]
 pub use describe_client_quotas_request::DescribeClientQuotasRequest;
 
+#[cfg(feature = "admin")]
 pub mod describe_client_quotas_response;
+#[cfg(feature = "admin")]
 pub use describe_client_quotas_response::DescribeClientQuotasResponse;
 
+#[cfg(feature = "admin")]
 pub mod describe_cluster_request;
+#[cfg(feature = "admin")]
 pub use describe_cluster_request::DescribeClusterRequest;
 
+#[cfg(feature = "admin")]
 pub mod describe_cluster_response;
+#[cfg(feature = "admin")]
 pub use describe_cluster_response::DescribeClusterResponse;
 
+#[cfg(feature = "admin")]
 pub mod describe_configs_request;
+#[cfg(feature = "admin")]
 pub use describe_configs_request::DescribeConfigsRequest;
 
+#[cfg(feature = "admin")]
 pub mod describe_configs_response;
+#[cfg(feature = "admin")]
 pub use describe_configs_response::DescribeConfigsResponse;
 
+#[cfg(feature = "admin")]
 pub mod describe_delegation_token_request;
+#[cfg(feature = "admin")]
 pub use describe_delegation_token_request::DescribeDelegationTokenRequest;
 
+#[cfg(feature = "admin")]
 pub mod describe_delegation_token_response;
+#[cfg(feature = "admin")]
 pub use describe_delegation_token_response::DescribeDelegationTokenResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod describe_groups_request;
+#[cfg(feature = "consumer-group")]
 pub use describe_groups_request::DescribeGroupsRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod describe_groups_response;
+#[cfg(feature = "consumer-group")]
 pub use describe_groups_response::DescribeGroupsResponse;
 
+#[cfg(feature = "admin")]
 pub mod describe_log_dirs_request;
+#[cfg(feature = "admin")]
 pub use describe_log_dirs_request::DescribeLogDirsRequest;
 
+#[cfg(feature = "admin")]
 pub mod describe_log_dirs_response;
+#[cfg(feature = "admin")]
 pub use describe_log_dirs_response::DescribeLogDirsResponse;
 
+#[cfg(feature = "transactions")]
 pub mod describe_producers_request;
+#[cfg(feature = "transactions")]
 pub use describe_producers_request::DescribeProducersRequest;
 
+#[cfg(feature = "transactions")]
 pub mod describe_producers_response;
+#[cfg(feature = "transactions")]
 pub use describe_producers_response::DescribeProducersResponse;
 
+#[cfg(feature = "raft")]
 pub mod describe_quorum_request;
+#[cfg(feature = "raft")]
 pub use describe_quorum_request::DescribeQuorumRequest;
 
+#[cfg(feature = "raft")]
 pub mod describe_quorum_response;
+#[cfg(feature = "raft")]
 pub use describe_quorum_response::DescribeQuorumResponse;
 
+#[cfg(feature = "transactions")]
 pub mod describe_transactions_request;
+#[cfg(feature = "transactions")]
 pub use describe_transactions_request::DescribeTransactionsRequest;
 
+#[cfg(feature = "transactions")]
 pub mod describe_transactions_response;
+#[cfg(feature = "transactions")]
 pub use describe_transactions_response::DescribeTransactionsResponse;
 
+#[cfg(feature = "admin")]
 pub mod describe_user_scram_credentials_request;
+#[cfg(feature = "admin")]
 pub use describe_user_scram_credentials_request::DescribeUserScramCredentialsRequest;
 
+#[cfg(feature = "admin")]
 pub mod describe_user_scram_credentials_response;
+#[cfg(feature = "admin")]
 pub use describe_user_scram_credentials_response::DescribeUserScramCredentialsResponse;
 
+#[cfg(feature = "admin")]
 pub mod elect_leaders_request;
+#[cfg(feature = "admin")]
 pub use elect_leaders_request::ElectLeadersRequest;
 
+#[cfg(feature = "admin")]
 pub mod elect_leaders_response;
+#[cfg(feature = "admin")]
 pub use elect_leaders_response::ElectLeadersResponse;
 
+#[cfg(feature = "raft")]
 pub mod end_quorum_epoch_request;
+#[cfg(feature = "raft")]
 pub use end_quorum_epoch_request::EndQuorumEpochRequest;
 
+#[cfg(feature = "raft")]
 pub mod end_quorum_epoch_response;
+#[cfg(feature = "raft")]
 pub use end_quorum_epoch_response::EndQuorumEpochResponse;
 
+#[cfg(feature = "transactions")]
 pub mod end_txn_request;
+#[cfg(feature = "transactions")]
 pub use end_txn_request::EndTxnRequest;
 
+#[cfg(feature = "transactions")]
 pub mod end_txn_response;
+#[cfg(feature = "transactions")]
 pub use end_txn_response::EndTxnResponse;
 
+#[cfg(feature = "broker-internal")]
 pub mod envelope_request;
+#[cfg(feature = "broker-internal")]
 pub use envelope_request::EnvelopeRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod envelope_response;
+#[cfg(feature = "broker-internal")]
 pub use envelope_response::EnvelopeResponse;
 
+#[cfg(feature = "admin")]
 pub mod expire_delegation_token_request;
+#[cfg(feature = "admin")]
 pub use expire_delegation_token_request::ExpireDelegationTokenRequest;
 
+#[cfg(feature = "admin")]
 pub mod expire_delegation_token_response;
+#[cfg(feature = "admin")]
 pub use expire_delegation_token_response::ExpireDelegationTokenResponse;
 
+#[cfg(feature = "client-core")]
 pub mod fetch_request;
+#[cfg(feature = "client-core")]
 pub use fetch_request::FetchRequest;
 
+#[cfg(feature = "client-core")]
 pub mod fetch_response;
+#[cfg(feature = "client-core")]
 pub use fetch_response::FetchResponse;
 
+#[cfg(feature = "raft")]
 pub mod fetch_snapshot_request;
+#[cfg(feature = "raft")]
 pub use fetch_snapshot_request::FetchSnapshotRequest;
 
+#[cfg(feature = "raft")]
 pub mod fetch_snapshot_response;
+#[cfg(feature = "raft")]
 pub use fetch_snapshot_response::FetchSnapshotResponse;
 
+#[cfg(feature = "client-core")]
 pub mod find_coordinator_request;
+#[cfg(feature = "client-core")]
 pub use find_coordinator_request::FindCoordinatorRequest;
 
+#[cfg(feature = "client-core")]
 pub mod find_coordinator_response;
+#[cfg(feature = "client-core")]
 pub use find_coordinator_response::FindCoordinatorResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod heartbeat_request;
+#[cfg(feature = "consumer-group")]
 pub use heartbeat_request::HeartbeatRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod heartbeat_response;
+#[cfg(feature = "consumer-group")]
 pub use heartbeat_response::HeartbeatResponse;
 
+#[cfg(feature = "admin")]
 pub mod incremental_alter_configs_request;
+#[cfg(feature = "admin")]
 pub use incremental_alter_configs_request::IncrementalAlterConfigsRequest;
 
+#[cfg(feature = "admin")]
 pub mod incremental_alter_configs_response;
+#[cfg(feature = "admin")]
 pub use incremental_alter_configs_response::IncrementalAlterConfigsResponse;
 
+#[cfg(feature = "transactions")]
 pub mod init_producer_id_request;
+#[cfg(feature = "transactions")]
 pub use init_producer_id_request::InitProducerIdRequest;
 
+#[cfg(feature = "transactions")]
 pub mod init_producer_id_response;
+#[cfg(feature = "transactions")]
 pub use init_producer_id_response::InitProducerIdResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod join_group_request;
+#[cfg(feature = "consumer-group")]
 pub use join_group_request::JoinGroupRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod join_group_response;
+#[cfg(feature = "consumer-group")]
 pub use join_group_response::JoinGroupResponse;
 
+#[cfg(feature = "broker-internal")]
 pub mod leader_and_isr_request;
+#[cfg(feature = "broker-internal")]
 pub use leader_and_isr_request::LeaderAndIsrRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod leader_and_isr_response;
+#[cfg(feature = "broker-internal")]
 pub use leader_and_isr_response::LeaderAndIsrResponse;
 
 pub mod leader_change_message;
 pub use leader_change_message::LeaderChangeMessage;
 
+#[cfg(feature = "consumer-group")]
 pub mod leave_group_request;
+#[cfg(feature = "consumer-group")]
 pub use leave_group_request::LeaveGroupRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod leave_group_response;
+#[cfg(feature = "consumer-group")]
 pub use leave_group_response::LeaveGroupResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod list_groups_request;
+#[cfg(feature = "consumer-group")]
 pub use list_groups_request::ListGroupsRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod list_groups_response;
+#[cfg(feature = "consumer-group")]
 pub use list_groups_response::ListGroupsResponse;
 
+#[cfg(feature = "client-core")]
 pub mod list_offsets_request;
+#[cfg(feature = "client-core")]
 pub use list_offsets_request::ListOffsetsRequest;
 
+#[cfg(feature = "client-core")]
 pub mod list_offsets_response;
+#[cfg(feature = "client-core")]
 pub use list_offsets_response::ListOffsetsResponse;
 
+#[cfg(feature = "admin")]
 pub mod list_partition_reassignments_request;
+#[cfg(feature = "admin")]
 pub use list_partition_reassignments_request::ListPartitionReassignmentsRequest;
 
+#[cfg(feature = "admin")]
 pub mod list_partition_reassignments_response;
+#[cfg(feature = "admin")]
 pub use list_partition_reassignments_response::ListPartitionReassignmentsResponse;
 
+#[cfg(feature = "transactions")]
 pub mod list_transactions_request;
+#[cfg(feature = "transactions")]
 pub use list_transactions_request::ListTransactionsRequest;
 
+#[cfg(feature = "transactions")]
 pub mod list_transactions_response;
+#[cfg(feature = "transactions")]
 pub use list_transactions_response::ListTransactionsResponse;
 
+#[cfg(feature = "client-core")]
 pub mod metadata_request;
+#[cfg(feature = "client-core")]
 pub use metadata_request::MetadataRequest;
 
+#[cfg(feature = "client-core")]
 pub mod metadata_response;
+#[cfg(feature = "client-core")]
 pub use metadata_response::MetadataResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod offset_commit_request;
+#[cfg(feature = "consumer-group")]
 pub use offset_commit_request::OffsetCommitRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod offset_commit_response;
+#[cfg(feature = "consumer-group")]
 pub use offset_commit_response::OffsetCommitResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod offset_delete_request;
+#[cfg(feature = "consumer-group")]
 pub use offset_delete_request::OffsetDeleteRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod offset_delete_response;
+#[cfg(feature = "consumer-group")]
 pub use offset_delete_response::OffsetDeleteResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod offset_fetch_request;
+#[cfg(feature = "consumer-group")]
 pub use offset_fetch_request::OffsetFetchRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod offset_fetch_response;
+#[cfg(feature = "consumer-group")]
 pub use offset_fetch_response::OffsetFetchResponse;
 
+#[cfg(feature = "client-core")]
 pub mod offset_for_leader_epoch_request;
+#[cfg(feature = "client-core")]
 pub use offset_for_leader_epoch_request::OffsetForLeaderEpochRequest;
 
+#[cfg(feature = "client-core")]
 pub mod offset_for_leader_epoch_response;
+#[cfg(feature = "client-core")]
 pub use offset_for_leader_epoch_response::OffsetForLeaderEpochResponse;
 
+#[cfg(feature = "client-core")]
 pub mod produce_request;
+#[cfg(feature = "client-core")]
 pub use produce_request::ProduceRequest;
 
+#[cfg(feature = "client-core")]
 pub mod produce_response;
+#[cfg(feature = "client-core")]
 pub use produce_response::ProduceResponse;
 
+#[cfg(feature = "admin")]
 pub mod renew_delegation_token_request;
+#[cfg(feature = "admin")]
 pub use renew_delegation_token_request::RenewDelegationTokenRequest;
 
+#[cfg(feature = "admin")]
 pub mod renew_delegation_token_response;
+#[cfg(feature = "admin")]
 pub use renew_delegation_token_response::RenewDelegationTokenResponse;
 
 pub mod request_header;
@@ -378,16 +614,24 @@ pub use request_header::RequestHeader;
 pub mod response_header;
 pub use response_header::ResponseHeader;
 
+#[cfg(feature = "client-core")]
 pub mod sasl_authenticate_request;
+#[cfg(feature = "client-core")]
 pub use sasl_authenticate_request::SaslAuthenticateRequest;
 
+#[cfg(feature = "client-core")]
 pub mod sasl_authenticate_response;
+#[cfg(feature = "client-core")]
 pub use sasl_authenticate_response::SaslAuthenticateResponse;
 
+#[cfg(feature = "client-core")]
 pub mod sasl_handshake_request;
+#[cfg(feature = "client-core")]
 pub use sasl_handshake_request::SaslHandshakeRequest;
 
+#[cfg(feature = "client-core")]
 pub mod sasl_handshake_response;
+#[cfg(feature = "client-core")]
 pub use sasl_handshake_response::SaslHandshakeResponse;
 
 pub mod snapshot_footer_record;
@@ -396,139 +640,189 @@ pub use snapshot_footer_record::SnapshotFooterRecord;
 pub mod snapshot_header_record;
 pub use snapshot_header_record::SnapshotHeaderRecord;
 
+#[cfg(feature = "broker-internal")]
 pub mod stop_replica_request;
+#[cfg(feature = "broker-internal")]
 pub use stop_replica_request::StopReplicaRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod stop_replica_response;
+#[cfg(feature = "broker-internal")]
 pub use stop_replica_response::StopReplicaResponse;
 
+#[cfg(feature = "consumer-group")]
 pub mod sync_group_request;
+#[cfg(feature = "consumer-group")]
 pub use sync_group_request::SyncGroupRequest;
 
+#[cfg(feature = "consumer-group")]
 pub mod sync_group_response;
+#[cfg(feature = "consumer-group")]
 pub use sync_group_response::SyncGroupResponse;
 
+#[cfg(feature = "transactions")]
 pub mod txn_offset_commit_request;
+#[cfg(feature = "transactions")]
 pub use txn_offset_commit_request::TxnOffsetCommitRequest;
 
+#[cfg(feature = "transactions")]
 pub mod txn_offset_commit_response;
+#[cfg(feature = "transactions")]
 pub use txn_offset_commit_response::TxnOffsetCommitResponse;
 
+#[cfg(feature = "admin")]
 pub mod unregister_broker_request;
+#[cfg(feature = "admin")]
 pub use unregister_broker_request::UnregisterBrokerRequest;
 
+#[cfg(feature = "admin")]
 pub mod unregister_broker_response;
+#[cfg(feature = "admin")]
 pub use unregister_broker_response::UnregisterBrokerResponse;
 
+#[cfg(feature = "admin")]
 pub mod update_features_request;
+#[cfg(feature = "admin")]
 pub use update_features_request::UpdateFeaturesRequest;
 
+#[cfg(feature = "admin")]
 pub mod update_features_response;
+#[cfg(feature = "admin")]
 pub use update_features_response::UpdateFeaturesResponse;
 
+#[cfg(feature = "broker-internal")]
 pub mod update_metadata_request;
+#[cfg(feature = "broker-internal")]
 pub use update_metadata_request::UpdateMetadataRequest;
 
+#[cfg(feature = "broker-internal")]
 pub mod update_metadata_response;
+#[cfg(feature = "broker-internal")]
 pub use update_metadata_response::UpdateMetadataResponse;
 
+#[cfg(feature = "raft")]
 pub mod vote_request;
+#[cfg(feature = "raft")]
 pub use vote_request::VoteRequest;
 
+#[cfg(feature = "raft")]
 pub mod vote_response;
+#[cfg(feature = "raft")]
 pub use vote_response::VoteResponse;
 
+#[cfg(feature = "transactions")]
 pub mod write_txn_markers_request;
+#[cfg(feature = "transactions")]
 pub use write_txn_markers_request::WriteTxnMarkersRequest;
 
+#[cfg(feature = "transactions")]
 pub mod write_txn_markers_response;
+#[cfg(feature = "transactions")]
 pub use write_txn_markers_response::WriteTxnMarkersResponse;
 
+#[cfg(feature = "client-core")]
 impl Request for ProduceRequest {
     const KEY: i16 = 0;
     type Response = ProduceResponse;
 }
 
+#[cfg(feature = "client-core")]
 impl Request for FetchRequest {
     const KEY: i16 = 1;
     type Response = FetchResponse;
 }
 
+#[cfg(feature = "client-core")]
 impl Request for ListOffsetsRequest {
     const KEY: i16 = 2;
     type Response = ListOffsetsResponse;
 }
 
+#[cfg(feature = "client-core")]
 impl Request for MetadataRequest {
     const KEY: i16 = 3;
     type Response = MetadataResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for LeaderAndIsrRequest {
     const KEY: i16 = 4;
     type Response = LeaderAndIsrResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for StopReplicaRequest {
     const KEY: i16 = 5;
     type Response = StopReplicaResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for UpdateMetadataRequest {
     const KEY: i16 = 6;
     type Response = UpdateMetadataResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for ControlledShutdownRequest {
     const KEY: i16 = 7;
     type Response = ControlledShutdownResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for OffsetCommitRequest {
     const KEY: i16 = 8;
     type Response = OffsetCommitResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for OffsetFetchRequest {
     const KEY: i16 = 9;
     type Response = OffsetFetchResponse;
 }
 
+#[cfg(feature = "client-core")]
 impl Request for FindCoordinatorRequest {
     const KEY: i16 = 10;
     type Response = FindCoordinatorResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for JoinGroupRequest {
     const KEY: i16 = 11;
     type Response = JoinGroupResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for HeartbeatRequest {
     const KEY: i16 = 12;
     type Response = HeartbeatResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for LeaveGroupRequest {
     const KEY: i16 = 13;
     type Response = LeaveGroupResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for SyncGroupRequest {
     const KEY: i16 = 14;
     type Response = SyncGroupResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for DescribeGroupsRequest {
     const KEY: i16 = 15;
     type Response = DescribeGroupsResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for ListGroupsRequest {
     const KEY: i16 = 16;
     type Response = ListGroupsResponse;
 }
 
+#[cfg(feature = "client-core")]
 impl Request for SaslHandshakeRequest {
     const KEY: i16 = 17;
     type Response = SaslHandshakeResponse;
@@ -539,396 +833,515 @@ impl Request for ApiVersionsRequest {
     type Response = ApiVersionsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for CreateTopicsRequest {
     const KEY: i16 = 19;
     type Response = CreateTopicsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DeleteTopicsRequest {
     const KEY: i16 = 20;
     type Response = DeleteTopicsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DeleteRecordsRequest {
     const KEY: i16 = 21;
     type Response = DeleteRecordsResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for InitProducerIdRequest {
     const KEY: i16 = 22;
     type Response = InitProducerIdResponse;
 }
 
+#[cfg(feature = "client-core")]
 impl Request for OffsetForLeaderEpochRequest {
     const KEY: i16 = 23;
     type Response = OffsetForLeaderEpochResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for AddPartitionsToTxnRequest {
     const KEY: i16 = 24;
     type Response = AddPartitionsToTxnResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for AddOffsetsToTxnRequest {
     const KEY: i16 = 25;
     type Response = AddOffsetsToTxnResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for EndTxnRequest {
     const KEY: i16 = 26;
     type Response = EndTxnResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for WriteTxnMarkersRequest {
     const KEY: i16 = 27;
     type Response = WriteTxnMarkersResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for TxnOffsetCommitRequest {
     const KEY: i16 = 28;
     type Response = TxnOffsetCommitResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DescribeAclsRequest {
     const KEY: i16 = 29;
     type Response = DescribeAclsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for CreateAclsRequest {
     const KEY: i16 = 30;
     type Response = CreateAclsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DeleteAclsRequest {
     const KEY: i16 = 31;
     type Response = DeleteAclsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DescribeConfigsRequest {
     const KEY: i16 = 32;
     type Response = DescribeConfigsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for AlterConfigsRequest {
     const KEY: i16 = 33;
     type Response = AlterConfigsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for AlterReplicaLogDirsRequest {
     const KEY: i16 = 34;
     type Response = AlterReplicaLogDirsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DescribeLogDirsRequest {
     const KEY: i16 = 35;
     type Response = DescribeLogDirsResponse;
 }
 
+#[cfg(feature = "client-core")]
 impl Request for SaslAuthenticateRequest {
     const KEY: i16 = 36;
     type Response = SaslAuthenticateResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for CreatePartitionsRequest {
     const KEY: i16 = 37;
     type Response = CreatePartitionsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for CreateDelegationTokenRequest {
     const KEY: i16 = 38;
     type Response = CreateDelegationTokenResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for RenewDelegationTokenRequest {
     const KEY: i16 = 39;
     type Response = RenewDelegationTokenResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for ExpireDelegationTokenRequest {
     const KEY: i16 = 40;
     type Response = ExpireDelegationTokenResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DescribeDelegationTokenRequest {
     const KEY: i16 = 41;
     type Response = DescribeDelegationTokenResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for DeleteGroupsRequest {
     const KEY: i16 = 42;
     type Response = DeleteGroupsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for ElectLeadersRequest {
     const KEY: i16 = 43;
     type Response = ElectLeadersResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for IncrementalAlterConfigsRequest {
     const KEY: i16 = 44;
     type Response = IncrementalAlterConfigsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for AlterPartitionReassignmentsRequest {
     const KEY: i16 = 45;
     type Response = AlterPartitionReassignmentsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for ListPartitionReassignmentsRequest {
     const KEY: i16 = 46;
     type Response = ListPartitionReassignmentsResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for OffsetDeleteRequest {
     const KEY: i16 = 47;
     type Response = OffsetDeleteResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DescribeClientQuotasRequest {
     const KEY: i16 = 48;
     type Response = DescribeClientQuotasResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for AlterClientQuotasRequest {
     const KEY: i16 = 49;
     type Response = AlterClientQuotasResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DescribeUserScramCredentialsRequest {
     const KEY: i16 = 50;
     type Response = DescribeUserScramCredentialsResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for AlterUserScramCredentialsRequest {
     const KEY: i16 = 51;
     type Response = AlterUserScramCredentialsResponse;
 }
 
+#[cfg(feature = "raft")]
 impl Request for VoteRequest {
     const KEY: i16 = 52;
     type Response = VoteResponse;
 }
 
+#[cfg(feature = "raft")]
 impl Request for BeginQuorumEpochRequest {
     const KEY: i16 = 53;
     type Response = BeginQuorumEpochResponse;
 }
 
+#[cfg(feature = "raft")]
 impl Request for EndQuorumEpochRequest {
     const KEY: i16 = 54;
     type Response = EndQuorumEpochResponse;
 }
 
+#[cfg(feature = "raft")]
 impl Request for DescribeQuorumRequest {
     const KEY: i16 = 55;
     type Response = DescribeQuorumResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for AlterPartitionRequest {
     const KEY: i16 = 56;
     type Response = AlterPartitionResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for UpdateFeaturesRequest {
     const KEY: i16 = 57;
     type Response = UpdateFeaturesResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for EnvelopeRequest {
     const KEY: i16 = 58;
     type Response = EnvelopeResponse;
 }
 
+#[cfg(feature = "raft")]
 impl Request for FetchSnapshotRequest {
     const KEY: i16 = 59;
     type Response = FetchSnapshotResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for DescribeClusterRequest {
     const KEY: i16 = 60;
     type Response = DescribeClusterResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for DescribeProducersRequest {
     const KEY: i16 = 61;
     type Response = DescribeProducersResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for BrokerRegistrationRequest {
     const KEY: i16 = 62;
     type Response = BrokerRegistrationResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for BrokerHeartbeatRequest {
     const KEY: i16 = 63;
     type Response = BrokerHeartbeatResponse;
 }
 
+#[cfg(feature = "admin")]
 impl Request for UnregisterBrokerRequest {
     const KEY: i16 = 64;
     type Response = UnregisterBrokerResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for DescribeTransactionsRequest {
     const KEY: i16 = 65;
     type Response = DescribeTransactionsResponse;
 }
 
+#[cfg(feature = "transactions")]
 impl Request for ListTransactionsRequest {
     const KEY: i16 = 66;
     type Response = ListTransactionsResponse;
 }
 
+#[cfg(feature = "broker-internal")]
 impl Request for AllocateProducerIdsRequest {
     const KEY: i16 = 67;
     type Response = AllocateProducerIdsResponse;
 }
 
+#[cfg(feature = "consumer-group")]
 impl Request for ConsumerGroupHeartbeatRequest {
     const KEY: i16 = 68;
     type Response = ConsumerGroupHeartbeatResponse;
 }
 
 /// Valid API keys in the Kafka protocol.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ApiKey {
     /// API key for request ProduceRequest
+    #[cfg(feature = "client-core")]
     ProduceKey = 0,
     /// API key for request FetchRequest
+    #[cfg(feature = "client-core")]
     FetchKey = 1,
     /// API key for request ListOffsetsRequest
+    #[cfg(feature = "client-core")]
     ListOffsetsKey = 2,
     /// API key for request MetadataRequest
+    #[cfg(feature = "client-core")]
     MetadataKey = 3,
     /// API key for request LeaderAndIsrRequest
+    #[cfg(feature = "broker-internal")]
     LeaderAndIsrKey = 4,
     /// API key for request StopReplicaRequest
+    #[cfg(feature = "broker-internal")]
     StopReplicaKey = 5,
     /// API key for request UpdateMetadataRequest
+    #[cfg(feature = "broker-internal")]
     UpdateMetadataKey = 6,
     /// API key for request ControlledShutdownRequest
+    #[cfg(feature = "broker-internal")]
     ControlledShutdownKey = 7,
     /// API key for request OffsetCommitRequest
+    #[cfg(feature = "consumer-group")]
     OffsetCommitKey = 8,
     /// API key for request OffsetFetchRequest
+    #[cfg(feature = "consumer-group")]
     OffsetFetchKey = 9,
     /// API key for request FindCoordinatorRequest
+    #[cfg(feature = "client-core")]
     FindCoordinatorKey = 10,
     /// API key for request JoinGroupRequest
+    #[cfg(feature = "consumer-group")]
     JoinGroupKey = 11,
     /// API key for request HeartbeatRequest
+    #[cfg(feature = "consumer-group")]
     HeartbeatKey = 12,
     /// API key for request LeaveGroupRequest
+    #[cfg(feature = "consumer-group")]
     LeaveGroupKey = 13,
     /// API key for request SyncGroupRequest
+    #[cfg(feature = "consumer-group")]
     SyncGroupKey = 14,
     /// API key for request DescribeGroupsRequest
+    #[cfg(feature = "consumer-group")]
     DescribeGroupsKey = 15,
     /// API key for request ListGroupsRequest
+    #[cfg(feature = "consumer-group")]
     ListGroupsKey = 16,
     /// API key for request SaslHandshakeRequest
+    #[cfg(feature = "client-core")]
     SaslHandshakeKey = 17,
     /// API key for request ApiVersionsRequest
     ApiVersionsKey = 18,
     /// API key for request CreateTopicsRequest
+    #[cfg(feature = "admin")]
     CreateTopicsKey = 19,
     /// API key for request DeleteTopicsRequest
+    #[cfg(feature = "admin")]
     DeleteTopicsKey = 20,
     /// API key for request DeleteRecordsRequest
+    #[cfg(feature = "admin")]
     DeleteRecordsKey = 21,
     /// API key for request InitProducerIdRequest
+    #[cfg(feature = "transactions")]
     InitProducerIdKey = 22,
     /// API key for request OffsetForLeaderEpochRequest
+    #[cfg(feature = "client-core")]
     OffsetForLeaderEpochKey = 23,
     /// API key for request AddPartitionsToTxnRequest
+    #[cfg(feature = "transactions")]
     AddPartitionsToTxnKey = 24,
     /// API key for request AddOffsetsToTxnRequest
+    #[cfg(feature = "transactions")]
     AddOffsetsToTxnKey = 25,
     /// API key for request EndTxnRequest
+    #[cfg(feature = "transactions")]
     EndTxnKey = 26,
     /// API key for request WriteTxnMarkersRequest
+    #[cfg(feature = "transactions")]
     WriteTxnMarkersKey = 27,
     /// API key for request TxnOffsetCommitRequest
+    #[cfg(feature = "transactions")]
     TxnOffsetCommitKey = 28,
     /// API key for request DescribeAclsRequest
+    #[cfg(feature = "admin")]
     DescribeAclsKey = 29,
     /// API key for request CreateAclsRequest
+    #[cfg(feature = "admin")]
     CreateAclsKey = 30,
     /// API key for request DeleteAclsRequest
+    #[cfg(feature = "admin")]
     DeleteAclsKey = 31,
     /// API key for request DescribeConfigsRequest
+    #[cfg(feature = "admin")]
     DescribeConfigsKey = 32,
     /// API key for request AlterConfigsRequest
+    #[cfg(feature = "admin")]
     AlterConfigsKey = 33,
     /// API key for request AlterReplicaLogDirsRequest
+    #[cfg(feature = "admin")]
     AlterReplicaLogDirsKey = 34,
     /// API key for request DescribeLogDirsRequest
+    #[cfg(feature = "admin")]
     DescribeLogDirsKey = 35,
     /// API key for request SaslAuthenticateRequest
+    #[cfg(feature = "client-core")]
     SaslAuthenticateKey = 36,
     /// API key for request CreatePartitionsRequest
+    #[cfg(feature = "admin")]
     CreatePartitionsKey = 37,
     /// API key for request CreateDelegationTokenRequest
+    #[cfg(feature = "admin")]
     CreateDelegationTokenKey = 38,
     /// API key for request RenewDelegationTokenRequest
+    #[cfg(feature = "admin")]
     RenewDelegationTokenKey = 39,
     /// API key for request ExpireDelegationTokenRequest
+    #[cfg(feature = "admin")]
     ExpireDelegationTokenKey = 40,
     /// API key for request DescribeDelegationTokenRequest
+    #[cfg(feature = "admin")]
     DescribeDelegationTokenKey = 41,
     /// API key for request DeleteGroupsRequest
+    #[cfg(feature = "consumer-group")]
     DeleteGroupsKey = 42,
     /// API key for request ElectLeadersRequest
+    #[cfg(feature = "admin")]
     ElectLeadersKey = 43,
     /// API key for request IncrementalAlterConfigsRequest
+    #[cfg(feature = "admin")]
     IncrementalAlterConfigsKey = 44,
     /// API key for request AlterPartitionReassignmentsRequest
+    #[cfg(feature = "admin")]
     AlterPartitionReassignmentsKey = 45,
     /// API key for request ListPartitionReassignmentsRequest
+    #[cfg(feature = "admin")]
     ListPartitionReassignmentsKey = 46,
     /// API key for request OffsetDeleteRequest
+    #[cfg(feature = "consumer-group")]
     OffsetDeleteKey = 47,
     /// API key for request DescribeClientQuotasRequest
+    #[cfg(feature = "admin")]
     DescribeClientQuotasKey = 48,
     /// API key for request AlterClientQuotasRequest
+    #[cfg(feature = "admin")]
     AlterClientQuotasKey = 49,
     /// API key for request DescribeUserScramCredentialsRequest
+    #[cfg(feature = "admin")]
     DescribeUserScramCredentialsKey = 50,
     /// API key for request AlterUserScramCredentialsRequest
+    #[cfg(feature = "admin")]
     AlterUserScramCredentialsKey = 51,
     /// API key for request VoteRequest
+    #[cfg(feature = "raft")]
     VoteKey = 52,
     /// API key for request BeginQuorumEpochRequest
+    #[cfg(feature = "raft")]
     BeginQuorumEpochKey = 53,
     /// API key for request EndQuorumEpochRequest
+    #[cfg(feature = "raft")]
     EndQuorumEpochKey = 54,
     /// API key for request DescribeQuorumRequest
+    #[cfg(feature = "raft")]
     DescribeQuorumKey = 55,
     /// API key for request AlterPartitionRequest
+    #[cfg(feature = "broker-internal")]
     AlterPartitionKey = 56,
     /// API key for request UpdateFeaturesRequest
+    #[cfg(feature = "admin")]
     UpdateFeaturesKey = 57,
     /// API key for request EnvelopeRequest
+    #[cfg(feature = "broker-internal")]
     EnvelopeKey = 58,
     /// API key for request FetchSnapshotRequest
+    #[cfg(feature = "raft")]
     FetchSnapshotKey = 59,
     /// API key for request DescribeClusterRequest
+    #[cfg(feature = "admin")]
     DescribeClusterKey = 60,
     /// API key for request DescribeProducersRequest
+    #[cfg(feature = "transactions")]
     DescribeProducersKey = 61,
     /// API key for request BrokerRegistrationRequest
+    #[cfg(feature = "broker-internal")]
     BrokerRegistrationKey = 62,
     /// API key for request BrokerHeartbeatRequest
+    #[cfg(feature = "broker-internal")]
     BrokerHeartbeatKey = 63,
     /// API key for request UnregisterBrokerRequest
+    #[cfg(feature = "admin")]
     UnregisterBrokerKey = 64,
     /// API key for request DescribeTransactionsRequest
+    #[cfg(feature = "transactions")]
     DescribeTransactionsKey = 65,
     /// API key for request ListTransactionsRequest
+    #[cfg(feature = "transactions")]
     ListTransactionsKey = 66,
     /// API key for request AllocateProducerIdsRequest
+    #[cfg(feature = "broker-internal")]
     AllocateProducerIdsKey = 67,
     /// API key for request ConsumerGroupHeartbeatRequest
+    #[cfg(feature = "consumer-group")]
     ConsumerGroupHeartbeatKey = 68,
 }
 
@@ -936,225 +1349,1299 @@ impl ApiKey {
     /// Get the version of request header that needs to be prepended to this message
     pub fn request_header_version(&self, version: i16) -> i16 {
         match self {
+            #[cfg(feature = "client-core")]
             ApiKey::ProduceKey => ProduceRequest::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::FetchKey => FetchRequest::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::ListOffsetsKey => ListOffsetsRequest::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::MetadataKey => MetadataRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::LeaderAndIsrKey => LeaderAndIsrRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::StopReplicaKey => StopReplicaRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::UpdateMetadataKey => UpdateMetadataRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::ControlledShutdownKey => ControlledShutdownRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::OffsetCommitKey => OffsetCommitRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::OffsetFetchKey => OffsetFetchRequest::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::FindCoordinatorKey => FindCoordinatorRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::JoinGroupKey => JoinGroupRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::HeartbeatKey => HeartbeatRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::LeaveGroupKey => LeaveGroupRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::SyncGroupKey => SyncGroupRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::DescribeGroupsKey => DescribeGroupsRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::ListGroupsKey => ListGroupsRequest::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::SaslHandshakeKey => SaslHandshakeRequest::header_version(version),
             ApiKey::ApiVersionsKey => ApiVersionsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::CreateTopicsKey => CreateTopicsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DeleteTopicsKey => DeleteTopicsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DeleteRecordsKey => DeleteRecordsRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::InitProducerIdKey => InitProducerIdRequest::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::OffsetForLeaderEpochKey => OffsetForLeaderEpochRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::AddPartitionsToTxnKey => AddPartitionsToTxnRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::AddOffsetsToTxnKey => AddOffsetsToTxnRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::EndTxnKey => EndTxnRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::WriteTxnMarkersKey => WriteTxnMarkersRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::TxnOffsetCommitKey => TxnOffsetCommitRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeAclsKey => DescribeAclsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::CreateAclsKey => CreateAclsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DeleteAclsKey => DeleteAclsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeConfigsKey => DescribeConfigsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterConfigsKey => AlterConfigsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterReplicaLogDirsKey => AlterReplicaLogDirsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeLogDirsKey => DescribeLogDirsRequest::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::SaslAuthenticateKey => SaslAuthenticateRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::CreatePartitionsKey => CreatePartitionsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::CreateDelegationTokenKey => CreateDelegationTokenRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::RenewDelegationTokenKey => RenewDelegationTokenRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::ExpireDelegationTokenKey => ExpireDelegationTokenRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeDelegationTokenKey => DescribeDelegationTokenRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::DeleteGroupsKey => DeleteGroupsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::ElectLeadersKey => ElectLeadersRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::IncrementalAlterConfigsKey => IncrementalAlterConfigsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterPartitionReassignmentsKey => AlterPartitionReassignmentsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::ListPartitionReassignmentsKey => ListPartitionReassignmentsRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::OffsetDeleteKey => OffsetDeleteRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeClientQuotasKey => DescribeClientQuotasRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterClientQuotasKey => AlterClientQuotasRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeUserScramCredentialsKey => DescribeUserScramCredentialsRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterUserScramCredentialsKey => AlterUserScramCredentialsRequest::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::VoteKey => VoteRequest::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::BeginQuorumEpochKey => BeginQuorumEpochRequest::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::EndQuorumEpochKey => EndQuorumEpochRequest::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::DescribeQuorumKey => DescribeQuorumRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::AlterPartitionKey => AlterPartitionRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::UpdateFeaturesKey => UpdateFeaturesRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::EnvelopeKey => EnvelopeRequest::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::FetchSnapshotKey => FetchSnapshotRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeClusterKey => DescribeClusterRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::DescribeProducersKey => DescribeProducersRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::BrokerRegistrationKey => BrokerRegistrationRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::BrokerHeartbeatKey => BrokerHeartbeatRequest::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::UnregisterBrokerKey => UnregisterBrokerRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::DescribeTransactionsKey => DescribeTransactionsRequest::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::ListTransactionsKey => ListTransactionsRequest::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::AllocateProducerIdsKey => AllocateProducerIdsRequest::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::ConsumerGroupHeartbeatKey => ConsumerGroupHeartbeatRequest::header_version(version),
         }
     }
     /// Get the version of response header that needs to be prepended to this message
     pub fn response_header_version(&self, version: i16) -> i16 {
         match self {
+            #[cfg(feature = "client-core")]
             ApiKey::ProduceKey => ProduceResponse::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::FetchKey => FetchResponse::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::ListOffsetsKey => ListOffsetsResponse::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::MetadataKey => MetadataResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::LeaderAndIsrKey => LeaderAndIsrResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::StopReplicaKey => StopReplicaResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::UpdateMetadataKey => UpdateMetadataResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::ControlledShutdownKey => ControlledShutdownResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::OffsetCommitKey => OffsetCommitResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::OffsetFetchKey => OffsetFetchResponse::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::FindCoordinatorKey => FindCoordinatorResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::JoinGroupKey => JoinGroupResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::HeartbeatKey => HeartbeatResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::LeaveGroupKey => LeaveGroupResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::SyncGroupKey => SyncGroupResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::DescribeGroupsKey => DescribeGroupsResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::ListGroupsKey => ListGroupsResponse::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::SaslHandshakeKey => SaslHandshakeResponse::header_version(version),
             ApiKey::ApiVersionsKey => ApiVersionsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::CreateTopicsKey => CreateTopicsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DeleteTopicsKey => DeleteTopicsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DeleteRecordsKey => DeleteRecordsResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::InitProducerIdKey => InitProducerIdResponse::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::OffsetForLeaderEpochKey => OffsetForLeaderEpochResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::AddPartitionsToTxnKey => AddPartitionsToTxnResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::AddOffsetsToTxnKey => AddOffsetsToTxnResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::EndTxnKey => EndTxnResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::WriteTxnMarkersKey => WriteTxnMarkersResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::TxnOffsetCommitKey => TxnOffsetCommitResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeAclsKey => DescribeAclsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::CreateAclsKey => CreateAclsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DeleteAclsKey => DeleteAclsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeConfigsKey => DescribeConfigsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterConfigsKey => AlterConfigsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterReplicaLogDirsKey => AlterReplicaLogDirsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeLogDirsKey => DescribeLogDirsResponse::header_version(version),
+            #[cfg(feature = "client-core")]
             ApiKey::SaslAuthenticateKey => SaslAuthenticateResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::CreatePartitionsKey => CreatePartitionsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::CreateDelegationTokenKey => CreateDelegationTokenResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::RenewDelegationTokenKey => RenewDelegationTokenResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::ExpireDelegationTokenKey => ExpireDelegationTokenResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeDelegationTokenKey => DescribeDelegationTokenResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::DeleteGroupsKey => DeleteGroupsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::ElectLeadersKey => ElectLeadersResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::IncrementalAlterConfigsKey => IncrementalAlterConfigsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterPartitionReassignmentsKey => AlterPartitionReassignmentsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::ListPartitionReassignmentsKey => ListPartitionReassignmentsResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::OffsetDeleteKey => OffsetDeleteResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeClientQuotasKey => DescribeClientQuotasResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterClientQuotasKey => AlterClientQuotasResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeUserScramCredentialsKey => DescribeUserScramCredentialsResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::AlterUserScramCredentialsKey => AlterUserScramCredentialsResponse::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::VoteKey => VoteResponse::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::BeginQuorumEpochKey => BeginQuorumEpochResponse::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::EndQuorumEpochKey => EndQuorumEpochResponse::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::DescribeQuorumKey => DescribeQuorumResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::AlterPartitionKey => AlterPartitionResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::UpdateFeaturesKey => UpdateFeaturesResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::EnvelopeKey => EnvelopeResponse::header_version(version),
+            #[cfg(feature = "raft")]
             ApiKey::FetchSnapshotKey => FetchSnapshotResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::DescribeClusterKey => DescribeClusterResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::DescribeProducersKey => DescribeProducersResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::BrokerRegistrationKey => BrokerRegistrationResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::BrokerHeartbeatKey => BrokerHeartbeatResponse::header_version(version),
+            #[cfg(feature = "admin")]
             ApiKey::UnregisterBrokerKey => UnregisterBrokerResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::DescribeTransactionsKey => DescribeTransactionsResponse::header_version(version),
+            #[cfg(feature = "transactions")]
             ApiKey::ListTransactionsKey => ListTransactionsResponse::header_version(version),
+            #[cfg(feature = "broker-internal")]
             ApiKey::AllocateProducerIdsKey => AllocateProducerIdsResponse::header_version(version),
+            #[cfg(feature = "consumer-group")]
             ApiKey::ConsumerGroupHeartbeatKey => ConsumerGroupHeartbeatResponse::header_version(version),
         }
     }
+
+    /// All API keys known to this crate, in ascending order.
+    pub fn all() -> &'static [ApiKey] {
+        &[
+            #[cfg(feature = "client-core")]
+            ApiKey::ProduceKey,
+            #[cfg(feature = "client-core")]
+            ApiKey::FetchKey,
+            #[cfg(feature = "client-core")]
+            ApiKey::ListOffsetsKey,
+            #[cfg(feature = "client-core")]
+            ApiKey::MetadataKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::LeaderAndIsrKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::StopReplicaKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::UpdateMetadataKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::ControlledShutdownKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetCommitKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetFetchKey,
+            #[cfg(feature = "client-core")]
+            ApiKey::FindCoordinatorKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::JoinGroupKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::HeartbeatKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::LeaveGroupKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::SyncGroupKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DescribeGroupsKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ListGroupsKey,
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslHandshakeKey,
+            ApiKey::ApiVersionsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateTopicsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteTopicsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteRecordsKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::InitProducerIdKey,
+            #[cfg(feature = "client-core")]
+            ApiKey::OffsetForLeaderEpochKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::AddPartitionsToTxnKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::AddOffsetsToTxnKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::EndTxnKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::WriteTxnMarkersKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::TxnOffsetCommitKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeAclsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateAclsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteAclsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeConfigsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterConfigsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterReplicaLogDirsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeLogDirsKey,
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslAuthenticateKey,
+            #[cfg(feature = "admin")]
+            ApiKey::CreatePartitionsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateDelegationTokenKey,
+            #[cfg(feature = "admin")]
+            ApiKey::RenewDelegationTokenKey,
+            #[cfg(feature = "admin")]
+            ApiKey::ExpireDelegationTokenKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeDelegationTokenKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DeleteGroupsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::ElectLeadersKey,
+            #[cfg(feature = "admin")]
+            ApiKey::IncrementalAlterConfigsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterPartitionReassignmentsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::ListPartitionReassignmentsKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetDeleteKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClientQuotasKey,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterClientQuotasKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeUserScramCredentialsKey,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterUserScramCredentialsKey,
+            #[cfg(feature = "raft")]
+            ApiKey::VoteKey,
+            #[cfg(feature = "raft")]
+            ApiKey::BeginQuorumEpochKey,
+            #[cfg(feature = "raft")]
+            ApiKey::EndQuorumEpochKey,
+            #[cfg(feature = "raft")]
+            ApiKey::DescribeQuorumKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AlterPartitionKey,
+            #[cfg(feature = "admin")]
+            ApiKey::UpdateFeaturesKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::EnvelopeKey,
+            #[cfg(feature = "raft")]
+            ApiKey::FetchSnapshotKey,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClusterKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeProducersKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerRegistrationKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerHeartbeatKey,
+            #[cfg(feature = "admin")]
+            ApiKey::UnregisterBrokerKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeTransactionsKey,
+            #[cfg(feature = "transactions")]
+            ApiKey::ListTransactionsKey,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AllocateProducerIdsKey,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ConsumerGroupHeartbeatKey,
+        ]
+    }
+
+    /// The name of this API as used by Kafka, e.g. `Produce`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "client-core")]
+            ApiKey::ProduceKey => "Produce",
+            #[cfg(feature = "client-core")]
+            ApiKey::FetchKey => "Fetch",
+            #[cfg(feature = "client-core")]
+            ApiKey::ListOffsetsKey => "ListOffsets",
+            #[cfg(feature = "client-core")]
+            ApiKey::MetadataKey => "Metadata",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::LeaderAndIsrKey => "LeaderAndIsr",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::StopReplicaKey => "StopReplica",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::UpdateMetadataKey => "UpdateMetadata",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::ControlledShutdownKey => "ControlledShutdown",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetCommitKey => "OffsetCommit",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetFetchKey => "OffsetFetch",
+            #[cfg(feature = "client-core")]
+            ApiKey::FindCoordinatorKey => "FindCoordinator",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::JoinGroupKey => "JoinGroup",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::HeartbeatKey => "Heartbeat",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::LeaveGroupKey => "LeaveGroup",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::SyncGroupKey => "SyncGroup",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DescribeGroupsKey => "DescribeGroups",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ListGroupsKey => "ListGroups",
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslHandshakeKey => "SaslHandshake",
+            ApiKey::ApiVersionsKey => "ApiVersions",
+            #[cfg(feature = "admin")]
+            ApiKey::CreateTopicsKey => "CreateTopics",
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteTopicsKey => "DeleteTopics",
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteRecordsKey => "DeleteRecords",
+            #[cfg(feature = "transactions")]
+            ApiKey::InitProducerIdKey => "InitProducerId",
+            #[cfg(feature = "client-core")]
+            ApiKey::OffsetForLeaderEpochKey => "OffsetForLeaderEpoch",
+            #[cfg(feature = "transactions")]
+            ApiKey::AddPartitionsToTxnKey => "AddPartitionsToTxn",
+            #[cfg(feature = "transactions")]
+            ApiKey::AddOffsetsToTxnKey => "AddOffsetsToTxn",
+            #[cfg(feature = "transactions")]
+            ApiKey::EndTxnKey => "EndTxn",
+            #[cfg(feature = "transactions")]
+            ApiKey::WriteTxnMarkersKey => "WriteTxnMarkers",
+            #[cfg(feature = "transactions")]
+            ApiKey::TxnOffsetCommitKey => "TxnOffsetCommit",
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeAclsKey => "DescribeAcls",
+            #[cfg(feature = "admin")]
+            ApiKey::CreateAclsKey => "CreateAcls",
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteAclsKey => "DeleteAcls",
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeConfigsKey => "DescribeConfigs",
+            #[cfg(feature = "admin")]
+            ApiKey::AlterConfigsKey => "AlterConfigs",
+            #[cfg(feature = "admin")]
+            ApiKey::AlterReplicaLogDirsKey => "AlterReplicaLogDirs",
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeLogDirsKey => "DescribeLogDirs",
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslAuthenticateKey => "SaslAuthenticate",
+            #[cfg(feature = "admin")]
+            ApiKey::CreatePartitionsKey => "CreatePartitions",
+            #[cfg(feature = "admin")]
+            ApiKey::CreateDelegationTokenKey => "CreateDelegationToken",
+            #[cfg(feature = "admin")]
+            ApiKey::RenewDelegationTokenKey => "RenewDelegationToken",
+            #[cfg(feature = "admin")]
+            ApiKey::ExpireDelegationTokenKey => "ExpireDelegationToken",
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeDelegationTokenKey => "DescribeDelegationToken",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DeleteGroupsKey => "DeleteGroups",
+            #[cfg(feature = "admin")]
+            ApiKey::ElectLeadersKey => "ElectLeaders",
+            #[cfg(feature = "admin")]
+            ApiKey::IncrementalAlterConfigsKey => "IncrementalAlterConfigs",
+            #[cfg(feature = "admin")]
+            ApiKey::AlterPartitionReassignmentsKey => "AlterPartitionReassignments",
+            #[cfg(feature = "admin")]
+            ApiKey::ListPartitionReassignmentsKey => "ListPartitionReassignments",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetDeleteKey => "OffsetDelete",
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClientQuotasKey => "DescribeClientQuotas",
+            #[cfg(feature = "admin")]
+            ApiKey::AlterClientQuotasKey => "AlterClientQuotas",
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeUserScramCredentialsKey => "DescribeUserScramCredentials",
+            #[cfg(feature = "admin")]
+            ApiKey::AlterUserScramCredentialsKey => "AlterUserScramCredentials",
+            #[cfg(feature = "raft")]
+            ApiKey::VoteKey => "Vote",
+            #[cfg(feature = "raft")]
+            ApiKey::BeginQuorumEpochKey => "BeginQuorumEpoch",
+            #[cfg(feature = "raft")]
+            ApiKey::EndQuorumEpochKey => "EndQuorumEpoch",
+            #[cfg(feature = "raft")]
+            ApiKey::DescribeQuorumKey => "DescribeQuorum",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AlterPartitionKey => "AlterPartition",
+            #[cfg(feature = "admin")]
+            ApiKey::UpdateFeaturesKey => "UpdateFeatures",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::EnvelopeKey => "Envelope",
+            #[cfg(feature = "raft")]
+            ApiKey::FetchSnapshotKey => "FetchSnapshot",
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClusterKey => "DescribeCluster",
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeProducersKey => "DescribeProducers",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerRegistrationKey => "BrokerRegistration",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerHeartbeatKey => "BrokerHeartbeat",
+            #[cfg(feature = "admin")]
+            ApiKey::UnregisterBrokerKey => "UnregisterBroker",
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeTransactionsKey => "DescribeTransactions",
+            #[cfg(feature = "transactions")]
+            ApiKey::ListTransactionsKey => "ListTransactions",
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AllocateProducerIdsKey => "AllocateProducerIds",
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ConsumerGroupHeartbeatKey => "ConsumerGroupHeartbeat",
+        }
+    }
+
+    /// The versions of this API supported by this crate.
+    pub fn valid_versions(&self) -> VersionRange {
+        match self {
+            #[cfg(feature = "client-core")]
+            ApiKey::ProduceKey => ProduceRequest::VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::FetchKey => FetchRequest::VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::ListOffsetsKey => ListOffsetsRequest::VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::MetadataKey => MetadataRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::LeaderAndIsrKey => LeaderAndIsrRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::StopReplicaKey => StopReplicaRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::UpdateMetadataKey => UpdateMetadataRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::ControlledShutdownKey => ControlledShutdownRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetCommitKey => OffsetCommitRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetFetchKey => OffsetFetchRequest::VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::FindCoordinatorKey => FindCoordinatorRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::JoinGroupKey => JoinGroupRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::HeartbeatKey => HeartbeatRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::LeaveGroupKey => LeaveGroupRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::SyncGroupKey => SyncGroupRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DescribeGroupsKey => DescribeGroupsRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ListGroupsKey => ListGroupsRequest::VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslHandshakeKey => SaslHandshakeRequest::VERSIONS,
+            ApiKey::ApiVersionsKey => ApiVersionsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateTopicsKey => CreateTopicsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteTopicsKey => DeleteTopicsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteRecordsKey => DeleteRecordsRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::InitProducerIdKey => InitProducerIdRequest::VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::OffsetForLeaderEpochKey => OffsetForLeaderEpochRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::AddPartitionsToTxnKey => AddPartitionsToTxnRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::AddOffsetsToTxnKey => AddOffsetsToTxnRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::EndTxnKey => EndTxnRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::WriteTxnMarkersKey => WriteTxnMarkersRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::TxnOffsetCommitKey => TxnOffsetCommitRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeAclsKey => DescribeAclsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateAclsKey => CreateAclsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteAclsKey => DeleteAclsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeConfigsKey => DescribeConfigsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterConfigsKey => AlterConfigsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterReplicaLogDirsKey => AlterReplicaLogDirsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeLogDirsKey => DescribeLogDirsRequest::VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslAuthenticateKey => SaslAuthenticateRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::CreatePartitionsKey => CreatePartitionsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateDelegationTokenKey => CreateDelegationTokenRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::RenewDelegationTokenKey => RenewDelegationTokenRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::ExpireDelegationTokenKey => ExpireDelegationTokenRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeDelegationTokenKey => DescribeDelegationTokenRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DeleteGroupsKey => DeleteGroupsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::ElectLeadersKey => ElectLeadersRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::IncrementalAlterConfigsKey => IncrementalAlterConfigsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterPartitionReassignmentsKey => AlterPartitionReassignmentsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::ListPartitionReassignmentsKey => ListPartitionReassignmentsRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetDeleteKey => OffsetDeleteRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClientQuotasKey => DescribeClientQuotasRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterClientQuotasKey => AlterClientQuotasRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeUserScramCredentialsKey => DescribeUserScramCredentialsRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterUserScramCredentialsKey => AlterUserScramCredentialsRequest::VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::VoteKey => VoteRequest::VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::BeginQuorumEpochKey => BeginQuorumEpochRequest::VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::EndQuorumEpochKey => EndQuorumEpochRequest::VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::DescribeQuorumKey => DescribeQuorumRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AlterPartitionKey => AlterPartitionRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::UpdateFeaturesKey => UpdateFeaturesRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::EnvelopeKey => EnvelopeRequest::VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::FetchSnapshotKey => FetchSnapshotRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClusterKey => DescribeClusterRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeProducersKey => DescribeProducersRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerRegistrationKey => BrokerRegistrationRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerHeartbeatKey => BrokerHeartbeatRequest::VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::UnregisterBrokerKey => UnregisterBrokerRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeTransactionsKey => DescribeTransactionsRequest::VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::ListTransactionsKey => ListTransactionsRequest::VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AllocateProducerIdsKey => AllocateProducerIdsRequest::VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ConsumerGroupHeartbeatKey => ConsumerGroupHeartbeatRequest::VERSIONS,
+        }
+    }
+
+    /// The versions of this API that use the flexible encoding (compact types and tagged fields).
+    ///
+    /// An API with no flexible versions returns an empty range. Like [`ApiKey::valid_versions`], the
+    /// range only includes unstable versions if the `unstable-apis` feature is enabled.
+    pub fn flexible_versions(&self) -> VersionRange {
+        match self {
+            #[cfg(feature = "client-core")]
+            ApiKey::ProduceKey => VersionRange { min: 9, max: 9 },
+            #[cfg(feature = "client-core")]
+            ApiKey::FetchKey => VersionRange { min: 12, max: 15 },
+            #[cfg(feature = "client-core")]
+            ApiKey::ListOffsetsKey => VersionRange { min: 6, max: 8 },
+            #[cfg(feature = "client-core")]
+            ApiKey::MetadataKey => VersionRange { min: 9, max: 12 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::LeaderAndIsrKey => VersionRange { min: 4, max: 7 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::StopReplicaKey => VersionRange { min: 2, max: 4 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::UpdateMetadataKey => VersionRange { min: 6, max: 8 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::ControlledShutdownKey => VersionRange { min: 3, max: 3 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetCommitKey => VersionRange { min: 8, max: 9 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetFetchKey => VersionRange { min: 6, max: 8 },
+            #[cfg(feature = "client-core")]
+            ApiKey::FindCoordinatorKey => VersionRange { min: 3, max: 4 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::JoinGroupKey => VersionRange { min: 6, max: 9 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::HeartbeatKey => VersionRange { min: 4, max: 4 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::LeaveGroupKey => VersionRange { min: 4, max: 5 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::SyncGroupKey => VersionRange { min: 4, max: 5 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DescribeGroupsKey => VersionRange { min: 5, max: 5 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ListGroupsKey => VersionRange { min: 3, max: 4 },
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslHandshakeKey => VersionRange { min: 0, max: -1 },
+            ApiKey::ApiVersionsKey => VersionRange { min: 3, max: 3 },
+            #[cfg(feature = "admin")]
+            ApiKey::CreateTopicsKey => VersionRange { min: 5, max: 7 },
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteTopicsKey => VersionRange { min: 4, max: 6 },
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteRecordsKey => VersionRange { min: 2, max: 2 },
+            #[cfg(feature = "transactions")]
+            ApiKey::InitProducerIdKey => VersionRange { min: 2, max: 4 },
+            #[cfg(feature = "client-core")]
+            ApiKey::OffsetForLeaderEpochKey => VersionRange { min: 4, max: 4 },
+            #[cfg(feature = "transactions")]
+            ApiKey::AddPartitionsToTxnKey => VersionRange { min: 3, max: 4 },
+            #[cfg(feature = "transactions")]
+            ApiKey::AddOffsetsToTxnKey => VersionRange { min: 3, max: 3 },
+            #[cfg(feature = "transactions")]
+            ApiKey::EndTxnKey => VersionRange { min: 3, max: 3 },
+            #[cfg(feature = "transactions")]
+            ApiKey::WriteTxnMarkersKey => VersionRange { min: 1, max: 1 },
+            #[cfg(feature = "transactions")]
+            ApiKey::TxnOffsetCommitKey => VersionRange { min: 3, max: 3 },
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeAclsKey => VersionRange { min: 2, max: 3 },
+            #[cfg(feature = "admin")]
+            ApiKey::CreateAclsKey => VersionRange { min: 2, max: 3 },
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteAclsKey => VersionRange { min: 2, max: 3 },
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeConfigsKey => VersionRange { min: 4, max: 4 },
+            #[cfg(feature = "admin")]
+            ApiKey::AlterConfigsKey => VersionRange { min: 2, max: 2 },
+            #[cfg(feature = "admin")]
+            ApiKey::AlterReplicaLogDirsKey => VersionRange { min: 2, max: 2 },
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeLogDirsKey => VersionRange { min: 2, max: 4 },
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslAuthenticateKey => VersionRange { min: 2, max: 2 },
+            #[cfg(feature = "admin")]
+            ApiKey::CreatePartitionsKey => VersionRange { min: 2, max: 3 },
+            #[cfg(feature = "admin")]
+            ApiKey::CreateDelegationTokenKey => VersionRange { min: 2, max: 3 },
+            #[cfg(feature = "admin")]
+            ApiKey::RenewDelegationTokenKey => VersionRange { min: 2, max: 2 },
+            #[cfg(feature = "admin")]
+            ApiKey::ExpireDelegationTokenKey => VersionRange { min: 2, max: 2 },
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeDelegationTokenKey => VersionRange { min: 2, max: 3 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DeleteGroupsKey => VersionRange { min: 2, max: 2 },
+            #[cfg(feature = "admin")]
+            ApiKey::ElectLeadersKey => VersionRange { min: 2, max: 2 },
+            #[cfg(feature = "admin")]
+            ApiKey::IncrementalAlterConfigsKey => VersionRange { min: 1, max: 1 },
+            #[cfg(feature = "admin")]
+            ApiKey::AlterPartitionReassignmentsKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "admin")]
+            ApiKey::ListPartitionReassignmentsKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetDeleteKey => VersionRange { min: 0, max: -1 },
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClientQuotasKey => VersionRange { min: 1, max: 1 },
+            #[cfg(feature = "admin")]
+            ApiKey::AlterClientQuotasKey => VersionRange { min: 1, max: 1 },
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeUserScramCredentialsKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "admin")]
+            ApiKey::AlterUserScramCredentialsKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "raft")]
+            ApiKey::VoteKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "raft")]
+            ApiKey::BeginQuorumEpochKey => VersionRange { min: 0, max: -1 },
+            #[cfg(feature = "raft")]
+            ApiKey::EndQuorumEpochKey => VersionRange { min: 0, max: -1 },
+            #[cfg(feature = "raft")]
+            ApiKey::DescribeQuorumKey => VersionRange { min: 0, max: 1 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AlterPartitionKey => VersionRange { min: 0, max: 3 },
+            #[cfg(feature = "admin")]
+            ApiKey::UpdateFeaturesKey => VersionRange { min: 0, max: 1 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::EnvelopeKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "raft")]
+            ApiKey::FetchSnapshotKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClusterKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeProducersKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerRegistrationKey => VersionRange { min: 0, max: 1 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerHeartbeatKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "admin")]
+            ApiKey::UnregisterBrokerKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeTransactionsKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "transactions")]
+            ApiKey::ListTransactionsKey => VersionRange { min: 0, max: 0 },
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AllocateProducerIdsKey => VersionRange { min: 0, max: 0 },
+            #[cfg(all(feature = "consumer-group", feature = "unstable-apis"))]
+            ApiKey::ConsumerGroupHeartbeatKey => VersionRange { min: 0, max: 0 },
+            #[cfg(all(feature = "consumer-group", not(feature = "unstable-apis")))]
+            ApiKey::ConsumerGroupHeartbeatKey => VersionRange { min: 0, max: -1 },
+        }
+    }
+
+    /// The versions of this API that are unstable, i.e. still under development upstream and subject
+    /// to incompatible changes. They are only part of [`ApiKey::valid_versions`] if the `unstable-apis`
+    /// feature is enabled.
+    pub fn unstable_versions(&self) -> Option<VersionRange> {
+        match self {
+            #[cfg(feature = "client-core")]
+            ApiKey::ProduceKey => ProduceRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::FetchKey => FetchRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::ListOffsetsKey => ListOffsetsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::MetadataKey => MetadataRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::LeaderAndIsrKey => LeaderAndIsrRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::StopReplicaKey => StopReplicaRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::UpdateMetadataKey => UpdateMetadataRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::ControlledShutdownKey => ControlledShutdownRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetCommitKey => OffsetCommitRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetFetchKey => OffsetFetchRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::FindCoordinatorKey => FindCoordinatorRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::JoinGroupKey => JoinGroupRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::HeartbeatKey => HeartbeatRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::LeaveGroupKey => LeaveGroupRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::SyncGroupKey => SyncGroupRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DescribeGroupsKey => DescribeGroupsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ListGroupsKey => ListGroupsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslHandshakeKey => SaslHandshakeRequest::UNSTABLE_VERSIONS,
+            ApiKey::ApiVersionsKey => ApiVersionsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateTopicsKey => CreateTopicsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteTopicsKey => DeleteTopicsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteRecordsKey => DeleteRecordsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::InitProducerIdKey => InitProducerIdRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::OffsetForLeaderEpochKey => OffsetForLeaderEpochRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::AddPartitionsToTxnKey => AddPartitionsToTxnRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::AddOffsetsToTxnKey => AddOffsetsToTxnRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::EndTxnKey => EndTxnRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::WriteTxnMarkersKey => WriteTxnMarkersRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::TxnOffsetCommitKey => TxnOffsetCommitRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeAclsKey => DescribeAclsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateAclsKey => CreateAclsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteAclsKey => DeleteAclsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeConfigsKey => DescribeConfigsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterConfigsKey => AlterConfigsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterReplicaLogDirsKey => AlterReplicaLogDirsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeLogDirsKey => DescribeLogDirsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslAuthenticateKey => SaslAuthenticateRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::CreatePartitionsKey => CreatePartitionsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::CreateDelegationTokenKey => CreateDelegationTokenRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::RenewDelegationTokenKey => RenewDelegationTokenRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::ExpireDelegationTokenKey => ExpireDelegationTokenRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeDelegationTokenKey => DescribeDelegationTokenRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DeleteGroupsKey => DeleteGroupsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::ElectLeadersKey => ElectLeadersRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::IncrementalAlterConfigsKey => IncrementalAlterConfigsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterPartitionReassignmentsKey => AlterPartitionReassignmentsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::ListPartitionReassignmentsKey => ListPartitionReassignmentsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetDeleteKey => OffsetDeleteRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClientQuotasKey => DescribeClientQuotasRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterClientQuotasKey => AlterClientQuotasRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeUserScramCredentialsKey => DescribeUserScramCredentialsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::AlterUserScramCredentialsKey => AlterUserScramCredentialsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::VoteKey => VoteRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::BeginQuorumEpochKey => BeginQuorumEpochRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::EndQuorumEpochKey => EndQuorumEpochRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::DescribeQuorumKey => DescribeQuorumRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AlterPartitionKey => AlterPartitionRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::UpdateFeaturesKey => UpdateFeaturesRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::EnvelopeKey => EnvelopeRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "raft")]
+            ApiKey::FetchSnapshotKey => FetchSnapshotRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClusterKey => DescribeClusterRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeProducersKey => DescribeProducersRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerRegistrationKey => BrokerRegistrationRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerHeartbeatKey => BrokerHeartbeatRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "admin")]
+            ApiKey::UnregisterBrokerKey => UnregisterBrokerRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeTransactionsKey => DescribeTransactionsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "transactions")]
+            ApiKey::ListTransactionsKey => ListTransactionsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AllocateProducerIdsKey => AllocateProducerIdsRequest::UNSTABLE_VERSIONS,
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ConsumerGroupHeartbeatKey => ConsumerGroupHeartbeatRequest::UNSTABLE_VERSIONS,
+        }
+    }
+
+    /// Whether the given version of this API is unstable, see [`ApiKey::unstable_versions`].
+    pub fn is_unstable(&self, version: i16) -> bool {
+        self.unstable_versions().map_or(false, |versions| versions.contains(version))
+    }
+
+    /// The listeners on which this API is exposed by a Kafka node.
+    pub fn listeners(&self) -> &'static [ListenerType] {
+        match self {
+            #[cfg(feature = "client-core")]
+            ApiKey::ProduceKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "client-core")]
+            ApiKey::FetchKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "client-core")]
+            ApiKey::ListOffsetsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "client-core")]
+            ApiKey::MetadataKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::LeaderAndIsrKey => &[ListenerType::ZkBroker],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::StopReplicaKey => &[ListenerType::ZkBroker],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::UpdateMetadataKey => &[ListenerType::ZkBroker],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::ControlledShutdownKey => &[ListenerType::ZkBroker, ListenerType::Controller],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetCommitKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetFetchKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "client-core")]
+            ApiKey::FindCoordinatorKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::JoinGroupKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::HeartbeatKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::LeaveGroupKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::SyncGroupKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DescribeGroupsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ListGroupsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslHandshakeKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            ApiKey::ApiVersionsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::CreateTopicsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteTopicsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteRecordsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "transactions")]
+            ApiKey::InitProducerIdKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "client-core")]
+            ApiKey::OffsetForLeaderEpochKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "transactions")]
+            ApiKey::AddPartitionsToTxnKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "transactions")]
+            ApiKey::AddOffsetsToTxnKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "transactions")]
+            ApiKey::EndTxnKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "transactions")]
+            ApiKey::WriteTxnMarkersKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "transactions")]
+            ApiKey::TxnOffsetCommitKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeAclsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::CreateAclsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::DeleteAclsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeConfigsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "admin")]
+            ApiKey::AlterConfigsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::AlterReplicaLogDirsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeLogDirsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "client-core")]
+            ApiKey::SaslAuthenticateKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::CreatePartitionsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::CreateDelegationTokenKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::RenewDelegationTokenKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::ExpireDelegationTokenKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeDelegationTokenKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::DeleteGroupsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "admin")]
+            ApiKey::ElectLeadersKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::IncrementalAlterConfigsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::AlterPartitionReassignmentsKey => &[ListenerType::Broker, ListenerType::Controller, ListenerType::ZkBroker],
+            #[cfg(feature = "admin")]
+            ApiKey::ListPartitionReassignmentsKey => &[ListenerType::Broker, ListenerType::Controller, ListenerType::ZkBroker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::OffsetDeleteKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClientQuotasKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "admin")]
+            ApiKey::AlterClientQuotasKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeUserScramCredentialsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "admin")]
+            ApiKey::AlterUserScramCredentialsKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "raft")]
+            ApiKey::VoteKey => &[ListenerType::Controller],
+            #[cfg(feature = "raft")]
+            ApiKey::BeginQuorumEpochKey => &[ListenerType::Controller],
+            #[cfg(feature = "raft")]
+            ApiKey::EndQuorumEpochKey => &[ListenerType::Controller],
+            #[cfg(feature = "raft")]
+            ApiKey::DescribeQuorumKey => &[ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AlterPartitionKey => &[ListenerType::ZkBroker, ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::UpdateFeaturesKey => &[ListenerType::ZkBroker, ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::EnvelopeKey => &[ListenerType::Controller],
+            #[cfg(feature = "raft")]
+            ApiKey::FetchSnapshotKey => &[ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::DescribeClusterKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeProducersKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerRegistrationKey => &[ListenerType::Controller],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::BrokerHeartbeatKey => &[ListenerType::Controller],
+            #[cfg(feature = "admin")]
+            ApiKey::UnregisterBrokerKey => &[ListenerType::Broker, ListenerType::Controller],
+            #[cfg(feature = "transactions")]
+            ApiKey::DescribeTransactionsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "transactions")]
+            ApiKey::ListTransactionsKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+            #[cfg(feature = "broker-internal")]
+            ApiKey::AllocateProducerIdsKey => &[ListenerType::Controller, ListenerType::ZkBroker],
+            #[cfg(feature = "consumer-group")]
+            ApiKey::ConsumerGroupHeartbeatKey => &[ListenerType::ZkBroker, ListenerType::Broker],
+        }
+    }
 }
 impl TryFrom<i16> for ApiKey {
     type Error = ();
 
     fn try_from(v: i16) -> Result<Self, Self::Error> {
         match v {
+            #[cfg(feature = "client-core")]
             x if x == ApiKey::ProduceKey as i16 => Ok(ApiKey::ProduceKey),
+            #[cfg(feature = "client-core")]
             x if x == ApiKey::FetchKey as i16 => Ok(ApiKey::FetchKey),
+            #[cfg(feature = "client-core")]
             x if x == ApiKey::ListOffsetsKey as i16 => Ok(ApiKey::ListOffsetsKey),
+            #[cfg(feature = "client-core")]
             x if x == ApiKey::MetadataKey as i16 => Ok(ApiKey::MetadataKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::LeaderAndIsrKey as i16 => Ok(ApiKey::LeaderAndIsrKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::StopReplicaKey as i16 => Ok(ApiKey::StopReplicaKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::UpdateMetadataKey as i16 => Ok(ApiKey::UpdateMetadataKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::ControlledShutdownKey as i16 => Ok(ApiKey::ControlledShutdownKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::OffsetCommitKey as i16 => Ok(ApiKey::OffsetCommitKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::OffsetFetchKey as i16 => Ok(ApiKey::OffsetFetchKey),
+            #[cfg(feature = "client-core")]
             x if x == ApiKey::FindCoordinatorKey as i16 => Ok(ApiKey::FindCoordinatorKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::JoinGroupKey as i16 => Ok(ApiKey::JoinGroupKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::HeartbeatKey as i16 => Ok(ApiKey::HeartbeatKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::LeaveGroupKey as i16 => Ok(ApiKey::LeaveGroupKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::SyncGroupKey as i16 => Ok(ApiKey::SyncGroupKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::DescribeGroupsKey as i16 => Ok(ApiKey::DescribeGroupsKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::ListGroupsKey as i16 => Ok(ApiKey::ListGroupsKey),
+            #[cfg(feature = "client-core")]
             x if x == ApiKey::SaslHandshakeKey as i16 => Ok(ApiKey::SaslHandshakeKey),
             x if x == ApiKey::ApiVersionsKey as i16 => Ok(ApiKey::ApiVersionsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::CreateTopicsKey as i16 => Ok(ApiKey::CreateTopicsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DeleteTopicsKey as i16 => Ok(ApiKey::DeleteTopicsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DeleteRecordsKey as i16 => Ok(ApiKey::DeleteRecordsKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::InitProducerIdKey as i16 => Ok(ApiKey::InitProducerIdKey),
+            #[cfg(feature = "client-core")]
             x if x == ApiKey::OffsetForLeaderEpochKey as i16 => Ok(ApiKey::OffsetForLeaderEpochKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::AddPartitionsToTxnKey as i16 => Ok(ApiKey::AddPartitionsToTxnKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::AddOffsetsToTxnKey as i16 => Ok(ApiKey::AddOffsetsToTxnKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::EndTxnKey as i16 => Ok(ApiKey::EndTxnKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::WriteTxnMarkersKey as i16 => Ok(ApiKey::WriteTxnMarkersKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::TxnOffsetCommitKey as i16 => Ok(ApiKey::TxnOffsetCommitKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DescribeAclsKey as i16 => Ok(ApiKey::DescribeAclsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::CreateAclsKey as i16 => Ok(ApiKey::CreateAclsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DeleteAclsKey as i16 => Ok(ApiKey::DeleteAclsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DescribeConfigsKey as i16 => Ok(ApiKey::DescribeConfigsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::AlterConfigsKey as i16 => Ok(ApiKey::AlterConfigsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::AlterReplicaLogDirsKey as i16 => Ok(ApiKey::AlterReplicaLogDirsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DescribeLogDirsKey as i16 => Ok(ApiKey::DescribeLogDirsKey),
+            #[cfg(feature = "client-core")]
             x if x == ApiKey::SaslAuthenticateKey as i16 => Ok(ApiKey::SaslAuthenticateKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::CreatePartitionsKey as i16 => Ok(ApiKey::CreatePartitionsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::CreateDelegationTokenKey as i16 => Ok(ApiKey::CreateDelegationTokenKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::RenewDelegationTokenKey as i16 => Ok(ApiKey::RenewDelegationTokenKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::ExpireDelegationTokenKey as i16 => Ok(ApiKey::ExpireDelegationTokenKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DescribeDelegationTokenKey as i16 => Ok(ApiKey::DescribeDelegationTokenKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::DeleteGroupsKey as i16 => Ok(ApiKey::DeleteGroupsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::ElectLeadersKey as i16 => Ok(ApiKey::ElectLeadersKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::IncrementalAlterConfigsKey as i16 => Ok(ApiKey::IncrementalAlterConfigsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::AlterPartitionReassignmentsKey as i16 => Ok(ApiKey::AlterPartitionReassignmentsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::ListPartitionReassignmentsKey as i16 => Ok(ApiKey::ListPartitionReassignmentsKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::OffsetDeleteKey as i16 => Ok(ApiKey::OffsetDeleteKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DescribeClientQuotasKey as i16 => Ok(ApiKey::DescribeClientQuotasKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::AlterClientQuotasKey as i16 => Ok(ApiKey::AlterClientQuotasKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DescribeUserScramCredentialsKey as i16 => Ok(ApiKey::DescribeUserScramCredentialsKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::AlterUserScramCredentialsKey as i16 => Ok(ApiKey::AlterUserScramCredentialsKey),
+            #[cfg(feature = "raft")]
             x if x == ApiKey::VoteKey as i16 => Ok(ApiKey::VoteKey),
+            #[cfg(feature = "raft")]
             x if x == ApiKey::BeginQuorumEpochKey as i16 => Ok(ApiKey::BeginQuorumEpochKey),
+            #[cfg(feature = "raft")]
             x if x == ApiKey::EndQuorumEpochKey as i16 => Ok(ApiKey::EndQuorumEpochKey),
+            #[cfg(feature = "raft")]
             x if x == ApiKey::DescribeQuorumKey as i16 => Ok(ApiKey::DescribeQuorumKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::AlterPartitionKey as i16 => Ok(ApiKey::AlterPartitionKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::UpdateFeaturesKey as i16 => Ok(ApiKey::UpdateFeaturesKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::EnvelopeKey as i16 => Ok(ApiKey::EnvelopeKey),
+            #[cfg(feature = "raft")]
             x if x == ApiKey::FetchSnapshotKey as i16 => Ok(ApiKey::FetchSnapshotKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::DescribeClusterKey as i16 => Ok(ApiKey::DescribeClusterKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::DescribeProducersKey as i16 => Ok(ApiKey::DescribeProducersKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::BrokerRegistrationKey as i16 => Ok(ApiKey::BrokerRegistrationKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::BrokerHeartbeatKey as i16 => Ok(ApiKey::BrokerHeartbeatKey),
+            #[cfg(feature = "admin")]
             x if x == ApiKey::UnregisterBrokerKey as i16 => Ok(ApiKey::UnregisterBrokerKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::DescribeTransactionsKey as i16 => Ok(ApiKey::DescribeTransactionsKey),
+            #[cfg(feature = "transactions")]
             x if x == ApiKey::ListTransactionsKey as i16 => Ok(ApiKey::ListTransactionsKey),
+            #[cfg(feature = "broker-internal")]
             x if x == ApiKey::AllocateProducerIdsKey as i16 => Ok(ApiKey::AllocateProducerIdsKey),
+            #[cfg(feature = "consumer-group")]
             x if x == ApiKey::ConsumerGroupHeartbeatKey as i16 => Ok(ApiKey::ConsumerGroupHeartbeatKey),
             _ => Err(()),
         }
@@ -1164,293 +2651,433 @@ impl TryFrom<i16> for ApiKey {
 /// Wrapping enum for all requests in the Kafka protocol.
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RequestKind {
     /// ProduceRequest,
+    #[cfg(feature = "client-core")]
     ProduceRequest(ProduceRequest),
     /// FetchRequest,
+    #[cfg(feature = "client-core")]
     FetchRequest(FetchRequest),
     /// ListOffsetsRequest,
+    #[cfg(feature = "client-core")]
     ListOffsetsRequest(ListOffsetsRequest),
     /// MetadataRequest,
+    #[cfg(feature = "client-core")]
     MetadataRequest(MetadataRequest),
     /// LeaderAndIsrRequest,
+    #[cfg(feature = "broker-internal")]
     LeaderAndIsrRequest(LeaderAndIsrRequest),
     /// StopReplicaRequest,
+    #[cfg(feature = "broker-internal")]
     StopReplicaRequest(StopReplicaRequest),
     /// UpdateMetadataRequest,
+    #[cfg(feature = "broker-internal")]
     UpdateMetadataRequest(UpdateMetadataRequest),
     /// ControlledShutdownRequest,
+    #[cfg(feature = "broker-internal")]
     ControlledShutdownRequest(ControlledShutdownRequest),
     /// OffsetCommitRequest,
+    #[cfg(feature = "consumer-group")]
     OffsetCommitRequest(OffsetCommitRequest),
     /// OffsetFetchRequest,
+    #[cfg(feature = "consumer-group")]
     OffsetFetchRequest(OffsetFetchRequest),
     /// FindCoordinatorRequest,
+    #[cfg(feature = "client-core")]
     FindCoordinatorRequest(FindCoordinatorRequest),
     /// JoinGroupRequest,
+    #[cfg(feature = "consumer-group")]
     JoinGroupRequest(JoinGroupRequest),
     /// HeartbeatRequest,
+    #[cfg(feature = "consumer-group")]
     HeartbeatRequest(HeartbeatRequest),
     /// LeaveGroupRequest,
+    #[cfg(feature = "consumer-group")]
     LeaveGroupRequest(LeaveGroupRequest),
     /// SyncGroupRequest,
+    #[cfg(feature = "consumer-group")]
     SyncGroupRequest(SyncGroupRequest),
     /// DescribeGroupsRequest,
+    #[cfg(feature = "consumer-group")]
     DescribeGroupsRequest(DescribeGroupsRequest),
     /// ListGroupsRequest,
+    #[cfg(feature = "consumer-group")]
     ListGroupsRequest(ListGroupsRequest),
     /// SaslHandshakeRequest,
+    #[cfg(feature = "client-core")]
     SaslHandshakeRequest(SaslHandshakeRequest),
     /// ApiVersionsRequest,
     ApiVersionsRequest(ApiVersionsRequest),
     /// CreateTopicsRequest,
+    #[cfg(feature = "admin")]
     CreateTopicsRequest(CreateTopicsRequest),
     /// DeleteTopicsRequest,
+    #[cfg(feature = "admin")]
     DeleteTopicsRequest(DeleteTopicsRequest),
     /// DeleteRecordsRequest,
+    #[cfg(feature = "admin")]
     DeleteRecordsRequest(DeleteRecordsRequest),
     /// InitProducerIdRequest,
+    #[cfg(feature = "transactions")]
     InitProducerIdRequest(InitProducerIdRequest),
     /// OffsetForLeaderEpochRequest,
+    #[cfg(feature = "client-core")]
     OffsetForLeaderEpochRequest(OffsetForLeaderEpochRequest),
     /// AddPartitionsToTxnRequest,
+    #[cfg(feature = "transactions")]
     AddPartitionsToTxnRequest(AddPartitionsToTxnRequest),
     /// AddOffsetsToTxnRequest,
+    #[cfg(feature = "transactions")]
     AddOffsetsToTxnRequest(AddOffsetsToTxnRequest),
     /// EndTxnRequest,
+    #[cfg(feature = "transactions")]
     EndTxnRequest(EndTxnRequest),
     /// WriteTxnMarkersRequest,
+    #[cfg(feature = "transactions")]
     WriteTxnMarkersRequest(WriteTxnMarkersRequest),
     /// TxnOffsetCommitRequest,
+    #[cfg(feature = "transactions")]
     TxnOffsetCommitRequest(TxnOffsetCommitRequest),
     /// DescribeAclsRequest,
+    #[cfg(feature = "admin")]
     DescribeAclsRequest(DescribeAclsRequest),
     /// CreateAclsRequest,
+    #[cfg(feature = "admin")]
     CreateAclsRequest(CreateAclsRequest),
     /// DeleteAclsRequest,
+    #[cfg(feature = "admin")]
     DeleteAclsRequest(DeleteAclsRequest),
     /// DescribeConfigsRequest,
+    #[cfg(feature = "admin")]
     DescribeConfigsRequest(DescribeConfigsRequest),
     /// AlterConfigsRequest,
+    #[cfg(feature = "admin")]
     AlterConfigsRequest(AlterConfigsRequest),
     /// AlterReplicaLogDirsRequest,
+    #[cfg(feature = "admin")]
     AlterReplicaLogDirsRequest(AlterReplicaLogDirsRequest),
     /// DescribeLogDirsRequest,
+    #[cfg(feature = "admin")]
     DescribeLogDirsRequest(DescribeLogDirsRequest),
     /// SaslAuthenticateRequest,
+    #[cfg(feature = "client-core")]
     SaslAuthenticateRequest(SaslAuthenticateRequest),
     /// CreatePartitionsRequest,
+    #[cfg(feature = "admin")]
     CreatePartitionsRequest(CreatePartitionsRequest),
     /// CreateDelegationTokenRequest,
+    #[cfg(feature = "admin")]
     CreateDelegationTokenRequest(CreateDelegationTokenRequest),
     /// RenewDelegationTokenRequest,
+    #[cfg(feature = "admin")]
     RenewDelegationTokenRequest(RenewDelegationTokenRequest),
     /// ExpireDelegationTokenRequest,
+    #[cfg(feature = "admin")]
     ExpireDelegationTokenRequest(ExpireDelegationTokenRequest),
     /// DescribeDelegationTokenRequest,
+    #[cfg(feature = "admin")]
     DescribeDelegationTokenRequest(DescribeDelegationTokenRequest),
     /// DeleteGroupsRequest,
+    #[cfg(feature = "consumer-group")]
     DeleteGroupsRequest(DeleteGroupsRequest),
     /// ElectLeadersRequest,
+    #[cfg(feature = "admin")]
     ElectLeadersRequest(ElectLeadersRequest),
     /// IncrementalAlterConfigsRequest,
+    #[cfg(feature = "admin")]
     IncrementalAlterConfigsRequest(IncrementalAlterConfigsRequest),
     /// AlterPartitionReassignmentsRequest,
+    #[cfg(feature = "admin")]
     AlterPartitionReassignmentsRequest(AlterPartitionReassignmentsRequest),
     /// ListPartitionReassignmentsRequest,
+    #[cfg(feature = "admin")]
     ListPartitionReassignmentsRequest(ListPartitionReassignmentsRequest),
     /// OffsetDeleteRequest,
+    #[cfg(feature = "consumer-group")]
     OffsetDeleteRequest(OffsetDeleteRequest),
     /// DescribeClientQuotasRequest,
+    #[cfg(feature = "admin")]
     DescribeClientQuotasRequest(DescribeClientQuotasRequest),
     /// AlterClientQuotasRequest,
+    #[cfg(feature = "admin")]
     AlterClientQuotasRequest(AlterClientQuotasRequest),
     /// DescribeUserScramCredentialsRequest,
+    #[cfg(feature = "admin")]
     DescribeUserScramCredentialsRequest(DescribeUserScramCredentialsRequest),
     /// AlterUserScramCredentialsRequest,
+    #[cfg(feature = "admin")]
     AlterUserScramCredentialsRequest(AlterUserScramCredentialsRequest),
     /// VoteRequest,
+    #[cfg(feature = "raft")]
     VoteRequest(VoteRequest),
     /// BeginQuorumEpochRequest,
+    #[cfg(feature = "raft")]
     BeginQuorumEpochRequest(BeginQuorumEpochRequest),
     /// EndQuorumEpochRequest,
+    #[cfg(feature = "raft")]
     EndQuorumEpochRequest(EndQuorumEpochRequest),
     /// DescribeQuorumRequest,
+    #[cfg(feature = "raft")]
     DescribeQuorumRequest(DescribeQuorumRequest),
     /// AlterPartitionRequest,
+    #[cfg(feature = "broker-internal")]
     AlterPartitionRequest(AlterPartitionRequest),
     /// UpdateFeaturesRequest,
+    #[cfg(feature = "admin")]
     UpdateFeaturesRequest(UpdateFeaturesRequest),
     /// EnvelopeRequest,
+    #[cfg(feature = "broker-internal")]
     EnvelopeRequest(EnvelopeRequest),
     /// FetchSnapshotRequest,
+    #[cfg(feature = "raft")]
     FetchSnapshotRequest(FetchSnapshotRequest),
     /// DescribeClusterRequest,
+    #[cfg(feature = "admin")]
     DescribeClusterRequest(DescribeClusterRequest),
     /// DescribeProducersRequest,
+    #[cfg(feature = "transactions")]
     DescribeProducersRequest(DescribeProducersRequest),
     /// BrokerRegistrationRequest,
+    #[cfg(feature = "broker-internal")]
     BrokerRegistrationRequest(BrokerRegistrationRequest),
     /// BrokerHeartbeatRequest,
+    #[cfg(feature = "broker-internal")]
     BrokerHeartbeatRequest(BrokerHeartbeatRequest),
     /// UnregisterBrokerRequest,
+    #[cfg(feature = "admin")]
     UnregisterBrokerRequest(UnregisterBrokerRequest),
     /// DescribeTransactionsRequest,
+    #[cfg(feature = "transactions")]
     DescribeTransactionsRequest(DescribeTransactionsRequest),
     /// ListTransactionsRequest,
+    #[cfg(feature = "transactions")]
     ListTransactionsRequest(ListTransactionsRequest),
     /// AllocateProducerIdsRequest,
+    #[cfg(feature = "broker-internal")]
     AllocateProducerIdsRequest(AllocateProducerIdsRequest),
     /// ConsumerGroupHeartbeatRequest,
+    #[cfg(feature = "consumer-group")]
     ConsumerGroupHeartbeatRequest(ConsumerGroupHeartbeatRequest),
 }
 
 /// Wrapping enum for all responses in the Kafka protocol.
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResponseKind {
     /// ProduceResponse,
+    #[cfg(feature = "client-core")]
     ProduceResponse(ProduceResponse),
     /// FetchResponse,
+    #[cfg(feature = "client-core")]
     FetchResponse(FetchResponse),
     /// ListOffsetsResponse,
+    #[cfg(feature = "client-core")]
     ListOffsetsResponse(ListOffsetsResponse),
     /// MetadataResponse,
+    #[cfg(feature = "client-core")]
     MetadataResponse(MetadataResponse),
     /// LeaderAndIsrResponse,
+    #[cfg(feature = "broker-internal")]
     LeaderAndIsrResponse(LeaderAndIsrResponse),
     /// StopReplicaResponse,
+    #[cfg(feature = "broker-internal")]
     StopReplicaResponse(StopReplicaResponse),
     /// UpdateMetadataResponse,
+    #[cfg(feature = "broker-internal")]
     UpdateMetadataResponse(UpdateMetadataResponse),
     /// ControlledShutdownResponse,
+    #[cfg(feature = "broker-internal")]
     ControlledShutdownResponse(ControlledShutdownResponse),
     /// OffsetCommitResponse,
+    #[cfg(feature = "consumer-group")]
     OffsetCommitResponse(OffsetCommitResponse),
     /// OffsetFetchResponse,
+    #[cfg(feature = "consumer-group")]
     OffsetFetchResponse(OffsetFetchResponse),
     /// FindCoordinatorResponse,
+    #[cfg(feature = "client-core")]
     FindCoordinatorResponse(FindCoordinatorResponse),
     /// JoinGroupResponse,
+    #[cfg(feature = "consumer-group")]
     JoinGroupResponse(JoinGroupResponse),
     /// HeartbeatResponse,
+    #[cfg(feature = "consumer-group")]
     HeartbeatResponse(HeartbeatResponse),
     /// LeaveGroupResponse,
+    #[cfg(feature = "consumer-group")]
     LeaveGroupResponse(LeaveGroupResponse),
     /// SyncGroupResponse,
+    #[cfg(feature = "consumer-group")]
     SyncGroupResponse(SyncGroupResponse),
     /// DescribeGroupsResponse,
+    #[cfg(feature = "consumer-group")]
     DescribeGroupsResponse(DescribeGroupsResponse),
     /// ListGroupsResponse,
+    #[cfg(feature = "consumer-group")]
     ListGroupsResponse(ListGroupsResponse),
     /// SaslHandshakeResponse,
+    #[cfg(feature = "client-core")]
     SaslHandshakeResponse(SaslHandshakeResponse),
     /// ApiVersionsResponse,
     ApiVersionsResponse(ApiVersionsResponse),
     /// CreateTopicsResponse,
+    #[cfg(feature = "admin")]
     CreateTopicsResponse(CreateTopicsResponse),
     /// DeleteTopicsResponse,
+    #[cfg(feature = "admin")]
     DeleteTopicsResponse(DeleteTopicsResponse),
     /// DeleteRecordsResponse,
+    #[cfg(feature = "admin")]
     DeleteRecordsResponse(DeleteRecordsResponse),
     /// InitProducerIdResponse,
+    #[cfg(feature = "transactions")]
     InitProducerIdResponse(InitProducerIdResponse),
     /// OffsetForLeaderEpochResponse,
+    #[cfg(feature = "client-core")]
     OffsetForLeaderEpochResponse(OffsetForLeaderEpochResponse),
     /// AddPartitionsToTxnResponse,
+    #[cfg(feature = "transactions")]
     AddPartitionsToTxnResponse(AddPartitionsToTxnResponse),
     /// AddOffsetsToTxnResponse,
+    #[cfg(feature = "transactions")]
     AddOffsetsToTxnResponse(AddOffsetsToTxnResponse),
     /// EndTxnResponse,
+    #[cfg(feature = "transactions")]
     EndTxnResponse(EndTxnResponse),
     /// WriteTxnMarkersResponse,
+    #[cfg(feature = "transactions")]
     WriteTxnMarkersResponse(WriteTxnMarkersResponse),
     /// TxnOffsetCommitResponse,
+    #[cfg(feature = "transactions")]
     TxnOffsetCommitResponse(TxnOffsetCommitResponse),
     /// DescribeAclsResponse,
+    #[cfg(feature = "admin")]
     DescribeAclsResponse(DescribeAclsResponse),
     /// CreateAclsResponse,
+    #[cfg(feature = "admin")]
     CreateAclsResponse(CreateAclsResponse),
     /// DeleteAclsResponse,
+    #[cfg(feature = "admin")]
     DeleteAclsResponse(DeleteAclsResponse),
     /// DescribeConfigsResponse,
+    #[cfg(feature = "admin")]
     DescribeConfigsResponse(DescribeConfigsResponse),
     /// AlterConfigsResponse,
+    #[cfg(feature = "admin")]
     AlterConfigsResponse(AlterConfigsResponse),
     /// AlterReplicaLogDirsResponse,
+    #[cfg(feature = "admin")]
     AlterReplicaLogDirsResponse(AlterReplicaLogDirsResponse),
     /// DescribeLogDirsResponse,
+    #[cfg(feature = "admin")]
     DescribeLogDirsResponse(DescribeLogDirsResponse),
     /// SaslAuthenticateResponse,
+    #[cfg(feature = "client-core")]
     SaslAuthenticateResponse(SaslAuthenticateResponse),
     /// CreatePartitionsResponse,
+    #[cfg(feature = "admin")]
     CreatePartitionsResponse(CreatePartitionsResponse),
     /// CreateDelegationTokenResponse,
+    #[cfg(feature = "admin")]
     CreateDelegationTokenResponse(CreateDelegationTokenResponse),
     /// RenewDelegationTokenResponse,
+    #[cfg(feature = "admin")]
     RenewDelegationTokenResponse(RenewDelegationTokenResponse),
     /// ExpireDelegationTokenResponse,
+    #[cfg(feature = "admin")]
     ExpireDelegationTokenResponse(ExpireDelegationTokenResponse),
     /// DescribeDelegationTokenResponse,
+    #[cfg(feature = "admin")]
     DescribeDelegationTokenResponse(DescribeDelegationTokenResponse),
     /// DeleteGroupsResponse,
+    #[cfg(feature = "consumer-group")]
     DeleteGroupsResponse(DeleteGroupsResponse),
     /// ElectLeadersResponse,
+    #[cfg(feature = "admin")]
     ElectLeadersResponse(ElectLeadersResponse),
     /// IncrementalAlterConfigsResponse,
+    #[cfg(feature = "admin")]
     IncrementalAlterConfigsResponse(IncrementalAlterConfigsResponse),
     /// AlterPartitionReassignmentsResponse,
+    #[cfg(feature = "admin")]
     AlterPartitionReassignmentsResponse(AlterPartitionReassignmentsResponse),
     /// ListPartitionReassignmentsResponse,
+    #[cfg(feature = "admin")]
     ListPartitionReassignmentsResponse(ListPartitionReassignmentsResponse),
     /// OffsetDeleteResponse,
+    #[cfg(feature = "consumer-group")]
     OffsetDeleteResponse(OffsetDeleteResponse),
     /// DescribeClientQuotasResponse,
+    #[cfg(feature = "admin")]
     DescribeClientQuotasResponse(DescribeClientQuotasResponse),
     /// AlterClientQuotasResponse,
+    #[cfg(feature = "admin")]
     AlterClientQuotasResponse(AlterClientQuotasResponse),
     /// DescribeUserScramCredentialsResponse,
+    #[cfg(feature = "admin")]
     DescribeUserScramCredentialsResponse(DescribeUserScramCredentialsResponse),
     /// AlterUserScramCredentialsResponse,
+    #[cfg(feature = "admin")]
     AlterUserScramCredentialsResponse(AlterUserScramCredentialsResponse),
     /// VoteResponse,
+    #[cfg(feature = "raft")]
     VoteResponse(VoteResponse),
     /// BeginQuorumEpochResponse,
+    #[cfg(feature = "raft")]
     BeginQuorumEpochResponse(BeginQuorumEpochResponse),
     /// EndQuorumEpochResponse,
+    #[cfg(feature = "raft")]
     EndQuorumEpochResponse(EndQuorumEpochResponse),
     /// DescribeQuorumResponse,
+    #[cfg(feature = "raft")]
     DescribeQuorumResponse(DescribeQuorumResponse),
     /// AlterPartitionResponse,
+    #[cfg(feature = "broker-internal")]
     AlterPartitionResponse(AlterPartitionResponse),
     /// UpdateFeaturesResponse,
+    #[cfg(feature = "admin")]
     UpdateFeaturesResponse(UpdateFeaturesResponse),
     /// EnvelopeResponse,
+    #[cfg(feature = "broker-internal")]
     EnvelopeResponse(EnvelopeResponse),
     /// FetchSnapshotResponse,
+    #[cfg(feature = "raft")]
     FetchSnapshotResponse(FetchSnapshotResponse),
     /// DescribeClusterResponse,
+    #[cfg(feature = "admin")]
     DescribeClusterResponse(DescribeClusterResponse),
     /// DescribeProducersResponse,
+    #[cfg(feature = "transactions")]
     DescribeProducersResponse(DescribeProducersResponse),
     /// BrokerRegistrationResponse,
+    #[cfg(feature = "broker-internal")]
     BrokerRegistrationResponse(BrokerRegistrationResponse),
     /// BrokerHeartbeatResponse,
+    #[cfg(feature = "broker-internal")]
     BrokerHeartbeatResponse(BrokerHeartbeatResponse),
     /// UnregisterBrokerResponse,
+    #[cfg(feature = "admin")]
     UnregisterBrokerResponse(UnregisterBrokerResponse),
     /// DescribeTransactionsResponse,
+    #[cfg(feature = "transactions")]
     DescribeTransactionsResponse(DescribeTransactionsResponse),
     /// ListTransactionsResponse,
+    #[cfg(feature = "transactions")]
     ListTransactionsResponse(ListTransactionsResponse),
     /// AllocateProducerIdsResponse,
+    #[cfg(feature = "broker-internal")]
     AllocateProducerIdsResponse(AllocateProducerIdsResponse),
     /// ConsumerGroupHeartbeatResponse,
+    #[cfg(feature = "consumer-group")]
     ConsumerGroupHeartbeatResponse(ConsumerGroupHeartbeatResponse),
 }
 
 /// The ID of the requesting broker
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct BrokerId(pub i32);
 
 impl From<i32> for BrokerId {
@@ -1473,10 +3100,17 @@ impl std::cmp::PartialEq<BrokerId> for i32 {
     fn eq(&self, other: &BrokerId) -> bool { self == &other.0 }
 }
 impl NewType<i32> for BrokerId {}
+#[cfg(feature = "json")]
+impl crate::json::JsonValue for BrokerId {
+    fn to_json(&self) -> serde_json::Value { crate::json::JsonValue::to_json(&self.0) }
+    fn from_json(node: &serde_json::Value, about: &str) -> Result<Self, crate::json::JsonError> { <i32 as crate::json::JsonValue>::from_json(node, about).map(Self) }
+}
 
 /// The unique group identifier.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
-pub struct GroupId(pub StrBytes);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct GroupId(#[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))] pub StrBytes);
 
 impl From<StrBytes> for GroupId {
     fn from(other: StrBytes) -> Self { Self(other) }
@@ -1498,9 +3132,16 @@ impl std::cmp::PartialEq<GroupId> for StrBytes {
     fn eq(&self, other: &GroupId) -> bool { self == &other.0 }
 }
 impl NewType<StrBytes> for GroupId {}
+#[cfg(feature = "json")]
+impl crate::json::JsonValue for GroupId {
+    fn to_json(&self) -> serde_json::Value { crate::json::JsonValue::to_json(&self.0) }
+    fn from_json(node: &serde_json::Value, about: &str) -> Result<Self, crate::json::JsonError> { <StrBytes as crate::json::JsonValue>::from_json(node, about).map(Self) }
+}
 
 /// Current producer id in use by the transactional id.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ProducerId(pub i64);
 
 impl From<i64> for ProducerId {
@@ -1523,10 +3164,17 @@ impl std::cmp::PartialEq<ProducerId> for i64 {
     fn eq(&self, other: &ProducerId) -> bool { self == &other.0 }
 }
 impl NewType<i64> for ProducerId {}
+#[cfg(feature = "json")]
+impl crate::json::JsonValue for ProducerId {
+    fn to_json(&self) -> serde_json::Value { crate::json::JsonValue::to_json(&self.0) }
+    fn from_json(node: &serde_json::Value, about: &str) -> Result<Self, crate::json::JsonError> { <i64 as crate::json::JsonValue>::from_json(node, about).map(Self) }
+}
 
 /// The name of the topic.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
-pub struct TopicName(pub StrBytes);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct TopicName(#[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))] pub StrBytes);
 
 impl From<StrBytes> for TopicName {
     fn from(other: StrBytes) -> Self { Self(other) }
@@ -1548,10 +3196,17 @@ impl std::cmp::PartialEq<TopicName> for StrBytes {
     fn eq(&self, other: &TopicName) -> bool { self == &other.0 }
 }
 impl NewType<StrBytes> for TopicName {}
+#[cfg(feature = "json")]
+impl crate::json::JsonValue for TopicName {
+    fn to_json(&self) -> serde_json::Value { crate::json::JsonValue::to_json(&self.0) }
+    fn from_json(node: &serde_json::Value, about: &str) -> Result<Self, crate::json::JsonError> { <StrBytes as crate::json::JsonValue>::from_json(node, about).map(Self) }
+}
 
 /// The transactional id corresponding to the transaction.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
-pub struct TransactionalId(pub StrBytes);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct TransactionalId(#[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))] pub StrBytes);
 
 impl From<StrBytes> for TransactionalId {
     fn from(other: StrBytes) -> Self { Self(other) }
@@ -1573,4 +3228,9 @@ impl std::cmp::PartialEq<TransactionalId> for StrBytes {
     fn eq(&self, other: &TransactionalId) -> bool { self == &other.0 }
 }
 impl NewType<StrBytes> for TransactionalId {}
+#[cfg(feature = "json")]
+impl crate::json::JsonValue for TransactionalId {
+    fn to_json(&self) -> serde_json::Value { crate::json::JsonValue::to_json(&self.0) }
+    fn from_json(node: &serde_json::Value, about: &str) -> Result<Self, crate::json::JsonError> { <StrBytes as crate::json::JsonValue>::from_json(node, about).map(Self) }
+}
 
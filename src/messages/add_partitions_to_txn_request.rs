@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AddPartitionsToTxnTopic {
     /// The partition indexes to add to the transaction
@@ -28,7 +34,7 @@ pub struct AddPartitionsToTxnTopic {
     pub partitions: Vec<i32>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AddPartitionsToTxnTopic {
@@ -42,49 +48,53 @@ impl Builder for AddPartitionsToTxnTopic {
 impl MapEncodable for AddPartitionsToTxnTopic {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "name", version))?;
         }
         if version >= 3 {
-            types::CompactArray(types::Int32).encode(buf, &self.partitions)?;
+            types::CompactArray(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "partitions", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.partitions)?;
+            types::Array(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "partitions", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "name", version))?;
         }
         if version >= 3 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.partitions)?;
+            total_size += types::Array(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "partitions", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -93,24 +103,24 @@ impl MapEncodable for AddPartitionsToTxnTopic {
 impl MapDecodable for AddPartitionsToTxnTopic {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "name", version, start - buf.remaining()))?
         };
         let partitions = if version >= 3 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "partitions", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AddPartitionsToTxnTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -125,7 +135,7 @@ impl Default for AddPartitionsToTxnTopic {
     fn default() -> Self {
         Self {
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -134,9 +144,70 @@ impl Message for AddPartitionsToTxnTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl AddPartitionsToTxnTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl AddPartitionsToTxnTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("partitions".into(), JsonValue::to_json(&self.partitions));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "AddPartitionsToTxnTopic")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "AddPartitionsToTxnTopic.name")?;
+        } else {
+            return Err(crate::json::missing("AddPartitionsToTxnTopic", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = JsonValue::from_json(value, "AddPartitionsToTxnTopic.partitions")?;
+        } else {
+            return Err(crate::json::missing("AddPartitionsToTxnTopic", "partitions", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AddPartitionsToTxnTransaction {
     /// Current producer id in use by the transactional id.
@@ -160,7 +231,7 @@ pub struct AddPartitionsToTxnTransaction {
     pub topics: indexmap::IndexMap<super::TopicName, AddPartitionsToTxnTopic>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AddPartitionsToTxnTransaction {
@@ -174,99 +245,103 @@ impl Builder for AddPartitionsToTxnTransaction {
 impl MapEncodable for AddPartitionsToTxnTransaction {
     type Key = super::TransactionalId;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 4 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "transactional_id", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "transactional_id", version));
             }
         }
         if version >= 4 {
-            types::Int64.encode(buf, &self.producer_id)?;
+            types::Int64.encode(buf, &self.producer_id).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "producer_id", version))?;
         } else {
             if self.producer_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "producer_id", version));
             }
         }
         if version >= 4 {
-            types::Int16.encode(buf, &self.producer_epoch)?;
+            types::Int16.encode(buf, &self.producer_epoch).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "producer_epoch", version))?;
         } else {
             if self.producer_epoch != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "producer_epoch", version));
             }
         }
         if version >= 4 {
-            types::Boolean.encode(buf, &self.verify_only)?;
+            types::Boolean.encode(buf, &self.verify_only).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "verify_only", version))?;
         } else {
             if self.verify_only {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "verify_only", version));
             }
         }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "topics", version))?;
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "topics", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "transactional_id", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "transactional_id", version));
             }
         }
         if version >= 4 {
-            total_size += types::Int64.compute_size(&self.producer_id)?;
+            total_size += types::Int64.compute_size(&self.producer_id).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "producer_id", version))?;
         } else {
             if self.producer_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "producer_id", version));
             }
         }
         if version >= 4 {
-            total_size += types::Int16.compute_size(&self.producer_epoch)?;
+            total_size += types::Int16.compute_size(&self.producer_epoch).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "producer_epoch", version))?;
         } else {
             if self.producer_epoch != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "producer_epoch", version));
             }
         }
         if version >= 4 {
-            total_size += types::Boolean.compute_size(&self.verify_only)?;
+            total_size += types::Boolean.compute_size(&self.verify_only).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "verify_only", version))?;
         } else {
             if self.verify_only {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "verify_only", version));
             }
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "topics", version))?;
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnTransaction", "topics", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -275,39 +350,39 @@ impl MapEncodable for AddPartitionsToTxnTransaction {
 impl MapDecodable for AddPartitionsToTxnTransaction {
     type Key = super::TransactionalId;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "transactional_id", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let producer_id = if version >= 4 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "producer_id", version, start - buf.remaining()))?
         } else {
             (0).into()
         };
         let producer_epoch = if version >= 4 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "producer_epoch", version, start - buf.remaining()))?
         } else {
             0
         };
         let verify_only = if version >= 4 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "verify_only", version, start - buf.remaining()))?
         } else {
             false
         };
         let topics = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "topics", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AddPartitionsToTxnTransaction", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -328,7 +403,7 @@ impl Default for AddPartitionsToTxnTransaction {
             producer_epoch: 0,
             verify_only: false,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -337,9 +412,138 @@ impl Message for AddPartitionsToTxnTransaction {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl AddPartitionsToTxnTransaction {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 4 {
+            self.producer_id = (0).into();
+        }
+        if version < 4 {
+            self.producer_epoch = 0;
+        }
+        if version < 4 {
+            self.verify_only = false;
+        }
+        if version >= 4 {
+            for item in self.topics.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topics = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 4 {
+            if self.producer_id != 0 {
+                invalid.push(format!("{}producer_id", path));
+            }
+        }
+        if version < 4 {
+            if self.producer_epoch != 0 {
+                invalid.push(format!("{}producer_epoch", path));
+            }
+        }
+        if version < 4 {
+            if self.verify_only {
+                invalid.push(format!("{}verify_only", path));
+            }
+        }
+        if version >= 4 {
+            for (i, item) in self.topics.values().enumerate() {
+                item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topics.is_empty() {
+                invalid.push(format!("{}topics", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AddPartitionsToTxnTransaction {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TransactionalId, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TransactionalId, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 4 {
+            node.insert("transactionalId".into(), JsonValue::to_json(key));
+        }
+        if version >= 4 {
+            node.insert("producerId".into(), JsonValue::to_json(&self.producer_id));
+        }
+        if version >= 4 {
+            node.insert("producerEpoch".into(), JsonValue::to_json(&self.producer_epoch));
+        }
+        if version >= 4 {
+            node.insert("verifyOnly".into(), JsonValue::to_json(&self.verify_only));
+        }
+        if version >= 4 {
+            node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TransactionalId, Self), JsonError> {
+        let object = crate::json::object(node, "AddPartitionsToTxnTransaction")?;
+        let mut this = Self::default();
+        let mut key = super::TransactionalId::default();
+        if let Some(value) = object.get("transactionalId") {
+            key = JsonValue::from_json(value, "AddPartitionsToTxnTransaction.transactionalId")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnTransaction", "transactionalId", version));
+        }
+        if let Some(value) = object.get("producerId") {
+            this.producer_id = JsonValue::from_json(value, "AddPartitionsToTxnTransaction.producerId")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnTransaction", "producerId", version));
+        }
+        if let Some(value) = object.get("producerEpoch") {
+            this.producer_epoch = JsonValue::from_json(value, "AddPartitionsToTxnTransaction.producerEpoch")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnTransaction", "producerEpoch", version));
+        }
+        if let Some(value) = object.get("verifyOnly") {
+            this.verify_only = JsonValue::from_json(value, "AddPartitionsToTxnTransaction.verifyOnly")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnTransaction", "verifyOnly", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "AddPartitionsToTxnTransaction.topics")?.iter().map(|item| AddPartitionsToTxnTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnTransaction", "topics", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AddPartitionsToTxnRequest {
     /// List of transactions to add partitions to.
@@ -368,7 +572,7 @@ pub struct AddPartitionsToTxnRequest {
     pub v3_and_below_topics: indexmap::IndexMap<super::TopicName, AddPartitionsToTxnTopic>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AddPartitionsToTxnRequest {
@@ -381,115 +585,125 @@ impl Builder for AddPartitionsToTxnRequest {
 
 impl Encodable for AddPartitionsToTxnRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnRequest", version));
+        }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.transactions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.transactions).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "transactions", version))?;
         } else {
             if !self.transactions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "transactions", version));
             }
         }
         if version <= 3 {
             if version >= 3 {
-                types::CompactString.encode(buf, &self.v3_and_below_transactional_id)?;
+                types::CompactString.encode(buf, &self.v3_and_below_transactional_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version))?;
             } else {
-                types::String.encode(buf, &self.v3_and_below_transactional_id)?;
+                types::String.encode(buf, &self.v3_and_below_transactional_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version))?;
             }
         } else {
             if !self.v3_and_below_transactional_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version));
             }
         }
         if version <= 3 {
-            types::Int64.encode(buf, &self.v3_and_below_producer_id)?;
+            types::Int64.encode(buf, &self.v3_and_below_producer_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version))?;
         } else {
             if self.v3_and_below_producer_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version));
             }
         }
         if version <= 3 {
-            types::Int16.encode(buf, &self.v3_and_below_producer_epoch)?;
+            types::Int16.encode(buf, &self.v3_and_below_producer_epoch).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version))?;
         } else {
             if self.v3_and_below_producer_epoch != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version));
             }
         }
         if version <= 3 {
             if version >= 3 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.v3_and_below_topics)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.v3_and_below_topics).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.v3_and_below_topics)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.v3_and_below_topics).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version))?;
             }
         } else {
             if !self.v3_and_below_topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnRequest", version));
+        }
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.transactions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.transactions).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "transactions", version))?;
         } else {
             if !self.transactions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "transactions", version));
             }
         }
         if version <= 3 {
             if version >= 3 {
-                total_size += types::CompactString.compute_size(&self.v3_and_below_transactional_id)?;
+                total_size += types::CompactString.compute_size(&self.v3_and_below_transactional_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.v3_and_below_transactional_id)?;
+                total_size += types::String.compute_size(&self.v3_and_below_transactional_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version))?;
             }
         } else {
             if !self.v3_and_below_transactional_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version));
             }
         }
         if version <= 3 {
-            total_size += types::Int64.compute_size(&self.v3_and_below_producer_id)?;
+            total_size += types::Int64.compute_size(&self.v3_and_below_producer_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version))?;
         } else {
             if self.v3_and_below_producer_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version));
             }
         }
         if version <= 3 {
-            total_size += types::Int16.compute_size(&self.v3_and_below_producer_epoch)?;
+            total_size += types::Int16.compute_size(&self.v3_and_below_producer_epoch).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version))?;
         } else {
             if self.v3_and_below_producer_epoch != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version));
             }
         }
         if version <= 3 {
             if version >= 3 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.v3_and_below_topics)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.v3_and_below_topics).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.v3_and_below_topics)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.v3_and_below_topics).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version))?;
             }
         } else {
             if !self.v3_and_below_topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -497,47 +711,50 @@ impl Encodable for AddPartitionsToTxnRequest {
 
 impl Decodable for AddPartitionsToTxnRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnRequest", version));
+        }
+        let start = buf.remaining();
         let transactions = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "transactions", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let v3_and_below_transactional_id = if version <= 3 {
             if version >= 3 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let v3_and_below_producer_id = if version <= 3 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version, start - buf.remaining()))?
         } else {
             (0).into()
         };
         let v3_and_below_producer_epoch = if version <= 3 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version, start - buf.remaining()))?
         } else {
             0
         };
         let v3_and_below_topics = if version <= 3 {
             if version >= 3 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -560,7 +777,7 @@ impl Default for AddPartitionsToTxnRequest {
             v3_and_below_producer_id: (0).into(),
             v3_and_below_producer_epoch: 0,
             v3_and_below_topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -569,6 +786,294 @@ impl Message for AddPartitionsToTxnRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl AddPartitionsToTxnRequest {
+    /// Encode the message with the elements of `transactions` produced by an iterator of known length, in place of
+    /// the `transactions` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_transactions<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, transactions: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TransactionalId, AddPartitionsToTxnTransaction)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let transactions = transactions.into_iter();
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(transactions)).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "transactions", version))?;
+        } else {
+            if transactions.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "transactions", version));
+            }
+        }
+        if version <= 3 {
+            if version >= 3 {
+                types::CompactString.encode(buf, &self.v3_and_below_transactional_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version))?;
+            } else {
+                types::String.encode(buf, &self.v3_and_below_transactional_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version))?;
+            }
+        } else {
+            if !self.v3_and_below_transactional_id.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version));
+            }
+        }
+        if version <= 3 {
+            types::Int64.encode(buf, &self.v3_and_below_producer_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version))?;
+        } else {
+            if self.v3_and_below_producer_id != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version));
+            }
+        }
+        if version <= 3 {
+            types::Int16.encode(buf, &self.v3_and_below_producer_epoch).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version))?;
+        } else {
+            if self.v3_and_below_producer_epoch != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version));
+            }
+        }
+        if version <= 3 {
+            if version >= 3 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.v3_and_below_topics).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.v3_and_below_topics).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version))?;
+            }
+        } else {
+            if !self.v3_and_below_topics.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version));
+            }
+        }
+        if version >= 3 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `v3_and_below_topics` produced by an iterator of known length, in place of
+    /// the `v3_and_below_topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_v3_and_below_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, v3_and_below_topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, AddPartitionsToTxnTopic)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let v3_and_below_topics = v3_and_below_topics.into_iter();
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.transactions).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "transactions", version))?;
+        } else {
+            if !self.transactions.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "transactions", version));
+            }
+        }
+        if version <= 3 {
+            if version >= 3 {
+                types::CompactString.encode(buf, &self.v3_and_below_transactional_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version))?;
+            } else {
+                types::String.encode(buf, &self.v3_and_below_transactional_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version))?;
+            }
+        } else {
+            if !self.v3_and_below_transactional_id.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_transactional_id", version));
+            }
+        }
+        if version <= 3 {
+            types::Int64.encode(buf, &self.v3_and_below_producer_id).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version))?;
+        } else {
+            if self.v3_and_below_producer_id != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_id", version));
+            }
+        }
+        if version <= 3 {
+            types::Int16.encode(buf, &self.v3_and_below_producer_epoch).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version))?;
+        } else {
+            if self.v3_and_below_producer_epoch != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_producer_epoch", version));
+            }
+        }
+        if version <= 3 {
+            if version >= 3 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(v3_and_below_topics)).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(v3_and_below_topics)).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version))?;
+            }
+        } else {
+            if v3_and_below_topics.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnRequest", "v3_and_below_topics", version));
+            }
+        }
+        if version >= 3 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl AddPartitionsToTxnRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version >= 4 {
+            for item in self.transactions.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.transactions = Default::default();
+        }
+        if version > 3 {
+            self.v3_and_below_transactional_id = Default::default();
+        }
+        if version > 3 {
+            self.v3_and_below_producer_id = (0).into();
+        }
+        if version > 3 {
+            self.v3_and_below_producer_epoch = 0;
+        }
+        if version <= 3 {
+            for item in self.v3_and_below_topics.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.v3_and_below_topics = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version >= 4 {
+            for (i, item) in self.transactions.values().enumerate() {
+                item.validate_into(version, &format!("{}transactions[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.transactions.is_empty() {
+                invalid.push(format!("{}transactions", path));
+            }
+        }
+        if version > 3 {
+            if !self.v3_and_below_transactional_id.is_empty() {
+                invalid.push(format!("{}v3_and_below_transactional_id", path));
+            }
+        }
+        if version > 3 {
+            if self.v3_and_below_producer_id != 0 {
+                invalid.push(format!("{}v3_and_below_producer_id", path));
+            }
+        }
+        if version > 3 {
+            if self.v3_and_below_producer_epoch != 0 {
+                invalid.push(format!("{}v3_and_below_producer_epoch", path));
+            }
+        }
+        if version <= 3 {
+            for (i, item) in self.v3_and_below_topics.values().enumerate() {
+                item.validate_into(version, &format!("{}v3_and_below_topics[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.v3_and_below_topics.is_empty() {
+                invalid.push(format!("{}v3_and_below_topics", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AddPartitionsToTxnRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 4 {
+            node.insert("transactions".into(), serde_json::Value::Array(self.transactions.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        }
+        if version <= 3 {
+            node.insert("v3AndBelowTransactionalId".into(), JsonValue::to_json(&self.v3_and_below_transactional_id));
+        }
+        if version <= 3 {
+            node.insert("v3AndBelowProducerId".into(), JsonValue::to_json(&self.v3_and_below_producer_id));
+        }
+        if version <= 3 {
+            node.insert("v3AndBelowProducerEpoch".into(), JsonValue::to_json(&self.v3_and_below_producer_epoch));
+        }
+        if version <= 3 {
+            node.insert("v3AndBelowTopics".into(), serde_json::Value::Array(self.v3_and_below_topics.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AddPartitionsToTxnRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("transactions") {
+            this.transactions = crate::json::array(value, "AddPartitionsToTxnRequest.transactions")?.iter().map(|item| AddPartitionsToTxnTransaction::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnRequest", "transactions", version));
+        }
+        if let Some(value) = object.get("v3AndBelowTransactionalId") {
+            this.v3_and_below_transactional_id = JsonValue::from_json(value, "AddPartitionsToTxnRequest.v3AndBelowTransactionalId")?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("AddPartitionsToTxnRequest", "v3AndBelowTransactionalId", version));
+        }
+        if let Some(value) = object.get("v3AndBelowProducerId") {
+            this.v3_and_below_producer_id = JsonValue::from_json(value, "AddPartitionsToTxnRequest.v3AndBelowProducerId")?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("AddPartitionsToTxnRequest", "v3AndBelowProducerId", version));
+        }
+        if let Some(value) = object.get("v3AndBelowProducerEpoch") {
+            this.v3_and_below_producer_epoch = JsonValue::from_json(value, "AddPartitionsToTxnRequest.v3AndBelowProducerEpoch")?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("AddPartitionsToTxnRequest", "v3AndBelowProducerEpoch", version));
+        }
+        if let Some(value) = object.get("v3AndBelowTopics") {
+            this.v3_and_below_topics = crate::json::array(value, "AddPartitionsToTxnRequest.v3AndBelowTopics")?.iter().map(|item| AddPartitionsToTxnTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("AddPartitionsToTxnRequest", "v3AndBelowTopics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AddPartitionsToTxnRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 3 {
@@ -579,3 +1084,54 @@ impl HeaderVersion for AddPartitionsToTxnRequest {
     }
 }
 
+impl AddPartitionsToTxnRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::AddPartitionsToTxnResponse {
+        let mut response = super::AddPartitionsToTxnResponse::default();
+        if version >= 4 {
+            response.error_code = error.code();
+        }
+        if version >= 4 {
+            let mut items0 = indexmap::IndexMap::new();
+            for (key0, request0) in &self.transactions {
+                let mut response0 = super::add_partitions_to_txn_response::AddPartitionsToTxnResult::default();
+                let mut items1 = indexmap::IndexMap::new();
+                for (key1, request1) in &request0.topics {
+                    let mut response1 = super::add_partitions_to_txn_response::AddPartitionsToTxnTopicResult::default();
+                    let mut items2 = indexmap::IndexMap::new();
+                    for request2 in &request1.partitions {
+                        let mut response2 = super::add_partitions_to_txn_response::AddPartitionsToTxnPartitionResult::default();
+                        response2.partition_error_code = error.code();
+                        items2.insert(*request2, response2);
+                    }
+                    response1.results_by_partition = items2;
+                    items1.insert(key1.clone(), response1);
+                }
+                response0.topic_results = items1;
+                items0.insert(key0.clone(), response0);
+            }
+            response.results_by_transaction = items0;
+        }
+        if version <= 3 {
+            let mut items0 = indexmap::IndexMap::new();
+            for (key0, request0) in &self.v3_and_below_topics {
+                let mut response0 = super::add_partitions_to_txn_response::AddPartitionsToTxnTopicResult::default();
+                let mut items1 = indexmap::IndexMap::new();
+                for request1 in &request0.partitions {
+                    let mut response1 = super::add_partitions_to_txn_response::AddPartitionsToTxnPartitionResult::default();
+                    response1.partition_error_code = error.code();
+                    items1.insert(*request1, response1);
+                }
+                response0.results_by_partition = items1;
+                items0.insert(key0.clone(), response0);
+            }
+            response.results_by_topic_v3_and_below = items0;
+        }
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AddPartitionsToTxnTopicResult {
     /// The results for each partition
@@ -28,7 +34,7 @@ pub struct AddPartitionsToTxnTopicResult {
     pub results_by_partition: indexmap::IndexMap<i32, AddPartitionsToTxnPartitionResult>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AddPartitionsToTxnTopicResult {
@@ -42,49 +48,53 @@ impl Builder for AddPartitionsToTxnTopicResult {
 impl MapEncodable for AddPartitionsToTxnTopicResult {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "name", version))?;
         }
         if version >= 3 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.results_by_partition)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.results_by_partition).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "results_by_partition", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.results_by_partition)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.results_by_partition).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "results_by_partition", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "name", version))?;
         }
         if version >= 3 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.results_by_partition)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.results_by_partition).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "results_by_partition", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.results_by_partition)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.results_by_partition).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "results_by_partition", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -93,24 +103,24 @@ impl MapEncodable for AddPartitionsToTxnTopicResult {
 impl MapDecodable for AddPartitionsToTxnTopicResult {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "name", version, start - buf.remaining()))?
         };
         let results_by_partition = if version >= 3 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "results_by_partition", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "results_by_partition", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AddPartitionsToTxnTopicResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -125,7 +135,7 @@ impl Default for AddPartitionsToTxnTopicResult {
     fn default() -> Self {
         Self {
             results_by_partition: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -134,9 +144,76 @@ impl Message for AddPartitionsToTxnTopicResult {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl AddPartitionsToTxnTopicResult {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.results_by_partition.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.results_by_partition.values().enumerate() {
+            item.validate_into(version, &format!("{}results_by_partition[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AddPartitionsToTxnTopicResult {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("resultsByPartition".into(), serde_json::Value::Array(self.results_by_partition.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "AddPartitionsToTxnTopicResult")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "AddPartitionsToTxnTopicResult.name")?;
+        } else {
+            return Err(crate::json::missing("AddPartitionsToTxnTopicResult", "name", version));
+        }
+        if let Some(value) = object.get("resultsByPartition") {
+            this.results_by_partition = crate::json::array(value, "AddPartitionsToTxnTopicResult.resultsByPartition")?.iter().map(|item| AddPartitionsToTxnPartitionResult::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AddPartitionsToTxnTopicResult", "resultsByPartition", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AddPartitionsToTxnPartitionResult {
     /// The response error code.
@@ -145,7 +222,7 @@ pub struct AddPartitionsToTxnPartitionResult {
     pub partition_error_code: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AddPartitionsToTxnPartitionResult {
@@ -159,33 +236,37 @@ impl Builder for AddPartitionsToTxnPartitionResult {
 impl MapEncodable for AddPartitionsToTxnPartitionResult {
     type Key = i32;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, key)?;
-        types::Int16.encode(buf, &self.partition_error_code)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, key).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "partition_index", version))?;
+        types::Int16.encode(buf, &self.partition_error_code).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "partition_error_code", version))?;
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(key)?;
-        total_size += types::Int16.compute_size(&self.partition_error_code)?;
+        total_size += types::Int32.compute_size(key).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "partition_index", version))?;
+        total_size += types::Int16.compute_size(&self.partition_error_code).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "partition_error_code", version))?;
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -194,16 +275,16 @@ impl MapEncodable for AddPartitionsToTxnPartitionResult {
 impl MapDecodable for AddPartitionsToTxnPartitionResult {
     type Key = i32;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::Int32.decode(buf)?;
-        let partition_error_code = types::Int16.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let start = buf.remaining();
+        let key_field = types::Int32.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "partition_index", version, start - buf.remaining()))?;
+        let partition_error_code = types::Int16.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "partition_error_code", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AddPartitionsToTxnPartitionResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -218,7 +299,7 @@ impl Default for AddPartitionsToTxnPartitionResult {
     fn default() -> Self {
         Self {
             partition_error_code: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -227,9 +308,70 @@ impl Message for AddPartitionsToTxnPartitionResult {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl AddPartitionsToTxnPartitionResult {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl AddPartitionsToTxnPartitionResult {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &i32, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &i32, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(key));
+        node.insert("partitionErrorCode".into(), JsonValue::to_json(&self.partition_error_code));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(i32, Self), JsonError> {
+        let object = crate::json::object(node, "AddPartitionsToTxnPartitionResult")?;
+        let mut this = Self::default();
+        let mut key = i32::default();
+        if let Some(value) = object.get("partitionIndex") {
+            key = JsonValue::from_json(value, "AddPartitionsToTxnPartitionResult.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("AddPartitionsToTxnPartitionResult", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("partitionErrorCode") {
+            this.partition_error_code = JsonValue::from_json(value, "AddPartitionsToTxnPartitionResult.partitionErrorCode")?;
+        } else {
+            return Err(crate::json::missing("AddPartitionsToTxnPartitionResult", "partitionErrorCode", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AddPartitionsToTxnResult {
     /// The results for each topic.
@@ -238,7 +380,7 @@ pub struct AddPartitionsToTxnResult {
     pub topic_results: indexmap::IndexMap<super::TopicName, AddPartitionsToTxnTopicResult>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AddPartitionsToTxnResult {
@@ -252,57 +394,61 @@ impl Builder for AddPartitionsToTxnResult {
 impl MapEncodable for AddPartitionsToTxnResult {
     type Key = super::TransactionalId;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 4 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("AddPartitionsToTxnResult", "transactional_id", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResult", "transactional_id", version));
             }
         }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topic_results)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_results).map_err(|e| e.with_field("AddPartitionsToTxnResult", "topic_results", version))?;
         } else {
             if !self.topic_results.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResult", "topic_results", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("AddPartitionsToTxnResult", "transactional_id", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResult", "transactional_id", version));
             }
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topic_results)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topic_results).map_err(|e| e.with_field("AddPartitionsToTxnResult", "topic_results", version))?;
         } else {
             if !self.topic_results.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResult", "topic_results", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -311,24 +457,24 @@ impl MapEncodable for AddPartitionsToTxnResult {
 impl MapDecodable for AddPartitionsToTxnResult {
     type Key = super::TransactionalId;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResult", "transactional_id", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topic_results = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResult", "topic_results", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AddPartitionsToTxnResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -343,7 +489,7 @@ impl Default for AddPartitionsToTxnResult {
     fn default() -> Self {
         Self {
             topic_results: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -352,9 +498,90 @@ impl Message for AddPartitionsToTxnResult {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl AddPartitionsToTxnResult {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version >= 4 {
+            for item in self.topic_results.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topic_results = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version >= 4 {
+            for (i, item) in self.topic_results.values().enumerate() {
+                item.validate_into(version, &format!("{}topic_results[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topic_results.is_empty() {
+                invalid.push(format!("{}topic_results", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AddPartitionsToTxnResult {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TransactionalId, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TransactionalId, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 4 {
+            node.insert("transactionalId".into(), JsonValue::to_json(key));
+        }
+        if version >= 4 {
+            node.insert("topicResults".into(), serde_json::Value::Array(self.topic_results.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TransactionalId, Self), JsonError> {
+        let object = crate::json::object(node, "AddPartitionsToTxnResult")?;
+        let mut this = Self::default();
+        let mut key = super::TransactionalId::default();
+        if let Some(value) = object.get("transactionalId") {
+            key = JsonValue::from_json(value, "AddPartitionsToTxnResult.transactionalId")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnResult", "transactionalId", version));
+        }
+        if let Some(value) = object.get("topicResults") {
+            this.topic_results = crate::json::array(value, "AddPartitionsToTxnResult.topicResults")?.iter().map(|item| AddPartitionsToTxnTopicResult::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnResult", "topicResults", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AddPartitionsToTxnResponse {
     /// Duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -378,7 +605,7 @@ pub struct AddPartitionsToTxnResponse {
     pub results_by_topic_v3_and_below: indexmap::IndexMap<super::TopicName, AddPartitionsToTxnTopicResult>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AddPartitionsToTxnResponse {
@@ -391,73 +618,83 @@ impl Builder for AddPartitionsToTxnResponse {
 
 impl Encodable for AddPartitionsToTxnResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "throttle_time_ms", version))?;
         if version >= 4 {
-            types::Int16.encode(buf, &self.error_code)?;
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "error_code", version))?;
         }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.results_by_transaction)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.results_by_transaction).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_transaction", version))?;
         } else {
             if !self.results_by_transaction.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResponse", "results_by_transaction", version));
             }
         }
         if version <= 3 {
             if version >= 3 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.results_by_topic_v3_and_below)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.results_by_topic_v3_and_below).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.results_by_topic_v3_and_below)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.results_by_topic_v3_and_below).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version))?;
             }
         } else {
             if !self.results_by_topic_v3_and_below.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "throttle_time_ms", version))?;
         if version >= 4 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "error_code", version))?;
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.results_by_transaction)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.results_by_transaction).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_transaction", version))?;
         } else {
             if !self.results_by_transaction.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResponse", "results_by_transaction", version));
             }
         }
         if version <= 3 {
             if version >= 3 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.results_by_topic_v3_and_below)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.results_by_topic_v3_and_below).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.results_by_topic_v3_and_below)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.results_by_topic_v3_and_below).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version))?;
             }
         } else {
             if !self.results_by_topic_v3_and_below.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -465,34 +702,37 @@ impl Encodable for AddPartitionsToTxnResponse {
 
 impl Decodable for AddPartitionsToTxnResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "throttle_time_ms", version, start - buf.remaining()))?;
         let error_code = if version >= 4 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
         let results_by_transaction = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_transaction", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let results_by_topic_v3_and_below = if version <= 3 {
             if version >= 3 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -513,7 +753,7 @@ impl Default for AddPartitionsToTxnResponse {
             error_code: 0,
             results_by_transaction: Default::default(),
             results_by_topic_v3_and_below: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -522,6 +762,226 @@ impl Message for AddPartitionsToTxnResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl AddPartitionsToTxnResponse {
+    /// Encode the message with the elements of `results_by_transaction` produced by an iterator of known length, in place of
+    /// the `results_by_transaction` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_results_by_transaction<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, results_by_transaction: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TransactionalId, AddPartitionsToTxnResult)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let results_by_transaction = results_by_transaction.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "throttle_time_ms", version))?;
+        if version >= 4 {
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "error_code", version))?;
+        }
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(results_by_transaction)).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_transaction", version))?;
+        } else {
+            if results_by_transaction.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResponse", "results_by_transaction", version));
+            }
+        }
+        if version <= 3 {
+            if version >= 3 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.results_by_topic_v3_and_below).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.results_by_topic_v3_and_below).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version))?;
+            }
+        } else {
+            if !self.results_by_topic_v3_and_below.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version));
+            }
+        }
+        if version >= 3 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `results_by_topic_v3_and_below` produced by an iterator of known length, in place of
+    /// the `results_by_topic_v3_and_below` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_results_by_topic_v3_and_below<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, results_by_topic_v3_and_below: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, AddPartitionsToTxnTopicResult)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AddPartitionsToTxnResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let results_by_topic_v3_and_below = results_by_topic_v3_and_below.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "throttle_time_ms", version))?;
+        if version >= 4 {
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "error_code", version))?;
+        }
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.results_by_transaction).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_transaction", version))?;
+        } else {
+            if !self.results_by_transaction.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResponse", "results_by_transaction", version));
+            }
+        }
+        if version <= 3 {
+            if version >= 3 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(results_by_topic_v3_and_below)).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(results_by_topic_v3_and_below)).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version))?;
+            }
+        } else {
+            if results_by_topic_v3_and_below.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AddPartitionsToTxnResponse", "results_by_topic_v3_and_below", version));
+            }
+        }
+        if version >= 3 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AddPartitionsToTxnResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl AddPartitionsToTxnResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 4 {
+            self.error_code = 0;
+        }
+        if version >= 4 {
+            for item in self.results_by_transaction.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.results_by_transaction = Default::default();
+        }
+        if version <= 3 {
+            for item in self.results_by_topic_v3_and_below.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.results_by_topic_v3_and_below = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 4 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+        if version >= 4 {
+            for (i, item) in self.results_by_transaction.values().enumerate() {
+                item.validate_into(version, &format!("{}results_by_transaction[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.results_by_transaction.is_empty() {
+                invalid.push(format!("{}results_by_transaction", path));
+            }
+        }
+        if version <= 3 {
+            for (i, item) in self.results_by_topic_v3_and_below.values().enumerate() {
+                item.validate_into(version, &format!("{}results_by_topic_v3_and_below[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.results_by_topic_v3_and_below.is_empty() {
+                invalid.push(format!("{}results_by_topic_v3_and_below", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AddPartitionsToTxnResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        if version >= 4 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        if version >= 4 {
+            node.insert("resultsByTransaction".into(), serde_json::Value::Array(self.results_by_transaction.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        }
+        if version <= 3 {
+            node.insert("resultsByTopicV3AndBelow".into(), serde_json::Value::Array(self.results_by_topic_v3_and_below.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AddPartitionsToTxnResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "AddPartitionsToTxnResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("AddPartitionsToTxnResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "AddPartitionsToTxnResponse.errorCode")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("resultsByTransaction") {
+            this.results_by_transaction = crate::json::array(value, "AddPartitionsToTxnResponse.resultsByTransaction")?.iter().map(|item| AddPartitionsToTxnResult::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AddPartitionsToTxnResponse", "resultsByTransaction", version));
+        }
+        if let Some(value) = object.get("resultsByTopicV3AndBelow") {
+            this.results_by_topic_v3_and_below = crate::json::array(value, "AddPartitionsToTxnResponse.resultsByTopicV3AndBelow")?.iter().map(|item| AddPartitionsToTxnTopicResult::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("AddPartitionsToTxnResponse", "resultsByTopicV3AndBelow", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AddPartitionsToTxnResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 3 {
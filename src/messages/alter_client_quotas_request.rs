@@ -5,35 +5,44 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct EntityData {
     /// The entity type.
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub entity_type: StrBytes,
 
     /// The name of the entity, or null if the default.
     /// 
     /// Supported API versions: 0-1
+    /// May be `None` in versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub entity_name: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for EntityData {
@@ -46,49 +55,53 @@ impl Builder for EntityData {
 
 impl Encodable for EntityData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 {
-            types::CompactString.encode(buf, &self.entity_type)?;
+            types::CompactString.encode(buf, &self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
         } else {
-            types::String.encode(buf, &self.entity_type)?;
+            types::String.encode(buf, &self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
         }
         if version >= 1 {
-            types::CompactString.encode(buf, &self.entity_name)?;
+            types::CompactString.encode(buf, &self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
         } else {
-            types::String.encode(buf, &self.entity_name)?;
+            types::String.encode(buf, &self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntityData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::CompactString.compute_size(&self.entity_type)?;
+            total_size += types::CompactString.compute_size(&self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.entity_type)?;
+            total_size += types::String.compute_size(&self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
         }
         if version >= 1 {
-            total_size += types::CompactString.compute_size(&self.entity_name)?;
+            total_size += types::CompactString.compute_size(&self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.entity_name)?;
+            total_size += types::String.compute_size(&self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntityData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -96,24 +109,24 @@ impl Encodable for EntityData {
 
 impl Decodable for EntityData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let entity_type = if version >= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("EntityData", "entity_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("EntityData", "entity_type", version, start - buf.remaining()))?
         };
         let entity_name = if version >= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("EntityData", "entity_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("EntityData", "entity_name", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -130,7 +143,7 @@ impl Default for EntityData {
         Self {
             entity_type: Default::default(),
             entity_name: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -139,14 +152,75 @@ impl Message for EntityData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl EntityData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl EntityData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("entityType".into(), JsonValue::to_json(&self.entity_type));
+        node.insert("entityName".into(), JsonValue::to_json(&self.entity_name));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "EntityData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("entityType") {
+            this.entity_type = JsonValue::from_json(value, "EntityData.entityType")?;
+        } else {
+            return Err(crate::json::missing("EntityData", "entityType", version));
+        }
+        if let Some(value) = object.get("entityName") {
+            this.entity_name = JsonValue::from_json(value, "EntityData.entityName")?;
+        } else {
+            return Err(crate::json::missing("EntityData", "entityName", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OpData {
     /// The quota configuration key.
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub key: StrBytes,
 
     /// The value to set, otherwise ignored if the value is to be removed.
@@ -160,7 +234,7 @@ pub struct OpData {
     pub remove: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OpData {
@@ -173,43 +247,47 @@ impl Builder for OpData {
 
 impl Encodable for OpData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 {
-            types::CompactString.encode(buf, &self.key)?;
+            types::CompactString.encode(buf, &self.key).map_err(|e| e.with_field("OpData", "key", version))?;
         } else {
-            types::String.encode(buf, &self.key)?;
+            types::String.encode(buf, &self.key).map_err(|e| e.with_field("OpData", "key", version))?;
         }
-        types::Float64.encode(buf, &self.value)?;
-        types::Boolean.encode(buf, &self.remove)?;
+        types::Float64.encode(buf, &self.value).map_err(|e| e.with_field("OpData", "value", version))?;
+        types::Boolean.encode(buf, &self.remove).map_err(|e| e.with_field("OpData", "remove", version))?;
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OpData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OpData", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OpData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::CompactString.compute_size(&self.key)?;
+            total_size += types::CompactString.compute_size(&self.key).map_err(|e| e.with_field("OpData", "key", version))?;
         } else {
-            total_size += types::String.compute_size(&self.key)?;
+            total_size += types::String.compute_size(&self.key).map_err(|e| e.with_field("OpData", "key", version))?;
         }
-        total_size += types::Float64.compute_size(&self.value)?;
-        total_size += types::Boolean.compute_size(&self.remove)?;
+        total_size += types::Float64.compute_size(&self.value).map_err(|e| e.with_field("OpData", "value", version))?;
+        total_size += types::Boolean.compute_size(&self.remove).map_err(|e| e.with_field("OpData", "remove", version))?;
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OpData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OpData", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OpData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -217,21 +295,21 @@ impl Encodable for OpData {
 
 impl Decodable for OpData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let key = if version >= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OpData", "key", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("OpData", "key", version, start - buf.remaining()))?
         };
-        let value = types::Float64.decode(buf)?;
-        let remove = types::Boolean.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let value = types::Float64.decode(buf).map_err(|e| e.with_field("OpData", "value", version, start - buf.remaining()))?;
+        let remove = types::Boolean.decode(buf).map_err(|e| e.with_field("OpData", "remove", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OpData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OpData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OpData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OpData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -250,7 +328,7 @@ impl Default for OpData {
             key: Default::default(),
             value: 0.0,
             remove: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -259,9 +337,75 @@ impl Message for OpData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl OpData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl OpData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("key".into(), JsonValue::to_json(&self.key));
+        node.insert("value".into(), JsonValue::to_json(&self.value));
+        node.insert("remove".into(), JsonValue::to_json(&self.remove));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OpData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("key") {
+            this.key = JsonValue::from_json(value, "OpData.key")?;
+        } else {
+            return Err(crate::json::missing("OpData", "key", version));
+        }
+        if let Some(value) = object.get("value") {
+            this.value = JsonValue::from_json(value, "OpData.value")?;
+        } else {
+            return Err(crate::json::missing("OpData", "value", version));
+        }
+        if let Some(value) = object.get("remove") {
+            this.remove = JsonValue::from_json(value, "OpData.remove")?;
+        } else {
+            return Err(crate::json::missing("OpData", "remove", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct EntryData {
     /// The quota entity to alter.
@@ -275,7 +419,7 @@ pub struct EntryData {
     pub ops: Vec<OpData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for EntryData {
@@ -288,49 +432,53 @@ impl Builder for EntryData {
 
 impl Encodable for EntryData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.entity)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.entity).map_err(|e| e.with_field("EntryData", "entity", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.entity)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.entity).map_err(|e| e.with_field("EntryData", "entity", version))?;
         }
         if version >= 1 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.ops)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.ops).map_err(|e| e.with_field("EntryData", "ops", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.ops)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ops).map_err(|e| e.with_field("EntryData", "ops", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntryData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.entity)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.entity).map_err(|e| e.with_field("EntryData", "entity", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.entity)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.entity).map_err(|e| e.with_field("EntryData", "entity", version))?;
         }
         if version >= 1 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.ops)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.ops).map_err(|e| e.with_field("EntryData", "ops", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.ops)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.ops).map_err(|e| e.with_field("EntryData", "ops", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntryData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -338,24 +486,24 @@ impl Encodable for EntryData {
 
 impl Decodable for EntryData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let entity = if version >= 1 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("EntryData", "entity", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("EntryData", "entity", version, start - buf.remaining()))?
         };
         let ops = if version >= 1 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("EntryData", "ops", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("EntryData", "ops", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -372,7 +520,7 @@ impl Default for EntryData {
         Self {
             entity: Default::default(),
             ops: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -381,9 +529,81 @@ impl Message for EntryData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl EntryData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.entity.iter_mut() {
+            item.normalize_to(version);
+        }
+        for item in self.ops.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.entity.iter().enumerate() {
+            item.validate_into(version, &format!("{}entity[{}].", path, i), invalid);
+        }
+        for (i, item) in self.ops.iter().enumerate() {
+            item.validate_into(version, &format!("{}ops[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl EntryData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("entity".into(), serde_json::Value::Array(self.entity.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("ops".into(), serde_json::Value::Array(self.ops.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "EntryData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("entity") {
+            this.entity = crate::json::array(value, "EntryData.entity")?.iter().map(|item| EntityData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("EntryData", "entity", version));
+        }
+        if let Some(value) = object.get("ops") {
+            this.ops = crate::json::array(value, "EntryData.ops")?.iter().map(|item| OpData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("EntryData", "ops", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterClientQuotasRequest {
     /// The quota configuration entries to alter.
@@ -397,7 +617,7 @@ pub struct AlterClientQuotasRequest {
     pub validate_only: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterClientQuotasRequest {
@@ -410,41 +630,51 @@ impl Builder for AlterClientQuotasRequest {
 
 impl Encodable for AlterClientQuotasRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterClientQuotasRequest", version));
+        }
         if version >= 1 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.entries)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.entries).map_err(|e| e.with_field("AlterClientQuotasRequest", "entries", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.entries)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.entries).map_err(|e| e.with_field("AlterClientQuotasRequest", "entries", version))?;
         }
-        types::Boolean.encode(buf, &self.validate_only)?;
+        types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("AlterClientQuotasRequest", "validate_only", version))?;
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterClientQuotasRequest", version));
+        }
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.entries)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.entries).map_err(|e| e.with_field("AlterClientQuotasRequest", "entries", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.entries)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.entries).map_err(|e| e.with_field("AlterClientQuotasRequest", "entries", version))?;
         }
-        total_size += types::Boolean.compute_size(&self.validate_only)?;
+        total_size += types::Boolean.compute_size(&self.validate_only).map_err(|e| e.with_field("AlterClientQuotasRequest", "validate_only", version))?;
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -452,20 +682,23 @@ impl Encodable for AlterClientQuotasRequest {
 
 impl Decodable for AlterClientQuotasRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterClientQuotasRequest", version));
+        }
+        let start = buf.remaining();
         let entries = if version >= 1 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterClientQuotasRequest", "entries", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterClientQuotasRequest", "entries", version, start - buf.remaining()))?
         };
-        let validate_only = types::Boolean.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let validate_only = types::Boolean.decode(buf).map_err(|e| e.with_field("AlterClientQuotasRequest", "validate_only", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -482,7 +715,7 @@ impl Default for AlterClientQuotasRequest {
         Self {
             entries: Default::default(),
             validate_only: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -491,6 +724,107 @@ impl Message for AlterClientQuotasRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl AlterClientQuotasRequest {
+    /// Encode the message with the elements of `entries` produced by an iterator of known length, in place of
+    /// the `entries` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_entries<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, entries: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = EntryData>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterClientQuotasRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let entries = entries.into_iter();
+        if version >= 1 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(entries)).map_err(|e| e.with_field("AlterClientQuotasRequest", "entries", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(entries)).map_err(|e| e.with_field("AlterClientQuotasRequest", "entries", version))?;
+        }
+        types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("AlterClientQuotasRequest", "validate_only", version))?;
+        if version >= 1 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterClientQuotasRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl AlterClientQuotasRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.entries.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.entries.iter().enumerate() {
+            item.validate_into(version, &format!("{}entries[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterClientQuotasRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("entries".into(), serde_json::Value::Array(self.entries.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("validateOnly".into(), JsonValue::to_json(&self.validate_only));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterClientQuotasRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("entries") {
+            this.entries = crate::json::array(value, "AlterClientQuotasRequest.entries")?.iter().map(|item| EntryData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterClientQuotasRequest", "entries", version));
+        }
+        if let Some(value) = object.get("validateOnly") {
+            this.validate_only = JsonValue::from_json(value, "AlterClientQuotasRequest.validateOnly")?;
+        } else {
+            return Err(crate::json::missing("AlterClientQuotasRequest", "validateOnly", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterClientQuotasRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 1 {
@@ -501,3 +835,15 @@ impl HeaderVersion for AlterClientQuotasRequest {
     }
 }
 
+impl AlterClientQuotasRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::AlterClientQuotasResponse {
+        let mut response = super::AlterClientQuotasResponse::default();
+        response
+    }
+}
+
@@ -5,35 +5,44 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct EntityData {
     /// The entity type.
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub entity_type: StrBytes,
 
     /// The name of the entity, or null if the default.
     /// 
     /// Supported API versions: 0-1
+    /// May be `None` in versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub entity_name: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for EntityData {
@@ -46,49 +55,53 @@ impl Builder for EntityData {
 
 impl Encodable for EntityData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 {
-            types::CompactString.encode(buf, &self.entity_type)?;
+            types::CompactString.encode(buf, &self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
         } else {
-            types::String.encode(buf, &self.entity_type)?;
+            types::String.encode(buf, &self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
         }
         if version >= 1 {
-            types::CompactString.encode(buf, &self.entity_name)?;
+            types::CompactString.encode(buf, &self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
         } else {
-            types::String.encode(buf, &self.entity_name)?;
+            types::String.encode(buf, &self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntityData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::CompactString.compute_size(&self.entity_type)?;
+            total_size += types::CompactString.compute_size(&self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.entity_type)?;
+            total_size += types::String.compute_size(&self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
         }
         if version >= 1 {
-            total_size += types::CompactString.compute_size(&self.entity_name)?;
+            total_size += types::CompactString.compute_size(&self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.entity_name)?;
+            total_size += types::String.compute_size(&self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntityData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -96,24 +109,24 @@ impl Encodable for EntityData {
 
 impl Decodable for EntityData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let entity_type = if version >= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("EntityData", "entity_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("EntityData", "entity_type", version, start - buf.remaining()))?
         };
         let entity_name = if version >= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("EntityData", "entity_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("EntityData", "entity_name", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -130,7 +143,7 @@ impl Default for EntityData {
         Self {
             entity_type: Default::default(),
             entity_name: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -139,9 +152,69 @@ impl Message for EntityData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl EntityData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl EntityData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("entityType".into(), JsonValue::to_json(&self.entity_type));
+        node.insert("entityName".into(), JsonValue::to_json(&self.entity_name));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "EntityData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("entityType") {
+            this.entity_type = JsonValue::from_json(value, "EntityData.entityType")?;
+        } else {
+            return Err(crate::json::missing("EntityData", "entityType", version));
+        }
+        if let Some(value) = object.get("entityName") {
+            this.entity_name = JsonValue::from_json(value, "EntityData.entityName")?;
+        } else {
+            return Err(crate::json::missing("EntityData", "entityName", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct EntryData {
     /// The error code, or `0` if the quota alteration succeeded.
@@ -152,6 +225,8 @@ pub struct EntryData {
     /// The error message, or `null` if the quota alteration succeeded.
     /// 
     /// Supported API versions: 0-1
+    /// May be `None` in versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The quota entity to alter.
@@ -160,7 +235,7 @@ pub struct EntryData {
     pub entity: Vec<EntityData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for EntryData {
@@ -173,51 +248,55 @@ impl Builder for EntryData {
 
 impl Encodable for EntryData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("EntryData", "error_code", version))?;
         if version >= 1 {
-            types::CompactString.encode(buf, &self.error_message)?;
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("EntryData", "error_message", version))?;
         } else {
-            types::String.encode(buf, &self.error_message)?;
+            types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("EntryData", "error_message", version))?;
         }
         if version >= 1 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.entity)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.entity).map_err(|e| e.with_field("EntryData", "entity", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.entity)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.entity).map_err(|e| e.with_field("EntryData", "entity", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntryData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("EntryData", "error_code", version))?;
         if version >= 1 {
-            total_size += types::CompactString.compute_size(&self.error_message)?;
+            total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("EntryData", "error_message", version))?;
         } else {
-            total_size += types::String.compute_size(&self.error_message)?;
+            total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("EntryData", "error_message", version))?;
         }
         if version >= 1 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.entity)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.entity).map_err(|e| e.with_field("EntryData", "entity", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.entity)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.entity).map_err(|e| e.with_field("EntryData", "entity", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntryData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -225,25 +304,25 @@ impl Encodable for EntryData {
 
 impl Decodable for EntryData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("EntryData", "error_code", version, start - buf.remaining()))?;
         let error_message = if version >= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("EntryData", "error_message", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("EntryData", "error_message", version, start - buf.remaining()))?
         };
         let entity = if version >= 1 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("EntryData", "entity", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("EntryData", "entity", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("EntryData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -262,7 +341,7 @@ impl Default for EntryData {
             error_code: 0,
             error_message: Some(Default::default()),
             entity: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -271,9 +350,81 @@ impl Message for EntryData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl EntryData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.entity.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.entity.iter().enumerate() {
+            item.validate_into(version, &format!("{}entity[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl EntryData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("entity".into(), serde_json::Value::Array(self.entity.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "EntryData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "EntryData.errorCode")?;
+        } else {
+            return Err(crate::json::missing("EntryData", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "EntryData.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("EntryData", "errorMessage", version));
+        }
+        if let Some(value) = object.get("entity") {
+            this.entity = crate::json::array(value, "EntryData.entity")?.iter().map(|item| EntityData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("EntryData", "entity", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterClientQuotasResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -287,7 +438,7 @@ pub struct AlterClientQuotasResponse {
     pub entries: Vec<EntryData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterClientQuotasResponse {
@@ -300,41 +451,51 @@ impl Builder for AlterClientQuotasResponse {
 
 impl Encodable for AlterClientQuotasResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterClientQuotasResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AlterClientQuotasResponse", "throttle_time_ms", version))?;
         if version >= 1 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.entries)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.entries).map_err(|e| e.with_field("AlterClientQuotasResponse", "entries", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.entries)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.entries).map_err(|e| e.with_field("AlterClientQuotasResponse", "entries", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterClientQuotasResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("AlterClientQuotasResponse", "throttle_time_ms", version))?;
         if version >= 1 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.entries)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.entries).map_err(|e| e.with_field("AlterClientQuotasResponse", "entries", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.entries)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.entries).map_err(|e| e.with_field("AlterClientQuotasResponse", "entries", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -342,20 +503,23 @@ impl Encodable for AlterClientQuotasResponse {
 
 impl Decodable for AlterClientQuotasResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterClientQuotasResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("AlterClientQuotasResponse", "throttle_time_ms", version, start - buf.remaining()))?;
         let entries = if version >= 1 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterClientQuotasResponse", "entries", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterClientQuotasResponse", "entries", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -372,7 +536,7 @@ impl Default for AlterClientQuotasResponse {
         Self {
             throttle_time_ms: 0,
             entries: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -381,6 +545,107 @@ impl Message for AlterClientQuotasResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl AlterClientQuotasResponse {
+    /// Encode the message with the elements of `entries` produced by an iterator of known length, in place of
+    /// the `entries` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_entries<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, entries: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = EntryData>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterClientQuotasResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let entries = entries.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AlterClientQuotasResponse", "throttle_time_ms", version))?;
+        if version >= 1 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(entries)).map_err(|e| e.with_field("AlterClientQuotasResponse", "entries", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(entries)).map_err(|e| e.with_field("AlterClientQuotasResponse", "entries", version))?;
+        }
+        if version >= 1 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterClientQuotasResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl AlterClientQuotasResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.entries.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.entries.iter().enumerate() {
+            item.validate_into(version, &format!("{}entries[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterClientQuotasResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("entries".into(), serde_json::Value::Array(self.entries.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterClientQuotasResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "AlterClientQuotasResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("AlterClientQuotasResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("entries") {
+            this.entries = crate::json::array(value, "AlterClientQuotasResponse.entries")?.iter().map(|item| EntryData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterClientQuotasResponse", "entries", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterClientQuotasResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 1 {
@@ -5,30 +5,38 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterableConfig {
     /// The value to set for the configuration key.
     /// 
     /// Supported API versions: 0-2
+    /// May be `None` in versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub value: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterableConfig {
@@ -42,49 +50,53 @@ impl Builder for AlterableConfig {
 impl MapEncodable for AlterableConfig {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("AlterableConfig", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("AlterableConfig", "name", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.value)?;
+            types::CompactString.encode(buf, &self.value).map_err(|e| e.with_field("AlterableConfig", "value", version))?;
         } else {
-            types::String.encode(buf, &self.value)?;
+            types::String.encode(buf, &self.value).map_err(|e| e.with_field("AlterableConfig", "value", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterableConfig", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterableConfig", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterableConfig", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("AlterableConfig", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("AlterableConfig", "name", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.value)?;
+            total_size += types::CompactString.compute_size(&self.value).map_err(|e| e.with_field("AlterableConfig", "value", version))?;
         } else {
-            total_size += types::String.compute_size(&self.value)?;
+            total_size += types::String.compute_size(&self.value).map_err(|e| e.with_field("AlterableConfig", "value", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterableConfig", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterableConfig", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterableConfig", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -93,24 +105,24 @@ impl MapEncodable for AlterableConfig {
 impl MapDecodable for AlterableConfig {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AlterableConfig", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AlterableConfig", "name", version, start - buf.remaining()))?
         };
         let value = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AlterableConfig", "value", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AlterableConfig", "value", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterableConfig", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterableConfig", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterableConfig", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterableConfig", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -125,7 +137,7 @@ impl Default for AlterableConfig {
     fn default() -> Self {
         Self {
             value: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -134,9 +146,70 @@ impl Message for AlterableConfig {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl AlterableConfig {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterableConfig {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("value".into(), JsonValue::to_json(&self.value));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "AlterableConfig")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "AlterableConfig.name")?;
+        } else {
+            return Err(crate::json::missing("AlterableConfig", "name", version));
+        }
+        if let Some(value) = object.get("value") {
+            this.value = JsonValue::from_json(value, "AlterableConfig.value")?;
+        } else {
+            return Err(crate::json::missing("AlterableConfig", "value", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterConfigsResource {
     /// The resource type.
@@ -147,15 +220,17 @@ pub struct AlterConfigsResource {
     /// The resource name.
     /// 
     /// Supported API versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub resource_name: StrBytes,
 
     /// The configurations.
     /// 
     /// Supported API versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub configs: indexmap::IndexMap<StrBytes, AlterableConfig>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterConfigsResource {
@@ -168,51 +243,55 @@ impl Builder for AlterConfigsResource {
 
 impl Encodable for AlterConfigsResource {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int8.encode(buf, &self.resource_type)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int8.encode(buf, &self.resource_type).map_err(|e| e.with_field("AlterConfigsResource", "resource_type", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.resource_name)?;
+            types::CompactString.encode(buf, &self.resource_name).map_err(|e| e.with_field("AlterConfigsResource", "resource_name", version))?;
         } else {
-            types::String.encode(buf, &self.resource_name)?;
+            types::String.encode(buf, &self.resource_name).map_err(|e| e.with_field("AlterConfigsResource", "resource_name", version))?;
         }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.configs)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.configs).map_err(|e| e.with_field("AlterConfigsResource", "configs", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.configs)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.configs).map_err(|e| e.with_field("AlterConfigsResource", "configs", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsResource", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsResource", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsResource", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int8.compute_size(&self.resource_type)?;
+        total_size += types::Int8.compute_size(&self.resource_type).map_err(|e| e.with_field("AlterConfigsResource", "resource_type", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.resource_name)?;
+            total_size += types::CompactString.compute_size(&self.resource_name).map_err(|e| e.with_field("AlterConfigsResource", "resource_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.resource_name)?;
+            total_size += types::String.compute_size(&self.resource_name).map_err(|e| e.with_field("AlterConfigsResource", "resource_name", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.configs)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.configs).map_err(|e| e.with_field("AlterConfigsResource", "configs", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.configs)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.configs).map_err(|e| e.with_field("AlterConfigsResource", "configs", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsResource", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsResource", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsResource", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -220,25 +299,25 @@ impl Encodable for AlterConfigsResource {
 
 impl Decodable for AlterConfigsResource {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let resource_type = types::Int8.decode(buf)?;
+        let start = buf.remaining();
+        let resource_type = types::Int8.decode(buf).map_err(|e| e.with_field("AlterConfigsResource", "resource_type", version, start - buf.remaining()))?;
         let resource_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AlterConfigsResource", "resource_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AlterConfigsResource", "resource_name", version, start - buf.remaining()))?
         };
         let configs = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterConfigsResource", "configs", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterConfigsResource", "configs", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterConfigsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -257,7 +336,7 @@ impl Default for AlterConfigsResource {
             resource_type: 0,
             resource_name: Default::default(),
             configs: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -266,9 +345,81 @@ impl Message for AlterConfigsResource {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl AlterConfigsResource {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.configs.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.configs.values().enumerate() {
+            item.validate_into(version, &format!("{}configs[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterConfigsResource {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("resourceType".into(), JsonValue::to_json(&self.resource_type));
+        node.insert("resourceName".into(), JsonValue::to_json(&self.resource_name));
+        node.insert("configs".into(), serde_json::Value::Array(self.configs.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterConfigsResource")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("resourceType") {
+            this.resource_type = JsonValue::from_json(value, "AlterConfigsResource.resourceType")?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResource", "resourceType", version));
+        }
+        if let Some(value) = object.get("resourceName") {
+            this.resource_name = JsonValue::from_json(value, "AlterConfigsResource.resourceName")?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResource", "resourceName", version));
+        }
+        if let Some(value) = object.get("configs") {
+            this.configs = crate::json::array(value, "AlterConfigsResource.configs")?.iter().map(|item| AlterableConfig::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResource", "configs", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterConfigsRequest {
     /// The updates for each resource.
@@ -282,7 +433,7 @@ pub struct AlterConfigsRequest {
     pub validate_only: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterConfigsRequest {
@@ -295,41 +446,51 @@ impl Builder for AlterConfigsRequest {
 
 impl Encodable for AlterConfigsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterConfigsRequest", version));
+        }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.resources)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.resources).map_err(|e| e.with_field("AlterConfigsRequest", "resources", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.resources)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.resources).map_err(|e| e.with_field("AlterConfigsRequest", "resources", version))?;
         }
-        types::Boolean.encode(buf, &self.validate_only)?;
+        types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("AlterConfigsRequest", "validate_only", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterConfigsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.resources)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.resources).map_err(|e| e.with_field("AlterConfigsRequest", "resources", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.resources)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.resources).map_err(|e| e.with_field("AlterConfigsRequest", "resources", version))?;
         }
-        total_size += types::Boolean.compute_size(&self.validate_only)?;
+        total_size += types::Boolean.compute_size(&self.validate_only).map_err(|e| e.with_field("AlterConfigsRequest", "validate_only", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -337,20 +498,23 @@ impl Encodable for AlterConfigsRequest {
 
 impl Decodable for AlterConfigsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterConfigsRequest", version));
+        }
+        let start = buf.remaining();
         let resources = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterConfigsRequest", "resources", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterConfigsRequest", "resources", version, start - buf.remaining()))?
         };
-        let validate_only = types::Boolean.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let validate_only = types::Boolean.decode(buf).map_err(|e| e.with_field("AlterConfigsRequest", "validate_only", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -367,7 +531,7 @@ impl Default for AlterConfigsRequest {
         Self {
             resources: Default::default(),
             validate_only: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -376,6 +540,107 @@ impl Message for AlterConfigsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl AlterConfigsRequest {
+    /// Encode the message with the elements of `resources` produced by an iterator of known length, in place of
+    /// the `resources` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_resources<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, resources: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = AlterConfigsResource>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterConfigsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let resources = resources.into_iter();
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(resources)).map_err(|e| e.with_field("AlterConfigsRequest", "resources", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(resources)).map_err(|e| e.with_field("AlterConfigsRequest", "resources", version))?;
+        }
+        types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("AlterConfigsRequest", "validate_only", version))?;
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl AlterConfigsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.resources.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.resources.iter().enumerate() {
+            item.validate_into(version, &format!("{}resources[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterConfigsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("resources".into(), serde_json::Value::Array(self.resources.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("validateOnly".into(), JsonValue::to_json(&self.validate_only));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterConfigsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("resources") {
+            this.resources = crate::json::array(value, "AlterConfigsRequest.resources")?.iter().map(|item| AlterConfigsResource::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsRequest", "resources", version));
+        }
+        if let Some(value) = object.get("validateOnly") {
+            this.validate_only = JsonValue::from_json(value, "AlterConfigsRequest.validateOnly")?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsRequest", "validateOnly", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterConfigsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -386,3 +651,24 @@ impl HeaderVersion for AlterConfigsRequest {
     }
 }
 
+impl AlterConfigsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::AlterConfigsResponse {
+        let mut response = super::AlterConfigsResponse::default();
+        let mut items0 = Vec::new();
+        for request0 in &self.resources {
+            let mut response0 = super::alter_configs_response::AlterConfigsResourceResponse::default();
+            response0.error_code = error.code();
+            response0.resource_type = request0.resource_type;
+            response0.resource_name = request0.resource_name.clone();
+            items0.push(response0);
+        }
+        response.responses = items0;
+        response
+    }
+}
+
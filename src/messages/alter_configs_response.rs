@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterConfigsResourceResponse {
     /// The resource error code.
@@ -30,6 +36,8 @@ pub struct AlterConfigsResourceResponse {
     /// The resource error message, or null if there was no error.
     /// 
     /// Supported API versions: 0-2
+    /// May be `None` in versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The resource type.
@@ -40,10 +48,11 @@ pub struct AlterConfigsResourceResponse {
     /// The resource name.
     /// 
     /// Supported API versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub resource_name: StrBytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterConfigsResourceResponse {
@@ -56,53 +65,57 @@ impl Builder for AlterConfigsResourceResponse {
 
 impl Encodable for AlterConfigsResourceResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_code", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.error_message)?;
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_message", version))?;
         } else {
-            types::String.encode(buf, &self.error_message)?;
+            types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_message", version))?;
         }
-        types::Int8.encode(buf, &self.resource_type)?;
+        types::Int8.encode(buf, &self.resource_type).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_type", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.resource_name)?;
+            types::CompactString.encode(buf, &self.resource_name).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_name", version))?;
         } else {
-            types::String.encode(buf, &self.resource_name)?;
+            types::String.encode(buf, &self.resource_name).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_name", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_code", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.error_message)?;
+            total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_message", version))?;
         } else {
-            total_size += types::String.compute_size(&self.error_message)?;
+            total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_message", version))?;
         }
-        total_size += types::Int8.compute_size(&self.resource_type)?;
+        total_size += types::Int8.compute_size(&self.resource_type).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_type", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.resource_name)?;
+            total_size += types::CompactString.compute_size(&self.resource_name).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.resource_name)?;
+            total_size += types::String.compute_size(&self.resource_name).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_name", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -110,26 +123,26 @@ impl Encodable for AlterConfigsResourceResponse {
 
 impl Decodable for AlterConfigsResourceResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_code", version, start - buf.remaining()))?;
         let error_message = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_message", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "error_message", version, start - buf.remaining()))?
         };
-        let resource_type = types::Int8.decode(buf)?;
+        let resource_type = types::Int8.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_type", version, start - buf.remaining()))?;
         let resource_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "resource_name", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterConfigsResourceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -150,7 +163,7 @@ impl Default for AlterConfigsResourceResponse {
             error_message: Some(Default::default()),
             resource_type: 0,
             resource_name: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -159,9 +172,81 @@ impl Message for AlterConfigsResourceResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl AlterConfigsResourceResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterConfigsResourceResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("resourceType".into(), JsonValue::to_json(&self.resource_type));
+        node.insert("resourceName".into(), JsonValue::to_json(&self.resource_name));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterConfigsResourceResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "AlterConfigsResourceResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResourceResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "AlterConfigsResourceResponse.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResourceResponse", "errorMessage", version));
+        }
+        if let Some(value) = object.get("resourceType") {
+            this.resource_type = JsonValue::from_json(value, "AlterConfigsResourceResponse.resourceType")?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResourceResponse", "resourceType", version));
+        }
+        if let Some(value) = object.get("resourceName") {
+            this.resource_name = JsonValue::from_json(value, "AlterConfigsResourceResponse.resourceName")?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResourceResponse", "resourceName", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterConfigsResponse {
     /// Duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -175,7 +260,7 @@ pub struct AlterConfigsResponse {
     pub responses: Vec<AlterConfigsResourceResponse>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterConfigsResponse {
@@ -188,41 +273,51 @@ impl Builder for AlterConfigsResponse {
 
 impl Encodable for AlterConfigsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterConfigsResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AlterConfigsResponse", "throttle_time_ms", version))?;
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.responses)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.responses).map_err(|e| e.with_field("AlterConfigsResponse", "responses", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.responses)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.responses).map_err(|e| e.with_field("AlterConfigsResponse", "responses", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterConfigsResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("AlterConfigsResponse", "throttle_time_ms", version))?;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.responses)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.responses).map_err(|e| e.with_field("AlterConfigsResponse", "responses", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.responses)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.responses).map_err(|e| e.with_field("AlterConfigsResponse", "responses", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -230,20 +325,23 @@ impl Encodable for AlterConfigsResponse {
 
 impl Decodable for AlterConfigsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterConfigsResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("AlterConfigsResponse", "throttle_time_ms", version, start - buf.remaining()))?;
         let responses = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterConfigsResponse", "responses", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterConfigsResponse", "responses", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -260,7 +358,7 @@ impl Default for AlterConfigsResponse {
         Self {
             throttle_time_ms: 0,
             responses: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -269,6 +367,107 @@ impl Message for AlterConfigsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl AlterConfigsResponse {
+    /// Encode the message with the elements of `responses` produced by an iterator of known length, in place of
+    /// the `responses` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_responses<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, responses: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = AlterConfigsResourceResponse>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterConfigsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let responses = responses.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AlterConfigsResponse", "throttle_time_ms", version))?;
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(responses)).map_err(|e| e.with_field("AlterConfigsResponse", "responses", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(responses)).map_err(|e| e.with_field("AlterConfigsResponse", "responses", version))?;
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterConfigsResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterConfigsResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl AlterConfigsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.responses.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.responses.iter().enumerate() {
+            item.validate_into(version, &format!("{}responses[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterConfigsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("responses".into(), serde_json::Value::Array(self.responses.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterConfigsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "AlterConfigsResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("responses") {
+            this.responses = crate::json::array(value, "AlterConfigsResponse.responses")?.iter().map(|item| AlterConfigsResourceResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterConfigsResponse", "responses", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterConfigsResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
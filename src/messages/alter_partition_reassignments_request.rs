@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ReassignablePartition {
     /// The partition index.
@@ -30,10 +36,11 @@ pub struct ReassignablePartition {
     /// The replicas to place the partitions on, or null to cancel a pending reassignment for this partition.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
     pub replicas: Option<Vec<super::BrokerId>>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ReassignablePartition {
@@ -46,45 +53,49 @@ impl Builder for ReassignablePartition {
 
 impl Encodable for ReassignablePartition {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::CompactArray(types::Int32).encode(buf, &self.replicas)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("ReassignablePartition", "partition_index", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("ReassignablePartition", "replicas", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReassignablePartition", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ReassignablePartition", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ReassignablePartition", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::CompactArray(types::Int32).compute_size(&self.replicas)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("ReassignablePartition", "partition_index", version))?;
+        total_size += types::CompactArray(types::Int32).compute_size(&self.replicas).map_err(|e| e.with_field("ReassignablePartition", "replicas", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReassignablePartition", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ReassignablePartition", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ReassignablePartition", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ReassignablePartition {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let replicas = types::CompactArray(types::Int32).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("ReassignablePartition", "partition_index", version, start - buf.remaining()))?;
+        let replicas = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("ReassignablePartition", "replicas", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignablePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignablePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignablePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ReassignablePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -100,7 +111,7 @@ impl Default for ReassignablePartition {
         Self {
             partition_index: 0,
             replicas: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -109,9 +120,69 @@ impl Message for ReassignablePartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl ReassignablePartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl ReassignablePartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("replicas".into(), JsonValue::to_json(&self.replicas));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ReassignablePartition")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "ReassignablePartition.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("ReassignablePartition", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("replicas") {
+            this.replicas = JsonValue::from_json(value, "ReassignablePartition.replicas")?;
+        } else {
+            return Err(crate::json::missing("ReassignablePartition", "replicas", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ReassignableTopic {
     /// The topic name.
@@ -125,7 +196,7 @@ pub struct ReassignableTopic {
     pub partitions: Vec<ReassignablePartition>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ReassignableTopic {
@@ -138,45 +209,49 @@ impl Builder for ReassignableTopic {
 
 impl Encodable for ReassignableTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, &self.name)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("ReassignableTopic", "name", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("ReassignableTopic", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReassignableTopic", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ReassignableTopic", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ReassignableTopic", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(&self.name)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+        total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("ReassignableTopic", "name", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("ReassignableTopic", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReassignableTopic", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ReassignableTopic", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ReassignableTopic", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ReassignableTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let name = types::CompactString.decode(buf)?;
-        let partitions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let name = types::CompactString.decode(buf).map_err(|e| e.with_field("ReassignableTopic", "name", version, start - buf.remaining()))?;
+        let partitions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ReassignableTopic", "partitions", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignableTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignableTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignableTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ReassignableTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -192,7 +267,7 @@ impl Default for ReassignableTopic {
         Self {
             name: Default::default(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -201,9 +276,75 @@ impl Message for ReassignableTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl ReassignableTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ReassignableTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ReassignableTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "ReassignableTopic.name")?;
+        } else {
+            return Err(crate::json::missing("ReassignableTopic", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "ReassignableTopic.partitions")?.iter().map(|item| ReassignablePartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ReassignableTopic", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterPartitionReassignmentsRequest {
     /// The time in ms to wait for the request to complete.
@@ -217,7 +358,7 @@ pub struct AlterPartitionReassignmentsRequest {
     pub topics: Vec<ReassignableTopic>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterPartitionReassignmentsRequest {
@@ -230,45 +371,58 @@ impl Builder for AlterPartitionReassignmentsRequest {
 
 impl Encodable for AlterPartitionReassignmentsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.timeout_ms)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionReassignmentsRequest", version));
+        }
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "timeout_ms", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "topics", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionReassignmentsRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.timeout_ms)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+        total_size += types::Int32.compute_size(&self.timeout_ms).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "timeout_ms", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "topics", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for AlterPartitionReassignmentsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let timeout_ms = types::Int32.decode(buf)?;
-        let topics = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterPartitionReassignmentsRequest", version));
+        }
+        let start = buf.remaining();
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "timeout_ms", version, start - buf.remaining()))?;
+        let topics = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "topics", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -284,7 +438,7 @@ impl Default for AlterPartitionReassignmentsRequest {
         Self {
             timeout_ms: 60000,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -293,9 +447,132 @@ impl Message for AlterPartitionReassignmentsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl AlterPartitionReassignmentsRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = ReassignableTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionReassignmentsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "timeout_ms", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "topics", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionReassignmentsRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl AlterPartitionReassignmentsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterPartitionReassignmentsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("timeoutMs".into(), JsonValue::to_json(&self.timeout_ms));
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterPartitionReassignmentsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("timeoutMs") {
+            this.timeout_ms = JsonValue::from_json(value, "AlterPartitionReassignmentsRequest.timeoutMs")?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionReassignmentsRequest", "timeoutMs", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "AlterPartitionReassignmentsRequest.topics")?.iter().map(|item| ReassignableTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionReassignmentsRequest", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterPartitionReassignmentsRequest {
     fn header_version(version: i16) -> i16 {
         2
     }
 }
 
+impl AlterPartitionReassignmentsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::AlterPartitionReassignmentsResponse {
+        let mut response = super::AlterPartitionReassignmentsResponse::default();
+        response.error_code = error.code();
+        let mut items0 = Vec::new();
+        for request0 in &self.topics {
+            let mut response0 = super::alter_partition_reassignments_response::ReassignableTopicResponse::default();
+            response0.name = request0.name.clone();
+            let mut items1 = Vec::new();
+            for request1 in &request0.partitions {
+                let mut response1 = super::alter_partition_reassignments_response::ReassignablePartitionResponse::default();
+                response1.partition_index = request1.partition_index;
+                response1.error_code = error.code();
+                items1.push(response1);
+            }
+            response0.partitions = items1;
+            items0.push(response0);
+        }
+        response.responses = items0;
+        response
+    }
+}
+
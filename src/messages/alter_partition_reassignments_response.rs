@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ReassignablePartitionResponse {
     /// The partition index.
@@ -35,10 +41,12 @@ pub struct ReassignablePartitionResponse {
     /// The error message for this partition, or null if there was no error.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ReassignablePartitionResponse {
@@ -51,48 +59,52 @@ impl Builder for ReassignablePartitionResponse {
 
 impl Encodable for ReassignablePartitionResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::CompactString.encode(buf, &self.error_message)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("ReassignablePartitionResponse", "partition_index", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("ReassignablePartitionResponse", "error_code", version))?;
+        types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("ReassignablePartitionResponse", "error_message", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::CompactString.compute_size(&self.error_message)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("ReassignablePartitionResponse", "partition_index", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("ReassignablePartitionResponse", "error_code", version))?;
+        total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("ReassignablePartitionResponse", "error_message", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ReassignablePartitionResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let error_message = types::CompactString.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("ReassignablePartitionResponse", "partition_index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("ReassignablePartitionResponse", "error_code", version, start - buf.remaining()))?;
+        let error_message = types::CompactString.decode(buf).map_err(|e| e.with_field("ReassignablePartitionResponse", "error_message", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ReassignablePartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -110,7 +122,7 @@ impl Default for ReassignablePartitionResponse {
             partition_index: 0,
             error_code: 0,
             error_message: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -119,9 +131,75 @@ impl Message for ReassignablePartitionResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl ReassignablePartitionResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl ReassignablePartitionResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ReassignablePartitionResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "ReassignablePartitionResponse.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("ReassignablePartitionResponse", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "ReassignablePartitionResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("ReassignablePartitionResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "ReassignablePartitionResponse.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("ReassignablePartitionResponse", "errorMessage", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ReassignableTopicResponse {
     /// The topic name
@@ -135,7 +213,7 @@ pub struct ReassignableTopicResponse {
     pub partitions: Vec<ReassignablePartitionResponse>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ReassignableTopicResponse {
@@ -148,45 +226,49 @@ impl Builder for ReassignableTopicResponse {
 
 impl Encodable for ReassignableTopicResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, &self.name)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("ReassignableTopicResponse", "name", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("ReassignableTopicResponse", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReassignableTopicResponse", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ReassignableTopicResponse", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ReassignableTopicResponse", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(&self.name)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+        total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("ReassignableTopicResponse", "name", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("ReassignableTopicResponse", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReassignableTopicResponse", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ReassignableTopicResponse", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ReassignableTopicResponse", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ReassignableTopicResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let name = types::CompactString.decode(buf)?;
-        let partitions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let name = types::CompactString.decode(buf).map_err(|e| e.with_field("ReassignableTopicResponse", "name", version, start - buf.remaining()))?;
+        let partitions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ReassignableTopicResponse", "partitions", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignableTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignableTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReassignableTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ReassignableTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -202,7 +284,7 @@ impl Default for ReassignableTopicResponse {
         Self {
             name: Default::default(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -211,9 +293,75 @@ impl Message for ReassignableTopicResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl ReassignableTopicResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ReassignableTopicResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ReassignableTopicResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "ReassignableTopicResponse.name")?;
+        } else {
+            return Err(crate::json::missing("ReassignableTopicResponse", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "ReassignableTopicResponse.partitions")?.iter().map(|item| ReassignablePartitionResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ReassignableTopicResponse", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterPartitionReassignmentsResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -229,6 +377,8 @@ pub struct AlterPartitionReassignmentsResponse {
     /// The top-level error message, or null if there was no error.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The responses to topics to reassign.
@@ -237,7 +387,7 @@ pub struct AlterPartitionReassignmentsResponse {
     pub responses: Vec<ReassignableTopicResponse>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterPartitionReassignmentsResponse {
@@ -250,51 +400,64 @@ impl Builder for AlterPartitionReassignmentsResponse {
 
 impl Encodable for AlterPartitionReassignmentsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::CompactString.encode(buf, &self.error_message)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.responses)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionReassignmentsResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "error_code", version))?;
+        types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "error_message", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.responses).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "responses", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionReassignmentsResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::CompactString.compute_size(&self.error_message)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.responses)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "throttle_time_ms", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "error_code", version))?;
+        total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "error_message", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.responses).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "responses", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for AlterPartitionReassignmentsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let error_message = types::CompactString.decode(buf)?;
-        let responses = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterPartitionReassignmentsResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "throttle_time_ms", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "error_code", version, start - buf.remaining()))?;
+        let error_message = types::CompactString.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "error_message", version, start - buf.remaining()))?;
+        let responses = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "responses", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -314,7 +477,7 @@ impl Default for AlterPartitionReassignmentsResponse {
             error_code: 0,
             error_message: Some(Default::default()),
             responses: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -323,6 +486,115 @@ impl Message for AlterPartitionReassignmentsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl AlterPartitionReassignmentsResponse {
+    /// Encode the message with the elements of `responses` produced by an iterator of known length, in place of
+    /// the `responses` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_responses<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, responses: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = ReassignableTopicResponse>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionReassignmentsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let responses = responses.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "error_code", version))?;
+        types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "error_message", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(responses)).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "responses", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionReassignmentsResponse", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl AlterPartitionReassignmentsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.responses.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.responses.iter().enumerate() {
+            item.validate_into(version, &format!("{}responses[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterPartitionReassignmentsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("responses".into(), serde_json::Value::Array(self.responses.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterPartitionReassignmentsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "AlterPartitionReassignmentsResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionReassignmentsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "AlterPartitionReassignmentsResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionReassignmentsResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "AlterPartitionReassignmentsResponse.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionReassignmentsResponse", "errorMessage", version));
+        }
+        if let Some(value) = object.get("responses") {
+            this.responses = crate::json::array(value, "AlterPartitionReassignmentsResponse.responses")?.iter().map(|item| ReassignableTopicResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionReassignmentsResponse", "responses", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterPartitionReassignmentsResponse {
     fn header_version(version: i16) -> i16 {
         1
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct BrokerState {
     /// The ID of the broker.
@@ -33,7 +39,7 @@ pub struct BrokerState {
     pub broker_epoch: i64,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for BrokerState {
@@ -46,77 +52,81 @@ impl Builder for BrokerState {
 
 impl Encodable for BrokerState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
-            types::Int32.encode(buf, &self.broker_id)?;
+            types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("BrokerState", "broker_id", version))?;
         } else {
             if self.broker_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BrokerState", "broker_id", version));
             }
         }
         if version >= 3 {
-            types::Int64.encode(buf, &self.broker_epoch)?;
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("BrokerState", "broker_epoch", version))?;
         } else {
             if self.broker_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BrokerState", "broker_epoch", version));
             }
         }
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerState", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BrokerState", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerState", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::Int32.compute_size(&self.broker_id)?;
+            total_size += types::Int32.compute_size(&self.broker_id).map_err(|e| e.with_field("BrokerState", "broker_id", version))?;
         } else {
             if self.broker_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BrokerState", "broker_id", version));
             }
         }
         if version >= 3 {
-            total_size += types::Int64.compute_size(&self.broker_epoch)?;
+            total_size += types::Int64.compute_size(&self.broker_epoch).map_err(|e| e.with_field("BrokerState", "broker_epoch", version))?;
         } else {
             if self.broker_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BrokerState", "broker_epoch", version));
             }
         }
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerState", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("BrokerState", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerState", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for BrokerState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let broker_id = if version >= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("BrokerState", "broker_id", version, start - buf.remaining()))?
         } else {
             (0).into()
         };
         let broker_epoch = if version >= 3 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("BrokerState", "broker_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerState", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("BrokerState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -132,7 +142,7 @@ impl Default for BrokerState {
         Self {
             broker_id: (0).into(),
             broker_epoch: -1,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -141,9 +151,89 @@ impl Message for BrokerState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl BrokerState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.broker_id = (0).into();
+        }
+        if version < 3 {
+            self.broker_epoch = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.broker_id != 0 {
+                invalid.push(format!("{}broker_id", path));
+            }
+        }
+        if version < 3 {
+            if self.broker_epoch != -1 {
+                invalid.push(format!("{}broker_epoch", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl BrokerState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("brokerId".into(), JsonValue::to_json(&self.broker_id));
+        }
+        if version >= 3 {
+            node.insert("brokerEpoch".into(), JsonValue::to_json(&self.broker_epoch));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "BrokerState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("brokerId") {
+            this.broker_id = JsonValue::from_json(value, "BrokerState.brokerId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("BrokerState", "brokerId", version));
+        }
+        if let Some(value) = object.get("brokerEpoch") {
+            this.broker_epoch = JsonValue::from_json(value, "BrokerState.brokerEpoch")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("BrokerState", "brokerEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct PartitionData {
     /// The partition index
@@ -177,7 +267,7 @@ pub struct PartitionData {
     pub partition_epoch: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for PartitionData {
@@ -190,105 +280,109 @@ impl Builder for PartitionData {
 
 impl Encodable for PartitionData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int32.encode(buf, &self.leader_epoch)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionData", "leader_epoch", version))?;
         if version <= 2 {
-            types::CompactArray(types::Int32).encode(buf, &self.new_isr)?;
+            types::CompactArray(types::Int32).encode(buf, &self.new_isr).map_err(|e| e.with_field("PartitionData", "new_isr", version))?;
         } else {
             if !self.new_isr.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("PartitionData", "new_isr", version));
             }
         }
         if version >= 3 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.new_isr_with_epochs)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.new_isr_with_epochs).map_err(|e| e.with_field("PartitionData", "new_isr_with_epochs", version))?;
         } else {
             if !self.new_isr_with_epochs.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("PartitionData", "new_isr_with_epochs", version));
             }
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.leader_recovery_state)?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("PartitionData", "leader_recovery_state", version))?;
         } else {
             if self.leader_recovery_state != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("PartitionData", "leader_recovery_state", version));
             }
         }
-        types::Int32.encode(buf, &self.partition_epoch)?;
+        types::Int32.encode(buf, &self.partition_epoch).map_err(|e| e.with_field("PartitionData", "partition_epoch", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int32.compute_size(&self.leader_epoch)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("PartitionData", "leader_epoch", version))?;
         if version <= 2 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.new_isr)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.new_isr).map_err(|e| e.with_field("PartitionData", "new_isr", version))?;
         } else {
             if !self.new_isr.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("PartitionData", "new_isr", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.new_isr_with_epochs)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.new_isr_with_epochs).map_err(|e| e.with_field("PartitionData", "new_isr_with_epochs", version))?;
         } else {
             if !self.new_isr_with_epochs.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("PartitionData", "new_isr_with_epochs", version));
             }
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.leader_recovery_state)?;
+            total_size += types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionData", "leader_recovery_state", version))?;
         } else {
             if self.leader_recovery_state != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("PartitionData", "leader_recovery_state", version));
             }
         }
-        total_size += types::Int32.compute_size(&self.partition_epoch)?;
+        total_size += types::Int32.compute_size(&self.partition_epoch).map_err(|e| e.with_field("PartitionData", "partition_epoch", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for PartitionData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let leader_epoch = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "partition_index", version, start - buf.remaining()))?;
+        let leader_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_epoch", version, start - buf.remaining()))?;
         let new_isr = if version <= 2 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionData", "new_isr", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let new_isr_with_epochs = if version >= 3 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("PartitionData", "new_isr_with_epochs", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let leader_recovery_state = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_recovery_state", version, start - buf.remaining()))?
         } else {
             0
         };
-        let partition_epoch = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let partition_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "partition_epoch", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -312,7 +406,7 @@ impl Default for PartitionData {
             new_isr_with_epochs: Default::default(),
             leader_recovery_state: 0,
             partition_epoch: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -321,9 +415,131 @@ impl Message for PartitionData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl PartitionData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 2 {
+            self.new_isr = Default::default();
+        }
+        if version >= 3 {
+            for item in self.new_isr_with_epochs.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.new_isr_with_epochs = Default::default();
+        }
+        if version < 1 {
+            self.leader_recovery_state = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 2 {
+            if !self.new_isr.is_empty() {
+                invalid.push(format!("{}new_isr", path));
+            }
+        }
+        if version >= 3 {
+            for (i, item) in self.new_isr_with_epochs.iter().enumerate() {
+                item.validate_into(version, &format!("{}new_isr_with_epochs[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.new_isr_with_epochs.is_empty() {
+                invalid.push(format!("{}new_isr_with_epochs", path));
+            }
+        }
+        if version < 1 {
+            if self.leader_recovery_state != 0 {
+                invalid.push(format!("{}leader_recovery_state", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        if version <= 2 {
+            node.insert("newIsr".into(), JsonValue::to_json(&self.new_isr));
+        }
+        if version >= 3 {
+            node.insert("newIsrWithEpochs".into(), serde_json::Value::Array(self.new_isr_with_epochs.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 1 {
+            node.insert("leaderRecoveryState".into(), JsonValue::to_json(&self.leader_recovery_state));
+        }
+        node.insert("partitionEpoch".into(), JsonValue::to_json(&self.partition_epoch));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "PartitionData.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "PartitionData.leaderEpoch")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "leaderEpoch", version));
+        }
+        if let Some(value) = object.get("newIsr") {
+            this.new_isr = JsonValue::from_json(value, "PartitionData.newIsr")?;
+        } else if version <= 2 {
+            return Err(crate::json::missing("PartitionData", "newIsr", version));
+        }
+        if let Some(value) = object.get("newIsrWithEpochs") {
+            this.new_isr_with_epochs = crate::json::array(value, "PartitionData.newIsrWithEpochs")?.iter().map(|item| BrokerState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("PartitionData", "newIsrWithEpochs", version));
+        }
+        if let Some(value) = object.get("leaderRecoveryState") {
+            this.leader_recovery_state = JsonValue::from_json(value, "PartitionData.leaderRecoveryState")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("PartitionData", "leaderRecoveryState", version));
+        }
+        if let Some(value) = object.get("partitionEpoch") {
+            this.partition_epoch = JsonValue::from_json(value, "PartitionData.partitionEpoch")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "partitionEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicData {
     /// The name of the topic to alter ISRs for
@@ -342,7 +558,7 @@ pub struct TopicData {
     pub partitions: Vec<PartitionData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TopicData {
@@ -355,64 +571,68 @@ impl Builder for TopicData {
 
 impl Encodable for TopicData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 1 {
-            types::CompactString.encode(buf, &self.topic_name)?;
+            types::CompactString.encode(buf, &self.topic_name).map_err(|e| e.with_field("TopicData", "topic_name", version))?;
         }
         if version >= 2 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("TopicData", "topic_id", version))?;
         }
-        types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicData", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicData", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 1 {
-            total_size += types::CompactString.compute_size(&self.topic_name)?;
+            total_size += types::CompactString.compute_size(&self.topic_name).map_err(|e| e.with_field("TopicData", "topic_name", version))?;
         }
         if version >= 2 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("TopicData", "topic_id", version))?;
         }
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("TopicData", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicData", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for TopicData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version <= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicData", "topic_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topic_id = if version >= 2 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("TopicData", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
-        let partitions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let partitions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TopicData", "partitions", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -430,7 +650,7 @@ impl Default for TopicData {
             topic_name: Default::default(),
             topic_id: Uuid::nil(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -439,9 +659,101 @@ impl Message for TopicData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl TopicData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 1 {
+            self.topic_name = Default::default();
+        }
+        if version < 2 {
+            self.topic_id = Uuid::nil();
+        }
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 1 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+        if version < 2 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 1 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        if version >= 2 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TopicData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "TopicData.topicName")?;
+        } else if version <= 1 {
+            return Err(crate::json::missing("TopicData", "topicName", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "TopicData.topicId")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("TopicData", "topicId", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "TopicData.partitions")?.iter().map(|item| PartitionData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("TopicData", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterPartitionRequest {
     /// The ID of the requesting broker
@@ -460,7 +772,7 @@ pub struct AlterPartitionRequest {
     pub topics: Vec<TopicData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterPartitionRequest {
@@ -473,48 +785,61 @@ impl Builder for AlterPartitionRequest {
 
 impl Encodable for AlterPartitionRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.broker_id)?;
-        types::Int64.encode(buf, &self.broker_epoch)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionRequest", version));
+        }
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("AlterPartitionRequest", "broker_id", version))?;
+        types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("AlterPartitionRequest", "broker_epoch", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("AlterPartitionRequest", "topics", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionRequest", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.broker_id)?;
-        total_size += types::Int64.compute_size(&self.broker_epoch)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+        total_size += types::Int32.compute_size(&self.broker_id).map_err(|e| e.with_field("AlterPartitionRequest", "broker_id", version))?;
+        total_size += types::Int64.compute_size(&self.broker_epoch).map_err(|e| e.with_field("AlterPartitionRequest", "broker_epoch", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("AlterPartitionRequest", "topics", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionRequest", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for AlterPartitionRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let broker_id = types::Int32.decode(buf)?;
-        let broker_epoch = types::Int64.decode(buf)?;
-        let topics = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterPartitionRequest", version));
+        }
+        let start = buf.remaining();
+        let broker_id = types::Int32.decode(buf).map_err(|e| e.with_field("AlterPartitionRequest", "broker_id", version, start - buf.remaining()))?;
+        let broker_epoch = types::Int64.decode(buf).map_err(|e| e.with_field("AlterPartitionRequest", "broker_epoch", version, start - buf.remaining()))?;
+        let topics = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterPartitionRequest", "topics", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -532,7 +857,7 @@ impl Default for AlterPartitionRequest {
             broker_id: (0).into(),
             broker_epoch: -1,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -541,9 +866,144 @@ impl Message for AlterPartitionRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl AlterPartitionRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = TopicData>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("AlterPartitionRequest", "broker_id", version))?;
+        types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("AlterPartitionRequest", "broker_epoch", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("AlterPartitionRequest", "topics", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl AlterPartitionRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterPartitionRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("brokerId".into(), JsonValue::to_json(&self.broker_id));
+        node.insert("brokerEpoch".into(), JsonValue::to_json(&self.broker_epoch));
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterPartitionRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("brokerId") {
+            this.broker_id = JsonValue::from_json(value, "AlterPartitionRequest.brokerId")?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionRequest", "brokerId", version));
+        }
+        if let Some(value) = object.get("brokerEpoch") {
+            this.broker_epoch = JsonValue::from_json(value, "AlterPartitionRequest.brokerEpoch")?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionRequest", "brokerEpoch", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "AlterPartitionRequest.topics")?.iter().map(|item| TopicData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionRequest", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterPartitionRequest {
     fn header_version(version: i16) -> i16 {
         2
     }
 }
 
+impl AlterPartitionRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::AlterPartitionResponse {
+        let mut response = super::AlterPartitionResponse::default();
+        response.error_code = error.code();
+        let mut items0 = Vec::new();
+        for request0 in &self.topics {
+            let mut response0 = super::alter_partition_response::TopicData::default();
+            if version <= 1 {
+                response0.topic_name = request0.topic_name.clone();
+            }
+            if version >= 2 {
+                response0.topic_id = request0.topic_id;
+            }
+            let mut items1 = Vec::new();
+            for request1 in &request0.partitions {
+                let mut response1 = super::alter_partition_response::PartitionData::default();
+                response1.partition_index = request1.partition_index;
+                response1.error_code = error.code();
+                items1.push(response1);
+            }
+            response0.partitions = items1;
+            items0.push(response0);
+        }
+        response.topics = items0;
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct PartitionData {
     /// The partition index
@@ -58,7 +64,7 @@ pub struct PartitionData {
     pub partition_epoch: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for PartitionData {
@@ -71,68 +77,72 @@ impl Builder for PartitionData {
 
 impl Encodable for PartitionData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::Int32.encode(buf, &self.leader_id)?;
-        types::Int32.encode(buf, &self.leader_epoch)?;
-        types::CompactArray(types::Int32).encode(buf, &self.isr)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("PartitionData", "error_code", version))?;
+        types::Int32.encode(buf, &self.leader_id).map_err(|e| e.with_field("PartitionData", "leader_id", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionData", "leader_epoch", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("PartitionData", "isr", version))?;
         if version >= 1 {
-            types::Int8.encode(buf, &self.leader_recovery_state)?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("PartitionData", "leader_recovery_state", version))?;
         }
-        types::Int32.encode(buf, &self.partition_epoch)?;
+        types::Int32.encode(buf, &self.partition_epoch).map_err(|e| e.with_field("PartitionData", "partition_epoch", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::Int32.compute_size(&self.leader_id)?;
-        total_size += types::Int32.compute_size(&self.leader_epoch)?;
-        total_size += types::CompactArray(types::Int32).compute_size(&self.isr)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("PartitionData", "error_code", version))?;
+        total_size += types::Int32.compute_size(&self.leader_id).map_err(|e| e.with_field("PartitionData", "leader_id", version))?;
+        total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("PartitionData", "leader_epoch", version))?;
+        total_size += types::CompactArray(types::Int32).compute_size(&self.isr).map_err(|e| e.with_field("PartitionData", "isr", version))?;
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.leader_recovery_state)?;
+            total_size += types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionData", "leader_recovery_state", version))?;
         }
-        total_size += types::Int32.compute_size(&self.partition_epoch)?;
+        total_size += types::Int32.compute_size(&self.partition_epoch).map_err(|e| e.with_field("PartitionData", "partition_epoch", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for PartitionData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let leader_id = types::Int32.decode(buf)?;
-        let leader_epoch = types::Int32.decode(buf)?;
-        let isr = types::CompactArray(types::Int32).decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "partition_index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("PartitionData", "error_code", version, start - buf.remaining()))?;
+        let leader_id = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_id", version, start - buf.remaining()))?;
+        let leader_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_epoch", version, start - buf.remaining()))?;
+        let isr = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionData", "isr", version, start - buf.remaining()))?;
         let leader_recovery_state = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_recovery_state", version, start - buf.remaining()))?
         } else {
             0
         };
-        let partition_epoch = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let partition_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "partition_epoch", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -158,7 +168,7 @@ impl Default for PartitionData {
             isr: Default::default(),
             leader_recovery_state: 0,
             partition_epoch: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -167,9 +177,109 @@ impl Message for PartitionData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl PartitionData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.leader_recovery_state = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.leader_recovery_state != 0 {
+                invalid.push(format!("{}leader_recovery_state", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("leaderId".into(), JsonValue::to_json(&self.leader_id));
+        node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        node.insert("isr".into(), JsonValue::to_json(&self.isr));
+        if version >= 1 {
+            node.insert("leaderRecoveryState".into(), JsonValue::to_json(&self.leader_recovery_state));
+        }
+        node.insert("partitionEpoch".into(), JsonValue::to_json(&self.partition_epoch));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "PartitionData.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "PartitionData.errorCode")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "errorCode", version));
+        }
+        if let Some(value) = object.get("leaderId") {
+            this.leader_id = JsonValue::from_json(value, "PartitionData.leaderId")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "leaderId", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "PartitionData.leaderEpoch")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "leaderEpoch", version));
+        }
+        if let Some(value) = object.get("isr") {
+            this.isr = JsonValue::from_json(value, "PartitionData.isr")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "isr", version));
+        }
+        if let Some(value) = object.get("leaderRecoveryState") {
+            this.leader_recovery_state = JsonValue::from_json(value, "PartitionData.leaderRecoveryState")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("PartitionData", "leaderRecoveryState", version));
+        }
+        if let Some(value) = object.get("partitionEpoch") {
+            this.partition_epoch = JsonValue::from_json(value, "PartitionData.partitionEpoch")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "partitionEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicData {
     /// The name of the topic
@@ -188,7 +298,7 @@ pub struct TopicData {
     pub partitions: Vec<PartitionData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TopicData {
@@ -201,64 +311,68 @@ impl Builder for TopicData {
 
 impl Encodable for TopicData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 1 {
-            types::CompactString.encode(buf, &self.topic_name)?;
+            types::CompactString.encode(buf, &self.topic_name).map_err(|e| e.with_field("TopicData", "topic_name", version))?;
         }
         if version >= 2 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("TopicData", "topic_id", version))?;
         }
-        types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicData", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicData", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 1 {
-            total_size += types::CompactString.compute_size(&self.topic_name)?;
+            total_size += types::CompactString.compute_size(&self.topic_name).map_err(|e| e.with_field("TopicData", "topic_name", version))?;
         }
         if version >= 2 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("TopicData", "topic_id", version))?;
         }
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("TopicData", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicData", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for TopicData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version <= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicData", "topic_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topic_id = if version >= 2 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("TopicData", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
-        let partitions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let partitions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TopicData", "partitions", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TopicData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -276,7 +390,7 @@ impl Default for TopicData {
             topic_name: Default::default(),
             topic_id: Uuid::nil(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -285,9 +399,101 @@ impl Message for TopicData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl TopicData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 1 {
+            self.topic_name = Default::default();
+        }
+        if version < 2 {
+            self.topic_id = Uuid::nil();
+        }
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 1 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+        if version < 2 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 1 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        if version >= 2 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TopicData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "TopicData.topicName")?;
+        } else if version <= 1 {
+            return Err(crate::json::missing("TopicData", "topicName", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "TopicData.topicId")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("TopicData", "topicId", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "TopicData.partitions")?.iter().map(|item| PartitionData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("TopicData", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterPartitionResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -306,7 +512,7 @@ pub struct AlterPartitionResponse {
     pub topics: Vec<TopicData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterPartitionResponse {
@@ -319,48 +525,61 @@ impl Builder for AlterPartitionResponse {
 
 impl Encodable for AlterPartitionResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AlterPartitionResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("AlterPartitionResponse", "error_code", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("AlterPartitionResponse", "topics", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionResponse", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("AlterPartitionResponse", "throttle_time_ms", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("AlterPartitionResponse", "error_code", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("AlterPartitionResponse", "topics", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionResponse", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for AlterPartitionResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let topics = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterPartitionResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("AlterPartitionResponse", "throttle_time_ms", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("AlterPartitionResponse", "error_code", version, start - buf.remaining()))?;
+        let topics = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterPartitionResponse", "topics", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -378,7 +597,7 @@ impl Default for AlterPartitionResponse {
             throttle_time_ms: 0,
             error_code: 0,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -387,6 +606,108 @@ impl Message for AlterPartitionResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl AlterPartitionResponse {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = TopicData>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterPartitionResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("AlterPartitionResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("AlterPartitionResponse", "error_code", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("AlterPartitionResponse", "topics", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterPartitionResponse", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterPartitionResponse", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl AlterPartitionResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterPartitionResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterPartitionResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "AlterPartitionResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "AlterPartitionResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "AlterPartitionResponse.topics")?.iter().map(|item| TopicData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterPartitionResponse", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterPartitionResponse {
     fn header_version(version: i16) -> i16 {
         1
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterReplicaLogDirTopic {
     /// The partition indexes.
@@ -28,7 +34,7 @@ pub struct AlterReplicaLogDirTopic {
     pub partitions: Vec<i32>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterReplicaLogDirTopic {
@@ -42,49 +48,53 @@ impl Builder for AlterReplicaLogDirTopic {
 impl MapEncodable for AlterReplicaLogDirTopic {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "name", version))?;
         }
         if version >= 2 {
-            types::CompactArray(types::Int32).encode(buf, &self.partitions)?;
+            types::CompactArray(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "partitions", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.partitions)?;
+            types::Array(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "partitions", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "name", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.partitions)?;
+            total_size += types::Array(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "partitions", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -93,24 +103,24 @@ impl MapEncodable for AlterReplicaLogDirTopic {
 impl MapDecodable for AlterReplicaLogDirTopic {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "name", version, start - buf.remaining()))?
         };
         let partitions = if version >= 2 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "partitions", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterReplicaLogDirTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -125,7 +135,7 @@ impl Default for AlterReplicaLogDirTopic {
     fn default() -> Self {
         Self {
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -134,9 +144,70 @@ impl Message for AlterReplicaLogDirTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl AlterReplicaLogDirTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterReplicaLogDirTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("partitions".into(), JsonValue::to_json(&self.partitions));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "AlterReplicaLogDirTopic")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "AlterReplicaLogDirTopic.name")?;
+        } else {
+            return Err(crate::json::missing("AlterReplicaLogDirTopic", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = JsonValue::from_json(value, "AlterReplicaLogDirTopic.partitions")?;
+        } else {
+            return Err(crate::json::missing("AlterReplicaLogDirTopic", "partitions", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterReplicaLogDir {
     /// The topics to add to the directory.
@@ -145,7 +216,7 @@ pub struct AlterReplicaLogDir {
     pub topics: indexmap::IndexMap<super::TopicName, AlterReplicaLogDirTopic>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterReplicaLogDir {
@@ -159,49 +230,53 @@ impl Builder for AlterReplicaLogDir {
 impl MapEncodable for AlterReplicaLogDir {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("AlterReplicaLogDir", "path", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("AlterReplicaLogDir", "path", version))?;
         }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("AlterReplicaLogDir", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("AlterReplicaLogDir", "topics", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterReplicaLogDir", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterReplicaLogDir", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterReplicaLogDir", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("AlterReplicaLogDir", "path", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("AlterReplicaLogDir", "path", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("AlterReplicaLogDir", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("AlterReplicaLogDir", "topics", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterReplicaLogDir", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterReplicaLogDir", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterReplicaLogDir", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -210,24 +285,24 @@ impl MapEncodable for AlterReplicaLogDir {
 impl MapDecodable for AlterReplicaLogDir {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDir", "path", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDir", "path", version, start - buf.remaining()))?
         };
         let topics = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterReplicaLogDir", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterReplicaLogDir", "topics", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDir", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDir", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDir", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterReplicaLogDir", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -242,7 +317,7 @@ impl Default for AlterReplicaLogDir {
     fn default() -> Self {
         Self {
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -251,18 +326,86 @@ impl Message for AlterReplicaLogDir {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl AlterReplicaLogDir {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.topics.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.topics.values().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterReplicaLogDir {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("path".into(), JsonValue::to_json(key));
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "AlterReplicaLogDir")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("path") {
+            key = JsonValue::from_json(value, "AlterReplicaLogDir.path")?;
+        } else {
+            return Err(crate::json::missing("AlterReplicaLogDir", "path", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "AlterReplicaLogDir.topics")?.iter().map(|item| AlterReplicaLogDirTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterReplicaLogDir", "topics", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterReplicaLogDirsRequest {
     /// The alterations to make for each directory.
     /// 
     /// Supported API versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub dirs: indexmap::IndexMap<StrBytes, AlterReplicaLogDir>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterReplicaLogDirsRequest {
@@ -275,39 +418,49 @@ impl Builder for AlterReplicaLogDirsRequest {
 
 impl Encodable for AlterReplicaLogDirsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterReplicaLogDirsRequest", version));
+        }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.dirs)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.dirs).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "dirs", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.dirs)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.dirs).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "dirs", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterReplicaLogDirsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.dirs)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.dirs).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "dirs", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.dirs)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.dirs).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "dirs", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -315,19 +468,22 @@ impl Encodable for AlterReplicaLogDirsRequest {
 
 impl Decodable for AlterReplicaLogDirsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterReplicaLogDirsRequest", version));
+        }
+        let start = buf.remaining();
         let dirs = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "dirs", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "dirs", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -342,7 +498,7 @@ impl Default for AlterReplicaLogDirsRequest {
     fn default() -> Self {
         Self {
             dirs: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -351,6 +507,100 @@ impl Message for AlterReplicaLogDirsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl AlterReplicaLogDirsRequest {
+    /// Encode the message with the elements of `dirs` produced by an iterator of known length, in place of
+    /// the `dirs` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_dirs<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, dirs: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (StrBytes, AlterReplicaLogDir)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterReplicaLogDirsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let dirs = dirs.into_iter();
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(dirs)).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "dirs", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(dirs)).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "dirs", version))?;
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterReplicaLogDirsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl AlterReplicaLogDirsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.dirs.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.dirs.values().enumerate() {
+            item.validate_into(version, &format!("{}dirs[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterReplicaLogDirsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("dirs".into(), serde_json::Value::Array(self.dirs.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterReplicaLogDirsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("dirs") {
+            this.dirs = crate::json::array(value, "AlterReplicaLogDirsRequest.dirs")?.iter().map(|item| AlterReplicaLogDir::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterReplicaLogDirsRequest", "dirs", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterReplicaLogDirsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -361,3 +611,15 @@ impl HeaderVersion for AlterReplicaLogDirsRequest {
     }
 }
 
+impl AlterReplicaLogDirsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::AlterReplicaLogDirsResponse {
+        let mut response = super::AlterReplicaLogDirsResponse::default();
+        response
+    }
+}
+
@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ScramCredentialDeletion {
     /// The user name.
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub name: StrBytes,
 
     /// The SCRAM mechanism.
@@ -33,7 +40,7 @@ pub struct ScramCredentialDeletion {
     pub mechanism: i8,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ScramCredentialDeletion {
@@ -46,45 +53,49 @@ impl Builder for ScramCredentialDeletion {
 
 impl Encodable for ScramCredentialDeletion {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, &self.name)?;
-        types::Int8.encode(buf, &self.mechanism)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("ScramCredentialDeletion", "name", version))?;
+        types::Int8.encode(buf, &self.mechanism).map_err(|e| e.with_field("ScramCredentialDeletion", "mechanism", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ScramCredentialDeletion", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ScramCredentialDeletion", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ScramCredentialDeletion", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(&self.name)?;
-        total_size += types::Int8.compute_size(&self.mechanism)?;
+        total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("ScramCredentialDeletion", "name", version))?;
+        total_size += types::Int8.compute_size(&self.mechanism).map_err(|e| e.with_field("ScramCredentialDeletion", "mechanism", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ScramCredentialDeletion", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ScramCredentialDeletion", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ScramCredentialDeletion", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ScramCredentialDeletion {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let name = types::CompactString.decode(buf)?;
-        let mechanism = types::Int8.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let name = types::CompactString.decode(buf).map_err(|e| e.with_field("ScramCredentialDeletion", "name", version, start - buf.remaining()))?;
+        let mechanism = types::Int8.decode(buf).map_err(|e| e.with_field("ScramCredentialDeletion", "mechanism", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ScramCredentialDeletion", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ScramCredentialDeletion", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ScramCredentialDeletion", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ScramCredentialDeletion", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -100,7 +111,7 @@ impl Default for ScramCredentialDeletion {
         Self {
             name: Default::default(),
             mechanism: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -109,14 +120,75 @@ impl Message for ScramCredentialDeletion {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl ScramCredentialDeletion {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl ScramCredentialDeletion {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("mechanism".into(), JsonValue::to_json(&self.mechanism));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ScramCredentialDeletion")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "ScramCredentialDeletion.name")?;
+        } else {
+            return Err(crate::json::missing("ScramCredentialDeletion", "name", version));
+        }
+        if let Some(value) = object.get("mechanism") {
+            this.mechanism = JsonValue::from_json(value, "ScramCredentialDeletion.mechanism")?;
+        } else {
+            return Err(crate::json::missing("ScramCredentialDeletion", "mechanism", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ScramCredentialUpsertion {
     /// The user name.
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub name: StrBytes,
 
     /// The SCRAM mechanism.
@@ -140,7 +212,7 @@ pub struct ScramCredentialUpsertion {
     pub salted_password: Bytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ScramCredentialUpsertion {
@@ -153,54 +225,58 @@ impl Builder for ScramCredentialUpsertion {
 
 impl Encodable for ScramCredentialUpsertion {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, &self.name)?;
-        types::Int8.encode(buf, &self.mechanism)?;
-        types::Int32.encode(buf, &self.iterations)?;
-        types::CompactBytes.encode(buf, &self.salt)?;
-        types::CompactBytes.encode(buf, &self.salted_password)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("ScramCredentialUpsertion", "name", version))?;
+        types::Int8.encode(buf, &self.mechanism).map_err(|e| e.with_field("ScramCredentialUpsertion", "mechanism", version))?;
+        types::Int32.encode(buf, &self.iterations).map_err(|e| e.with_field("ScramCredentialUpsertion", "iterations", version))?;
+        types::CompactBytes.encode(buf, &self.salt).map_err(|e| e.with_field("ScramCredentialUpsertion", "salt", version))?;
+        types::CompactBytes.encode(buf, &self.salted_password).map_err(|e| e.with_field("ScramCredentialUpsertion", "salted_password", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(&self.name)?;
-        total_size += types::Int8.compute_size(&self.mechanism)?;
-        total_size += types::Int32.compute_size(&self.iterations)?;
-        total_size += types::CompactBytes.compute_size(&self.salt)?;
-        total_size += types::CompactBytes.compute_size(&self.salted_password)?;
+        total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("ScramCredentialUpsertion", "name", version))?;
+        total_size += types::Int8.compute_size(&self.mechanism).map_err(|e| e.with_field("ScramCredentialUpsertion", "mechanism", version))?;
+        total_size += types::Int32.compute_size(&self.iterations).map_err(|e| e.with_field("ScramCredentialUpsertion", "iterations", version))?;
+        total_size += types::CompactBytes.compute_size(&self.salt).map_err(|e| e.with_field("ScramCredentialUpsertion", "salt", version))?;
+        total_size += types::CompactBytes.compute_size(&self.salted_password).map_err(|e| e.with_field("ScramCredentialUpsertion", "salted_password", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ScramCredentialUpsertion {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let name = types::CompactString.decode(buf)?;
-        let mechanism = types::Int8.decode(buf)?;
-        let iterations = types::Int32.decode(buf)?;
-        let salt = types::CompactBytes.decode(buf)?;
-        let salted_password = types::CompactBytes.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let name = types::CompactString.decode(buf).map_err(|e| e.with_field("ScramCredentialUpsertion", "name", version, start - buf.remaining()))?;
+        let mechanism = types::Int8.decode(buf).map_err(|e| e.with_field("ScramCredentialUpsertion", "mechanism", version, start - buf.remaining()))?;
+        let iterations = types::Int32.decode(buf).map_err(|e| e.with_field("ScramCredentialUpsertion", "iterations", version, start - buf.remaining()))?;
+        let salt = types::CompactBytes.decode(buf).map_err(|e| e.with_field("ScramCredentialUpsertion", "salt", version, start - buf.remaining()))?;
+        let salted_password = types::CompactBytes.decode(buf).map_err(|e| e.with_field("ScramCredentialUpsertion", "salted_password", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ScramCredentialUpsertion", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -222,7 +298,7 @@ impl Default for ScramCredentialUpsertion {
             iterations: 0,
             salt: Default::default(),
             salted_password: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -231,9 +307,87 @@ impl Message for ScramCredentialUpsertion {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl ScramCredentialUpsertion {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl ScramCredentialUpsertion {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("mechanism".into(), JsonValue::to_json(&self.mechanism));
+        node.insert("iterations".into(), JsonValue::to_json(&self.iterations));
+        node.insert("salt".into(), JsonValue::to_json(&self.salt));
+        node.insert("saltedPassword".into(), JsonValue::to_json(&self.salted_password));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ScramCredentialUpsertion")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "ScramCredentialUpsertion.name")?;
+        } else {
+            return Err(crate::json::missing("ScramCredentialUpsertion", "name", version));
+        }
+        if let Some(value) = object.get("mechanism") {
+            this.mechanism = JsonValue::from_json(value, "ScramCredentialUpsertion.mechanism")?;
+        } else {
+            return Err(crate::json::missing("ScramCredentialUpsertion", "mechanism", version));
+        }
+        if let Some(value) = object.get("iterations") {
+            this.iterations = JsonValue::from_json(value, "ScramCredentialUpsertion.iterations")?;
+        } else {
+            return Err(crate::json::missing("ScramCredentialUpsertion", "iterations", version));
+        }
+        if let Some(value) = object.get("salt") {
+            this.salt = JsonValue::from_json(value, "ScramCredentialUpsertion.salt")?;
+        } else {
+            return Err(crate::json::missing("ScramCredentialUpsertion", "salt", version));
+        }
+        if let Some(value) = object.get("saltedPassword") {
+            this.salted_password = JsonValue::from_json(value, "ScramCredentialUpsertion.saltedPassword")?;
+        } else {
+            return Err(crate::json::missing("ScramCredentialUpsertion", "saltedPassword", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AlterUserScramCredentialsRequest {
     /// The SCRAM credentials to remove.
@@ -247,7 +401,7 @@ pub struct AlterUserScramCredentialsRequest {
     pub upsertions: Vec<ScramCredentialUpsertion>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AlterUserScramCredentialsRequest {
@@ -260,45 +414,58 @@ impl Builder for AlterUserScramCredentialsRequest {
 
 impl Encodable for AlterUserScramCredentialsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactArray(types::Struct { version }).encode(buf, &self.deletions)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.upsertions)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterUserScramCredentialsRequest", version));
+        }
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.deletions).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "deletions", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.upsertions).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "upsertions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterUserScramCredentialsRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.deletions)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.upsertions)?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.deletions).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "deletions", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.upsertions).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "upsertions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for AlterUserScramCredentialsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let deletions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let upsertions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AlterUserScramCredentialsRequest", version));
+        }
+        let start = buf.remaining();
+        let deletions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "deletions", version, start - buf.remaining()))?;
+        let upsertions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "upsertions", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -314,7 +481,7 @@ impl Default for AlterUserScramCredentialsRequest {
         Self {
             deletions: Default::default(),
             upsertions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -323,9 +490,151 @@ impl Message for AlterUserScramCredentialsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl AlterUserScramCredentialsRequest {
+    /// Encode the message with the elements of `deletions` produced by an iterator of known length, in place of
+    /// the `deletions` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_deletions<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, deletions: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = ScramCredentialDeletion>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterUserScramCredentialsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let deletions = deletions.into_iter();
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(deletions)).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "deletions", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.upsertions).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "upsertions", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `upsertions` produced by an iterator of known length, in place of
+    /// the `upsertions` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_upsertions<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, upsertions: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = ScramCredentialUpsertion>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AlterUserScramCredentialsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let upsertions = upsertions.into_iter();
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.deletions).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "deletions", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(upsertions)).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "upsertions", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AlterUserScramCredentialsRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl AlterUserScramCredentialsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.deletions.iter_mut() {
+            item.normalize_to(version);
+        }
+        for item in self.upsertions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.deletions.iter().enumerate() {
+            item.validate_into(version, &format!("{}deletions[{}].", path, i), invalid);
+        }
+        for (i, item) in self.upsertions.iter().enumerate() {
+            item.validate_into(version, &format!("{}upsertions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AlterUserScramCredentialsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("deletions".into(), serde_json::Value::Array(self.deletions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("upsertions".into(), serde_json::Value::Array(self.upsertions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AlterUserScramCredentialsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("deletions") {
+            this.deletions = crate::json::array(value, "AlterUserScramCredentialsRequest.deletions")?.iter().map(|item| ScramCredentialDeletion::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterUserScramCredentialsRequest", "deletions", version));
+        }
+        if let Some(value) = object.get("upsertions") {
+            this.upsertions = crate::json::array(value, "AlterUserScramCredentialsRequest.upsertions")?.iter().map(|item| ScramCredentialUpsertion::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("AlterUserScramCredentialsRequest", "upsertions", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for AlterUserScramCredentialsRequest {
     fn header_version(version: i16) -> i16 {
         2
     }
 }
 
+impl AlterUserScramCredentialsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::AlterUserScramCredentialsResponse {
+        let mut response = super::AlterUserScramCredentialsResponse::default();
+        response
+    }
+}
+
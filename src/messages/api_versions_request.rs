@@ -5,35 +5,43 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ApiVersionsRequest {
     /// The name of the client.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub client_software_name: StrBytes,
 
     /// The version of the client.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub client_software_version: StrBytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ApiVersionsRequest {
@@ -46,41 +54,51 @@ impl Builder for ApiVersionsRequest {
 
 impl Encodable for ApiVersionsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ApiVersionsRequest", version));
+        }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.client_software_name)?;
+            types::CompactString.encode(buf, &self.client_software_name).map_err(|e| e.with_field("ApiVersionsRequest", "client_software_name", version))?;
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.client_software_version)?;
+            types::CompactString.encode(buf, &self.client_software_version).map_err(|e| e.with_field("ApiVersionsRequest", "client_software_version", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ApiVersionsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ApiVersionsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ApiVersionsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.client_software_name)?;
+            total_size += types::CompactString.compute_size(&self.client_software_name).map_err(|e| e.with_field("ApiVersionsRequest", "client_software_name", version))?;
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.client_software_version)?;
+            total_size += types::CompactString.compute_size(&self.client_software_version).map_err(|e| e.with_field("ApiVersionsRequest", "client_software_version", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ApiVersionsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ApiVersionsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -88,24 +106,27 @@ impl Encodable for ApiVersionsRequest {
 
 impl Decodable for ApiVersionsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ApiVersionsRequest", version));
+        }
+        let start = buf.remaining();
         let client_software_name = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("ApiVersionsRequest", "client_software_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let client_software_version = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("ApiVersionsRequest", "client_software_version", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ApiVersionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -122,7 +143,7 @@ impl Default for ApiVersionsRequest {
         Self {
             client_software_name: Default::default(),
             client_software_version: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -131,6 +152,84 @@ impl Message for ApiVersionsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl ApiVersionsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.client_software_name = Default::default();
+        }
+        if version < 3 {
+            self.client_software_version = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.client_software_name.is_empty() {
+                invalid.push(format!("{}client_software_name", path));
+            }
+        }
+        if version < 3 {
+            if !self.client_software_version.is_empty() {
+                invalid.push(format!("{}client_software_version", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ApiVersionsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("clientSoftwareName".into(), JsonValue::to_json(&self.client_software_name));
+        }
+        if version >= 3 {
+            node.insert("clientSoftwareVersion".into(), JsonValue::to_json(&self.client_software_version));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ApiVersionsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("clientSoftwareName") {
+            this.client_software_name = JsonValue::from_json(value, "ApiVersionsRequest.clientSoftwareName")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("ApiVersionsRequest", "clientSoftwareName", version));
+        }
+        if let Some(value) = object.get("clientSoftwareVersion") {
+            this.client_software_version = JsonValue::from_json(value, "ApiVersionsRequest.clientSoftwareVersion")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("ApiVersionsRequest", "clientSoftwareVersion", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for ApiVersionsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 3 {
@@ -141,3 +240,16 @@ impl HeaderVersion for ApiVersionsRequest {
     }
 }
 
+impl ApiVersionsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::ApiVersionsResponse {
+        let mut response = super::ApiVersionsResponse::default();
+        response.error_code = error.code();
+        response
+    }
+}
+
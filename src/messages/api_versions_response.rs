@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ApiVersion {
     /// The minimum supported version, inclusive.
@@ -33,7 +39,7 @@ pub struct ApiVersion {
     pub max_version: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ApiVersion {
@@ -47,35 +53,39 @@ impl Builder for ApiVersion {
 impl MapEncodable for ApiVersion {
     type Key = i16;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, key)?;
-        types::Int16.encode(buf, &self.min_version)?;
-        types::Int16.encode(buf, &self.max_version)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, key).map_err(|e| e.with_field("ApiVersion", "api_key", version))?;
+        types::Int16.encode(buf, &self.min_version).map_err(|e| e.with_field("ApiVersion", "min_version", version))?;
+        types::Int16.encode(buf, &self.max_version).map_err(|e| e.with_field("ApiVersion", "max_version", version))?;
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersion", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ApiVersion", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ApiVersion", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(key)?;
-        total_size += types::Int16.compute_size(&self.min_version)?;
-        total_size += types::Int16.compute_size(&self.max_version)?;
+        total_size += types::Int16.compute_size(key).map_err(|e| e.with_field("ApiVersion", "api_key", version))?;
+        total_size += types::Int16.compute_size(&self.min_version).map_err(|e| e.with_field("ApiVersion", "min_version", version))?;
+        total_size += types::Int16.compute_size(&self.max_version).map_err(|e| e.with_field("ApiVersion", "max_version", version))?;
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersion", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ApiVersion", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ApiVersion", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -84,17 +94,17 @@ impl MapEncodable for ApiVersion {
 impl MapDecodable for ApiVersion {
     type Key = i16;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::Int16.decode(buf)?;
-        let min_version = types::Int16.decode(buf)?;
-        let max_version = types::Int16.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let start = buf.remaining();
+        let key_field = types::Int16.decode(buf).map_err(|e| e.with_field("ApiVersion", "api_key", version, start - buf.remaining()))?;
+        let min_version = types::Int16.decode(buf).map_err(|e| e.with_field("ApiVersion", "min_version", version, start - buf.remaining()))?;
+        let max_version = types::Int16.decode(buf).map_err(|e| e.with_field("ApiVersion", "max_version", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersion", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersion", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersion", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ApiVersion", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -111,7 +121,7 @@ impl Default for ApiVersion {
         Self {
             min_version: 0,
             max_version: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -120,9 +130,76 @@ impl Message for ApiVersion {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl ApiVersion {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl ApiVersion {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &i16, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &i16, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("apiKey".into(), JsonValue::to_json(key));
+        node.insert("minVersion".into(), JsonValue::to_json(&self.min_version));
+        node.insert("maxVersion".into(), JsonValue::to_json(&self.max_version));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(i16, Self), JsonError> {
+        let object = crate::json::object(node, "ApiVersion")?;
+        let mut this = Self::default();
+        let mut key = i16::default();
+        if let Some(value) = object.get("apiKey") {
+            key = JsonValue::from_json(value, "ApiVersion.apiKey")?;
+        } else {
+            return Err(crate::json::missing("ApiVersion", "apiKey", version));
+        }
+        if let Some(value) = object.get("minVersion") {
+            this.min_version = JsonValue::from_json(value, "ApiVersion.minVersion")?;
+        } else {
+            return Err(crate::json::missing("ApiVersion", "minVersion", version));
+        }
+        if let Some(value) = object.get("maxVersion") {
+            this.max_version = JsonValue::from_json(value, "ApiVersion.maxVersion")?;
+        } else {
+            return Err(crate::json::missing("ApiVersion", "maxVersion", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct SupportedFeatureKey {
     /// The minimum supported version for the feature.
@@ -136,7 +213,7 @@ pub struct SupportedFeatureKey {
     pub max_version: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for SupportedFeatureKey {
@@ -150,71 +227,75 @@ impl Builder for SupportedFeatureKey {
 impl MapEncodable for SupportedFeatureKey {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("SupportedFeatureKey", "name", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SupportedFeatureKey", "name", version));
             }
         }
         if version >= 3 {
-            types::Int16.encode(buf, &self.min_version)?;
+            types::Int16.encode(buf, &self.min_version).map_err(|e| e.with_field("SupportedFeatureKey", "min_version", version))?;
         } else {
             if self.min_version != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SupportedFeatureKey", "min_version", version));
             }
         }
         if version >= 3 {
-            types::Int16.encode(buf, &self.max_version)?;
+            types::Int16.encode(buf, &self.max_version).map_err(|e| e.with_field("SupportedFeatureKey", "max_version", version))?;
         } else {
             if self.max_version != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SupportedFeatureKey", "max_version", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SupportedFeatureKey", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("SupportedFeatureKey", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("SupportedFeatureKey", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("SupportedFeatureKey", "name", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SupportedFeatureKey", "name", version));
             }
         }
         if version >= 3 {
-            total_size += types::Int16.compute_size(&self.min_version)?;
+            total_size += types::Int16.compute_size(&self.min_version).map_err(|e| e.with_field("SupportedFeatureKey", "min_version", version))?;
         } else {
             if self.min_version != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SupportedFeatureKey", "min_version", version));
             }
         }
         if version >= 3 {
-            total_size += types::Int16.compute_size(&self.max_version)?;
+            total_size += types::Int16.compute_size(&self.max_version).map_err(|e| e.with_field("SupportedFeatureKey", "max_version", version))?;
         } else {
             if self.max_version != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SupportedFeatureKey", "max_version", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SupportedFeatureKey", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("SupportedFeatureKey", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("SupportedFeatureKey", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -223,29 +304,29 @@ impl MapEncodable for SupportedFeatureKey {
 impl MapDecodable for SupportedFeatureKey {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SupportedFeatureKey", "name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let min_version = if version >= 3 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("SupportedFeatureKey", "min_version", version, start - buf.remaining()))?
         } else {
             0
         };
         let max_version = if version >= 3 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("SupportedFeatureKey", "max_version", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SupportedFeatureKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SupportedFeatureKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SupportedFeatureKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("SupportedFeatureKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -262,7 +343,7 @@ impl Default for SupportedFeatureKey {
         Self {
             min_version: 0,
             max_version: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -271,9 +352,98 @@ impl Message for SupportedFeatureKey {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl SupportedFeatureKey {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.min_version = 0;
+        }
+        if version < 3 {
+            self.max_version = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.min_version != 0 {
+                invalid.push(format!("{}min_version", path));
+            }
+        }
+        if version < 3 {
+            if self.max_version != 0 {
+                invalid.push(format!("{}max_version", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl SupportedFeatureKey {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("name".into(), JsonValue::to_json(key));
+        }
+        if version >= 3 {
+            node.insert("minVersion".into(), JsonValue::to_json(&self.min_version));
+        }
+        if version >= 3 {
+            node.insert("maxVersion".into(), JsonValue::to_json(&self.max_version));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "SupportedFeatureKey")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "SupportedFeatureKey.name")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("SupportedFeatureKey", "name", version));
+        }
+        if let Some(value) = object.get("minVersion") {
+            this.min_version = JsonValue::from_json(value, "SupportedFeatureKey.minVersion")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("SupportedFeatureKey", "minVersion", version));
+        }
+        if let Some(value) = object.get("maxVersion") {
+            this.max_version = JsonValue::from_json(value, "SupportedFeatureKey.maxVersion")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("SupportedFeatureKey", "maxVersion", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct FinalizedFeatureKey {
     /// The cluster-wide finalized max version level for the feature.
@@ -287,7 +457,7 @@ pub struct FinalizedFeatureKey {
     pub min_version_level: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for FinalizedFeatureKey {
@@ -301,71 +471,75 @@ impl Builder for FinalizedFeatureKey {
 impl MapEncodable for FinalizedFeatureKey {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("FinalizedFeatureKey", "name", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FinalizedFeatureKey", "name", version));
             }
         }
         if version >= 3 {
-            types::Int16.encode(buf, &self.max_version_level)?;
+            types::Int16.encode(buf, &self.max_version_level).map_err(|e| e.with_field("FinalizedFeatureKey", "max_version_level", version))?;
         } else {
             if self.max_version_level != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FinalizedFeatureKey", "max_version_level", version));
             }
         }
         if version >= 3 {
-            types::Int16.encode(buf, &self.min_version_level)?;
+            types::Int16.encode(buf, &self.min_version_level).map_err(|e| e.with_field("FinalizedFeatureKey", "min_version_level", version))?;
         } else {
             if self.min_version_level != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FinalizedFeatureKey", "min_version_level", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FinalizedFeatureKey", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FinalizedFeatureKey", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FinalizedFeatureKey", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("FinalizedFeatureKey", "name", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FinalizedFeatureKey", "name", version));
             }
         }
         if version >= 3 {
-            total_size += types::Int16.compute_size(&self.max_version_level)?;
+            total_size += types::Int16.compute_size(&self.max_version_level).map_err(|e| e.with_field("FinalizedFeatureKey", "max_version_level", version))?;
         } else {
             if self.max_version_level != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FinalizedFeatureKey", "max_version_level", version));
             }
         }
         if version >= 3 {
-            total_size += types::Int16.compute_size(&self.min_version_level)?;
+            total_size += types::Int16.compute_size(&self.min_version_level).map_err(|e| e.with_field("FinalizedFeatureKey", "min_version_level", version))?;
         } else {
             if self.min_version_level != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FinalizedFeatureKey", "min_version_level", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FinalizedFeatureKey", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FinalizedFeatureKey", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FinalizedFeatureKey", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -374,29 +548,29 @@ impl MapEncodable for FinalizedFeatureKey {
 impl MapDecodable for FinalizedFeatureKey {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("FinalizedFeatureKey", "name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let max_version_level = if version >= 3 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("FinalizedFeatureKey", "max_version_level", version, start - buf.remaining()))?
         } else {
             0
         };
         let min_version_level = if version >= 3 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("FinalizedFeatureKey", "min_version_level", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FinalizedFeatureKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FinalizedFeatureKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FinalizedFeatureKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FinalizedFeatureKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -413,7 +587,7 @@ impl Default for FinalizedFeatureKey {
         Self {
             max_version_level: 0,
             min_version_level: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -422,9 +596,98 @@ impl Message for FinalizedFeatureKey {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl FinalizedFeatureKey {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.max_version_level = 0;
+        }
+        if version < 3 {
+            self.min_version_level = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.max_version_level != 0 {
+                invalid.push(format!("{}max_version_level", path));
+            }
+        }
+        if version < 3 {
+            if self.min_version_level != 0 {
+                invalid.push(format!("{}min_version_level", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FinalizedFeatureKey {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("name".into(), JsonValue::to_json(key));
+        }
+        if version >= 3 {
+            node.insert("maxVersionLevel".into(), JsonValue::to_json(&self.max_version_level));
+        }
+        if version >= 3 {
+            node.insert("minVersionLevel".into(), JsonValue::to_json(&self.min_version_level));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "FinalizedFeatureKey")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "FinalizedFeatureKey.name")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("FinalizedFeatureKey", "name", version));
+        }
+        if let Some(value) = object.get("maxVersionLevel") {
+            this.max_version_level = JsonValue::from_json(value, "FinalizedFeatureKey.maxVersionLevel")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("FinalizedFeatureKey", "maxVersionLevel", version));
+        }
+        if let Some(value) = object.get("minVersionLevel") {
+            this.min_version_level = JsonValue::from_json(value, "FinalizedFeatureKey.minVersionLevel")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("FinalizedFeatureKey", "minVersionLevel", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ApiVersionsResponse {
     /// The top-level error code.
@@ -445,6 +708,7 @@ pub struct ApiVersionsResponse {
     /// Features supported by the broker.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub supported_features: indexmap::IndexMap<StrBytes, SupportedFeatureKey>,
 
     /// The monotonically increasing epoch for the finalized features information. Valid values are >= 0. A value of -1 is special and represents unknown epoch.
@@ -455,6 +719,7 @@ pub struct ApiVersionsResponse {
     /// List of cluster-wide finalized features. The information is valid only if FinalizedFeaturesEpoch >= 0.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub finalized_features: indexmap::IndexMap<StrBytes, FinalizedFeatureKey>,
 
     /// Set by a KRaft controller if the required configurations for ZK migration are present
@@ -463,7 +728,7 @@ pub struct ApiVersionsResponse {
     pub zk_migration_ready: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ApiVersionsResponse {
@@ -476,14 +741,23 @@ impl Builder for ApiVersionsResponse {
 
 impl Encodable for ApiVersionsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ApiVersionsResponse", version));
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("ApiVersionsResponse", "error_code", version))?;
         if version >= 3 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.api_keys)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.api_keys).map_err(|e| e.with_field("ApiVersionsResponse", "api_keys", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.api_keys)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.api_keys).map_err(|e| e.with_field("ApiVersionsResponse", "api_keys", version))?;
         }
         if version >= 1 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("ApiVersionsResponse", "throttle_time_ms", version))?;
         }
         if version >= 3 {
             let mut num_tagged_fields = self.unknown_tagged_fields.len();
@@ -500,65 +774,63 @@ impl Encodable for ApiVersionsResponse {
                 num_tagged_fields += 1;
             }
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version))?;
             if !self.supported_features.is_empty() {
-                let computed_size = types::CompactArray(types::Struct { version }).compute_size(&self.supported_features)?;
+                let computed_size = cache.size_of(&self.supported_features, || types::CompactArray(CachedStruct { version, cache }).compute_size(&self.supported_features)).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "supported_features", version));
                 }
-                types::UnsignedVarInt.encode(buf, 0)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::CompactArray(types::Struct { version }).encode(buf, &self.supported_features)?;
+                types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.supported_features).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
             }
             if self.finalized_features_epoch != -1 {
-                let computed_size = types::Int64.compute_size(&self.finalized_features_epoch)?;
+                let computed_size = types::Int64.compute_size(&self.finalized_features_epoch).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "finalized_features_epoch", version));
                 }
-                types::UnsignedVarInt.encode(buf, 1)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::Int64.encode(buf, &self.finalized_features_epoch)?;
+                types::UnsignedVarInt.encode(buf, 1).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
+                types::Int64.encode(buf, &self.finalized_features_epoch).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
             }
             if !self.finalized_features.is_empty() {
-                let computed_size = types::CompactArray(types::Struct { version }).compute_size(&self.finalized_features)?;
+                let computed_size = cache.size_of(&self.finalized_features, || types::CompactArray(CachedStruct { version, cache }).compute_size(&self.finalized_features)).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "finalized_features", version));
                 }
-                types::UnsignedVarInt.encode(buf, 2)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::CompactArray(types::Struct { version }).encode(buf, &self.finalized_features)?;
+                types::UnsignedVarInt.encode(buf, 2).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.finalized_features).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
             }
             if self.zk_migration_ready {
-                let computed_size = types::Boolean.compute_size(&self.zk_migration_ready)?;
+                let computed_size = types::Boolean.compute_size(&self.zk_migration_ready).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "zk_migration_ready", version));
                 }
-                types::UnsignedVarInt.encode(buf, 3)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::Boolean.encode(buf, &self.zk_migration_ready)?;
+                types::UnsignedVarInt.encode(buf, 3).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
+                types::Boolean.encode(buf, &self.zk_migration_ready).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
             }
 
-            write_unknown_tagged_fields(buf, 4.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 4.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ApiVersionsResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("ApiVersionsResponse", "error_code", version))?;
         if version >= 3 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.api_keys)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.api_keys).map_err(|e| e.with_field("ApiVersionsResponse", "api_keys", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.api_keys)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.api_keys).map_err(|e| e.with_field("ApiVersionsResponse", "api_keys", version))?;
         }
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("ApiVersionsResponse", "throttle_time_ms", version))?;
         }
         if version >= 3 {
             let mut num_tagged_fields = self.unknown_tagged_fields.len();
@@ -575,52 +847,47 @@ impl Encodable for ApiVersionsResponse {
                 num_tagged_fields += 1;
             }
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version))?;
             if !self.supported_features.is_empty() {
-                let computed_size = types::CompactArray(types::Struct { version }).compute_size(&self.supported_features)?;
+                let computed_size = cache.size_of(&self.supported_features, || types::CompactArray(CachedStruct { version, cache }).compute_size(&self.supported_features)).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "supported_features", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(0)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
                 total_size += computed_size;
             }
             if self.finalized_features_epoch != -1 {
-                let computed_size = types::Int64.compute_size(&self.finalized_features_epoch)?;
+                let computed_size = types::Int64.compute_size(&self.finalized_features_epoch).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "finalized_features_epoch", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(1)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(1).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
                 total_size += computed_size;
             }
             if !self.finalized_features.is_empty() {
-                let computed_size = types::CompactArray(types::Struct { version }).compute_size(&self.finalized_features)?;
+                let computed_size = cache.size_of(&self.finalized_features, || types::CompactArray(CachedStruct { version, cache }).compute_size(&self.finalized_features)).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "finalized_features", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(2)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(2).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
                 total_size += computed_size;
             }
             if self.zk_migration_ready {
-                let computed_size = types::Boolean.compute_size(&self.zk_migration_ready)?;
+                let computed_size = types::Boolean.compute_size(&self.zk_migration_ready).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "zk_migration_ready", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(3)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(3).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
                 total_size += computed_size;
             }
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -628,14 +895,18 @@ impl Encodable for ApiVersionsResponse {
 
 impl Decodable for ApiVersionsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ApiVersionsResponse", version));
+        }
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "error_code", version, start - buf.remaining()))?;
         let api_keys = if version >= 3 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "api_keys", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "api_keys", version, start - buf.remaining()))?
         };
         let throttle_time_ms = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
@@ -643,28 +914,27 @@ impl Decodable for ApiVersionsResponse {
         let mut finalized_features_epoch = -1;
         let mut finalized_features = Default::default();
         let mut zk_migration_ready = false;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 match tag {
                     0 => {
-                        supported_features = types::CompactArray(types::Struct { version }).decode(buf)?;
+                        supported_features = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version, start - buf.remaining()))?;
                     },
                     1 => {
-                        finalized_features_epoch = types::Int64.decode(buf)?;
+                        finalized_features_epoch = types::Int64.decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version, start - buf.remaining()))?;
                     },
                     2 => {
-                        finalized_features = types::CompactArray(types::Struct { version }).decode(buf)?;
+                        finalized_features = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version, start - buf.remaining()))?;
                     },
                     3 => {
-                        zk_migration_ready = types::Boolean.decode(buf)?;
+                        zk_migration_ready = types::Boolean.decode(buf).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version, start - buf.remaining()))?;
                     },
                     _ => {
-                        let mut unknown_value = vec![0; size as usize];
-                        buf.try_copy_to_slice(&mut unknown_value)?;
+                        let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                         unknown_tagged_fields.insert(tag as i32, unknown_value);
                     }
                 }
@@ -693,7 +963,7 @@ impl Default for ApiVersionsResponse {
             finalized_features_epoch: -1,
             finalized_features: Default::default(),
             zk_migration_ready: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -702,6 +972,254 @@ impl Message for ApiVersionsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl ApiVersionsResponse {
+    /// Encode the message with the elements of `api_keys` produced by an iterator of known length, in place of
+    /// the `api_keys` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_api_keys<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, api_keys: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (i16, ApiVersion)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ApiVersionsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let api_keys = api_keys.into_iter();
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("ApiVersionsResponse", "error_code", version))?;
+        if version >= 3 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(api_keys)).map_err(|e| e.with_field("ApiVersionsResponse", "api_keys", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(api_keys)).map_err(|e| e.with_field("ApiVersionsResponse", "api_keys", version))?;
+        }
+        if version >= 1 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("ApiVersionsResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 3 {
+            let mut num_tagged_fields = self.unknown_tagged_fields.len();
+            if !self.supported_features.is_empty() {
+                num_tagged_fields += 1;
+            }
+            if self.finalized_features_epoch != -1 {
+                num_tagged_fields += 1;
+            }
+            if !self.finalized_features.is_empty() {
+                num_tagged_fields += 1;
+            }
+            if self.zk_migration_ready {
+                num_tagged_fields += 1;
+            }
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version))?;
+            if !self.supported_features.is_empty() {
+                let computed_size = cache.size_of(&self.supported_features, || types::CompactArray(CachedStruct { version, cache }).compute_size(&self.supported_features)).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
+                if computed_size > std::u32::MAX as usize {
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "supported_features", version));
+                }
+                types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.supported_features).map_err(|e| e.with_field("ApiVersionsResponse", "supported_features", version))?;
+            }
+            if self.finalized_features_epoch != -1 {
+                let computed_size = types::Int64.compute_size(&self.finalized_features_epoch).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
+                if computed_size > std::u32::MAX as usize {
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "finalized_features_epoch", version));
+                }
+                types::UnsignedVarInt.encode(buf, 1).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
+                types::Int64.encode(buf, &self.finalized_features_epoch).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features_epoch", version))?;
+            }
+            if !self.finalized_features.is_empty() {
+                let computed_size = cache.size_of(&self.finalized_features, || types::CompactArray(CachedStruct { version, cache }).compute_size(&self.finalized_features)).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
+                if computed_size > std::u32::MAX as usize {
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "finalized_features", version));
+                }
+                types::UnsignedVarInt.encode(buf, 2).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.finalized_features).map_err(|e| e.with_field("ApiVersionsResponse", "finalized_features", version))?;
+            }
+            if self.zk_migration_ready {
+                let computed_size = types::Boolean.compute_size(&self.zk_migration_ready).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
+                if computed_size > std::u32::MAX as usize {
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ApiVersionsResponse", "zk_migration_ready", version));
+                }
+                types::UnsignedVarInt.encode(buf, 3).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
+                types::Boolean.encode(buf, &self.zk_migration_ready).map_err(|e| e.with_field("ApiVersionsResponse", "zk_migration_ready", version))?;
+            }
+
+            write_unknown_tagged_fields(buf, 4.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ApiVersionsResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl ApiVersionsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.api_keys.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 1 {
+            self.throttle_time_ms = 0;
+        }
+        if version >= 3 {
+            for item in self.supported_features.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.supported_features = Default::default();
+        }
+        if version < 3 {
+            self.finalized_features_epoch = -1;
+        }
+        if version >= 3 {
+            for item in self.finalized_features.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.finalized_features = Default::default();
+        }
+        if version < 3 {
+            self.zk_migration_ready = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.api_keys.values().enumerate() {
+            item.validate_into(version, &format!("{}api_keys[{}].", path, i), invalid);
+        }
+        if version < 1 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        if version >= 3 {
+            for (i, item) in self.supported_features.values().enumerate() {
+                item.validate_into(version, &format!("{}supported_features[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.supported_features.is_empty() {
+                invalid.push(format!("{}supported_features", path));
+            }
+        }
+        if version < 3 {
+            if self.finalized_features_epoch != -1 {
+                invalid.push(format!("{}finalized_features_epoch", path));
+            }
+        }
+        if version >= 3 {
+            for (i, item) in self.finalized_features.values().enumerate() {
+                item.validate_into(version, &format!("{}finalized_features[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.finalized_features.is_empty() {
+                invalid.push(format!("{}finalized_features", path));
+            }
+        }
+        if version < 3 {
+            if self.zk_migration_ready {
+                invalid.push(format!("{}zk_migration_ready", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ApiVersionsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("apiKeys".into(), serde_json::Value::Array(self.api_keys.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        if version >= 1 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        if version >= 3 {
+            if !self.supported_features.is_empty() {
+                node.insert("supportedFeatures".into(), serde_json::Value::Array(self.supported_features.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+            }
+        }
+        if version >= 3 {
+            if self.finalized_features_epoch != -1 {
+                node.insert("finalizedFeaturesEpoch".into(), JsonValue::to_json(&self.finalized_features_epoch));
+            }
+        }
+        if version >= 3 {
+            if !self.finalized_features.is_empty() {
+                node.insert("finalizedFeatures".into(), serde_json::Value::Array(self.finalized_features.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+            }
+        }
+        if version >= 3 {
+            if self.zk_migration_ready {
+                node.insert("zkMigrationReady".into(), JsonValue::to_json(&self.zk_migration_ready));
+            }
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ApiVersionsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "ApiVersionsResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("ApiVersionsResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("apiKeys") {
+            this.api_keys = crate::json::array(value, "ApiVersionsResponse.apiKeys")?.iter().map(|item| ApiVersion::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ApiVersionsResponse", "apiKeys", version));
+        }
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "ApiVersionsResponse.throttleTimeMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("ApiVersionsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("supportedFeatures") {
+            this.supported_features = crate::json::array(value, "ApiVersionsResponse.supportedFeatures")?.iter().map(|item| SupportedFeatureKey::from_json(item, version)).collect::<Result<_, _>>()?;
+        }
+        if let Some(value) = object.get("finalizedFeaturesEpoch") {
+            this.finalized_features_epoch = JsonValue::from_json(value, "ApiVersionsResponse.finalizedFeaturesEpoch")?;
+        }
+        if let Some(value) = object.get("finalizedFeatures") {
+            this.finalized_features = crate::json::array(value, "ApiVersionsResponse.finalizedFeatures")?.iter().map(|item| FinalizedFeatureKey::from_json(item, version)).collect::<Result<_, _>>()?;
+        }
+        if let Some(value) = object.get("zkMigrationReady") {
+            this.zk_migration_ready = JsonValue::from_json(value, "ApiVersionsResponse.zkMigrationReady")?;
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for ApiVersionsResponse {
     fn header_version(version: i16) -> i16 {
         0
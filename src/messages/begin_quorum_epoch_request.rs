@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct PartitionData {
     /// The partition index.
@@ -49,17 +55,23 @@ impl Builder for PartitionData {
 
 impl Encodable for PartitionData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int32.encode(buf, &self.leader_id)?;
-        types::Int32.encode(buf, &self.leader_epoch)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        types::Int32.encode(buf, &self.leader_id).map_err(|e| e.with_field("PartitionData", "leader_id", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionData", "leader_epoch", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int32.compute_size(&self.leader_id)?;
-        total_size += types::Int32.compute_size(&self.leader_epoch)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        total_size += types::Int32.compute_size(&self.leader_id).map_err(|e| e.with_field("PartitionData", "leader_id", version))?;
+        total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("PartitionData", "leader_epoch", version))?;
 
         Ok(total_size)
     }
@@ -67,9 +79,10 @@ impl Encodable for PartitionData {
 
 impl Decodable for PartitionData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let leader_id = types::Int32.decode(buf)?;
-        let leader_epoch = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "partition_index", version, start - buf.remaining()))?;
+        let leader_id = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_id", version, start - buf.remaining()))?;
+        let leader_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_epoch", version, start - buf.remaining()))?;
         Ok(Self {
             partition_index,
             leader_id,
@@ -92,9 +105,75 @@ impl Message for PartitionData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl PartitionData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("leaderId".into(), JsonValue::to_json(&self.leader_id));
+        node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "PartitionData.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("leaderId") {
+            this.leader_id = JsonValue::from_json(value, "PartitionData.leaderId")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "leaderId", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "PartitionData.leaderEpoch")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "leaderEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicData {
     /// The topic name.
@@ -119,15 +198,21 @@ impl Builder for TopicData {
 
 impl Encodable for TopicData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::String.encode(buf, &self.topic_name)?;
-        types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::String.encode(buf, &self.topic_name).map_err(|e| e.with_field("TopicData", "topic_name", version))?;
+        types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicData", "partitions", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::String.compute_size(&self.topic_name)?;
-        total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+        total_size += types::String.compute_size(&self.topic_name).map_err(|e| e.with_field("TopicData", "topic_name", version))?;
+        total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("TopicData", "partitions", version))?;
 
         Ok(total_size)
     }
@@ -135,8 +220,9 @@ impl Encodable for TopicData {
 
 impl Decodable for TopicData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let topic_name = types::String.decode(buf)?;
-        let partitions = types::Array(types::Struct { version }).decode(buf)?;
+        let start = buf.remaining();
+        let topic_name = types::String.decode(buf).map_err(|e| e.with_field("TopicData", "topic_name", version, start - buf.remaining()))?;
+        let partitions = types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TopicData", "partitions", version, start - buf.remaining()))?;
         Ok(Self {
             topic_name,
             partitions,
@@ -157,14 +243,82 @@ impl Message for TopicData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl TopicData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TopicData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "TopicData.topicName")?;
+        } else {
+            return Err(crate::json::missing("TopicData", "topicName", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "TopicData.partitions")?.iter().map(|item| PartitionData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("TopicData", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct BeginQuorumEpochRequest {
     /// 
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub cluster_id: Option<StrBytes>,
 
     /// 
@@ -184,15 +338,27 @@ impl Builder for BeginQuorumEpochRequest {
 
 impl Encodable for BeginQuorumEpochRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::String.encode(buf, &self.cluster_id)?;
-        types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BeginQuorumEpochRequest", version));
+        }
+        types::String.encode(buf, &self.cluster_id).map_err(|e| e.with_field("BeginQuorumEpochRequest", "cluster_id", version))?;
+        types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("BeginQuorumEpochRequest", "topics", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BeginQuorumEpochRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::String.compute_size(&self.cluster_id)?;
-        total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+        total_size += types::String.compute_size(&self.cluster_id).map_err(|e| e.with_field("BeginQuorumEpochRequest", "cluster_id", version))?;
+        total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("BeginQuorumEpochRequest", "topics", version))?;
 
         Ok(total_size)
     }
@@ -200,8 +366,12 @@ impl Encodable for BeginQuorumEpochRequest {
 
 impl Decodable for BeginQuorumEpochRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let cluster_id = types::String.decode(buf)?;
-        let topics = types::Array(types::Struct { version }).decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("BeginQuorumEpochRequest", version));
+        }
+        let start = buf.remaining();
+        let cluster_id = types::String.decode(buf).map_err(|e| e.with_field("BeginQuorumEpochRequest", "cluster_id", version, start - buf.remaining()))?;
+        let topics = types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("BeginQuorumEpochRequest", "topics", version, start - buf.remaining()))?;
         Ok(Self {
             cluster_id,
             topics,
@@ -222,9 +392,127 @@ impl Message for BeginQuorumEpochRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl BeginQuorumEpochRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = TopicData>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BeginQuorumEpochRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        types::String.encode(buf, &self.cluster_id).map_err(|e| e.with_field("BeginQuorumEpochRequest", "cluster_id", version))?;
+        types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("BeginQuorumEpochRequest", "topics", version))?;
+
+        Ok(())
+    }
+}
+
+impl BeginQuorumEpochRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl BeginQuorumEpochRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("clusterId".into(), JsonValue::to_json(&self.cluster_id));
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "BeginQuorumEpochRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("clusterId") {
+            this.cluster_id = JsonValue::from_json(value, "BeginQuorumEpochRequest.clusterId")?;
+        } else {
+            return Err(crate::json::missing("BeginQuorumEpochRequest", "clusterId", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "BeginQuorumEpochRequest.topics")?.iter().map(|item| TopicData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("BeginQuorumEpochRequest", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for BeginQuorumEpochRequest {
     fn header_version(version: i16) -> i16 {
         1
     }
 }
 
+impl BeginQuorumEpochRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::BeginQuorumEpochResponse {
+        let mut response = super::BeginQuorumEpochResponse::default();
+        response.error_code = error.code();
+        let mut items0 = Vec::new();
+        for request0 in &self.topics {
+            let mut response0 = super::begin_quorum_epoch_response::TopicData::default();
+            response0.topic_name = request0.topic_name.clone();
+            let mut items1 = Vec::new();
+            for request1 in &request0.partitions {
+                let mut response1 = super::begin_quorum_epoch_response::PartitionData::default();
+                response1.partition_index = request1.partition_index;
+                response1.error_code = error.code();
+                response1.leader_id = request1.leader_id;
+                items1.push(response1);
+            }
+            response0.partitions = items1;
+            items0.push(response0);
+        }
+        response.topics = items0;
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct PartitionData {
     /// The partition index.
@@ -54,19 +60,25 @@ impl Builder for PartitionData {
 
 impl Encodable for PartitionData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::Int32.encode(buf, &self.leader_id)?;
-        types::Int32.encode(buf, &self.leader_epoch)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("PartitionData", "error_code", version))?;
+        types::Int32.encode(buf, &self.leader_id).map_err(|e| e.with_field("PartitionData", "leader_id", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionData", "leader_epoch", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::Int32.compute_size(&self.leader_id)?;
-        total_size += types::Int32.compute_size(&self.leader_epoch)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("PartitionData", "error_code", version))?;
+        total_size += types::Int32.compute_size(&self.leader_id).map_err(|e| e.with_field("PartitionData", "leader_id", version))?;
+        total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("PartitionData", "leader_epoch", version))?;
 
         Ok(total_size)
     }
@@ -74,10 +86,11 @@ impl Encodable for PartitionData {
 
 impl Decodable for PartitionData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let leader_id = types::Int32.decode(buf)?;
-        let leader_epoch = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "partition_index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("PartitionData", "error_code", version, start - buf.remaining()))?;
+        let leader_id = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_id", version, start - buf.remaining()))?;
+        let leader_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "leader_epoch", version, start - buf.remaining()))?;
         Ok(Self {
             partition_index,
             error_code,
@@ -102,9 +115,81 @@ impl Message for PartitionData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl PartitionData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("leaderId".into(), JsonValue::to_json(&self.leader_id));
+        node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "PartitionData.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "PartitionData.errorCode")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "errorCode", version));
+        }
+        if let Some(value) = object.get("leaderId") {
+            this.leader_id = JsonValue::from_json(value, "PartitionData.leaderId")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "leaderId", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "PartitionData.leaderEpoch")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "leaderEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicData {
     /// The topic name.
@@ -129,15 +214,21 @@ impl Builder for TopicData {
 
 impl Encodable for TopicData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::String.encode(buf, &self.topic_name)?;
-        types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::String.encode(buf, &self.topic_name).map_err(|e| e.with_field("TopicData", "topic_name", version))?;
+        types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicData", "partitions", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::String.compute_size(&self.topic_name)?;
-        total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+        total_size += types::String.compute_size(&self.topic_name).map_err(|e| e.with_field("TopicData", "topic_name", version))?;
+        total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("TopicData", "partitions", version))?;
 
         Ok(total_size)
     }
@@ -145,8 +236,9 @@ impl Encodable for TopicData {
 
 impl Decodable for TopicData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let topic_name = types::String.decode(buf)?;
-        let partitions = types::Array(types::Struct { version }).decode(buf)?;
+        let start = buf.remaining();
+        let topic_name = types::String.decode(buf).map_err(|e| e.with_field("TopicData", "topic_name", version, start - buf.remaining()))?;
+        let partitions = types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TopicData", "partitions", version, start - buf.remaining()))?;
         Ok(Self {
             topic_name,
             partitions,
@@ -167,9 +259,75 @@ impl Message for TopicData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl TopicData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TopicData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "TopicData.topicName")?;
+        } else {
+            return Err(crate::json::missing("TopicData", "topicName", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "TopicData.partitions")?.iter().map(|item| PartitionData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("TopicData", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct BeginQuorumEpochResponse {
     /// The top level error code.
@@ -194,15 +352,27 @@ impl Builder for BeginQuorumEpochResponse {
 
 impl Encodable for BeginQuorumEpochResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
-        types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BeginQuorumEpochResponse", version));
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("BeginQuorumEpochResponse", "error_code", version))?;
+        types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("BeginQuorumEpochResponse", "topics", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BeginQuorumEpochResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("BeginQuorumEpochResponse", "error_code", version))?;
+        total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("BeginQuorumEpochResponse", "topics", version))?;
 
         Ok(total_size)
     }
@@ -210,8 +380,12 @@ impl Encodable for BeginQuorumEpochResponse {
 
 impl Decodable for BeginQuorumEpochResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
-        let topics = types::Array(types::Struct { version }).decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("BeginQuorumEpochResponse", version));
+        }
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("BeginQuorumEpochResponse", "error_code", version, start - buf.remaining()))?;
+        let topics = types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("BeginQuorumEpochResponse", "topics", version, start - buf.remaining()))?;
         Ok(Self {
             error_code,
             topics,
@@ -232,6 +406,95 @@ impl Message for BeginQuorumEpochResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl BeginQuorumEpochResponse {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = TopicData>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BeginQuorumEpochResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("BeginQuorumEpochResponse", "error_code", version))?;
+        types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("BeginQuorumEpochResponse", "topics", version))?;
+
+        Ok(())
+    }
+}
+
+impl BeginQuorumEpochResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl BeginQuorumEpochResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "BeginQuorumEpochResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "BeginQuorumEpochResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("BeginQuorumEpochResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "BeginQuorumEpochResponse.topics")?.iter().map(|item| TopicData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("BeginQuorumEpochResponse", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for BeginQuorumEpochResponse {
     fn header_version(version: i16) -> i16 {
         0
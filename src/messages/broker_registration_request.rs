@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct Listener {
     /// The hostname.
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The port.
@@ -38,7 +45,7 @@ pub struct Listener {
     pub security_protocol: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for Listener {
@@ -52,34 +59,38 @@ impl Builder for Listener {
 impl MapEncodable for Listener {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, key)?;
-        types::CompactString.encode(buf, &self.host)?;
-        types::UInt16.encode(buf, &self.port)?;
-        types::Int16.encode(buf, &self.security_protocol)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, key).map_err(|e| e.with_field("Listener", "name", version))?;
+        types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("Listener", "host", version))?;
+        types::UInt16.encode(buf, &self.port).map_err(|e| e.with_field("Listener", "port", version))?;
+        types::Int16.encode(buf, &self.security_protocol).map_err(|e| e.with_field("Listener", "security_protocol", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Listener", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("Listener", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("Listener", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(key)?;
-        total_size += types::CompactString.compute_size(&self.host)?;
-        total_size += types::UInt16.compute_size(&self.port)?;
-        total_size += types::Int16.compute_size(&self.security_protocol)?;
+        total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("Listener", "name", version))?;
+        total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("Listener", "host", version))?;
+        total_size += types::UInt16.compute_size(&self.port).map_err(|e| e.with_field("Listener", "port", version))?;
+        total_size += types::Int16.compute_size(&self.security_protocol).map_err(|e| e.with_field("Listener", "security_protocol", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Listener", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("Listener", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("Listener", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
@@ -87,17 +98,17 @@ impl MapEncodable for Listener {
 impl MapDecodable for Listener {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::CompactString.decode(buf)?;
-        let host = types::CompactString.decode(buf)?;
-        let port = types::UInt16.decode(buf)?;
-        let security_protocol = types::Int16.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let key_field = types::CompactString.decode(buf).map_err(|e| e.with_field("Listener", "name", version, start - buf.remaining()))?;
+        let host = types::CompactString.decode(buf).map_err(|e| e.with_field("Listener", "host", version, start - buf.remaining()))?;
+        let port = types::UInt16.decode(buf).map_err(|e| e.with_field("Listener", "port", version, start - buf.remaining()))?;
+        let security_protocol = types::Int16.decode(buf).map_err(|e| e.with_field("Listener", "security_protocol", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Listener", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Listener", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Listener", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("Listener", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok((key_field, Self {
@@ -115,7 +126,7 @@ impl Default for Listener {
             host: Default::default(),
             port: 0,
             security_protocol: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -124,9 +135,82 @@ impl Message for Listener {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl Listener {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl Listener {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("host".into(), JsonValue::to_json(&self.host));
+        node.insert("port".into(), JsonValue::to_json(&self.port));
+        node.insert("securityProtocol".into(), JsonValue::to_json(&self.security_protocol));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "Listener")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "Listener.name")?;
+        } else {
+            return Err(crate::json::missing("Listener", "name", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "Listener.host")?;
+        } else {
+            return Err(crate::json::missing("Listener", "host", version));
+        }
+        if let Some(value) = object.get("port") {
+            this.port = JsonValue::from_json(value, "Listener.port")?;
+        } else {
+            return Err(crate::json::missing("Listener", "port", version));
+        }
+        if let Some(value) = object.get("securityProtocol") {
+            this.security_protocol = JsonValue::from_json(value, "Listener.securityProtocol")?;
+        } else {
+            return Err(crate::json::missing("Listener", "securityProtocol", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct Feature {
     /// The minimum supported feature level.
@@ -140,7 +224,7 @@ pub struct Feature {
     pub max_supported_version: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for Feature {
@@ -154,32 +238,36 @@ impl Builder for Feature {
 impl MapEncodable for Feature {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, key)?;
-        types::Int16.encode(buf, &self.min_supported_version)?;
-        types::Int16.encode(buf, &self.max_supported_version)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, key).map_err(|e| e.with_field("Feature", "name", version))?;
+        types::Int16.encode(buf, &self.min_supported_version).map_err(|e| e.with_field("Feature", "min_supported_version", version))?;
+        types::Int16.encode(buf, &self.max_supported_version).map_err(|e| e.with_field("Feature", "max_supported_version", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Feature", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("Feature", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("Feature", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(key)?;
-        total_size += types::Int16.compute_size(&self.min_supported_version)?;
-        total_size += types::Int16.compute_size(&self.max_supported_version)?;
+        total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("Feature", "name", version))?;
+        total_size += types::Int16.compute_size(&self.min_supported_version).map_err(|e| e.with_field("Feature", "min_supported_version", version))?;
+        total_size += types::Int16.compute_size(&self.max_supported_version).map_err(|e| e.with_field("Feature", "max_supported_version", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Feature", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("Feature", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("Feature", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
@@ -187,16 +275,16 @@ impl MapEncodable for Feature {
 impl MapDecodable for Feature {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::CompactString.decode(buf)?;
-        let min_supported_version = types::Int16.decode(buf)?;
-        let max_supported_version = types::Int16.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let key_field = types::CompactString.decode(buf).map_err(|e| e.with_field("Feature", "name", version, start - buf.remaining()))?;
+        let min_supported_version = types::Int16.decode(buf).map_err(|e| e.with_field("Feature", "min_supported_version", version, start - buf.remaining()))?;
+        let max_supported_version = types::Int16.decode(buf).map_err(|e| e.with_field("Feature", "max_supported_version", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Feature", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Feature", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Feature", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("Feature", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok((key_field, Self {
@@ -212,7 +300,7 @@ impl Default for Feature {
         Self {
             min_supported_version: 0,
             max_supported_version: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -221,9 +309,76 @@ impl Message for Feature {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl Feature {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl Feature {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("minSupportedVersion".into(), JsonValue::to_json(&self.min_supported_version));
+        node.insert("maxSupportedVersion".into(), JsonValue::to_json(&self.max_supported_version));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "Feature")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "Feature.name")?;
+        } else {
+            return Err(crate::json::missing("Feature", "name", version));
+        }
+        if let Some(value) = object.get("minSupportedVersion") {
+            this.min_supported_version = JsonValue::from_json(value, "Feature.minSupportedVersion")?;
+        } else {
+            return Err(crate::json::missing("Feature", "minSupportedVersion", version));
+        }
+        if let Some(value) = object.get("maxSupportedVersion") {
+            this.max_supported_version = JsonValue::from_json(value, "Feature.maxSupportedVersion")?;
+        } else {
+            return Err(crate::json::missing("Feature", "maxSupportedVersion", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct BrokerRegistrationRequest {
     /// The broker ID.
@@ -234,6 +389,7 @@ pub struct BrokerRegistrationRequest {
     /// The cluster id of the broker process.
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub cluster_id: StrBytes,
 
     /// The incarnation id of the broker process.
@@ -244,16 +400,20 @@ pub struct BrokerRegistrationRequest {
     /// The listeners of this broker
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub listeners: indexmap::IndexMap<StrBytes, Listener>,
 
     /// The features on this broker
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub features: indexmap::IndexMap<StrBytes, Feature>,
 
     /// The rack which this broker is in.
     /// 
     /// Supported API versions: 0-1
+    /// May be `None` in versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub rack: Option<StrBytes>,
 
     /// If the required configurations for ZK migration are present, this value is set to true
@@ -262,7 +422,7 @@ pub struct BrokerRegistrationRequest {
     pub is_migrating_zk_broker: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for BrokerRegistrationRequest {
@@ -275,76 +435,89 @@ impl Builder for BrokerRegistrationRequest {
 
 impl Encodable for BrokerRegistrationRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.broker_id)?;
-        types::CompactString.encode(buf, &self.cluster_id)?;
-        types::Uuid.encode(buf, &self.incarnation_id)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.listeners)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.features)?;
-        types::CompactString.encode(buf, &self.rack)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BrokerRegistrationRequest", version));
+        }
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "broker_id", version))?;
+        types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "cluster_id", version))?;
+        types::Uuid.encode(buf, &self.incarnation_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "incarnation_id", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.listeners).map_err(|e| e.with_field("BrokerRegistrationRequest", "listeners", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.features).map_err(|e| e.with_field("BrokerRegistrationRequest", "features", version))?;
+        types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("BrokerRegistrationRequest", "rack", version))?;
         if version >= 1 {
-            types::Boolean.encode(buf, &self.is_migrating_zk_broker)?;
+            types::Boolean.encode(buf, &self.is_migrating_zk_broker).map_err(|e| e.with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version))?;
         } else {
             if self.is_migrating_zk_broker {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version));
             }
         }
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BrokerRegistrationRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.broker_id)?;
-        total_size += types::CompactString.compute_size(&self.cluster_id)?;
-        total_size += types::Uuid.compute_size(&self.incarnation_id)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.listeners)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.features)?;
-        total_size += types::CompactString.compute_size(&self.rack)?;
+        total_size += types::Int32.compute_size(&self.broker_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "broker_id", version))?;
+        total_size += types::CompactString.compute_size(&self.cluster_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "cluster_id", version))?;
+        total_size += types::Uuid.compute_size(&self.incarnation_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "incarnation_id", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.listeners).map_err(|e| e.with_field("BrokerRegistrationRequest", "listeners", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.features).map_err(|e| e.with_field("BrokerRegistrationRequest", "features", version))?;
+        total_size += types::CompactString.compute_size(&self.rack).map_err(|e| e.with_field("BrokerRegistrationRequest", "rack", version))?;
         if version >= 1 {
-            total_size += types::Boolean.compute_size(&self.is_migrating_zk_broker)?;
+            total_size += types::Boolean.compute_size(&self.is_migrating_zk_broker).map_err(|e| e.with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version))?;
         } else {
             if self.is_migrating_zk_broker {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version));
             }
         }
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for BrokerRegistrationRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let broker_id = types::Int32.decode(buf)?;
-        let cluster_id = types::CompactString.decode(buf)?;
-        let incarnation_id = types::Uuid.decode(buf)?;
-        let listeners = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let features = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let rack = types::CompactString.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("BrokerRegistrationRequest", version));
+        }
+        let start = buf.remaining();
+        let broker_id = types::Int32.decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "broker_id", version, start - buf.remaining()))?;
+        let cluster_id = types::CompactString.decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "cluster_id", version, start - buf.remaining()))?;
+        let incarnation_id = types::Uuid.decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "incarnation_id", version, start - buf.remaining()))?;
+        let listeners = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "listeners", version, start - buf.remaining()))?;
+        let features = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "features", version, start - buf.remaining()))?;
+        let rack = types::CompactString.decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "rack", version, start - buf.remaining()))?;
         let is_migrating_zk_broker = if version >= 1 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -370,7 +543,7 @@ impl Default for BrokerRegistrationRequest {
             features: Default::default(),
             rack: Some(Default::default()),
             is_migrating_zk_broker: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -379,9 +552,214 @@ impl Message for BrokerRegistrationRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl BrokerRegistrationRequest {
+    /// Encode the message with the elements of `listeners` produced by an iterator of known length, in place of
+    /// the `listeners` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_listeners<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, listeners: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (StrBytes, Listener)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BrokerRegistrationRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let listeners = listeners.into_iter();
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "broker_id", version))?;
+        types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "cluster_id", version))?;
+        types::Uuid.encode(buf, &self.incarnation_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "incarnation_id", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(listeners)).map_err(|e| e.with_field("BrokerRegistrationRequest", "listeners", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.features).map_err(|e| e.with_field("BrokerRegistrationRequest", "features", version))?;
+        types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("BrokerRegistrationRequest", "rack", version))?;
+        if version >= 1 {
+            types::Boolean.encode(buf, &self.is_migrating_zk_broker).map_err(|e| e.with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version))?;
+        } else {
+            if self.is_migrating_zk_broker {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version));
+            }
+        }
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `features` produced by an iterator of known length, in place of
+    /// the `features` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_features<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, features: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (StrBytes, Feature)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BrokerRegistrationRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let features = features.into_iter();
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "broker_id", version))?;
+        types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "cluster_id", version))?;
+        types::Uuid.encode(buf, &self.incarnation_id).map_err(|e| e.with_field("BrokerRegistrationRequest", "incarnation_id", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.listeners).map_err(|e| e.with_field("BrokerRegistrationRequest", "listeners", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(features)).map_err(|e| e.with_field("BrokerRegistrationRequest", "features", version))?;
+        types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("BrokerRegistrationRequest", "rack", version))?;
+        if version >= 1 {
+            types::Boolean.encode(buf, &self.is_migrating_zk_broker).map_err(|e| e.with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version))?;
+        } else {
+            if self.is_migrating_zk_broker {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BrokerRegistrationRequest", "is_migrating_zk_broker", version));
+            }
+        }
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerRegistrationRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl BrokerRegistrationRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.listeners.values_mut() {
+            item.normalize_to(version);
+        }
+        for item in self.features.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 1 {
+            self.is_migrating_zk_broker = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.listeners.values().enumerate() {
+            item.validate_into(version, &format!("{}listeners[{}].", path, i), invalid);
+        }
+        for (i, item) in self.features.values().enumerate() {
+            item.validate_into(version, &format!("{}features[{}].", path, i), invalid);
+        }
+        if version < 1 {
+            if self.is_migrating_zk_broker {
+                invalid.push(format!("{}is_migrating_zk_broker", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl BrokerRegistrationRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("brokerId".into(), JsonValue::to_json(&self.broker_id));
+        node.insert("clusterId".into(), JsonValue::to_json(&self.cluster_id));
+        node.insert("incarnationId".into(), JsonValue::to_json(&self.incarnation_id));
+        node.insert("listeners".into(), serde_json::Value::Array(self.listeners.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("features".into(), serde_json::Value::Array(self.features.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("rack".into(), JsonValue::to_json(&self.rack));
+        if version >= 1 {
+            node.insert("isMigratingZkBroker".into(), JsonValue::to_json(&self.is_migrating_zk_broker));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "BrokerRegistrationRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("brokerId") {
+            this.broker_id = JsonValue::from_json(value, "BrokerRegistrationRequest.brokerId")?;
+        } else {
+            return Err(crate::json::missing("BrokerRegistrationRequest", "brokerId", version));
+        }
+        if let Some(value) = object.get("clusterId") {
+            this.cluster_id = JsonValue::from_json(value, "BrokerRegistrationRequest.clusterId")?;
+        } else {
+            return Err(crate::json::missing("BrokerRegistrationRequest", "clusterId", version));
+        }
+        if let Some(value) = object.get("incarnationId") {
+            this.incarnation_id = JsonValue::from_json(value, "BrokerRegistrationRequest.incarnationId")?;
+        } else {
+            return Err(crate::json::missing("BrokerRegistrationRequest", "incarnationId", version));
+        }
+        if let Some(value) = object.get("listeners") {
+            this.listeners = crate::json::array(value, "BrokerRegistrationRequest.listeners")?.iter().map(|item| Listener::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("BrokerRegistrationRequest", "listeners", version));
+        }
+        if let Some(value) = object.get("features") {
+            this.features = crate::json::array(value, "BrokerRegistrationRequest.features")?.iter().map(|item| Feature::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("BrokerRegistrationRequest", "features", version));
+        }
+        if let Some(value) = object.get("rack") {
+            this.rack = JsonValue::from_json(value, "BrokerRegistrationRequest.rack")?;
+        } else {
+            return Err(crate::json::missing("BrokerRegistrationRequest", "rack", version));
+        }
+        if let Some(value) = object.get("isMigratingZkBroker") {
+            this.is_migrating_zk_broker = JsonValue::from_json(value, "BrokerRegistrationRequest.isMigratingZkBroker")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("BrokerRegistrationRequest", "isMigratingZkBroker", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for BrokerRegistrationRequest {
     fn header_version(version: i16) -> i16 {
         2
     }
 }
 
+impl BrokerRegistrationRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::BrokerRegistrationResponse {
+        let mut response = super::BrokerRegistrationResponse::default();
+        response.error_code = error.code();
+        response
+    }
+}
+
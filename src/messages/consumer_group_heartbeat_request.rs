@@ -5,26 +5,35 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
+///
+/// Unstable versions: 0 (requires the `unstable-apis` feature)
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct Assignor {
     /// The name of the assignor.
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub name: StrBytes,
 
     /// The minimum supported version for the metadata.
@@ -53,7 +62,7 @@ pub struct Assignor {
     pub metadata_bytes: Bytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for Assignor {
@@ -66,57 +75,61 @@ impl Builder for Assignor {
 
 impl Encodable for Assignor {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, &self.name)?;
-        types::Int16.encode(buf, &self.minimum_version)?;
-        types::Int16.encode(buf, &self.maximum_version)?;
-        types::Int8.encode(buf, &self.reason)?;
-        types::Int16.encode(buf, &self.metadata_version)?;
-        types::CompactBytes.encode(buf, &self.metadata_bytes)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("Assignor", "name", version))?;
+        types::Int16.encode(buf, &self.minimum_version).map_err(|e| e.with_field("Assignor", "minimum_version", version))?;
+        types::Int16.encode(buf, &self.maximum_version).map_err(|e| e.with_field("Assignor", "maximum_version", version))?;
+        types::Int8.encode(buf, &self.reason).map_err(|e| e.with_field("Assignor", "reason", version))?;
+        types::Int16.encode(buf, &self.metadata_version).map_err(|e| e.with_field("Assignor", "metadata_version", version))?;
+        types::CompactBytes.encode(buf, &self.metadata_bytes).map_err(|e| e.with_field("Assignor", "metadata_bytes", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Assignor", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("Assignor", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("Assignor", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(&self.name)?;
-        total_size += types::Int16.compute_size(&self.minimum_version)?;
-        total_size += types::Int16.compute_size(&self.maximum_version)?;
-        total_size += types::Int8.compute_size(&self.reason)?;
-        total_size += types::Int16.compute_size(&self.metadata_version)?;
-        total_size += types::CompactBytes.compute_size(&self.metadata_bytes)?;
+        total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("Assignor", "name", version))?;
+        total_size += types::Int16.compute_size(&self.minimum_version).map_err(|e| e.with_field("Assignor", "minimum_version", version))?;
+        total_size += types::Int16.compute_size(&self.maximum_version).map_err(|e| e.with_field("Assignor", "maximum_version", version))?;
+        total_size += types::Int8.compute_size(&self.reason).map_err(|e| e.with_field("Assignor", "reason", version))?;
+        total_size += types::Int16.compute_size(&self.metadata_version).map_err(|e| e.with_field("Assignor", "metadata_version", version))?;
+        total_size += types::CompactBytes.compute_size(&self.metadata_bytes).map_err(|e| e.with_field("Assignor", "metadata_bytes", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Assignor", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("Assignor", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("Assignor", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for Assignor {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let name = types::CompactString.decode(buf)?;
-        let minimum_version = types::Int16.decode(buf)?;
-        let maximum_version = types::Int16.decode(buf)?;
-        let reason = types::Int8.decode(buf)?;
-        let metadata_version = types::Int16.decode(buf)?;
-        let metadata_bytes = types::CompactBytes.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let name = types::CompactString.decode(buf).map_err(|e| e.with_field("Assignor", "name", version, start - buf.remaining()))?;
+        let minimum_version = types::Int16.decode(buf).map_err(|e| e.with_field("Assignor", "minimum_version", version, start - buf.remaining()))?;
+        let maximum_version = types::Int16.decode(buf).map_err(|e| e.with_field("Assignor", "maximum_version", version, start - buf.remaining()))?;
+        let reason = types::Int8.decode(buf).map_err(|e| e.with_field("Assignor", "reason", version, start - buf.remaining()))?;
+        let metadata_version = types::Int16.decode(buf).map_err(|e| e.with_field("Assignor", "metadata_version", version, start - buf.remaining()))?;
+        let metadata_bytes = types::CompactBytes.decode(buf).map_err(|e| e.with_field("Assignor", "metadata_bytes", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Assignor", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Assignor", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Assignor", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("Assignor", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -140,18 +153,108 @@ impl Default for Assignor {
             reason: 0,
             metadata_version: 0,
             metadata_bytes: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
 
 impl Message for Assignor {
+    #[cfg(feature = "unstable-apis")]
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+    #[cfg(not(feature = "unstable-apis"))]
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: -1 };
+    const UNSTABLE_VERSIONS: Option<VersionRange> = Some(VersionRange { min: 0, max: 0 });
+}
+
+impl Assignor {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl Assignor {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("minimumVersion".into(), JsonValue::to_json(&self.minimum_version));
+        node.insert("maximumVersion".into(), JsonValue::to_json(&self.maximum_version));
+        node.insert("reason".into(), JsonValue::to_json(&self.reason));
+        node.insert("metadataVersion".into(), JsonValue::to_json(&self.metadata_version));
+        node.insert("metadataBytes".into(), JsonValue::to_json(&self.metadata_bytes));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "Assignor")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "Assignor.name")?;
+        } else {
+            return Err(crate::json::missing("Assignor", "name", version));
+        }
+        if let Some(value) = object.get("minimumVersion") {
+            this.minimum_version = JsonValue::from_json(value, "Assignor.minimumVersion")?;
+        } else {
+            return Err(crate::json::missing("Assignor", "minimumVersion", version));
+        }
+        if let Some(value) = object.get("maximumVersion") {
+            this.maximum_version = JsonValue::from_json(value, "Assignor.maximumVersion")?;
+        } else {
+            return Err(crate::json::missing("Assignor", "maximumVersion", version));
+        }
+        if let Some(value) = object.get("reason") {
+            this.reason = JsonValue::from_json(value, "Assignor.reason")?;
+        } else {
+            return Err(crate::json::missing("Assignor", "reason", version));
+        }
+        if let Some(value) = object.get("metadataVersion") {
+            this.metadata_version = JsonValue::from_json(value, "Assignor.metadataVersion")?;
+        } else {
+            return Err(crate::json::missing("Assignor", "metadataVersion", version));
+        }
+        if let Some(value) = object.get("metadataBytes") {
+            this.metadata_bytes = JsonValue::from_json(value, "Assignor.metadataBytes")?;
+        } else {
+            return Err(crate::json::missing("Assignor", "metadataBytes", version));
+        }
+        Ok(this)
+    }
 }
 
 /// Valid versions: 0
+///
+/// Unstable versions: 0 (requires the `unstable-apis` feature)
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicPartitions {
     /// The topic ID.
@@ -165,7 +268,7 @@ pub struct TopicPartitions {
     pub partitions: Vec<i32>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TopicPartitions {
@@ -178,45 +281,49 @@ impl Builder for TopicPartitions {
 
 impl Encodable for TopicPartitions {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Uuid.encode(buf, &self.topic_id)?;
-        types::CompactArray(types::Int32).encode(buf, &self.partitions)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("TopicPartitions", "topic_id", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicPartitions", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicPartitions", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Uuid.compute_size(&self.topic_id)?;
-        total_size += types::CompactArray(types::Int32).compute_size(&self.partitions)?;
+        total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("TopicPartitions", "topic_id", version))?;
+        total_size += types::CompactArray(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("TopicPartitions", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicPartitions", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for TopicPartitions {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let topic_id = types::Uuid.decode(buf)?;
-        let partitions = types::CompactArray(types::Int32).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let topic_id = types::Uuid.decode(buf).map_err(|e| e.with_field("TopicPartitions", "topic_id", version, start - buf.remaining()))?;
+        let partitions = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("TopicPartitions", "partitions", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -232,18 +339,84 @@ impl Default for TopicPartitions {
         Self {
             topic_id: Uuid::nil(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
 
 impl Message for TopicPartitions {
+    #[cfg(feature = "unstable-apis")]
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+    #[cfg(not(feature = "unstable-apis"))]
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: -1 };
+    const UNSTABLE_VERSIONS: Option<VersionRange> = Some(VersionRange { min: 0, max: 0 });
+}
+
+impl TopicPartitions {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicPartitions {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        node.insert("partitions".into(), JsonValue::to_json(&self.partitions));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TopicPartitions")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "TopicPartitions.topicId")?;
+        } else {
+            return Err(crate::json::missing("TopicPartitions", "topicId", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = JsonValue::from_json(value, "TopicPartitions.partitions")?;
+        } else {
+            return Err(crate::json::missing("TopicPartitions", "partitions", version));
+        }
+        Ok(this)
+    }
 }
 
 /// Valid versions: 0
+///
+/// Unstable versions: 0 (requires the `unstable-apis` feature)
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ConsumerGroupHeartbeatRequest {
     /// The group identifier.
@@ -254,6 +427,7 @@ pub struct ConsumerGroupHeartbeatRequest {
     /// The member id generated by the coordinator. The member id must be kept during the entire lifetime of the member.
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The current member epoch; 0 to join the group; -1 to leave the group; -2 to indicate that the static member will rejoin.
@@ -264,11 +438,15 @@ pub struct ConsumerGroupHeartbeatRequest {
     /// null if not provided or if it didn't change since the last heartbeat; the instance Id otherwise.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub instance_id: Option<StrBytes>,
 
     /// null if not provided or if it didn't change since the last heartbeat; the rack ID of consumer otherwise.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub rack_id: Option<StrBytes>,
 
     /// -1 if it didn't chance since the last heartbeat; the maximum time in milliseconds that the coordinator will wait on the member to revoke its partitions otherwise.
@@ -279,30 +457,37 @@ pub struct ConsumerGroupHeartbeatRequest {
     /// null if it didn't change since the last heartbeat; the subscribed topic names otherwise.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
     pub subscribed_topic_names: Option<Vec<super::TopicName>>,
 
     /// null if it didn't change since the last heartbeat; the subscribed topic regex otherwise
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub subscribed_topic_regex: Option<StrBytes>,
 
     /// null if not used or if it didn't change since the last heartbeat; the server side assignor to use otherwise.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub server_assignor: Option<StrBytes>,
 
     /// null if not used or if it didn't change since the last heartbeat; the list of client-side assignors otherwise.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
     pub client_assignors: Option<Vec<Assignor>>,
 
     /// null if it didn't change since the last heartbeat; the partitions owned by the member.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
     pub topic_partitions: Option<Vec<TopicPartitions>>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ConsumerGroupHeartbeatRequest {
@@ -315,72 +500,85 @@ impl Builder for ConsumerGroupHeartbeatRequest {
 
 impl Encodable for ConsumerGroupHeartbeatRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, &self.group_id)?;
-        types::CompactString.encode(buf, &self.member_id)?;
-        types::Int32.encode(buf, &self.member_epoch)?;
-        types::CompactString.encode(buf, &self.instance_id)?;
-        types::CompactString.encode(buf, &self.rack_id)?;
-        types::Int32.encode(buf, &self.rebalance_timeout_ms)?;
-        types::CompactArray(types::CompactString).encode(buf, &self.subscribed_topic_names)?;
-        types::CompactString.encode(buf, &self.subscribed_topic_regex)?;
-        types::CompactString.encode(buf, &self.server_assignor)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.client_assignors)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.topic_partitions)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatRequest", version));
+        }
+        types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "group_id", version))?;
+        types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_id", version))?;
+        types::Int32.encode(buf, &self.member_epoch).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_epoch", version))?;
+        types::CompactString.encode(buf, &self.instance_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "instance_id", version))?;
+        types::CompactString.encode(buf, &self.rack_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rack_id", version))?;
+        types::Int32.encode(buf, &self.rebalance_timeout_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rebalance_timeout_ms", version))?;
+        types::CompactArray(types::CompactString).encode(buf, &self.subscribed_topic_names).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_names", version))?;
+        types::CompactString.encode(buf, &self.subscribed_topic_regex).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_regex", version))?;
+        types::CompactString.encode(buf, &self.server_assignor).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "server_assignor", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.client_assignors).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "client_assignors", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_partitions).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "topic_partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(&self.group_id)?;
-        total_size += types::CompactString.compute_size(&self.member_id)?;
-        total_size += types::Int32.compute_size(&self.member_epoch)?;
-        total_size += types::CompactString.compute_size(&self.instance_id)?;
-        total_size += types::CompactString.compute_size(&self.rack_id)?;
-        total_size += types::Int32.compute_size(&self.rebalance_timeout_ms)?;
-        total_size += types::CompactArray(types::CompactString).compute_size(&self.subscribed_topic_names)?;
-        total_size += types::CompactString.compute_size(&self.subscribed_topic_regex)?;
-        total_size += types::CompactString.compute_size(&self.server_assignor)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.client_assignors)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topic_partitions)?;
+        total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "group_id", version))?;
+        total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_id", version))?;
+        total_size += types::Int32.compute_size(&self.member_epoch).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_epoch", version))?;
+        total_size += types::CompactString.compute_size(&self.instance_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "instance_id", version))?;
+        total_size += types::CompactString.compute_size(&self.rack_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rack_id", version))?;
+        total_size += types::Int32.compute_size(&self.rebalance_timeout_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rebalance_timeout_ms", version))?;
+        total_size += types::CompactArray(types::CompactString).compute_size(&self.subscribed_topic_names).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_names", version))?;
+        total_size += types::CompactString.compute_size(&self.subscribed_topic_regex).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_regex", version))?;
+        total_size += types::CompactString.compute_size(&self.server_assignor).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "server_assignor", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.client_assignors).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "client_assignors", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topic_partitions).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "topic_partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ConsumerGroupHeartbeatRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let group_id = types::CompactString.decode(buf)?;
-        let member_id = types::CompactString.decode(buf)?;
-        let member_epoch = types::Int32.decode(buf)?;
-        let instance_id = types::CompactString.decode(buf)?;
-        let rack_id = types::CompactString.decode(buf)?;
-        let rebalance_timeout_ms = types::Int32.decode(buf)?;
-        let subscribed_topic_names = types::CompactArray(types::CompactString).decode(buf)?;
-        let subscribed_topic_regex = types::CompactString.decode(buf)?;
-        let server_assignor = types::CompactString.decode(buf)?;
-        let client_assignors = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let topic_partitions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatRequest", version));
+        }
+        let start = buf.remaining();
+        let group_id = types::CompactString.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "group_id", version, start - buf.remaining()))?;
+        let member_id = types::CompactString.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_id", version, start - buf.remaining()))?;
+        let member_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_epoch", version, start - buf.remaining()))?;
+        let instance_id = types::CompactString.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "instance_id", version, start - buf.remaining()))?;
+        let rack_id = types::CompactString.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rack_id", version, start - buf.remaining()))?;
+        let rebalance_timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rebalance_timeout_ms", version, start - buf.remaining()))?;
+        let subscribed_topic_names = types::CompactArray(types::CompactString).decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_names", version, start - buf.remaining()))?;
+        let subscribed_topic_regex = types::CompactString.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_regex", version, start - buf.remaining()))?;
+        let server_assignor = types::CompactString.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "server_assignor", version, start - buf.remaining()))?;
+        let client_assignors = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "client_assignors", version, start - buf.remaining()))?;
+        let topic_partitions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "topic_partitions", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -414,13 +612,265 @@ impl Default for ConsumerGroupHeartbeatRequest {
             server_assignor: None,
             client_assignors: None,
             topic_partitions: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
 
 impl Message for ConsumerGroupHeartbeatRequest {
+    #[cfg(feature = "unstable-apis")]
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+    #[cfg(not(feature = "unstable-apis"))]
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: -1 };
+    const UNSTABLE_VERSIONS: Option<VersionRange> = Some(VersionRange { min: 0, max: 0 });
+}
+
+impl ConsumerGroupHeartbeatRequest {
+    /// Encode the message with the elements of `subscribed_topic_names` produced by an iterator of known length, in place of
+    /// the `subscribed_topic_names` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_subscribed_topic_names<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, subscribed_topic_names: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = super::TopicName>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let subscribed_topic_names = subscribed_topic_names.into_iter();
+        types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "group_id", version))?;
+        types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_id", version))?;
+        types::Int32.encode(buf, &self.member_epoch).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_epoch", version))?;
+        types::CompactString.encode(buf, &self.instance_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "instance_id", version))?;
+        types::CompactString.encode(buf, &self.rack_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rack_id", version))?;
+        types::Int32.encode(buf, &self.rebalance_timeout_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rebalance_timeout_ms", version))?;
+        types::CompactArray(types::CompactString).encode(buf, types::Streamed(subscribed_topic_names)).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_names", version))?;
+        types::CompactString.encode(buf, &self.subscribed_topic_regex).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_regex", version))?;
+        types::CompactString.encode(buf, &self.server_assignor).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "server_assignor", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.client_assignors).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "client_assignors", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_partitions).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "topic_partitions", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `client_assignors` produced by an iterator of known length, in place of
+    /// the `client_assignors` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_client_assignors<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, client_assignors: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = Assignor>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let client_assignors = client_assignors.into_iter();
+        types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "group_id", version))?;
+        types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_id", version))?;
+        types::Int32.encode(buf, &self.member_epoch).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_epoch", version))?;
+        types::CompactString.encode(buf, &self.instance_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "instance_id", version))?;
+        types::CompactString.encode(buf, &self.rack_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rack_id", version))?;
+        types::Int32.encode(buf, &self.rebalance_timeout_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rebalance_timeout_ms", version))?;
+        types::CompactArray(types::CompactString).encode(buf, &self.subscribed_topic_names).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_names", version))?;
+        types::CompactString.encode(buf, &self.subscribed_topic_regex).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_regex", version))?;
+        types::CompactString.encode(buf, &self.server_assignor).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "server_assignor", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(client_assignors)).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "client_assignors", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_partitions).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "topic_partitions", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `topic_partitions` produced by an iterator of known length, in place of
+    /// the `topic_partitions` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topic_partitions<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topic_partitions: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = TopicPartitions>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topic_partitions = topic_partitions.into_iter();
+        types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "group_id", version))?;
+        types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_id", version))?;
+        types::Int32.encode(buf, &self.member_epoch).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "member_epoch", version))?;
+        types::CompactString.encode(buf, &self.instance_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "instance_id", version))?;
+        types::CompactString.encode(buf, &self.rack_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rack_id", version))?;
+        types::Int32.encode(buf, &self.rebalance_timeout_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "rebalance_timeout_ms", version))?;
+        types::CompactArray(types::CompactString).encode(buf, &self.subscribed_topic_names).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_names", version))?;
+        types::CompactString.encode(buf, &self.subscribed_topic_regex).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "subscribed_topic_regex", version))?;
+        types::CompactString.encode(buf, &self.server_assignor).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "server_assignor", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.client_assignors).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "client_assignors", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topic_partitions)).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "topic_partitions", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ConsumerGroupHeartbeatRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl ConsumerGroupHeartbeatRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if let Some(items) = &mut self.client_assignors {
+            for item in items.iter_mut() {
+                item.normalize_to(version);
+            }
+        }
+        if let Some(items) = &mut self.topic_partitions {
+            for item in items.iter_mut() {
+                item.normalize_to(version);
+            }
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if let Some(items) = &self.client_assignors {
+            for (i, item) in items.iter().enumerate() {
+                item.validate_into(version, &format!("{}client_assignors[{}].", path, i), invalid);
+            }
+        }
+        if let Some(items) = &self.topic_partitions {
+            for (i, item) in items.iter().enumerate() {
+                item.validate_into(version, &format!("{}topic_partitions[{}].", path, i), invalid);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ConsumerGroupHeartbeatRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        node.insert("memberEpoch".into(), JsonValue::to_json(&self.member_epoch));
+        node.insert("instanceId".into(), JsonValue::to_json(&self.instance_id));
+        node.insert("rackId".into(), JsonValue::to_json(&self.rack_id));
+        node.insert("rebalanceTimeoutMs".into(), JsonValue::to_json(&self.rebalance_timeout_ms));
+        node.insert("subscribedTopicNames".into(), JsonValue::to_json(&self.subscribed_topic_names));
+        node.insert("subscribedTopicRegex".into(), JsonValue::to_json(&self.subscribed_topic_regex));
+        node.insert("serverAssignor".into(), JsonValue::to_json(&self.server_assignor));
+        node.insert("clientAssignors".into(), self.client_assignors.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        node.insert("topicPartitions".into(), self.topic_partitions.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ConsumerGroupHeartbeatRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.groupId")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "groupId", version));
+        }
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.memberId")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "memberId", version));
+        }
+        if let Some(value) = object.get("memberEpoch") {
+            this.member_epoch = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.memberEpoch")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "memberEpoch", version));
+        }
+        if let Some(value) = object.get("instanceId") {
+            this.instance_id = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.instanceId")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "instanceId", version));
+        }
+        if let Some(value) = object.get("rackId") {
+            this.rack_id = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.rackId")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "rackId", version));
+        }
+        if let Some(value) = object.get("rebalanceTimeoutMs") {
+            this.rebalance_timeout_ms = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.rebalanceTimeoutMs")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "rebalanceTimeoutMs", version));
+        }
+        if let Some(value) = object.get("subscribedTopicNames") {
+            this.subscribed_topic_names = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.subscribedTopicNames")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "subscribedTopicNames", version));
+        }
+        if let Some(value) = object.get("subscribedTopicRegex") {
+            this.subscribed_topic_regex = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.subscribedTopicRegex")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "subscribedTopicRegex", version));
+        }
+        if let Some(value) = object.get("serverAssignor") {
+            this.server_assignor = JsonValue::from_json(value, "ConsumerGroupHeartbeatRequest.serverAssignor")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "serverAssignor", version));
+        }
+        if let Some(value) = object.get("clientAssignors") {
+            this.client_assignors = if value.is_null() { None } else { Some(crate::json::array(value, "ConsumerGroupHeartbeatRequest.clientAssignors")?.iter().map(|item| Assignor::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "clientAssignors", version));
+        }
+        if let Some(value) = object.get("topicPartitions") {
+            this.topic_partitions = if value.is_null() { None } else { Some(crate::json::array(value, "ConsumerGroupHeartbeatRequest.topicPartitions")?.iter().map(|item| TopicPartitions::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatRequest", "topicPartitions", version));
+        }
+        Ok(this)
+    }
 }
 
 impl HeaderVersion for ConsumerGroupHeartbeatRequest {
@@ -429,3 +879,17 @@ impl HeaderVersion for ConsumerGroupHeartbeatRequest {
     }
 }
 
+impl ConsumerGroupHeartbeatRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::ConsumerGroupHeartbeatResponse {
+        let mut response = super::ConsumerGroupHeartbeatResponse::default();
+        response.error_code = error.code();
+        response.member_id = Some(self.member_id.clone());
+        response
+    }
+}
+
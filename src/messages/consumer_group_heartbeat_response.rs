@@ -5,21 +5,29 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
+///
+/// Unstable versions: 0 (requires the `unstable-apis` feature)
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicPartitions {
     /// The topic ID.
@@ -33,7 +41,7 @@ pub struct TopicPartitions {
     pub partitions: Vec<i32>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TopicPartitions {
@@ -46,45 +54,49 @@ impl Builder for TopicPartitions {
 
 impl Encodable for TopicPartitions {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Uuid.encode(buf, &self.topic_id)?;
-        types::CompactArray(types::Int32).encode(buf, &self.partitions)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("TopicPartitions", "topic_id", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicPartitions", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicPartitions", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Uuid.compute_size(&self.topic_id)?;
-        total_size += types::CompactArray(types::Int32).compute_size(&self.partitions)?;
+        total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("TopicPartitions", "topic_id", version))?;
+        total_size += types::CompactArray(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("TopicPartitions", "partitions", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicPartitions", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for TopicPartitions {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let topic_id = types::Uuid.decode(buf)?;
-        let partitions = types::CompactArray(types::Int32).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let topic_id = types::Uuid.decode(buf).map_err(|e| e.with_field("TopicPartitions", "topic_id", version, start - buf.remaining()))?;
+        let partitions = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("TopicPartitions", "partitions", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -100,18 +112,84 @@ impl Default for TopicPartitions {
         Self {
             topic_id: Uuid::nil(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
 
 impl Message for TopicPartitions {
+    #[cfg(feature = "unstable-apis")]
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+    #[cfg(not(feature = "unstable-apis"))]
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: -1 };
+    const UNSTABLE_VERSIONS: Option<VersionRange> = Some(VersionRange { min: 0, max: 0 });
+}
+
+impl TopicPartitions {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicPartitions {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        node.insert("partitions".into(), JsonValue::to_json(&self.partitions));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TopicPartitions")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "TopicPartitions.topicId")?;
+        } else {
+            return Err(crate::json::missing("TopicPartitions", "topicId", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = JsonValue::from_json(value, "TopicPartitions.partitions")?;
+        } else {
+            return Err(crate::json::missing("TopicPartitions", "partitions", version));
+        }
+        Ok(this)
+    }
 }
 
 /// Valid versions: 0
+///
+/// Unstable versions: 0 (requires the `unstable-apis` feature)
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct Assignment {
     /// The assigned error.
@@ -140,7 +218,7 @@ pub struct Assignment {
     pub metadata_bytes: Bytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for Assignment {
@@ -153,54 +231,58 @@ impl Builder for Assignment {
 
 impl Encodable for Assignment {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int8.encode(buf, &self.error)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.assigned_topic_partitions)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.pending_topic_partitions)?;
-        types::Int16.encode(buf, &self.metadata_version)?;
-        types::CompactBytes.encode(buf, &self.metadata_bytes)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int8.encode(buf, &self.error).map_err(|e| e.with_field("Assignment", "error", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.assigned_topic_partitions).map_err(|e| e.with_field("Assignment", "assigned_topic_partitions", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.pending_topic_partitions).map_err(|e| e.with_field("Assignment", "pending_topic_partitions", version))?;
+        types::Int16.encode(buf, &self.metadata_version).map_err(|e| e.with_field("Assignment", "metadata_version", version))?;
+        types::CompactBytes.encode(buf, &self.metadata_bytes).map_err(|e| e.with_field("Assignment", "metadata_bytes", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Assignment", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("Assignment", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("Assignment", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int8.compute_size(&self.error)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.assigned_topic_partitions)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.pending_topic_partitions)?;
-        total_size += types::Int16.compute_size(&self.metadata_version)?;
-        total_size += types::CompactBytes.compute_size(&self.metadata_bytes)?;
+        total_size += types::Int8.compute_size(&self.error).map_err(|e| e.with_field("Assignment", "error", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.assigned_topic_partitions).map_err(|e| e.with_field("Assignment", "assigned_topic_partitions", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.pending_topic_partitions).map_err(|e| e.with_field("Assignment", "pending_topic_partitions", version))?;
+        total_size += types::Int16.compute_size(&self.metadata_version).map_err(|e| e.with_field("Assignment", "metadata_version", version))?;
+        total_size += types::CompactBytes.compute_size(&self.metadata_bytes).map_err(|e| e.with_field("Assignment", "metadata_bytes", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Assignment", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("Assignment", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("Assignment", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for Assignment {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error = types::Int8.decode(buf)?;
-        let assigned_topic_partitions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let pending_topic_partitions = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let metadata_version = types::Int16.decode(buf)?;
-        let metadata_bytes = types::CompactBytes.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let error = types::Int8.decode(buf).map_err(|e| e.with_field("Assignment", "error", version, start - buf.remaining()))?;
+        let assigned_topic_partitions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("Assignment", "assigned_topic_partitions", version, start - buf.remaining()))?;
+        let pending_topic_partitions = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("Assignment", "pending_topic_partitions", version, start - buf.remaining()))?;
+        let metadata_version = types::Int16.decode(buf).map_err(|e| e.with_field("Assignment", "metadata_version", version, start - buf.remaining()))?;
+        let metadata_bytes = types::CompactBytes.decode(buf).map_err(|e| e.with_field("Assignment", "metadata_bytes", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Assignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Assignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Assignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("Assignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -222,18 +304,114 @@ impl Default for Assignment {
             pending_topic_partitions: Default::default(),
             metadata_version: 0,
             metadata_bytes: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
 
 impl Message for Assignment {
+    #[cfg(feature = "unstable-apis")]
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+    #[cfg(not(feature = "unstable-apis"))]
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: -1 };
+    const UNSTABLE_VERSIONS: Option<VersionRange> = Some(VersionRange { min: 0, max: 0 });
+}
+
+impl Assignment {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.assigned_topic_partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+        for item in self.pending_topic_partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.assigned_topic_partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}assigned_topic_partitions[{}].", path, i), invalid);
+        }
+        for (i, item) in self.pending_topic_partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}pending_topic_partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl Assignment {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("error".into(), JsonValue::to_json(&self.error));
+        node.insert("assignedTopicPartitions".into(), serde_json::Value::Array(self.assigned_topic_partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("pendingTopicPartitions".into(), serde_json::Value::Array(self.pending_topic_partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("metadataVersion".into(), JsonValue::to_json(&self.metadata_version));
+        node.insert("metadataBytes".into(), JsonValue::to_json(&self.metadata_bytes));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "Assignment")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("error") {
+            this.error = JsonValue::from_json(value, "Assignment.error")?;
+        } else {
+            return Err(crate::json::missing("Assignment", "error", version));
+        }
+        if let Some(value) = object.get("assignedTopicPartitions") {
+            this.assigned_topic_partitions = crate::json::array(value, "Assignment.assignedTopicPartitions")?.iter().map(|item| TopicPartitions::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("Assignment", "assignedTopicPartitions", version));
+        }
+        if let Some(value) = object.get("pendingTopicPartitions") {
+            this.pending_topic_partitions = crate::json::array(value, "Assignment.pendingTopicPartitions")?.iter().map(|item| TopicPartitions::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("Assignment", "pendingTopicPartitions", version));
+        }
+        if let Some(value) = object.get("metadataVersion") {
+            this.metadata_version = JsonValue::from_json(value, "Assignment.metadataVersion")?;
+        } else {
+            return Err(crate::json::missing("Assignment", "metadataVersion", version));
+        }
+        if let Some(value) = object.get("metadataBytes") {
+            this.metadata_bytes = JsonValue::from_json(value, "Assignment.metadataBytes")?;
+        } else {
+            return Err(crate::json::missing("Assignment", "metadataBytes", version));
+        }
+        Ok(this)
+    }
 }
 
 /// Valid versions: 0
+///
+/// Unstable versions: 0 (requires the `unstable-apis` feature)
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ConsumerGroupHeartbeatResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -249,11 +427,15 @@ pub struct ConsumerGroupHeartbeatResponse {
     /// The top-level error message, or null if there was no error.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The member id generated by the coordinator. Only provided when the member joins with MemberEpoch == 0.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: Option<StrBytes>,
 
     /// The member epoch.
@@ -274,10 +456,11 @@ pub struct ConsumerGroupHeartbeatResponse {
     /// null if not provided; the assignment otherwise.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
     pub assignment: Option<Assignment>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ConsumerGroupHeartbeatResponse {
@@ -290,63 +473,76 @@ impl Builder for ConsumerGroupHeartbeatResponse {
 
 impl Encodable for ConsumerGroupHeartbeatResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::CompactString.encode(buf, &self.error_message)?;
-        types::CompactString.encode(buf, &self.member_id)?;
-        types::Int32.encode(buf, &self.member_epoch)?;
-        types::Boolean.encode(buf, &self.should_compute_assignment)?;
-        types::Int32.encode(buf, &self.heartbeat_interval_ms)?;
-        types::Struct { version }.encode(buf, &self.assignment)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "error_code", version))?;
+        types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "error_message", version))?;
+        types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "member_id", version))?;
+        types::Int32.encode(buf, &self.member_epoch).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "member_epoch", version))?;
+        types::Boolean.encode(buf, &self.should_compute_assignment).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "should_compute_assignment", version))?;
+        types::Int32.encode(buf, &self.heartbeat_interval_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "heartbeat_interval_ms", version))?;
+        CachedStruct { version, cache }.encode(buf, &self.assignment).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "assignment", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::CompactString.compute_size(&self.error_message)?;
-        total_size += types::CompactString.compute_size(&self.member_id)?;
-        total_size += types::Int32.compute_size(&self.member_epoch)?;
-        total_size += types::Boolean.compute_size(&self.should_compute_assignment)?;
-        total_size += types::Int32.compute_size(&self.heartbeat_interval_ms)?;
-        total_size += types::Struct { version }.compute_size(&self.assignment)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "throttle_time_ms", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "error_code", version))?;
+        total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "error_message", version))?;
+        total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "member_id", version))?;
+        total_size += types::Int32.compute_size(&self.member_epoch).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "member_epoch", version))?;
+        total_size += types::Boolean.compute_size(&self.should_compute_assignment).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "should_compute_assignment", version))?;
+        total_size += types::Int32.compute_size(&self.heartbeat_interval_ms).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "heartbeat_interval_ms", version))?;
+        total_size += CachedStruct { version, cache }.compute_size(&self.assignment).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "assignment", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ConsumerGroupHeartbeatResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let error_message = types::CompactString.decode(buf)?;
-        let member_id = types::CompactString.decode(buf)?;
-        let member_epoch = types::Int32.decode(buf)?;
-        let should_compute_assignment = types::Boolean.decode(buf)?;
-        let heartbeat_interval_ms = types::Int32.decode(buf)?;
-        let assignment = types::Struct { version }.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ConsumerGroupHeartbeatResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "throttle_time_ms", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "error_code", version, start - buf.remaining()))?;
+        let error_message = types::CompactString.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "error_message", version, start - buf.remaining()))?;
+        let member_id = types::CompactString.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "member_id", version, start - buf.remaining()))?;
+        let member_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "member_epoch", version, start - buf.remaining()))?;
+        let should_compute_assignment = types::Boolean.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "should_compute_assignment", version, start - buf.remaining()))?;
+        let heartbeat_interval_ms = types::Int32.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "heartbeat_interval_ms", version, start - buf.remaining()))?;
+        let assignment = types::Struct { version }.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "assignment", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ConsumerGroupHeartbeatResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -374,13 +570,117 @@ impl Default for ConsumerGroupHeartbeatResponse {
             should_compute_assignment: false,
             heartbeat_interval_ms: 0,
             assignment: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
 
 impl Message for ConsumerGroupHeartbeatResponse {
+    #[cfg(feature = "unstable-apis")]
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+    #[cfg(not(feature = "unstable-apis"))]
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: -1 };
+    const UNSTABLE_VERSIONS: Option<VersionRange> = Some(VersionRange { min: 0, max: 0 });
+}
+
+impl ConsumerGroupHeartbeatResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if let Some(item) = &mut self.assignment {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if let Some(item) = &self.assignment {
+            item.validate_into(version, &format!("{}assignment.", path), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ConsumerGroupHeartbeatResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        node.insert("memberEpoch".into(), JsonValue::to_json(&self.member_epoch));
+        node.insert("shouldComputeAssignment".into(), JsonValue::to_json(&self.should_compute_assignment));
+        node.insert("heartbeatIntervalMs".into(), JsonValue::to_json(&self.heartbeat_interval_ms));
+        node.insert("assignment".into(), self.assignment.as_ref().map(|item| item.to_json_with(version, records)).unwrap_or(serde_json::Value::Null));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ConsumerGroupHeartbeatResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "ConsumerGroupHeartbeatResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "ConsumerGroupHeartbeatResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "ConsumerGroupHeartbeatResponse.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatResponse", "errorMessage", version));
+        }
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "ConsumerGroupHeartbeatResponse.memberId")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatResponse", "memberId", version));
+        }
+        if let Some(value) = object.get("memberEpoch") {
+            this.member_epoch = JsonValue::from_json(value, "ConsumerGroupHeartbeatResponse.memberEpoch")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatResponse", "memberEpoch", version));
+        }
+        if let Some(value) = object.get("shouldComputeAssignment") {
+            this.should_compute_assignment = JsonValue::from_json(value, "ConsumerGroupHeartbeatResponse.shouldComputeAssignment")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatResponse", "shouldComputeAssignment", version));
+        }
+        if let Some(value) = object.get("heartbeatIntervalMs") {
+            this.heartbeat_interval_ms = JsonValue::from_json(value, "ConsumerGroupHeartbeatResponse.heartbeatIntervalMs")?;
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatResponse", "heartbeatIntervalMs", version));
+        }
+        if let Some(value) = object.get("assignment") {
+            this.assignment = if value.is_null() { None } else { Some(Assignment::from_json(value, version)?) };
+        } else {
+            return Err(crate::json::missing("ConsumerGroupHeartbeatResponse", "assignment", version));
+        }
+        Ok(this)
+    }
 }
 
 impl HeaderVersion for ConsumerGroupHeartbeatResponse {
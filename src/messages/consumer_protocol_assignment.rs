@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicPartition {
     /// 
@@ -40,15 +46,21 @@ impl Builder for TopicPartition {
 impl MapEncodable for TopicPartition {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::String.encode(buf, key)?;
-        types::Array(types::Int32).encode(buf, &self.partitions)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::String.encode(buf, key).map_err(|e| e.with_field("TopicPartition", "topic", version))?;
+        types::Array(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicPartition", "partitions", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::String.compute_size(key)?;
-        total_size += types::Array(types::Int32).compute_size(&self.partitions)?;
+        total_size += types::String.compute_size(key).map_err(|e| e.with_field("TopicPartition", "topic", version))?;
+        total_size += types::Array(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("TopicPartition", "partitions", version))?;
 
         Ok(total_size)
     }
@@ -57,8 +69,9 @@ impl MapEncodable for TopicPartition {
 impl MapDecodable for TopicPartition {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::String.decode(buf)?;
-        let partitions = types::Array(types::Int32).decode(buf)?;
+        let start = buf.remaining();
+        let key_field = types::String.decode(buf).map_err(|e| e.with_field("TopicPartition", "topic", version, start - buf.remaining()))?;
+        let partitions = types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("TopicPartition", "partitions", version, start - buf.remaining()))?;
         Ok((key_field, Self {
             partitions,
         }))
@@ -77,9 +90,70 @@ impl Message for TopicPartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl TopicPartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicPartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topic".into(), JsonValue::to_json(key));
+        node.insert("partitions".into(), JsonValue::to_json(&self.partitions));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "TopicPartition")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("topic") {
+            key = JsonValue::from_json(value, "TopicPartition.topic")?;
+        } else {
+            return Err(crate::json::missing("TopicPartition", "topic", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = JsonValue::from_json(value, "TopicPartition.partitions")?;
+        } else {
+            return Err(crate::json::missing("TopicPartition", "partitions", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ConsumerProtocolAssignment {
     /// 
@@ -90,6 +164,7 @@ pub struct ConsumerProtocolAssignment {
     /// 
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
     pub user_data: Option<Bytes>,
 
 }
@@ -104,15 +179,21 @@ impl Builder for ConsumerProtocolAssignment {
 
 impl Encodable for ConsumerProtocolAssignment {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Array(types::Struct { version }).encode(buf, &self.assigned_partitions)?;
-        types::Bytes.encode(buf, &self.user_data)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Array(CachedStruct { version, cache }).encode(buf, &self.assigned_partitions).map_err(|e| e.with_field("ConsumerProtocolAssignment", "assigned_partitions", version))?;
+        types::Bytes.encode(buf, &self.user_data).map_err(|e| e.with_field("ConsumerProtocolAssignment", "user_data", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Array(types::Struct { version }).compute_size(&self.assigned_partitions)?;
-        total_size += types::Bytes.compute_size(&self.user_data)?;
+        total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.assigned_partitions).map_err(|e| e.with_field("ConsumerProtocolAssignment", "assigned_partitions", version))?;
+        total_size += types::Bytes.compute_size(&self.user_data).map_err(|e| e.with_field("ConsumerProtocolAssignment", "user_data", version))?;
 
         Ok(total_size)
     }
@@ -120,8 +201,9 @@ impl Encodable for ConsumerProtocolAssignment {
 
 impl Decodable for ConsumerProtocolAssignment {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let assigned_partitions = types::Array(types::Struct { version }).decode(buf)?;
-        let user_data = types::Bytes.decode(buf)?;
+        let start = buf.remaining();
+        let assigned_partitions = types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ConsumerProtocolAssignment", "assigned_partitions", version, start - buf.remaining()))?;
+        let user_data = types::Bytes.decode(buf).map_err(|e| e.with_field("ConsumerProtocolAssignment", "user_data", version, start - buf.remaining()))?;
         Ok(Self {
             assigned_partitions,
             user_data,
@@ -142,3 +224,67 @@ impl Message for ConsumerProtocolAssignment {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl ConsumerProtocolAssignment {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.assigned_partitions.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.assigned_partitions.values().enumerate() {
+            item.validate_into(version, &format!("{}assigned_partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ConsumerProtocolAssignment {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("assignedPartitions".into(), serde_json::Value::Array(self.assigned_partitions.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("userData".into(), JsonValue::to_json(&self.user_data));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ConsumerProtocolAssignment")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("assignedPartitions") {
+            this.assigned_partitions = crate::json::array(value, "ConsumerProtocolAssignment.assignedPartitions")?.iter().map(|item| TopicPartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ConsumerProtocolAssignment", "assignedPartitions", version));
+        }
+        if let Some(value) = object.get("userData") {
+            this.user_data = JsonValue::from_json(value, "ConsumerProtocolAssignment.userData")?;
+        } else {
+            return Err(crate::json::missing("ConsumerProtocolAssignment", "userData", version));
+        }
+        Ok(this)
+    }
+}
+
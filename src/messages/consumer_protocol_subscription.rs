@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicPartition {
     /// 
@@ -40,37 +46,43 @@ impl Builder for TopicPartition {
 impl MapEncodable for TopicPartition {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("TopicPartition", "topic", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TopicPartition", "topic", version));
             }
         }
         if version >= 1 {
-            types::Array(types::Int32).encode(buf, &self.partitions)?;
+            types::Array(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicPartition", "partitions", version))?;
         } else {
             if !self.partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TopicPartition", "partitions", version));
             }
         }
 
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("TopicPartition", "topic", version))?;
         } else {
             if !key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TopicPartition", "topic", version));
             }
         }
         if version >= 1 {
-            total_size += types::Array(types::Int32).compute_size(&self.partitions)?;
+            total_size += types::Array(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("TopicPartition", "partitions", version))?;
         } else {
             if !self.partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TopicPartition", "partitions", version));
             }
         }
 
@@ -81,13 +93,14 @@ impl MapEncodable for TopicPartition {
 impl MapDecodable for TopicPartition {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 1 {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("TopicPartition", "topic", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let partitions = if version >= 1 {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("TopicPartition", "partitions", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
@@ -109,19 +122,94 @@ impl Message for TopicPartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl TopicPartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.partitions = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if !self.partitions.is_empty() {
+                invalid.push(format!("{}partitions", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicPartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("topic".into(), JsonValue::to_json(key));
+        }
+        if version >= 1 {
+            node.insert("partitions".into(), JsonValue::to_json(&self.partitions));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "TopicPartition")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("topic") {
+            key = JsonValue::from_json(value, "TopicPartition.topic")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("TopicPartition", "topic", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = JsonValue::from_json(value, "TopicPartition.partitions")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("TopicPartition", "partitions", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ConsumerProtocolSubscription {
     /// 
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub topics: Vec<StrBytes>,
 
     /// 
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
     pub user_data: Option<Bytes>,
 
     /// 
@@ -137,6 +225,8 @@ pub struct ConsumerProtocolSubscription {
     /// 
     /// 
     /// Supported API versions: 3
+    /// May be `None` in versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub rack_id: Option<StrBytes>,
 
 }
@@ -151,32 +241,38 @@ impl Builder for ConsumerProtocolSubscription {
 
 impl Encodable for ConsumerProtocolSubscription {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Array(types::String).encode(buf, &self.topics)?;
-        types::Bytes.encode(buf, &self.user_data)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Array(types::String).encode(buf, &self.topics).map_err(|e| e.with_field("ConsumerProtocolSubscription", "topics", version))?;
+        types::Bytes.encode(buf, &self.user_data).map_err(|e| e.with_field("ConsumerProtocolSubscription", "user_data", version))?;
         if version >= 1 {
-            types::Array(types::Struct { version }).encode(buf, &self.owned_partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.owned_partitions).map_err(|e| e.with_field("ConsumerProtocolSubscription", "owned_partitions", version))?;
         }
         if version >= 2 {
-            types::Int32.encode(buf, &self.generation_id)?;
+            types::Int32.encode(buf, &self.generation_id).map_err(|e| e.with_field("ConsumerProtocolSubscription", "generation_id", version))?;
         }
         if version >= 3 {
-            types::String.encode(buf, &self.rack_id)?;
+            types::String.encode(buf, &self.rack_id).map_err(|e| e.with_field("ConsumerProtocolSubscription", "rack_id", version))?;
         }
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Array(types::String).compute_size(&self.topics)?;
-        total_size += types::Bytes.compute_size(&self.user_data)?;
+        total_size += types::Array(types::String).compute_size(&self.topics).map_err(|e| e.with_field("ConsumerProtocolSubscription", "topics", version))?;
+        total_size += types::Bytes.compute_size(&self.user_data).map_err(|e| e.with_field("ConsumerProtocolSubscription", "user_data", version))?;
         if version >= 1 {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.owned_partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.owned_partitions).map_err(|e| e.with_field("ConsumerProtocolSubscription", "owned_partitions", version))?;
         }
         if version >= 2 {
-            total_size += types::Int32.compute_size(&self.generation_id)?;
+            total_size += types::Int32.compute_size(&self.generation_id).map_err(|e| e.with_field("ConsumerProtocolSubscription", "generation_id", version))?;
         }
         if version >= 3 {
-            total_size += types::String.compute_size(&self.rack_id)?;
+            total_size += types::String.compute_size(&self.rack_id).map_err(|e| e.with_field("ConsumerProtocolSubscription", "rack_id", version))?;
         }
 
         Ok(total_size)
@@ -185,20 +281,21 @@ impl Encodable for ConsumerProtocolSubscription {
 
 impl Decodable for ConsumerProtocolSubscription {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let topics = types::Array(types::String).decode(buf)?;
-        let user_data = types::Bytes.decode(buf)?;
+        let start = buf.remaining();
+        let topics = types::Array(types::String).decode(buf).map_err(|e| e.with_field("ConsumerProtocolSubscription", "topics", version, start - buf.remaining()))?;
+        let user_data = types::Bytes.decode(buf).map_err(|e| e.with_field("ConsumerProtocolSubscription", "user_data", version, start - buf.remaining()))?;
         let owned_partitions = if version >= 1 {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ConsumerProtocolSubscription", "owned_partitions", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let generation_id = if version >= 2 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("ConsumerProtocolSubscription", "generation_id", version, start - buf.remaining()))?
         } else {
             -1
         };
         let rack_id = if version >= 3 {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("ConsumerProtocolSubscription", "rack_id", version, start - buf.remaining()))?
         } else {
             None
         };
@@ -228,3 +325,117 @@ impl Message for ConsumerProtocolSubscription {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl ConsumerProtocolSubscription {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version >= 1 {
+            for item in self.owned_partitions.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.owned_partitions = Default::default();
+        }
+        if version < 2 {
+            self.generation_id = -1;
+        }
+        if version < 3 {
+            self.rack_id = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version >= 1 {
+            for (i, item) in self.owned_partitions.values().enumerate() {
+                item.validate_into(version, &format!("{}owned_partitions[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.owned_partitions.is_empty() {
+                invalid.push(format!("{}owned_partitions", path));
+            }
+        }
+        if version < 2 {
+            if self.generation_id != -1 {
+                invalid.push(format!("{}generation_id", path));
+            }
+        }
+        if version < 3 {
+            if !self.rack_id.is_none() {
+                invalid.push(format!("{}rack_id", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ConsumerProtocolSubscription {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topics".into(), JsonValue::to_json(&self.topics));
+        node.insert("userData".into(), JsonValue::to_json(&self.user_data));
+        if version >= 1 {
+            node.insert("ownedPartitions".into(), serde_json::Value::Array(self.owned_partitions.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        }
+        if version >= 2 {
+            node.insert("generationId".into(), JsonValue::to_json(&self.generation_id));
+        }
+        if version >= 3 {
+            node.insert("rackId".into(), JsonValue::to_json(&self.rack_id));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ConsumerProtocolSubscription")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topics") {
+            this.topics = JsonValue::from_json(value, "ConsumerProtocolSubscription.topics")?;
+        } else {
+            return Err(crate::json::missing("ConsumerProtocolSubscription", "topics", version));
+        }
+        if let Some(value) = object.get("userData") {
+            this.user_data = JsonValue::from_json(value, "ConsumerProtocolSubscription.userData")?;
+        } else {
+            return Err(crate::json::missing("ConsumerProtocolSubscription", "userData", version));
+        }
+        if let Some(value) = object.get("ownedPartitions") {
+            this.owned_partitions = crate::json::array(value, "ConsumerProtocolSubscription.ownedPartitions")?.iter().map(|item| TopicPartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("ConsumerProtocolSubscription", "ownedPartitions", version));
+        }
+        if let Some(value) = object.get("generationId") {
+            this.generation_id = JsonValue::from_json(value, "ConsumerProtocolSubscription.generationId")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("ConsumerProtocolSubscription", "generationId", version));
+        }
+        if let Some(value) = object.get("rackId") {
+            this.rack_id = JsonValue::from_json(value, "ConsumerProtocolSubscription.rackId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("ConsumerProtocolSubscription", "rackId", version));
+        }
+        Ok(this)
+    }
+}
+
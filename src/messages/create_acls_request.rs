@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AclCreation {
     /// The type of the resource.
@@ -30,6 +36,7 @@ pub struct AclCreation {
     /// The resource name for the ACL.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub resource_name: StrBytes,
 
     /// The pattern type for the ACL.
@@ -40,11 +47,13 @@ pub struct AclCreation {
     /// The principal for the ACL.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal: StrBytes,
 
     /// The host for the ACL.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The operation type for the ACL (read, write, etc.).
@@ -58,7 +67,7 @@ pub struct AclCreation {
     pub permission_type: i8,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AclCreation {
@@ -71,79 +80,83 @@ impl Builder for AclCreation {
 
 impl Encodable for AclCreation {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int8.encode(buf, &self.resource_type)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int8.encode(buf, &self.resource_type).map_err(|e| e.with_field("AclCreation", "resource_type", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.resource_name)?;
+            types::CompactString.encode(buf, &self.resource_name).map_err(|e| e.with_field("AclCreation", "resource_name", version))?;
         } else {
-            types::String.encode(buf, &self.resource_name)?;
+            types::String.encode(buf, &self.resource_name).map_err(|e| e.with_field("AclCreation", "resource_name", version))?;
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.resource_pattern_type)?;
+            types::Int8.encode(buf, &self.resource_pattern_type).map_err(|e| e.with_field("AclCreation", "resource_pattern_type", version))?;
         } else {
             if self.resource_pattern_type != 3 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AclCreation", "resource_pattern_type", version));
             }
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal)?;
+            types::CompactString.encode(buf, &self.principal).map_err(|e| e.with_field("AclCreation", "principal", version))?;
         } else {
-            types::String.encode(buf, &self.principal)?;
+            types::String.encode(buf, &self.principal).map_err(|e| e.with_field("AclCreation", "principal", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.host)?;
+            types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("AclCreation", "host", version))?;
         } else {
-            types::String.encode(buf, &self.host)?;
+            types::String.encode(buf, &self.host).map_err(|e| e.with_field("AclCreation", "host", version))?;
         }
-        types::Int8.encode(buf, &self.operation)?;
-        types::Int8.encode(buf, &self.permission_type)?;
+        types::Int8.encode(buf, &self.operation).map_err(|e| e.with_field("AclCreation", "operation", version))?;
+        types::Int8.encode(buf, &self.permission_type).map_err(|e| e.with_field("AclCreation", "permission_type", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AclCreation", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AclCreation", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AclCreation", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int8.compute_size(&self.resource_type)?;
+        total_size += types::Int8.compute_size(&self.resource_type).map_err(|e| e.with_field("AclCreation", "resource_type", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.resource_name)?;
+            total_size += types::CompactString.compute_size(&self.resource_name).map_err(|e| e.with_field("AclCreation", "resource_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.resource_name)?;
+            total_size += types::String.compute_size(&self.resource_name).map_err(|e| e.with_field("AclCreation", "resource_name", version))?;
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.resource_pattern_type)?;
+            total_size += types::Int8.compute_size(&self.resource_pattern_type).map_err(|e| e.with_field("AclCreation", "resource_pattern_type", version))?;
         } else {
             if self.resource_pattern_type != 3 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AclCreation", "resource_pattern_type", version));
             }
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal)?;
+            total_size += types::CompactString.compute_size(&self.principal).map_err(|e| e.with_field("AclCreation", "principal", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal)?;
+            total_size += types::String.compute_size(&self.principal).map_err(|e| e.with_field("AclCreation", "principal", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.host)?;
+            total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("AclCreation", "host", version))?;
         } else {
-            total_size += types::String.compute_size(&self.host)?;
+            total_size += types::String.compute_size(&self.host).map_err(|e| e.with_field("AclCreation", "host", version))?;
         }
-        total_size += types::Int8.compute_size(&self.operation)?;
-        total_size += types::Int8.compute_size(&self.permission_type)?;
+        total_size += types::Int8.compute_size(&self.operation).map_err(|e| e.with_field("AclCreation", "operation", version))?;
+        total_size += types::Int8.compute_size(&self.permission_type).map_err(|e| e.with_field("AclCreation", "permission_type", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AclCreation", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AclCreation", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AclCreation", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -151,37 +164,37 @@ impl Encodable for AclCreation {
 
 impl Decodable for AclCreation {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let resource_type = types::Int8.decode(buf)?;
+        let start = buf.remaining();
+        let resource_type = types::Int8.decode(buf).map_err(|e| e.with_field("AclCreation", "resource_type", version, start - buf.remaining()))?;
         let resource_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AclCreation", "resource_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AclCreation", "resource_name", version, start - buf.remaining()))?
         };
         let resource_pattern_type = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("AclCreation", "resource_pattern_type", version, start - buf.remaining()))?
         } else {
             3
         };
         let principal = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AclCreation", "principal", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AclCreation", "principal", version, start - buf.remaining()))?
         };
         let host = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AclCreation", "host", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AclCreation", "host", version, start - buf.remaining()))?
         };
-        let operation = types::Int8.decode(buf)?;
-        let permission_type = types::Int8.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let operation = types::Int8.decode(buf).map_err(|e| e.with_field("AclCreation", "operation", version, start - buf.remaining()))?;
+        let permission_type = types::Int8.decode(buf).map_err(|e| e.with_field("AclCreation", "permission_type", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AclCreation", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AclCreation", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AclCreation", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AclCreation", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -208,7 +221,7 @@ impl Default for AclCreation {
             host: Default::default(),
             operation: 0,
             permission_type: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -217,9 +230,109 @@ impl Message for AclCreation {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl AclCreation {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.resource_pattern_type = 3;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.resource_pattern_type != 3 {
+                invalid.push(format!("{}resource_pattern_type", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AclCreation {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("resourceType".into(), JsonValue::to_json(&self.resource_type));
+        node.insert("resourceName".into(), JsonValue::to_json(&self.resource_name));
+        if version >= 1 {
+            node.insert("resourcePatternType".into(), JsonValue::to_json(&self.resource_pattern_type));
+        }
+        node.insert("principal".into(), JsonValue::to_json(&self.principal));
+        node.insert("host".into(), JsonValue::to_json(&self.host));
+        node.insert("operation".into(), JsonValue::to_json(&self.operation));
+        node.insert("permissionType".into(), JsonValue::to_json(&self.permission_type));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AclCreation")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("resourceType") {
+            this.resource_type = JsonValue::from_json(value, "AclCreation.resourceType")?;
+        } else {
+            return Err(crate::json::missing("AclCreation", "resourceType", version));
+        }
+        if let Some(value) = object.get("resourceName") {
+            this.resource_name = JsonValue::from_json(value, "AclCreation.resourceName")?;
+        } else {
+            return Err(crate::json::missing("AclCreation", "resourceName", version));
+        }
+        if let Some(value) = object.get("resourcePatternType") {
+            this.resource_pattern_type = JsonValue::from_json(value, "AclCreation.resourcePatternType")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("AclCreation", "resourcePatternType", version));
+        }
+        if let Some(value) = object.get("principal") {
+            this.principal = JsonValue::from_json(value, "AclCreation.principal")?;
+        } else {
+            return Err(crate::json::missing("AclCreation", "principal", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "AclCreation.host")?;
+        } else {
+            return Err(crate::json::missing("AclCreation", "host", version));
+        }
+        if let Some(value) = object.get("operation") {
+            this.operation = JsonValue::from_json(value, "AclCreation.operation")?;
+        } else {
+            return Err(crate::json::missing("AclCreation", "operation", version));
+        }
+        if let Some(value) = object.get("permissionType") {
+            this.permission_type = JsonValue::from_json(value, "AclCreation.permissionType")?;
+        } else {
+            return Err(crate::json::missing("AclCreation", "permissionType", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreateAclsRequest {
     /// The ACLs that we want to create.
@@ -228,7 +341,7 @@ pub struct CreateAclsRequest {
     pub creations: Vec<AclCreation>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreateAclsRequest {
@@ -241,39 +354,49 @@ impl Builder for CreateAclsRequest {
 
 impl Encodable for CreateAclsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateAclsRequest", version));
+        }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.creations)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.creations).map_err(|e| e.with_field("CreateAclsRequest", "creations", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.creations)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.creations).map_err(|e| e.with_field("CreateAclsRequest", "creations", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateAclsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateAclsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.creations)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.creations).map_err(|e| e.with_field("CreateAclsRequest", "creations", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.creations)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.creations).map_err(|e| e.with_field("CreateAclsRequest", "creations", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateAclsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -281,19 +404,22 @@ impl Encodable for CreateAclsRequest {
 
 impl Decodable for CreateAclsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("CreateAclsRequest", version));
+        }
+        let start = buf.remaining();
         let creations = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreateAclsRequest", "creations", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreateAclsRequest", "creations", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -308,7 +434,7 @@ impl Default for CreateAclsRequest {
     fn default() -> Self {
         Self {
             creations: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -317,6 +443,100 @@ impl Message for CreateAclsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl CreateAclsRequest {
+    /// Encode the message with the elements of `creations` produced by an iterator of known length, in place of
+    /// the `creations` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_creations<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, creations: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = AclCreation>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateAclsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let creations = creations.into_iter();
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(creations)).map_err(|e| e.with_field("CreateAclsRequest", "creations", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(creations)).map_err(|e| e.with_field("CreateAclsRequest", "creations", version))?;
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateAclsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateAclsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl CreateAclsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.creations.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.creations.iter().enumerate() {
+            item.validate_into(version, &format!("{}creations[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreateAclsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("creations".into(), serde_json::Value::Array(self.creations.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreateAclsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("creations") {
+            this.creations = crate::json::array(value, "CreateAclsRequest.creations")?.iter().map(|item| AclCreation::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("CreateAclsRequest", "creations", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for CreateAclsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -327,3 +547,15 @@ impl HeaderVersion for CreateAclsRequest {
     }
 }
 
+impl CreateAclsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::CreateAclsResponse {
+        let mut response = super::CreateAclsResponse::default();
+        response
+    }
+}
+
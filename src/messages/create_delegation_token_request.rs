@@ -5,35 +5,43 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreatableRenewers {
     /// The type of the Kafka principal.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_type: StrBytes,
 
     /// The name of the Kafka principal.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_name: StrBytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreatableRenewers {
@@ -46,49 +54,53 @@ impl Builder for CreatableRenewers {
 
 impl Encodable for CreatableRenewers {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_type)?;
+            types::CompactString.encode(buf, &self.principal_type).map_err(|e| e.with_field("CreatableRenewers", "principal_type", version))?;
         } else {
-            types::String.encode(buf, &self.principal_type)?;
+            types::String.encode(buf, &self.principal_type).map_err(|e| e.with_field("CreatableRenewers", "principal_type", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_name)?;
+            types::CompactString.encode(buf, &self.principal_name).map_err(|e| e.with_field("CreatableRenewers", "principal_name", version))?;
         } else {
-            types::String.encode(buf, &self.principal_name)?;
+            types::String.encode(buf, &self.principal_name).map_err(|e| e.with_field("CreatableRenewers", "principal_name", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableRenewers", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatableRenewers", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableRenewers", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_type)?;
+            total_size += types::CompactString.compute_size(&self.principal_type).map_err(|e| e.with_field("CreatableRenewers", "principal_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_type)?;
+            total_size += types::String.compute_size(&self.principal_type).map_err(|e| e.with_field("CreatableRenewers", "principal_type", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_name)?;
+            total_size += types::CompactString.compute_size(&self.principal_name).map_err(|e| e.with_field("CreatableRenewers", "principal_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_name)?;
+            total_size += types::String.compute_size(&self.principal_name).map_err(|e| e.with_field("CreatableRenewers", "principal_name", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableRenewers", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreatableRenewers", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableRenewers", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -96,24 +108,24 @@ impl Encodable for CreatableRenewers {
 
 impl Decodable for CreatableRenewers {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let principal_type = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreatableRenewers", "principal_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreatableRenewers", "principal_type", version, start - buf.remaining()))?
         };
         let principal_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreatableRenewers", "principal_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreatableRenewers", "principal_name", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableRenewers", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableRenewers", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableRenewers", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreatableRenewers", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -130,7 +142,7 @@ impl Default for CreatableRenewers {
         Self {
             principal_type: Default::default(),
             principal_name: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -139,19 +151,83 @@ impl Message for CreatableRenewers {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl CreatableRenewers {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreatableRenewers {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("principalType".into(), JsonValue::to_json(&self.principal_type));
+        node.insert("principalName".into(), JsonValue::to_json(&self.principal_name));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreatableRenewers")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("principalType") {
+            this.principal_type = JsonValue::from_json(value, "CreatableRenewers.principalType")?;
+        } else {
+            return Err(crate::json::missing("CreatableRenewers", "principalType", version));
+        }
+        if let Some(value) = object.get("principalName") {
+            this.principal_name = JsonValue::from_json(value, "CreatableRenewers.principalName")?;
+        } else {
+            return Err(crate::json::missing("CreatableRenewers", "principalName", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreateDelegationTokenRequest {
     /// The principal type of the owner of the token. If it's null it defaults to the token request principal.
     /// 
     /// Supported API versions: 3
+    /// May be `None` in versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub owner_principal_type: Option<StrBytes>,
 
     /// The principal name of the owner of the token. If it's null it defaults to the token request principal.
     /// 
     /// Supported API versions: 3
+    /// May be `None` in versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub owner_principal_name: Option<StrBytes>,
 
     /// A list of those who are allowed to renew this token before it expires.
@@ -165,7 +241,7 @@ pub struct CreateDelegationTokenRequest {
     pub max_lifetime_ms: i64,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreateDelegationTokenRequest {
@@ -178,69 +254,79 @@ impl Builder for CreateDelegationTokenRequest {
 
 impl Encodable for CreateDelegationTokenRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateDelegationTokenRequest", version));
+        }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.owner_principal_type)?;
+            types::CompactString.encode(buf, &self.owner_principal_type).map_err(|e| e.with_field("CreateDelegationTokenRequest", "owner_principal_type", version))?;
         } else {
             if !self.owner_principal_type.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenRequest", "owner_principal_type", version));
             }
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.owner_principal_name)?;
+            types::CompactString.encode(buf, &self.owner_principal_name).map_err(|e| e.with_field("CreateDelegationTokenRequest", "owner_principal_name", version))?;
         } else {
             if !self.owner_principal_name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenRequest", "owner_principal_name", version));
             }
         }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.renewers)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.renewers).map_err(|e| e.with_field("CreateDelegationTokenRequest", "renewers", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.renewers)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.renewers).map_err(|e| e.with_field("CreateDelegationTokenRequest", "renewers", version))?;
         }
-        types::Int64.encode(buf, &self.max_lifetime_ms)?;
+        types::Int64.encode(buf, &self.max_lifetime_ms).map_err(|e| e.with_field("CreateDelegationTokenRequest", "max_lifetime_ms", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateDelegationTokenRequest", version));
+        }
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.owner_principal_type)?;
+            total_size += types::CompactString.compute_size(&self.owner_principal_type).map_err(|e| e.with_field("CreateDelegationTokenRequest", "owner_principal_type", version))?;
         } else {
             if !self.owner_principal_type.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenRequest", "owner_principal_type", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.owner_principal_name)?;
+            total_size += types::CompactString.compute_size(&self.owner_principal_name).map_err(|e| e.with_field("CreateDelegationTokenRequest", "owner_principal_name", version))?;
         } else {
             if !self.owner_principal_name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenRequest", "owner_principal_name", version));
             }
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.renewers)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.renewers).map_err(|e| e.with_field("CreateDelegationTokenRequest", "renewers", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.renewers)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.renewers).map_err(|e| e.with_field("CreateDelegationTokenRequest", "renewers", version))?;
         }
-        total_size += types::Int64.compute_size(&self.max_lifetime_ms)?;
+        total_size += types::Int64.compute_size(&self.max_lifetime_ms).map_err(|e| e.with_field("CreateDelegationTokenRequest", "max_lifetime_ms", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -248,30 +334,33 @@ impl Encodable for CreateDelegationTokenRequest {
 
 impl Decodable for CreateDelegationTokenRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("CreateDelegationTokenRequest", version));
+        }
+        let start = buf.remaining();
         let owner_principal_type = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenRequest", "owner_principal_type", version, start - buf.remaining()))?
         } else {
             Some(Default::default())
         };
         let owner_principal_name = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenRequest", "owner_principal_name", version, start - buf.remaining()))?
         } else {
             Some(Default::default())
         };
         let renewers = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreateDelegationTokenRequest", "renewers", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreateDelegationTokenRequest", "renewers", version, start - buf.remaining()))?
         };
-        let max_lifetime_ms = types::Int64.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let max_lifetime_ms = types::Int64.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenRequest", "max_lifetime_ms", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -292,7 +381,7 @@ impl Default for CreateDelegationTokenRequest {
             owner_principal_name: Some(Default::default()),
             renewers: Default::default(),
             max_lifetime_ms: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -301,6 +390,153 @@ impl Message for CreateDelegationTokenRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl CreateDelegationTokenRequest {
+    /// Encode the message with the elements of `renewers` produced by an iterator of known length, in place of
+    /// the `renewers` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_renewers<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, renewers: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = CreatableRenewers>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateDelegationTokenRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let renewers = renewers.into_iter();
+        if version >= 3 {
+            types::CompactString.encode(buf, &self.owner_principal_type).map_err(|e| e.with_field("CreateDelegationTokenRequest", "owner_principal_type", version))?;
+        } else {
+            if !self.owner_principal_type.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenRequest", "owner_principal_type", version));
+            }
+        }
+        if version >= 3 {
+            types::CompactString.encode(buf, &self.owner_principal_name).map_err(|e| e.with_field("CreateDelegationTokenRequest", "owner_principal_name", version))?;
+        } else {
+            if !self.owner_principal_name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenRequest", "owner_principal_name", version));
+            }
+        }
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(renewers)).map_err(|e| e.with_field("CreateDelegationTokenRequest", "renewers", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(renewers)).map_err(|e| e.with_field("CreateDelegationTokenRequest", "renewers", version))?;
+        }
+        types::Int64.encode(buf, &self.max_lifetime_ms).map_err(|e| e.with_field("CreateDelegationTokenRequest", "max_lifetime_ms", version))?;
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateDelegationTokenRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl CreateDelegationTokenRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.owner_principal_type = Some(Default::default());
+        }
+        if version < 3 {
+            self.owner_principal_name = Some(Default::default());
+        }
+        for item in self.renewers.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.owner_principal_type.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}owner_principal_type", path));
+            }
+        }
+        if version < 3 {
+            if !self.owner_principal_name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}owner_principal_name", path));
+            }
+        }
+        for (i, item) in self.renewers.iter().enumerate() {
+            item.validate_into(version, &format!("{}renewers[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreateDelegationTokenRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("ownerPrincipalType".into(), JsonValue::to_json(&self.owner_principal_type));
+        }
+        if version >= 3 {
+            node.insert("ownerPrincipalName".into(), JsonValue::to_json(&self.owner_principal_name));
+        }
+        node.insert("renewers".into(), serde_json::Value::Array(self.renewers.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("maxLifetimeMs".into(), JsonValue::to_json(&self.max_lifetime_ms));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreateDelegationTokenRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("ownerPrincipalType") {
+            this.owner_principal_type = JsonValue::from_json(value, "CreateDelegationTokenRequest.ownerPrincipalType")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("CreateDelegationTokenRequest", "ownerPrincipalType", version));
+        }
+        if let Some(value) = object.get("ownerPrincipalName") {
+            this.owner_principal_name = JsonValue::from_json(value, "CreateDelegationTokenRequest.ownerPrincipalName")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("CreateDelegationTokenRequest", "ownerPrincipalName", version));
+        }
+        if let Some(value) = object.get("renewers") {
+            this.renewers = crate::json::array(value, "CreateDelegationTokenRequest.renewers")?.iter().map(|item| CreatableRenewers::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenRequest", "renewers", version));
+        }
+        if let Some(value) = object.get("maxLifetimeMs") {
+            this.max_lifetime_ms = JsonValue::from_json(value, "CreateDelegationTokenRequest.maxLifetimeMs")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenRequest", "maxLifetimeMs", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for CreateDelegationTokenRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -311,3 +547,16 @@ impl HeaderVersion for CreateDelegationTokenRequest {
     }
 }
 
+impl CreateDelegationTokenRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::CreateDelegationTokenResponse {
+        let mut response = super::CreateDelegationTokenResponse::default();
+        response.error_code = error.code();
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreateDelegationTokenResponse {
     /// The top-level error, or zero if there was no error.
@@ -30,21 +36,25 @@ pub struct CreateDelegationTokenResponse {
     /// The principal type of the token owner.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_type: StrBytes,
 
     /// The name of the token owner.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_name: StrBytes,
 
     /// The principal type of the requester of the token.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub token_requester_principal_type: StrBytes,
 
     /// The principal type of the requester of the token.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub token_requester_principal_name: StrBytes,
 
     /// When this token was generated.
@@ -65,6 +75,7 @@ pub struct CreateDelegationTokenResponse {
     /// The token UUID.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub token_id: StrBytes,
 
     /// HMAC of the delegation token.
@@ -78,7 +89,7 @@ pub struct CreateDelegationTokenResponse {
     pub throttle_time_ms: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreateDelegationTokenResponse {
@@ -91,107 +102,117 @@ impl Builder for CreateDelegationTokenResponse {
 
 impl Encodable for CreateDelegationTokenResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateDelegationTokenResponse", version));
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("CreateDelegationTokenResponse", "error_code", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_type)?;
+            types::CompactString.encode(buf, &self.principal_type).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_type", version))?;
         } else {
-            types::String.encode(buf, &self.principal_type)?;
+            types::String.encode(buf, &self.principal_type).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_type", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_name)?;
+            types::CompactString.encode(buf, &self.principal_name).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_name", version))?;
         } else {
-            types::String.encode(buf, &self.principal_name)?;
+            types::String.encode(buf, &self.principal_name).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_name", version))?;
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.token_requester_principal_type)?;
+            types::CompactString.encode(buf, &self.token_requester_principal_type).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_requester_principal_type", version))?;
         } else {
             if !self.token_requester_principal_type.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenResponse", "token_requester_principal_type", version));
             }
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.token_requester_principal_name)?;
+            types::CompactString.encode(buf, &self.token_requester_principal_name).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_requester_principal_name", version))?;
         } else {
             if !self.token_requester_principal_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenResponse", "token_requester_principal_name", version));
             }
         }
-        types::Int64.encode(buf, &self.issue_timestamp_ms)?;
-        types::Int64.encode(buf, &self.expiry_timestamp_ms)?;
-        types::Int64.encode(buf, &self.max_timestamp_ms)?;
+        types::Int64.encode(buf, &self.issue_timestamp_ms).map_err(|e| e.with_field("CreateDelegationTokenResponse", "issue_timestamp_ms", version))?;
+        types::Int64.encode(buf, &self.expiry_timestamp_ms).map_err(|e| e.with_field("CreateDelegationTokenResponse", "expiry_timestamp_ms", version))?;
+        types::Int64.encode(buf, &self.max_timestamp_ms).map_err(|e| e.with_field("CreateDelegationTokenResponse", "max_timestamp_ms", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.token_id)?;
+            types::CompactString.encode(buf, &self.token_id).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_id", version))?;
         } else {
-            types::String.encode(buf, &self.token_id)?;
+            types::String.encode(buf, &self.token_id).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_id", version))?;
         }
         if version >= 2 {
-            types::CompactBytes.encode(buf, &self.hmac)?;
+            types::CompactBytes.encode(buf, &self.hmac).map_err(|e| e.with_field("CreateDelegationTokenResponse", "hmac", version))?;
         } else {
-            types::Bytes.encode(buf, &self.hmac)?;
+            types::Bytes.encode(buf, &self.hmac).map_err(|e| e.with_field("CreateDelegationTokenResponse", "hmac", version))?;
         }
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("CreateDelegationTokenResponse", "throttle_time_ms", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateDelegationTokenResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("CreateDelegationTokenResponse", "error_code", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_type)?;
+            total_size += types::CompactString.compute_size(&self.principal_type).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_type)?;
+            total_size += types::String.compute_size(&self.principal_type).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_type", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_name)?;
+            total_size += types::CompactString.compute_size(&self.principal_name).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_name)?;
+            total_size += types::String.compute_size(&self.principal_name).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_name", version))?;
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.token_requester_principal_type)?;
+            total_size += types::CompactString.compute_size(&self.token_requester_principal_type).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_requester_principal_type", version))?;
         } else {
             if !self.token_requester_principal_type.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenResponse", "token_requester_principal_type", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.token_requester_principal_name)?;
+            total_size += types::CompactString.compute_size(&self.token_requester_principal_name).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_requester_principal_name", version))?;
         } else {
             if !self.token_requester_principal_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateDelegationTokenResponse", "token_requester_principal_name", version));
             }
         }
-        total_size += types::Int64.compute_size(&self.issue_timestamp_ms)?;
-        total_size += types::Int64.compute_size(&self.expiry_timestamp_ms)?;
-        total_size += types::Int64.compute_size(&self.max_timestamp_ms)?;
+        total_size += types::Int64.compute_size(&self.issue_timestamp_ms).map_err(|e| e.with_field("CreateDelegationTokenResponse", "issue_timestamp_ms", version))?;
+        total_size += types::Int64.compute_size(&self.expiry_timestamp_ms).map_err(|e| e.with_field("CreateDelegationTokenResponse", "expiry_timestamp_ms", version))?;
+        total_size += types::Int64.compute_size(&self.max_timestamp_ms).map_err(|e| e.with_field("CreateDelegationTokenResponse", "max_timestamp_ms", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.token_id)?;
+            total_size += types::CompactString.compute_size(&self.token_id).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.token_id)?;
+            total_size += types::String.compute_size(&self.token_id).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_id", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactBytes.compute_size(&self.hmac)?;
+            total_size += types::CompactBytes.compute_size(&self.hmac).map_err(|e| e.with_field("CreateDelegationTokenResponse", "hmac", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.hmac)?;
+            total_size += types::Bytes.compute_size(&self.hmac).map_err(|e| e.with_field("CreateDelegationTokenResponse", "hmac", version))?;
         }
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("CreateDelegationTokenResponse", "throttle_time_ms", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -199,49 +220,52 @@ impl Encodable for CreateDelegationTokenResponse {
 
 impl Decodable for CreateDelegationTokenResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("CreateDelegationTokenResponse", version));
+        }
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "error_code", version, start - buf.remaining()))?;
         let principal_type = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_type", version, start - buf.remaining()))?
         };
         let principal_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "principal_name", version, start - buf.remaining()))?
         };
         let token_requester_principal_type = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_requester_principal_type", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let token_requester_principal_name = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_requester_principal_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let issue_timestamp_ms = types::Int64.decode(buf)?;
-        let expiry_timestamp_ms = types::Int64.decode(buf)?;
-        let max_timestamp_ms = types::Int64.decode(buf)?;
+        let issue_timestamp_ms = types::Int64.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "issue_timestamp_ms", version, start - buf.remaining()))?;
+        let expiry_timestamp_ms = types::Int64.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "expiry_timestamp_ms", version, start - buf.remaining()))?;
+        let max_timestamp_ms = types::Int64.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "max_timestamp_ms", version, start - buf.remaining()))?;
         let token_id = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "token_id", version, start - buf.remaining()))?
         };
         let hmac = if version >= 2 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "hmac", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "hmac", version, start - buf.remaining()))?
         };
-        let throttle_time_ms = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "throttle_time_ms", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreateDelegationTokenResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -276,7 +300,7 @@ impl Default for CreateDelegationTokenResponse {
             token_id: Default::default(),
             hmac: Default::default(),
             throttle_time_ms: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -285,6 +309,138 @@ impl Message for CreateDelegationTokenResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl CreateDelegationTokenResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.token_requester_principal_type = Default::default();
+        }
+        if version < 3 {
+            self.token_requester_principal_name = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.token_requester_principal_type.is_empty() {
+                invalid.push(format!("{}token_requester_principal_type", path));
+            }
+        }
+        if version < 3 {
+            if !self.token_requester_principal_name.is_empty() {
+                invalid.push(format!("{}token_requester_principal_name", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreateDelegationTokenResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("principalType".into(), JsonValue::to_json(&self.principal_type));
+        node.insert("principalName".into(), JsonValue::to_json(&self.principal_name));
+        if version >= 3 {
+            node.insert("tokenRequesterPrincipalType".into(), JsonValue::to_json(&self.token_requester_principal_type));
+        }
+        if version >= 3 {
+            node.insert("tokenRequesterPrincipalName".into(), JsonValue::to_json(&self.token_requester_principal_name));
+        }
+        node.insert("issueTimestampMs".into(), JsonValue::to_json(&self.issue_timestamp_ms));
+        node.insert("expiryTimestampMs".into(), JsonValue::to_json(&self.expiry_timestamp_ms));
+        node.insert("maxTimestampMs".into(), JsonValue::to_json(&self.max_timestamp_ms));
+        node.insert("tokenId".into(), JsonValue::to_json(&self.token_id));
+        node.insert("hmac".into(), JsonValue::to_json(&self.hmac));
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreateDelegationTokenResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "CreateDelegationTokenResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("principalType") {
+            this.principal_type = JsonValue::from_json(value, "CreateDelegationTokenResponse.principalType")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "principalType", version));
+        }
+        if let Some(value) = object.get("principalName") {
+            this.principal_name = JsonValue::from_json(value, "CreateDelegationTokenResponse.principalName")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "principalName", version));
+        }
+        if let Some(value) = object.get("tokenRequesterPrincipalType") {
+            this.token_requester_principal_type = JsonValue::from_json(value, "CreateDelegationTokenResponse.tokenRequesterPrincipalType")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "tokenRequesterPrincipalType", version));
+        }
+        if let Some(value) = object.get("tokenRequesterPrincipalName") {
+            this.token_requester_principal_name = JsonValue::from_json(value, "CreateDelegationTokenResponse.tokenRequesterPrincipalName")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "tokenRequesterPrincipalName", version));
+        }
+        if let Some(value) = object.get("issueTimestampMs") {
+            this.issue_timestamp_ms = JsonValue::from_json(value, "CreateDelegationTokenResponse.issueTimestampMs")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "issueTimestampMs", version));
+        }
+        if let Some(value) = object.get("expiryTimestampMs") {
+            this.expiry_timestamp_ms = JsonValue::from_json(value, "CreateDelegationTokenResponse.expiryTimestampMs")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "expiryTimestampMs", version));
+        }
+        if let Some(value) = object.get("maxTimestampMs") {
+            this.max_timestamp_ms = JsonValue::from_json(value, "CreateDelegationTokenResponse.maxTimestampMs")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "maxTimestampMs", version));
+        }
+        if let Some(value) = object.get("tokenId") {
+            this.token_id = JsonValue::from_json(value, "CreateDelegationTokenResponse.tokenId")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "tokenId", version));
+        }
+        if let Some(value) = object.get("hmac") {
+            this.hmac = JsonValue::from_json(value, "CreateDelegationTokenResponse.hmac")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "hmac", version));
+        }
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "CreateDelegationTokenResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("CreateDelegationTokenResponse", "throttleTimeMs", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for CreateDelegationTokenResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
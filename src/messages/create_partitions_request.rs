@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreatePartitionsAssignment {
     /// The assigned broker IDs.
@@ -28,7 +34,7 @@ pub struct CreatePartitionsAssignment {
     pub broker_ids: Vec<super::BrokerId>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreatePartitionsAssignment {
@@ -41,39 +47,43 @@ impl Builder for CreatePartitionsAssignment {
 
 impl Encodable for CreatePartitionsAssignment {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactArray(types::Int32).encode(buf, &self.broker_ids)?;
+            types::CompactArray(types::Int32).encode(buf, &self.broker_ids).map_err(|e| e.with_field("CreatePartitionsAssignment", "broker_ids", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.broker_ids)?;
+            types::Array(types::Int32).encode(buf, &self.broker_ids).map_err(|e| e.with_field("CreatePartitionsAssignment", "broker_ids", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.broker_ids)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.broker_ids).map_err(|e| e.with_field("CreatePartitionsAssignment", "broker_ids", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.broker_ids)?;
+            total_size += types::Array(types::Int32).compute_size(&self.broker_ids).map_err(|e| e.with_field("CreatePartitionsAssignment", "broker_ids", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -81,19 +91,19 @@ impl Encodable for CreatePartitionsAssignment {
 
 impl Decodable for CreatePartitionsAssignment {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let broker_ids = if version >= 2 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("CreatePartitionsAssignment", "broker_ids", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("CreatePartitionsAssignment", "broker_ids", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreatePartitionsAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -108,7 +118,7 @@ impl Default for CreatePartitionsAssignment {
     fn default() -> Self {
         Self {
             broker_ids: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -117,9 +127,63 @@ impl Message for CreatePartitionsAssignment {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl CreatePartitionsAssignment {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreatePartitionsAssignment {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("brokerIds".into(), JsonValue::to_json(&self.broker_ids));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreatePartitionsAssignment")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("brokerIds") {
+            this.broker_ids = JsonValue::from_json(value, "CreatePartitionsAssignment.brokerIds")?;
+        } else {
+            return Err(crate::json::missing("CreatePartitionsAssignment", "brokerIds", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreatePartitionsTopic {
     /// The new partition count.
@@ -130,10 +194,11 @@ pub struct CreatePartitionsTopic {
     /// The new partition assignments.
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
     pub assignments: Option<Vec<CreatePartitionsAssignment>>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreatePartitionsTopic {
@@ -147,51 +212,55 @@ impl Builder for CreatePartitionsTopic {
 impl MapEncodable for CreatePartitionsTopic {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("CreatePartitionsTopic", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("CreatePartitionsTopic", "name", version))?;
         }
-        types::Int32.encode(buf, &self.count)?;
+        types::Int32.encode(buf, &self.count).map_err(|e| e.with_field("CreatePartitionsTopic", "count", version))?;
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.assignments)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.assignments).map_err(|e| e.with_field("CreatePartitionsTopic", "assignments", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.assignments)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.assignments).map_err(|e| e.with_field("CreatePartitionsTopic", "assignments", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatePartitionsTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatePartitionsTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatePartitionsTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("CreatePartitionsTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("CreatePartitionsTopic", "name", version))?;
         }
-        total_size += types::Int32.compute_size(&self.count)?;
+        total_size += types::Int32.compute_size(&self.count).map_err(|e| e.with_field("CreatePartitionsTopic", "count", version))?;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.assignments)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.assignments).map_err(|e| e.with_field("CreatePartitionsTopic", "assignments", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.assignments)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.assignments).map_err(|e| e.with_field("CreatePartitionsTopic", "assignments", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatePartitionsTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreatePartitionsTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreatePartitionsTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -200,25 +269,25 @@ impl MapEncodable for CreatePartitionsTopic {
 impl MapDecodable for CreatePartitionsTopic {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreatePartitionsTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreatePartitionsTopic", "name", version, start - buf.remaining()))?
         };
-        let count = types::Int32.decode(buf)?;
+        let count = types::Int32.decode(buf).map_err(|e| e.with_field("CreatePartitionsTopic", "count", version, start - buf.remaining()))?;
         let assignments = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatePartitionsTopic", "assignments", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatePartitionsTopic", "assignments", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreatePartitionsTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -235,7 +304,7 @@ impl Default for CreatePartitionsTopic {
         Self {
             count: 0,
             assignments: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -244,9 +313,86 @@ impl Message for CreatePartitionsTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl CreatePartitionsTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if let Some(items) = &mut self.assignments {
+            for item in items.iter_mut() {
+                item.normalize_to(version);
+            }
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if let Some(items) = &self.assignments {
+            for (i, item) in items.iter().enumerate() {
+                item.validate_into(version, &format!("{}assignments[{}].", path, i), invalid);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreatePartitionsTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("count".into(), JsonValue::to_json(&self.count));
+        node.insert("assignments".into(), self.assignments.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "CreatePartitionsTopic")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "CreatePartitionsTopic.name")?;
+        } else {
+            return Err(crate::json::missing("CreatePartitionsTopic", "name", version));
+        }
+        if let Some(value) = object.get("count") {
+            this.count = JsonValue::from_json(value, "CreatePartitionsTopic.count")?;
+        } else {
+            return Err(crate::json::missing("CreatePartitionsTopic", "count", version));
+        }
+        if let Some(value) = object.get("assignments") {
+            this.assignments = if value.is_null() { None } else { Some(crate::json::array(value, "CreatePartitionsTopic.assignments")?.iter().map(|item| CreatePartitionsAssignment::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else {
+            return Err(crate::json::missing("CreatePartitionsTopic", "assignments", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreatePartitionsRequest {
     /// Each topic that we want to create new partitions inside.
@@ -265,7 +411,7 @@ pub struct CreatePartitionsRequest {
     pub validate_only: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreatePartitionsRequest {
@@ -278,43 +424,53 @@ impl Builder for CreatePartitionsRequest {
 
 impl Encodable for CreatePartitionsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreatePartitionsRequest", version));
+        }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("CreatePartitionsRequest", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("CreatePartitionsRequest", "topics", version))?;
         }
-        types::Int32.encode(buf, &self.timeout_ms)?;
-        types::Boolean.encode(buf, &self.validate_only)?;
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("CreatePartitionsRequest", "timeout_ms", version))?;
+        types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("CreatePartitionsRequest", "validate_only", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatePartitionsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreatePartitionsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("CreatePartitionsRequest", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("CreatePartitionsRequest", "topics", version))?;
         }
-        total_size += types::Int32.compute_size(&self.timeout_ms)?;
-        total_size += types::Boolean.compute_size(&self.validate_only)?;
+        total_size += types::Int32.compute_size(&self.timeout_ms).map_err(|e| e.with_field("CreatePartitionsRequest", "timeout_ms", version))?;
+        total_size += types::Boolean.compute_size(&self.validate_only).map_err(|e| e.with_field("CreatePartitionsRequest", "validate_only", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatePartitionsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -322,21 +478,24 @@ impl Encodable for CreatePartitionsRequest {
 
 impl Decodable for CreatePartitionsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("CreatePartitionsRequest", version));
+        }
+        let start = buf.remaining();
         let topics = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatePartitionsRequest", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatePartitionsRequest", "topics", version, start - buf.remaining()))?
         };
-        let timeout_ms = types::Int32.decode(buf)?;
-        let validate_only = types::Boolean.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("CreatePartitionsRequest", "timeout_ms", version, start - buf.remaining()))?;
+        let validate_only = types::Boolean.decode(buf).map_err(|e| e.with_field("CreatePartitionsRequest", "validate_only", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -355,7 +514,7 @@ impl Default for CreatePartitionsRequest {
             topics: Default::default(),
             timeout_ms: 0,
             validate_only: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -364,6 +523,114 @@ impl Message for CreatePartitionsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl CreatePartitionsRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, CreatePartitionsTopic)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreatePartitionsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("CreatePartitionsRequest", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("CreatePartitionsRequest", "topics", version))?;
+        }
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("CreatePartitionsRequest", "timeout_ms", version))?;
+        types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("CreatePartitionsRequest", "validate_only", version))?;
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatePartitionsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatePartitionsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl CreatePartitionsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.topics.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.topics.values().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreatePartitionsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("timeoutMs".into(), JsonValue::to_json(&self.timeout_ms));
+        node.insert("validateOnly".into(), JsonValue::to_json(&self.validate_only));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreatePartitionsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "CreatePartitionsRequest.topics")?.iter().map(|item| CreatePartitionsTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("CreatePartitionsRequest", "topics", version));
+        }
+        if let Some(value) = object.get("timeoutMs") {
+            this.timeout_ms = JsonValue::from_json(value, "CreatePartitionsRequest.timeoutMs")?;
+        } else {
+            return Err(crate::json::missing("CreatePartitionsRequest", "timeoutMs", version));
+        }
+        if let Some(value) = object.get("validateOnly") {
+            this.validate_only = JsonValue::from_json(value, "CreatePartitionsRequest.validateOnly")?;
+        } else {
+            return Err(crate::json::missing("CreatePartitionsRequest", "validateOnly", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for CreatePartitionsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -374,3 +641,23 @@ impl HeaderVersion for CreatePartitionsRequest {
     }
 }
 
+impl CreatePartitionsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::CreatePartitionsResponse {
+        let mut response = super::CreatePartitionsResponse::default();
+        let mut items0 = Vec::new();
+        for (key0, request0) in &self.topics {
+            let mut response0 = super::create_partitions_response::CreatePartitionsTopicResult::default();
+            response0.name = key0.clone();
+            response0.error_code = error.code();
+            items0.push(response0);
+        }
+        response.results = items0;
+        response
+    }
+}
+
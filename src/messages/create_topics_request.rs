@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreatableReplicaAssignment {
     /// The brokers to place the partition on.
@@ -28,7 +34,7 @@ pub struct CreatableReplicaAssignment {
     pub broker_ids: Vec<super::BrokerId>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreatableReplicaAssignment {
@@ -42,41 +48,45 @@ impl Builder for CreatableReplicaAssignment {
 impl MapEncodable for CreatableReplicaAssignment {
     type Key = i32;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, key)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, key).map_err(|e| e.with_field("CreatableReplicaAssignment", "partition_index", version))?;
         if version >= 5 {
-            types::CompactArray(types::Int32).encode(buf, &self.broker_ids)?;
+            types::CompactArray(types::Int32).encode(buf, &self.broker_ids).map_err(|e| e.with_field("CreatableReplicaAssignment", "broker_ids", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.broker_ids)?;
+            types::Array(types::Int32).encode(buf, &self.broker_ids).map_err(|e| e.with_field("CreatableReplicaAssignment", "broker_ids", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(key)?;
+        total_size += types::Int32.compute_size(key).map_err(|e| e.with_field("CreatableReplicaAssignment", "partition_index", version))?;
         if version >= 5 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.broker_ids)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.broker_ids).map_err(|e| e.with_field("CreatableReplicaAssignment", "broker_ids", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.broker_ids)?;
+            total_size += types::Array(types::Int32).compute_size(&self.broker_ids).map_err(|e| e.with_field("CreatableReplicaAssignment", "broker_ids", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -85,20 +95,20 @@ impl MapEncodable for CreatableReplicaAssignment {
 impl MapDecodable for CreatableReplicaAssignment {
     type Key = i32;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let key_field = types::Int32.decode(buf).map_err(|e| e.with_field("CreatableReplicaAssignment", "partition_index", version, start - buf.remaining()))?;
         let broker_ids = if version >= 5 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("CreatableReplicaAssignment", "broker_ids", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("CreatableReplicaAssignment", "broker_ids", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreatableReplicaAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -113,7 +123,7 @@ impl Default for CreatableReplicaAssignment {
     fn default() -> Self {
         Self {
             broker_ids: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -122,18 +132,81 @@ impl Message for CreatableReplicaAssignment {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl CreatableReplicaAssignment {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreatableReplicaAssignment {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &i32, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &i32, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(key));
+        node.insert("brokerIds".into(), JsonValue::to_json(&self.broker_ids));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(i32, Self), JsonError> {
+        let object = crate::json::object(node, "CreatableReplicaAssignment")?;
+        let mut this = Self::default();
+        let mut key = i32::default();
+        if let Some(value) = object.get("partitionIndex") {
+            key = JsonValue::from_json(value, "CreatableReplicaAssignment.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("CreatableReplicaAssignment", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("brokerIds") {
+            this.broker_ids = JsonValue::from_json(value, "CreatableReplicaAssignment.brokerIds")?;
+        } else {
+            return Err(crate::json::missing("CreatableReplicaAssignment", "brokerIds", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreateableTopicConfig {
     /// The configuration value.
     /// 
     /// Supported API versions: 0-7
+    /// May be `None` in versions: 0-7
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub value: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreateableTopicConfig {
@@ -147,49 +220,53 @@ impl Builder for CreateableTopicConfig {
 impl MapEncodable for CreateableTopicConfig {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 5 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("CreateableTopicConfig", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("CreateableTopicConfig", "name", version))?;
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.value)?;
+            types::CompactString.encode(buf, &self.value).map_err(|e| e.with_field("CreateableTopicConfig", "value", version))?;
         } else {
-            types::String.encode(buf, &self.value)?;
+            types::String.encode(buf, &self.value).map_err(|e| e.with_field("CreateableTopicConfig", "value", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateableTopicConfig", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateableTopicConfig", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateableTopicConfig", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 5 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("CreateableTopicConfig", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("CreateableTopicConfig", "name", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.value)?;
+            total_size += types::CompactString.compute_size(&self.value).map_err(|e| e.with_field("CreateableTopicConfig", "value", version))?;
         } else {
-            total_size += types::String.compute_size(&self.value)?;
+            total_size += types::String.compute_size(&self.value).map_err(|e| e.with_field("CreateableTopicConfig", "value", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateableTopicConfig", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreateableTopicConfig", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreateableTopicConfig", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -198,24 +275,24 @@ impl MapEncodable for CreateableTopicConfig {
 impl MapDecodable for CreateableTopicConfig {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateableTopicConfig", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreateableTopicConfig", "name", version, start - buf.remaining()))?
         };
         let value = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreateableTopicConfig", "value", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreateableTopicConfig", "value", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateableTopicConfig", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateableTopicConfig", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateableTopicConfig", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreateableTopicConfig", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -230,7 +307,7 @@ impl Default for CreateableTopicConfig {
     fn default() -> Self {
         Self {
             value: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -239,9 +316,70 @@ impl Message for CreateableTopicConfig {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl CreateableTopicConfig {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreateableTopicConfig {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("value".into(), JsonValue::to_json(&self.value));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "CreateableTopicConfig")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "CreateableTopicConfig.name")?;
+        } else {
+            return Err(crate::json::missing("CreateableTopicConfig", "name", version));
+        }
+        if let Some(value) = object.get("value") {
+            this.value = JsonValue::from_json(value, "CreateableTopicConfig.value")?;
+        } else {
+            return Err(crate::json::missing("CreateableTopicConfig", "value", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreatableTopic {
     /// The number of partitions to create in the topic, or -1 if we are either specifying a manual partition assignment or using the default partitions.
@@ -262,10 +400,11 @@ pub struct CreatableTopic {
     /// The custom topic configurations to set.
     /// 
     /// Supported API versions: 0-7
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub configs: indexmap::IndexMap<StrBytes, CreateableTopicConfig>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreatableTopic {
@@ -279,63 +418,67 @@ impl Builder for CreatableTopic {
 impl MapEncodable for CreatableTopic {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 5 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("CreatableTopic", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("CreatableTopic", "name", version))?;
         }
-        types::Int32.encode(buf, &self.num_partitions)?;
-        types::Int16.encode(buf, &self.replication_factor)?;
+        types::Int32.encode(buf, &self.num_partitions).map_err(|e| e.with_field("CreatableTopic", "num_partitions", version))?;
+        types::Int16.encode(buf, &self.replication_factor).map_err(|e| e.with_field("CreatableTopic", "replication_factor", version))?;
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.assignments)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.assignments).map_err(|e| e.with_field("CreatableTopic", "assignments", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.assignments)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.assignments).map_err(|e| e.with_field("CreatableTopic", "assignments", version))?;
         }
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.configs)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.configs).map_err(|e| e.with_field("CreatableTopic", "configs", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.configs)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.configs).map_err(|e| e.with_field("CreatableTopic", "configs", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatableTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 5 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("CreatableTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("CreatableTopic", "name", version))?;
         }
-        total_size += types::Int32.compute_size(&self.num_partitions)?;
-        total_size += types::Int16.compute_size(&self.replication_factor)?;
+        total_size += types::Int32.compute_size(&self.num_partitions).map_err(|e| e.with_field("CreatableTopic", "num_partitions", version))?;
+        total_size += types::Int16.compute_size(&self.replication_factor).map_err(|e| e.with_field("CreatableTopic", "replication_factor", version))?;
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.assignments)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.assignments).map_err(|e| e.with_field("CreatableTopic", "assignments", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.assignments)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.assignments).map_err(|e| e.with_field("CreatableTopic", "assignments", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.configs)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.configs).map_err(|e| e.with_field("CreatableTopic", "configs", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.configs)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.configs).map_err(|e| e.with_field("CreatableTopic", "configs", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreatableTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -344,31 +487,31 @@ impl MapEncodable for CreatableTopic {
 impl MapDecodable for CreatableTopic {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreatableTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreatableTopic", "name", version, start - buf.remaining()))?
         };
-        let num_partitions = types::Int32.decode(buf)?;
-        let replication_factor = types::Int16.decode(buf)?;
+        let num_partitions = types::Int32.decode(buf).map_err(|e| e.with_field("CreatableTopic", "num_partitions", version, start - buf.remaining()))?;
+        let replication_factor = types::Int16.decode(buf).map_err(|e| e.with_field("CreatableTopic", "replication_factor", version, start - buf.remaining()))?;
         let assignments = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatableTopic", "assignments", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatableTopic", "assignments", version, start - buf.remaining()))?
         };
         let configs = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatableTopic", "configs", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatableTopic", "configs", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreatableTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -389,7 +532,7 @@ impl Default for CreatableTopic {
             replication_factor: 0,
             assignments: Default::default(),
             configs: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -398,9 +541,100 @@ impl Message for CreatableTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl CreatableTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.assignments.values_mut() {
+            item.normalize_to(version);
+        }
+        for item in self.configs.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.assignments.values().enumerate() {
+            item.validate_into(version, &format!("{}assignments[{}].", path, i), invalid);
+        }
+        for (i, item) in self.configs.values().enumerate() {
+            item.validate_into(version, &format!("{}configs[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreatableTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("numPartitions".into(), JsonValue::to_json(&self.num_partitions));
+        node.insert("replicationFactor".into(), JsonValue::to_json(&self.replication_factor));
+        node.insert("assignments".into(), serde_json::Value::Array(self.assignments.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("configs".into(), serde_json::Value::Array(self.configs.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "CreatableTopic")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "CreatableTopic.name")?;
+        } else {
+            return Err(crate::json::missing("CreatableTopic", "name", version));
+        }
+        if let Some(value) = object.get("numPartitions") {
+            this.num_partitions = JsonValue::from_json(value, "CreatableTopic.numPartitions")?;
+        } else {
+            return Err(crate::json::missing("CreatableTopic", "numPartitions", version));
+        }
+        if let Some(value) = object.get("replicationFactor") {
+            this.replication_factor = JsonValue::from_json(value, "CreatableTopic.replicationFactor")?;
+        } else {
+            return Err(crate::json::missing("CreatableTopic", "replicationFactor", version));
+        }
+        if let Some(value) = object.get("assignments") {
+            this.assignments = crate::json::array(value, "CreatableTopic.assignments")?.iter().map(|item| CreatableReplicaAssignment::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("CreatableTopic", "assignments", version));
+        }
+        if let Some(value) = object.get("configs") {
+            this.configs = crate::json::array(value, "CreatableTopic.configs")?.iter().map(|item| CreateableTopicConfig::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("CreatableTopic", "configs", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreateTopicsRequest {
     /// The topics to create.
@@ -419,7 +653,7 @@ pub struct CreateTopicsRequest {
     pub validate_only: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreateTopicsRequest {
@@ -432,55 +666,65 @@ impl Builder for CreateTopicsRequest {
 
 impl Encodable for CreateTopicsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateTopicsRequest", version));
+        }
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("CreateTopicsRequest", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("CreateTopicsRequest", "topics", version))?;
         }
-        types::Int32.encode(buf, &self.timeout_ms)?;
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("CreateTopicsRequest", "timeout_ms", version))?;
         if version >= 1 {
-            types::Boolean.encode(buf, &self.validate_only)?;
+            types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("CreateTopicsRequest", "validate_only", version))?;
         } else {
             if self.validate_only {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateTopicsRequest", "validate_only", version));
             }
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateTopicsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateTopicsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("CreateTopicsRequest", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("CreateTopicsRequest", "topics", version))?;
         }
-        total_size += types::Int32.compute_size(&self.timeout_ms)?;
+        total_size += types::Int32.compute_size(&self.timeout_ms).map_err(|e| e.with_field("CreateTopicsRequest", "timeout_ms", version))?;
         if version >= 1 {
-            total_size += types::Boolean.compute_size(&self.validate_only)?;
+            total_size += types::Boolean.compute_size(&self.validate_only).map_err(|e| e.with_field("CreateTopicsRequest", "validate_only", version))?;
         } else {
             if self.validate_only {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateTopicsRequest", "validate_only", version));
             }
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateTopicsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -488,25 +732,28 @@ impl Encodable for CreateTopicsRequest {
 
 impl Decodable for CreateTopicsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("CreateTopicsRequest", version));
+        }
+        let start = buf.remaining();
         let topics = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreateTopicsRequest", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreateTopicsRequest", "topics", version, start - buf.remaining()))?
         };
-        let timeout_ms = types::Int32.decode(buf)?;
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("CreateTopicsRequest", "timeout_ms", version, start - buf.remaining()))?;
         let validate_only = if version >= 1 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("CreateTopicsRequest", "validate_only", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -525,7 +772,7 @@ impl Default for CreateTopicsRequest {
             topics: Default::default(),
             timeout_ms: 60000,
             validate_only: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -534,6 +781,130 @@ impl Message for CreateTopicsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl CreateTopicsRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, CreatableTopic)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateTopicsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 5 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("CreateTopicsRequest", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("CreateTopicsRequest", "topics", version))?;
+        }
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("CreateTopicsRequest", "timeout_ms", version))?;
+        if version >= 1 {
+            types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("CreateTopicsRequest", "validate_only", version))?;
+        } else {
+            if self.validate_only {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreateTopicsRequest", "validate_only", version));
+            }
+        }
+        if version >= 5 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateTopicsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateTopicsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl CreateTopicsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.topics.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 1 {
+            self.validate_only = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.topics.values().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+        if version < 1 {
+            if self.validate_only {
+                invalid.push(format!("{}validate_only", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreateTopicsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("timeoutMs".into(), JsonValue::to_json(&self.timeout_ms));
+        if version >= 1 {
+            node.insert("validateOnly".into(), JsonValue::to_json(&self.validate_only));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreateTopicsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "CreateTopicsRequest.topics")?.iter().map(|item| CreatableTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("CreateTopicsRequest", "topics", version));
+        }
+        if let Some(value) = object.get("timeoutMs") {
+            this.timeout_ms = JsonValue::from_json(value, "CreateTopicsRequest.timeoutMs")?;
+        } else {
+            return Err(crate::json::missing("CreateTopicsRequest", "timeoutMs", version));
+        }
+        if let Some(value) = object.get("validateOnly") {
+            this.validate_only = JsonValue::from_json(value, "CreateTopicsRequest.validateOnly")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("CreateTopicsRequest", "validateOnly", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for CreateTopicsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 5 {
@@ -544,3 +915,25 @@ impl HeaderVersion for CreateTopicsRequest {
     }
 }
 
+impl CreateTopicsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::CreateTopicsResponse {
+        let mut response = super::CreateTopicsResponse::default();
+        let mut items0 = indexmap::IndexMap::new();
+        for (key0, request0) in &self.topics {
+            let mut response0 = super::create_topics_response::CreatableTopicResult::default();
+            response0.error_code = error.code();
+            if version >= 5 {
+                response0.topic_config_error_code = error.code();
+            }
+            items0.insert(key0.clone(), response0);
+        }
+        response.topics = items0;
+        response
+    }
+}
+
@@ -5,31 +5,40 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreatableTopicConfigs {
     /// The configuration name.
     /// 
     /// Supported API versions: 5-7
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub name: StrBytes,
 
     /// The configuration value.
     /// 
     /// Supported API versions: 5-7
+    /// May be `None` in versions: 5-7
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub value: Option<StrBytes>,
 
     /// True if the configuration is read-only.
@@ -48,7 +57,7 @@ pub struct CreatableTopicConfigs {
     pub is_sensitive: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreatableTopicConfigs {
@@ -61,91 +70,95 @@ impl Builder for CreatableTopicConfigs {
 
 impl Encodable for CreatableTopicConfigs {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 5 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("CreatableTopicConfigs", "name", version))?;
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreatableTopicConfigs", "name", version));
             }
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.value)?;
+            types::CompactString.encode(buf, &self.value).map_err(|e| e.with_field("CreatableTopicConfigs", "value", version))?;
         } else {
             if !self.value.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreatableTopicConfigs", "value", version));
             }
         }
         if version >= 5 {
-            types::Boolean.encode(buf, &self.read_only)?;
+            types::Boolean.encode(buf, &self.read_only).map_err(|e| e.with_field("CreatableTopicConfigs", "read_only", version))?;
         } else {
             if self.read_only {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreatableTopicConfigs", "read_only", version));
             }
         }
         if version >= 5 {
-            types::Int8.encode(buf, &self.config_source)?;
+            types::Int8.encode(buf, &self.config_source).map_err(|e| e.with_field("CreatableTopicConfigs", "config_source", version))?;
         }
         if version >= 5 {
-            types::Boolean.encode(buf, &self.is_sensitive)?;
+            types::Boolean.encode(buf, &self.is_sensitive).map_err(|e| e.with_field("CreatableTopicConfigs", "is_sensitive", version))?;
         } else {
             if self.is_sensitive {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreatableTopicConfigs", "is_sensitive", version));
             }
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableTopicConfigs", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatableTopicConfigs", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableTopicConfigs", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("CreatableTopicConfigs", "name", version))?;
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreatableTopicConfigs", "name", version));
             }
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.value)?;
+            total_size += types::CompactString.compute_size(&self.value).map_err(|e| e.with_field("CreatableTopicConfigs", "value", version))?;
         } else {
             if !self.value.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreatableTopicConfigs", "value", version));
             }
         }
         if version >= 5 {
-            total_size += types::Boolean.compute_size(&self.read_only)?;
+            total_size += types::Boolean.compute_size(&self.read_only).map_err(|e| e.with_field("CreatableTopicConfigs", "read_only", version))?;
         } else {
             if self.read_only {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreatableTopicConfigs", "read_only", version));
             }
         }
         if version >= 5 {
-            total_size += types::Int8.compute_size(&self.config_source)?;
+            total_size += types::Int8.compute_size(&self.config_source).map_err(|e| e.with_field("CreatableTopicConfigs", "config_source", version))?;
         }
         if version >= 5 {
-            total_size += types::Boolean.compute_size(&self.is_sensitive)?;
+            total_size += types::Boolean.compute_size(&self.is_sensitive).map_err(|e| e.with_field("CreatableTopicConfigs", "is_sensitive", version))?;
         } else {
             if self.is_sensitive {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("CreatableTopicConfigs", "is_sensitive", version));
             }
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableTopicConfigs", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreatableTopicConfigs", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableTopicConfigs", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -153,39 +166,39 @@ impl Encodable for CreatableTopicConfigs {
 
 impl Decodable for CreatableTopicConfigs {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreatableTopicConfigs", "name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let value = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreatableTopicConfigs", "value", version, start - buf.remaining()))?
         } else {
             Some(Default::default())
         };
         let read_only = if version >= 5 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("CreatableTopicConfigs", "read_only", version, start - buf.remaining()))?
         } else {
             false
         };
         let config_source = if version >= 5 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("CreatableTopicConfigs", "config_source", version, start - buf.remaining()))?
         } else {
             -1
         };
         let is_sensitive = if version >= 5 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("CreatableTopicConfigs", "is_sensitive", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopicConfigs", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopicConfigs", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopicConfigs", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreatableTopicConfigs", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -208,7 +221,7 @@ impl Default for CreatableTopicConfigs {
             read_only: false,
             config_source: -1,
             is_sensitive: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -217,9 +230,137 @@ impl Message for CreatableTopicConfigs {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl CreatableTopicConfigs {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 5 {
+            self.name = Default::default();
+        }
+        if version < 5 {
+            self.value = Some(Default::default());
+        }
+        if version < 5 {
+            self.read_only = false;
+        }
+        if version < 5 {
+            self.config_source = -1;
+        }
+        if version < 5 {
+            self.is_sensitive = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 5 {
+            if !self.name.is_empty() {
+                invalid.push(format!("{}name", path));
+            }
+        }
+        if version < 5 {
+            if !self.value.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}value", path));
+            }
+        }
+        if version < 5 {
+            if self.read_only {
+                invalid.push(format!("{}read_only", path));
+            }
+        }
+        if version < 5 {
+            if self.config_source != -1 {
+                invalid.push(format!("{}config_source", path));
+            }
+        }
+        if version < 5 {
+            if self.is_sensitive {
+                invalid.push(format!("{}is_sensitive", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreatableTopicConfigs {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 5 {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        if version >= 5 {
+            node.insert("value".into(), JsonValue::to_json(&self.value));
+        }
+        if version >= 5 {
+            node.insert("readOnly".into(), JsonValue::to_json(&self.read_only));
+        }
+        if version >= 5 {
+            node.insert("configSource".into(), JsonValue::to_json(&self.config_source));
+        }
+        if version >= 5 {
+            node.insert("isSensitive".into(), JsonValue::to_json(&self.is_sensitive));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreatableTopicConfigs")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "CreatableTopicConfigs.name")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("CreatableTopicConfigs", "name", version));
+        }
+        if let Some(value) = object.get("value") {
+            this.value = JsonValue::from_json(value, "CreatableTopicConfigs.value")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("CreatableTopicConfigs", "value", version));
+        }
+        if let Some(value) = object.get("readOnly") {
+            this.read_only = JsonValue::from_json(value, "CreatableTopicConfigs.readOnly")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("CreatableTopicConfigs", "readOnly", version));
+        }
+        if let Some(value) = object.get("configSource") {
+            this.config_source = JsonValue::from_json(value, "CreatableTopicConfigs.configSource")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("CreatableTopicConfigs", "configSource", version));
+        }
+        if let Some(value) = object.get("isSensitive") {
+            this.is_sensitive = JsonValue::from_json(value, "CreatableTopicConfigs.isSensitive")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("CreatableTopicConfigs", "isSensitive", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreatableTopicResult {
     /// The unique topic ID
@@ -235,6 +376,8 @@ pub struct CreatableTopicResult {
     /// The error message, or null if there was no error.
     /// 
     /// Supported API versions: 1-7
+    /// May be `None` in versions: 1-7
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// Optional topic config error returned if configs are not returned in the response.
@@ -255,10 +398,11 @@ pub struct CreatableTopicResult {
     /// Configuration of the topic.
     /// 
     /// Supported API versions: 5-7
+    /// May be `None` in versions: 5-7
     pub configs: Option<Vec<CreatableTopicConfigs>>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreatableTopicResult {
@@ -272,30 +416,36 @@ impl Builder for CreatableTopicResult {
 impl MapEncodable for CreatableTopicResult {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 5 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("CreatableTopicResult", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("CreatableTopicResult", "name", version))?;
         }
         if version >= 7 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("CreatableTopicResult", "topic_id", version))?;
         }
-        types::Int16.encode(buf, &self.error_code)?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("CreatableTopicResult", "error_code", version))?;
         if version >= 1 {
             if version >= 5 {
-                types::CompactString.encode(buf, &self.error_message)?;
+                types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("CreatableTopicResult", "error_message", version))?;
             } else {
-                types::String.encode(buf, &self.error_message)?;
+                types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("CreatableTopicResult", "error_message", version))?;
             }
         }
         if version >= 5 {
-            types::Int32.encode(buf, &self.num_partitions)?;
+            types::Int32.encode(buf, &self.num_partitions).map_err(|e| e.with_field("CreatableTopicResult", "num_partitions", version))?;
         }
         if version >= 5 {
-            types::Int16.encode(buf, &self.replication_factor)?;
+            types::Int16.encode(buf, &self.replication_factor).map_err(|e| e.with_field("CreatableTopicResult", "replication_factor", version))?;
         }
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.configs)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.configs).map_err(|e| e.with_field("CreatableTopicResult", "configs", version))?;
         }
         if version >= 5 {
             let mut num_tagged_fields = self.unknown_tagged_fields.len();
@@ -303,51 +453,49 @@ impl MapEncodable for CreatableTopicResult {
                 num_tagged_fields += 1;
             }
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableTopicResult", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreatableTopicResult", "unknown_tagged_fields", version))?;
             if self.topic_config_error_code != 0 {
-                let computed_size = types::Int16.compute_size(&self.topic_config_error_code)?;
+                let computed_size = types::Int16.compute_size(&self.topic_config_error_code).map_err(|e| e.with_field("CreatableTopicResult", "topic_config_error_code", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableTopicResult", "topic_config_error_code", version));
                 }
-                types::UnsignedVarInt.encode(buf, 0)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::Int16.encode(buf, &self.topic_config_error_code)?;
+                types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("CreatableTopicResult", "topic_config_error_code", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("CreatableTopicResult", "topic_config_error_code", version))?;
+                types::Int16.encode(buf, &self.topic_config_error_code).map_err(|e| e.with_field("CreatableTopicResult", "topic_config_error_code", version))?;
             }
 
-            write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableTopicResult", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 5 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("CreatableTopicResult", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("CreatableTopicResult", "name", version))?;
         }
         if version >= 7 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("CreatableTopicResult", "topic_id", version))?;
         }
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("CreatableTopicResult", "error_code", version))?;
         if version >= 1 {
             if version >= 5 {
-                total_size += types::CompactString.compute_size(&self.error_message)?;
+                total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("CreatableTopicResult", "error_message", version))?;
             } else {
-                total_size += types::String.compute_size(&self.error_message)?;
+                total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("CreatableTopicResult", "error_message", version))?;
             }
         }
         if version >= 5 {
-            total_size += types::Int32.compute_size(&self.num_partitions)?;
+            total_size += types::Int32.compute_size(&self.num_partitions).map_err(|e| e.with_field("CreatableTopicResult", "num_partitions", version))?;
         }
         if version >= 5 {
-            total_size += types::Int16.compute_size(&self.replication_factor)?;
+            total_size += types::Int16.compute_size(&self.replication_factor).map_err(|e| e.with_field("CreatableTopicResult", "replication_factor", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.configs)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.configs).map_err(|e| e.with_field("CreatableTopicResult", "configs", version))?;
         }
         if version >= 5 {
             let mut num_tagged_fields = self.unknown_tagged_fields.len();
@@ -355,22 +503,20 @@ impl MapEncodable for CreatableTopicResult {
                 num_tagged_fields += 1;
             }
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableTopicResult", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreatableTopicResult", "unknown_tagged_fields", version))?;
             if self.topic_config_error_code != 0 {
-                let computed_size = types::Int16.compute_size(&self.topic_config_error_code)?;
+                let computed_size = types::Int16.compute_size(&self.topic_config_error_code).map_err(|e| e.with_field("CreatableTopicResult", "topic_config_error_code", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreatableTopicResult", "topic_config_error_code", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(0)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("CreatableTopicResult", "topic_config_error_code", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("CreatableTopicResult", "topic_config_error_code", version))?;
                 total_size += computed_size;
             }
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreatableTopicResult", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -379,55 +525,55 @@ impl MapEncodable for CreatableTopicResult {
 impl MapDecodable for CreatableTopicResult {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "name", version, start - buf.remaining()))?
         };
         let topic_id = if version >= 7 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
-        let error_code = types::Int16.decode(buf)?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "error_code", version, start - buf.remaining()))?;
         let error_message = if version >= 1 {
             if version >= 5 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "error_message", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "error_message", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
         let mut topic_config_error_code = 0;
         let num_partitions = if version >= 5 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "num_partitions", version, start - buf.remaining()))?
         } else {
             -1
         };
         let replication_factor = if version >= 5 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "replication_factor", version, start - buf.remaining()))?
         } else {
             -1
         };
         let configs = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "configs", version, start - buf.remaining()))?
         } else {
             Some(Default::default())
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 match tag {
                     0 => {
-                        topic_config_error_code = types::Int16.decode(buf)?;
+                        topic_config_error_code = types::Int16.decode(buf).map_err(|e| e.with_field("CreatableTopicResult", "topic_config_error_code", version, start - buf.remaining()))?;
                     },
                     _ => {
-                        let mut unknown_value = vec![0; size as usize];
-                        buf.try_copy_to_slice(&mut unknown_value)?;
+                        let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreatableTopicResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
                         unknown_tagged_fields.insert(tag as i32, unknown_value);
                     }
                 }
@@ -456,7 +602,7 @@ impl Default for CreatableTopicResult {
             num_partitions: -1,
             replication_factor: -1,
             configs: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -465,9 +611,178 @@ impl Message for CreatableTopicResult {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl CreatableTopicResult {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 7 {
+            self.topic_id = Uuid::nil();
+        }
+        if version < 1 {
+            self.error_message = Some(Default::default());
+        }
+        if version < 5 {
+            self.topic_config_error_code = 0;
+        }
+        if version < 5 {
+            self.num_partitions = -1;
+        }
+        if version < 5 {
+            self.replication_factor = -1;
+        }
+        if version >= 5 {
+            if let Some(items) = &mut self.configs {
+                for item in items.iter_mut() {
+                    item.normalize_to(version);
+                }
+            }
+        } else {
+            self.configs = Some(Default::default());
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 7 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        if version < 1 {
+            if !self.error_message.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}error_message", path));
+            }
+        }
+        if version < 5 {
+            if self.topic_config_error_code != 0 {
+                invalid.push(format!("{}topic_config_error_code", path));
+            }
+        }
+        if version < 5 {
+            if self.num_partitions != -1 {
+                invalid.push(format!("{}num_partitions", path));
+            }
+        }
+        if version < 5 {
+            if self.replication_factor != -1 {
+                invalid.push(format!("{}replication_factor", path));
+            }
+        }
+        if version >= 5 {
+            if let Some(items) = &self.configs {
+                for (i, item) in items.iter().enumerate() {
+                    item.validate_into(version, &format!("{}configs[{}].", path, i), invalid);
+                }
+            }
+        } else {
+            if !self.configs.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}configs", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreatableTopicResult {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        if version >= 7 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        if version >= 1 {
+            node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        }
+        if version >= 5 {
+            if self.topic_config_error_code != 0 {
+                node.insert("topicConfigErrorCode".into(), JsonValue::to_json(&self.topic_config_error_code));
+            }
+        }
+        if version >= 5 {
+            node.insert("numPartitions".into(), JsonValue::to_json(&self.num_partitions));
+        }
+        if version >= 5 {
+            node.insert("replicationFactor".into(), JsonValue::to_json(&self.replication_factor));
+        }
+        if version >= 5 {
+            node.insert("configs".into(), self.configs.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "CreatableTopicResult")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "CreatableTopicResult.name")?;
+        } else {
+            return Err(crate::json::missing("CreatableTopicResult", "name", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "CreatableTopicResult.topicId")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("CreatableTopicResult", "topicId", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "CreatableTopicResult.errorCode")?;
+        } else {
+            return Err(crate::json::missing("CreatableTopicResult", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "CreatableTopicResult.errorMessage")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("CreatableTopicResult", "errorMessage", version));
+        }
+        if let Some(value) = object.get("topicConfigErrorCode") {
+            this.topic_config_error_code = JsonValue::from_json(value, "CreatableTopicResult.topicConfigErrorCode")?;
+        }
+        if let Some(value) = object.get("numPartitions") {
+            this.num_partitions = JsonValue::from_json(value, "CreatableTopicResult.numPartitions")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("CreatableTopicResult", "numPartitions", version));
+        }
+        if let Some(value) = object.get("replicationFactor") {
+            this.replication_factor = JsonValue::from_json(value, "CreatableTopicResult.replicationFactor")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("CreatableTopicResult", "replicationFactor", version));
+        }
+        if let Some(value) = object.get("configs") {
+            this.configs = if value.is_null() { None } else { Some(crate::json::array(value, "CreatableTopicResult.configs")?.iter().map(|item| CreatableTopicConfigs::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else if version >= 5 {
+            return Err(crate::json::missing("CreatableTopicResult", "configs", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct CreateTopicsResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -481,7 +796,7 @@ pub struct CreateTopicsResponse {
     pub topics: indexmap::IndexMap<super::TopicName, CreatableTopicResult>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for CreateTopicsResponse {
@@ -494,45 +809,55 @@ impl Builder for CreateTopicsResponse {
 
 impl Encodable for CreateTopicsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateTopicsResponse", version));
+        }
         if version >= 2 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("CreateTopicsResponse", "throttle_time_ms", version))?;
         }
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("CreateTopicsResponse", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("CreateTopicsResponse", "topics", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateTopicsResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateTopicsResponse", version));
+        }
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("CreateTopicsResponse", "throttle_time_ms", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("CreateTopicsResponse", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("CreateTopicsResponse", "topics", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateTopicsResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -540,24 +865,27 @@ impl Encodable for CreateTopicsResponse {
 
 impl Decodable for CreateTopicsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("CreateTopicsResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 2 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("CreateTopicsResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
         let topics = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreateTopicsResponse", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("CreateTopicsResponse", "topics", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -574,7 +902,7 @@ impl Default for CreateTopicsResponse {
         Self {
             throttle_time_ms: 0,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -583,6 +911,119 @@ impl Message for CreateTopicsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl CreateTopicsResponse {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, CreatableTopicResult)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("CreateTopicsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 2 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("CreateTopicsResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 5 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("CreateTopicsResponse", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("CreateTopicsResponse", "topics", version))?;
+        }
+        if version >= 5 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("CreateTopicsResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("CreateTopicsResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl CreateTopicsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.throttle_time_ms = 0;
+        }
+        for item in self.topics.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        for (i, item) in self.topics.values().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl CreateTopicsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 2 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "CreateTopicsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "CreateTopicsResponse.throttleTimeMs")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("CreateTopicsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "CreateTopicsResponse.topics")?.iter().map(|item| CreatableTopicResult::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("CreateTopicsResponse", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for CreateTopicsResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 5 {
@@ -5,31 +5,39 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DefaultPrincipalData {
     /// The principal type
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub _type: StrBytes,
 
     /// The principal name
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub name: StrBytes,
 
     /// Whether the principal was authenticated by a delegation token on the forwarding broker.
@@ -38,7 +46,7 @@ pub struct DefaultPrincipalData {
     pub token_authenticated: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DefaultPrincipalData {
@@ -51,48 +59,52 @@ impl Builder for DefaultPrincipalData {
 
 impl Encodable for DefaultPrincipalData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, &self._type)?;
-        types::CompactString.encode(buf, &self.name)?;
-        types::Boolean.encode(buf, &self.token_authenticated)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, &self._type).map_err(|e| e.with_field("DefaultPrincipalData", "_type", version))?;
+        types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("DefaultPrincipalData", "name", version))?;
+        types::Boolean.encode(buf, &self.token_authenticated).map_err(|e| e.with_field("DefaultPrincipalData", "token_authenticated", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DefaultPrincipalData", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DefaultPrincipalData", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DefaultPrincipalData", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(&self._type)?;
-        total_size += types::CompactString.compute_size(&self.name)?;
-        total_size += types::Boolean.compute_size(&self.token_authenticated)?;
+        total_size += types::CompactString.compute_size(&self._type).map_err(|e| e.with_field("DefaultPrincipalData", "_type", version))?;
+        total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("DefaultPrincipalData", "name", version))?;
+        total_size += types::Boolean.compute_size(&self.token_authenticated).map_err(|e| e.with_field("DefaultPrincipalData", "token_authenticated", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DefaultPrincipalData", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DefaultPrincipalData", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DefaultPrincipalData", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for DefaultPrincipalData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let _type = types::CompactString.decode(buf)?;
-        let name = types::CompactString.decode(buf)?;
-        let token_authenticated = types::Boolean.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let _type = types::CompactString.decode(buf).map_err(|e| e.with_field("DefaultPrincipalData", "_type", version, start - buf.remaining()))?;
+        let name = types::CompactString.decode(buf).map_err(|e| e.with_field("DefaultPrincipalData", "name", version, start - buf.remaining()))?;
+        let token_authenticated = types::Boolean.decode(buf).map_err(|e| e.with_field("DefaultPrincipalData", "token_authenticated", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DefaultPrincipalData", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DefaultPrincipalData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DefaultPrincipalData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DefaultPrincipalData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -110,7 +122,7 @@ impl Default for DefaultPrincipalData {
             _type: Default::default(),
             name: Default::default(),
             token_authenticated: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -119,3 +131,67 @@ impl Message for DefaultPrincipalData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl DefaultPrincipalData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl DefaultPrincipalData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("type".into(), JsonValue::to_json(&self._type));
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("tokenAuthenticated".into(), JsonValue::to_json(&self.token_authenticated));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DefaultPrincipalData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("type") {
+            this._type = JsonValue::from_json(value, "DefaultPrincipalData.type")?;
+        } else {
+            return Err(crate::json::missing("DefaultPrincipalData", "type", version));
+        }
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "DefaultPrincipalData.name")?;
+        } else {
+            return Err(crate::json::missing("DefaultPrincipalData", "name", version));
+        }
+        if let Some(value) = object.get("tokenAuthenticated") {
+            this.token_authenticated = JsonValue::from_json(value, "DefaultPrincipalData.tokenAuthenticated")?;
+        } else {
+            return Err(crate::json::missing("DefaultPrincipalData", "tokenAuthenticated", version));
+        }
+        Ok(this)
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DeleteAclsFilter {
     /// The resource type.
@@ -30,6 +36,8 @@ pub struct DeleteAclsFilter {
     /// The resource name.
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub resource_name_filter: Option<StrBytes>,
 
     /// The pattern type.
@@ -40,11 +48,15 @@ pub struct DeleteAclsFilter {
     /// The principal filter, or null to accept all principals.
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_filter: Option<StrBytes>,
 
     /// The host filter, or null to accept all hosts.
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host_filter: Option<StrBytes>,
 
     /// The ACL operation.
@@ -58,7 +70,7 @@ pub struct DeleteAclsFilter {
     pub permission_type: i8,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DeleteAclsFilter {
@@ -71,79 +83,83 @@ impl Builder for DeleteAclsFilter {
 
 impl Encodable for DeleteAclsFilter {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int8.encode(buf, &self.resource_type_filter)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int8.encode(buf, &self.resource_type_filter).map_err(|e| e.with_field("DeleteAclsFilter", "resource_type_filter", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.resource_name_filter)?;
+            types::CompactString.encode(buf, &self.resource_name_filter).map_err(|e| e.with_field("DeleteAclsFilter", "resource_name_filter", version))?;
         } else {
-            types::String.encode(buf, &self.resource_name_filter)?;
+            types::String.encode(buf, &self.resource_name_filter).map_err(|e| e.with_field("DeleteAclsFilter", "resource_name_filter", version))?;
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.pattern_type_filter)?;
+            types::Int8.encode(buf, &self.pattern_type_filter).map_err(|e| e.with_field("DeleteAclsFilter", "pattern_type_filter", version))?;
         } else {
             if self.pattern_type_filter != 3 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteAclsFilter", "pattern_type_filter", version));
             }
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_filter)?;
+            types::CompactString.encode(buf, &self.principal_filter).map_err(|e| e.with_field("DeleteAclsFilter", "principal_filter", version))?;
         } else {
-            types::String.encode(buf, &self.principal_filter)?;
+            types::String.encode(buf, &self.principal_filter).map_err(|e| e.with_field("DeleteAclsFilter", "principal_filter", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.host_filter)?;
+            types::CompactString.encode(buf, &self.host_filter).map_err(|e| e.with_field("DeleteAclsFilter", "host_filter", version))?;
         } else {
-            types::String.encode(buf, &self.host_filter)?;
+            types::String.encode(buf, &self.host_filter).map_err(|e| e.with_field("DeleteAclsFilter", "host_filter", version))?;
         }
-        types::Int8.encode(buf, &self.operation)?;
-        types::Int8.encode(buf, &self.permission_type)?;
+        types::Int8.encode(buf, &self.operation).map_err(|e| e.with_field("DeleteAclsFilter", "operation", version))?;
+        types::Int8.encode(buf, &self.permission_type).map_err(|e| e.with_field("DeleteAclsFilter", "permission_type", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsFilter", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsFilter", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsFilter", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int8.compute_size(&self.resource_type_filter)?;
+        total_size += types::Int8.compute_size(&self.resource_type_filter).map_err(|e| e.with_field("DeleteAclsFilter", "resource_type_filter", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.resource_name_filter)?;
+            total_size += types::CompactString.compute_size(&self.resource_name_filter).map_err(|e| e.with_field("DeleteAclsFilter", "resource_name_filter", version))?;
         } else {
-            total_size += types::String.compute_size(&self.resource_name_filter)?;
+            total_size += types::String.compute_size(&self.resource_name_filter).map_err(|e| e.with_field("DeleteAclsFilter", "resource_name_filter", version))?;
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.pattern_type_filter)?;
+            total_size += types::Int8.compute_size(&self.pattern_type_filter).map_err(|e| e.with_field("DeleteAclsFilter", "pattern_type_filter", version))?;
         } else {
             if self.pattern_type_filter != 3 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteAclsFilter", "pattern_type_filter", version));
             }
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_filter)?;
+            total_size += types::CompactString.compute_size(&self.principal_filter).map_err(|e| e.with_field("DeleteAclsFilter", "principal_filter", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_filter)?;
+            total_size += types::String.compute_size(&self.principal_filter).map_err(|e| e.with_field("DeleteAclsFilter", "principal_filter", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.host_filter)?;
+            total_size += types::CompactString.compute_size(&self.host_filter).map_err(|e| e.with_field("DeleteAclsFilter", "host_filter", version))?;
         } else {
-            total_size += types::String.compute_size(&self.host_filter)?;
+            total_size += types::String.compute_size(&self.host_filter).map_err(|e| e.with_field("DeleteAclsFilter", "host_filter", version))?;
         }
-        total_size += types::Int8.compute_size(&self.operation)?;
-        total_size += types::Int8.compute_size(&self.permission_type)?;
+        total_size += types::Int8.compute_size(&self.operation).map_err(|e| e.with_field("DeleteAclsFilter", "operation", version))?;
+        total_size += types::Int8.compute_size(&self.permission_type).map_err(|e| e.with_field("DeleteAclsFilter", "permission_type", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsFilter", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsFilter", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsFilter", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -151,37 +167,37 @@ impl Encodable for DeleteAclsFilter {
 
 impl Decodable for DeleteAclsFilter {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let resource_type_filter = types::Int8.decode(buf)?;
+        let start = buf.remaining();
+        let resource_type_filter = types::Int8.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "resource_type_filter", version, start - buf.remaining()))?;
         let resource_name_filter = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "resource_name_filter", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "resource_name_filter", version, start - buf.remaining()))?
         };
         let pattern_type_filter = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "pattern_type_filter", version, start - buf.remaining()))?
         } else {
             3
         };
         let principal_filter = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "principal_filter", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "principal_filter", version, start - buf.remaining()))?
         };
         let host_filter = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "host_filter", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "host_filter", version, start - buf.remaining()))?
         };
-        let operation = types::Int8.decode(buf)?;
-        let permission_type = types::Int8.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let operation = types::Int8.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "operation", version, start - buf.remaining()))?;
+        let permission_type = types::Int8.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "permission_type", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsFilter", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DeleteAclsFilter", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -208,7 +224,7 @@ impl Default for DeleteAclsFilter {
             host_filter: Some(Default::default()),
             operation: 0,
             permission_type: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -217,9 +233,109 @@ impl Message for DeleteAclsFilter {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DeleteAclsFilter {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.pattern_type_filter = 3;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.pattern_type_filter != 3 {
+                invalid.push(format!("{}pattern_type_filter", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DeleteAclsFilter {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("resourceTypeFilter".into(), JsonValue::to_json(&self.resource_type_filter));
+        node.insert("resourceNameFilter".into(), JsonValue::to_json(&self.resource_name_filter));
+        if version >= 1 {
+            node.insert("patternTypeFilter".into(), JsonValue::to_json(&self.pattern_type_filter));
+        }
+        node.insert("principalFilter".into(), JsonValue::to_json(&self.principal_filter));
+        node.insert("hostFilter".into(), JsonValue::to_json(&self.host_filter));
+        node.insert("operation".into(), JsonValue::to_json(&self.operation));
+        node.insert("permissionType".into(), JsonValue::to_json(&self.permission_type));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DeleteAclsFilter")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("resourceTypeFilter") {
+            this.resource_type_filter = JsonValue::from_json(value, "DeleteAclsFilter.resourceTypeFilter")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilter", "resourceTypeFilter", version));
+        }
+        if let Some(value) = object.get("resourceNameFilter") {
+            this.resource_name_filter = JsonValue::from_json(value, "DeleteAclsFilter.resourceNameFilter")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilter", "resourceNameFilter", version));
+        }
+        if let Some(value) = object.get("patternTypeFilter") {
+            this.pattern_type_filter = JsonValue::from_json(value, "DeleteAclsFilter.patternTypeFilter")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DeleteAclsFilter", "patternTypeFilter", version));
+        }
+        if let Some(value) = object.get("principalFilter") {
+            this.principal_filter = JsonValue::from_json(value, "DeleteAclsFilter.principalFilter")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilter", "principalFilter", version));
+        }
+        if let Some(value) = object.get("hostFilter") {
+            this.host_filter = JsonValue::from_json(value, "DeleteAclsFilter.hostFilter")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilter", "hostFilter", version));
+        }
+        if let Some(value) = object.get("operation") {
+            this.operation = JsonValue::from_json(value, "DeleteAclsFilter.operation")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilter", "operation", version));
+        }
+        if let Some(value) = object.get("permissionType") {
+            this.permission_type = JsonValue::from_json(value, "DeleteAclsFilter.permissionType")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilter", "permissionType", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DeleteAclsRequest {
     /// The filters to use when deleting ACLs.
@@ -228,7 +344,7 @@ pub struct DeleteAclsRequest {
     pub filters: Vec<DeleteAclsFilter>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DeleteAclsRequest {
@@ -241,39 +357,49 @@ impl Builder for DeleteAclsRequest {
 
 impl Encodable for DeleteAclsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteAclsRequest", version));
+        }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.filters)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.filters).map_err(|e| e.with_field("DeleteAclsRequest", "filters", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.filters)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.filters).map_err(|e| e.with_field("DeleteAclsRequest", "filters", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteAclsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.filters)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.filters).map_err(|e| e.with_field("DeleteAclsRequest", "filters", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.filters)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.filters).map_err(|e| e.with_field("DeleteAclsRequest", "filters", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -281,19 +407,22 @@ impl Encodable for DeleteAclsRequest {
 
 impl Decodable for DeleteAclsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DeleteAclsRequest", version));
+        }
+        let start = buf.remaining();
         let filters = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DeleteAclsRequest", "filters", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DeleteAclsRequest", "filters", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -308,7 +437,7 @@ impl Default for DeleteAclsRequest {
     fn default() -> Self {
         Self {
             filters: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -317,6 +446,100 @@ impl Message for DeleteAclsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DeleteAclsRequest {
+    /// Encode the message with the elements of `filters` produced by an iterator of known length, in place of
+    /// the `filters` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_filters<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, filters: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = DeleteAclsFilter>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteAclsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let filters = filters.into_iter();
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(filters)).map_err(|e| e.with_field("DeleteAclsRequest", "filters", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(filters)).map_err(|e| e.with_field("DeleteAclsRequest", "filters", version))?;
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DeleteAclsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.filters.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.filters.iter().enumerate() {
+            item.validate_into(version, &format!("{}filters[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DeleteAclsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("filters".into(), serde_json::Value::Array(self.filters.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DeleteAclsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("filters") {
+            this.filters = crate::json::array(value, "DeleteAclsRequest.filters")?.iter().map(|item| DeleteAclsFilter::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsRequest", "filters", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DeleteAclsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -327,3 +550,15 @@ impl HeaderVersion for DeleteAclsRequest {
     }
 }
 
+impl DeleteAclsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::DeleteAclsResponse {
+        let mut response = super::DeleteAclsResponse::default();
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DeleteAclsMatchingAcl {
     /// The deletion error code, or 0 if the deletion succeeded.
@@ -30,6 +36,8 @@ pub struct DeleteAclsMatchingAcl {
     /// The deletion error message, or null if the deletion succeeded.
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The ACL resource type.
@@ -40,6 +48,7 @@ pub struct DeleteAclsMatchingAcl {
     /// The ACL resource name.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub resource_name: StrBytes,
 
     /// The ACL resource pattern type.
@@ -50,11 +59,13 @@ pub struct DeleteAclsMatchingAcl {
     /// The ACL principal.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal: StrBytes,
 
     /// The ACL host.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The ACL operation.
@@ -68,7 +79,7 @@ pub struct DeleteAclsMatchingAcl {
     pub permission_type: i8,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DeleteAclsMatchingAcl {
@@ -81,91 +92,95 @@ impl Builder for DeleteAclsMatchingAcl {
 
 impl Encodable for DeleteAclsMatchingAcl {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_code", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.error_message)?;
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_message", version))?;
         } else {
-            types::String.encode(buf, &self.error_message)?;
+            types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_message", version))?;
         }
-        types::Int8.encode(buf, &self.resource_type)?;
+        types::Int8.encode(buf, &self.resource_type).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_type", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.resource_name)?;
+            types::CompactString.encode(buf, &self.resource_name).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_name", version))?;
         } else {
-            types::String.encode(buf, &self.resource_name)?;
+            types::String.encode(buf, &self.resource_name).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_name", version))?;
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.pattern_type)?;
+            types::Int8.encode(buf, &self.pattern_type).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "pattern_type", version))?;
         } else {
             if self.pattern_type != 3 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteAclsMatchingAcl", "pattern_type", version));
             }
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal)?;
+            types::CompactString.encode(buf, &self.principal).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "principal", version))?;
         } else {
-            types::String.encode(buf, &self.principal)?;
+            types::String.encode(buf, &self.principal).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "principal", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.host)?;
+            types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "host", version))?;
         } else {
-            types::String.encode(buf, &self.host)?;
+            types::String.encode(buf, &self.host).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "host", version))?;
         }
-        types::Int8.encode(buf, &self.operation)?;
-        types::Int8.encode(buf, &self.permission_type)?;
+        types::Int8.encode(buf, &self.operation).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "operation", version))?;
+        types::Int8.encode(buf, &self.permission_type).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "permission_type", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_code", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.error_message)?;
+            total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_message", version))?;
         } else {
-            total_size += types::String.compute_size(&self.error_message)?;
+            total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_message", version))?;
         }
-        total_size += types::Int8.compute_size(&self.resource_type)?;
+        total_size += types::Int8.compute_size(&self.resource_type).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_type", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.resource_name)?;
+            total_size += types::CompactString.compute_size(&self.resource_name).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.resource_name)?;
+            total_size += types::String.compute_size(&self.resource_name).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_name", version))?;
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.pattern_type)?;
+            total_size += types::Int8.compute_size(&self.pattern_type).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "pattern_type", version))?;
         } else {
             if self.pattern_type != 3 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteAclsMatchingAcl", "pattern_type", version));
             }
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal)?;
+            total_size += types::CompactString.compute_size(&self.principal).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "principal", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal)?;
+            total_size += types::String.compute_size(&self.principal).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "principal", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.host)?;
+            total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "host", version))?;
         } else {
-            total_size += types::String.compute_size(&self.host)?;
+            total_size += types::String.compute_size(&self.host).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "host", version))?;
         }
-        total_size += types::Int8.compute_size(&self.operation)?;
-        total_size += types::Int8.compute_size(&self.permission_type)?;
+        total_size += types::Int8.compute_size(&self.operation).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "operation", version))?;
+        total_size += types::Int8.compute_size(&self.permission_type).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "permission_type", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -173,43 +188,43 @@ impl Encodable for DeleteAclsMatchingAcl {
 
 impl Decodable for DeleteAclsMatchingAcl {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_code", version, start - buf.remaining()))?;
         let error_message = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_message", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "error_message", version, start - buf.remaining()))?
         };
-        let resource_type = types::Int8.decode(buf)?;
+        let resource_type = types::Int8.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_type", version, start - buf.remaining()))?;
         let resource_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "resource_name", version, start - buf.remaining()))?
         };
         let pattern_type = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "pattern_type", version, start - buf.remaining()))?
         } else {
             3
         };
         let principal = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "principal", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "principal", version, start - buf.remaining()))?
         };
         let host = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "host", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "host", version, start - buf.remaining()))?
         };
-        let operation = types::Int8.decode(buf)?;
-        let permission_type = types::Int8.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let operation = types::Int8.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "operation", version, start - buf.remaining()))?;
+        let permission_type = types::Int8.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "permission_type", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DeleteAclsMatchingAcl", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -240,7 +255,7 @@ impl Default for DeleteAclsMatchingAcl {
             host: Default::default(),
             operation: 0,
             permission_type: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -249,9 +264,121 @@ impl Message for DeleteAclsMatchingAcl {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DeleteAclsMatchingAcl {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.pattern_type = 3;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.pattern_type != 3 {
+                invalid.push(format!("{}pattern_type", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DeleteAclsMatchingAcl {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("resourceType".into(), JsonValue::to_json(&self.resource_type));
+        node.insert("resourceName".into(), JsonValue::to_json(&self.resource_name));
+        if version >= 1 {
+            node.insert("patternType".into(), JsonValue::to_json(&self.pattern_type));
+        }
+        node.insert("principal".into(), JsonValue::to_json(&self.principal));
+        node.insert("host".into(), JsonValue::to_json(&self.host));
+        node.insert("operation".into(), JsonValue::to_json(&self.operation));
+        node.insert("permissionType".into(), JsonValue::to_json(&self.permission_type));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DeleteAclsMatchingAcl")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "DeleteAclsMatchingAcl.errorCode")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "DeleteAclsMatchingAcl.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "errorMessage", version));
+        }
+        if let Some(value) = object.get("resourceType") {
+            this.resource_type = JsonValue::from_json(value, "DeleteAclsMatchingAcl.resourceType")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "resourceType", version));
+        }
+        if let Some(value) = object.get("resourceName") {
+            this.resource_name = JsonValue::from_json(value, "DeleteAclsMatchingAcl.resourceName")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "resourceName", version));
+        }
+        if let Some(value) = object.get("patternType") {
+            this.pattern_type = JsonValue::from_json(value, "DeleteAclsMatchingAcl.patternType")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "patternType", version));
+        }
+        if let Some(value) = object.get("principal") {
+            this.principal = JsonValue::from_json(value, "DeleteAclsMatchingAcl.principal")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "principal", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "DeleteAclsMatchingAcl.host")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "host", version));
+        }
+        if let Some(value) = object.get("operation") {
+            this.operation = JsonValue::from_json(value, "DeleteAclsMatchingAcl.operation")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "operation", version));
+        }
+        if let Some(value) = object.get("permissionType") {
+            this.permission_type = JsonValue::from_json(value, "DeleteAclsMatchingAcl.permissionType")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsMatchingAcl", "permissionType", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DeleteAclsFilterResult {
     /// The error code, or 0 if the filter succeeded.
@@ -262,6 +389,8 @@ pub struct DeleteAclsFilterResult {
     /// The error message, or null if the filter succeeded.
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The ACLs which matched this filter.
@@ -270,7 +399,7 @@ pub struct DeleteAclsFilterResult {
     pub matching_acls: Vec<DeleteAclsMatchingAcl>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DeleteAclsFilterResult {
@@ -283,51 +412,55 @@ impl Builder for DeleteAclsFilterResult {
 
 impl Encodable for DeleteAclsFilterResult {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_code", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.error_message)?;
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_message", version))?;
         } else {
-            types::String.encode(buf, &self.error_message)?;
+            types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_message", version))?;
         }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.matching_acls)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.matching_acls).map_err(|e| e.with_field("DeleteAclsFilterResult", "matching_acls", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.matching_acls)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.matching_acls).map_err(|e| e.with_field("DeleteAclsFilterResult", "matching_acls", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_code", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.error_message)?;
+            total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_message", version))?;
         } else {
-            total_size += types::String.compute_size(&self.error_message)?;
+            total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_message", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.matching_acls)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.matching_acls).map_err(|e| e.with_field("DeleteAclsFilterResult", "matching_acls", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.matching_acls)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.matching_acls).map_err(|e| e.with_field("DeleteAclsFilterResult", "matching_acls", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -335,25 +468,25 @@ impl Encodable for DeleteAclsFilterResult {
 
 impl Decodable for DeleteAclsFilterResult {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_code", version, start - buf.remaining()))?;
         let error_message = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_message", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DeleteAclsFilterResult", "error_message", version, start - buf.remaining()))?
         };
         let matching_acls = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DeleteAclsFilterResult", "matching_acls", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DeleteAclsFilterResult", "matching_acls", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DeleteAclsFilterResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -372,7 +505,7 @@ impl Default for DeleteAclsFilterResult {
             error_code: 0,
             error_message: Some(Default::default()),
             matching_acls: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -381,9 +514,81 @@ impl Message for DeleteAclsFilterResult {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DeleteAclsFilterResult {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.matching_acls.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.matching_acls.iter().enumerate() {
+            item.validate_into(version, &format!("{}matching_acls[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DeleteAclsFilterResult {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("matchingAcls".into(), serde_json::Value::Array(self.matching_acls.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DeleteAclsFilterResult")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "DeleteAclsFilterResult.errorCode")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilterResult", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "DeleteAclsFilterResult.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilterResult", "errorMessage", version));
+        }
+        if let Some(value) = object.get("matchingAcls") {
+            this.matching_acls = crate::json::array(value, "DeleteAclsFilterResult.matchingAcls")?.iter().map(|item| DeleteAclsMatchingAcl::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsFilterResult", "matchingAcls", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DeleteAclsResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -397,7 +602,7 @@ pub struct DeleteAclsResponse {
     pub filter_results: Vec<DeleteAclsFilterResult>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DeleteAclsResponse {
@@ -410,41 +615,51 @@ impl Builder for DeleteAclsResponse {
 
 impl Encodable for DeleteAclsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteAclsResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DeleteAclsResponse", "throttle_time_ms", version))?;
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.filter_results)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.filter_results).map_err(|e| e.with_field("DeleteAclsResponse", "filter_results", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.filter_results)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.filter_results).map_err(|e| e.with_field("DeleteAclsResponse", "filter_results", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteAclsResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("DeleteAclsResponse", "throttle_time_ms", version))?;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.filter_results)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.filter_results).map_err(|e| e.with_field("DeleteAclsResponse", "filter_results", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.filter_results)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.filter_results).map_err(|e| e.with_field("DeleteAclsResponse", "filter_results", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -452,20 +667,23 @@ impl Encodable for DeleteAclsResponse {
 
 impl Decodable for DeleteAclsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DeleteAclsResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("DeleteAclsResponse", "throttle_time_ms", version, start - buf.remaining()))?;
         let filter_results = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DeleteAclsResponse", "filter_results", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DeleteAclsResponse", "filter_results", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -482,7 +700,7 @@ impl Default for DeleteAclsResponse {
         Self {
             throttle_time_ms: 0,
             filter_results: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -491,6 +709,107 @@ impl Message for DeleteAclsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DeleteAclsResponse {
+    /// Encode the message with the elements of `filter_results` produced by an iterator of known length, in place of
+    /// the `filter_results` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_filter_results<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, filter_results: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = DeleteAclsFilterResult>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteAclsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let filter_results = filter_results.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DeleteAclsResponse", "throttle_time_ms", version))?;
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(filter_results)).map_err(|e| e.with_field("DeleteAclsResponse", "filter_results", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(filter_results)).map_err(|e| e.with_field("DeleteAclsResponse", "filter_results", version))?;
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteAclsResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteAclsResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DeleteAclsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.filter_results.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.filter_results.iter().enumerate() {
+            item.validate_into(version, &format!("{}filter_results[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DeleteAclsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("filterResults".into(), serde_json::Value::Array(self.filter_results.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DeleteAclsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "DeleteAclsResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("filterResults") {
+            this.filter_results = crate::json::array(value, "DeleteAclsResponse.filterResults")?.iter().map(|item| DeleteAclsFilterResult::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DeleteAclsResponse", "filterResults", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DeleteAclsResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-6
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DeleteTopicState {
     /// The topic name
     /// 
     /// Supported API versions: 6
+    /// May be `None` in versions: 6
     pub name: Option<super::TopicName>,
 
     /// The unique topic ID
@@ -33,7 +40,7 @@ pub struct DeleteTopicState {
     pub topic_id: Uuid,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DeleteTopicState {
@@ -46,57 +53,61 @@ impl Builder for DeleteTopicState {
 
 impl Encodable for DeleteTopicState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 6 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("DeleteTopicState", "name", version))?;
         } else {
             if !self.name.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteTopicState", "name", version));
             }
         }
         if version >= 6 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("DeleteTopicState", "topic_id", version))?;
         } else {
             if &self.topic_id != &Uuid::nil() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteTopicState", "topic_id", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteTopicState", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteTopicState", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteTopicState", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("DeleteTopicState", "name", version))?;
         } else {
             if !self.name.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteTopicState", "name", version));
             }
         }
         if version >= 6 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("DeleteTopicState", "topic_id", version))?;
         } else {
             if &self.topic_id != &Uuid::nil() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteTopicState", "topic_id", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteTopicState", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DeleteTopicState", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteTopicState", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -104,24 +115,24 @@ impl Encodable for DeleteTopicState {
 
 impl Decodable for DeleteTopicState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DeleteTopicState", "name", version, start - buf.remaining()))?
         } else {
             None
         };
         let topic_id = if version >= 6 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("DeleteTopicState", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DeleteTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -138,7 +149,7 @@ impl Default for DeleteTopicState {
         Self {
             name: None,
             topic_id: Uuid::nil(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -147,9 +158,89 @@ impl Message for DeleteTopicState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 6 };
 }
 
+impl DeleteTopicState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 6 {
+            self.name = None;
+        }
+        if version < 6 {
+            self.topic_id = Uuid::nil();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 6 {
+            if !self.name.is_none() {
+                invalid.push(format!("{}name", path));
+            }
+        }
+        if version < 6 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DeleteTopicState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 6 {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        if version >= 6 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DeleteTopicState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "DeleteTopicState.name")?;
+        } else if version >= 6 {
+            return Err(crate::json::missing("DeleteTopicState", "name", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "DeleteTopicState.topicId")?;
+        } else if version >= 6 {
+            return Err(crate::json::missing("DeleteTopicState", "topicId", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-6
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DeleteTopicsRequest {
     /// The name or topic ID of the topic
@@ -168,7 +259,7 @@ pub struct DeleteTopicsRequest {
     pub timeout_ms: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DeleteTopicsRequest {
@@ -181,59 +272,69 @@ impl Builder for DeleteTopicsRequest {
 
 impl Encodable for DeleteTopicsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteTopicsRequest", version));
+        }
         if version >= 6 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("DeleteTopicsRequest", "topics", version))?;
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteTopicsRequest", "topics", version));
             }
         }
         if version <= 5 {
             if version >= 4 {
-                types::CompactArray(types::CompactString).encode(buf, &self.topic_names)?;
+                types::CompactArray(types::CompactString).encode(buf, &self.topic_names).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version))?;
             } else {
-                types::Array(types::String).encode(buf, &self.topic_names)?;
+                types::Array(types::String).encode(buf, &self.topic_names).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version))?;
             }
         }
-        types::Int32.encode(buf, &self.timeout_ms)?;
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("DeleteTopicsRequest", "timeout_ms", version))?;
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteTopicsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteTopicsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 6 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("DeleteTopicsRequest", "topics", version))?;
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteTopicsRequest", "topics", version));
             }
         }
         if version <= 5 {
             if version >= 4 {
-                total_size += types::CompactArray(types::CompactString).compute_size(&self.topic_names)?;
+                total_size += types::CompactArray(types::CompactString).compute_size(&self.topic_names).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version))?;
             } else {
-                total_size += types::Array(types::String).compute_size(&self.topic_names)?;
+                total_size += types::Array(types::String).compute_size(&self.topic_names).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version))?;
             }
         }
-        total_size += types::Int32.compute_size(&self.timeout_ms)?;
+        total_size += types::Int32.compute_size(&self.timeout_ms).map_err(|e| e.with_field("DeleteTopicsRequest", "timeout_ms", version))?;
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteTopicsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -241,29 +342,32 @@ impl Encodable for DeleteTopicsRequest {
 
 impl Decodable for DeleteTopicsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DeleteTopicsRequest", version));
+        }
+        let start = buf.remaining();
         let topics = if version >= 6 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DeleteTopicsRequest", "topics", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topic_names = if version <= 5 {
             if version >= 4 {
-                types::CompactArray(types::CompactString).decode(buf)?
+                types::CompactArray(types::CompactString).decode(buf).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version, start - buf.remaining()))?
             } else {
-                types::Array(types::String).decode(buf)?
+                types::Array(types::String).decode(buf).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let timeout_ms = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("DeleteTopicsRequest", "timeout_ms", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -282,7 +386,7 @@ impl Default for DeleteTopicsRequest {
             topics: Default::default(),
             topic_names: Default::default(),
             timeout_ms: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -291,6 +395,188 @@ impl Message for DeleteTopicsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 6 };
 }
 
+impl DeleteTopicsRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = DeleteTopicState>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteTopicsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("DeleteTopicsRequest", "topics", version))?;
+        } else {
+            if topics.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteTopicsRequest", "topics", version));
+            }
+        }
+        if version <= 5 {
+            if version >= 4 {
+                types::CompactArray(types::CompactString).encode(buf, &self.topic_names).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version))?;
+            } else {
+                types::Array(types::String).encode(buf, &self.topic_names).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version))?;
+            }
+        }
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("DeleteTopicsRequest", "timeout_ms", version))?;
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteTopicsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `topic_names` produced by an iterator of known length, in place of
+    /// the `topic_names` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topic_names<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topic_names: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = super::TopicName>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DeleteTopicsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topic_names = topic_names.into_iter();
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("DeleteTopicsRequest", "topics", version))?;
+        } else {
+            if !self.topics.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DeleteTopicsRequest", "topics", version));
+            }
+        }
+        if version <= 5 {
+            if version >= 4 {
+                types::CompactArray(types::CompactString).encode(buf, types::Streamed(topic_names)).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version))?;
+            } else {
+                types::Array(types::String).encode(buf, types::Streamed(topic_names)).map_err(|e| e.with_field("DeleteTopicsRequest", "topic_names", version))?;
+            }
+        }
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("DeleteTopicsRequest", "timeout_ms", version))?;
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DeleteTopicsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DeleteTopicsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DeleteTopicsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version >= 6 {
+            for item in self.topics.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topics = Default::default();
+        }
+        if version > 5 {
+            self.topic_names = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version >= 6 {
+            for (i, item) in self.topics.iter().enumerate() {
+                item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topics.is_empty() {
+                invalid.push(format!("{}topics", path));
+            }
+        }
+        if version > 5 {
+            if !self.topic_names.is_empty() {
+                invalid.push(format!("{}topic_names", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DeleteTopicsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 6 {
+            node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version <= 5 {
+            node.insert("topicNames".into(), JsonValue::to_json(&self.topic_names));
+        }
+        node.insert("timeoutMs".into(), JsonValue::to_json(&self.timeout_ms));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DeleteTopicsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "DeleteTopicsRequest.topics")?.iter().map(|item| DeleteTopicState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 6 {
+            return Err(crate::json::missing("DeleteTopicsRequest", "topics", version));
+        }
+        if let Some(value) = object.get("topicNames") {
+            this.topic_names = JsonValue::from_json(value, "DeleteTopicsRequest.topicNames")?;
+        } else if version <= 5 {
+            return Err(crate::json::missing("DeleteTopicsRequest", "topicNames", version));
+        }
+        if let Some(value) = object.get("timeoutMs") {
+            this.timeout_ms = JsonValue::from_json(value, "DeleteTopicsRequest.timeoutMs")?;
+        } else {
+            return Err(crate::json::missing("DeleteTopicsRequest", "timeoutMs", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DeleteTopicsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
@@ -301,3 +587,31 @@ impl HeaderVersion for DeleteTopicsRequest {
     }
 }
 
+impl DeleteTopicsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::DeleteTopicsResponse {
+        let mut response = super::DeleteTopicsResponse::default();
+        let mut items0 = indexmap::IndexMap::new();
+        if version >= 6 {
+            for request0 in &self.topics {
+                let mut response0 = super::delete_topics_response::DeletableTopicResult::default();
+                response0.topic_id = request0.topic_id;
+                response0.error_code = error.code();
+                items0.insert(request0.name.clone().unwrap_or_default(), response0);
+            }
+        } else if version <= 5 {
+            for request0 in &self.topic_names {
+                let mut response0 = super::delete_topics_response::DeletableTopicResult::default();
+                response0.error_code = error.code();
+                items0.insert(request0.clone(), response0);
+            }
+        }
+        response.responses = items0;
+        response
+    }
+}
+
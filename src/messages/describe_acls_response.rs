@@ -5,31 +5,39 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AclDescription {
     /// The ACL principal.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal: StrBytes,
 
     /// The ACL host.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The ACL operation.
@@ -43,7 +51,7 @@ pub struct AclDescription {
     pub permission_type: i8,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AclDescription {
@@ -56,53 +64,57 @@ impl Builder for AclDescription {
 
 impl Encodable for AclDescription {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal)?;
+            types::CompactString.encode(buf, &self.principal).map_err(|e| e.with_field("AclDescription", "principal", version))?;
         } else {
-            types::String.encode(buf, &self.principal)?;
+            types::String.encode(buf, &self.principal).map_err(|e| e.with_field("AclDescription", "principal", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.host)?;
+            types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("AclDescription", "host", version))?;
         } else {
-            types::String.encode(buf, &self.host)?;
+            types::String.encode(buf, &self.host).map_err(|e| e.with_field("AclDescription", "host", version))?;
         }
-        types::Int8.encode(buf, &self.operation)?;
-        types::Int8.encode(buf, &self.permission_type)?;
+        types::Int8.encode(buf, &self.operation).map_err(|e| e.with_field("AclDescription", "operation", version))?;
+        types::Int8.encode(buf, &self.permission_type).map_err(|e| e.with_field("AclDescription", "permission_type", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AclDescription", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AclDescription", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AclDescription", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal)?;
+            total_size += types::CompactString.compute_size(&self.principal).map_err(|e| e.with_field("AclDescription", "principal", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal)?;
+            total_size += types::String.compute_size(&self.principal).map_err(|e| e.with_field("AclDescription", "principal", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.host)?;
+            total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("AclDescription", "host", version))?;
         } else {
-            total_size += types::String.compute_size(&self.host)?;
+            total_size += types::String.compute_size(&self.host).map_err(|e| e.with_field("AclDescription", "host", version))?;
         }
-        total_size += types::Int8.compute_size(&self.operation)?;
-        total_size += types::Int8.compute_size(&self.permission_type)?;
+        total_size += types::Int8.compute_size(&self.operation).map_err(|e| e.with_field("AclDescription", "operation", version))?;
+        total_size += types::Int8.compute_size(&self.permission_type).map_err(|e| e.with_field("AclDescription", "permission_type", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AclDescription", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AclDescription", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AclDescription", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -110,26 +122,26 @@ impl Encodable for AclDescription {
 
 impl Decodable for AclDescription {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let principal = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AclDescription", "principal", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AclDescription", "principal", version, start - buf.remaining()))?
         };
         let host = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("AclDescription", "host", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("AclDescription", "host", version, start - buf.remaining()))?
         };
-        let operation = types::Int8.decode(buf)?;
-        let permission_type = types::Int8.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let operation = types::Int8.decode(buf).map_err(|e| e.with_field("AclDescription", "operation", version, start - buf.remaining()))?;
+        let permission_type = types::Int8.decode(buf).map_err(|e| e.with_field("AclDescription", "permission_type", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AclDescription", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AclDescription", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AclDescription", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AclDescription", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -150,7 +162,7 @@ impl Default for AclDescription {
             host: Default::default(),
             operation: 0,
             permission_type: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -159,9 +171,81 @@ impl Message for AclDescription {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl AclDescription {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl AclDescription {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("principal".into(), JsonValue::to_json(&self.principal));
+        node.insert("host".into(), JsonValue::to_json(&self.host));
+        node.insert("operation".into(), JsonValue::to_json(&self.operation));
+        node.insert("permissionType".into(), JsonValue::to_json(&self.permission_type));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AclDescription")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("principal") {
+            this.principal = JsonValue::from_json(value, "AclDescription.principal")?;
+        } else {
+            return Err(crate::json::missing("AclDescription", "principal", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "AclDescription.host")?;
+        } else {
+            return Err(crate::json::missing("AclDescription", "host", version));
+        }
+        if let Some(value) = object.get("operation") {
+            this.operation = JsonValue::from_json(value, "AclDescription.operation")?;
+        } else {
+            return Err(crate::json::missing("AclDescription", "operation", version));
+        }
+        if let Some(value) = object.get("permissionType") {
+            this.permission_type = JsonValue::from_json(value, "AclDescription.permissionType")?;
+        } else {
+            return Err(crate::json::missing("AclDescription", "permissionType", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeAclsResource {
     /// The resource type.
@@ -172,6 +256,7 @@ pub struct DescribeAclsResource {
     /// The resource name.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub resource_name: StrBytes,
 
     /// The resource pattern type.
@@ -185,7 +270,7 @@ pub struct DescribeAclsResource {
     pub acls: Vec<AclDescription>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeAclsResource {
@@ -198,65 +283,69 @@ impl Builder for DescribeAclsResource {
 
 impl Encodable for DescribeAclsResource {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int8.encode(buf, &self.resource_type)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int8.encode(buf, &self.resource_type).map_err(|e| e.with_field("DescribeAclsResource", "resource_type", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.resource_name)?;
+            types::CompactString.encode(buf, &self.resource_name).map_err(|e| e.with_field("DescribeAclsResource", "resource_name", version))?;
         } else {
-            types::String.encode(buf, &self.resource_name)?;
+            types::String.encode(buf, &self.resource_name).map_err(|e| e.with_field("DescribeAclsResource", "resource_name", version))?;
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.pattern_type)?;
+            types::Int8.encode(buf, &self.pattern_type).map_err(|e| e.with_field("DescribeAclsResource", "pattern_type", version))?;
         } else {
             if self.pattern_type != 3 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeAclsResource", "pattern_type", version));
             }
         }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.acls)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.acls).map_err(|e| e.with_field("DescribeAclsResource", "acls", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.acls)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.acls).map_err(|e| e.with_field("DescribeAclsResource", "acls", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeAclsResource", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeAclsResource", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeAclsResource", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int8.compute_size(&self.resource_type)?;
+        total_size += types::Int8.compute_size(&self.resource_type).map_err(|e| e.with_field("DescribeAclsResource", "resource_type", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.resource_name)?;
+            total_size += types::CompactString.compute_size(&self.resource_name).map_err(|e| e.with_field("DescribeAclsResource", "resource_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.resource_name)?;
+            total_size += types::String.compute_size(&self.resource_name).map_err(|e| e.with_field("DescribeAclsResource", "resource_name", version))?;
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.pattern_type)?;
+            total_size += types::Int8.compute_size(&self.pattern_type).map_err(|e| e.with_field("DescribeAclsResource", "pattern_type", version))?;
         } else {
             if self.pattern_type != 3 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeAclsResource", "pattern_type", version));
             }
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.acls)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.acls).map_err(|e| e.with_field("DescribeAclsResource", "acls", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.acls)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.acls).map_err(|e| e.with_field("DescribeAclsResource", "acls", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeAclsResource", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeAclsResource", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeAclsResource", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -264,30 +353,30 @@ impl Encodable for DescribeAclsResource {
 
 impl Decodable for DescribeAclsResource {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let resource_type = types::Int8.decode(buf)?;
+        let start = buf.remaining();
+        let resource_type = types::Int8.decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "resource_type", version, start - buf.remaining()))?;
         let resource_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "resource_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "resource_name", version, start - buf.remaining()))?
         };
         let pattern_type = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "pattern_type", version, start - buf.remaining()))?
         } else {
             3
         };
         let acls = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "acls", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "acls", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeAclsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeAclsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -308,7 +397,7 @@ impl Default for DescribeAclsResource {
             resource_name: Default::default(),
             pattern_type: 3,
             acls: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -317,9 +406,97 @@ impl Message for DescribeAclsResource {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DescribeAclsResource {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.pattern_type = 3;
+        }
+        for item in self.acls.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.pattern_type != 3 {
+                invalid.push(format!("{}pattern_type", path));
+            }
+        }
+        for (i, item) in self.acls.iter().enumerate() {
+            item.validate_into(version, &format!("{}acls[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeAclsResource {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("resourceType".into(), JsonValue::to_json(&self.resource_type));
+        node.insert("resourceName".into(), JsonValue::to_json(&self.resource_name));
+        if version >= 1 {
+            node.insert("patternType".into(), JsonValue::to_json(&self.pattern_type));
+        }
+        node.insert("acls".into(), serde_json::Value::Array(self.acls.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeAclsResource")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("resourceType") {
+            this.resource_type = JsonValue::from_json(value, "DescribeAclsResource.resourceType")?;
+        } else {
+            return Err(crate::json::missing("DescribeAclsResource", "resourceType", version));
+        }
+        if let Some(value) = object.get("resourceName") {
+            this.resource_name = JsonValue::from_json(value, "DescribeAclsResource.resourceName")?;
+        } else {
+            return Err(crate::json::missing("DescribeAclsResource", "resourceName", version));
+        }
+        if let Some(value) = object.get("patternType") {
+            this.pattern_type = JsonValue::from_json(value, "DescribeAclsResource.patternType")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DescribeAclsResource", "patternType", version));
+        }
+        if let Some(value) = object.get("acls") {
+            this.acls = crate::json::array(value, "DescribeAclsResource.acls")?.iter().map(|item| AclDescription::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeAclsResource", "acls", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeAclsResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -335,6 +512,8 @@ pub struct DescribeAclsResponse {
     /// The error message, or null if there was no error.
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// Each Resource that is referenced in an ACL.
@@ -343,7 +522,7 @@ pub struct DescribeAclsResponse {
     pub resources: Vec<DescribeAclsResource>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeAclsResponse {
@@ -356,53 +535,63 @@ impl Builder for DescribeAclsResponse {
 
 impl Encodable for DescribeAclsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeAclsResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeAclsResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DescribeAclsResponse", "error_code", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.error_message)?;
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("DescribeAclsResponse", "error_message", version))?;
         } else {
-            types::String.encode(buf, &self.error_message)?;
+            types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("DescribeAclsResponse", "error_message", version))?;
         }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.resources)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.resources).map_err(|e| e.with_field("DescribeAclsResponse", "resources", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.resources)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.resources).map_err(|e| e.with_field("DescribeAclsResponse", "resources", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeAclsResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeAclsResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("DescribeAclsResponse", "throttle_time_ms", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("DescribeAclsResponse", "error_code", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.error_message)?;
+            total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("DescribeAclsResponse", "error_message", version))?;
         } else {
-            total_size += types::String.compute_size(&self.error_message)?;
+            total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("DescribeAclsResponse", "error_message", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.resources)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.resources).map_err(|e| e.with_field("DescribeAclsResponse", "resources", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.resources)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.resources).map_err(|e| e.with_field("DescribeAclsResponse", "resources", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeAclsResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -410,26 +599,29 @@ impl Encodable for DescribeAclsResponse {
 
 impl Decodable for DescribeAclsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DescribeAclsResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "throttle_time_ms", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "error_code", version, start - buf.remaining()))?;
         let error_message = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "error_message", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "error_message", version, start - buf.remaining()))?
         };
         let resources = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "resources", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "resources", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -450,7 +642,7 @@ impl Default for DescribeAclsResponse {
             error_code: 0,
             error_message: Some(Default::default()),
             resources: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -459,6 +651,125 @@ impl Message for DescribeAclsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DescribeAclsResponse {
+    /// Encode the message with the elements of `resources` produced by an iterator of known length, in place of
+    /// the `resources` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_resources<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, resources: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = DescribeAclsResource>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeAclsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let resources = resources.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeAclsResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DescribeAclsResponse", "error_code", version))?;
+        if version >= 2 {
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("DescribeAclsResponse", "error_message", version))?;
+        } else {
+            types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("DescribeAclsResponse", "error_message", version))?;
+        }
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(resources)).map_err(|e| e.with_field("DescribeAclsResponse", "resources", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(resources)).map_err(|e| e.with_field("DescribeAclsResponse", "resources", version))?;
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeAclsResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeAclsResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DescribeAclsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.resources.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.resources.iter().enumerate() {
+            item.validate_into(version, &format!("{}resources[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeAclsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("resources".into(), serde_json::Value::Array(self.resources.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeAclsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "DescribeAclsResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("DescribeAclsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "DescribeAclsResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("DescribeAclsResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "DescribeAclsResponse.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("DescribeAclsResponse", "errorMessage", version));
+        }
+        if let Some(value) = object.get("resources") {
+            this.resources = crate::json::array(value, "DescribeAclsResponse.resources")?.iter().map(|item| DescribeAclsResource::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeAclsResponse", "resources", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DescribeAclsResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ComponentData {
     /// The entity type that the filter component applies to.
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub entity_type: StrBytes,
 
     /// How to match the entity {0 = exact name, 1 = default name, 2 = any specified name}.
@@ -35,10 +42,12 @@ pub struct ComponentData {
     /// The string to match against, or null if unused for the match type.
     /// 
     /// Supported API versions: 0-1
+    /// May be `None` in versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub _match: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ComponentData {
@@ -51,51 +60,55 @@ impl Builder for ComponentData {
 
 impl Encodable for ComponentData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 {
-            types::CompactString.encode(buf, &self.entity_type)?;
+            types::CompactString.encode(buf, &self.entity_type).map_err(|e| e.with_field("ComponentData", "entity_type", version))?;
         } else {
-            types::String.encode(buf, &self.entity_type)?;
+            types::String.encode(buf, &self.entity_type).map_err(|e| e.with_field("ComponentData", "entity_type", version))?;
         }
-        types::Int8.encode(buf, &self.match_type)?;
+        types::Int8.encode(buf, &self.match_type).map_err(|e| e.with_field("ComponentData", "match_type", version))?;
         if version >= 1 {
-            types::CompactString.encode(buf, &self._match)?;
+            types::CompactString.encode(buf, &self._match).map_err(|e| e.with_field("ComponentData", "_match", version))?;
         } else {
-            types::String.encode(buf, &self._match)?;
+            types::String.encode(buf, &self._match).map_err(|e| e.with_field("ComponentData", "_match", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ComponentData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ComponentData", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ComponentData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::CompactString.compute_size(&self.entity_type)?;
+            total_size += types::CompactString.compute_size(&self.entity_type).map_err(|e| e.with_field("ComponentData", "entity_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.entity_type)?;
+            total_size += types::String.compute_size(&self.entity_type).map_err(|e| e.with_field("ComponentData", "entity_type", version))?;
         }
-        total_size += types::Int8.compute_size(&self.match_type)?;
+        total_size += types::Int8.compute_size(&self.match_type).map_err(|e| e.with_field("ComponentData", "match_type", version))?;
         if version >= 1 {
-            total_size += types::CompactString.compute_size(&self._match)?;
+            total_size += types::CompactString.compute_size(&self._match).map_err(|e| e.with_field("ComponentData", "_match", version))?;
         } else {
-            total_size += types::String.compute_size(&self._match)?;
+            total_size += types::String.compute_size(&self._match).map_err(|e| e.with_field("ComponentData", "_match", version))?;
         }
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ComponentData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ComponentData", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ComponentData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -103,25 +116,25 @@ impl Encodable for ComponentData {
 
 impl Decodable for ComponentData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let entity_type = if version >= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("ComponentData", "entity_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("ComponentData", "entity_type", version, start - buf.remaining()))?
         };
-        let match_type = types::Int8.decode(buf)?;
+        let match_type = types::Int8.decode(buf).map_err(|e| e.with_field("ComponentData", "match_type", version, start - buf.remaining()))?;
         let _match = if version >= 1 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("ComponentData", "_match", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("ComponentData", "_match", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ComponentData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ComponentData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ComponentData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ComponentData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -140,7 +153,7 @@ impl Default for ComponentData {
             entity_type: Default::default(),
             match_type: 0,
             _match: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -149,9 +162,75 @@ impl Message for ComponentData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl ComponentData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl ComponentData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("entityType".into(), JsonValue::to_json(&self.entity_type));
+        node.insert("matchType".into(), JsonValue::to_json(&self.match_type));
+        node.insert("match".into(), JsonValue::to_json(&self._match));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ComponentData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("entityType") {
+            this.entity_type = JsonValue::from_json(value, "ComponentData.entityType")?;
+        } else {
+            return Err(crate::json::missing("ComponentData", "entityType", version));
+        }
+        if let Some(value) = object.get("matchType") {
+            this.match_type = JsonValue::from_json(value, "ComponentData.matchType")?;
+        } else {
+            return Err(crate::json::missing("ComponentData", "matchType", version));
+        }
+        if let Some(value) = object.get("match") {
+            this._match = JsonValue::from_json(value, "ComponentData.match")?;
+        } else {
+            return Err(crate::json::missing("ComponentData", "match", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeClientQuotasRequest {
     /// Filter components to apply to quota entities.
@@ -165,7 +244,7 @@ pub struct DescribeClientQuotasRequest {
     pub strict: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeClientQuotasRequest {
@@ -178,41 +257,51 @@ impl Builder for DescribeClientQuotasRequest {
 
 impl Encodable for DescribeClientQuotasRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeClientQuotasRequest", version));
+        }
         if version >= 1 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.components)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.components).map_err(|e| e.with_field("DescribeClientQuotasRequest", "components", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.components)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.components).map_err(|e| e.with_field("DescribeClientQuotasRequest", "components", version))?;
         }
-        types::Boolean.encode(buf, &self.strict)?;
+        types::Boolean.encode(buf, &self.strict).map_err(|e| e.with_field("DescribeClientQuotasRequest", "strict", version))?;
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeClientQuotasRequest", version));
+        }
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.components)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.components).map_err(|e| e.with_field("DescribeClientQuotasRequest", "components", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.components)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.components).map_err(|e| e.with_field("DescribeClientQuotasRequest", "components", version))?;
         }
-        total_size += types::Boolean.compute_size(&self.strict)?;
+        total_size += types::Boolean.compute_size(&self.strict).map_err(|e| e.with_field("DescribeClientQuotasRequest", "strict", version))?;
         if version >= 1 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -220,20 +309,23 @@ impl Encodable for DescribeClientQuotasRequest {
 
 impl Decodable for DescribeClientQuotasRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DescribeClientQuotasRequest", version));
+        }
+        let start = buf.remaining();
         let components = if version >= 1 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeClientQuotasRequest", "components", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeClientQuotasRequest", "components", version, start - buf.remaining()))?
         };
-        let strict = types::Boolean.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let strict = types::Boolean.decode(buf).map_err(|e| e.with_field("DescribeClientQuotasRequest", "strict", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 1 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -250,7 +342,7 @@ impl Default for DescribeClientQuotasRequest {
         Self {
             components: Default::default(),
             strict: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -259,6 +351,107 @@ impl Message for DescribeClientQuotasRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl DescribeClientQuotasRequest {
+    /// Encode the message with the elements of `components` produced by an iterator of known length, in place of
+    /// the `components` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_components<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, components: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = ComponentData>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeClientQuotasRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let components = components.into_iter();
+        if version >= 1 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(components)).map_err(|e| e.with_field("DescribeClientQuotasRequest", "components", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(components)).map_err(|e| e.with_field("DescribeClientQuotasRequest", "components", version))?;
+        }
+        types::Boolean.encode(buf, &self.strict).map_err(|e| e.with_field("DescribeClientQuotasRequest", "strict", version))?;
+        if version >= 1 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeClientQuotasRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DescribeClientQuotasRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.components.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.components.iter().enumerate() {
+            item.validate_into(version, &format!("{}components[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeClientQuotasRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("components".into(), serde_json::Value::Array(self.components.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("strict".into(), JsonValue::to_json(&self.strict));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeClientQuotasRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("components") {
+            this.components = crate::json::array(value, "DescribeClientQuotasRequest.components")?.iter().map(|item| ComponentData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeClientQuotasRequest", "components", version));
+        }
+        if let Some(value) = object.get("strict") {
+            this.strict = JsonValue::from_json(value, "DescribeClientQuotasRequest.strict")?;
+        } else {
+            return Err(crate::json::missing("DescribeClientQuotasRequest", "strict", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DescribeClientQuotasRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 1 {
@@ -269,3 +462,16 @@ impl HeaderVersion for DescribeClientQuotasRequest {
     }
 }
 
+impl DescribeClientQuotasRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::DescribeClientQuotasResponse {
+        let mut response = super::DescribeClientQuotasResponse::default();
+        response.error_code = error.code();
+        response
+    }
+}
+
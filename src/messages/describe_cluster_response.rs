@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeClusterBroker {
     /// The broker hostname.
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The broker port.
@@ -35,10 +42,12 @@ pub struct DescribeClusterBroker {
     /// The rack of the broker, or null if it has not been assigned to a rack.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub rack: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeClusterBroker {
@@ -52,34 +61,38 @@ impl Builder for DescribeClusterBroker {
 impl MapEncodable for DescribeClusterBroker {
     type Key = super::BrokerId;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, key)?;
-        types::CompactString.encode(buf, &self.host)?;
-        types::Int32.encode(buf, &self.port)?;
-        types::CompactString.encode(buf, &self.rack)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, key).map_err(|e| e.with_field("DescribeClusterBroker", "broker_id", version))?;
+        types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("DescribeClusterBroker", "host", version))?;
+        types::Int32.encode(buf, &self.port).map_err(|e| e.with_field("DescribeClusterBroker", "port", version))?;
+        types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("DescribeClusterBroker", "rack", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeClusterBroker", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeClusterBroker", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeClusterBroker", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(key)?;
-        total_size += types::CompactString.compute_size(&self.host)?;
-        total_size += types::Int32.compute_size(&self.port)?;
-        total_size += types::CompactString.compute_size(&self.rack)?;
+        total_size += types::Int32.compute_size(key).map_err(|e| e.with_field("DescribeClusterBroker", "broker_id", version))?;
+        total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("DescribeClusterBroker", "host", version))?;
+        total_size += types::Int32.compute_size(&self.port).map_err(|e| e.with_field("DescribeClusterBroker", "port", version))?;
+        total_size += types::CompactString.compute_size(&self.rack).map_err(|e| e.with_field("DescribeClusterBroker", "rack", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeClusterBroker", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeClusterBroker", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeClusterBroker", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
@@ -87,17 +100,17 @@ impl MapEncodable for DescribeClusterBroker {
 impl MapDecodable for DescribeClusterBroker {
     type Key = super::BrokerId;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::Int32.decode(buf)?;
-        let host = types::CompactString.decode(buf)?;
-        let port = types::Int32.decode(buf)?;
-        let rack = types::CompactString.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let start = buf.remaining();
+        let key_field = types::Int32.decode(buf).map_err(|e| e.with_field("DescribeClusterBroker", "broker_id", version, start - buf.remaining()))?;
+        let host = types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeClusterBroker", "host", version, start - buf.remaining()))?;
+        let port = types::Int32.decode(buf).map_err(|e| e.with_field("DescribeClusterBroker", "port", version, start - buf.remaining()))?;
+        let rack = types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeClusterBroker", "rack", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClusterBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClusterBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClusterBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeClusterBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok((key_field, Self {
@@ -115,7 +128,7 @@ impl Default for DescribeClusterBroker {
             host: Default::default(),
             port: 0,
             rack: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -124,9 +137,82 @@ impl Message for DescribeClusterBroker {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl DescribeClusterBroker {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeClusterBroker {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::BrokerId, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::BrokerId, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("brokerId".into(), JsonValue::to_json(key));
+        node.insert("host".into(), JsonValue::to_json(&self.host));
+        node.insert("port".into(), JsonValue::to_json(&self.port));
+        node.insert("rack".into(), JsonValue::to_json(&self.rack));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::BrokerId, Self), JsonError> {
+        let object = crate::json::object(node, "DescribeClusterBroker")?;
+        let mut this = Self::default();
+        let mut key = super::BrokerId::default();
+        if let Some(value) = object.get("brokerId") {
+            key = JsonValue::from_json(value, "DescribeClusterBroker.brokerId")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterBroker", "brokerId", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "DescribeClusterBroker.host")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterBroker", "host", version));
+        }
+        if let Some(value) = object.get("port") {
+            this.port = JsonValue::from_json(value, "DescribeClusterBroker.port")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterBroker", "port", version));
+        }
+        if let Some(value) = object.get("rack") {
+            this.rack = JsonValue::from_json(value, "DescribeClusterBroker.rack")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterBroker", "rack", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeClusterResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -142,11 +228,14 @@ pub struct DescribeClusterResponse {
     /// The top-level error message, or null if there was no error.
     /// 
     /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The cluster ID that responding broker belongs to.
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub cluster_id: StrBytes,
 
     /// The ID of the controller broker.
@@ -165,7 +254,7 @@ pub struct DescribeClusterResponse {
     pub cluster_authorized_operations: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeClusterResponse {
@@ -178,60 +267,73 @@ impl Builder for DescribeClusterResponse {
 
 impl Encodable for DescribeClusterResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::CompactString.encode(buf, &self.error_message)?;
-        types::CompactString.encode(buf, &self.cluster_id)?;
-        types::Int32.encode(buf, &self.controller_id)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.brokers)?;
-        types::Int32.encode(buf, &self.cluster_authorized_operations)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeClusterResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeClusterResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DescribeClusterResponse", "error_code", version))?;
+        types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("DescribeClusterResponse", "error_message", version))?;
+        types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("DescribeClusterResponse", "cluster_id", version))?;
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("DescribeClusterResponse", "controller_id", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.brokers).map_err(|e| e.with_field("DescribeClusterResponse", "brokers", version))?;
+        types::Int32.encode(buf, &self.cluster_authorized_operations).map_err(|e| e.with_field("DescribeClusterResponse", "cluster_authorized_operations", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeClusterResponse", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeClusterResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::CompactString.compute_size(&self.error_message)?;
-        total_size += types::CompactString.compute_size(&self.cluster_id)?;
-        total_size += types::Int32.compute_size(&self.controller_id)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.brokers)?;
-        total_size += types::Int32.compute_size(&self.cluster_authorized_operations)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("DescribeClusterResponse", "throttle_time_ms", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("DescribeClusterResponse", "error_code", version))?;
+        total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("DescribeClusterResponse", "error_message", version))?;
+        total_size += types::CompactString.compute_size(&self.cluster_id).map_err(|e| e.with_field("DescribeClusterResponse", "cluster_id", version))?;
+        total_size += types::Int32.compute_size(&self.controller_id).map_err(|e| e.with_field("DescribeClusterResponse", "controller_id", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.brokers).map_err(|e| e.with_field("DescribeClusterResponse", "brokers", version))?;
+        total_size += types::Int32.compute_size(&self.cluster_authorized_operations).map_err(|e| e.with_field("DescribeClusterResponse", "cluster_authorized_operations", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeClusterResponse", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for DescribeClusterResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let error_message = types::CompactString.decode(buf)?;
-        let cluster_id = types::CompactString.decode(buf)?;
-        let controller_id = types::Int32.decode(buf)?;
-        let brokers = types::CompactArray(types::Struct { version }).decode(buf)?;
-        let cluster_authorized_operations = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DescribeClusterResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "throttle_time_ms", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "error_code", version, start - buf.remaining()))?;
+        let error_message = types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "error_message", version, start - buf.remaining()))?;
+        let cluster_id = types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "cluster_id", version, start - buf.remaining()))?;
+        let controller_id = types::Int32.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "controller_id", version, start - buf.remaining()))?;
+        let brokers = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "brokers", version, start - buf.remaining()))?;
+        let cluster_authorized_operations = types::Int32.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "cluster_authorized_operations", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -257,7 +359,7 @@ impl Default for DescribeClusterResponse {
             controller_id: (-1).into(),
             brokers: Default::default(),
             cluster_authorized_operations: -2147483648,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -266,6 +368,136 @@ impl Message for DescribeClusterResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl DescribeClusterResponse {
+    /// Encode the message with the elements of `brokers` produced by an iterator of known length, in place of
+    /// the `brokers` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_brokers<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, brokers: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::BrokerId, DescribeClusterBroker)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeClusterResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let brokers = brokers.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeClusterResponse", "throttle_time_ms", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DescribeClusterResponse", "error_code", version))?;
+        types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("DescribeClusterResponse", "error_message", version))?;
+        types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("DescribeClusterResponse", "cluster_id", version))?;
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("DescribeClusterResponse", "controller_id", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(brokers)).map_err(|e| e.with_field("DescribeClusterResponse", "brokers", version))?;
+        types::Int32.encode(buf, &self.cluster_authorized_operations).map_err(|e| e.with_field("DescribeClusterResponse", "cluster_authorized_operations", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeClusterResponse", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeClusterResponse", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl DescribeClusterResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.brokers.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.brokers.values().enumerate() {
+            item.validate_into(version, &format!("{}brokers[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeClusterResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("clusterId".into(), JsonValue::to_json(&self.cluster_id));
+        node.insert("controllerId".into(), JsonValue::to_json(&self.controller_id));
+        node.insert("brokers".into(), serde_json::Value::Array(self.brokers.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("clusterAuthorizedOperations".into(), JsonValue::to_json(&self.cluster_authorized_operations));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeClusterResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "DescribeClusterResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "DescribeClusterResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "DescribeClusterResponse.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterResponse", "errorMessage", version));
+        }
+        if let Some(value) = object.get("clusterId") {
+            this.cluster_id = JsonValue::from_json(value, "DescribeClusterResponse.clusterId")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterResponse", "clusterId", version));
+        }
+        if let Some(value) = object.get("controllerId") {
+            this.controller_id = JsonValue::from_json(value, "DescribeClusterResponse.controllerId")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterResponse", "controllerId", version));
+        }
+        if let Some(value) = object.get("brokers") {
+            this.brokers = crate::json::array(value, "DescribeClusterResponse.brokers")?.iter().map(|item| DescribeClusterBroker::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterResponse", "brokers", version));
+        }
+        if let Some(value) = object.get("clusterAuthorizedOperations") {
+            this.cluster_authorized_operations = JsonValue::from_json(value, "DescribeClusterResponse.clusterAuthorizedOperations")?;
+        } else {
+            return Err(crate::json::missing("DescribeClusterResponse", "clusterAuthorizedOperations", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DescribeClusterResponse {
     fn header_version(version: i16) -> i16 {
         1
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeConfigsResource {
     /// The resource type.
@@ -30,15 +36,18 @@ pub struct DescribeConfigsResource {
     /// The resource name.
     /// 
     /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub resource_name: StrBytes,
 
     /// The configuration keys to list, or null to list all configuration keys.
     /// 
     /// Supported API versions: 0-4
+    /// May be `None` in versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub configuration_keys: Option<Vec<StrBytes>>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeConfigsResource {
@@ -51,51 +60,55 @@ impl Builder for DescribeConfigsResource {
 
 impl Encodable for DescribeConfigsResource {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int8.encode(buf, &self.resource_type)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int8.encode(buf, &self.resource_type).map_err(|e| e.with_field("DescribeConfigsResource", "resource_type", version))?;
         if version >= 4 {
-            types::CompactString.encode(buf, &self.resource_name)?;
+            types::CompactString.encode(buf, &self.resource_name).map_err(|e| e.with_field("DescribeConfigsResource", "resource_name", version))?;
         } else {
-            types::String.encode(buf, &self.resource_name)?;
+            types::String.encode(buf, &self.resource_name).map_err(|e| e.with_field("DescribeConfigsResource", "resource_name", version))?;
         }
         if version >= 4 {
-            types::CompactArray(types::CompactString).encode(buf, &self.configuration_keys)?;
+            types::CompactArray(types::CompactString).encode(buf, &self.configuration_keys).map_err(|e| e.with_field("DescribeConfigsResource", "configuration_keys", version))?;
         } else {
-            types::Array(types::String).encode(buf, &self.configuration_keys)?;
+            types::Array(types::String).encode(buf, &self.configuration_keys).map_err(|e| e.with_field("DescribeConfigsResource", "configuration_keys", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResource", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResource", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResource", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int8.compute_size(&self.resource_type)?;
+        total_size += types::Int8.compute_size(&self.resource_type).map_err(|e| e.with_field("DescribeConfigsResource", "resource_type", version))?;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.resource_name)?;
+            total_size += types::CompactString.compute_size(&self.resource_name).map_err(|e| e.with_field("DescribeConfigsResource", "resource_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.resource_name)?;
+            total_size += types::String.compute_size(&self.resource_name).map_err(|e| e.with_field("DescribeConfigsResource", "resource_name", version))?;
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::CompactString).compute_size(&self.configuration_keys)?;
+            total_size += types::CompactArray(types::CompactString).compute_size(&self.configuration_keys).map_err(|e| e.with_field("DescribeConfigsResource", "configuration_keys", version))?;
         } else {
-            total_size += types::Array(types::String).compute_size(&self.configuration_keys)?;
+            total_size += types::Array(types::String).compute_size(&self.configuration_keys).map_err(|e| e.with_field("DescribeConfigsResource", "configuration_keys", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResource", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResource", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResource", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -103,25 +116,25 @@ impl Encodable for DescribeConfigsResource {
 
 impl Decodable for DescribeConfigsResource {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let resource_type = types::Int8.decode(buf)?;
+        let start = buf.remaining();
+        let resource_type = types::Int8.decode(buf).map_err(|e| e.with_field("DescribeConfigsResource", "resource_type", version, start - buf.remaining()))?;
         let resource_name = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeConfigsResource", "resource_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribeConfigsResource", "resource_name", version, start - buf.remaining()))?
         };
         let configuration_keys = if version >= 4 {
-            types::CompactArray(types::CompactString).decode(buf)?
+            types::CompactArray(types::CompactString).decode(buf).map_err(|e| e.with_field("DescribeConfigsResource", "configuration_keys", version, start - buf.remaining()))?
         } else {
-            types::Array(types::String).decode(buf)?
+            types::Array(types::String).decode(buf).map_err(|e| e.with_field("DescribeConfigsResource", "configuration_keys", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeConfigsResource", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -140,7 +153,7 @@ impl Default for DescribeConfigsResource {
             resource_type: 0,
             resource_name: Default::default(),
             configuration_keys: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -149,9 +162,75 @@ impl Message for DescribeConfigsResource {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl DescribeConfigsResource {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeConfigsResource {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("resourceType".into(), JsonValue::to_json(&self.resource_type));
+        node.insert("resourceName".into(), JsonValue::to_json(&self.resource_name));
+        node.insert("configurationKeys".into(), JsonValue::to_json(&self.configuration_keys));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeConfigsResource")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("resourceType") {
+            this.resource_type = JsonValue::from_json(value, "DescribeConfigsResource.resourceType")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResource", "resourceType", version));
+        }
+        if let Some(value) = object.get("resourceName") {
+            this.resource_name = JsonValue::from_json(value, "DescribeConfigsResource.resourceName")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResource", "resourceName", version));
+        }
+        if let Some(value) = object.get("configurationKeys") {
+            this.configuration_keys = JsonValue::from_json(value, "DescribeConfigsResource.configurationKeys")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResource", "configurationKeys", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeConfigsRequest {
     /// The resources whose configurations we want to describe.
@@ -170,7 +249,7 @@ pub struct DescribeConfigsRequest {
     pub include_documentation: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeConfigsRequest {
@@ -183,67 +262,77 @@ impl Builder for DescribeConfigsRequest {
 
 impl Encodable for DescribeConfigsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeConfigsRequest", version));
+        }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.resources)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.resources).map_err(|e| e.with_field("DescribeConfigsRequest", "resources", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.resources)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.resources).map_err(|e| e.with_field("DescribeConfigsRequest", "resources", version))?;
         }
         if version >= 1 {
-            types::Boolean.encode(buf, &self.include_synonyms)?;
+            types::Boolean.encode(buf, &self.include_synonyms).map_err(|e| e.with_field("DescribeConfigsRequest", "include_synonyms", version))?;
         } else {
             if self.include_synonyms {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsRequest", "include_synonyms", version));
             }
         }
         if version >= 3 {
-            types::Boolean.encode(buf, &self.include_documentation)?;
+            types::Boolean.encode(buf, &self.include_documentation).map_err(|e| e.with_field("DescribeConfigsRequest", "include_documentation", version))?;
         } else {
             if self.include_documentation {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsRequest", "include_documentation", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeConfigsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.resources)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.resources).map_err(|e| e.with_field("DescribeConfigsRequest", "resources", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.resources)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.resources).map_err(|e| e.with_field("DescribeConfigsRequest", "resources", version))?;
         }
         if version >= 1 {
-            total_size += types::Boolean.compute_size(&self.include_synonyms)?;
+            total_size += types::Boolean.compute_size(&self.include_synonyms).map_err(|e| e.with_field("DescribeConfigsRequest", "include_synonyms", version))?;
         } else {
             if self.include_synonyms {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsRequest", "include_synonyms", version));
             }
         }
         if version >= 3 {
-            total_size += types::Boolean.compute_size(&self.include_documentation)?;
+            total_size += types::Boolean.compute_size(&self.include_documentation).map_err(|e| e.with_field("DescribeConfigsRequest", "include_documentation", version))?;
         } else {
             if self.include_documentation {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsRequest", "include_documentation", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -251,29 +340,32 @@ impl Encodable for DescribeConfigsRequest {
 
 impl Decodable for DescribeConfigsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DescribeConfigsRequest", version));
+        }
+        let start = buf.remaining();
         let resources = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeConfigsRequest", "resources", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeConfigsRequest", "resources", version, start - buf.remaining()))?
         };
         let include_synonyms = if version >= 1 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("DescribeConfigsRequest", "include_synonyms", version, start - buf.remaining()))?
         } else {
             false
         };
         let include_documentation = if version >= 3 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("DescribeConfigsRequest", "include_documentation", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -292,7 +384,7 @@ impl Default for DescribeConfigsRequest {
             resources: Default::default(),
             include_synonyms: false,
             include_documentation: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -301,6 +393,146 @@ impl Message for DescribeConfigsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl DescribeConfigsRequest {
+    /// Encode the message with the elements of `resources` produced by an iterator of known length, in place of
+    /// the `resources` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_resources<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, resources: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = DescribeConfigsResource>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeConfigsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let resources = resources.into_iter();
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(resources)).map_err(|e| e.with_field("DescribeConfigsRequest", "resources", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(resources)).map_err(|e| e.with_field("DescribeConfigsRequest", "resources", version))?;
+        }
+        if version >= 1 {
+            types::Boolean.encode(buf, &self.include_synonyms).map_err(|e| e.with_field("DescribeConfigsRequest", "include_synonyms", version))?;
+        } else {
+            if self.include_synonyms {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsRequest", "include_synonyms", version));
+            }
+        }
+        if version >= 3 {
+            types::Boolean.encode(buf, &self.include_documentation).map_err(|e| e.with_field("DescribeConfigsRequest", "include_documentation", version))?;
+        } else {
+            if self.include_documentation {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsRequest", "include_documentation", version));
+            }
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DescribeConfigsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.resources.iter_mut() {
+            item.normalize_to(version);
+        }
+        if version < 1 {
+            self.include_synonyms = false;
+        }
+        if version < 3 {
+            self.include_documentation = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.resources.iter().enumerate() {
+            item.validate_into(version, &format!("{}resources[{}].", path, i), invalid);
+        }
+        if version < 1 {
+            if self.include_synonyms {
+                invalid.push(format!("{}include_synonyms", path));
+            }
+        }
+        if version < 3 {
+            if self.include_documentation {
+                invalid.push(format!("{}include_documentation", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeConfigsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("resources".into(), serde_json::Value::Array(self.resources.iter().map(|item| item.to_json_with(version, records)).collect()));
+        if version >= 1 {
+            node.insert("includeSynonyms".into(), JsonValue::to_json(&self.include_synonyms));
+        }
+        if version >= 3 {
+            node.insert("includeDocumentation".into(), JsonValue::to_json(&self.include_documentation));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeConfigsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("resources") {
+            this.resources = crate::json::array(value, "DescribeConfigsRequest.resources")?.iter().map(|item| DescribeConfigsResource::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsRequest", "resources", version));
+        }
+        if let Some(value) = object.get("includeSynonyms") {
+            this.include_synonyms = JsonValue::from_json(value, "DescribeConfigsRequest.includeSynonyms")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DescribeConfigsRequest", "includeSynonyms", version));
+        }
+        if let Some(value) = object.get("includeDocumentation") {
+            this.include_documentation = JsonValue::from_json(value, "DescribeConfigsRequest.includeDocumentation")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("DescribeConfigsRequest", "includeDocumentation", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DescribeConfigsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
@@ -311,3 +543,24 @@ impl HeaderVersion for DescribeConfigsRequest {
     }
 }
 
+impl DescribeConfigsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::DescribeConfigsResponse {
+        let mut response = super::DescribeConfigsResponse::default();
+        let mut items0 = Vec::new();
+        for request0 in &self.resources {
+            let mut response0 = super::describe_configs_response::DescribeConfigsResult::default();
+            response0.error_code = error.code();
+            response0.resource_type = request0.resource_type;
+            response0.resource_name = request0.resource_name.clone();
+            items0.push(response0);
+        }
+        response.results = items0;
+        response
+    }
+}
+
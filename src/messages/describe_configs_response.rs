@@ -5,31 +5,40 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeConfigsSynonym {
     /// The synonym name.
     /// 
     /// Supported API versions: 1-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub name: StrBytes,
 
     /// The synonym value.
     /// 
     /// Supported API versions: 1-4
+    /// May be `None` in versions: 1-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub value: Option<StrBytes>,
 
     /// The synonym source.
@@ -38,7 +47,7 @@ pub struct DescribeConfigsSynonym {
     pub source: i8,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeConfigsSynonym {
@@ -51,87 +60,91 @@ impl Builder for DescribeConfigsSynonym {
 
 impl Encodable for DescribeConfigsSynonym {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.name)?;
+                types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("DescribeConfigsSynonym", "name", version))?;
             } else {
-                types::String.encode(buf, &self.name)?;
+                types::String.encode(buf, &self.name).map_err(|e| e.with_field("DescribeConfigsSynonym", "name", version))?;
             }
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsSynonym", "name", version));
             }
         }
         if version >= 1 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.value)?;
+                types::CompactString.encode(buf, &self.value).map_err(|e| e.with_field("DescribeConfigsSynonym", "value", version))?;
             } else {
-                types::String.encode(buf, &self.value)?;
+                types::String.encode(buf, &self.value).map_err(|e| e.with_field("DescribeConfigsSynonym", "value", version))?;
             }
         } else {
             if !self.value.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsSynonym", "value", version));
             }
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.source)?;
+            types::Int8.encode(buf, &self.source).map_err(|e| e.with_field("DescribeConfigsSynonym", "source", version))?;
         } else {
             if self.source != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsSynonym", "source", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.name)?;
+                total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("DescribeConfigsSynonym", "name", version))?;
             } else {
-                total_size += types::String.compute_size(&self.name)?;
+                total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("DescribeConfigsSynonym", "name", version))?;
             }
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsSynonym", "name", version));
             }
         }
         if version >= 1 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.value)?;
+                total_size += types::CompactString.compute_size(&self.value).map_err(|e| e.with_field("DescribeConfigsSynonym", "value", version))?;
             } else {
-                total_size += types::String.compute_size(&self.value)?;
+                total_size += types::String.compute_size(&self.value).map_err(|e| e.with_field("DescribeConfigsSynonym", "value", version))?;
             }
         } else {
             if !self.value.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsSynonym", "value", version));
             }
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.source)?;
+            total_size += types::Int8.compute_size(&self.source).map_err(|e| e.with_field("DescribeConfigsSynonym", "source", version))?;
         } else {
             if self.source != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsSynonym", "source", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -139,37 +152,37 @@ impl Encodable for DescribeConfigsSynonym {
 
 impl Decodable for DescribeConfigsSynonym {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 1 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeConfigsSynonym", "name", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("DescribeConfigsSynonym", "name", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let value = if version >= 1 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeConfigsSynonym", "value", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("DescribeConfigsSynonym", "value", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
         let source = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("DescribeConfigsSynonym", "source", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeConfigsSynonym", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -188,7 +201,7 @@ impl Default for DescribeConfigsSynonym {
             name: Default::default(),
             value: Some(Default::default()),
             source: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -197,19 +210,118 @@ impl Message for DescribeConfigsSynonym {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl DescribeConfigsSynonym {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.name = Default::default();
+        }
+        if version < 1 {
+            self.value = Some(Default::default());
+        }
+        if version < 1 {
+            self.source = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if !self.name.is_empty() {
+                invalid.push(format!("{}name", path));
+            }
+        }
+        if version < 1 {
+            if !self.value.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}value", path));
+            }
+        }
+        if version < 1 {
+            if self.source != 0 {
+                invalid.push(format!("{}source", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeConfigsSynonym {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        if version >= 1 {
+            node.insert("value".into(), JsonValue::to_json(&self.value));
+        }
+        if version >= 1 {
+            node.insert("source".into(), JsonValue::to_json(&self.source));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeConfigsSynonym")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "DescribeConfigsSynonym.name")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DescribeConfigsSynonym", "name", version));
+        }
+        if let Some(value) = object.get("value") {
+            this.value = JsonValue::from_json(value, "DescribeConfigsSynonym.value")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DescribeConfigsSynonym", "value", version));
+        }
+        if let Some(value) = object.get("source") {
+            this.source = JsonValue::from_json(value, "DescribeConfigsSynonym.source")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DescribeConfigsSynonym", "source", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeConfigsResourceResult {
     /// The configuration name.
     /// 
     /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub name: StrBytes,
 
     /// The configuration value.
     /// 
     /// Supported API versions: 0-4
+    /// May be `None` in versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub value: Option<StrBytes>,
 
     /// True if the configuration is read-only.
@@ -245,10 +357,12 @@ pub struct DescribeConfigsResourceResult {
     /// The configuration documentation.
     /// 
     /// Supported API versions: 3-4
+    /// May be `None` in versions: 3-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub documentation: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeConfigsResourceResult {
@@ -261,107 +375,111 @@ impl Builder for DescribeConfigsResourceResult {
 
 impl Encodable for DescribeConfigsResourceResult {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 4 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("DescribeConfigsResourceResult", "name", version))?;
         } else {
-            types::String.encode(buf, &self.name)?;
+            types::String.encode(buf, &self.name).map_err(|e| e.with_field("DescribeConfigsResourceResult", "name", version))?;
         }
         if version >= 4 {
-            types::CompactString.encode(buf, &self.value)?;
+            types::CompactString.encode(buf, &self.value).map_err(|e| e.with_field("DescribeConfigsResourceResult", "value", version))?;
         } else {
-            types::String.encode(buf, &self.value)?;
+            types::String.encode(buf, &self.value).map_err(|e| e.with_field("DescribeConfigsResourceResult", "value", version))?;
         }
-        types::Boolean.encode(buf, &self.read_only)?;
+        types::Boolean.encode(buf, &self.read_only).map_err(|e| e.with_field("DescribeConfigsResourceResult", "read_only", version))?;
         if version == 0 {
-            types::Boolean.encode(buf, &self.is_default)?;
+            types::Boolean.encode(buf, &self.is_default).map_err(|e| e.with_field("DescribeConfigsResourceResult", "is_default", version))?;
         } else {
             if self.is_default {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsResourceResult", "is_default", version));
             }
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.config_source)?;
+            types::Int8.encode(buf, &self.config_source).map_err(|e| e.with_field("DescribeConfigsResourceResult", "config_source", version))?;
         }
-        types::Boolean.encode(buf, &self.is_sensitive)?;
+        types::Boolean.encode(buf, &self.is_sensitive).map_err(|e| e.with_field("DescribeConfigsResourceResult", "is_sensitive", version))?;
         if version >= 1 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.synonyms)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.synonyms).map_err(|e| e.with_field("DescribeConfigsResourceResult", "synonyms", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.synonyms)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.synonyms).map_err(|e| e.with_field("DescribeConfigsResourceResult", "synonyms", version))?;
             }
         }
         if version >= 3 {
-            types::Int8.encode(buf, &self.config_type)?;
+            types::Int8.encode(buf, &self.config_type).map_err(|e| e.with_field("DescribeConfigsResourceResult", "config_type", version))?;
         }
         if version >= 3 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.documentation)?;
+                types::CompactString.encode(buf, &self.documentation).map_err(|e| e.with_field("DescribeConfigsResourceResult", "documentation", version))?;
             } else {
-                types::String.encode(buf, &self.documentation)?;
+                types::String.encode(buf, &self.documentation).map_err(|e| e.with_field("DescribeConfigsResourceResult", "documentation", version))?;
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("DescribeConfigsResourceResult", "name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.name)?;
+            total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("DescribeConfigsResourceResult", "name", version))?;
         }
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.value)?;
+            total_size += types::CompactString.compute_size(&self.value).map_err(|e| e.with_field("DescribeConfigsResourceResult", "value", version))?;
         } else {
-            total_size += types::String.compute_size(&self.value)?;
+            total_size += types::String.compute_size(&self.value).map_err(|e| e.with_field("DescribeConfigsResourceResult", "value", version))?;
         }
-        total_size += types::Boolean.compute_size(&self.read_only)?;
+        total_size += types::Boolean.compute_size(&self.read_only).map_err(|e| e.with_field("DescribeConfigsResourceResult", "read_only", version))?;
         if version == 0 {
-            total_size += types::Boolean.compute_size(&self.is_default)?;
+            total_size += types::Boolean.compute_size(&self.is_default).map_err(|e| e.with_field("DescribeConfigsResourceResult", "is_default", version))?;
         } else {
             if self.is_default {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeConfigsResourceResult", "is_default", version));
             }
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.config_source)?;
+            total_size += types::Int8.compute_size(&self.config_source).map_err(|e| e.with_field("DescribeConfigsResourceResult", "config_source", version))?;
         }
-        total_size += types::Boolean.compute_size(&self.is_sensitive)?;
+        total_size += types::Boolean.compute_size(&self.is_sensitive).map_err(|e| e.with_field("DescribeConfigsResourceResult", "is_sensitive", version))?;
         if version >= 1 {
             if version >= 4 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.synonyms)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.synonyms).map_err(|e| e.with_field("DescribeConfigsResourceResult", "synonyms", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.synonyms)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.synonyms).map_err(|e| e.with_field("DescribeConfigsResourceResult", "synonyms", version))?;
             }
         }
         if version >= 3 {
-            total_size += types::Int8.compute_size(&self.config_type)?;
+            total_size += types::Int8.compute_size(&self.config_type).map_err(|e| e.with_field("DescribeConfigsResourceResult", "config_type", version))?;
         }
         if version >= 3 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.documentation)?;
+                total_size += types::CompactString.compute_size(&self.documentation).map_err(|e| e.with_field("DescribeConfigsResourceResult", "documentation", version))?;
             } else {
-                total_size += types::String.compute_size(&self.documentation)?;
+                total_size += types::String.compute_size(&self.documentation).map_err(|e| e.with_field("DescribeConfigsResourceResult", "documentation", version))?;
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -369,59 +487,59 @@ impl Encodable for DescribeConfigsResourceResult {
 
 impl Decodable for DescribeConfigsResourceResult {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "name", version, start - buf.remaining()))?
         };
         let value = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "value", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "value", version, start - buf.remaining()))?
         };
-        let read_only = types::Boolean.decode(buf)?;
+        let read_only = types::Boolean.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "read_only", version, start - buf.remaining()))?;
         let is_default = if version == 0 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "is_default", version, start - buf.remaining()))?
         } else {
             false
         };
         let config_source = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "config_source", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let is_sensitive = types::Boolean.decode(buf)?;
+        let is_sensitive = types::Boolean.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "is_sensitive", version, start - buf.remaining()))?;
         let synonyms = if version >= 1 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "synonyms", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "synonyms", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let config_type = if version >= 3 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "config_type", version, start - buf.remaining()))?
         } else {
             0
         };
         let documentation = if version >= 3 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "documentation", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "documentation", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeConfigsResourceResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -452,7 +570,7 @@ impl Default for DescribeConfigsResourceResult {
             synonyms: Default::default(),
             config_type: 0,
             documentation: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -461,9 +579,169 @@ impl Message for DescribeConfigsResourceResult {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl DescribeConfigsResourceResult {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version != 0 {
+            self.is_default = false;
+        }
+        if version < 1 {
+            self.config_source = -1;
+        }
+        if version >= 1 {
+            for item in self.synonyms.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.synonyms = Default::default();
+        }
+        if version < 3 {
+            self.config_type = 0;
+        }
+        if version < 3 {
+            self.documentation = Some(Default::default());
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version != 0 {
+            if self.is_default {
+                invalid.push(format!("{}is_default", path));
+            }
+        }
+        if version < 1 {
+            if self.config_source != -1 {
+                invalid.push(format!("{}config_source", path));
+            }
+        }
+        if version >= 1 {
+            for (i, item) in self.synonyms.iter().enumerate() {
+                item.validate_into(version, &format!("{}synonyms[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.synonyms.is_empty() {
+                invalid.push(format!("{}synonyms", path));
+            }
+        }
+        if version < 3 {
+            if self.config_type != 0 {
+                invalid.push(format!("{}config_type", path));
+            }
+        }
+        if version < 3 {
+            if !self.documentation.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}documentation", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeConfigsResourceResult {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("value".into(), JsonValue::to_json(&self.value));
+        node.insert("readOnly".into(), JsonValue::to_json(&self.read_only));
+        if version == 0 {
+            node.insert("isDefault".into(), JsonValue::to_json(&self.is_default));
+        }
+        if version >= 1 {
+            node.insert("configSource".into(), JsonValue::to_json(&self.config_source));
+        }
+        node.insert("isSensitive".into(), JsonValue::to_json(&self.is_sensitive));
+        if version >= 1 {
+            node.insert("synonyms".into(), serde_json::Value::Array(self.synonyms.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 3 {
+            node.insert("configType".into(), JsonValue::to_json(&self.config_type));
+        }
+        if version >= 3 {
+            node.insert("documentation".into(), JsonValue::to_json(&self.documentation));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeConfigsResourceResult")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "DescribeConfigsResourceResult.name")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "name", version));
+        }
+        if let Some(value) = object.get("value") {
+            this.value = JsonValue::from_json(value, "DescribeConfigsResourceResult.value")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "value", version));
+        }
+        if let Some(value) = object.get("readOnly") {
+            this.read_only = JsonValue::from_json(value, "DescribeConfigsResourceResult.readOnly")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "readOnly", version));
+        }
+        if let Some(value) = object.get("isDefault") {
+            this.is_default = JsonValue::from_json(value, "DescribeConfigsResourceResult.isDefault")?;
+        } else if version == 0 {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "isDefault", version));
+        }
+        if let Some(value) = object.get("configSource") {
+            this.config_source = JsonValue::from_json(value, "DescribeConfigsResourceResult.configSource")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "configSource", version));
+        }
+        if let Some(value) = object.get("isSensitive") {
+            this.is_sensitive = JsonValue::from_json(value, "DescribeConfigsResourceResult.isSensitive")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "isSensitive", version));
+        }
+        if let Some(value) = object.get("synonyms") {
+            this.synonyms = crate::json::array(value, "DescribeConfigsResourceResult.synonyms")?.iter().map(|item| DescribeConfigsSynonym::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "synonyms", version));
+        }
+        if let Some(value) = object.get("configType") {
+            this.config_type = JsonValue::from_json(value, "DescribeConfigsResourceResult.configType")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "configType", version));
+        }
+        if let Some(value) = object.get("documentation") {
+            this.documentation = JsonValue::from_json(value, "DescribeConfigsResourceResult.documentation")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("DescribeConfigsResourceResult", "documentation", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeConfigsResult {
     /// The error code, or 0 if we were able to successfully describe the configurations.
@@ -474,6 +752,8 @@ pub struct DescribeConfigsResult {
     /// The error message, or null if we were able to successfully describe the configurations.
     /// 
     /// Supported API versions: 0-4
+    /// May be `None` in versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The resource type.
@@ -484,6 +764,7 @@ pub struct DescribeConfigsResult {
     /// The resource name.
     /// 
     /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub resource_name: StrBytes,
 
     /// Each listed configuration.
@@ -492,7 +773,7 @@ pub struct DescribeConfigsResult {
     pub configs: Vec<DescribeConfigsResourceResult>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeConfigsResult {
@@ -505,63 +786,67 @@ impl Builder for DescribeConfigsResult {
 
 impl Encodable for DescribeConfigsResult {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DescribeConfigsResult", "error_code", version))?;
         if version >= 4 {
-            types::CompactString.encode(buf, &self.error_message)?;
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("DescribeConfigsResult", "error_message", version))?;
         } else {
-            types::String.encode(buf, &self.error_message)?;
+            types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("DescribeConfigsResult", "error_message", version))?;
         }
-        types::Int8.encode(buf, &self.resource_type)?;
+        types::Int8.encode(buf, &self.resource_type).map_err(|e| e.with_field("DescribeConfigsResult", "resource_type", version))?;
         if version >= 4 {
-            types::CompactString.encode(buf, &self.resource_name)?;
+            types::CompactString.encode(buf, &self.resource_name).map_err(|e| e.with_field("DescribeConfigsResult", "resource_name", version))?;
         } else {
-            types::String.encode(buf, &self.resource_name)?;
+            types::String.encode(buf, &self.resource_name).map_err(|e| e.with_field("DescribeConfigsResult", "resource_name", version))?;
         }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.configs)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.configs).map_err(|e| e.with_field("DescribeConfigsResult", "configs", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.configs)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.configs).map_err(|e| e.with_field("DescribeConfigsResult", "configs", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResult", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResult", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResult", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("DescribeConfigsResult", "error_code", version))?;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.error_message)?;
+            total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("DescribeConfigsResult", "error_message", version))?;
         } else {
-            total_size += types::String.compute_size(&self.error_message)?;
+            total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("DescribeConfigsResult", "error_message", version))?;
         }
-        total_size += types::Int8.compute_size(&self.resource_type)?;
+        total_size += types::Int8.compute_size(&self.resource_type).map_err(|e| e.with_field("DescribeConfigsResult", "resource_type", version))?;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.resource_name)?;
+            total_size += types::CompactString.compute_size(&self.resource_name).map_err(|e| e.with_field("DescribeConfigsResult", "resource_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.resource_name)?;
+            total_size += types::String.compute_size(&self.resource_name).map_err(|e| e.with_field("DescribeConfigsResult", "resource_name", version))?;
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.configs)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.configs).map_err(|e| e.with_field("DescribeConfigsResult", "configs", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.configs)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.configs).map_err(|e| e.with_field("DescribeConfigsResult", "configs", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResult", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResult", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResult", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -569,31 +854,31 @@ impl Encodable for DescribeConfigsResult {
 
 impl Decodable for DescribeConfigsResult {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "error_code", version, start - buf.remaining()))?;
         let error_message = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "error_message", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "error_message", version, start - buf.remaining()))?
         };
-        let resource_type = types::Int8.decode(buf)?;
+        let resource_type = types::Int8.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "resource_type", version, start - buf.remaining()))?;
         let resource_name = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "resource_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "resource_name", version, start - buf.remaining()))?
         };
         let configs = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "configs", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "configs", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeConfigsResult", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -616,7 +901,7 @@ impl Default for DescribeConfigsResult {
             resource_type: 0,
             resource_name: Default::default(),
             configs: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -625,9 +910,93 @@ impl Message for DescribeConfigsResult {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl DescribeConfigsResult {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.configs.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.configs.iter().enumerate() {
+            item.validate_into(version, &format!("{}configs[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeConfigsResult {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("resourceType".into(), JsonValue::to_json(&self.resource_type));
+        node.insert("resourceName".into(), JsonValue::to_json(&self.resource_name));
+        node.insert("configs".into(), serde_json::Value::Array(self.configs.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeConfigsResult")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "DescribeConfigsResult.errorCode")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResult", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "DescribeConfigsResult.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResult", "errorMessage", version));
+        }
+        if let Some(value) = object.get("resourceType") {
+            this.resource_type = JsonValue::from_json(value, "DescribeConfigsResult.resourceType")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResult", "resourceType", version));
+        }
+        if let Some(value) = object.get("resourceName") {
+            this.resource_name = JsonValue::from_json(value, "DescribeConfigsResult.resourceName")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResult", "resourceName", version));
+        }
+        if let Some(value) = object.get("configs") {
+            this.configs = crate::json::array(value, "DescribeConfigsResult.configs")?.iter().map(|item| DescribeConfigsResourceResult::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResult", "configs", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeConfigsResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -641,7 +1010,7 @@ pub struct DescribeConfigsResponse {
     pub results: Vec<DescribeConfigsResult>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeConfigsResponse {
@@ -654,41 +1023,51 @@ impl Builder for DescribeConfigsResponse {
 
 impl Encodable for DescribeConfigsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeConfigsResponse", version));
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeConfigsResponse", "throttle_time_ms", version))?;
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.results)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.results).map_err(|e| e.with_field("DescribeConfigsResponse", "results", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.results)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.results).map_err(|e| e.with_field("DescribeConfigsResponse", "results", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeConfigsResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("DescribeConfigsResponse", "throttle_time_ms", version))?;
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.results)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.results).map_err(|e| e.with_field("DescribeConfigsResponse", "results", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.results)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.results).map_err(|e| e.with_field("DescribeConfigsResponse", "results", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -696,20 +1075,23 @@ impl Encodable for DescribeConfigsResponse {
 
 impl Decodable for DescribeConfigsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DescribeConfigsResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("DescribeConfigsResponse", "throttle_time_ms", version, start - buf.remaining()))?;
         let results = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeConfigsResponse", "results", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeConfigsResponse", "results", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -726,7 +1108,7 @@ impl Default for DescribeConfigsResponse {
         Self {
             throttle_time_ms: 0,
             results: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -735,6 +1117,107 @@ impl Message for DescribeConfigsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl DescribeConfigsResponse {
+    /// Encode the message with the elements of `results` produced by an iterator of known length, in place of
+    /// the `results` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_results<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, results: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = DescribeConfigsResult>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeConfigsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let results = results.into_iter();
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeConfigsResponse", "throttle_time_ms", version))?;
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(results)).map_err(|e| e.with_field("DescribeConfigsResponse", "results", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(results)).map_err(|e| e.with_field("DescribeConfigsResponse", "results", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeConfigsResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeConfigsResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DescribeConfigsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.results.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.results.iter().enumerate() {
+            item.validate_into(version, &format!("{}results[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeConfigsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        node.insert("results".into(), serde_json::Value::Array(self.results.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeConfigsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "DescribeConfigsResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("results") {
+            this.results = crate::json::array(value, "DescribeConfigsResponse.results")?.iter().map(|item| DescribeConfigsResult::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeConfigsResponse", "results", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DescribeConfigsResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
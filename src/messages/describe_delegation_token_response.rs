@@ -5,35 +5,43 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribedDelegationTokenRenewer {
     /// The renewer principal type
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_type: StrBytes,
 
     /// The renewer principal name
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_name: StrBytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribedDelegationTokenRenewer {
@@ -46,49 +54,53 @@ impl Builder for DescribedDelegationTokenRenewer {
 
 impl Encodable for DescribedDelegationTokenRenewer {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_type)?;
+            types::CompactString.encode(buf, &self.principal_type).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_type", version))?;
         } else {
-            types::String.encode(buf, &self.principal_type)?;
+            types::String.encode(buf, &self.principal_type).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_type", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_name)?;
+            types::CompactString.encode(buf, &self.principal_name).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_name", version))?;
         } else {
-            types::String.encode(buf, &self.principal_name)?;
+            types::String.encode(buf, &self.principal_name).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_name", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_type)?;
+            total_size += types::CompactString.compute_size(&self.principal_type).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_type)?;
+            total_size += types::String.compute_size(&self.principal_type).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_type", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_name)?;
+            total_size += types::CompactString.compute_size(&self.principal_name).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_name)?;
+            total_size += types::String.compute_size(&self.principal_name).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_name", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -96,24 +108,24 @@ impl Encodable for DescribedDelegationTokenRenewer {
 
 impl Decodable for DescribedDelegationTokenRenewer {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let principal_type = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_type", version, start - buf.remaining()))?
         };
         let principal_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "principal_name", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribedDelegationTokenRenewer", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -130,7 +142,7 @@ impl Default for DescribedDelegationTokenRenewer {
         Self {
             principal_type: Default::default(),
             principal_name: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -139,29 +151,93 @@ impl Message for DescribedDelegationTokenRenewer {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DescribedDelegationTokenRenewer {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribedDelegationTokenRenewer {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("principalType".into(), JsonValue::to_json(&self.principal_type));
+        node.insert("principalName".into(), JsonValue::to_json(&self.principal_name));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribedDelegationTokenRenewer")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("principalType") {
+            this.principal_type = JsonValue::from_json(value, "DescribedDelegationTokenRenewer.principalType")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationTokenRenewer", "principalType", version));
+        }
+        if let Some(value) = object.get("principalName") {
+            this.principal_name = JsonValue::from_json(value, "DescribedDelegationTokenRenewer.principalName")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationTokenRenewer", "principalName", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribedDelegationToken {
     /// The token principal type.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_type: StrBytes,
 
     /// The token principal name.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub principal_name: StrBytes,
 
     /// The principal type of the requester of the token.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub token_requester_principal_type: StrBytes,
 
     /// The principal type of the requester of the token.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub token_requester_principal_name: StrBytes,
 
     /// The token issue timestamp in milliseconds.
@@ -182,6 +258,7 @@ pub struct DescribedDelegationToken {
     /// The token ID.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub token_id: StrBytes,
 
     /// The token HMAC.
@@ -195,7 +272,7 @@ pub struct DescribedDelegationToken {
     pub renewers: Vec<DescribedDelegationTokenRenewer>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribedDelegationToken {
@@ -208,113 +285,117 @@ impl Builder for DescribedDelegationToken {
 
 impl Encodable for DescribedDelegationToken {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_type)?;
+            types::CompactString.encode(buf, &self.principal_type).map_err(|e| e.with_field("DescribedDelegationToken", "principal_type", version))?;
         } else {
-            types::String.encode(buf, &self.principal_type)?;
+            types::String.encode(buf, &self.principal_type).map_err(|e| e.with_field("DescribedDelegationToken", "principal_type", version))?;
         }
         if version >= 2 {
-            types::CompactString.encode(buf, &self.principal_name)?;
+            types::CompactString.encode(buf, &self.principal_name).map_err(|e| e.with_field("DescribedDelegationToken", "principal_name", version))?;
         } else {
-            types::String.encode(buf, &self.principal_name)?;
+            types::String.encode(buf, &self.principal_name).map_err(|e| e.with_field("DescribedDelegationToken", "principal_name", version))?;
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.token_requester_principal_type)?;
+            types::CompactString.encode(buf, &self.token_requester_principal_type).map_err(|e| e.with_field("DescribedDelegationToken", "token_requester_principal_type", version))?;
         } else {
             if !self.token_requester_principal_type.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribedDelegationToken", "token_requester_principal_type", version));
             }
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.token_requester_principal_name)?;
+            types::CompactString.encode(buf, &self.token_requester_principal_name).map_err(|e| e.with_field("DescribedDelegationToken", "token_requester_principal_name", version))?;
         } else {
             if !self.token_requester_principal_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribedDelegationToken", "token_requester_principal_name", version));
             }
         }
-        types::Int64.encode(buf, &self.issue_timestamp)?;
-        types::Int64.encode(buf, &self.expiry_timestamp)?;
-        types::Int64.encode(buf, &self.max_timestamp)?;
+        types::Int64.encode(buf, &self.issue_timestamp).map_err(|e| e.with_field("DescribedDelegationToken", "issue_timestamp", version))?;
+        types::Int64.encode(buf, &self.expiry_timestamp).map_err(|e| e.with_field("DescribedDelegationToken", "expiry_timestamp", version))?;
+        types::Int64.encode(buf, &self.max_timestamp).map_err(|e| e.with_field("DescribedDelegationToken", "max_timestamp", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.token_id)?;
+            types::CompactString.encode(buf, &self.token_id).map_err(|e| e.with_field("DescribedDelegationToken", "token_id", version))?;
         } else {
-            types::String.encode(buf, &self.token_id)?;
+            types::String.encode(buf, &self.token_id).map_err(|e| e.with_field("DescribedDelegationToken", "token_id", version))?;
         }
         if version >= 2 {
-            types::CompactBytes.encode(buf, &self.hmac)?;
+            types::CompactBytes.encode(buf, &self.hmac).map_err(|e| e.with_field("DescribedDelegationToken", "hmac", version))?;
         } else {
-            types::Bytes.encode(buf, &self.hmac)?;
+            types::Bytes.encode(buf, &self.hmac).map_err(|e| e.with_field("DescribedDelegationToken", "hmac", version))?;
         }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.renewers)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.renewers).map_err(|e| e.with_field("DescribedDelegationToken", "renewers", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.renewers)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.renewers).map_err(|e| e.with_field("DescribedDelegationToken", "renewers", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribedDelegationToken", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribedDelegationToken", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribedDelegationToken", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_type)?;
+            total_size += types::CompactString.compute_size(&self.principal_type).map_err(|e| e.with_field("DescribedDelegationToken", "principal_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_type)?;
+            total_size += types::String.compute_size(&self.principal_type).map_err(|e| e.with_field("DescribedDelegationToken", "principal_type", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.principal_name)?;
+            total_size += types::CompactString.compute_size(&self.principal_name).map_err(|e| e.with_field("DescribedDelegationToken", "principal_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.principal_name)?;
+            total_size += types::String.compute_size(&self.principal_name).map_err(|e| e.with_field("DescribedDelegationToken", "principal_name", version))?;
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.token_requester_principal_type)?;
+            total_size += types::CompactString.compute_size(&self.token_requester_principal_type).map_err(|e| e.with_field("DescribedDelegationToken", "token_requester_principal_type", version))?;
         } else {
             if !self.token_requester_principal_type.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribedDelegationToken", "token_requester_principal_type", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.token_requester_principal_name)?;
+            total_size += types::CompactString.compute_size(&self.token_requester_principal_name).map_err(|e| e.with_field("DescribedDelegationToken", "token_requester_principal_name", version))?;
         } else {
             if !self.token_requester_principal_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribedDelegationToken", "token_requester_principal_name", version));
             }
         }
-        total_size += types::Int64.compute_size(&self.issue_timestamp)?;
-        total_size += types::Int64.compute_size(&self.expiry_timestamp)?;
-        total_size += types::Int64.compute_size(&self.max_timestamp)?;
+        total_size += types::Int64.compute_size(&self.issue_timestamp).map_err(|e| e.with_field("DescribedDelegationToken", "issue_timestamp", version))?;
+        total_size += types::Int64.compute_size(&self.expiry_timestamp).map_err(|e| e.with_field("DescribedDelegationToken", "expiry_timestamp", version))?;
+        total_size += types::Int64.compute_size(&self.max_timestamp).map_err(|e| e.with_field("DescribedDelegationToken", "max_timestamp", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.token_id)?;
+            total_size += types::CompactString.compute_size(&self.token_id).map_err(|e| e.with_field("DescribedDelegationToken", "token_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.token_id)?;
+            total_size += types::String.compute_size(&self.token_id).map_err(|e| e.with_field("DescribedDelegationToken", "token_id", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactBytes.compute_size(&self.hmac)?;
+            total_size += types::CompactBytes.compute_size(&self.hmac).map_err(|e| e.with_field("DescribedDelegationToken", "hmac", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.hmac)?;
+            total_size += types::Bytes.compute_size(&self.hmac).map_err(|e| e.with_field("DescribedDelegationToken", "hmac", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.renewers)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.renewers).map_err(|e| e.with_field("DescribedDelegationToken", "renewers", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.renewers)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.renewers).map_err(|e| e.with_field("DescribedDelegationToken", "renewers", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribedDelegationToken", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribedDelegationToken", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribedDelegationToken", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -322,52 +403,52 @@ impl Encodable for DescribedDelegationToken {
 
 impl Decodable for DescribedDelegationToken {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let principal_type = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "principal_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "principal_type", version, start - buf.remaining()))?
         };
         let principal_name = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "principal_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "principal_name", version, start - buf.remaining()))?
         };
         let token_requester_principal_type = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "token_requester_principal_type", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let token_requester_principal_name = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "token_requester_principal_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let issue_timestamp = types::Int64.decode(buf)?;
-        let expiry_timestamp = types::Int64.decode(buf)?;
-        let max_timestamp = types::Int64.decode(buf)?;
+        let issue_timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "issue_timestamp", version, start - buf.remaining()))?;
+        let expiry_timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "expiry_timestamp", version, start - buf.remaining()))?;
+        let max_timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "max_timestamp", version, start - buf.remaining()))?;
         let token_id = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "token_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "token_id", version, start - buf.remaining()))?
         };
         let hmac = if version >= 2 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "hmac", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "hmac", version, start - buf.remaining()))?
         };
         let renewers = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "renewers", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "renewers", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedDelegationToken", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribedDelegationToken", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -400,7 +481,7 @@ impl Default for DescribedDelegationToken {
             token_id: Default::default(),
             hmac: Default::default(),
             renewers: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -409,9 +490,143 @@ impl Message for DescribedDelegationToken {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DescribedDelegationToken {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.token_requester_principal_type = Default::default();
+        }
+        if version < 3 {
+            self.token_requester_principal_name = Default::default();
+        }
+        for item in self.renewers.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.token_requester_principal_type.is_empty() {
+                invalid.push(format!("{}token_requester_principal_type", path));
+            }
+        }
+        if version < 3 {
+            if !self.token_requester_principal_name.is_empty() {
+                invalid.push(format!("{}token_requester_principal_name", path));
+            }
+        }
+        for (i, item) in self.renewers.iter().enumerate() {
+            item.validate_into(version, &format!("{}renewers[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribedDelegationToken {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("principalType".into(), JsonValue::to_json(&self.principal_type));
+        node.insert("principalName".into(), JsonValue::to_json(&self.principal_name));
+        if version >= 3 {
+            node.insert("tokenRequesterPrincipalType".into(), JsonValue::to_json(&self.token_requester_principal_type));
+        }
+        if version >= 3 {
+            node.insert("tokenRequesterPrincipalName".into(), JsonValue::to_json(&self.token_requester_principal_name));
+        }
+        node.insert("issueTimestamp".into(), JsonValue::to_json(&self.issue_timestamp));
+        node.insert("expiryTimestamp".into(), JsonValue::to_json(&self.expiry_timestamp));
+        node.insert("maxTimestamp".into(), JsonValue::to_json(&self.max_timestamp));
+        node.insert("tokenId".into(), JsonValue::to_json(&self.token_id));
+        node.insert("hmac".into(), JsonValue::to_json(&self.hmac));
+        node.insert("renewers".into(), serde_json::Value::Array(self.renewers.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribedDelegationToken")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("principalType") {
+            this.principal_type = JsonValue::from_json(value, "DescribedDelegationToken.principalType")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationToken", "principalType", version));
+        }
+        if let Some(value) = object.get("principalName") {
+            this.principal_name = JsonValue::from_json(value, "DescribedDelegationToken.principalName")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationToken", "principalName", version));
+        }
+        if let Some(value) = object.get("tokenRequesterPrincipalType") {
+            this.token_requester_principal_type = JsonValue::from_json(value, "DescribedDelegationToken.tokenRequesterPrincipalType")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("DescribedDelegationToken", "tokenRequesterPrincipalType", version));
+        }
+        if let Some(value) = object.get("tokenRequesterPrincipalName") {
+            this.token_requester_principal_name = JsonValue::from_json(value, "DescribedDelegationToken.tokenRequesterPrincipalName")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("DescribedDelegationToken", "tokenRequesterPrincipalName", version));
+        }
+        if let Some(value) = object.get("issueTimestamp") {
+            this.issue_timestamp = JsonValue::from_json(value, "DescribedDelegationToken.issueTimestamp")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationToken", "issueTimestamp", version));
+        }
+        if let Some(value) = object.get("expiryTimestamp") {
+            this.expiry_timestamp = JsonValue::from_json(value, "DescribedDelegationToken.expiryTimestamp")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationToken", "expiryTimestamp", version));
+        }
+        if let Some(value) = object.get("maxTimestamp") {
+            this.max_timestamp = JsonValue::from_json(value, "DescribedDelegationToken.maxTimestamp")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationToken", "maxTimestamp", version));
+        }
+        if let Some(value) = object.get("tokenId") {
+            this.token_id = JsonValue::from_json(value, "DescribedDelegationToken.tokenId")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationToken", "tokenId", version));
+        }
+        if let Some(value) = object.get("hmac") {
+            this.hmac = JsonValue::from_json(value, "DescribedDelegationToken.hmac")?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationToken", "hmac", version));
+        }
+        if let Some(value) = object.get("renewers") {
+            this.renewers = crate::json::array(value, "DescribedDelegationToken.renewers")?.iter().map(|item| DescribedDelegationTokenRenewer::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribedDelegationToken", "renewers", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeDelegationTokenResponse {
     /// The error code, or 0 if there was no error.
@@ -430,7 +645,7 @@ pub struct DescribeDelegationTokenResponse {
     pub throttle_time_ms: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeDelegationTokenResponse {
@@ -443,43 +658,53 @@ impl Builder for DescribeDelegationTokenResponse {
 
 impl Encodable for DescribeDelegationTokenResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeDelegationTokenResponse", version));
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "error_code", version))?;
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.tokens)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.tokens).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "tokens", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.tokens)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.tokens).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "tokens", version))?;
         }
-        types::Int32.encode(buf, &self.throttle_time_ms)?;
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "throttle_time_ms", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeDelegationTokenResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "error_code", version))?;
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.tokens)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.tokens).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "tokens", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.tokens)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.tokens).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "tokens", version))?;
         }
-        total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+        total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "throttle_time_ms", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -487,21 +712,24 @@ impl Encodable for DescribeDelegationTokenResponse {
 
 impl Decodable for DescribeDelegationTokenResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DescribeDelegationTokenResponse", version));
+        }
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "error_code", version, start - buf.remaining()))?;
         let tokens = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "tokens", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "tokens", version, start - buf.remaining()))?
         };
-        let throttle_time_ms = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let throttle_time_ms = types::Int32.decode(buf).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "throttle_time_ms", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -520,7 +748,7 @@ impl Default for DescribeDelegationTokenResponse {
             error_code: 0,
             tokens: Default::default(),
             throttle_time_ms: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -529,6 +757,114 @@ impl Message for DescribeDelegationTokenResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl DescribeDelegationTokenResponse {
+    /// Encode the message with the elements of `tokens` produced by an iterator of known length, in place of
+    /// the `tokens` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_tokens<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, tokens: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = DescribedDelegationToken>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeDelegationTokenResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let tokens = tokens.into_iter();
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "error_code", version))?;
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(tokens)).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "tokens", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(tokens)).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "tokens", version))?;
+        }
+        types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "throttle_time_ms", version))?;
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeDelegationTokenResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DescribeDelegationTokenResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.tokens.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.tokens.iter().enumerate() {
+            item.validate_into(version, &format!("{}tokens[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeDelegationTokenResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("tokens".into(), serde_json::Value::Array(self.tokens.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeDelegationTokenResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "DescribeDelegationTokenResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("DescribeDelegationTokenResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("tokens") {
+            this.tokens = crate::json::array(value, "DescribeDelegationTokenResponse.tokens")?.iter().map(|item| DescribedDelegationToken::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeDelegationTokenResponse", "tokens", version));
+        }
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "DescribeDelegationTokenResponse.throttleTimeMs")?;
+        } else {
+            return Err(crate::json::missing("DescribeDelegationTokenResponse", "throttleTimeMs", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DescribeDelegationTokenResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
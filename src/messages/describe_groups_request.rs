@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeGroupsRequest {
     /// The names of the groups to describe
@@ -33,7 +39,7 @@ pub struct DescribeGroupsRequest {
     pub include_authorized_operations: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeGroupsRequest {
@@ -46,53 +52,63 @@ impl Builder for DescribeGroupsRequest {
 
 impl Encodable for DescribeGroupsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeGroupsRequest", version));
+        }
         if version >= 5 {
-            types::CompactArray(types::CompactString).encode(buf, &self.groups)?;
+            types::CompactArray(types::CompactString).encode(buf, &self.groups).map_err(|e| e.with_field("DescribeGroupsRequest", "groups", version))?;
         } else {
-            types::Array(types::String).encode(buf, &self.groups)?;
+            types::Array(types::String).encode(buf, &self.groups).map_err(|e| e.with_field("DescribeGroupsRequest", "groups", version))?;
         }
         if version >= 3 {
-            types::Boolean.encode(buf, &self.include_authorized_operations)?;
+            types::Boolean.encode(buf, &self.include_authorized_operations).map_err(|e| e.with_field("DescribeGroupsRequest", "include_authorized_operations", version))?;
         } else {
             if self.include_authorized_operations {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeGroupsRequest", "include_authorized_operations", version));
             }
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeGroupsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeGroupsRequest", version));
+        }
         let mut total_size = 0;
         if version >= 5 {
-            total_size += types::CompactArray(types::CompactString).compute_size(&self.groups)?;
+            total_size += types::CompactArray(types::CompactString).compute_size(&self.groups).map_err(|e| e.with_field("DescribeGroupsRequest", "groups", version))?;
         } else {
-            total_size += types::Array(types::String).compute_size(&self.groups)?;
+            total_size += types::Array(types::String).compute_size(&self.groups).map_err(|e| e.with_field("DescribeGroupsRequest", "groups", version))?;
         }
         if version >= 3 {
-            total_size += types::Boolean.compute_size(&self.include_authorized_operations)?;
+            total_size += types::Boolean.compute_size(&self.include_authorized_operations).map_err(|e| e.with_field("DescribeGroupsRequest", "include_authorized_operations", version))?;
         } else {
             if self.include_authorized_operations {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeGroupsRequest", "include_authorized_operations", version));
             }
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeGroupsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -100,24 +116,27 @@ impl Encodable for DescribeGroupsRequest {
 
 impl Decodable for DescribeGroupsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DescribeGroupsRequest", version));
+        }
+        let start = buf.remaining();
         let groups = if version >= 5 {
-            types::CompactArray(types::CompactString).decode(buf)?
+            types::CompactArray(types::CompactString).decode(buf).map_err(|e| e.with_field("DescribeGroupsRequest", "groups", version, start - buf.remaining()))?
         } else {
-            types::Array(types::String).decode(buf)?
+            types::Array(types::String).decode(buf).map_err(|e| e.with_field("DescribeGroupsRequest", "groups", version, start - buf.remaining()))?
         };
         let include_authorized_operations = if version >= 3 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("DescribeGroupsRequest", "include_authorized_operations", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -134,7 +153,7 @@ impl Default for DescribeGroupsRequest {
         Self {
             groups: Default::default(),
             include_authorized_operations: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -143,6 +162,117 @@ impl Message for DescribeGroupsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl DescribeGroupsRequest {
+    /// Encode the message with the elements of `groups` produced by an iterator of known length, in place of
+    /// the `groups` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_groups<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, groups: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = super::GroupId>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeGroupsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let groups = groups.into_iter();
+        if version >= 5 {
+            types::CompactArray(types::CompactString).encode(buf, types::Streamed(groups)).map_err(|e| e.with_field("DescribeGroupsRequest", "groups", version))?;
+        } else {
+            types::Array(types::String).encode(buf, types::Streamed(groups)).map_err(|e| e.with_field("DescribeGroupsRequest", "groups", version))?;
+        }
+        if version >= 3 {
+            types::Boolean.encode(buf, &self.include_authorized_operations).map_err(|e| e.with_field("DescribeGroupsRequest", "include_authorized_operations", version))?;
+        } else {
+            if self.include_authorized_operations {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribeGroupsRequest", "include_authorized_operations", version));
+            }
+        }
+        if version >= 5 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeGroupsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeGroupsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DescribeGroupsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.include_authorized_operations = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.include_authorized_operations {
+                invalid.push(format!("{}include_authorized_operations", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeGroupsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("groups".into(), JsonValue::to_json(&self.groups));
+        if version >= 3 {
+            node.insert("includeAuthorizedOperations".into(), JsonValue::to_json(&self.include_authorized_operations));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeGroupsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groups") {
+            this.groups = JsonValue::from_json(value, "DescribeGroupsRequest.groups")?;
+        } else {
+            return Err(crate::json::missing("DescribeGroupsRequest", "groups", version));
+        }
+        if let Some(value) = object.get("includeAuthorizedOperations") {
+            this.include_authorized_operations = JsonValue::from_json(value, "DescribeGroupsRequest.includeAuthorizedOperations")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("DescribeGroupsRequest", "includeAuthorizedOperations", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DescribeGroupsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 5 {
@@ -153,3 +283,23 @@ impl HeaderVersion for DescribeGroupsRequest {
     }
 }
 
+impl DescribeGroupsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::DescribeGroupsResponse {
+        let mut response = super::DescribeGroupsResponse::default();
+        let mut items0 = Vec::new();
+        for request0 in &self.groups {
+            let mut response0 = super::describe_groups_response::DescribedGroup::default();
+            response0.group_id = request0.clone();
+            response0.error_code = error.code();
+            items0.push(response0);
+        }
+        response.groups = items0;
+        response
+    }
+}
+
@@ -5,41 +5,52 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribedGroupMember {
     /// The member ID assigned by the group coordinator.
     /// 
     /// Supported API versions: 0-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The unique identifier of the consumer instance provided by end user.
     /// 
     /// Supported API versions: 4-5
+    /// May be `None` in versions: 4-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_instance_id: Option<StrBytes>,
 
     /// The client ID used in the member's latest join group request.
     /// 
     /// Supported API versions: 0-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub client_id: StrBytes,
 
     /// The client host.
     /// 
     /// Supported API versions: 0-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub client_host: StrBytes,
 
     /// The metadata corresponding to the current group protocol in use.
@@ -53,7 +64,7 @@ pub struct DescribedGroupMember {
     pub member_assignment: Bytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribedGroupMember {
@@ -66,93 +77,97 @@ impl Builder for DescribedGroupMember {
 
 impl Encodable for DescribedGroupMember {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 5 {
-            types::CompactString.encode(buf, &self.member_id)?;
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("DescribedGroupMember", "member_id", version))?;
         } else {
-            types::String.encode(buf, &self.member_id)?;
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("DescribedGroupMember", "member_id", version))?;
         }
         if version >= 4 {
             if version >= 5 {
-                types::CompactString.encode(buf, &self.group_instance_id)?;
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("DescribedGroupMember", "group_instance_id", version))?;
             } else {
-                types::String.encode(buf, &self.group_instance_id)?;
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("DescribedGroupMember", "group_instance_id", version))?;
             }
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.client_id)?;
+            types::CompactString.encode(buf, &self.client_id).map_err(|e| e.with_field("DescribedGroupMember", "client_id", version))?;
         } else {
-            types::String.encode(buf, &self.client_id)?;
+            types::String.encode(buf, &self.client_id).map_err(|e| e.with_field("DescribedGroupMember", "client_id", version))?;
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.client_host)?;
+            types::CompactString.encode(buf, &self.client_host).map_err(|e| e.with_field("DescribedGroupMember", "client_host", version))?;
         } else {
-            types::String.encode(buf, &self.client_host)?;
+            types::String.encode(buf, &self.client_host).map_err(|e| e.with_field("DescribedGroupMember", "client_host", version))?;
         }
         if version >= 5 {
-            types::CompactBytes.encode(buf, &self.member_metadata)?;
+            types::CompactBytes.encode(buf, &self.member_metadata).map_err(|e| e.with_field("DescribedGroupMember", "member_metadata", version))?;
         } else {
-            types::Bytes.encode(buf, &self.member_metadata)?;
+            types::Bytes.encode(buf, &self.member_metadata).map_err(|e| e.with_field("DescribedGroupMember", "member_metadata", version))?;
         }
         if version >= 5 {
-            types::CompactBytes.encode(buf, &self.member_assignment)?;
+            types::CompactBytes.encode(buf, &self.member_assignment).map_err(|e| e.with_field("DescribedGroupMember", "member_assignment", version))?;
         } else {
-            types::Bytes.encode(buf, &self.member_assignment)?;
+            types::Bytes.encode(buf, &self.member_assignment).map_err(|e| e.with_field("DescribedGroupMember", "member_assignment", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribedGroupMember", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribedGroupMember", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribedGroupMember", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.member_id)?;
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("DescribedGroupMember", "member_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.member_id)?;
+            total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("DescribedGroupMember", "member_id", version))?;
         }
         if version >= 4 {
             if version >= 5 {
-                total_size += types::CompactString.compute_size(&self.group_instance_id)?;
+                total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("DescribedGroupMember", "group_instance_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.group_instance_id)?;
+                total_size += types::String.compute_size(&self.group_instance_id).map_err(|e| e.with_field("DescribedGroupMember", "group_instance_id", version))?;
             }
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.client_id)?;
+            total_size += types::CompactString.compute_size(&self.client_id).map_err(|e| e.with_field("DescribedGroupMember", "client_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.client_id)?;
+            total_size += types::String.compute_size(&self.client_id).map_err(|e| e.with_field("DescribedGroupMember", "client_id", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.client_host)?;
+            total_size += types::CompactString.compute_size(&self.client_host).map_err(|e| e.with_field("DescribedGroupMember", "client_host", version))?;
         } else {
-            total_size += types::String.compute_size(&self.client_host)?;
+            total_size += types::String.compute_size(&self.client_host).map_err(|e| e.with_field("DescribedGroupMember", "client_host", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactBytes.compute_size(&self.member_metadata)?;
+            total_size += types::CompactBytes.compute_size(&self.member_metadata).map_err(|e| e.with_field("DescribedGroupMember", "member_metadata", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.member_metadata)?;
+            total_size += types::Bytes.compute_size(&self.member_metadata).map_err(|e| e.with_field("DescribedGroupMember", "member_metadata", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactBytes.compute_size(&self.member_assignment)?;
+            total_size += types::CompactBytes.compute_size(&self.member_assignment).map_err(|e| e.with_field("DescribedGroupMember", "member_assignment", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.member_assignment)?;
+            total_size += types::Bytes.compute_size(&self.member_assignment).map_err(|e| e.with_field("DescribedGroupMember", "member_assignment", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribedGroupMember", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribedGroupMember", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribedGroupMember", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -160,48 +175,48 @@ impl Encodable for DescribedGroupMember {
 
 impl Decodable for DescribedGroupMember {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let member_id = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "member_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "member_id", version, start - buf.remaining()))?
         };
         let group_instance_id = if version >= 4 {
             if version >= 5 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "group_instance_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "group_instance_id", version, start - buf.remaining()))?
             }
         } else {
             None
         };
         let client_id = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "client_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "client_id", version, start - buf.remaining()))?
         };
         let client_host = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "client_host", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "client_host", version, start - buf.remaining()))?
         };
         let member_metadata = if version >= 5 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "member_metadata", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "member_metadata", version, start - buf.remaining()))?
         };
         let member_assignment = if version >= 5 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "member_assignment", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "member_assignment", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedGroupMember", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribedGroupMember", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -226,7 +241,7 @@ impl Default for DescribedGroupMember {
             client_host: Default::default(),
             member_metadata: Default::default(),
             member_assignment: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -235,9 +250,103 @@ impl Message for DescribedGroupMember {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl DescribedGroupMember {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 4 {
+            self.group_instance_id = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 4 {
+            if !self.group_instance_id.is_none() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribedGroupMember {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        if version >= 4 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        node.insert("clientId".into(), JsonValue::to_json(&self.client_id));
+        node.insert("clientHost".into(), JsonValue::to_json(&self.client_host));
+        node.insert("memberMetadata".into(), JsonValue::to_json(&self.member_metadata));
+        node.insert("memberAssignment".into(), JsonValue::to_json(&self.member_assignment));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribedGroupMember")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "DescribedGroupMember.memberId")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroupMember", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "DescribedGroupMember.groupInstanceId")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("DescribedGroupMember", "groupInstanceId", version));
+        }
+        if let Some(value) = object.get("clientId") {
+            this.client_id = JsonValue::from_json(value, "DescribedGroupMember.clientId")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroupMember", "clientId", version));
+        }
+        if let Some(value) = object.get("clientHost") {
+            this.client_host = JsonValue::from_json(value, "DescribedGroupMember.clientHost")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroupMember", "clientHost", version));
+        }
+        if let Some(value) = object.get("memberMetadata") {
+            this.member_metadata = JsonValue::from_json(value, "DescribedGroupMember.memberMetadata")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroupMember", "memberMetadata", version));
+        }
+        if let Some(value) = object.get("memberAssignment") {
+            this.member_assignment = JsonValue::from_json(value, "DescribedGroupMember.memberAssignment")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroupMember", "memberAssignment", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribedGroup {
     /// The describe error, or 0 if there was no error.
@@ -253,16 +362,19 @@ pub struct DescribedGroup {
     /// The group state string, or the empty string.
     /// 
     /// Supported API versions: 0-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_state: StrBytes,
 
     /// The group protocol type, or the empty string.
     /// 
     /// Supported API versions: 0-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_type: StrBytes,
 
     /// The group protocol data, or the empty string.
     /// 
     /// Supported API versions: 0-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_data: StrBytes,
 
     /// The group members.
@@ -276,7 +388,7 @@ pub struct DescribedGroup {
     pub authorized_operations: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribedGroup {
@@ -289,95 +401,99 @@ impl Builder for DescribedGroup {
 
 impl Encodable for DescribedGroup {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("DescribedGroup", "error_code", version))?;
         if version >= 5 {
-            types::CompactString.encode(buf, &self.group_id)?;
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("DescribedGroup", "group_id", version))?;
         } else {
-            types::String.encode(buf, &self.group_id)?;
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("DescribedGroup", "group_id", version))?;
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.group_state)?;
+            types::CompactString.encode(buf, &self.group_state).map_err(|e| e.with_field("DescribedGroup", "group_state", version))?;
         } else {
-            types::String.encode(buf, &self.group_state)?;
+            types::String.encode(buf, &self.group_state).map_err(|e| e.with_field("DescribedGroup", "group_state", version))?;
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.protocol_type)?;
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("DescribedGroup", "protocol_type", version))?;
         } else {
-            types::String.encode(buf, &self.protocol_type)?;
+            types::String.encode(buf, &self.protocol_type).map_err(|e| e.with_field("DescribedGroup", "protocol_type", version))?;
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.protocol_data)?;
+            types::CompactString.encode(buf, &self.protocol_data).map_err(|e| e.with_field("DescribedGroup", "protocol_data", version))?;
         } else {
-            types::String.encode(buf, &self.protocol_data)?;
+            types::String.encode(buf, &self.protocol_data).map_err(|e| e.with_field("DescribedGroup", "protocol_data", version))?;
         }
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.members)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.members).map_err(|e| e.with_field("DescribedGroup", "members", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.members)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.members).map_err(|e| e.with_field("DescribedGroup", "members", version))?;
         }
         if version >= 3 {
-            types::Int32.encode(buf, &self.authorized_operations)?;
+            types::Int32.encode(buf, &self.authorized_operations).map_err(|e| e.with_field("DescribedGroup", "authorized_operations", version))?;
         } else {
             if self.authorized_operations != -2147483648 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribedGroup", "authorized_operations", version));
             }
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribedGroup", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribedGroup", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribedGroup", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("DescribedGroup", "error_code", version))?;
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.group_id)?;
+            total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("DescribedGroup", "group_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.group_id)?;
+            total_size += types::String.compute_size(&self.group_id).map_err(|e| e.with_field("DescribedGroup", "group_id", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.group_state)?;
+            total_size += types::CompactString.compute_size(&self.group_state).map_err(|e| e.with_field("DescribedGroup", "group_state", version))?;
         } else {
-            total_size += types::String.compute_size(&self.group_state)?;
+            total_size += types::String.compute_size(&self.group_state).map_err(|e| e.with_field("DescribedGroup", "group_state", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.protocol_type)?;
+            total_size += types::CompactString.compute_size(&self.protocol_type).map_err(|e| e.with_field("DescribedGroup", "protocol_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.protocol_type)?;
+            total_size += types::String.compute_size(&self.protocol_type).map_err(|e| e.with_field("DescribedGroup", "protocol_type", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.protocol_data)?;
+            total_size += types::CompactString.compute_size(&self.protocol_data).map_err(|e| e.with_field("DescribedGroup", "protocol_data", version))?;
         } else {
-            total_size += types::String.compute_size(&self.protocol_data)?;
+            total_size += types::String.compute_size(&self.protocol_data).map_err(|e| e.with_field("DescribedGroup", "protocol_data", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.members)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.members).map_err(|e| e.with_field("DescribedGroup", "members", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.members)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.members).map_err(|e| e.with_field("DescribedGroup", "members", version))?;
         }
         if version >= 3 {
-            total_size += types::Int32.compute_size(&self.authorized_operations)?;
+            total_size += types::Int32.compute_size(&self.authorized_operations).map_err(|e| e.with_field("DescribedGroup", "authorized_operations", version))?;
         } else {
             if self.authorized_operations != -2147483648 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("DescribedGroup", "authorized_operations", version));
             }
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribedGroup", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribedGroup", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribedGroup", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -385,45 +501,45 @@ impl Encodable for DescribedGroup {
 
 impl Decodable for DescribedGroup {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("DescribedGroup", "error_code", version, start - buf.remaining()))?;
         let group_id = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedGroup", "group_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedGroup", "group_id", version, start - buf.remaining()))?
         };
         let group_state = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedGroup", "group_state", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedGroup", "group_state", version, start - buf.remaining()))?
         };
         let protocol_type = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedGroup", "protocol_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedGroup", "protocol_type", version, start - buf.remaining()))?
         };
         let protocol_data = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("DescribedGroup", "protocol_data", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("DescribedGroup", "protocol_data", version, start - buf.remaining()))?
         };
         let members = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribedGroup", "members", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribedGroup", "members", version, start - buf.remaining()))?
         };
         let authorized_operations = if version >= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("DescribedGroup", "authorized_operations", version, start - buf.remaining()))?
         } else {
             -2147483648
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribedGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribedGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -450,7 +566,7 @@ impl Default for DescribedGroup {
             protocol_data: Default::default(),
             members: Default::default(),
             authorized_operations: -2147483648,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -459,9 +575,115 @@ impl Message for DescribedGroup {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl DescribedGroup {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.members.iter_mut() {
+            item.normalize_to(version);
+        }
+        if version < 3 {
+            self.authorized_operations = -2147483648;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.members.iter().enumerate() {
+            item.validate_into(version, &format!("{}members[{}].", path, i), invalid);
+        }
+        if version < 3 {
+            if self.authorized_operations != -2147483648 {
+                invalid.push(format!("{}authorized_operations", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribedGroup {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        node.insert("groupState".into(), JsonValue::to_json(&self.group_state));
+        node.insert("protocolType".into(), JsonValue::to_json(&self.protocol_type));
+        node.insert("protocolData".into(), JsonValue::to_json(&self.protocol_data));
+        node.insert("members".into(), serde_json::Value::Array(self.members.iter().map(|item| item.to_json_with(version, records)).collect()));
+        if version >= 3 {
+            node.insert("authorizedOperations".into(), JsonValue::to_json(&self.authorized_operations));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribedGroup")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "DescribedGroup.errorCode")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroup", "errorCode", version));
+        }
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "DescribedGroup.groupId")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroup", "groupId", version));
+        }
+        if let Some(value) = object.get("groupState") {
+            this.group_state = JsonValue::from_json(value, "DescribedGroup.groupState")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroup", "groupState", version));
+        }
+        if let Some(value) = object.get("protocolType") {
+            this.protocol_type = JsonValue::from_json(value, "DescribedGroup.protocolType")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroup", "protocolType", version));
+        }
+        if let Some(value) = object.get("protocolData") {
+            this.protocol_data = JsonValue::from_json(value, "DescribedGroup.protocolData")?;
+        } else {
+            return Err(crate::json::missing("DescribedGroup", "protocolData", version));
+        }
+        if let Some(value) = object.get("members") {
+            this.members = crate::json::array(value, "DescribedGroup.members")?.iter().map(|item| DescribedGroupMember::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribedGroup", "members", version));
+        }
+        if let Some(value) = object.get("authorizedOperations") {
+            this.authorized_operations = JsonValue::from_json(value, "DescribedGroup.authorizedOperations")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("DescribedGroup", "authorizedOperations", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct DescribeGroupsResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -475,7 +697,7 @@ pub struct DescribeGroupsResponse {
     pub groups: Vec<DescribedGroup>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for DescribeGroupsResponse {
@@ -488,45 +710,55 @@ impl Builder for DescribeGroupsResponse {
 
 impl Encodable for DescribeGroupsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeGroupsResponse", version));
+        }
         if version >= 1 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeGroupsResponse", "throttle_time_ms", version))?;
         }
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.groups)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.groups).map_err(|e| e.with_field("DescribeGroupsResponse", "groups", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.groups)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.groups).map_err(|e| e.with_field("DescribeGroupsResponse", "groups", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeGroupsResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeGroupsResponse", version));
+        }
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("DescribeGroupsResponse", "throttle_time_ms", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.groups)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.groups).map_err(|e| e.with_field("DescribeGroupsResponse", "groups", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.groups)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.groups).map_err(|e| e.with_field("DescribeGroupsResponse", "groups", version))?;
         }
         if version >= 5 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeGroupsResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -534,24 +766,27 @@ impl Encodable for DescribeGroupsResponse {
 
 impl Decodable for DescribeGroupsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DescribeGroupsResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("DescribeGroupsResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
         let groups = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeGroupsResponse", "groups", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("DescribeGroupsResponse", "groups", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 5 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -568,7 +803,7 @@ impl Default for DescribeGroupsResponse {
         Self {
             throttle_time_ms: 0,
             groups: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -577,6 +812,119 @@ impl Message for DescribeGroupsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl DescribeGroupsResponse {
+    /// Encode the message with the elements of `groups` produced by an iterator of known length, in place of
+    /// the `groups` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_groups<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, groups: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = DescribedGroup>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DescribeGroupsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let groups = groups.into_iter();
+        if version >= 1 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("DescribeGroupsResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 5 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(groups)).map_err(|e| e.with_field("DescribeGroupsResponse", "groups", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(groups)).map_err(|e| e.with_field("DescribeGroupsResponse", "groups", version))?;
+        }
+        if version >= 5 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DescribeGroupsResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DescribeGroupsResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl DescribeGroupsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.throttle_time_ms = 0;
+        }
+        for item in self.groups.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        for (i, item) in self.groups.iter().enumerate() {
+            item.validate_into(version, &format!("{}groups[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl DescribeGroupsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        node.insert("groups".into(), serde_json::Value::Array(self.groups.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DescribeGroupsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "DescribeGroupsResponse.throttleTimeMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("DescribeGroupsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("groups") {
+            this.groups = crate::json::array(value, "DescribeGroupsResponse.groups")?.iter().map(|item| DescribedGroup::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("DescribeGroupsResponse", "groups", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for DescribeGroupsResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 5 {
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicPartitions {
     /// The partitions of this topic whose leader should be elected.
@@ -28,7 +34,7 @@ pub struct TopicPartitions {
     pub partitions: Vec<i32>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TopicPartitions {
@@ -42,49 +48,53 @@ impl Builder for TopicPartitions {
 impl MapEncodable for TopicPartitions {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("TopicPartitions", "topic", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("TopicPartitions", "topic", version))?;
         }
         if version >= 2 {
-            types::CompactArray(types::Int32).encode(buf, &self.partitions)?;
+            types::CompactArray(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicPartitions", "partitions", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.partitions)?;
+            types::Array(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("TopicPartitions", "partitions", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicPartitions", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("TopicPartitions", "topic", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("TopicPartitions", "topic", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("TopicPartitions", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.partitions)?;
+            total_size += types::Array(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("TopicPartitions", "partitions", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicPartitions", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -93,24 +103,24 @@ impl MapEncodable for TopicPartitions {
 impl MapDecodable for TopicPartitions {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicPartitions", "topic", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("TopicPartitions", "topic", version, start - buf.remaining()))?
         };
         let partitions = if version >= 2 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("TopicPartitions", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("TopicPartitions", "partitions", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TopicPartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -125,7 +135,7 @@ impl Default for TopicPartitions {
     fn default() -> Self {
         Self {
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -134,9 +144,70 @@ impl Message for TopicPartitions {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl TopicPartitions {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicPartitions {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topic".into(), JsonValue::to_json(key));
+        node.insert("partitions".into(), JsonValue::to_json(&self.partitions));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "TopicPartitions")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("topic") {
+            key = JsonValue::from_json(value, "TopicPartitions.topic")?;
+        } else {
+            return Err(crate::json::missing("TopicPartitions", "topic", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = JsonValue::from_json(value, "TopicPartitions.partitions")?;
+        } else {
+            return Err(crate::json::missing("TopicPartitions", "partitions", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ElectLeadersRequest {
     /// Type of elections to conduct for the partition. A value of '0' elects the preferred replica. A value of '1' elects the first live replica if there are no in-sync replica.
@@ -147,6 +218,7 @@ pub struct ElectLeadersRequest {
     /// The topic partitions to elect leaders.
     /// 
     /// Supported API versions: 0-2
+    /// May be `None` in versions: 0-2
     pub topic_partitions: Option<indexmap::IndexMap<super::TopicName, TopicPartitions>>,
 
     /// The time in ms to wait for the election to complete.
@@ -155,7 +227,7 @@ pub struct ElectLeadersRequest {
     pub timeout_ms: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ElectLeadersRequest {
@@ -168,55 +240,65 @@ impl Builder for ElectLeadersRequest {
 
 impl Encodable for ElectLeadersRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ElectLeadersRequest", version));
+        }
         if version >= 1 {
-            types::Int8.encode(buf, &self.election_type)?;
+            types::Int8.encode(buf, &self.election_type).map_err(|e| e.with_field("ElectLeadersRequest", "election_type", version))?;
         } else {
             if self.election_type != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ElectLeadersRequest", "election_type", version));
             }
         }
         if version >= 2 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topic_partitions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_partitions).map_err(|e| e.with_field("ElectLeadersRequest", "topic_partitions", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topic_partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topic_partitions).map_err(|e| e.with_field("ElectLeadersRequest", "topic_partitions", version))?;
         }
-        types::Int32.encode(buf, &self.timeout_ms)?;
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("ElectLeadersRequest", "timeout_ms", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ElectLeadersRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ElectLeadersRequest", version));
+        }
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.election_type)?;
+            total_size += types::Int8.compute_size(&self.election_type).map_err(|e| e.with_field("ElectLeadersRequest", "election_type", version))?;
         } else {
             if self.election_type != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ElectLeadersRequest", "election_type", version));
             }
         }
         if version >= 2 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topic_partitions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topic_partitions).map_err(|e| e.with_field("ElectLeadersRequest", "topic_partitions", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topic_partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topic_partitions).map_err(|e| e.with_field("ElectLeadersRequest", "topic_partitions", version))?;
         }
-        total_size += types::Int32.compute_size(&self.timeout_ms)?;
+        total_size += types::Int32.compute_size(&self.timeout_ms).map_err(|e| e.with_field("ElectLeadersRequest", "timeout_ms", version))?;
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ElectLeadersRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -224,25 +306,28 @@ impl Encodable for ElectLeadersRequest {
 
 impl Decodable for ElectLeadersRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ElectLeadersRequest", version));
+        }
+        let start = buf.remaining();
         let election_type = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("ElectLeadersRequest", "election_type", version, start - buf.remaining()))?
         } else {
             0
         };
         let topic_partitions = if version >= 2 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ElectLeadersRequest", "topic_partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ElectLeadersRequest", "topic_partitions", version, start - buf.remaining()))?
         };
-        let timeout_ms = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("ElectLeadersRequest", "timeout_ms", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -261,7 +346,7 @@ impl Default for ElectLeadersRequest {
             election_type: 0,
             topic_partitions: Some(Default::default()),
             timeout_ms: 60000,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -270,6 +355,134 @@ impl Message for ElectLeadersRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl ElectLeadersRequest {
+    /// Encode the message with the elements of `topic_partitions` produced by an iterator of known length, in place of
+    /// the `topic_partitions` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topic_partitions<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topic_partitions: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, TopicPartitions)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ElectLeadersRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topic_partitions = topic_partitions.into_iter();
+        if version >= 1 {
+            types::Int8.encode(buf, &self.election_type).map_err(|e| e.with_field("ElectLeadersRequest", "election_type", version))?;
+        } else {
+            if self.election_type != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ElectLeadersRequest", "election_type", version));
+            }
+        }
+        if version >= 2 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topic_partitions)).map_err(|e| e.with_field("ElectLeadersRequest", "topic_partitions", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topic_partitions)).map_err(|e| e.with_field("ElectLeadersRequest", "topic_partitions", version))?;
+        }
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("ElectLeadersRequest", "timeout_ms", version))?;
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ElectLeadersRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ElectLeadersRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl ElectLeadersRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.election_type = 0;
+        }
+        if let Some(items) = &mut self.topic_partitions {
+            for item in items.values_mut() {
+                item.normalize_to(version);
+            }
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.election_type != 0 {
+                invalid.push(format!("{}election_type", path));
+            }
+        }
+        if let Some(items) = &self.topic_partitions {
+            for (i, item) in items.values().enumerate() {
+                item.validate_into(version, &format!("{}topic_partitions[{}].", path, i), invalid);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ElectLeadersRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("electionType".into(), JsonValue::to_json(&self.election_type));
+        }
+        node.insert("topicPartitions".into(), self.topic_partitions.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        node.insert("timeoutMs".into(), JsonValue::to_json(&self.timeout_ms));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ElectLeadersRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("electionType") {
+            this.election_type = JsonValue::from_json(value, "ElectLeadersRequest.electionType")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("ElectLeadersRequest", "electionType", version));
+        }
+        if let Some(value) = object.get("topicPartitions") {
+            this.topic_partitions = if value.is_null() { None } else { Some(crate::json::array(value, "ElectLeadersRequest.topicPartitions")?.iter().map(|item| TopicPartitions::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else {
+            return Err(crate::json::missing("ElectLeadersRequest", "topicPartitions", version));
+        }
+        if let Some(value) = object.get("timeoutMs") {
+            this.timeout_ms = JsonValue::from_json(value, "ElectLeadersRequest.timeoutMs")?;
+        } else {
+            return Err(crate::json::missing("ElectLeadersRequest", "timeoutMs", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for ElectLeadersRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -280,3 +493,33 @@ impl HeaderVersion for ElectLeadersRequest {
     }
 }
 
+impl ElectLeadersRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::ElectLeadersResponse {
+        let mut response = super::ElectLeadersResponse::default();
+        if version >= 1 {
+            response.error_code = error.code();
+        }
+        let mut items0 = Vec::new();
+        for (key0, request0) in self.topic_partitions.iter().flatten() {
+            let mut response0 = super::elect_leaders_response::ReplicaElectionResult::default();
+            response0.topic = key0.clone();
+            let mut items1 = Vec::new();
+            for request1 in &request0.partitions {
+                let mut response1 = super::elect_leaders_response::PartitionResult::default();
+                response1.partition_id = *request1;
+                response1.error_code = error.code();
+                items1.push(response1);
+            }
+            response0.partition_result = items1;
+            items0.push(response0);
+        }
+        response.replica_election_results = items0;
+        response
+    }
+}
+
@@ -5,21 +5,29 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+use bytes::Buf;
+use crate::protocol::view::{self, ArrayView};
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-15
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ReplicaState {
     /// The replica ID of the follower, or -1 if this request is from a consumer.
@@ -33,7 +41,7 @@ pub struct ReplicaState {
     pub replica_epoch: i64,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ReplicaState {
@@ -46,57 +54,61 @@ impl Builder for ReplicaState {
 
 impl Encodable for ReplicaState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 15 {
-            types::Int32.encode(buf, &self.replica_id)?;
+            types::Int32.encode(buf, &self.replica_id).map_err(|e| e.with_field("ReplicaState", "replica_id", version))?;
         } else {
             if self.replica_id != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ReplicaState", "replica_id", version));
             }
         }
         if version >= 15 {
-            types::Int64.encode(buf, &self.replica_epoch)?;
+            types::Int64.encode(buf, &self.replica_epoch).map_err(|e| e.with_field("ReplicaState", "replica_epoch", version))?;
         } else {
             if self.replica_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ReplicaState", "replica_epoch", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReplicaState", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ReplicaState", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ReplicaState", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 15 {
-            total_size += types::Int32.compute_size(&self.replica_id)?;
+            total_size += types::Int32.compute_size(&self.replica_id).map_err(|e| e.with_field("ReplicaState", "replica_id", version))?;
         } else {
             if self.replica_id != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ReplicaState", "replica_id", version));
             }
         }
         if version >= 15 {
-            total_size += types::Int64.compute_size(&self.replica_epoch)?;
+            total_size += types::Int64.compute_size(&self.replica_epoch).map_err(|e| e.with_field("ReplicaState", "replica_epoch", version))?;
         } else {
             if self.replica_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ReplicaState", "replica_epoch", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ReplicaState", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ReplicaState", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ReplicaState", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -104,24 +116,24 @@ impl Encodable for ReplicaState {
 
 impl Decodable for ReplicaState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let replica_id = if version >= 15 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("ReplicaState", "replica_id", version, start - buf.remaining()))?
         } else {
             (-1).into()
         };
         let replica_epoch = if version >= 15 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("ReplicaState", "replica_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReplicaState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReplicaState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReplicaState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ReplicaState", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -138,7 +150,7 @@ impl Default for ReplicaState {
         Self {
             replica_id: (-1).into(),
             replica_epoch: -1,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -147,9 +159,161 @@ impl Message for ReplicaState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl ReplicaState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 15 {
+            self.replica_id = (-1).into();
+        }
+        if version < 15 {
+            self.replica_epoch = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 15 {
+            if self.replica_id != -1 {
+                invalid.push(format!("{}replica_id", path));
+            }
+        }
+        if version < 15 {
+            if self.replica_epoch != -1 {
+                invalid.push(format!("{}replica_epoch", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ReplicaState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 15 {
+            node.insert("replicaId".into(), JsonValue::to_json(&self.replica_id));
+        }
+        if version >= 15 {
+            node.insert("replicaEpoch".into(), JsonValue::to_json(&self.replica_epoch));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ReplicaState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("replicaId") {
+            this.replica_id = JsonValue::from_json(value, "ReplicaState.replicaId")?;
+        } else if version >= 15 {
+            return Err(crate::json::missing("ReplicaState", "replicaId", version));
+        }
+        if let Some(value) = object.get("replicaEpoch") {
+            this.replica_epoch = JsonValue::from_json(value, "ReplicaState.replicaEpoch")?;
+        } else if version >= 15 {
+            return Err(crate::json::missing("ReplicaState", "replicaEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ReplicaState`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplicaStateView {
+    /// The replica ID of the follower, or -1 if this request is from a consumer.
+    /// 
+    /// Supported API versions: 15
+    pub replica_id: super::BrokerId,
+
+    /// The epoch of this follower, or -1 if not available.
+    /// 
+    /// Supported API versions: 15
+    pub replica_epoch: i64,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ReplicaStateView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let replica_id = if version >= 15 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("ReplicaState", "replica_id", version, start - buf.remaining()))?
+        } else {
+            (-1).into()
+        };
+        let replica_epoch = if version >= 15 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("ReplicaState", "replica_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ReplicaState", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ReplicaState", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            replica_id,
+            replica_epoch,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 15 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("ReplicaState", "replica_id", version, start - buf.remaining()))?;
+        }
+        if version >= 15 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("ReplicaState", "replica_epoch", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("ReplicaState", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ReplicaStateView {
+    fn default() -> Self {
+        Self {
+            replica_id: (-1).into(),
+            replica_epoch: -1,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-15
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct FetchPartition {
     /// The partition index.
@@ -183,7 +347,7 @@ pub struct FetchPartition {
     pub partition_max_bytes: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for FetchPartition {
@@ -196,61 +360,65 @@ impl Builder for FetchPartition {
 
 impl Encodable for FetchPartition {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition).map_err(|e| e.with_field("FetchPartition", "partition", version))?;
         if version >= 9 {
-            types::Int32.encode(buf, &self.current_leader_epoch)?;
+            types::Int32.encode(buf, &self.current_leader_epoch).map_err(|e| e.with_field("FetchPartition", "current_leader_epoch", version))?;
         }
-        types::Int64.encode(buf, &self.fetch_offset)?;
+        types::Int64.encode(buf, &self.fetch_offset).map_err(|e| e.with_field("FetchPartition", "fetch_offset", version))?;
         if version >= 12 {
-            types::Int32.encode(buf, &self.last_fetched_epoch)?;
+            types::Int32.encode(buf, &self.last_fetched_epoch).map_err(|e| e.with_field("FetchPartition", "last_fetched_epoch", version))?;
         } else {
             if self.last_fetched_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchPartition", "last_fetched_epoch", version));
             }
         }
         if version >= 5 {
-            types::Int64.encode(buf, &self.log_start_offset)?;
+            types::Int64.encode(buf, &self.log_start_offset).map_err(|e| e.with_field("FetchPartition", "log_start_offset", version))?;
         }
-        types::Int32.encode(buf, &self.partition_max_bytes)?;
+        types::Int32.encode(buf, &self.partition_max_bytes).map_err(|e| e.with_field("FetchPartition", "partition_max_bytes", version))?;
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchPartition", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FetchPartition", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FetchPartition", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition)?;
+        total_size += types::Int32.compute_size(&self.partition).map_err(|e| e.with_field("FetchPartition", "partition", version))?;
         if version >= 9 {
-            total_size += types::Int32.compute_size(&self.current_leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.current_leader_epoch).map_err(|e| e.with_field("FetchPartition", "current_leader_epoch", version))?;
         }
-        total_size += types::Int64.compute_size(&self.fetch_offset)?;
+        total_size += types::Int64.compute_size(&self.fetch_offset).map_err(|e| e.with_field("FetchPartition", "fetch_offset", version))?;
         if version >= 12 {
-            total_size += types::Int32.compute_size(&self.last_fetched_epoch)?;
+            total_size += types::Int32.compute_size(&self.last_fetched_epoch).map_err(|e| e.with_field("FetchPartition", "last_fetched_epoch", version))?;
         } else {
             if self.last_fetched_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchPartition", "last_fetched_epoch", version));
             }
         }
         if version >= 5 {
-            total_size += types::Int64.compute_size(&self.log_start_offset)?;
+            total_size += types::Int64.compute_size(&self.log_start_offset).map_err(|e| e.with_field("FetchPartition", "log_start_offset", version))?;
         }
-        total_size += types::Int32.compute_size(&self.partition_max_bytes)?;
+        total_size += types::Int32.compute_size(&self.partition_max_bytes).map_err(|e| e.with_field("FetchPartition", "partition_max_bytes", version))?;
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchPartition", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FetchPartition", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FetchPartition", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -258,32 +426,32 @@ impl Encodable for FetchPartition {
 
 impl Decodable for FetchPartition {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let partition = types::Int32.decode(buf).map_err(|e| e.with_field("FetchPartition", "partition", version, start - buf.remaining()))?;
         let current_leader_epoch = if version >= 9 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchPartition", "current_leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let fetch_offset = types::Int64.decode(buf)?;
+        let fetch_offset = types::Int64.decode(buf).map_err(|e| e.with_field("FetchPartition", "fetch_offset", version, start - buf.remaining()))?;
         let last_fetched_epoch = if version >= 12 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchPartition", "last_fetched_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let log_start_offset = if version >= 5 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("FetchPartition", "log_start_offset", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let partition_max_bytes = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let partition_max_bytes = types::Int32.decode(buf).map_err(|e| e.with_field("FetchPartition", "partition_max_bytes", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FetchPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -308,7 +476,7 @@ impl Default for FetchPartition {
             last_fetched_epoch: -1,
             log_start_offset: -1,
             partition_max_bytes: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -317,9 +485,237 @@ impl Message for FetchPartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl FetchPartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 9 {
+            self.current_leader_epoch = -1;
+        }
+        if version < 12 {
+            self.last_fetched_epoch = -1;
+        }
+        if version < 5 {
+            self.log_start_offset = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 9 {
+            if self.current_leader_epoch != -1 {
+                invalid.push(format!("{}current_leader_epoch", path));
+            }
+        }
+        if version < 12 {
+            if self.last_fetched_epoch != -1 {
+                invalid.push(format!("{}last_fetched_epoch", path));
+            }
+        }
+        if version < 5 {
+            if self.log_start_offset != -1 {
+                invalid.push(format!("{}log_start_offset", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FetchPartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partition".into(), JsonValue::to_json(&self.partition));
+        if version >= 9 {
+            node.insert("currentLeaderEpoch".into(), JsonValue::to_json(&self.current_leader_epoch));
+        }
+        node.insert("fetchOffset".into(), JsonValue::to_json(&self.fetch_offset));
+        if version >= 12 {
+            node.insert("lastFetchedEpoch".into(), JsonValue::to_json(&self.last_fetched_epoch));
+        }
+        if version >= 5 {
+            node.insert("logStartOffset".into(), JsonValue::to_json(&self.log_start_offset));
+        }
+        node.insert("partitionMaxBytes".into(), JsonValue::to_json(&self.partition_max_bytes));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "FetchPartition")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partition") {
+            this.partition = JsonValue::from_json(value, "FetchPartition.partition")?;
+        } else {
+            return Err(crate::json::missing("FetchPartition", "partition", version));
+        }
+        if let Some(value) = object.get("currentLeaderEpoch") {
+            this.current_leader_epoch = JsonValue::from_json(value, "FetchPartition.currentLeaderEpoch")?;
+        } else if version >= 9 {
+            return Err(crate::json::missing("FetchPartition", "currentLeaderEpoch", version));
+        }
+        if let Some(value) = object.get("fetchOffset") {
+            this.fetch_offset = JsonValue::from_json(value, "FetchPartition.fetchOffset")?;
+        } else {
+            return Err(crate::json::missing("FetchPartition", "fetchOffset", version));
+        }
+        if let Some(value) = object.get("lastFetchedEpoch") {
+            this.last_fetched_epoch = JsonValue::from_json(value, "FetchPartition.lastFetchedEpoch")?;
+        } else if version >= 12 {
+            return Err(crate::json::missing("FetchPartition", "lastFetchedEpoch", version));
+        }
+        if let Some(value) = object.get("logStartOffset") {
+            this.log_start_offset = JsonValue::from_json(value, "FetchPartition.logStartOffset")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("FetchPartition", "logStartOffset", version));
+        }
+        if let Some(value) = object.get("partitionMaxBytes") {
+            this.partition_max_bytes = JsonValue::from_json(value, "FetchPartition.partitionMaxBytes")?;
+        } else {
+            return Err(crate::json::missing("FetchPartition", "partitionMaxBytes", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`FetchPartition`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchPartitionView {
+    /// The partition index.
+    /// 
+    /// Supported API versions: 0-15
+    pub partition: i32,
+
+    /// The current leader epoch of the partition.
+    /// 
+    /// Supported API versions: 9-15
+    pub current_leader_epoch: i32,
+
+    /// The message offset.
+    /// 
+    /// Supported API versions: 0-15
+    pub fetch_offset: i64,
+
+    /// The epoch of the last fetched record or -1 if there is none
+    /// 
+    /// Supported API versions: 12-15
+    pub last_fetched_epoch: i32,
+
+    /// The earliest available offset of the follower replica.  The field is only used when the request is sent by the follower.
+    /// 
+    /// Supported API versions: 5-15
+    pub log_start_offset: i64,
+
+    /// The maximum bytes to fetch from this partition.  See KIP-74 for cases where this limit may not be honored.
+    /// 
+    /// Supported API versions: 0-15
+    pub partition_max_bytes: i32,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl FetchPartitionView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let partition = types::Int32.decode(buf).map_err(|e| e.with_field("FetchPartition", "partition", version, start - buf.remaining()))?;
+        let current_leader_epoch = if version >= 9 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchPartition", "current_leader_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let fetch_offset = types::Int64.decode(buf).map_err(|e| e.with_field("FetchPartition", "fetch_offset", version, start - buf.remaining()))?;
+        let last_fetched_epoch = if version >= 12 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchPartition", "last_fetched_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let log_start_offset = if version >= 5 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("FetchPartition", "log_start_offset", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let partition_max_bytes = types::Int32.decode(buf).map_err(|e| e.with_field("FetchPartition", "partition_max_bytes", version, start - buf.remaining()))?;
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchPartition", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("FetchPartition", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            partition,
+            current_leader_epoch,
+            fetch_offset,
+            last_fetched_epoch,
+            log_start_offset,
+            partition_max_bytes,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("FetchPartition", "partition", version, start - buf.remaining()))?;
+        if version >= 9 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("FetchPartition", "current_leader_epoch", version, start - buf.remaining()))?;
+        }
+        view::skip_fixed(buf, 8).map_err(|e| e.with_field("FetchPartition", "fetch_offset", version, start - buf.remaining()))?;
+        if version >= 12 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("FetchPartition", "last_fetched_epoch", version, start - buf.remaining()))?;
+        }
+        if version >= 5 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("FetchPartition", "log_start_offset", version, start - buf.remaining()))?;
+        }
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("FetchPartition", "partition_max_bytes", version, start - buf.remaining()))?;
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("FetchPartition", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for FetchPartitionView {
+    fn default() -> Self {
+        Self {
+            partition: 0,
+            current_leader_epoch: -1,
+            fetch_offset: 0,
+            last_fetched_epoch: -1,
+            log_start_offset: -1,
+            partition_max_bytes: 0,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-15
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct FetchTopic {
     /// The name of the topic to fetch.
@@ -338,7 +734,7 @@ pub struct FetchTopic {
     pub partitions: Vec<FetchPartition>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for FetchTopic {
@@ -351,59 +747,63 @@ impl Builder for FetchTopic {
 
 impl Encodable for FetchTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 12 {
             if version >= 12 {
-                types::CompactString.encode(buf, &self.topic)?;
+                types::CompactString.encode(buf, &self.topic).map_err(|e| e.with_field("FetchTopic", "topic", version))?;
             } else {
-                types::String.encode(buf, &self.topic)?;
+                types::String.encode(buf, &self.topic).map_err(|e| e.with_field("FetchTopic", "topic", version))?;
             }
         }
         if version >= 13 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("FetchTopic", "topic_id", version))?;
         }
         if version >= 12 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("FetchTopic", "partitions", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("FetchTopic", "partitions", version))?;
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FetchTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FetchTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 12 {
             if version >= 12 {
-                total_size += types::CompactString.compute_size(&self.topic)?;
+                total_size += types::CompactString.compute_size(&self.topic).map_err(|e| e.with_field("FetchTopic", "topic", version))?;
             } else {
-                total_size += types::String.compute_size(&self.topic)?;
+                total_size += types::String.compute_size(&self.topic).map_err(|e| e.with_field("FetchTopic", "topic", version))?;
             }
         }
         if version >= 13 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("FetchTopic", "topic_id", version))?;
         }
         if version >= 12 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("FetchTopic", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("FetchTopic", "partitions", version))?;
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FetchTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FetchTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -411,33 +811,33 @@ impl Encodable for FetchTopic {
 
 impl Decodable for FetchTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic = if version <= 12 {
             if version >= 12 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FetchTopic", "topic", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("FetchTopic", "topic", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let topic_id = if version >= 13 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("FetchTopic", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
         let partitions = if version >= 12 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchTopic", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchTopic", "partitions", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FetchTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -456,7 +856,7 @@ impl Default for FetchTopic {
             topic: Default::default(),
             topic_id: Uuid::nil(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -465,14 +865,105 @@ impl Message for FetchTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl FetchTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 12 {
+            self.topic = Default::default();
+        }
+        if version < 13 {
+            self.topic_id = Uuid::nil();
+        }
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 12 {
+            if !self.topic.is_empty() {
+                invalid.push(format!("{}topic", path));
+            }
+        }
+        if version < 13 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FetchTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 12 {
+            node.insert("topic".into(), JsonValue::to_json(&self.topic));
+        }
+        if version >= 13 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "FetchTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topic") {
+            this.topic = JsonValue::from_json(value, "FetchTopic.topic")?;
+        } else if version <= 12 {
+            return Err(crate::json::missing("FetchTopic", "topic", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "FetchTopic.topicId")?;
+        } else if version >= 13 {
+            return Err(crate::json::missing("FetchTopic", "topicId", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "FetchTopic.partitions")?.iter().map(|item| FetchPartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("FetchTopic", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`FetchTopic`], decoded without allocating from the buffer it borrows.
+///
 /// Valid versions: 0-15
 #[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
-#[builder(default)]
-pub struct ForgottenTopic {
-    /// The topic name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchTopicView {
+    /// The name of the topic to fetch.
     /// 
-    /// Supported API versions: 7-12
+    /// Supported API versions: 0-12
     pub topic: super::TopicName,
 
     /// The unique topic ID
@@ -480,90 +971,192 @@ pub struct ForgottenTopic {
     /// Supported API versions: 13-15
     pub topic_id: Uuid,
 
-    /// The partitions indexes to forget.
+    /// The partitions to fetch.
     /// 
-    /// Supported API versions: 7-15
-    pub partitions: Vec<i32>,
+    /// Supported API versions: 0-15
+    pub partitions: ArrayView<FetchPartitionView>,
 
-    /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
 }
 
-impl Builder for ForgottenTopic {
-    type Builder = ForgottenTopicBuilder;
-
-    fn builder() -> Self::Builder{
+impl FetchTopicView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let topic = if version <= 12 {
+            if version >= 12 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FetchTopic", "topic", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("FetchTopic", "topic", version, start - buf.remaining()))?
+            }
+        } else {
+            Default::default()
+        };
+        let topic_id = if version >= 13 {
+            types::Uuid.decode(buf).map_err(|e| e.with_field("FetchTopic", "topic_id", version, start - buf.remaining()))?
+        } else {
+            Uuid::nil()
+        };
+        let partitions = if version >= 12 {
+            ArrayView::decode(buf, version, true, FetchPartitionView::decode, FetchPartitionView::skip).map_err(|e| e.with_field("FetchTopic", "partitions", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, FetchPartitionView::decode, FetchPartitionView::skip).map_err(|e| e.with_field("FetchTopic", "partitions", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchTopic", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("FetchTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            topic,
+            topic_id,
+            partitions,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version <= 12 {
+            if version >= 12 {
+                view::skip_string(buf, true).map_err(|e| e.with_field("FetchTopic", "topic", version, start - buf.remaining()))?;
+            } else {
+                view::skip_string(buf, false).map_err(|e| e.with_field("FetchTopic", "topic", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 13 {
+            view::skip_fixed(buf, 16).map_err(|e| e.with_field("FetchTopic", "topic_id", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_array(buf, version, true, FetchPartitionView::skip).map_err(|e| e.with_field("FetchTopic", "partitions", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, FetchPartitionView::skip).map_err(|e| e.with_field("FetchTopic", "partitions", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("FetchTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for FetchTopicView {
+    fn default() -> Self {
+        Self {
+            topic: Default::default(),
+            topic_id: Uuid::nil(),
+            partitions: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct ForgottenTopic {
+    /// The topic name.
+    /// 
+    /// Supported API versions: 7-12
+    pub topic: super::TopicName,
+
+    /// The unique topic ID
+    /// 
+    /// Supported API versions: 13-15
+    pub topic_id: Uuid,
+
+    /// The partitions indexes to forget.
+    /// 
+    /// Supported API versions: 7-15
+    pub partitions: Vec<i32>,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for ForgottenTopic {
+    type Builder = ForgottenTopicBuilder;
+
+    fn builder() -> Self::Builder{
         ForgottenTopicBuilder::default()
     }
 }
 
 impl Encodable for ForgottenTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 7 && version <= 12 {
             if version >= 12 {
-                types::CompactString.encode(buf, &self.topic)?;
+                types::CompactString.encode(buf, &self.topic).map_err(|e| e.with_field("ForgottenTopic", "topic", version))?;
             } else {
-                types::String.encode(buf, &self.topic)?;
+                types::String.encode(buf, &self.topic).map_err(|e| e.with_field("ForgottenTopic", "topic", version))?;
             }
         }
         if version >= 13 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("ForgottenTopic", "topic_id", version))?;
         }
         if version >= 7 {
             if version >= 12 {
-                types::CompactArray(types::Int32).encode(buf, &self.partitions)?;
+                types::CompactArray(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("ForgottenTopic", "partitions", version))?;
             } else {
-                types::Array(types::Int32).encode(buf, &self.partitions)?;
+                types::Array(types::Int32).encode(buf, &self.partitions).map_err(|e| e.with_field("ForgottenTopic", "partitions", version))?;
             }
         } else {
             if !self.partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ForgottenTopic", "partitions", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ForgottenTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ForgottenTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ForgottenTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 7 && version <= 12 {
             if version >= 12 {
-                total_size += types::CompactString.compute_size(&self.topic)?;
+                total_size += types::CompactString.compute_size(&self.topic).map_err(|e| e.with_field("ForgottenTopic", "topic", version))?;
             } else {
-                total_size += types::String.compute_size(&self.topic)?;
+                total_size += types::String.compute_size(&self.topic).map_err(|e| e.with_field("ForgottenTopic", "topic", version))?;
             }
         }
         if version >= 13 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("ForgottenTopic", "topic_id", version))?;
         }
         if version >= 7 {
             if version >= 12 {
-                total_size += types::CompactArray(types::Int32).compute_size(&self.partitions)?;
+                total_size += types::CompactArray(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("ForgottenTopic", "partitions", version))?;
             } else {
-                total_size += types::Array(types::Int32).compute_size(&self.partitions)?;
+                total_size += types::Array(types::Int32).compute_size(&self.partitions).map_err(|e| e.with_field("ForgottenTopic", "partitions", version))?;
             }
         } else {
             if !self.partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ForgottenTopic", "partitions", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ForgottenTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ForgottenTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ForgottenTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -571,37 +1164,37 @@ impl Encodable for ForgottenTopic {
 
 impl Decodable for ForgottenTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic = if version >= 7 && version <= 12 {
             if version >= 12 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "topic", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "topic", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let topic_id = if version >= 13 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
         let partitions = if version >= 7 {
             if version >= 12 {
-                types::CompactArray(types::Int32).decode(buf)?
+                types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("ForgottenTopic", "partitions", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Int32).decode(buf)?
+                types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("ForgottenTopic", "partitions", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ForgottenTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -620,7 +1213,7 @@ impl Default for ForgottenTopic {
             topic: Default::default(),
             topic_id: Uuid::nil(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -629,14 +1222,215 @@ impl Message for ForgottenTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl ForgottenTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 7 || version > 12 {
+            self.topic = Default::default();
+        }
+        if version < 13 {
+            self.topic_id = Uuid::nil();
+        }
+        if version < 7 {
+            self.partitions = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 7 || version > 12 {
+            if !self.topic.is_empty() {
+                invalid.push(format!("{}topic", path));
+            }
+        }
+        if version < 13 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        if version < 7 {
+            if !self.partitions.is_empty() {
+                invalid.push(format!("{}partitions", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ForgottenTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 7 && version <= 12 {
+            node.insert("topic".into(), JsonValue::to_json(&self.topic));
+        }
+        if version >= 13 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        if version >= 7 {
+            node.insert("partitions".into(), JsonValue::to_json(&self.partitions));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ForgottenTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topic") {
+            this.topic = JsonValue::from_json(value, "ForgottenTopic.topic")?;
+        } else if version >= 7 && version <= 12 {
+            return Err(crate::json::missing("ForgottenTopic", "topic", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "ForgottenTopic.topicId")?;
+        } else if version >= 13 {
+            return Err(crate::json::missing("ForgottenTopic", "topicId", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = JsonValue::from_json(value, "ForgottenTopic.partitions")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("ForgottenTopic", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ForgottenTopic`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForgottenTopicView {
+    /// The topic name.
+    /// 
+    /// Supported API versions: 7-12
+    pub topic: super::TopicName,
+
+    /// The unique topic ID
+    /// 
+    /// Supported API versions: 13-15
+    pub topic_id: Uuid,
+
+    /// The partitions indexes to forget.
+    /// 
+    /// Supported API versions: 7-15
+    pub partitions: ArrayView<i32>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ForgottenTopicView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let topic = if version >= 7 && version <= 12 {
+            if version >= 12 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "topic", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "topic", version, start - buf.remaining()))?
+            }
+        } else {
+            Default::default()
+        };
+        let topic_id = if version >= 13 {
+            types::Uuid.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "topic_id", version, start - buf.remaining()))?
+        } else {
+            Uuid::nil()
+        };
+        let partitions = if version >= 7 {
+            if version >= 12 {
+                ArrayView::decode(buf, version, true, |buf, _| types::Int32.decode(buf), |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("ForgottenTopic", "partitions", version, start - buf.remaining()))?
+            } else {
+                ArrayView::decode(buf, version, false, |buf, _| types::Int32.decode(buf), |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("ForgottenTopic", "partitions", version, start - buf.remaining()))?
+            }
+        } else {
+            Default::default()
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ForgottenTopic", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ForgottenTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            topic,
+            topic_id,
+            partitions,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 7 && version <= 12 {
+            if version >= 12 {
+                view::skip_string(buf, true).map_err(|e| e.with_field("ForgottenTopic", "topic", version, start - buf.remaining()))?;
+            } else {
+                view::skip_string(buf, false).map_err(|e| e.with_field("ForgottenTopic", "topic", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 13 {
+            view::skip_fixed(buf, 16).map_err(|e| e.with_field("ForgottenTopic", "topic_id", version, start - buf.remaining()))?;
+        }
+        if version >= 7 {
+            if version >= 12 {
+                view::skip_array(buf, version, true, |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("ForgottenTopic", "partitions", version, start - buf.remaining()))?;
+            } else {
+                view::skip_array(buf, version, false, |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("ForgottenTopic", "partitions", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("ForgottenTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ForgottenTopicView {
+    fn default() -> Self {
+        Self {
+            topic: Default::default(),
+            topic_id: Uuid::nil(),
+            partitions: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-15
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct FetchRequest {
     /// The clusterId if known. This is used to validate metadata fetches prior to broker registration.
     /// 
     /// Supported API versions: 12-15
+    /// May be `None` in versions: 12-15
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub cluster_id: Option<StrBytes>,
 
     /// The broker ID of the follower, of -1 if this request is from a consumer.
@@ -692,10 +1486,11 @@ pub struct FetchRequest {
     /// Rack ID of the consumer making this request
     /// 
     /// Supported API versions: 11-15
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub rack_id: StrBytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for FetchRequest {
@@ -708,48 +1503,57 @@ impl Builder for FetchRequest {
 
 impl Encodable for FetchRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FetchRequest", version));
+        }
         if version <= 14 {
-            types::Int32.encode(buf, &self.replica_id)?;
+            types::Int32.encode(buf, &self.replica_id).map_err(|e| e.with_field("FetchRequest", "replica_id", version))?;
         } else {
             if self.replica_id != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "replica_id", version));
             }
         }
-        types::Int32.encode(buf, &self.max_wait_ms)?;
-        types::Int32.encode(buf, &self.min_bytes)?;
+        types::Int32.encode(buf, &self.max_wait_ms).map_err(|e| e.with_field("FetchRequest", "max_wait_ms", version))?;
+        types::Int32.encode(buf, &self.min_bytes).map_err(|e| e.with_field("FetchRequest", "min_bytes", version))?;
         if version >= 3 {
-            types::Int32.encode(buf, &self.max_bytes)?;
+            types::Int32.encode(buf, &self.max_bytes).map_err(|e| e.with_field("FetchRequest", "max_bytes", version))?;
         }
         if version >= 4 {
-            types::Int8.encode(buf, &self.isolation_level)?;
+            types::Int8.encode(buf, &self.isolation_level).map_err(|e| e.with_field("FetchRequest", "isolation_level", version))?;
         }
         if version >= 7 {
-            types::Int32.encode(buf, &self.session_id)?;
+            types::Int32.encode(buf, &self.session_id).map_err(|e| e.with_field("FetchRequest", "session_id", version))?;
         }
         if version >= 7 {
-            types::Int32.encode(buf, &self.session_epoch)?;
+            types::Int32.encode(buf, &self.session_epoch).map_err(|e| e.with_field("FetchRequest", "session_epoch", version))?;
         }
         if version >= 12 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("FetchRequest", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("FetchRequest", "topics", version))?;
         }
         if version >= 7 {
             if version >= 12 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.forgotten_topics_data)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.forgotten_topics_data).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.forgotten_topics_data)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.forgotten_topics_data).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version))?;
             }
         } else {
             if !self.forgotten_topics_data.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "forgotten_topics_data", version));
             }
         }
         if version >= 11 {
             if version >= 12 {
-                types::CompactString.encode(buf, &self.rack_id)?;
+                types::CompactString.encode(buf, &self.rack_id).map_err(|e| e.with_field("FetchRequest", "rack_id", version))?;
             } else {
-                types::String.encode(buf, &self.rack_id)?;
+                types::String.encode(buf, &self.rack_id).map_err(|e| e.with_field("FetchRequest", "rack_id", version))?;
             }
         }
         if version >= 12 {
@@ -762,80 +1566,80 @@ impl Encodable for FetchRequest {
                     num_tagged_fields += 1;
                 }
             }if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version))?;
             if !self.cluster_id.is_none() {
-                let computed_size = types::CompactString.compute_size(&self.cluster_id)?;
+                let computed_size = types::CompactString.compute_size(&self.cluster_id).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "cluster_id", version));
                 }
-                types::UnsignedVarInt.encode(buf, 0)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::CompactString.encode(buf, &self.cluster_id)?;
+                types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
             }
             if version >= 15 {
                 if &self.replica_state != &Default::default() {
-                    let computed_size = types::Struct { version }.compute_size(&self.replica_state)?;
+                    let computed_size = cache.size_of(&self.replica_state, || CachedStruct { version, cache }.compute_size(&self.replica_state)).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
                     if computed_size > std::u32::MAX as usize {
-                        error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                        return Err(EncodeError);
+                        return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "replica_state", version));
                     }
-                    types::UnsignedVarInt.encode(buf, 1)?;
-                    types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                    types::Struct { version }.encode(buf, &self.replica_state)?;
+                    types::UnsignedVarInt.encode(buf, 1).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    CachedStruct { version, cache }.encode(buf, &self.replica_state).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
                 }
             }
-            write_unknown_tagged_fields(buf, 2.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 2.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FetchRequest", version));
+        }
         let mut total_size = 0;
         if version <= 14 {
-            total_size += types::Int32.compute_size(&self.replica_id)?;
+            total_size += types::Int32.compute_size(&self.replica_id).map_err(|e| e.with_field("FetchRequest", "replica_id", version))?;
         } else {
             if self.replica_id != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "replica_id", version));
             }
         }
-        total_size += types::Int32.compute_size(&self.max_wait_ms)?;
-        total_size += types::Int32.compute_size(&self.min_bytes)?;
+        total_size += types::Int32.compute_size(&self.max_wait_ms).map_err(|e| e.with_field("FetchRequest", "max_wait_ms", version))?;
+        total_size += types::Int32.compute_size(&self.min_bytes).map_err(|e| e.with_field("FetchRequest", "min_bytes", version))?;
         if version >= 3 {
-            total_size += types::Int32.compute_size(&self.max_bytes)?;
+            total_size += types::Int32.compute_size(&self.max_bytes).map_err(|e| e.with_field("FetchRequest", "max_bytes", version))?;
         }
         if version >= 4 {
-            total_size += types::Int8.compute_size(&self.isolation_level)?;
+            total_size += types::Int8.compute_size(&self.isolation_level).map_err(|e| e.with_field("FetchRequest", "isolation_level", version))?;
         }
         if version >= 7 {
-            total_size += types::Int32.compute_size(&self.session_id)?;
+            total_size += types::Int32.compute_size(&self.session_id).map_err(|e| e.with_field("FetchRequest", "session_id", version))?;
         }
         if version >= 7 {
-            total_size += types::Int32.compute_size(&self.session_epoch)?;
+            total_size += types::Int32.compute_size(&self.session_epoch).map_err(|e| e.with_field("FetchRequest", "session_epoch", version))?;
         }
         if version >= 12 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("FetchRequest", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("FetchRequest", "topics", version))?;
         }
         if version >= 7 {
             if version >= 12 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.forgotten_topics_data)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.forgotten_topics_data).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.forgotten_topics_data)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.forgotten_topics_data).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version))?;
             }
         } else {
             if !self.forgotten_topics_data.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "forgotten_topics_data", version));
             }
         }
         if version >= 11 {
             if version >= 12 {
-                total_size += types::CompactString.compute_size(&self.rack_id)?;
+                total_size += types::CompactString.compute_size(&self.rack_id).map_err(|e| e.with_field("FetchRequest", "rack_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.rack_id)?;
+                total_size += types::String.compute_size(&self.rack_id).map_err(|e| e.with_field("FetchRequest", "rack_id", version))?;
             }
         }
         if version >= 12 {
@@ -848,33 +1652,30 @@ impl Encodable for FetchRequest {
                     num_tagged_fields += 1;
                 }
             }if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version))?;
             if !self.cluster_id.is_none() {
-                let computed_size = types::CompactString.compute_size(&self.cluster_id)?;
+                let computed_size = types::CompactString.compute_size(&self.cluster_id).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "cluster_id", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(0)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
                 total_size += computed_size;
             }
             if version >= 15 {
                 if &self.replica_state != &Default::default() {
-                    let computed_size = types::Struct { version }.compute_size(&self.replica_state)?;
+                    let computed_size = cache.size_of(&self.replica_state, || CachedStruct { version, cache }.compute_size(&self.replica_state)).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
                     if computed_size > std::u32::MAX as usize {
-                        error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                        return Err(EncodeError);
+                        return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "replica_state", version));
                     }
-                    total_size += types::UnsignedVarInt.compute_size(1)?;
-                    total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                    total_size += types::UnsignedVarInt.compute_size(1).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
                     total_size += computed_size;
                 }
             }
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -882,79 +1683,81 @@ impl Encodable for FetchRequest {
 
 impl Decodable for FetchRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("FetchRequest", version));
+        }
+        let start = buf.remaining();
         let mut cluster_id = None;
         let replica_id = if version <= 14 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "replica_id", version, start - buf.remaining()))?
         } else {
             (-1).into()
         };
         let mut replica_state = Default::default();
-        let max_wait_ms = types::Int32.decode(buf)?;
-        let min_bytes = types::Int32.decode(buf)?;
+        let max_wait_ms = types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "max_wait_ms", version, start - buf.remaining()))?;
+        let min_bytes = types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "min_bytes", version, start - buf.remaining()))?;
         let max_bytes = if version >= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "max_bytes", version, start - buf.remaining()))?
         } else {
             0x7fffffff
         };
         let isolation_level = if version >= 4 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("FetchRequest", "isolation_level", version, start - buf.remaining()))?
         } else {
             0
         };
         let session_id = if version >= 7 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "session_id", version, start - buf.remaining()))?
         } else {
             0
         };
         let session_epoch = if version >= 7 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "session_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let topics = if version >= 12 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchRequest", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchRequest", "topics", version, start - buf.remaining()))?
         };
         let forgotten_topics_data = if version >= 7 {
             if version >= 12 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let rack_id = if version >= 11 {
             if version >= 12 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FetchRequest", "rack_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("FetchRequest", "rack_id", version, start - buf.remaining()))?
             }
         } else {
             StrBytes::from_str("")
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 match tag {
                     0 => {
-                        cluster_id = types::CompactString.decode(buf)?;
+                        cluster_id = types::CompactString.decode(buf).map_err(|e| e.with_field("FetchRequest", "cluster_id", version, start - buf.remaining()))?;
                     },
                     1 => {
                         if version >= 15 {
-                            replica_state = types::Struct { version }.decode(buf)?;
+                            replica_state = types::Struct { version }.decode(buf).map_err(|e| e.with_field("FetchRequest", "replica_state", version, start - buf.remaining()))?;
                         } else {
-                            error!("Tag {} is not valid for version {}", tag, version);
-                            return Err(DecodeError);
+                            return Err(DecodeError::new(DecodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "replica_state", version, start - buf.remaining()));
                         }
                     },
                     _ => {
-                        let mut unknown_value = vec![0; size as usize];
-                        buf.try_copy_to_slice(&mut unknown_value)?;
+                        let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                         unknown_tagged_fields.insert(tag as i32, unknown_value);
                     }
                 }
@@ -993,7 +1796,7 @@ impl Default for FetchRequest {
             topics: Default::default(),
             forgotten_topics_data: Default::default(),
             rack_id: StrBytes::from_str(""),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -1002,13 +1805,663 @@ impl Message for FetchRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
-impl HeaderVersion for FetchRequest {
-    fn header_version(version: i16) -> i16 {
+impl FetchRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = FetchTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FetchRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version <= 14 {
+            types::Int32.encode(buf, &self.replica_id).map_err(|e| e.with_field("FetchRequest", "replica_id", version))?;
+        } else {
+            if self.replica_id != -1 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "replica_id", version));
+            }
+        }
+        types::Int32.encode(buf, &self.max_wait_ms).map_err(|e| e.with_field("FetchRequest", "max_wait_ms", version))?;
+        types::Int32.encode(buf, &self.min_bytes).map_err(|e| e.with_field("FetchRequest", "min_bytes", version))?;
+        if version >= 3 {
+            types::Int32.encode(buf, &self.max_bytes).map_err(|e| e.with_field("FetchRequest", "max_bytes", version))?;
+        }
+        if version >= 4 {
+            types::Int8.encode(buf, &self.isolation_level).map_err(|e| e.with_field("FetchRequest", "isolation_level", version))?;
+        }
+        if version >= 7 {
+            types::Int32.encode(buf, &self.session_id).map_err(|e| e.with_field("FetchRequest", "session_id", version))?;
+        }
+        if version >= 7 {
+            types::Int32.encode(buf, &self.session_epoch).map_err(|e| e.with_field("FetchRequest", "session_epoch", version))?;
+        }
         if version >= 12 {
-            2
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("FetchRequest", "topics", version))?;
         } else {
-            1
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("FetchRequest", "topics", version))?;
+        }
+        if version >= 7 {
+            if version >= 12 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.forgotten_topics_data).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.forgotten_topics_data).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version))?;
+            }
+        } else {
+            if !self.forgotten_topics_data.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "forgotten_topics_data", version));
+            }
+        }
+        if version >= 11 {
+            if version >= 12 {
+                types::CompactString.encode(buf, &self.rack_id).map_err(|e| e.with_field("FetchRequest", "rack_id", version))?;
+            } else {
+                types::String.encode(buf, &self.rack_id).map_err(|e| e.with_field("FetchRequest", "rack_id", version))?;
+            }
+        }
+        if version >= 12 {
+            let mut num_tagged_fields = self.unknown_tagged_fields.len();
+            if !self.cluster_id.is_none() {
+                num_tagged_fields += 1;
+            }
+            if version >= 15 {
+                if &self.replica_state != &Default::default() {
+                    num_tagged_fields += 1;
+                }
+            }if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version))?;
+            if !self.cluster_id.is_none() {
+                let computed_size = types::CompactString.compute_size(&self.cluster_id).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                if computed_size > std::u32::MAX as usize {
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "cluster_id", version));
+                }
+                types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+            }
+            if version >= 15 {
+                if &self.replica_state != &Default::default() {
+                    let computed_size = cache.size_of(&self.replica_state, || CachedStruct { version, cache }.compute_size(&self.replica_state)).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    if computed_size > std::u32::MAX as usize {
+                        return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "replica_state", version));
+                    }
+                    types::UnsignedVarInt.encode(buf, 1).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    CachedStruct { version, cache }.encode(buf, &self.replica_state).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                }
+            }
+            write_unknown_tagged_fields(buf, 2.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `forgotten_topics_data` produced by an iterator of known length, in place of
+    /// the `forgotten_topics_data` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_forgotten_topics_data<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, forgotten_topics_data: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = ForgottenTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FetchRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let forgotten_topics_data = forgotten_topics_data.into_iter();
+        if version <= 14 {
+            types::Int32.encode(buf, &self.replica_id).map_err(|e| e.with_field("FetchRequest", "replica_id", version))?;
+        } else {
+            if self.replica_id != -1 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "replica_id", version));
+            }
+        }
+        types::Int32.encode(buf, &self.max_wait_ms).map_err(|e| e.with_field("FetchRequest", "max_wait_ms", version))?;
+        types::Int32.encode(buf, &self.min_bytes).map_err(|e| e.with_field("FetchRequest", "min_bytes", version))?;
+        if version >= 3 {
+            types::Int32.encode(buf, &self.max_bytes).map_err(|e| e.with_field("FetchRequest", "max_bytes", version))?;
+        }
+        if version >= 4 {
+            types::Int8.encode(buf, &self.isolation_level).map_err(|e| e.with_field("FetchRequest", "isolation_level", version))?;
+        }
+        if version >= 7 {
+            types::Int32.encode(buf, &self.session_id).map_err(|e| e.with_field("FetchRequest", "session_id", version))?;
+        }
+        if version >= 7 {
+            types::Int32.encode(buf, &self.session_epoch).map_err(|e| e.with_field("FetchRequest", "session_epoch", version))?;
+        }
+        if version >= 12 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("FetchRequest", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("FetchRequest", "topics", version))?;
+        }
+        if version >= 7 {
+            if version >= 12 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(forgotten_topics_data)).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(forgotten_topics_data)).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version))?;
+            }
+        } else {
+            if forgotten_topics_data.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "forgotten_topics_data", version));
+            }
+        }
+        if version >= 11 {
+            if version >= 12 {
+                types::CompactString.encode(buf, &self.rack_id).map_err(|e| e.with_field("FetchRequest", "rack_id", version))?;
+            } else {
+                types::String.encode(buf, &self.rack_id).map_err(|e| e.with_field("FetchRequest", "rack_id", version))?;
+            }
+        }
+        if version >= 12 {
+            let mut num_tagged_fields = self.unknown_tagged_fields.len();
+            if !self.cluster_id.is_none() {
+                num_tagged_fields += 1;
+            }
+            if version >= 15 {
+                if &self.replica_state != &Default::default() {
+                    num_tagged_fields += 1;
+                }
+            }if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version))?;
+            if !self.cluster_id.is_none() {
+                let computed_size = types::CompactString.compute_size(&self.cluster_id).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                if computed_size > std::u32::MAX as usize {
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "cluster_id", version));
+                }
+                types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+                types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("FetchRequest", "cluster_id", version))?;
+            }
+            if version >= 15 {
+                if &self.replica_state != &Default::default() {
+                    let computed_size = cache.size_of(&self.replica_state, || CachedStruct { version, cache }.compute_size(&self.replica_state)).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    if computed_size > std::u32::MAX as usize {
+                        return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchRequest", "replica_state", version));
+                    }
+                    types::UnsignedVarInt.encode(buf, 1).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                    CachedStruct { version, cache }.encode(buf, &self.replica_state).map_err(|e| e.with_field("FetchRequest", "replica_state", version))?;
+                }
+            }
+            write_unknown_tagged_fields(buf, 2.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FetchRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl FetchRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 12 {
+            self.cluster_id = None;
+        }
+        if version > 14 {
+            self.replica_id = (-1).into();
+        }
+        if version >= 15 {
+            self.replica_state.normalize_to(version);
+        } else {
+            self.replica_state = Default::default();
+        }
+        if version < 3 {
+            self.max_bytes = 0x7fffffff;
+        }
+        if version < 4 {
+            self.isolation_level = 0;
+        }
+        if version < 7 {
+            self.session_id = 0;
+        }
+        if version < 7 {
+            self.session_epoch = -1;
+        }
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+        if version >= 7 {
+            for item in self.forgotten_topics_data.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.forgotten_topics_data = Default::default();
+        }
+        if version < 11 {
+            self.rack_id = StrBytes::from_str("");
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 12 {
+            if !self.cluster_id.is_none() {
+                invalid.push(format!("{}cluster_id", path));
+            }
+        }
+        if version > 14 {
+            if self.replica_id != -1 {
+                invalid.push(format!("{}replica_id", path));
+            }
+        }
+        if version >= 15 {
+            self.replica_state.validate_into(version, &format!("{}replica_state.", path), invalid);
+        } else {
+            if &self.replica_state != &Default::default() {
+                invalid.push(format!("{}replica_state", path));
+            }
+        }
+        if version < 3 {
+            if self.max_bytes != 0x7fffffff {
+                invalid.push(format!("{}max_bytes", path));
+            }
+        }
+        if version < 4 {
+            if self.isolation_level != 0 {
+                invalid.push(format!("{}isolation_level", path));
+            }
+        }
+        if version < 7 {
+            if self.session_id != 0 {
+                invalid.push(format!("{}session_id", path));
+            }
+        }
+        if version < 7 {
+            if self.session_epoch != -1 {
+                invalid.push(format!("{}session_epoch", path));
+            }
+        }
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+        if version >= 7 {
+            for (i, item) in self.forgotten_topics_data.iter().enumerate() {
+                item.validate_into(version, &format!("{}forgotten_topics_data[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.forgotten_topics_data.is_empty() {
+                invalid.push(format!("{}forgotten_topics_data", path));
+            }
+        }
+        if version < 11 {
+            if &self.rack_id != "" {
+                invalid.push(format!("{}rack_id", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FetchRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 12 {
+            if !self.cluster_id.is_none() {
+                node.insert("clusterId".into(), JsonValue::to_json(&self.cluster_id));
+            }
+        }
+        if version <= 14 {
+            node.insert("replicaId".into(), JsonValue::to_json(&self.replica_id));
+        }
+        if version >= 15 {
+            if &self.replica_state != &Default::default() {
+                node.insert("replicaState".into(), self.replica_state.to_json_with(version, records));
+            }
+        }
+        node.insert("maxWaitMs".into(), JsonValue::to_json(&self.max_wait_ms));
+        node.insert("minBytes".into(), JsonValue::to_json(&self.min_bytes));
+        if version >= 3 {
+            node.insert("maxBytes".into(), JsonValue::to_json(&self.max_bytes));
+        }
+        if version >= 4 {
+            node.insert("isolationLevel".into(), JsonValue::to_json(&self.isolation_level));
+        }
+        if version >= 7 {
+            node.insert("sessionId".into(), JsonValue::to_json(&self.session_id));
+        }
+        if version >= 7 {
+            node.insert("sessionEpoch".into(), JsonValue::to_json(&self.session_epoch));
+        }
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        if version >= 7 {
+            node.insert("forgottenTopicsData".into(), serde_json::Value::Array(self.forgotten_topics_data.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 11 {
+            node.insert("rackId".into(), JsonValue::to_json(&self.rack_id));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "FetchRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("clusterId") {
+            this.cluster_id = JsonValue::from_json(value, "FetchRequest.clusterId")?;
+        }
+        if let Some(value) = object.get("replicaId") {
+            this.replica_id = JsonValue::from_json(value, "FetchRequest.replicaId")?;
+        } else if version <= 14 {
+            return Err(crate::json::missing("FetchRequest", "replicaId", version));
+        }
+        if let Some(value) = object.get("replicaState") {
+            this.replica_state = ReplicaState::from_json(value, version)?;
+        }
+        if let Some(value) = object.get("maxWaitMs") {
+            this.max_wait_ms = JsonValue::from_json(value, "FetchRequest.maxWaitMs")?;
+        } else {
+            return Err(crate::json::missing("FetchRequest", "maxWaitMs", version));
+        }
+        if let Some(value) = object.get("minBytes") {
+            this.min_bytes = JsonValue::from_json(value, "FetchRequest.minBytes")?;
+        } else {
+            return Err(crate::json::missing("FetchRequest", "minBytes", version));
+        }
+        if let Some(value) = object.get("maxBytes") {
+            this.max_bytes = JsonValue::from_json(value, "FetchRequest.maxBytes")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("FetchRequest", "maxBytes", version));
+        }
+        if let Some(value) = object.get("isolationLevel") {
+            this.isolation_level = JsonValue::from_json(value, "FetchRequest.isolationLevel")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("FetchRequest", "isolationLevel", version));
+        }
+        if let Some(value) = object.get("sessionId") {
+            this.session_id = JsonValue::from_json(value, "FetchRequest.sessionId")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("FetchRequest", "sessionId", version));
+        }
+        if let Some(value) = object.get("sessionEpoch") {
+            this.session_epoch = JsonValue::from_json(value, "FetchRequest.sessionEpoch")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("FetchRequest", "sessionEpoch", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "FetchRequest.topics")?.iter().map(|item| FetchTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("FetchRequest", "topics", version));
+        }
+        if let Some(value) = object.get("forgottenTopicsData") {
+            this.forgotten_topics_data = crate::json::array(value, "FetchRequest.forgottenTopicsData")?.iter().map(|item| ForgottenTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("FetchRequest", "forgottenTopicsData", version));
+        }
+        if let Some(value) = object.get("rackId") {
+            this.rack_id = JsonValue::from_json(value, "FetchRequest.rackId")?;
+        } else if version >= 11 {
+            return Err(crate::json::missing("FetchRequest", "rackId", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`FetchRequest`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchRequestView {
+    /// The clusterId if known. This is used to validate metadata fetches prior to broker registration.
+    /// 
+    /// Supported API versions: 12-15
+    pub cluster_id: Option<StrBytes>,
+
+    /// The broker ID of the follower, of -1 if this request is from a consumer.
+    /// 
+    /// Supported API versions: 0-14
+    pub replica_id: super::BrokerId,
+
+    /// 
+    /// 
+    /// Supported API versions: 15
+    pub replica_state: ReplicaStateView,
+
+    /// The maximum time in milliseconds to wait for the response.
+    /// 
+    /// Supported API versions: 0-15
+    pub max_wait_ms: i32,
+
+    /// The minimum bytes to accumulate in the response.
+    /// 
+    /// Supported API versions: 0-15
+    pub min_bytes: i32,
+
+    /// The maximum bytes to fetch.  See KIP-74 for cases where this limit may not be honored.
+    /// 
+    /// Supported API versions: 3-15
+    pub max_bytes: i32,
+
+    /// This setting controls the visibility of transactional records. Using READ_UNCOMMITTED (isolation_level = 0) makes all records visible. With READ_COMMITTED (isolation_level = 1), non-transactional and COMMITTED transactional records are visible. To be more concrete, READ_COMMITTED returns all data from offsets smaller than the current LSO (last stable offset), and enables the inclusion of the list of aborted transactions in the result, which allows consumers to discard ABORTED transactional records
+    /// 
+    /// Supported API versions: 4-15
+    pub isolation_level: i8,
+
+    /// The fetch session ID.
+    /// 
+    /// Supported API versions: 7-15
+    pub session_id: i32,
+
+    /// The fetch session epoch, which is used for ordering requests in a session.
+    /// 
+    /// Supported API versions: 7-15
+    pub session_epoch: i32,
+
+    /// The topics to fetch.
+    /// 
+    /// Supported API versions: 0-15
+    pub topics: ArrayView<FetchTopicView>,
+
+    /// In an incremental fetch request, the partitions to remove.
+    /// 
+    /// Supported API versions: 7-15
+    pub forgotten_topics_data: ArrayView<ForgottenTopicView>,
+
+    /// Rack ID of the consumer making this request
+    /// 
+    /// Supported API versions: 11-15
+    pub rack_id: StrBytes,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl FetchRequestView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        if !FetchRequest::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("FetchRequest", version));
+        }
+        let start = buf.remaining();
+        let mut cluster_id = None;
+        let replica_id = if version <= 14 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "replica_id", version, start - buf.remaining()))?
+        } else {
+            (-1).into()
+        };
+        let mut replica_state = Default::default();
+        let max_wait_ms = types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "max_wait_ms", version, start - buf.remaining()))?;
+        let min_bytes = types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "min_bytes", version, start - buf.remaining()))?;
+        let max_bytes = if version >= 3 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "max_bytes", version, start - buf.remaining()))?
+        } else {
+            0x7fffffff
+        };
+        let isolation_level = if version >= 4 {
+            types::Int8.decode(buf).map_err(|e| e.with_field("FetchRequest", "isolation_level", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let session_id = if version >= 7 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "session_id", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let session_epoch = if version >= 7 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchRequest", "session_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let topics = if version >= 12 {
+            ArrayView::decode(buf, version, true, FetchTopicView::decode, FetchTopicView::skip).map_err(|e| e.with_field("FetchRequest", "topics", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, FetchTopicView::decode, FetchTopicView::skip).map_err(|e| e.with_field("FetchRequest", "topics", version, start - buf.remaining()))?
+        };
+        let forgotten_topics_data = if version >= 7 {
+            if version >= 12 {
+                ArrayView::decode(buf, version, true, ForgottenTopicView::decode, ForgottenTopicView::skip).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version, start - buf.remaining()))?
+            } else {
+                ArrayView::decode(buf, version, false, ForgottenTopicView::decode, ForgottenTopicView::skip).map_err(|e| e.with_field("FetchRequest", "forgotten_topics_data", version, start - buf.remaining()))?
+            }
+        } else {
+            Default::default()
+        };
+        let rack_id = if version >= 11 {
+            if version >= 12 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FetchRequest", "rack_id", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("FetchRequest", "rack_id", version, start - buf.remaining()))?
+            }
+        } else {
+            StrBytes::from_str("")
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchRequest", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("FetchRequest", "tagged_fields", version, start - buf.remaining()))?;
+            for tagged_field in &tagged_fields {
+                let (tag, mut value) = tagged_field.map_err(|e| e.with_field("FetchRequest", "tagged_fields", version, start - buf.remaining()))?;
+                match tag {
+                    0 => {
+                        cluster_id = types::CompactString.decode(&mut value).map_err(|e| e.with_field("FetchRequest", "cluster_id", version, start - buf.remaining()))?;
+                    },
+                    1 => {
+                        if version >= 15 {
+                            replica_state = ReplicaStateView::decode(&mut value, version).map_err(|e| e.with_field("FetchRequest", "replica_state", version, start - buf.remaining()))?;
+                        } else {
+                            return Err(DecodeError::new(DecodeErrorKind::FieldNotValidAtVersion).with_field("FetchRequest", "replica_state", version, start - buf.remaining()));
+                        }
+                    },
+                    _ => {
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            cluster_id,
+            replica_id,
+            replica_state,
+            max_wait_ms,
+            min_bytes,
+            max_bytes,
+            isolation_level,
+            session_id,
+            session_epoch,
+            topics,
+            forgotten_topics_data,
+            rack_id,
+            tagged_fields,
+        })
+    }
+}
+
+impl Default for FetchRequestView {
+    fn default() -> Self {
+        Self {
+            cluster_id: None,
+            replica_id: (-1).into(),
+            replica_state: Default::default(),
+            max_wait_ms: 0,
+            min_bytes: 0,
+            max_bytes: 0x7fffffff,
+            isolation_level: 0,
+            session_id: 0,
+            session_epoch: -1,
+            topics: Default::default(),
+            forgotten_topics_data: Default::default(),
+            rack_id: StrBytes::from_str(""),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
+impl HeaderVersion for FetchRequest {
+    fn header_version(version: i16) -> i16 {
+        if version >= 12 {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+impl FetchRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::FetchResponse {
+        let mut response = super::FetchResponse::default();
+        if version >= 7 {
+            response.error_code = error.code();
+        }
+        let mut items0 = Vec::new();
+        for request0 in &self.topics {
+            let mut response0 = super::fetch_response::FetchableTopicResponse::default();
+            if version <= 12 {
+                response0.topic = request0.topic.clone();
+            }
+            if version >= 13 {
+                response0.topic_id = request0.topic_id;
+            }
+            let mut items1 = Vec::new();
+            for request1 in &request0.partitions {
+                let mut response1 = super::fetch_response::PartitionData::default();
+                response1.partition_index = request1.partition;
+                response1.error_code = error.code();
+                items1.push(response1);
+            }
+            response0.partitions = items1;
+            items0.push(response0);
         }
+        response.responses = items0;
+        response
     }
 }
 
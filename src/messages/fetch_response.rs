@@ -5,21 +5,29 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+use bytes::Buf;
+use crate::protocol::view::{self, ArrayView};
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-15
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct EpochEndOffset {
     /// 
@@ -33,7 +41,7 @@ pub struct EpochEndOffset {
     pub end_offset: i64,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for EpochEndOffset {
@@ -46,57 +54,61 @@ impl Builder for EpochEndOffset {
 
 impl Encodable for EpochEndOffset {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 12 {
-            types::Int32.encode(buf, &self.epoch)?;
+            types::Int32.encode(buf, &self.epoch).map_err(|e| e.with_field("EpochEndOffset", "epoch", version))?;
         } else {
             if self.epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("EpochEndOffset", "epoch", version));
             }
         }
         if version >= 12 {
-            types::Int64.encode(buf, &self.end_offset)?;
+            types::Int64.encode(buf, &self.end_offset).map_err(|e| e.with_field("EpochEndOffset", "end_offset", version))?;
         } else {
             if self.end_offset != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("EpochEndOffset", "end_offset", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EpochEndOffset", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("EpochEndOffset", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("EpochEndOffset", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 12 {
-            total_size += types::Int32.compute_size(&self.epoch)?;
+            total_size += types::Int32.compute_size(&self.epoch).map_err(|e| e.with_field("EpochEndOffset", "epoch", version))?;
         } else {
             if self.epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("EpochEndOffset", "epoch", version));
             }
         }
         if version >= 12 {
-            total_size += types::Int64.compute_size(&self.end_offset)?;
+            total_size += types::Int64.compute_size(&self.end_offset).map_err(|e| e.with_field("EpochEndOffset", "end_offset", version))?;
         } else {
             if self.end_offset != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("EpochEndOffset", "end_offset", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EpochEndOffset", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("EpochEndOffset", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("EpochEndOffset", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -104,24 +116,24 @@ impl Encodable for EpochEndOffset {
 
 impl Decodable for EpochEndOffset {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let epoch = if version >= 12 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("EpochEndOffset", "epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let end_offset = if version >= 12 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("EpochEndOffset", "end_offset", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EpochEndOffset", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EpochEndOffset", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EpochEndOffset", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("EpochEndOffset", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -138,7 +150,7 @@ impl Default for EpochEndOffset {
         Self {
             epoch: -1,
             end_offset: -1,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -147,9 +159,161 @@ impl Message for EpochEndOffset {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl EpochEndOffset {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 12 {
+            self.epoch = -1;
+        }
+        if version < 12 {
+            self.end_offset = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 12 {
+            if self.epoch != -1 {
+                invalid.push(format!("{}epoch", path));
+            }
+        }
+        if version < 12 {
+            if self.end_offset != -1 {
+                invalid.push(format!("{}end_offset", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl EpochEndOffset {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 12 {
+            node.insert("epoch".into(), JsonValue::to_json(&self.epoch));
+        }
+        if version >= 12 {
+            node.insert("endOffset".into(), JsonValue::to_json(&self.end_offset));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "EpochEndOffset")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("epoch") {
+            this.epoch = JsonValue::from_json(value, "EpochEndOffset.epoch")?;
+        } else if version >= 12 {
+            return Err(crate::json::missing("EpochEndOffset", "epoch", version));
+        }
+        if let Some(value) = object.get("endOffset") {
+            this.end_offset = JsonValue::from_json(value, "EpochEndOffset.endOffset")?;
+        } else if version >= 12 {
+            return Err(crate::json::missing("EpochEndOffset", "endOffset", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`EpochEndOffset`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochEndOffsetView {
+    /// 
+    /// 
+    /// Supported API versions: 12-15
+    pub epoch: i32,
+
+    /// 
+    /// 
+    /// Supported API versions: 12-15
+    pub end_offset: i64,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl EpochEndOffsetView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let epoch = if version >= 12 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("EpochEndOffset", "epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let end_offset = if version >= 12 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("EpochEndOffset", "end_offset", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EpochEndOffset", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("EpochEndOffset", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            epoch,
+            end_offset,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 12 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("EpochEndOffset", "epoch", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("EpochEndOffset", "end_offset", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("EpochEndOffset", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for EpochEndOffsetView {
+    fn default() -> Self {
+        Self {
+            epoch: -1,
+            end_offset: -1,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-15
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaderIdAndEpoch {
     /// The ID of the current leader or -1 if the leader is unknown.
@@ -163,7 +327,7 @@ pub struct LeaderIdAndEpoch {
     pub leader_epoch: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaderIdAndEpoch {
@@ -176,57 +340,61 @@ impl Builder for LeaderIdAndEpoch {
 
 impl Encodable for LeaderIdAndEpoch {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 12 {
-            types::Int32.encode(buf, &self.leader_id)?;
+            types::Int32.encode(buf, &self.leader_id).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_id", version))?;
         } else {
             if self.leader_id != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderIdAndEpoch", "leader_id", version));
             }
         }
         if version >= 12 {
-            types::Int32.encode(buf, &self.leader_epoch)?;
+            types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_epoch", version))?;
         } else {
             if self.leader_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderIdAndEpoch", "leader_epoch", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 12 {
-            total_size += types::Int32.compute_size(&self.leader_id)?;
+            total_size += types::Int32.compute_size(&self.leader_id).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_id", version))?;
         } else {
             if self.leader_id != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderIdAndEpoch", "leader_id", version));
             }
         }
         if version >= 12 {
-            total_size += types::Int32.compute_size(&self.leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_epoch", version))?;
         } else {
             if self.leader_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderIdAndEpoch", "leader_epoch", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -234,24 +402,24 @@ impl Encodable for LeaderIdAndEpoch {
 
 impl Decodable for LeaderIdAndEpoch {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let leader_id = if version >= 12 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_id", version, start - buf.remaining()))?
         } else {
             (-1).into()
         };
         let leader_epoch = if version >= 12 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaderIdAndEpoch", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -268,7 +436,7 @@ impl Default for LeaderIdAndEpoch {
         Self {
             leader_id: (-1).into(),
             leader_epoch: -1,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -277,9 +445,161 @@ impl Message for LeaderIdAndEpoch {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl LeaderIdAndEpoch {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 12 {
+            self.leader_id = (-1).into();
+        }
+        if version < 12 {
+            self.leader_epoch = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 12 {
+            if self.leader_id != -1 {
+                invalid.push(format!("{}leader_id", path));
+            }
+        }
+        if version < 12 {
+            if self.leader_epoch != -1 {
+                invalid.push(format!("{}leader_epoch", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaderIdAndEpoch {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 12 {
+            node.insert("leaderId".into(), JsonValue::to_json(&self.leader_id));
+        }
+        if version >= 12 {
+            node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "LeaderIdAndEpoch")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("leaderId") {
+            this.leader_id = JsonValue::from_json(value, "LeaderIdAndEpoch.leaderId")?;
+        } else if version >= 12 {
+            return Err(crate::json::missing("LeaderIdAndEpoch", "leaderId", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "LeaderIdAndEpoch.leaderEpoch")?;
+        } else if version >= 12 {
+            return Err(crate::json::missing("LeaderIdAndEpoch", "leaderEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`LeaderIdAndEpoch`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaderIdAndEpochView {
+    /// The ID of the current leader or -1 if the leader is unknown.
+    /// 
+    /// Supported API versions: 12-15
+    pub leader_id: super::BrokerId,
+
+    /// The latest known leader epoch
+    /// 
+    /// Supported API versions: 12-15
+    pub leader_epoch: i32,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl LeaderIdAndEpochView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let leader_id = if version >= 12 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_id", version, start - buf.remaining()))?
+        } else {
+            (-1).into()
+        };
+        let leader_epoch = if version >= 12 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderIdAndEpoch", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("LeaderIdAndEpoch", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            leader_id,
+            leader_epoch,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 12 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_id", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("LeaderIdAndEpoch", "leader_epoch", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("LeaderIdAndEpoch", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for LeaderIdAndEpochView {
+    fn default() -> Self {
+        Self {
+            leader_id: (-1).into(),
+            leader_epoch: -1,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-15
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct SnapshotId {
     /// 
@@ -293,7 +613,7 @@ pub struct SnapshotId {
     pub epoch: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for SnapshotId {
@@ -306,33 +626,37 @@ impl Builder for SnapshotId {
 
 impl Encodable for SnapshotId {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int64.encode(buf, &self.end_offset)?;
-        types::Int32.encode(buf, &self.epoch)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int64.encode(buf, &self.end_offset).map_err(|e| e.with_field("SnapshotId", "end_offset", version))?;
+        types::Int32.encode(buf, &self.epoch).map_err(|e| e.with_field("SnapshotId", "epoch", version))?;
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SnapshotId", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("SnapshotId", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("SnapshotId", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int64.compute_size(&self.end_offset)?;
-        total_size += types::Int32.compute_size(&self.epoch)?;
+        total_size += types::Int64.compute_size(&self.end_offset).map_err(|e| e.with_field("SnapshotId", "end_offset", version))?;
+        total_size += types::Int32.compute_size(&self.epoch).map_err(|e| e.with_field("SnapshotId", "epoch", version))?;
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SnapshotId", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("SnapshotId", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("SnapshotId", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -340,16 +664,16 @@ impl Encodable for SnapshotId {
 
 impl Decodable for SnapshotId {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let end_offset = types::Int64.decode(buf)?;
-        let epoch = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let start = buf.remaining();
+        let end_offset = types::Int64.decode(buf).map_err(|e| e.with_field("SnapshotId", "end_offset", version, start - buf.remaining()))?;
+        let epoch = types::Int32.decode(buf).map_err(|e| e.with_field("SnapshotId", "epoch", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SnapshotId", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SnapshotId", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SnapshotId", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("SnapshotId", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -366,7 +690,7 @@ impl Default for SnapshotId {
         Self {
             end_offset: -1,
             epoch: -1,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -375,9 +699,129 @@ impl Message for SnapshotId {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl SnapshotId {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl SnapshotId {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("endOffset".into(), JsonValue::to_json(&self.end_offset));
+        node.insert("epoch".into(), JsonValue::to_json(&self.epoch));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "SnapshotId")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("endOffset") {
+            this.end_offset = JsonValue::from_json(value, "SnapshotId.endOffset")?;
+        } else {
+            return Err(crate::json::missing("SnapshotId", "endOffset", version));
+        }
+        if let Some(value) = object.get("epoch") {
+            this.epoch = JsonValue::from_json(value, "SnapshotId.epoch")?;
+        } else {
+            return Err(crate::json::missing("SnapshotId", "epoch", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`SnapshotId`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotIdView {
+    /// 
+    /// 
+    /// Supported API versions: 0-15
+    pub end_offset: i64,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-15
+    pub epoch: i32,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl SnapshotIdView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let end_offset = types::Int64.decode(buf).map_err(|e| e.with_field("SnapshotId", "end_offset", version, start - buf.remaining()))?;
+        let epoch = types::Int32.decode(buf).map_err(|e| e.with_field("SnapshotId", "epoch", version, start - buf.remaining()))?;
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SnapshotId", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("SnapshotId", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            end_offset,
+            epoch,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 8).map_err(|e| e.with_field("SnapshotId", "end_offset", version, start - buf.remaining()))?;
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("SnapshotId", "epoch", version, start - buf.remaining()))?;
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("SnapshotId", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for SnapshotIdView {
+    fn default() -> Self {
+        Self {
+            end_offset: -1,
+            epoch: -1,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-15
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct AbortedTransaction {
     /// The producer id associated with the aborted transaction.
@@ -391,7 +835,7 @@ pub struct AbortedTransaction {
     pub first_offset: i64,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for AbortedTransaction {
@@ -404,57 +848,61 @@ impl Builder for AbortedTransaction {
 
 impl Encodable for AbortedTransaction {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 4 {
-            types::Int64.encode(buf, &self.producer_id)?;
+            types::Int64.encode(buf, &self.producer_id).map_err(|e| e.with_field("AbortedTransaction", "producer_id", version))?;
         } else {
             if self.producer_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AbortedTransaction", "producer_id", version));
             }
         }
         if version >= 4 {
-            types::Int64.encode(buf, &self.first_offset)?;
+            types::Int64.encode(buf, &self.first_offset).map_err(|e| e.with_field("AbortedTransaction", "first_offset", version))?;
         } else {
             if self.first_offset != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AbortedTransaction", "first_offset", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AbortedTransaction", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AbortedTransaction", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AbortedTransaction", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::Int64.compute_size(&self.producer_id)?;
+            total_size += types::Int64.compute_size(&self.producer_id).map_err(|e| e.with_field("AbortedTransaction", "producer_id", version))?;
         } else {
             if self.producer_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AbortedTransaction", "producer_id", version));
             }
         }
         if version >= 4 {
-            total_size += types::Int64.compute_size(&self.first_offset)?;
+            total_size += types::Int64.compute_size(&self.first_offset).map_err(|e| e.with_field("AbortedTransaction", "first_offset", version))?;
         } else {
             if self.first_offset != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("AbortedTransaction", "first_offset", version));
             }
         }
         if version >= 12 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AbortedTransaction", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AbortedTransaction", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AbortedTransaction", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -462,24 +910,24 @@ impl Encodable for AbortedTransaction {
 
 impl Decodable for AbortedTransaction {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let producer_id = if version >= 4 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("AbortedTransaction", "producer_id", version, start - buf.remaining()))?
         } else {
             (0).into()
         };
         let first_offset = if version >= 4 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("AbortedTransaction", "first_offset", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AbortedTransaction", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AbortedTransaction", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AbortedTransaction", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AbortedTransaction", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -496,7 +944,7 @@ impl Default for AbortedTransaction {
         Self {
             producer_id: (0).into(),
             first_offset: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -505,19 +953,171 @@ impl Message for AbortedTransaction {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl AbortedTransaction {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 4 {
+            self.producer_id = (0).into();
+        }
+        if version < 4 {
+            self.first_offset = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 4 {
+            if self.producer_id != 0 {
+                invalid.push(format!("{}producer_id", path));
+            }
+        }
+        if version < 4 {
+            if self.first_offset != 0 {
+                invalid.push(format!("{}first_offset", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl AbortedTransaction {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 4 {
+            node.insert("producerId".into(), JsonValue::to_json(&self.producer_id));
+        }
+        if version >= 4 {
+            node.insert("firstOffset".into(), JsonValue::to_json(&self.first_offset));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AbortedTransaction")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("producerId") {
+            this.producer_id = JsonValue::from_json(value, "AbortedTransaction.producerId")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AbortedTransaction", "producerId", version));
+        }
+        if let Some(value) = object.get("firstOffset") {
+            this.first_offset = JsonValue::from_json(value, "AbortedTransaction.firstOffset")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("AbortedTransaction", "firstOffset", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`AbortedTransaction`], decoded without allocating from the buffer it borrows.
+///
 /// Valid versions: 0-15
 #[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
-#[builder(default)]
-pub struct PartitionData {
-    /// The partition index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbortedTransactionView {
+    /// The producer id associated with the aborted transaction.
     /// 
-    /// Supported API versions: 0-15
-    pub partition_index: i32,
+    /// Supported API versions: 4-15
+    pub producer_id: super::ProducerId,
 
-    /// The error code, or 0 if there was no fetch error.
+    /// The first offset in the aborted transaction.
     /// 
-    /// Supported API versions: 0-15
+    /// Supported API versions: 4-15
+    pub first_offset: i64,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl AbortedTransactionView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let producer_id = if version >= 4 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("AbortedTransaction", "producer_id", version, start - buf.remaining()))?
+        } else {
+            (0).into()
+        };
+        let first_offset = if version >= 4 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("AbortedTransaction", "first_offset", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AbortedTransaction", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("AbortedTransaction", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            producer_id,
+            first_offset,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 4 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("AbortedTransaction", "producer_id", version, start - buf.remaining()))?;
+        }
+        if version >= 4 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("AbortedTransaction", "first_offset", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("AbortedTransaction", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for AbortedTransactionView {
+    fn default() -> Self {
+        Self {
+            producer_id: (0).into(),
+            first_offset: 0,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct PartitionData {
+    /// The partition index.
+    /// 
+    /// Supported API versions: 0-15
+    pub partition_index: i32,
+
+    /// The error code, or 0 if there was no fetch error.
+    /// 
+    /// Supported API versions: 0-15
     pub error_code: i16,
 
     /// The current high water mark.
@@ -553,6 +1153,7 @@ pub struct PartitionData {
     /// The aborted transactions.
     /// 
     /// Supported API versions: 4-15
+    /// May be `None` in versions: 4-15
     pub aborted_transactions: Option<Vec<AbortedTransaction>>,
 
     /// The preferred read replica for the consumer to use on its next fetch request
@@ -563,10 +1164,11 @@ pub struct PartitionData {
     /// The record data.
     /// 
     /// Supported API versions: 0-15
+    /// May be `None` in versions: 0-15
     pub records: Option<Bytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for PartitionData {
@@ -579,33 +1181,39 @@ impl Builder for PartitionData {
 
 impl Encodable for PartitionData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::Int64.encode(buf, &self.high_watermark)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("PartitionData", "error_code", version))?;
+        types::Int64.encode(buf, &self.high_watermark).map_err(|e| e.with_field("PartitionData", "high_watermark", version))?;
         if version >= 4 {
-            types::Int64.encode(buf, &self.last_stable_offset)?;
+            types::Int64.encode(buf, &self.last_stable_offset).map_err(|e| e.with_field("PartitionData", "last_stable_offset", version))?;
         }
         if version >= 5 {
-            types::Int64.encode(buf, &self.log_start_offset)?;
+            types::Int64.encode(buf, &self.log_start_offset).map_err(|e| e.with_field("PartitionData", "log_start_offset", version))?;
         }
         if version >= 4 {
             if version >= 12 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.aborted_transactions)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.aborted_transactions).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.aborted_transactions)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.aborted_transactions).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version))?;
             }
         }
         if version >= 11 {
-            types::Int32.encode(buf, &self.preferred_read_replica)?;
+            types::Int32.encode(buf, &self.preferred_read_replica).map_err(|e| e.with_field("PartitionData", "preferred_read_replica", version))?;
         } else {
             if self.preferred_read_replica != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("PartitionData", "preferred_read_replica", version));
             }
         }
         if version >= 12 {
-            types::CompactBytes.encode(buf, &self.records)?;
+            types::CompactBytes.encode(buf, &self.records).map_err(|e| e.with_field("PartitionData", "records", version))?;
         } else {
-            types::Bytes.encode(buf, &self.records)?;
+            types::Bytes.encode(buf, &self.records).map_err(|e| e.with_field("PartitionData", "records", version))?;
         }
         if version >= 12 {
             let mut num_tagged_fields = self.unknown_tagged_fields.len();
@@ -619,74 +1227,70 @@ impl Encodable for PartitionData {
                 num_tagged_fields += 1;
             }
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
             if &self.diverging_epoch != &Default::default() {
-                let computed_size = types::Struct { version }.compute_size(&self.diverging_epoch)?;
+                let computed_size = cache.size_of(&self.diverging_epoch, || CachedStruct { version, cache }.compute_size(&self.diverging_epoch)).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "diverging_epoch", version));
                 }
-                types::UnsignedVarInt.encode(buf, 0)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::Struct { version }.encode(buf, &self.diverging_epoch)?;
+                types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version))?;
+                CachedStruct { version, cache }.encode(buf, &self.diverging_epoch).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version))?;
             }
             if &self.current_leader != &Default::default() {
-                let computed_size = types::Struct { version }.compute_size(&self.current_leader)?;
+                let computed_size = cache.size_of(&self.current_leader, || CachedStruct { version, cache }.compute_size(&self.current_leader)).map_err(|e| e.with_field("PartitionData", "current_leader", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "current_leader", version));
                 }
-                types::UnsignedVarInt.encode(buf, 1)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::Struct { version }.encode(buf, &self.current_leader)?;
+                types::UnsignedVarInt.encode(buf, 1).map_err(|e| e.with_field("PartitionData", "current_leader", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionData", "current_leader", version))?;
+                CachedStruct { version, cache }.encode(buf, &self.current_leader).map_err(|e| e.with_field("PartitionData", "current_leader", version))?;
             }
             if &self.snapshot_id != &Default::default() {
-                let computed_size = types::Struct { version }.compute_size(&self.snapshot_id)?;
+                let computed_size = cache.size_of(&self.snapshot_id, || CachedStruct { version, cache }.compute_size(&self.snapshot_id)).map_err(|e| e.with_field("PartitionData", "snapshot_id", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "snapshot_id", version));
                 }
-                types::UnsignedVarInt.encode(buf, 2)?;
-                types::UnsignedVarInt.encode(buf, computed_size as u32)?;
-                types::Struct { version }.encode(buf, &self.snapshot_id)?;
+                types::UnsignedVarInt.encode(buf, 2).map_err(|e| e.with_field("PartitionData", "snapshot_id", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionData", "snapshot_id", version))?;
+                CachedStruct { version, cache }.encode(buf, &self.snapshot_id).map_err(|e| e.with_field("PartitionData", "snapshot_id", version))?;
             }
 
-            write_unknown_tagged_fields(buf, 3.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 3.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::Int64.compute_size(&self.high_watermark)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("PartitionData", "partition_index", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("PartitionData", "error_code", version))?;
+        total_size += types::Int64.compute_size(&self.high_watermark).map_err(|e| e.with_field("PartitionData", "high_watermark", version))?;
         if version >= 4 {
-            total_size += types::Int64.compute_size(&self.last_stable_offset)?;
+            total_size += types::Int64.compute_size(&self.last_stable_offset).map_err(|e| e.with_field("PartitionData", "last_stable_offset", version))?;
         }
         if version >= 5 {
-            total_size += types::Int64.compute_size(&self.log_start_offset)?;
+            total_size += types::Int64.compute_size(&self.log_start_offset).map_err(|e| e.with_field("PartitionData", "log_start_offset", version))?;
         }
         if version >= 4 {
             if version >= 12 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.aborted_transactions)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.aborted_transactions).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.aborted_transactions)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.aborted_transactions).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version))?;
             }
         }
         if version >= 11 {
-            total_size += types::Int32.compute_size(&self.preferred_read_replica)?;
+            total_size += types::Int32.compute_size(&self.preferred_read_replica).map_err(|e| e.with_field("PartitionData", "preferred_read_replica", version))?;
         } else {
             if self.preferred_read_replica != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("PartitionData", "preferred_read_replica", version));
             }
         }
         if version >= 12 {
-            total_size += types::CompactBytes.compute_size(&self.records)?;
+            total_size += types::CompactBytes.compute_size(&self.records).map_err(|e| e.with_field("PartitionData", "records", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.records)?;
+            total_size += types::Bytes.compute_size(&self.records).map_err(|e| e.with_field("PartitionData", "records", version))?;
         }
         if version >= 12 {
             let mut num_tagged_fields = self.unknown_tagged_fields.len();
@@ -700,42 +1304,38 @@ impl Encodable for PartitionData {
                 num_tagged_fields += 1;
             }
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
             if &self.diverging_epoch != &Default::default() {
-                let computed_size = types::Struct { version }.compute_size(&self.diverging_epoch)?;
+                let computed_size = cache.size_of(&self.diverging_epoch, || CachedStruct { version, cache }.compute_size(&self.diverging_epoch)).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "diverging_epoch", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(0)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version))?;
                 total_size += computed_size;
             }
             if &self.current_leader != &Default::default() {
-                let computed_size = types::Struct { version }.compute_size(&self.current_leader)?;
+                let computed_size = cache.size_of(&self.current_leader, || CachedStruct { version, cache }.compute_size(&self.current_leader)).map_err(|e| e.with_field("PartitionData", "current_leader", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "current_leader", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(1)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(1).map_err(|e| e.with_field("PartitionData", "current_leader", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionData", "current_leader", version))?;
                 total_size += computed_size;
             }
             if &self.snapshot_id != &Default::default() {
-                let computed_size = types::Struct { version }.compute_size(&self.snapshot_id)?;
+                let computed_size = cache.size_of(&self.snapshot_id, || CachedStruct { version, cache }.compute_size(&self.snapshot_id)).map_err(|e| e.with_field("PartitionData", "snapshot_id", version))?;
                 if computed_size > std::u32::MAX as usize {
-                    error!("Tagged field is too large to encode ({} bytes)", computed_size);
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionData", "snapshot_id", version));
                 }
-                total_size += types::UnsignedVarInt.compute_size(2)?;
-                total_size += types::UnsignedVarInt.compute_size(computed_size as u32)?;
+                total_size += types::UnsignedVarInt.compute_size(2).map_err(|e| e.with_field("PartitionData", "snapshot_id", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionData", "snapshot_id", version))?;
                 total_size += computed_size;
             }
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -743,16 +1343,17 @@ impl Encodable for PartitionData {
 
 impl Decodable for PartitionData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let high_watermark = types::Int64.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "partition_index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("PartitionData", "error_code", version, start - buf.remaining()))?;
+        let high_watermark = types::Int64.decode(buf).map_err(|e| e.with_field("PartitionData", "high_watermark", version, start - buf.remaining()))?;
         let last_stable_offset = if version >= 4 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("PartitionData", "last_stable_offset", version, start - buf.remaining()))?
         } else {
             -1
         };
         let log_start_offset = if version >= 5 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("PartitionData", "log_start_offset", version, start - buf.remaining()))?
         } else {
             -1
         };
@@ -761,42 +1362,41 @@ impl Decodable for PartitionData {
         let mut snapshot_id = Default::default();
         let aborted_transactions = if version >= 4 {
             if version >= 12 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
         let preferred_read_replica = if version >= 11 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "preferred_read_replica", version, start - buf.remaining()))?
         } else {
             (-1).into()
         };
         let records = if version >= 12 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("PartitionData", "records", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("PartitionData", "records", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 match tag {
                     0 => {
-                        diverging_epoch = types::Struct { version }.decode(buf)?;
+                        diverging_epoch = types::Struct { version }.decode(buf).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version, start - buf.remaining()))?;
                     },
                     1 => {
-                        current_leader = types::Struct { version }.decode(buf)?;
+                        current_leader = types::Struct { version }.decode(buf).map_err(|e| e.with_field("PartitionData", "current_leader", version, start - buf.remaining()))?;
                     },
                     2 => {
-                        snapshot_id = types::Struct { version }.decode(buf)?;
+                        snapshot_id = types::Struct { version }.decode(buf).map_err(|e| e.with_field("PartitionData", "snapshot_id", version, start - buf.remaining()))?;
                     },
                     _ => {
-                        let mut unknown_value = vec![0; size as usize];
-                        buf.try_copy_to_slice(&mut unknown_value)?;
+                        let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("PartitionData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                         unknown_tagged_fields.insert(tag as i32, unknown_value);
                     }
                 }
@@ -833,7 +1433,7 @@ impl Default for PartitionData {
             aborted_transactions: Some(Default::default()),
             preferred_read_replica: (-1).into(),
             records: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -842,287 +1442,910 @@ impl Message for PartitionData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
-/// Valid versions: 0-15
-#[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
-#[builder(default)]
-pub struct FetchableTopicResponse {
-    /// The topic name.
-    /// 
-    /// Supported API versions: 0-12
-    pub topic: super::TopicName,
-
-    /// The unique topic ID
-    /// 
-    /// Supported API versions: 13-15
-    pub topic_id: Uuid,
-
-    /// The topic partitions.
-    /// 
-    /// Supported API versions: 0-15
-    pub partitions: Vec<PartitionData>,
-
-    /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
-}
-
-impl Builder for FetchableTopicResponse {
-    type Builder = FetchableTopicResponseBuilder;
-
-    fn builder() -> Self::Builder{
-        FetchableTopicResponseBuilder::default()
+impl PartitionData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
     }
-}
 
-impl Encodable for FetchableTopicResponse {
-    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        if version <= 12 {
-            if version >= 12 {
-                types::CompactString.encode(buf, &self.topic)?;
-            } else {
-                types::String.encode(buf, &self.topic)?;
-            }
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 4 {
+            self.last_stable_offset = -1;
         }
-        if version >= 13 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+        if version < 5 {
+            self.log_start_offset = -1;
         }
         if version >= 12 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+            self.diverging_epoch.normalize_to(version);
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+            self.diverging_epoch = Default::default();
         }
         if version >= 12 {
-            let num_tagged_fields = self.unknown_tagged_fields.len();
-            if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+            self.current_leader.normalize_to(version);
+        } else {
+            self.current_leader = Default::default();
+        }
+        if version >= 12 {
+            self.snapshot_id.normalize_to(version);
+        } else {
+            self.snapshot_id = Default::default();
+        }
+        if version >= 4 {
+            if let Some(items) = &mut self.aborted_transactions {
+                for item in items.iter_mut() {
+                    item.normalize_to(version);
+                }
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
-
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        } else {
+            self.aborted_transactions = Some(Default::default());
+        }
+        if version < 11 {
+            self.preferred_read_replica = (-1).into();
         }
-        Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
-        let mut total_size = 0;
-        if version <= 12 {
-            if version >= 12 {
-                total_size += types::CompactString.compute_size(&self.topic)?;
-            } else {
-                total_size += types::String.compute_size(&self.topic)?;
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 4 {
+            if self.last_stable_offset != -1 {
+                invalid.push(format!("{}last_stable_offset", path));
             }
         }
-        if version >= 13 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+        if version < 5 {
+            if self.log_start_offset != -1 {
+                invalid.push(format!("{}log_start_offset", path));
+            }
         }
         if version >= 12 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+            self.diverging_epoch.validate_into(version, &format!("{}diverging_epoch.", path), invalid);
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+            if &self.diverging_epoch != &Default::default() {
+                invalid.push(format!("{}diverging_epoch", path));
+            }
         }
         if version >= 12 {
-            let num_tagged_fields = self.unknown_tagged_fields.len();
-            if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+            self.current_leader.validate_into(version, &format!("{}current_leader.", path), invalid);
+        } else {
+            if &self.current_leader != &Default::default() {
+                invalid.push(format!("{}current_leader", path));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
-
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
         }
-        Ok(total_size)
-    }
-}
-
-impl Decodable for FetchableTopicResponse {
-    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let topic = if version <= 12 {
-            if version >= 12 {
-                types::CompactString.decode(buf)?
-            } else {
-                types::String.decode(buf)?
-            }
-        } else {
-            Default::default()
-        };
-        let topic_id = if version >= 13 {
-            types::Uuid.decode(buf)?
+        if version >= 12 {
+            self.snapshot_id.validate_into(version, &format!("{}snapshot_id.", path), invalid);
         } else {
-            Uuid::nil()
-        };
-        let partitions = if version >= 12 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            if &self.snapshot_id != &Default::default() {
+                invalid.push(format!("{}snapshot_id", path));
+            }
+        }
+        if version >= 4 {
+            if let Some(items) = &self.aborted_transactions {
+                for (i, item) in items.iter().enumerate() {
+                    item.validate_into(version, &format!("{}aborted_transactions[{}].", path, i), invalid);
+                }
+            }
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
-        };
-        let mut unknown_tagged_fields = BTreeMap::new();
-        if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
-            for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
-                unknown_tagged_fields.insert(tag as i32, unknown_value);
+            if !self.aborted_transactions.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}aborted_transactions", path));
             }
         }
-        Ok(Self {
-            topic,
-            topic_id,
-            partitions,
-            unknown_tagged_fields,
-        })
-    }
-}
-
-impl Default for FetchableTopicResponse {
-    fn default() -> Self {
-        Self {
-            topic: Default::default(),
-            topic_id: Uuid::nil(),
-            partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+        if version < 11 {
+            if self.preferred_read_replica != -1 {
+                invalid.push(format!("{}preferred_read_replica", path));
+            }
         }
     }
 }
 
-impl Message for FetchableTopicResponse {
-    const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
-}
-
-/// Valid versions: 0-15
-#[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
-#[builder(default)]
-pub struct FetchResponse {
-    /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
-    /// 
-    /// Supported API versions: 1-15
-    pub throttle_time_ms: i32,
-
-    /// The top level response error code.
-    /// 
-    /// Supported API versions: 7-15
-    pub error_code: i16,
-
-    /// The fetch session ID, or 0 if this is not part of a fetch session.
-    /// 
-    /// Supported API versions: 7-15
-    pub session_id: i32,
-
-    /// The response topics.
-    /// 
-    /// Supported API versions: 0-15
-    pub responses: Vec<FetchableTopicResponse>,
-
-    /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
-}
-
-impl Builder for FetchResponse {
-    type Builder = FetchResponseBuilder;
-
-    fn builder() -> Self::Builder{
-        FetchResponseBuilder::default()
+#[cfg(feature = "json")]
+impl PartitionData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
     }
-}
 
-impl Encodable for FetchResponse {
-    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        if version >= 1 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("highWatermark".into(), JsonValue::to_json(&self.high_watermark));
+        if version >= 4 {
+            node.insert("lastStableOffset".into(), JsonValue::to_json(&self.last_stable_offset));
         }
-        if version >= 7 {
-            types::Int16.encode(buf, &self.error_code)?;
+        if version >= 5 {
+            node.insert("logStartOffset".into(), JsonValue::to_json(&self.log_start_offset));
         }
-        if version >= 7 {
-            types::Int32.encode(buf, &self.session_id)?;
-        } else {
-            if self.session_id != 0 {
-                return Err(EncodeError)
+        if version >= 12 {
+            if &self.diverging_epoch != &Default::default() {
+                node.insert("divergingEpoch".into(), self.diverging_epoch.to_json_with(version, records));
             }
         }
         if version >= 12 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.responses)?;
-        } else {
-            types::Array(types::Struct { version }).encode(buf, &self.responses)?;
+            if &self.current_leader != &Default::default() {
+                node.insert("currentLeader".into(), self.current_leader.to_json_with(version, records));
+            }
         }
         if version >= 12 {
-            let num_tagged_fields = self.unknown_tagged_fields.len();
-            if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+            if &self.snapshot_id != &Default::default() {
+                node.insert("snapshotId".into(), self.snapshot_id.to_json_with(version, records));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
-
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
         }
-        Ok(())
-    }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
-        let mut total_size = 0;
-        if version >= 1 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+        if version >= 4 {
+            node.insert("abortedTransactions".into(), self.aborted_transactions.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
         }
-        if version >= 7 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+        if version >= 11 {
+            node.insert("preferredReadReplica".into(), JsonValue::to_json(&self.preferred_read_replica));
         }
-        if version >= 7 {
-            total_size += types::Int32.compute_size(&self.session_id)?;
+        crate::json::insert_records(&mut node, "records", self.records.as_ref(), records);
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "PartitionData.partitionIndex")?;
         } else {
-            if self.session_id != 0 {
-                return Err(EncodeError)
-            }
+            return Err(crate::json::missing("PartitionData", "partitionIndex", version));
         }
-        if version >= 12 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.responses)?;
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "PartitionData.errorCode")?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.responses)?;
+            return Err(crate::json::missing("PartitionData", "errorCode", version));
         }
-        if version >= 12 {
-            let num_tagged_fields = self.unknown_tagged_fields.len();
-            if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
-            }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
-
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        if let Some(value) = object.get("highWatermark") {
+            this.high_watermark = JsonValue::from_json(value, "PartitionData.highWatermark")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "highWatermark", version));
         }
-        Ok(total_size)
+        if let Some(value) = object.get("lastStableOffset") {
+            this.last_stable_offset = JsonValue::from_json(value, "PartitionData.lastStableOffset")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("PartitionData", "lastStableOffset", version));
+        }
+        if let Some(value) = object.get("logStartOffset") {
+            this.log_start_offset = JsonValue::from_json(value, "PartitionData.logStartOffset")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("PartitionData", "logStartOffset", version));
+        }
+        if let Some(value) = object.get("divergingEpoch") {
+            this.diverging_epoch = EpochEndOffset::from_json(value, version)?;
+        }
+        if let Some(value) = object.get("currentLeader") {
+            this.current_leader = LeaderIdAndEpoch::from_json(value, version)?;
+        }
+        if let Some(value) = object.get("snapshotId") {
+            this.snapshot_id = SnapshotId::from_json(value, version)?;
+        }
+        if let Some(value) = object.get("abortedTransactions") {
+            this.aborted_transactions = if value.is_null() { None } else { Some(crate::json::array(value, "PartitionData.abortedTransactions")?.iter().map(|item| AbortedTransaction::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else if version >= 4 {
+            return Err(crate::json::missing("PartitionData", "abortedTransactions", version));
+        }
+        if let Some(value) = object.get("preferredReadReplica") {
+            this.preferred_read_replica = JsonValue::from_json(value, "PartitionData.preferredReadReplica")?;
+        } else if version >= 11 {
+            return Err(crate::json::missing("PartitionData", "preferredReadReplica", version));
+        }
+        if let Some(value) = object.get("records") {
+            this.records = JsonValue::from_json(value, "PartitionData.records")?;
+        } else {
+            return Err(crate::json::missing("PartitionData", "records", version));
+        }
+        Ok(this)
     }
 }
 
-impl Decodable for FetchResponse {
-    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let throttle_time_ms = if version >= 1 {
-            types::Int32.decode(buf)?
-        } else {
-            0
+/// A view of [`PartitionData`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionDataView {
+    /// The partition index.
+    /// 
+    /// Supported API versions: 0-15
+    pub partition_index: i32,
+
+    /// The error code, or 0 if there was no fetch error.
+    /// 
+    /// Supported API versions: 0-15
+    pub error_code: i16,
+
+    /// The current high water mark.
+    /// 
+    /// Supported API versions: 0-15
+    pub high_watermark: i64,
+
+    /// The last stable offset (or LSO) of the partition. This is the last offset such that the state of all transactional records prior to this offset have been decided (ABORTED or COMMITTED)
+    /// 
+    /// Supported API versions: 4-15
+    pub last_stable_offset: i64,
+
+    /// The current log start offset.
+    /// 
+    /// Supported API versions: 5-15
+    pub log_start_offset: i64,
+
+    /// In case divergence is detected based on the `LastFetchedEpoch` and `FetchOffset` in the request, this field indicates the largest epoch and its end offset such that subsequent records are known to diverge
+    /// 
+    /// Supported API versions: 12-15
+    pub diverging_epoch: EpochEndOffsetView,
+
+    /// 
+    /// 
+    /// Supported API versions: 12-15
+    pub current_leader: LeaderIdAndEpochView,
+
+    /// In the case of fetching an offset less than the LogStartOffset, this is the end offset and epoch that should be used in the FetchSnapshot request.
+    /// 
+    /// Supported API versions: 12-15
+    pub snapshot_id: SnapshotIdView,
+
+    /// The aborted transactions.
+    /// 
+    /// Supported API versions: 4-15
+    pub aborted_transactions: Option<ArrayView<AbortedTransactionView>>,
+
+    /// The preferred read replica for the consumer to use on its next fetch request
+    /// 
+    /// Supported API versions: 11-15
+    pub preferred_read_replica: super::BrokerId,
+
+    /// The record data.
+    /// 
+    /// Supported API versions: 0-15
+    pub records: Option<Bytes>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl PartitionDataView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "partition_index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("PartitionData", "error_code", version, start - buf.remaining()))?;
+        let high_watermark = types::Int64.decode(buf).map_err(|e| e.with_field("PartitionData", "high_watermark", version, start - buf.remaining()))?;
+        let last_stable_offset = if version >= 4 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("PartitionData", "last_stable_offset", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let log_start_offset = if version >= 5 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("PartitionData", "log_start_offset", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let mut diverging_epoch = Default::default();
+        let mut current_leader = Default::default();
+        let mut snapshot_id = Default::default();
+        let aborted_transactions = if version >= 4 {
+            if version >= 12 {
+                ArrayView::decode_nullable(buf, version, true, AbortedTransactionView::decode, AbortedTransactionView::skip).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version, start - buf.remaining()))?
+            } else {
+                ArrayView::decode_nullable(buf, version, false, AbortedTransactionView::decode, AbortedTransactionView::skip).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version, start - buf.remaining()))?
+            }
+        } else {
+            Some(Default::default())
+        };
+        let preferred_read_replica = if version >= 11 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("PartitionData", "preferred_read_replica", version, start - buf.remaining()))?
+        } else {
+            (-1).into()
+        };
+        let records = if version >= 12 {
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("PartitionData", "records", version, start - buf.remaining()))?
+        } else {
+            types::Bytes.decode(buf).map_err(|e| e.with_field("PartitionData", "records", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionData", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("PartitionData", "tagged_fields", version, start - buf.remaining()))?;
+            for tagged_field in &tagged_fields {
+                let (tag, mut value) = tagged_field.map_err(|e| e.with_field("PartitionData", "tagged_fields", version, start - buf.remaining()))?;
+                match tag {
+                    0 => {
+                        diverging_epoch = EpochEndOffsetView::decode(&mut value, version).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version, start - buf.remaining()))?;
+                    },
+                    1 => {
+                        current_leader = LeaderIdAndEpochView::decode(&mut value, version).map_err(|e| e.with_field("PartitionData", "current_leader", version, start - buf.remaining()))?;
+                    },
+                    2 => {
+                        snapshot_id = SnapshotIdView::decode(&mut value, version).map_err(|e| e.with_field("PartitionData", "snapshot_id", version, start - buf.remaining()))?;
+                    },
+                    _ => {
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            partition_index,
+            error_code,
+            high_watermark,
+            last_stable_offset,
+            log_start_offset,
+            diverging_epoch,
+            current_leader,
+            snapshot_id,
+            aborted_transactions,
+            preferred_read_replica,
+            records,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("PartitionData", "partition_index", version, start - buf.remaining()))?;
+        view::skip_fixed(buf, 2).map_err(|e| e.with_field("PartitionData", "error_code", version, start - buf.remaining()))?;
+        view::skip_fixed(buf, 8).map_err(|e| e.with_field("PartitionData", "high_watermark", version, start - buf.remaining()))?;
+        if version >= 4 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("PartitionData", "last_stable_offset", version, start - buf.remaining()))?;
+        }
+        if version >= 5 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("PartitionData", "log_start_offset", version, start - buf.remaining()))?;
+        }
+        if version < 12 {
+            if version >= 12 {
+                EpochEndOffsetView::skip(buf, version).map_err(|e| e.with_field("PartitionData", "diverging_epoch", version, start - buf.remaining()))?;
+            }
+        }
+        if version < 12 {
+            if version >= 12 {
+                LeaderIdAndEpochView::skip(buf, version).map_err(|e| e.with_field("PartitionData", "current_leader", version, start - buf.remaining()))?;
+            }
+        }
+        if version < 12 {
+            if version >= 12 {
+                SnapshotIdView::skip(buf, version).map_err(|e| e.with_field("PartitionData", "snapshot_id", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 4 {
+            if version >= 12 {
+                view::skip_array(buf, version, true, AbortedTransactionView::skip).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version, start - buf.remaining()))?;
+            } else {
+                view::skip_array(buf, version, false, AbortedTransactionView::skip).map_err(|e| e.with_field("PartitionData", "aborted_transactions", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 11 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("PartitionData", "preferred_read_replica", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_bytes(buf, true).map_err(|e| e.with_field("PartitionData", "records", version, start - buf.remaining()))?;
+        } else {
+            view::skip_bytes(buf, false).map_err(|e| e.with_field("PartitionData", "records", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("PartitionData", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for PartitionDataView {
+    fn default() -> Self {
+        Self {
+            partition_index: 0,
+            error_code: 0,
+            high_watermark: 0,
+            last_stable_offset: -1,
+            log_start_offset: -1,
+            diverging_epoch: Default::default(),
+            current_leader: Default::default(),
+            snapshot_id: Default::default(),
+            aborted_transactions: Some(Default::default()),
+            preferred_read_replica: (-1).into(),
+            records: Some(Default::default()),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct FetchableTopicResponse {
+    /// The topic name.
+    /// 
+    /// Supported API versions: 0-12
+    pub topic: super::TopicName,
+
+    /// The unique topic ID
+    /// 
+    /// Supported API versions: 13-15
+    pub topic_id: Uuid,
+
+    /// The topic partitions.
+    /// 
+    /// Supported API versions: 0-15
+    pub partitions: Vec<PartitionData>,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for FetchableTopicResponse {
+    type Builder = FetchableTopicResponseBuilder;
+
+    fn builder() -> Self::Builder{
+        FetchableTopicResponseBuilder::default()
+    }
+}
+
+impl Encodable for FetchableTopicResponse {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if version <= 12 {
+            if version >= 12 {
+                types::CompactString.encode(buf, &self.topic).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version))?;
+            } else {
+                types::String.encode(buf, &self.topic).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version))?;
+            }
+        }
+        if version >= 13 {
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("FetchableTopicResponse", "topic_id", version))?;
+        }
+        if version >= 12 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version))?;
+        }
+        if version >= 12 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchableTopicResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FetchableTopicResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FetchableTopicResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        if version <= 12 {
+            if version >= 12 {
+                total_size += types::CompactString.compute_size(&self.topic).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version))?;
+            } else {
+                total_size += types::String.compute_size(&self.topic).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version))?;
+            }
+        }
+        if version >= 13 {
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("FetchableTopicResponse", "topic_id", version))?;
+        }
+        if version >= 12 {
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version))?;
+        } else {
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version))?;
+        }
+        if version >= 12 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchableTopicResponse", "unknown_tagged_fields", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FetchableTopicResponse", "unknown_tagged_fields", version))?;
+
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FetchableTopicResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(total_size)
+    }
+}
+
+impl Decodable for FetchableTopicResponse {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let topic = if version <= 12 {
+            if version >= 12 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version, start - buf.remaining()))?
+            }
+        } else {
+            Default::default()
+        };
+        let topic_id = if version >= 13 {
+            types::Uuid.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "topic_id", version, start - buf.remaining()))?
+        } else {
+            Uuid::nil()
+        };
+        let partitions = if version >= 12 {
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version, start - buf.remaining()))?
+        } else {
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version, start - buf.remaining()))?
+        };
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        if version >= 12 {
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            for _ in 0..num_tagged_fields {
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FetchableTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                unknown_tagged_fields.insert(tag as i32, unknown_value);
+            }
+        }
+        Ok(Self {
+            topic,
+            topic_id,
+            partitions,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for FetchableTopicResponse {
+    fn default() -> Self {
+        Self {
+            topic: Default::default(),
+            topic_id: Uuid::nil(),
+            partitions: Default::default(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for FetchableTopicResponse {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
+}
+
+impl FetchableTopicResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 12 {
+            self.topic = Default::default();
+        }
+        if version < 13 {
+            self.topic_id = Uuid::nil();
+        }
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 12 {
+            if !self.topic.is_empty() {
+                invalid.push(format!("{}topic", path));
+            }
+        }
+        if version < 13 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FetchableTopicResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 12 {
+            node.insert("topic".into(), JsonValue::to_json(&self.topic));
+        }
+        if version >= 13 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "FetchableTopicResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topic") {
+            this.topic = JsonValue::from_json(value, "FetchableTopicResponse.topic")?;
+        } else if version <= 12 {
+            return Err(crate::json::missing("FetchableTopicResponse", "topic", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "FetchableTopicResponse.topicId")?;
+        } else if version >= 13 {
+            return Err(crate::json::missing("FetchableTopicResponse", "topicId", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "FetchableTopicResponse.partitions")?.iter().map(|item| PartitionData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("FetchableTopicResponse", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`FetchableTopicResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchableTopicResponseView {
+    /// The topic name.
+    /// 
+    /// Supported API versions: 0-12
+    pub topic: super::TopicName,
+
+    /// The unique topic ID
+    /// 
+    /// Supported API versions: 13-15
+    pub topic_id: Uuid,
+
+    /// The topic partitions.
+    /// 
+    /// Supported API versions: 0-15
+    pub partitions: ArrayView<PartitionDataView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl FetchableTopicResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let topic = if version <= 12 {
+            if version >= 12 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version, start - buf.remaining()))?
+            }
+        } else {
+            Default::default()
+        };
+        let topic_id = if version >= 13 {
+            types::Uuid.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "topic_id", version, start - buf.remaining()))?
+        } else {
+            Uuid::nil()
+        };
+        let partitions = if version >= 12 {
+            ArrayView::decode(buf, version, true, PartitionDataView::decode, PartitionDataView::skip).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, PartitionDataView::decode, PartitionDataView::skip).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchableTopicResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("FetchableTopicResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            topic,
+            topic_id,
+            partitions,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version <= 12 {
+            if version >= 12 {
+                view::skip_string(buf, true).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version, start - buf.remaining()))?;
+            } else {
+                view::skip_string(buf, false).map_err(|e| e.with_field("FetchableTopicResponse", "topic", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 13 {
+            view::skip_fixed(buf, 16).map_err(|e| e.with_field("FetchableTopicResponse", "topic_id", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_array(buf, version, true, PartitionDataView::skip).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, PartitionDataView::skip).map_err(|e| e.with_field("FetchableTopicResponse", "partitions", version, start - buf.remaining()))?;
+        }
+        if version >= 12 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("FetchableTopicResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for FetchableTopicResponseView {
+    fn default() -> Self {
+        Self {
+            topic: Default::default(),
+            topic_id: Uuid::nil(),
+            partitions: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct FetchResponse {
+    /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
+    /// 
+    /// Supported API versions: 1-15
+    pub throttle_time_ms: i32,
+
+    /// The top level response error code.
+    /// 
+    /// Supported API versions: 7-15
+    pub error_code: i16,
+
+    /// The fetch session ID, or 0 if this is not part of a fetch session.
+    /// 
+    /// Supported API versions: 7-15
+    pub session_id: i32,
+
+    /// The response topics.
+    /// 
+    /// Supported API versions: 0-15
+    pub responses: Vec<FetchableTopicResponse>,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for FetchResponse {
+    type Builder = FetchResponseBuilder;
+
+    fn builder() -> Self::Builder{
+        FetchResponseBuilder::default()
+    }
+}
+
+impl Encodable for FetchResponse {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FetchResponse", version));
+        }
+        if version >= 1 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("FetchResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 7 {
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("FetchResponse", "error_code", version))?;
+        }
+        if version >= 7 {
+            types::Int32.encode(buf, &self.session_id).map_err(|e| e.with_field("FetchResponse", "session_id", version))?;
+        } else {
+            if self.session_id != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchResponse", "session_id", version));
+            }
+        }
+        if version >= 12 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.responses).map_err(|e| e.with_field("FetchResponse", "responses", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.responses).map_err(|e| e.with_field("FetchResponse", "responses", version))?;
+        }
+        if version >= 12 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FetchResponse", version));
+        }
+        let mut total_size = 0;
+        if version >= 1 {
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("FetchResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 7 {
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("FetchResponse", "error_code", version))?;
+        }
+        if version >= 7 {
+            total_size += types::Int32.compute_size(&self.session_id).map_err(|e| e.with_field("FetchResponse", "session_id", version))?;
+        } else {
+            if self.session_id != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchResponse", "session_id", version));
+            }
+        }
+        if version >= 12 {
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.responses).map_err(|e| e.with_field("FetchResponse", "responses", version))?;
+        } else {
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.responses).map_err(|e| e.with_field("FetchResponse", "responses", version))?;
+        }
+        if version >= 12 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchResponse", "unknown_tagged_fields", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version))?;
+
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(total_size)
+    }
+}
+
+impl Decodable for FetchResponse {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("FetchResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = if version >= 1 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchResponse", "throttle_time_ms", version, start - buf.remaining()))?
+        } else {
+            0
         };
         let error_code = if version >= 7 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("FetchResponse", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
         let session_id = if version >= 7 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchResponse", "session_id", version, start - buf.remaining()))?
         } else {
             0
         };
         let responses = if version >= 12 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchResponse", "responses", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FetchResponse", "responses", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 12 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -1143,7 +2366,7 @@ impl Default for FetchResponse {
             error_code: 0,
             session_id: 0,
             responses: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -1152,6 +2375,245 @@ impl Message for FetchResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 15 };
 }
 
+impl FetchResponse {
+    /// Encode the message with the elements of `responses` produced by an iterator of known length, in place of
+    /// the `responses` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_responses<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, responses: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = FetchableTopicResponse>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FetchResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let responses = responses.into_iter();
+        if version >= 1 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("FetchResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 7 {
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("FetchResponse", "error_code", version))?;
+        }
+        if version >= 7 {
+            types::Int32.encode(buf, &self.session_id).map_err(|e| e.with_field("FetchResponse", "session_id", version))?;
+        } else {
+            if self.session_id != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FetchResponse", "session_id", version));
+            }
+        }
+        if version >= 12 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(responses)).map_err(|e| e.with_field("FetchResponse", "responses", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(responses)).map_err(|e| e.with_field("FetchResponse", "responses", version))?;
+        }
+        if version >= 12 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FetchResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FetchResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl FetchResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.throttle_time_ms = 0;
+        }
+        if version < 7 {
+            self.error_code = 0;
+        }
+        if version < 7 {
+            self.session_id = 0;
+        }
+        for item in self.responses.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        if version < 7 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+        if version < 7 {
+            if self.session_id != 0 {
+                invalid.push(format!("{}session_id", path));
+            }
+        }
+        for (i, item) in self.responses.iter().enumerate() {
+            item.validate_into(version, &format!("{}responses[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FetchResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        if version >= 7 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        if version >= 7 {
+            node.insert("sessionId".into(), JsonValue::to_json(&self.session_id));
+        }
+        node.insert("responses".into(), serde_json::Value::Array(self.responses.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "FetchResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "FetchResponse.throttleTimeMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("FetchResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "FetchResponse.errorCode")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("FetchResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("sessionId") {
+            this.session_id = JsonValue::from_json(value, "FetchResponse.sessionId")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("FetchResponse", "sessionId", version));
+        }
+        if let Some(value) = object.get("responses") {
+            this.responses = crate::json::array(value, "FetchResponse.responses")?.iter().map(|item| FetchableTopicResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("FetchResponse", "responses", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`FetchResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-15
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchResponseView {
+    /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
+    /// 
+    /// Supported API versions: 1-15
+    pub throttle_time_ms: i32,
+
+    /// The top level response error code.
+    /// 
+    /// Supported API versions: 7-15
+    pub error_code: i16,
+
+    /// The fetch session ID, or 0 if this is not part of a fetch session.
+    /// 
+    /// Supported API versions: 7-15
+    pub session_id: i32,
+
+    /// The response topics.
+    /// 
+    /// Supported API versions: 0-15
+    pub responses: ArrayView<FetchableTopicResponseView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl FetchResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        if !FetchResponse::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("FetchResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = if version >= 1 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchResponse", "throttle_time_ms", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let error_code = if version >= 7 {
+            types::Int16.decode(buf).map_err(|e| e.with_field("FetchResponse", "error_code", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let session_id = if version >= 7 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("FetchResponse", "session_id", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let responses = if version >= 12 {
+            ArrayView::decode(buf, version, true, FetchableTopicResponseView::decode, FetchableTopicResponseView::skip).map_err(|e| e.with_field("FetchResponse", "responses", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, FetchableTopicResponseView::decode, FetchableTopicResponseView::skip).map_err(|e| e.with_field("FetchResponse", "responses", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 12 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FetchResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("FetchResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            throttle_time_ms,
+            error_code,
+            session_id,
+            responses,
+            tagged_fields,
+        })
+    }
+}
+
+impl Default for FetchResponseView {
+    fn default() -> Self {
+        Self {
+            throttle_time_ms: 0,
+            error_code: 0,
+            session_id: 0,
+            responses: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 impl HeaderVersion for FetchResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 12 {
@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct FindCoordinatorRequest {
     /// The coordinator key.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub key: StrBytes,
 
     /// The coordinator key type. (Group, transaction, etc.)
@@ -35,10 +42,11 @@ pub struct FindCoordinatorRequest {
     /// The coordinator keys.
     /// 
     /// Supported API versions: 4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub coordinator_keys: Vec<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for FindCoordinatorRequest {
@@ -51,79 +59,89 @@ impl Builder for FindCoordinatorRequest {
 
 impl Encodable for FindCoordinatorRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FindCoordinatorRequest", version));
+        }
         if version <= 3 {
             if version >= 3 {
-                types::CompactString.encode(buf, &self.key)?;
+                types::CompactString.encode(buf, &self.key).map_err(|e| e.with_field("FindCoordinatorRequest", "key", version))?;
             } else {
-                types::String.encode(buf, &self.key)?;
+                types::String.encode(buf, &self.key).map_err(|e| e.with_field("FindCoordinatorRequest", "key", version))?;
             }
         } else {
             if !self.key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "key", version));
             }
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.key_type)?;
+            types::Int8.encode(buf, &self.key_type).map_err(|e| e.with_field("FindCoordinatorRequest", "key_type", version))?;
         } else {
             if self.key_type != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "key_type", version));
             }
         }
         if version >= 4 {
-            types::CompactArray(types::CompactString).encode(buf, &self.coordinator_keys)?;
+            types::CompactArray(types::CompactString).encode(buf, &self.coordinator_keys).map_err(|e| e.with_field("FindCoordinatorRequest", "coordinator_keys", version))?;
         } else {
             if !self.coordinator_keys.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "coordinator_keys", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FindCoordinatorRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FindCoordinatorRequest", version));
+        }
         let mut total_size = 0;
         if version <= 3 {
             if version >= 3 {
-                total_size += types::CompactString.compute_size(&self.key)?;
+                total_size += types::CompactString.compute_size(&self.key).map_err(|e| e.with_field("FindCoordinatorRequest", "key", version))?;
             } else {
-                total_size += types::String.compute_size(&self.key)?;
+                total_size += types::String.compute_size(&self.key).map_err(|e| e.with_field("FindCoordinatorRequest", "key", version))?;
             }
         } else {
             if !self.key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "key", version));
             }
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.key_type)?;
+            total_size += types::Int8.compute_size(&self.key_type).map_err(|e| e.with_field("FindCoordinatorRequest", "key_type", version))?;
         } else {
             if self.key_type != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "key_type", version));
             }
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::CompactString).compute_size(&self.coordinator_keys)?;
+            total_size += types::CompactArray(types::CompactString).compute_size(&self.coordinator_keys).map_err(|e| e.with_field("FindCoordinatorRequest", "coordinator_keys", version))?;
         } else {
             if !self.coordinator_keys.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "coordinator_keys", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FindCoordinatorRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -131,33 +149,36 @@ impl Encodable for FindCoordinatorRequest {
 
 impl Decodable for FindCoordinatorRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("FindCoordinatorRequest", version));
+        }
+        let start = buf.remaining();
         let key = if version <= 3 {
             if version >= 3 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FindCoordinatorRequest", "key", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("FindCoordinatorRequest", "key", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let key_type = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("FindCoordinatorRequest", "key_type", version, start - buf.remaining()))?
         } else {
             0
         };
         let coordinator_keys = if version >= 4 {
-            types::CompactArray(types::CompactString).decode(buf)?
+            types::CompactArray(types::CompactString).decode(buf).map_err(|e| e.with_field("FindCoordinatorRequest", "coordinator_keys", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -176,7 +197,7 @@ impl Default for FindCoordinatorRequest {
             key: Default::default(),
             key_type: 0,
             coordinator_keys: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -185,6 +206,156 @@ impl Message for FindCoordinatorRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl FindCoordinatorRequest {
+    /// Encode the message with the elements of `coordinator_keys` produced by an iterator of known length, in place of
+    /// the `coordinator_keys` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_coordinator_keys<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, coordinator_keys: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = StrBytes>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FindCoordinatorRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let coordinator_keys = coordinator_keys.into_iter();
+        if version <= 3 {
+            if version >= 3 {
+                types::CompactString.encode(buf, &self.key).map_err(|e| e.with_field("FindCoordinatorRequest", "key", version))?;
+            } else {
+                types::String.encode(buf, &self.key).map_err(|e| e.with_field("FindCoordinatorRequest", "key", version))?;
+            }
+        } else {
+            if !self.key.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "key", version));
+            }
+        }
+        if version >= 1 {
+            types::Int8.encode(buf, &self.key_type).map_err(|e| e.with_field("FindCoordinatorRequest", "key_type", version))?;
+        } else {
+            if self.key_type != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "key_type", version));
+            }
+        }
+        if version >= 4 {
+            types::CompactArray(types::CompactString).encode(buf, types::Streamed(coordinator_keys)).map_err(|e| e.with_field("FindCoordinatorRequest", "coordinator_keys", version))?;
+        } else {
+            if coordinator_keys.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorRequest", "coordinator_keys", version));
+            }
+        }
+        if version >= 3 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FindCoordinatorRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FindCoordinatorRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl FindCoordinatorRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 3 {
+            self.key = Default::default();
+        }
+        if version < 1 {
+            self.key_type = 0;
+        }
+        if version < 4 {
+            self.coordinator_keys = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 3 {
+            if !self.key.is_empty() {
+                invalid.push(format!("{}key", path));
+            }
+        }
+        if version < 1 {
+            if self.key_type != 0 {
+                invalid.push(format!("{}key_type", path));
+            }
+        }
+        if version < 4 {
+            if !self.coordinator_keys.is_empty() {
+                invalid.push(format!("{}coordinator_keys", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FindCoordinatorRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 3 {
+            node.insert("key".into(), JsonValue::to_json(&self.key));
+        }
+        if version >= 1 {
+            node.insert("keyType".into(), JsonValue::to_json(&self.key_type));
+        }
+        if version >= 4 {
+            node.insert("coordinatorKeys".into(), JsonValue::to_json(&self.coordinator_keys));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "FindCoordinatorRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("key") {
+            this.key = JsonValue::from_json(value, "FindCoordinatorRequest.key")?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("FindCoordinatorRequest", "key", version));
+        }
+        if let Some(value) = object.get("keyType") {
+            this.key_type = JsonValue::from_json(value, "FindCoordinatorRequest.keyType")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("FindCoordinatorRequest", "keyType", version));
+        }
+        if let Some(value) = object.get("coordinatorKeys") {
+            this.coordinator_keys = JsonValue::from_json(value, "FindCoordinatorRequest.coordinatorKeys")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("FindCoordinatorRequest", "coordinatorKeys", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for FindCoordinatorRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 3 {
@@ -195,3 +366,28 @@ impl HeaderVersion for FindCoordinatorRequest {
     }
 }
 
+impl FindCoordinatorRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::FindCoordinatorResponse {
+        let mut response = super::FindCoordinatorResponse::default();
+        if version <= 3 {
+            response.error_code = error.code();
+        }
+        if version >= 4 {
+            let mut items0 = Vec::new();
+            for request0 in &self.coordinator_keys {
+                let mut response0 = super::find_coordinator_response::Coordinator::default();
+                response0.key = request0.clone();
+                response0.error_code = error.code();
+                items0.push(response0);
+            }
+            response.coordinators = items0;
+        }
+        response
+    }
+}
+
@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct Coordinator {
     /// The coordinator key.
     /// 
     /// Supported API versions: 4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub key: StrBytes,
 
     /// The node id.
@@ -35,6 +42,7 @@ pub struct Coordinator {
     /// The host name.
     /// 
     /// Supported API versions: 4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The port.
@@ -50,10 +58,12 @@ pub struct Coordinator {
     /// The error message, or null if there was no error.
     /// 
     /// Supported API versions: 4
+    /// May be `None` in versions: 4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for Coordinator {
@@ -66,105 +76,109 @@ impl Builder for Coordinator {
 
 impl Encodable for Coordinator {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 4 {
-            types::CompactString.encode(buf, &self.key)?;
+            types::CompactString.encode(buf, &self.key).map_err(|e| e.with_field("Coordinator", "key", version))?;
         } else {
             if !self.key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "key", version));
             }
         }
         if version >= 4 {
-            types::Int32.encode(buf, &self.node_id)?;
+            types::Int32.encode(buf, &self.node_id).map_err(|e| e.with_field("Coordinator", "node_id", version))?;
         } else {
             if self.node_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "node_id", version));
             }
         }
         if version >= 4 {
-            types::CompactString.encode(buf, &self.host)?;
+            types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("Coordinator", "host", version))?;
         } else {
             if !self.host.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "host", version));
             }
         }
         if version >= 4 {
-            types::Int32.encode(buf, &self.port)?;
+            types::Int32.encode(buf, &self.port).map_err(|e| e.with_field("Coordinator", "port", version))?;
         } else {
             if self.port != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "port", version));
             }
         }
         if version >= 4 {
-            types::Int16.encode(buf, &self.error_code)?;
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("Coordinator", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "error_code", version));
             }
         }
         if version >= 4 {
-            types::CompactString.encode(buf, &self.error_message)?;
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("Coordinator", "error_message", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Coordinator", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("Coordinator", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("Coordinator", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.key)?;
+            total_size += types::CompactString.compute_size(&self.key).map_err(|e| e.with_field("Coordinator", "key", version))?;
         } else {
             if !self.key.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "key", version));
             }
         }
         if version >= 4 {
-            total_size += types::Int32.compute_size(&self.node_id)?;
+            total_size += types::Int32.compute_size(&self.node_id).map_err(|e| e.with_field("Coordinator", "node_id", version))?;
         } else {
             if self.node_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "node_id", version));
             }
         }
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.host)?;
+            total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("Coordinator", "host", version))?;
         } else {
             if !self.host.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "host", version));
             }
         }
         if version >= 4 {
-            total_size += types::Int32.compute_size(&self.port)?;
+            total_size += types::Int32.compute_size(&self.port).map_err(|e| e.with_field("Coordinator", "port", version))?;
         } else {
             if self.port != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "port", version));
             }
         }
         if version >= 4 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("Coordinator", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("Coordinator", "error_code", version));
             }
         }
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.error_message)?;
+            total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("Coordinator", "error_message", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("Coordinator", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("Coordinator", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("Coordinator", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -172,44 +186,44 @@ impl Encodable for Coordinator {
 
 impl Decodable for Coordinator {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let key = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("Coordinator", "key", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let node_id = if version >= 4 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("Coordinator", "node_id", version, start - buf.remaining()))?
         } else {
             (0).into()
         };
         let host = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("Coordinator", "host", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let port = if version >= 4 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("Coordinator", "port", version, start - buf.remaining()))?
         } else {
             0
         };
         let error_code = if version >= 4 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("Coordinator", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
         let error_message = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("Coordinator", "error_message", version, start - buf.remaining()))?
         } else {
             Some(Default::default())
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Coordinator", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Coordinator", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("Coordinator", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("Coordinator", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -234,7 +248,7 @@ impl Default for Coordinator {
             port: 0,
             error_code: 0,
             error_message: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -243,9 +257,153 @@ impl Message for Coordinator {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl Coordinator {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 4 {
+            self.key = Default::default();
+        }
+        if version < 4 {
+            self.node_id = (0).into();
+        }
+        if version < 4 {
+            self.host = Default::default();
+        }
+        if version < 4 {
+            self.port = 0;
+        }
+        if version < 4 {
+            self.error_code = 0;
+        }
+        if version < 4 {
+            self.error_message = Some(Default::default());
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 4 {
+            if !self.key.is_empty() {
+                invalid.push(format!("{}key", path));
+            }
+        }
+        if version < 4 {
+            if self.node_id != 0 {
+                invalid.push(format!("{}node_id", path));
+            }
+        }
+        if version < 4 {
+            if !self.host.is_empty() {
+                invalid.push(format!("{}host", path));
+            }
+        }
+        if version < 4 {
+            if self.port != 0 {
+                invalid.push(format!("{}port", path));
+            }
+        }
+        if version < 4 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+        if version < 4 {
+            if !self.error_message.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}error_message", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl Coordinator {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 4 {
+            node.insert("key".into(), JsonValue::to_json(&self.key));
+        }
+        if version >= 4 {
+            node.insert("nodeId".into(), JsonValue::to_json(&self.node_id));
+        }
+        if version >= 4 {
+            node.insert("host".into(), JsonValue::to_json(&self.host));
+        }
+        if version >= 4 {
+            node.insert("port".into(), JsonValue::to_json(&self.port));
+        }
+        if version >= 4 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        if version >= 4 {
+            node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "Coordinator")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("key") {
+            this.key = JsonValue::from_json(value, "Coordinator.key")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("Coordinator", "key", version));
+        }
+        if let Some(value) = object.get("nodeId") {
+            this.node_id = JsonValue::from_json(value, "Coordinator.nodeId")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("Coordinator", "nodeId", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "Coordinator.host")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("Coordinator", "host", version));
+        }
+        if let Some(value) = object.get("port") {
+            this.port = JsonValue::from_json(value, "Coordinator.port")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("Coordinator", "port", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "Coordinator.errorCode")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("Coordinator", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "Coordinator.errorMessage")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("Coordinator", "errorMessage", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct FindCoordinatorResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -261,6 +419,8 @@ pub struct FindCoordinatorResponse {
     /// The error message, or null if there was no error.
     /// 
     /// Supported API versions: 1-3
+    /// May be `None` in versions: 1-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The node id.
@@ -271,6 +431,7 @@ pub struct FindCoordinatorResponse {
     /// The host name.
     /// 
     /// Supported API versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The port.
@@ -284,7 +445,7 @@ pub struct FindCoordinatorResponse {
     pub coordinators: Vec<Coordinator>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for FindCoordinatorResponse {
@@ -297,127 +458,137 @@ impl Builder for FindCoordinatorResponse {
 
 impl Encodable for FindCoordinatorResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FindCoordinatorResponse", version));
+        }
         if version >= 1 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("FindCoordinatorResponse", "throttle_time_ms", version))?;
         }
         if version <= 3 {
-            types::Int16.encode(buf, &self.error_code)?;
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("FindCoordinatorResponse", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "error_code", version));
             }
         }
         if version >= 1 && version <= 3 {
             if version >= 3 {
-                types::CompactString.encode(buf, &self.error_message)?;
+                types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("FindCoordinatorResponse", "error_message", version))?;
             } else {
-                types::String.encode(buf, &self.error_message)?;
+                types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("FindCoordinatorResponse", "error_message", version))?;
             }
         }
         if version <= 3 {
-            types::Int32.encode(buf, &self.node_id)?;
+            types::Int32.encode(buf, &self.node_id).map_err(|e| e.with_field("FindCoordinatorResponse", "node_id", version))?;
         } else {
             if self.node_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "node_id", version));
             }
         }
         if version <= 3 {
             if version >= 3 {
-                types::CompactString.encode(buf, &self.host)?;
+                types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("FindCoordinatorResponse", "host", version))?;
             } else {
-                types::String.encode(buf, &self.host)?;
+                types::String.encode(buf, &self.host).map_err(|e| e.with_field("FindCoordinatorResponse", "host", version))?;
             }
         } else {
             if !self.host.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "host", version));
             }
         }
         if version <= 3 {
-            types::Int32.encode(buf, &self.port)?;
+            types::Int32.encode(buf, &self.port).map_err(|e| e.with_field("FindCoordinatorResponse", "port", version))?;
         } else {
             if self.port != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "port", version));
             }
         }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.coordinators)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.coordinators).map_err(|e| e.with_field("FindCoordinatorResponse", "coordinators", version))?;
         } else {
             if !self.coordinators.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "coordinators", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FindCoordinatorResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FindCoordinatorResponse", version));
+        }
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("FindCoordinatorResponse", "throttle_time_ms", version))?;
         }
         if version <= 3 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("FindCoordinatorResponse", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "error_code", version));
             }
         }
         if version >= 1 && version <= 3 {
             if version >= 3 {
-                total_size += types::CompactString.compute_size(&self.error_message)?;
+                total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("FindCoordinatorResponse", "error_message", version))?;
             } else {
-                total_size += types::String.compute_size(&self.error_message)?;
+                total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("FindCoordinatorResponse", "error_message", version))?;
             }
         }
         if version <= 3 {
-            total_size += types::Int32.compute_size(&self.node_id)?;
+            total_size += types::Int32.compute_size(&self.node_id).map_err(|e| e.with_field("FindCoordinatorResponse", "node_id", version))?;
         } else {
             if self.node_id != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "node_id", version));
             }
         }
         if version <= 3 {
             if version >= 3 {
-                total_size += types::CompactString.compute_size(&self.host)?;
+                total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("FindCoordinatorResponse", "host", version))?;
             } else {
-                total_size += types::String.compute_size(&self.host)?;
+                total_size += types::String.compute_size(&self.host).map_err(|e| e.with_field("FindCoordinatorResponse", "host", version))?;
             }
         } else {
             if !self.host.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "host", version));
             }
         }
         if version <= 3 {
-            total_size += types::Int32.compute_size(&self.port)?;
+            total_size += types::Int32.compute_size(&self.port).map_err(|e| e.with_field("FindCoordinatorResponse", "port", version))?;
         } else {
             if self.port != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "port", version));
             }
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.coordinators)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.coordinators).map_err(|e| e.with_field("FindCoordinatorResponse", "coordinators", version))?;
         } else {
             if !self.coordinators.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "coordinators", version));
             }
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FindCoordinatorResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -425,57 +596,60 @@ impl Encodable for FindCoordinatorResponse {
 
 impl Decodable for FindCoordinatorResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("FindCoordinatorResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
         let error_code = if version <= 3 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
         let error_message = if version >= 1 && version <= 3 {
             if version >= 3 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "error_message", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "error_message", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
         let node_id = if version <= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "node_id", version, start - buf.remaining()))?
         } else {
             (0).into()
         };
         let host = if version <= 3 {
             if version >= 3 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "host", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "host", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let port = if version <= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "port", version, start - buf.remaining()))?
         } else {
             0
         };
         let coordinators = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "coordinators", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -502,7 +676,7 @@ impl Default for FindCoordinatorResponse {
             host: Default::default(),
             port: 0,
             coordinators: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -511,6 +685,252 @@ impl Message for FindCoordinatorResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl FindCoordinatorResponse {
+    /// Encode the message with the elements of `coordinators` produced by an iterator of known length, in place of
+    /// the `coordinators` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_coordinators<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, coordinators: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = Coordinator>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("FindCoordinatorResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let coordinators = coordinators.into_iter();
+        if version >= 1 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("FindCoordinatorResponse", "throttle_time_ms", version))?;
+        }
+        if version <= 3 {
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("FindCoordinatorResponse", "error_code", version))?;
+        } else {
+            if self.error_code != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "error_code", version));
+            }
+        }
+        if version >= 1 && version <= 3 {
+            if version >= 3 {
+                types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("FindCoordinatorResponse", "error_message", version))?;
+            } else {
+                types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("FindCoordinatorResponse", "error_message", version))?;
+            }
+        }
+        if version <= 3 {
+            types::Int32.encode(buf, &self.node_id).map_err(|e| e.with_field("FindCoordinatorResponse", "node_id", version))?;
+        } else {
+            if self.node_id != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "node_id", version));
+            }
+        }
+        if version <= 3 {
+            if version >= 3 {
+                types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("FindCoordinatorResponse", "host", version))?;
+            } else {
+                types::String.encode(buf, &self.host).map_err(|e| e.with_field("FindCoordinatorResponse", "host", version))?;
+            }
+        } else {
+            if !self.host.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "host", version));
+            }
+        }
+        if version <= 3 {
+            types::Int32.encode(buf, &self.port).map_err(|e| e.with_field("FindCoordinatorResponse", "port", version))?;
+        } else {
+            if self.port != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "port", version));
+            }
+        }
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(coordinators)).map_err(|e| e.with_field("FindCoordinatorResponse", "coordinators", version))?;
+        } else {
+            if coordinators.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FindCoordinatorResponse", "coordinators", version));
+            }
+        }
+        if version >= 3 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FindCoordinatorResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FindCoordinatorResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl FindCoordinatorResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.throttle_time_ms = 0;
+        }
+        if version > 3 {
+            self.error_code = 0;
+        }
+        if version < 1 || version > 3 {
+            self.error_message = Some(Default::default());
+        }
+        if version > 3 {
+            self.node_id = (0).into();
+        }
+        if version > 3 {
+            self.host = Default::default();
+        }
+        if version > 3 {
+            self.port = 0;
+        }
+        if version >= 4 {
+            for item in self.coordinators.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.coordinators = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        if version > 3 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+        if version < 1 || version > 3 {
+            if !self.error_message.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}error_message", path));
+            }
+        }
+        if version > 3 {
+            if self.node_id != 0 {
+                invalid.push(format!("{}node_id", path));
+            }
+        }
+        if version > 3 {
+            if !self.host.is_empty() {
+                invalid.push(format!("{}host", path));
+            }
+        }
+        if version > 3 {
+            if self.port != 0 {
+                invalid.push(format!("{}port", path));
+            }
+        }
+        if version >= 4 {
+            for (i, item) in self.coordinators.iter().enumerate() {
+                item.validate_into(version, &format!("{}coordinators[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.coordinators.is_empty() {
+                invalid.push(format!("{}coordinators", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FindCoordinatorResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        if version <= 3 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        if version >= 1 && version <= 3 {
+            node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        }
+        if version <= 3 {
+            node.insert("nodeId".into(), JsonValue::to_json(&self.node_id));
+        }
+        if version <= 3 {
+            node.insert("host".into(), JsonValue::to_json(&self.host));
+        }
+        if version <= 3 {
+            node.insert("port".into(), JsonValue::to_json(&self.port));
+        }
+        if version >= 4 {
+            node.insert("coordinators".into(), serde_json::Value::Array(self.coordinators.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "FindCoordinatorResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "FindCoordinatorResponse.throttleTimeMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("FindCoordinatorResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "FindCoordinatorResponse.errorCode")?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("FindCoordinatorResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "FindCoordinatorResponse.errorMessage")?;
+        } else if version >= 1 && version <= 3 {
+            return Err(crate::json::missing("FindCoordinatorResponse", "errorMessage", version));
+        }
+        if let Some(value) = object.get("nodeId") {
+            this.node_id = JsonValue::from_json(value, "FindCoordinatorResponse.nodeId")?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("FindCoordinatorResponse", "nodeId", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "FindCoordinatorResponse.host")?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("FindCoordinatorResponse", "host", version));
+        }
+        if let Some(value) = object.get("port") {
+            this.port = JsonValue::from_json(value, "FindCoordinatorResponse.port")?;
+        } else if version <= 3 {
+            return Err(crate::json::missing("FindCoordinatorResponse", "port", version));
+        }
+        if let Some(value) = object.get("coordinators") {
+            this.coordinators = crate::json::array(value, "FindCoordinatorResponse.coordinators")?.iter().map(|item| Coordinator::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("FindCoordinatorResponse", "coordinators", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for FindCoordinatorResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 3 {
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct HeartbeatRequest {
     /// The group id.
@@ -35,15 +41,18 @@ pub struct HeartbeatRequest {
     /// The member ID.
     /// 
     /// Supported API versions: 0-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The unique identifier of the consumer instance provided by end user.
     /// 
     /// Supported API versions: 3-4
+    /// May be `None` in versions: 3-4
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_instance_id: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for HeartbeatRequest {
@@ -56,73 +65,83 @@ impl Builder for HeartbeatRequest {
 
 impl Encodable for HeartbeatRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("HeartbeatRequest", version));
+        }
         if version >= 4 {
-            types::CompactString.encode(buf, &self.group_id)?;
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("HeartbeatRequest", "group_id", version))?;
         } else {
-            types::String.encode(buf, &self.group_id)?;
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("HeartbeatRequest", "group_id", version))?;
         }
-        types::Int32.encode(buf, &self.generation_id)?;
+        types::Int32.encode(buf, &self.generation_id).map_err(|e| e.with_field("HeartbeatRequest", "generation_id", version))?;
         if version >= 4 {
-            types::CompactString.encode(buf, &self.member_id)?;
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("HeartbeatRequest", "member_id", version))?;
         } else {
-            types::String.encode(buf, &self.member_id)?;
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("HeartbeatRequest", "member_id", version))?;
         }
         if version >= 3 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.group_instance_id)?;
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("HeartbeatRequest", "group_instance_id", version))?;
             } else {
-                types::String.encode(buf, &self.group_instance_id)?;
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("HeartbeatRequest", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("HeartbeatRequest", "group_instance_id", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("HeartbeatRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("HeartbeatRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("HeartbeatRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("HeartbeatRequest", version));
+        }
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.group_id)?;
+            total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("HeartbeatRequest", "group_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.group_id)?;
+            total_size += types::String.compute_size(&self.group_id).map_err(|e| e.with_field("HeartbeatRequest", "group_id", version))?;
         }
-        total_size += types::Int32.compute_size(&self.generation_id)?;
+        total_size += types::Int32.compute_size(&self.generation_id).map_err(|e| e.with_field("HeartbeatRequest", "generation_id", version))?;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.member_id)?;
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("HeartbeatRequest", "member_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.member_id)?;
+            total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("HeartbeatRequest", "member_id", version))?;
         }
         if version >= 3 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.group_instance_id)?;
+                total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("HeartbeatRequest", "group_instance_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.group_instance_id)?;
+                total_size += types::String.compute_size(&self.group_instance_id).map_err(|e| e.with_field("HeartbeatRequest", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("HeartbeatRequest", "group_instance_id", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("HeartbeatRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("HeartbeatRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("HeartbeatRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -130,34 +149,37 @@ impl Encodable for HeartbeatRequest {
 
 impl Decodable for HeartbeatRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("HeartbeatRequest", version));
+        }
+        let start = buf.remaining();
         let group_id = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "group_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "group_id", version, start - buf.remaining()))?
         };
-        let generation_id = types::Int32.decode(buf)?;
+        let generation_id = types::Int32.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "generation_id", version, start - buf.remaining()))?;
         let member_id = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "member_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "member_id", version, start - buf.remaining()))?
         };
         let group_instance_id = if version >= 3 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "group_instance_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "group_instance_id", version, start - buf.remaining()))?
             }
         } else {
             None
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("HeartbeatRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("HeartbeatRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -178,7 +200,7 @@ impl Default for HeartbeatRequest {
             generation_id: 0,
             member_id: Default::default(),
             group_instance_id: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -187,6 +209,86 @@ impl Message for HeartbeatRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl HeartbeatRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.group_instance_id = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.group_instance_id.is_none() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl HeartbeatRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        node.insert("generationId".into(), JsonValue::to_json(&self.generation_id));
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        if version >= 3 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "HeartbeatRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "HeartbeatRequest.groupId")?;
+        } else {
+            return Err(crate::json::missing("HeartbeatRequest", "groupId", version));
+        }
+        if let Some(value) = object.get("generationId") {
+            this.generation_id = JsonValue::from_json(value, "HeartbeatRequest.generationId")?;
+        } else {
+            return Err(crate::json::missing("HeartbeatRequest", "generationId", version));
+        }
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "HeartbeatRequest.memberId")?;
+        } else {
+            return Err(crate::json::missing("HeartbeatRequest", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "HeartbeatRequest.groupInstanceId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("HeartbeatRequest", "groupInstanceId", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for HeartbeatRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
@@ -197,3 +299,16 @@ impl HeaderVersion for HeartbeatRequest {
     }
 }
 
+impl HeartbeatRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::HeartbeatResponse {
+        let mut response = super::HeartbeatResponse::default();
+        response.error_code = error.code();
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct JoinGroupRequestProtocol {
     /// The protocol metadata.
@@ -28,7 +34,7 @@ pub struct JoinGroupRequestProtocol {
     pub metadata: Bytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for JoinGroupRequestProtocol {
@@ -42,49 +48,53 @@ impl Builder for JoinGroupRequestProtocol {
 impl MapEncodable for JoinGroupRequestProtocol {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 6 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("JoinGroupRequestProtocol", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("JoinGroupRequestProtocol", "name", version))?;
         }
         if version >= 6 {
-            types::CompactBytes.encode(buf, &self.metadata)?;
+            types::CompactBytes.encode(buf, &self.metadata).map_err(|e| e.with_field("JoinGroupRequestProtocol", "metadata", version))?;
         } else {
-            types::Bytes.encode(buf, &self.metadata)?;
+            types::Bytes.encode(buf, &self.metadata).map_err(|e| e.with_field("JoinGroupRequestProtocol", "metadata", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 6 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("JoinGroupRequestProtocol", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("JoinGroupRequestProtocol", "name", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactBytes.compute_size(&self.metadata)?;
+            total_size += types::CompactBytes.compute_size(&self.metadata).map_err(|e| e.with_field("JoinGroupRequestProtocol", "metadata", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.metadata)?;
+            total_size += types::Bytes.compute_size(&self.metadata).map_err(|e| e.with_field("JoinGroupRequestProtocol", "metadata", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -93,24 +103,24 @@ impl MapEncodable for JoinGroupRequestProtocol {
 impl MapDecodable for JoinGroupRequestProtocol {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupRequestProtocol", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("JoinGroupRequestProtocol", "name", version, start - buf.remaining()))?
         };
         let metadata = if version >= 6 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("JoinGroupRequestProtocol", "metadata", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("JoinGroupRequestProtocol", "metadata", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("JoinGroupRequestProtocol", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -125,7 +135,7 @@ impl Default for JoinGroupRequestProtocol {
     fn default() -> Self {
         Self {
             metadata: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -134,9 +144,70 @@ impl Message for JoinGroupRequestProtocol {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl JoinGroupRequestProtocol {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl JoinGroupRequestProtocol {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("metadata".into(), JsonValue::to_json(&self.metadata));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "JoinGroupRequestProtocol")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "JoinGroupRequestProtocol.name")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupRequestProtocol", "name", version));
+        }
+        if let Some(value) = object.get("metadata") {
+            this.metadata = JsonValue::from_json(value, "JoinGroupRequestProtocol.metadata")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupRequestProtocol", "metadata", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct JoinGroupRequest {
     /// The group identifier.
@@ -157,30 +228,37 @@ pub struct JoinGroupRequest {
     /// The member id assigned by the group coordinator.
     /// 
     /// Supported API versions: 0-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The unique identifier of the consumer instance provided by end user.
     /// 
     /// Supported API versions: 5-9
+    /// May be `None` in versions: 5-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_instance_id: Option<StrBytes>,
 
     /// The unique name the for class of protocols implemented by the group we want to join.
     /// 
     /// Supported API versions: 0-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_type: StrBytes,
 
     /// The list of protocols that the member supports.
     /// 
     /// Supported API versions: 0-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocols: indexmap::IndexMap<StrBytes, JoinGroupRequestProtocol>,
 
     /// The reason why the member (re-)joins the group.
     /// 
     /// Supported API versions: 8-9
+    /// May be `None` in versions: 8-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub reason: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for JoinGroupRequest {
@@ -193,105 +271,115 @@ impl Builder for JoinGroupRequest {
 
 impl Encodable for JoinGroupRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("JoinGroupRequest", version));
+        }
         if version >= 6 {
-            types::CompactString.encode(buf, &self.group_id)?;
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("JoinGroupRequest", "group_id", version))?;
         } else {
-            types::String.encode(buf, &self.group_id)?;
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("JoinGroupRequest", "group_id", version))?;
         }
-        types::Int32.encode(buf, &self.session_timeout_ms)?;
+        types::Int32.encode(buf, &self.session_timeout_ms).map_err(|e| e.with_field("JoinGroupRequest", "session_timeout_ms", version))?;
         if version >= 1 {
-            types::Int32.encode(buf, &self.rebalance_timeout_ms)?;
+            types::Int32.encode(buf, &self.rebalance_timeout_ms).map_err(|e| e.with_field("JoinGroupRequest", "rebalance_timeout_ms", version))?;
         }
         if version >= 6 {
-            types::CompactString.encode(buf, &self.member_id)?;
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupRequest", "member_id", version))?;
         } else {
-            types::String.encode(buf, &self.member_id)?;
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupRequest", "member_id", version))?;
         }
         if version >= 5 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.group_instance_id)?;
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("JoinGroupRequest", "group_instance_id", version))?;
             } else {
-                types::String.encode(buf, &self.group_instance_id)?;
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("JoinGroupRequest", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("JoinGroupRequest", "group_instance_id", version));
             }
         }
         if version >= 6 {
-            types::CompactString.encode(buf, &self.protocol_type)?;
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("JoinGroupRequest", "protocol_type", version))?;
         } else {
-            types::String.encode(buf, &self.protocol_type)?;
+            types::String.encode(buf, &self.protocol_type).map_err(|e| e.with_field("JoinGroupRequest", "protocol_type", version))?;
         }
         if version >= 6 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.protocols)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.protocols).map_err(|e| e.with_field("JoinGroupRequest", "protocols", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.protocols)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.protocols).map_err(|e| e.with_field("JoinGroupRequest", "protocols", version))?;
         }
         if version >= 8 {
-            types::CompactString.encode(buf, &self.reason)?;
+            types::CompactString.encode(buf, &self.reason).map_err(|e| e.with_field("JoinGroupRequest", "reason", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("JoinGroupRequest", version));
+        }
         let mut total_size = 0;
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.group_id)?;
+            total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("JoinGroupRequest", "group_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.group_id)?;
+            total_size += types::String.compute_size(&self.group_id).map_err(|e| e.with_field("JoinGroupRequest", "group_id", version))?;
         }
-        total_size += types::Int32.compute_size(&self.session_timeout_ms)?;
+        total_size += types::Int32.compute_size(&self.session_timeout_ms).map_err(|e| e.with_field("JoinGroupRequest", "session_timeout_ms", version))?;
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.rebalance_timeout_ms)?;
+            total_size += types::Int32.compute_size(&self.rebalance_timeout_ms).map_err(|e| e.with_field("JoinGroupRequest", "rebalance_timeout_ms", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.member_id)?;
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("JoinGroupRequest", "member_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.member_id)?;
+            total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("JoinGroupRequest", "member_id", version))?;
         }
         if version >= 5 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.group_instance_id)?;
+                total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("JoinGroupRequest", "group_instance_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.group_instance_id)?;
+                total_size += types::String.compute_size(&self.group_instance_id).map_err(|e| e.with_field("JoinGroupRequest", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("JoinGroupRequest", "group_instance_id", version));
             }
         }
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.protocol_type)?;
+            total_size += types::CompactString.compute_size(&self.protocol_type).map_err(|e| e.with_field("JoinGroupRequest", "protocol_type", version))?;
         } else {
-            total_size += types::String.compute_size(&self.protocol_type)?;
+            total_size += types::String.compute_size(&self.protocol_type).map_err(|e| e.with_field("JoinGroupRequest", "protocol_type", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.protocols)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.protocols).map_err(|e| e.with_field("JoinGroupRequest", "protocols", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.protocols)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.protocols).map_err(|e| e.with_field("JoinGroupRequest", "protocols", version))?;
         }
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.reason)?;
+            total_size += types::CompactString.compute_size(&self.reason).map_err(|e| e.with_field("JoinGroupRequest", "reason", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -299,54 +387,57 @@ impl Encodable for JoinGroupRequest {
 
 impl Decodable for JoinGroupRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("JoinGroupRequest", version));
+        }
+        let start = buf.remaining();
         let group_id = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "group_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "group_id", version, start - buf.remaining()))?
         };
-        let session_timeout_ms = types::Int32.decode(buf)?;
+        let session_timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "session_timeout_ms", version, start - buf.remaining()))?;
         let rebalance_timeout_ms = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "rebalance_timeout_ms", version, start - buf.remaining()))?
         } else {
             -1
         };
         let member_id = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "member_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "member_id", version, start - buf.remaining()))?
         };
         let group_instance_id = if version >= 5 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "group_instance_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "group_instance_id", version, start - buf.remaining()))?
             }
         } else {
             None
         };
         let protocol_type = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "protocol_type", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "protocol_type", version, start - buf.remaining()))?
         };
         let protocols = if version >= 6 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "protocols", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "protocols", version, start - buf.remaining()))?
         };
         let reason = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "reason", version, start - buf.remaining()))?
         } else {
             None
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -375,7 +466,7 @@ impl Default for JoinGroupRequest {
             protocol_type: Default::default(),
             protocols: Default::default(),
             reason: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -384,6 +475,205 @@ impl Message for JoinGroupRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl JoinGroupRequest {
+    /// Encode the message with the elements of `protocols` produced by an iterator of known length, in place of
+    /// the `protocols` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_protocols<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, protocols: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (StrBytes, JoinGroupRequestProtocol)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("JoinGroupRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let protocols = protocols.into_iter();
+        if version >= 6 {
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("JoinGroupRequest", "group_id", version))?;
+        } else {
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("JoinGroupRequest", "group_id", version))?;
+        }
+        types::Int32.encode(buf, &self.session_timeout_ms).map_err(|e| e.with_field("JoinGroupRequest", "session_timeout_ms", version))?;
+        if version >= 1 {
+            types::Int32.encode(buf, &self.rebalance_timeout_ms).map_err(|e| e.with_field("JoinGroupRequest", "rebalance_timeout_ms", version))?;
+        }
+        if version >= 6 {
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupRequest", "member_id", version))?;
+        } else {
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupRequest", "member_id", version))?;
+        }
+        if version >= 5 {
+            if version >= 6 {
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("JoinGroupRequest", "group_instance_id", version))?;
+            } else {
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("JoinGroupRequest", "group_instance_id", version))?;
+            }
+        } else {
+            if !self.group_instance_id.is_none() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("JoinGroupRequest", "group_instance_id", version));
+            }
+        }
+        if version >= 6 {
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("JoinGroupRequest", "protocol_type", version))?;
+        } else {
+            types::String.encode(buf, &self.protocol_type).map_err(|e| e.with_field("JoinGroupRequest", "protocol_type", version))?;
+        }
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(protocols)).map_err(|e| e.with_field("JoinGroupRequest", "protocols", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(protocols)).map_err(|e| e.with_field("JoinGroupRequest", "protocols", version))?;
+        }
+        if version >= 8 {
+            types::CompactString.encode(buf, &self.reason).map_err(|e| e.with_field("JoinGroupRequest", "reason", version))?;
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl JoinGroupRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.rebalance_timeout_ms = -1;
+        }
+        if version < 5 {
+            self.group_instance_id = None;
+        }
+        for item in self.protocols.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 8 {
+            self.reason = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.rebalance_timeout_ms != -1 {
+                invalid.push(format!("{}rebalance_timeout_ms", path));
+            }
+        }
+        if version < 5 {
+            if !self.group_instance_id.is_none() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+        for (i, item) in self.protocols.values().enumerate() {
+            item.validate_into(version, &format!("{}protocols[{}].", path, i), invalid);
+        }
+        if version < 8 {
+            if !self.reason.is_none() {
+                invalid.push(format!("{}reason", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl JoinGroupRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        node.insert("sessionTimeoutMs".into(), JsonValue::to_json(&self.session_timeout_ms));
+        if version >= 1 {
+            node.insert("rebalanceTimeoutMs".into(), JsonValue::to_json(&self.rebalance_timeout_ms));
+        }
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        if version >= 5 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        node.insert("protocolType".into(), JsonValue::to_json(&self.protocol_type));
+        node.insert("protocols".into(), serde_json::Value::Array(self.protocols.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        if version >= 8 {
+            node.insert("reason".into(), JsonValue::to_json(&self.reason));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "JoinGroupRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "JoinGroupRequest.groupId")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupRequest", "groupId", version));
+        }
+        if let Some(value) = object.get("sessionTimeoutMs") {
+            this.session_timeout_ms = JsonValue::from_json(value, "JoinGroupRequest.sessionTimeoutMs")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupRequest", "sessionTimeoutMs", version));
+        }
+        if let Some(value) = object.get("rebalanceTimeoutMs") {
+            this.rebalance_timeout_ms = JsonValue::from_json(value, "JoinGroupRequest.rebalanceTimeoutMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("JoinGroupRequest", "rebalanceTimeoutMs", version));
+        }
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "JoinGroupRequest.memberId")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupRequest", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "JoinGroupRequest.groupInstanceId")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("JoinGroupRequest", "groupInstanceId", version));
+        }
+        if let Some(value) = object.get("protocolType") {
+            this.protocol_type = JsonValue::from_json(value, "JoinGroupRequest.protocolType")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupRequest", "protocolType", version));
+        }
+        if let Some(value) = object.get("protocols") {
+            this.protocols = crate::json::array(value, "JoinGroupRequest.protocols")?.iter().map(|item| JoinGroupRequestProtocol::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("JoinGroupRequest", "protocols", version));
+        }
+        if let Some(value) = object.get("reason") {
+            this.reason = JsonValue::from_json(value, "JoinGroupRequest.reason")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("JoinGroupRequest", "reason", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for JoinGroupRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 6 {
@@ -394,3 +684,17 @@ impl HeaderVersion for JoinGroupRequest {
     }
 }
 
+impl JoinGroupRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::JoinGroupResponse {
+        let mut response = super::JoinGroupResponse::default();
+        response.error_code = error.code();
+        response.member_id = self.member_id.clone();
+        response
+    }
+}
+
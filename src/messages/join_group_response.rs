@@ -5,31 +5,40 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct JoinGroupResponseMember {
     /// The group member ID.
     /// 
     /// Supported API versions: 0-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The unique identifier of the consumer instance provided by end user.
     /// 
     /// Supported API versions: 5-9
+    /// May be `None` in versions: 5-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_instance_id: Option<StrBytes>,
 
     /// The group member metadata.
@@ -38,7 +47,7 @@ pub struct JoinGroupResponseMember {
     pub metadata: Bytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for JoinGroupResponseMember {
@@ -51,63 +60,67 @@ impl Builder for JoinGroupResponseMember {
 
 impl Encodable for JoinGroupResponseMember {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 6 {
-            types::CompactString.encode(buf, &self.member_id)?;
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupResponseMember", "member_id", version))?;
         } else {
-            types::String.encode(buf, &self.member_id)?;
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupResponseMember", "member_id", version))?;
         }
         if version >= 5 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.group_instance_id)?;
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("JoinGroupResponseMember", "group_instance_id", version))?;
             } else {
-                types::String.encode(buf, &self.group_instance_id)?;
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("JoinGroupResponseMember", "group_instance_id", version))?;
             }
         }
         if version >= 6 {
-            types::CompactBytes.encode(buf, &self.metadata)?;
+            types::CompactBytes.encode(buf, &self.metadata).map_err(|e| e.with_field("JoinGroupResponseMember", "metadata", version))?;
         } else {
-            types::Bytes.encode(buf, &self.metadata)?;
+            types::Bytes.encode(buf, &self.metadata).map_err(|e| e.with_field("JoinGroupResponseMember", "metadata", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupResponseMember", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupResponseMember", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupResponseMember", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.member_id)?;
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("JoinGroupResponseMember", "member_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.member_id)?;
+            total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("JoinGroupResponseMember", "member_id", version))?;
         }
         if version >= 5 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.group_instance_id)?;
+                total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("JoinGroupResponseMember", "group_instance_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.group_instance_id)?;
+                total_size += types::String.compute_size(&self.group_instance_id).map_err(|e| e.with_field("JoinGroupResponseMember", "group_instance_id", version))?;
             }
         }
         if version >= 6 {
-            total_size += types::CompactBytes.compute_size(&self.metadata)?;
+            total_size += types::CompactBytes.compute_size(&self.metadata).map_err(|e| e.with_field("JoinGroupResponseMember", "metadata", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.metadata)?;
+            total_size += types::Bytes.compute_size(&self.metadata).map_err(|e| e.with_field("JoinGroupResponseMember", "metadata", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupResponseMember", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupResponseMember", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupResponseMember", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -115,33 +128,33 @@ impl Encodable for JoinGroupResponseMember {
 
 impl Decodable for JoinGroupResponseMember {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let member_id = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "member_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "member_id", version, start - buf.remaining()))?
         };
         let group_instance_id = if version >= 5 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "group_instance_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "group_instance_id", version, start - buf.remaining()))?
             }
         } else {
             None
         };
         let metadata = if version >= 6 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "metadata", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "metadata", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupResponseMember", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("JoinGroupResponseMember", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -160,7 +173,7 @@ impl Default for JoinGroupResponseMember {
             member_id: Default::default(),
             group_instance_id: None,
             metadata: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -169,9 +182,85 @@ impl Message for JoinGroupResponseMember {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl JoinGroupResponseMember {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 5 {
+            self.group_instance_id = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 5 {
+            if !self.group_instance_id.is_none() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl JoinGroupResponseMember {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        if version >= 5 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        node.insert("metadata".into(), JsonValue::to_json(&self.metadata));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "JoinGroupResponseMember")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "JoinGroupResponseMember.memberId")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupResponseMember", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "JoinGroupResponseMember.groupInstanceId")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("JoinGroupResponseMember", "groupInstanceId", version));
+        }
+        if let Some(value) = object.get("metadata") {
+            this.metadata = JsonValue::from_json(value, "JoinGroupResponseMember.metadata")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupResponseMember", "metadata", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct JoinGroupResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -192,16 +281,21 @@ pub struct JoinGroupResponse {
     /// The group protocol name.
     /// 
     /// Supported API versions: 7-9
+    /// May be `None` in versions: 7-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_type: Option<StrBytes>,
 
     /// The group protocol selected by the coordinator.
     /// 
     /// Supported API versions: 0-9
+    /// May be `None` in versions: 0-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_name: Option<StrBytes>,
 
     /// The leader of the group.
     /// 
     /// Supported API versions: 0-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub leader: StrBytes,
 
     /// True if the leader must skip running the assignment.
@@ -212,6 +306,7 @@ pub struct JoinGroupResponse {
     /// The member ID assigned by the group coordinator.
     /// 
     /// Supported API versions: 0-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// 
@@ -220,7 +315,7 @@ pub struct JoinGroupResponse {
     pub members: Vec<JoinGroupResponseMember>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for JoinGroupResponse {
@@ -233,99 +328,109 @@ impl Builder for JoinGroupResponse {
 
 impl Encodable for JoinGroupResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("JoinGroupResponse", version));
+        }
         if version >= 2 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("JoinGroupResponse", "throttle_time_ms", version))?;
         }
-        types::Int16.encode(buf, &self.error_code)?;
-        types::Int32.encode(buf, &self.generation_id)?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("JoinGroupResponse", "error_code", version))?;
+        types::Int32.encode(buf, &self.generation_id).map_err(|e| e.with_field("JoinGroupResponse", "generation_id", version))?;
         if version >= 7 {
-            types::CompactString.encode(buf, &self.protocol_type)?;
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("JoinGroupResponse", "protocol_type", version))?;
         }
         if version >= 6 {
-            types::CompactString.encode(buf, &self.protocol_name)?;
+            types::CompactString.encode(buf, &self.protocol_name).map_err(|e| e.with_field("JoinGroupResponse", "protocol_name", version))?;
         } else {
-            types::String.encode(buf, &self.protocol_name)?;
+            types::String.encode(buf, &self.protocol_name).map_err(|e| e.with_field("JoinGroupResponse", "protocol_name", version))?;
         }
         if version >= 6 {
-            types::CompactString.encode(buf, &self.leader)?;
+            types::CompactString.encode(buf, &self.leader).map_err(|e| e.with_field("JoinGroupResponse", "leader", version))?;
         } else {
-            types::String.encode(buf, &self.leader)?;
+            types::String.encode(buf, &self.leader).map_err(|e| e.with_field("JoinGroupResponse", "leader", version))?;
         }
         if version >= 9 {
-            types::Boolean.encode(buf, &self.skip_assignment)?;
+            types::Boolean.encode(buf, &self.skip_assignment).map_err(|e| e.with_field("JoinGroupResponse", "skip_assignment", version))?;
         } else {
             if self.skip_assignment {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("JoinGroupResponse", "skip_assignment", version));
             }
         }
         if version >= 6 {
-            types::CompactString.encode(buf, &self.member_id)?;
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupResponse", "member_id", version))?;
         } else {
-            types::String.encode(buf, &self.member_id)?;
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupResponse", "member_id", version))?;
         }
         if version >= 6 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.members)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.members).map_err(|e| e.with_field("JoinGroupResponse", "members", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.members)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.members).map_err(|e| e.with_field("JoinGroupResponse", "members", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("JoinGroupResponse", version));
+        }
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("JoinGroupResponse", "throttle_time_ms", version))?;
         }
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::Int32.compute_size(&self.generation_id)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("JoinGroupResponse", "error_code", version))?;
+        total_size += types::Int32.compute_size(&self.generation_id).map_err(|e| e.with_field("JoinGroupResponse", "generation_id", version))?;
         if version >= 7 {
-            total_size += types::CompactString.compute_size(&self.protocol_type)?;
+            total_size += types::CompactString.compute_size(&self.protocol_type).map_err(|e| e.with_field("JoinGroupResponse", "protocol_type", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.protocol_name)?;
+            total_size += types::CompactString.compute_size(&self.protocol_name).map_err(|e| e.with_field("JoinGroupResponse", "protocol_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.protocol_name)?;
+            total_size += types::String.compute_size(&self.protocol_name).map_err(|e| e.with_field("JoinGroupResponse", "protocol_name", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.leader)?;
+            total_size += types::CompactString.compute_size(&self.leader).map_err(|e| e.with_field("JoinGroupResponse", "leader", version))?;
         } else {
-            total_size += types::String.compute_size(&self.leader)?;
+            total_size += types::String.compute_size(&self.leader).map_err(|e| e.with_field("JoinGroupResponse", "leader", version))?;
         }
         if version >= 9 {
-            total_size += types::Boolean.compute_size(&self.skip_assignment)?;
+            total_size += types::Boolean.compute_size(&self.skip_assignment).map_err(|e| e.with_field("JoinGroupResponse", "skip_assignment", version))?;
         } else {
             if self.skip_assignment {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("JoinGroupResponse", "skip_assignment", version));
             }
         }
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.member_id)?;
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("JoinGroupResponse", "member_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.member_id)?;
+            total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("JoinGroupResponse", "member_id", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.members)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.members).map_err(|e| e.with_field("JoinGroupResponse", "members", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.members)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.members).map_err(|e| e.with_field("JoinGroupResponse", "members", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -333,51 +438,54 @@ impl Encodable for JoinGroupResponse {
 
 impl Decodable for JoinGroupResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("JoinGroupResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 2 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
-        let error_code = types::Int16.decode(buf)?;
-        let generation_id = types::Int32.decode(buf)?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "error_code", version, start - buf.remaining()))?;
+        let generation_id = types::Int32.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "generation_id", version, start - buf.remaining()))?;
         let protocol_type = if version >= 7 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "protocol_type", version, start - buf.remaining()))?
         } else {
             None
         };
         let protocol_name = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "protocol_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "protocol_name", version, start - buf.remaining()))?
         };
         let leader = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "leader", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "leader", version, start - buf.remaining()))?
         };
         let skip_assignment = if version >= 9 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "skip_assignment", version, start - buf.remaining()))?
         } else {
             false
         };
         let member_id = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "member_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "member_id", version, start - buf.remaining()))?
         };
         let members = if version >= 6 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "members", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "members", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -408,7 +516,7 @@ impl Default for JoinGroupResponse {
             skip_assignment: false,
             member_id: Default::default(),
             members: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -417,6 +525,208 @@ impl Message for JoinGroupResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl JoinGroupResponse {
+    /// Encode the message with the elements of `members` produced by an iterator of known length, in place of
+    /// the `members` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_members<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, members: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = JoinGroupResponseMember>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("JoinGroupResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let members = members.into_iter();
+        if version >= 2 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("JoinGroupResponse", "throttle_time_ms", version))?;
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("JoinGroupResponse", "error_code", version))?;
+        types::Int32.encode(buf, &self.generation_id).map_err(|e| e.with_field("JoinGroupResponse", "generation_id", version))?;
+        if version >= 7 {
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("JoinGroupResponse", "protocol_type", version))?;
+        }
+        if version >= 6 {
+            types::CompactString.encode(buf, &self.protocol_name).map_err(|e| e.with_field("JoinGroupResponse", "protocol_name", version))?;
+        } else {
+            types::String.encode(buf, &self.protocol_name).map_err(|e| e.with_field("JoinGroupResponse", "protocol_name", version))?;
+        }
+        if version >= 6 {
+            types::CompactString.encode(buf, &self.leader).map_err(|e| e.with_field("JoinGroupResponse", "leader", version))?;
+        } else {
+            types::String.encode(buf, &self.leader).map_err(|e| e.with_field("JoinGroupResponse", "leader", version))?;
+        }
+        if version >= 9 {
+            types::Boolean.encode(buf, &self.skip_assignment).map_err(|e| e.with_field("JoinGroupResponse", "skip_assignment", version))?;
+        } else {
+            if self.skip_assignment {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("JoinGroupResponse", "skip_assignment", version));
+            }
+        }
+        if version >= 6 {
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupResponse", "member_id", version))?;
+        } else {
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("JoinGroupResponse", "member_id", version))?;
+        }
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(members)).map_err(|e| e.with_field("JoinGroupResponse", "members", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(members)).map_err(|e| e.with_field("JoinGroupResponse", "members", version))?;
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("JoinGroupResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("JoinGroupResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl JoinGroupResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.throttle_time_ms = 0;
+        }
+        if version < 7 {
+            self.protocol_type = None;
+        }
+        if version < 9 {
+            self.skip_assignment = false;
+        }
+        for item in self.members.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        if version < 7 {
+            if !self.protocol_type.is_none() {
+                invalid.push(format!("{}protocol_type", path));
+            }
+        }
+        if version < 9 {
+            if self.skip_assignment {
+                invalid.push(format!("{}skip_assignment", path));
+            }
+        }
+        for (i, item) in self.members.iter().enumerate() {
+            item.validate_into(version, &format!("{}members[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl JoinGroupResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 2 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("generationId".into(), JsonValue::to_json(&self.generation_id));
+        if version >= 7 {
+            node.insert("protocolType".into(), JsonValue::to_json(&self.protocol_type));
+        }
+        node.insert("protocolName".into(), JsonValue::to_json(&self.protocol_name));
+        node.insert("leader".into(), JsonValue::to_json(&self.leader));
+        if version >= 9 {
+            node.insert("skipAssignment".into(), JsonValue::to_json(&self.skip_assignment));
+        }
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        node.insert("members".into(), serde_json::Value::Array(self.members.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "JoinGroupResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "JoinGroupResponse.throttleTimeMs")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("JoinGroupResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "JoinGroupResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("generationId") {
+            this.generation_id = JsonValue::from_json(value, "JoinGroupResponse.generationId")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupResponse", "generationId", version));
+        }
+        if let Some(value) = object.get("protocolType") {
+            this.protocol_type = JsonValue::from_json(value, "JoinGroupResponse.protocolType")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("JoinGroupResponse", "protocolType", version));
+        }
+        if let Some(value) = object.get("protocolName") {
+            this.protocol_name = JsonValue::from_json(value, "JoinGroupResponse.protocolName")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupResponse", "protocolName", version));
+        }
+        if let Some(value) = object.get("leader") {
+            this.leader = JsonValue::from_json(value, "JoinGroupResponse.leader")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupResponse", "leader", version));
+        }
+        if let Some(value) = object.get("skipAssignment") {
+            this.skip_assignment = JsonValue::from_json(value, "JoinGroupResponse.skipAssignment")?;
+        } else if version >= 9 {
+            return Err(crate::json::missing("JoinGroupResponse", "skipAssignment", version));
+        }
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "JoinGroupResponse.memberId")?;
+        } else {
+            return Err(crate::json::missing("JoinGroupResponse", "memberId", version));
+        }
+        if let Some(value) = object.get("members") {
+            this.members = crate::json::array(value, "JoinGroupResponse.members")?.iter().map(|item| JoinGroupResponseMember::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("JoinGroupResponse", "members", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for JoinGroupResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 6 {
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaderAndIsrPartitionState {
     /// The topic name.  This is only present in v0 or v1.
@@ -83,7 +89,7 @@ pub struct LeaderAndIsrPartitionState {
     pub leader_recovery_state: i8,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaderAndIsrPartitionState {
@@ -96,113 +102,117 @@ impl Builder for LeaderAndIsrPartitionState {
 
 impl Encodable for LeaderAndIsrPartitionState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 1 {
-            types::String.encode(buf, &self.topic_name)?;
+            types::String.encode(buf, &self.topic_name).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "topic_name", version))?;
         }
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int32.encode(buf, &self.controller_epoch)?;
-        types::Int32.encode(buf, &self.leader)?;
-        types::Int32.encode(buf, &self.leader_epoch)?;
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "partition_index", version))?;
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "controller_epoch", version))?;
+        types::Int32.encode(buf, &self.leader).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader_epoch", version))?;
         if version >= 4 {
-            types::CompactArray(types::Int32).encode(buf, &self.isr)?;
+            types::CompactArray(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "isr", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.isr)?;
+            types::Array(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "isr", version))?;
         }
-        types::Int32.encode(buf, &self.partition_epoch)?;
+        types::Int32.encode(buf, &self.partition_epoch).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "partition_epoch", version))?;
         if version >= 4 {
-            types::CompactArray(types::Int32).encode(buf, &self.replicas)?;
+            types::CompactArray(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "replicas", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.replicas)?;
+            types::Array(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "replicas", version))?;
         }
         if version >= 3 {
             if version >= 4 {
-                types::CompactArray(types::Int32).encode(buf, &self.adding_replicas)?;
+                types::CompactArray(types::Int32).encode(buf, &self.adding_replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "adding_replicas", version))?;
             } else {
-                types::Array(types::Int32).encode(buf, &self.adding_replicas)?;
+                types::Array(types::Int32).encode(buf, &self.adding_replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "adding_replicas", version))?;
             }
         }
         if version >= 3 {
             if version >= 4 {
-                types::CompactArray(types::Int32).encode(buf, &self.removing_replicas)?;
+                types::CompactArray(types::Int32).encode(buf, &self.removing_replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "removing_replicas", version))?;
             } else {
-                types::Array(types::Int32).encode(buf, &self.removing_replicas)?;
+                types::Array(types::Int32).encode(buf, &self.removing_replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "removing_replicas", version))?;
             }
         }
         if version >= 1 {
-            types::Boolean.encode(buf, &self.is_new)?;
+            types::Boolean.encode(buf, &self.is_new).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "is_new", version))?;
         }
         if version >= 6 {
-            types::Int8.encode(buf, &self.leader_recovery_state)?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader_recovery_state", version))?;
         } else {
             if self.leader_recovery_state != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrPartitionState", "leader_recovery_state", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 1 {
-            total_size += types::String.compute_size(&self.topic_name)?;
+            total_size += types::String.compute_size(&self.topic_name).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "topic_name", version))?;
         }
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int32.compute_size(&self.controller_epoch)?;
-        total_size += types::Int32.compute_size(&self.leader)?;
-        total_size += types::Int32.compute_size(&self.leader_epoch)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "partition_index", version))?;
+        total_size += types::Int32.compute_size(&self.controller_epoch).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "controller_epoch", version))?;
+        total_size += types::Int32.compute_size(&self.leader).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader", version))?;
+        total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader_epoch", version))?;
         if version >= 4 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.isr)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.isr).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "isr", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.isr)?;
+            total_size += types::Array(types::Int32).compute_size(&self.isr).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "isr", version))?;
         }
-        total_size += types::Int32.compute_size(&self.partition_epoch)?;
+        total_size += types::Int32.compute_size(&self.partition_epoch).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "partition_epoch", version))?;
         if version >= 4 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.replicas)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "replicas", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.replicas)?;
+            total_size += types::Array(types::Int32).compute_size(&self.replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "replicas", version))?;
         }
         if version >= 3 {
             if version >= 4 {
-                total_size += types::CompactArray(types::Int32).compute_size(&self.adding_replicas)?;
+                total_size += types::CompactArray(types::Int32).compute_size(&self.adding_replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "adding_replicas", version))?;
             } else {
-                total_size += types::Array(types::Int32).compute_size(&self.adding_replicas)?;
+                total_size += types::Array(types::Int32).compute_size(&self.adding_replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "adding_replicas", version))?;
             }
         }
         if version >= 3 {
             if version >= 4 {
-                total_size += types::CompactArray(types::Int32).compute_size(&self.removing_replicas)?;
+                total_size += types::CompactArray(types::Int32).compute_size(&self.removing_replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "removing_replicas", version))?;
             } else {
-                total_size += types::Array(types::Int32).compute_size(&self.removing_replicas)?;
+                total_size += types::Array(types::Int32).compute_size(&self.removing_replicas).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "removing_replicas", version))?;
             }
         }
         if version >= 1 {
-            total_size += types::Boolean.compute_size(&self.is_new)?;
+            total_size += types::Boolean.compute_size(&self.is_new).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "is_new", version))?;
         }
         if version >= 6 {
-            total_size += types::Int8.compute_size(&self.leader_recovery_state)?;
+            total_size += types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader_recovery_state", version))?;
         } else {
             if self.leader_recovery_state != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrPartitionState", "leader_recovery_state", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -210,62 +220,62 @@ impl Encodable for LeaderAndIsrPartitionState {
 
 impl Decodable for LeaderAndIsrPartitionState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version <= 1 {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "topic_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let partition_index = types::Int32.decode(buf)?;
-        let controller_epoch = types::Int32.decode(buf)?;
-        let leader = types::Int32.decode(buf)?;
-        let leader_epoch = types::Int32.decode(buf)?;
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "partition_index", version, start - buf.remaining()))?;
+        let controller_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "controller_epoch", version, start - buf.remaining()))?;
+        let leader = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader", version, start - buf.remaining()))?;
+        let leader_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader_epoch", version, start - buf.remaining()))?;
         let isr = if version >= 4 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "isr", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "isr", version, start - buf.remaining()))?
         };
-        let partition_epoch = types::Int32.decode(buf)?;
+        let partition_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "partition_epoch", version, start - buf.remaining()))?;
         let replicas = if version >= 4 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "replicas", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "replicas", version, start - buf.remaining()))?
         };
         let adding_replicas = if version >= 3 {
             if version >= 4 {
-                types::CompactArray(types::Int32).decode(buf)?
+                types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "adding_replicas", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Int32).decode(buf)?
+                types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "adding_replicas", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let removing_replicas = if version >= 3 {
             if version >= 4 {
-                types::CompactArray(types::Int32).decode(buf)?
+                types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "removing_replicas", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Int32).decode(buf)?
+                types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "removing_replicas", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let is_new = if version >= 1 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "is_new", version, start - buf.remaining()))?
         } else {
             false
         };
         let leader_recovery_state = if version >= 6 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "leader_recovery_state", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaderAndIsrPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -302,7 +312,7 @@ impl Default for LeaderAndIsrPartitionState {
             removing_replicas: Default::default(),
             is_new: false,
             leader_recovery_state: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -311,9 +321,179 @@ impl Message for LeaderAndIsrPartitionState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl LeaderAndIsrPartitionState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 1 {
+            self.topic_name = Default::default();
+        }
+        if version < 3 {
+            self.adding_replicas = Default::default();
+        }
+        if version < 3 {
+            self.removing_replicas = Default::default();
+        }
+        if version < 1 {
+            self.is_new = false;
+        }
+        if version < 6 {
+            self.leader_recovery_state = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 1 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+        if version < 3 {
+            if !self.adding_replicas.is_empty() {
+                invalid.push(format!("{}adding_replicas", path));
+            }
+        }
+        if version < 3 {
+            if !self.removing_replicas.is_empty() {
+                invalid.push(format!("{}removing_replicas", path));
+            }
+        }
+        if version < 1 {
+            if self.is_new {
+                invalid.push(format!("{}is_new", path));
+            }
+        }
+        if version < 6 {
+            if self.leader_recovery_state != 0 {
+                invalid.push(format!("{}leader_recovery_state", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaderAndIsrPartitionState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 1 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("controllerEpoch".into(), JsonValue::to_json(&self.controller_epoch));
+        node.insert("leader".into(), JsonValue::to_json(&self.leader));
+        node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        node.insert("isr".into(), JsonValue::to_json(&self.isr));
+        node.insert("partitionEpoch".into(), JsonValue::to_json(&self.partition_epoch));
+        node.insert("replicas".into(), JsonValue::to_json(&self.replicas));
+        if version >= 3 {
+            node.insert("addingReplicas".into(), JsonValue::to_json(&self.adding_replicas));
+        }
+        if version >= 3 {
+            node.insert("removingReplicas".into(), JsonValue::to_json(&self.removing_replicas));
+        }
+        if version >= 1 {
+            node.insert("isNew".into(), JsonValue::to_json(&self.is_new));
+        }
+        if version >= 6 {
+            node.insert("leaderRecoveryState".into(), JsonValue::to_json(&self.leader_recovery_state));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "LeaderAndIsrPartitionState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "LeaderAndIsrPartitionState.topicName")?;
+        } else if version <= 1 {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "topicName", version));
+        }
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "LeaderAndIsrPartitionState.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("controllerEpoch") {
+            this.controller_epoch = JsonValue::from_json(value, "LeaderAndIsrPartitionState.controllerEpoch")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "controllerEpoch", version));
+        }
+        if let Some(value) = object.get("leader") {
+            this.leader = JsonValue::from_json(value, "LeaderAndIsrPartitionState.leader")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "leader", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "LeaderAndIsrPartitionState.leaderEpoch")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "leaderEpoch", version));
+        }
+        if let Some(value) = object.get("isr") {
+            this.isr = JsonValue::from_json(value, "LeaderAndIsrPartitionState.isr")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "isr", version));
+        }
+        if let Some(value) = object.get("partitionEpoch") {
+            this.partition_epoch = JsonValue::from_json(value, "LeaderAndIsrPartitionState.partitionEpoch")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "partitionEpoch", version));
+        }
+        if let Some(value) = object.get("replicas") {
+            this.replicas = JsonValue::from_json(value, "LeaderAndIsrPartitionState.replicas")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "replicas", version));
+        }
+        if let Some(value) = object.get("addingReplicas") {
+            this.adding_replicas = JsonValue::from_json(value, "LeaderAndIsrPartitionState.addingReplicas")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "addingReplicas", version));
+        }
+        if let Some(value) = object.get("removingReplicas") {
+            this.removing_replicas = JsonValue::from_json(value, "LeaderAndIsrPartitionState.removingReplicas")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "removingReplicas", version));
+        }
+        if let Some(value) = object.get("isNew") {
+            this.is_new = JsonValue::from_json(value, "LeaderAndIsrPartitionState.isNew")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "isNew", version));
+        }
+        if let Some(value) = object.get("leaderRecoveryState") {
+            this.leader_recovery_state = JsonValue::from_json(value, "LeaderAndIsrPartitionState.leaderRecoveryState")?;
+        } else if version >= 6 {
+            return Err(crate::json::missing("LeaderAndIsrPartitionState", "leaderRecoveryState", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaderAndIsrTopicState {
     /// The topic name.
@@ -332,7 +512,7 @@ pub struct LeaderAndIsrTopicState {
     pub partition_states: Vec<LeaderAndIsrPartitionState>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaderAndIsrTopicState {
@@ -345,79 +525,83 @@ impl Builder for LeaderAndIsrTopicState {
 
 impl Encodable for LeaderAndIsrTopicState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 2 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.topic_name)?;
+                types::CompactString.encode(buf, &self.topic_name).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_name", version))?;
             } else {
-                types::String.encode(buf, &self.topic_name)?;
+                types::String.encode(buf, &self.topic_name).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_name", version))?;
             }
         } else {
             if !self.topic_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrTopicState", "topic_name", version));
             }
         }
         if version >= 5 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_id", version))?;
         }
         if version >= 2 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.partition_states)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partition_states).map_err(|e| e.with_field("LeaderAndIsrTopicState", "partition_states", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.partition_states)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.partition_states).map_err(|e| e.with_field("LeaderAndIsrTopicState", "partition_states", version))?;
             }
         } else {
             if !self.partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrTopicState", "partition_states", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 2 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.topic_name)?;
+                total_size += types::CompactString.compute_size(&self.topic_name).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_name", version))?;
             } else {
-                total_size += types::String.compute_size(&self.topic_name)?;
+                total_size += types::String.compute_size(&self.topic_name).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_name", version))?;
             }
         } else {
             if !self.topic_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrTopicState", "topic_name", version));
             }
         }
         if version >= 5 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_id", version))?;
         }
         if version >= 2 {
             if version >= 4 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partition_states)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partition_states).map_err(|e| e.with_field("LeaderAndIsrTopicState", "partition_states", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.partition_states)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partition_states).map_err(|e| e.with_field("LeaderAndIsrTopicState", "partition_states", version))?;
             }
         } else {
             if !self.partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrTopicState", "partition_states", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -425,37 +609,37 @@ impl Encodable for LeaderAndIsrTopicState {
 
 impl Decodable for LeaderAndIsrTopicState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version >= 2 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_name", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_name", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let topic_id = if version >= 5 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicState", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
         let partition_states = if version >= 2 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicState", "partition_states", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicState", "partition_states", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaderAndIsrTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -474,7 +658,7 @@ impl Default for LeaderAndIsrTopicState {
             topic_name: Default::default(),
             topic_id: Uuid::nil(),
             partition_states: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -483,9 +667,113 @@ impl Message for LeaderAndIsrTopicState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl LeaderAndIsrTopicState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.topic_name = Default::default();
+        }
+        if version < 5 {
+            self.topic_id = Uuid::nil();
+        }
+        if version >= 2 {
+            for item in self.partition_states.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.partition_states = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+        if version < 5 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        if version >= 2 {
+            for (i, item) in self.partition_states.iter().enumerate() {
+                item.validate_into(version, &format!("{}partition_states[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.partition_states.is_empty() {
+                invalid.push(format!("{}partition_states", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaderAndIsrTopicState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 2 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        if version >= 5 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        if version >= 2 {
+            node.insert("partitionStates".into(), serde_json::Value::Array(self.partition_states.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "LeaderAndIsrTopicState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "LeaderAndIsrTopicState.topicName")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("LeaderAndIsrTopicState", "topicName", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "LeaderAndIsrTopicState.topicId")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("LeaderAndIsrTopicState", "topicId", version));
+        }
+        if let Some(value) = object.get("partitionStates") {
+            this.partition_states = crate::json::array(value, "LeaderAndIsrTopicState.partitionStates")?.iter().map(|item| LeaderAndIsrPartitionState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("LeaderAndIsrTopicState", "partitionStates", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaderAndIsrLiveLeader {
     /// The leader's broker ID.
@@ -496,6 +784,7 @@ pub struct LeaderAndIsrLiveLeader {
     /// The leader's hostname.
     /// 
     /// Supported API versions: 0-7
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host_name: StrBytes,
 
     /// The leader's port.
@@ -504,7 +793,7 @@ pub struct LeaderAndIsrLiveLeader {
     pub port: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaderAndIsrLiveLeader {
@@ -517,43 +806,47 @@ impl Builder for LeaderAndIsrLiveLeader {
 
 impl Encodable for LeaderAndIsrLiveLeader {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.broker_id)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "broker_id", version))?;
         if version >= 4 {
-            types::CompactString.encode(buf, &self.host_name)?;
+            types::CompactString.encode(buf, &self.host_name).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "host_name", version))?;
         } else {
-            types::String.encode(buf, &self.host_name)?;
+            types::String.encode(buf, &self.host_name).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "host_name", version))?;
         }
-        types::Int32.encode(buf, &self.port)?;
+        types::Int32.encode(buf, &self.port).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "port", version))?;
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.broker_id)?;
+        total_size += types::Int32.compute_size(&self.broker_id).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "broker_id", version))?;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.host_name)?;
+            total_size += types::CompactString.compute_size(&self.host_name).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "host_name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.host_name)?;
+            total_size += types::String.compute_size(&self.host_name).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "host_name", version))?;
         }
-        total_size += types::Int32.compute_size(&self.port)?;
+        total_size += types::Int32.compute_size(&self.port).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "port", version))?;
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -561,21 +854,21 @@ impl Encodable for LeaderAndIsrLiveLeader {
 
 impl Decodable for LeaderAndIsrLiveLeader {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let broker_id = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let broker_id = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "broker_id", version, start - buf.remaining()))?;
         let host_name = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "host_name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "host_name", version, start - buf.remaining()))?
         };
-        let port = types::Int32.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let port = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "port", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaderAndIsrLiveLeader", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -594,7 +887,7 @@ impl Default for LeaderAndIsrLiveLeader {
             broker_id: (0).into(),
             host_name: Default::default(),
             port: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -603,9 +896,75 @@ impl Message for LeaderAndIsrLiveLeader {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl LeaderAndIsrLiveLeader {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaderAndIsrLiveLeader {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("brokerId".into(), JsonValue::to_json(&self.broker_id));
+        node.insert("hostName".into(), JsonValue::to_json(&self.host_name));
+        node.insert("port".into(), JsonValue::to_json(&self.port));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "LeaderAndIsrLiveLeader")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("brokerId") {
+            this.broker_id = JsonValue::from_json(value, "LeaderAndIsrLiveLeader.brokerId")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrLiveLeader", "brokerId", version));
+        }
+        if let Some(value) = object.get("hostName") {
+            this.host_name = JsonValue::from_json(value, "LeaderAndIsrLiveLeader.hostName")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrLiveLeader", "hostName", version));
+        }
+        if let Some(value) = object.get("port") {
+            this.port = JsonValue::from_json(value, "LeaderAndIsrLiveLeader.port")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrLiveLeader", "port", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaderAndIsrRequest {
     /// The current controller ID.
@@ -649,7 +1008,7 @@ pub struct LeaderAndIsrRequest {
     pub live_leaders: Vec<LeaderAndIsrLiveLeader>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaderAndIsrRequest {
@@ -662,113 +1021,123 @@ impl Builder for LeaderAndIsrRequest {
 
 impl Encodable for LeaderAndIsrRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.controller_id)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrRequest", version));
+        }
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_id", version))?;
         if version >= 7 {
-            types::Boolean.encode(buf, &self.is_k_raft_controller)?;
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("LeaderAndIsrRequest", "is_k_raft_controller", version))?;
         } else {
             if self.is_k_raft_controller {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "is_k_raft_controller", version));
             }
         }
-        types::Int32.encode(buf, &self.controller_epoch)?;
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_epoch", version))?;
         if version >= 2 {
-            types::Int64.encode(buf, &self.broker_epoch)?;
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "broker_epoch", version))?;
         }
         if version >= 5 {
-            types::Int8.encode(buf, &self._type)?;
+            types::Int8.encode(buf, &self._type).map_err(|e| e.with_field("LeaderAndIsrRequest", "_type", version))?;
         } else {
             if self._type != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "_type", version));
             }
         }
         if version <= 1 {
-            types::Array(types::Struct { version }).encode(buf, &self.ungrouped_partition_states)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partition_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version))?;
         } else {
             if !self.ungrouped_partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version));
             }
         }
         if version >= 2 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.topic_states)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.topic_states)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
             }
         } else {
             if !self.topic_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "topic_states", version));
             }
         }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.live_leaders)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.live_leaders).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.live_leaders)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.live_leaders).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.controller_id)?;
+        total_size += types::Int32.compute_size(&self.controller_id).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_id", version))?;
         if version >= 7 {
-            total_size += types::Boolean.compute_size(&self.is_k_raft_controller)?;
+            total_size += types::Boolean.compute_size(&self.is_k_raft_controller).map_err(|e| e.with_field("LeaderAndIsrRequest", "is_k_raft_controller", version))?;
         } else {
             if self.is_k_raft_controller {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "is_k_raft_controller", version));
             }
         }
-        total_size += types::Int32.compute_size(&self.controller_epoch)?;
+        total_size += types::Int32.compute_size(&self.controller_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_epoch", version))?;
         if version >= 2 {
-            total_size += types::Int64.compute_size(&self.broker_epoch)?;
+            total_size += types::Int64.compute_size(&self.broker_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "broker_epoch", version))?;
         }
         if version >= 5 {
-            total_size += types::Int8.compute_size(&self._type)?;
+            total_size += types::Int8.compute_size(&self._type).map_err(|e| e.with_field("LeaderAndIsrRequest", "_type", version))?;
         } else {
             if self._type != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "_type", version));
             }
         }
         if version <= 1 {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.ungrouped_partition_states)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.ungrouped_partition_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version))?;
         } else {
             if !self.ungrouped_partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version));
             }
         }
         if version >= 2 {
             if version >= 4 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topic_states)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topic_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.topic_states)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topic_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
             }
         } else {
             if !self.topic_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "topic_states", version));
             }
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.live_leaders)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.live_leaders).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.live_leaders)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.live_leaders).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -776,50 +1145,53 @@ impl Encodable for LeaderAndIsrRequest {
 
 impl Decodable for LeaderAndIsrRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let controller_id = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrRequest", version));
+        }
+        let start = buf.remaining();
+        let controller_id = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_id", version, start - buf.remaining()))?;
         let is_k_raft_controller = if version >= 7 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "is_k_raft_controller", version, start - buf.remaining()))?
         } else {
             false
         };
-        let controller_epoch = types::Int32.decode(buf)?;
+        let controller_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_epoch", version, start - buf.remaining()))?;
         let broker_epoch = if version >= 2 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "broker_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let _type = if version >= 5 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "_type", version, start - buf.remaining()))?
         } else {
             0
         };
         let ungrouped_partition_states = if version <= 1 {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topic_states = if version >= 2 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let live_leaders = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -848,7 +1220,7 @@ impl Default for LeaderAndIsrRequest {
             ungrouped_partition_states: Default::default(),
             topic_states: Default::default(),
             live_leaders: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -857,6 +1229,387 @@ impl Message for LeaderAndIsrRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl LeaderAndIsrRequest {
+    /// Encode the message with the elements of `ungrouped_partition_states` produced by an iterator of known length, in place of
+    /// the `ungrouped_partition_states` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_ungrouped_partition_states<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, ungrouped_partition_states: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = LeaderAndIsrPartitionState>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let ungrouped_partition_states = ungrouped_partition_states.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_id", version))?;
+        if version >= 7 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("LeaderAndIsrRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_epoch", version))?;
+        if version >= 2 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "broker_epoch", version))?;
+        }
+        if version >= 5 {
+            types::Int8.encode(buf, &self._type).map_err(|e| e.with_field("LeaderAndIsrRequest", "_type", version))?;
+        } else {
+            if self._type != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "_type", version));
+            }
+        }
+        if version <= 1 {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(ungrouped_partition_states)).map_err(|e| e.with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version))?;
+        } else {
+            if ungrouped_partition_states.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version));
+            }
+        }
+        if version >= 2 {
+            if version >= 4 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
+            }
+        } else {
+            if !self.topic_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "topic_states", version));
+            }
+        }
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.live_leaders).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.live_leaders).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `topic_states` produced by an iterator of known length, in place of
+    /// the `topic_states` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topic_states<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topic_states: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = LeaderAndIsrTopicState>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topic_states = topic_states.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_id", version))?;
+        if version >= 7 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("LeaderAndIsrRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_epoch", version))?;
+        if version >= 2 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "broker_epoch", version))?;
+        }
+        if version >= 5 {
+            types::Int8.encode(buf, &self._type).map_err(|e| e.with_field("LeaderAndIsrRequest", "_type", version))?;
+        } else {
+            if self._type != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "_type", version));
+            }
+        }
+        if version <= 1 {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partition_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version))?;
+        } else {
+            if !self.ungrouped_partition_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version));
+            }
+        }
+        if version >= 2 {
+            if version >= 4 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topic_states)).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topic_states)).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
+            }
+        } else {
+            if topic_states.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "topic_states", version));
+            }
+        }
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.live_leaders).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.live_leaders).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `live_leaders` produced by an iterator of known length, in place of
+    /// the `live_leaders` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_live_leaders<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, live_leaders: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = LeaderAndIsrLiveLeader>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let live_leaders = live_leaders.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_id", version))?;
+        if version >= 7 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("LeaderAndIsrRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "controller_epoch", version))?;
+        if version >= 2 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("LeaderAndIsrRequest", "broker_epoch", version))?;
+        }
+        if version >= 5 {
+            types::Int8.encode(buf, &self._type).map_err(|e| e.with_field("LeaderAndIsrRequest", "_type", version))?;
+        } else {
+            if self._type != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "_type", version));
+            }
+        }
+        if version <= 1 {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partition_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version))?;
+        } else {
+            if !self.ungrouped_partition_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "ungrouped_partition_states", version));
+            }
+        }
+        if version >= 2 {
+            if version >= 4 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("LeaderAndIsrRequest", "topic_states", version))?;
+            }
+        } else {
+            if !self.topic_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrRequest", "topic_states", version));
+            }
+        }
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(live_leaders)).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(live_leaders)).map_err(|e| e.with_field("LeaderAndIsrRequest", "live_leaders", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl LeaderAndIsrRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 7 {
+            self.is_k_raft_controller = false;
+        }
+        if version < 2 {
+            self.broker_epoch = -1;
+        }
+        if version < 5 {
+            self._type = 0;
+        }
+        if version <= 1 {
+            for item in self.ungrouped_partition_states.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.ungrouped_partition_states = Default::default();
+        }
+        if version >= 2 {
+            for item in self.topic_states.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topic_states = Default::default();
+        }
+        for item in self.live_leaders.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 7 {
+            if self.is_k_raft_controller {
+                invalid.push(format!("{}is_k_raft_controller", path));
+            }
+        }
+        if version < 2 {
+            if self.broker_epoch != -1 {
+                invalid.push(format!("{}broker_epoch", path));
+            }
+        }
+        if version < 5 {
+            if self._type != 0 {
+                invalid.push(format!("{}_type", path));
+            }
+        }
+        if version <= 1 {
+            for (i, item) in self.ungrouped_partition_states.iter().enumerate() {
+                item.validate_into(version, &format!("{}ungrouped_partition_states[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.ungrouped_partition_states.is_empty() {
+                invalid.push(format!("{}ungrouped_partition_states", path));
+            }
+        }
+        if version >= 2 {
+            for (i, item) in self.topic_states.iter().enumerate() {
+                item.validate_into(version, &format!("{}topic_states[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topic_states.is_empty() {
+                invalid.push(format!("{}topic_states", path));
+            }
+        }
+        for (i, item) in self.live_leaders.iter().enumerate() {
+            item.validate_into(version, &format!("{}live_leaders[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaderAndIsrRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("controllerId".into(), JsonValue::to_json(&self.controller_id));
+        if version >= 7 {
+            node.insert("isKRaftController".into(), JsonValue::to_json(&self.is_k_raft_controller));
+        }
+        node.insert("controllerEpoch".into(), JsonValue::to_json(&self.controller_epoch));
+        if version >= 2 {
+            node.insert("brokerEpoch".into(), JsonValue::to_json(&self.broker_epoch));
+        }
+        if version >= 5 {
+            node.insert("type".into(), JsonValue::to_json(&self._type));
+        }
+        if version <= 1 {
+            node.insert("ungroupedPartitionStates".into(), serde_json::Value::Array(self.ungrouped_partition_states.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 2 {
+            node.insert("topicStates".into(), serde_json::Value::Array(self.topic_states.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        node.insert("liveLeaders".into(), serde_json::Value::Array(self.live_leaders.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "LeaderAndIsrRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("controllerId") {
+            this.controller_id = JsonValue::from_json(value, "LeaderAndIsrRequest.controllerId")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrRequest", "controllerId", version));
+        }
+        if let Some(value) = object.get("isKRaftController") {
+            this.is_k_raft_controller = JsonValue::from_json(value, "LeaderAndIsrRequest.isKRaftController")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("LeaderAndIsrRequest", "isKRaftController", version));
+        }
+        if let Some(value) = object.get("controllerEpoch") {
+            this.controller_epoch = JsonValue::from_json(value, "LeaderAndIsrRequest.controllerEpoch")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrRequest", "controllerEpoch", version));
+        }
+        if let Some(value) = object.get("brokerEpoch") {
+            this.broker_epoch = JsonValue::from_json(value, "LeaderAndIsrRequest.brokerEpoch")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("LeaderAndIsrRequest", "brokerEpoch", version));
+        }
+        if let Some(value) = object.get("type") {
+            this._type = JsonValue::from_json(value, "LeaderAndIsrRequest.type")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("LeaderAndIsrRequest", "type", version));
+        }
+        if let Some(value) = object.get("ungroupedPartitionStates") {
+            this.ungrouped_partition_states = crate::json::array(value, "LeaderAndIsrRequest.ungroupedPartitionStates")?.iter().map(|item| LeaderAndIsrPartitionState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version <= 1 {
+            return Err(crate::json::missing("LeaderAndIsrRequest", "ungroupedPartitionStates", version));
+        }
+        if let Some(value) = object.get("topicStates") {
+            this.topic_states = crate::json::array(value, "LeaderAndIsrRequest.topicStates")?.iter().map(|item| LeaderAndIsrTopicState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("LeaderAndIsrRequest", "topicStates", version));
+        }
+        if let Some(value) = object.get("liveLeaders") {
+            this.live_leaders = crate::json::array(value, "LeaderAndIsrRequest.liveLeaders")?.iter().map(|item| LeaderAndIsrLiveLeader::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrRequest", "liveLeaders", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for LeaderAndIsrRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
@@ -867,3 +1620,52 @@ impl HeaderVersion for LeaderAndIsrRequest {
     }
 }
 
+impl LeaderAndIsrRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::LeaderAndIsrResponse {
+        let mut response = super::LeaderAndIsrResponse::default();
+        response.error_code = error.code();
+        if version <= 4 {
+            let mut items0 = Vec::new();
+            if version <= 1 {
+                for request0 in &self.ungrouped_partition_states {
+                    let mut response0 = super::leader_and_isr_response::LeaderAndIsrPartitionError::default();
+                    response0.topic_name = request0.topic_name.clone();
+                    response0.partition_index = request0.partition_index;
+                    response0.error_code = error.code();
+                    items0.push(response0);
+                }
+            } else if version >= 2 {
+                for request0 in &self.topic_states {
+                    let mut response0 = super::leader_and_isr_response::LeaderAndIsrPartitionError::default();
+                    response0.topic_name = request0.topic_name.clone();
+                    response0.error_code = error.code();
+                    items0.push(response0);
+                }
+            }
+            response.partition_errors = items0;
+        }
+        if version >= 5 {
+            let mut items0 = indexmap::IndexMap::new();
+            for request0 in &self.topic_states {
+                let mut response0 = super::leader_and_isr_response::LeaderAndIsrTopicError::default();
+                let mut items1 = Vec::new();
+                for request1 in &request0.partition_states {
+                    let mut response1 = super::leader_and_isr_response::LeaderAndIsrPartitionError::default();
+                    response1.partition_index = request1.partition_index;
+                    response1.error_code = error.code();
+                    items1.push(response1);
+                }
+                response0.partition_errors = items1;
+                items0.insert(request0.topic_id, response0);
+            }
+            response.topics = items0;
+        }
+        response
+    }
+}
+
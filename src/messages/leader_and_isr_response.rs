@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaderAndIsrPartitionError {
     /// The topic name.
@@ -38,7 +44,7 @@ pub struct LeaderAndIsrPartitionError {
     pub error_code: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaderAndIsrPartitionError {
@@ -51,47 +57,51 @@ impl Builder for LeaderAndIsrPartitionError {
 
 impl Encodable for LeaderAndIsrPartitionError {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 4 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.topic_name)?;
+                types::CompactString.encode(buf, &self.topic_name).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "topic_name", version))?;
             } else {
-                types::String.encode(buf, &self.topic_name)?;
+                types::String.encode(buf, &self.topic_name).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "topic_name", version))?;
             }
         }
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int16.encode(buf, &self.error_code)?;
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "partition_index", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "error_code", version))?;
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 4 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.topic_name)?;
+                total_size += types::CompactString.compute_size(&self.topic_name).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "topic_name", version))?;
             } else {
-                total_size += types::String.compute_size(&self.topic_name)?;
+                total_size += types::String.compute_size(&self.topic_name).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "topic_name", version))?;
             }
         }
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "partition_index", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "error_code", version))?;
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -99,25 +109,25 @@ impl Encodable for LeaderAndIsrPartitionError {
 
 impl Decodable for LeaderAndIsrPartitionError {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version <= 4 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "topic_name", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "topic_name", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let partition_index = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "partition_index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "error_code", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaderAndIsrPartitionError", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -136,7 +146,7 @@ impl Default for LeaderAndIsrPartitionError {
             topic_name: Default::default(),
             partition_index: 0,
             error_code: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -145,9 +155,85 @@ impl Message for LeaderAndIsrPartitionError {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl LeaderAndIsrPartitionError {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 4 {
+            self.topic_name = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 4 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaderAndIsrPartitionError {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 4 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "LeaderAndIsrPartitionError")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "LeaderAndIsrPartitionError.topicName")?;
+        } else if version <= 4 {
+            return Err(crate::json::missing("LeaderAndIsrPartitionError", "topicName", version));
+        }
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "LeaderAndIsrPartitionError.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionError", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "LeaderAndIsrPartitionError.errorCode")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrPartitionError", "errorCode", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaderAndIsrTopicError {
     /// Each partition.
@@ -156,7 +242,7 @@ pub struct LeaderAndIsrTopicError {
     pub partition_errors: Vec<LeaderAndIsrPartitionError>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaderAndIsrTopicError {
@@ -170,57 +256,61 @@ impl Builder for LeaderAndIsrTopicError {
 impl MapEncodable for LeaderAndIsrTopicError {
     type Key = Uuid;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 5 {
-            types::Uuid.encode(buf, key)?;
+            types::Uuid.encode(buf, key).map_err(|e| e.with_field("LeaderAndIsrTopicError", "topic_id", version))?;
         } else {
             if key != &Uuid::nil() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrTopicError", "topic_id", version));
             }
         }
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partition_errors)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partition_errors).map_err(|e| e.with_field("LeaderAndIsrTopicError", "partition_errors", version))?;
         } else {
             if !self.partition_errors.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrTopicError", "partition_errors", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 5 {
-            total_size += types::Uuid.compute_size(key)?;
+            total_size += types::Uuid.compute_size(key).map_err(|e| e.with_field("LeaderAndIsrTopicError", "topic_id", version))?;
         } else {
             if key != &Uuid::nil() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrTopicError", "topic_id", version));
             }
         }
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partition_errors)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partition_errors).map_err(|e| e.with_field("LeaderAndIsrTopicError", "partition_errors", version))?;
         } else {
             if !self.partition_errors.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrTopicError", "partition_errors", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -229,24 +319,24 @@ impl MapEncodable for LeaderAndIsrTopicError {
 impl MapDecodable for LeaderAndIsrTopicError {
     type Key = Uuid;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 5 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicError", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
         let partition_errors = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicError", "partition_errors", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaderAndIsrTopicError", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -261,7 +351,7 @@ impl Default for LeaderAndIsrTopicError {
     fn default() -> Self {
         Self {
             partition_errors: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -270,9 +360,90 @@ impl Message for LeaderAndIsrTopicError {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl LeaderAndIsrTopicError {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version >= 5 {
+            for item in self.partition_errors.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.partition_errors = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version >= 5 {
+            for (i, item) in self.partition_errors.iter().enumerate() {
+                item.validate_into(version, &format!("{}partition_errors[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.partition_errors.is_empty() {
+                invalid.push(format!("{}partition_errors", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaderAndIsrTopicError {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &Uuid, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &Uuid, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 5 {
+            node.insert("topicId".into(), JsonValue::to_json(key));
+        }
+        if version >= 5 {
+            node.insert("partitionErrors".into(), serde_json::Value::Array(self.partition_errors.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(Uuid, Self), JsonError> {
+        let object = crate::json::object(node, "LeaderAndIsrTopicError")?;
+        let mut this = Self::default();
+        let mut key = Uuid::default();
+        if let Some(value) = object.get("topicId") {
+            key = JsonValue::from_json(value, "LeaderAndIsrTopicError.topicId")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("LeaderAndIsrTopicError", "topicId", version));
+        }
+        if let Some(value) = object.get("partitionErrors") {
+            this.partition_errors = crate::json::array(value, "LeaderAndIsrTopicError.partitionErrors")?.iter().map(|item| LeaderAndIsrPartitionError::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("LeaderAndIsrTopicError", "partitionErrors", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-7
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaderAndIsrResponse {
     /// The error code, or 0 if there was no error.
@@ -291,7 +462,7 @@ pub struct LeaderAndIsrResponse {
     pub topics: indexmap::IndexMap<Uuid, LeaderAndIsrTopicError>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaderAndIsrResponse {
@@ -304,67 +475,77 @@ impl Builder for LeaderAndIsrResponse {
 
 impl Encodable for LeaderAndIsrResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrResponse", version));
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("LeaderAndIsrResponse", "error_code", version))?;
         if version <= 4 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.partition_errors)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partition_errors).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.partition_errors)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.partition_errors).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version))?;
             }
         } else {
             if !self.partition_errors.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrResponse", "partition_errors", version));
             }
         }
         if version >= 5 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("LeaderAndIsrResponse", "topics", version))?;
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrResponse", "topics", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("LeaderAndIsrResponse", "error_code", version))?;
         if version <= 4 {
             if version >= 4 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partition_errors)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partition_errors).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.partition_errors)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partition_errors).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version))?;
             }
         } else {
             if !self.partition_errors.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrResponse", "partition_errors", version));
             }
         }
         if version >= 5 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("LeaderAndIsrResponse", "topics", version))?;
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrResponse", "topics", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -372,29 +553,32 @@ impl Encodable for LeaderAndIsrResponse {
 
 impl Decodable for LeaderAndIsrResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrResponse", version));
+        }
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("LeaderAndIsrResponse", "error_code", version, start - buf.remaining()))?;
         let partition_errors = if version <= 4 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let topics = if version >= 5 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaderAndIsrResponse", "topics", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -413,7 +597,7 @@ impl Default for LeaderAndIsrResponse {
             error_code: 0,
             partition_errors: Default::default(),
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -422,6 +606,204 @@ impl Message for LeaderAndIsrResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 7 };
 }
 
+impl LeaderAndIsrResponse {
+    /// Encode the message with the elements of `partition_errors` produced by an iterator of known length, in place of
+    /// the `partition_errors` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_partition_errors<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, partition_errors: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = LeaderAndIsrPartitionError>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let partition_errors = partition_errors.into_iter();
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("LeaderAndIsrResponse", "error_code", version))?;
+        if version <= 4 {
+            if version >= 4 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(partition_errors)).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(partition_errors)).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version))?;
+            }
+        } else {
+            if partition_errors.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrResponse", "partition_errors", version));
+            }
+        }
+        if version >= 5 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("LeaderAndIsrResponse", "topics", version))?;
+        } else {
+            if !self.topics.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrResponse", "topics", version));
+            }
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (Uuid, LeaderAndIsrTopicError)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaderAndIsrResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("LeaderAndIsrResponse", "error_code", version))?;
+        if version <= 4 {
+            if version >= 4 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partition_errors).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.partition_errors).map_err(|e| e.with_field("LeaderAndIsrResponse", "partition_errors", version))?;
+            }
+        } else {
+            if !self.partition_errors.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrResponse", "partition_errors", version));
+            }
+        }
+        if version >= 5 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("LeaderAndIsrResponse", "topics", version))?;
+        } else {
+            if topics.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaderAndIsrResponse", "topics", version));
+            }
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaderAndIsrResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl LeaderAndIsrResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version <= 4 {
+            for item in self.partition_errors.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.partition_errors = Default::default();
+        }
+        if version >= 5 {
+            for item in self.topics.values_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topics = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version <= 4 {
+            for (i, item) in self.partition_errors.iter().enumerate() {
+                item.validate_into(version, &format!("{}partition_errors[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.partition_errors.is_empty() {
+                invalid.push(format!("{}partition_errors", path));
+            }
+        }
+        if version >= 5 {
+            for (i, item) in self.topics.values().enumerate() {
+                item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topics.is_empty() {
+                invalid.push(format!("{}topics", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaderAndIsrResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        if version <= 4 {
+            node.insert("partitionErrors".into(), serde_json::Value::Array(self.partition_errors.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 5 {
+            node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "LeaderAndIsrResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "LeaderAndIsrResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("LeaderAndIsrResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("partitionErrors") {
+            this.partition_errors = crate::json::array(value, "LeaderAndIsrResponse.partitionErrors")?.iter().map(|item| LeaderAndIsrPartitionError::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version <= 4 {
+            return Err(crate::json::missing("LeaderAndIsrResponse", "partitionErrors", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "LeaderAndIsrResponse.topics")?.iter().map(|item| LeaderAndIsrTopicError::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("LeaderAndIsrResponse", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for LeaderAndIsrResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
@@ -5,31 +5,40 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct MemberResponse {
     /// The member ID to remove from the group.
     /// 
     /// Supported API versions: 3-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The group instance ID to remove from the group.
     /// 
     /// Supported API versions: 3-5
+    /// May be `None` in versions: 3-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_instance_id: Option<StrBytes>,
 
     /// The error code, or 0 if there was no error.
@@ -38,7 +47,7 @@ pub struct MemberResponse {
     pub error_code: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for MemberResponse {
@@ -51,87 +60,91 @@ impl Builder for MemberResponse {
 
 impl Encodable for MemberResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.member_id)?;
+                types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("MemberResponse", "member_id", version))?;
             } else {
-                types::String.encode(buf, &self.member_id)?;
+                types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("MemberResponse", "member_id", version))?;
             }
         } else {
             if !self.member_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MemberResponse", "member_id", version));
             }
         }
         if version >= 3 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.group_instance_id)?;
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("MemberResponse", "group_instance_id", version))?;
             } else {
-                types::String.encode(buf, &self.group_instance_id)?;
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("MemberResponse", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MemberResponse", "group_instance_id", version));
             }
         }
         if version >= 3 {
-            types::Int16.encode(buf, &self.error_code)?;
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("MemberResponse", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MemberResponse", "error_code", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MemberResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MemberResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MemberResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.member_id)?;
+                total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("MemberResponse", "member_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.member_id)?;
+                total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("MemberResponse", "member_id", version))?;
             }
         } else {
             if !self.member_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MemberResponse", "member_id", version));
             }
         }
         if version >= 3 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.group_instance_id)?;
+                total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("MemberResponse", "group_instance_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.group_instance_id)?;
+                total_size += types::String.compute_size(&self.group_instance_id).map_err(|e| e.with_field("MemberResponse", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MemberResponse", "group_instance_id", version));
             }
         }
         if version >= 3 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("MemberResponse", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MemberResponse", "error_code", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MemberResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("MemberResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("MemberResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -139,37 +152,37 @@ impl Encodable for MemberResponse {
 
 impl Decodable for MemberResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let member_id = if version >= 3 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("MemberResponse", "member_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("MemberResponse", "member_id", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let group_instance_id = if version >= 3 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("MemberResponse", "group_instance_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("MemberResponse", "group_instance_id", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
         let error_code = if version >= 3 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("MemberResponse", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MemberResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MemberResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MemberResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("MemberResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -188,7 +201,7 @@ impl Default for MemberResponse {
             member_id: Default::default(),
             group_instance_id: Some(Default::default()),
             error_code: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -197,9 +210,105 @@ impl Message for MemberResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl MemberResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.member_id = Default::default();
+        }
+        if version < 3 {
+            self.group_instance_id = Some(Default::default());
+        }
+        if version < 3 {
+            self.error_code = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.member_id.is_empty() {
+                invalid.push(format!("{}member_id", path));
+            }
+        }
+        if version < 3 {
+            if !self.group_instance_id.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+        if version < 3 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MemberResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        }
+        if version >= 3 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        if version >= 3 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "MemberResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "MemberResponse.memberId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("MemberResponse", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "MemberResponse.groupInstanceId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("MemberResponse", "groupInstanceId", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "MemberResponse.errorCode")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("MemberResponse", "errorCode", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct LeaveGroupResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -218,7 +327,7 @@ pub struct LeaveGroupResponse {
     pub members: Vec<MemberResponse>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for LeaveGroupResponse {
@@ -231,59 +340,69 @@ impl Builder for LeaveGroupResponse {
 
 impl Encodable for LeaveGroupResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaveGroupResponse", version));
+        }
         if version >= 1 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("LeaveGroupResponse", "throttle_time_ms", version))?;
         }
-        types::Int16.encode(buf, &self.error_code)?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("LeaveGroupResponse", "error_code", version))?;
         if version >= 3 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.members)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.members).map_err(|e| e.with_field("LeaveGroupResponse", "members", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.members)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.members).map_err(|e| e.with_field("LeaveGroupResponse", "members", version))?;
             }
         } else {
             if !self.members.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaveGroupResponse", "members", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaveGroupResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaveGroupResponse", version));
+        }
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("LeaveGroupResponse", "throttle_time_ms", version))?;
         }
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("LeaveGroupResponse", "error_code", version))?;
         if version >= 3 {
             if version >= 4 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.members)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.members).map_err(|e| e.with_field("LeaveGroupResponse", "members", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.members)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.members).map_err(|e| e.with_field("LeaveGroupResponse", "members", version))?;
             }
         } else {
             if !self.members.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaveGroupResponse", "members", version));
             }
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaveGroupResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -291,29 +410,32 @@ impl Encodable for LeaveGroupResponse {
 
 impl Decodable for LeaveGroupResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("LeaveGroupResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("LeaveGroupResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
-        let error_code = types::Int16.decode(buf)?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("LeaveGroupResponse", "error_code", version, start - buf.remaining()))?;
         let members = if version >= 3 {
             if version >= 4 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaveGroupResponse", "members", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("LeaveGroupResponse", "members", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -332,7 +454,7 @@ impl Default for LeaveGroupResponse {
             throttle_time_ms: 0,
             error_code: 0,
             members: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -341,6 +463,144 @@ impl Message for LeaveGroupResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl LeaveGroupResponse {
+    /// Encode the message with the elements of `members` produced by an iterator of known length, in place of
+    /// the `members` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_members<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, members: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = MemberResponse>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("LeaveGroupResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let members = members.into_iter();
+        if version >= 1 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("LeaveGroupResponse", "throttle_time_ms", version))?;
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("LeaveGroupResponse", "error_code", version))?;
+        if version >= 3 {
+            if version >= 4 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(members)).map_err(|e| e.with_field("LeaveGroupResponse", "members", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(members)).map_err(|e| e.with_field("LeaveGroupResponse", "members", version))?;
+            }
+        } else {
+            if members.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("LeaveGroupResponse", "members", version));
+            }
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("LeaveGroupResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("LeaveGroupResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl LeaveGroupResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.throttle_time_ms = 0;
+        }
+        if version >= 3 {
+            for item in self.members.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.members = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        if version >= 3 {
+            for (i, item) in self.members.iter().enumerate() {
+                item.validate_into(version, &format!("{}members[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.members.is_empty() {
+                invalid.push(format!("{}members", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl LeaveGroupResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        if version >= 3 {
+            node.insert("members".into(), serde_json::Value::Array(self.members.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "LeaveGroupResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "LeaveGroupResponse.throttleTimeMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("LeaveGroupResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "LeaveGroupResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("LeaveGroupResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("members") {
+            this.members = crate::json::array(value, "LeaveGroupResponse.members")?.iter().map(|item| MemberResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("LeaveGroupResponse", "members", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for LeaveGroupResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
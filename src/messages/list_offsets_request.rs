@@ -5,21 +5,29 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+use bytes::Buf;
+use crate::protocol::view::{self, ArrayView};
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ListOffsetsPartition {
     /// The partition index.
@@ -43,7 +51,7 @@ pub struct ListOffsetsPartition {
     pub max_num_offsets: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ListOffsetsPartition {
@@ -56,53 +64,57 @@ impl Builder for ListOffsetsPartition {
 
 impl Encodable for ListOffsetsPartition {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("ListOffsetsPartition", "partition_index", version))?;
         if version >= 4 {
-            types::Int32.encode(buf, &self.current_leader_epoch)?;
+            types::Int32.encode(buf, &self.current_leader_epoch).map_err(|e| e.with_field("ListOffsetsPartition", "current_leader_epoch", version))?;
         }
-        types::Int64.encode(buf, &self.timestamp)?;
+        types::Int64.encode(buf, &self.timestamp).map_err(|e| e.with_field("ListOffsetsPartition", "timestamp", version))?;
         if version == 0 {
-            types::Int32.encode(buf, &self.max_num_offsets)?;
+            types::Int32.encode(buf, &self.max_num_offsets).map_err(|e| e.with_field("ListOffsetsPartition", "max_num_offsets", version))?;
         } else {
             if self.max_num_offsets != 1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartition", "max_num_offsets", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsPartition", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsPartition", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsPartition", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("ListOffsetsPartition", "partition_index", version))?;
         if version >= 4 {
-            total_size += types::Int32.compute_size(&self.current_leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.current_leader_epoch).map_err(|e| e.with_field("ListOffsetsPartition", "current_leader_epoch", version))?;
         }
-        total_size += types::Int64.compute_size(&self.timestamp)?;
+        total_size += types::Int64.compute_size(&self.timestamp).map_err(|e| e.with_field("ListOffsetsPartition", "timestamp", version))?;
         if version == 0 {
-            total_size += types::Int32.compute_size(&self.max_num_offsets)?;
+            total_size += types::Int32.compute_size(&self.max_num_offsets).map_err(|e| e.with_field("ListOffsetsPartition", "max_num_offsets", version))?;
         } else {
             if self.max_num_offsets != 1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartition", "max_num_offsets", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsPartition", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsPartition", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsPartition", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -110,26 +122,26 @@ impl Encodable for ListOffsetsPartition {
 
 impl Decodable for ListOffsetsPartition {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "partition_index", version, start - buf.remaining()))?;
         let current_leader_epoch = if version >= 4 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "current_leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let timestamp = types::Int64.decode(buf)?;
+        let timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "timestamp", version, start - buf.remaining()))?;
         let max_num_offsets = if version == 0 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "max_num_offsets", version, start - buf.remaining()))?
         } else {
             1
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ListOffsetsPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -150,7 +162,7 @@ impl Default for ListOffsetsPartition {
             current_leader_epoch: -1,
             timestamp: 0,
             max_num_offsets: 1,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -159,9 +171,191 @@ impl Message for ListOffsetsPartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl ListOffsetsPartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 4 {
+            self.current_leader_epoch = -1;
+        }
+        if version != 0 {
+            self.max_num_offsets = 1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 4 {
+            if self.current_leader_epoch != -1 {
+                invalid.push(format!("{}current_leader_epoch", path));
+            }
+        }
+        if version != 0 {
+            if self.max_num_offsets != 1 {
+                invalid.push(format!("{}max_num_offsets", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ListOffsetsPartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        if version >= 4 {
+            node.insert("currentLeaderEpoch".into(), JsonValue::to_json(&self.current_leader_epoch));
+        }
+        node.insert("timestamp".into(), JsonValue::to_json(&self.timestamp));
+        if version == 0 {
+            node.insert("maxNumOffsets".into(), JsonValue::to_json(&self.max_num_offsets));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ListOffsetsPartition")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "ListOffsetsPartition.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsPartition", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("currentLeaderEpoch") {
+            this.current_leader_epoch = JsonValue::from_json(value, "ListOffsetsPartition.currentLeaderEpoch")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("ListOffsetsPartition", "currentLeaderEpoch", version));
+        }
+        if let Some(value) = object.get("timestamp") {
+            this.timestamp = JsonValue::from_json(value, "ListOffsetsPartition.timestamp")?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsPartition", "timestamp", version));
+        }
+        if let Some(value) = object.get("maxNumOffsets") {
+            this.max_num_offsets = JsonValue::from_json(value, "ListOffsetsPartition.maxNumOffsets")?;
+        } else if version == 0 {
+            return Err(crate::json::missing("ListOffsetsPartition", "maxNumOffsets", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ListOffsetsPartition`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-8
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsPartitionView {
+    /// The partition index.
+    /// 
+    /// Supported API versions: 0-8
+    pub partition_index: i32,
+
+    /// The current leader epoch.
+    /// 
+    /// Supported API versions: 4-8
+    pub current_leader_epoch: i32,
+
+    /// The current timestamp.
+    /// 
+    /// Supported API versions: 0-8
+    pub timestamp: i64,
+
+    /// The maximum number of offsets to report.
+    /// 
+    /// Supported API versions: 0
+    pub max_num_offsets: i32,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ListOffsetsPartitionView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "partition_index", version, start - buf.remaining()))?;
+        let current_leader_epoch = if version >= 4 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "current_leader_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "timestamp", version, start - buf.remaining()))?;
+        let max_num_offsets = if version == 0 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "max_num_offsets", version, start - buf.remaining()))?
+        } else {
+            1
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 6 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsPartition", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ListOffsetsPartition", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            partition_index,
+            current_leader_epoch,
+            timestamp,
+            max_num_offsets,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("ListOffsetsPartition", "partition_index", version, start - buf.remaining()))?;
+        if version >= 4 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("ListOffsetsPartition", "current_leader_epoch", version, start - buf.remaining()))?;
+        }
+        view::skip_fixed(buf, 8).map_err(|e| e.with_field("ListOffsetsPartition", "timestamp", version, start - buf.remaining()))?;
+        if version == 0 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("ListOffsetsPartition", "max_num_offsets", version, start - buf.remaining()))?;
+        }
+        if version >= 6 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("ListOffsetsPartition", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ListOffsetsPartitionView {
+    fn default() -> Self {
+        Self {
+            partition_index: 0,
+            current_leader_epoch: -1,
+            timestamp: 0,
+            max_num_offsets: 1,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ListOffsetsTopic {
     /// The topic name.
@@ -175,7 +369,7 @@ pub struct ListOffsetsTopic {
     pub partitions: Vec<ListOffsetsPartition>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ListOffsetsTopic {
@@ -188,49 +382,53 @@ impl Builder for ListOffsetsTopic {
 
 impl Encodable for ListOffsetsTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 6 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("ListOffsetsTopic", "name", version))?;
         } else {
-            types::String.encode(buf, &self.name)?;
+            types::String.encode(buf, &self.name).map_err(|e| e.with_field("ListOffsetsTopic", "name", version))?;
         }
         if version >= 6 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("ListOffsetsTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.name)?;
+            total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("ListOffsetsTopic", "name", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -238,24 +436,24 @@ impl Encodable for ListOffsetsTopic {
 
 impl Decodable for ListOffsetsTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "name", version, start - buf.remaining()))?
         };
         let partitions = if version >= 6 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ListOffsetsTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -272,7 +470,7 @@ impl Default for ListOffsetsTopic {
         Self {
             name: Default::default(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -281,9 +479,151 @@ impl Message for ListOffsetsTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl ListOffsetsTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ListOffsetsTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ListOffsetsTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "ListOffsetsTopic.name")?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsTopic", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "ListOffsetsTopic.partitions")?.iter().map(|item| ListOffsetsPartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsTopic", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ListOffsetsTopic`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-8
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsTopicView {
+    /// The topic name.
+    /// 
+    /// Supported API versions: 0-8
+    pub name: super::TopicName,
+
+    /// Each partition in the request.
+    /// 
+    /// Supported API versions: 0-8
+    pub partitions: ArrayView<ListOffsetsPartitionView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ListOffsetsTopicView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let name = if version >= 6 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "name", version, start - buf.remaining()))?
+        };
+        let partitions = if version >= 6 {
+            ArrayView::decode(buf, version, true, ListOffsetsPartitionView::decode, ListOffsetsPartitionView::skip).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, ListOffsetsPartitionView::decode, ListOffsetsPartitionView::skip).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 6 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsTopic", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ListOffsetsTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            name,
+            partitions,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 6 {
+            view::skip_string(buf, true).map_err(|e| e.with_field("ListOffsetsTopic", "name", version, start - buf.remaining()))?;
+        } else {
+            view::skip_string(buf, false).map_err(|e| e.with_field("ListOffsetsTopic", "name", version, start - buf.remaining()))?;
+        }
+        if version >= 6 {
+            view::skip_array(buf, version, true, ListOffsetsPartitionView::skip).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, ListOffsetsPartitionView::skip).map_err(|e| e.with_field("ListOffsetsTopic", "partitions", version, start - buf.remaining()))?;
+        }
+        if version >= 6 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("ListOffsetsTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ListOffsetsTopicView {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            partitions: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ListOffsetsRequest {
     /// The broker ID of the requester, or -1 if this request is being made by a normal consumer.
@@ -302,7 +642,7 @@ pub struct ListOffsetsRequest {
     pub topics: Vec<ListOffsetsTopic>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ListOffsetsRequest {
@@ -315,55 +655,65 @@ impl Builder for ListOffsetsRequest {
 
 impl Encodable for ListOffsetsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.replica_id)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListOffsetsRequest", version));
+        }
+        types::Int32.encode(buf, &self.replica_id).map_err(|e| e.with_field("ListOffsetsRequest", "replica_id", version))?;
         if version >= 2 {
-            types::Int8.encode(buf, &self.isolation_level)?;
+            types::Int8.encode(buf, &self.isolation_level).map_err(|e| e.with_field("ListOffsetsRequest", "isolation_level", version))?;
         } else {
             if self.isolation_level != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsRequest", "isolation_level", version));
             }
         }
         if version >= 6 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListOffsetsRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.replica_id)?;
+        total_size += types::Int32.compute_size(&self.replica_id).map_err(|e| e.with_field("ListOffsetsRequest", "replica_id", version))?;
         if version >= 2 {
-            total_size += types::Int8.compute_size(&self.isolation_level)?;
+            total_size += types::Int8.compute_size(&self.isolation_level).map_err(|e| e.with_field("ListOffsetsRequest", "isolation_level", version))?;
         } else {
             if self.isolation_level != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsRequest", "isolation_level", version));
             }
         }
         if version >= 6 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -371,25 +721,28 @@ impl Encodable for ListOffsetsRequest {
 
 impl Decodable for ListOffsetsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let replica_id = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ListOffsetsRequest", version));
+        }
+        let start = buf.remaining();
+        let replica_id = types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "replica_id", version, start - buf.remaining()))?;
         let isolation_level = if version >= 2 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "isolation_level", version, start - buf.remaining()))?
         } else {
             0
         };
         let topics = if version >= 6 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -408,7 +761,7 @@ impl Default for ListOffsetsRequest {
             replica_id: (0).into(),
             isolation_level: 0,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -417,6 +770,198 @@ impl Message for ListOffsetsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl ListOffsetsRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = ListOffsetsTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListOffsetsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        types::Int32.encode(buf, &self.replica_id).map_err(|e| e.with_field("ListOffsetsRequest", "replica_id", version))?;
+        if version >= 2 {
+            types::Int8.encode(buf, &self.isolation_level).map_err(|e| e.with_field("ListOffsetsRequest", "isolation_level", version))?;
+        } else {
+            if self.isolation_level != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsRequest", "isolation_level", version));
+            }
+        }
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version))?;
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl ListOffsetsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.isolation_level = 0;
+        }
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if self.isolation_level != 0 {
+                invalid.push(format!("{}isolation_level", path));
+            }
+        }
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ListOffsetsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("replicaId".into(), JsonValue::to_json(&self.replica_id));
+        if version >= 2 {
+            node.insert("isolationLevel".into(), JsonValue::to_json(&self.isolation_level));
+        }
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ListOffsetsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("replicaId") {
+            this.replica_id = JsonValue::from_json(value, "ListOffsetsRequest.replicaId")?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsRequest", "replicaId", version));
+        }
+        if let Some(value) = object.get("isolationLevel") {
+            this.isolation_level = JsonValue::from_json(value, "ListOffsetsRequest.isolationLevel")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("ListOffsetsRequest", "isolationLevel", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "ListOffsetsRequest.topics")?.iter().map(|item| ListOffsetsTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsRequest", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ListOffsetsRequest`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-8
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsRequestView {
+    /// The broker ID of the requester, or -1 if this request is being made by a normal consumer.
+    /// 
+    /// Supported API versions: 0-8
+    pub replica_id: super::BrokerId,
+
+    /// This setting controls the visibility of transactional records. Using READ_UNCOMMITTED (isolation_level = 0) makes all records visible. With READ_COMMITTED (isolation_level = 1), non-transactional and COMMITTED transactional records are visible. To be more concrete, READ_COMMITTED returns all data from offsets smaller than the current LSO (last stable offset), and enables the inclusion of the list of aborted transactions in the result, which allows consumers to discard ABORTED transactional records
+    /// 
+    /// Supported API versions: 2-8
+    pub isolation_level: i8,
+
+    /// Each topic in the request.
+    /// 
+    /// Supported API versions: 0-8
+    pub topics: ArrayView<ListOffsetsTopicView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ListOffsetsRequestView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        if !ListOffsetsRequest::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ListOffsetsRequest", version));
+        }
+        let start = buf.remaining();
+        let replica_id = types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "replica_id", version, start - buf.remaining()))?;
+        let isolation_level = if version >= 2 {
+            types::Int8.decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "isolation_level", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let topics = if version >= 6 {
+            ArrayView::decode(buf, version, true, ListOffsetsTopicView::decode, ListOffsetsTopicView::skip).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, ListOffsetsTopicView::decode, ListOffsetsTopicView::skip).map_err(|e| e.with_field("ListOffsetsRequest", "topics", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 6 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsRequest", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ListOffsetsRequest", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            replica_id,
+            isolation_level,
+            topics,
+            tagged_fields,
+        })
+    }
+}
+
+impl Default for ListOffsetsRequestView {
+    fn default() -> Self {
+        Self {
+            replica_id: (0).into(),
+            isolation_level: 0,
+            topics: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 impl HeaderVersion for ListOffsetsRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 6 {
@@ -427,3 +972,30 @@ impl HeaderVersion for ListOffsetsRequest {
     }
 }
 
+impl ListOffsetsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::ListOffsetsResponse {
+        let mut response = super::ListOffsetsResponse::default();
+        let mut items0 = Vec::new();
+        for request0 in &self.topics {
+            let mut response0 = super::list_offsets_response::ListOffsetsTopicResponse::default();
+            response0.name = request0.name.clone();
+            let mut items1 = Vec::new();
+            for request1 in &request0.partitions {
+                let mut response1 = super::list_offsets_response::ListOffsetsPartitionResponse::default();
+                response1.partition_index = request1.partition_index;
+                response1.error_code = error.code();
+                items1.push(response1);
+            }
+            response0.partitions = items1;
+            items0.push(response0);
+        }
+        response.topics = items0;
+        response
+    }
+}
+
@@ -5,21 +5,29 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+use bytes::Buf;
+use crate::protocol::view::{self, ArrayView};
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ListOffsetsPartitionResponse {
     /// The partition index.
@@ -53,7 +61,7 @@ pub struct ListOffsetsPartitionResponse {
     pub leader_epoch: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ListOffsetsPartitionResponse {
@@ -66,89 +74,93 @@ impl Builder for ListOffsetsPartitionResponse {
 
 impl Encodable for ListOffsetsPartitionResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "partition_index", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "error_code", version))?;
         if version == 0 {
-            types::Array(types::Int64).encode(buf, &self.old_style_offsets)?;
+            types::Array(types::Int64).encode(buf, &self.old_style_offsets).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "old_style_offsets", version))?;
         } else {
             if !self.old_style_offsets.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartitionResponse", "old_style_offsets", version));
             }
         }
         if version >= 1 {
-            types::Int64.encode(buf, &self.timestamp)?;
+            types::Int64.encode(buf, &self.timestamp).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "timestamp", version))?;
         } else {
             if self.timestamp != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartitionResponse", "timestamp", version));
             }
         }
         if version >= 1 {
-            types::Int64.encode(buf, &self.offset)?;
+            types::Int64.encode(buf, &self.offset).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "offset", version))?;
         } else {
             if self.offset != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartitionResponse", "offset", version));
             }
         }
         if version >= 4 {
-            types::Int32.encode(buf, &self.leader_epoch)?;
+            types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "leader_epoch", version))?;
         } else {
             if self.leader_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartitionResponse", "leader_epoch", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "partition_index", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "error_code", version))?;
         if version == 0 {
-            total_size += types::Array(types::Int64).compute_size(&self.old_style_offsets)?;
+            total_size += types::Array(types::Int64).compute_size(&self.old_style_offsets).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "old_style_offsets", version))?;
         } else {
             if !self.old_style_offsets.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartitionResponse", "old_style_offsets", version));
             }
         }
         if version >= 1 {
-            total_size += types::Int64.compute_size(&self.timestamp)?;
+            total_size += types::Int64.compute_size(&self.timestamp).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "timestamp", version))?;
         } else {
             if self.timestamp != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartitionResponse", "timestamp", version));
             }
         }
         if version >= 1 {
-            total_size += types::Int64.compute_size(&self.offset)?;
+            total_size += types::Int64.compute_size(&self.offset).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "offset", version))?;
         } else {
             if self.offset != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartitionResponse", "offset", version));
             }
         }
         if version >= 4 {
-            total_size += types::Int32.compute_size(&self.leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "leader_epoch", version))?;
         } else {
             if self.leader_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ListOffsetsPartitionResponse", "leader_epoch", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -156,36 +168,36 @@ impl Encodable for ListOffsetsPartitionResponse {
 
 impl Decodable for ListOffsetsPartitionResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "partition_index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "error_code", version, start - buf.remaining()))?;
         let old_style_offsets = if version == 0 {
-            types::Array(types::Int64).decode(buf)?
+            types::Array(types::Int64).decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "old_style_offsets", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let timestamp = if version >= 1 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "timestamp", version, start - buf.remaining()))?
         } else {
             -1
         };
         let offset = if version >= 1 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "offset", version, start - buf.remaining()))?
         } else {
             -1
         };
         let leader_epoch = if version >= 4 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -210,7 +222,7 @@ impl Default for ListOffsetsPartitionResponse {
             timestamp: -1,
             offset: -1,
             leader_epoch: -1,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -219,9 +231,253 @@ impl Message for ListOffsetsPartitionResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl ListOffsetsPartitionResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version != 0 {
+            self.old_style_offsets = Default::default();
+        }
+        if version < 1 {
+            self.timestamp = -1;
+        }
+        if version < 1 {
+            self.offset = -1;
+        }
+        if version < 4 {
+            self.leader_epoch = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version != 0 {
+            if !self.old_style_offsets.is_empty() {
+                invalid.push(format!("{}old_style_offsets", path));
+            }
+        }
+        if version < 1 {
+            if self.timestamp != -1 {
+                invalid.push(format!("{}timestamp", path));
+            }
+        }
+        if version < 1 {
+            if self.offset != -1 {
+                invalid.push(format!("{}offset", path));
+            }
+        }
+        if version < 4 {
+            if self.leader_epoch != -1 {
+                invalid.push(format!("{}leader_epoch", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ListOffsetsPartitionResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        if version == 0 {
+            node.insert("oldStyleOffsets".into(), JsonValue::to_json(&self.old_style_offsets));
+        }
+        if version >= 1 {
+            node.insert("timestamp".into(), JsonValue::to_json(&self.timestamp));
+        }
+        if version >= 1 {
+            node.insert("offset".into(), JsonValue::to_json(&self.offset));
+        }
+        if version >= 4 {
+            node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ListOffsetsPartitionResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "ListOffsetsPartitionResponse.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsPartitionResponse", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "ListOffsetsPartitionResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsPartitionResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("oldStyleOffsets") {
+            this.old_style_offsets = JsonValue::from_json(value, "ListOffsetsPartitionResponse.oldStyleOffsets")?;
+        } else if version == 0 {
+            return Err(crate::json::missing("ListOffsetsPartitionResponse", "oldStyleOffsets", version));
+        }
+        if let Some(value) = object.get("timestamp") {
+            this.timestamp = JsonValue::from_json(value, "ListOffsetsPartitionResponse.timestamp")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("ListOffsetsPartitionResponse", "timestamp", version));
+        }
+        if let Some(value) = object.get("offset") {
+            this.offset = JsonValue::from_json(value, "ListOffsetsPartitionResponse.offset")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("ListOffsetsPartitionResponse", "offset", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "ListOffsetsPartitionResponse.leaderEpoch")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("ListOffsetsPartitionResponse", "leaderEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ListOffsetsPartitionResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-8
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsPartitionResponseView {
+    /// The partition index.
+    /// 
+    /// Supported API versions: 0-8
+    pub partition_index: i32,
+
+    /// The partition error code, or 0 if there was no error.
+    /// 
+    /// Supported API versions: 0-8
+    pub error_code: i16,
+
+    /// The result offsets.
+    /// 
+    /// Supported API versions: 0
+    pub old_style_offsets: ArrayView<i64>,
+
+    /// The timestamp associated with the returned offset.
+    /// 
+    /// Supported API versions: 1-8
+    pub timestamp: i64,
+
+    /// The returned offset.
+    /// 
+    /// Supported API versions: 1-8
+    pub offset: i64,
+
+    /// 
+    /// 
+    /// Supported API versions: 4-8
+    pub leader_epoch: i32,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ListOffsetsPartitionResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "partition_index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "error_code", version, start - buf.remaining()))?;
+        let old_style_offsets = if version == 0 {
+            ArrayView::decode(buf, version, false, |buf, _| types::Int64.decode(buf), |buf, _| view::skip_fixed(buf, 8)).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "old_style_offsets", version, start - buf.remaining()))?
+        } else {
+            Default::default()
+        };
+        let timestamp = if version >= 1 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "timestamp", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let offset = if version >= 1 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "offset", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let leader_epoch = if version >= 4 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "leader_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 6 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            partition_index,
+            error_code,
+            old_style_offsets,
+            timestamp,
+            offset,
+            leader_epoch,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "partition_index", version, start - buf.remaining()))?;
+        view::skip_fixed(buf, 2).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "error_code", version, start - buf.remaining()))?;
+        if version == 0 {
+            view::skip_array(buf, version, false, |buf, _| view::skip_fixed(buf, 8)).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "old_style_offsets", version, start - buf.remaining()))?;
+        }
+        if version >= 1 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "timestamp", version, start - buf.remaining()))?;
+        }
+        if version >= 1 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "offset", version, start - buf.remaining()))?;
+        }
+        if version >= 4 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "leader_epoch", version, start - buf.remaining()))?;
+        }
+        if version >= 6 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("ListOffsetsPartitionResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ListOffsetsPartitionResponseView {
+    fn default() -> Self {
+        Self {
+            partition_index: 0,
+            error_code: 0,
+            old_style_offsets: Default::default(),
+            timestamp: -1,
+            offset: -1,
+            leader_epoch: -1,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ListOffsetsTopicResponse {
     /// The topic name
@@ -235,7 +491,7 @@ pub struct ListOffsetsTopicResponse {
     pub partitions: Vec<ListOffsetsPartitionResponse>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ListOffsetsTopicResponse {
@@ -248,49 +504,53 @@ impl Builder for ListOffsetsTopicResponse {
 
 impl Encodable for ListOffsetsTopicResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 6 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version))?;
         } else {
-            types::String.encode(buf, &self.name)?;
+            types::String.encode(buf, &self.name).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version))?;
         }
         if version >= 6 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 6 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.name)?;
+            total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -298,24 +558,24 @@ impl Encodable for ListOffsetsTopicResponse {
 
 impl Decodable for ListOffsetsTopicResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 6 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version, start - buf.remaining()))?
         };
         let partitions = if version >= 6 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ListOffsetsTopicResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -332,7 +592,7 @@ impl Default for ListOffsetsTopicResponse {
         Self {
             name: Default::default(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -341,9 +601,151 @@ impl Message for ListOffsetsTopicResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl ListOffsetsTopicResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ListOffsetsTopicResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ListOffsetsTopicResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "ListOffsetsTopicResponse.name")?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsTopicResponse", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "ListOffsetsTopicResponse.partitions")?.iter().map(|item| ListOffsetsPartitionResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsTopicResponse", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ListOffsetsTopicResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-8
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsTopicResponseView {
+    /// The topic name
+    /// 
+    /// Supported API versions: 0-8
+    pub name: super::TopicName,
+
+    /// Each partition in the response.
+    /// 
+    /// Supported API versions: 0-8
+    pub partitions: ArrayView<ListOffsetsPartitionResponseView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ListOffsetsTopicResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let name = if version >= 6 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version, start - buf.remaining()))?
+        };
+        let partitions = if version >= 6 {
+            ArrayView::decode(buf, version, true, ListOffsetsPartitionResponseView::decode, ListOffsetsPartitionResponseView::skip).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, ListOffsetsPartitionResponseView::decode, ListOffsetsPartitionResponseView::skip).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 6 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsTopicResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ListOffsetsTopicResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            name,
+            partitions,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 6 {
+            view::skip_string(buf, true).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version, start - buf.remaining()))?;
+        } else {
+            view::skip_string(buf, false).map_err(|e| e.with_field("ListOffsetsTopicResponse", "name", version, start - buf.remaining()))?;
+        }
+        if version >= 6 {
+            view::skip_array(buf, version, true, ListOffsetsPartitionResponseView::skip).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, ListOffsetsPartitionResponseView::skip).map_err(|e| e.with_field("ListOffsetsTopicResponse", "partitions", version, start - buf.remaining()))?;
+        }
+        if version >= 6 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("ListOffsetsTopicResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ListOffsetsTopicResponseView {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            partitions: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ListOffsetsResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -357,7 +759,7 @@ pub struct ListOffsetsResponse {
     pub topics: Vec<ListOffsetsTopicResponse>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ListOffsetsResponse {
@@ -370,45 +772,55 @@ impl Builder for ListOffsetsResponse {
 
 impl Encodable for ListOffsetsResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListOffsetsResponse", version));
+        }
         if version >= 2 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("ListOffsetsResponse", "throttle_time_ms", version))?;
         }
         if version >= 6 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListOffsetsResponse", version));
+        }
         let mut total_size = 0;
         if version >= 2 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("ListOffsetsResponse", "throttle_time_ms", version))?;
         }
         if version >= 6 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -416,24 +828,27 @@ impl Encodable for ListOffsetsResponse {
 
 impl Decodable for ListOffsetsResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ListOffsetsResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 2 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
         let topics = if version >= 6 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -450,7 +865,7 @@ impl Default for ListOffsetsResponse {
         Self {
             throttle_time_ms: 0,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -459,6 +874,179 @@ impl Message for ListOffsetsResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl ListOffsetsResponse {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = ListOffsetsTopicResponse>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListOffsetsResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 2 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("ListOffsetsResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version))?;
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListOffsetsResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListOffsetsResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl ListOffsetsResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.throttle_time_ms = 0;
+        }
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ListOffsetsResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 2 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ListOffsetsResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "ListOffsetsResponse.throttleTimeMs")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("ListOffsetsResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "ListOffsetsResponse.topics")?.iter().map(|item| ListOffsetsTopicResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ListOffsetsResponse", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ListOffsetsResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-8
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOffsetsResponseView {
+    /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
+    /// 
+    /// Supported API versions: 2-8
+    pub throttle_time_ms: i32,
+
+    /// Each topic in the response.
+    /// 
+    /// Supported API versions: 0-8
+    pub topics: ArrayView<ListOffsetsTopicResponseView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ListOffsetsResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        if !ListOffsetsResponse::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ListOffsetsResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = if version >= 2 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("ListOffsetsResponse", "throttle_time_ms", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let topics = if version >= 6 {
+            ArrayView::decode(buf, version, true, ListOffsetsTopicResponseView::decode, ListOffsetsTopicResponseView::skip).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, ListOffsetsTopicResponseView::decode, ListOffsetsTopicResponseView::skip).map_err(|e| e.with_field("ListOffsetsResponse", "topics", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 6 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListOffsetsResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ListOffsetsResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            throttle_time_ms,
+            topics,
+            tagged_fields,
+        })
+    }
+}
+
+impl Default for ListOffsetsResponseView {
+    fn default() -> Self {
+        Self {
+            throttle_time_ms: 0,
+            topics: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 impl HeaderVersion for ListOffsetsResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 6 {
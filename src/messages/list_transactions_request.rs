@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ListTransactionsRequest {
     /// The transaction states to filter by: if empty, all transactions are returned; if non-empty, then only transactions matching one of the filtered states will be returned
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub state_filters: Vec<StrBytes>,
 
     /// The producerIds to filter by: if empty, all transactions will be returned; if non-empty, only transactions which match one of the filtered producerIds will be returned
@@ -33,7 +40,7 @@ pub struct ListTransactionsRequest {
     pub producer_id_filters: Vec<super::ProducerId>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ListTransactionsRequest {
@@ -46,45 +53,58 @@ impl Builder for ListTransactionsRequest {
 
 impl Encodable for ListTransactionsRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactArray(types::CompactString).encode(buf, &self.state_filters)?;
-        types::CompactArray(types::Int64).encode(buf, &self.producer_id_filters)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListTransactionsRequest", version));
+        }
+        types::CompactArray(types::CompactString).encode(buf, &self.state_filters).map_err(|e| e.with_field("ListTransactionsRequest", "state_filters", version))?;
+        types::CompactArray(types::Int64).encode(buf, &self.producer_id_filters).map_err(|e| e.with_field("ListTransactionsRequest", "producer_id_filters", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListTransactionsRequest", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListTransactionsRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::CompactArray(types::CompactString).compute_size(&self.state_filters)?;
-        total_size += types::CompactArray(types::Int64).compute_size(&self.producer_id_filters)?;
+        total_size += types::CompactArray(types::CompactString).compute_size(&self.state_filters).map_err(|e| e.with_field("ListTransactionsRequest", "state_filters", version))?;
+        total_size += types::CompactArray(types::Int64).compute_size(&self.producer_id_filters).map_err(|e| e.with_field("ListTransactionsRequest", "producer_id_filters", version))?;
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListTransactionsRequest", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for ListTransactionsRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let state_filters = types::CompactArray(types::CompactString).decode(buf)?;
-        let producer_id_filters = types::CompactArray(types::Int64).decode(buf)?;
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ListTransactionsRequest", version));
+        }
+        let start = buf.remaining();
+        let state_filters = types::CompactArray(types::CompactString).decode(buf).map_err(|e| e.with_field("ListTransactionsRequest", "state_filters", version, start - buf.remaining()))?;
+        let producer_id_filters = types::CompactArray(types::Int64).decode(buf).map_err(|e| e.with_field("ListTransactionsRequest", "producer_id_filters", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -100,7 +120,7 @@ impl Default for ListTransactionsRequest {
         Self {
             state_filters: Default::default(),
             producer_id_filters: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -109,9 +129,140 @@ impl Message for ListTransactionsRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
 }
 
+impl ListTransactionsRequest {
+    /// Encode the message with the elements of `state_filters` produced by an iterator of known length, in place of
+    /// the `state_filters` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_state_filters<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, state_filters: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = StrBytes>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListTransactionsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let state_filters = state_filters.into_iter();
+        types::CompactArray(types::CompactString).encode(buf, types::Streamed(state_filters)).map_err(|e| e.with_field("ListTransactionsRequest", "state_filters", version))?;
+        types::CompactArray(types::Int64).encode(buf, &self.producer_id_filters).map_err(|e| e.with_field("ListTransactionsRequest", "producer_id_filters", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListTransactionsRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `producer_id_filters` produced by an iterator of known length, in place of
+    /// the `producer_id_filters` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_producer_id_filters<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, producer_id_filters: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = super::ProducerId>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ListTransactionsRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let producer_id_filters = producer_id_filters.into_iter();
+        types::CompactArray(types::CompactString).encode(buf, &self.state_filters).map_err(|e| e.with_field("ListTransactionsRequest", "state_filters", version))?;
+        types::CompactArray(types::Int64).encode(buf, types::Streamed(producer_id_filters)).map_err(|e| e.with_field("ListTransactionsRequest", "producer_id_filters", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ListTransactionsRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ListTransactionsRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl ListTransactionsRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl ListTransactionsRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("stateFilters".into(), JsonValue::to_json(&self.state_filters));
+        node.insert("producerIdFilters".into(), JsonValue::to_json(&self.producer_id_filters));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ListTransactionsRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("stateFilters") {
+            this.state_filters = JsonValue::from_json(value, "ListTransactionsRequest.stateFilters")?;
+        } else {
+            return Err(crate::json::missing("ListTransactionsRequest", "stateFilters", version));
+        }
+        if let Some(value) = object.get("producerIdFilters") {
+            this.producer_id_filters = JsonValue::from_json(value, "ListTransactionsRequest.producerIdFilters")?;
+        } else {
+            return Err(crate::json::missing("ListTransactionsRequest", "producerIdFilters", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for ListTransactionsRequest {
     fn header_version(version: i16) -> i16 {
         2
     }
 }
 
+impl ListTransactionsRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::ListTransactionsResponse {
+        let mut response = super::ListTransactionsResponse::default();
+        response.error_code = error.code();
+        response
+    }
+}
+
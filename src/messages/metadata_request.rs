@@ -5,21 +5,29 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+use bytes::Buf;
+use crate::protocol::view::{self, ArrayView};
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-12
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct MetadataRequestTopic {
     /// The topic id.
@@ -30,10 +38,11 @@ pub struct MetadataRequestTopic {
     /// The topic name.
     /// 
     /// Supported API versions: 0-12
+    /// May be `None` in versions: 0-12
     pub name: Option<super::TopicName>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for MetadataRequestTopic {
@@ -46,45 +55,49 @@ impl Builder for MetadataRequestTopic {
 
 impl Encodable for MetadataRequestTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 10 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("MetadataRequestTopic", "topic_id", version))?;
         }
         if version >= 9 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("MetadataRequestTopic", "name", version))?;
         } else {
-            types::String.encode(buf, &self.name)?;
+            types::String.encode(buf, &self.name).map_err(|e| e.with_field("MetadataRequestTopic", "name", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataRequestTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataRequestTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataRequestTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 10 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("MetadataRequestTopic", "topic_id", version))?;
         }
         if version >= 9 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("MetadataRequestTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.name)?;
+            total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("MetadataRequestTopic", "name", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataRequestTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("MetadataRequestTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataRequestTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -92,24 +105,24 @@ impl Encodable for MetadataRequestTopic {
 
 impl Decodable for MetadataRequestTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_id = if version >= 10 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
         let name = if version >= 9 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "name", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("MetadataRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -126,7 +139,7 @@ impl Default for MetadataRequestTopic {
         Self {
             topic_id: Uuid::nil(),
             name: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -135,14 +148,159 @@ impl Message for MetadataRequestTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 12 };
 }
 
+impl MetadataRequestTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 10 {
+            self.topic_id = Uuid::nil();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 10 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MetadataRequestTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 10 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "MetadataRequestTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "MetadataRequestTopic.topicId")?;
+        } else if version >= 10 {
+            return Err(crate::json::missing("MetadataRequestTopic", "topicId", version));
+        }
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "MetadataRequestTopic.name")?;
+        } else {
+            return Err(crate::json::missing("MetadataRequestTopic", "name", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`MetadataRequestTopic`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-12
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataRequestTopicView {
+    /// The topic id.
+    /// 
+    /// Supported API versions: 10-12
+    pub topic_id: Uuid,
+
+    /// The topic name.
+    /// 
+    /// Supported API versions: 0-12
+    pub name: Option<super::TopicName>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl MetadataRequestTopicView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let topic_id = if version >= 10 {
+            types::Uuid.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "topic_id", version, start - buf.remaining()))?
+        } else {
+            Uuid::nil()
+        };
+        let name = if version >= 9 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "name", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataRequestTopic", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("MetadataRequestTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            topic_id,
+            name,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 10 {
+            view::skip_fixed(buf, 16).map_err(|e| e.with_field("MetadataRequestTopic", "topic_id", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_string(buf, true).map_err(|e| e.with_field("MetadataRequestTopic", "name", version, start - buf.remaining()))?;
+        } else {
+            view::skip_string(buf, false).map_err(|e| e.with_field("MetadataRequestTopic", "name", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("MetadataRequestTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for MetadataRequestTopicView {
+    fn default() -> Self {
+        Self {
+            topic_id: Uuid::nil(),
+            name: Some(Default::default()),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-12
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct MetadataRequest {
     /// The topics to fetch metadata for.
     /// 
     /// Supported API versions: 0-12
+    /// May be `None` in versions: 0-12
     pub topics: Option<Vec<MetadataRequestTopic>>,
 
     /// If this is true, the broker may auto-create topics that we requested which do not already exist, if it is configured to do so.
@@ -161,7 +319,7 @@ pub struct MetadataRequest {
     pub include_topic_authorized_operations: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for MetadataRequest {
@@ -174,81 +332,91 @@ impl Builder for MetadataRequest {
 
 impl Encodable for MetadataRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("MetadataRequest", version));
+        }
         if version >= 9 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("MetadataRequest", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("MetadataRequest", "topics", version))?;
         }
         if version >= 4 {
-            types::Boolean.encode(buf, &self.allow_auto_topic_creation)?;
+            types::Boolean.encode(buf, &self.allow_auto_topic_creation).map_err(|e| e.with_field("MetadataRequest", "allow_auto_topic_creation", version))?;
         } else {
             if !self.allow_auto_topic_creation {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "allow_auto_topic_creation", version));
             }
         }
         if version >= 8 && version <= 10 {
-            types::Boolean.encode(buf, &self.include_cluster_authorized_operations)?;
+            types::Boolean.encode(buf, &self.include_cluster_authorized_operations).map_err(|e| e.with_field("MetadataRequest", "include_cluster_authorized_operations", version))?;
         } else {
             if self.include_cluster_authorized_operations {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "include_cluster_authorized_operations", version));
             }
         }
         if version >= 8 {
-            types::Boolean.encode(buf, &self.include_topic_authorized_operations)?;
+            types::Boolean.encode(buf, &self.include_topic_authorized_operations).map_err(|e| e.with_field("MetadataRequest", "include_topic_authorized_operations", version))?;
         } else {
             if self.include_topic_authorized_operations {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "include_topic_authorized_operations", version));
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("MetadataRequest", version));
+        }
         let mut total_size = 0;
         if version >= 9 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("MetadataRequest", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("MetadataRequest", "topics", version))?;
         }
         if version >= 4 {
-            total_size += types::Boolean.compute_size(&self.allow_auto_topic_creation)?;
+            total_size += types::Boolean.compute_size(&self.allow_auto_topic_creation).map_err(|e| e.with_field("MetadataRequest", "allow_auto_topic_creation", version))?;
         } else {
             if !self.allow_auto_topic_creation {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "allow_auto_topic_creation", version));
             }
         }
         if version >= 8 && version <= 10 {
-            total_size += types::Boolean.compute_size(&self.include_cluster_authorized_operations)?;
+            total_size += types::Boolean.compute_size(&self.include_cluster_authorized_operations).map_err(|e| e.with_field("MetadataRequest", "include_cluster_authorized_operations", version))?;
         } else {
             if self.include_cluster_authorized_operations {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "include_cluster_authorized_operations", version));
             }
         }
         if version >= 8 {
-            total_size += types::Boolean.compute_size(&self.include_topic_authorized_operations)?;
+            total_size += types::Boolean.compute_size(&self.include_topic_authorized_operations).map_err(|e| e.with_field("MetadataRequest", "include_topic_authorized_operations", version))?;
         } else {
             if self.include_topic_authorized_operations {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "include_topic_authorized_operations", version));
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -256,34 +424,37 @@ impl Encodable for MetadataRequest {
 
 impl Decodable for MetadataRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("MetadataRequest", version));
+        }
+        let start = buf.remaining();
         let topics = if version >= 9 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("MetadataRequest", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("MetadataRequest", "topics", version, start - buf.remaining()))?
         };
         let allow_auto_topic_creation = if version >= 4 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("MetadataRequest", "allow_auto_topic_creation", version, start - buf.remaining()))?
         } else {
             true
         };
         let include_cluster_authorized_operations = if version >= 8 && version <= 10 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("MetadataRequest", "include_cluster_authorized_operations", version, start - buf.remaining()))?
         } else {
             false
         };
         let include_topic_authorized_operations = if version >= 8 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("MetadataRequest", "include_topic_authorized_operations", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -304,7 +475,7 @@ impl Default for MetadataRequest {
             allow_auto_topic_creation: true,
             include_cluster_authorized_operations: false,
             include_topic_authorized_operations: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -313,6 +484,257 @@ impl Message for MetadataRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 12 };
 }
 
+impl MetadataRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = MetadataRequestTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("MetadataRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 9 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("MetadataRequest", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("MetadataRequest", "topics", version))?;
+        }
+        if version >= 4 {
+            types::Boolean.encode(buf, &self.allow_auto_topic_creation).map_err(|e| e.with_field("MetadataRequest", "allow_auto_topic_creation", version))?;
+        } else {
+            if !self.allow_auto_topic_creation {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "allow_auto_topic_creation", version));
+            }
+        }
+        if version >= 8 && version <= 10 {
+            types::Boolean.encode(buf, &self.include_cluster_authorized_operations).map_err(|e| e.with_field("MetadataRequest", "include_cluster_authorized_operations", version))?;
+        } else {
+            if self.include_cluster_authorized_operations {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "include_cluster_authorized_operations", version));
+            }
+        }
+        if version >= 8 {
+            types::Boolean.encode(buf, &self.include_topic_authorized_operations).map_err(|e| e.with_field("MetadataRequest", "include_topic_authorized_operations", version))?;
+        } else {
+            if self.include_topic_authorized_operations {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataRequest", "include_topic_authorized_operations", version));
+            }
+        }
+        if version >= 9 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl MetadataRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if let Some(items) = &mut self.topics {
+            for item in items.iter_mut() {
+                item.normalize_to(version);
+            }
+        }
+        if version < 4 {
+            self.allow_auto_topic_creation = true;
+        }
+        if version < 8 || version > 10 {
+            self.include_cluster_authorized_operations = false;
+        }
+        if version < 8 {
+            self.include_topic_authorized_operations = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if let Some(items) = &self.topics {
+            for (i, item) in items.iter().enumerate() {
+                item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+            }
+        }
+        if version < 4 {
+            if !self.allow_auto_topic_creation {
+                invalid.push(format!("{}allow_auto_topic_creation", path));
+            }
+        }
+        if version < 8 || version > 10 {
+            if self.include_cluster_authorized_operations {
+                invalid.push(format!("{}include_cluster_authorized_operations", path));
+            }
+        }
+        if version < 8 {
+            if self.include_topic_authorized_operations {
+                invalid.push(format!("{}include_topic_authorized_operations", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MetadataRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topics".into(), self.topics.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        if version >= 4 {
+            node.insert("allowAutoTopicCreation".into(), JsonValue::to_json(&self.allow_auto_topic_creation));
+        }
+        if version >= 8 && version <= 10 {
+            node.insert("includeClusterAuthorizedOperations".into(), JsonValue::to_json(&self.include_cluster_authorized_operations));
+        }
+        if version >= 8 {
+            node.insert("includeTopicAuthorizedOperations".into(), JsonValue::to_json(&self.include_topic_authorized_operations));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "MetadataRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topics") {
+            this.topics = if value.is_null() { None } else { Some(crate::json::array(value, "MetadataRequest.topics")?.iter().map(|item| MetadataRequestTopic::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else {
+            return Err(crate::json::missing("MetadataRequest", "topics", version));
+        }
+        if let Some(value) = object.get("allowAutoTopicCreation") {
+            this.allow_auto_topic_creation = JsonValue::from_json(value, "MetadataRequest.allowAutoTopicCreation")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("MetadataRequest", "allowAutoTopicCreation", version));
+        }
+        if let Some(value) = object.get("includeClusterAuthorizedOperations") {
+            this.include_cluster_authorized_operations = JsonValue::from_json(value, "MetadataRequest.includeClusterAuthorizedOperations")?;
+        } else if version >= 8 && version <= 10 {
+            return Err(crate::json::missing("MetadataRequest", "includeClusterAuthorizedOperations", version));
+        }
+        if let Some(value) = object.get("includeTopicAuthorizedOperations") {
+            this.include_topic_authorized_operations = JsonValue::from_json(value, "MetadataRequest.includeTopicAuthorizedOperations")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("MetadataRequest", "includeTopicAuthorizedOperations", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`MetadataRequest`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-12
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataRequestView {
+    /// The topics to fetch metadata for.
+    /// 
+    /// Supported API versions: 0-12
+    pub topics: Option<ArrayView<MetadataRequestTopicView>>,
+
+    /// If this is true, the broker may auto-create topics that we requested which do not already exist, if it is configured to do so.
+    /// 
+    /// Supported API versions: 4-12
+    pub allow_auto_topic_creation: bool,
+
+    /// Whether to include cluster authorized operations.
+    /// 
+    /// Supported API versions: 8-10
+    pub include_cluster_authorized_operations: bool,
+
+    /// Whether to include topic authorized operations.
+    /// 
+    /// Supported API versions: 8-12
+    pub include_topic_authorized_operations: bool,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl MetadataRequestView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        if !MetadataRequest::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("MetadataRequest", version));
+        }
+        let start = buf.remaining();
+        let topics = if version >= 9 {
+            ArrayView::decode_nullable(buf, version, true, MetadataRequestTopicView::decode, MetadataRequestTopicView::skip).map_err(|e| e.with_field("MetadataRequest", "topics", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode_nullable(buf, version, false, MetadataRequestTopicView::decode, MetadataRequestTopicView::skip).map_err(|e| e.with_field("MetadataRequest", "topics", version, start - buf.remaining()))?
+        };
+        let allow_auto_topic_creation = if version >= 4 {
+            types::Boolean.decode(buf).map_err(|e| e.with_field("MetadataRequest", "allow_auto_topic_creation", version, start - buf.remaining()))?
+        } else {
+            true
+        };
+        let include_cluster_authorized_operations = if version >= 8 && version <= 10 {
+            types::Boolean.decode(buf).map_err(|e| e.with_field("MetadataRequest", "include_cluster_authorized_operations", version, start - buf.remaining()))?
+        } else {
+            false
+        };
+        let include_topic_authorized_operations = if version >= 8 {
+            types::Boolean.decode(buf).map_err(|e| e.with_field("MetadataRequest", "include_topic_authorized_operations", version, start - buf.remaining()))?
+        } else {
+            false
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataRequest", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("MetadataRequest", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            topics,
+            allow_auto_topic_creation,
+            include_cluster_authorized_operations,
+            include_topic_authorized_operations,
+            tagged_fields,
+        })
+    }
+}
+
+impl Default for MetadataRequestView {
+    fn default() -> Self {
+        Self {
+            topics: Some(Default::default()),
+            allow_auto_topic_creation: true,
+            include_cluster_authorized_operations: false,
+            include_topic_authorized_operations: false,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 impl HeaderVersion for MetadataRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 9 {
@@ -323,3 +745,25 @@ impl HeaderVersion for MetadataRequest {
     }
 }
 
+impl MetadataRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::MetadataResponse {
+        let mut response = super::MetadataResponse::default();
+        let mut items0 = indexmap::IndexMap::new();
+        for request0 in self.topics.iter().flatten() {
+            let mut response0 = super::metadata_response::MetadataResponseTopic::default();
+            response0.error_code = error.code();
+            if version >= 10 {
+                response0.topic_id = request0.topic_id;
+            }
+            items0.insert(request0.name.clone().unwrap_or_default(), response0);
+        }
+        response.topics = items0;
+        response
+    }
+}
+
@@ -5,26 +5,35 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+use bytes::Buf;
+use crate::protocol::view::{self, ArrayView};
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-12
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct MetadataResponseBroker {
     /// The broker hostname.
     /// 
     /// Supported API versions: 0-12
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The broker port.
@@ -35,10 +44,12 @@ pub struct MetadataResponseBroker {
     /// The rack of the broker, or null if it has not been assigned to a rack.
     /// 
     /// Supported API versions: 1-12
+    /// May be `None` in versions: 1-12
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub rack: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for MetadataResponseBroker {
@@ -52,57 +63,61 @@ impl Builder for MetadataResponseBroker {
 impl MapEncodable for MetadataResponseBroker {
     type Key = super::BrokerId;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, key)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, key).map_err(|e| e.with_field("MetadataResponseBroker", "node_id", version))?;
         if version >= 9 {
-            types::CompactString.encode(buf, &self.host)?;
+            types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("MetadataResponseBroker", "host", version))?;
         } else {
-            types::String.encode(buf, &self.host)?;
+            types::String.encode(buf, &self.host).map_err(|e| e.with_field("MetadataResponseBroker", "host", version))?;
         }
-        types::Int32.encode(buf, &self.port)?;
+        types::Int32.encode(buf, &self.port).map_err(|e| e.with_field("MetadataResponseBroker", "port", version))?;
         if version >= 1 {
             if version >= 9 {
-                types::CompactString.encode(buf, &self.rack)?;
+                types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version))?;
             } else {
-                types::String.encode(buf, &self.rack)?;
+                types::String.encode(buf, &self.rack).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version))?;
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponseBroker", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponseBroker", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponseBroker", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(key)?;
+        total_size += types::Int32.compute_size(key).map_err(|e| e.with_field("MetadataResponseBroker", "node_id", version))?;
         if version >= 9 {
-            total_size += types::CompactString.compute_size(&self.host)?;
+            total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("MetadataResponseBroker", "host", version))?;
         } else {
-            total_size += types::String.compute_size(&self.host)?;
+            total_size += types::String.compute_size(&self.host).map_err(|e| e.with_field("MetadataResponseBroker", "host", version))?;
         }
-        total_size += types::Int32.compute_size(&self.port)?;
+        total_size += types::Int32.compute_size(&self.port).map_err(|e| e.with_field("MetadataResponseBroker", "port", version))?;
         if version >= 1 {
             if version >= 9 {
-                total_size += types::CompactString.compute_size(&self.rack)?;
+                total_size += types::CompactString.compute_size(&self.rack).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version))?;
             } else {
-                total_size += types::String.compute_size(&self.rack)?;
+                total_size += types::String.compute_size(&self.rack).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version))?;
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponseBroker", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponseBroker", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponseBroker", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -111,30 +126,30 @@ impl MapEncodable for MetadataResponseBroker {
 impl MapDecodable for MetadataResponseBroker {
     type Key = super::BrokerId;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let key_field = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "node_id", version, start - buf.remaining()))?;
         let host = if version >= 9 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "host", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "host", version, start - buf.remaining()))?
         };
-        let port = types::Int32.decode(buf)?;
+        let port = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "port", version, start - buf.remaining()))?;
         let rack = if version >= 1 {
             if version >= 9 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version, start - buf.remaining()))?
             }
         } else {
             None
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("MetadataResponseBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -153,7 +168,7 @@ impl Default for MetadataResponseBroker {
             host: Default::default(),
             port: 0,
             rack: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -162,9 +177,215 @@ impl Message for MetadataResponseBroker {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 12 };
 }
 
+impl MetadataResponseBroker {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.rack = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if !self.rack.is_none() {
+                invalid.push(format!("{}rack", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MetadataResponseBroker {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::BrokerId, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::BrokerId, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("nodeId".into(), JsonValue::to_json(key));
+        node.insert("host".into(), JsonValue::to_json(&self.host));
+        node.insert("port".into(), JsonValue::to_json(&self.port));
+        if version >= 1 {
+            node.insert("rack".into(), JsonValue::to_json(&self.rack));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::BrokerId, Self), JsonError> {
+        let object = crate::json::object(node, "MetadataResponseBroker")?;
+        let mut this = Self::default();
+        let mut key = super::BrokerId::default();
+        if let Some(value) = object.get("nodeId") {
+            key = JsonValue::from_json(value, "MetadataResponseBroker.nodeId")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponseBroker", "nodeId", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "MetadataResponseBroker.host")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponseBroker", "host", version));
+        }
+        if let Some(value) = object.get("port") {
+            this.port = JsonValue::from_json(value, "MetadataResponseBroker.port")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponseBroker", "port", version));
+        }
+        if let Some(value) = object.get("rack") {
+            this.rack = JsonValue::from_json(value, "MetadataResponseBroker.rack")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("MetadataResponseBroker", "rack", version));
+        }
+        Ok((key, this))
+    }
+}
+
+/// A view of [`MetadataResponseBroker`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-12
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataResponseBrokerView {
+    /// The broker ID.
+    /// 
+    /// Supported API versions: 0-12
+    pub node_id: super::BrokerId,
+
+    /// The broker hostname.
+    /// 
+    /// Supported API versions: 0-12
+    pub host: StrBytes,
+
+    /// The broker port.
+    /// 
+    /// Supported API versions: 0-12
+    pub port: i32,
+
+    /// The rack of the broker, or null if it has not been assigned to a rack.
+    /// 
+    /// Supported API versions: 1-12
+    pub rack: Option<StrBytes>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl MetadataResponseBrokerView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let key_field = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "node_id", version, start - buf.remaining()))?;
+        let host = if version >= 9 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "host", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "host", version, start - buf.remaining()))?
+        };
+        let port = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "port", version, start - buf.remaining()))?;
+        let rack = if version >= 1 {
+            if version >= 9 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version, start - buf.remaining()))?
+            }
+        } else {
+            None
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("MetadataResponseBroker", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            node_id: key_field,
+            host,
+            port,
+            rack,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("MetadataResponseBroker", "node_id", version, start - buf.remaining()))?;
+        if version >= 9 {
+            view::skip_string(buf, true).map_err(|e| e.with_field("MetadataResponseBroker", "host", version, start - buf.remaining()))?;
+        } else {
+            view::skip_string(buf, false).map_err(|e| e.with_field("MetadataResponseBroker", "host", version, start - buf.remaining()))?;
+        }
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("MetadataResponseBroker", "port", version, start - buf.remaining()))?;
+        if version >= 1 {
+            if version >= 9 {
+                view::skip_string(buf, true).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version, start - buf.remaining()))?;
+            } else {
+                view::skip_string(buf, false).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("MetadataResponseBroker", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the `node_id` of the struct at `version`, skipping over its other fields.
+    pub fn decode_key<B: ByteBuf>(buf: &mut B, version: i16) -> Result<super::BrokerId, DecodeError> {
+        let start = buf.remaining();
+        let key_field = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponseBroker", "node_id", version, start - buf.remaining()))?;
+        if version >= 9 {
+            view::skip_string(buf, true).map_err(|e| e.with_field("MetadataResponseBroker", "host", version, start - buf.remaining()))?;
+        } else {
+            view::skip_string(buf, false).map_err(|e| e.with_field("MetadataResponseBroker", "host", version, start - buf.remaining()))?;
+        }
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("MetadataResponseBroker", "port", version, start - buf.remaining()))?;
+        if version >= 1 {
+            if version >= 9 {
+                view::skip_string(buf, true).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version, start - buf.remaining()))?;
+            } else {
+                view::skip_string(buf, false).map_err(|e| e.with_field("MetadataResponseBroker", "rack", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("MetadataResponseBroker", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(key_field)
+    }
+}
+
+impl Default for MetadataResponseBrokerView {
+    fn default() -> Self {
+        Self {
+            node_id: (0).into(),
+            host: Default::default(),
+            port: 0,
+            rack: None,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-12
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct MetadataResponsePartition {
     /// The partition error, or 0 if there was no error.
@@ -203,7 +424,7 @@ pub struct MetadataResponsePartition {
     pub offline_replicas: Vec<super::BrokerId>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for MetadataResponsePartition {
@@ -216,75 +437,79 @@ impl Builder for MetadataResponsePartition {
 
 impl Encodable for MetadataResponsePartition {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int32.encode(buf, &self.leader_id)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("MetadataResponsePartition", "error_code", version))?;
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("MetadataResponsePartition", "partition_index", version))?;
+        types::Int32.encode(buf, &self.leader_id).map_err(|e| e.with_field("MetadataResponsePartition", "leader_id", version))?;
         if version >= 7 {
-            types::Int32.encode(buf, &self.leader_epoch)?;
+            types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("MetadataResponsePartition", "leader_epoch", version))?;
         }
         if version >= 9 {
-            types::CompactArray(types::Int32).encode(buf, &self.replica_nodes)?;
+            types::CompactArray(types::Int32).encode(buf, &self.replica_nodes).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.replica_nodes)?;
+            types::Array(types::Int32).encode(buf, &self.replica_nodes).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version))?;
         }
         if version >= 9 {
-            types::CompactArray(types::Int32).encode(buf, &self.isr_nodes)?;
+            types::CompactArray(types::Int32).encode(buf, &self.isr_nodes).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.isr_nodes)?;
+            types::Array(types::Int32).encode(buf, &self.isr_nodes).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version))?;
         }
         if version >= 5 {
             if version >= 9 {
-                types::CompactArray(types::Int32).encode(buf, &self.offline_replicas)?;
+                types::CompactArray(types::Int32).encode(buf, &self.offline_replicas).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version))?;
             } else {
-                types::Array(types::Int32).encode(buf, &self.offline_replicas)?;
+                types::Array(types::Int32).encode(buf, &self.offline_replicas).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version))?;
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponsePartition", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponsePartition", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponsePartition", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int32.compute_size(&self.leader_id)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("MetadataResponsePartition", "error_code", version))?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("MetadataResponsePartition", "partition_index", version))?;
+        total_size += types::Int32.compute_size(&self.leader_id).map_err(|e| e.with_field("MetadataResponsePartition", "leader_id", version))?;
         if version >= 7 {
-            total_size += types::Int32.compute_size(&self.leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("MetadataResponsePartition", "leader_epoch", version))?;
         }
         if version >= 9 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.replica_nodes)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.replica_nodes).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.replica_nodes)?;
+            total_size += types::Array(types::Int32).compute_size(&self.replica_nodes).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version))?;
         }
         if version >= 9 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.isr_nodes)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.isr_nodes).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.isr_nodes)?;
+            total_size += types::Array(types::Int32).compute_size(&self.isr_nodes).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version))?;
         }
         if version >= 5 {
             if version >= 9 {
-                total_size += types::CompactArray(types::Int32).compute_size(&self.offline_replicas)?;
+                total_size += types::CompactArray(types::Int32).compute_size(&self.offline_replicas).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version))?;
             } else {
-                total_size += types::Array(types::Int32).compute_size(&self.offline_replicas)?;
+                total_size += types::Array(types::Int32).compute_size(&self.offline_replicas).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version))?;
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponsePartition", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponsePartition", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponsePartition", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -292,41 +517,41 @@ impl Encodable for MetadataResponsePartition {
 
 impl Decodable for MetadataResponsePartition {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
-        let partition_index = types::Int32.decode(buf)?;
-        let leader_id = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "error_code", version, start - buf.remaining()))?;
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "partition_index", version, start - buf.remaining()))?;
+        let leader_id = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "leader_id", version, start - buf.remaining()))?;
         let leader_epoch = if version >= 7 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let replica_nodes = if version >= 9 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version, start - buf.remaining()))?
         };
         let isr_nodes = if version >= 9 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version, start - buf.remaining()))?
         };
         let offline_replicas = if version >= 5 {
             if version >= 9 {
-                types::CompactArray(types::Int32).decode(buf)?
+                types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Int32).decode(buf)?
+                types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("MetadataResponsePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -353,7 +578,7 @@ impl Default for MetadataResponsePartition {
             replica_nodes: Default::default(),
             isr_nodes: Default::default(),
             offline_replicas: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -362,9 +587,260 @@ impl Message for MetadataResponsePartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 12 };
 }
 
+impl MetadataResponsePartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 7 {
+            self.leader_epoch = -1;
+        }
+        if version < 5 {
+            self.offline_replicas = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 7 {
+            if self.leader_epoch != -1 {
+                invalid.push(format!("{}leader_epoch", path));
+            }
+        }
+        if version < 5 {
+            if !self.offline_replicas.is_empty() {
+                invalid.push(format!("{}offline_replicas", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MetadataResponsePartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("leaderId".into(), JsonValue::to_json(&self.leader_id));
+        if version >= 7 {
+            node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        }
+        node.insert("replicaNodes".into(), JsonValue::to_json(&self.replica_nodes));
+        node.insert("isrNodes".into(), JsonValue::to_json(&self.isr_nodes));
+        if version >= 5 {
+            node.insert("offlineReplicas".into(), JsonValue::to_json(&self.offline_replicas));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "MetadataResponsePartition")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "MetadataResponsePartition.errorCode")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponsePartition", "errorCode", version));
+        }
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "MetadataResponsePartition.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponsePartition", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("leaderId") {
+            this.leader_id = JsonValue::from_json(value, "MetadataResponsePartition.leaderId")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponsePartition", "leaderId", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "MetadataResponsePartition.leaderEpoch")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("MetadataResponsePartition", "leaderEpoch", version));
+        }
+        if let Some(value) = object.get("replicaNodes") {
+            this.replica_nodes = JsonValue::from_json(value, "MetadataResponsePartition.replicaNodes")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponsePartition", "replicaNodes", version));
+        }
+        if let Some(value) = object.get("isrNodes") {
+            this.isr_nodes = JsonValue::from_json(value, "MetadataResponsePartition.isrNodes")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponsePartition", "isrNodes", version));
+        }
+        if let Some(value) = object.get("offlineReplicas") {
+            this.offline_replicas = JsonValue::from_json(value, "MetadataResponsePartition.offlineReplicas")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("MetadataResponsePartition", "offlineReplicas", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`MetadataResponsePartition`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-12
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataResponsePartitionView {
+    /// The partition error, or 0 if there was no error.
+    /// 
+    /// Supported API versions: 0-12
+    pub error_code: i16,
+
+    /// The partition index.
+    /// 
+    /// Supported API versions: 0-12
+    pub partition_index: i32,
+
+    /// The ID of the leader broker.
+    /// 
+    /// Supported API versions: 0-12
+    pub leader_id: super::BrokerId,
+
+    /// The leader epoch of this partition.
+    /// 
+    /// Supported API versions: 7-12
+    pub leader_epoch: i32,
+
+    /// The set of all nodes that host this partition.
+    /// 
+    /// Supported API versions: 0-12
+    pub replica_nodes: ArrayView<super::BrokerId>,
+
+    /// The set of nodes that are in sync with the leader for this partition.
+    /// 
+    /// Supported API versions: 0-12
+    pub isr_nodes: ArrayView<super::BrokerId>,
+
+    /// The set of offline replicas of this partition.
+    /// 
+    /// Supported API versions: 5-12
+    pub offline_replicas: ArrayView<super::BrokerId>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl MetadataResponsePartitionView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "error_code", version, start - buf.remaining()))?;
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "partition_index", version, start - buf.remaining()))?;
+        let leader_id = types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "leader_id", version, start - buf.remaining()))?;
+        let leader_epoch = if version >= 7 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "leader_epoch", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let replica_nodes = if version >= 9 {
+            ArrayView::decode(buf, version, true, |buf, _| types::Int32.decode(buf), |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, |buf, _| types::Int32.decode(buf), |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version, start - buf.remaining()))?
+        };
+        let isr_nodes = if version >= 9 {
+            ArrayView::decode(buf, version, true, |buf, _| types::Int32.decode(buf), |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, |buf, _| types::Int32.decode(buf), |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version, start - buf.remaining()))?
+        };
+        let offline_replicas = if version >= 5 {
+            if version >= 9 {
+                ArrayView::decode(buf, version, true, |buf, _| types::Int32.decode(buf), |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version, start - buf.remaining()))?
+            } else {
+                ArrayView::decode(buf, version, false, |buf, _| types::Int32.decode(buf), |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version, start - buf.remaining()))?
+            }
+        } else {
+            Default::default()
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponsePartition", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("MetadataResponsePartition", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            error_code,
+            partition_index,
+            leader_id,
+            leader_epoch,
+            replica_nodes,
+            isr_nodes,
+            offline_replicas,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 2).map_err(|e| e.with_field("MetadataResponsePartition", "error_code", version, start - buf.remaining()))?;
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("MetadataResponsePartition", "partition_index", version, start - buf.remaining()))?;
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("MetadataResponsePartition", "leader_id", version, start - buf.remaining()))?;
+        if version >= 7 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("MetadataResponsePartition", "leader_epoch", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_array(buf, version, true, |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "replica_nodes", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_array(buf, version, true, |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "isr_nodes", version, start - buf.remaining()))?;
+        }
+        if version >= 5 {
+            if version >= 9 {
+                view::skip_array(buf, version, true, |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version, start - buf.remaining()))?;
+            } else {
+                view::skip_array(buf, version, false, |buf, _| view::skip_fixed(buf, 4)).map_err(|e| e.with_field("MetadataResponsePartition", "offline_replicas", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("MetadataResponsePartition", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for MetadataResponsePartitionView {
+    fn default() -> Self {
+        Self {
+            error_code: 0,
+            partition_index: 0,
+            leader_id: (0).into(),
+            leader_epoch: -1,
+            replica_nodes: Default::default(),
+            isr_nodes: Default::default(),
+            offline_replicas: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-12
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct MetadataResponseTopic {
     /// The topic error, or 0 if there was no error.
@@ -393,7 +869,7 @@ pub struct MetadataResponseTopic {
     pub topic_authorized_operations: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for MetadataResponseTopic {
@@ -407,77 +883,81 @@ impl Builder for MetadataResponseTopic {
 impl MapEncodable for MetadataResponseTopic {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("MetadataResponseTopic", "error_code", version))?;
         if version >= 9 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("MetadataResponseTopic", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("MetadataResponseTopic", "name", version))?;
         }
         if version >= 10 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("MetadataResponseTopic", "topic_id", version))?;
         }
         if version >= 1 {
-            types::Boolean.encode(buf, &self.is_internal)?;
+            types::Boolean.encode(buf, &self.is_internal).map_err(|e| e.with_field("MetadataResponseTopic", "is_internal", version))?;
         }
         if version >= 9 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version))?;
         }
         if version >= 8 {
-            types::Int32.encode(buf, &self.topic_authorized_operations)?;
+            types::Int32.encode(buf, &self.topic_authorized_operations).map_err(|e| e.with_field("MetadataResponseTopic", "topic_authorized_operations", version))?;
         } else {
             if self.topic_authorized_operations != -2147483648 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataResponseTopic", "topic_authorized_operations", version));
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponseTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponseTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponseTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("MetadataResponseTopic", "error_code", version))?;
         if version >= 9 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("MetadataResponseTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("MetadataResponseTopic", "name", version))?;
         }
         if version >= 10 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("MetadataResponseTopic", "topic_id", version))?;
         }
         if version >= 1 {
-            total_size += types::Boolean.compute_size(&self.is_internal)?;
+            total_size += types::Boolean.compute_size(&self.is_internal).map_err(|e| e.with_field("MetadataResponseTopic", "is_internal", version))?;
         }
         if version >= 9 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version))?;
         }
         if version >= 8 {
-            total_size += types::Int32.compute_size(&self.topic_authorized_operations)?;
+            total_size += types::Int32.compute_size(&self.topic_authorized_operations).map_err(|e| e.with_field("MetadataResponseTopic", "topic_authorized_operations", version))?;
         } else {
             if self.topic_authorized_operations != -2147483648 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataResponseTopic", "topic_authorized_operations", version));
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponseTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponseTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponseTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -486,40 +966,40 @@ impl MapEncodable for MetadataResponseTopic {
 impl MapDecodable for MetadataResponseTopic {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "error_code", version, start - buf.remaining()))?;
         let key_field = if version >= 9 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "name", version, start - buf.remaining()))?
         };
         let topic_id = if version >= 10 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
         let is_internal = if version >= 1 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "is_internal", version, start - buf.remaining()))?
         } else {
             false
         };
         let partitions = if version >= 9 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version, start - buf.remaining()))?
         };
         let topic_authorized_operations = if version >= 8 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "topic_authorized_operations", version, start - buf.remaining()))?
         } else {
             -2147483648
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("MetadataResponseTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -542,7 +1022,7 @@ impl Default for MetadataResponseTopic {
             is_internal: false,
             partitions: Default::default(),
             topic_authorized_operations: -2147483648,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -551,14 +1031,294 @@ impl Message for MetadataResponseTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 12 };
 }
 
-/// Valid versions: 0-12
-#[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
-#[builder(default)]
-pub struct MetadataResponse {
-    /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
-    /// 
-    /// Supported API versions: 3-12
+impl MetadataResponseTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 10 {
+            self.topic_id = Uuid::nil();
+        }
+        if version < 1 {
+            self.is_internal = false;
+        }
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+        if version < 8 {
+            self.topic_authorized_operations = -2147483648;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 10 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        if version < 1 {
+            if self.is_internal {
+                invalid.push(format!("{}is_internal", path));
+            }
+        }
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+        if version < 8 {
+            if self.topic_authorized_operations != -2147483648 {
+                invalid.push(format!("{}topic_authorized_operations", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MetadataResponseTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("name".into(), JsonValue::to_json(key));
+        if version >= 10 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        if version >= 1 {
+            node.insert("isInternal".into(), JsonValue::to_json(&self.is_internal));
+        }
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        if version >= 8 {
+            node.insert("topicAuthorizedOperations".into(), JsonValue::to_json(&self.topic_authorized_operations));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "MetadataResponseTopic")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "MetadataResponseTopic.errorCode")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponseTopic", "errorCode", version));
+        }
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "MetadataResponseTopic.name")?;
+        } else {
+            return Err(crate::json::missing("MetadataResponseTopic", "name", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "MetadataResponseTopic.topicId")?;
+        } else if version >= 10 {
+            return Err(crate::json::missing("MetadataResponseTopic", "topicId", version));
+        }
+        if let Some(value) = object.get("isInternal") {
+            this.is_internal = JsonValue::from_json(value, "MetadataResponseTopic.isInternal")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("MetadataResponseTopic", "isInternal", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "MetadataResponseTopic.partitions")?.iter().map(|item| MetadataResponsePartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("MetadataResponseTopic", "partitions", version));
+        }
+        if let Some(value) = object.get("topicAuthorizedOperations") {
+            this.topic_authorized_operations = JsonValue::from_json(value, "MetadataResponseTopic.topicAuthorizedOperations")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("MetadataResponseTopic", "topicAuthorizedOperations", version));
+        }
+        Ok((key, this))
+    }
+}
+
+/// A view of [`MetadataResponseTopic`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-12
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataResponseTopicView {
+    /// The topic error, or 0 if there was no error.
+    /// 
+    /// Supported API versions: 0-12
+    pub error_code: i16,
+
+    /// The topic name.
+    /// 
+    /// Supported API versions: 0-12
+    pub name: super::TopicName,
+
+    /// The topic id.
+    /// 
+    /// Supported API versions: 10-12
+    pub topic_id: Uuid,
+
+    /// True if the topic is internal.
+    /// 
+    /// Supported API versions: 1-12
+    pub is_internal: bool,
+
+    /// Each partition in the topic.
+    /// 
+    /// Supported API versions: 0-12
+    pub partitions: ArrayView<MetadataResponsePartitionView>,
+
+    /// 32-bit bitfield to represent authorized operations for this topic.
+    /// 
+    /// Supported API versions: 8-12
+    pub topic_authorized_operations: i32,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl MetadataResponseTopicView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "error_code", version, start - buf.remaining()))?;
+        let key_field = if version >= 9 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "name", version, start - buf.remaining()))?
+        };
+        let topic_id = if version >= 10 {
+            types::Uuid.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "topic_id", version, start - buf.remaining()))?
+        } else {
+            Uuid::nil()
+        };
+        let is_internal = if version >= 1 {
+            types::Boolean.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "is_internal", version, start - buf.remaining()))?
+        } else {
+            false
+        };
+        let partitions = if version >= 9 {
+            ArrayView::decode(buf, version, true, MetadataResponsePartitionView::decode, MetadataResponsePartitionView::skip).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, MetadataResponsePartitionView::decode, MetadataResponsePartitionView::skip).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version, start - buf.remaining()))?
+        };
+        let topic_authorized_operations = if version >= 8 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "topic_authorized_operations", version, start - buf.remaining()))?
+        } else {
+            -2147483648
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("MetadataResponseTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            error_code,
+            name: key_field,
+            topic_id,
+            is_internal,
+            partitions,
+            topic_authorized_operations,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 2).map_err(|e| e.with_field("MetadataResponseTopic", "error_code", version, start - buf.remaining()))?;
+        if version >= 9 {
+            view::skip_string(buf, true).map_err(|e| e.with_field("MetadataResponseTopic", "name", version, start - buf.remaining()))?;
+        } else {
+            view::skip_string(buf, false).map_err(|e| e.with_field("MetadataResponseTopic", "name", version, start - buf.remaining()))?;
+        }
+        if version >= 10 {
+            view::skip_fixed(buf, 16).map_err(|e| e.with_field("MetadataResponseTopic", "topic_id", version, start - buf.remaining()))?;
+        }
+        if version >= 1 {
+            view::skip_fixed(buf, 1).map_err(|e| e.with_field("MetadataResponseTopic", "is_internal", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_array(buf, version, true, MetadataResponsePartitionView::skip).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, MetadataResponsePartitionView::skip).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version, start - buf.remaining()))?;
+        }
+        if version >= 8 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("MetadataResponseTopic", "topic_authorized_operations", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("MetadataResponseTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the `name` of the struct at `version`, skipping over its other fields.
+    pub fn decode_key<B: ByteBuf>(buf: &mut B, version: i16) -> Result<super::TopicName, DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 2).map_err(|e| e.with_field("MetadataResponseTopic", "error_code", version, start - buf.remaining()))?;
+        let key_field = if version >= 9 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("MetadataResponseTopic", "name", version, start - buf.remaining()))?
+        };
+        if version >= 10 {
+            view::skip_fixed(buf, 16).map_err(|e| e.with_field("MetadataResponseTopic", "topic_id", version, start - buf.remaining()))?;
+        }
+        if version >= 1 {
+            view::skip_fixed(buf, 1).map_err(|e| e.with_field("MetadataResponseTopic", "is_internal", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_array(buf, version, true, MetadataResponsePartitionView::skip).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, MetadataResponsePartitionView::skip).map_err(|e| e.with_field("MetadataResponseTopic", "partitions", version, start - buf.remaining()))?;
+        }
+        if version >= 8 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("MetadataResponseTopic", "topic_authorized_operations", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("MetadataResponseTopic", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(key_field)
+    }
+}
+
+impl Default for MetadataResponseTopicView {
+    fn default() -> Self {
+        Self {
+            error_code: 0,
+            name: Default::default(),
+            topic_id: Uuid::nil(),
+            is_internal: false,
+            partitions: Default::default(),
+            topic_authorized_operations: -2147483648,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
+/// Valid versions: 0-12
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct MetadataResponse {
+    /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
+    /// 
+    /// Supported API versions: 3-12
     pub throttle_time_ms: i32,
 
     /// Each broker in the response.
@@ -569,6 +1329,8 @@ pub struct MetadataResponse {
     /// The cluster ID that responding broker belongs to.
     /// 
     /// Supported API versions: 2-12
+    /// May be `None` in versions: 2-12
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub cluster_id: Option<StrBytes>,
 
     /// The ID of the controller broker.
@@ -587,7 +1349,7 @@ pub struct MetadataResponse {
     pub cluster_authorized_operations: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for MetadataResponse {
@@ -600,89 +1362,99 @@ impl Builder for MetadataResponse {
 
 impl Encodable for MetadataResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("MetadataResponse", version));
+        }
         if version >= 3 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("MetadataResponse", "throttle_time_ms", version))?;
         }
         if version >= 9 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.brokers)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.brokers).map_err(|e| e.with_field("MetadataResponse", "brokers", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.brokers)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.brokers).map_err(|e| e.with_field("MetadataResponse", "brokers", version))?;
         }
         if version >= 2 {
             if version >= 9 {
-                types::CompactString.encode(buf, &self.cluster_id)?;
+                types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version))?;
             } else {
-                types::String.encode(buf, &self.cluster_id)?;
+                types::String.encode(buf, &self.cluster_id).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version))?;
             }
         }
         if version >= 1 {
-            types::Int32.encode(buf, &self.controller_id)?;
+            types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("MetadataResponse", "controller_id", version))?;
         }
         if version >= 9 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("MetadataResponse", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("MetadataResponse", "topics", version))?;
         }
         if version >= 8 && version <= 10 {
-            types::Int32.encode(buf, &self.cluster_authorized_operations)?;
+            types::Int32.encode(buf, &self.cluster_authorized_operations).map_err(|e| e.with_field("MetadataResponse", "cluster_authorized_operations", version))?;
         } else {
             if self.cluster_authorized_operations != -2147483648 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataResponse", "cluster_authorized_operations", version));
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("MetadataResponse", version));
+        }
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("MetadataResponse", "throttle_time_ms", version))?;
         }
         if version >= 9 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.brokers)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.brokers).map_err(|e| e.with_field("MetadataResponse", "brokers", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.brokers)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.brokers).map_err(|e| e.with_field("MetadataResponse", "brokers", version))?;
         }
         if version >= 2 {
             if version >= 9 {
-                total_size += types::CompactString.compute_size(&self.cluster_id)?;
+                total_size += types::CompactString.compute_size(&self.cluster_id).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.cluster_id)?;
+                total_size += types::String.compute_size(&self.cluster_id).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version))?;
             }
         }
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.controller_id)?;
+            total_size += types::Int32.compute_size(&self.controller_id).map_err(|e| e.with_field("MetadataResponse", "controller_id", version))?;
         }
         if version >= 9 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("MetadataResponse", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("MetadataResponse", "topics", version))?;
         }
         if version >= 8 && version <= 10 {
-            total_size += types::Int32.compute_size(&self.cluster_authorized_operations)?;
+            total_size += types::Int32.compute_size(&self.cluster_authorized_operations).map_err(|e| e.with_field("MetadataResponse", "cluster_authorized_operations", version))?;
         } else {
             if self.cluster_authorized_operations != -2147483648 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataResponse", "cluster_authorized_operations", version));
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -690,48 +1462,51 @@ impl Encodable for MetadataResponse {
 
 impl Decodable for MetadataResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("MetadataResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
         let brokers = if version >= 9 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("MetadataResponse", "brokers", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("MetadataResponse", "brokers", version, start - buf.remaining()))?
         };
         let cluster_id = if version >= 2 {
             if version >= 9 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version, start - buf.remaining()))?
             }
         } else {
             None
         };
         let controller_id = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponse", "controller_id", version, start - buf.remaining()))?
         } else {
             (-1).into()
         };
         let topics = if version >= 9 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("MetadataResponse", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("MetadataResponse", "topics", version, start - buf.remaining()))?
         };
         let cluster_authorized_operations = if version >= 8 && version <= 10 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponse", "cluster_authorized_operations", version, start - buf.remaining()))?
         } else {
             -2147483648
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -756,7 +1531,7 @@ impl Default for MetadataResponse {
             controller_id: (-1).into(),
             topics: Default::default(),
             cluster_authorized_operations: -2147483648,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -765,6 +1540,372 @@ impl Message for MetadataResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 12 };
 }
 
+impl MetadataResponse {
+    /// Encode the message with the elements of `brokers` produced by an iterator of known length, in place of
+    /// the `brokers` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_brokers<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, brokers: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::BrokerId, MetadataResponseBroker)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("MetadataResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let brokers = brokers.into_iter();
+        if version >= 3 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("MetadataResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 9 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(brokers)).map_err(|e| e.with_field("MetadataResponse", "brokers", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(brokers)).map_err(|e| e.with_field("MetadataResponse", "brokers", version))?;
+        }
+        if version >= 2 {
+            if version >= 9 {
+                types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version))?;
+            } else {
+                types::String.encode(buf, &self.cluster_id).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version))?;
+            }
+        }
+        if version >= 1 {
+            types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("MetadataResponse", "controller_id", version))?;
+        }
+        if version >= 9 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("MetadataResponse", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("MetadataResponse", "topics", version))?;
+        }
+        if version >= 8 && version <= 10 {
+            types::Int32.encode(buf, &self.cluster_authorized_operations).map_err(|e| e.with_field("MetadataResponse", "cluster_authorized_operations", version))?;
+        } else {
+            if self.cluster_authorized_operations != -2147483648 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataResponse", "cluster_authorized_operations", version));
+            }
+        }
+        if version >= 9 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, MetadataResponseTopic)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("MetadataResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 3 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("MetadataResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 9 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.brokers).map_err(|e| e.with_field("MetadataResponse", "brokers", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.brokers).map_err(|e| e.with_field("MetadataResponse", "brokers", version))?;
+        }
+        if version >= 2 {
+            if version >= 9 {
+                types::CompactString.encode(buf, &self.cluster_id).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version))?;
+            } else {
+                types::String.encode(buf, &self.cluster_id).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version))?;
+            }
+        }
+        if version >= 1 {
+            types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("MetadataResponse", "controller_id", version))?;
+        }
+        if version >= 9 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("MetadataResponse", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topics)).map_err(|e| e.with_field("MetadataResponse", "topics", version))?;
+        }
+        if version >= 8 && version <= 10 {
+            types::Int32.encode(buf, &self.cluster_authorized_operations).map_err(|e| e.with_field("MetadataResponse", "cluster_authorized_operations", version))?;
+        } else {
+            if self.cluster_authorized_operations != -2147483648 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("MetadataResponse", "cluster_authorized_operations", version));
+            }
+        }
+        if version >= 9 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("MetadataResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("MetadataResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl MetadataResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.throttle_time_ms = 0;
+        }
+        for item in self.brokers.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 2 {
+            self.cluster_id = None;
+        }
+        if version < 1 {
+            self.controller_id = (-1).into();
+        }
+        for item in self.topics.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 8 || version > 10 {
+            self.cluster_authorized_operations = -2147483648;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        for (i, item) in self.brokers.values().enumerate() {
+            item.validate_into(version, &format!("{}brokers[{}].", path, i), invalid);
+        }
+        if version < 2 {
+            if !self.cluster_id.is_none() {
+                invalid.push(format!("{}cluster_id", path));
+            }
+        }
+        if version < 1 {
+            if self.controller_id != -1 {
+                invalid.push(format!("{}controller_id", path));
+            }
+        }
+        for (i, item) in self.topics.values().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+        if version < 8 || version > 10 {
+            if self.cluster_authorized_operations != -2147483648 {
+                invalid.push(format!("{}cluster_authorized_operations", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl MetadataResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        node.insert("brokers".into(), serde_json::Value::Array(self.brokers.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        if version >= 2 {
+            node.insert("clusterId".into(), JsonValue::to_json(&self.cluster_id));
+        }
+        if version >= 1 {
+            node.insert("controllerId".into(), JsonValue::to_json(&self.controller_id));
+        }
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        if version >= 8 && version <= 10 {
+            node.insert("clusterAuthorizedOperations".into(), JsonValue::to_json(&self.cluster_authorized_operations));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "MetadataResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "MetadataResponse.throttleTimeMs")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("MetadataResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("brokers") {
+            this.brokers = crate::json::array(value, "MetadataResponse.brokers")?.iter().map(|item| MetadataResponseBroker::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("MetadataResponse", "brokers", version));
+        }
+        if let Some(value) = object.get("clusterId") {
+            this.cluster_id = JsonValue::from_json(value, "MetadataResponse.clusterId")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("MetadataResponse", "clusterId", version));
+        }
+        if let Some(value) = object.get("controllerId") {
+            this.controller_id = JsonValue::from_json(value, "MetadataResponse.controllerId")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("MetadataResponse", "controllerId", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "MetadataResponse.topics")?.iter().map(|item| MetadataResponseTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("MetadataResponse", "topics", version));
+        }
+        if let Some(value) = object.get("clusterAuthorizedOperations") {
+            this.cluster_authorized_operations = JsonValue::from_json(value, "MetadataResponse.clusterAuthorizedOperations")?;
+        } else if version >= 8 && version <= 10 {
+            return Err(crate::json::missing("MetadataResponse", "clusterAuthorizedOperations", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`MetadataResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-12
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataResponseView {
+    /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
+    /// 
+    /// Supported API versions: 3-12
+    pub throttle_time_ms: i32,
+
+    /// Each broker in the response.
+    /// 
+    /// Supported API versions: 0-12
+    pub brokers: ArrayView<MetadataResponseBrokerView>,
+
+    /// The cluster ID that responding broker belongs to.
+    /// 
+    /// Supported API versions: 2-12
+    pub cluster_id: Option<StrBytes>,
+
+    /// The ID of the controller broker.
+    /// 
+    /// Supported API versions: 1-12
+    pub controller_id: super::BrokerId,
+
+    /// Each topic in the response.
+    /// 
+    /// Supported API versions: 0-12
+    pub topics: ArrayView<MetadataResponseTopicView>,
+
+    /// 32-bit bitfield to represent authorized operations for this cluster.
+    /// 
+    /// Supported API versions: 8-10
+    pub cluster_authorized_operations: i32,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl MetadataResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        if !MetadataResponse::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("MetadataResponse", version));
+        }
+        let start = buf.remaining();
+        let throttle_time_ms = if version >= 3 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponse", "throttle_time_ms", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let brokers = if version >= 9 {
+            ArrayView::decode(buf, version, true, MetadataResponseBrokerView::decode, MetadataResponseBrokerView::skip).map_err(|e| e.with_field("MetadataResponse", "brokers", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, MetadataResponseBrokerView::decode, MetadataResponseBrokerView::skip).map_err(|e| e.with_field("MetadataResponse", "brokers", version, start - buf.remaining()))?
+        };
+        let cluster_id = if version >= 2 {
+            if version >= 9 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("MetadataResponse", "cluster_id", version, start - buf.remaining()))?
+            }
+        } else {
+            None
+        };
+        let controller_id = if version >= 1 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponse", "controller_id", version, start - buf.remaining()))?
+        } else {
+            (-1).into()
+        };
+        let topics = if version >= 9 {
+            ArrayView::decode(buf, version, true, MetadataResponseTopicView::decode, MetadataResponseTopicView::skip).map_err(|e| e.with_field("MetadataResponse", "topics", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, MetadataResponseTopicView::decode, MetadataResponseTopicView::skip).map_err(|e| e.with_field("MetadataResponse", "topics", version, start - buf.remaining()))?
+        };
+        let cluster_authorized_operations = if version >= 8 && version <= 10 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("MetadataResponse", "cluster_authorized_operations", version, start - buf.remaining()))?
+        } else {
+            -2147483648
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("MetadataResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("MetadataResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            throttle_time_ms,
+            brokers,
+            cluster_id,
+            controller_id,
+            topics,
+            cluster_authorized_operations,
+            tagged_fields,
+        })
+    }
+}
+
+impl Default for MetadataResponseView {
+    fn default() -> Self {
+        Self {
+            throttle_time_ms: 0,
+            brokers: Default::default(),
+            cluster_id: None,
+            controller_id: (-1).into(),
+            topics: Default::default(),
+            cluster_authorized_operations: -2147483648,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 impl HeaderVersion for MetadataResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 9 {
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetCommitRequestPartition {
     /// The partition index.
@@ -45,10 +51,12 @@ pub struct OffsetCommitRequestPartition {
     /// Any associated metadata the client wants to keep.
     /// 
     /// Supported API versions: 0-9
+    /// May be `None` in versions: 0-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub committed_metadata: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetCommitRequestPartition {
@@ -61,63 +69,67 @@ impl Builder for OffsetCommitRequestPartition {
 
 impl Encodable for OffsetCommitRequestPartition {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int64.encode(buf, &self.committed_offset)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("OffsetCommitRequestPartition", "partition_index", version))?;
+        types::Int64.encode(buf, &self.committed_offset).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_offset", version))?;
         if version >= 6 {
-            types::Int32.encode(buf, &self.committed_leader_epoch)?;
+            types::Int32.encode(buf, &self.committed_leader_epoch).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_leader_epoch", version))?;
         }
         if version == 1 {
-            types::Int64.encode(buf, &self.commit_timestamp)?;
+            types::Int64.encode(buf, &self.commit_timestamp).map_err(|e| e.with_field("OffsetCommitRequestPartition", "commit_timestamp", version))?;
         } else {
             if self.commit_timestamp != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetCommitRequestPartition", "commit_timestamp", version));
             }
         }
         if version >= 8 {
-            types::CompactString.encode(buf, &self.committed_metadata)?;
+            types::CompactString.encode(buf, &self.committed_metadata).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_metadata", version))?;
         } else {
-            types::String.encode(buf, &self.committed_metadata)?;
+            types::String.encode(buf, &self.committed_metadata).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_metadata", version))?;
         }
         if version >= 8 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int64.compute_size(&self.committed_offset)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("OffsetCommitRequestPartition", "partition_index", version))?;
+        total_size += types::Int64.compute_size(&self.committed_offset).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_offset", version))?;
         if version >= 6 {
-            total_size += types::Int32.compute_size(&self.committed_leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.committed_leader_epoch).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_leader_epoch", version))?;
         }
         if version == 1 {
-            total_size += types::Int64.compute_size(&self.commit_timestamp)?;
+            total_size += types::Int64.compute_size(&self.commit_timestamp).map_err(|e| e.with_field("OffsetCommitRequestPartition", "commit_timestamp", version))?;
         } else {
             if self.commit_timestamp != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetCommitRequestPartition", "commit_timestamp", version));
             }
         }
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.committed_metadata)?;
+            total_size += types::CompactString.compute_size(&self.committed_metadata).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_metadata", version))?;
         } else {
-            total_size += types::String.compute_size(&self.committed_metadata)?;
+            total_size += types::String.compute_size(&self.committed_metadata).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_metadata", version))?;
         }
         if version >= 8 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -125,31 +137,31 @@ impl Encodable for OffsetCommitRequestPartition {
 
 impl Decodable for OffsetCommitRequestPartition {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let committed_offset = types::Int64.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "partition_index", version, start - buf.remaining()))?;
+        let committed_offset = types::Int64.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_offset", version, start - buf.remaining()))?;
         let committed_leader_epoch = if version >= 6 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let commit_timestamp = if version == 1 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "commit_timestamp", version, start - buf.remaining()))?
         } else {
             -1
         };
         let committed_metadata = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_metadata", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "committed_metadata", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 8 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetCommitRequestPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -172,7 +184,7 @@ impl Default for OffsetCommitRequestPartition {
             committed_leader_epoch: -1,
             commit_timestamp: -1,
             committed_metadata: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -181,9 +193,107 @@ impl Message for OffsetCommitRequestPartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl OffsetCommitRequestPartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 6 {
+            self.committed_leader_epoch = -1;
+        }
+        if version != 1 {
+            self.commit_timestamp = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 6 {
+            if self.committed_leader_epoch != -1 {
+                invalid.push(format!("{}committed_leader_epoch", path));
+            }
+        }
+        if version != 1 {
+            if self.commit_timestamp != -1 {
+                invalid.push(format!("{}commit_timestamp", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetCommitRequestPartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("committedOffset".into(), JsonValue::to_json(&self.committed_offset));
+        if version >= 6 {
+            node.insert("committedLeaderEpoch".into(), JsonValue::to_json(&self.committed_leader_epoch));
+        }
+        if version == 1 {
+            node.insert("commitTimestamp".into(), JsonValue::to_json(&self.commit_timestamp));
+        }
+        node.insert("committedMetadata".into(), JsonValue::to_json(&self.committed_metadata));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetCommitRequestPartition")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "OffsetCommitRequestPartition.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitRequestPartition", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("committedOffset") {
+            this.committed_offset = JsonValue::from_json(value, "OffsetCommitRequestPartition.committedOffset")?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitRequestPartition", "committedOffset", version));
+        }
+        if let Some(value) = object.get("committedLeaderEpoch") {
+            this.committed_leader_epoch = JsonValue::from_json(value, "OffsetCommitRequestPartition.committedLeaderEpoch")?;
+        } else if version >= 6 {
+            return Err(crate::json::missing("OffsetCommitRequestPartition", "committedLeaderEpoch", version));
+        }
+        if let Some(value) = object.get("commitTimestamp") {
+            this.commit_timestamp = JsonValue::from_json(value, "OffsetCommitRequestPartition.commitTimestamp")?;
+        } else if version == 1 {
+            return Err(crate::json::missing("OffsetCommitRequestPartition", "commitTimestamp", version));
+        }
+        if let Some(value) = object.get("committedMetadata") {
+            this.committed_metadata = JsonValue::from_json(value, "OffsetCommitRequestPartition.committedMetadata")?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitRequestPartition", "committedMetadata", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetCommitRequestTopic {
     /// The topic name.
@@ -197,7 +307,7 @@ pub struct OffsetCommitRequestTopic {
     pub partitions: Vec<OffsetCommitRequestPartition>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetCommitRequestTopic {
@@ -210,49 +320,53 @@ impl Builder for OffsetCommitRequestTopic {
 
 impl Encodable for OffsetCommitRequestTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 8 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("OffsetCommitRequestTopic", "name", version))?;
         } else {
-            types::String.encode(buf, &self.name)?;
+            types::String.encode(buf, &self.name).map_err(|e| e.with_field("OffsetCommitRequestTopic", "name", version))?;
         }
         if version >= 8 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("OffsetCommitRequestTopic", "partitions", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("OffsetCommitRequestTopic", "partitions", version))?;
         }
         if version >= 8 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("OffsetCommitRequestTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.name)?;
+            total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("OffsetCommitRequestTopic", "name", version))?;
         }
         if version >= 8 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("OffsetCommitRequestTopic", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("OffsetCommitRequestTopic", "partitions", version))?;
         }
         if version >= 8 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -260,24 +374,24 @@ impl Encodable for OffsetCommitRequestTopic {
 
 impl Decodable for OffsetCommitRequestTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestTopic", "name", version, start - buf.remaining()))?
         };
         let partitions = if version >= 8 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetCommitRequestTopic", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetCommitRequestTopic", "partitions", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 8 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetCommitRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -294,7 +408,7 @@ impl Default for OffsetCommitRequestTopic {
         Self {
             name: Default::default(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -303,9 +417,75 @@ impl Message for OffsetCommitRequestTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl OffsetCommitRequestTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetCommitRequestTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetCommitRequestTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "OffsetCommitRequestTopic.name")?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitRequestTopic", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "OffsetCommitRequestTopic.partitions")?.iter().map(|item| OffsetCommitRequestPartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitRequestTopic", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetCommitRequest {
     /// The unique group identifier.
@@ -321,11 +501,14 @@ pub struct OffsetCommitRequest {
     /// The member ID assigned by the group coordinator.
     /// 
     /// Supported API versions: 1-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The unique identifier of the consumer instance provided by end user.
     /// 
     /// Supported API versions: 7-9
+    /// May be `None` in versions: 7-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_instance_id: Option<StrBytes>,
 
     /// The time period in ms to retain the offset.
@@ -339,7 +522,7 @@ pub struct OffsetCommitRequest {
     pub topics: Vec<OffsetCommitRequestTopic>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetCommitRequest {
@@ -352,97 +535,107 @@ impl Builder for OffsetCommitRequest {
 
 impl Encodable for OffsetCommitRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetCommitRequest", version));
+        }
         if version >= 8 {
-            types::CompactString.encode(buf, &self.group_id)?;
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_id", version))?;
         } else {
-            types::String.encode(buf, &self.group_id)?;
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_id", version))?;
         }
         if version >= 1 {
-            types::Int32.encode(buf, &self.generation_id_or_member_epoch)?;
+            types::Int32.encode(buf, &self.generation_id_or_member_epoch).map_err(|e| e.with_field("OffsetCommitRequest", "generation_id_or_member_epoch", version))?;
         }
         if version >= 1 {
             if version >= 8 {
-                types::CompactString.encode(buf, &self.member_id)?;
+                types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("OffsetCommitRequest", "member_id", version))?;
             } else {
-                types::String.encode(buf, &self.member_id)?;
+                types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("OffsetCommitRequest", "member_id", version))?;
             }
         }
         if version >= 7 {
             if version >= 8 {
-                types::CompactString.encode(buf, &self.group_instance_id)?;
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_instance_id", version))?;
             } else {
-                types::String.encode(buf, &self.group_instance_id)?;
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetCommitRequest", "group_instance_id", version));
             }
         }
         if version >= 2 && version <= 4 {
-            types::Int64.encode(buf, &self.retention_time_ms)?;
+            types::Int64.encode(buf, &self.retention_time_ms).map_err(|e| e.with_field("OffsetCommitRequest", "retention_time_ms", version))?;
         }
         if version >= 8 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetCommitRequest", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetCommitRequest", "topics", version))?;
         }
         if version >= 8 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetCommitRequest", version));
+        }
         let mut total_size = 0;
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.group_id)?;
+            total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.group_id)?;
+            total_size += types::String.compute_size(&self.group_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_id", version))?;
         }
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.generation_id_or_member_epoch)?;
+            total_size += types::Int32.compute_size(&self.generation_id_or_member_epoch).map_err(|e| e.with_field("OffsetCommitRequest", "generation_id_or_member_epoch", version))?;
         }
         if version >= 1 {
             if version >= 8 {
-                total_size += types::CompactString.compute_size(&self.member_id)?;
+                total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("OffsetCommitRequest", "member_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.member_id)?;
+                total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("OffsetCommitRequest", "member_id", version))?;
             }
         }
         if version >= 7 {
             if version >= 8 {
-                total_size += types::CompactString.compute_size(&self.group_instance_id)?;
+                total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_instance_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.group_instance_id)?;
+                total_size += types::String.compute_size(&self.group_instance_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetCommitRequest", "group_instance_id", version));
             }
         }
         if version >= 2 && version <= 4 {
-            total_size += types::Int64.compute_size(&self.retention_time_ms)?;
+            total_size += types::Int64.compute_size(&self.retention_time_ms).map_err(|e| e.with_field("OffsetCommitRequest", "retention_time_ms", version))?;
         }
         if version >= 8 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("OffsetCommitRequest", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("OffsetCommitRequest", "topics", version))?;
         }
         if version >= 8 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -450,52 +643,55 @@ impl Encodable for OffsetCommitRequest {
 
 impl Decodable for OffsetCommitRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("OffsetCommitRequest", version));
+        }
+        let start = buf.remaining();
         let group_id = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "group_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "group_id", version, start - buf.remaining()))?
         };
         let generation_id_or_member_epoch = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "generation_id_or_member_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let member_id = if version >= 1 {
             if version >= 8 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "member_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "member_id", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let group_instance_id = if version >= 7 {
             if version >= 8 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "group_instance_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "group_instance_id", version, start - buf.remaining()))?
             }
         } else {
             None
         };
         let retention_time_ms = if version >= 2 && version <= 4 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "retention_time_ms", version, start - buf.remaining()))?
         } else {
             -1
         };
         let topics = if version >= 8 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "topics", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 8 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -520,7 +716,7 @@ impl Default for OffsetCommitRequest {
             group_instance_id: None,
             retention_time_ms: -1,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -529,6 +725,199 @@ impl Message for OffsetCommitRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl OffsetCommitRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = OffsetCommitRequestTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetCommitRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 8 {
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_id", version))?;
+        } else {
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_id", version))?;
+        }
+        if version >= 1 {
+            types::Int32.encode(buf, &self.generation_id_or_member_epoch).map_err(|e| e.with_field("OffsetCommitRequest", "generation_id_or_member_epoch", version))?;
+        }
+        if version >= 1 {
+            if version >= 8 {
+                types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("OffsetCommitRequest", "member_id", version))?;
+            } else {
+                types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("OffsetCommitRequest", "member_id", version))?;
+            }
+        }
+        if version >= 7 {
+            if version >= 8 {
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_instance_id", version))?;
+            } else {
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("OffsetCommitRequest", "group_instance_id", version))?;
+            }
+        } else {
+            if !self.group_instance_id.is_none() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetCommitRequest", "group_instance_id", version));
+            }
+        }
+        if version >= 2 && version <= 4 {
+            types::Int64.encode(buf, &self.retention_time_ms).map_err(|e| e.with_field("OffsetCommitRequest", "retention_time_ms", version))?;
+        }
+        if version >= 8 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("OffsetCommitRequest", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("OffsetCommitRequest", "topics", version))?;
+        }
+        if version >= 8 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetCommitRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetCommitRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl OffsetCommitRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.generation_id_or_member_epoch = -1;
+        }
+        if version < 1 {
+            self.member_id = Default::default();
+        }
+        if version < 7 {
+            self.group_instance_id = None;
+        }
+        if version < 2 || version > 4 {
+            self.retention_time_ms = -1;
+        }
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.generation_id_or_member_epoch != -1 {
+                invalid.push(format!("{}generation_id_or_member_epoch", path));
+            }
+        }
+        if version < 1 {
+            if !self.member_id.is_empty() {
+                invalid.push(format!("{}member_id", path));
+            }
+        }
+        if version < 7 {
+            if !self.group_instance_id.is_none() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+        if version < 2 || version > 4 {
+            if self.retention_time_ms != -1 {
+                invalid.push(format!("{}retention_time_ms", path));
+            }
+        }
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetCommitRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        if version >= 1 {
+            node.insert("generationIdOrMemberEpoch".into(), JsonValue::to_json(&self.generation_id_or_member_epoch));
+        }
+        if version >= 1 {
+            node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        }
+        if version >= 7 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        if version >= 2 && version <= 4 {
+            node.insert("retentionTimeMs".into(), JsonValue::to_json(&self.retention_time_ms));
+        }
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetCommitRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "OffsetCommitRequest.groupId")?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitRequest", "groupId", version));
+        }
+        if let Some(value) = object.get("generationIdOrMemberEpoch") {
+            this.generation_id_or_member_epoch = JsonValue::from_json(value, "OffsetCommitRequest.generationIdOrMemberEpoch")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("OffsetCommitRequest", "generationIdOrMemberEpoch", version));
+        }
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "OffsetCommitRequest.memberId")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("OffsetCommitRequest", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "OffsetCommitRequest.groupInstanceId")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("OffsetCommitRequest", "groupInstanceId", version));
+        }
+        if let Some(value) = object.get("retentionTimeMs") {
+            this.retention_time_ms = JsonValue::from_json(value, "OffsetCommitRequest.retentionTimeMs")?;
+        } else if version >= 2 && version <= 4 {
+            return Err(crate::json::missing("OffsetCommitRequest", "retentionTimeMs", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "OffsetCommitRequest.topics")?.iter().map(|item| OffsetCommitRequestTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("OffsetCommitRequest", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for OffsetCommitRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 8 {
@@ -539,3 +928,30 @@ impl HeaderVersion for OffsetCommitRequest {
     }
 }
 
+impl OffsetCommitRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::OffsetCommitResponse {
+        let mut response = super::OffsetCommitResponse::default();
+        let mut items0 = Vec::new();
+        for request0 in &self.topics {
+            let mut response0 = super::offset_commit_response::OffsetCommitResponseTopic::default();
+            response0.name = request0.name.clone();
+            let mut items1 = Vec::new();
+            for request1 in &request0.partitions {
+                let mut response1 = super::offset_commit_response::OffsetCommitResponsePartition::default();
+                response1.partition_index = request1.partition_index;
+                response1.error_code = error.code();
+                items1.push(response1);
+            }
+            response0.partitions = items1;
+            items0.push(response0);
+        }
+        response.topics = items0;
+        response
+    }
+}
+
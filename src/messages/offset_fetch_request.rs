@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchRequestTopic {
     /// The topic name.
@@ -33,7 +39,7 @@ pub struct OffsetFetchRequestTopic {
     pub partition_indexes: Vec<i32>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchRequestTopic {
@@ -46,73 +52,77 @@ impl Builder for OffsetFetchRequestTopic {
 
 impl Encodable for OffsetFetchRequestTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 7 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.name)?;
+                types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("OffsetFetchRequestTopic", "name", version))?;
             } else {
-                types::String.encode(buf, &self.name)?;
+                types::String.encode(buf, &self.name).map_err(|e| e.with_field("OffsetFetchRequestTopic", "name", version))?;
             }
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestTopic", "name", version));
             }
         }
         if version <= 7 {
             if version >= 6 {
-                types::CompactArray(types::Int32).encode(buf, &self.partition_indexes)?;
+                types::CompactArray(types::Int32).encode(buf, &self.partition_indexes).map_err(|e| e.with_field("OffsetFetchRequestTopic", "partition_indexes", version))?;
             } else {
-                types::Array(types::Int32).encode(buf, &self.partition_indexes)?;
+                types::Array(types::Int32).encode(buf, &self.partition_indexes).map_err(|e| e.with_field("OffsetFetchRequestTopic", "partition_indexes", version))?;
             }
         } else {
             if !self.partition_indexes.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestTopic", "partition_indexes", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 7 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.name)?;
+                total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("OffsetFetchRequestTopic", "name", version))?;
             } else {
-                total_size += types::String.compute_size(&self.name)?;
+                total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("OffsetFetchRequestTopic", "name", version))?;
             }
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestTopic", "name", version));
             }
         }
         if version <= 7 {
             if version >= 6 {
-                total_size += types::CompactArray(types::Int32).compute_size(&self.partition_indexes)?;
+                total_size += types::CompactArray(types::Int32).compute_size(&self.partition_indexes).map_err(|e| e.with_field("OffsetFetchRequestTopic", "partition_indexes", version))?;
             } else {
-                total_size += types::Array(types::Int32).compute_size(&self.partition_indexes)?;
+                total_size += types::Array(types::Int32).compute_size(&self.partition_indexes).map_err(|e| e.with_field("OffsetFetchRequestTopic", "partition_indexes", version))?;
             }
         } else {
             if !self.partition_indexes.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestTopic", "partition_indexes", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -120,32 +130,32 @@ impl Encodable for OffsetFetchRequestTopic {
 
 impl Decodable for OffsetFetchRequestTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version <= 7 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopic", "name", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopic", "name", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let partition_indexes = if version <= 7 {
             if version >= 6 {
-                types::CompactArray(types::Int32).decode(buf)?
+                types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopic", "partition_indexes", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Int32).decode(buf)?
+                types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopic", "partition_indexes", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -162,7 +172,7 @@ impl Default for OffsetFetchRequestTopic {
         Self {
             name: Default::default(),
             partition_indexes: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -171,9 +181,89 @@ impl Message for OffsetFetchRequestTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchRequestTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 7 {
+            self.name = Default::default();
+        }
+        if version > 7 {
+            self.partition_indexes = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 7 {
+            if !self.name.is_empty() {
+                invalid.push(format!("{}name", path));
+            }
+        }
+        if version > 7 {
+            if !self.partition_indexes.is_empty() {
+                invalid.push(format!("{}partition_indexes", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchRequestTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 7 {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        if version <= 7 {
+            node.insert("partitionIndexes".into(), JsonValue::to_json(&self.partition_indexes));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchRequestTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "OffsetFetchRequestTopic.name")?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchRequestTopic", "name", version));
+        }
+        if let Some(value) = object.get("partitionIndexes") {
+            this.partition_indexes = JsonValue::from_json(value, "OffsetFetchRequestTopic.partitionIndexes")?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchRequestTopic", "partitionIndexes", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchRequestTopics {
     /// The topic name.
@@ -187,7 +277,7 @@ pub struct OffsetFetchRequestTopics {
     pub partition_indexes: Vec<i32>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchRequestTopics {
@@ -200,57 +290,61 @@ impl Builder for OffsetFetchRequestTopics {
 
 impl Encodable for OffsetFetchRequestTopics {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 8 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("OffsetFetchRequestTopics", "name", version))?;
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestTopics", "name", version));
             }
         }
         if version >= 8 {
-            types::CompactArray(types::Int32).encode(buf, &self.partition_indexes)?;
+            types::CompactArray(types::Int32).encode(buf, &self.partition_indexes).map_err(|e| e.with_field("OffsetFetchRequestTopics", "partition_indexes", version))?;
         } else {
             if !self.partition_indexes.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestTopics", "partition_indexes", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("OffsetFetchRequestTopics", "name", version))?;
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestTopics", "name", version));
             }
         }
         if version >= 8 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.partition_indexes)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.partition_indexes).map_err(|e| e.with_field("OffsetFetchRequestTopics", "partition_indexes", version))?;
         } else {
             if !self.partition_indexes.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestTopics", "partition_indexes", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -258,24 +352,24 @@ impl Encodable for OffsetFetchRequestTopics {
 
 impl Decodable for OffsetFetchRequestTopics {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopics", "name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let partition_indexes = if version >= 8 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopics", "partition_indexes", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchRequestTopics", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -292,7 +386,7 @@ impl Default for OffsetFetchRequestTopics {
         Self {
             name: Default::default(),
             partition_indexes: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -301,9 +395,89 @@ impl Message for OffsetFetchRequestTopics {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchRequestTopics {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 8 {
+            self.name = Default::default();
+        }
+        if version < 8 {
+            self.partition_indexes = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 8 {
+            if !self.name.is_empty() {
+                invalid.push(format!("{}name", path));
+            }
+        }
+        if version < 8 {
+            if !self.partition_indexes.is_empty() {
+                invalid.push(format!("{}partition_indexes", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchRequestTopics {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 8 {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        if version >= 8 {
+            node.insert("partitionIndexes".into(), JsonValue::to_json(&self.partition_indexes));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchRequestTopics")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "OffsetFetchRequestTopics.name")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchRequestTopics", "name", version));
+        }
+        if let Some(value) = object.get("partitionIndexes") {
+            this.partition_indexes = JsonValue::from_json(value, "OffsetFetchRequestTopics.partitionIndexes")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchRequestTopics", "partitionIndexes", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchRequestGroup {
     /// The group ID.
@@ -314,10 +488,11 @@ pub struct OffsetFetchRequestGroup {
     /// Each topic we would like to fetch offsets for, or null to fetch offsets for all topics.
     /// 
     /// Supported API versions: 8
+    /// May be `None` in versions: 8
     pub topics: Option<Vec<OffsetFetchRequestTopics>>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchRequestGroup {
@@ -330,57 +505,61 @@ impl Builder for OffsetFetchRequestGroup {
 
 impl Encodable for OffsetFetchRequestGroup {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 8 {
-            types::CompactString.encode(buf, &self.group_id)?;
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetFetchRequestGroup", "group_id", version))?;
         } else {
             if !self.group_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestGroup", "group_id", version));
             }
         }
         if version >= 8 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchRequestGroup", "topics", version))?;
         } else {
             if !self.topics.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestGroup", "topics", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.group_id)?;
+            total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("OffsetFetchRequestGroup", "group_id", version))?;
         } else {
             if !self.group_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestGroup", "group_id", version));
             }
         }
         if version >= 8 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("OffsetFetchRequestGroup", "topics", version))?;
         } else {
             if !self.topics.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequestGroup", "topics", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -388,24 +567,24 @@ impl Encodable for OffsetFetchRequestGroup {
 
 impl Decodable for OffsetFetchRequestGroup {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let group_id = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestGroup", "group_id", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topics = if version >= 8 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchRequestGroup", "topics", version, start - buf.remaining()))?
         } else {
             Some(Default::default())
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchRequestGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -422,7 +601,7 @@ impl Default for OffsetFetchRequestGroup {
         Self {
             group_id: Default::default(),
             topics: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -431,9 +610,101 @@ impl Message for OffsetFetchRequestGroup {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchRequestGroup {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 8 {
+            self.group_id = Default::default();
+        }
+        if version >= 8 {
+            if let Some(items) = &mut self.topics {
+                for item in items.iter_mut() {
+                    item.normalize_to(version);
+                }
+            }
+        } else {
+            self.topics = Some(Default::default());
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 8 {
+            if !self.group_id.is_empty() {
+                invalid.push(format!("{}group_id", path));
+            }
+        }
+        if version >= 8 {
+            if let Some(items) = &self.topics {
+                for (i, item) in items.iter().enumerate() {
+                    item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+                }
+            }
+        } else {
+            if !self.topics.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}topics", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchRequestGroup {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 8 {
+            node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        }
+        if version >= 8 {
+            node.insert("topics".into(), self.topics.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchRequestGroup")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "OffsetFetchRequestGroup.groupId")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchRequestGroup", "groupId", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = if value.is_null() { None } else { Some(crate::json::array(value, "OffsetFetchRequestGroup.topics")?.iter().map(|item| OffsetFetchRequestTopics::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchRequestGroup", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchRequest {
     /// The group to fetch offsets for.
@@ -444,6 +715,7 @@ pub struct OffsetFetchRequest {
     /// Each topic we would like to fetch offsets for, or null to fetch offsets for all topics.
     /// 
     /// Supported API versions: 0-7
+    /// May be `None` in versions: 0-7
     pub topics: Option<Vec<OffsetFetchRequestTopic>>,
 
     /// Each group we would like to fetch offsets for
@@ -457,7 +729,7 @@ pub struct OffsetFetchRequest {
     pub require_stable: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchRequest {
@@ -470,101 +742,111 @@ impl Builder for OffsetFetchRequest {
 
 impl Encodable for OffsetFetchRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetFetchRequest", version));
+        }
         if version <= 7 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.group_id)?;
+                types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version))?;
             } else {
-                types::String.encode(buf, &self.group_id)?;
+                types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version))?;
             }
         } else {
             if !self.group_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "group_id", version));
             }
         }
         if version <= 7 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version))?;
             }
         } else {
             if !self.topics.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "topics", version));
             }
         }
         if version >= 8 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.groups)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.groups).map_err(|e| e.with_field("OffsetFetchRequest", "groups", version))?;
         } else {
             if !self.groups.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "groups", version));
             }
         }
         if version >= 7 {
-            types::Boolean.encode(buf, &self.require_stable)?;
+            types::Boolean.encode(buf, &self.require_stable).map_err(|e| e.with_field("OffsetFetchRequest", "require_stable", version))?;
         } else {
             if self.require_stable {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "require_stable", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetFetchRequest", version));
+        }
         let mut total_size = 0;
         if version <= 7 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.group_id)?;
+                total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.group_id)?;
+                total_size += types::String.compute_size(&self.group_id).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version))?;
             }
         } else {
             if !self.group_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "group_id", version));
             }
         }
         if version <= 7 {
             if version >= 6 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version))?;
             }
         } else {
             if !self.topics.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "topics", version));
             }
         }
         if version >= 8 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.groups)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.groups).map_err(|e| e.with_field("OffsetFetchRequest", "groups", version))?;
         } else {
             if !self.groups.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "groups", version));
             }
         }
         if version >= 7 {
-            total_size += types::Boolean.compute_size(&self.require_stable)?;
+            total_size += types::Boolean.compute_size(&self.require_stable).map_err(|e| e.with_field("OffsetFetchRequest", "require_stable", version))?;
         } else {
             if self.require_stable {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "require_stable", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -572,42 +854,45 @@ impl Encodable for OffsetFetchRequest {
 
 impl Decodable for OffsetFetchRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("OffsetFetchRequest", version));
+        }
+        let start = buf.remaining();
         let group_id = if version <= 7 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let topics = if version <= 7 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
         let groups = if version >= 8 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "groups", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let require_stable = if version >= 7 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "require_stable", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -628,7 +913,7 @@ impl Default for OffsetFetchRequest {
             topics: Some(Default::default()),
             groups: Default::default(),
             require_stable: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -637,6 +922,268 @@ impl Message for OffsetFetchRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = OffsetFetchRequestTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetFetchRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version <= 7 {
+            if version >= 6 {
+                types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version))?;
+            } else {
+                types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version))?;
+            }
+        } else {
+            if !self.group_id.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "group_id", version));
+            }
+        }
+        if version <= 7 {
+            if version >= 6 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version))?;
+            }
+        } else {
+            if topics.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "topics", version));
+            }
+        }
+        if version >= 8 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.groups).map_err(|e| e.with_field("OffsetFetchRequest", "groups", version))?;
+        } else {
+            if !self.groups.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "groups", version));
+            }
+        }
+        if version >= 7 {
+            types::Boolean.encode(buf, &self.require_stable).map_err(|e| e.with_field("OffsetFetchRequest", "require_stable", version))?;
+        } else {
+            if self.require_stable {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "require_stable", version));
+            }
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `groups` produced by an iterator of known length, in place of
+    /// the `groups` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_groups<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, groups: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = OffsetFetchRequestGroup>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetFetchRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let groups = groups.into_iter();
+        if version <= 7 {
+            if version >= 6 {
+                types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version))?;
+            } else {
+                types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetFetchRequest", "group_id", version))?;
+            }
+        } else {
+            if !self.group_id.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "group_id", version));
+            }
+        }
+        if version <= 7 {
+            if version >= 6 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchRequest", "topics", version))?;
+            }
+        } else {
+            if !self.topics.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "topics", version));
+            }
+        }
+        if version >= 8 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(groups)).map_err(|e| e.with_field("OffsetFetchRequest", "groups", version))?;
+        } else {
+            if groups.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "groups", version));
+            }
+        }
+        if version >= 7 {
+            types::Boolean.encode(buf, &self.require_stable).map_err(|e| e.with_field("OffsetFetchRequest", "require_stable", version))?;
+        } else {
+            if self.require_stable {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchRequest", "require_stable", version));
+            }
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl OffsetFetchRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 7 {
+            self.group_id = Default::default();
+        }
+        if version <= 7 {
+            if let Some(items) = &mut self.topics {
+                for item in items.iter_mut() {
+                    item.normalize_to(version);
+                }
+            }
+        } else {
+            self.topics = Some(Default::default());
+        }
+        if version >= 8 {
+            for item in self.groups.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.groups = Default::default();
+        }
+        if version < 7 {
+            self.require_stable = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 7 {
+            if !self.group_id.is_empty() {
+                invalid.push(format!("{}group_id", path));
+            }
+        }
+        if version <= 7 {
+            if let Some(items) = &self.topics {
+                for (i, item) in items.iter().enumerate() {
+                    item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+                }
+            }
+        } else {
+            if !self.topics.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}topics", path));
+            }
+        }
+        if version >= 8 {
+            for (i, item) in self.groups.iter().enumerate() {
+                item.validate_into(version, &format!("{}groups[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.groups.is_empty() {
+                invalid.push(format!("{}groups", path));
+            }
+        }
+        if version < 7 {
+            if self.require_stable {
+                invalid.push(format!("{}require_stable", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 7 {
+            node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        }
+        if version <= 7 {
+            node.insert("topics".into(), self.topics.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        }
+        if version >= 8 {
+            node.insert("groups".into(), serde_json::Value::Array(self.groups.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 7 {
+            node.insert("requireStable".into(), JsonValue::to_json(&self.require_stable));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "OffsetFetchRequest.groupId")?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchRequest", "groupId", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = if value.is_null() { None } else { Some(crate::json::array(value, "OffsetFetchRequest.topics")?.iter().map(|item| OffsetFetchRequestTopic::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchRequest", "topics", version));
+        }
+        if let Some(value) = object.get("groups") {
+            this.groups = crate::json::array(value, "OffsetFetchRequest.groups")?.iter().map(|item| OffsetFetchRequestGroup::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchRequest", "groups", version));
+        }
+        if let Some(value) = object.get("requireStable") {
+            this.require_stable = JsonValue::from_json(value, "OffsetFetchRequest.requireStable")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("OffsetFetchRequest", "requireStable", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for OffsetFetchRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 6 {
@@ -647,3 +1194,60 @@ impl HeaderVersion for OffsetFetchRequest {
     }
 }
 
+impl OffsetFetchRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::OffsetFetchResponse {
+        let mut response = super::OffsetFetchResponse::default();
+        if version <= 7 {
+            let mut items0 = Vec::new();
+            for request0 in self.topics.iter().flatten() {
+                let mut response0 = super::offset_fetch_response::OffsetFetchResponseTopic::default();
+                response0.name = request0.name.clone();
+                let mut items1 = Vec::new();
+                for request1 in &request0.partition_indexes {
+                    let mut response1 = super::offset_fetch_response::OffsetFetchResponsePartition::default();
+                    response1.partition_index = *request1;
+                    response1.error_code = error.code();
+                    items1.push(response1);
+                }
+                response0.partitions = items1;
+                items0.push(response0);
+            }
+            response.topics = items0;
+        }
+        if version >= 2 && version <= 7 {
+            response.error_code = error.code();
+        }
+        if version >= 8 {
+            let mut items0 = Vec::new();
+            for request0 in &self.groups {
+                let mut response0 = super::offset_fetch_response::OffsetFetchResponseGroup::default();
+                response0.group_id = request0.group_id.clone();
+                let mut items1 = Vec::new();
+                for request1 in request0.topics.iter().flatten() {
+                    let mut response1 = super::offset_fetch_response::OffsetFetchResponseTopics::default();
+                    response1.name = request1.name.clone();
+                    let mut items2 = Vec::new();
+                    for request2 in &request1.partition_indexes {
+                        let mut response2 = super::offset_fetch_response::OffsetFetchResponsePartitions::default();
+                        response2.partition_index = *request2;
+                        response2.error_code = error.code();
+                        items2.push(response2);
+                    }
+                    response1.partitions = items2;
+                    items1.push(response1);
+                }
+                response0.topics = items1;
+                response0.error_code = error.code();
+                items0.push(response0);
+            }
+            response.groups = items0;
+        }
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchResponsePartition {
     /// The partition index.
@@ -40,6 +46,8 @@ pub struct OffsetFetchResponsePartition {
     /// The partition metadata.
     /// 
     /// Supported API versions: 0-7
+    /// May be `None` in versions: 0-7
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub metadata: Option<StrBytes>,
 
     /// The error code, or 0 if there was no error.
@@ -48,7 +56,7 @@ pub struct OffsetFetchResponsePartition {
     pub error_code: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchResponsePartition {
@@ -61,99 +69,103 @@ impl Builder for OffsetFetchResponsePartition {
 
 impl Encodable for OffsetFetchResponsePartition {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 7 {
-            types::Int32.encode(buf, &self.partition_index)?;
+            types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("OffsetFetchResponsePartition", "partition_index", version))?;
         } else {
             if self.partition_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartition", "partition_index", version));
             }
         }
         if version <= 7 {
-            types::Int64.encode(buf, &self.committed_offset)?;
+            types::Int64.encode(buf, &self.committed_offset).map_err(|e| e.with_field("OffsetFetchResponsePartition", "committed_offset", version))?;
         } else {
             if self.committed_offset != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartition", "committed_offset", version));
             }
         }
         if version >= 5 && version <= 7 {
-            types::Int32.encode(buf, &self.committed_leader_epoch)?;
+            types::Int32.encode(buf, &self.committed_leader_epoch).map_err(|e| e.with_field("OffsetFetchResponsePartition", "committed_leader_epoch", version))?;
         }
         if version <= 7 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.metadata)?;
+                types::CompactString.encode(buf, &self.metadata).map_err(|e| e.with_field("OffsetFetchResponsePartition", "metadata", version))?;
             } else {
-                types::String.encode(buf, &self.metadata)?;
+                types::String.encode(buf, &self.metadata).map_err(|e| e.with_field("OffsetFetchResponsePartition", "metadata", version))?;
             }
         } else {
             if !self.metadata.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartition", "metadata", version));
             }
         }
         if version <= 7 {
-            types::Int16.encode(buf, &self.error_code)?;
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("OffsetFetchResponsePartition", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartition", "error_code", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 7 {
-            total_size += types::Int32.compute_size(&self.partition_index)?;
+            total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("OffsetFetchResponsePartition", "partition_index", version))?;
         } else {
             if self.partition_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartition", "partition_index", version));
             }
         }
         if version <= 7 {
-            total_size += types::Int64.compute_size(&self.committed_offset)?;
+            total_size += types::Int64.compute_size(&self.committed_offset).map_err(|e| e.with_field("OffsetFetchResponsePartition", "committed_offset", version))?;
         } else {
             if self.committed_offset != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartition", "committed_offset", version));
             }
         }
         if version >= 5 && version <= 7 {
-            total_size += types::Int32.compute_size(&self.committed_leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.committed_leader_epoch).map_err(|e| e.with_field("OffsetFetchResponsePartition", "committed_leader_epoch", version))?;
         }
         if version <= 7 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.metadata)?;
+                total_size += types::CompactString.compute_size(&self.metadata).map_err(|e| e.with_field("OffsetFetchResponsePartition", "metadata", version))?;
             } else {
-                total_size += types::String.compute_size(&self.metadata)?;
+                total_size += types::String.compute_size(&self.metadata).map_err(|e| e.with_field("OffsetFetchResponsePartition", "metadata", version))?;
             }
         } else {
             if !self.metadata.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartition", "metadata", version));
             }
         }
         if version <= 7 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("OffsetFetchResponsePartition", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartition", "error_code", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -161,43 +173,43 @@ impl Encodable for OffsetFetchResponsePartition {
 
 impl Decodable for OffsetFetchResponsePartition {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let partition_index = if version <= 7 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "partition_index", version, start - buf.remaining()))?
         } else {
             0
         };
         let committed_offset = if version <= 7 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "committed_offset", version, start - buf.remaining()))?
         } else {
             0
         };
         let committed_leader_epoch = if version >= 5 && version <= 7 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "committed_leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let metadata = if version <= 7 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "metadata", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "metadata", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
         let error_code = if version <= 7 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchResponsePartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -220,7 +232,7 @@ impl Default for OffsetFetchResponsePartition {
             committed_leader_epoch: -1,
             metadata: Some(Default::default()),
             error_code: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -229,9 +241,137 @@ impl Message for OffsetFetchResponsePartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchResponsePartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 7 {
+            self.partition_index = 0;
+        }
+        if version > 7 {
+            self.committed_offset = 0;
+        }
+        if version < 5 || version > 7 {
+            self.committed_leader_epoch = -1;
+        }
+        if version > 7 {
+            self.metadata = Some(Default::default());
+        }
+        if version > 7 {
+            self.error_code = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 7 {
+            if self.partition_index != 0 {
+                invalid.push(format!("{}partition_index", path));
+            }
+        }
+        if version > 7 {
+            if self.committed_offset != 0 {
+                invalid.push(format!("{}committed_offset", path));
+            }
+        }
+        if version < 5 || version > 7 {
+            if self.committed_leader_epoch != -1 {
+                invalid.push(format!("{}committed_leader_epoch", path));
+            }
+        }
+        if version > 7 {
+            if !self.metadata.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}metadata", path));
+            }
+        }
+        if version > 7 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchResponsePartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 7 {
+            node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        }
+        if version <= 7 {
+            node.insert("committedOffset".into(), JsonValue::to_json(&self.committed_offset));
+        }
+        if version >= 5 && version <= 7 {
+            node.insert("committedLeaderEpoch".into(), JsonValue::to_json(&self.committed_leader_epoch));
+        }
+        if version <= 7 {
+            node.insert("metadata".into(), JsonValue::to_json(&self.metadata));
+        }
+        if version <= 7 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchResponsePartition")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "OffsetFetchResponsePartition.partitionIndex")?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponsePartition", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("committedOffset") {
+            this.committed_offset = JsonValue::from_json(value, "OffsetFetchResponsePartition.committedOffset")?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponsePartition", "committedOffset", version));
+        }
+        if let Some(value) = object.get("committedLeaderEpoch") {
+            this.committed_leader_epoch = JsonValue::from_json(value, "OffsetFetchResponsePartition.committedLeaderEpoch")?;
+        } else if version >= 5 && version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponsePartition", "committedLeaderEpoch", version));
+        }
+        if let Some(value) = object.get("metadata") {
+            this.metadata = JsonValue::from_json(value, "OffsetFetchResponsePartition.metadata")?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponsePartition", "metadata", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "OffsetFetchResponsePartition.errorCode")?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponsePartition", "errorCode", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchResponseTopic {
     /// The topic name.
@@ -245,7 +385,7 @@ pub struct OffsetFetchResponseTopic {
     pub partitions: Vec<OffsetFetchResponsePartition>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchResponseTopic {
@@ -258,73 +398,77 @@ impl Builder for OffsetFetchResponseTopic {
 
 impl Encodable for OffsetFetchResponseTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 7 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.name)?;
+                types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("OffsetFetchResponseTopic", "name", version))?;
             } else {
-                types::String.encode(buf, &self.name)?;
+                types::String.encode(buf, &self.name).map_err(|e| e.with_field("OffsetFetchResponseTopic", "name", version))?;
             }
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseTopic", "name", version));
             }
         }
         if version <= 7 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("OffsetFetchResponseTopic", "partitions", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("OffsetFetchResponseTopic", "partitions", version))?;
             }
         } else {
             if !self.partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseTopic", "partitions", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 7 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.name)?;
+                total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("OffsetFetchResponseTopic", "name", version))?;
             } else {
-                total_size += types::String.compute_size(&self.name)?;
+                total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("OffsetFetchResponseTopic", "name", version))?;
             }
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseTopic", "name", version));
             }
         }
         if version <= 7 {
             if version >= 6 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("OffsetFetchResponseTopic", "partitions", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("OffsetFetchResponseTopic", "partitions", version))?;
             }
         } else {
             if !self.partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseTopic", "partitions", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -332,32 +476,32 @@ impl Encodable for OffsetFetchResponseTopic {
 
 impl Decodable for OffsetFetchResponseTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version <= 7 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopic", "name", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopic", "name", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let partitions = if version <= 7 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopic", "partitions", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopic", "partitions", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchResponseTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -374,7 +518,7 @@ impl Default for OffsetFetchResponseTopic {
         Self {
             name: Default::default(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -383,9 +527,97 @@ impl Message for OffsetFetchResponseTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchResponseTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 7 {
+            self.name = Default::default();
+        }
+        if version <= 7 {
+            for item in self.partitions.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.partitions = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 7 {
+            if !self.name.is_empty() {
+                invalid.push(format!("{}name", path));
+            }
+        }
+        if version <= 7 {
+            for (i, item) in self.partitions.iter().enumerate() {
+                item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.partitions.is_empty() {
+                invalid.push(format!("{}partitions", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchResponseTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 7 {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        if version <= 7 {
+            node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchResponseTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "OffsetFetchResponseTopic.name")?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponseTopic", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "OffsetFetchResponseTopic.partitions")?.iter().map(|item| OffsetFetchResponsePartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponseTopic", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchResponsePartitions {
     /// The partition index.
@@ -406,6 +638,8 @@ pub struct OffsetFetchResponsePartitions {
     /// The partition metadata.
     /// 
     /// Supported API versions: 8
+    /// May be `None` in versions: 8
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub metadata: Option<StrBytes>,
 
     /// The partition-level error code, or 0 if there was no error.
@@ -414,7 +648,7 @@ pub struct OffsetFetchResponsePartitions {
     pub error_code: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchResponsePartitions {
@@ -427,91 +661,95 @@ impl Builder for OffsetFetchResponsePartitions {
 
 impl Encodable for OffsetFetchResponsePartitions {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 8 {
-            types::Int32.encode(buf, &self.partition_index)?;
+            types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "partition_index", version))?;
         } else {
             if self.partition_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartitions", "partition_index", version));
             }
         }
         if version >= 8 {
-            types::Int64.encode(buf, &self.committed_offset)?;
+            types::Int64.encode(buf, &self.committed_offset).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "committed_offset", version))?;
         } else {
             if self.committed_offset != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartitions", "committed_offset", version));
             }
         }
         if version >= 8 {
-            types::Int32.encode(buf, &self.committed_leader_epoch)?;
+            types::Int32.encode(buf, &self.committed_leader_epoch).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "committed_leader_epoch", version))?;
         }
         if version >= 8 {
-            types::CompactString.encode(buf, &self.metadata)?;
+            types::CompactString.encode(buf, &self.metadata).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "metadata", version))?;
         } else {
             if !self.metadata.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartitions", "metadata", version));
             }
         }
         if version >= 8 {
-            types::Int16.encode(buf, &self.error_code)?;
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartitions", "error_code", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 8 {
-            total_size += types::Int32.compute_size(&self.partition_index)?;
+            total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "partition_index", version))?;
         } else {
             if self.partition_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartitions", "partition_index", version));
             }
         }
         if version >= 8 {
-            total_size += types::Int64.compute_size(&self.committed_offset)?;
+            total_size += types::Int64.compute_size(&self.committed_offset).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "committed_offset", version))?;
         } else {
             if self.committed_offset != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartitions", "committed_offset", version));
             }
         }
         if version >= 8 {
-            total_size += types::Int32.compute_size(&self.committed_leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.committed_leader_epoch).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "committed_leader_epoch", version))?;
         }
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.metadata)?;
+            total_size += types::CompactString.compute_size(&self.metadata).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "metadata", version))?;
         } else {
             if !self.metadata.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartitions", "metadata", version));
             }
         }
         if version >= 8 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponsePartitions", "error_code", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -519,39 +757,39 @@ impl Encodable for OffsetFetchResponsePartitions {
 
 impl Decodable for OffsetFetchResponsePartitions {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let partition_index = if version >= 8 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "partition_index", version, start - buf.remaining()))?
         } else {
             0
         };
         let committed_offset = if version >= 8 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "committed_offset", version, start - buf.remaining()))?
         } else {
             0
         };
         let committed_leader_epoch = if version >= 8 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "committed_leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let metadata = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "metadata", version, start - buf.remaining()))?
         } else {
             Some(Default::default())
         };
         let error_code = if version >= 8 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchResponsePartitions", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -574,7 +812,7 @@ impl Default for OffsetFetchResponsePartitions {
             committed_leader_epoch: -1,
             metadata: Some(Default::default()),
             error_code: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -583,9 +821,137 @@ impl Message for OffsetFetchResponsePartitions {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchResponsePartitions {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 8 {
+            self.partition_index = 0;
+        }
+        if version < 8 {
+            self.committed_offset = 0;
+        }
+        if version < 8 {
+            self.committed_leader_epoch = -1;
+        }
+        if version < 8 {
+            self.metadata = Some(Default::default());
+        }
+        if version < 8 {
+            self.error_code = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 8 {
+            if self.partition_index != 0 {
+                invalid.push(format!("{}partition_index", path));
+            }
+        }
+        if version < 8 {
+            if self.committed_offset != 0 {
+                invalid.push(format!("{}committed_offset", path));
+            }
+        }
+        if version < 8 {
+            if self.committed_leader_epoch != -1 {
+                invalid.push(format!("{}committed_leader_epoch", path));
+            }
+        }
+        if version < 8 {
+            if !self.metadata.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}metadata", path));
+            }
+        }
+        if version < 8 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchResponsePartitions {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 8 {
+            node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        }
+        if version >= 8 {
+            node.insert("committedOffset".into(), JsonValue::to_json(&self.committed_offset));
+        }
+        if version >= 8 {
+            node.insert("committedLeaderEpoch".into(), JsonValue::to_json(&self.committed_leader_epoch));
+        }
+        if version >= 8 {
+            node.insert("metadata".into(), JsonValue::to_json(&self.metadata));
+        }
+        if version >= 8 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchResponsePartitions")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "OffsetFetchResponsePartitions.partitionIndex")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponsePartitions", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("committedOffset") {
+            this.committed_offset = JsonValue::from_json(value, "OffsetFetchResponsePartitions.committedOffset")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponsePartitions", "committedOffset", version));
+        }
+        if let Some(value) = object.get("committedLeaderEpoch") {
+            this.committed_leader_epoch = JsonValue::from_json(value, "OffsetFetchResponsePartitions.committedLeaderEpoch")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponsePartitions", "committedLeaderEpoch", version));
+        }
+        if let Some(value) = object.get("metadata") {
+            this.metadata = JsonValue::from_json(value, "OffsetFetchResponsePartitions.metadata")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponsePartitions", "metadata", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "OffsetFetchResponsePartitions.errorCode")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponsePartitions", "errorCode", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchResponseTopics {
     /// The topic name.
@@ -599,7 +965,7 @@ pub struct OffsetFetchResponseTopics {
     pub partitions: Vec<OffsetFetchResponsePartitions>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchResponseTopics {
@@ -612,57 +978,61 @@ impl Builder for OffsetFetchResponseTopics {
 
 impl Encodable for OffsetFetchResponseTopics {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 8 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("OffsetFetchResponseTopics", "name", version))?;
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseTopics", "name", version));
             }
         }
         if version >= 8 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("OffsetFetchResponseTopics", "partitions", version))?;
         } else {
             if !self.partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseTopics", "partitions", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("OffsetFetchResponseTopics", "name", version))?;
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseTopics", "name", version));
             }
         }
         if version >= 8 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("OffsetFetchResponseTopics", "partitions", version))?;
         } else {
             if !self.partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseTopics", "partitions", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -670,24 +1040,24 @@ impl Encodable for OffsetFetchResponseTopics {
 
 impl Decodable for OffsetFetchResponseTopics {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopics", "name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let partitions = if version >= 8 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopics", "partitions", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchResponseTopics", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -704,7 +1074,7 @@ impl Default for OffsetFetchResponseTopics {
         Self {
             name: Default::default(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -713,9 +1083,97 @@ impl Message for OffsetFetchResponseTopics {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchResponseTopics {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 8 {
+            self.name = Default::default();
+        }
+        if version >= 8 {
+            for item in self.partitions.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.partitions = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 8 {
+            if !self.name.is_empty() {
+                invalid.push(format!("{}name", path));
+            }
+        }
+        if version >= 8 {
+            for (i, item) in self.partitions.iter().enumerate() {
+                item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.partitions.is_empty() {
+                invalid.push(format!("{}partitions", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchResponseTopics {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 8 {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        if version >= 8 {
+            node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchResponseTopics")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "OffsetFetchResponseTopics.name")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponseTopics", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "OffsetFetchResponseTopics.partitions")?.iter().map(|item| OffsetFetchResponsePartitions::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponseTopics", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchResponseGroup {
     /// The group ID.
@@ -734,7 +1192,7 @@ pub struct OffsetFetchResponseGroup {
     pub error_code: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchResponseGroup {
@@ -747,71 +1205,75 @@ impl Builder for OffsetFetchResponseGroup {
 
 impl Encodable for OffsetFetchResponseGroup {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 8 {
-            types::CompactString.encode(buf, &self.group_id)?;
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("OffsetFetchResponseGroup", "group_id", version))?;
         } else {
             if !self.group_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseGroup", "group_id", version));
             }
         }
         if version >= 8 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchResponseGroup", "topics", version))?;
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseGroup", "topics", version));
             }
         }
         if version >= 8 {
-            types::Int16.encode(buf, &self.error_code)?;
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("OffsetFetchResponseGroup", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseGroup", "error_code", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 8 {
-            total_size += types::CompactString.compute_size(&self.group_id)?;
+            total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("OffsetFetchResponseGroup", "group_id", version))?;
         } else {
             if !self.group_id.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseGroup", "group_id", version));
             }
         }
         if version >= 8 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("OffsetFetchResponseGroup", "topics", version))?;
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseGroup", "topics", version));
             }
         }
         if version >= 8 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("OffsetFetchResponseGroup", "error_code", version))?;
         } else {
             if self.error_code != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponseGroup", "error_code", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -819,29 +1281,29 @@ impl Encodable for OffsetFetchResponseGroup {
 
 impl Decodable for OffsetFetchResponseGroup {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let group_id = if version >= 8 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseGroup", "group_id", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topics = if version >= 8 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchResponseGroup", "topics", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let error_code = if version >= 8 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseGroup", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchResponseGroup", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -860,7 +1322,7 @@ impl Default for OffsetFetchResponseGroup {
             group_id: Default::default(),
             topics: Default::default(),
             error_code: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -869,9 +1331,113 @@ impl Message for OffsetFetchResponseGroup {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchResponseGroup {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 8 {
+            self.group_id = Default::default();
+        }
+        if version >= 8 {
+            for item in self.topics.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topics = Default::default();
+        }
+        if version < 8 {
+            self.error_code = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 8 {
+            if !self.group_id.is_empty() {
+                invalid.push(format!("{}group_id", path));
+            }
+        }
+        if version >= 8 {
+            for (i, item) in self.topics.iter().enumerate() {
+                item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topics.is_empty() {
+                invalid.push(format!("{}topics", path));
+            }
+        }
+        if version < 8 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchResponseGroup {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 8 {
+            node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        }
+        if version >= 8 {
+            node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 8 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchResponseGroup")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "OffsetFetchResponseGroup.groupId")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponseGroup", "groupId", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "OffsetFetchResponseGroup.topics")?.iter().map(|item| OffsetFetchResponseTopics::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponseGroup", "topics", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "OffsetFetchResponseGroup.errorCode")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponseGroup", "errorCode", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct OffsetFetchResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -895,7 +1461,7 @@ pub struct OffsetFetchResponse {
     pub groups: Vec<OffsetFetchResponseGroup>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for OffsetFetchResponse {
@@ -908,77 +1474,87 @@ impl Builder for OffsetFetchResponse {
 
 impl Encodable for OffsetFetchResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetFetchResponse", version));
+        }
         if version >= 3 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("OffsetFetchResponse", "throttle_time_ms", version))?;
         }
         if version <= 7 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version))?;
             }
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponse", "topics", version));
             }
         }
         if version >= 2 && version <= 7 {
-            types::Int16.encode(buf, &self.error_code)?;
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("OffsetFetchResponse", "error_code", version))?;
         }
         if version >= 8 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.groups)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.groups).map_err(|e| e.with_field("OffsetFetchResponse", "groups", version))?;
         } else {
             if !self.groups.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponse", "groups", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetFetchResponse", version));
+        }
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("OffsetFetchResponse", "throttle_time_ms", version))?;
         }
         if version <= 7 {
             if version >= 6 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version))?;
             }
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponse", "topics", version));
             }
         }
         if version >= 2 && version <= 7 {
-            total_size += types::Int16.compute_size(&self.error_code)?;
+            total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("OffsetFetchResponse", "error_code", version))?;
         }
         if version >= 8 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.groups)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.groups).map_err(|e| e.with_field("OffsetFetchResponse", "groups", version))?;
         } else {
             if !self.groups.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponse", "groups", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -986,38 +1562,41 @@ impl Encodable for OffsetFetchResponse {
 
 impl Decodable for OffsetFetchResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("OffsetFetchResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("OffsetFetchResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
         let topics = if version <= 7 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let error_code = if version >= 2 && version <= 7 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("OffsetFetchResponse", "error_code", version, start - buf.remaining()))?
         } else {
             0
         };
         let groups = if version >= 8 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("OffsetFetchResponse", "groups", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -1038,7 +1617,7 @@ impl Default for OffsetFetchResponse {
             topics: Default::default(),
             error_code: 0,
             groups: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -1047,6 +1626,240 @@ impl Message for OffsetFetchResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl OffsetFetchResponse {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = OffsetFetchResponseTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetFetchResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 3 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("OffsetFetchResponse", "throttle_time_ms", version))?;
+        }
+        if version <= 7 {
+            if version >= 6 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version))?;
+            }
+        } else {
+            if topics.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponse", "topics", version));
+            }
+        }
+        if version >= 2 && version <= 7 {
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("OffsetFetchResponse", "error_code", version))?;
+        }
+        if version >= 8 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.groups).map_err(|e| e.with_field("OffsetFetchResponse", "groups", version))?;
+        } else {
+            if !self.groups.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponse", "groups", version));
+            }
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `groups` produced by an iterator of known length, in place of
+    /// the `groups` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_groups<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, groups: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = OffsetFetchResponseGroup>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("OffsetFetchResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let groups = groups.into_iter();
+        if version >= 3 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("OffsetFetchResponse", "throttle_time_ms", version))?;
+        }
+        if version <= 7 {
+            if version >= 6 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("OffsetFetchResponse", "topics", version))?;
+            }
+        } else {
+            if !self.topics.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponse", "topics", version));
+            }
+        }
+        if version >= 2 && version <= 7 {
+            types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("OffsetFetchResponse", "error_code", version))?;
+        }
+        if version >= 8 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(groups)).map_err(|e| e.with_field("OffsetFetchResponse", "groups", version))?;
+        } else {
+            if groups.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("OffsetFetchResponse", "groups", version));
+            }
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("OffsetFetchResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("OffsetFetchResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl OffsetFetchResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.throttle_time_ms = 0;
+        }
+        if version <= 7 {
+            for item in self.topics.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topics = Default::default();
+        }
+        if version < 2 || version > 7 {
+            self.error_code = 0;
+        }
+        if version >= 8 {
+            for item in self.groups.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.groups = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        if version <= 7 {
+            for (i, item) in self.topics.iter().enumerate() {
+                item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topics.is_empty() {
+                invalid.push(format!("{}topics", path));
+            }
+        }
+        if version < 2 || version > 7 {
+            if self.error_code != 0 {
+                invalid.push(format!("{}error_code", path));
+            }
+        }
+        if version >= 8 {
+            for (i, item) in self.groups.iter().enumerate() {
+                item.validate_into(version, &format!("{}groups[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.groups.is_empty() {
+                invalid.push(format!("{}groups", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl OffsetFetchResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        if version <= 7 {
+            node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 2 && version <= 7 {
+            node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        }
+        if version >= 8 {
+            node.insert("groups".into(), serde_json::Value::Array(self.groups.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "OffsetFetchResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "OffsetFetchResponse.throttleTimeMs")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("OffsetFetchResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "OffsetFetchResponse.topics")?.iter().map(|item| OffsetFetchResponseTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponse", "topics", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "OffsetFetchResponse.errorCode")?;
+        } else if version >= 2 && version <= 7 {
+            return Err(crate::json::missing("OffsetFetchResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("groups") {
+            this.groups = crate::json::array(value, "OffsetFetchResponse.groups")?.iter().map(|item| OffsetFetchResponseGroup::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("OffsetFetchResponse", "groups", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for OffsetFetchResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 6 {
@@ -5,21 +5,29 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+use bytes::Buf;
+use crate::protocol::view::{self, ArrayView};
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct PartitionProduceData {
     /// The partition index.
@@ -30,10 +38,11 @@ pub struct PartitionProduceData {
     /// The record data to be produced.
     /// 
     /// Supported API versions: 0-9
+    /// May be `None` in versions: 0-9
     pub records: Option<Bytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for PartitionProduceData {
@@ -46,41 +55,45 @@ impl Builder for PartitionProduceData {
 
 impl Encodable for PartitionProduceData {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.index)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.index).map_err(|e| e.with_field("PartitionProduceData", "index", version))?;
         if version >= 9 {
-            types::CompactBytes.encode(buf, &self.records)?;
+            types::CompactBytes.encode(buf, &self.records).map_err(|e| e.with_field("PartitionProduceData", "records", version))?;
         } else {
-            types::Bytes.encode(buf, &self.records)?;
+            types::Bytes.encode(buf, &self.records).map_err(|e| e.with_field("PartitionProduceData", "records", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionProduceData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionProduceData", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionProduceData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.index)?;
+        total_size += types::Int32.compute_size(&self.index).map_err(|e| e.with_field("PartitionProduceData", "index", version))?;
         if version >= 9 {
-            total_size += types::CompactBytes.compute_size(&self.records)?;
+            total_size += types::CompactBytes.compute_size(&self.records).map_err(|e| e.with_field("PartitionProduceData", "records", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.records)?;
+            total_size += types::Bytes.compute_size(&self.records).map_err(|e| e.with_field("PartitionProduceData", "records", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionProduceData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("PartitionProduceData", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionProduceData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -88,20 +101,20 @@ impl Encodable for PartitionProduceData {
 
 impl Decodable for PartitionProduceData {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let index = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "index", version, start - buf.remaining()))?;
         let records = if version >= 9 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "records", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "records", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("PartitionProduceData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -118,7 +131,7 @@ impl Default for PartitionProduceData {
         Self {
             index: 0,
             records: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -127,9 +140,137 @@ impl Message for PartitionProduceData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl PartitionProduceData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionProduceData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("index".into(), JsonValue::to_json(&self.index));
+        crate::json::insert_records(&mut node, "records", self.records.as_ref(), records);
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionProduceData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("index") {
+            this.index = JsonValue::from_json(value, "PartitionProduceData.index")?;
+        } else {
+            return Err(crate::json::missing("PartitionProduceData", "index", version));
+        }
+        if let Some(value) = object.get("records") {
+            this.records = JsonValue::from_json(value, "PartitionProduceData.records")?;
+        } else {
+            return Err(crate::json::missing("PartitionProduceData", "records", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`PartitionProduceData`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-9
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionProduceDataView {
+    /// The partition index.
+    /// 
+    /// Supported API versions: 0-9
+    pub index: i32,
+
+    /// The record data to be produced.
+    /// 
+    /// Supported API versions: 0-9
+    pub records: Option<Bytes>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl PartitionProduceDataView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "index", version, start - buf.remaining()))?;
+        let records = if version >= 9 {
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "records", version, start - buf.remaining()))?
+        } else {
+            types::Bytes.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "records", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionProduceData", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("PartitionProduceData", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            index,
+            records,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("PartitionProduceData", "index", version, start - buf.remaining()))?;
+        if version >= 9 {
+            view::skip_bytes(buf, true).map_err(|e| e.with_field("PartitionProduceData", "records", version, start - buf.remaining()))?;
+        } else {
+            view::skip_bytes(buf, false).map_err(|e| e.with_field("PartitionProduceData", "records", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("PartitionProduceData", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for PartitionProduceDataView {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            records: Some(Default::default()),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicProduceData {
     /// Each partition to produce to.
@@ -138,7 +279,7 @@ pub struct TopicProduceData {
     pub partition_data: Vec<PartitionProduceData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TopicProduceData {
@@ -152,49 +293,53 @@ impl Builder for TopicProduceData {
 impl MapEncodable for TopicProduceData {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 9 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("TopicProduceData", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("TopicProduceData", "name", version))?;
         }
         if version >= 9 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partition_data)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partition_data).map_err(|e| e.with_field("TopicProduceData", "partition_data", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partition_data)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partition_data).map_err(|e| e.with_field("TopicProduceData", "partition_data", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicProduceData", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TopicProduceData", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TopicProduceData", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 9 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("TopicProduceData", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("TopicProduceData", "name", version))?;
         }
         if version >= 9 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partition_data)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partition_data).map_err(|e| e.with_field("TopicProduceData", "partition_data", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partition_data)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partition_data).map_err(|e| e.with_field("TopicProduceData", "partition_data", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicProduceData", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TopicProduceData", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TopicProduceData", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -203,24 +348,24 @@ impl MapEncodable for TopicProduceData {
 impl MapDecodable for TopicProduceData {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 9 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicProduceData", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("TopicProduceData", "name", version, start - buf.remaining()))?
         };
         let partition_data = if version >= 9 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TopicProduceData", "partition_data", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TopicProduceData", "partition_data", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicProduceData", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicProduceData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicProduceData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TopicProduceData", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -235,7 +380,7 @@ impl Default for TopicProduceData {
     fn default() -> Self {
         Self {
             partition_data: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -244,14 +389,177 @@ impl Message for TopicProduceData {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl TopicProduceData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partition_data.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partition_data.iter().enumerate() {
+            item.validate_into(version, &format!("{}partition_data[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicProduceData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("partitionData".into(), serde_json::Value::Array(self.partition_data.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "TopicProduceData")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "TopicProduceData.name")?;
+        } else {
+            return Err(crate::json::missing("TopicProduceData", "name", version));
+        }
+        if let Some(value) = object.get("partitionData") {
+            this.partition_data = crate::json::array(value, "TopicProduceData.partitionData")?.iter().map(|item| PartitionProduceData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("TopicProduceData", "partitionData", version));
+        }
+        Ok((key, this))
+    }
+}
+
+/// A view of [`TopicProduceData`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-9
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicProduceDataView {
+    /// The topic name.
+    /// 
+    /// Supported API versions: 0-9
+    pub name: super::TopicName,
+
+    /// Each partition to produce to.
+    /// 
+    /// Supported API versions: 0-9
+    pub partition_data: ArrayView<PartitionProduceDataView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl TopicProduceDataView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let key_field = if version >= 9 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicProduceData", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("TopicProduceData", "name", version, start - buf.remaining()))?
+        };
+        let partition_data = if version >= 9 {
+            ArrayView::decode(buf, version, true, PartitionProduceDataView::decode, PartitionProduceDataView::skip).map_err(|e| e.with_field("TopicProduceData", "partition_data", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, PartitionProduceDataView::decode, PartitionProduceDataView::skip).map_err(|e| e.with_field("TopicProduceData", "partition_data", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicProduceData", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("TopicProduceData", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            name: key_field,
+            partition_data,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 9 {
+            view::skip_string(buf, true).map_err(|e| e.with_field("TopicProduceData", "name", version, start - buf.remaining()))?;
+        } else {
+            view::skip_string(buf, false).map_err(|e| e.with_field("TopicProduceData", "name", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_array(buf, version, true, PartitionProduceDataView::skip).map_err(|e| e.with_field("TopicProduceData", "partition_data", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, PartitionProduceDataView::skip).map_err(|e| e.with_field("TopicProduceData", "partition_data", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("TopicProduceData", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the `name` of the struct at `version`, skipping over its other fields.
+    pub fn decode_key<B: ByteBuf>(buf: &mut B, version: i16) -> Result<super::TopicName, DecodeError> {
+        let start = buf.remaining();
+        let key_field = if version >= 9 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicProduceData", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("TopicProduceData", "name", version, start - buf.remaining()))?
+        };
+        if version >= 9 {
+            view::skip_array(buf, version, true, PartitionProduceDataView::skip).map_err(|e| e.with_field("TopicProduceData", "partition_data", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, PartitionProduceDataView::skip).map_err(|e| e.with_field("TopicProduceData", "partition_data", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("TopicProduceData", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(key_field)
+    }
+}
+
+impl Default for TopicProduceDataView {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            partition_data: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ProduceRequest {
     /// The transactional ID, or null if the producer is not transactional.
     /// 
     /// Supported API versions: 3-9
+    /// May be `None` in versions: 3-9
     pub transactional_id: Option<super::TransactionalId>,
 
     /// The number of acknowledgments the producer requires the leader to have received before considering a request complete. Allowed values: 0 for no acknowledgments, 1 for only the leader and -1 for the full ISR.
@@ -270,7 +578,7 @@ pub struct ProduceRequest {
     pub topic_data: indexmap::IndexMap<super::TopicName, TopicProduceData>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ProduceRequest {
@@ -283,65 +591,75 @@ impl Builder for ProduceRequest {
 
 impl Encodable for ProduceRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ProduceRequest", version));
+        }
         if version >= 3 {
             if version >= 9 {
-                types::CompactString.encode(buf, &self.transactional_id)?;
+                types::CompactString.encode(buf, &self.transactional_id).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version))?;
             } else {
-                types::String.encode(buf, &self.transactional_id)?;
+                types::String.encode(buf, &self.transactional_id).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version))?;
             }
         } else {
             if !self.transactional_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ProduceRequest", "transactional_id", version));
             }
         }
-        types::Int16.encode(buf, &self.acks)?;
-        types::Int32.encode(buf, &self.timeout_ms)?;
+        types::Int16.encode(buf, &self.acks).map_err(|e| e.with_field("ProduceRequest", "acks", version))?;
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("ProduceRequest", "timeout_ms", version))?;
         if version >= 9 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topic_data)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_data).map_err(|e| e.with_field("ProduceRequest", "topic_data", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topic_data)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topic_data).map_err(|e| e.with_field("ProduceRequest", "topic_data", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ProduceRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ProduceRequest", version));
+        }
         let mut total_size = 0;
         if version >= 3 {
             if version >= 9 {
-                total_size += types::CompactString.compute_size(&self.transactional_id)?;
+                total_size += types::CompactString.compute_size(&self.transactional_id).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.transactional_id)?;
+                total_size += types::String.compute_size(&self.transactional_id).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version))?;
             }
         } else {
             if !self.transactional_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ProduceRequest", "transactional_id", version));
             }
         }
-        total_size += types::Int16.compute_size(&self.acks)?;
-        total_size += types::Int32.compute_size(&self.timeout_ms)?;
+        total_size += types::Int16.compute_size(&self.acks).map_err(|e| e.with_field("ProduceRequest", "acks", version))?;
+        total_size += types::Int32.compute_size(&self.timeout_ms).map_err(|e| e.with_field("ProduceRequest", "timeout_ms", version))?;
         if version >= 9 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topic_data)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topic_data).map_err(|e| e.with_field("ProduceRequest", "topic_data", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topic_data)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topic_data).map_err(|e| e.with_field("ProduceRequest", "topic_data", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ProduceRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -349,30 +667,33 @@ impl Encodable for ProduceRequest {
 
 impl Decodable for ProduceRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ProduceRequest", version));
+        }
+        let start = buf.remaining();
         let transactional_id = if version >= 3 {
             if version >= 9 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version, start - buf.remaining()))?
             }
         } else {
             None
         };
-        let acks = types::Int16.decode(buf)?;
-        let timeout_ms = types::Int32.decode(buf)?;
+        let acks = types::Int16.decode(buf).map_err(|e| e.with_field("ProduceRequest", "acks", version, start - buf.remaining()))?;
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("ProduceRequest", "timeout_ms", version, start - buf.remaining()))?;
         let topic_data = if version >= 9 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ProduceRequest", "topic_data", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ProduceRequest", "topic_data", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -393,7 +714,7 @@ impl Default for ProduceRequest {
             acks: 0,
             timeout_ms: 0,
             topic_data: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -402,6 +723,287 @@ impl Message for ProduceRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl ProduceRequest {
+    /// Encode the message with the elements of `topic_data` produced by an iterator of known length, in place of
+    /// the `topic_data` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topic_data<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topic_data: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, TopicProduceData)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ProduceRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topic_data = topic_data.into_iter();
+        if version >= 3 {
+            if version >= 9 {
+                types::CompactString.encode(buf, &self.transactional_id).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version))?;
+            } else {
+                types::String.encode(buf, &self.transactional_id).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version))?;
+            }
+        } else {
+            if !self.transactional_id.is_none() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("ProduceRequest", "transactional_id", version));
+            }
+        }
+        types::Int16.encode(buf, &self.acks).map_err(|e| e.with_field("ProduceRequest", "acks", version))?;
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("ProduceRequest", "timeout_ms", version))?;
+        if version >= 9 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topic_data)).map_err(|e| e.with_field("ProduceRequest", "topic_data", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(topic_data)).map_err(|e| e.with_field("ProduceRequest", "topic_data", version))?;
+        }
+        if version >= 9 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ProduceRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ProduceRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl ProduceRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.transactional_id = None;
+        }
+        for item in self.topic_data.values_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.transactional_id.is_none() {
+                invalid.push(format!("{}transactional_id", path));
+            }
+        }
+        for (i, item) in self.topic_data.values().enumerate() {
+            item.validate_into(version, &format!("{}topic_data[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ProduceRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("transactionalId".into(), JsonValue::to_json(&self.transactional_id));
+        }
+        node.insert("acks".into(), JsonValue::to_json(&self.acks));
+        node.insert("timeoutMs".into(), JsonValue::to_json(&self.timeout_ms));
+        node.insert("topicData".into(), serde_json::Value::Array(self.topic_data.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ProduceRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("transactionalId") {
+            this.transactional_id = JsonValue::from_json(value, "ProduceRequest.transactionalId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("ProduceRequest", "transactionalId", version));
+        }
+        if let Some(value) = object.get("acks") {
+            this.acks = JsonValue::from_json(value, "ProduceRequest.acks")?;
+        } else {
+            return Err(crate::json::missing("ProduceRequest", "acks", version));
+        }
+        if let Some(value) = object.get("timeoutMs") {
+            this.timeout_ms = JsonValue::from_json(value, "ProduceRequest.timeoutMs")?;
+        } else {
+            return Err(crate::json::missing("ProduceRequest", "timeoutMs", version));
+        }
+        if let Some(value) = object.get("topicData") {
+            this.topic_data = crate::json::array(value, "ProduceRequest.topicData")?.iter().map(|item| TopicProduceData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ProduceRequest", "topicData", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ProduceRequest`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-9
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProduceRequestView {
+    /// The transactional ID, or null if the producer is not transactional.
+    /// 
+    /// Supported API versions: 3-9
+    pub transactional_id: Option<super::TransactionalId>,
+
+    /// The number of acknowledgments the producer requires the leader to have received before considering a request complete. Allowed values: 0 for no acknowledgments, 1 for only the leader and -1 for the full ISR.
+    /// 
+    /// Supported API versions: 0-9
+    pub acks: i16,
+
+    /// The timeout to await a response in milliseconds.
+    /// 
+    /// Supported API versions: 0-9
+    pub timeout_ms: i32,
+
+    /// Each topic to produce to.
+    /// 
+    /// Supported API versions: 0-9
+    pub topic_data: ArrayView<TopicProduceDataView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ProduceRequestView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        if !ProduceRequest::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ProduceRequest", version));
+        }
+        let start = buf.remaining();
+        let transactional_id = if version >= 3 {
+            if version >= 9 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version, start - buf.remaining()))?
+            }
+        } else {
+            None
+        };
+        let acks = types::Int16.decode(buf).map_err(|e| e.with_field("ProduceRequest", "acks", version, start - buf.remaining()))?;
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("ProduceRequest", "timeout_ms", version, start - buf.remaining()))?;
+        let topic_data = if version >= 9 {
+            ArrayView::decode(buf, version, true, TopicProduceDataView::decode, TopicProduceDataView::skip).map_err(|e| e.with_field("ProduceRequest", "topic_data", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, TopicProduceDataView::decode, TopicProduceDataView::skip).map_err(|e| e.with_field("ProduceRequest", "topic_data", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ProduceRequest", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ProduceRequest", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            transactional_id,
+            acks,
+            timeout_ms,
+            topic_data,
+            tagged_fields,
+        })
+    }
+}
+
+impl Default for ProduceRequestView {
+    fn default() -> Self {
+        Self {
+            transactional_id: None,
+            acks: 0,
+            timeout_ms: 0,
+            topic_data: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
+/// The top-level fields of a [`ProduceRequest`], decoded with only the keys of its maps, skipping over the
+/// rest of their entries.
+///
+/// Skipping an entry still walks it, including the records it holds, so each key is only decoded once
+/// the entries before it have been received in full.
+///
+/// Valid versions: 0-9
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProduceRequestHead {
+    /// The transactional ID, or null if the producer is not transactional.
+    /// 
+    /// Supported API versions: 3-9
+    pub transactional_id: Option<super::TransactionalId>,
+
+    /// The number of acknowledgments the producer requires the leader to have received before considering a request complete. Allowed values: 0 for no acknowledgments, 1 for only the leader and -1 for the full ISR.
+    /// 
+    /// Supported API versions: 0-9
+    pub acks: i16,
+
+    /// The timeout to await a response in milliseconds.
+    /// 
+    /// Supported API versions: 0-9
+    pub timeout_ms: i32,
+
+    /// Each topic to produce to.
+    /// 
+    /// Supported API versions: 0-9
+    /// 
+    /// Only the key of each entry is decoded.
+    pub topic_data: Vec<super::TopicName>,
+
+}
+
+impl ProduceRequestHead {
+    /// Decodes the head of the message at `version`, reading no further than the keys of its last map.
+    pub fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !ProduceRequest::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ProduceRequest", version));
+        }
+        let start = buf.remaining();
+        let transactional_id = if version >= 3 {
+            if version >= 9 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("ProduceRequest", "transactional_id", version, start - buf.remaining()))?
+            }
+        } else {
+            None
+        };
+        let acks = types::Int16.decode(buf).map_err(|e| e.with_field("ProduceRequest", "acks", version, start - buf.remaining()))?;
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("ProduceRequest", "timeout_ms", version, start - buf.remaining()))?;
+        let topic_data = if version >= 9 {
+            view::decode_keys(buf, version, true, TopicProduceDataView::decode_key).map_err(|e| e.with_field("ProduceRequest", "topic_data", version, start - buf.remaining()))?
+        } else {
+            view::decode_keys(buf, version, false, TopicProduceDataView::decode_key).map_err(|e| e.with_field("ProduceRequest", "topic_data", version, start - buf.remaining()))?
+        };
+        Ok(Self {
+            transactional_id,
+            acks,
+            timeout_ms,
+            topic_data,
+        })
+    }
+}
+
 impl HeaderVersion for ProduceRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 9 {
@@ -412,3 +1014,29 @@ impl HeaderVersion for ProduceRequest {
     }
 }
 
+impl ProduceRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::ProduceResponse {
+        let mut response = super::ProduceResponse::default();
+        let mut items0 = indexmap::IndexMap::new();
+        for (key0, request0) in &self.topic_data {
+            let mut response0 = super::produce_response::TopicProduceResponse::default();
+            let mut items1 = Vec::new();
+            for request1 in &request0.partition_data {
+                let mut response1 = super::produce_response::PartitionProduceResponse::default();
+                response1.index = request1.index;
+                response1.error_code = error.code();
+                items1.push(response1);
+            }
+            response0.partition_responses = items1;
+            items0.insert(key0.clone(), response0);
+        }
+        response.responses = items0;
+        response
+    }
+}
+
@@ -5,21 +5,29 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+use bytes::Buf;
+use crate::protocol::view::{self, ArrayView};
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct BatchIndexAndErrorMessage {
     /// The batch index of the record that cause the batch to be dropped
@@ -30,10 +38,12 @@ pub struct BatchIndexAndErrorMessage {
     /// The error message of the record that caused the batch to be dropped
     /// 
     /// Supported API versions: 8-9
+    /// May be `None` in versions: 8-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub batch_index_error_message: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for BatchIndexAndErrorMessage {
@@ -46,65 +56,69 @@ impl Builder for BatchIndexAndErrorMessage {
 
 impl Encodable for BatchIndexAndErrorMessage {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 8 {
-            types::Int32.encode(buf, &self.batch_index)?;
+            types::Int32.encode(buf, &self.batch_index).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index", version))?;
         } else {
             if self.batch_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BatchIndexAndErrorMessage", "batch_index", version));
             }
         }
         if version >= 8 {
             if version >= 9 {
-                types::CompactString.encode(buf, &self.batch_index_error_message)?;
+                types::CompactString.encode(buf, &self.batch_index_error_message).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version))?;
             } else {
-                types::String.encode(buf, &self.batch_index_error_message)?;
+                types::String.encode(buf, &self.batch_index_error_message).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version))?;
             }
         } else {
             if !self.batch_index_error_message.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version));
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 8 {
-            total_size += types::Int32.compute_size(&self.batch_index)?;
+            total_size += types::Int32.compute_size(&self.batch_index).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index", version))?;
         } else {
             if self.batch_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BatchIndexAndErrorMessage", "batch_index", version));
             }
         }
         if version >= 8 {
             if version >= 9 {
-                total_size += types::CompactString.compute_size(&self.batch_index_error_message)?;
+                total_size += types::CompactString.compute_size(&self.batch_index_error_message).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version))?;
             } else {
-                total_size += types::String.compute_size(&self.batch_index_error_message)?;
+                total_size += types::String.compute_size(&self.batch_index_error_message).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version))?;
             }
         } else {
             if !self.batch_index_error_message.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version));
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -112,28 +126,28 @@ impl Encodable for BatchIndexAndErrorMessage {
 
 impl Decodable for BatchIndexAndErrorMessage {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let batch_index = if version >= 8 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index", version, start - buf.remaining()))?
         } else {
             0
         };
         let batch_index_error_message = if version >= 8 {
             if version >= 9 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version, start - buf.remaining()))?
             }
         } else {
             None
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -150,7 +164,7 @@ impl Default for BatchIndexAndErrorMessage {
         Self {
             batch_index: 0,
             batch_index_error_message: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -159,9 +173,169 @@ impl Message for BatchIndexAndErrorMessage {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl BatchIndexAndErrorMessage {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 8 {
+            self.batch_index = 0;
+        }
+        if version < 8 {
+            self.batch_index_error_message = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 8 {
+            if self.batch_index != 0 {
+                invalid.push(format!("{}batch_index", path));
+            }
+        }
+        if version < 8 {
+            if !self.batch_index_error_message.is_none() {
+                invalid.push(format!("{}batch_index_error_message", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl BatchIndexAndErrorMessage {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 8 {
+            node.insert("batchIndex".into(), JsonValue::to_json(&self.batch_index));
+        }
+        if version >= 8 {
+            node.insert("batchIndexErrorMessage".into(), JsonValue::to_json(&self.batch_index_error_message));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "BatchIndexAndErrorMessage")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("batchIndex") {
+            this.batch_index = JsonValue::from_json(value, "BatchIndexAndErrorMessage.batchIndex")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("BatchIndexAndErrorMessage", "batchIndex", version));
+        }
+        if let Some(value) = object.get("batchIndexErrorMessage") {
+            this.batch_index_error_message = JsonValue::from_json(value, "BatchIndexAndErrorMessage.batchIndexErrorMessage")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("BatchIndexAndErrorMessage", "batchIndexErrorMessage", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`BatchIndexAndErrorMessage`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-9
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchIndexAndErrorMessageView {
+    /// The batch index of the record that cause the batch to be dropped
+    /// 
+    /// Supported API versions: 8-9
+    pub batch_index: i32,
+
+    /// The error message of the record that caused the batch to be dropped
+    /// 
+    /// Supported API versions: 8-9
+    pub batch_index_error_message: Option<StrBytes>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl BatchIndexAndErrorMessageView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let batch_index = if version >= 8 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let batch_index_error_message = if version >= 8 {
+            if version >= 9 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version, start - buf.remaining()))?
+            }
+        } else {
+            None
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            batch_index,
+            batch_index_error_message,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 8 {
+            view::skip_fixed(buf, 4).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index", version, start - buf.remaining()))?;
+        }
+        if version >= 8 {
+            if version >= 9 {
+                view::skip_string(buf, true).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version, start - buf.remaining()))?;
+            } else {
+                view::skip_string(buf, false).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "batch_index_error_message", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("BatchIndexAndErrorMessage", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for BatchIndexAndErrorMessageView {
+    fn default() -> Self {
+        Self {
+            batch_index: 0,
+            batch_index_error_message: None,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct PartitionProduceResponse {
     /// The partition index.
@@ -197,10 +371,12 @@ pub struct PartitionProduceResponse {
     /// The global error message summarizing the common root cause of the records that caused the batch to be dropped
     /// 
     /// Supported API versions: 8-9
+    /// May be `None` in versions: 8-9
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for PartitionProduceResponse {
@@ -213,75 +389,79 @@ impl Builder for PartitionProduceResponse {
 
 impl Encodable for PartitionProduceResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.index)?;
-        types::Int16.encode(buf, &self.error_code)?;
-        types::Int64.encode(buf, &self.base_offset)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.index).map_err(|e| e.with_field("PartitionProduceResponse", "index", version))?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("PartitionProduceResponse", "error_code", version))?;
+        types::Int64.encode(buf, &self.base_offset).map_err(|e| e.with_field("PartitionProduceResponse", "base_offset", version))?;
         if version >= 2 {
-            types::Int64.encode(buf, &self.log_append_time_ms)?;
+            types::Int64.encode(buf, &self.log_append_time_ms).map_err(|e| e.with_field("PartitionProduceResponse", "log_append_time_ms", version))?;
         }
         if version >= 5 {
-            types::Int64.encode(buf, &self.log_start_offset)?;
+            types::Int64.encode(buf, &self.log_start_offset).map_err(|e| e.with_field("PartitionProduceResponse", "log_start_offset", version))?;
         }
         if version >= 8 {
             if version >= 9 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.record_errors)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.record_errors).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.record_errors)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.record_errors).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version))?;
             }
         }
         if version >= 8 {
             if version >= 9 {
-                types::CompactString.encode(buf, &self.error_message)?;
+                types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version))?;
             } else {
-                types::String.encode(buf, &self.error_message)?;
+                types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version))?;
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionProduceResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionProduceResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionProduceResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.index)?;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::Int64.compute_size(&self.base_offset)?;
+        total_size += types::Int32.compute_size(&self.index).map_err(|e| e.with_field("PartitionProduceResponse", "index", version))?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("PartitionProduceResponse", "error_code", version))?;
+        total_size += types::Int64.compute_size(&self.base_offset).map_err(|e| e.with_field("PartitionProduceResponse", "base_offset", version))?;
         if version >= 2 {
-            total_size += types::Int64.compute_size(&self.log_append_time_ms)?;
+            total_size += types::Int64.compute_size(&self.log_append_time_ms).map_err(|e| e.with_field("PartitionProduceResponse", "log_append_time_ms", version))?;
         }
         if version >= 5 {
-            total_size += types::Int64.compute_size(&self.log_start_offset)?;
+            total_size += types::Int64.compute_size(&self.log_start_offset).map_err(|e| e.with_field("PartitionProduceResponse", "log_start_offset", version))?;
         }
         if version >= 8 {
             if version >= 9 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.record_errors)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.record_errors).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.record_errors)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.record_errors).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version))?;
             }
         }
         if version >= 8 {
             if version >= 9 {
-                total_size += types::CompactString.compute_size(&self.error_message)?;
+                total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version))?;
             } else {
-                total_size += types::String.compute_size(&self.error_message)?;
+                total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version))?;
             }
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionProduceResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("PartitionProduceResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionProduceResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -289,45 +469,45 @@ impl Encodable for PartitionProduceResponse {
 
 impl Decodable for PartitionProduceResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let index = types::Int32.decode(buf)?;
-        let error_code = types::Int16.decode(buf)?;
-        let base_offset = types::Int64.decode(buf)?;
+        let start = buf.remaining();
+        let index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "error_code", version, start - buf.remaining()))?;
+        let base_offset = types::Int64.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "base_offset", version, start - buf.remaining()))?;
         let log_append_time_ms = if version >= 2 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "log_append_time_ms", version, start - buf.remaining()))?
         } else {
             -1
         };
         let log_start_offset = if version >= 5 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "log_start_offset", version, start - buf.remaining()))?
         } else {
             -1
         };
         let record_errors = if version >= 8 {
             if version >= 9 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let error_message = if version >= 8 {
             if version >= 9 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version, start - buf.remaining()))?
             }
         } else {
             None
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("PartitionProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -354,7 +534,7 @@ impl Default for PartitionProduceResponse {
             log_start_offset: -1,
             record_errors: Default::default(),
             error_message: None,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -363,9 +543,292 @@ impl Message for PartitionProduceResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl PartitionProduceResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.log_append_time_ms = -1;
+        }
+        if version < 5 {
+            self.log_start_offset = -1;
+        }
+        if version >= 8 {
+            for item in self.record_errors.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.record_errors = Default::default();
+        }
+        if version < 8 {
+            self.error_message = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if self.log_append_time_ms != -1 {
+                invalid.push(format!("{}log_append_time_ms", path));
+            }
+        }
+        if version < 5 {
+            if self.log_start_offset != -1 {
+                invalid.push(format!("{}log_start_offset", path));
+            }
+        }
+        if version >= 8 {
+            for (i, item) in self.record_errors.iter().enumerate() {
+                item.validate_into(version, &format!("{}record_errors[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.record_errors.is_empty() {
+                invalid.push(format!("{}record_errors", path));
+            }
+        }
+        if version < 8 {
+            if !self.error_message.is_none() {
+                invalid.push(format!("{}error_message", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionProduceResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("index".into(), JsonValue::to_json(&self.index));
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("baseOffset".into(), JsonValue::to_json(&self.base_offset));
+        if version >= 2 {
+            node.insert("logAppendTimeMs".into(), JsonValue::to_json(&self.log_append_time_ms));
+        }
+        if version >= 5 {
+            node.insert("logStartOffset".into(), JsonValue::to_json(&self.log_start_offset));
+        }
+        if version >= 8 {
+            node.insert("recordErrors".into(), serde_json::Value::Array(self.record_errors.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 8 {
+            node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionProduceResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("index") {
+            this.index = JsonValue::from_json(value, "PartitionProduceResponse.index")?;
+        } else {
+            return Err(crate::json::missing("PartitionProduceResponse", "index", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "PartitionProduceResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("PartitionProduceResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("baseOffset") {
+            this.base_offset = JsonValue::from_json(value, "PartitionProduceResponse.baseOffset")?;
+        } else {
+            return Err(crate::json::missing("PartitionProduceResponse", "baseOffset", version));
+        }
+        if let Some(value) = object.get("logAppendTimeMs") {
+            this.log_append_time_ms = JsonValue::from_json(value, "PartitionProduceResponse.logAppendTimeMs")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("PartitionProduceResponse", "logAppendTimeMs", version));
+        }
+        if let Some(value) = object.get("logStartOffset") {
+            this.log_start_offset = JsonValue::from_json(value, "PartitionProduceResponse.logStartOffset")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("PartitionProduceResponse", "logStartOffset", version));
+        }
+        if let Some(value) = object.get("recordErrors") {
+            this.record_errors = crate::json::array(value, "PartitionProduceResponse.recordErrors")?.iter().map(|item| BatchIndexAndErrorMessage::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("PartitionProduceResponse", "recordErrors", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "PartitionProduceResponse.errorMessage")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("PartitionProduceResponse", "errorMessage", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`PartitionProduceResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-9
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionProduceResponseView {
+    /// The partition index.
+    /// 
+    /// Supported API versions: 0-9
+    pub index: i32,
+
+    /// The error code, or 0 if there was no error.
+    /// 
+    /// Supported API versions: 0-9
+    pub error_code: i16,
+
+    /// The base offset.
+    /// 
+    /// Supported API versions: 0-9
+    pub base_offset: i64,
+
+    /// The timestamp returned by broker after appending the messages. If CreateTime is used for the topic, the timestamp will be -1.  If LogAppendTime is used for the topic, the timestamp will be the broker local time when the messages are appended.
+    /// 
+    /// Supported API versions: 2-9
+    pub log_append_time_ms: i64,
+
+    /// The log start offset.
+    /// 
+    /// Supported API versions: 5-9
+    pub log_start_offset: i64,
+
+    /// The batch indices of records that caused the batch to be dropped
+    /// 
+    /// Supported API versions: 8-9
+    pub record_errors: ArrayView<BatchIndexAndErrorMessageView>,
+
+    /// The global error message summarizing the common root cause of the records that caused the batch to be dropped
+    /// 
+    /// Supported API versions: 8-9
+    pub error_message: Option<StrBytes>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl PartitionProduceResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let index = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "index", version, start - buf.remaining()))?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "error_code", version, start - buf.remaining()))?;
+        let base_offset = types::Int64.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "base_offset", version, start - buf.remaining()))?;
+        let log_append_time_ms = if version >= 2 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "log_append_time_ms", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let log_start_offset = if version >= 5 {
+            types::Int64.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "log_start_offset", version, start - buf.remaining()))?
+        } else {
+            -1
+        };
+        let record_errors = if version >= 8 {
+            if version >= 9 {
+                ArrayView::decode(buf, version, true, BatchIndexAndErrorMessageView::decode, BatchIndexAndErrorMessageView::skip).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version, start - buf.remaining()))?
+            } else {
+                ArrayView::decode(buf, version, false, BatchIndexAndErrorMessageView::decode, BatchIndexAndErrorMessageView::skip).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version, start - buf.remaining()))?
+            }
+        } else {
+            Default::default()
+        };
+        let error_message = if version >= 8 {
+            if version >= 9 {
+                types::CompactString.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version, start - buf.remaining()))?
+            } else {
+                types::String.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version, start - buf.remaining()))?
+            }
+        } else {
+            None
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("PartitionProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            index,
+            error_code,
+            base_offset,
+            log_append_time_ms,
+            log_start_offset,
+            record_errors,
+            error_message,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        view::skip_fixed(buf, 4).map_err(|e| e.with_field("PartitionProduceResponse", "index", version, start - buf.remaining()))?;
+        view::skip_fixed(buf, 2).map_err(|e| e.with_field("PartitionProduceResponse", "error_code", version, start - buf.remaining()))?;
+        view::skip_fixed(buf, 8).map_err(|e| e.with_field("PartitionProduceResponse", "base_offset", version, start - buf.remaining()))?;
+        if version >= 2 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("PartitionProduceResponse", "log_append_time_ms", version, start - buf.remaining()))?;
+        }
+        if version >= 5 {
+            view::skip_fixed(buf, 8).map_err(|e| e.with_field("PartitionProduceResponse", "log_start_offset", version, start - buf.remaining()))?;
+        }
+        if version >= 8 {
+            if version >= 9 {
+                view::skip_array(buf, version, true, BatchIndexAndErrorMessageView::skip).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version, start - buf.remaining()))?;
+            } else {
+                view::skip_array(buf, version, false, BatchIndexAndErrorMessageView::skip).map_err(|e| e.with_field("PartitionProduceResponse", "record_errors", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 8 {
+            if version >= 9 {
+                view::skip_string(buf, true).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version, start - buf.remaining()))?;
+            } else {
+                view::skip_string(buf, false).map_err(|e| e.with_field("PartitionProduceResponse", "error_message", version, start - buf.remaining()))?;
+            }
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("PartitionProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for PartitionProduceResponseView {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            error_code: 0,
+            base_offset: 0,
+            log_append_time_ms: -1,
+            log_start_offset: -1,
+            record_errors: Default::default(),
+            error_message: None,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TopicProduceResponse {
     /// Each partition that we produced to within the topic.
@@ -374,7 +837,7 @@ pub struct TopicProduceResponse {
     pub partition_responses: Vec<PartitionProduceResponse>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TopicProduceResponse {
@@ -388,49 +851,53 @@ impl Builder for TopicProduceResponse {
 impl MapEncodable for TopicProduceResponse {
     type Key = super::TopicName;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 9 {
-            types::CompactString.encode(buf, key)?;
+            types::CompactString.encode(buf, key).map_err(|e| e.with_field("TopicProduceResponse", "name", version))?;
         } else {
-            types::String.encode(buf, key)?;
+            types::String.encode(buf, key).map_err(|e| e.with_field("TopicProduceResponse", "name", version))?;
         }
         if version >= 9 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partition_responses)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partition_responses).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partition_responses)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partition_responses).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicProduceResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TopicProduceResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TopicProduceResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 9 {
-            total_size += types::CompactString.compute_size(key)?;
+            total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("TopicProduceResponse", "name", version))?;
         } else {
-            total_size += types::String.compute_size(key)?;
+            total_size += types::String.compute_size(key).map_err(|e| e.with_field("TopicProduceResponse", "name", version))?;
         }
         if version >= 9 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partition_responses)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partition_responses).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partition_responses)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partition_responses).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TopicProduceResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TopicProduceResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TopicProduceResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -439,24 +906,24 @@ impl MapEncodable for TopicProduceResponse {
 impl MapDecodable for TopicProduceResponse {
     type Key = super::TopicName;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
         let key_field = if version >= 9 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "name", version, start - buf.remaining()))?
         };
         let partition_responses = if version >= 9 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TopicProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -471,7 +938,7 @@ impl Default for TopicProduceResponse {
     fn default() -> Self {
         Self {
             partition_responses: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -480,9 +947,171 @@ impl Message for TopicProduceResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl TopicProduceResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partition_responses.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partition_responses.iter().enumerate() {
+            item.validate_into(version, &format!("{}partition_responses[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TopicProduceResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &super::TopicName, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &super::TopicName, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("partitionResponses".into(), serde_json::Value::Array(self.partition_responses.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(super::TopicName, Self), JsonError> {
+        let object = crate::json::object(node, "TopicProduceResponse")?;
+        let mut this = Self::default();
+        let mut key = super::TopicName::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "TopicProduceResponse.name")?;
+        } else {
+            return Err(crate::json::missing("TopicProduceResponse", "name", version));
+        }
+        if let Some(value) = object.get("partitionResponses") {
+            this.partition_responses = crate::json::array(value, "TopicProduceResponse.partitionResponses")?.iter().map(|item| PartitionProduceResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("TopicProduceResponse", "partitionResponses", version));
+        }
+        Ok((key, this))
+    }
+}
+
+/// A view of [`TopicProduceResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-9
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicProduceResponseView {
+    /// The topic name
+    /// 
+    /// Supported API versions: 0-9
+    pub name: super::TopicName,
+
+    /// Each partition that we produced to within the topic.
+    /// 
+    /// Supported API versions: 0-9
+    pub partition_responses: ArrayView<PartitionProduceResponseView>,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl TopicProduceResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let key_field = if version >= 9 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "name", version, start - buf.remaining()))?
+        };
+        let partition_responses = if version >= 9 {
+            ArrayView::decode(buf, version, true, PartitionProduceResponseView::decode, PartitionProduceResponseView::skip).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, PartitionProduceResponseView::decode, PartitionProduceResponseView::skip).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version, start - buf.remaining()))?
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("TopicProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            name: key_field,
+            partition_responses,
+            tagged_fields,
+        })
+    }
+
+    /// Skips over the struct at `version`, reading no more than the lengths of its fields.
+    pub fn skip<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+        let start = buf.remaining();
+        if version >= 9 {
+            view::skip_string(buf, true).map_err(|e| e.with_field("TopicProduceResponse", "name", version, start - buf.remaining()))?;
+        } else {
+            view::skip_string(buf, false).map_err(|e| e.with_field("TopicProduceResponse", "name", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_array(buf, version, true, PartitionProduceResponseView::skip).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, PartitionProduceResponseView::skip).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("TopicProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the `name` of the struct at `version`, skipping over its other fields.
+    pub fn decode_key<B: ByteBuf>(buf: &mut B, version: i16) -> Result<super::TopicName, DecodeError> {
+        let start = buf.remaining();
+        let key_field = if version >= 9 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "name", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("TopicProduceResponse", "name", version, start - buf.remaining()))?
+        };
+        if version >= 9 {
+            view::skip_array(buf, version, true, PartitionProduceResponseView::skip).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version, start - buf.remaining()))?;
+        } else {
+            view::skip_array(buf, version, false, PartitionProduceResponseView::skip).map_err(|e| e.with_field("TopicProduceResponse", "partition_responses", version, start - buf.remaining()))?;
+        }
+        if version >= 9 {
+            view::skip_tagged_fields(buf, version).map_err(|e| e.with_field("TopicProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(key_field)
+    }
+}
+
+impl Default for TopicProduceResponseView {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            partition_responses: Default::default(),
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 /// Valid versions: 0-9
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct ProduceResponse {
     /// Each produce response
@@ -496,7 +1125,7 @@ pub struct ProduceResponse {
     pub throttle_time_ms: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for ProduceResponse {
@@ -509,45 +1138,55 @@ impl Builder for ProduceResponse {
 
 impl Encodable for ProduceResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ProduceResponse", version));
+        }
         if version >= 9 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.responses)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.responses).map_err(|e| e.with_field("ProduceResponse", "responses", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.responses)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.responses).map_err(|e| e.with_field("ProduceResponse", "responses", version))?;
         }
         if version >= 1 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("ProduceResponse", "throttle_time_ms", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ProduceResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ProduceResponse", version));
+        }
         let mut total_size = 0;
         if version >= 9 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.responses)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.responses).map_err(|e| e.with_field("ProduceResponse", "responses", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.responses)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.responses).map_err(|e| e.with_field("ProduceResponse", "responses", version))?;
         }
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("ProduceResponse", "throttle_time_ms", version))?;
         }
         if version >= 9 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ProduceResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -555,24 +1194,27 @@ impl Encodable for ProduceResponse {
 
 impl Decodable for ProduceResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ProduceResponse", version));
+        }
+        let start = buf.remaining();
         let responses = if version >= 9 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ProduceResponse", "responses", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ProduceResponse", "responses", version, start - buf.remaining()))?
         };
         let throttle_time_ms = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("ProduceResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 9 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -589,7 +1231,7 @@ impl Default for ProduceResponse {
         Self {
             responses: Default::default(),
             throttle_time_ms: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -598,6 +1240,179 @@ impl Message for ProduceResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 9 };
 }
 
+impl ProduceResponse {
+    /// Encode the message with the elements of `responses` produced by an iterator of known length, in place of
+    /// the `responses` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_responses<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, responses: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (super::TopicName, TopicProduceResponse)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ProduceResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let responses = responses.into_iter();
+        if version >= 9 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(responses)).map_err(|e| e.with_field("ProduceResponse", "responses", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::StreamedMap(responses)).map_err(|e| e.with_field("ProduceResponse", "responses", version))?;
+        }
+        if version >= 1 {
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("ProduceResponse", "throttle_time_ms", version))?;
+        }
+        if version >= 9 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ProduceResponse", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ProduceResponse", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl ProduceResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.responses.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 1 {
+            self.throttle_time_ms = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.responses.values().enumerate() {
+            item.validate_into(version, &format!("{}responses[{}].", path, i), invalid);
+        }
+        if version < 1 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ProduceResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("responses".into(), serde_json::Value::Array(self.responses.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        if version >= 1 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ProduceResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("responses") {
+            this.responses = crate::json::array(value, "ProduceResponse.responses")?.iter().map(|item| TopicProduceResponse::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ProduceResponse", "responses", version));
+        }
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "ProduceResponse.throttleTimeMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("ProduceResponse", "throttleTimeMs", version));
+        }
+        Ok(this)
+    }
+}
+
+/// A view of [`ProduceResponse`], decoded without allocating from the buffer it borrows.
+///
+/// Valid versions: 0-9
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProduceResponseView {
+    /// Each produce response
+    /// 
+    /// Supported API versions: 0-9
+    pub responses: ArrayView<TopicProduceResponseView>,
+
+    /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
+    /// 
+    /// Supported API versions: 1-9
+    pub throttle_time_ms: i32,
+
+    /// Every tagged field as its tag and encoded value, including the known ones decoded above.
+    pub tagged_fields: ArrayView<(i32, Bytes)>,
+}
+
+impl ProduceResponseView {
+    /// Decodes a view of the struct at `version`, borrowing from `buf`.
+    pub fn decode(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        if !ProduceResponse::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ProduceResponse", version));
+        }
+        let start = buf.remaining();
+        let responses = if version >= 9 {
+            ArrayView::decode(buf, version, true, TopicProduceResponseView::decode, TopicProduceResponseView::skip).map_err(|e| e.with_field("ProduceResponse", "responses", version, start - buf.remaining()))?
+        } else {
+            ArrayView::decode(buf, version, false, TopicProduceResponseView::decode, TopicProduceResponseView::skip).map_err(|e| e.with_field("ProduceResponse", "responses", version, start - buf.remaining()))?
+        };
+        let throttle_time_ms = if version >= 1 {
+            types::Int32.decode(buf).map_err(|e| e.with_field("ProduceResponse", "throttle_time_ms", version, start - buf.remaining()))?
+        } else {
+            0
+        };
+        let mut tagged_fields = ArrayView::default();
+        if version >= 9 {
+            let num_tagged_fields: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+            tagged_fields = ArrayView::decode_elements(buf, num_tagged_fields as usize, version, view::decode_tagged_field, view::skip_tagged_field).map_err(|e| e.with_field("ProduceResponse", "tagged_fields", version, start - buf.remaining()))?;
+        }
+        Ok(Self {
+            responses,
+            throttle_time_ms,
+            tagged_fields,
+        })
+    }
+}
+
+impl Default for ProduceResponseView {
+    fn default() -> Self {
+        Self {
+            responses: Default::default(),
+            throttle_time_ms: 0,
+            tagged_fields: Default::default(),
+        }
+    }
+}
+
 impl HeaderVersion for ProduceResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 9 {
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct RequestHeader {
     /// The API key of this request.
@@ -40,10 +46,12 @@ pub struct RequestHeader {
     /// The client ID string.
     /// 
     /// Supported API versions: 1-2
+    /// May be `None` in versions: 1-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub client_id: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for RequestHeader {
@@ -56,41 +64,45 @@ impl Builder for RequestHeader {
 
 impl Encodable for RequestHeader {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.request_api_key)?;
-        types::Int16.encode(buf, &self.request_api_version)?;
-        types::Int32.encode(buf, &self.correlation_id)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int16.encode(buf, &self.request_api_key).map_err(|e| e.with_field("RequestHeader", "request_api_key", version))?;
+        types::Int16.encode(buf, &self.request_api_version).map_err(|e| e.with_field("RequestHeader", "request_api_version", version))?;
+        types::Int32.encode(buf, &self.correlation_id).map_err(|e| e.with_field("RequestHeader", "correlation_id", version))?;
         if version >= 1 {
-            types::String.encode(buf, &self.client_id)?;
+            types::String.encode(buf, &self.client_id).map_err(|e| e.with_field("RequestHeader", "client_id", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RequestHeader", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("RequestHeader", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("RequestHeader", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.request_api_key)?;
-        total_size += types::Int16.compute_size(&self.request_api_version)?;
-        total_size += types::Int32.compute_size(&self.correlation_id)?;
+        total_size += types::Int16.compute_size(&self.request_api_key).map_err(|e| e.with_field("RequestHeader", "request_api_key", version))?;
+        total_size += types::Int16.compute_size(&self.request_api_version).map_err(|e| e.with_field("RequestHeader", "request_api_version", version))?;
+        total_size += types::Int32.compute_size(&self.correlation_id).map_err(|e| e.with_field("RequestHeader", "correlation_id", version))?;
         if version >= 1 {
-            total_size += types::String.compute_size(&self.client_id)?;
+            total_size += types::String.compute_size(&self.client_id).map_err(|e| e.with_field("RequestHeader", "client_id", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RequestHeader", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("RequestHeader", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("RequestHeader", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -98,22 +110,22 @@ impl Encodable for RequestHeader {
 
 impl Decodable for RequestHeader {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let request_api_key = types::Int16.decode(buf)?;
-        let request_api_version = types::Int16.decode(buf)?;
-        let correlation_id = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let request_api_key = types::Int16.decode(buf).map_err(|e| e.with_field("RequestHeader", "request_api_key", version, start - buf.remaining()))?;
+        let request_api_version = types::Int16.decode(buf).map_err(|e| e.with_field("RequestHeader", "request_api_version", version, start - buf.remaining()))?;
+        let correlation_id = types::Int32.decode(buf).map_err(|e| e.with_field("RequestHeader", "correlation_id", version, start - buf.remaining()))?;
         let client_id = if version >= 1 {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("RequestHeader", "client_id", version, start - buf.remaining()))?
         } else {
             Some(Default::default())
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("RequestHeader", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("RequestHeader", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("RequestHeader", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("RequestHeader", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -134,7 +146,7 @@ impl Default for RequestHeader {
             request_api_version: 0,
             correlation_id: 0,
             client_id: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -143,3 +155,83 @@ impl Message for RequestHeader {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl RequestHeader {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.client_id = Some(Default::default());
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if !self.client_id.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}client_id", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl RequestHeader {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("requestApiKey".into(), JsonValue::to_json(&self.request_api_key));
+        node.insert("requestApiVersion".into(), JsonValue::to_json(&self.request_api_version));
+        node.insert("correlationId".into(), JsonValue::to_json(&self.correlation_id));
+        if version >= 1 {
+            node.insert("clientId".into(), JsonValue::to_json(&self.client_id));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "RequestHeader")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("requestApiKey") {
+            this.request_api_key = JsonValue::from_json(value, "RequestHeader.requestApiKey")?;
+        } else {
+            return Err(crate::json::missing("RequestHeader", "requestApiKey", version));
+        }
+        if let Some(value) = object.get("requestApiVersion") {
+            this.request_api_version = JsonValue::from_json(value, "RequestHeader.requestApiVersion")?;
+        } else {
+            return Err(crate::json::missing("RequestHeader", "requestApiVersion", version));
+        }
+        if let Some(value) = object.get("correlationId") {
+            this.correlation_id = JsonValue::from_json(value, "RequestHeader.correlationId")?;
+        } else {
+            return Err(crate::json::missing("RequestHeader", "correlationId", version));
+        }
+        if let Some(value) = object.get("clientId") {
+            this.client_id = JsonValue::from_json(value, "RequestHeader.clientId")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("RequestHeader", "clientId", version));
+        }
+        Ok(this)
+    }
+}
+
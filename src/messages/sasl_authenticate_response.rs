@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-2
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct SaslAuthenticateResponse {
     /// The error code, or 0 if there was no error.
@@ -30,6 +36,8 @@ pub struct SaslAuthenticateResponse {
     /// The error message, or null if there was no error.
     /// 
     /// Supported API versions: 0-2
+    /// May be `None` in versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub error_message: Option<StrBytes>,
 
     /// The SASL authentication bytes from the server, as defined by the SASL mechanism.
@@ -43,7 +51,7 @@ pub struct SaslAuthenticateResponse {
     pub session_lifetime_ms: i64,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for SaslAuthenticateResponse {
@@ -56,57 +64,67 @@ impl Builder for SaslAuthenticateResponse {
 
 impl Encodable for SaslAuthenticateResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SaslAuthenticateResponse", version));
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_code", version))?;
         if version >= 2 {
-            types::CompactString.encode(buf, &self.error_message)?;
+            types::CompactString.encode(buf, &self.error_message).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_message", version))?;
         } else {
-            types::String.encode(buf, &self.error_message)?;
+            types::String.encode(buf, &self.error_message).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_message", version))?;
         }
         if version >= 2 {
-            types::CompactBytes.encode(buf, &self.auth_bytes)?;
+            types::CompactBytes.encode(buf, &self.auth_bytes).map_err(|e| e.with_field("SaslAuthenticateResponse", "auth_bytes", version))?;
         } else {
-            types::Bytes.encode(buf, &self.auth_bytes)?;
+            types::Bytes.encode(buf, &self.auth_bytes).map_err(|e| e.with_field("SaslAuthenticateResponse", "auth_bytes", version))?;
         }
         if version >= 1 {
-            types::Int64.encode(buf, &self.session_lifetime_ms)?;
+            types::Int64.encode(buf, &self.session_lifetime_ms).map_err(|e| e.with_field("SaslAuthenticateResponse", "session_lifetime_ms", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SaslAuthenticateResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_code", version))?;
         if version >= 2 {
-            total_size += types::CompactString.compute_size(&self.error_message)?;
+            total_size += types::CompactString.compute_size(&self.error_message).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_message", version))?;
         } else {
-            total_size += types::String.compute_size(&self.error_message)?;
+            total_size += types::String.compute_size(&self.error_message).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_message", version))?;
         }
         if version >= 2 {
-            total_size += types::CompactBytes.compute_size(&self.auth_bytes)?;
+            total_size += types::CompactBytes.compute_size(&self.auth_bytes).map_err(|e| e.with_field("SaslAuthenticateResponse", "auth_bytes", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.auth_bytes)?;
+            total_size += types::Bytes.compute_size(&self.auth_bytes).map_err(|e| e.with_field("SaslAuthenticateResponse", "auth_bytes", version))?;
         }
         if version >= 1 {
-            total_size += types::Int64.compute_size(&self.session_lifetime_ms)?;
+            total_size += types::Int64.compute_size(&self.session_lifetime_ms).map_err(|e| e.with_field("SaslAuthenticateResponse", "session_lifetime_ms", version))?;
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -114,30 +132,33 @@ impl Encodable for SaslAuthenticateResponse {
 
 impl Decodable for SaslAuthenticateResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("SaslAuthenticateResponse", version));
+        }
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_code", version, start - buf.remaining()))?;
         let error_message = if version >= 2 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_message", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "error_message", version, start - buf.remaining()))?
         };
         let auth_bytes = if version >= 2 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "auth_bytes", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "auth_bytes", version, start - buf.remaining()))?
         };
         let session_lifetime_ms = if version >= 1 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "session_lifetime_ms", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("SaslAuthenticateResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -158,7 +179,7 @@ impl Default for SaslAuthenticateResponse {
             error_message: Some(Default::default()),
             auth_bytes: Default::default(),
             session_lifetime_ms: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -167,6 +188,86 @@ impl Message for SaslAuthenticateResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
 }
 
+impl SaslAuthenticateResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.session_lifetime_ms = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.session_lifetime_ms != 0 {
+                invalid.push(format!("{}session_lifetime_ms", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl SaslAuthenticateResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("errorMessage".into(), JsonValue::to_json(&self.error_message));
+        node.insert("authBytes".into(), JsonValue::to_json(&self.auth_bytes));
+        if version >= 1 {
+            node.insert("sessionLifetimeMs".into(), JsonValue::to_json(&self.session_lifetime_ms));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "SaslAuthenticateResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "SaslAuthenticateResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("SaslAuthenticateResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("errorMessage") {
+            this.error_message = JsonValue::from_json(value, "SaslAuthenticateResponse.errorMessage")?;
+        } else {
+            return Err(crate::json::missing("SaslAuthenticateResponse", "errorMessage", version));
+        }
+        if let Some(value) = object.get("authBytes") {
+            this.auth_bytes = JsonValue::from_json(value, "SaslAuthenticateResponse.authBytes")?;
+        } else {
+            return Err(crate::json::missing("SaslAuthenticateResponse", "authBytes", version));
+        }
+        if let Some(value) = object.get("sessionLifetimeMs") {
+            this.session_lifetime_ms = JsonValue::from_json(value, "SaslAuthenticateResponse.sessionLifetimeMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("SaslAuthenticateResponse", "sessionLifetimeMs", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for SaslAuthenticateResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
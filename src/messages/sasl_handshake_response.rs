@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct SaslHandshakeResponse {
     /// The error code, or 0 if there was no error.
@@ -30,6 +36,7 @@ pub struct SaslHandshakeResponse {
     /// The mechanisms enabled in the server.
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub mechanisms: Vec<StrBytes>,
 
 }
@@ -44,15 +51,27 @@ impl Builder for SaslHandshakeResponse {
 
 impl Encodable for SaslHandshakeResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int16.encode(buf, &self.error_code)?;
-        types::Array(types::String).encode(buf, &self.mechanisms)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SaslHandshakeResponse", version));
+        }
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("SaslHandshakeResponse", "error_code", version))?;
+        types::Array(types::String).encode(buf, &self.mechanisms).map_err(|e| e.with_field("SaslHandshakeResponse", "mechanisms", version))?;
 
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SaslHandshakeResponse", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int16.compute_size(&self.error_code)?;
-        total_size += types::Array(types::String).compute_size(&self.mechanisms)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("SaslHandshakeResponse", "error_code", version))?;
+        total_size += types::Array(types::String).compute_size(&self.mechanisms).map_err(|e| e.with_field("SaslHandshakeResponse", "mechanisms", version))?;
 
         Ok(total_size)
     }
@@ -60,8 +79,12 @@ impl Encodable for SaslHandshakeResponse {
 
 impl Decodable for SaslHandshakeResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let error_code = types::Int16.decode(buf)?;
-        let mechanisms = types::Array(types::String).decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("SaslHandshakeResponse", version));
+        }
+        let start = buf.remaining();
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("SaslHandshakeResponse", "error_code", version, start - buf.remaining()))?;
+        let mechanisms = types::Array(types::String).decode(buf).map_err(|e| e.with_field("SaslHandshakeResponse", "mechanisms", version, start - buf.remaining()))?;
         Ok(Self {
             error_code,
             mechanisms,
@@ -82,6 +105,89 @@ impl Message for SaslHandshakeResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl SaslHandshakeResponse {
+    /// Encode the message with the elements of `mechanisms` produced by an iterator of known length, in place of
+    /// the `mechanisms` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_mechanisms<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, mechanisms: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = StrBytes>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SaslHandshakeResponse", version));
+        }
+        let cache = &SizeCache::default();
+        let mechanisms = mechanisms.into_iter();
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("SaslHandshakeResponse", "error_code", version))?;
+        types::Array(types::String).encode(buf, types::Streamed(mechanisms)).map_err(|e| e.with_field("SaslHandshakeResponse", "mechanisms", version))?;
+
+        Ok(())
+    }
+}
+
+impl SaslHandshakeResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl SaslHandshakeResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        node.insert("mechanisms".into(), JsonValue::to_json(&self.mechanisms));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "SaslHandshakeResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "SaslHandshakeResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("SaslHandshakeResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("mechanisms") {
+            this.mechanisms = JsonValue::from_json(value, "SaslHandshakeResponse.mechanisms")?;
+        } else {
+            return Err(crate::json::missing("SaslHandshakeResponse", "mechanisms", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for SaslHandshakeResponse {
     fn header_version(version: i16) -> i16 {
         0
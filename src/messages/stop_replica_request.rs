@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct StopReplicaPartitionV0 {
     /// The topic name.
@@ -33,7 +39,7 @@ pub struct StopReplicaPartitionV0 {
     pub partition_index: i32,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for StopReplicaPartitionV0 {
@@ -46,57 +52,61 @@ impl Builder for StopReplicaPartitionV0 {
 
 impl Encodable for StopReplicaPartitionV0 {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version == 0 {
-            types::String.encode(buf, &self.topic_name)?;
+            types::String.encode(buf, &self.topic_name).map_err(|e| e.with_field("StopReplicaPartitionV0", "topic_name", version))?;
         } else {
             if !self.topic_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionV0", "topic_name", version));
             }
         }
         if version == 0 {
-            types::Int32.encode(buf, &self.partition_index)?;
+            types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("StopReplicaPartitionV0", "partition_index", version))?;
         } else {
             if self.partition_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionV0", "partition_index", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version == 0 {
-            total_size += types::String.compute_size(&self.topic_name)?;
+            total_size += types::String.compute_size(&self.topic_name).map_err(|e| e.with_field("StopReplicaPartitionV0", "topic_name", version))?;
         } else {
             if !self.topic_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionV0", "topic_name", version));
             }
         }
         if version == 0 {
-            total_size += types::Int32.compute_size(&self.partition_index)?;
+            total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("StopReplicaPartitionV0", "partition_index", version))?;
         } else {
             if self.partition_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionV0", "partition_index", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -104,24 +114,24 @@ impl Encodable for StopReplicaPartitionV0 {
 
 impl Decodable for StopReplicaPartitionV0 {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version == 0 {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionV0", "topic_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let partition_index = if version == 0 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionV0", "partition_index", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("StopReplicaPartitionV0", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -138,7 +148,7 @@ impl Default for StopReplicaPartitionV0 {
         Self {
             topic_name: Default::default(),
             partition_index: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -147,9 +157,89 @@ impl Message for StopReplicaPartitionV0 {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl StopReplicaPartitionV0 {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version != 0 {
+            self.topic_name = Default::default();
+        }
+        if version != 0 {
+            self.partition_index = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version != 0 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+        if version != 0 {
+            if self.partition_index != 0 {
+                invalid.push(format!("{}partition_index", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl StopReplicaPartitionV0 {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version == 0 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        if version == 0 {
+            node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "StopReplicaPartitionV0")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "StopReplicaPartitionV0.topicName")?;
+        } else if version == 0 {
+            return Err(crate::json::missing("StopReplicaPartitionV0", "topicName", version));
+        }
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "StopReplicaPartitionV0.partitionIndex")?;
+        } else if version == 0 {
+            return Err(crate::json::missing("StopReplicaPartitionV0", "partitionIndex", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct StopReplicaTopicV1 {
     /// The topic name.
@@ -163,7 +253,7 @@ pub struct StopReplicaTopicV1 {
     pub partition_indexes: Vec<i32>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for StopReplicaTopicV1 {
@@ -176,73 +266,77 @@ impl Builder for StopReplicaTopicV1 {
 
 impl Encodable for StopReplicaTopicV1 {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 && version <= 2 {
             if version >= 2 {
-                types::CompactString.encode(buf, &self.name)?;
+                types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("StopReplicaTopicV1", "name", version))?;
             } else {
-                types::String.encode(buf, &self.name)?;
+                types::String.encode(buf, &self.name).map_err(|e| e.with_field("StopReplicaTopicV1", "name", version))?;
             }
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaTopicV1", "name", version));
             }
         }
         if version >= 1 && version <= 2 {
             if version >= 2 {
-                types::CompactArray(types::Int32).encode(buf, &self.partition_indexes)?;
+                types::CompactArray(types::Int32).encode(buf, &self.partition_indexes).map_err(|e| e.with_field("StopReplicaTopicV1", "partition_indexes", version))?;
             } else {
-                types::Array(types::Int32).encode(buf, &self.partition_indexes)?;
+                types::Array(types::Int32).encode(buf, &self.partition_indexes).map_err(|e| e.with_field("StopReplicaTopicV1", "partition_indexes", version))?;
             }
         } else {
             if !self.partition_indexes.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaTopicV1", "partition_indexes", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaTopicV1", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaTopicV1", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaTopicV1", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 && version <= 2 {
             if version >= 2 {
-                total_size += types::CompactString.compute_size(&self.name)?;
+                total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("StopReplicaTopicV1", "name", version))?;
             } else {
-                total_size += types::String.compute_size(&self.name)?;
+                total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("StopReplicaTopicV1", "name", version))?;
             }
         } else {
             if !self.name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaTopicV1", "name", version));
             }
         }
         if version >= 1 && version <= 2 {
             if version >= 2 {
-                total_size += types::CompactArray(types::Int32).compute_size(&self.partition_indexes)?;
+                total_size += types::CompactArray(types::Int32).compute_size(&self.partition_indexes).map_err(|e| e.with_field("StopReplicaTopicV1", "partition_indexes", version))?;
             } else {
-                total_size += types::Array(types::Int32).compute_size(&self.partition_indexes)?;
+                total_size += types::Array(types::Int32).compute_size(&self.partition_indexes).map_err(|e| e.with_field("StopReplicaTopicV1", "partition_indexes", version))?;
             }
         } else {
             if !self.partition_indexes.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaTopicV1", "partition_indexes", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaTopicV1", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaTopicV1", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaTopicV1", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -250,32 +344,32 @@ impl Encodable for StopReplicaTopicV1 {
 
 impl Decodable for StopReplicaTopicV1 {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 1 && version <= 2 {
             if version >= 2 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("StopReplicaTopicV1", "name", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("StopReplicaTopicV1", "name", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let partition_indexes = if version >= 1 && version <= 2 {
             if version >= 2 {
-                types::CompactArray(types::Int32).decode(buf)?
+                types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("StopReplicaTopicV1", "partition_indexes", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Int32).decode(buf)?
+                types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("StopReplicaTopicV1", "partition_indexes", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaTopicV1", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaTopicV1", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaTopicV1", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("StopReplicaTopicV1", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -292,7 +386,7 @@ impl Default for StopReplicaTopicV1 {
         Self {
             name: Default::default(),
             partition_indexes: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -301,9 +395,89 @@ impl Message for StopReplicaTopicV1 {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl StopReplicaTopicV1 {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 || version > 2 {
+            self.name = Default::default();
+        }
+        if version < 1 || version > 2 {
+            self.partition_indexes = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 || version > 2 {
+            if !self.name.is_empty() {
+                invalid.push(format!("{}name", path));
+            }
+        }
+        if version < 1 || version > 2 {
+            if !self.partition_indexes.is_empty() {
+                invalid.push(format!("{}partition_indexes", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl StopReplicaTopicV1 {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 && version <= 2 {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        if version >= 1 && version <= 2 {
+            node.insert("partitionIndexes".into(), JsonValue::to_json(&self.partition_indexes));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "StopReplicaTopicV1")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "StopReplicaTopicV1.name")?;
+        } else if version >= 1 && version <= 2 {
+            return Err(crate::json::missing("StopReplicaTopicV1", "name", version));
+        }
+        if let Some(value) = object.get("partitionIndexes") {
+            this.partition_indexes = JsonValue::from_json(value, "StopReplicaTopicV1.partitionIndexes")?;
+        } else if version >= 1 && version <= 2 {
+            return Err(crate::json::missing("StopReplicaTopicV1", "partitionIndexes", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct StopReplicaPartitionState {
     /// The partition index.
@@ -322,7 +496,7 @@ pub struct StopReplicaPartitionState {
     pub delete_partition: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for StopReplicaPartitionState {
@@ -335,71 +509,75 @@ impl Builder for StopReplicaPartitionState {
 
 impl Encodable for StopReplicaPartitionState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
-            types::Int32.encode(buf, &self.partition_index)?;
+            types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("StopReplicaPartitionState", "partition_index", version))?;
         } else {
             if self.partition_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionState", "partition_index", version));
             }
         }
         if version >= 3 {
-            types::Int32.encode(buf, &self.leader_epoch)?;
+            types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("StopReplicaPartitionState", "leader_epoch", version))?;
         } else {
             if self.leader_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionState", "leader_epoch", version));
             }
         }
         if version >= 3 {
-            types::Boolean.encode(buf, &self.delete_partition)?;
+            types::Boolean.encode(buf, &self.delete_partition).map_err(|e| e.with_field("StopReplicaPartitionState", "delete_partition", version))?;
         } else {
             if self.delete_partition {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionState", "delete_partition", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaPartitionState", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaPartitionState", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaPartitionState", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::Int32.compute_size(&self.partition_index)?;
+            total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("StopReplicaPartitionState", "partition_index", version))?;
         } else {
             if self.partition_index != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionState", "partition_index", version));
             }
         }
         if version >= 3 {
-            total_size += types::Int32.compute_size(&self.leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("StopReplicaPartitionState", "leader_epoch", version))?;
         } else {
             if self.leader_epoch != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionState", "leader_epoch", version));
             }
         }
         if version >= 3 {
-            total_size += types::Boolean.compute_size(&self.delete_partition)?;
+            total_size += types::Boolean.compute_size(&self.delete_partition).map_err(|e| e.with_field("StopReplicaPartitionState", "delete_partition", version))?;
         } else {
             if self.delete_partition {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaPartitionState", "delete_partition", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaPartitionState", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaPartitionState", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaPartitionState", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -407,29 +585,29 @@ impl Encodable for StopReplicaPartitionState {
 
 impl Decodable for StopReplicaPartitionState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let partition_index = if version >= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionState", "partition_index", version, start - buf.remaining()))?
         } else {
             0
         };
         let leader_epoch = if version >= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionState", "leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let delete_partition = if version >= 3 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionState", "delete_partition", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("StopReplicaPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -448,7 +626,7 @@ impl Default for StopReplicaPartitionState {
             partition_index: 0,
             leader_epoch: -1,
             delete_partition: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -457,9 +635,105 @@ impl Message for StopReplicaPartitionState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl StopReplicaPartitionState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.partition_index = 0;
+        }
+        if version < 3 {
+            self.leader_epoch = -1;
+        }
+        if version < 3 {
+            self.delete_partition = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.partition_index != 0 {
+                invalid.push(format!("{}partition_index", path));
+            }
+        }
+        if version < 3 {
+            if self.leader_epoch != -1 {
+                invalid.push(format!("{}leader_epoch", path));
+            }
+        }
+        if version < 3 {
+            if self.delete_partition {
+                invalid.push(format!("{}delete_partition", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl StopReplicaPartitionState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        }
+        if version >= 3 {
+            node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        }
+        if version >= 3 {
+            node.insert("deletePartition".into(), JsonValue::to_json(&self.delete_partition));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "StopReplicaPartitionState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "StopReplicaPartitionState.partitionIndex")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("StopReplicaPartitionState", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "StopReplicaPartitionState.leaderEpoch")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("StopReplicaPartitionState", "leaderEpoch", version));
+        }
+        if let Some(value) = object.get("deletePartition") {
+            this.delete_partition = JsonValue::from_json(value, "StopReplicaPartitionState.deletePartition")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("StopReplicaPartitionState", "deletePartition", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct StopReplicaTopicState {
     /// The topic name.
@@ -473,7 +747,7 @@ pub struct StopReplicaTopicState {
     pub partition_states: Vec<StopReplicaPartitionState>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for StopReplicaTopicState {
@@ -486,57 +760,61 @@ impl Builder for StopReplicaTopicState {
 
 impl Encodable for StopReplicaTopicState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
-            types::CompactString.encode(buf, &self.topic_name)?;
+            types::CompactString.encode(buf, &self.topic_name).map_err(|e| e.with_field("StopReplicaTopicState", "topic_name", version))?;
         } else {
             if !self.topic_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaTopicState", "topic_name", version));
             }
         }
         if version >= 3 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partition_states)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partition_states).map_err(|e| e.with_field("StopReplicaTopicState", "partition_states", version))?;
         } else {
             if !self.partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaTopicState", "partition_states", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaTopicState", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaTopicState", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaTopicState", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.topic_name)?;
+            total_size += types::CompactString.compute_size(&self.topic_name).map_err(|e| e.with_field("StopReplicaTopicState", "topic_name", version))?;
         } else {
             if !self.topic_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaTopicState", "topic_name", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partition_states)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partition_states).map_err(|e| e.with_field("StopReplicaTopicState", "partition_states", version))?;
         } else {
             if !self.partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaTopicState", "partition_states", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaTopicState", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaTopicState", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaTopicState", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -544,24 +822,24 @@ impl Encodable for StopReplicaTopicState {
 
 impl Decodable for StopReplicaTopicState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("StopReplicaTopicState", "topic_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let partition_states = if version >= 3 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("StopReplicaTopicState", "partition_states", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("StopReplicaTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -578,7 +856,7 @@ impl Default for StopReplicaTopicState {
         Self {
             topic_name: Default::default(),
             partition_states: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -587,9 +865,97 @@ impl Message for StopReplicaTopicState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl StopReplicaTopicState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.topic_name = Default::default();
+        }
+        if version >= 3 {
+            for item in self.partition_states.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.partition_states = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+        if version >= 3 {
+            for (i, item) in self.partition_states.iter().enumerate() {
+                item.validate_into(version, &format!("{}partition_states[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.partition_states.is_empty() {
+                invalid.push(format!("{}partition_states", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl StopReplicaTopicState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 3 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        if version >= 3 {
+            node.insert("partitionStates".into(), serde_json::Value::Array(self.partition_states.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "StopReplicaTopicState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "StopReplicaTopicState.topicName")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("StopReplicaTopicState", "topicName", version));
+        }
+        if let Some(value) = object.get("partitionStates") {
+            this.partition_states = crate::json::array(value, "StopReplicaTopicState.partitionStates")?.iter().map(|item| StopReplicaPartitionState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("StopReplicaTopicState", "partitionStates", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-4
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct StopReplicaRequest {
     /// The controller id.
@@ -633,7 +999,7 @@ pub struct StopReplicaRequest {
     pub topic_states: Vec<StopReplicaTopicState>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for StopReplicaRequest {
@@ -646,117 +1012,127 @@ impl Builder for StopReplicaRequest {
 
 impl Encodable for StopReplicaRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.controller_id)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("StopReplicaRequest", version));
+        }
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("StopReplicaRequest", "controller_id", version))?;
         if version >= 4 {
-            types::Boolean.encode(buf, &self.is_k_raft_controller)?;
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("StopReplicaRequest", "is_k_raft_controller", version))?;
         } else {
             if self.is_k_raft_controller {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "is_k_raft_controller", version));
             }
         }
-        types::Int32.encode(buf, &self.controller_epoch)?;
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("StopReplicaRequest", "controller_epoch", version))?;
         if version >= 1 {
-            types::Int64.encode(buf, &self.broker_epoch)?;
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("StopReplicaRequest", "broker_epoch", version))?;
         }
         if version <= 2 {
-            types::Boolean.encode(buf, &self.delete_partitions)?;
+            types::Boolean.encode(buf, &self.delete_partitions).map_err(|e| e.with_field("StopReplicaRequest", "delete_partitions", version))?;
         } else {
             if self.delete_partitions {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "delete_partitions", version));
             }
         }
         if version == 0 {
-            types::Array(types::Struct { version }).encode(buf, &self.ungrouped_partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partitions).map_err(|e| e.with_field("StopReplicaRequest", "ungrouped_partitions", version))?;
         } else {
             if !self.ungrouped_partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "ungrouped_partitions", version));
             }
         }
         if version >= 1 && version <= 2 {
             if version >= 2 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
             }
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topics", version));
             }
         }
         if version >= 3 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topic_states)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("StopReplicaRequest", "topic_states", version))?;
         } else {
             if !self.topic_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topic_states", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("StopReplicaRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.controller_id)?;
+        total_size += types::Int32.compute_size(&self.controller_id).map_err(|e| e.with_field("StopReplicaRequest", "controller_id", version))?;
         if version >= 4 {
-            total_size += types::Boolean.compute_size(&self.is_k_raft_controller)?;
+            total_size += types::Boolean.compute_size(&self.is_k_raft_controller).map_err(|e| e.with_field("StopReplicaRequest", "is_k_raft_controller", version))?;
         } else {
             if self.is_k_raft_controller {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "is_k_raft_controller", version));
             }
         }
-        total_size += types::Int32.compute_size(&self.controller_epoch)?;
+        total_size += types::Int32.compute_size(&self.controller_epoch).map_err(|e| e.with_field("StopReplicaRequest", "controller_epoch", version))?;
         if version >= 1 {
-            total_size += types::Int64.compute_size(&self.broker_epoch)?;
+            total_size += types::Int64.compute_size(&self.broker_epoch).map_err(|e| e.with_field("StopReplicaRequest", "broker_epoch", version))?;
         }
         if version <= 2 {
-            total_size += types::Boolean.compute_size(&self.delete_partitions)?;
+            total_size += types::Boolean.compute_size(&self.delete_partitions).map_err(|e| e.with_field("StopReplicaRequest", "delete_partitions", version))?;
         } else {
             if self.delete_partitions {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "delete_partitions", version));
             }
         }
         if version == 0 {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.ungrouped_partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.ungrouped_partitions).map_err(|e| e.with_field("StopReplicaRequest", "ungrouped_partitions", version))?;
         } else {
             if !self.ungrouped_partitions.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "ungrouped_partitions", version));
             }
         }
         if version >= 1 && version <= 2 {
             if version >= 2 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
             }
         } else {
             if !self.topics.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topics", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topic_states)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topic_states).map_err(|e| e.with_field("StopReplicaRequest", "topic_states", version))?;
         } else {
             if !self.topic_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topic_states", version));
             }
         }
         if version >= 2 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -764,50 +1140,53 @@ impl Encodable for StopReplicaRequest {
 
 impl Decodable for StopReplicaRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let controller_id = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("StopReplicaRequest", version));
+        }
+        let start = buf.remaining();
+        let controller_id = types::Int32.decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "controller_id", version, start - buf.remaining()))?;
         let is_k_raft_controller = if version >= 4 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "is_k_raft_controller", version, start - buf.remaining()))?
         } else {
             false
         };
-        let controller_epoch = types::Int32.decode(buf)?;
+        let controller_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "controller_epoch", version, start - buf.remaining()))?;
         let broker_epoch = if version >= 1 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "broker_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let delete_partitions = if version <= 2 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "delete_partitions", version, start - buf.remaining()))?
         } else {
             false
         };
         let ungrouped_partitions = if version == 0 {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "ungrouped_partitions", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topics = if version >= 1 && version <= 2 {
             if version >= 2 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "topics", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "topics", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let topic_states = if version >= 3 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "topic_states", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 2 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -836,7 +1215,7 @@ impl Default for StopReplicaRequest {
             ungrouped_partitions: Default::default(),
             topics: Default::default(),
             topic_states: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -845,6 +1224,405 @@ impl Message for StopReplicaRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 4 };
 }
 
+impl StopReplicaRequest {
+    /// Encode the message with the elements of `ungrouped_partitions` produced by an iterator of known length, in place of
+    /// the `ungrouped_partitions` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_ungrouped_partitions<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, ungrouped_partitions: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = StopReplicaPartitionV0>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("StopReplicaRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let ungrouped_partitions = ungrouped_partitions.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("StopReplicaRequest", "controller_id", version))?;
+        if version >= 4 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("StopReplicaRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("StopReplicaRequest", "controller_epoch", version))?;
+        if version >= 1 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("StopReplicaRequest", "broker_epoch", version))?;
+        }
+        if version <= 2 {
+            types::Boolean.encode(buf, &self.delete_partitions).map_err(|e| e.with_field("StopReplicaRequest", "delete_partitions", version))?;
+        } else {
+            if self.delete_partitions {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "delete_partitions", version));
+            }
+        }
+        if version == 0 {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(ungrouped_partitions)).map_err(|e| e.with_field("StopReplicaRequest", "ungrouped_partitions", version))?;
+        } else {
+            if ungrouped_partitions.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "ungrouped_partitions", version));
+            }
+        }
+        if version >= 1 && version <= 2 {
+            if version >= 2 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
+            }
+        } else {
+            if !self.topics.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topics", version));
+            }
+        }
+        if version >= 3 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("StopReplicaRequest", "topic_states", version))?;
+        } else {
+            if !self.topic_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topic_states", version));
+            }
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = StopReplicaTopicV1>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("StopReplicaRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("StopReplicaRequest", "controller_id", version))?;
+        if version >= 4 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("StopReplicaRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("StopReplicaRequest", "controller_epoch", version))?;
+        if version >= 1 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("StopReplicaRequest", "broker_epoch", version))?;
+        }
+        if version <= 2 {
+            types::Boolean.encode(buf, &self.delete_partitions).map_err(|e| e.with_field("StopReplicaRequest", "delete_partitions", version))?;
+        } else {
+            if self.delete_partitions {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "delete_partitions", version));
+            }
+        }
+        if version == 0 {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partitions).map_err(|e| e.with_field("StopReplicaRequest", "ungrouped_partitions", version))?;
+        } else {
+            if !self.ungrouped_partitions.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "ungrouped_partitions", version));
+            }
+        }
+        if version >= 1 && version <= 2 {
+            if version >= 2 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
+            }
+        } else {
+            if topics.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topics", version));
+            }
+        }
+        if version >= 3 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("StopReplicaRequest", "topic_states", version))?;
+        } else {
+            if !self.topic_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topic_states", version));
+            }
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `topic_states` produced by an iterator of known length, in place of
+    /// the `topic_states` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topic_states<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topic_states: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = StopReplicaTopicState>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("StopReplicaRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topic_states = topic_states.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("StopReplicaRequest", "controller_id", version))?;
+        if version >= 4 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("StopReplicaRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("StopReplicaRequest", "controller_epoch", version))?;
+        if version >= 1 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("StopReplicaRequest", "broker_epoch", version))?;
+        }
+        if version <= 2 {
+            types::Boolean.encode(buf, &self.delete_partitions).map_err(|e| e.with_field("StopReplicaRequest", "delete_partitions", version))?;
+        } else {
+            if self.delete_partitions {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "delete_partitions", version));
+            }
+        }
+        if version == 0 {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partitions).map_err(|e| e.with_field("StopReplicaRequest", "ungrouped_partitions", version))?;
+        } else {
+            if !self.ungrouped_partitions.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "ungrouped_partitions", version));
+            }
+        }
+        if version >= 1 && version <= 2 {
+            if version >= 2 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("StopReplicaRequest", "topics", version))?;
+            }
+        } else {
+            if !self.topics.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topics", version));
+            }
+        }
+        if version >= 3 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topic_states)).map_err(|e| e.with_field("StopReplicaRequest", "topic_states", version))?;
+        } else {
+            if topic_states.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("StopReplicaRequest", "topic_states", version));
+            }
+        }
+        if version >= 2 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("StopReplicaRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("StopReplicaRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl StopReplicaRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 4 {
+            self.is_k_raft_controller = false;
+        }
+        if version < 1 {
+            self.broker_epoch = -1;
+        }
+        if version > 2 {
+            self.delete_partitions = false;
+        }
+        if version == 0 {
+            for item in self.ungrouped_partitions.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.ungrouped_partitions = Default::default();
+        }
+        if version >= 1 && version <= 2 {
+            for item in self.topics.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topics = Default::default();
+        }
+        if version >= 3 {
+            for item in self.topic_states.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topic_states = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 4 {
+            if self.is_k_raft_controller {
+                invalid.push(format!("{}is_k_raft_controller", path));
+            }
+        }
+        if version < 1 {
+            if self.broker_epoch != -1 {
+                invalid.push(format!("{}broker_epoch", path));
+            }
+        }
+        if version > 2 {
+            if self.delete_partitions {
+                invalid.push(format!("{}delete_partitions", path));
+            }
+        }
+        if version == 0 {
+            for (i, item) in self.ungrouped_partitions.iter().enumerate() {
+                item.validate_into(version, &format!("{}ungrouped_partitions[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.ungrouped_partitions.is_empty() {
+                invalid.push(format!("{}ungrouped_partitions", path));
+            }
+        }
+        if version >= 1 && version <= 2 {
+            for (i, item) in self.topics.iter().enumerate() {
+                item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topics.is_empty() {
+                invalid.push(format!("{}topics", path));
+            }
+        }
+        if version >= 3 {
+            for (i, item) in self.topic_states.iter().enumerate() {
+                item.validate_into(version, &format!("{}topic_states[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topic_states.is_empty() {
+                invalid.push(format!("{}topic_states", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl StopReplicaRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("controllerId".into(), JsonValue::to_json(&self.controller_id));
+        if version >= 4 {
+            node.insert("isKRaftController".into(), JsonValue::to_json(&self.is_k_raft_controller));
+        }
+        node.insert("controllerEpoch".into(), JsonValue::to_json(&self.controller_epoch));
+        if version >= 1 {
+            node.insert("brokerEpoch".into(), JsonValue::to_json(&self.broker_epoch));
+        }
+        if version <= 2 {
+            node.insert("deletePartitions".into(), JsonValue::to_json(&self.delete_partitions));
+        }
+        if version == 0 {
+            node.insert("ungroupedPartitions".into(), serde_json::Value::Array(self.ungrouped_partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 1 && version <= 2 {
+            node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 3 {
+            node.insert("topicStates".into(), serde_json::Value::Array(self.topic_states.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "StopReplicaRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("controllerId") {
+            this.controller_id = JsonValue::from_json(value, "StopReplicaRequest.controllerId")?;
+        } else {
+            return Err(crate::json::missing("StopReplicaRequest", "controllerId", version));
+        }
+        if let Some(value) = object.get("isKRaftController") {
+            this.is_k_raft_controller = JsonValue::from_json(value, "StopReplicaRequest.isKRaftController")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("StopReplicaRequest", "isKRaftController", version));
+        }
+        if let Some(value) = object.get("controllerEpoch") {
+            this.controller_epoch = JsonValue::from_json(value, "StopReplicaRequest.controllerEpoch")?;
+        } else {
+            return Err(crate::json::missing("StopReplicaRequest", "controllerEpoch", version));
+        }
+        if let Some(value) = object.get("brokerEpoch") {
+            this.broker_epoch = JsonValue::from_json(value, "StopReplicaRequest.brokerEpoch")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("StopReplicaRequest", "brokerEpoch", version));
+        }
+        if let Some(value) = object.get("deletePartitions") {
+            this.delete_partitions = JsonValue::from_json(value, "StopReplicaRequest.deletePartitions")?;
+        } else if version <= 2 {
+            return Err(crate::json::missing("StopReplicaRequest", "deletePartitions", version));
+        }
+        if let Some(value) = object.get("ungroupedPartitions") {
+            this.ungrouped_partitions = crate::json::array(value, "StopReplicaRequest.ungroupedPartitions")?.iter().map(|item| StopReplicaPartitionV0::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version == 0 {
+            return Err(crate::json::missing("StopReplicaRequest", "ungroupedPartitions", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "StopReplicaRequest.topics")?.iter().map(|item| StopReplicaTopicV1::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 1 && version <= 2 {
+            return Err(crate::json::missing("StopReplicaRequest", "topics", version));
+        }
+        if let Some(value) = object.get("topicStates") {
+            this.topic_states = crate::json::array(value, "StopReplicaRequest.topicStates")?.iter().map(|item| StopReplicaTopicState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("StopReplicaRequest", "topicStates", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for StopReplicaRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 2 {
@@ -855,3 +1633,41 @@ impl HeaderVersion for StopReplicaRequest {
     }
 }
 
+impl StopReplicaRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::StopReplicaResponse {
+        let mut response = super::StopReplicaResponse::default();
+        response.error_code = error.code();
+        let mut items0 = Vec::new();
+        if version == 0 {
+            for request0 in &self.ungrouped_partitions {
+                let mut response0 = super::stop_replica_response::StopReplicaPartitionError::default();
+                response0.topic_name = request0.topic_name.clone();
+                response0.partition_index = request0.partition_index;
+                response0.error_code = error.code();
+                items0.push(response0);
+            }
+        } else if version >= 1 && version <= 2 {
+            for request0 in &self.topics {
+                let mut response0 = super::stop_replica_response::StopReplicaPartitionError::default();
+                response0.topic_name = request0.name.clone();
+                response0.error_code = error.code();
+                items0.push(response0);
+            }
+        } else if version >= 3 {
+            for request0 in &self.topic_states {
+                let mut response0 = super::stop_replica_response::StopReplicaPartitionError::default();
+                response0.topic_name = request0.topic_name.clone();
+                response0.error_code = error.code();
+                items0.push(response0);
+            }
+        }
+        response.partition_errors = items0;
+        response
+    }
+}
+
@@ -5,26 +5,33 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct SyncGroupRequestAssignment {
     /// The ID of the member to assign.
     /// 
     /// Supported API versions: 0-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The member assignment.
@@ -33,7 +40,7 @@ pub struct SyncGroupRequestAssignment {
     pub assignment: Bytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for SyncGroupRequestAssignment {
@@ -46,49 +53,53 @@ impl Builder for SyncGroupRequestAssignment {
 
 impl Encodable for SyncGroupRequestAssignment {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 4 {
-            types::CompactString.encode(buf, &self.member_id)?;
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("SyncGroupRequestAssignment", "member_id", version))?;
         } else {
-            types::String.encode(buf, &self.member_id)?;
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("SyncGroupRequestAssignment", "member_id", version))?;
         }
         if version >= 4 {
-            types::CompactBytes.encode(buf, &self.assignment)?;
+            types::CompactBytes.encode(buf, &self.assignment).map_err(|e| e.with_field("SyncGroupRequestAssignment", "assignment", version))?;
         } else {
-            types::Bytes.encode(buf, &self.assignment)?;
+            types::Bytes.encode(buf, &self.assignment).map_err(|e| e.with_field("SyncGroupRequestAssignment", "assignment", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.member_id)?;
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("SyncGroupRequestAssignment", "member_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.member_id)?;
+            total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("SyncGroupRequestAssignment", "member_id", version))?;
         }
         if version >= 4 {
-            total_size += types::CompactBytes.compute_size(&self.assignment)?;
+            total_size += types::CompactBytes.compute_size(&self.assignment).map_err(|e| e.with_field("SyncGroupRequestAssignment", "assignment", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.assignment)?;
+            total_size += types::Bytes.compute_size(&self.assignment).map_err(|e| e.with_field("SyncGroupRequestAssignment", "assignment", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -96,24 +107,24 @@ impl Encodable for SyncGroupRequestAssignment {
 
 impl Decodable for SyncGroupRequestAssignment {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let member_id = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SyncGroupRequestAssignment", "member_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("SyncGroupRequestAssignment", "member_id", version, start - buf.remaining()))?
         };
         let assignment = if version >= 4 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("SyncGroupRequestAssignment", "assignment", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("SyncGroupRequestAssignment", "assignment", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("SyncGroupRequestAssignment", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -130,7 +141,7 @@ impl Default for SyncGroupRequestAssignment {
         Self {
             member_id: Default::default(),
             assignment: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -139,9 +150,69 @@ impl Message for SyncGroupRequestAssignment {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl SyncGroupRequestAssignment {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl SyncGroupRequestAssignment {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        node.insert("assignment".into(), JsonValue::to_json(&self.assignment));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "SyncGroupRequestAssignment")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "SyncGroupRequestAssignment.memberId")?;
+        } else {
+            return Err(crate::json::missing("SyncGroupRequestAssignment", "memberId", version));
+        }
+        if let Some(value) = object.get("assignment") {
+            this.assignment = JsonValue::from_json(value, "SyncGroupRequestAssignment.assignment")?;
+        } else {
+            return Err(crate::json::missing("SyncGroupRequestAssignment", "assignment", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct SyncGroupRequest {
     /// The unique group identifier.
@@ -157,21 +228,28 @@ pub struct SyncGroupRequest {
     /// The member ID assigned by the group.
     /// 
     /// Supported API versions: 0-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The unique identifier of the consumer instance provided by end user.
     /// 
     /// Supported API versions: 3-5
+    /// May be `None` in versions: 3-5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_instance_id: Option<StrBytes>,
 
     /// The group protocol type.
     /// 
     /// Supported API versions: 5
+    /// May be `None` in versions: 5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_type: Option<StrBytes>,
 
     /// The group protocol name.
     /// 
     /// Supported API versions: 5
+    /// May be `None` in versions: 5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_name: Option<StrBytes>,
 
     /// Each assignment.
@@ -180,7 +258,7 @@ pub struct SyncGroupRequest {
     pub assignments: Vec<SyncGroupRequestAssignment>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for SyncGroupRequest {
@@ -193,95 +271,105 @@ impl Builder for SyncGroupRequest {
 
 impl Encodable for SyncGroupRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SyncGroupRequest", version));
+        }
         if version >= 4 {
-            types::CompactString.encode(buf, &self.group_id)?;
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("SyncGroupRequest", "group_id", version))?;
         } else {
-            types::String.encode(buf, &self.group_id)?;
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("SyncGroupRequest", "group_id", version))?;
         }
-        types::Int32.encode(buf, &self.generation_id)?;
+        types::Int32.encode(buf, &self.generation_id).map_err(|e| e.with_field("SyncGroupRequest", "generation_id", version))?;
         if version >= 4 {
-            types::CompactString.encode(buf, &self.member_id)?;
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("SyncGroupRequest", "member_id", version))?;
         } else {
-            types::String.encode(buf, &self.member_id)?;
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("SyncGroupRequest", "member_id", version))?;
         }
         if version >= 3 {
             if version >= 4 {
-                types::CompactString.encode(buf, &self.group_instance_id)?;
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("SyncGroupRequest", "group_instance_id", version))?;
             } else {
-                types::String.encode(buf, &self.group_instance_id)?;
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("SyncGroupRequest", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SyncGroupRequest", "group_instance_id", version));
             }
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.protocol_type)?;
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("SyncGroupRequest", "protocol_type", version))?;
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.protocol_name)?;
+            types::CompactString.encode(buf, &self.protocol_name).map_err(|e| e.with_field("SyncGroupRequest", "protocol_name", version))?;
         }
         if version >= 4 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.assignments)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.assignments).map_err(|e| e.with_field("SyncGroupRequest", "assignments", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.assignments)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.assignments).map_err(|e| e.with_field("SyncGroupRequest", "assignments", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SyncGroupRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SyncGroupRequest", version));
+        }
         let mut total_size = 0;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.group_id)?;
+            total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("SyncGroupRequest", "group_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.group_id)?;
+            total_size += types::String.compute_size(&self.group_id).map_err(|e| e.with_field("SyncGroupRequest", "group_id", version))?;
         }
-        total_size += types::Int32.compute_size(&self.generation_id)?;
+        total_size += types::Int32.compute_size(&self.generation_id).map_err(|e| e.with_field("SyncGroupRequest", "generation_id", version))?;
         if version >= 4 {
-            total_size += types::CompactString.compute_size(&self.member_id)?;
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("SyncGroupRequest", "member_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.member_id)?;
+            total_size += types::String.compute_size(&self.member_id).map_err(|e| e.with_field("SyncGroupRequest", "member_id", version))?;
         }
         if version >= 3 {
             if version >= 4 {
-                total_size += types::CompactString.compute_size(&self.group_instance_id)?;
+                total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("SyncGroupRequest", "group_instance_id", version))?;
             } else {
-                total_size += types::String.compute_size(&self.group_instance_id)?;
+                total_size += types::String.compute_size(&self.group_instance_id).map_err(|e| e.with_field("SyncGroupRequest", "group_instance_id", version))?;
             }
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SyncGroupRequest", "group_instance_id", version));
             }
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.protocol_type)?;
+            total_size += types::CompactString.compute_size(&self.protocol_type).map_err(|e| e.with_field("SyncGroupRequest", "protocol_type", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.protocol_name)?;
+            total_size += types::CompactString.compute_size(&self.protocol_name).map_err(|e| e.with_field("SyncGroupRequest", "protocol_name", version))?;
         }
         if version >= 4 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.assignments)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.assignments).map_err(|e| e.with_field("SyncGroupRequest", "assignments", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.assignments)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.assignments).map_err(|e| e.with_field("SyncGroupRequest", "assignments", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SyncGroupRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -289,49 +377,52 @@ impl Encodable for SyncGroupRequest {
 
 impl Decodable for SyncGroupRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("SyncGroupRequest", version));
+        }
+        let start = buf.remaining();
         let group_id = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "group_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "group_id", version, start - buf.remaining()))?
         };
-        let generation_id = types::Int32.decode(buf)?;
+        let generation_id = types::Int32.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "generation_id", version, start - buf.remaining()))?;
         let member_id = if version >= 4 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "member_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "member_id", version, start - buf.remaining()))?
         };
         let group_instance_id = if version >= 3 {
             if version >= 4 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "group_instance_id", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "group_instance_id", version, start - buf.remaining()))?
             }
         } else {
             None
         };
         let protocol_type = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "protocol_type", version, start - buf.remaining()))?
         } else {
             None
         };
         let protocol_name = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "protocol_name", version, start - buf.remaining()))?
         } else {
             None
         };
         let assignments = if version >= 4 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "assignments", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "assignments", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -358,7 +449,7 @@ impl Default for SyncGroupRequest {
             protocol_type: None,
             protocol_name: None,
             assignments: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -367,6 +458,194 @@ impl Message for SyncGroupRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl SyncGroupRequest {
+    /// Encode the message with the elements of `assignments` produced by an iterator of known length, in place of
+    /// the `assignments` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_assignments<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, assignments: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = SyncGroupRequestAssignment>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SyncGroupRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let assignments = assignments.into_iter();
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("SyncGroupRequest", "group_id", version))?;
+        } else {
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("SyncGroupRequest", "group_id", version))?;
+        }
+        types::Int32.encode(buf, &self.generation_id).map_err(|e| e.with_field("SyncGroupRequest", "generation_id", version))?;
+        if version >= 4 {
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("SyncGroupRequest", "member_id", version))?;
+        } else {
+            types::String.encode(buf, &self.member_id).map_err(|e| e.with_field("SyncGroupRequest", "member_id", version))?;
+        }
+        if version >= 3 {
+            if version >= 4 {
+                types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("SyncGroupRequest", "group_instance_id", version))?;
+            } else {
+                types::String.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("SyncGroupRequest", "group_instance_id", version))?;
+            }
+        } else {
+            if !self.group_instance_id.is_none() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("SyncGroupRequest", "group_instance_id", version));
+            }
+        }
+        if version >= 5 {
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("SyncGroupRequest", "protocol_type", version))?;
+        }
+        if version >= 5 {
+            types::CompactString.encode(buf, &self.protocol_name).map_err(|e| e.with_field("SyncGroupRequest", "protocol_name", version))?;
+        }
+        if version >= 4 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(assignments)).map_err(|e| e.with_field("SyncGroupRequest", "assignments", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(assignments)).map_err(|e| e.with_field("SyncGroupRequest", "assignments", version))?;
+        }
+        if version >= 4 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SyncGroupRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("SyncGroupRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl SyncGroupRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.group_instance_id = None;
+        }
+        if version < 5 {
+            self.protocol_type = None;
+        }
+        if version < 5 {
+            self.protocol_name = None;
+        }
+        for item in self.assignments.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if !self.group_instance_id.is_none() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+        if version < 5 {
+            if !self.protocol_type.is_none() {
+                invalid.push(format!("{}protocol_type", path));
+            }
+        }
+        if version < 5 {
+            if !self.protocol_name.is_none() {
+                invalid.push(format!("{}protocol_name", path));
+            }
+        }
+        for (i, item) in self.assignments.iter().enumerate() {
+            item.validate_into(version, &format!("{}assignments[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl SyncGroupRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        node.insert("generationId".into(), JsonValue::to_json(&self.generation_id));
+        node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        if version >= 3 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        if version >= 5 {
+            node.insert("protocolType".into(), JsonValue::to_json(&self.protocol_type));
+        }
+        if version >= 5 {
+            node.insert("protocolName".into(), JsonValue::to_json(&self.protocol_name));
+        }
+        node.insert("assignments".into(), serde_json::Value::Array(self.assignments.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "SyncGroupRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "SyncGroupRequest.groupId")?;
+        } else {
+            return Err(crate::json::missing("SyncGroupRequest", "groupId", version));
+        }
+        if let Some(value) = object.get("generationId") {
+            this.generation_id = JsonValue::from_json(value, "SyncGroupRequest.generationId")?;
+        } else {
+            return Err(crate::json::missing("SyncGroupRequest", "generationId", version));
+        }
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "SyncGroupRequest.memberId")?;
+        } else {
+            return Err(crate::json::missing("SyncGroupRequest", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "SyncGroupRequest.groupInstanceId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("SyncGroupRequest", "groupInstanceId", version));
+        }
+        if let Some(value) = object.get("protocolType") {
+            this.protocol_type = JsonValue::from_json(value, "SyncGroupRequest.protocolType")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("SyncGroupRequest", "protocolType", version));
+        }
+        if let Some(value) = object.get("protocolName") {
+            this.protocol_name = JsonValue::from_json(value, "SyncGroupRequest.protocolName")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("SyncGroupRequest", "protocolName", version));
+        }
+        if let Some(value) = object.get("assignments") {
+            this.assignments = crate::json::array(value, "SyncGroupRequest.assignments")?.iter().map(|item| SyncGroupRequestAssignment::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("SyncGroupRequest", "assignments", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for SyncGroupRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
@@ -377,3 +656,16 @@ impl HeaderVersion for SyncGroupRequest {
     }
 }
 
+impl SyncGroupRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::SyncGroupResponse {
+        let mut response = super::SyncGroupResponse::default();
+        response.error_code = error.code();
+        response
+    }
+}
+
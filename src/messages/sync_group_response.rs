@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-5
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct SyncGroupResponse {
     /// The duration in milliseconds for which the request was throttled due to a quota violation, or zero if the request did not violate any quota.
@@ -35,11 +41,15 @@ pub struct SyncGroupResponse {
     /// The group protocol type.
     /// 
     /// Supported API versions: 5
+    /// May be `None` in versions: 5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_type: Option<StrBytes>,
 
     /// The group protocol name.
     /// 
     /// Supported API versions: 5
+    /// May be `None` in versions: 5
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub protocol_name: Option<StrBytes>,
 
     /// The member assignment.
@@ -48,7 +58,7 @@ pub struct SyncGroupResponse {
     pub assignment: Bytes,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for SyncGroupResponse {
@@ -61,59 +71,69 @@ impl Builder for SyncGroupResponse {
 
 impl Encodable for SyncGroupResponse {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SyncGroupResponse", version));
+        }
         if version >= 1 {
-            types::Int32.encode(buf, &self.throttle_time_ms)?;
+            types::Int32.encode(buf, &self.throttle_time_ms).map_err(|e| e.with_field("SyncGroupResponse", "throttle_time_ms", version))?;
         }
-        types::Int16.encode(buf, &self.error_code)?;
+        types::Int16.encode(buf, &self.error_code).map_err(|e| e.with_field("SyncGroupResponse", "error_code", version))?;
         if version >= 5 {
-            types::CompactString.encode(buf, &self.protocol_type)?;
+            types::CompactString.encode(buf, &self.protocol_type).map_err(|e| e.with_field("SyncGroupResponse", "protocol_type", version))?;
         }
         if version >= 5 {
-            types::CompactString.encode(buf, &self.protocol_name)?;
+            types::CompactString.encode(buf, &self.protocol_name).map_err(|e| e.with_field("SyncGroupResponse", "protocol_name", version))?;
         }
         if version >= 4 {
-            types::CompactBytes.encode(buf, &self.assignment)?;
+            types::CompactBytes.encode(buf, &self.assignment).map_err(|e| e.with_field("SyncGroupResponse", "assignment", version))?;
         } else {
-            types::Bytes.encode(buf, &self.assignment)?;
+            types::Bytes.encode(buf, &self.assignment).map_err(|e| e.with_field("SyncGroupResponse", "assignment", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SyncGroupResponse", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("SyncGroupResponse", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("SyncGroupResponse", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("SyncGroupResponse", version));
+        }
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.throttle_time_ms)?;
+            total_size += types::Int32.compute_size(&self.throttle_time_ms).map_err(|e| e.with_field("SyncGroupResponse", "throttle_time_ms", version))?;
         }
-        total_size += types::Int16.compute_size(&self.error_code)?;
+        total_size += types::Int16.compute_size(&self.error_code).map_err(|e| e.with_field("SyncGroupResponse", "error_code", version))?;
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.protocol_type)?;
+            total_size += types::CompactString.compute_size(&self.protocol_type).map_err(|e| e.with_field("SyncGroupResponse", "protocol_type", version))?;
         }
         if version >= 5 {
-            total_size += types::CompactString.compute_size(&self.protocol_name)?;
+            total_size += types::CompactString.compute_size(&self.protocol_name).map_err(|e| e.with_field("SyncGroupResponse", "protocol_name", version))?;
         }
         if version >= 4 {
-            total_size += types::CompactBytes.compute_size(&self.assignment)?;
+            total_size += types::CompactBytes.compute_size(&self.assignment).map_err(|e| e.with_field("SyncGroupResponse", "assignment", version))?;
         } else {
-            total_size += types::Bytes.compute_size(&self.assignment)?;
+            total_size += types::Bytes.compute_size(&self.assignment).map_err(|e| e.with_field("SyncGroupResponse", "assignment", version))?;
         }
         if version >= 4 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("SyncGroupResponse", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("SyncGroupResponse", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("SyncGroupResponse", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -121,35 +141,38 @@ impl Encodable for SyncGroupResponse {
 
 impl Decodable for SyncGroupResponse {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("SyncGroupResponse", version));
+        }
+        let start = buf.remaining();
         let throttle_time_ms = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "throttle_time_ms", version, start - buf.remaining()))?
         } else {
             0
         };
-        let error_code = types::Int16.decode(buf)?;
+        let error_code = types::Int16.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "error_code", version, start - buf.remaining()))?;
         let protocol_type = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "protocol_type", version, start - buf.remaining()))?
         } else {
             None
         };
         let protocol_name = if version >= 5 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "protocol_name", version, start - buf.remaining()))?
         } else {
             None
         };
         let assignment = if version >= 4 {
-            types::CompactBytes.decode(buf)?
+            types::CompactBytes.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "assignment", version, start - buf.remaining()))?
         } else {
-            types::Bytes.decode(buf)?
+            types::Bytes.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "assignment", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 4 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("SyncGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("SyncGroupResponse", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -172,7 +195,7 @@ impl Default for SyncGroupResponse {
             protocol_type: None,
             protocol_name: None,
             assignment: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -181,6 +204,112 @@ impl Message for SyncGroupResponse {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 5 };
 }
 
+impl SyncGroupResponse {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.throttle_time_ms = 0;
+        }
+        if version < 5 {
+            self.protocol_type = None;
+        }
+        if version < 5 {
+            self.protocol_name = None;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.throttle_time_ms != 0 {
+                invalid.push(format!("{}throttle_time_ms", path));
+            }
+        }
+        if version < 5 {
+            if !self.protocol_type.is_none() {
+                invalid.push(format!("{}protocol_type", path));
+            }
+        }
+        if version < 5 {
+            if !self.protocol_name.is_none() {
+                invalid.push(format!("{}protocol_name", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl SyncGroupResponse {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("throttleTimeMs".into(), JsonValue::to_json(&self.throttle_time_ms));
+        }
+        node.insert("errorCode".into(), JsonValue::to_json(&self.error_code));
+        if version >= 5 {
+            node.insert("protocolType".into(), JsonValue::to_json(&self.protocol_type));
+        }
+        if version >= 5 {
+            node.insert("protocolName".into(), JsonValue::to_json(&self.protocol_name));
+        }
+        node.insert("assignment".into(), JsonValue::to_json(&self.assignment));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "SyncGroupResponse")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("throttleTimeMs") {
+            this.throttle_time_ms = JsonValue::from_json(value, "SyncGroupResponse.throttleTimeMs")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("SyncGroupResponse", "throttleTimeMs", version));
+        }
+        if let Some(value) = object.get("errorCode") {
+            this.error_code = JsonValue::from_json(value, "SyncGroupResponse.errorCode")?;
+        } else {
+            return Err(crate::json::missing("SyncGroupResponse", "errorCode", version));
+        }
+        if let Some(value) = object.get("protocolType") {
+            this.protocol_type = JsonValue::from_json(value, "SyncGroupResponse.protocolType")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("SyncGroupResponse", "protocolType", version));
+        }
+        if let Some(value) = object.get("protocolName") {
+            this.protocol_name = JsonValue::from_json(value, "SyncGroupResponse.protocolName")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("SyncGroupResponse", "protocolName", version));
+        }
+        if let Some(value) = object.get("assignment") {
+            this.assignment = JsonValue::from_json(value, "SyncGroupResponse.assignment")?;
+        } else {
+            return Err(crate::json::missing("SyncGroupResponse", "assignment", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for SyncGroupResponse {
     fn header_version(version: i16) -> i16 {
         if version >= 4 {
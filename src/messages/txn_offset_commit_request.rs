@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TxnOffsetCommitRequestPartition {
     /// The index of the partition within the topic.
@@ -40,10 +46,12 @@ pub struct TxnOffsetCommitRequestPartition {
     /// Any associated metadata the client wants to keep.
     /// 
     /// Supported API versions: 0-3
+    /// May be `None` in versions: 0-3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub committed_metadata: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TxnOffsetCommitRequestPartition {
@@ -56,49 +64,53 @@ impl Builder for TxnOffsetCommitRequestPartition {
 
 impl Encodable for TxnOffsetCommitRequestPartition {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int64.encode(buf, &self.committed_offset)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "partition_index", version))?;
+        types::Int64.encode(buf, &self.committed_offset).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_offset", version))?;
         if version >= 2 {
-            types::Int32.encode(buf, &self.committed_leader_epoch)?;
+            types::Int32.encode(buf, &self.committed_leader_epoch).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_leader_epoch", version))?;
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.committed_metadata)?;
+            types::CompactString.encode(buf, &self.committed_metadata).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_metadata", version))?;
         } else {
-            types::String.encode(buf, &self.committed_metadata)?;
+            types::String.encode(buf, &self.committed_metadata).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_metadata", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int64.compute_size(&self.committed_offset)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "partition_index", version))?;
+        total_size += types::Int64.compute_size(&self.committed_offset).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_offset", version))?;
         if version >= 2 {
-            total_size += types::Int32.compute_size(&self.committed_leader_epoch)?;
+            total_size += types::Int32.compute_size(&self.committed_leader_epoch).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_leader_epoch", version))?;
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.committed_metadata)?;
+            total_size += types::CompactString.compute_size(&self.committed_metadata).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_metadata", version))?;
         } else {
-            total_size += types::String.compute_size(&self.committed_metadata)?;
+            total_size += types::String.compute_size(&self.committed_metadata).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_metadata", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -106,26 +118,26 @@ impl Encodable for TxnOffsetCommitRequestPartition {
 
 impl Decodable for TxnOffsetCommitRequestPartition {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let partition_index = types::Int32.decode(buf)?;
-        let committed_offset = types::Int64.decode(buf)?;
+        let start = buf.remaining();
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "partition_index", version, start - buf.remaining()))?;
+        let committed_offset = types::Int64.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_offset", version, start - buf.remaining()))?;
         let committed_leader_epoch = if version >= 2 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_leader_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let committed_metadata = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_metadata", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "committed_metadata", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TxnOffsetCommitRequestPartition", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -146,7 +158,7 @@ impl Default for TxnOffsetCommitRequestPartition {
             committed_offset: 0,
             committed_leader_epoch: -1,
             committed_metadata: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -155,9 +167,91 @@ impl Message for TxnOffsetCommitRequestPartition {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl TxnOffsetCommitRequestPartition {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.committed_leader_epoch = -1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if self.committed_leader_epoch != -1 {
+                invalid.push(format!("{}committed_leader_epoch", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TxnOffsetCommitRequestPartition {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("committedOffset".into(), JsonValue::to_json(&self.committed_offset));
+        if version >= 2 {
+            node.insert("committedLeaderEpoch".into(), JsonValue::to_json(&self.committed_leader_epoch));
+        }
+        node.insert("committedMetadata".into(), JsonValue::to_json(&self.committed_metadata));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TxnOffsetCommitRequestPartition")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "TxnOffsetCommitRequestPartition.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequestPartition", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("committedOffset") {
+            this.committed_offset = JsonValue::from_json(value, "TxnOffsetCommitRequestPartition.committedOffset")?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequestPartition", "committedOffset", version));
+        }
+        if let Some(value) = object.get("committedLeaderEpoch") {
+            this.committed_leader_epoch = JsonValue::from_json(value, "TxnOffsetCommitRequestPartition.committedLeaderEpoch")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("TxnOffsetCommitRequestPartition", "committedLeaderEpoch", version));
+        }
+        if let Some(value) = object.get("committedMetadata") {
+            this.committed_metadata = JsonValue::from_json(value, "TxnOffsetCommitRequestPartition.committedMetadata")?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequestPartition", "committedMetadata", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TxnOffsetCommitRequestTopic {
     /// The topic name.
@@ -171,7 +265,7 @@ pub struct TxnOffsetCommitRequestTopic {
     pub partitions: Vec<TxnOffsetCommitRequestPartition>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TxnOffsetCommitRequestTopic {
@@ -184,49 +278,53 @@ impl Builder for TxnOffsetCommitRequestTopic {
 
 impl Encodable for TxnOffsetCommitRequestTopic {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 3 {
-            types::CompactString.encode(buf, &self.name)?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "name", version))?;
         } else {
-            types::String.encode(buf, &self.name)?;
+            types::String.encode(buf, &self.name).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "name", version))?;
         }
         if version >= 3 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "partitions", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.partitions)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.partitions).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "partitions", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.name)?;
+            total_size += types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "name", version))?;
         } else {
-            total_size += types::String.compute_size(&self.name)?;
+            total_size += types::String.compute_size(&self.name).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "name", version))?;
         }
         if version >= 3 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "partitions", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.partitions)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partitions).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "partitions", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -234,24 +332,24 @@ impl Encodable for TxnOffsetCommitRequestTopic {
 
 impl Decodable for TxnOffsetCommitRequestTopic {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let name = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "name", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "name", version, start - buf.remaining()))?
         };
         let partitions = if version >= 3 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "partitions", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "partitions", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TxnOffsetCommitRequestTopic", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -268,7 +366,7 @@ impl Default for TxnOffsetCommitRequestTopic {
         Self {
             name: Default::default(),
             partitions: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -277,9 +375,75 @@ impl Message for TxnOffsetCommitRequestTopic {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl TxnOffsetCommitRequestTopic {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.partitions.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.partitions.iter().enumerate() {
+            item.validate_into(version, &format!("{}partitions[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TxnOffsetCommitRequestTopic {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(&self.name));
+        node.insert("partitions".into(), serde_json::Value::Array(self.partitions.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TxnOffsetCommitRequestTopic")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "TxnOffsetCommitRequestTopic.name")?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequestTopic", "name", version));
+        }
+        if let Some(value) = object.get("partitions") {
+            this.partitions = crate::json::array(value, "TxnOffsetCommitRequestTopic.partitions")?.iter().map(|item| TxnOffsetCommitRequestPartition::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequestTopic", "partitions", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-3
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct TxnOffsetCommitRequest {
     /// The ID of the transaction.
@@ -310,11 +474,14 @@ pub struct TxnOffsetCommitRequest {
     /// The member ID assigned by the group coordinator.
     /// 
     /// Supported API versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub member_id: StrBytes,
 
     /// The unique identifier of the consumer instance provided by end user.
     /// 
     /// Supported API versions: 3
+    /// May be `None` in versions: 3
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub group_instance_id: Option<StrBytes>,
 
     /// Each topic that we want to commit offsets for.
@@ -323,7 +490,7 @@ pub struct TxnOffsetCommitRequest {
     pub topics: Vec<TxnOffsetCommitRequestTopic>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for TxnOffsetCommitRequest {
@@ -336,105 +503,115 @@ impl Builder for TxnOffsetCommitRequest {
 
 impl Encodable for TxnOffsetCommitRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("TxnOffsetCommitRequest", version));
+        }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.transactional_id)?;
+            types::CompactString.encode(buf, &self.transactional_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "transactional_id", version))?;
         } else {
-            types::String.encode(buf, &self.transactional_id)?;
+            types::String.encode(buf, &self.transactional_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "transactional_id", version))?;
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.group_id)?;
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_id", version))?;
         } else {
-            types::String.encode(buf, &self.group_id)?;
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_id", version))?;
         }
-        types::Int64.encode(buf, &self.producer_id)?;
-        types::Int16.encode(buf, &self.producer_epoch)?;
+        types::Int64.encode(buf, &self.producer_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "producer_id", version))?;
+        types::Int16.encode(buf, &self.producer_epoch).map_err(|e| e.with_field("TxnOffsetCommitRequest", "producer_epoch", version))?;
         if version >= 3 {
-            types::Int32.encode(buf, &self.generation_id)?;
+            types::Int32.encode(buf, &self.generation_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "generation_id", version))?;
         } else {
             if self.generation_id != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "generation_id", version));
             }
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.member_id)?;
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "member_id", version))?;
         } else {
             if &self.member_id != "" {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "member_id", version));
             }
         }
         if version >= 3 {
-            types::CompactString.encode(buf, &self.group_instance_id)?;
+            types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_instance_id", version))?;
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "group_instance_id", version));
             }
         }
         if version >= 3 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.topics)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("TxnOffsetCommitRequest", "topics", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.topics)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.topics).map_err(|e| e.with_field("TxnOffsetCommitRequest", "topics", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("TxnOffsetCommitRequest", version));
+        }
         let mut total_size = 0;
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.transactional_id)?;
+            total_size += types::CompactString.compute_size(&self.transactional_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "transactional_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.transactional_id)?;
+            total_size += types::String.compute_size(&self.transactional_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "transactional_id", version))?;
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.group_id)?;
+            total_size += types::CompactString.compute_size(&self.group_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_id", version))?;
         } else {
-            total_size += types::String.compute_size(&self.group_id)?;
+            total_size += types::String.compute_size(&self.group_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_id", version))?;
         }
-        total_size += types::Int64.compute_size(&self.producer_id)?;
-        total_size += types::Int16.compute_size(&self.producer_epoch)?;
+        total_size += types::Int64.compute_size(&self.producer_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "producer_id", version))?;
+        total_size += types::Int16.compute_size(&self.producer_epoch).map_err(|e| e.with_field("TxnOffsetCommitRequest", "producer_epoch", version))?;
         if version >= 3 {
-            total_size += types::Int32.compute_size(&self.generation_id)?;
+            total_size += types::Int32.compute_size(&self.generation_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "generation_id", version))?;
         } else {
             if self.generation_id != -1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "generation_id", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.member_id)?;
+            total_size += types::CompactString.compute_size(&self.member_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "member_id", version))?;
         } else {
             if &self.member_id != "" {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "member_id", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactString.compute_size(&self.group_instance_id)?;
+            total_size += types::CompactString.compute_size(&self.group_instance_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_instance_id", version))?;
         } else {
             if !self.group_instance_id.is_none() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "group_instance_id", version));
             }
         }
         if version >= 3 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("TxnOffsetCommitRequest", "topics", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.topics)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topics).map_err(|e| e.with_field("TxnOffsetCommitRequest", "topics", version))?;
         }
         if version >= 3 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -442,46 +619,49 @@ impl Encodable for TxnOffsetCommitRequest {
 
 impl Decodable for TxnOffsetCommitRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("TxnOffsetCommitRequest", version));
+        }
+        let start = buf.remaining();
         let transactional_id = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "transactional_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "transactional_id", version, start - buf.remaining()))?
         };
         let group_id = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_id", version, start - buf.remaining()))?
         } else {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_id", version, start - buf.remaining()))?
         };
-        let producer_id = types::Int64.decode(buf)?;
-        let producer_epoch = types::Int16.decode(buf)?;
+        let producer_id = types::Int64.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "producer_id", version, start - buf.remaining()))?;
+        let producer_epoch = types::Int16.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "producer_epoch", version, start - buf.remaining()))?;
         let generation_id = if version >= 3 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "generation_id", version, start - buf.remaining()))?
         } else {
             -1
         };
         let member_id = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "member_id", version, start - buf.remaining()))?
         } else {
             StrBytes::from_str("")
         };
         let group_instance_id = if version >= 3 {
-            types::CompactString.decode(buf)?
+            types::CompactString.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_instance_id", version, start - buf.remaining()))?
         } else {
             None
         };
         let topics = if version >= 3 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "topics", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "topics", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 3 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -510,7 +690,7 @@ impl Default for TxnOffsetCommitRequest {
             member_id: StrBytes::from_str(""),
             group_instance_id: None,
             topics: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -519,6 +699,205 @@ impl Message for TxnOffsetCommitRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 3 };
 }
 
+impl TxnOffsetCommitRequest {
+    /// Encode the message with the elements of `topics` produced by an iterator of known length, in place of
+    /// the `topics` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topics<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topics: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = TxnOffsetCommitRequestTopic>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("TxnOffsetCommitRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topics = topics.into_iter();
+        if version >= 3 {
+            types::CompactString.encode(buf, &self.transactional_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "transactional_id", version))?;
+        } else {
+            types::String.encode(buf, &self.transactional_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "transactional_id", version))?;
+        }
+        if version >= 3 {
+            types::CompactString.encode(buf, &self.group_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_id", version))?;
+        } else {
+            types::String.encode(buf, &self.group_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_id", version))?;
+        }
+        types::Int64.encode(buf, &self.producer_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "producer_id", version))?;
+        types::Int16.encode(buf, &self.producer_epoch).map_err(|e| e.with_field("TxnOffsetCommitRequest", "producer_epoch", version))?;
+        if version >= 3 {
+            types::Int32.encode(buf, &self.generation_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "generation_id", version))?;
+        } else {
+            if self.generation_id != -1 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "generation_id", version));
+            }
+        }
+        if version >= 3 {
+            types::CompactString.encode(buf, &self.member_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "member_id", version))?;
+        } else {
+            if &self.member_id != "" {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "member_id", version));
+            }
+        }
+        if version >= 3 {
+            types::CompactString.encode(buf, &self.group_instance_id).map_err(|e| e.with_field("TxnOffsetCommitRequest", "group_instance_id", version))?;
+        } else {
+            if !self.group_instance_id.is_none() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("TxnOffsetCommitRequest", "group_instance_id", version));
+            }
+        }
+        if version >= 3 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("TxnOffsetCommitRequest", "topics", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topics)).map_err(|e| e.with_field("TxnOffsetCommitRequest", "topics", version))?;
+        }
+        if version >= 3 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TxnOffsetCommitRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl TxnOffsetCommitRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 3 {
+            self.generation_id = -1;
+        }
+        if version < 3 {
+            self.member_id = StrBytes::from_str("");
+        }
+        if version < 3 {
+            self.group_instance_id = None;
+        }
+        for item in self.topics.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 3 {
+            if self.generation_id != -1 {
+                invalid.push(format!("{}generation_id", path));
+            }
+        }
+        if version < 3 {
+            if &self.member_id != "" {
+                invalid.push(format!("{}member_id", path));
+            }
+        }
+        if version < 3 {
+            if !self.group_instance_id.is_none() {
+                invalid.push(format!("{}group_instance_id", path));
+            }
+        }
+        for (i, item) in self.topics.iter().enumerate() {
+            item.validate_into(version, &format!("{}topics[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TxnOffsetCommitRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("transactionalId".into(), JsonValue::to_json(&self.transactional_id));
+        node.insert("groupId".into(), JsonValue::to_json(&self.group_id));
+        node.insert("producerId".into(), JsonValue::to_json(&self.producer_id));
+        node.insert("producerEpoch".into(), JsonValue::to_json(&self.producer_epoch));
+        if version >= 3 {
+            node.insert("generationId".into(), JsonValue::to_json(&self.generation_id));
+        }
+        if version >= 3 {
+            node.insert("memberId".into(), JsonValue::to_json(&self.member_id));
+        }
+        if version >= 3 {
+            node.insert("groupInstanceId".into(), JsonValue::to_json(&self.group_instance_id));
+        }
+        node.insert("topics".into(), serde_json::Value::Array(self.topics.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TxnOffsetCommitRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("transactionalId") {
+            this.transactional_id = JsonValue::from_json(value, "TxnOffsetCommitRequest.transactionalId")?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequest", "transactionalId", version));
+        }
+        if let Some(value) = object.get("groupId") {
+            this.group_id = JsonValue::from_json(value, "TxnOffsetCommitRequest.groupId")?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequest", "groupId", version));
+        }
+        if let Some(value) = object.get("producerId") {
+            this.producer_id = JsonValue::from_json(value, "TxnOffsetCommitRequest.producerId")?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequest", "producerId", version));
+        }
+        if let Some(value) = object.get("producerEpoch") {
+            this.producer_epoch = JsonValue::from_json(value, "TxnOffsetCommitRequest.producerEpoch")?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequest", "producerEpoch", version));
+        }
+        if let Some(value) = object.get("generationId") {
+            this.generation_id = JsonValue::from_json(value, "TxnOffsetCommitRequest.generationId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("TxnOffsetCommitRequest", "generationId", version));
+        }
+        if let Some(value) = object.get("memberId") {
+            this.member_id = JsonValue::from_json(value, "TxnOffsetCommitRequest.memberId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("TxnOffsetCommitRequest", "memberId", version));
+        }
+        if let Some(value) = object.get("groupInstanceId") {
+            this.group_instance_id = JsonValue::from_json(value, "TxnOffsetCommitRequest.groupInstanceId")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("TxnOffsetCommitRequest", "groupInstanceId", version));
+        }
+        if let Some(value) = object.get("topics") {
+            this.topics = crate::json::array(value, "TxnOffsetCommitRequest.topics")?.iter().map(|item| TxnOffsetCommitRequestTopic::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("TxnOffsetCommitRequest", "topics", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for TxnOffsetCommitRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 3 {
@@ -529,3 +908,30 @@ impl HeaderVersion for TxnOffsetCommitRequest {
     }
 }
 
+impl TxnOffsetCommitRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::TxnOffsetCommitResponse {
+        let mut response = super::TxnOffsetCommitResponse::default();
+        let mut items0 = Vec::new();
+        for request0 in &self.topics {
+            let mut response0 = super::txn_offset_commit_response::TxnOffsetCommitResponseTopic::default();
+            response0.name = request0.name.clone();
+            let mut items1 = Vec::new();
+            for request1 in &request0.partitions {
+                let mut response1 = super::txn_offset_commit_response::TxnOffsetCommitResponsePartition::default();
+                response1.partition_index = request1.partition_index;
+                response1.error_code = error.code();
+                items1.push(response1);
+            }
+            response0.partitions = items1;
+            items0.push(response0);
+        }
+        response.topics = items0;
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct FeatureUpdateKey {
     /// The new maximum version level for the finalized feature. A value >= 1 is valid. A value < 1, is special, and can be used to request the deletion of the finalized feature.
@@ -38,7 +44,7 @@ pub struct FeatureUpdateKey {
     pub upgrade_type: i8,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for FeatureUpdateKey {
@@ -52,58 +58,62 @@ impl Builder for FeatureUpdateKey {
 impl MapEncodable for FeatureUpdateKey {
     type Key = StrBytes;
     fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::CompactString.encode(buf, key)?;
-        types::Int16.encode(buf, &self.max_version_level)?;
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, key).map_err(|e| e.with_field("FeatureUpdateKey", "feature", version))?;
+        types::Int16.encode(buf, &self.max_version_level).map_err(|e| e.with_field("FeatureUpdateKey", "max_version_level", version))?;
         if version == 0 {
-            types::Boolean.encode(buf, &self.allow_downgrade)?;
+            types::Boolean.encode(buf, &self.allow_downgrade).map_err(|e| e.with_field("FeatureUpdateKey", "allow_downgrade", version))?;
         } else {
             if self.allow_downgrade {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FeatureUpdateKey", "allow_downgrade", version));
             }
         }
         if version >= 1 {
-            types::Int8.encode(buf, &self.upgrade_type)?;
+            types::Int8.encode(buf, &self.upgrade_type).map_err(|e| e.with_field("FeatureUpdateKey", "upgrade_type", version))?;
         } else {
             if self.upgrade_type != 1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FeatureUpdateKey", "upgrade_type", version));
             }
         }
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FeatureUpdateKey", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("FeatureUpdateKey", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("FeatureUpdateKey", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::CompactString.compute_size(key)?;
-        total_size += types::Int16.compute_size(&self.max_version_level)?;
+        total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("FeatureUpdateKey", "feature", version))?;
+        total_size += types::Int16.compute_size(&self.max_version_level).map_err(|e| e.with_field("FeatureUpdateKey", "max_version_level", version))?;
         if version == 0 {
-            total_size += types::Boolean.compute_size(&self.allow_downgrade)?;
+            total_size += types::Boolean.compute_size(&self.allow_downgrade).map_err(|e| e.with_field("FeatureUpdateKey", "allow_downgrade", version))?;
         } else {
             if self.allow_downgrade {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FeatureUpdateKey", "allow_downgrade", version));
             }
         }
         if version >= 1 {
-            total_size += types::Int8.compute_size(&self.upgrade_type)?;
+            total_size += types::Int8.compute_size(&self.upgrade_type).map_err(|e| e.with_field("FeatureUpdateKey", "upgrade_type", version))?;
         } else {
             if self.upgrade_type != 1 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("FeatureUpdateKey", "upgrade_type", version));
             }
         }
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("FeatureUpdateKey", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("FeatureUpdateKey", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("FeatureUpdateKey", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
@@ -111,25 +121,25 @@ impl MapEncodable for FeatureUpdateKey {
 impl MapDecodable for FeatureUpdateKey {
     type Key = StrBytes;
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
-        let key_field = types::CompactString.decode(buf)?;
-        let max_version_level = types::Int16.decode(buf)?;
+        let start = buf.remaining();
+        let key_field = types::CompactString.decode(buf).map_err(|e| e.with_field("FeatureUpdateKey", "feature", version, start - buf.remaining()))?;
+        let max_version_level = types::Int16.decode(buf).map_err(|e| e.with_field("FeatureUpdateKey", "max_version_level", version, start - buf.remaining()))?;
         let allow_downgrade = if version == 0 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("FeatureUpdateKey", "allow_downgrade", version, start - buf.remaining()))?
         } else {
             false
         };
         let upgrade_type = if version >= 1 {
-            types::Int8.decode(buf)?
+            types::Int8.decode(buf).map_err(|e| e.with_field("FeatureUpdateKey", "upgrade_type", version, start - buf.remaining()))?
         } else {
             1
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FeatureUpdateKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FeatureUpdateKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("FeatureUpdateKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("FeatureUpdateKey", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok((key_field, Self {
@@ -147,7 +157,7 @@ impl Default for FeatureUpdateKey {
             max_version_level: 0,
             allow_downgrade: false,
             upgrade_type: 1,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -156,9 +166,102 @@ impl Message for FeatureUpdateKey {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl FeatureUpdateKey {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version != 0 {
+            self.allow_downgrade = false;
+        }
+        if version < 1 {
+            self.upgrade_type = 1;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version != 0 {
+            if self.allow_downgrade {
+                invalid.push(format!("{}allow_downgrade", path));
+            }
+        }
+        if version < 1 {
+            if self.upgrade_type != 1 {
+                invalid.push(format!("{}upgrade_type", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl FeatureUpdateKey {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("feature".into(), JsonValue::to_json(key));
+        node.insert("maxVersionLevel".into(), JsonValue::to_json(&self.max_version_level));
+        if version == 0 {
+            node.insert("allowDowngrade".into(), JsonValue::to_json(&self.allow_downgrade));
+        }
+        if version >= 1 {
+            node.insert("upgradeType".into(), JsonValue::to_json(&self.upgrade_type));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "FeatureUpdateKey")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("feature") {
+            key = JsonValue::from_json(value, "FeatureUpdateKey.feature")?;
+        } else {
+            return Err(crate::json::missing("FeatureUpdateKey", "feature", version));
+        }
+        if let Some(value) = object.get("maxVersionLevel") {
+            this.max_version_level = JsonValue::from_json(value, "FeatureUpdateKey.maxVersionLevel")?;
+        } else {
+            return Err(crate::json::missing("FeatureUpdateKey", "maxVersionLevel", version));
+        }
+        if let Some(value) = object.get("allowDowngrade") {
+            this.allow_downgrade = JsonValue::from_json(value, "FeatureUpdateKey.allowDowngrade")?;
+        } else if version == 0 {
+            return Err(crate::json::missing("FeatureUpdateKey", "allowDowngrade", version));
+        }
+        if let Some(value) = object.get("upgradeType") {
+            this.upgrade_type = JsonValue::from_json(value, "FeatureUpdateKey.upgradeType")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("FeatureUpdateKey", "upgradeType", version));
+        }
+        Ok((key, this))
+    }
+}
+
 /// Valid versions: 0-1
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct UpdateFeaturesRequest {
     /// How long to wait in milliseconds before timing out the request.
@@ -169,6 +272,7 @@ pub struct UpdateFeaturesRequest {
     /// The list of updates to finalized features.
     /// 
     /// Supported API versions: 0-1
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub feature_updates: indexmap::IndexMap<StrBytes, FeatureUpdateKey>,
 
     /// True if we should validate the request, but not perform the upgrade or downgrade.
@@ -177,7 +281,7 @@ pub struct UpdateFeaturesRequest {
     pub validate_only: bool,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for UpdateFeaturesRequest {
@@ -190,64 +294,77 @@ impl Builder for UpdateFeaturesRequest {
 
 impl Encodable for UpdateFeaturesRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.timeout_ms)?;
-        types::CompactArray(types::Struct { version }).encode(buf, &self.feature_updates)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("UpdateFeaturesRequest", version));
+        }
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("UpdateFeaturesRequest", "timeout_ms", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.feature_updates).map_err(|e| e.with_field("UpdateFeaturesRequest", "feature_updates", version))?;
         if version >= 1 {
-            types::Boolean.encode(buf, &self.validate_only)?;
+            types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("UpdateFeaturesRequest", "validate_only", version))?;
         } else {
             if self.validate_only {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateFeaturesRequest", "validate_only", version));
             }
         }
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version));
         }
-        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version))?;
 
-        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version))?;
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("UpdateFeaturesRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.timeout_ms)?;
-        total_size += types::CompactArray(types::Struct { version }).compute_size(&self.feature_updates)?;
+        total_size += types::Int32.compute_size(&self.timeout_ms).map_err(|e| e.with_field("UpdateFeaturesRequest", "timeout_ms", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.feature_updates).map_err(|e| e.with_field("UpdateFeaturesRequest", "feature_updates", version))?;
         if version >= 1 {
-            total_size += types::Boolean.compute_size(&self.validate_only)?;
+            total_size += types::Boolean.compute_size(&self.validate_only).map_err(|e| e.with_field("UpdateFeaturesRequest", "validate_only", version))?;
         } else {
             if self.validate_only {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateFeaturesRequest", "validate_only", version));
             }
         }
         let num_tagged_fields = self.unknown_tagged_fields.len();
         if num_tagged_fields > std::u32::MAX as usize {
-            error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version));
         }
-        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version))?;
 
-        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version))?;
         Ok(total_size)
     }
 }
 
 impl Decodable for UpdateFeaturesRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let timeout_ms = types::Int32.decode(buf)?;
-        let feature_updates = types::CompactArray(types::Struct { version }).decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("UpdateFeaturesRequest", version));
+        }
+        let start = buf.remaining();
+        let timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateFeaturesRequest", "timeout_ms", version, start - buf.remaining()))?;
+        let feature_updates = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateFeaturesRequest", "feature_updates", version, start - buf.remaining()))?;
         let validate_only = if version >= 1 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("UpdateFeaturesRequest", "validate_only", version, start - buf.remaining()))?
         } else {
             false
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
-        let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
         for _ in 0..num_tagged_fields {
-            let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-            let size: u32 = types::UnsignedVarInt.decode(buf)?;
-            let mut unknown_value = vec![0; size as usize];
-            buf.try_copy_to_slice(&mut unknown_value)?;
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             unknown_tagged_fields.insert(tag as i32, unknown_value);
         }
         Ok(Self {
@@ -265,7 +382,7 @@ impl Default for UpdateFeaturesRequest {
             timeout_ms: 60000,
             feature_updates: Default::default(),
             validate_only: false,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -274,9 +391,147 @@ impl Message for UpdateFeaturesRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
 }
 
+impl UpdateFeaturesRequest {
+    /// Encode the message with the elements of `feature_updates` produced by an iterator of known length, in place of
+    /// the `feature_updates` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_feature_updates<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, feature_updates: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (StrBytes, FeatureUpdateKey)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("UpdateFeaturesRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let feature_updates = feature_updates.into_iter();
+        types::Int32.encode(buf, &self.timeout_ms).map_err(|e| e.with_field("UpdateFeaturesRequest", "timeout_ms", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(feature_updates)).map_err(|e| e.with_field("UpdateFeaturesRequest", "feature_updates", version))?;
+        if version >= 1 {
+            types::Boolean.encode(buf, &self.validate_only).map_err(|e| e.with_field("UpdateFeaturesRequest", "validate_only", version))?;
+        } else {
+            if self.validate_only {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateFeaturesRequest", "validate_only", version));
+            }
+        }
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateFeaturesRequest", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl UpdateFeaturesRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.feature_updates.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 1 {
+            self.validate_only = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.feature_updates.values().enumerate() {
+            item.validate_into(version, &format!("{}feature_updates[{}].", path, i), invalid);
+        }
+        if version < 1 {
+            if self.validate_only {
+                invalid.push(format!("{}validate_only", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl UpdateFeaturesRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("timeoutMs".into(), JsonValue::to_json(&self.timeout_ms));
+        node.insert("featureUpdates".into(), serde_json::Value::Array(self.feature_updates.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        if version >= 1 {
+            node.insert("validateOnly".into(), JsonValue::to_json(&self.validate_only));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "UpdateFeaturesRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("timeoutMs") {
+            this.timeout_ms = JsonValue::from_json(value, "UpdateFeaturesRequest.timeoutMs")?;
+        } else {
+            return Err(crate::json::missing("UpdateFeaturesRequest", "timeoutMs", version));
+        }
+        if let Some(value) = object.get("featureUpdates") {
+            this.feature_updates = crate::json::array(value, "UpdateFeaturesRequest.featureUpdates")?.iter().map(|item| FeatureUpdateKey::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("UpdateFeaturesRequest", "featureUpdates", version));
+        }
+        if let Some(value) = object.get("validateOnly") {
+            this.validate_only = JsonValue::from_json(value, "UpdateFeaturesRequest.validateOnly")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("UpdateFeaturesRequest", "validateOnly", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for UpdateFeaturesRequest {
     fn header_version(version: i16) -> i16 {
         2
     }
 }
 
+impl UpdateFeaturesRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::UpdateFeaturesResponse {
+        let mut response = super::UpdateFeaturesResponse::default();
+        response.error_code = error.code();
+        let mut items0 = indexmap::IndexMap::new();
+        for (key0, request0) in &self.feature_updates {
+            let mut response0 = super::update_features_response::UpdatableFeatureResult::default();
+            response0.error_code = error.code();
+            items0.insert(key0.clone(), response0);
+        }
+        response.results = items0;
+        response
+    }
+}
+
@@ -5,21 +5,27 @@
 #![allow(unused)]
 
 use std::borrow::Borrow;
-use std::collections::BTreeMap;
 
 use bytes::Bytes;
-use log::error;
 use uuid::Uuid;
 
 use crate::protocol::{
-    Encodable, Decodable, MapEncodable, MapDecodable, Encoder, Decoder, EncodeError, DecodeError, Message, HeaderVersion, VersionRange,
-    types, write_unknown_tagged_fields, compute_unknown_tagged_fields_size, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
 };
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
 
 
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct UpdateMetadataPartitionState {
     /// In older versions of this RPC, the topic name.
@@ -68,7 +74,7 @@ pub struct UpdateMetadataPartitionState {
     pub offline_replicas: Vec<super::BrokerId>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for UpdateMetadataPartitionState {
@@ -81,79 +87,83 @@ impl Builder for UpdateMetadataPartitionState {
 
 impl Encodable for UpdateMetadataPartitionState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version <= 4 {
-            types::String.encode(buf, &self.topic_name)?;
+            types::String.encode(buf, &self.topic_name).map_err(|e| e.with_field("UpdateMetadataPartitionState", "topic_name", version))?;
         }
-        types::Int32.encode(buf, &self.partition_index)?;
-        types::Int32.encode(buf, &self.controller_epoch)?;
-        types::Int32.encode(buf, &self.leader)?;
-        types::Int32.encode(buf, &self.leader_epoch)?;
+        types::Int32.encode(buf, &self.partition_index).map_err(|e| e.with_field("UpdateMetadataPartitionState", "partition_index", version))?;
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("UpdateMetadataPartitionState", "controller_epoch", version))?;
+        types::Int32.encode(buf, &self.leader).map_err(|e| e.with_field("UpdateMetadataPartitionState", "leader", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("UpdateMetadataPartitionState", "leader_epoch", version))?;
         if version >= 6 {
-            types::CompactArray(types::Int32).encode(buf, &self.isr)?;
+            types::CompactArray(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("UpdateMetadataPartitionState", "isr", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.isr)?;
+            types::Array(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("UpdateMetadataPartitionState", "isr", version))?;
         }
-        types::Int32.encode(buf, &self.zk_version)?;
+        types::Int32.encode(buf, &self.zk_version).map_err(|e| e.with_field("UpdateMetadataPartitionState", "zk_version", version))?;
         if version >= 6 {
-            types::CompactArray(types::Int32).encode(buf, &self.replicas)?;
+            types::CompactArray(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("UpdateMetadataPartitionState", "replicas", version))?;
         } else {
-            types::Array(types::Int32).encode(buf, &self.replicas)?;
+            types::Array(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("UpdateMetadataPartitionState", "replicas", version))?;
         }
         if version >= 4 {
             if version >= 6 {
-                types::CompactArray(types::Int32).encode(buf, &self.offline_replicas)?;
+                types::CompactArray(types::Int32).encode(buf, &self.offline_replicas).map_err(|e| e.with_field("UpdateMetadataPartitionState", "offline_replicas", version))?;
             } else {
-                types::Array(types::Int32).encode(buf, &self.offline_replicas)?;
+                types::Array(types::Int32).encode(buf, &self.offline_replicas).map_err(|e| e.with_field("UpdateMetadataPartitionState", "offline_replicas", version))?;
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version <= 4 {
-            total_size += types::String.compute_size(&self.topic_name)?;
+            total_size += types::String.compute_size(&self.topic_name).map_err(|e| e.with_field("UpdateMetadataPartitionState", "topic_name", version))?;
         }
-        total_size += types::Int32.compute_size(&self.partition_index)?;
-        total_size += types::Int32.compute_size(&self.controller_epoch)?;
-        total_size += types::Int32.compute_size(&self.leader)?;
-        total_size += types::Int32.compute_size(&self.leader_epoch)?;
+        total_size += types::Int32.compute_size(&self.partition_index).map_err(|e| e.with_field("UpdateMetadataPartitionState", "partition_index", version))?;
+        total_size += types::Int32.compute_size(&self.controller_epoch).map_err(|e| e.with_field("UpdateMetadataPartitionState", "controller_epoch", version))?;
+        total_size += types::Int32.compute_size(&self.leader).map_err(|e| e.with_field("UpdateMetadataPartitionState", "leader", version))?;
+        total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("UpdateMetadataPartitionState", "leader_epoch", version))?;
         if version >= 6 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.isr)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.isr).map_err(|e| e.with_field("UpdateMetadataPartitionState", "isr", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.isr)?;
+            total_size += types::Array(types::Int32).compute_size(&self.isr).map_err(|e| e.with_field("UpdateMetadataPartitionState", "isr", version))?;
         }
-        total_size += types::Int32.compute_size(&self.zk_version)?;
+        total_size += types::Int32.compute_size(&self.zk_version).map_err(|e| e.with_field("UpdateMetadataPartitionState", "zk_version", version))?;
         if version >= 6 {
-            total_size += types::CompactArray(types::Int32).compute_size(&self.replicas)?;
+            total_size += types::CompactArray(types::Int32).compute_size(&self.replicas).map_err(|e| e.with_field("UpdateMetadataPartitionState", "replicas", version))?;
         } else {
-            total_size += types::Array(types::Int32).compute_size(&self.replicas)?;
+            total_size += types::Array(types::Int32).compute_size(&self.replicas).map_err(|e| e.with_field("UpdateMetadataPartitionState", "replicas", version))?;
         }
         if version >= 4 {
             if version >= 6 {
-                total_size += types::CompactArray(types::Int32).compute_size(&self.offline_replicas)?;
+                total_size += types::CompactArray(types::Int32).compute_size(&self.offline_replicas).map_err(|e| e.with_field("UpdateMetadataPartitionState", "offline_replicas", version))?;
             } else {
-                total_size += types::Array(types::Int32).compute_size(&self.offline_replicas)?;
+                total_size += types::Array(types::Int32).compute_size(&self.offline_replicas).map_err(|e| e.with_field("UpdateMetadataPartitionState", "offline_replicas", version))?;
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -161,43 +171,43 @@ impl Encodable for UpdateMetadataPartitionState {
 
 impl Decodable for UpdateMetadataPartitionState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version <= 4 {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "topic_name", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
-        let partition_index = types::Int32.decode(buf)?;
-        let controller_epoch = types::Int32.decode(buf)?;
-        let leader = types::Int32.decode(buf)?;
-        let leader_epoch = types::Int32.decode(buf)?;
+        let partition_index = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "partition_index", version, start - buf.remaining()))?;
+        let controller_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "controller_epoch", version, start - buf.remaining()))?;
+        let leader = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "leader", version, start - buf.remaining()))?;
+        let leader_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "leader_epoch", version, start - buf.remaining()))?;
         let isr = if version >= 6 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "isr", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "isr", version, start - buf.remaining()))?
         };
-        let zk_version = types::Int32.decode(buf)?;
+        let zk_version = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "zk_version", version, start - buf.remaining()))?;
         let replicas = if version >= 6 {
-            types::CompactArray(types::Int32).decode(buf)?
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "replicas", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Int32).decode(buf)?
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "replicas", version, start - buf.remaining()))?
         };
         let offline_replicas = if version >= 4 {
             if version >= 6 {
-                types::CompactArray(types::Int32).decode(buf)?
+                types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "offline_replicas", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Int32).decode(buf)?
+                types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "offline_replicas", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("UpdateMetadataPartitionState", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -228,7 +238,7 @@ impl Default for UpdateMetadataPartitionState {
             zk_version: 0,
             replicas: Default::default(),
             offline_replicas: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -237,9 +247,131 @@ impl Message for UpdateMetadataPartitionState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl UpdateMetadataPartitionState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version > 4 {
+            self.topic_name = Default::default();
+        }
+        if version < 4 {
+            self.offline_replicas = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version > 4 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+        if version < 4 {
+            if !self.offline_replicas.is_empty() {
+                invalid.push(format!("{}offline_replicas", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl UpdateMetadataPartitionState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version <= 4 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        node.insert("partitionIndex".into(), JsonValue::to_json(&self.partition_index));
+        node.insert("controllerEpoch".into(), JsonValue::to_json(&self.controller_epoch));
+        node.insert("leader".into(), JsonValue::to_json(&self.leader));
+        node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        node.insert("isr".into(), JsonValue::to_json(&self.isr));
+        node.insert("zkVersion".into(), JsonValue::to_json(&self.zk_version));
+        node.insert("replicas".into(), JsonValue::to_json(&self.replicas));
+        if version >= 4 {
+            node.insert("offlineReplicas".into(), JsonValue::to_json(&self.offline_replicas));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "UpdateMetadataPartitionState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "UpdateMetadataPartitionState.topicName")?;
+        } else if version <= 4 {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "topicName", version));
+        }
+        if let Some(value) = object.get("partitionIndex") {
+            this.partition_index = JsonValue::from_json(value, "UpdateMetadataPartitionState.partitionIndex")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "partitionIndex", version));
+        }
+        if let Some(value) = object.get("controllerEpoch") {
+            this.controller_epoch = JsonValue::from_json(value, "UpdateMetadataPartitionState.controllerEpoch")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "controllerEpoch", version));
+        }
+        if let Some(value) = object.get("leader") {
+            this.leader = JsonValue::from_json(value, "UpdateMetadataPartitionState.leader")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "leader", version));
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "UpdateMetadataPartitionState.leaderEpoch")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "leaderEpoch", version));
+        }
+        if let Some(value) = object.get("isr") {
+            this.isr = JsonValue::from_json(value, "UpdateMetadataPartitionState.isr")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "isr", version));
+        }
+        if let Some(value) = object.get("zkVersion") {
+            this.zk_version = JsonValue::from_json(value, "UpdateMetadataPartitionState.zkVersion")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "zkVersion", version));
+        }
+        if let Some(value) = object.get("replicas") {
+            this.replicas = JsonValue::from_json(value, "UpdateMetadataPartitionState.replicas")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "replicas", version));
+        }
+        if let Some(value) = object.get("offlineReplicas") {
+            this.offline_replicas = JsonValue::from_json(value, "UpdateMetadataPartitionState.offlineReplicas")?;
+        } else if version >= 4 {
+            return Err(crate::json::missing("UpdateMetadataPartitionState", "offlineReplicas", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct UpdateMetadataTopicState {
     /// The topic name.
@@ -258,7 +390,7 @@ pub struct UpdateMetadataTopicState {
     pub partition_states: Vec<UpdateMetadataPartitionState>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for UpdateMetadataTopicState {
@@ -271,79 +403,83 @@ impl Builder for UpdateMetadataTopicState {
 
 impl Encodable for UpdateMetadataTopicState {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 5 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.topic_name)?;
+                types::CompactString.encode(buf, &self.topic_name).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_name", version))?;
             } else {
-                types::String.encode(buf, &self.topic_name)?;
+                types::String.encode(buf, &self.topic_name).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_name", version))?;
             }
         } else {
             if !self.topic_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataTopicState", "topic_name", version));
             }
         }
         if version >= 7 {
-            types::Uuid.encode(buf, &self.topic_id)?;
+            types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_id", version))?;
         }
         if version >= 5 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.partition_states)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.partition_states).map_err(|e| e.with_field("UpdateMetadataTopicState", "partition_states", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.partition_states)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.partition_states).map_err(|e| e.with_field("UpdateMetadataTopicState", "partition_states", version))?;
             }
         } else {
             if !self.partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataTopicState", "partition_states", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 5 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.topic_name)?;
+                total_size += types::CompactString.compute_size(&self.topic_name).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_name", version))?;
             } else {
-                total_size += types::String.compute_size(&self.topic_name)?;
+                total_size += types::String.compute_size(&self.topic_name).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_name", version))?;
             }
         } else {
             if !self.topic_name.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataTopicState", "topic_name", version));
             }
         }
         if version >= 7 {
-            total_size += types::Uuid.compute_size(&self.topic_id)?;
+            total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_id", version))?;
         }
         if version >= 5 {
             if version >= 6 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.partition_states)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.partition_states).map_err(|e| e.with_field("UpdateMetadataTopicState", "partition_states", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.partition_states)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.partition_states).map_err(|e| e.with_field("UpdateMetadataTopicState", "partition_states", version))?;
             }
         } else {
             if !self.partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataTopicState", "partition_states", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -351,37 +487,37 @@ impl Encodable for UpdateMetadataTopicState {
 
 impl Decodable for UpdateMetadataTopicState {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let topic_name = if version >= 5 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_name", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_name", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let topic_id = if version >= 7 {
-            types::Uuid.decode(buf)?
+            types::Uuid.decode(buf).map_err(|e| e.with_field("UpdateMetadataTopicState", "topic_id", version, start - buf.remaining()))?
         } else {
             Uuid::nil()
         };
         let partition_states = if version >= 5 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataTopicState", "partition_states", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataTopicState", "partition_states", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("UpdateMetadataTopicState", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -400,7 +536,7 @@ impl Default for UpdateMetadataTopicState {
             topic_name: Default::default(),
             topic_id: Uuid::nil(),
             partition_states: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -409,9 +545,113 @@ impl Message for UpdateMetadataTopicState {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl UpdateMetadataTopicState {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 5 {
+            self.topic_name = Default::default();
+        }
+        if version < 7 {
+            self.topic_id = Uuid::nil();
+        }
+        if version >= 5 {
+            for item in self.partition_states.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.partition_states = Default::default();
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 5 {
+            if !self.topic_name.is_empty() {
+                invalid.push(format!("{}topic_name", path));
+            }
+        }
+        if version < 7 {
+            if &self.topic_id != &Uuid::nil() {
+                invalid.push(format!("{}topic_id", path));
+            }
+        }
+        if version >= 5 {
+            for (i, item) in self.partition_states.iter().enumerate() {
+                item.validate_into(version, &format!("{}partition_states[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.partition_states.is_empty() {
+                invalid.push(format!("{}partition_states", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl UpdateMetadataTopicState {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 5 {
+            node.insert("topicName".into(), JsonValue::to_json(&self.topic_name));
+        }
+        if version >= 7 {
+            node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        }
+        if version >= 5 {
+            node.insert("partitionStates".into(), serde_json::Value::Array(self.partition_states.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "UpdateMetadataTopicState")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topicName") {
+            this.topic_name = JsonValue::from_json(value, "UpdateMetadataTopicState.topicName")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("UpdateMetadataTopicState", "topicName", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "UpdateMetadataTopicState.topicId")?;
+        } else if version >= 7 {
+            return Err(crate::json::missing("UpdateMetadataTopicState", "topicId", version));
+        }
+        if let Some(value) = object.get("partitionStates") {
+            this.partition_states = crate::json::array(value, "UpdateMetadataTopicState.partitionStates")?.iter().map(|item| UpdateMetadataPartitionState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("UpdateMetadataTopicState", "partitionStates", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct UpdateMetadataEndpoint {
     /// The port of this endpoint
@@ -422,11 +662,13 @@ pub struct UpdateMetadataEndpoint {
     /// The hostname of this endpoint
     /// 
     /// Supported API versions: 1-8
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub host: StrBytes,
 
     /// The listener name.
     /// 
     /// Supported API versions: 3-8
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub listener: StrBytes,
 
     /// The security protocol type.
@@ -435,7 +677,7 @@ pub struct UpdateMetadataEndpoint {
     pub security_protocol: i16,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for UpdateMetadataEndpoint {
@@ -448,93 +690,97 @@ impl Builder for UpdateMetadataEndpoint {
 
 impl Encodable for UpdateMetadataEndpoint {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
         if version >= 1 {
-            types::Int32.encode(buf, &self.port)?;
+            types::Int32.encode(buf, &self.port).map_err(|e| e.with_field("UpdateMetadataEndpoint", "port", version))?;
         } else {
             if self.port != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataEndpoint", "port", version));
             }
         }
         if version >= 1 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.host)?;
+                types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("UpdateMetadataEndpoint", "host", version))?;
             } else {
-                types::String.encode(buf, &self.host)?;
+                types::String.encode(buf, &self.host).map_err(|e| e.with_field("UpdateMetadataEndpoint", "host", version))?;
             }
         } else {
             if !self.host.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataEndpoint", "host", version));
             }
         }
         if version >= 3 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.listener)?;
+                types::CompactString.encode(buf, &self.listener).map_err(|e| e.with_field("UpdateMetadataEndpoint", "listener", version))?;
             } else {
-                types::String.encode(buf, &self.listener)?;
+                types::String.encode(buf, &self.listener).map_err(|e| e.with_field("UpdateMetadataEndpoint", "listener", version))?;
             }
         }
         if version >= 1 {
-            types::Int16.encode(buf, &self.security_protocol)?;
+            types::Int16.encode(buf, &self.security_protocol).map_err(|e| e.with_field("UpdateMetadataEndpoint", "security_protocol", version))?;
         } else {
             if self.security_protocol != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataEndpoint", "security_protocol", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
         if version >= 1 {
-            total_size += types::Int32.compute_size(&self.port)?;
+            total_size += types::Int32.compute_size(&self.port).map_err(|e| e.with_field("UpdateMetadataEndpoint", "port", version))?;
         } else {
             if self.port != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataEndpoint", "port", version));
             }
         }
         if version >= 1 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.host)?;
+                total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("UpdateMetadataEndpoint", "host", version))?;
             } else {
-                total_size += types::String.compute_size(&self.host)?;
+                total_size += types::String.compute_size(&self.host).map_err(|e| e.with_field("UpdateMetadataEndpoint", "host", version))?;
             }
         } else {
             if !self.host.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataEndpoint", "host", version));
             }
         }
         if version >= 3 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.listener)?;
+                total_size += types::CompactString.compute_size(&self.listener).map_err(|e| e.with_field("UpdateMetadataEndpoint", "listener", version))?;
             } else {
-                total_size += types::String.compute_size(&self.listener)?;
+                total_size += types::String.compute_size(&self.listener).map_err(|e| e.with_field("UpdateMetadataEndpoint", "listener", version))?;
             }
         }
         if version >= 1 {
-            total_size += types::Int16.compute_size(&self.security_protocol)?;
+            total_size += types::Int16.compute_size(&self.security_protocol).map_err(|e| e.with_field("UpdateMetadataEndpoint", "security_protocol", version))?;
         } else {
             if self.security_protocol != 0 {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataEndpoint", "security_protocol", version));
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -542,42 +788,42 @@ impl Encodable for UpdateMetadataEndpoint {
 
 impl Decodable for UpdateMetadataEndpoint {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
         let port = if version >= 1 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "port", version, start - buf.remaining()))?
         } else {
             0
         };
         let host = if version >= 1 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "host", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "host", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let listener = if version >= 3 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "listener", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "listener", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let security_protocol = if version >= 1 {
-            types::Int16.decode(buf)?
+            types::Int16.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "security_protocol", version, start - buf.remaining()))?
         } else {
             0
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("UpdateMetadataEndpoint", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -598,7 +844,7 @@ impl Default for UpdateMetadataEndpoint {
             host: Default::default(),
             listener: Default::default(),
             security_protocol: 0,
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -607,9 +853,121 @@ impl Message for UpdateMetadataEndpoint {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl UpdateMetadataEndpoint {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.port = 0;
+        }
+        if version < 1 {
+            self.host = Default::default();
+        }
+        if version < 3 {
+            self.listener = Default::default();
+        }
+        if version < 1 {
+            self.security_protocol = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.port != 0 {
+                invalid.push(format!("{}port", path));
+            }
+        }
+        if version < 1 {
+            if !self.host.is_empty() {
+                invalid.push(format!("{}host", path));
+            }
+        }
+        if version < 3 {
+            if !self.listener.is_empty() {
+                invalid.push(format!("{}listener", path));
+            }
+        }
+        if version < 1 {
+            if self.security_protocol != 0 {
+                invalid.push(format!("{}security_protocol", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl UpdateMetadataEndpoint {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if version >= 1 {
+            node.insert("port".into(), JsonValue::to_json(&self.port));
+        }
+        if version >= 1 {
+            node.insert("host".into(), JsonValue::to_json(&self.host));
+        }
+        if version >= 3 {
+            node.insert("listener".into(), JsonValue::to_json(&self.listener));
+        }
+        if version >= 1 {
+            node.insert("securityProtocol".into(), JsonValue::to_json(&self.security_protocol));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "UpdateMetadataEndpoint")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("port") {
+            this.port = JsonValue::from_json(value, "UpdateMetadataEndpoint.port")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("UpdateMetadataEndpoint", "port", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "UpdateMetadataEndpoint.host")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("UpdateMetadataEndpoint", "host", version));
+        }
+        if let Some(value) = object.get("listener") {
+            this.listener = JsonValue::from_json(value, "UpdateMetadataEndpoint.listener")?;
+        } else if version >= 3 {
+            return Err(crate::json::missing("UpdateMetadataEndpoint", "listener", version));
+        }
+        if let Some(value) = object.get("securityProtocol") {
+            this.security_protocol = JsonValue::from_json(value, "UpdateMetadataEndpoint.securityProtocol")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("UpdateMetadataEndpoint", "securityProtocol", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct UpdateMetadataBroker {
     /// The broker id.
@@ -620,6 +978,7 @@ pub struct UpdateMetadataBroker {
     /// The broker hostname.
     /// 
     /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub v0_host: StrBytes,
 
     /// The broker port.
@@ -635,10 +994,12 @@ pub struct UpdateMetadataBroker {
     /// The rack which this broker belongs to.
     /// 
     /// Supported API versions: 2-8
+    /// May be `None` in versions: 2-8
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub rack: Option<StrBytes>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for UpdateMetadataBroker {
@@ -651,71 +1012,75 @@ impl Builder for UpdateMetadataBroker {
 
 impl Encodable for UpdateMetadataBroker {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.id)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int32.encode(buf, &self.id).map_err(|e| e.with_field("UpdateMetadataBroker", "id", version))?;
         if version == 0 {
-            types::String.encode(buf, &self.v0_host)?;
+            types::String.encode(buf, &self.v0_host).map_err(|e| e.with_field("UpdateMetadataBroker", "v0_host", version))?;
         }
         if version == 0 {
-            types::Int32.encode(buf, &self.v0_port)?;
+            types::Int32.encode(buf, &self.v0_port).map_err(|e| e.with_field("UpdateMetadataBroker", "v0_port", version))?;
         }
         if version >= 1 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.endpoints)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.endpoints).map_err(|e| e.with_field("UpdateMetadataBroker", "endpoints", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.endpoints)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.endpoints).map_err(|e| e.with_field("UpdateMetadataBroker", "endpoints", version))?;
             }
         }
         if version >= 2 {
             if version >= 6 {
-                types::CompactString.encode(buf, &self.rack)?;
+                types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("UpdateMetadataBroker", "rack", version))?;
             } else {
-                types::String.encode(buf, &self.rack)?;
+                types::String.encode(buf, &self.rack).map_err(|e| e.with_field("UpdateMetadataBroker", "rack", version))?;
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataBroker", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataBroker", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataBroker", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.id)?;
+        total_size += types::Int32.compute_size(&self.id).map_err(|e| e.with_field("UpdateMetadataBroker", "id", version))?;
         if version == 0 {
-            total_size += types::String.compute_size(&self.v0_host)?;
+            total_size += types::String.compute_size(&self.v0_host).map_err(|e| e.with_field("UpdateMetadataBroker", "v0_host", version))?;
         }
         if version == 0 {
-            total_size += types::Int32.compute_size(&self.v0_port)?;
+            total_size += types::Int32.compute_size(&self.v0_port).map_err(|e| e.with_field("UpdateMetadataBroker", "v0_port", version))?;
         }
         if version >= 1 {
             if version >= 6 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.endpoints)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.endpoints).map_err(|e| e.with_field("UpdateMetadataBroker", "endpoints", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.endpoints)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.endpoints).map_err(|e| e.with_field("UpdateMetadataBroker", "endpoints", version))?;
             }
         }
         if version >= 2 {
             if version >= 6 {
-                total_size += types::CompactString.compute_size(&self.rack)?;
+                total_size += types::CompactString.compute_size(&self.rack).map_err(|e| e.with_field("UpdateMetadataBroker", "rack", version))?;
             } else {
-                total_size += types::String.compute_size(&self.rack)?;
+                total_size += types::String.compute_size(&self.rack).map_err(|e| e.with_field("UpdateMetadataBroker", "rack", version))?;
             }
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataBroker", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataBroker", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataBroker", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -723,43 +1088,43 @@ impl Encodable for UpdateMetadataBroker {
 
 impl Decodable for UpdateMetadataBroker {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let id = types::Int32.decode(buf)?;
+        let start = buf.remaining();
+        let id = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "id", version, start - buf.remaining()))?;
         let v0_host = if version == 0 {
-            types::String.decode(buf)?
+            types::String.decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "v0_host", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let v0_port = if version == 0 {
-            types::Int32.decode(buf)?
+            types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "v0_port", version, start - buf.remaining()))?
         } else {
             0
         };
         let endpoints = if version >= 1 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "endpoints", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "endpoints", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let rack = if version >= 2 {
             if version >= 6 {
-                types::CompactString.decode(buf)?
+                types::CompactString.decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "rack", version, start - buf.remaining()))?
             } else {
-                types::String.decode(buf)?
+                types::String.decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "rack", version, start - buf.remaining()))?
             }
         } else {
             Some(Default::default())
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("UpdateMetadataBroker", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -782,7 +1147,7 @@ impl Default for UpdateMetadataBroker {
             v0_port: 0,
             endpoints: Default::default(),
             rack: Some(Default::default()),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -791,9 +1156,135 @@ impl Message for UpdateMetadataBroker {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl UpdateMetadataBroker {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version != 0 {
+            self.v0_host = Default::default();
+        }
+        if version != 0 {
+            self.v0_port = 0;
+        }
+        if version >= 1 {
+            for item in self.endpoints.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.endpoints = Default::default();
+        }
+        if version < 2 {
+            self.rack = Some(Default::default());
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version != 0 {
+            if !self.v0_host.is_empty() {
+                invalid.push(format!("{}v0_host", path));
+            }
+        }
+        if version != 0 {
+            if self.v0_port != 0 {
+                invalid.push(format!("{}v0_port", path));
+            }
+        }
+        if version >= 1 {
+            for (i, item) in self.endpoints.iter().enumerate() {
+                item.validate_into(version, &format!("{}endpoints[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.endpoints.is_empty() {
+                invalid.push(format!("{}endpoints", path));
+            }
+        }
+        if version < 2 {
+            if !self.rack.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+                invalid.push(format!("{}rack", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl UpdateMetadataBroker {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("id".into(), JsonValue::to_json(&self.id));
+        if version == 0 {
+            node.insert("v0Host".into(), JsonValue::to_json(&self.v0_host));
+        }
+        if version == 0 {
+            node.insert("v0Port".into(), JsonValue::to_json(&self.v0_port));
+        }
+        if version >= 1 {
+            node.insert("endpoints".into(), serde_json::Value::Array(self.endpoints.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 2 {
+            node.insert("rack".into(), JsonValue::to_json(&self.rack));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "UpdateMetadataBroker")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("id") {
+            this.id = JsonValue::from_json(value, "UpdateMetadataBroker.id")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataBroker", "id", version));
+        }
+        if let Some(value) = object.get("v0Host") {
+            this.v0_host = JsonValue::from_json(value, "UpdateMetadataBroker.v0Host")?;
+        } else if version == 0 {
+            return Err(crate::json::missing("UpdateMetadataBroker", "v0Host", version));
+        }
+        if let Some(value) = object.get("v0Port") {
+            this.v0_port = JsonValue::from_json(value, "UpdateMetadataBroker.v0Port")?;
+        } else if version == 0 {
+            return Err(crate::json::missing("UpdateMetadataBroker", "v0Port", version));
+        }
+        if let Some(value) = object.get("endpoints") {
+            this.endpoints = crate::json::array(value, "UpdateMetadataBroker.endpoints")?.iter().map(|item| UpdateMetadataEndpoint::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("UpdateMetadataBroker", "endpoints", version));
+        }
+        if let Some(value) = object.get("rack") {
+            this.rack = JsonValue::from_json(value, "UpdateMetadataBroker.rack")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("UpdateMetadataBroker", "rack", version));
+        }
+        Ok(this)
+    }
+}
+
 /// Valid versions: 0-8
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[builder(default)]
 pub struct UpdateMetadataRequest {
     /// The controller id.
@@ -832,7 +1323,7 @@ pub struct UpdateMetadataRequest {
     pub live_brokers: Vec<UpdateMetadataBroker>,
 
     /// Other tagged fields
-    pub unknown_tagged_fields: BTreeMap<i32, Vec<u8>>,
+    pub unknown_tagged_fields: UnknownTaggedFields,
 }
 
 impl Builder for UpdateMetadataRequest {
@@ -845,99 +1336,109 @@ impl Builder for UpdateMetadataRequest {
 
 impl Encodable for UpdateMetadataRequest {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
-        types::Int32.encode(buf, &self.controller_id)?;
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("UpdateMetadataRequest", version));
+        }
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_id", version))?;
         if version >= 8 {
-            types::Boolean.encode(buf, &self.is_k_raft_controller)?;
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("UpdateMetadataRequest", "is_k_raft_controller", version))?;
         } else {
             if self.is_k_raft_controller {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "is_k_raft_controller", version));
             }
         }
-        types::Int32.encode(buf, &self.controller_epoch)?;
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_epoch", version))?;
         if version >= 5 {
-            types::Int64.encode(buf, &self.broker_epoch)?;
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "broker_epoch", version))?;
         }
         if version <= 4 {
-            types::Array(types::Struct { version }).encode(buf, &self.ungrouped_partition_states)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partition_states).map_err(|e| e.with_field("UpdateMetadataRequest", "ungrouped_partition_states", version))?;
         } else {
             if !self.ungrouped_partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "ungrouped_partition_states", version));
             }
         }
         if version >= 5 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).encode(buf, &self.topic_states)?;
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
             } else {
-                types::Array(types::Struct { version }).encode(buf, &self.topic_states)?;
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
             }
         } else {
             if !self.topic_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "topic_states", version));
             }
         }
         if version >= 6 {
-            types::CompactArray(types::Struct { version }).encode(buf, &self.live_brokers)?;
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.live_brokers).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
         } else {
-            types::Array(types::Struct { version }).encode(buf, &self.live_brokers)?;
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.live_brokers).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataRequest", "unknown_tagged_fields", version));
             }
-            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32)?;
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
 
-            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields)?;
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
         }
         Ok(())
     }
-    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("UpdateMetadataRequest", version));
+        }
         let mut total_size = 0;
-        total_size += types::Int32.compute_size(&self.controller_id)?;
+        total_size += types::Int32.compute_size(&self.controller_id).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_id", version))?;
         if version >= 8 {
-            total_size += types::Boolean.compute_size(&self.is_k_raft_controller)?;
+            total_size += types::Boolean.compute_size(&self.is_k_raft_controller).map_err(|e| e.with_field("UpdateMetadataRequest", "is_k_raft_controller", version))?;
         } else {
             if self.is_k_raft_controller {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "is_k_raft_controller", version));
             }
         }
-        total_size += types::Int32.compute_size(&self.controller_epoch)?;
+        total_size += types::Int32.compute_size(&self.controller_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_epoch", version))?;
         if version >= 5 {
-            total_size += types::Int64.compute_size(&self.broker_epoch)?;
+            total_size += types::Int64.compute_size(&self.broker_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "broker_epoch", version))?;
         }
         if version <= 4 {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.ungrouped_partition_states)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.ungrouped_partition_states).map_err(|e| e.with_field("UpdateMetadataRequest", "ungrouped_partition_states", version))?;
         } else {
             if !self.ungrouped_partition_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "ungrouped_partition_states", version));
             }
         }
         if version >= 5 {
             if version >= 6 {
-                total_size += types::CompactArray(types::Struct { version }).compute_size(&self.topic_states)?;
+                total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.topic_states).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
             } else {
-                total_size += types::Array(types::Struct { version }).compute_size(&self.topic_states)?;
+                total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.topic_states).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
             }
         } else {
             if !self.topic_states.is_empty() {
-                return Err(EncodeError)
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "topic_states", version));
             }
         }
         if version >= 6 {
-            total_size += types::CompactArray(types::Struct { version }).compute_size(&self.live_brokers)?;
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.live_brokers).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
         } else {
-            total_size += types::Array(types::Struct { version }).compute_size(&self.live_brokers)?;
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.live_brokers).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
         }
         if version >= 6 {
             let num_tagged_fields = self.unknown_tagged_fields.len();
             if num_tagged_fields > std::u32::MAX as usize {
-                error!("Too many tagged fields to encode ({} fields)", num_tagged_fields);
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataRequest", "unknown_tagged_fields", version));
             }
-            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32)?;
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
 
-            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields)?;
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
         }
         Ok(total_size)
     }
@@ -945,45 +1446,48 @@ impl Encodable for UpdateMetadataRequest {
 
 impl Decodable for UpdateMetadataRequest {
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
-        let controller_id = types::Int32.decode(buf)?;
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("UpdateMetadataRequest", version));
+        }
+        let start = buf.remaining();
+        let controller_id = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_id", version, start - buf.remaining()))?;
         let is_k_raft_controller = if version >= 8 {
-            types::Boolean.decode(buf)?
+            types::Boolean.decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "is_k_raft_controller", version, start - buf.remaining()))?
         } else {
             false
         };
-        let controller_epoch = types::Int32.decode(buf)?;
+        let controller_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_epoch", version, start - buf.remaining()))?;
         let broker_epoch = if version >= 5 {
-            types::Int64.decode(buf)?
+            types::Int64.decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "broker_epoch", version, start - buf.remaining()))?
         } else {
             -1
         };
         let ungrouped_partition_states = if version <= 4 {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "ungrouped_partition_states", version, start - buf.remaining()))?
         } else {
             Default::default()
         };
         let topic_states = if version >= 5 {
             if version >= 6 {
-                types::CompactArray(types::Struct { version }).decode(buf)?
+                types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version, start - buf.remaining()))?
             } else {
-                types::Array(types::Struct { version }).decode(buf)?
+                types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version, start - buf.remaining()))?
             }
         } else {
             Default::default()
         };
         let live_brokers = if version >= 6 {
-            types::CompactArray(types::Struct { version }).decode(buf)?
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version, start - buf.remaining()))?
         } else {
-            types::Array(types::Struct { version }).decode(buf)?
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version, start - buf.remaining()))?
         };
-        let mut unknown_tagged_fields = BTreeMap::new();
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
         if version >= 6 {
-            let num_tagged_fields = types::UnsignedVarInt.decode(buf)?;
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
             for _ in 0..num_tagged_fields {
-                let tag: u32 = types::UnsignedVarInt.decode(buf)?;
-                let size: u32 = types::UnsignedVarInt.decode(buf)?;
-                let mut unknown_value = vec![0; size as usize];
-                buf.try_copy_to_slice(&mut unknown_value)?;
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version, start - buf.remaining()))?;
                 unknown_tagged_fields.insert(tag as i32, unknown_value);
             }
         }
@@ -1010,7 +1514,7 @@ impl Default for UpdateMetadataRequest {
             ungrouped_partition_states: Default::default(),
             topic_states: Default::default(),
             live_brokers: Default::default(),
-            unknown_tagged_fields: BTreeMap::new(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
         }
     }
 }
@@ -1019,6 +1523,350 @@ impl Message for UpdateMetadataRequest {
     const VERSIONS: VersionRange = VersionRange { min: 0, max: 8 };
 }
 
+impl UpdateMetadataRequest {
+    /// Encode the message with the elements of `ungrouped_partition_states` produced by an iterator of known length, in place of
+    /// the `ungrouped_partition_states` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_ungrouped_partition_states<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, ungrouped_partition_states: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = UpdateMetadataPartitionState>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("UpdateMetadataRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let ungrouped_partition_states = ungrouped_partition_states.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_id", version))?;
+        if version >= 8 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("UpdateMetadataRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_epoch", version))?;
+        if version >= 5 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "broker_epoch", version))?;
+        }
+        if version <= 4 {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(ungrouped_partition_states)).map_err(|e| e.with_field("UpdateMetadataRequest", "ungrouped_partition_states", version))?;
+        } else {
+            if ungrouped_partition_states.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "ungrouped_partition_states", version));
+            }
+        }
+        if version >= 5 {
+            if version >= 6 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
+            }
+        } else {
+            if !self.topic_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "topic_states", version));
+            }
+        }
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.live_brokers).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.live_brokers).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `topic_states` produced by an iterator of known length, in place of
+    /// the `topic_states` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_topic_states<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, topic_states: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = UpdateMetadataTopicState>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("UpdateMetadataRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let topic_states = topic_states.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_id", version))?;
+        if version >= 8 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("UpdateMetadataRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_epoch", version))?;
+        if version >= 5 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "broker_epoch", version))?;
+        }
+        if version <= 4 {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partition_states).map_err(|e| e.with_field("UpdateMetadataRequest", "ungrouped_partition_states", version))?;
+        } else {
+            if !self.ungrouped_partition_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "ungrouped_partition_states", version));
+            }
+        }
+        if version >= 5 {
+            if version >= 6 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(topic_states)).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(topic_states)).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
+            }
+        } else {
+            if topic_states.len() != 0 {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "topic_states", version));
+            }
+        }
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.live_brokers).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.live_brokers).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `live_brokers` produced by an iterator of known length, in place of
+    /// the `live_brokers` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_live_brokers<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, live_brokers: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = UpdateMetadataBroker>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("UpdateMetadataRequest", version));
+        }
+        let cache = &SizeCache::default();
+        let live_brokers = live_brokers.into_iter();
+        types::Int32.encode(buf, &self.controller_id).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_id", version))?;
+        if version >= 8 {
+            types::Boolean.encode(buf, &self.is_k_raft_controller).map_err(|e| e.with_field("UpdateMetadataRequest", "is_k_raft_controller", version))?;
+        } else {
+            if self.is_k_raft_controller {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "is_k_raft_controller", version));
+            }
+        }
+        types::Int32.encode(buf, &self.controller_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "controller_epoch", version))?;
+        if version >= 5 {
+            types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("UpdateMetadataRequest", "broker_epoch", version))?;
+        }
+        if version <= 4 {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.ungrouped_partition_states).map_err(|e| e.with_field("UpdateMetadataRequest", "ungrouped_partition_states", version))?;
+        } else {
+            if !self.ungrouped_partition_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "ungrouped_partition_states", version));
+            }
+        }
+        if version >= 5 {
+            if version >= 6 {
+                types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
+            } else {
+                types::Array(CachedStruct { version, cache }).encode(buf, &self.topic_states).map_err(|e| e.with_field("UpdateMetadataRequest", "topic_states", version))?;
+            }
+        } else {
+            if !self.topic_states.is_empty() {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("UpdateMetadataRequest", "topic_states", version));
+            }
+        }
+        if version >= 6 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(live_brokers)).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, types::Streamed(live_brokers)).map_err(|e| e.with_field("UpdateMetadataRequest", "live_brokers", version))?;
+        }
+        if version >= 6 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("UpdateMetadataRequest", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("UpdateMetadataRequest", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+}
+
+impl UpdateMetadataRequest {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 8 {
+            self.is_k_raft_controller = false;
+        }
+        if version < 5 {
+            self.broker_epoch = -1;
+        }
+        if version <= 4 {
+            for item in self.ungrouped_partition_states.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.ungrouped_partition_states = Default::default();
+        }
+        if version >= 5 {
+            for item in self.topic_states.iter_mut() {
+                item.normalize_to(version);
+            }
+        } else {
+            self.topic_states = Default::default();
+        }
+        for item in self.live_brokers.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 8 {
+            if self.is_k_raft_controller {
+                invalid.push(format!("{}is_k_raft_controller", path));
+            }
+        }
+        if version < 5 {
+            if self.broker_epoch != -1 {
+                invalid.push(format!("{}broker_epoch", path));
+            }
+        }
+        if version <= 4 {
+            for (i, item) in self.ungrouped_partition_states.iter().enumerate() {
+                item.validate_into(version, &format!("{}ungrouped_partition_states[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.ungrouped_partition_states.is_empty() {
+                invalid.push(format!("{}ungrouped_partition_states", path));
+            }
+        }
+        if version >= 5 {
+            for (i, item) in self.topic_states.iter().enumerate() {
+                item.validate_into(version, &format!("{}topic_states[{}].", path, i), invalid);
+            }
+        } else {
+            if !self.topic_states.is_empty() {
+                invalid.push(format!("{}topic_states", path));
+            }
+        }
+        for (i, item) in self.live_brokers.iter().enumerate() {
+            item.validate_into(version, &format!("{}live_brokers[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl UpdateMetadataRequest {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("controllerId".into(), JsonValue::to_json(&self.controller_id));
+        if version >= 8 {
+            node.insert("isKRaftController".into(), JsonValue::to_json(&self.is_k_raft_controller));
+        }
+        node.insert("controllerEpoch".into(), JsonValue::to_json(&self.controller_epoch));
+        if version >= 5 {
+            node.insert("brokerEpoch".into(), JsonValue::to_json(&self.broker_epoch));
+        }
+        if version <= 4 {
+            node.insert("ungroupedPartitionStates".into(), serde_json::Value::Array(self.ungrouped_partition_states.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        if version >= 5 {
+            node.insert("topicStates".into(), serde_json::Value::Array(self.topic_states.iter().map(|item| item.to_json_with(version, records)).collect()));
+        }
+        node.insert("liveBrokers".into(), serde_json::Value::Array(self.live_brokers.iter().map(|item| item.to_json_with(version, records)).collect()));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "UpdateMetadataRequest")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("controllerId") {
+            this.controller_id = JsonValue::from_json(value, "UpdateMetadataRequest.controllerId")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataRequest", "controllerId", version));
+        }
+        if let Some(value) = object.get("isKRaftController") {
+            this.is_k_raft_controller = JsonValue::from_json(value, "UpdateMetadataRequest.isKRaftController")?;
+        } else if version >= 8 {
+            return Err(crate::json::missing("UpdateMetadataRequest", "isKRaftController", version));
+        }
+        if let Some(value) = object.get("controllerEpoch") {
+            this.controller_epoch = JsonValue::from_json(value, "UpdateMetadataRequest.controllerEpoch")?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataRequest", "controllerEpoch", version));
+        }
+        if let Some(value) = object.get("brokerEpoch") {
+            this.broker_epoch = JsonValue::from_json(value, "UpdateMetadataRequest.brokerEpoch")?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("UpdateMetadataRequest", "brokerEpoch", version));
+        }
+        if let Some(value) = object.get("ungroupedPartitionStates") {
+            this.ungrouped_partition_states = crate::json::array(value, "UpdateMetadataRequest.ungroupedPartitionStates")?.iter().map(|item| UpdateMetadataPartitionState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version <= 4 {
+            return Err(crate::json::missing("UpdateMetadataRequest", "ungroupedPartitionStates", version));
+        }
+        if let Some(value) = object.get("topicStates") {
+            this.topic_states = crate::json::array(value, "UpdateMetadataRequest.topicStates")?.iter().map(|item| UpdateMetadataTopicState::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else if version >= 5 {
+            return Err(crate::json::missing("UpdateMetadataRequest", "topicStates", version));
+        }
+        if let Some(value) = object.get("liveBrokers") {
+            this.live_brokers = crate::json::array(value, "UpdateMetadataRequest.liveBrokers")?.iter().map(|item| UpdateMetadataBroker::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("UpdateMetadataRequest", "liveBrokers", version));
+        }
+        Ok(this)
+    }
+}
+
 impl HeaderVersion for UpdateMetadataRequest {
     fn header_version(version: i16) -> i16 {
         if version >= 6 {
@@ -1029,3 +1877,16 @@ impl HeaderVersion for UpdateMetadataRequest {
     }
 }
 
+impl UpdateMetadataRequest {
+    /// Builds a response reporting `error` for this request and every resource it contains.
+    ///
+    /// Topics, partitions, groups and similar resources are mirrored from the request, and every
+    /// error code available in `version` is set. Other fields are left at their default values.
+    #[allow(clippy::field_reassign_with_default)]
+    pub fn error_response(&self, version: i16, error: ResponseError) -> super::UpdateMetadataResponse {
+        let mut response = super::UpdateMetadataResponse::default();
+        response.error_code = error.code();
+        response
+    }
+}
+
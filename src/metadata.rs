@@ -0,0 +1,613 @@
+//! KRaft cluster metadata records, as stored in the `__cluster_metadata` log and its snapshots.
+//!
+//! These records are generated programmatically. See [Kafka's metadata schemas](https://github.com/apache/kafka/tree/trunk/metadata/src/main/resources/common/metadata) for more information about a given record type.
+//!
+//! Each record in the log is framed as an [`ApiMessageAndVersion`], which can be decoded from the value of a
+//! [`Record`](crate::records::Record) read from the log.
+// WARNING: the items of this module are generated and should not be edited directly.
+
+use bytes::Buf;
+use crate::protocol::{Decodable, DecodeError, DecodeErrorKind, Encodable, EncodeError, EncodeErrorKind, Message, VersionRange, buf::{ByteBuf, ByteBufMut}, types, Decoder, Encoder};
+use std::convert::TryFrom;
+
+pub use crate::messages::{BrokerId, TopicName};
+
+pub mod abort_transaction_record;
+pub use abort_transaction_record::AbortTransactionRecord;
+
+pub mod access_control_entry_record;
+pub use access_control_entry_record::AccessControlEntryRecord;
+
+pub mod begin_transaction_record;
+pub use begin_transaction_record::BeginTransactionRecord;
+
+pub mod broker_registration_change_record;
+pub use broker_registration_change_record::BrokerRegistrationChangeRecord;
+
+pub mod client_quota_record;
+pub use client_quota_record::ClientQuotaRecord;
+
+pub mod config_record;
+pub use config_record::ConfigRecord;
+
+pub mod delegation_token_record;
+pub use delegation_token_record::DelegationTokenRecord;
+
+pub mod end_transaction_record;
+pub use end_transaction_record::EndTransactionRecord;
+
+pub mod feature_level_record;
+pub use feature_level_record::FeatureLevelRecord;
+
+pub mod fence_broker_record;
+pub use fence_broker_record::FenceBrokerRecord;
+
+pub mod no_op_record;
+pub use no_op_record::NoOpRecord;
+
+pub mod partition_change_record;
+pub use partition_change_record::PartitionChangeRecord;
+
+pub mod partition_record;
+pub use partition_record::PartitionRecord;
+
+pub mod producer_ids_record;
+pub use producer_ids_record::ProducerIdsRecord;
+
+pub mod register_broker_record;
+pub use register_broker_record::RegisterBrokerRecord;
+
+pub mod remove_access_control_entry_record;
+pub use remove_access_control_entry_record::RemoveAccessControlEntryRecord;
+
+pub mod remove_delegation_token_record;
+pub use remove_delegation_token_record::RemoveDelegationTokenRecord;
+
+pub mod remove_topic_record;
+pub use remove_topic_record::RemoveTopicRecord;
+
+pub mod remove_user_scram_credential_record;
+pub use remove_user_scram_credential_record::RemoveUserScramCredentialRecord;
+
+pub mod topic_record;
+pub use topic_record::TopicRecord;
+
+pub mod unfence_broker_record;
+pub use unfence_broker_record::UnfenceBrokerRecord;
+
+pub mod unregister_broker_record;
+pub use unregister_broker_record::UnregisterBrokerRecord;
+
+pub mod user_scram_credential_record;
+pub use user_scram_credential_record::UserScramCredentialRecord;
+
+pub mod zk_migration_state_record;
+pub use zk_migration_state_record::ZkMigrationStateRecord;
+
+/// The type of a cluster metadata record, identified by its api key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MetadataRecordType {
+    /// The type of [`RegisterBrokerRecord`]
+    RegisterBrokerRecord = 0,
+    /// The type of [`UnregisterBrokerRecord`]
+    UnregisterBrokerRecord = 1,
+    /// The type of [`TopicRecord`]
+    TopicRecord = 2,
+    /// The type of [`PartitionRecord`]
+    PartitionRecord = 3,
+    /// The type of [`ConfigRecord`]
+    ConfigRecord = 4,
+    /// The type of [`PartitionChangeRecord`]
+    PartitionChangeRecord = 5,
+    /// The type of [`AccessControlEntryRecord`]
+    AccessControlEntryRecord = 6,
+    /// The type of [`FenceBrokerRecord`]
+    FenceBrokerRecord = 7,
+    /// The type of [`UnfenceBrokerRecord`]
+    UnfenceBrokerRecord = 8,
+    /// The type of [`RemoveTopicRecord`]
+    RemoveTopicRecord = 9,
+    /// The type of [`DelegationTokenRecord`]
+    DelegationTokenRecord = 10,
+    /// The type of [`UserScramCredentialRecord`]
+    UserScramCredentialRecord = 11,
+    /// The type of [`FeatureLevelRecord`]
+    FeatureLevelRecord = 12,
+    /// The type of [`ClientQuotaRecord`]
+    ClientQuotaRecord = 14,
+    /// The type of [`ProducerIdsRecord`]
+    ProducerIdsRecord = 15,
+    /// The type of [`BrokerRegistrationChangeRecord`]
+    BrokerRegistrationChangeRecord = 17,
+    /// The type of [`RemoveAccessControlEntryRecord`]
+    RemoveAccessControlEntryRecord = 18,
+    /// The type of [`RemoveDelegationTokenRecord`]
+    RemoveDelegationTokenRecord = 19,
+    /// The type of [`NoOpRecord`]
+    NoOpRecord = 20,
+    /// The type of [`ZkMigrationStateRecord`]
+    ZkMigrationStateRecord = 21,
+    /// The type of [`RemoveUserScramCredentialRecord`]
+    RemoveUserScramCredentialRecord = 22,
+    /// The type of [`BeginTransactionRecord`]
+    BeginTransactionRecord = 23,
+    /// The type of [`EndTransactionRecord`]
+    EndTransactionRecord = 24,
+    /// The type of [`AbortTransactionRecord`]
+    AbortTransactionRecord = 25,
+}
+
+impl MetadataRecordType {
+    /// All record types known to this crate, in ascending order.
+    pub fn all() -> &'static [MetadataRecordType] {
+        &[
+            MetadataRecordType::RegisterBrokerRecord,
+            MetadataRecordType::UnregisterBrokerRecord,
+            MetadataRecordType::TopicRecord,
+            MetadataRecordType::PartitionRecord,
+            MetadataRecordType::ConfigRecord,
+            MetadataRecordType::PartitionChangeRecord,
+            MetadataRecordType::AccessControlEntryRecord,
+            MetadataRecordType::FenceBrokerRecord,
+            MetadataRecordType::UnfenceBrokerRecord,
+            MetadataRecordType::RemoveTopicRecord,
+            MetadataRecordType::DelegationTokenRecord,
+            MetadataRecordType::UserScramCredentialRecord,
+            MetadataRecordType::FeatureLevelRecord,
+            MetadataRecordType::ClientQuotaRecord,
+            MetadataRecordType::ProducerIdsRecord,
+            MetadataRecordType::BrokerRegistrationChangeRecord,
+            MetadataRecordType::RemoveAccessControlEntryRecord,
+            MetadataRecordType::RemoveDelegationTokenRecord,
+            MetadataRecordType::NoOpRecord,
+            MetadataRecordType::ZkMigrationStateRecord,
+            MetadataRecordType::RemoveUserScramCredentialRecord,
+            MetadataRecordType::BeginTransactionRecord,
+            MetadataRecordType::EndTransactionRecord,
+            MetadataRecordType::AbortTransactionRecord,
+        ]
+    }
+
+    /// The name of this record type as used by Kafka, e.g. `TopicRecord`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MetadataRecordType::RegisterBrokerRecord => "RegisterBrokerRecord",
+            MetadataRecordType::UnregisterBrokerRecord => "UnregisterBrokerRecord",
+            MetadataRecordType::TopicRecord => "TopicRecord",
+            MetadataRecordType::PartitionRecord => "PartitionRecord",
+            MetadataRecordType::ConfigRecord => "ConfigRecord",
+            MetadataRecordType::PartitionChangeRecord => "PartitionChangeRecord",
+            MetadataRecordType::AccessControlEntryRecord => "AccessControlEntryRecord",
+            MetadataRecordType::FenceBrokerRecord => "FenceBrokerRecord",
+            MetadataRecordType::UnfenceBrokerRecord => "UnfenceBrokerRecord",
+            MetadataRecordType::RemoveTopicRecord => "RemoveTopicRecord",
+            MetadataRecordType::DelegationTokenRecord => "DelegationTokenRecord",
+            MetadataRecordType::UserScramCredentialRecord => "UserScramCredentialRecord",
+            MetadataRecordType::FeatureLevelRecord => "FeatureLevelRecord",
+            MetadataRecordType::ClientQuotaRecord => "ClientQuotaRecord",
+            MetadataRecordType::ProducerIdsRecord => "ProducerIdsRecord",
+            MetadataRecordType::BrokerRegistrationChangeRecord => "BrokerRegistrationChangeRecord",
+            MetadataRecordType::RemoveAccessControlEntryRecord => "RemoveAccessControlEntryRecord",
+            MetadataRecordType::RemoveDelegationTokenRecord => "RemoveDelegationTokenRecord",
+            MetadataRecordType::NoOpRecord => "NoOpRecord",
+            MetadataRecordType::ZkMigrationStateRecord => "ZkMigrationStateRecord",
+            MetadataRecordType::RemoveUserScramCredentialRecord => "RemoveUserScramCredentialRecord",
+            MetadataRecordType::BeginTransactionRecord => "BeginTransactionRecord",
+            MetadataRecordType::EndTransactionRecord => "EndTransactionRecord",
+            MetadataRecordType::AbortTransactionRecord => "AbortTransactionRecord",
+        }
+    }
+
+    /// The versions of this record type supported by this crate.
+    pub fn valid_versions(&self) -> VersionRange {
+        match self {
+            MetadataRecordType::RegisterBrokerRecord => RegisterBrokerRecord::VERSIONS,
+            MetadataRecordType::UnregisterBrokerRecord => UnregisterBrokerRecord::VERSIONS,
+            MetadataRecordType::TopicRecord => TopicRecord::VERSIONS,
+            MetadataRecordType::PartitionRecord => PartitionRecord::VERSIONS,
+            MetadataRecordType::ConfigRecord => ConfigRecord::VERSIONS,
+            MetadataRecordType::PartitionChangeRecord => PartitionChangeRecord::VERSIONS,
+            MetadataRecordType::AccessControlEntryRecord => AccessControlEntryRecord::VERSIONS,
+            MetadataRecordType::FenceBrokerRecord => FenceBrokerRecord::VERSIONS,
+            MetadataRecordType::UnfenceBrokerRecord => UnfenceBrokerRecord::VERSIONS,
+            MetadataRecordType::RemoveTopicRecord => RemoveTopicRecord::VERSIONS,
+            MetadataRecordType::DelegationTokenRecord => DelegationTokenRecord::VERSIONS,
+            MetadataRecordType::UserScramCredentialRecord => UserScramCredentialRecord::VERSIONS,
+            MetadataRecordType::FeatureLevelRecord => FeatureLevelRecord::VERSIONS,
+            MetadataRecordType::ClientQuotaRecord => ClientQuotaRecord::VERSIONS,
+            MetadataRecordType::ProducerIdsRecord => ProducerIdsRecord::VERSIONS,
+            MetadataRecordType::BrokerRegistrationChangeRecord => BrokerRegistrationChangeRecord::VERSIONS,
+            MetadataRecordType::RemoveAccessControlEntryRecord => RemoveAccessControlEntryRecord::VERSIONS,
+            MetadataRecordType::RemoveDelegationTokenRecord => RemoveDelegationTokenRecord::VERSIONS,
+            MetadataRecordType::NoOpRecord => NoOpRecord::VERSIONS,
+            MetadataRecordType::ZkMigrationStateRecord => ZkMigrationStateRecord::VERSIONS,
+            MetadataRecordType::RemoveUserScramCredentialRecord => RemoveUserScramCredentialRecord::VERSIONS,
+            MetadataRecordType::BeginTransactionRecord => BeginTransactionRecord::VERSIONS,
+            MetadataRecordType::EndTransactionRecord => EndTransactionRecord::VERSIONS,
+            MetadataRecordType::AbortTransactionRecord => AbortTransactionRecord::VERSIONS,
+        }
+    }
+}
+
+impl TryFrom<i16> for MetadataRecordType {
+    type Error = ();
+
+    fn try_from(v: i16) -> Result<Self, Self::Error> {
+        match v {
+            x if x == MetadataRecordType::RegisterBrokerRecord as i16 => Ok(MetadataRecordType::RegisterBrokerRecord),
+            x if x == MetadataRecordType::UnregisterBrokerRecord as i16 => Ok(MetadataRecordType::UnregisterBrokerRecord),
+            x if x == MetadataRecordType::TopicRecord as i16 => Ok(MetadataRecordType::TopicRecord),
+            x if x == MetadataRecordType::PartitionRecord as i16 => Ok(MetadataRecordType::PartitionRecord),
+            x if x == MetadataRecordType::ConfigRecord as i16 => Ok(MetadataRecordType::ConfigRecord),
+            x if x == MetadataRecordType::PartitionChangeRecord as i16 => Ok(MetadataRecordType::PartitionChangeRecord),
+            x if x == MetadataRecordType::AccessControlEntryRecord as i16 => Ok(MetadataRecordType::AccessControlEntryRecord),
+            x if x == MetadataRecordType::FenceBrokerRecord as i16 => Ok(MetadataRecordType::FenceBrokerRecord),
+            x if x == MetadataRecordType::UnfenceBrokerRecord as i16 => Ok(MetadataRecordType::UnfenceBrokerRecord),
+            x if x == MetadataRecordType::RemoveTopicRecord as i16 => Ok(MetadataRecordType::RemoveTopicRecord),
+            x if x == MetadataRecordType::DelegationTokenRecord as i16 => Ok(MetadataRecordType::DelegationTokenRecord),
+            x if x == MetadataRecordType::UserScramCredentialRecord as i16 => Ok(MetadataRecordType::UserScramCredentialRecord),
+            x if x == MetadataRecordType::FeatureLevelRecord as i16 => Ok(MetadataRecordType::FeatureLevelRecord),
+            x if x == MetadataRecordType::ClientQuotaRecord as i16 => Ok(MetadataRecordType::ClientQuotaRecord),
+            x if x == MetadataRecordType::ProducerIdsRecord as i16 => Ok(MetadataRecordType::ProducerIdsRecord),
+            x if x == MetadataRecordType::BrokerRegistrationChangeRecord as i16 => Ok(MetadataRecordType::BrokerRegistrationChangeRecord),
+            x if x == MetadataRecordType::RemoveAccessControlEntryRecord as i16 => Ok(MetadataRecordType::RemoveAccessControlEntryRecord),
+            x if x == MetadataRecordType::RemoveDelegationTokenRecord as i16 => Ok(MetadataRecordType::RemoveDelegationTokenRecord),
+            x if x == MetadataRecordType::NoOpRecord as i16 => Ok(MetadataRecordType::NoOpRecord),
+            x if x == MetadataRecordType::ZkMigrationStateRecord as i16 => Ok(MetadataRecordType::ZkMigrationStateRecord),
+            x if x == MetadataRecordType::RemoveUserScramCredentialRecord as i16 => Ok(MetadataRecordType::RemoveUserScramCredentialRecord),
+            x if x == MetadataRecordType::BeginTransactionRecord as i16 => Ok(MetadataRecordType::BeginTransactionRecord),
+            x if x == MetadataRecordType::EndTransactionRecord as i16 => Ok(MetadataRecordType::EndTransactionRecord),
+            x if x == MetadataRecordType::AbortTransactionRecord as i16 => Ok(MetadataRecordType::AbortTransactionRecord),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Wrapping enum for all cluster metadata records.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MetadataRecord {
+    /// RegisterBrokerRecord,
+    RegisterBrokerRecord(RegisterBrokerRecord),
+    /// UnregisterBrokerRecord,
+    UnregisterBrokerRecord(UnregisterBrokerRecord),
+    /// TopicRecord,
+    TopicRecord(TopicRecord),
+    /// PartitionRecord,
+    PartitionRecord(PartitionRecord),
+    /// ConfigRecord,
+    ConfigRecord(ConfigRecord),
+    /// PartitionChangeRecord,
+    PartitionChangeRecord(PartitionChangeRecord),
+    /// AccessControlEntryRecord,
+    AccessControlEntryRecord(AccessControlEntryRecord),
+    /// FenceBrokerRecord,
+    FenceBrokerRecord(FenceBrokerRecord),
+    /// UnfenceBrokerRecord,
+    UnfenceBrokerRecord(UnfenceBrokerRecord),
+    /// RemoveTopicRecord,
+    RemoveTopicRecord(RemoveTopicRecord),
+    /// DelegationTokenRecord,
+    DelegationTokenRecord(DelegationTokenRecord),
+    /// UserScramCredentialRecord,
+    UserScramCredentialRecord(UserScramCredentialRecord),
+    /// FeatureLevelRecord,
+    FeatureLevelRecord(FeatureLevelRecord),
+    /// ClientQuotaRecord,
+    ClientQuotaRecord(ClientQuotaRecord),
+    /// ProducerIdsRecord,
+    ProducerIdsRecord(ProducerIdsRecord),
+    /// BrokerRegistrationChangeRecord,
+    BrokerRegistrationChangeRecord(BrokerRegistrationChangeRecord),
+    /// RemoveAccessControlEntryRecord,
+    RemoveAccessControlEntryRecord(RemoveAccessControlEntryRecord),
+    /// RemoveDelegationTokenRecord,
+    RemoveDelegationTokenRecord(RemoveDelegationTokenRecord),
+    /// NoOpRecord,
+    NoOpRecord(NoOpRecord),
+    /// ZkMigrationStateRecord,
+    ZkMigrationStateRecord(ZkMigrationStateRecord),
+    /// RemoveUserScramCredentialRecord,
+    RemoveUserScramCredentialRecord(RemoveUserScramCredentialRecord),
+    /// BeginTransactionRecord,
+    BeginTransactionRecord(BeginTransactionRecord),
+    /// EndTransactionRecord,
+    EndTransactionRecord(EndTransactionRecord),
+    /// AbortTransactionRecord,
+    AbortTransactionRecord(AbortTransactionRecord),
+}
+
+impl MetadataRecord {
+    /// The type of this record.
+    pub fn record_type(&self) -> MetadataRecordType {
+        match self {
+            MetadataRecord::RegisterBrokerRecord(_) => MetadataRecordType::RegisterBrokerRecord,
+            MetadataRecord::UnregisterBrokerRecord(_) => MetadataRecordType::UnregisterBrokerRecord,
+            MetadataRecord::TopicRecord(_) => MetadataRecordType::TopicRecord,
+            MetadataRecord::PartitionRecord(_) => MetadataRecordType::PartitionRecord,
+            MetadataRecord::ConfigRecord(_) => MetadataRecordType::ConfigRecord,
+            MetadataRecord::PartitionChangeRecord(_) => MetadataRecordType::PartitionChangeRecord,
+            MetadataRecord::AccessControlEntryRecord(_) => MetadataRecordType::AccessControlEntryRecord,
+            MetadataRecord::FenceBrokerRecord(_) => MetadataRecordType::FenceBrokerRecord,
+            MetadataRecord::UnfenceBrokerRecord(_) => MetadataRecordType::UnfenceBrokerRecord,
+            MetadataRecord::RemoveTopicRecord(_) => MetadataRecordType::RemoveTopicRecord,
+            MetadataRecord::DelegationTokenRecord(_) => MetadataRecordType::DelegationTokenRecord,
+            MetadataRecord::UserScramCredentialRecord(_) => MetadataRecordType::UserScramCredentialRecord,
+            MetadataRecord::FeatureLevelRecord(_) => MetadataRecordType::FeatureLevelRecord,
+            MetadataRecord::ClientQuotaRecord(_) => MetadataRecordType::ClientQuotaRecord,
+            MetadataRecord::ProducerIdsRecord(_) => MetadataRecordType::ProducerIdsRecord,
+            MetadataRecord::BrokerRegistrationChangeRecord(_) => MetadataRecordType::BrokerRegistrationChangeRecord,
+            MetadataRecord::RemoveAccessControlEntryRecord(_) => MetadataRecordType::RemoveAccessControlEntryRecord,
+            MetadataRecord::RemoveDelegationTokenRecord(_) => MetadataRecordType::RemoveDelegationTokenRecord,
+            MetadataRecord::NoOpRecord(_) => MetadataRecordType::NoOpRecord,
+            MetadataRecord::ZkMigrationStateRecord(_) => MetadataRecordType::ZkMigrationStateRecord,
+            MetadataRecord::RemoveUserScramCredentialRecord(_) => MetadataRecordType::RemoveUserScramCredentialRecord,
+            MetadataRecord::BeginTransactionRecord(_) => MetadataRecordType::BeginTransactionRecord,
+            MetadataRecord::EndTransactionRecord(_) => MetadataRecordType::EndTransactionRecord,
+            MetadataRecord::AbortTransactionRecord(_) => MetadataRecordType::AbortTransactionRecord,
+        }
+    }
+
+    /// Decode a record of the given type and version.
+    pub fn decode<B: ByteBuf>(record_type: MetadataRecordType, buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        match record_type {
+            MetadataRecordType::RegisterBrokerRecord => RegisterBrokerRecord::decode(buf, version).map(MetadataRecord::RegisterBrokerRecord),
+            MetadataRecordType::UnregisterBrokerRecord => UnregisterBrokerRecord::decode(buf, version).map(MetadataRecord::UnregisterBrokerRecord),
+            MetadataRecordType::TopicRecord => TopicRecord::decode(buf, version).map(MetadataRecord::TopicRecord),
+            MetadataRecordType::PartitionRecord => PartitionRecord::decode(buf, version).map(MetadataRecord::PartitionRecord),
+            MetadataRecordType::ConfigRecord => ConfigRecord::decode(buf, version).map(MetadataRecord::ConfigRecord),
+            MetadataRecordType::PartitionChangeRecord => PartitionChangeRecord::decode(buf, version).map(MetadataRecord::PartitionChangeRecord),
+            MetadataRecordType::AccessControlEntryRecord => AccessControlEntryRecord::decode(buf, version).map(MetadataRecord::AccessControlEntryRecord),
+            MetadataRecordType::FenceBrokerRecord => FenceBrokerRecord::decode(buf, version).map(MetadataRecord::FenceBrokerRecord),
+            MetadataRecordType::UnfenceBrokerRecord => UnfenceBrokerRecord::decode(buf, version).map(MetadataRecord::UnfenceBrokerRecord),
+            MetadataRecordType::RemoveTopicRecord => RemoveTopicRecord::decode(buf, version).map(MetadataRecord::RemoveTopicRecord),
+            MetadataRecordType::DelegationTokenRecord => DelegationTokenRecord::decode(buf, version).map(MetadataRecord::DelegationTokenRecord),
+            MetadataRecordType::UserScramCredentialRecord => UserScramCredentialRecord::decode(buf, version).map(MetadataRecord::UserScramCredentialRecord),
+            MetadataRecordType::FeatureLevelRecord => FeatureLevelRecord::decode(buf, version).map(MetadataRecord::FeatureLevelRecord),
+            MetadataRecordType::ClientQuotaRecord => ClientQuotaRecord::decode(buf, version).map(MetadataRecord::ClientQuotaRecord),
+            MetadataRecordType::ProducerIdsRecord => ProducerIdsRecord::decode(buf, version).map(MetadataRecord::ProducerIdsRecord),
+            MetadataRecordType::BrokerRegistrationChangeRecord => BrokerRegistrationChangeRecord::decode(buf, version).map(MetadataRecord::BrokerRegistrationChangeRecord),
+            MetadataRecordType::RemoveAccessControlEntryRecord => RemoveAccessControlEntryRecord::decode(buf, version).map(MetadataRecord::RemoveAccessControlEntryRecord),
+            MetadataRecordType::RemoveDelegationTokenRecord => RemoveDelegationTokenRecord::decode(buf, version).map(MetadataRecord::RemoveDelegationTokenRecord),
+            MetadataRecordType::NoOpRecord => NoOpRecord::decode(buf, version).map(MetadataRecord::NoOpRecord),
+            MetadataRecordType::ZkMigrationStateRecord => ZkMigrationStateRecord::decode(buf, version).map(MetadataRecord::ZkMigrationStateRecord),
+            MetadataRecordType::RemoveUserScramCredentialRecord => RemoveUserScramCredentialRecord::decode(buf, version).map(MetadataRecord::RemoveUserScramCredentialRecord),
+            MetadataRecordType::BeginTransactionRecord => BeginTransactionRecord::decode(buf, version).map(MetadataRecord::BeginTransactionRecord),
+            MetadataRecordType::EndTransactionRecord => EndTransactionRecord::decode(buf, version).map(MetadataRecord::EndTransactionRecord),
+            MetadataRecordType::AbortTransactionRecord => AbortTransactionRecord::decode(buf, version).map(MetadataRecord::AbortTransactionRecord),
+        }
+    }
+
+    /// Encode this record with the given version.
+    pub fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        match self {
+            MetadataRecord::RegisterBrokerRecord(record) => record.encode(buf, version),
+            MetadataRecord::UnregisterBrokerRecord(record) => record.encode(buf, version),
+            MetadataRecord::TopicRecord(record) => record.encode(buf, version),
+            MetadataRecord::PartitionRecord(record) => record.encode(buf, version),
+            MetadataRecord::ConfigRecord(record) => record.encode(buf, version),
+            MetadataRecord::PartitionChangeRecord(record) => record.encode(buf, version),
+            MetadataRecord::AccessControlEntryRecord(record) => record.encode(buf, version),
+            MetadataRecord::FenceBrokerRecord(record) => record.encode(buf, version),
+            MetadataRecord::UnfenceBrokerRecord(record) => record.encode(buf, version),
+            MetadataRecord::RemoveTopicRecord(record) => record.encode(buf, version),
+            MetadataRecord::DelegationTokenRecord(record) => record.encode(buf, version),
+            MetadataRecord::UserScramCredentialRecord(record) => record.encode(buf, version),
+            MetadataRecord::FeatureLevelRecord(record) => record.encode(buf, version),
+            MetadataRecord::ClientQuotaRecord(record) => record.encode(buf, version),
+            MetadataRecord::ProducerIdsRecord(record) => record.encode(buf, version),
+            MetadataRecord::BrokerRegistrationChangeRecord(record) => record.encode(buf, version),
+            MetadataRecord::RemoveAccessControlEntryRecord(record) => record.encode(buf, version),
+            MetadataRecord::RemoveDelegationTokenRecord(record) => record.encode(buf, version),
+            MetadataRecord::NoOpRecord(record) => record.encode(buf, version),
+            MetadataRecord::ZkMigrationStateRecord(record) => record.encode(buf, version),
+            MetadataRecord::RemoveUserScramCredentialRecord(record) => record.encode(buf, version),
+            MetadataRecord::BeginTransactionRecord(record) => record.encode(buf, version),
+            MetadataRecord::EndTransactionRecord(record) => record.encode(buf, version),
+            MetadataRecord::AbortTransactionRecord(record) => record.encode(buf, version),
+        }
+    }
+
+    /// Compute the size of this record when encoded with the given version.
+    pub fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        match self {
+            MetadataRecord::RegisterBrokerRecord(record) => record.compute_size(version),
+            MetadataRecord::UnregisterBrokerRecord(record) => record.compute_size(version),
+            MetadataRecord::TopicRecord(record) => record.compute_size(version),
+            MetadataRecord::PartitionRecord(record) => record.compute_size(version),
+            MetadataRecord::ConfigRecord(record) => record.compute_size(version),
+            MetadataRecord::PartitionChangeRecord(record) => record.compute_size(version),
+            MetadataRecord::AccessControlEntryRecord(record) => record.compute_size(version),
+            MetadataRecord::FenceBrokerRecord(record) => record.compute_size(version),
+            MetadataRecord::UnfenceBrokerRecord(record) => record.compute_size(version),
+            MetadataRecord::RemoveTopicRecord(record) => record.compute_size(version),
+            MetadataRecord::DelegationTokenRecord(record) => record.compute_size(version),
+            MetadataRecord::UserScramCredentialRecord(record) => record.compute_size(version),
+            MetadataRecord::FeatureLevelRecord(record) => record.compute_size(version),
+            MetadataRecord::ClientQuotaRecord(record) => record.compute_size(version),
+            MetadataRecord::ProducerIdsRecord(record) => record.compute_size(version),
+            MetadataRecord::BrokerRegistrationChangeRecord(record) => record.compute_size(version),
+            MetadataRecord::RemoveAccessControlEntryRecord(record) => record.compute_size(version),
+            MetadataRecord::RemoveDelegationTokenRecord(record) => record.compute_size(version),
+            MetadataRecord::NoOpRecord(record) => record.compute_size(version),
+            MetadataRecord::ZkMigrationStateRecord(record) => record.compute_size(version),
+            MetadataRecord::RemoveUserScramCredentialRecord(record) => record.compute_size(version),
+            MetadataRecord::BeginTransactionRecord(record) => record.compute_size(version),
+            MetadataRecord::EndTransactionRecord(record) => record.compute_size(version),
+            MetadataRecord::AbortTransactionRecord(record) => record.compute_size(version),
+        }
+    }
+}
+
+impl From<RegisterBrokerRecord> for MetadataRecord {
+    fn from(record: RegisterBrokerRecord) -> Self { MetadataRecord::RegisterBrokerRecord(record) }
+}
+
+impl From<UnregisterBrokerRecord> for MetadataRecord {
+    fn from(record: UnregisterBrokerRecord) -> Self { MetadataRecord::UnregisterBrokerRecord(record) }
+}
+
+impl From<TopicRecord> for MetadataRecord {
+    fn from(record: TopicRecord) -> Self { MetadataRecord::TopicRecord(record) }
+}
+
+impl From<PartitionRecord> for MetadataRecord {
+    fn from(record: PartitionRecord) -> Self { MetadataRecord::PartitionRecord(record) }
+}
+
+impl From<ConfigRecord> for MetadataRecord {
+    fn from(record: ConfigRecord) -> Self { MetadataRecord::ConfigRecord(record) }
+}
+
+impl From<PartitionChangeRecord> for MetadataRecord {
+    fn from(record: PartitionChangeRecord) -> Self { MetadataRecord::PartitionChangeRecord(record) }
+}
+
+impl From<AccessControlEntryRecord> for MetadataRecord {
+    fn from(record: AccessControlEntryRecord) -> Self { MetadataRecord::AccessControlEntryRecord(record) }
+}
+
+impl From<FenceBrokerRecord> for MetadataRecord {
+    fn from(record: FenceBrokerRecord) -> Self { MetadataRecord::FenceBrokerRecord(record) }
+}
+
+impl From<UnfenceBrokerRecord> for MetadataRecord {
+    fn from(record: UnfenceBrokerRecord) -> Self { MetadataRecord::UnfenceBrokerRecord(record) }
+}
+
+impl From<RemoveTopicRecord> for MetadataRecord {
+    fn from(record: RemoveTopicRecord) -> Self { MetadataRecord::RemoveTopicRecord(record) }
+}
+
+impl From<DelegationTokenRecord> for MetadataRecord {
+    fn from(record: DelegationTokenRecord) -> Self { MetadataRecord::DelegationTokenRecord(record) }
+}
+
+impl From<UserScramCredentialRecord> for MetadataRecord {
+    fn from(record: UserScramCredentialRecord) -> Self { MetadataRecord::UserScramCredentialRecord(record) }
+}
+
+impl From<FeatureLevelRecord> for MetadataRecord {
+    fn from(record: FeatureLevelRecord) -> Self { MetadataRecord::FeatureLevelRecord(record) }
+}
+
+impl From<ClientQuotaRecord> for MetadataRecord {
+    fn from(record: ClientQuotaRecord) -> Self { MetadataRecord::ClientQuotaRecord(record) }
+}
+
+impl From<ProducerIdsRecord> for MetadataRecord {
+    fn from(record: ProducerIdsRecord) -> Self { MetadataRecord::ProducerIdsRecord(record) }
+}
+
+impl From<BrokerRegistrationChangeRecord> for MetadataRecord {
+    fn from(record: BrokerRegistrationChangeRecord) -> Self { MetadataRecord::BrokerRegistrationChangeRecord(record) }
+}
+
+impl From<RemoveAccessControlEntryRecord> for MetadataRecord {
+    fn from(record: RemoveAccessControlEntryRecord) -> Self { MetadataRecord::RemoveAccessControlEntryRecord(record) }
+}
+
+impl From<RemoveDelegationTokenRecord> for MetadataRecord {
+    fn from(record: RemoveDelegationTokenRecord) -> Self { MetadataRecord::RemoveDelegationTokenRecord(record) }
+}
+
+impl From<NoOpRecord> for MetadataRecord {
+    fn from(record: NoOpRecord) -> Self { MetadataRecord::NoOpRecord(record) }
+}
+
+impl From<ZkMigrationStateRecord> for MetadataRecord {
+    fn from(record: ZkMigrationStateRecord) -> Self { MetadataRecord::ZkMigrationStateRecord(record) }
+}
+
+impl From<RemoveUserScramCredentialRecord> for MetadataRecord {
+    fn from(record: RemoveUserScramCredentialRecord) -> Self { MetadataRecord::RemoveUserScramCredentialRecord(record) }
+}
+
+impl From<BeginTransactionRecord> for MetadataRecord {
+    fn from(record: BeginTransactionRecord) -> Self { MetadataRecord::BeginTransactionRecord(record) }
+}
+
+impl From<EndTransactionRecord> for MetadataRecord {
+    fn from(record: EndTransactionRecord) -> Self { MetadataRecord::EndTransactionRecord(record) }
+}
+
+impl From<AbortTransactionRecord> for MetadataRecord {
+    fn from(record: AbortTransactionRecord) -> Self { MetadataRecord::AbortTransactionRecord(record) }
+}
+
+/// A metadata record together with the version it is encoded with, as framed in the
+/// `__cluster_metadata` log and snapshots.
+///
+/// The frame consists of the frame version, the record's api key and the record's version, each an
+/// unsigned varint, followed by the record itself.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ApiMessageAndVersion {
+    /// The record.
+    pub message: MetadataRecord,
+    /// The version the record is encoded with.
+    pub version: i16,
+}
+
+impl ApiMessageAndVersion {
+    /// The only frame version written by Kafka.
+    pub const FRAME_VERSION: u32 = 1;
+
+    /// Create a frame for `message` encoded with `version`.
+    pub fn new<M: Into<MetadataRecord>>(message: M, version: i16) -> Self {
+        Self { message: message.into(), version }
+    }
+
+    /// Decode a framed record, e.g. the value of a record in the `__cluster_metadata` log.
+    pub fn decode<B: ByteBuf>(buf: &mut B) -> Result<Self, DecodeError> {
+        let frame_version: u32 = types::UnsignedVarInt.decode(buf)?;
+        if frame_version != Self::FRAME_VERSION {
+            error!("Unknown metadata record frame version {}", frame_version);
+            return Err(DecodeError::new(DecodeErrorKind::InvalidValue));
+        }
+        let api_key: u32 = types::UnsignedVarInt.decode(buf)?;
+        let record_type = i16::try_from(api_key).ok().and_then(|k| MetadataRecordType::try_from(k).ok()).ok_or_else(|| {
+            error!("Unknown metadata record type {}", api_key);
+            DecodeError::new(DecodeErrorKind::InvalidValue)
+        })?;
+        let version: u32 = types::UnsignedVarInt.decode(buf)?;
+        let version = i16::try_from(version).map_err(|_| DecodeError::new(DecodeErrorKind::InvalidValue))?;
+        let message = MetadataRecord::decode(record_type, buf, version)?;
+        Ok(Self { message, version })
+    }
+
+    /// Decode the framed record held in the value of a record read from the `__cluster_metadata` log.
+    pub fn decode_record(record: &crate::records::Record) -> Result<Self, DecodeError> {
+        let mut value = record.value.clone().ok_or_else(|| {
+            error!("Metadata record at offset {} has no value", record.offset);
+            DecodeError::new(DecodeErrorKind::InvalidValue)
+        })?;
+        let frame = Self::decode(&mut value)?;
+        if value.has_remaining() {
+            error!("Metadata record at offset {} has {} trailing bytes", record.offset, value.remaining());
+            return Err(DecodeError::new(DecodeErrorKind::InvalidValue));
+        }
+        Ok(frame)
+    }
+
+    /// Encode this frame.
+    pub fn encode<B: ByteBufMut>(&self, buf: &mut B) -> Result<(), EncodeError> {
+        types::UnsignedVarInt.encode(buf, Self::FRAME_VERSION)?;
+        types::UnsignedVarInt.encode(buf, self.message.record_type() as u32)?;
+        let version = u32::try_from(self.version).map_err(|_| EncodeError::new(EncodeErrorKind::InvalidValue))?;
+        types::UnsignedVarInt.encode(buf, version)?;
+        self.message.encode(buf, self.version)
+    }
+
+    /// Compute the size of this frame when encoded.
+    pub fn compute_size(&self) -> Result<usize, EncodeError> {
+        let version = u32::try_from(self.version).map_err(|_| EncodeError::new(EncodeErrorKind::InvalidValue))?;
+        Ok(types::UnsignedVarInt.compute_size(Self::FRAME_VERSION)?
+            + types::UnsignedVarInt.compute_size(self.message.record_type() as u32)?
+            + types::UnsignedVarInt.compute_size(version)?
+            + self.message.compute_size(self.version)?)
+    }
+}
+
@@ -0,0 +1,288 @@
+//! AccessControlEntryRecord
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/metadata/src/main/resources/common/metadata/AccessControlEntryRecord.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct AccessControlEntryRecord {
+    /// The ACL ID.
+    /// 
+    /// Supported API versions: 0
+    pub id: Uuid,
+
+    /// The resource type
+    /// 
+    /// Supported API versions: 0
+    pub resource_type: i8,
+
+    /// The resource name, or null if this is for the default resource.
+    /// 
+    /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub resource_name: Option<StrBytes>,
+
+    /// The pattern type (literal, prefixed, etc.)
+    /// 
+    /// Supported API versions: 0
+    pub pattern_type: i8,
+
+    /// The principal name.
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub principal: StrBytes,
+
+    /// The host.
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub host: StrBytes,
+
+    /// The operation type.
+    /// 
+    /// Supported API versions: 0
+    pub operation: i8,
+
+    /// The permission type (allow, deny).
+    /// 
+    /// Supported API versions: 0
+    pub permission_type: i8,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for AccessControlEntryRecord {
+    type Builder = AccessControlEntryRecordBuilder;
+
+    fn builder() -> Self::Builder{
+        AccessControlEntryRecordBuilder::default()
+    }
+}
+
+impl Encodable for AccessControlEntryRecord {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AccessControlEntryRecord", version));
+        }
+        types::Uuid.encode(buf, &self.id).map_err(|e| e.with_field("AccessControlEntryRecord", "id", version))?;
+        types::Int8.encode(buf, &self.resource_type).map_err(|e| e.with_field("AccessControlEntryRecord", "resource_type", version))?;
+        types::CompactString.encode(buf, &self.resource_name).map_err(|e| e.with_field("AccessControlEntryRecord", "resource_name", version))?;
+        types::Int8.encode(buf, &self.pattern_type).map_err(|e| e.with_field("AccessControlEntryRecord", "pattern_type", version))?;
+        types::CompactString.encode(buf, &self.principal).map_err(|e| e.with_field("AccessControlEntryRecord", "principal", version))?;
+        types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("AccessControlEntryRecord", "host", version))?;
+        types::Int8.encode(buf, &self.operation).map_err(|e| e.with_field("AccessControlEntryRecord", "operation", version))?;
+        types::Int8.encode(buf, &self.permission_type).map_err(|e| e.with_field("AccessControlEntryRecord", "permission_type", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AccessControlEntryRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("AccessControlEntryRecord", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("AccessControlEntryRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("AccessControlEntryRecord", version));
+        }
+        let mut total_size = 0;
+        total_size += types::Uuid.compute_size(&self.id).map_err(|e| e.with_field("AccessControlEntryRecord", "id", version))?;
+        total_size += types::Int8.compute_size(&self.resource_type).map_err(|e| e.with_field("AccessControlEntryRecord", "resource_type", version))?;
+        total_size += types::CompactString.compute_size(&self.resource_name).map_err(|e| e.with_field("AccessControlEntryRecord", "resource_name", version))?;
+        total_size += types::Int8.compute_size(&self.pattern_type).map_err(|e| e.with_field("AccessControlEntryRecord", "pattern_type", version))?;
+        total_size += types::CompactString.compute_size(&self.principal).map_err(|e| e.with_field("AccessControlEntryRecord", "principal", version))?;
+        total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("AccessControlEntryRecord", "host", version))?;
+        total_size += types::Int8.compute_size(&self.operation).map_err(|e| e.with_field("AccessControlEntryRecord", "operation", version))?;
+        total_size += types::Int8.compute_size(&self.permission_type).map_err(|e| e.with_field("AccessControlEntryRecord", "permission_type", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("AccessControlEntryRecord", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("AccessControlEntryRecord", "unknown_tagged_fields", version))?;
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("AccessControlEntryRecord", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for AccessControlEntryRecord {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("AccessControlEntryRecord", version));
+        }
+        let start = buf.remaining();
+        let id = types::Uuid.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "id", version, start - buf.remaining()))?;
+        let resource_type = types::Int8.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "resource_type", version, start - buf.remaining()))?;
+        let resource_name = types::CompactString.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "resource_name", version, start - buf.remaining()))?;
+        let pattern_type = types::Int8.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "pattern_type", version, start - buf.remaining()))?;
+        let principal = types::CompactString.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "principal", version, start - buf.remaining()))?;
+        let host = types::CompactString.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "host", version, start - buf.remaining()))?;
+        let operation = types::Int8.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "operation", version, start - buf.remaining()))?;
+        let permission_type = types::Int8.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "permission_type", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("AccessControlEntryRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("AccessControlEntryRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            unknown_tagged_fields.insert(tag as i32, unknown_value);
+        }
+        Ok(Self {
+            id,
+            resource_type,
+            resource_name,
+            pattern_type,
+            principal,
+            host,
+            operation,
+            permission_type,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for AccessControlEntryRecord {
+    fn default() -> Self {
+        Self {
+            id: Uuid::nil(),
+            resource_type: 0,
+            resource_name: Some(Default::default()),
+            pattern_type: 0,
+            principal: Default::default(),
+            host: Default::default(),
+            operation: 0,
+            permission_type: 0,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for AccessControlEntryRecord {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+}
+
+impl AccessControlEntryRecord {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl AccessControlEntryRecord {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("id".into(), JsonValue::to_json(&self.id));
+        node.insert("resourceType".into(), JsonValue::to_json(&self.resource_type));
+        node.insert("resourceName".into(), JsonValue::to_json(&self.resource_name));
+        node.insert("patternType".into(), JsonValue::to_json(&self.pattern_type));
+        node.insert("principal".into(), JsonValue::to_json(&self.principal));
+        node.insert("host".into(), JsonValue::to_json(&self.host));
+        node.insert("operation".into(), JsonValue::to_json(&self.operation));
+        node.insert("permissionType".into(), JsonValue::to_json(&self.permission_type));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "AccessControlEntryRecord")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("id") {
+            this.id = JsonValue::from_json(value, "AccessControlEntryRecord.id")?;
+        } else {
+            return Err(crate::json::missing("AccessControlEntryRecord", "id", version));
+        }
+        if let Some(value) = object.get("resourceType") {
+            this.resource_type = JsonValue::from_json(value, "AccessControlEntryRecord.resourceType")?;
+        } else {
+            return Err(crate::json::missing("AccessControlEntryRecord", "resourceType", version));
+        }
+        if let Some(value) = object.get("resourceName") {
+            this.resource_name = JsonValue::from_json(value, "AccessControlEntryRecord.resourceName")?;
+        } else {
+            return Err(crate::json::missing("AccessControlEntryRecord", "resourceName", version));
+        }
+        if let Some(value) = object.get("patternType") {
+            this.pattern_type = JsonValue::from_json(value, "AccessControlEntryRecord.patternType")?;
+        } else {
+            return Err(crate::json::missing("AccessControlEntryRecord", "patternType", version));
+        }
+        if let Some(value) = object.get("principal") {
+            this.principal = JsonValue::from_json(value, "AccessControlEntryRecord.principal")?;
+        } else {
+            return Err(crate::json::missing("AccessControlEntryRecord", "principal", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "AccessControlEntryRecord.host")?;
+        } else {
+            return Err(crate::json::missing("AccessControlEntryRecord", "host", version));
+        }
+        if let Some(value) = object.get("operation") {
+            this.operation = JsonValue::from_json(value, "AccessControlEntryRecord.operation")?;
+        } else {
+            return Err(crate::json::missing("AccessControlEntryRecord", "operation", version));
+        }
+        if let Some(value) = object.get("permissionType") {
+            this.permission_type = JsonValue::from_json(value, "AccessControlEntryRecord.permissionType")?;
+        } else {
+            return Err(crate::json::missing("AccessControlEntryRecord", "permissionType", version));
+        }
+        Ok(this)
+    }
+}
+
@@ -0,0 +1,203 @@
+//! BeginTransactionRecord
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/metadata/src/main/resources/common/metadata/BeginTransactionRecord.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct BeginTransactionRecord {
+    /// An optional textual description of this transaction.
+    /// 
+    /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub name: Option<StrBytes>,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for BeginTransactionRecord {
+    type Builder = BeginTransactionRecordBuilder;
+
+    fn builder() -> Self::Builder{
+        BeginTransactionRecordBuilder::default()
+    }
+}
+
+impl Encodable for BeginTransactionRecord {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BeginTransactionRecord", version));
+        }
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if !self.name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BeginTransactionRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BeginTransactionRecord", "unknown_tagged_fields", version))?;
+        if !self.name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+            let computed_size = types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("BeginTransactionRecord", "name", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BeginTransactionRecord", "name", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("BeginTransactionRecord", "name", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("BeginTransactionRecord", "name", version))?;
+            types::CompactString.encode(buf, &self.name).map_err(|e| e.with_field("BeginTransactionRecord", "name", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BeginTransactionRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BeginTransactionRecord", version));
+        }
+        let mut total_size = 0;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if !self.name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BeginTransactionRecord", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("BeginTransactionRecord", "unknown_tagged_fields", version))?;
+        if !self.name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+            let computed_size = types::CompactString.compute_size(&self.name).map_err(|e| e.with_field("BeginTransactionRecord", "name", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BeginTransactionRecord", "name", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("BeginTransactionRecord", "name", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("BeginTransactionRecord", "name", version))?;
+            total_size += computed_size;
+        }
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("BeginTransactionRecord", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for BeginTransactionRecord {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("BeginTransactionRecord", version));
+        }
+        let start = buf.remaining();
+        let mut name = Some(Default::default());
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BeginTransactionRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BeginTransactionRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BeginTransactionRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            match tag {
+                0 => {
+                    name = types::CompactString.decode(buf).map_err(|e| e.with_field("BeginTransactionRecord", "name", version, start - buf.remaining()))?;
+                },
+                _ => {
+                    let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("BeginTransactionRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                    unknown_tagged_fields.insert(tag as i32, unknown_value);
+                }
+            }
+        }
+        Ok(Self {
+            name,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for BeginTransactionRecord {
+    fn default() -> Self {
+        Self {
+            name: Some(Default::default()),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for BeginTransactionRecord {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+}
+
+impl BeginTransactionRecord {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl BeginTransactionRecord {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        if !self.name.as_ref().map(|x| x.is_empty()).unwrap_or_default() {
+            node.insert("name".into(), JsonValue::to_json(&self.name));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "BeginTransactionRecord")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("name") {
+            this.name = JsonValue::from_json(value, "BeginTransactionRecord.name")?;
+        }
+        Ok(this)
+    }
+}
+
@@ -0,0 +1,292 @@
+//! BrokerRegistrationChangeRecord
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/metadata/src/main/resources/common/metadata/BrokerRegistrationChangeRecord.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0-1
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct BrokerRegistrationChangeRecord {
+    /// The broker id.
+    /// 
+    /// Supported API versions: 0-1
+    pub broker_id: super::BrokerId,
+
+    /// The broker epoch assigned by the controller.
+    /// 
+    /// Supported API versions: 0-1
+    pub broker_epoch: i64,
+
+    /// -1 if the broker has been unfenced, 0 if no change, 1 if the broker has been fenced.
+    /// 
+    /// Supported API versions: 0-1
+    pub fenced: i8,
+
+    /// 0 if no change, 1 if the broker is in controlled shutdown.
+    /// 
+    /// Supported API versions: 1
+    pub in_controlled_shutdown: i8,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for BrokerRegistrationChangeRecord {
+    type Builder = BrokerRegistrationChangeRecordBuilder;
+
+    fn builder() -> Self::Builder{
+        BrokerRegistrationChangeRecordBuilder::default()
+    }
+}
+
+impl Encodable for BrokerRegistrationChangeRecord {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BrokerRegistrationChangeRecord", version));
+        }
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "broker_id", version))?;
+        types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "broker_epoch", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if self.fenced != 0 {
+            num_tagged_fields += 1;
+        }
+        if version >= 1 {
+            if self.in_controlled_shutdown != 0 {
+                num_tagged_fields += 1;
+            }
+        }if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version))?;
+        if self.fenced != 0 {
+            let computed_size = types::Int8.compute_size(&self.fenced).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "fenced", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationChangeRecord", "fenced", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "fenced", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "fenced", version))?;
+            types::Int8.encode(buf, &self.fenced).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "fenced", version))?;
+        }
+        if version >= 1 {
+            if self.in_controlled_shutdown != 0 {
+                let computed_size = types::Int8.compute_size(&self.in_controlled_shutdown).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version))?;
+                if computed_size > std::u32::MAX as usize {
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version));
+                }
+                types::UnsignedVarInt.encode(buf, 1).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version))?;
+                types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version))?;
+                types::Int8.encode(buf, &self.in_controlled_shutdown).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version))?;
+            }
+        }
+        write_unknown_tagged_fields(buf, 2.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("BrokerRegistrationChangeRecord", version));
+        }
+        let mut total_size = 0;
+        total_size += types::Int32.compute_size(&self.broker_id).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "broker_id", version))?;
+        total_size += types::Int64.compute_size(&self.broker_epoch).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "broker_epoch", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if self.fenced != 0 {
+            num_tagged_fields += 1;
+        }
+        if version >= 1 {
+            if self.in_controlled_shutdown != 0 {
+                num_tagged_fields += 1;
+            }
+        }if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version))?;
+        if self.fenced != 0 {
+            let computed_size = types::Int8.compute_size(&self.fenced).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "fenced", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationChangeRecord", "fenced", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "fenced", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "fenced", version))?;
+            total_size += computed_size;
+        }
+        if version >= 1 {
+            if self.in_controlled_shutdown != 0 {
+                let computed_size = types::Int8.compute_size(&self.in_controlled_shutdown).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version))?;
+                if computed_size > std::u32::MAX as usize {
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version));
+                }
+                total_size += types::UnsignedVarInt.compute_size(1).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version))?;
+                total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version))?;
+                total_size += computed_size;
+            }
+        }
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for BrokerRegistrationChangeRecord {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("BrokerRegistrationChangeRecord", version));
+        }
+        let start = buf.remaining();
+        let broker_id = types::Int32.decode(buf).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "broker_id", version, start - buf.remaining()))?;
+        let broker_epoch = types::Int64.decode(buf).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "broker_epoch", version, start - buf.remaining()))?;
+        let mut fenced = 0;
+        let mut in_controlled_shutdown = 0;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            match tag {
+                0 => {
+                    fenced = types::Int8.decode(buf).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "fenced", version, start - buf.remaining()))?;
+                },
+                1 => {
+                    if version >= 1 {
+                        in_controlled_shutdown = types::Int8.decode(buf).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version, start - buf.remaining()))?;
+                    } else {
+                        return Err(DecodeError::new(DecodeErrorKind::FieldNotValidAtVersion).with_field("BrokerRegistrationChangeRecord", "in_controlled_shutdown", version, start - buf.remaining()));
+                    }
+                },
+                _ => {
+                    let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("BrokerRegistrationChangeRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                    unknown_tagged_fields.insert(tag as i32, unknown_value);
+                }
+            }
+        }
+        Ok(Self {
+            broker_id,
+            broker_epoch,
+            fenced,
+            in_controlled_shutdown,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for BrokerRegistrationChangeRecord {
+    fn default() -> Self {
+        Self {
+            broker_id: (0).into(),
+            broker_epoch: 0,
+            fenced: 0,
+            in_controlled_shutdown: 0,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for BrokerRegistrationChangeRecord {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
+}
+
+impl BrokerRegistrationChangeRecord {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 1 {
+            self.in_controlled_shutdown = 0;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 1 {
+            if self.in_controlled_shutdown != 0 {
+                invalid.push(format!("{}in_controlled_shutdown", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl BrokerRegistrationChangeRecord {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("brokerId".into(), JsonValue::to_json(&self.broker_id));
+        node.insert("brokerEpoch".into(), JsonValue::to_json(&self.broker_epoch));
+        if self.fenced != 0 {
+            node.insert("fenced".into(), JsonValue::to_json(&self.fenced));
+        }
+        if version >= 1 {
+            if self.in_controlled_shutdown != 0 {
+                node.insert("inControlledShutdown".into(), JsonValue::to_json(&self.in_controlled_shutdown));
+            }
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "BrokerRegistrationChangeRecord")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("brokerId") {
+            this.broker_id = JsonValue::from_json(value, "BrokerRegistrationChangeRecord.brokerId")?;
+        } else {
+            return Err(crate::json::missing("BrokerRegistrationChangeRecord", "brokerId", version));
+        }
+        if let Some(value) = object.get("brokerEpoch") {
+            this.broker_epoch = JsonValue::from_json(value, "BrokerRegistrationChangeRecord.brokerEpoch")?;
+        } else {
+            return Err(crate::json::missing("BrokerRegistrationChangeRecord", "brokerEpoch", version));
+        }
+        if let Some(value) = object.get("fenced") {
+            this.fenced = JsonValue::from_json(value, "BrokerRegistrationChangeRecord.fenced")?;
+        }
+        if let Some(value) = object.get("inControlledShutdown") {
+            this.in_controlled_shutdown = JsonValue::from_json(value, "BrokerRegistrationChangeRecord.inControlledShutdown")?;
+        }
+        Ok(this)
+    }
+}
+
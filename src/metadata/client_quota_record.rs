@@ -0,0 +1,419 @@
+//! ClientQuotaRecord
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/metadata/src/main/resources/common/metadata/ClientQuotaRecord.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct EntityData {
+    /// The entity type.
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub entity_type: StrBytes,
+
+    /// The name of the entity, or null if the default.
+    /// 
+    /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub entity_name: Option<StrBytes>,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for EntityData {
+    type Builder = EntityDataBuilder;
+
+    fn builder() -> Self::Builder{
+        EntityDataBuilder::default()
+    }
+}
+
+impl Encodable for EntityData {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, &self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
+        types::CompactString.encode(buf, &self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntityData", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        total_size += types::CompactString.compute_size(&self.entity_type).map_err(|e| e.with_field("EntityData", "entity_type", version))?;
+        total_size += types::CompactString.compute_size(&self.entity_name).map_err(|e| e.with_field("EntityData", "entity_name", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("EntityData", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for EntityData {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let entity_type = types::CompactString.decode(buf).map_err(|e| e.with_field("EntityData", "entity_type", version, start - buf.remaining()))?;
+        let entity_name = types::CompactString.decode(buf).map_err(|e| e.with_field("EntityData", "entity_name", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("EntityData", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            unknown_tagged_fields.insert(tag as i32, unknown_value);
+        }
+        Ok(Self {
+            entity_type,
+            entity_name,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for EntityData {
+    fn default() -> Self {
+        Self {
+            entity_type: Default::default(),
+            entity_name: Some(Default::default()),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for EntityData {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+}
+
+impl EntityData {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl EntityData {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("entityType".into(), JsonValue::to_json(&self.entity_type));
+        node.insert("entityName".into(), JsonValue::to_json(&self.entity_name));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "EntityData")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("entityType") {
+            this.entity_type = JsonValue::from_json(value, "EntityData.entityType")?;
+        } else {
+            return Err(crate::json::missing("EntityData", "entityType", version));
+        }
+        if let Some(value) = object.get("entityName") {
+            this.entity_name = JsonValue::from_json(value, "EntityData.entityName")?;
+        } else {
+            return Err(crate::json::missing("EntityData", "entityName", version));
+        }
+        Ok(this)
+    }
+}
+
+/// Valid versions: 0
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct ClientQuotaRecord {
+    /// The quota entity to update.
+    /// 
+    /// Supported API versions: 0
+    pub entity: Vec<EntityData>,
+
+    /// The quota configuration key.
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub key: StrBytes,
+
+    /// The value to set, otherwise ignored if the value is to be removed.
+    /// 
+    /// Supported API versions: 0
+    pub value: f64,
+
+    /// Whether the quota configuration value should be removed, otherwise set.
+    /// 
+    /// Supported API versions: 0
+    pub remove: bool,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for ClientQuotaRecord {
+    type Builder = ClientQuotaRecordBuilder;
+
+    fn builder() -> Self::Builder{
+        ClientQuotaRecordBuilder::default()
+    }
+}
+
+impl Encodable for ClientQuotaRecord {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ClientQuotaRecord", version));
+        }
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.entity).map_err(|e| e.with_field("ClientQuotaRecord", "entity", version))?;
+        types::CompactString.encode(buf, &self.key).map_err(|e| e.with_field("ClientQuotaRecord", "key", version))?;
+        types::Float64.encode(buf, &self.value).map_err(|e| e.with_field("ClientQuotaRecord", "value", version))?;
+        types::Boolean.encode(buf, &self.remove).map_err(|e| e.with_field("ClientQuotaRecord", "remove", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ClientQuotaRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ClientQuotaRecord", version));
+        }
+        let mut total_size = 0;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.entity).map_err(|e| e.with_field("ClientQuotaRecord", "entity", version))?;
+        total_size += types::CompactString.compute_size(&self.key).map_err(|e| e.with_field("ClientQuotaRecord", "key", version))?;
+        total_size += types::Float64.compute_size(&self.value).map_err(|e| e.with_field("ClientQuotaRecord", "value", version))?;
+        total_size += types::Boolean.compute_size(&self.remove).map_err(|e| e.with_field("ClientQuotaRecord", "remove", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ClientQuotaRecord", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version))?;
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for ClientQuotaRecord {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("ClientQuotaRecord", version));
+        }
+        let start = buf.remaining();
+        let entity = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("ClientQuotaRecord", "entity", version, start - buf.remaining()))?;
+        let key = types::CompactString.decode(buf).map_err(|e| e.with_field("ClientQuotaRecord", "key", version, start - buf.remaining()))?;
+        let value = types::Float64.decode(buf).map_err(|e| e.with_field("ClientQuotaRecord", "value", version, start - buf.remaining()))?;
+        let remove = types::Boolean.decode(buf).map_err(|e| e.with_field("ClientQuotaRecord", "remove", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            unknown_tagged_fields.insert(tag as i32, unknown_value);
+        }
+        Ok(Self {
+            entity,
+            key,
+            value,
+            remove,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for ClientQuotaRecord {
+    fn default() -> Self {
+        Self {
+            entity: Default::default(),
+            key: Default::default(),
+            value: 0.0,
+            remove: false,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for ClientQuotaRecord {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+}
+
+impl ClientQuotaRecord {
+    /// Encode the message with the elements of `entity` produced by an iterator of known length, in place of
+    /// the `entity` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_entity<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, entity: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = EntityData>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("ClientQuotaRecord", version));
+        }
+        let cache = &SizeCache::default();
+        let entity = entity.into_iter();
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::Streamed(entity)).map_err(|e| e.with_field("ClientQuotaRecord", "entity", version))?;
+        types::CompactString.encode(buf, &self.key).map_err(|e| e.with_field("ClientQuotaRecord", "key", version))?;
+        types::Float64.encode(buf, &self.value).map_err(|e| e.with_field("ClientQuotaRecord", "value", version))?;
+        types::Boolean.encode(buf, &self.remove).map_err(|e| e.with_field("ClientQuotaRecord", "remove", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("ClientQuotaRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("ClientQuotaRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl ClientQuotaRecord {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        for item in self.entity.iter_mut() {
+            item.normalize_to(version);
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        for (i, item) in self.entity.iter().enumerate() {
+            item.validate_into(version, &format!("{}entity[{}].", path, i), invalid);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ClientQuotaRecord {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("entity".into(), serde_json::Value::Array(self.entity.iter().map(|item| item.to_json_with(version, records)).collect()));
+        node.insert("key".into(), JsonValue::to_json(&self.key));
+        node.insert("value".into(), JsonValue::to_json(&self.value));
+        node.insert("remove".into(), JsonValue::to_json(&self.remove));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "ClientQuotaRecord")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("entity") {
+            this.entity = crate::json::array(value, "ClientQuotaRecord.entity")?.iter().map(|item| EntityData::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("ClientQuotaRecord", "entity", version));
+        }
+        if let Some(value) = object.get("key") {
+            this.key = JsonValue::from_json(value, "ClientQuotaRecord.key")?;
+        } else {
+            return Err(crate::json::missing("ClientQuotaRecord", "key", version));
+        }
+        if let Some(value) = object.get("value") {
+            this.value = JsonValue::from_json(value, "ClientQuotaRecord.value")?;
+        } else {
+            return Err(crate::json::missing("ClientQuotaRecord", "value", version));
+        }
+        if let Some(value) = object.get("remove") {
+            this.remove = JsonValue::from_json(value, "ClientQuotaRecord.remove")?;
+        } else {
+            return Err(crate::json::missing("ClientQuotaRecord", "remove", version));
+        }
+        Ok(this)
+    }
+}
+
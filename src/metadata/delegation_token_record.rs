@@ -0,0 +1,308 @@
+//! DelegationTokenRecord
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/metadata/src/main/resources/common/metadata/DelegationTokenRecord.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct DelegationTokenRecord {
+    /// The delegation token owner.
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub owner: StrBytes,
+
+    /// The principal that requested this token.
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub requester: StrBytes,
+
+    /// The principals which have renewed this token.
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub renewers: Vec<StrBytes>,
+
+    /// The time at which this timestamp was issued.
+    /// 
+    /// Supported API versions: 0
+    pub issue_timestamp: i64,
+
+    /// The time at which this token cannot be renewed any more.
+    /// 
+    /// Supported API versions: 0
+    pub max_timestamp: i64,
+
+    /// The next time at which this token must be renewed.
+    /// 
+    /// Supported API versions: 0
+    pub expiration_timestamp: i64,
+
+    /// The token id.
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub token_id: StrBytes,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for DelegationTokenRecord {
+    type Builder = DelegationTokenRecordBuilder;
+
+    fn builder() -> Self::Builder{
+        DelegationTokenRecordBuilder::default()
+    }
+}
+
+impl Encodable for DelegationTokenRecord {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DelegationTokenRecord", version));
+        }
+        types::CompactString.encode(buf, &self.owner).map_err(|e| e.with_field("DelegationTokenRecord", "owner", version))?;
+        types::CompactString.encode(buf, &self.requester).map_err(|e| e.with_field("DelegationTokenRecord", "requester", version))?;
+        types::CompactArray(types::CompactString).encode(buf, &self.renewers).map_err(|e| e.with_field("DelegationTokenRecord", "renewers", version))?;
+        types::Int64.encode(buf, &self.issue_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "issue_timestamp", version))?;
+        types::Int64.encode(buf, &self.max_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "max_timestamp", version))?;
+        types::Int64.encode(buf, &self.expiration_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "expiration_timestamp", version))?;
+        types::CompactString.encode(buf, &self.token_id).map_err(|e| e.with_field("DelegationTokenRecord", "token_id", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DelegationTokenRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DelegationTokenRecord", version));
+        }
+        let mut total_size = 0;
+        total_size += types::CompactString.compute_size(&self.owner).map_err(|e| e.with_field("DelegationTokenRecord", "owner", version))?;
+        total_size += types::CompactString.compute_size(&self.requester).map_err(|e| e.with_field("DelegationTokenRecord", "requester", version))?;
+        total_size += types::CompactArray(types::CompactString).compute_size(&self.renewers).map_err(|e| e.with_field("DelegationTokenRecord", "renewers", version))?;
+        total_size += types::Int64.compute_size(&self.issue_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "issue_timestamp", version))?;
+        total_size += types::Int64.compute_size(&self.max_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "max_timestamp", version))?;
+        total_size += types::Int64.compute_size(&self.expiration_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "expiration_timestamp", version))?;
+        total_size += types::CompactString.compute_size(&self.token_id).map_err(|e| e.with_field("DelegationTokenRecord", "token_id", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DelegationTokenRecord", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version))?;
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for DelegationTokenRecord {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("DelegationTokenRecord", version));
+        }
+        let start = buf.remaining();
+        let owner = types::CompactString.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "owner", version, start - buf.remaining()))?;
+        let requester = types::CompactString.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "requester", version, start - buf.remaining()))?;
+        let renewers = types::CompactArray(types::CompactString).decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "renewers", version, start - buf.remaining()))?;
+        let issue_timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "issue_timestamp", version, start - buf.remaining()))?;
+        let max_timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "max_timestamp", version, start - buf.remaining()))?;
+        let expiration_timestamp = types::Int64.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "expiration_timestamp", version, start - buf.remaining()))?;
+        let token_id = types::CompactString.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "token_id", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            unknown_tagged_fields.insert(tag as i32, unknown_value);
+        }
+        Ok(Self {
+            owner,
+            requester,
+            renewers,
+            issue_timestamp,
+            max_timestamp,
+            expiration_timestamp,
+            token_id,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for DelegationTokenRecord {
+    fn default() -> Self {
+        Self {
+            owner: Default::default(),
+            requester: Default::default(),
+            renewers: Default::default(),
+            issue_timestamp: 0,
+            max_timestamp: 0,
+            expiration_timestamp: 0,
+            token_id: Default::default(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for DelegationTokenRecord {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+}
+
+impl DelegationTokenRecord {
+    /// Encode the message with the elements of `renewers` produced by an iterator of known length, in place of
+    /// the `renewers` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_renewers<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, renewers: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = StrBytes>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("DelegationTokenRecord", version));
+        }
+        let cache = &SizeCache::default();
+        let renewers = renewers.into_iter();
+        types::CompactString.encode(buf, &self.owner).map_err(|e| e.with_field("DelegationTokenRecord", "owner", version))?;
+        types::CompactString.encode(buf, &self.requester).map_err(|e| e.with_field("DelegationTokenRecord", "requester", version))?;
+        types::CompactArray(types::CompactString).encode(buf, types::Streamed(renewers)).map_err(|e| e.with_field("DelegationTokenRecord", "renewers", version))?;
+        types::Int64.encode(buf, &self.issue_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "issue_timestamp", version))?;
+        types::Int64.encode(buf, &self.max_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "max_timestamp", version))?;
+        types::Int64.encode(buf, &self.expiration_timestamp).map_err(|e| e.with_field("DelegationTokenRecord", "expiration_timestamp", version))?;
+        types::CompactString.encode(buf, &self.token_id).map_err(|e| e.with_field("DelegationTokenRecord", "token_id", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("DelegationTokenRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("DelegationTokenRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl DelegationTokenRecord {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl DelegationTokenRecord {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("owner".into(), JsonValue::to_json(&self.owner));
+        node.insert("requester".into(), JsonValue::to_json(&self.requester));
+        node.insert("renewers".into(), JsonValue::to_json(&self.renewers));
+        node.insert("issueTimestamp".into(), JsonValue::to_json(&self.issue_timestamp));
+        node.insert("maxTimestamp".into(), JsonValue::to_json(&self.max_timestamp));
+        node.insert("expirationTimestamp".into(), JsonValue::to_json(&self.expiration_timestamp));
+        node.insert("tokenId".into(), JsonValue::to_json(&self.token_id));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "DelegationTokenRecord")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("owner") {
+            this.owner = JsonValue::from_json(value, "DelegationTokenRecord.owner")?;
+        } else {
+            return Err(crate::json::missing("DelegationTokenRecord", "owner", version));
+        }
+        if let Some(value) = object.get("requester") {
+            this.requester = JsonValue::from_json(value, "DelegationTokenRecord.requester")?;
+        } else {
+            return Err(crate::json::missing("DelegationTokenRecord", "requester", version));
+        }
+        if let Some(value) = object.get("renewers") {
+            this.renewers = JsonValue::from_json(value, "DelegationTokenRecord.renewers")?;
+        } else {
+            return Err(crate::json::missing("DelegationTokenRecord", "renewers", version));
+        }
+        if let Some(value) = object.get("issueTimestamp") {
+            this.issue_timestamp = JsonValue::from_json(value, "DelegationTokenRecord.issueTimestamp")?;
+        } else {
+            return Err(crate::json::missing("DelegationTokenRecord", "issueTimestamp", version));
+        }
+        if let Some(value) = object.get("maxTimestamp") {
+            this.max_timestamp = JsonValue::from_json(value, "DelegationTokenRecord.maxTimestamp")?;
+        } else {
+            return Err(crate::json::missing("DelegationTokenRecord", "maxTimestamp", version));
+        }
+        if let Some(value) = object.get("expirationTimestamp") {
+            this.expiration_timestamp = JsonValue::from_json(value, "DelegationTokenRecord.expirationTimestamp")?;
+        } else {
+            return Err(crate::json::missing("DelegationTokenRecord", "expirationTimestamp", version));
+        }
+        if let Some(value) = object.get("tokenId") {
+            this.token_id = JsonValue::from_json(value, "DelegationTokenRecord.tokenId")?;
+        } else {
+            return Err(crate::json::missing("DelegationTokenRecord", "tokenId", version));
+        }
+        Ok(this)
+    }
+}
+
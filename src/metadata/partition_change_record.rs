@@ -0,0 +1,442 @@
+//! PartitionChangeRecord
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/metadata/src/main/resources/common/metadata/PartitionChangeRecord.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct PartitionChangeRecord {
+    /// The partition id.
+    /// 
+    /// Supported API versions: 0
+    pub partition_id: i32,
+
+    /// The unique ID of this topic.
+    /// 
+    /// Supported API versions: 0
+    pub topic_id: Uuid,
+
+    /// null if the ISR didn't change; the new in-sync replicas otherwise.
+    /// 
+    /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    pub isr: Option<Vec<super::BrokerId>>,
+
+    /// -1 if there is now no leader; -2 if the leader didn't change; the new leader otherwise.
+    /// 
+    /// Supported API versions: 0
+    pub leader: super::BrokerId,
+
+    /// null if the replicas didn't change; the new replicas otherwise.
+    /// 
+    /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    pub replicas: Option<Vec<super::BrokerId>>,
+
+    /// null if the removing replicas didn't change; the new removing replicas otherwise.
+    /// 
+    /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    pub removing_replicas: Option<Vec<super::BrokerId>>,
+
+    /// null if the adding replicas didn't change; the new adding replicas otherwise.
+    /// 
+    /// Supported API versions: 0
+    /// May be `None` in versions: 0
+    pub adding_replicas: Option<Vec<super::BrokerId>>,
+
+    /// -1 if it didn't change; 0 if the leader was elected from the ISR or recovered from an unclean election; 1 if the leader that was elected using unclean leader election and it is still recovering.
+    /// 
+    /// Supported API versions: 0
+    pub leader_recovery_state: i8,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for PartitionChangeRecord {
+    type Builder = PartitionChangeRecordBuilder;
+
+    fn builder() -> Self::Builder{
+        PartitionChangeRecordBuilder::default()
+    }
+}
+
+impl Encodable for PartitionChangeRecord {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("PartitionChangeRecord", version));
+        }
+        types::Int32.encode(buf, &self.partition_id).map_err(|e| e.with_field("PartitionChangeRecord", "partition_id", version))?;
+        types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("PartitionChangeRecord", "topic_id", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if !self.isr.is_none() {
+            num_tagged_fields += 1;
+        }
+        if self.leader != -2 {
+            num_tagged_fields += 1;
+        }
+        if !self.replicas.is_none() {
+            num_tagged_fields += 1;
+        }
+        if !self.removing_replicas.is_none() {
+            num_tagged_fields += 1;
+        }
+        if !self.adding_replicas.is_none() {
+            num_tagged_fields += 1;
+        }
+        if self.leader_recovery_state != -1 {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionChangeRecord", "unknown_tagged_fields", version))?;
+        if !self.isr.is_none() {
+            let computed_size = types::CompactArray(types::Int32).compute_size(&self.isr).map_err(|e| e.with_field("PartitionChangeRecord", "isr", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "isr", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("PartitionChangeRecord", "isr", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "isr", version))?;
+            types::CompactArray(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("PartitionChangeRecord", "isr", version))?;
+        }
+        if self.leader != -2 {
+            let computed_size = types::Int32.compute_size(&self.leader).map_err(|e| e.with_field("PartitionChangeRecord", "leader", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "leader", version));
+            }
+            types::UnsignedVarInt.encode(buf, 1).map_err(|e| e.with_field("PartitionChangeRecord", "leader", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "leader", version))?;
+            types::Int32.encode(buf, &self.leader).map_err(|e| e.with_field("PartitionChangeRecord", "leader", version))?;
+        }
+        if !self.replicas.is_none() {
+            let computed_size = types::CompactArray(types::Int32).compute_size(&self.replicas).map_err(|e| e.with_field("PartitionChangeRecord", "replicas", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "replicas", version));
+            }
+            types::UnsignedVarInt.encode(buf, 2).map_err(|e| e.with_field("PartitionChangeRecord", "replicas", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "replicas", version))?;
+            types::CompactArray(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("PartitionChangeRecord", "replicas", version))?;
+        }
+        if !self.removing_replicas.is_none() {
+            let computed_size = types::CompactArray(types::Int32).compute_size(&self.removing_replicas).map_err(|e| e.with_field("PartitionChangeRecord", "removing_replicas", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "removing_replicas", version));
+            }
+            types::UnsignedVarInt.encode(buf, 3).map_err(|e| e.with_field("PartitionChangeRecord", "removing_replicas", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "removing_replicas", version))?;
+            types::CompactArray(types::Int32).encode(buf, &self.removing_replicas).map_err(|e| e.with_field("PartitionChangeRecord", "removing_replicas", version))?;
+        }
+        if !self.adding_replicas.is_none() {
+            let computed_size = types::CompactArray(types::Int32).compute_size(&self.adding_replicas).map_err(|e| e.with_field("PartitionChangeRecord", "adding_replicas", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "adding_replicas", version));
+            }
+            types::UnsignedVarInt.encode(buf, 4).map_err(|e| e.with_field("PartitionChangeRecord", "adding_replicas", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "adding_replicas", version))?;
+            types::CompactArray(types::Int32).encode(buf, &self.adding_replicas).map_err(|e| e.with_field("PartitionChangeRecord", "adding_replicas", version))?;
+        }
+        if self.leader_recovery_state != -1 {
+            let computed_size = types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionChangeRecord", "leader_recovery_state", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "leader_recovery_state", version));
+            }
+            types::UnsignedVarInt.encode(buf, 5).map_err(|e| e.with_field("PartitionChangeRecord", "leader_recovery_state", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "leader_recovery_state", version))?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("PartitionChangeRecord", "leader_recovery_state", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 6.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionChangeRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("PartitionChangeRecord", version));
+        }
+        let mut total_size = 0;
+        total_size += types::Int32.compute_size(&self.partition_id).map_err(|e| e.with_field("PartitionChangeRecord", "partition_id", version))?;
+        total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("PartitionChangeRecord", "topic_id", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if !self.isr.is_none() {
+            num_tagged_fields += 1;
+        }
+        if self.leader != -2 {
+            num_tagged_fields += 1;
+        }
+        if !self.replicas.is_none() {
+            num_tagged_fields += 1;
+        }
+        if !self.removing_replicas.is_none() {
+            num_tagged_fields += 1;
+        }
+        if !self.adding_replicas.is_none() {
+            num_tagged_fields += 1;
+        }
+        if self.leader_recovery_state != -1 {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("PartitionChangeRecord", "unknown_tagged_fields", version))?;
+        if !self.isr.is_none() {
+            let computed_size = types::CompactArray(types::Int32).compute_size(&self.isr).map_err(|e| e.with_field("PartitionChangeRecord", "isr", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "isr", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("PartitionChangeRecord", "isr", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "isr", version))?;
+            total_size += computed_size;
+        }
+        if self.leader != -2 {
+            let computed_size = types::Int32.compute_size(&self.leader).map_err(|e| e.with_field("PartitionChangeRecord", "leader", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "leader", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(1).map_err(|e| e.with_field("PartitionChangeRecord", "leader", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "leader", version))?;
+            total_size += computed_size;
+        }
+        if !self.replicas.is_none() {
+            let computed_size = types::CompactArray(types::Int32).compute_size(&self.replicas).map_err(|e| e.with_field("PartitionChangeRecord", "replicas", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "replicas", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(2).map_err(|e| e.with_field("PartitionChangeRecord", "replicas", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "replicas", version))?;
+            total_size += computed_size;
+        }
+        if !self.removing_replicas.is_none() {
+            let computed_size = types::CompactArray(types::Int32).compute_size(&self.removing_replicas).map_err(|e| e.with_field("PartitionChangeRecord", "removing_replicas", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "removing_replicas", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(3).map_err(|e| e.with_field("PartitionChangeRecord", "removing_replicas", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "removing_replicas", version))?;
+            total_size += computed_size;
+        }
+        if !self.adding_replicas.is_none() {
+            let computed_size = types::CompactArray(types::Int32).compute_size(&self.adding_replicas).map_err(|e| e.with_field("PartitionChangeRecord", "adding_replicas", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "adding_replicas", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(4).map_err(|e| e.with_field("PartitionChangeRecord", "adding_replicas", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "adding_replicas", version))?;
+            total_size += computed_size;
+        }
+        if self.leader_recovery_state != -1 {
+            let computed_size = types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionChangeRecord", "leader_recovery_state", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionChangeRecord", "leader_recovery_state", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(5).map_err(|e| e.with_field("PartitionChangeRecord", "leader_recovery_state", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionChangeRecord", "leader_recovery_state", version))?;
+            total_size += computed_size;
+        }
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionChangeRecord", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for PartitionChangeRecord {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("PartitionChangeRecord", version));
+        }
+        let start = buf.remaining();
+        let partition_id = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "partition_id", version, start - buf.remaining()))?;
+        let topic_id = types::Uuid.decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "topic_id", version, start - buf.remaining()))?;
+        let mut isr = None;
+        let mut leader = (-2).into();
+        let mut replicas = None;
+        let mut removing_replicas = None;
+        let mut adding_replicas = None;
+        let mut leader_recovery_state = -1;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            match tag {
+                0 => {
+                    isr = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "isr", version, start - buf.remaining()))?;
+                },
+                1 => {
+                    leader = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "leader", version, start - buf.remaining()))?;
+                },
+                2 => {
+                    replicas = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "replicas", version, start - buf.remaining()))?;
+                },
+                3 => {
+                    removing_replicas = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "removing_replicas", version, start - buf.remaining()))?;
+                },
+                4 => {
+                    adding_replicas = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "adding_replicas", version, start - buf.remaining()))?;
+                },
+                5 => {
+                    leader_recovery_state = types::Int8.decode(buf).map_err(|e| e.with_field("PartitionChangeRecord", "leader_recovery_state", version, start - buf.remaining()))?;
+                },
+                _ => {
+                    let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("PartitionChangeRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                    unknown_tagged_fields.insert(tag as i32, unknown_value);
+                }
+            }
+        }
+        Ok(Self {
+            partition_id,
+            topic_id,
+            isr,
+            leader,
+            replicas,
+            removing_replicas,
+            adding_replicas,
+            leader_recovery_state,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for PartitionChangeRecord {
+    fn default() -> Self {
+        Self {
+            partition_id: -1,
+            topic_id: Uuid::nil(),
+            isr: None,
+            leader: (-2).into(),
+            replicas: None,
+            removing_replicas: None,
+            adding_replicas: None,
+            leader_recovery_state: -1,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for PartitionChangeRecord {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+}
+
+impl PartitionChangeRecord {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionChangeRecord {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionId".into(), JsonValue::to_json(&self.partition_id));
+        node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        if !self.isr.is_none() {
+            node.insert("isr".into(), JsonValue::to_json(&self.isr));
+        }
+        if self.leader != -2 {
+            node.insert("leader".into(), JsonValue::to_json(&self.leader));
+        }
+        if !self.replicas.is_none() {
+            node.insert("replicas".into(), JsonValue::to_json(&self.replicas));
+        }
+        if !self.removing_replicas.is_none() {
+            node.insert("removingReplicas".into(), JsonValue::to_json(&self.removing_replicas));
+        }
+        if !self.adding_replicas.is_none() {
+            node.insert("addingReplicas".into(), JsonValue::to_json(&self.adding_replicas));
+        }
+        if self.leader_recovery_state != -1 {
+            node.insert("leaderRecoveryState".into(), JsonValue::to_json(&self.leader_recovery_state));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionChangeRecord")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionId") {
+            this.partition_id = JsonValue::from_json(value, "PartitionChangeRecord.partitionId")?;
+        } else {
+            return Err(crate::json::missing("PartitionChangeRecord", "partitionId", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "PartitionChangeRecord.topicId")?;
+        } else {
+            return Err(crate::json::missing("PartitionChangeRecord", "topicId", version));
+        }
+        if let Some(value) = object.get("isr") {
+            this.isr = JsonValue::from_json(value, "PartitionChangeRecord.isr")?;
+        }
+        if let Some(value) = object.get("leader") {
+            this.leader = JsonValue::from_json(value, "PartitionChangeRecord.leader")?;
+        }
+        if let Some(value) = object.get("replicas") {
+            this.replicas = JsonValue::from_json(value, "PartitionChangeRecord.replicas")?;
+        }
+        if let Some(value) = object.get("removingReplicas") {
+            this.removing_replicas = JsonValue::from_json(value, "PartitionChangeRecord.removingReplicas")?;
+        }
+        if let Some(value) = object.get("addingReplicas") {
+            this.adding_replicas = JsonValue::from_json(value, "PartitionChangeRecord.addingReplicas")?;
+        }
+        if let Some(value) = object.get("leaderRecoveryState") {
+            this.leader_recovery_state = JsonValue::from_json(value, "PartitionChangeRecord.leaderRecoveryState")?;
+        }
+        Ok(this)
+    }
+}
+
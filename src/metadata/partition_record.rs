@@ -0,0 +1,539 @@
+//! PartitionRecord
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/metadata/src/main/resources/common/metadata/PartitionRecord.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct PartitionRecord {
+    /// The partition id.
+    /// 
+    /// Supported API versions: 0
+    pub partition_id: i32,
+
+    /// The unique ID of this topic.
+    /// 
+    /// Supported API versions: 0
+    pub topic_id: Uuid,
+
+    /// The replicas of this partition, sorted by preferred order.
+    /// 
+    /// Supported API versions: 0
+    pub replicas: Vec<super::BrokerId>,
+
+    /// The in-sync replicas of this partition
+    /// 
+    /// Supported API versions: 0
+    pub isr: Vec<i32>,
+
+    /// The replicas that we are in the process of removing.
+    /// 
+    /// Supported API versions: 0
+    pub removing_replicas: Vec<super::BrokerId>,
+
+    /// The replicas that we are in the process of adding.
+    /// 
+    /// Supported API versions: 0
+    pub adding_replicas: Vec<super::BrokerId>,
+
+    /// The lead replica, or -1 if there is no leader.
+    /// 
+    /// Supported API versions: 0
+    pub leader: super::BrokerId,
+
+    /// 1 if the partition is recovering from an unclean leader election; 0 otherwise.
+    /// 
+    /// Supported API versions: 0
+    pub leader_recovery_state: i8,
+
+    /// The epoch of the partition leader.
+    /// 
+    /// Supported API versions: 0
+    pub leader_epoch: i32,
+
+    /// An epoch that gets incremented each time we change anything in the partition.
+    /// 
+    /// Supported API versions: 0
+    pub partition_epoch: i32,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for PartitionRecord {
+    type Builder = PartitionRecordBuilder;
+
+    fn builder() -> Self::Builder{
+        PartitionRecordBuilder::default()
+    }
+}
+
+impl Encodable for PartitionRecord {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("PartitionRecord", version));
+        }
+        types::Int32.encode(buf, &self.partition_id).map_err(|e| e.with_field("PartitionRecord", "partition_id", version))?;
+        types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("PartitionRecord", "topic_id", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("PartitionRecord", "replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("PartitionRecord", "isr", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.removing_replicas).map_err(|e| e.with_field("PartitionRecord", "removing_replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.adding_replicas).map_err(|e| e.with_field("PartitionRecord", "adding_replicas", version))?;
+        types::Int32.encode(buf, &self.leader).map_err(|e| e.with_field("PartitionRecord", "leader", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionRecord", "leader_epoch", version))?;
+        types::Int32.encode(buf, &self.partition_epoch).map_err(|e| e.with_field("PartitionRecord", "partition_epoch", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if self.leader_recovery_state != 0 {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        if self.leader_recovery_state != 0 {
+            let computed_size = types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "leader_recovery_state", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("PartitionRecord", version));
+        }
+        let mut total_size = 0;
+        total_size += types::Int32.compute_size(&self.partition_id).map_err(|e| e.with_field("PartitionRecord", "partition_id", version))?;
+        total_size += types::Uuid.compute_size(&self.topic_id).map_err(|e| e.with_field("PartitionRecord", "topic_id", version))?;
+        total_size += types::CompactArray(types::Int32).compute_size(&self.replicas).map_err(|e| e.with_field("PartitionRecord", "replicas", version))?;
+        total_size += types::CompactArray(types::Int32).compute_size(&self.isr).map_err(|e| e.with_field("PartitionRecord", "isr", version))?;
+        total_size += types::CompactArray(types::Int32).compute_size(&self.removing_replicas).map_err(|e| e.with_field("PartitionRecord", "removing_replicas", version))?;
+        total_size += types::CompactArray(types::Int32).compute_size(&self.adding_replicas).map_err(|e| e.with_field("PartitionRecord", "adding_replicas", version))?;
+        total_size += types::Int32.compute_size(&self.leader).map_err(|e| e.with_field("PartitionRecord", "leader", version))?;
+        total_size += types::Int32.compute_size(&self.leader_epoch).map_err(|e| e.with_field("PartitionRecord", "leader_epoch", version))?;
+        total_size += types::Int32.compute_size(&self.partition_epoch).map_err(|e| e.with_field("PartitionRecord", "partition_epoch", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if self.leader_recovery_state != 0 {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        if self.leader_recovery_state != 0 {
+            let computed_size = types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "leader_recovery_state", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            total_size += computed_size;
+        }
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for PartitionRecord {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("PartitionRecord", version));
+        }
+        let start = buf.remaining();
+        let partition_id = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionRecord", "partition_id", version, start - buf.remaining()))?;
+        let topic_id = types::Uuid.decode(buf).map_err(|e| e.with_field("PartitionRecord", "topic_id", version, start - buf.remaining()))?;
+        let replicas = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionRecord", "replicas", version, start - buf.remaining()))?;
+        let isr = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionRecord", "isr", version, start - buf.remaining()))?;
+        let removing_replicas = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionRecord", "removing_replicas", version, start - buf.remaining()))?;
+        let adding_replicas = types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionRecord", "adding_replicas", version, start - buf.remaining()))?;
+        let leader = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionRecord", "leader", version, start - buf.remaining()))?;
+        let mut leader_recovery_state = 0;
+        let leader_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionRecord", "leader_epoch", version, start - buf.remaining()))?;
+        let partition_epoch = types::Int32.decode(buf).map_err(|e| e.with_field("PartitionRecord", "partition_epoch", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            match tag {
+                0 => {
+                    leader_recovery_state = types::Int8.decode(buf).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version, start - buf.remaining()))?;
+                },
+                _ => {
+                    let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                    unknown_tagged_fields.insert(tag as i32, unknown_value);
+                }
+            }
+        }
+        Ok(Self {
+            partition_id,
+            topic_id,
+            replicas,
+            isr,
+            removing_replicas,
+            adding_replicas,
+            leader,
+            leader_recovery_state,
+            leader_epoch,
+            partition_epoch,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for PartitionRecord {
+    fn default() -> Self {
+        Self {
+            partition_id: -1,
+            topic_id: Uuid::nil(),
+            replicas: Default::default(),
+            isr: Default::default(),
+            removing_replicas: Default::default(),
+            adding_replicas: Default::default(),
+            leader: (-1).into(),
+            leader_recovery_state: 0,
+            leader_epoch: -1,
+            partition_epoch: -1,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for PartitionRecord {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+}
+
+impl PartitionRecord {
+    /// Encode the message with the elements of `replicas` produced by an iterator of known length, in place of
+    /// the `replicas` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_replicas<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, replicas: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = super::BrokerId>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("PartitionRecord", version));
+        }
+        let cache = &SizeCache::default();
+        let replicas = replicas.into_iter();
+        types::Int32.encode(buf, &self.partition_id).map_err(|e| e.with_field("PartitionRecord", "partition_id", version))?;
+        types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("PartitionRecord", "topic_id", version))?;
+        types::CompactArray(types::Int32).encode(buf, types::Streamed(replicas)).map_err(|e| e.with_field("PartitionRecord", "replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("PartitionRecord", "isr", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.removing_replicas).map_err(|e| e.with_field("PartitionRecord", "removing_replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.adding_replicas).map_err(|e| e.with_field("PartitionRecord", "adding_replicas", version))?;
+        types::Int32.encode(buf, &self.leader).map_err(|e| e.with_field("PartitionRecord", "leader", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionRecord", "leader_epoch", version))?;
+        types::Int32.encode(buf, &self.partition_epoch).map_err(|e| e.with_field("PartitionRecord", "partition_epoch", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if self.leader_recovery_state != 0 {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        if self.leader_recovery_state != 0 {
+            let computed_size = types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "leader_recovery_state", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `isr` produced by an iterator of known length, in place of
+    /// the `isr` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_isr<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, isr: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = i32>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("PartitionRecord", version));
+        }
+        let cache = &SizeCache::default();
+        let isr = isr.into_iter();
+        types::Int32.encode(buf, &self.partition_id).map_err(|e| e.with_field("PartitionRecord", "partition_id", version))?;
+        types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("PartitionRecord", "topic_id", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("PartitionRecord", "replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, types::Streamed(isr)).map_err(|e| e.with_field("PartitionRecord", "isr", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.removing_replicas).map_err(|e| e.with_field("PartitionRecord", "removing_replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.adding_replicas).map_err(|e| e.with_field("PartitionRecord", "adding_replicas", version))?;
+        types::Int32.encode(buf, &self.leader).map_err(|e| e.with_field("PartitionRecord", "leader", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionRecord", "leader_epoch", version))?;
+        types::Int32.encode(buf, &self.partition_epoch).map_err(|e| e.with_field("PartitionRecord", "partition_epoch", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if self.leader_recovery_state != 0 {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        if self.leader_recovery_state != 0 {
+            let computed_size = types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "leader_recovery_state", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `removing_replicas` produced by an iterator of known length, in place of
+    /// the `removing_replicas` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_removing_replicas<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, removing_replicas: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = super::BrokerId>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("PartitionRecord", version));
+        }
+        let cache = &SizeCache::default();
+        let removing_replicas = removing_replicas.into_iter();
+        types::Int32.encode(buf, &self.partition_id).map_err(|e| e.with_field("PartitionRecord", "partition_id", version))?;
+        types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("PartitionRecord", "topic_id", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("PartitionRecord", "replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("PartitionRecord", "isr", version))?;
+        types::CompactArray(types::Int32).encode(buf, types::Streamed(removing_replicas)).map_err(|e| e.with_field("PartitionRecord", "removing_replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.adding_replicas).map_err(|e| e.with_field("PartitionRecord", "adding_replicas", version))?;
+        types::Int32.encode(buf, &self.leader).map_err(|e| e.with_field("PartitionRecord", "leader", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionRecord", "leader_epoch", version))?;
+        types::Int32.encode(buf, &self.partition_epoch).map_err(|e| e.with_field("PartitionRecord", "partition_epoch", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if self.leader_recovery_state != 0 {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        if self.leader_recovery_state != 0 {
+            let computed_size = types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "leader_recovery_state", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `adding_replicas` produced by an iterator of known length, in place of
+    /// the `adding_replicas` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_adding_replicas<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, adding_replicas: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = super::BrokerId>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("PartitionRecord", version));
+        }
+        let cache = &SizeCache::default();
+        let adding_replicas = adding_replicas.into_iter();
+        types::Int32.encode(buf, &self.partition_id).map_err(|e| e.with_field("PartitionRecord", "partition_id", version))?;
+        types::Uuid.encode(buf, &self.topic_id).map_err(|e| e.with_field("PartitionRecord", "topic_id", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.replicas).map_err(|e| e.with_field("PartitionRecord", "replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.isr).map_err(|e| e.with_field("PartitionRecord", "isr", version))?;
+        types::CompactArray(types::Int32).encode(buf, &self.removing_replicas).map_err(|e| e.with_field("PartitionRecord", "removing_replicas", version))?;
+        types::CompactArray(types::Int32).encode(buf, types::Streamed(adding_replicas)).map_err(|e| e.with_field("PartitionRecord", "adding_replicas", version))?;
+        types::Int32.encode(buf, &self.leader).map_err(|e| e.with_field("PartitionRecord", "leader", version))?;
+        types::Int32.encode(buf, &self.leader_epoch).map_err(|e| e.with_field("PartitionRecord", "leader_epoch", version))?;
+        types::Int32.encode(buf, &self.partition_epoch).map_err(|e| e.with_field("PartitionRecord", "partition_epoch", version))?;
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if self.leader_recovery_state != 0 {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        if self.leader_recovery_state != 0 {
+            let computed_size = types::Int8.compute_size(&self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionRecord", "leader_recovery_state", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+            types::Int8.encode(buf, &self.leader_recovery_state).map_err(|e| e.with_field("PartitionRecord", "leader_recovery_state", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl PartitionRecord {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionRecord {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("partitionId".into(), JsonValue::to_json(&self.partition_id));
+        node.insert("topicId".into(), JsonValue::to_json(&self.topic_id));
+        node.insert("replicas".into(), JsonValue::to_json(&self.replicas));
+        node.insert("isr".into(), JsonValue::to_json(&self.isr));
+        node.insert("removingReplicas".into(), JsonValue::to_json(&self.removing_replicas));
+        node.insert("addingReplicas".into(), JsonValue::to_json(&self.adding_replicas));
+        node.insert("leader".into(), JsonValue::to_json(&self.leader));
+        if self.leader_recovery_state != 0 {
+            node.insert("leaderRecoveryState".into(), JsonValue::to_json(&self.leader_recovery_state));
+        }
+        node.insert("leaderEpoch".into(), JsonValue::to_json(&self.leader_epoch));
+        node.insert("partitionEpoch".into(), JsonValue::to_json(&self.partition_epoch));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionRecord")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("partitionId") {
+            this.partition_id = JsonValue::from_json(value, "PartitionRecord.partitionId")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "partitionId", version));
+        }
+        if let Some(value) = object.get("topicId") {
+            this.topic_id = JsonValue::from_json(value, "PartitionRecord.topicId")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "topicId", version));
+        }
+        if let Some(value) = object.get("replicas") {
+            this.replicas = JsonValue::from_json(value, "PartitionRecord.replicas")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "replicas", version));
+        }
+        if let Some(value) = object.get("isr") {
+            this.isr = JsonValue::from_json(value, "PartitionRecord.isr")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "isr", version));
+        }
+        if let Some(value) = object.get("removingReplicas") {
+            this.removing_replicas = JsonValue::from_json(value, "PartitionRecord.removingReplicas")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "removingReplicas", version));
+        }
+        if let Some(value) = object.get("addingReplicas") {
+            this.adding_replicas = JsonValue::from_json(value, "PartitionRecord.addingReplicas")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "addingReplicas", version));
+        }
+        if let Some(value) = object.get("leader") {
+            this.leader = JsonValue::from_json(value, "PartitionRecord.leader")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "leader", version));
+        }
+        if let Some(value) = object.get("leaderRecoveryState") {
+            this.leader_recovery_state = JsonValue::from_json(value, "PartitionRecord.leaderRecoveryState")?;
+        }
+        if let Some(value) = object.get("leaderEpoch") {
+            this.leader_epoch = JsonValue::from_json(value, "PartitionRecord.leaderEpoch")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "leaderEpoch", version));
+        }
+        if let Some(value) = object.get("partitionEpoch") {
+            this.partition_epoch = JsonValue::from_json(value, "PartitionRecord.partitionEpoch")?;
+        } else {
+            return Err(crate::json::missing("PartitionRecord", "partitionEpoch", version));
+        }
+        Ok(this)
+    }
+}
+
@@ -0,0 +1,870 @@
+//! RegisterBrokerRecord
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/metadata/src/main/resources/common/metadata/RegisterBrokerRecord.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0-2
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct BrokerEndpoint {
+    /// The hostname.
+    /// 
+    /// Supported API versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub host: StrBytes,
+
+    /// The port.
+    /// 
+    /// Supported API versions: 0-2
+    pub port: u16,
+
+    /// The security protocol.
+    /// 
+    /// Supported API versions: 0-2
+    pub security_protocol: i16,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for BrokerEndpoint {
+    type Builder = BrokerEndpointBuilder;
+
+    fn builder() -> Self::Builder{
+        BrokerEndpointBuilder::default()
+    }
+}
+
+impl MapEncodable for BrokerEndpoint {
+    type Key = StrBytes;
+    fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, key).map_err(|e| e.with_field("BrokerEndpoint", "name", version))?;
+        types::CompactString.encode(buf, &self.host).map_err(|e| e.with_field("BrokerEndpoint", "host", version))?;
+        types::UInt16.encode(buf, &self.port).map_err(|e| e.with_field("BrokerEndpoint", "port", version))?;
+        types::Int16.encode(buf, &self.security_protocol).map_err(|e| e.with_field("BrokerEndpoint", "security_protocol", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerEndpoint", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BrokerEndpoint", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerEndpoint", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("BrokerEndpoint", "name", version))?;
+        total_size += types::CompactString.compute_size(&self.host).map_err(|e| e.with_field("BrokerEndpoint", "host", version))?;
+        total_size += types::UInt16.compute_size(&self.port).map_err(|e| e.with_field("BrokerEndpoint", "port", version))?;
+        total_size += types::Int16.compute_size(&self.security_protocol).map_err(|e| e.with_field("BrokerEndpoint", "security_protocol", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerEndpoint", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("BrokerEndpoint", "unknown_tagged_fields", version))?;
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerEndpoint", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl MapDecodable for BrokerEndpoint {
+    type Key = StrBytes;
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
+        let key_field = types::CompactString.decode(buf).map_err(|e| e.with_field("BrokerEndpoint", "name", version, start - buf.remaining()))?;
+        let host = types::CompactString.decode(buf).map_err(|e| e.with_field("BrokerEndpoint", "host", version, start - buf.remaining()))?;
+        let port = types::UInt16.decode(buf).map_err(|e| e.with_field("BrokerEndpoint", "port", version, start - buf.remaining()))?;
+        let security_protocol = types::Int16.decode(buf).map_err(|e| e.with_field("BrokerEndpoint", "security_protocol", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerEndpoint", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerEndpoint", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerEndpoint", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("BrokerEndpoint", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            unknown_tagged_fields.insert(tag as i32, unknown_value);
+        }
+        Ok((key_field, Self {
+            host,
+            port,
+            security_protocol,
+            unknown_tagged_fields,
+        }))
+    }
+}
+
+impl Default for BrokerEndpoint {
+    fn default() -> Self {
+        Self {
+            host: Default::default(),
+            port: 0,
+            security_protocol: 0,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for BrokerEndpoint {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
+}
+
+impl BrokerEndpoint {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl BrokerEndpoint {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("host".into(), JsonValue::to_json(&self.host));
+        node.insert("port".into(), JsonValue::to_json(&self.port));
+        node.insert("securityProtocol".into(), JsonValue::to_json(&self.security_protocol));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "BrokerEndpoint")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "BrokerEndpoint.name")?;
+        } else {
+            return Err(crate::json::missing("BrokerEndpoint", "name", version));
+        }
+        if let Some(value) = object.get("host") {
+            this.host = JsonValue::from_json(value, "BrokerEndpoint.host")?;
+        } else {
+            return Err(crate::json::missing("BrokerEndpoint", "host", version));
+        }
+        if let Some(value) = object.get("port") {
+            this.port = JsonValue::from_json(value, "BrokerEndpoint.port")?;
+        } else {
+            return Err(crate::json::missing("BrokerEndpoint", "port", version));
+        }
+        if let Some(value) = object.get("securityProtocol") {
+            this.security_protocol = JsonValue::from_json(value, "BrokerEndpoint.securityProtocol")?;
+        } else {
+            return Err(crate::json::missing("BrokerEndpoint", "securityProtocol", version));
+        }
+        Ok((key, this))
+    }
+}
+
+/// Valid versions: 0-2
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct BrokerFeature {
+    /// The minimum supported feature level.
+    /// 
+    /// Supported API versions: 0-2
+    pub min_supported_version: i16,
+
+    /// The maximum supported feature level.
+    /// 
+    /// Supported API versions: 0-2
+    pub max_supported_version: i16,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for BrokerFeature {
+    type Builder = BrokerFeatureBuilder;
+
+    fn builder() -> Self::Builder{
+        BrokerFeatureBuilder::default()
+    }
+}
+
+impl MapEncodable for BrokerFeature {
+    type Key = StrBytes;
+    fn encode<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(key, buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(key, version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, key: &Self::Key, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::CompactString.encode(buf, key).map_err(|e| e.with_field("BrokerFeature", "name", version))?;
+        types::Int16.encode(buf, &self.min_supported_version).map_err(|e| e.with_field("BrokerFeature", "min_supported_version", version))?;
+        types::Int16.encode(buf, &self.max_supported_version).map_err(|e| e.with_field("BrokerFeature", "max_supported_version", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerFeature", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("BrokerFeature", "unknown_tagged_fields", version))?;
+
+        write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerFeature", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, key: &Self::Key, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        total_size += types::CompactString.compute_size(key).map_err(|e| e.with_field("BrokerFeature", "name", version))?;
+        total_size += types::Int16.compute_size(&self.min_supported_version).map_err(|e| e.with_field("BrokerFeature", "min_supported_version", version))?;
+        total_size += types::Int16.compute_size(&self.max_supported_version).map_err(|e| e.with_field("BrokerFeature", "max_supported_version", version))?;
+        let num_tagged_fields = self.unknown_tagged_fields.len();
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("BrokerFeature", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("BrokerFeature", "unknown_tagged_fields", version))?;
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("BrokerFeature", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl MapDecodable for BrokerFeature {
+    type Key = StrBytes;
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError> {
+        let start = buf.remaining();
+        let key_field = types::CompactString.decode(buf).map_err(|e| e.with_field("BrokerFeature", "name", version, start - buf.remaining()))?;
+        let min_supported_version = types::Int16.decode(buf).map_err(|e| e.with_field("BrokerFeature", "min_supported_version", version, start - buf.remaining()))?;
+        let max_supported_version = types::Int16.decode(buf).map_err(|e| e.with_field("BrokerFeature", "max_supported_version", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerFeature", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerFeature", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("BrokerFeature", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("BrokerFeature", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            unknown_tagged_fields.insert(tag as i32, unknown_value);
+        }
+        Ok((key_field, Self {
+            min_supported_version,
+            max_supported_version,
+            unknown_tagged_fields,
+        }))
+    }
+}
+
+impl Default for BrokerFeature {
+    fn default() -> Self {
+        Self {
+            min_supported_version: 0,
+            max_supported_version: 0,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for BrokerFeature {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
+}
+
+impl BrokerFeature {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl BrokerFeature {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, key: &StrBytes, version: i16) -> serde_json::Value {
+        self.to_json_with(key, version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, key: &StrBytes, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("name".into(), JsonValue::to_json(key));
+        node.insert("minSupportedVersion".into(), JsonValue::to_json(&self.min_supported_version));
+        node.insert("maxSupportedVersion".into(), JsonValue::to_json(&self.max_supported_version));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<(StrBytes, Self), JsonError> {
+        let object = crate::json::object(node, "BrokerFeature")?;
+        let mut this = Self::default();
+        let mut key = StrBytes::default();
+        if let Some(value) = object.get("name") {
+            key = JsonValue::from_json(value, "BrokerFeature.name")?;
+        } else {
+            return Err(crate::json::missing("BrokerFeature", "name", version));
+        }
+        if let Some(value) = object.get("minSupportedVersion") {
+            this.min_supported_version = JsonValue::from_json(value, "BrokerFeature.minSupportedVersion")?;
+        } else {
+            return Err(crate::json::missing("BrokerFeature", "minSupportedVersion", version));
+        }
+        if let Some(value) = object.get("maxSupportedVersion") {
+            this.max_supported_version = JsonValue::from_json(value, "BrokerFeature.maxSupportedVersion")?;
+        } else {
+            return Err(crate::json::missing("BrokerFeature", "maxSupportedVersion", version));
+        }
+        Ok((key, this))
+    }
+}
+
+/// Valid versions: 0-2
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct RegisterBrokerRecord {
+    /// The broker id.
+    /// 
+    /// Supported API versions: 0-2
+    pub broker_id: super::BrokerId,
+
+    /// True if the registering broker is a ZK broker.
+    /// 
+    /// Supported API versions: 2
+    pub is_migrating_zk_broker: bool,
+
+    /// The incarnation ID of the broker process
+    /// 
+    /// Supported API versions: 0-2
+    pub incarnation_id: Uuid,
+
+    /// The broker epoch assigned by the controller.
+    /// 
+    /// Supported API versions: 0-2
+    pub broker_epoch: i64,
+
+    /// The endpoints that can be used to communicate with this broker.
+    /// 
+    /// Supported API versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub end_points: indexmap::IndexMap<StrBytes, BrokerEndpoint>,
+
+    /// The features on this broker
+    /// 
+    /// Supported API versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub features: indexmap::IndexMap<StrBytes, BrokerFeature>,
+
+    /// The broker rack.
+    /// 
+    /// Supported API versions: 0-2
+    /// May be `None` in versions: 0-2
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub rack: Option<StrBytes>,
+
+    /// True if the broker is fenced.
+    /// 
+    /// Supported API versions: 0-2
+    pub fenced: bool,
+
+    /// True if the broker is in controlled shutdown.
+    /// 
+    /// Supported API versions: 1-2
+    pub in_controlled_shutdown: bool,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for RegisterBrokerRecord {
+    type Builder = RegisterBrokerRecordBuilder;
+
+    fn builder() -> Self::Builder{
+        RegisterBrokerRecordBuilder::default()
+    }
+}
+
+impl Encodable for RegisterBrokerRecord {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("RegisterBrokerRecord", version));
+        }
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_id", version))?;
+        if version >= 2 {
+            types::Boolean.encode(buf, &self.is_migrating_zk_broker).map_err(|e| e.with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version))?;
+        } else {
+            if self.is_migrating_zk_broker {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version));
+            }
+        }
+        types::Uuid.encode(buf, &self.incarnation_id).map_err(|e| e.with_field("RegisterBrokerRecord", "incarnation_id", version))?;
+        types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_epoch", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.end_points).map_err(|e| e.with_field("RegisterBrokerRecord", "end_points", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.features).map_err(|e| e.with_field("RegisterBrokerRecord", "features", version))?;
+        types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("RegisterBrokerRecord", "rack", version))?;
+        if version >= 1 {
+            types::Boolean.encode(buf, &self.in_controlled_shutdown).map_err(|e| e.with_field("RegisterBrokerRecord", "in_controlled_shutdown", version))?;
+        } else {
+            if self.in_controlled_shutdown {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("RegisterBrokerRecord", "in_controlled_shutdown", version));
+            }
+        }
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if !self.fenced {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RegisterBrokerRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version))?;
+        if !self.fenced {
+            let computed_size = types::Boolean.compute_size(&self.fenced).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RegisterBrokerRecord", "fenced", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            types::Boolean.encode(buf, &self.fenced).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("RegisterBrokerRecord", version));
+        }
+        let mut total_size = 0;
+        total_size += types::Int32.compute_size(&self.broker_id).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_id", version))?;
+        if version >= 2 {
+            total_size += types::Boolean.compute_size(&self.is_migrating_zk_broker).map_err(|e| e.with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version))?;
+        } else {
+            if self.is_migrating_zk_broker {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version));
+            }
+        }
+        total_size += types::Uuid.compute_size(&self.incarnation_id).map_err(|e| e.with_field("RegisterBrokerRecord", "incarnation_id", version))?;
+        total_size += types::Int64.compute_size(&self.broker_epoch).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_epoch", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.end_points).map_err(|e| e.with_field("RegisterBrokerRecord", "end_points", version))?;
+        total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.features).map_err(|e| e.with_field("RegisterBrokerRecord", "features", version))?;
+        total_size += types::CompactString.compute_size(&self.rack).map_err(|e| e.with_field("RegisterBrokerRecord", "rack", version))?;
+        if version >= 1 {
+            total_size += types::Boolean.compute_size(&self.in_controlled_shutdown).map_err(|e| e.with_field("RegisterBrokerRecord", "in_controlled_shutdown", version))?;
+        } else {
+            if self.in_controlled_shutdown {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("RegisterBrokerRecord", "in_controlled_shutdown", version));
+            }
+        }
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if !self.fenced {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RegisterBrokerRecord", "unknown_tagged_fields", version));
+        }
+        total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version))?;
+        if !self.fenced {
+            let computed_size = types::Boolean.compute_size(&self.fenced).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RegisterBrokerRecord", "fenced", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(0).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            total_size += types::UnsignedVarInt.compute_size(computed_size as u32).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            total_size += computed_size;
+        }
+
+        total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version))?;
+        Ok(total_size)
+    }
+}
+
+impl Decodable for RegisterBrokerRecord {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        if !Self::VERSIONS.contains(version) {
+            return Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion).with_message("RegisterBrokerRecord", version));
+        }
+        let start = buf.remaining();
+        let broker_id = types::Int32.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_id", version, start - buf.remaining()))?;
+        let is_migrating_zk_broker = if version >= 2 {
+            types::Boolean.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version, start - buf.remaining()))?
+        } else {
+            false
+        };
+        let incarnation_id = types::Uuid.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "incarnation_id", version, start - buf.remaining()))?;
+        let broker_epoch = types::Int64.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_epoch", version, start - buf.remaining()))?;
+        let end_points = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "end_points", version, start - buf.remaining()))?;
+        let features = types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "features", version, start - buf.remaining()))?;
+        let rack = types::CompactString.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "rack", version, start - buf.remaining()))?;
+        let mut fenced = true;
+        let in_controlled_shutdown = if version >= 1 {
+            types::Boolean.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "in_controlled_shutdown", version, start - buf.remaining()))?
+        } else {
+            false
+        };
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+        for _ in 0..num_tagged_fields {
+            let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            match tag {
+                0 => {
+                    fenced = types::Boolean.decode(buf).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version, start - buf.remaining()))?;
+                },
+                _ => {
+                    let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                    unknown_tagged_fields.insert(tag as i32, unknown_value);
+                }
+            }
+        }
+        Ok(Self {
+            broker_id,
+            is_migrating_zk_broker,
+            incarnation_id,
+            broker_epoch,
+            end_points,
+            features,
+            rack,
+            fenced,
+            in_controlled_shutdown,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for RegisterBrokerRecord {
+    fn default() -> Self {
+        Self {
+            broker_id: (0).into(),
+            is_migrating_zk_broker: false,
+            incarnation_id: Uuid::nil(),
+            broker_epoch: 0,
+            end_points: Default::default(),
+            features: Default::default(),
+            rack: Some(Default::default()),
+            fenced: true,
+            in_controlled_shutdown: false,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for RegisterBrokerRecord {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 2 };
+}
+
+impl RegisterBrokerRecord {
+    /// Encode the message with the elements of `end_points` produced by an iterator of known length, in place of
+    /// the `end_points` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_end_points<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, end_points: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (StrBytes, BrokerEndpoint)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("RegisterBrokerRecord", version));
+        }
+        let cache = &SizeCache::default();
+        let end_points = end_points.into_iter();
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_id", version))?;
+        if version >= 2 {
+            types::Boolean.encode(buf, &self.is_migrating_zk_broker).map_err(|e| e.with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version))?;
+        } else {
+            if self.is_migrating_zk_broker {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version));
+            }
+        }
+        types::Uuid.encode(buf, &self.incarnation_id).map_err(|e| e.with_field("RegisterBrokerRecord", "incarnation_id", version))?;
+        types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_epoch", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(end_points)).map_err(|e| e.with_field("RegisterBrokerRecord", "end_points", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.features).map_err(|e| e.with_field("RegisterBrokerRecord", "features", version))?;
+        types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("RegisterBrokerRecord", "rack", version))?;
+        if version >= 1 {
+            types::Boolean.encode(buf, &self.in_controlled_shutdown).map_err(|e| e.with_field("RegisterBrokerRecord", "in_controlled_shutdown", version))?;
+        } else {
+            if self.in_controlled_shutdown {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("RegisterBrokerRecord", "in_controlled_shutdown", version));
+            }
+        }
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if !self.fenced {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RegisterBrokerRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version))?;
+        if !self.fenced {
+            let computed_size = types::Boolean.compute_size(&self.fenced).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RegisterBrokerRecord", "fenced", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            types::Boolean.encode(buf, &self.fenced).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+
+    /// Encode the message with the elements of `features` produced by an iterator of known length, in place of
+    /// the `features` field, which is ignored. Elements are encoded as they are produced, so the array never
+    /// has to be built in memory. Only this top-level field is streamed: arrays nested in its elements are
+    /// encoded from the elements as usual.
+    ///
+    /// If the iterator produces a different number of elements than its length, an error is returned after
+    /// part of the message was written to `buf`, which should then be discarded.
+    pub fn encode_with_features<B: ByteBufMut, I>(&self, buf: &mut B, version: i16, features: I) -> Result<(), EncodeError>
+    where
+        I: IntoIterator<Item = (StrBytes, BrokerFeature)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if !Self::VERSIONS.contains(version) {
+            return Err(EncodeError::new(EncodeErrorKind::UnsupportedVersion).with_message("RegisterBrokerRecord", version));
+        }
+        let cache = &SizeCache::default();
+        let features = features.into_iter();
+        types::Int32.encode(buf, &self.broker_id).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_id", version))?;
+        if version >= 2 {
+            types::Boolean.encode(buf, &self.is_migrating_zk_broker).map_err(|e| e.with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version))?;
+        } else {
+            if self.is_migrating_zk_broker {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("RegisterBrokerRecord", "is_migrating_zk_broker", version));
+            }
+        }
+        types::Uuid.encode(buf, &self.incarnation_id).map_err(|e| e.with_field("RegisterBrokerRecord", "incarnation_id", version))?;
+        types::Int64.encode(buf, &self.broker_epoch).map_err(|e| e.with_field("RegisterBrokerRecord", "broker_epoch", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.end_points).map_err(|e| e.with_field("RegisterBrokerRecord", "end_points", version))?;
+        types::CompactArray(CachedStruct { version, cache }).encode(buf, types::StreamedMap(features)).map_err(|e| e.with_field("RegisterBrokerRecord", "features", version))?;
+        types::CompactString.encode(buf, &self.rack).map_err(|e| e.with_field("RegisterBrokerRecord", "rack", version))?;
+        if version >= 1 {
+            types::Boolean.encode(buf, &self.in_controlled_shutdown).map_err(|e| e.with_field("RegisterBrokerRecord", "in_controlled_shutdown", version))?;
+        } else {
+            if self.in_controlled_shutdown {
+                return Err(EncodeError::new(EncodeErrorKind::FieldNotValidAtVersion).with_field("RegisterBrokerRecord", "in_controlled_shutdown", version));
+            }
+        }
+        let mut num_tagged_fields = self.unknown_tagged_fields.len();
+        if !self.fenced {
+            num_tagged_fields += 1;
+        }
+        if num_tagged_fields > std::u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RegisterBrokerRecord", "unknown_tagged_fields", version));
+        }
+        types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version))?;
+        if !self.fenced {
+            let computed_size = types::Boolean.compute_size(&self.fenced).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            if computed_size > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("RegisterBrokerRecord", "fenced", version));
+            }
+            types::UnsignedVarInt.encode(buf, 0).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            types::UnsignedVarInt.encode(buf, computed_size as u32).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+            types::Boolean.encode(buf, &self.fenced).map_err(|e| e.with_field("RegisterBrokerRecord", "fenced", version))?;
+        }
+
+        write_unknown_tagged_fields(buf, 1.., &self.unknown_tagged_fields).map_err(|e| e.with_field("RegisterBrokerRecord", "unknown_tagged_fields", version))?;
+        Ok(())
+    }
+}
+
+impl RegisterBrokerRecord {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if version < 2 {
+            self.is_migrating_zk_broker = false;
+        }
+        for item in self.end_points.values_mut() {
+            item.normalize_to(version);
+        }
+        for item in self.features.values_mut() {
+            item.normalize_to(version);
+        }
+        if version < 1 {
+            self.in_controlled_shutdown = false;
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if version < 2 {
+            if self.is_migrating_zk_broker {
+                invalid.push(format!("{}is_migrating_zk_broker", path));
+            }
+        }
+        for (i, item) in self.end_points.values().enumerate() {
+            item.validate_into(version, &format!("{}end_points[{}].", path, i), invalid);
+        }
+        for (i, item) in self.features.values().enumerate() {
+            item.validate_into(version, &format!("{}features[{}].", path, i), invalid);
+        }
+        if version < 1 {
+            if self.in_controlled_shutdown {
+                invalid.push(format!("{}in_controlled_shutdown", path));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl RegisterBrokerRecord {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("brokerId".into(), JsonValue::to_json(&self.broker_id));
+        if version >= 2 {
+            node.insert("isMigratingZkBroker".into(), JsonValue::to_json(&self.is_migrating_zk_broker));
+        }
+        node.insert("incarnationId".into(), JsonValue::to_json(&self.incarnation_id));
+        node.insert("brokerEpoch".into(), JsonValue::to_json(&self.broker_epoch));
+        node.insert("endPoints".into(), serde_json::Value::Array(self.end_points.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("features".into(), serde_json::Value::Array(self.features.iter().map(|(key, item)| item.to_json_with(key, version, records)).collect()));
+        node.insert("rack".into(), JsonValue::to_json(&self.rack));
+        if !self.fenced {
+            node.insert("fenced".into(), JsonValue::to_json(&self.fenced));
+        }
+        if version >= 1 {
+            node.insert("inControlledShutdown".into(), JsonValue::to_json(&self.in_controlled_shutdown));
+        }
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "RegisterBrokerRecord")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("brokerId") {
+            this.broker_id = JsonValue::from_json(value, "RegisterBrokerRecord.brokerId")?;
+        } else {
+            return Err(crate::json::missing("RegisterBrokerRecord", "brokerId", version));
+        }
+        if let Some(value) = object.get("isMigratingZkBroker") {
+            this.is_migrating_zk_broker = JsonValue::from_json(value, "RegisterBrokerRecord.isMigratingZkBroker")?;
+        } else if version >= 2 {
+            return Err(crate::json::missing("RegisterBrokerRecord", "isMigratingZkBroker", version));
+        }
+        if let Some(value) = object.get("incarnationId") {
+            this.incarnation_id = JsonValue::from_json(value, "RegisterBrokerRecord.incarnationId")?;
+        } else {
+            return Err(crate::json::missing("RegisterBrokerRecord", "incarnationId", version));
+        }
+        if let Some(value) = object.get("brokerEpoch") {
+            this.broker_epoch = JsonValue::from_json(value, "RegisterBrokerRecord.brokerEpoch")?;
+        } else {
+            return Err(crate::json::missing("RegisterBrokerRecord", "brokerEpoch", version));
+        }
+        if let Some(value) = object.get("endPoints") {
+            this.end_points = crate::json::array(value, "RegisterBrokerRecord.endPoints")?.iter().map(|item| BrokerEndpoint::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("RegisterBrokerRecord", "endPoints", version));
+        }
+        if let Some(value) = object.get("features") {
+            this.features = crate::json::array(value, "RegisterBrokerRecord.features")?.iter().map(|item| BrokerFeature::from_json(item, version)).collect::<Result<_, _>>()?;
+        } else {
+            return Err(crate::json::missing("RegisterBrokerRecord", "features", version));
+        }
+        if let Some(value) = object.get("rack") {
+            this.rack = JsonValue::from_json(value, "RegisterBrokerRecord.rack")?;
+        } else {
+            return Err(crate::json::missing("RegisterBrokerRecord", "rack", version));
+        }
+        if let Some(value) = object.get("fenced") {
+            this.fenced = JsonValue::from_json(value, "RegisterBrokerRecord.fenced")?;
+        }
+        if let Some(value) = object.get("inControlledShutdown") {
+            this.in_controlled_shutdown = JsonValue::from_json(value, "RegisterBrokerRecord.inControlledShutdown")?;
+        } else if version >= 1 {
+            return Err(crate::json::missing("RegisterBrokerRecord", "inControlledShutdown", version));
+        }
+        Ok(this)
+    }
+}
+
@@ -3,10 +3,13 @@ use std::io::Cursor;
 use std::mem::size_of;
 use std::ops::Range;
 
+use bytes::buf::UninitSlice;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+use super::{DecodeError, DecodeErrorKind};
+
 /// Error indicating there are not enough remaining bytes in a buffer to perform a read.
 #[derive(Debug)]
 pub struct NotEnoughBytesError;
@@ -43,6 +46,16 @@ pub trait ByteBuf: Buf {
             Ok(self.get_bytes(size))
         }
     }
+    /// Try to advance past `cnt` bytes without reading them, returning an error if there are less
+    /// bytes than the requested number.
+    fn try_advance(&mut self, cnt: usize) -> Result<(), NotEnoughBytesError> {
+        if self.remaining() < cnt {
+            Err(NotEnoughBytesError)
+        } else {
+            self.advance(cnt);
+            Ok(())
+        }
+    }
     /// Attempt to copy from buffer into destination slice, returning an error if not enough space
     /// remains.
     fn try_copy_to_slice(&mut self, dst: &mut [u8]) -> Result<(), NotEnoughBytesError> {
@@ -117,6 +130,44 @@ pub trait ByteBuf: Buf {
             Ok(self.get_f64())
         }
     }
+    /// Whether [`ByteBuf::get_bytes`] returns slices sharing the memory of the buffer, rather than
+    /// copies.
+    fn shares_bytes(&self) -> bool {
+        false
+    }
+    /// Check that a string or bytes of `len` bytes may be decoded from the buffer, before
+    /// allocating for it.
+    fn check_bytes_len(&mut self, len: usize) -> Result<(), DecodeError> {
+        if self.remaining() < len {
+            Err(NotEnoughBytesError.into())
+        } else {
+            Ok(())
+        }
+    }
+    /// Check that `len` bytes may be taken from the buffer with [`ByteBuf::get_bytes`], which
+    /// only allocates for them if the buffer does not [share its bytes](ByteBuf::shares_bytes).
+    fn check_slice_len(&mut self, len: usize) -> Result<(), DecodeError> {
+        self.check_bytes_len(len)
+    }
+    /// Check that `len` bytes may be allocated for values which are not read from the buffer as
+    /// is, such as decompressed records.
+    fn check_allocation(&mut self, _len: usize) -> Result<(), DecodeError> {
+        Ok(())
+    }
+    /// Check that an array of `len` elements of type `T` may be decoded from the buffer,
+    /// returning the number of elements to allocate for up front.
+    ///
+    /// Every element takes at least one byte, so no more elements are allocated for than there
+    /// are bytes remaining, whatever the length prefix claims.
+    fn check_array_len<T>(&mut self, len: usize) -> Result<usize, DecodeError> {
+        Ok(len.min(self.remaining()))
+    }
+    /// Called before decoding a nested struct.
+    fn enter_struct(&mut self) -> Result<(), DecodeError> {
+        Ok(())
+    }
+    /// Called after decoding a nested struct.
+    fn exit_struct(&mut self) {}
 }
 
 impl ByteBuf for Bytes {
@@ -126,6 +177,9 @@ impl ByteBuf for Bytes {
     fn get_bytes(&mut self, size: usize) -> Bytes {
         self.split_to(size)
     }
+    fn shares_bytes(&self) -> bool {
+        true
+    }
 }
 
 impl ByteBuf for BytesMut {
@@ -135,6 +189,9 @@ impl ByteBuf for BytesMut {
     fn get_bytes(&mut self, size: usize) -> Bytes {
         self.split_to(size).freeze()
     }
+    fn shares_bytes(&self) -> bool {
+        true
+    }
 }
 
 impl<T: ByteBuf> ByteBuf for &mut T {
@@ -150,6 +207,30 @@ impl<T: ByteBuf> ByteBuf for &mut T {
     fn try_get_bytes(&mut self, size: usize) -> Result<Bytes, NotEnoughBytesError> {
         (**self).try_get_bytes(size)
     }
+    fn try_advance(&mut self, cnt: usize) -> Result<(), NotEnoughBytesError> {
+        (**self).try_advance(cnt)
+    }
+    fn shares_bytes(&self) -> bool {
+        (**self).shares_bytes()
+    }
+    fn check_bytes_len(&mut self, len: usize) -> Result<(), DecodeError> {
+        (**self).check_bytes_len(len)
+    }
+    fn check_slice_len(&mut self, len: usize) -> Result<(), DecodeError> {
+        (**self).check_slice_len(len)
+    }
+    fn check_allocation(&mut self, len: usize) -> Result<(), DecodeError> {
+        (**self).check_allocation(len)
+    }
+    fn check_array_len<U>(&mut self, len: usize) -> Result<usize, DecodeError> {
+        (**self).check_array_len::<U>(len)
+    }
+    fn enter_struct(&mut self) -> Result<(), DecodeError> {
+        (**self).enter_struct()
+    }
+    fn exit_struct(&mut self) {
+        (**self).exit_struct()
+    }
 }
 
 impl ByteBuf for &[u8] {
@@ -174,6 +255,164 @@ impl<T: AsRef<[u8]>> ByteBuf for Cursor<T> {
     }
 }
 
+/// Limits on the values decoded from untrusted input.
+///
+/// Decoding a message with [`Decodable::decode_with_limits`](super::Decodable::decode_with_limits)
+/// fails with [`DecodeErrorKind::LimitExceeded`] as soon as one of them is exceeded, before
+/// allocating for the offending value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// The maximum number of elements in an array.
+    pub max_array_len: usize,
+    /// The maximum length of a string or bytes, including records.
+    pub max_bytes_len: usize,
+    /// The maximum number of bytes allocated for the arrays, strings and bytes of a message, and
+    /// for decompressing records.
+    ///
+    /// Strings and bytes sliced out of a [`Bytes`] or [`BytesMut`] buffer share its memory, so
+    /// they don't count against this limit.
+    pub max_allocation: usize,
+    /// The maximum depth of nested structs.
+    pub max_depth: usize,
+}
+
+impl Default for DecodeLimits {
+    /// Limits matching the defaults of a Kafka broker, which accepts requests of up to 100MiB.
+    fn default() -> Self {
+        Self {
+            max_array_len: 1 << 20,
+            max_bytes_len: 100 << 20,
+            max_allocation: 256 << 20,
+            max_depth: 32,
+        }
+    }
+}
+
+/// A buffer which enforces [`DecodeLimits`] on the values decoded from it.
+#[derive(Debug)]
+pub struct LimitedBuf<B> {
+    inner: B,
+    limits: DecodeLimits,
+    allocated: usize,
+    depth: usize,
+}
+
+impl<B> LimitedBuf<B> {
+    /// Wrap a buffer, enforcing `limits` on the values decoded from it.
+    pub fn new(inner: B, limits: DecodeLimits) -> Self {
+        Self {
+            inner,
+            limits,
+            allocated: 0,
+            depth: 0,
+        }
+    }
+
+    /// The number of bytes allocated for the values decoded so far.
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Unwrap the inner buffer.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn check_max_bytes_len(&self, len: usize) -> Result<(), DecodeError> {
+        if len > self.limits.max_bytes_len {
+            error!(
+                "Length {} exceeds the limit of {} bytes",
+                len, self.limits.max_bytes_len
+            );
+            Err(DecodeError::new(DecodeErrorKind::LimitExceeded))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn allocate(&mut self, size: usize) -> Result<(), DecodeError> {
+        self.allocated = self.allocated.saturating_add(size);
+        if self.allocated > self.limits.max_allocation {
+            error!(
+                "Decoding would allocate more than {} bytes",
+                self.limits.max_allocation
+            );
+            Err(DecodeError::new(DecodeErrorKind::LimitExceeded))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<B: Buf> Buf for LimitedBuf<B> {
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+    fn chunk(&self) -> &[u8] {
+        self.inner.chunk()
+    }
+    fn advance(&mut self, cnt: usize) {
+        self.inner.advance(cnt)
+    }
+}
+
+impl<B: ByteBuf> ByteBuf for LimitedBuf<B> {
+    fn peek_bytes(&mut self, r: Range<usize>) -> Bytes {
+        self.inner.peek_bytes(r)
+    }
+    fn get_bytes(&mut self, size: usize) -> Bytes {
+        self.inner.get_bytes(size)
+    }
+    fn shares_bytes(&self) -> bool {
+        self.inner.shares_bytes()
+    }
+    fn check_bytes_len(&mut self, len: usize) -> Result<(), DecodeError> {
+        self.check_max_bytes_len(len)?;
+        self.inner.check_bytes_len(len)?;
+        self.allocate(len)
+    }
+    fn check_slice_len(&mut self, len: usize) -> Result<(), DecodeError> {
+        self.check_max_bytes_len(len)?;
+        self.inner.check_slice_len(len)?;
+        if self.inner.shares_bytes() {
+            Ok(())
+        } else {
+            self.allocate(len)
+        }
+    }
+    fn check_allocation(&mut self, len: usize) -> Result<(), DecodeError> {
+        self.inner.check_allocation(len)?;
+        self.allocate(len)
+    }
+    fn check_array_len<T>(&mut self, len: usize) -> Result<usize, DecodeError> {
+        if len > self.limits.max_array_len {
+            error!(
+                "Array length {} exceeds the limit of {} elements",
+                len, self.limits.max_array_len
+            );
+            return Err(DecodeError::new(DecodeErrorKind::LimitExceeded));
+        }
+        let capacity = self.inner.check_array_len::<T>(len)?;
+        self.allocate(capacity.saturating_mul(size_of::<T>()))?;
+        Ok(capacity)
+    }
+    fn enter_struct(&mut self) -> Result<(), DecodeError> {
+        if self.depth >= self.limits.max_depth {
+            error!(
+                "Structs are nested deeper than {} levels",
+                self.limits.max_depth
+            );
+            return Err(DecodeError::new(DecodeErrorKind::LimitExceeded));
+        }
+        self.depth += 1;
+        self.inner.enter_struct()
+    }
+    fn exit_struct(&mut self) {
+        self.depth -= 1;
+        self.inner.exit_struct()
+    }
+}
+
 /// A gap of specified length at the specified offset.
 #[derive(Debug, Copy, Clone)]
 pub struct Gap {
@@ -203,6 +442,7 @@ macro_rules! define_gap_types {
             use super::*;
             $(
                 #[derive(Copy, Clone, Debug)]
+                #[allow(dead_code)]
                 pub(crate) struct $n;
 
                 impl GapType for $n {
@@ -282,6 +522,12 @@ pub trait ByteBufMut: BufMut {
     fn fill_typed_gap<T: GapType>(&mut self, gap: TypedGap<T>, value: T::Value) {
         gap.type_.put(self.gap_buf(gap.gap), value);
     }
+
+    /// Put `bytes` at the current buffer offset, sharing them instead of copying them if the
+    /// buffer supports it.
+    fn put_shared_bytes(&mut self, bytes: &Bytes) {
+        self.put_slice(bytes);
+    }
 }
 
 impl ByteBufMut for BytesMut {
@@ -318,4 +564,122 @@ impl<T: ByteBufMut> ByteBufMut for &mut T {
     fn range(&mut self, r: Range<usize>) -> &mut [u8] {
         (**self).range(r)
     }
+    fn put_shared_bytes(&mut self, bytes: &Bytes) {
+        (**self).put_shared_bytes(bytes)
+    }
+}
+
+/// A buffer which encodes into a chain of [`Bytes`] chunks, suitable for vectored writes.
+///
+/// Bytes put with [`ByteBufMut::put_shared_bytes`], such as the records of a `ProduceRequest` or
+/// `FetchResponse`, are kept as chunks of their own instead of being copied, and everything else
+/// is written into chunks in between them.
+///
+/// ```rust
+/// use std::io::IoSlice;
+/// use bytes::{BufMut, Bytes};
+/// use kafka_protocol::protocol::buf::{ByteBufMut, BytesChain};
+///
+/// let records = Bytes::from(vec![0; 1024 * 1024]);
+/// let mut chain = BytesChain::new();
+/// chain.put_i32(records.len() as i32);
+/// chain.put_shared_bytes(&records);
+/// let chunks = chain.into_chunks();
+/// assert_eq!(chunks[1].as_ptr(), records.as_ptr());
+/// let slices: Vec<IoSlice> = chunks.iter().map(|chunk| IoSlice::new(chunk)).collect();
+/// ```
+#[derive(Debug, Default)]
+pub struct BytesChain {
+    chunks: Vec<Bytes>,
+    chunks_len: usize,
+    current: BytesMut,
+}
+
+impl BytesChain {
+    /// Create an empty chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The total length of the chunks in the chain.
+    pub fn len(&self) -> usize {
+        self.chunks_len + self.current.len()
+    }
+
+    /// Whether the chain is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Freeze the chain into its chunks.
+    pub fn into_chunks(mut self) -> Vec<Bytes> {
+        self.flush();
+        self.chunks
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            let chunk = self.current.split().freeze();
+            self.chunks_len += chunk.len();
+            self.chunks.push(chunk);
+        }
+    }
+
+    /// Copy the chunks from `offset` onwards back into the current chunk, so that they can be
+    /// modified. This only happens when filling a gap which spans shared bytes.
+    fn unshare_from(&mut self, offset: usize) {
+        if offset >= self.chunks_len {
+            return;
+        }
+        let mut start = self.chunks_len;
+        let mut first = self.chunks.len();
+        while start > offset {
+            first -= 1;
+            start -= self.chunks[first].len();
+        }
+        let mut merged = BytesMut::with_capacity(self.len() - start);
+        for chunk in self.chunks.drain(first..) {
+            merged.put_slice(&chunk);
+        }
+        merged.put_slice(&self.current);
+        self.chunks_len = start;
+        self.current = merged;
+    }
+}
+
+unsafe impl BufMut for BytesChain {
+    fn remaining_mut(&self) -> usize {
+        self.current.remaining_mut()
+    }
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        self.current.advance_mut(cnt)
+    }
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        self.current.chunk_mut()
+    }
+    fn put_slice(&mut self, src: &[u8]) {
+        self.current.put_slice(src)
+    }
+}
+
+impl ByteBufMut for BytesChain {
+    fn offset(&self) -> usize {
+        self.len()
+    }
+    fn seek(&mut self, offset: usize) {
+        self.unshare_from(offset);
+        self.current.resize(offset - self.chunks_len, 0);
+    }
+    fn range(&mut self, r: Range<usize>) -> &mut [u8] {
+        self.unshare_from(r.start);
+        let base = self.chunks_len;
+        &mut self.current[(r.start - base)..(r.end - base)]
+    }
+    fn put_shared_bytes(&mut self, bytes: &Bytes) {
+        if !bytes.is_empty() {
+            self.flush();
+            self.chunks_len += bytes.len();
+            self.chunks.push(bytes.clone());
+        }
+    }
 }
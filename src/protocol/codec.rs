@@ -0,0 +1,208 @@
+//! The traits and helpers generated messages encode and decode their fields with.
+//!
+//! They are public only so that messages generated from custom schemas outside this crate can use
+//! them, through [`crate::__private`], and are not meant to be used directly.
+
+use std::any::TypeId;
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::RangeBounds;
+
+use super::buf::{ByteBuf, ByteBufMut};
+use super::tagged_fields::UnknownTaggedFields;
+use super::{types, DecodeError, Encodable, EncodeError, EncodeErrorKind};
+
+/// A newtype around a primitive, such as the entity types of the generated messages.
+pub trait NewType<Inner>: From<Inner> + Into<Inner> + Borrow<Inner> {}
+
+impl<T> NewType<T> for T {}
+
+/// Encodes values of a field type, such as [`types::Int32`].
+pub trait Encoder<Value> {
+    /// Encodes `value` into `buf`.
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, value: Value) -> Result<(), EncodeError>;
+    /// The size of `value` once encoded.
+    fn compute_size(&self, value: Value) -> Result<usize, EncodeError>;
+    /// The size of every value once encoded, if it doesn't depend on the value.
+    fn fixed_size(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Decodes values of a field type, such as [`types::Int32`].
+pub trait Decoder<Value> {
+    /// Decodes a value from `buf`.
+    fn decode<B: ByteBuf>(&self, buf: &mut B) -> Result<Value, DecodeError>;
+}
+
+/// A struct encoded as an entry of a map, along with the field of the struct it is keyed by.
+pub trait MapEncodable: Sized {
+    /// The type of the field the struct is keyed by.
+    type Key;
+    /// Encodes the struct with its `key` into `buf` at `version`.
+    fn encode<B: ByteBufMut>(
+        &self,
+        key: &Self::Key,
+        buf: &mut B,
+        version: i16,
+    ) -> Result<(), EncodeError>;
+    /// The size of the struct with its `key` once encoded at `version`.
+    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError>;
+    /// Encodes the struct like [`MapEncodable::encode`], reusing the sizes cached in `cache`.
+    fn encode_cached<B: ByteBufMut>(
+        &self,
+        key: &Self::Key,
+        buf: &mut B,
+        version: i16,
+        _cache: &SizeCache,
+    ) -> Result<(), EncodeError> {
+        self.encode(key, buf, version)
+    }
+    /// Computes the size of the struct like [`MapEncodable::compute_size`], caching the sizes its
+    /// encoding needs in `cache`.
+    fn compute_size_cached(
+        &self,
+        key: &Self::Key,
+        version: i16,
+        _cache: &SizeCache,
+    ) -> Result<usize, EncodeError> {
+        self.compute_size(key, version)
+    }
+}
+
+/// A struct decoded as an entry of a map, along with the field of the struct it is keyed by.
+pub trait MapDecodable: Sized {
+    /// The type of the field the struct is keyed by.
+    type Key;
+    /// Decodes the struct and its key from `buf` at `version`.
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError>;
+}
+
+/// Writes the unknown tagged fields whose tags are within `range`.
+pub fn write_unknown_tagged_fields<B: ByteBufMut, R: RangeBounds<i32>>(
+    buf: &mut B,
+    range: R,
+    unknown_tagged_fields: &UnknownTaggedFields,
+) -> Result<(), EncodeError> {
+    for (k, v) in unknown_tagged_fields.range(range) {
+        if v.len() > u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        types::UnsignedVarInt.encode(buf, k as u32)?;
+        types::UnsignedVarInt.encode(buf, v.len() as u32)?;
+        buf.put_slice(v);
+    }
+    Ok(())
+}
+
+/// The encoded size of the unknown tagged fields.
+pub fn compute_unknown_tagged_fields_size(
+    unknown_tagged_fields: &UnknownTaggedFields,
+) -> Result<usize, EncodeError> {
+    let mut total_size = 0;
+    for (k, v) in unknown_tagged_fields.iter() {
+        if v.len() > u32::MAX as usize {
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        total_size += types::UnsignedVarInt.compute_size(k as u32)?;
+        total_size += types::UnsignedVarInt.compute_size(v.len() as u32)?;
+        total_size += v.len();
+    }
+    Ok(total_size)
+}
+
+/// The sizes of the tagged fields holding structs, which are written before the fields when
+/// encoding them. Computing the size of a message records them, so that encoding the message
+/// afterwards doesn't walk the structs once more for every tagged field they are nested in.
+///
+/// Fields are identified by their address and type, so the message must not change while the
+/// cache is in use.
+#[derive(Debug, Default)]
+pub struct SizeCache(RefCell<HashMap<(usize, TypeId), usize>>);
+
+impl SizeCache {
+    /// The size of `value`, computed by `compute` unless it was already cached.
+    pub fn size_of<T: 'static, F>(&self, value: &T, compute: F) -> Result<usize, EncodeError>
+    where
+        F: FnOnce() -> Result<usize, EncodeError>,
+    {
+        let key = (value as *const T as usize, TypeId::of::<T>());
+        let cached = self.0.borrow().get(&key).copied();
+        if let Some(size) = cached {
+            return Ok(size);
+        }
+        // `compute` caches the sizes of the fields nested in `value`, so the cache must not be
+        // borrowed while it runs
+        let size = compute()?;
+        self.0.borrow_mut().insert(key, size);
+        Ok(size)
+    }
+}
+
+/// Encodes structs like [`types::Struct`], caching the sizes of their tagged fields in `cache`.
+#[derive(Debug, Copy, Clone)]
+pub struct CachedStruct<'a> {
+    /// The version of the struct.
+    pub version: i16,
+    /// The sizes of the tagged fields of the struct.
+    pub cache: &'a SizeCache,
+}
+
+impl<T: Encodable> Encoder<&T> for CachedStruct<'_> {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, value: &T) -> Result<(), EncodeError> {
+        value.encode_cached(buf, self.version, self.cache)
+    }
+    fn compute_size(&self, value: &T) -> Result<usize, EncodeError> {
+        value.compute_size_cached(self.version, self.cache)
+    }
+}
+
+impl<T: Encodable> Encoder<&Option<T>> for CachedStruct<'_> {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, option: &Option<T>) -> Result<(), EncodeError> {
+        if let Some(value) = option {
+            types::Int8.encode(buf, 1)?;
+            self.encode(buf, value)
+        } else {
+            types::Int8.encode(buf, -1)
+        }
+    }
+    fn compute_size(&self, option: &Option<T>) -> Result<usize, EncodeError> {
+        match option {
+            Some(value) => Ok(self.compute_size(value)? + 1),
+            None => Ok(1),
+        }
+    }
+}
+
+impl<T: MapEncodable> Encoder<(&T::Key, &T)> for CachedStruct<'_> {
+    fn encode<B: ByteBufMut>(
+        &self,
+        buf: &mut B,
+        (key, value): (&T::Key, &T),
+    ) -> Result<(), EncodeError> {
+        value.encode_cached(key, buf, self.version, self.cache)
+    }
+    fn compute_size(&self, (key, value): (&T::Key, &T)) -> Result<usize, EncodeError> {
+        value.compute_size_cached(key, self.version, self.cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_cache_computes_each_value_once() {
+        let cache = SizeCache::default();
+        let values = [1, 2];
+        assert_eq!(cache.size_of(&values[0], || Ok(4)).unwrap(), 4);
+        assert_eq!(
+            cache.size_of(&values[0], || panic!("size cached")).unwrap(),
+            4
+        );
+        // Values at the same address are told apart by their type
+        assert_eq!(cache.size_of(&values, || Ok(8)).unwrap(), 8);
+        assert_eq!(cache.size_of(&values[1], || Ok(5)).unwrap(), 5);
+    }
+}
@@ -1,31 +1,256 @@
 //! Most types are used internally in encoding/decoding, and are not required by typical use cases
 //! for interacting with the protocol. However, types can be used for decoding partial messages,
 //! or rewriting parts of an encoded message.
-use std::borrow::Borrow;
 use std::cmp;
-use std::collections::BTreeMap;
-use std::ops::RangeBounds;
+use std::io::{self, Read, Write};
 use std::string::FromUtf8Error;
 use std::{error::Error, str::Utf8Error};
 
-use buf::{ByteBuf, ByteBufMut};
+use buf::{ByteBuf, ByteBufMut, BytesChain, DecodeLimits, LimitedBuf};
+use bytes::{Bytes, BytesMut};
 
 use self::buf::NotEnoughBytesError;
 
 pub mod buf;
+pub(crate) mod codec;
+#[cfg(feature = "serde")]
+pub(crate) mod serde_str_bytes;
+pub mod tagged_fields;
 pub mod types;
+pub mod view;
+
+pub(crate) use codec::{
+    compute_unknown_tagged_fields_size, write_unknown_tagged_fields, CachedStruct, Decoder,
+    Encoder, MapDecodable, MapEncodable, NewType, SizeCache,
+};
 
 /// A string type backed by [`bytes::Bytes`].
 pub type StrBytes = string::String<bytes::Bytes>;
 
+/// The kind of fault encountered while decoding a message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DecodeErrorKind {
+    /// The buffer ended before the value was fully decoded.
+    NotEnoughBytes,
+    /// A string was not valid UTF-8.
+    InvalidUtf8,
+    /// A length prefix was negative, or null where a value was required.
+    NegativeLength,
+    /// The message does not support the requested version.
+    UnsupportedVersion,
+    /// A tagged field was present in a version which does not support it.
+    FieldNotValidAtVersion,
+    /// A record batch failed its cyclic redundancy check.
+    CrcMismatch,
+    /// Compressed data could not be decompressed.
+    Compression,
+    /// A value was decoded which is not valid in its position.
+    InvalidValue,
+    /// A length or the nesting of the message exceeded the limits it was decoded with.
+    LimitExceeded,
+    /// A request was for an API which is unknown, or left out by the enabled features.
+    UnsupportedApiKey,
+}
+
+impl std::fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DecodeErrorKind::NotEnoughBytes => "not enough bytes",
+            DecodeErrorKind::InvalidUtf8 => "invalid UTF-8",
+            DecodeErrorKind::NegativeLength => "negative length",
+            DecodeErrorKind::UnsupportedVersion => "unsupported version",
+            DecodeErrorKind::FieldNotValidAtVersion => "field not valid at version",
+            DecodeErrorKind::CrcMismatch => "CRC mismatch",
+            DecodeErrorKind::Compression => "decompression failed",
+            DecodeErrorKind::InvalidValue => "invalid value",
+            DecodeErrorKind::LimitExceeded => "limit exceeded",
+            DecodeErrorKind::UnsupportedApiKey => "unsupported API key",
+        })
+    }
+}
+
+/// The kind of fault encountered while encoding a message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EncodeErrorKind {
+    /// A value was too large to be represented in the protocol.
+    ValueTooLarge,
+    /// A field was set to a non-default value in a version which does not support it.
+    FieldNotValidAtVersion,
+    /// The message does not support the requested version.
+    UnsupportedVersion,
+    /// Data could not be compressed.
+    Compression,
+    /// A value cannot be encoded with the requested options.
+    InvalidValue,
+    /// A nullable field was `None` in a version in which it is not nullable.
+    NullNotValidAtVersion,
+}
+
+impl std::fmt::Display for EncodeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            EncodeErrorKind::ValueTooLarge => "value too large",
+            EncodeErrorKind::FieldNotValidAtVersion => "field not valid at version",
+            EncodeErrorKind::UnsupportedVersion => "unsupported version",
+            EncodeErrorKind::Compression => "compression failed",
+            EncodeErrorKind::InvalidValue => "invalid value",
+            EncodeErrorKind::NullNotValidAtVersion => "null not valid at version",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Field(&'static str),
+    Index(usize),
+}
+
+/// An error kind, along with where in a message the error occurred. Filled in as the error
+/// propagates outwards, so the outermost message wins and path segments are stored innermost first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ErrorContext<K> {
+    kind: K,
+    message_type: Option<&'static str>,
+    version: Option<i16>,
+    path: Vec<PathSegment>,
+    offset: Option<usize>,
+}
+
+impl<K> ErrorContext<K> {
+    fn new(kind: K) -> Self {
+        Self {
+            kind,
+            message_type: None,
+            version: None,
+            path: Vec::new(),
+            offset: None,
+        }
+    }
+
+    fn push_field(&mut self, message_type: &'static str, field: &'static str, version: i16) {
+        self.message_type = Some(message_type);
+        self.version = Some(version);
+        self.path.push(PathSegment::Field(field));
+    }
+
+    fn field_path(&self) -> std::string::String {
+        let mut result = std::string::String::new();
+        if let Some(message_type) = self.message_type {
+            result.push_str(message_type);
+        }
+        for segment in self.path.iter().rev() {
+            match segment {
+                PathSegment::Field(field) => {
+                    if !result.is_empty() {
+                        result.push('.');
+                    }
+                    result.push_str(field);
+                }
+                PathSegment::Index(index) => {
+                    result.push_str(&format!("[{}]", index));
+                }
+            }
+        }
+        result
+    }
+
+    fn fmt_location(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.message_type.is_some() || !self.path.is_empty() {
+            write!(f, " in {}", self.field_path())?;
+        }
+        if let Some(version) = self.version {
+            write!(f, " (v{})", version)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " at offset {}", offset)?;
+        }
+        Ok(())
+    }
+}
+
 /// An error representing any fault while decoding a message, usually when the buffer is incorrectly
 /// sized or otherwise invalid for the decoded message.
-#[derive(Debug)]
-pub struct DecodeError;
+///
+/// Besides its [`DecodeErrorKind`], the error records the message type, field path and buffer
+/// offset at which decoding failed, where known. The offset is measured from the start of the
+/// outermost message being decoded, not from the start of the struct containing the field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError(Box<ErrorContext<DecodeErrorKind>>);
+
+impl DecodeError {
+    /// Create a new error of the provided kind, without any context.
+    pub fn new(kind: DecodeErrorKind) -> Self {
+        DecodeError(Box::new(ErrorContext::new(kind)))
+    }
+
+    /// The kind of fault which caused this error.
+    pub fn kind(&self) -> DecodeErrorKind {
+        self.0.kind
+    }
+
+    /// The outermost message type being decoded when this error occurred.
+    pub fn message_type(&self) -> Option<&'static str> {
+        self.0.message_type
+    }
+
+    /// The version of the message being decoded when this error occurred.
+    pub fn version(&self) -> Option<i16> {
+        self.0.version
+    }
+
+    /// The path to the field at fault, e.g. `FetchResponse.responses[2].partitions[0].records`.
+    pub fn field_path(&self) -> std::string::String {
+        self.0.field_path()
+    }
+
+    /// The offset in bytes from the start of the outermost message to where decoding failed.
+    pub fn offset(&self) -> Option<usize> {
+        self.0.offset
+    }
+
+    /// Record that this error occurred while decoding `field` of `message_type`, `offset` bytes
+    /// after the start of that message.
+    ///
+    /// Each enclosing message records the error again as it propagates outwards, replacing the
+    /// offset, so that it ends up measured from the start of the outermost message.
+    pub fn with_field(
+        mut self,
+        message_type: &'static str,
+        field: &'static str,
+        version: i16,
+        offset: usize,
+    ) -> Self {
+        self.0.push_field(message_type, field, version);
+        self.0.offset = Some(offset);
+        self
+    }
+
+    /// Record that this error occurred while decoding version `version` of `message_type`.
+    pub fn with_message(mut self, message_type: &'static str, version: i16) -> Self {
+        self.0.message_type = Some(message_type);
+        self.0.version = Some(version);
+        self
+    }
+
+    /// Record that this error occurred while decoding the array element at `index`.
+    pub fn with_index(mut self, index: usize) -> Self {
+        self.0.path.push(PathSegment::Index(index));
+        self
+    }
+}
+
+impl From<DecodeErrorKind> for DecodeError {
+    fn from(kind: DecodeErrorKind) -> Self {
+        DecodeError::new(kind)
+    }
+}
 
 impl std::fmt::Display for DecodeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "DecodeError")
+        write!(f, "DecodeError: {}", self.0.kind)?;
+        self.0.fmt_location(f)
     }
 }
 
@@ -33,53 +258,98 @@ impl Error for DecodeError {}
 
 impl From<NotEnoughBytesError> for DecodeError {
     fn from(_: NotEnoughBytesError) -> Self {
-        DecodeError
+        DecodeError::new(DecodeErrorKind::NotEnoughBytes)
     }
 }
 
 impl From<Utf8Error> for DecodeError {
     fn from(_: Utf8Error) -> Self {
-        DecodeError
+        DecodeError::new(DecodeErrorKind::InvalidUtf8)
     }
 }
 
 impl From<FromUtf8Error> for DecodeError {
     fn from(_: FromUtf8Error) -> Self {
-        DecodeError
+        DecodeError::new(DecodeErrorKind::InvalidUtf8)
     }
 }
 
 /// An error representing any fault while encoding a message, usually when a message in an invalid
 /// state is attempted to be encoded.
-#[derive(Debug)]
-pub struct EncodeError;
+///
+/// Besides its [`EncodeErrorKind`], the error records the message type and field path at which
+/// encoding failed, where known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeError(Box<ErrorContext<EncodeErrorKind>>);
 
-impl std::fmt::Display for EncodeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "EncodeError")
+impl EncodeError {
+    /// Create a new error of the provided kind, without any context.
+    pub fn new(kind: EncodeErrorKind) -> Self {
+        EncodeError(Box::new(ErrorContext::new(kind)))
     }
-}
 
-impl Error for EncodeError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        None
+    /// The kind of fault which caused this error.
+    pub fn kind(&self) -> EncodeErrorKind {
+        self.0.kind
     }
-}
 
-pub(crate) trait NewType<Inner>: From<Inner> + Into<Inner> + Borrow<Inner> {}
+    /// The outermost message type being encoded when this error occurred.
+    pub fn message_type(&self) -> Option<&'static str> {
+        self.0.message_type
+    }
 
-impl<T> NewType<T> for T {}
+    /// The version of the message being encoded when this error occurred.
+    pub fn version(&self) -> Option<i16> {
+        self.0.version
+    }
 
-pub(crate) trait Encoder<Value> {
-    fn encode<B: ByteBufMut>(&self, buf: &mut B, value: Value) -> Result<(), EncodeError>;
-    fn compute_size(&self, value: Value) -> Result<usize, EncodeError>;
-    fn fixed_size(&self) -> Option<usize> {
-        None
+    /// The path to the field at fault, e.g. `ProduceRequest.topic_data[0].name`.
+    pub fn field_path(&self) -> std::string::String {
+        self.0.field_path()
+    }
+
+    /// Record that this error occurred while encoding `field` of `message_type`.
+    pub fn with_field(
+        mut self,
+        message_type: &'static str,
+        field: &'static str,
+        version: i16,
+    ) -> Self {
+        self.0.push_field(message_type, field, version);
+        self
+    }
+
+    /// Record that this error occurred while encoding version `version` of `message_type`.
+    pub fn with_message(mut self, message_type: &'static str, version: i16) -> Self {
+        self.0.message_type = Some(message_type);
+        self.0.version = Some(version);
+        self
+    }
+
+    /// Record that this error occurred while encoding the array element at `index`.
+    pub fn with_index(mut self, index: usize) -> Self {
+        self.0.path.push(PathSegment::Index(index));
+        self
+    }
+}
+
+impl From<EncodeErrorKind> for EncodeError {
+    fn from(kind: EncodeErrorKind) -> Self {
+        EncodeError::new(kind)
+    }
+}
+
+impl std::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncodeError: {}", self.0.kind)?;
+        self.0.fmt_location(f)
     }
 }
 
-pub(crate) trait Decoder<Value> {
-    fn decode<B: ByteBuf>(&self, buf: &mut B) -> Result<Value, DecodeError>;
+impl Error for EncodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
 }
 
 /// The range of versions (min, max) allowed for agiven message.
@@ -104,6 +374,22 @@ impl VersionRange {
             max: cmp::min(self.max, other.max),
         }
     }
+
+    /// Checks whether the provided version is within the range.
+    pub fn contains(&self, version: i16) -> bool {
+        version >= self.min && version <= self.max
+    }
+}
+
+/// The kinds of listener a Kafka node can expose an API on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ListenerType {
+    /// A broker in a ZooKeeper-based cluster.
+    ZkBroker,
+    /// A broker in a KRaft-based cluster.
+    Broker,
+    /// A KRaft controller.
+    Controller,
 }
 
 /// An API request or response.
@@ -112,6 +398,13 @@ impl VersionRange {
 pub trait Message: Sized {
     /// The valid versions for this message.
     const VERSIONS: VersionRange;
+    /// The versions of this message that are deprecated, if any. Deprecated versions are still
+    /// valid but may be removed in a future release of Kafka.
+    const DEPRECATED_VERSIONS: Option<VersionRange> = None;
+    /// The versions of this message that are unstable, i.e. still under development upstream and
+    /// subject to incompatible changes, if any. They are excluded from [`Message::VERSIONS`] unless
+    /// the `unstable-apis` feature is enabled.
+    const UNSTABLE_VERSIONS: Option<VersionRange> = None;
 }
 
 /// An encodable message.
@@ -120,28 +413,77 @@ pub trait Encodable: Sized {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError>;
     /// Compute the total size of the message when encoded.
     fn compute_size(&self, version: i16) -> Result<usize, EncodeError>;
+    /// Encode the message like [`Encodable::encode`], reusing the sizes cached in `cache`.
+    #[doc(hidden)]
+    fn encode_cached<B: ByteBufMut>(
+        &self,
+        buf: &mut B,
+        version: i16,
+        _cache: &SizeCache,
+    ) -> Result<(), EncodeError> {
+        self.encode(buf, version)
+    }
+    /// Compute the size of the message like [`Encodable::compute_size`], caching the sizes its
+    /// encoding needs in `cache`.
+    #[doc(hidden)]
+    fn compute_size_cached(&self, version: i16, _cache: &SizeCache) -> Result<usize, EncodeError> {
+        self.compute_size(version)
+    }
+    /// Encode the message into a new buffer, allocated with the exact size of the message so that
+    /// encoding never reallocates or copies.
+    ///
+    /// The sizes of nested structs computed along the way are kept for encoding, so the message is
+    /// only walked once to compute its size and once to encode it.
+    fn encode_to_bytes(&self, version: i16) -> Result<Bytes, EncodeError> {
+        let cache = SizeCache::default();
+        let size = self.compute_size_cached(version, &cache)?;
+        let mut buf = BytesMut::with_capacity(size);
+        self.encode_cached(&mut buf, version, &cache)?;
+        debug_assert_eq!(buf.len(), size, "encoded size differs from computed size");
+        Ok(buf.freeze())
+    }
+    /// Encode the message into a chain of chunks for vectored writes, sharing the records it
+    /// contains instead of copying them.
+    fn encode_to_chunks(&self, version: i16) -> Result<Vec<Bytes>, EncodeError> {
+        let mut buf = BytesChain::new();
+        self.encode(&mut buf, version)?;
+        Ok(buf.into_chunks())
+    }
+    /// Encode the message to a writer, such as a file or `TcpStream`.
+    ///
+    /// Encoding errors are returned as [`io::ErrorKind::InvalidInput`] errors wrapping the
+    /// [`EncodeError`].
+    fn write_to<W: Write>(&self, writer: &mut W, version: i16) -> io::Result<()> {
+        let bytes = self
+            .encode_to_bytes(version)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        writer.write_all(&bytes)
+    }
 }
 
 /// A decodable message.
 pub trait Decodable: Sized {
     /// Decode the message from the provided buffer and version.
     fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError>;
-}
-
-pub(crate) trait MapEncodable: Sized {
-    type Key;
-    fn encode<B: ByteBufMut>(
-        &self,
-        key: &Self::Key,
+    /// Decode the message from untrusted input, failing if it exceeds the provided limits.
+    fn decode_with_limits<B: ByteBuf>(
         buf: &mut B,
         version: i16,
-    ) -> Result<(), EncodeError>;
-    fn compute_size(&self, key: &Self::Key, version: i16) -> Result<usize, EncodeError>;
-}
-
-pub(crate) trait MapDecodable: Sized {
-    type Key;
-    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(Self::Key, Self), DecodeError>;
+        limits: DecodeLimits,
+    ) -> Result<Self, DecodeError> {
+        Self::decode(&mut LimitedBuf::new(buf, limits), version)
+    }
+    /// Read the `len` bytes of an encoded message from a reader, such as a file or `TcpStream`,
+    /// and decode it.
+    ///
+    /// Decoding errors are returned as [`io::ErrorKind::InvalidData`] errors wrapping the
+    /// [`DecodeError`].
+    fn read_from<R: Read>(reader: &mut R, len: usize, version: i16) -> io::Result<Self> {
+        let mut buf = BytesMut::zeroed(len);
+        reader.read_exact(&mut buf)?;
+        Self::decode(&mut buf.freeze(), version)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
 }
 
 /// Every message has a set of versions valid for a given header version.
@@ -162,39 +504,6 @@ pub trait Request: Message + Encodable + Decodable + HeaderVersion {
     type Response: Message + Encodable + Decodable + HeaderVersion;
 }
 
-pub(crate) fn write_unknown_tagged_fields<B: ByteBufMut, R: RangeBounds<i32>>(
-    buf: &mut B,
-    range: R,
-    unknown_tagged_fields: &BTreeMap<i32, Vec<u8>>,
-) -> Result<(), EncodeError> {
-    for (&k, v) in unknown_tagged_fields.range(range) {
-        if v.len() > std::u32::MAX as usize {
-            error!("Tagged field is too long to encode ({} bytes)", v.len());
-            return Err(EncodeError);
-        }
-        types::UnsignedVarInt.encode(buf, k as u32)?;
-        types::UnsignedVarInt.encode(buf, v.len() as u32)?;
-        buf.put_slice(v.as_slice());
-    }
-    Ok(())
-}
-
-pub(crate) fn compute_unknown_tagged_fields_size(
-    unknown_tagged_fields: &BTreeMap<i32, Vec<u8>>,
-) -> Result<usize, EncodeError> {
-    let mut total_size = 0;
-    for (&k, v) in unknown_tagged_fields {
-        if v.len() > std::u32::MAX as usize {
-            error!("Tagged field is too long to encode ({} bytes)", v.len());
-            return Err(EncodeError);
-        }
-        total_size += types::UnsignedVarInt.compute_size(k as u32)?;
-        total_size += types::UnsignedVarInt.compute_size(v.len() as u32)?;
-        total_size += v.len();
-    }
-    Ok(total_size)
-}
-
 /// Every protocol item implements [`derive_builder::Builder`], which can be
 /// created using [`Default::default`] or retrieved via this trait.
 pub trait Builder {
@@ -0,0 +1,111 @@
+//! Serde support for [`StrBytes`](super::StrBytes), which lives in a foreign crate and so cannot
+//! implement `Serialize` and `Deserialize` directly.
+//!
+//! Used via `#[serde(with = "crate::protocol::serde_str_bytes")]` on fields containing `StrBytes`,
+//! either directly or inside an `Option`, a `Vec` or as the key of an `IndexMap`, and via
+//! `crate::__private::serde_str_bytes` by messages generated from custom schemas.
+
+use bytes::Bytes;
+use indexmap::IndexMap;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use string::TryFrom;
+
+use super::StrBytes;
+
+struct Borrowed<'a>(&'a StrBytes);
+
+impl Serialize for Borrowed<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct Owned(StrBytes);
+
+impl<'de> Deserialize<'de> for Owned {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = std::string::String::deserialize(deserializer)?;
+        StrBytes::try_from(Bytes::from(s))
+            .map(Owned)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// A type containing `StrBytes` which can be (de)serialized by this module.
+pub trait SerdeStrBytes: Sized {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl SerdeStrBytes for StrBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Borrowed(self).serialize(serializer)
+    }
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Owned::deserialize(deserializer).map(|s| s.0)
+    }
+}
+
+impl<T: SerdeStrBytes> SerdeStrBytes for Option<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => serializer.serialize_some(&Wrap(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<Unwrap<T>>::deserialize(deserializer).map(|value| value.map(|value| value.0))
+    }
+}
+
+impl SerdeStrBytes for Vec<StrBytes> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Borrowed))
+    }
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Owned>::deserialize(deserializer).map(|v| v.into_iter().map(|s| s.0).collect())
+    }
+}
+
+impl<V: Serialize + for<'de> Deserialize<'de>> SerdeStrBytes for IndexMap<StrBytes, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter().map(|(k, v)| (Borrowed(k), v)))
+    }
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        IndexMap::<Owned, V>::deserialize(deserializer)
+            .map(|m| m.into_iter().map(|(k, v)| (k.0, v)).collect())
+    }
+}
+
+struct Wrap<'a, T>(&'a T);
+
+impl<T: SerdeStrBytes> Serialize for Wrap<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+struct Unwrap<T>(T);
+
+impl<'de, T: SerdeStrBytes> Deserialize<'de> for Unwrap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Unwrap)
+    }
+}
+
+/// Serializes `value`, with its `StrBytes` as strings.
+pub fn serialize<T: SerdeStrBytes, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.serialize(serializer)
+}
+
+/// Deserializes a value, with its `StrBytes` from strings.
+pub fn deserialize<'de, T: SerdeStrBytes, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    T::deserialize(deserializer)
+}
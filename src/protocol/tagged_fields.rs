@@ -0,0 +1,189 @@
+//! The tagged fields of a message which are not known to this version of the protocol.
+//!
+//! Tagged fields are kept as slices of the buffer they were decoded from, and can be decoded as
+//! a given type once their meaning is known:
+//!
+//! ```rust
+//! use kafka_protocol::protocol::tagged_fields::UnknownTaggedFields;
+//!
+//! let mut fields = UnknownTaggedFields::new();
+//! fields.insert_as(7, &42i32, 0).unwrap();
+//! assert_eq!(fields.get_as::<i32>(7, 0).unwrap(), Some(42));
+//! ```
+
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+
+use bytes::{Bytes, BytesMut};
+use uuid::Uuid;
+
+use super::{types, Decodable, DecodeError, Decoder, Encodable, EncodeError, Encoder, StrBytes};
+
+/// The unknown tagged fields of a struct, ordered by tag.
+///
+/// Values are kept encoded, as slices of the buffer they were decoded from. Empty tagged fields
+/// do not allocate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnknownTaggedFields {
+    fields: Vec<(i32, Bytes)>,
+}
+
+impl UnknownTaggedFields {
+    /// Creates an empty set of tagged fields.
+    pub const fn new() -> Self {
+        Self { fields: Vec::new() }
+    }
+
+    /// The number of tagged fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Whether there are no tagged fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    fn position(&self, tag: i32) -> Result<usize, usize> {
+        self.fields.binary_search_by_key(&tag, |(k, _)| *k)
+    }
+
+    /// The encoded value of the tagged field `tag`.
+    pub fn get(&self, tag: i32) -> Option<&Bytes> {
+        self.position(tag).ok().map(|i| &self.fields[i].1)
+    }
+
+    /// Sets the encoded value of the tagged field `tag`, returning its previous value.
+    pub fn insert(&mut self, tag: i32, value: Bytes) -> Option<Bytes> {
+        match self.position(tag) {
+            Ok(i) => Some(std::mem::replace(&mut self.fields[i].1, value)),
+            Err(i) => {
+                self.fields.insert(i, (tag, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the tagged field `tag`, returning its encoded value.
+    pub fn remove(&mut self, tag: i32) -> Option<Bytes> {
+        self.position(tag).ok().map(|i| self.fields.remove(i).1)
+    }
+
+    /// Decodes the tagged field `tag` as a `T`, at the version of the struct it belongs to.
+    pub fn get_as<T: TaggedFieldValue>(
+        &self,
+        tag: i32,
+        version: i16,
+    ) -> Result<Option<T>, DecodeError> {
+        self.get(tag)
+            .map(|value| T::decode_tagged(&mut value.clone(), version))
+            .transpose()
+    }
+
+    /// Encodes `value` as the tagged field `tag`, at the version of the struct it belongs to,
+    /// returning the previous encoded value.
+    pub fn insert_as<T: TaggedFieldValue>(
+        &mut self,
+        tag: i32,
+        value: &T,
+        version: i16,
+    ) -> Result<Option<Bytes>, EncodeError> {
+        let mut buf = BytesMut::new();
+        value.encode_tagged(&mut buf, version)?;
+        Ok(self.insert(tag, buf.freeze()))
+    }
+
+    /// Iterates over the tags and encoded values of the tagged fields, in tag order.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &Bytes)> {
+        self.fields.iter().map(|(tag, value)| (*tag, value))
+    }
+
+    /// Iterates over the tagged fields whose tag is within `range`, in tag order.
+    pub fn range<R: RangeBounds<i32>>(&self, range: R) -> impl Iterator<Item = (i32, &Bytes)> {
+        let start = match range.start_bound() {
+            Bound::Included(&tag) => self.position(tag).unwrap_or_else(|i| i),
+            Bound::Excluded(&tag) => self.position(tag).map_or_else(|i| i, |i| i + 1),
+            Bound::Unbounded => 0,
+        };
+        self.fields[start..]
+            .iter()
+            .take_while(move |(tag, _)| range.contains(tag))
+            .map(|(tag, value)| (*tag, value))
+    }
+}
+
+impl FromIterator<(i32, Bytes)> for UnknownTaggedFields {
+    fn from_iter<I: IntoIterator<Item = (i32, Bytes)>>(iter: I) -> Self {
+        let mut fields = Self::new();
+        for (tag, value) in iter {
+            fields.insert(tag, value);
+        }
+        fields
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for UnknownTaggedFields {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for UnknownTaggedFields {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields: std::collections::BTreeMap<i32, Bytes> =
+            serde::Deserialize::deserialize(deserializer)?;
+        Ok(fields.into_iter().collect())
+    }
+}
+
+/// A type which the value of a tagged field can be decoded as.
+///
+/// Tagged fields only exist in flexible versions, so strings and bytes use their compact
+/// encodings.
+pub trait TaggedFieldValue: Sized {
+    /// Decodes the value of a tagged field of a struct at `version`.
+    fn decode_tagged(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError>;
+    /// Encodes the value of a tagged field of a struct at `version`.
+    fn encode_tagged(&self, buf: &mut BytesMut, version: i16) -> Result<(), EncodeError>;
+}
+
+macro_rules! define_tagged_field_value {
+    ($($t:ty => $e:expr,)*) => {
+        $(
+            impl TaggedFieldValue for $t {
+                fn decode_tagged(buf: &mut Bytes, _version: i16) -> Result<Self, DecodeError> {
+                    $e.decode(buf)
+                }
+                fn encode_tagged(&self, buf: &mut BytesMut, _version: i16) -> Result<(), EncodeError> {
+                    $e.encode(buf, self)
+                }
+            }
+        )*
+    };
+}
+
+define_tagged_field_value! {
+    bool => types::Boolean,
+    i8 => types::Int8,
+    i16 => types::Int16,
+    u16 => types::UInt16,
+    i32 => types::Int32,
+    i64 => types::Int64,
+    f64 => types::Float64,
+    Uuid => types::Uuid,
+    StrBytes => types::CompactString,
+    Option<StrBytes> => types::CompactString,
+    Bytes => types::CompactBytes,
+    Option<Bytes> => types::CompactBytes,
+}
+
+impl<T: Encodable + Decodable> TaggedFieldValue for T {
+    fn decode_tagged(buf: &mut Bytes, version: i16) -> Result<Self, DecodeError> {
+        T::decode(buf, version)
+    }
+    fn encode_tagged(&self, buf: &mut BytesMut, version: i16) -> Result<(), EncodeError> {
+        self.encode(buf, version)
+    }
+}
@@ -11,8 +11,8 @@ use indexmap::IndexMap;
 use string::TryFrom;
 
 use super::{
-    Decodable, DecodeError, Decoder, Encodable, EncodeError, Encoder, MapDecodable, MapEncodable,
-    NewType, StrBytes,
+    Decodable, DecodeError, DecodeErrorKind, Decoder, Encodable, EncodeError, EncodeErrorKind,
+    Encoder, MapDecodable, MapEncodable, NewType, StrBytes,
 };
 use crate::protocol::buf::{ByteBuf, ByteBufMut};
 
@@ -272,9 +272,9 @@ pub struct String;
 impl Encoder<Option<&str>> for String {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, value: Option<&str>) -> Result<(), EncodeError> {
         if let Some(s) = value {
-            if s.len() > std::i16::MAX as usize {
+            if s.len() > i16::MAX as usize {
                 error!("String is too long to encode ({} bytes)", s.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 Int16.encode(buf, s.len() as i16)?;
                 buf.put_slice(s.as_bytes());
@@ -287,9 +287,9 @@ impl Encoder<Option<&str>> for String {
     }
     fn compute_size(&self, value: Option<&str>) -> Result<usize, EncodeError> {
         if let Some(s) = value {
-            if s.len() > std::i16::MAX as usize {
+            if s.len() > i16::MAX as usize {
                 error!("String is too long to encode ({} bytes)", s.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 Ok(2 + s.len())
             }
@@ -375,13 +375,14 @@ impl Decoder<Option<StdString>> for String {
         match Int16.decode(buf)? {
             -1 => Ok(None),
             n if n >= 0 => {
+                buf.check_bytes_len(n as usize)?;
                 let mut strbuf = vec![0; n as usize];
                 buf.try_copy_to_slice(&mut strbuf)?;
                 Ok(Some(std::string::String::from_utf8(strbuf)?))
             }
             n => {
                 error!("String length is negative ({})", n);
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
         }
     }
@@ -392,12 +393,13 @@ impl<T: NewType<StrBytes>> Decoder<Option<T>> for String {
         match Int16.decode(buf)? {
             -1 => Ok(None),
             n if n >= 0 => {
+                buf.check_slice_len(n as usize)?;
                 let strbuf = StrBytes::try_from(buf.try_get_bytes(n as usize)?)?;
                 Ok(Some(strbuf.into()))
             }
             n => {
                 error!("String length is negative ({})", n);
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
         }
     }
@@ -408,7 +410,7 @@ impl Decoder<StdString> for String {
         match String.decode(buf) {
             Ok(None) => {
                 error!("String length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -421,7 +423,7 @@ impl<T: NewType<StrBytes>> Decoder<T> for String {
         match String.decode(buf) {
             Ok(None) => {
                 error!("String length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -437,9 +439,9 @@ impl Encoder<Option<&str>> for CompactString {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, value: Option<&str>) -> Result<(), EncodeError> {
         if let Some(s) = value {
             // Use >= because we're going to add one to the length
-            if s.len() >= std::u32::MAX as usize {
+            if s.len() >= u32::MAX as usize {
                 error!("CompactString is too long to encode ({} bytes)", s.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 UnsignedVarInt.encode(buf, (s.len() as u32) + 1)?;
                 buf.put_slice(s.as_bytes());
@@ -453,9 +455,9 @@ impl Encoder<Option<&str>> for CompactString {
     fn compute_size(&self, value: Option<&str>) -> Result<usize, EncodeError> {
         if let Some(s) = value {
             // Use >= because we're going to add one to the length
-            if s.len() >= std::u32::MAX as usize {
+            if s.len() >= u32::MAX as usize {
                 error!("CompactString is too long to encode ({} bytes)", s.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 Ok(UnsignedVarInt.compute_size((s.len() as u32) + 1)? + s.len())
             }
@@ -541,6 +543,7 @@ impl Decoder<Option<StdString>> for CompactString {
         match UnsignedVarInt.decode(buf)? {
             0 => Ok(None),
             n => {
+                buf.check_bytes_len((n - 1) as usize)?;
                 let mut strbuf = vec![0; (n - 1) as usize];
                 buf.try_copy_to_slice(&mut strbuf)?;
                 Ok(Some(std::string::String::from_utf8(strbuf)?))
@@ -554,6 +557,7 @@ impl<T: NewType<StrBytes>> Decoder<Option<T>> for CompactString {
         match UnsignedVarInt.decode(buf)? {
             0 => Ok(None),
             n => {
+                buf.check_slice_len((n - 1) as usize)?;
                 let strbuf = StrBytes::try_from(buf.try_get_bytes((n - 1) as usize)?)?;
                 Ok(Some(strbuf.into()))
             }
@@ -566,7 +570,7 @@ impl Decoder<StdString> for CompactString {
         match CompactString.decode(buf) {
             Ok(None) => {
                 error!("CompactString length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -579,7 +583,7 @@ impl<T: NewType<StrBytes>> Decoder<T> for CompactString {
         match CompactString.decode(buf) {
             Ok(None) => {
                 error!("CompactString length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -596,7 +600,7 @@ impl Encoder<Option<&[u8]>> for Bytes {
         if let Some(s) = value {
             if s.len() > i32::MAX as usize {
                 error!("Data is too long to encode ({} bytes)", s.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 Int32.encode(buf, s.len() as i32)?;
                 buf.put_slice(s);
@@ -611,7 +615,7 @@ impl Encoder<Option<&[u8]>> for Bytes {
         if let Some(s) = value {
             if s.len() > i32::MAX as usize {
                 error!("Data is too long to encode ({} bytes)", s.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 Ok(4 + s.len())
             }
@@ -653,7 +657,16 @@ impl Encoder<Option<&bytes::Bytes>> for Bytes {
         buf: &mut B,
         value: Option<&bytes::Bytes>,
     ) -> Result<(), EncodeError> {
-        Bytes.encode(buf, value.map(|s| &**s))
+        if let Some(s) = value {
+            // Fails if the data is too long to encode
+            Bytes.compute_size(value)?;
+            Int32.encode(buf, s.len() as i32)?;
+            buf.put_shared_bytes(s);
+            Ok(())
+        } else {
+            Int32.encode(buf, -1)?;
+            Ok(())
+        }
     }
     fn compute_size(&self, value: Option<&bytes::Bytes>) -> Result<usize, EncodeError> {
         Bytes.compute_size(value.map(|s| &**s))
@@ -705,13 +718,14 @@ impl Decoder<Option<Vec<u8>>> for Bytes {
         match Int32.decode(buf)? {
             -1 => Ok(None),
             n if n >= 0 => {
+                buf.check_bytes_len(n as usize)?;
                 let mut data = vec![0; n as usize];
                 buf.try_copy_to_slice(&mut data)?;
                 Ok(Some(data))
             }
             n => {
                 error!("Data length is negative ({})", n);
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
         }
     }
@@ -721,10 +735,13 @@ impl Decoder<Option<bytes::Bytes>> for Bytes {
     fn decode<B: ByteBuf>(&self, buf: &mut B) -> Result<Option<bytes::Bytes>, DecodeError> {
         match Int32.decode(buf)? {
             -1 => Ok(None),
-            n if n >= 0 => Ok(Some(buf.try_get_bytes(n as usize)?)),
+            n if n >= 0 => {
+                buf.check_slice_len(n as usize)?;
+                Ok(Some(buf.try_get_bytes(n as usize)?))
+            }
             n => {
                 error!("Data length is negative ({})", n);
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
         }
     }
@@ -735,7 +752,7 @@ impl Decoder<Vec<u8>> for Bytes {
         match Bytes.decode(buf) {
             Ok(None) => {
                 error!("Data length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -748,7 +765,7 @@ impl Decoder<bytes::Bytes> for Bytes {
         match Bytes.decode(buf) {
             Ok(None) => {
                 error!("Data length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -764,9 +781,9 @@ impl Encoder<Option<&[u8]>> for CompactBytes {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, value: Option<&[u8]>) -> Result<(), EncodeError> {
         if let Some(s) = value {
             // Use >= because we're going to add one to the length
-            if s.len() >= std::u32::MAX as usize {
+            if s.len() >= u32::MAX as usize {
                 error!("CompactBytes is too long to encode ({} bytes)", s.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 UnsignedVarInt.encode(buf, (s.len() as u32) + 1)?;
                 buf.put_slice(s);
@@ -780,9 +797,9 @@ impl Encoder<Option<&[u8]>> for CompactBytes {
     fn compute_size(&self, value: Option<&[u8]>) -> Result<usize, EncodeError> {
         if let Some(s) = value {
             // Use >= because we're going to add one to the length
-            if s.len() >= std::u32::MAX as usize {
+            if s.len() >= u32::MAX as usize {
                 error!("CompactBytes is too long to encode ({} bytes)", s.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 Ok(UnsignedVarInt.compute_size((s.len() as u32) + 1)? + s.len())
             }
@@ -824,7 +841,16 @@ impl Encoder<Option<&bytes::Bytes>> for CompactBytes {
         buf: &mut B,
         value: Option<&bytes::Bytes>,
     ) -> Result<(), EncodeError> {
-        CompactBytes.encode(buf, value.map(|s| &**s))
+        if let Some(s) = value {
+            // Fails if the data is too long to encode
+            CompactBytes.compute_size(value)?;
+            UnsignedVarInt.encode(buf, (s.len() as u32) + 1)?;
+            buf.put_shared_bytes(s);
+            Ok(())
+        } else {
+            UnsignedVarInt.encode(buf, 0)?;
+            Ok(())
+        }
     }
     fn compute_size(&self, value: Option<&bytes::Bytes>) -> Result<usize, EncodeError> {
         CompactBytes.compute_size(value.map(|s| &**s))
@@ -876,6 +902,7 @@ impl Decoder<Option<Vec<u8>>> for CompactBytes {
         match UnsignedVarInt.decode(buf)? {
             0 => Ok(None),
             n => {
+                buf.check_bytes_len((n - 1) as usize)?;
                 let mut data = vec![0; (n - 1) as usize];
                 buf.try_copy_to_slice(&mut data)?;
                 Ok(Some(data))
@@ -888,7 +915,10 @@ impl Decoder<Option<bytes::Bytes>> for CompactBytes {
     fn decode<B: ByteBuf>(&self, buf: &mut B) -> Result<Option<bytes::Bytes>, DecodeError> {
         match UnsignedVarInt.decode(buf)? {
             0 => Ok(None),
-            n => Ok(Some(buf.try_get_bytes((n - 1) as usize)?)),
+            n => {
+                buf.check_slice_len((n - 1) as usize)?;
+                Ok(Some(buf.try_get_bytes((n - 1) as usize)?))
+            }
         }
     }
 }
@@ -898,7 +928,7 @@ impl Decoder<Vec<u8>> for CompactBytes {
         match CompactBytes.decode(buf) {
             Ok(None) => {
                 error!("Data length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -911,7 +941,7 @@ impl Decoder<bytes::Bytes> for CompactBytes {
         match CompactBytes.decode(buf) {
             Ok(None) => {
                 error!("Data length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -947,7 +977,6 @@ impl<T: Encodable> Encoder<&Option<T>> for Struct {
             Int8.encode(buf, -1)?;
             Ok(())
         }
-        
     }
     fn compute_size(&self, option: &Option<T>) -> Result<usize, EncodeError> {
         if let Some(value) = option {
@@ -962,7 +991,10 @@ impl<T: Encodable> Encoder<&Option<T>> for Struct {
 
 impl<T: Decodable> Decoder<T> for Struct {
     fn decode<B: ByteBuf>(&self, buf: &mut B) -> Result<T, DecodeError> {
-        T::decode(buf, self.version)
+        buf.enter_struct()?;
+        let decoded = T::decode(buf, self.version);
+        buf.exit_struct();
+        decoded
     }
 }
 
@@ -973,7 +1005,10 @@ impl<T: Decodable> Decoder<Option<T>> for Struct {
         if optional == -1 {
             Ok(None)
         } else {
-            let decoded = T::decode(buf, self.version)?;
+            let decoded: T = Struct {
+                version: self.version,
+            }
+            .decode(buf)?;
             Ok(Some(decoded))
         }
     }
@@ -994,7 +1029,10 @@ impl<T: MapEncodable> Encoder<(&T::Key, &T)> for Struct {
 
 impl<T: MapDecodable> Decoder<(T::Key, T)> for Struct {
     fn decode<B: ByteBuf>(&self, buf: &mut B) -> Result<(T::Key, T), DecodeError> {
-        T::decode(buf, self.version)
+        buf.enter_struct()?;
+        let decoded = T::decode(buf, self.version);
+        buf.exit_struct();
+        decoded
     }
 }
 
@@ -1007,11 +1045,11 @@ impl<T, E: for<'a> Encoder<&'a T>> Encoder<Option<&[T]>> for Array<E> {
         if let Some(a) = value {
             if a.len() > i32::MAX as usize {
                 error!("Array is too long to encode ({} items)", a.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 Int32.encode(buf, a.len() as i32)?;
-                for item in a {
-                    self.0.encode(buf, item)?;
+                for (i, item) in a.iter().enumerate() {
+                    self.0.encode(buf, item).map_err(|e| e.with_index(i))?;
                 }
                 Ok(())
             }
@@ -1024,13 +1062,13 @@ impl<T, E: for<'a> Encoder<&'a T>> Encoder<Option<&[T]>> for Array<E> {
         if let Some(a) = value {
             if a.len() > i32::MAX as usize {
                 error!("Array is too long to encode ({} items)", a.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else if let Some(fixed_size) = self.0.fixed_size() {
                 Ok(4 + a.len() * fixed_size)
             } else {
                 let mut total_size = 4;
-                for item in a {
-                    total_size += self.0.compute_size(item)?;
+                for (i, item) in a.iter().enumerate() {
+                    total_size += self.0.compute_size(item).map_err(|e| e.with_index(i))?;
                 }
                 Ok(total_size)
             }
@@ -1095,11 +1133,11 @@ impl<K: Eq + Hash, V, E: for<'a> Encoder<(&'a K, &'a V)>> Encoder<Option<&IndexM
         if let Some(a) = value {
             if a.len() > i32::MAX as usize {
                 error!("Array is too long to encode ({} items)", a.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 Int32.encode(buf, a.len() as i32)?;
-                for item in a {
-                    self.0.encode(buf, item)?;
+                for (i, item) in a.iter().enumerate() {
+                    self.0.encode(buf, item).map_err(|e| e.with_index(i))?;
                 }
                 Ok(())
             }
@@ -1112,13 +1150,13 @@ impl<K: Eq + Hash, V, E: for<'a> Encoder<(&'a K, &'a V)>> Encoder<Option<&IndexM
         if let Some(a) = value {
             if a.len() > i32::MAX as usize {
                 error!("Array is too long to encode ({} items)", a.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else if let Some(fixed_size) = self.0.fixed_size() {
                 Ok(4 + a.len() * fixed_size)
             } else {
                 let mut total_size = 4;
-                for item in a {
-                    total_size += self.0.compute_size(item)?;
+                for (i, item) in a.iter().enumerate() {
+                    total_size += self.0.compute_size(item).map_err(|e| e.with_index(i))?;
                 }
                 Ok(total_size)
             }
@@ -1161,15 +1199,15 @@ impl<T, E: Decoder<T>> Decoder<Option<Vec<T>>> for Array<E> {
         match Int32.decode(buf)? {
             -1 => Ok(None),
             n if n >= 0 => {
-                let mut result = Vec::with_capacity(n as usize);
-                for _ in 0..n {
-                    result.push(self.0.decode(buf)?);
+                let mut result = Vec::with_capacity(buf.check_array_len::<T>(n as usize)?);
+                for i in 0..n {
+                    result.push(self.0.decode(buf).map_err(|e| e.with_index(i as usize))?);
                 }
                 Ok(Some(result))
             }
             n => {
                 error!("Array length is negative ({})", n);
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
         }
     }
@@ -1180,7 +1218,7 @@ impl<T, E: Decoder<T>> Decoder<Vec<T>> for Array<E> {
         match self.decode(buf) {
             Ok(None) => {
                 error!("Array length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -1193,16 +1231,17 @@ impl<K: Eq + Hash, V, E: Decoder<(K, V)>> Decoder<Option<IndexMap<K, V>>> for Ar
         match Int32.decode(buf)? {
             -1 => Ok(None),
             n if n >= 0 => {
-                let mut result = IndexMap::new();
-                for _ in 0..n {
-                    let (k, v) = self.0.decode(buf)?;
+                let mut result =
+                    IndexMap::with_capacity(buf.check_array_len::<(K, V)>(n as usize)?);
+                for i in 0..n {
+                    let (k, v) = self.0.decode(buf).map_err(|e| e.with_index(i as usize))?;
                     result.insert(k, v);
                 }
                 Ok(Some(result))
             }
             n => {
                 error!("Array length is negative ({})", n);
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
         }
     }
@@ -1213,7 +1252,7 @@ impl<K: Eq + Hash, V, E: Decoder<(K, V)>> Decoder<IndexMap<K, V>> for Array<E> {
         match self.decode(buf) {
             Ok(None) => {
                 error!("Array length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -1229,13 +1268,13 @@ impl<T, E: for<'a> Encoder<&'a T>> Encoder<Option<&[T]>> for CompactArray<E> {
     fn encode<B: ByteBufMut>(&self, buf: &mut B, value: Option<&[T]>) -> Result<(), EncodeError> {
         if let Some(a) = value {
             // Use >= because we're going to add one to the length
-            if a.len() >= std::u32::MAX as usize {
+            if a.len() >= u32::MAX as usize {
                 error!("CompactArray is too long to encode ({} items)", a.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 UnsignedVarInt.encode(buf, (a.len() as u32) + 1)?;
-                for item in a {
-                    self.0.encode(buf, item)?;
+                for (i, item) in a.iter().enumerate() {
+                    self.0.encode(buf, item).map_err(|e| e.with_index(i))?;
                 }
                 Ok(())
             }
@@ -1247,15 +1286,15 @@ impl<T, E: for<'a> Encoder<&'a T>> Encoder<Option<&[T]>> for CompactArray<E> {
     fn compute_size(&self, value: Option<&[T]>) -> Result<usize, EncodeError> {
         if let Some(a) = value {
             // Use >= because we're going to add one to the length
-            if a.len() >= std::u32::MAX as usize {
+            if a.len() >= u32::MAX as usize {
                 error!("CompactArray is too long to encode ({} items)", a.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else if let Some(fixed_size) = self.0.fixed_size() {
                 Ok(UnsignedVarInt.compute_size((a.len() as u32) + 1)? + a.len() * fixed_size)
             } else {
                 let mut total_size = UnsignedVarInt.compute_size((a.len() as u32) + 1)?;
-                for item in a {
-                    total_size += self.0.compute_size(item)?;
+                for (i, item) in a.iter().enumerate() {
+                    total_size += self.0.compute_size(item).map_err(|e| e.with_index(i))?;
                 }
                 Ok(total_size)
             }
@@ -1319,13 +1358,13 @@ impl<K: Eq + Hash, V, E: for<'a> Encoder<(&'a K, &'a V)>> Encoder<Option<&IndexM
     ) -> Result<(), EncodeError> {
         if let Some(a) = value {
             // Use >= because we're going to add one to the length
-            if a.len() >= std::u32::MAX as usize {
+            if a.len() >= u32::MAX as usize {
                 error!("CompactArray is too long to encode ({} items)", a.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else {
                 UnsignedVarInt.encode(buf, (a.len() as u32) + 1)?;
-                for item in a {
-                    self.0.encode(buf, item)?;
+                for (i, item) in a.iter().enumerate() {
+                    self.0.encode(buf, item).map_err(|e| e.with_index(i))?;
                 }
                 Ok(())
             }
@@ -1337,15 +1376,15 @@ impl<K: Eq + Hash, V, E: for<'a> Encoder<(&'a K, &'a V)>> Encoder<Option<&IndexM
     fn compute_size(&self, value: Option<&IndexMap<K, V>>) -> Result<usize, EncodeError> {
         if let Some(a) = value {
             // Use >= because we're going to add one to the length
-            if a.len() >= std::u32::MAX as usize {
+            if a.len() >= u32::MAX as usize {
                 error!("CompactArray is too long to encode ({} items)", a.len());
-                Err(EncodeError)
+                Err(EncodeError::new(EncodeErrorKind::ValueTooLarge))
             } else if let Some(fixed_size) = self.0.fixed_size() {
                 Ok(UnsignedVarInt.compute_size((a.len() as u32) + 1)? + a.len() * fixed_size)
             } else {
                 let mut total_size = UnsignedVarInt.compute_size((a.len() as u32) + 1)?;
-                for item in a {
-                    total_size += self.0.compute_size(item)?;
+                for (i, item) in a.iter().enumerate() {
+                    total_size += self.0.compute_size(item).map_err(|e| e.with_index(i))?;
                 }
                 Ok(total_size)
             }
@@ -1390,9 +1429,13 @@ impl<T, E: Decoder<T>> Decoder<Option<Vec<T>>> for CompactArray<E> {
         match UnsignedVarInt.decode(buf)? {
             0 => Ok(None),
             n => {
-                let mut result = Vec::with_capacity((n - 1) as usize);
-                for _ in 1..n {
-                    result.push(self.0.decode(buf)?);
+                let mut result = Vec::with_capacity(buf.check_array_len::<T>((n - 1) as usize)?);
+                for i in 1..n {
+                    result.push(
+                        self.0
+                            .decode(buf)
+                            .map_err(|e| e.with_index(i as usize - 1))?,
+                    );
                 }
                 Ok(Some(result))
             }
@@ -1405,7 +1448,7 @@ impl<T, E: Decoder<T>> Decoder<Vec<T>> for CompactArray<E> {
         match self.decode(buf) {
             Ok(None) => {
                 error!("CompactArray length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -1418,9 +1461,13 @@ impl<K: Eq + Hash, V, E: Decoder<(K, V)>> Decoder<Option<IndexMap<K, V>>> for Co
         match UnsignedVarInt.decode(buf)? {
             0 => Ok(None),
             n => {
-                let mut result = IndexMap::new();
-                for _ in 1..n {
-                    let (k, v) = self.0.decode(buf)?;
+                let mut result =
+                    IndexMap::with_capacity(buf.check_array_len::<(K, V)>((n - 1) as usize)?);
+                for i in 1..n {
+                    let (k, v) = self
+                        .0
+                        .decode(buf)
+                        .map_err(|e| e.with_index(i as usize - 1))?;
                     result.insert(k, v);
                 }
                 Ok(Some(result))
@@ -1434,7 +1481,7 @@ impl<K: Eq + Hash, V, E: Decoder<(K, V)>> Decoder<IndexMap<K, V>> for CompactArr
         match self.decode(buf) {
             Ok(None) => {
                 error!("CompactArray length is negative (-1)");
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
             }
             Ok(Some(s)) => Ok(s),
             Err(e) => Err(e),
@@ -1442,6 +1489,197 @@ impl<K: Eq + Hash, V, E: Decoder<(K, V)>> Decoder<IndexMap<K, V>> for CompactArr
     }
 }
 
+/// The elements of an array, produced by an iterator of known length and encoded as they are
+/// produced, so that the array never has to be built in memory.
+///
+/// Encoding fails with [`EncodeErrorKind::InvalidValue`] if the iterator produces a different
+/// number of elements than its length, after some of them have already been written. Computing
+/// the size consumes the iterator and fails the same way.
+#[derive(Debug, Clone)]
+pub struct Streamed<I>(pub I);
+
+/// The entries of a map, produced by an iterator of known length and encoded as they are
+/// produced, so that the map never has to be built in memory.
+///
+/// Encoding fails with [`EncodeErrorKind::InvalidValue`] if the iterator produces a different
+/// number of elements than its length, after some of them have already been written. Computing
+/// the size consumes the iterator and fails the same way.
+#[derive(Debug, Clone)]
+pub struct StreamedMap<I>(pub I);
+
+/// Encode the elements produced by `iter`, checking that it produced as many as it claimed.
+fn encode_streamed<B, I, F>(buf: &mut B, iter: I, len: usize, mut f: F) -> Result<(), EncodeError>
+where
+    B: ByteBufMut,
+    I: Iterator,
+    F: FnMut(&mut B, I::Item) -> Result<(), EncodeError>,
+{
+    let mut count = 0;
+    for (i, item) in iter.enumerate() {
+        if i == len {
+            count = i + 1;
+            break;
+        }
+        f(buf, item).map_err(|e| e.with_index(i))?;
+        count += 1;
+    }
+    if count != len {
+        error!(
+            "Iterator produced {} elements, but claimed to produce {}",
+            count, len
+        );
+        return Err(EncodeError::new(EncodeErrorKind::InvalidValue));
+    }
+    Ok(())
+}
+
+/// Compute the size of the elements produced by `iter`, checking that it produced as many as it
+/// claimed like [`encode_streamed`]. `f` is only called if `fixed_size` is `None`.
+fn compute_streamed_size<I, F>(
+    iter: I,
+    len: usize,
+    fixed_size: Option<usize>,
+    mut f: F,
+) -> Result<usize, EncodeError>
+where
+    I: Iterator,
+    F: FnMut(I::Item) -> Result<usize, EncodeError>,
+{
+    let mut count = 0;
+    let mut total_size = 0;
+    for (i, item) in iter.enumerate() {
+        if i == len {
+            count = i + 1;
+            break;
+        }
+        total_size += match fixed_size {
+            Some(size) => size,
+            None => f(item).map_err(|e| e.with_index(i))?,
+        };
+        count += 1;
+    }
+    if count != len {
+        error!(
+            "Iterator produced {} elements, but claimed to produce {}",
+            count, len
+        );
+        return Err(EncodeError::new(EncodeErrorKind::InvalidValue));
+    }
+    Ok(total_size)
+}
+
+impl<T, I: ExactSizeIterator<Item = T>, E: for<'a> Encoder<&'a T>> Encoder<Streamed<I>>
+    for Array<E>
+{
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, value: Streamed<I>) -> Result<(), EncodeError> {
+        let len = value.0.len();
+        if len > i32::MAX as usize {
+            error!("Array is too long to encode ({} items)", len);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        Int32.encode(buf, len as i32)?;
+        encode_streamed(buf, value.0, len, |buf, item| self.0.encode(buf, &item))
+    }
+    fn compute_size(&self, value: Streamed<I>) -> Result<usize, EncodeError> {
+        let len = value.0.len();
+        if len > i32::MAX as usize {
+            error!("Array is too long to encode ({} items)", len);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        let size = compute_streamed_size(value.0, len, self.0.fixed_size(), |item| {
+            self.0.compute_size(&item)
+        })?;
+        Ok(4 + size)
+    }
+}
+
+impl<K, V, I, E> Encoder<StreamedMap<I>> for Array<E>
+where
+    I: ExactSizeIterator<Item = (K, V)>,
+    E: for<'a> Encoder<(&'a K, &'a V)>,
+{
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, value: StreamedMap<I>) -> Result<(), EncodeError> {
+        let len = value.0.len();
+        if len > i32::MAX as usize {
+            error!("Array is too long to encode ({} items)", len);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        Int32.encode(buf, len as i32)?;
+        encode_streamed(buf, value.0, len, |buf, (k, v)| {
+            self.0.encode(buf, (&k, &v))
+        })
+    }
+    fn compute_size(&self, value: StreamedMap<I>) -> Result<usize, EncodeError> {
+        let len = value.0.len();
+        if len > i32::MAX as usize {
+            error!("Array is too long to encode ({} items)", len);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        let size = compute_streamed_size(value.0, len, self.0.fixed_size(), |(k, v)| {
+            self.0.compute_size((&k, &v))
+        })?;
+        Ok(4 + size)
+    }
+}
+
+impl<T, I: ExactSizeIterator<Item = T>, E: for<'a> Encoder<&'a T>> Encoder<Streamed<I>>
+    for CompactArray<E>
+{
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, value: Streamed<I>) -> Result<(), EncodeError> {
+        let len = value.0.len();
+        // Use >= because we're going to add one to the length
+        if len >= u32::MAX as usize {
+            error!("CompactArray is too long to encode ({} items)", len);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        UnsignedVarInt.encode(buf, (len as u32) + 1)?;
+        encode_streamed(buf, value.0, len, |buf, item| self.0.encode(buf, &item))
+    }
+    fn compute_size(&self, value: Streamed<I>) -> Result<usize, EncodeError> {
+        let len = value.0.len();
+        // Use >= because we're going to add one to the length
+        if len >= u32::MAX as usize {
+            error!("CompactArray is too long to encode ({} items)", len);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        let size = compute_streamed_size(value.0, len, self.0.fixed_size(), |item| {
+            self.0.compute_size(&item)
+        })?;
+        Ok(UnsignedVarInt.compute_size((len as u32) + 1)? + size)
+    }
+}
+
+impl<K, V, I, E> Encoder<StreamedMap<I>> for CompactArray<E>
+where
+    I: ExactSizeIterator<Item = (K, V)>,
+    E: for<'a> Encoder<(&'a K, &'a V)>,
+{
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, value: StreamedMap<I>) -> Result<(), EncodeError> {
+        let len = value.0.len();
+        // Use >= because we're going to add one to the length
+        if len >= u32::MAX as usize {
+            error!("CompactArray is too long to encode ({} items)", len);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        UnsignedVarInt.encode(buf, (len as u32) + 1)?;
+        encode_streamed(buf, value.0, len, |buf, (k, v)| {
+            self.0.encode(buf, (&k, &v))
+        })
+    }
+    fn compute_size(&self, value: StreamedMap<I>) -> Result<usize, EncodeError> {
+        let len = value.0.len();
+        // Use >= because we're going to add one to the length
+        if len >= u32::MAX as usize {
+            error!("CompactArray is too long to encode ({} items)", len);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
+        }
+        let size = compute_streamed_size(value.0, len, self.0.fixed_size(), |(k, v)| {
+            self.0.compute_size((&k, &v))
+        })?;
+        Ok(UnsignedVarInt.compute_size((len as u32) + 1)? + size)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1481,12 +1719,12 @@ mod tests {
         test_encoder_decoder(VarLong, 300, &[216, 4]);
         test_encoder_decoder(
             VarLong,
-            std::i64::MAX,
+            i64::MAX,
             &[254, 255, 255, 255, 255, 255, 255, 255, 255, 1],
         );
         test_encoder_decoder(
             VarLong,
-            std::i64::MIN,
+            i64::MIN,
             &[255, 255, 255, 255, 255, 255, 255, 255, 255, 1],
         );
     }
@@ -1522,4 +1760,46 @@ mod tests {
         test_encoder_decoder(CompactBytes, vec![1, 2, 3, 4], &[5, 1, 2, 3, 4]);
         test_encoder_decoder(CompactBytes, None::<Vec<u8>>, &[0]);
     }
+
+    /// An iterator of `i32`s which produces fewer elements than it claims to.
+    struct Lying(std::ops::Range<i32>);
+
+    impl Iterator for Lying {
+        type Item = i32;
+
+        fn next(&mut self) -> Option<i32> {
+            self.0.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (5, Some(5))
+        }
+    }
+
+    impl ExactSizeIterator for Lying {}
+
+    #[test]
+    fn streamed_size_checks_the_length() {
+        let mut buf = Vec::new();
+        Array(Int32).encode(&mut buf, Streamed(0..3)).unwrap();
+        assert_eq!(
+            Array(Int32).compute_size(Streamed(0..3)).unwrap(),
+            buf.len()
+        );
+        let mut buf = Vec::new();
+        CompactArray(Int32)
+            .encode(&mut buf, Streamed(0..3))
+            .unwrap();
+        assert_eq!(
+            CompactArray(Int32).compute_size(Streamed(0..3)).unwrap(),
+            buf.len()
+        );
+
+        for err in [
+            Array(Int32).compute_size(Streamed(Lying(0..3))),
+            CompactArray(Int32).compute_size(Streamed(Lying(0..3))),
+        ] {
+            assert_eq!(err.unwrap_err().kind(), EncodeErrorKind::InvalidValue);
+        }
+    }
 }
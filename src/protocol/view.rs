@@ -0,0 +1,349 @@
+//! Lazily decoded views of messages, which borrow from the buffer they were decoded from.
+//!
+//! Decoding a message into its owned struct allocates for every array and unknown tagged field.
+//! The views generated for the most frequently used APIs (Produce, Fetch, ListOffsets and
+//! Metadata) instead keep their arrays encoded as [`ArrayView`]s, slices of the original
+//! [`Bytes`] which are decoded one element at a time while iterating. Strings, bytes and records
+//! are slices of the original buffer, so decoding a view never allocates.
+//!
+//! Decoding a view only skips over the elements of its arrays, reading no more than their lengths,
+//! so an element which fails to decode is reported when iterating reaches it.
+//!
+//! ```rust
+//! # #[cfg(feature = "client-core")] {
+//! use bytes::Bytes;
+//! use kafka_protocol::messages::produce_request::ProduceRequestView;
+//! # use kafka_protocol::messages::produce_request::TopicProduceData;
+//! # use kafka_protocol::messages::ProduceRequest;
+//! # use kafka_protocol::messages::TopicName;
+//! # use kafka_protocol::protocol::{Encodable, StrBytes};
+//! # let mut request = ProduceRequest::default();
+//! # request.topic_data.insert(TopicName(StrBytes::from_str("events")), TopicProduceData::default());
+//! # let mut encoded = bytes::BytesMut::new();
+//! # request.encode(&mut encoded, 9).unwrap();
+//! # let mut buf: Bytes = encoded.freeze();
+//!
+//! let view = ProduceRequestView::decode(&mut buf, 9).unwrap();
+//! for topic in &view.topic_data {
+//!     assert_eq!(topic.unwrap().name, TopicName(StrBytes::from_str("events")));
+//! }
+//! # }
+//! ```
+
+use std::fmt::{Debug, Formatter};
+
+use bytes::Bytes;
+
+use super::buf::ByteBuf;
+use super::types::{Int16, Int32, Int8, UnsignedVarInt};
+use super::{DecodeError, DecodeErrorKind, Decoder};
+
+/// The function decoding one element of an [`ArrayView`] at a given version.
+pub type DecodeFn<T> = fn(&mut Bytes, i16) -> Result<T, DecodeError>;
+
+/// The function skipping over one encoded value at a given version, without decoding it.
+pub type SkipFn<B> = fn(&mut B, i16) -> Result<(), DecodeError>;
+
+/// An encoded array, whose elements are decoded while iterating over it.
+///
+/// Only the lengths of the elements are read when the view is created, to find where the array
+/// ends, so iterating yields an error for an element which fails to decode.
+pub struct ArrayView<T> {
+    buf: Bytes,
+    len: usize,
+    version: i16,
+    decode: DecodeFn<T>,
+}
+
+impl<T> ArrayView<T> {
+    fn new(buf: Bytes, len: usize, version: i16, decode: DecodeFn<T>) -> Self {
+        Self {
+            buf,
+            len,
+            version,
+            decode,
+        }
+    }
+
+    /// Decodes a nullable array, encoded with an `i32` length, or an unsigned varint length if
+    /// `compact`, skipping over its elements with `skip`.
+    pub fn decode_nullable(
+        buf: &mut Bytes,
+        version: i16,
+        compact: bool,
+        decode: DecodeFn<T>,
+        skip: SkipFn<Bytes>,
+    ) -> Result<Option<Self>, DecodeError> {
+        match decode_array_len(buf, compact)? {
+            Some(len) => Self::decode_elements(buf, len, version, decode, skip).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Decodes an array, encoded with an `i32` length, or an unsigned varint length if `compact`,
+    /// skipping over its elements with `skip`.
+    pub fn decode(
+        buf: &mut Bytes,
+        version: i16,
+        compact: bool,
+        decode: DecodeFn<T>,
+        skip: SkipFn<Bytes>,
+    ) -> Result<Self, DecodeError> {
+        Self::decode_nullable(buf, version, compact, decode, skip)?.ok_or_else(|| {
+            error!("Array length is negative (-1)");
+            DecodeError::new(DecodeErrorKind::NegativeLength)
+        })
+    }
+
+    /// Skips over `len` elements, keeping the slice of `buf` they were encoded in.
+    pub(crate) fn decode_elements(
+        buf: &mut Bytes,
+        len: usize,
+        version: i16,
+        decode: DecodeFn<T>,
+        skip: SkipFn<Bytes>,
+    ) -> Result<Self, DecodeError> {
+        let start = buf.clone();
+        for i in 0..len {
+            skip(buf, version).map_err(|e| e.with_index(i))?;
+        }
+        let elements = start.slice(..start.len() - buf.len());
+        Ok(Self::new(elements, len, version, decode))
+    }
+
+    /// The number of elements in the array.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The encoded elements of the array, without its length.
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.buf
+    }
+
+    /// Iterates over the elements of the array, decoding each of them in turn.
+    pub fn iter(&self) -> ArrayViewIter<T> {
+        ArrayViewIter {
+            buf: self.buf.clone(),
+            index: 0,
+            len: self.len,
+            version: self.version,
+            decode: self.decode,
+        }
+    }
+}
+
+impl<T> Default for ArrayView<T> {
+    fn default() -> Self {
+        Self::new(Bytes::new(), 0, 0, |_, _| {
+            unreachable!("an empty array view has no elements to decode")
+        })
+    }
+}
+
+impl<T> Clone for ArrayView<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.buf.clone(), self.len, self.version, self.decode)
+    }
+}
+
+impl<T: Debug> Debug for ArrayView<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
+        for element in self.iter() {
+            match element {
+                Ok(element) => list.entry(&element),
+                Err(e) => list.entry(&e),
+            };
+        }
+        list.finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArrayView<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> IntoIterator for &ArrayView<T> {
+    type Item = Result<T, DecodeError>;
+    type IntoIter = ArrayViewIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator decoding the elements of an [`ArrayView`].
+///
+/// The iterator ends after the first element which fails to decode, as the elements following it
+/// can't be found.
+pub struct ArrayViewIter<T> {
+    buf: Bytes,
+    index: usize,
+    len: usize,
+    version: i16,
+    decode: DecodeFn<T>,
+}
+
+impl<T> Iterator for ArrayViewIter<T> {
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        let index = self.index;
+        self.index += 1;
+        let element = (self.decode)(&mut self.buf, self.version);
+        if element.is_err() {
+            self.index = self.len;
+        }
+        Some(element.map_err(|e| e.with_index(index)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.len - self.index))
+    }
+}
+
+/// Decodes the length of a nullable array, encoded with an `i32` length, or an unsigned varint
+/// length if `compact`.
+fn decode_array_len<B: ByteBuf>(buf: &mut B, compact: bool) -> Result<Option<usize>, DecodeError> {
+    if compact {
+        let len: u32 = UnsignedVarInt.decode(buf)?;
+        Ok(len.checked_sub(1).map(|len| len as usize))
+    } else {
+        match Int32.decode(buf)? {
+            -1 => Ok(None),
+            n if n >= 0 => Ok(Some(n as usize)),
+            n => {
+                error!("Array length is negative ({})", n);
+                Err(DecodeError::new(DecodeErrorKind::NegativeLength))
+            }
+        }
+    }
+}
+
+/// Skips over a nullable length prefixed value, whose length is `-1` if null.
+fn skip_len_prefixed<B: ByteBuf>(buf: &mut B, len: i64) -> Result<(), DecodeError> {
+    match len {
+        -1 => Ok(()),
+        n if n >= 0 => Ok(buf.try_advance(n as usize)?),
+        n => {
+            error!("Length is negative ({})", n);
+            Err(DecodeError::new(DecodeErrorKind::NegativeLength))
+        }
+    }
+}
+
+/// Skips over a value of `size` bytes, such as an integer.
+pub fn skip_fixed<B: ByteBuf>(buf: &mut B, size: usize) -> Result<(), DecodeError> {
+    Ok(buf.try_advance(size)?)
+}
+
+/// Skips over a nullable string, encoded with an `i16` length, or an unsigned varint length if
+/// `compact`.
+pub fn skip_string<B: ByteBuf>(buf: &mut B, compact: bool) -> Result<(), DecodeError> {
+    let len = if compact {
+        let len: u32 = UnsignedVarInt.decode(buf)?;
+        len as i64 - 1
+    } else {
+        let len: i16 = Int16.decode(buf)?;
+        len as i64
+    };
+    skip_len_prefixed(buf, len)
+}
+
+/// Skips over nullable bytes or records, encoded with an `i32` length, or an unsigned varint
+/// length if `compact`.
+pub fn skip_bytes<B: ByteBuf>(buf: &mut B, compact: bool) -> Result<(), DecodeError> {
+    let len = if compact {
+        let len: u32 = UnsignedVarInt.decode(buf)?;
+        len as i64 - 1
+    } else {
+        let len: i32 = Int32.decode(buf)?;
+        len as i64
+    };
+    skip_len_prefixed(buf, len)
+}
+
+/// Skips over a nullable array, encoded with an `i32` length, or an unsigned varint length if
+/// `compact`, skipping over each of its elements with `skip`.
+pub fn skip_array<B: ByteBuf>(
+    buf: &mut B,
+    version: i16,
+    compact: bool,
+    skip: SkipFn<B>,
+) -> Result<(), DecodeError> {
+    let len = decode_array_len(buf, compact)?.unwrap_or_default();
+    for i in 0..len {
+        skip(buf, version).map_err(|e| e.with_index(i))?;
+    }
+    Ok(())
+}
+
+/// Decodes the keys of the entries of a map, encoded as an array with an `i32` length, or an
+/// unsigned varint length if `compact`, with `decode_key` skipping over the rest of each entry.
+pub fn decode_keys<B: ByteBuf, K>(
+    buf: &mut B,
+    version: i16,
+    compact: bool,
+    decode_key: fn(&mut B, i16) -> Result<K, DecodeError>,
+) -> Result<Vec<K>, DecodeError> {
+    let len = decode_array_len(buf, compact)?.ok_or_else(|| {
+        error!("Array length is negative (-1)");
+        DecodeError::new(DecodeErrorKind::NegativeLength)
+    })?;
+    let mut keys = Vec::with_capacity(buf.check_array_len::<K>(len)?);
+    for i in 0..len {
+        keys.push(decode_key(buf, version).map_err(|e| e.with_index(i))?);
+    }
+    Ok(keys)
+}
+
+/// Skips over a nullable struct, preceded by an `i8` which is `-1` if it is null.
+pub fn skip_nullable_struct<B: ByteBuf>(
+    buf: &mut B,
+    version: i16,
+    skip: SkipFn<B>,
+) -> Result<(), DecodeError> {
+    let present: i8 = Int8.decode(buf)?;
+    if present == -1 {
+        Ok(())
+    } else {
+        skip(buf, version)
+    }
+}
+
+/// Decodes one tagged field as its tag and encoded value.
+pub fn decode_tagged_field(buf: &mut Bytes, _version: i16) -> Result<(i32, Bytes), DecodeError> {
+    let tag: u32 = UnsignedVarInt.decode(buf)?;
+    let size: u32 = UnsignedVarInt.decode(buf)?;
+    if buf.len() < size as usize {
+        return Err(DecodeError::new(DecodeErrorKind::NotEnoughBytes));
+    }
+    Ok((tag as i32, buf.split_to(size as usize)))
+}
+
+/// Skips over one tagged field.
+pub fn skip_tagged_field<B: ByteBuf>(buf: &mut B, _version: i16) -> Result<(), DecodeError> {
+    let _tag: u32 = UnsignedVarInt.decode(buf)?;
+    let size: u32 = UnsignedVarInt.decode(buf)?;
+    Ok(buf.try_advance(size as usize)?)
+}
+
+/// Skips over the tagged fields at the end of a struct.
+pub fn skip_tagged_fields<B: ByteBuf>(buf: &mut B, version: i16) -> Result<(), DecodeError> {
+    let num_tagged_fields: u32 = UnsignedVarInt.decode(buf)?;
+    for i in 0..num_tagged_fields as usize {
+        skip_tagged_field(buf, version).map_err(|e| e.with_index(i))?;
+    }
+    Ok(())
+}
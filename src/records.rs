@@ -8,6 +8,7 @@
 //!
 //! Decoding a set of records from a [`FetchResponse`](crate::messages::fetch_response::FetchResponse):
 //! ```rust
+//! # #[cfg(feature = "client-core")] {
 //! use kafka_protocol::messages::FetchResponse;
 //! use kafka_protocol::protocol::Decodable;
 //! use kafka_protocol::records::RecordBatchDecoder;
@@ -29,8 +30,9 @@
 //!          let records = RecordBatchDecoder::decode(&mut records).unwrap();
 //!     }
 //! }
+//! # }
 //! ```
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 use indexmap::IndexMap;
 use crc32c::crc32c;
 use log::error;
@@ -38,12 +40,13 @@ use crc::{CRC_32_CKSUM, Crc};
 use string::TryFrom;
 
 use crate::protocol::{
-    buf::{gap, ByteBuf, ByteBufMut},
-    types, DecodeError, Decoder, EncodeError, Encoder, StrBytes,
+    buf::{gap, ByteBuf, ByteBufMut, DecodeLimits, LimitedBuf},
+    types, DecodeError, DecodeErrorKind, Decoder, EncodeError, EncodeErrorKind, Encoder, StrBytes,
 };
 
 use super::compression::{self as cmpr, Compressor, Decompressor};
 use std::cmp::Ordering;
+use std::ops::Range;
 
 /// IEEE (checksum) cyclic redundancy check.
 pub const IEEE: Crc<u32> = Crc::<u32>::new(&CRC_32_CKSUM);
@@ -65,6 +68,7 @@ pub enum Compression {
 
 /// Indicates the meaning of the timestamp field on a record.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TimestampType {
     /// The timestamp represents when the record was created by the client.
     Creation = 0,
@@ -114,8 +118,46 @@ struct BatchDecodeInfo {
     producer_epoch: i16,
 }
 
+/// The bytes of a record batch, which charges the allocations for decompressing them to the buffer
+/// they were taken from.
+struct BatchBuf<'a, B> {
+    batch: Bytes,
+    outer: &'a mut B,
+}
+
+impl<B> Buf for BatchBuf<'_, B> {
+    fn remaining(&self) -> usize {
+        self.batch.remaining()
+    }
+    fn chunk(&self) -> &[u8] {
+        self.batch.chunk()
+    }
+    fn advance(&mut self, cnt: usize) {
+        self.batch.advance(cnt)
+    }
+    fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+        self.batch.copy_to_bytes(len)
+    }
+}
+
+impl<B: ByteBuf> ByteBuf for BatchBuf<'_, B> {
+    fn peek_bytes(&mut self, r: Range<usize>) -> Bytes {
+        self.batch.peek_bytes(r)
+    }
+    fn get_bytes(&mut self, size: usize) -> Bytes {
+        self.batch.get_bytes(size)
+    }
+    fn shares_bytes(&self) -> bool {
+        true
+    }
+    fn check_allocation(&mut self, len: usize) -> Result<(), DecodeError> {
+        self.outer.check_allocation(len)
+    }
+}
+
 /// A Kafka message containing key, payload value, and all associated metadata.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Record {
     // Batch properties
     /// Whether this record is transactional.
@@ -143,6 +185,7 @@ pub struct Record {
     /// The payload of the record.
     pub value: Option<Bytes>,
     /// Headers associated with the record's payload.
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
     pub headers: IndexMap<StrBytes, Option<Bytes>>,
 }
 
@@ -233,7 +276,7 @@ impl RecordBatchEncoder {
                         "Record batch was too large to encode ({} bytes)",
                         value_size
                     );
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
                 }
                 buf.fill_typed_gap(size_gap, value_size as i32);
 
@@ -371,7 +414,7 @@ impl RecordBatchEncoder {
                 "Too many records to encode in one batch ({} records)",
                 num_records
             );
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         types::Int32.encode(buf, num_records as i32)?;
 
@@ -399,7 +442,7 @@ impl RecordBatchEncoder {
                 "Record batch was too large to encode ({} bytes)",
                 batch_size
             );
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
 
         buf.fill_typed_gap(size_gap, batch_size as i32);
@@ -434,6 +477,14 @@ impl RecordBatchDecoder {
         }
         Ok(records)
     }
+    /// Decode records from untrusted input, failing if decompressing them would allocate more
+    /// than [`DecodeLimits::max_allocation`] bytes.
+    pub fn decode_with_limits<B: ByteBuf>(
+        buf: &mut B,
+        limits: DecodeLimits,
+    ) -> Result<Vec<Record>, DecodeError> {
+        Self::decode(&mut LimitedBuf::new(buf, limits))
+    }
     fn decode_batch<B: ByteBuf>(buf: &mut B, records: &mut Vec<Record>) -> Result<(), DecodeError> {
         let version = buf.try_peek_bytes(MAGIC_BYTE_OFFSET..(MAGIC_BYTE_OFFSET + 1))?[0] as i8;
         debug!("Decoding record batch (version: {})", version);
@@ -442,7 +493,7 @@ impl RecordBatchDecoder {
             2 => Self::decode_new_batch(buf, version, records),
             _ => {
                 error!("Unknown record batch version ({})", version);
-                Err(DecodeError)
+                Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion))
             }
         }
     }
@@ -452,7 +503,7 @@ impl RecordBatchDecoder {
         version: i8,
         records: &mut Vec<Record>,
     ) -> Result<(), DecodeError> {
-        records.reserve(batch_decode_info.record_count);
+        records.reserve(buf.check_array_len::<Record>(batch_decode_info.record_count)?);
         for _ in 0..batch_decode_info.record_count {
             records.push(Record::decode_new(buf, batch_decode_info, version)?);
         }
@@ -470,11 +521,14 @@ impl RecordBatchDecoder {
         let batch_length: i32 = types::Int32.decode(buf)?;
         if batch_length < 0 {
             error!("Unexpected negative batch size: {}", batch_length);
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
         }
 
-        // Convert buf to bytes
-        let buf = &mut buf.try_get_bytes(batch_length as usize)?;
+        // Convert buf to bytes, keeping it to check the allocations for decompression
+        let buf = &mut BatchBuf {
+            batch: buf.try_get_bytes(batch_length as usize)?,
+            outer: buf,
+        };
 
         // Partition leader epoch
         let partition_leader_epoch = types::Int32.decode(buf)?;
@@ -483,19 +537,19 @@ impl RecordBatchDecoder {
         let magic: i8 = types::Int8.decode(buf)?;
         if magic != version {
             error!("Version mismatch ({} != {})", magic, version);
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::InvalidValue));
         }
 
         // CRC
         let supplied_crc: u32 = types::UInt32.decode(buf)?;
-        let actual_crc = crc32c(buf);
-        
+        let actual_crc = crc32c(buf.chunk());
+
         if supplied_crc != actual_crc {
             error!(
                 "Cyclic redundancy check failed ({} != {})",
                 supplied_crc, actual_crc
             );
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::CrcMismatch));
         }
 
         // Attributes
@@ -510,7 +564,7 @@ impl RecordBatchDecoder {
             4 => Compression::Zstd,
             other => {
                 error!("Unknown compression algorithm used: {}", other);
-                return Err(DecodeError);
+                return Err(DecodeError::new(DecodeErrorKind::InvalidValue));
             }
         };
         let timestamp_type = if (attributes & (1 << 3)) != 0 {
@@ -541,7 +595,7 @@ impl RecordBatchDecoder {
         let record_count: i32 = types::Int32.decode(buf)?;
         if record_count < 0 {
             error!("Unexpected negative record count ({})", record_count);
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
         }
         let record_count = record_count as usize;
 
@@ -600,7 +654,7 @@ impl Record {
                 "Compression algorithm '{:?}' is unsupported for record version '{}'",
                 options.compression, options.version
             );
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::InvalidValue));
         }
         types::Int8.encode(buf, compression)?;
 
@@ -612,7 +666,7 @@ impl Record {
         let message_size = message_end - message_start;
         if message_start > i32::MAX as usize {
             error!("Record was too large to encode ({} bytes)", message_size);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         buf.fill_typed_gap(size_gap, message_size as i32);
 
@@ -628,12 +682,12 @@ impl Record {
     ) -> Result<(), EncodeError> {
         if self.transactional || self.control {
             error!("Transactional and control records are not supported in this version of the protocol!");
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::InvalidValue));
         }
 
         if !self.headers.is_empty() {
             error!("Record headers are not supported in this version of the protocol!");
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::InvalidValue));
         }
 
         Self::encode_legacy_static(buf, options, |buf| {
@@ -657,7 +711,7 @@ impl Record {
         let size = self.compute_size_new(min_offset, min_timestamp, options)?;
         if size > i32::MAX as usize {
             error!("Record was too large to encode ({} bytes)", size);
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         types::VarInt.encode(buf, size as i32)?;
 
@@ -671,7 +725,7 @@ impl Record {
                 "Timestamps within batch are too far apart ({}, {})",
                 min_timestamp, self.timestamp
             );
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         types::VarInt.encode(buf, timestamp_delta as i32)?;
 
@@ -682,7 +736,7 @@ impl Record {
                 "Timestamps within batch are too far apart ({}, {})",
                 min_offset, self.offset
             );
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         types::VarInt.encode(buf, offset_delta as i32)?;
 
@@ -690,7 +744,7 @@ impl Record {
         if let Some(k) = self.key.as_ref() {
             if k.len() > i32::MAX as usize {
                 error!("Record key was too large to encode ({} bytes)", k.len());
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
             }
             types::VarInt.encode(buf, k.len() as i32)?;
             buf.put_slice(k);
@@ -702,7 +756,7 @@ impl Record {
         if let Some(v) = self.value.as_ref() {
             if v.len() > i32::MAX as usize {
                 error!("Record value was too large to encode ({} bytes)", v.len());
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
             }
             types::VarInt.encode(buf, v.len() as i32)?;
             buf.put_slice(v);
@@ -713,7 +767,7 @@ impl Record {
         // Headers
         if self.headers.len() > i32::MAX as usize {
             error!("Too many record headers encode ({})", self.headers.len());
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         types::VarInt.encode(buf, self.headers.len() as i32)?;
         for (k, v) in &self.headers {
@@ -723,7 +777,7 @@ impl Record {
                     "Record header key was too large to encode ({} bytes)",
                     k.len()
                 );
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
             }
             types::VarInt.encode(buf, k.len() as i32)?;
 
@@ -737,7 +791,7 @@ impl Record {
                         "Record header value was too large to encode ({} bytes)",
                         v.len()
                     );
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
                 }
                 types::VarInt.encode(buf, v.len() as i32)?;
                 buf.put_slice(v);
@@ -766,7 +820,7 @@ impl Record {
                 "Timestamps within batch are too far apart ({}, {})",
                 min_timestamp, self.timestamp
             );
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         total_size += types::VarInt.compute_size(timestamp_delta as i32)?;
 
@@ -777,7 +831,7 @@ impl Record {
                 "Timestamps within batch are too far apart ({}, {})",
                 min_offset, self.offset
             );
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         total_size += types::VarInt.compute_size(offset_delta as i32)?;
 
@@ -785,7 +839,7 @@ impl Record {
         if let Some(k) = self.key.as_ref() {
             if k.len() > i32::MAX as usize {
                 error!("Record key was too large to encode ({} bytes)", k.len());
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
             }
             total_size += types::VarInt.compute_size(k.len() as i32)?;
             total_size += k.len();
@@ -797,7 +851,7 @@ impl Record {
         if let Some(v) = self.value.as_ref() {
             if v.len() > i32::MAX as usize {
                 error!("Record value was too large to encode ({} bytes)", v.len());
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
             }
             total_size += types::VarInt.compute_size(v.len() as i32)?;
             total_size += v.len();
@@ -808,7 +862,7 @@ impl Record {
         // Headers
         if self.headers.len() > i32::MAX as usize {
             error!("Too many record headers encode ({})", self.headers.len());
-            return Err(EncodeError);
+            return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
         }
         total_size += types::VarInt.compute_size(self.headers.len() as i32)?;
         for (k, v) in &self.headers {
@@ -818,7 +872,7 @@ impl Record {
                     "Record header key was too large to encode ({} bytes)",
                     k.len()
                 );
-                return Err(EncodeError);
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
             }
             total_size += types::VarInt.compute_size(k.len() as i32)?;
 
@@ -832,7 +886,7 @@ impl Record {
                         "Record header value was too large to encode ({} bytes)",
                         v.len()
                     );
-                    return Err(EncodeError);
+                    return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge));
                 }
                 total_size += types::VarInt.compute_size(v.len() as i32)?;
                 total_size += v.len();
@@ -852,7 +906,7 @@ impl Record {
         let size: i32 = types::Int32.decode(buf)?;
         if size < 0 {
             error!("Unexpected negative record size: {}", size);
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
         }
 
         // Ensure we don't over-read
@@ -867,14 +921,14 @@ impl Record {
                 "Cyclic redundancy check failed ({} != {})",
                 supplied_crc, actual_crc
             );
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::CrcMismatch));
         }
 
         // Magic
         let magic: i8 = types::Int8.decode(buf)?;
         if magic != version {
             error!("Version mismatch ({} != {})", magic, version);
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::InvalidValue));
         }
 
         // Attributes
@@ -886,7 +940,7 @@ impl Record {
             3 if version > 0 => Compression::Lz4,
             other => {
                 error!("Unknown compression algorithm used: {}", other);
-                return Err(DecodeError);
+                return Err(DecodeError::new(DecodeErrorKind::InvalidValue));
             }
         };
         let timestamp_type = if (attributes & (1 << 3)) != 0 {
@@ -924,7 +978,7 @@ impl Record {
             // Wrapper record around a compressed MessageSet
             let mut value = value.ok_or_else(|| {
                 error!("Received compressed legacy record without a value");
-                DecodeError
+                DecodeError::new(DecodeErrorKind::InvalidValue)
             })?;
 
             while !value.is_empty() {
@@ -943,7 +997,7 @@ impl Record {
         let size: i32 = types::VarInt.decode(buf)?;
         if size < 0 {
             error!("Unexpected negative record size: {}", size);
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
         }
 
         // Ensure we don't over-read
@@ -966,7 +1020,7 @@ impl Record {
         let key = match key_len.cmp(&-1) {
             Ordering::Less => {
                 error!("Unexpected negative record key length ({} bytes)", key_len);
-                return Err(DecodeError);
+                return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
             }
             Ordering::Equal => None,
             Ordering::Greater => Some(buf.try_get_bytes(key_len as usize)?),
@@ -980,7 +1034,7 @@ impl Record {
                     "Unexpected negative record value length ({} bytes)",
                     value_len
                 );
-                return Err(DecodeError);
+                return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
             }
             Ordering::Equal => None,
             Ordering::Greater => Some(buf.try_get_bytes(value_len as usize)?),
@@ -990,11 +1044,12 @@ impl Record {
         let num_headers: i32 = types::VarInt.decode(buf)?;
         if num_headers < 0 {
             error!("Unexpected negative record header count: {}", num_headers);
-            return Err(DecodeError);
+            return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
         }
         let num_headers = num_headers as usize;
 
-        let mut headers = IndexMap::with_capacity(num_headers);
+        let mut headers =
+            IndexMap::with_capacity(buf.check_array_len::<(StrBytes, Option<Bytes>)>(num_headers)?);
         for _ in 0..num_headers {
             // Key len
             let key_len: i32 = types::VarInt.decode(buf)?;
@@ -1003,7 +1058,7 @@ impl Record {
                     "Unexpected negative record header key length ({} bytes)",
                     key_len
                 );
-                return Err(DecodeError);
+                return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
             }
 
             // Key
@@ -1019,7 +1074,7 @@ impl Record {
                         "Unexpected negative record header value length ({} bytes)",
                         value_len
                     );
-                    return Err(DecodeError);
+                    return Err(DecodeError::new(DecodeErrorKind::NegativeLength));
                 }
                 Ordering::Equal => None,
                 Ordering::Greater => Some(buf.try_get_bytes(value_len as usize)?),
@@ -0,0 +1,91 @@
+//! Records of the internal `__transaction_state` topic.
+//!
+//! These records are generated programmatically. See [Kafka's schemas](https://github.com/apache/kafka/tree/trunk/core/src/main/resources/common/message) for more information about a given record type.
+//!
+//! The key and value of each record are prefixed with the version they are encoded with, and the version of the key
+//! determines the type of the record. [`TransactionStateRecord`] decodes both from a [`Record`](crate::records::Record) read from the topic.
+// WARNING: the items of this module are generated and should not be edited directly.
+
+use bytes::Bytes;
+use crate::protocol::{Decodable, DecodeError, DecodeErrorKind, Message, types, Decoder};
+
+pub use crate::messages::{ProducerId, TopicName};
+
+pub mod transaction_log_key;
+pub use transaction_log_key::TransactionLogKey;
+
+pub mod transaction_log_value;
+pub use transaction_log_value::TransactionLogValue;
+
+/// A record of the `__transaction_state` topic.
+///
+/// The value is `None` for a tombstone, which deletes the key when the topic is compacted.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionStateRecord {
+    /// [`TransactionLogKey`] and [`TransactionLogValue`] records.
+    TransactionLog {
+        /// The key.
+        key: TransactionLogKey,
+        /// The value, or `None` for a tombstone.
+        value: Option<TransactionLogValue>,
+    },
+    /// A record whose key version is not known to this crate.
+    Unknown {
+        /// The version of the key.
+        version: i16,
+        /// The key, following its version.
+        key: Bytes,
+        /// The value including its version, or `None` for a tombstone.
+        value: Option<Bytes>,
+    },
+}
+
+impl TransactionStateRecord {
+    /// Decode the key and value of a record of the `__transaction_state` topic.
+    pub fn decode(mut key: Bytes, value: Option<Bytes>) -> Result<Self, DecodeError> {
+        let version: i16 = types::Int16.decode(&mut key)?;
+        match version {
+            0 => {
+                let key = TransactionLogKey::decode(&mut key, version)?;
+                let value = match value {
+                    Some(mut value) => {
+                        let version: i16 = types::Int16.decode(&mut value)?;
+                        check_version::<TransactionLogValue>(version)?;
+                        Some(TransactionLogValue::decode(&mut value, version)?)
+                    }
+                    None => None,
+                };
+                Ok(TransactionStateRecord::TransactionLog { key, value })
+            }
+            _ => Ok(TransactionStateRecord::Unknown { version, key, value }),
+        }
+    }
+
+    /// Decode a record read from the `__transaction_state` topic.
+    pub fn decode_record(record: &crate::records::Record) -> Result<Self, DecodeError> {
+        let key = record.key.clone().ok_or_else(|| {
+            error!("Record at offset {} of __transaction_state has no key", record.offset);
+            DecodeError::new(DecodeErrorKind::InvalidValue)
+        })?;
+        Self::decode(key, record.value.clone())
+    }
+
+    /// Whether this record is a tombstone, deleting its key.
+    pub fn is_tombstone(&self) -> bool {
+        match self {
+            TransactionStateRecord::TransactionLog { value, .. } => value.is_none(),
+            TransactionStateRecord::Unknown { value, .. } => value.is_none(),
+        }
+    }
+}
+
+fn check_version<M: Message>(version: i16) -> Result<(), DecodeError> {
+    if M::VERSIONS.contains(version) {
+        Ok(())
+    } else {
+        error!("Unsupported version {} of {}", version, std::any::type_name::<M>());
+        Err(DecodeError::new(DecodeErrorKind::UnsupportedVersion))
+    }
+}
+
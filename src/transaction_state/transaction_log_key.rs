@@ -0,0 +1,140 @@
+//! TransactionLogKey
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/core/src/main/resources/common/message/TransactionLogKey.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct TransactionLogKey {
+    /// the transactional id of the transaction
+    /// 
+    /// Supported API versions: 0
+    #[cfg_attr(feature = "serde", serde(with = "crate::protocol::serde_str_bytes"))]
+    pub transactional_id: StrBytes,
+
+}
+
+impl Builder for TransactionLogKey {
+    type Builder = TransactionLogKeyBuilder;
+
+    fn builder() -> Self::Builder{
+        TransactionLogKeyBuilder::default()
+    }
+}
+
+impl Encodable for TransactionLogKey {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::String.encode(buf, &self.transactional_id).map_err(|e| e.with_field("TransactionLogKey", "transactional_id", version))?;
+
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        total_size += types::String.compute_size(&self.transactional_id).map_err(|e| e.with_field("TransactionLogKey", "transactional_id", version))?;
+
+        Ok(total_size)
+    }
+}
+
+impl Decodable for TransactionLogKey {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let transactional_id = types::String.decode(buf).map_err(|e| e.with_field("TransactionLogKey", "transactional_id", version, start - buf.remaining()))?;
+        Ok(Self {
+            transactional_id,
+        })
+    }
+}
+
+impl Default for TransactionLogKey {
+    fn default() -> Self {
+        Self {
+            transactional_id: Default::default(),
+        }
+    }
+}
+
+impl Message for TransactionLogKey {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 0 };
+}
+
+impl TransactionLogKey {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl TransactionLogKey {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("transactionalId".into(), JsonValue::to_json(&self.transactional_id));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TransactionLogKey")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("transactionalId") {
+            this.transactional_id = JsonValue::from_json(value, "TransactionLogKey.transactionalId")?;
+        } else {
+            return Err(crate::json::missing("TransactionLogKey", "transactionalId", version));
+        }
+        Ok(this)
+    }
+}
+
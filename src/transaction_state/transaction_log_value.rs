@@ -0,0 +1,474 @@
+//! TransactionLogValue
+//!
+//! See the schema for this message [here](https://github.com/apache/kafka/blob/trunk/core/src/main/resources/common/message/TransactionLogValue.json).
+// WARNING: the items of this module are generated and should not be edited directly
+#![allow(unused)]
+
+use std::borrow::Borrow;
+
+use bytes::Bytes;
+use uuid::Uuid;
+
+use crate::protocol::{
+    Encodable, Decodable, EncodeError, EncodeErrorKind, DecodeError, DecodeErrorKind, Message, HeaderVersion, VersionRange,
+    types, StrBytes, buf::{ByteBuf, ByteBufMut}, Builder, tagged_fields::UnknownTaggedFields
+};
+use crate::protocol::{MapEncodable, MapDecodable, Encoder, Decoder, CachedStruct, SizeCache, write_unknown_tagged_fields, compute_unknown_tagged_fields_size};
+use crate::error::ResponseError;
+#[cfg(feature = "json")]
+use crate::json::{JsonError, RecordsJson};
+#[cfg(feature = "json")]
+use crate::json::JsonValue;
+
+
+/// Valid versions: 0-1
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct PartitionsSchema {
+    /// 
+    /// 
+    /// Supported API versions: 0-1
+    pub topic: super::TopicName,
+
+    /// 
+    /// 
+    /// Supported API versions: 0-1
+    pub partition_ids: Vec<i32>,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for PartitionsSchema {
+    type Builder = PartitionsSchemaBuilder;
+
+    fn builder() -> Self::Builder{
+        PartitionsSchemaBuilder::default()
+    }
+}
+
+impl Encodable for PartitionsSchema {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        if version >= 1 {
+            types::CompactString.encode(buf, &self.topic).map_err(|e| e.with_field("PartitionsSchema", "topic", version))?;
+        } else {
+            types::String.encode(buf, &self.topic).map_err(|e| e.with_field("PartitionsSchema", "topic", version))?;
+        }
+        if version >= 1 {
+            types::CompactArray(types::Int32).encode(buf, &self.partition_ids).map_err(|e| e.with_field("PartitionsSchema", "partition_ids", version))?;
+        } else {
+            types::Array(types::Int32).encode(buf, &self.partition_ids).map_err(|e| e.with_field("PartitionsSchema", "partition_ids", version))?;
+        }
+        if version >= 1 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionsSchema", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("PartitionsSchema", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionsSchema", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        if version >= 1 {
+            total_size += types::CompactString.compute_size(&self.topic).map_err(|e| e.with_field("PartitionsSchema", "topic", version))?;
+        } else {
+            total_size += types::String.compute_size(&self.topic).map_err(|e| e.with_field("PartitionsSchema", "topic", version))?;
+        }
+        if version >= 1 {
+            total_size += types::CompactArray(types::Int32).compute_size(&self.partition_ids).map_err(|e| e.with_field("PartitionsSchema", "partition_ids", version))?;
+        } else {
+            total_size += types::Array(types::Int32).compute_size(&self.partition_ids).map_err(|e| e.with_field("PartitionsSchema", "partition_ids", version))?;
+        }
+        if version >= 1 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("PartitionsSchema", "unknown_tagged_fields", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("PartitionsSchema", "unknown_tagged_fields", version))?;
+
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("PartitionsSchema", "unknown_tagged_fields", version))?;
+        }
+        Ok(total_size)
+    }
+}
+
+impl Decodable for PartitionsSchema {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let topic = if version >= 1 {
+            types::CompactString.decode(buf).map_err(|e| e.with_field("PartitionsSchema", "topic", version, start - buf.remaining()))?
+        } else {
+            types::String.decode(buf).map_err(|e| e.with_field("PartitionsSchema", "topic", version, start - buf.remaining()))?
+        };
+        let partition_ids = if version >= 1 {
+            types::CompactArray(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionsSchema", "partition_ids", version, start - buf.remaining()))?
+        } else {
+            types::Array(types::Int32).decode(buf).map_err(|e| e.with_field("PartitionsSchema", "partition_ids", version, start - buf.remaining()))?
+        };
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        if version >= 1 {
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionsSchema", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            for _ in 0..num_tagged_fields {
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionsSchema", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("PartitionsSchema", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("PartitionsSchema", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                unknown_tagged_fields.insert(tag as i32, unknown_value);
+            }
+        }
+        Ok(Self {
+            topic,
+            partition_ids,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for PartitionsSchema {
+    fn default() -> Self {
+        Self {
+            topic: Default::default(),
+            partition_ids: Default::default(),
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for PartitionsSchema {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
+}
+
+impl PartitionsSchema {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartitionsSchema {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("topic".into(), JsonValue::to_json(&self.topic));
+        node.insert("partitionIds".into(), JsonValue::to_json(&self.partition_ids));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "PartitionsSchema")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("topic") {
+            this.topic = JsonValue::from_json(value, "PartitionsSchema.topic")?;
+        } else {
+            return Err(crate::json::missing("PartitionsSchema", "topic", version));
+        }
+        if let Some(value) = object.get("partitionIds") {
+            this.partition_ids = JsonValue::from_json(value, "PartitionsSchema.partitionIds")?;
+        } else {
+            return Err(crate::json::missing("PartitionsSchema", "partitionIds", version));
+        }
+        Ok(this)
+    }
+}
+
+/// Valid versions: 0-1
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, derive_builder::Builder)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[builder(default)]
+pub struct TransactionLogValue {
+    /// Producer id in use by the transactional id
+    /// 
+    /// Supported API versions: 0-1
+    pub producer_id: super::ProducerId,
+
+    /// Epoch associated with the producer id
+    /// 
+    /// Supported API versions: 0-1
+    pub producer_epoch: i16,
+
+    /// Transaction timeout in milliseconds
+    /// 
+    /// Supported API versions: 0-1
+    pub transaction_timeout_ms: i32,
+
+    /// TransactionState the transaction is in
+    /// 
+    /// Supported API versions: 0-1
+    pub transaction_status: i8,
+
+    /// Set of partitions involved in the transaction
+    /// 
+    /// Supported API versions: 0-1
+    /// May be `None` in versions: 0-1
+    pub transaction_partitions: Option<Vec<PartitionsSchema>>,
+
+    /// Time the transaction was last updated
+    /// 
+    /// Supported API versions: 0-1
+    pub transaction_last_update_timestamp_ms: i64,
+
+    /// Time the transaction was started
+    /// 
+    /// Supported API versions: 0-1
+    pub transaction_start_timestamp_ms: i64,
+
+    /// Other tagged fields
+    pub unknown_tagged_fields: UnknownTaggedFields,
+}
+
+impl Builder for TransactionLogValue {
+    type Builder = TransactionLogValueBuilder;
+
+    fn builder() -> Self::Builder{
+        TransactionLogValueBuilder::default()
+    }
+}
+
+impl Encodable for TransactionLogValue {
+    fn encode<B: ByteBufMut>(&self, buf: &mut B, version: i16) -> Result<(), EncodeError> {
+        self.encode_cached(buf, version, &SizeCache::default())
+    }
+    fn compute_size(&self, version: i16) -> Result<usize, EncodeError> {
+        self.compute_size_cached(version, &SizeCache::default())
+    }
+    fn encode_cached<B: ByteBufMut>(&self, buf: &mut B, version: i16, cache: &SizeCache) -> Result<(), EncodeError> {
+        types::Int64.encode(buf, &self.producer_id).map_err(|e| e.with_field("TransactionLogValue", "producer_id", version))?;
+        types::Int16.encode(buf, &self.producer_epoch).map_err(|e| e.with_field("TransactionLogValue", "producer_epoch", version))?;
+        types::Int32.encode(buf, &self.transaction_timeout_ms).map_err(|e| e.with_field("TransactionLogValue", "transaction_timeout_ms", version))?;
+        types::Int8.encode(buf, &self.transaction_status).map_err(|e| e.with_field("TransactionLogValue", "transaction_status", version))?;
+        if version >= 1 {
+            types::CompactArray(CachedStruct { version, cache }).encode(buf, &self.transaction_partitions).map_err(|e| e.with_field("TransactionLogValue", "transaction_partitions", version))?;
+        } else {
+            types::Array(CachedStruct { version, cache }).encode(buf, &self.transaction_partitions).map_err(|e| e.with_field("TransactionLogValue", "transaction_partitions", version))?;
+        }
+        types::Int64.encode(buf, &self.transaction_last_update_timestamp_ms).map_err(|e| e.with_field("TransactionLogValue", "transaction_last_update_timestamp_ms", version))?;
+        types::Int64.encode(buf, &self.transaction_start_timestamp_ms).map_err(|e| e.with_field("TransactionLogValue", "transaction_start_timestamp_ms", version))?;
+        if version >= 1 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TransactionLogValue", "unknown_tagged_fields", version));
+            }
+            types::UnsignedVarInt.encode(buf, num_tagged_fields as u32).map_err(|e| e.with_field("TransactionLogValue", "unknown_tagged_fields", version))?;
+
+            write_unknown_tagged_fields(buf, 0.., &self.unknown_tagged_fields).map_err(|e| e.with_field("TransactionLogValue", "unknown_tagged_fields", version))?;
+        }
+        Ok(())
+    }
+    fn compute_size_cached(&self, version: i16, cache: &SizeCache) -> Result<usize, EncodeError> {
+        let mut total_size = 0;
+        total_size += types::Int64.compute_size(&self.producer_id).map_err(|e| e.with_field("TransactionLogValue", "producer_id", version))?;
+        total_size += types::Int16.compute_size(&self.producer_epoch).map_err(|e| e.with_field("TransactionLogValue", "producer_epoch", version))?;
+        total_size += types::Int32.compute_size(&self.transaction_timeout_ms).map_err(|e| e.with_field("TransactionLogValue", "transaction_timeout_ms", version))?;
+        total_size += types::Int8.compute_size(&self.transaction_status).map_err(|e| e.with_field("TransactionLogValue", "transaction_status", version))?;
+        if version >= 1 {
+            total_size += types::CompactArray(CachedStruct { version, cache }).compute_size(&self.transaction_partitions).map_err(|e| e.with_field("TransactionLogValue", "transaction_partitions", version))?;
+        } else {
+            total_size += types::Array(CachedStruct { version, cache }).compute_size(&self.transaction_partitions).map_err(|e| e.with_field("TransactionLogValue", "transaction_partitions", version))?;
+        }
+        total_size += types::Int64.compute_size(&self.transaction_last_update_timestamp_ms).map_err(|e| e.with_field("TransactionLogValue", "transaction_last_update_timestamp_ms", version))?;
+        total_size += types::Int64.compute_size(&self.transaction_start_timestamp_ms).map_err(|e| e.with_field("TransactionLogValue", "transaction_start_timestamp_ms", version))?;
+        if version >= 1 {
+            let num_tagged_fields = self.unknown_tagged_fields.len();
+            if num_tagged_fields > std::u32::MAX as usize {
+                return Err(EncodeError::new(EncodeErrorKind::ValueTooLarge).with_field("TransactionLogValue", "unknown_tagged_fields", version));
+            }
+            total_size += types::UnsignedVarInt.compute_size(num_tagged_fields as u32).map_err(|e| e.with_field("TransactionLogValue", "unknown_tagged_fields", version))?;
+
+            total_size += compute_unknown_tagged_fields_size(&self.unknown_tagged_fields).map_err(|e| e.with_field("TransactionLogValue", "unknown_tagged_fields", version))?;
+        }
+        Ok(total_size)
+    }
+}
+
+impl Decodable for TransactionLogValue {
+    fn decode<B: ByteBuf>(buf: &mut B, version: i16) -> Result<Self, DecodeError> {
+        let start = buf.remaining();
+        let producer_id = types::Int64.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "producer_id", version, start - buf.remaining()))?;
+        let producer_epoch = types::Int16.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "producer_epoch", version, start - buf.remaining()))?;
+        let transaction_timeout_ms = types::Int32.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "transaction_timeout_ms", version, start - buf.remaining()))?;
+        let transaction_status = types::Int8.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "transaction_status", version, start - buf.remaining()))?;
+        let transaction_partitions = if version >= 1 {
+            types::CompactArray(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TransactionLogValue", "transaction_partitions", version, start - buf.remaining()))?
+        } else {
+            types::Array(types::Struct { version }).decode(buf).map_err(|e| e.with_field("TransactionLogValue", "transaction_partitions", version, start - buf.remaining()))?
+        };
+        let transaction_last_update_timestamp_ms = types::Int64.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "transaction_last_update_timestamp_ms", version, start - buf.remaining()))?;
+        let transaction_start_timestamp_ms = types::Int64.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "transaction_start_timestamp_ms", version, start - buf.remaining()))?;
+        let mut unknown_tagged_fields = UnknownTaggedFields::new();
+        if version >= 1 {
+            let num_tagged_fields = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+            for _ in 0..num_tagged_fields {
+                let tag: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let size: u32 = types::UnsignedVarInt.decode(buf).map_err(|e| e.with_field("TransactionLogValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                let unknown_value = buf.try_get_bytes(size as usize).map_err(DecodeError::from).map_err(|e| e.with_field("TransactionLogValue", "unknown_tagged_fields", version, start - buf.remaining()))?;
+                unknown_tagged_fields.insert(tag as i32, unknown_value);
+            }
+        }
+        Ok(Self {
+            producer_id,
+            producer_epoch,
+            transaction_timeout_ms,
+            transaction_status,
+            transaction_partitions,
+            transaction_last_update_timestamp_ms,
+            transaction_start_timestamp_ms,
+            unknown_tagged_fields,
+        })
+    }
+}
+
+impl Default for TransactionLogValue {
+    fn default() -> Self {
+        Self {
+            producer_id: (0).into(),
+            producer_epoch: 0,
+            transaction_timeout_ms: 0,
+            transaction_status: 0,
+            transaction_partitions: Some(Default::default()),
+            transaction_last_update_timestamp_ms: 0,
+            transaction_start_timestamp_ms: 0,
+            unknown_tagged_fields: UnknownTaggedFields::new(),
+        }
+    }
+}
+
+impl Message for TransactionLogValue {
+    const VERSIONS: VersionRange = VersionRange { min: 0, max: 1 };
+}
+
+impl TransactionLogValue {
+    /// Lists the fields set to non-default values which are not valid in `version`.
+    ///
+    /// Such fields are dropped or rejected when encoding at `version`. Nested fields are reported
+    /// by their path, e.g. `topics[0].partitions[1].current_leader_epoch`.
+    pub fn validate(&self, version: i16) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.validate_into(version, "", &mut invalid);
+        invalid
+    }
+
+    /// Resets every field which is not valid in `version` to its default value, so that the
+    /// message encodes at `version` without any of its fields being dropped or rejected.
+    pub fn normalize_to(&mut self, version: i16) {
+        if let Some(items) = &mut self.transaction_partitions {
+            for item in items.iter_mut() {
+                item.normalize_to(version);
+            }
+        }
+    }
+
+    fn validate_into(&self, version: i16, path: &str, invalid: &mut Vec<String>) {
+        if let Some(items) = &self.transaction_partitions {
+            for (i, item) in items.iter().enumerate() {
+                item.validate_into(version, &format!("{}transaction_partitions[{}].", path, i), invalid);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TransactionLogValue {
+    /// Converts this message to the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields which are not valid in `version` are left out, see [`Self::validate`].
+    ///
+    /// Records are written as their size, see [`Self::to_json_with`] to write them otherwise.
+    pub fn to_json(&self, version: i16) -> serde_json::Value {
+        self.to_json_with(version, RecordsJson::SizeInBytes)
+    }
+
+    /// Converts this message to its JSON representation for `version` like [`Self::to_json`],
+    /// writing records as `records` says.
+    pub fn to_json_with(&self, version: i16, records: RecordsJson) -> serde_json::Value {
+        let mut node = serde_json::Map::new();
+        node.insert("producerId".into(), JsonValue::to_json(&self.producer_id));
+        node.insert("producerEpoch".into(), JsonValue::to_json(&self.producer_epoch));
+        node.insert("transactionTimeoutMs".into(), JsonValue::to_json(&self.transaction_timeout_ms));
+        node.insert("transactionStatus".into(), JsonValue::to_json(&self.transaction_status));
+        node.insert("transactionPartitions".into(), self.transaction_partitions.as_ref().map(|items| serde_json::Value::Array(items.iter().map(|item| item.to_json_with(version, records)).collect())).unwrap_or(serde_json::Value::Null));
+        node.insert("transactionLastUpdateTimestampMs".into(), JsonValue::to_json(&self.transaction_last_update_timestamp_ms));
+        node.insert("transactionStartTimestampMs".into(), JsonValue::to_json(&self.transaction_start_timestamp_ms));
+        serde_json::Value::Object(node)
+    }
+
+    /// Reads this message from the JSON representation written by Kafka's `JsonConverter` for
+    /// `version`. Fields missing from `node` take their default value, unless mandatory in `version`.
+    pub fn from_json(node: &serde_json::Value, version: i16) -> Result<Self, JsonError> {
+        let object = crate::json::object(node, "TransactionLogValue")?;
+        let mut this = Self::default();
+        if let Some(value) = object.get("producerId") {
+            this.producer_id = JsonValue::from_json(value, "TransactionLogValue.producerId")?;
+        } else {
+            return Err(crate::json::missing("TransactionLogValue", "producerId", version));
+        }
+        if let Some(value) = object.get("producerEpoch") {
+            this.producer_epoch = JsonValue::from_json(value, "TransactionLogValue.producerEpoch")?;
+        } else {
+            return Err(crate::json::missing("TransactionLogValue", "producerEpoch", version));
+        }
+        if let Some(value) = object.get("transactionTimeoutMs") {
+            this.transaction_timeout_ms = JsonValue::from_json(value, "TransactionLogValue.transactionTimeoutMs")?;
+        } else {
+            return Err(crate::json::missing("TransactionLogValue", "transactionTimeoutMs", version));
+        }
+        if let Some(value) = object.get("transactionStatus") {
+            this.transaction_status = JsonValue::from_json(value, "TransactionLogValue.transactionStatus")?;
+        } else {
+            return Err(crate::json::missing("TransactionLogValue", "transactionStatus", version));
+        }
+        if let Some(value) = object.get("transactionPartitions") {
+            this.transaction_partitions = if value.is_null() { None } else { Some(crate::json::array(value, "TransactionLogValue.transactionPartitions")?.iter().map(|item| PartitionsSchema::from_json(item, version)).collect::<Result<_, _>>()?) };
+        } else {
+            return Err(crate::json::missing("TransactionLogValue", "transactionPartitions", version));
+        }
+        if let Some(value) = object.get("transactionLastUpdateTimestampMs") {
+            this.transaction_last_update_timestamp_ms = JsonValue::from_json(value, "TransactionLogValue.transactionLastUpdateTimestampMs")?;
+        } else {
+            return Err(crate::json::missing("TransactionLogValue", "transactionLastUpdateTimestampMs", version));
+        }
+        if let Some(value) = object.get("transactionStartTimestampMs") {
+            this.transaction_start_timestamp_ms = JsonValue::from_json(value, "TransactionLogValue.transactionStartTimestampMs")?;
+        } else {
+            return Err(crate::json::missing("TransactionLogValue", "transactionStartTimestampMs", version));
+        }
+        Ok(this)
+    }
+}
+
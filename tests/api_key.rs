@@ -0,0 +1,66 @@
+// The key count below only holds with every API family enabled
+#![cfg(all(
+    feature = "client-core",
+    feature = "admin",
+    feature = "consumer-group",
+    feature = "transactions",
+    feature = "broker-internal",
+    feature = "raft"
+))]
+
+use kafka_protocol::messages::consumer_group_heartbeat_request::Assignor;
+use kafka_protocol::messages::{ApiKey, ConsumerGroupHeartbeatRequest};
+use kafka_protocol::protocol::ListenerType;
+use kafka_protocol::protocol::{Encodable, Message};
+use std::convert::TryFrom;
+
+#[test]
+fn api_key_metadata() {
+    assert_eq!(ApiKey::all().len(), 69);
+    for key in ApiKey::all() {
+        assert_eq!(ApiKey::try_from(*key as i16), Ok(*key));
+    }
+
+    let key = ApiKey::FetchKey;
+    assert_eq!(key.name(), "Fetch");
+    assert_eq!(key.valid_versions().min, 0);
+    assert_eq!(key.valid_versions().max, 15);
+    assert!(!key.flexible_versions().contains(11));
+    assert!(key.flexible_versions().contains(12));
+    assert!(key.listeners().contains(&ListenerType::Controller));
+
+    assert!(ApiKey::SaslHandshakeKey.flexible_versions().is_empty());
+    assert_eq!(
+        ApiKey::LeaderAndIsrKey.listeners(),
+        &[ListenerType::ZkBroker]
+    );
+
+    assert!(ApiKey::ConsumerGroupHeartbeatKey.is_unstable(0));
+    assert!(!ApiKey::ProduceKey.is_unstable(ApiKey::ProduceKey.valid_versions().max));
+}
+
+#[test]
+fn unstable_versions_require_feature() {
+    let key = ApiKey::ConsumerGroupHeartbeatKey;
+    assert_eq!(
+        key.unstable_versions().map(|v| (v.min, v.max)),
+        Some((0, 0))
+    );
+    assert!(ApiKey::ProduceKey.unstable_versions().is_none());
+    assert_eq!(
+        key.valid_versions().contains(0),
+        cfg!(feature = "unstable-apis")
+    );
+    assert_eq!(
+        key.flexible_versions().contains(0),
+        cfg!(feature = "unstable-apis")
+    );
+    assert_eq!(
+        Assignor::VERSIONS.contains(0),
+        cfg!(feature = "unstable-apis")
+    );
+
+    let mut buf = bytes::BytesMut::new();
+    let result = ConsumerGroupHeartbeatRequest::default().encode(&mut buf, 0);
+    assert_eq!(result.is_ok(), cfg!(feature = "unstable-apis"));
+}
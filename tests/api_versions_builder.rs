@@ -0,0 +1,49 @@
+#![cfg(all(feature = "client-core", feature = "consumer-group", feature = "raft"))]
+
+use bytes::BytesMut;
+use kafka_protocol::api_versions::ApiVersionsBuilder;
+use kafka_protocol::messages::{ApiKey, ApiVersionsResponse};
+use kafka_protocol::protocol::{Decodable, Encodable, ListenerType, StrBytes};
+
+#[test]
+fn api_versions_builder() {
+    let builder = ApiVersionsBuilder::new(ListenerType::Controller)
+        .deny([ApiKey::FetchSnapshotKey])
+        .supported_feature(StrBytes::from_str("metadata.version"), 1, 14)
+        .finalized_features_epoch(5);
+    let response = builder.build(3);
+
+    let vote = &response.api_keys[&(ApiKey::VoteKey as i16)];
+    assert_eq!(vote.min_version, 0);
+    assert_eq!(vote.max_version, ApiKey::VoteKey.valid_versions().max);
+    assert!(!response.api_keys.contains_key(&(ApiKey::ProduceKey as i16)));
+    assert!(!response
+        .api_keys
+        .contains_key(&(ApiKey::FetchSnapshotKey as i16)));
+    assert_eq!(response.supported_features.len(), 1);
+    assert_eq!(response.finalized_features_epoch, 5);
+
+    let mut buf = BytesMut::new();
+    response.encode(&mut buf, 3).unwrap();
+    assert_eq!(ApiVersionsResponse::decode(&mut buf, 3).unwrap(), response);
+
+    let legacy = builder.build(0);
+    assert!(legacy.supported_features.is_empty());
+    assert_eq!(legacy.finalized_features_epoch, -1);
+}
+
+#[test]
+fn api_versions_builder_allow_and_unstable() {
+    let builder = ApiVersionsBuilder::new(ListenerType::Broker)
+        .allow([ApiKey::ApiVersionsKey, ApiKey::ConsumerGroupHeartbeatKey]);
+    assert_eq!(builder.build(3).api_keys.len(), 1);
+    assert!(!builder.is_advertised(ApiKey::ConsumerGroupHeartbeatKey));
+
+    let builder = builder.enable_unstable(true);
+    let expected = if cfg!(feature = "unstable-apis") {
+        2
+    } else {
+        1
+    };
+    assert_eq!(builder.build(3).api_keys.len(), expected);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "client-core")]
+
+use bytes::{Bytes, BytesMut};
+use kafka_protocol::messages::fetch_response::{FetchableTopicResponse, PartitionData};
+use kafka_protocol::messages::{FetchResponse, TopicName};
+use kafka_protocol::protocol::{
+    Builder, Decodable, DecodeErrorKind, Encodable, EncodeErrorKind, StrBytes,
+};
+
+fn fetch_response() -> FetchResponse {
+    let topic = |name: &'static str, records: &'static [u8]| {
+        let partition = PartitionData::builder()
+            .records(Some(Bytes::from_static(records)))
+            .build()
+            .unwrap();
+        FetchableTopicResponse::builder()
+            .topic(TopicName(StrBytes::from_str(name)))
+            .partitions(vec![partition])
+            .build()
+            .unwrap()
+    };
+    FetchResponse::builder()
+        .responses(vec![topic("first", b"abc"), topic("second", b"defghijkl")])
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn decode_error_reports_field_path_and_offset() {
+    let mut buf = BytesMut::new();
+    fetch_response().encode(&mut buf, 4).unwrap();
+    let truncated = buf.len() - 4;
+    let mut buf = buf.freeze().slice(..truncated);
+
+    let err = FetchResponse::decode(&mut buf, 4).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::NotEnoughBytes);
+    assert_eq!(err.message_type(), Some("FetchResponse"));
+    assert_eq!(err.version(), Some(4));
+    assert_eq!(
+        err.field_path(),
+        "FetchResponse.responses[1].partitions[0].records"
+    );
+    // The records length prefix was consumed, the truncated records were not.
+    assert_eq!(err.offset(), Some(truncated - 5));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "DecodeError: not enough bytes in FetchResponse.responses[1].partitions[0].records (v4) at offset {}",
+            truncated - 5
+        )
+    );
+}
+
+#[test]
+fn decode_error_reports_unsupported_version() {
+    let err = FetchResponse::decode(&mut Bytes::new(), 100).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::UnsupportedVersion);
+    assert_eq!(err.message_type(), Some("FetchResponse"));
+}
+
+#[test]
+fn encode_error_reports_field_not_valid_at_version() {
+    let mut response = fetch_response();
+    response.session_id = 1;
+    let err = response.encode(&mut BytesMut::new(), 6).unwrap_err();
+    assert_eq!(err.kind(), EncodeErrorKind::FieldNotValidAtVersion);
+    assert_eq!(err.field_path(), "FetchResponse.session_id");
+    assert_eq!(err.version(), Some(6));
+    assert!(response.encode(&mut BytesMut::new(), 7).is_ok());
+}
@@ -0,0 +1,145 @@
+#![cfg(feature = "client-core")]
+
+use bytes::{Bytes, BytesMut};
+use kafka_protocol::messages::metadata_response::{
+    MetadataResponsePartition, MetadataResponseTopic,
+};
+use kafka_protocol::messages::{MetadataRequest, MetadataResponse, TopicName};
+use kafka_protocol::protocol::buf::{DecodeLimits, LimitedBuf};
+use kafka_protocol::protocol::{Builder, Decodable, DecodeErrorKind, Encodable, StrBytes};
+use kafka_protocol::records::{
+    Compression, Record, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions, TimestampType,
+};
+
+fn metadata_response() -> Bytes {
+    let mut response = MetadataResponse::default();
+    response.topics.insert(
+        TopicName(StrBytes::from_str("events")),
+        MetadataResponseTopic::builder()
+            .partitions(vec![MetadataResponsePartition::default(); 16])
+            .build()
+            .unwrap(),
+    );
+    let mut buf = BytesMut::new();
+    response.encode(&mut buf, 9).unwrap();
+    buf.freeze()
+}
+
+#[test]
+fn hostile_array_length_does_not_allocate() {
+    // A MetadataRequest v1 claiming i32::MAX topics.
+    let request: &'static [u8] = &[
+        0x7f, 0xff, 0xff, 0xff, 0x00, 0x06, b'e', b'v', b'e', b'n', b't', b's',
+    ];
+    let err = MetadataRequest::decode(&mut Bytes::from_static(request), 1).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::NotEnoughBytes);
+
+    let err = MetadataRequest::decode_with_limits(
+        &mut Bytes::from_static(request),
+        1,
+        DecodeLimits::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::LimitExceeded);
+}
+
+#[test]
+fn hostile_string_length() {
+    // A MetadataRequest v1 with one topic, whose name claims to be 32767 bytes long.
+    let request: &'static [u8] = &[0x00, 0x00, 0x00, 0x01, 0x7f, 0xff, b'e'];
+    let limits = DecodeLimits {
+        max_bytes_len: 1024,
+        ..Default::default()
+    };
+    let err = MetadataRequest::decode_with_limits(&mut Bytes::from_static(request), 1, limits)
+        .unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::LimitExceeded);
+}
+
+#[test]
+fn limits_on_well_formed_messages() {
+    let encoded = metadata_response();
+    let decode = |limits| MetadataResponse::decode_with_limits(&mut encoded.clone(), 9, limits);
+    assert!(decode(DecodeLimits::default()).is_ok());
+
+    let err = decode(DecodeLimits {
+        max_array_len: 8,
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::LimitExceeded);
+
+    // Topics are nested once, and their partitions twice.
+    let depth = |max_depth| {
+        decode(DecodeLimits {
+            max_depth,
+            ..Default::default()
+        })
+    };
+    assert!(depth(2).is_ok());
+    assert_eq!(depth(1).unwrap_err().kind(), DecodeErrorKind::LimitExceeded);
+
+    let err = decode(DecodeLimits {
+        max_allocation: 256,
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::LimitExceeded);
+}
+
+#[test]
+fn shared_bytes_are_not_allocations() {
+    let encoded = metadata_response();
+
+    let mut shared = LimitedBuf::new(encoded.clone(), DecodeLimits::default());
+    MetadataResponse::decode(&mut shared, 9).unwrap();
+    let mut copied = LimitedBuf::new(&encoded[..], DecodeLimits::default());
+    MetadataResponse::decode(&mut copied, 9).unwrap();
+
+    // Only the copy of the topic name is allocated for.
+    assert_eq!(copied.allocated() - shared.allocated(), "events".len());
+}
+
+fn compressed_records(compression: Compression) -> Bytes {
+    let record = Record {
+        transactional: false,
+        control: false,
+        partition_leader_epoch: 0,
+        producer_id: 0,
+        producer_epoch: 0,
+        timestamp_type: TimestampType::Creation,
+        offset: 0,
+        sequence: 0,
+        timestamp: 0,
+        key: None,
+        value: Some(Bytes::from(vec![0; 1 << 20])),
+        headers: Default::default(),
+    };
+    let options = RecordEncodeOptions {
+        version: 2,
+        compression,
+    };
+    let mut buf = BytesMut::new();
+    RecordBatchEncoder::encode(&mut buf, std::iter::once(&record), &options).unwrap();
+    buf.freeze()
+}
+
+#[test]
+fn decompressed_records_count_against_the_allocation() {
+    for compression in [Compression::Gzip, Compression::Snappy] {
+        let encoded = compressed_records(compression);
+        assert!(encoded.len() < 64 * 1024);
+
+        let records =
+            RecordBatchDecoder::decode_with_limits(&mut encoded.clone(), DecodeLimits::default())
+                .unwrap();
+        assert_eq!(records[0].value.as_ref().unwrap().len(), 1 << 20);
+
+        let limits = DecodeLimits {
+            max_allocation: 512 * 1024,
+            ..Default::default()
+        };
+        let err = RecordBatchDecoder::decode_with_limits(&mut encoded.clone(), limits).unwrap_err();
+        assert_eq!(err.kind(), DecodeErrorKind::LimitExceeded);
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "client-core")]
+
+use bytes::BytesMut;
+use kafka_protocol::messages::fetch_response::{FetchableTopicResponse, PartitionData};
+use kafka_protocol::messages::metadata_response::{
+    MetadataResponsePartition, MetadataResponseTopic,
+};
+use kafka_protocol::messages::{BrokerId, FetchResponse, MetadataResponse, TopicName};
+use kafka_protocol::protocol::{Builder, Decodable, Encodable, StrBytes};
+use uuid::Uuid;
+
+#[test]
+fn encode_to_bytes_matches_compute_size() {
+    let partitions: Vec<_> = (0..100_000)
+        .map(|i| {
+            MetadataResponsePartition::builder()
+                .partition_index(i)
+                .leader_id(BrokerId(1))
+                .replica_nodes(vec![BrokerId(1), BrokerId(2), BrokerId(3)])
+                .isr_nodes(vec![BrokerId(1), BrokerId(2)])
+                .build()
+                .unwrap()
+        })
+        .collect();
+    let mut response = MetadataResponse::default();
+    response.topics.insert(
+        TopicName(StrBytes::from_str("events")),
+        MetadataResponseTopic::builder()
+            .partitions(partitions)
+            .build()
+            .unwrap(),
+    );
+
+    for version in [0, 9, 12] {
+        let size = response.compute_size(version).unwrap();
+        let bytes = response.encode_to_bytes(version).unwrap();
+        assert_eq!(bytes.len(), size);
+        let decoded = MetadataResponse::decode(&mut bytes.clone(), version).unwrap();
+        assert_eq!(decoded.topics.len(), 1);
+        assert_eq!(decoded.topics[0].partitions.len(), 100_000);
+    }
+}
+
+#[test]
+fn encode_to_bytes_allocates_exact_size() {
+    let partition = |i: i32| {
+        let mut partition = PartitionData::default();
+        partition.partition_index = i;
+        partition.diverging_epoch.epoch = i;
+        partition.current_leader.leader_id = BrokerId(1);
+        partition.snapshot_id.end_offset = i64::from(i);
+        partition
+    };
+    let mut response = FetchResponse::default();
+    response.responses = vec![FetchableTopicResponse::builder()
+        .topic_id(Uuid::from_u128(1))
+        .partitions((0..1_000).map(partition).collect())
+        .build()
+        .unwrap()];
+
+    let bytes = response.encode_to_bytes(13).unwrap();
+    let mut expected = BytesMut::new();
+    response.encode(&mut expected, 13).unwrap();
+    assert_eq!(bytes, expected);
+    // The buffer is unique, so converting it back to a `Vec` keeps its allocation
+    let len = bytes.len();
+    assert_eq!(Vec::from(bytes).capacity(), len);
+}
@@ -0,0 +1,254 @@
+#![cfg(all(feature = "admin", feature = "client-core", feature = "consumer-group"))]
+
+use bytes::BytesMut;
+use kafka_protocol::error::ResponseError;
+use kafka_protocol::messages::create_topics_request::CreatableTopic;
+use kafka_protocol::messages::delete_topics_request::DeleteTopicState;
+use kafka_protocol::messages::fetch_request::{FetchPartition, FetchTopic};
+use kafka_protocol::messages::metadata_request::MetadataRequestTopic;
+use kafka_protocol::messages::offset_commit_request::{
+    OffsetCommitRequestPartition, OffsetCommitRequestTopic,
+};
+use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
+use kafka_protocol::messages::{
+    CreateTopicsRequest, DeleteTopicsRequest, FetchRequest, MetadataRequest, OffsetCommitRequest,
+    ProduceRequest, TopicName,
+};
+use kafka_protocol::protocol::{Builder, Encodable, Message, StrBytes, VersionRange};
+use uuid::Uuid;
+
+#[test]
+fn produce_error_response() {
+    let partitions = (0..3)
+        .map(|index| {
+            PartitionProduceData::builder()
+                .index(index)
+                .build()
+                .unwrap()
+        })
+        .collect();
+    let topic = TopicProduceData::builder()
+        .partition_data(partitions)
+        .build()
+        .unwrap();
+    let name = TopicName(StrBytes::from_str("events"));
+    let request = ProduceRequest::builder()
+        .topic_data(std::iter::once((name.clone(), topic)).collect())
+        .build()
+        .unwrap();
+
+    let response = request.error_response(9, ResponseError::NotLeaderOrFollower);
+    let partitions = &response.responses[&name].partition_responses;
+    assert_eq!(partitions.len(), 3);
+    for (index, partition) in partitions.iter().enumerate() {
+        assert_eq!(partition.index, index as i32);
+        assert_eq!(
+            partition.error_code,
+            ResponseError::NotLeaderOrFollower.code()
+        );
+    }
+    response.encode(&mut BytesMut::new(), 9).unwrap();
+}
+
+#[test]
+fn delete_topics_error_response_per_version() {
+    let name = TopicName(StrBytes::from_str("events"));
+    let request = DeleteTopicsRequest::builder()
+        .topic_names(vec![name.clone()])
+        .topics(vec![DeleteTopicState::builder()
+            .name(Some(name.clone()))
+            .build()
+            .unwrap()])
+        .build()
+        .unwrap();
+
+    for version in [5, 6] {
+        let response = request.error_response(version, ResponseError::TopicAuthorizationFailed);
+        assert_eq!(response.responses.len(), 1);
+        assert_eq!(
+            response.responses[&name].error_code,
+            ResponseError::TopicAuthorizationFailed.code()
+        );
+        response.encode(&mut BytesMut::new(), version).unwrap();
+    }
+}
+
+const TOPIC_ID: Uuid = Uuid::from_u128(1);
+
+/// The resources reported by an error response, each with its error code.
+type Reported = Vec<(String, i16)>;
+
+/// An API whose error response to a request mentioning the topic `events`, and its partitions 0
+/// and 1 where the request has partitions, is checked in every version.
+struct Case {
+    api: &'static str,
+    versions: VersionRange,
+    /// Answers the request with `error` in the given version and lists the resources reported.
+    error_response: fn(i16, ResponseError) -> Reported,
+    /// The resources the response should report.
+    expected: &'static [&'static str],
+}
+
+const CASES: &[Case] = &[
+    Case {
+        api: "Fetch",
+        versions: FetchRequest::VERSIONS,
+        error_response: fetch_error_response,
+        expected: &["events-0", "events-1"],
+    },
+    Case {
+        api: "Metadata",
+        versions: MetadataRequest::VERSIONS,
+        error_response: metadata_error_response,
+        expected: &["events"],
+    },
+    Case {
+        api: "OffsetCommit",
+        versions: OffsetCommitRequest::VERSIONS,
+        error_response: offset_commit_error_response,
+        expected: &["events-0", "events-1"],
+    },
+    Case {
+        api: "CreateTopics",
+        versions: CreateTopicsRequest::VERSIONS,
+        error_response: create_topics_error_response,
+        expected: &["events"],
+    },
+];
+
+fn events() -> TopicName {
+    TopicName(StrBytes::from_str("events"))
+}
+
+/// Names a topic the response identifies by name or, in newer versions, by ID.
+fn topic_label(name: &TopicName, topic_id: Uuid) -> String {
+    if topic_id == TOPIC_ID {
+        "events".to_string()
+    } else {
+        name.0.to_string()
+    }
+}
+
+fn encode<T: Encodable>(response: &T, version: i16) {
+    response.encode(&mut BytesMut::new(), version).unwrap();
+}
+
+fn fetch_error_response(version: i16, error: ResponseError) -> Reported {
+    let partitions = (0..2)
+        .map(|partition| {
+            FetchPartition::builder()
+                .partition(partition)
+                .build()
+                .unwrap()
+        })
+        .collect();
+    let topic = FetchTopic::builder()
+        .topic(events())
+        .topic_id(TOPIC_ID)
+        .partitions(partitions)
+        .build()
+        .unwrap();
+    let request = FetchRequest::builder().topics(vec![topic]).build().unwrap();
+
+    let response = request.error_response(version, error);
+    encode(&response, version);
+    response
+        .responses
+        .iter()
+        .flat_map(|topic| {
+            let label = topic_label(&topic.topic, topic.topic_id);
+            topic.partitions.iter().map(move |partition| {
+                (
+                    format!("{}-{}", label, partition.partition_index),
+                    partition.error_code,
+                )
+            })
+        })
+        .collect()
+}
+
+fn metadata_error_response(version: i16, error: ResponseError) -> Reported {
+    let topic = MetadataRequestTopic::builder()
+        .name(Some(events()))
+        .topic_id(TOPIC_ID)
+        .build()
+        .unwrap();
+    let request = MetadataRequest::builder()
+        .topics(Some(vec![topic]))
+        .build()
+        .unwrap();
+
+    let response = request.error_response(version, error);
+    encode(&response, version);
+    response
+        .topics
+        .iter()
+        .map(|(name, topic)| (name.0.to_string(), topic.error_code))
+        .collect()
+}
+
+fn offset_commit_error_response(version: i16, error: ResponseError) -> Reported {
+    let partitions = (0..2)
+        .map(|partition| {
+            OffsetCommitRequestPartition::builder()
+                .partition_index(partition)
+                .build()
+                .unwrap()
+        })
+        .collect();
+    let topic = OffsetCommitRequestTopic::builder()
+        .name(events())
+        .partitions(partitions)
+        .build()
+        .unwrap();
+    let request = OffsetCommitRequest::builder()
+        .topics(vec![topic])
+        .build()
+        .unwrap();
+
+    let response = request.error_response(version, error);
+    encode(&response, version);
+    response
+        .topics
+        .iter()
+        .flat_map(|topic| {
+            topic.partitions.iter().map(move |partition| {
+                (
+                    format!("{}-{}", topic.name.0, partition.partition_index),
+                    partition.error_code,
+                )
+            })
+        })
+        .collect()
+}
+
+fn create_topics_error_response(version: i16, error: ResponseError) -> Reported {
+    let request = CreateTopicsRequest::builder()
+        .topics(std::iter::once((events(), CreatableTopic::default())).collect())
+        .build()
+        .unwrap();
+
+    let response = request.error_response(version, error);
+    encode(&response, version);
+    response
+        .topics
+        .iter()
+        .map(|(name, topic)| (name.0.to_string(), topic.error_code))
+        .collect()
+}
+
+#[test]
+fn error_responses_mirror_the_request() {
+    let error = ResponseError::NotLeaderOrFollower;
+    for case in CASES {
+        let expected: Reported = case
+            .expected
+            .iter()
+            .map(|resource| (resource.to_string(), error.code()))
+            .collect();
+        for version in case.versions.min..=case.versions.max {
+            let reported = (case.error_response)(version, error);
+            assert_eq!(reported, expected, "{} version {}", case.api, version);
+        }
+    }
+}
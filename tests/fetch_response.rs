@@ -1,3 +1,5 @@
+#![cfg(feature = "client-core")]
+
 use bytes::Bytes;
 use kafka_protocol::{messages::FetchResponse, protocol::Decodable, records::RecordBatchDecoder};
 
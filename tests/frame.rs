@@ -0,0 +1,178 @@
+#![cfg(feature = "client-core")]
+
+use bytes::{Bytes, BytesMut};
+use kafka_protocol::frame::{Decoded, ProduceRequestHead, RequestDecoder};
+use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
+use kafka_protocol::messages::{ApiKey, ProduceRequest, RequestHeader, TopicName};
+use kafka_protocol::protocol::{
+    Builder, Decodable, DecodeErrorKind, Encodable, HeaderVersion, StrBytes,
+};
+
+fn frame(request: &ProduceRequest, version: i16, correlation_id: i32) -> Bytes {
+    let header = RequestHeader::builder()
+        .request_api_key(ApiKey::ProduceKey as i16)
+        .request_api_version(version)
+        .correlation_id(correlation_id)
+        .client_id(Some(StrBytes::from_str("producer")))
+        .build()
+        .unwrap();
+    let mut body = BytesMut::new();
+    header
+        .encode(&mut body, ProduceRequest::header_version(version))
+        .unwrap();
+    request.encode(&mut body, version).unwrap();
+    let mut frame = BytesMut::new();
+    frame.extend_from_slice(&(body.len() as i32).to_be_bytes());
+    frame.extend_from_slice(&body);
+    frame.freeze()
+}
+
+fn request() -> ProduceRequest {
+    let partition = PartitionProduceData::builder()
+        .index(0)
+        .records(Some(Bytes::from(vec![1; 4096])))
+        .build()
+        .unwrap();
+    let mut request = ProduceRequest::builder()
+        .acks(-1)
+        .timeout_ms(1500)
+        .build()
+        .unwrap();
+    request.topic_data.insert(
+        TopicName(StrBytes::from_str("events")),
+        TopicProduceData::builder()
+            .partition_data(vec![partition])
+            .build()
+            .unwrap(),
+    );
+    request
+}
+
+#[test]
+fn decodes_byte_by_byte() {
+    let request = request();
+    for version in [3, 9] {
+        let frame = frame(&request, version, 7);
+        let mut decoder = RequestDecoder::new();
+        let mut src = BytesMut::new();
+        let mut header_at = None;
+        let mut head_at = None;
+        for (i, byte) in frame.iter().enumerate() {
+            assert!(matches!(
+                decoder.decode(&mut src).unwrap(),
+                Decoded::Incomplete(n) if n > 0
+            ));
+            src.extend_from_slice(&[*byte]);
+            if header_at.is_none() {
+                if let Decoded::Complete(header) = decoder.decode_header(&src).unwrap() {
+                    assert_eq!(header.correlation_id, 7);
+                    header_at = Some(i);
+                }
+            }
+            if head_at.is_none() {
+                if let Decoded::Complete(head) = decoder
+                    .decode_prefix(&src, |buf, version| {
+                        ProduceRequestHead::decode(buf, version)
+                    })
+                    .unwrap()
+                {
+                    assert_eq!(head.acks, -1);
+                    assert_eq!(head.timeout_ms, 1500);
+                    assert_eq!(
+                        head.topic_data,
+                        vec![TopicName(StrBytes::from_str("events"))]
+                    );
+                    head_at = Some(i);
+                }
+            }
+        }
+        assert!(header_at.unwrap() < head_at.unwrap());
+        // The head is complete once the last topic has been received, which is followed by the
+        // tagged fields of the request in flexible versions.
+        let trailing = if version >= 9 { 1 } else { 0 };
+        assert_eq!(head_at.unwrap(), frame.len() - 1 - trailing);
+
+        let (header, mut body) = decoder.decode(&mut src).unwrap().complete().unwrap();
+        assert!(src.is_empty());
+        assert_eq!(header.request_api_version, version);
+        assert_eq!(ProduceRequest::decode(&mut body, version).unwrap(), request);
+    }
+}
+
+#[test]
+fn exact_frame_sizes() {
+    let frame = frame(&request(), 9, 1);
+    let mut decoder = RequestDecoder::new();
+    let mut src = BytesMut::from(&frame[..2]);
+    assert_eq!(decoder.decode(&mut src).unwrap(), Decoded::Incomplete(2));
+    src.extend_from_slice(&frame[2..100]);
+    assert_eq!(
+        decoder.decode(&mut src).unwrap(),
+        Decoded::Incomplete(frame.len() - 100)
+    );
+
+    // Two frames received at once are decoded one after the other.
+    src.extend_from_slice(&frame[100..]);
+    src.extend_from_slice(&frame);
+    assert!(decoder.decode(&mut src).unwrap().complete().is_some());
+    assert_eq!(src.len(), frame.len());
+    assert!(decoder.decode(&mut src).unwrap().complete().is_some());
+    assert!(src.is_empty());
+}
+
+#[test]
+fn reports_the_rest_of_the_field_being_read() {
+    let frame = frame(&request(), 9, 1);
+    let mut decoder = RequestDecoder::new();
+    // The size, API key and version, correlation ID, client ID length and 3 bytes of the client ID
+    let src = BytesMut::from(&frame[..4 + 10 + 3]);
+    assert_eq!(decoder.decode_header(&src).unwrap(), Decoded::Incomplete(5));
+}
+
+#[test]
+fn reports_the_rest_of_the_records_being_skipped() {
+    let frame = frame(&request(), 9, 1);
+    let mut decoder = RequestDecoder::new();
+    // The records are followed by the tagged fields of the partition, topic and request.
+    let src = BytesMut::from(&frame[..frame.len() - 3 - 100]);
+    let head = decoder
+        .decode_prefix(&src, |buf, version| {
+            ProduceRequestHead::decode(buf, version)
+        })
+        .unwrap();
+    assert_eq!(head, Decoded::Incomplete(100));
+}
+
+#[test]
+fn truncated_frames_fail() {
+    let frame = frame(&request(), 9, 1);
+    let mut src = BytesMut::new();
+    src.extend_from_slice(&12i32.to_be_bytes());
+    src.extend_from_slice(&frame[4..16]);
+    let mut decoder = RequestDecoder::new();
+    assert!(decoder.decode_header(&src).is_err());
+}
+
+#[test]
+fn frames_over_the_limit_fail() {
+    let frame = frame(&request(), 9, 1);
+    // Only the length prefix is needed to reject the frame
+    let src = BytesMut::from(&frame[..4]);
+    let mut decoder = RequestDecoder::with_max_frame_size(frame.len() - 5);
+    let err = decoder.decode_header(&src).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::LimitExceeded);
+
+    let mut decoder = RequestDecoder::with_max_frame_size(frame.len() - 4);
+    let mut src = BytesMut::from(&frame[..]);
+    assert!(decoder.decode(&mut src).unwrap().complete().is_some());
+}
+
+#[test]
+fn unsupported_api_keys_fail() {
+    let mut src = BytesMut::new();
+    src.extend_from_slice(&4i32.to_be_bytes());
+    src.extend_from_slice(&i16::MAX.to_be_bytes());
+    src.extend_from_slice(&0i16.to_be_bytes());
+    let err = RequestDecoder::new().decode_header(&src).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::UnsupportedApiKey);
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "consumer-group")]
+
+use bytes::{BufMut, Bytes, BytesMut};
+use indexmap::IndexMap;
+use kafka_protocol::consumer_offsets::group_metadata_value::MemberMetadata;
+use kafka_protocol::consumer_offsets::{
+    ConsumerOffsetsRecord, GroupMetadataKey, GroupMetadataValue, OffsetCommitKey, OffsetCommitValue,
+};
+use kafka_protocol::messages::ProducerId;
+use kafka_protocol::protocol::{Builder, DecodeErrorKind, Encodable, StrBytes};
+use kafka_protocol::records::{Record, TimestampType};
+use kafka_protocol::transaction_state::{
+    TransactionLogKey, TransactionLogValue, TransactionStateRecord,
+};
+
+fn versioned<M: Encodable>(message: &M, version: i16) -> Bytes {
+    let mut buf = BytesMut::new();
+    buf.put_i16(version);
+    message.encode(&mut buf, version).unwrap();
+    buf.freeze()
+}
+
+fn record(key: Bytes, value: Option<Bytes>) -> Record {
+    Record {
+        transactional: false,
+        control: false,
+        partition_leader_epoch: 0,
+        producer_id: -1,
+        producer_epoch: -1,
+        timestamp_type: TimestampType::Creation,
+        offset: 0,
+        sequence: -1,
+        timestamp: 0,
+        key: Some(key),
+        value,
+        headers: IndexMap::new(),
+    }
+}
+
+fn offset_commit_key() -> OffsetCommitKey {
+    OffsetCommitKey::builder()
+        .group(StrBytes::from_str("group"))
+        .topic(StrBytes::from_str("events"))
+        .partition(3)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn offset_commit() {
+    let key = offset_commit_key();
+    let value = OffsetCommitValue::builder()
+        .offset(42)
+        .leader_epoch(5)
+        .commit_timestamp(1000)
+        .build()
+        .unwrap();
+
+    let decoded = ConsumerOffsetsRecord::decode_record(&record(
+        versioned(&key, 1),
+        Some(versioned(&value, 3)),
+    ))
+    .unwrap();
+    assert!(!decoded.is_tombstone());
+    assert_eq!(
+        decoded,
+        ConsumerOffsetsRecord::OffsetCommit {
+            key,
+            value: Some(value),
+        }
+    );
+}
+
+#[test]
+fn offset_commit_tombstone() {
+    let key = offset_commit_key();
+    let decoded = ConsumerOffsetsRecord::decode(versioned(&key, 0), None).unwrap();
+    assert!(decoded.is_tombstone());
+    assert_eq!(
+        decoded,
+        ConsumerOffsetsRecord::OffsetCommit { key, value: None }
+    );
+}
+
+#[test]
+fn group_metadata() {
+    let key = GroupMetadataKey::builder()
+        .group(StrBytes::from_str("group"))
+        .build()
+        .unwrap();
+    let member = MemberMetadata::builder()
+        .member_id(StrBytes::from_str("member-1"))
+        .client_id(StrBytes::from_str("client"))
+        .client_host(StrBytes::from_str("/127.0.0.1"))
+        .rebalance_timeout(300_000)
+        .session_timeout(45_000)
+        .subscription(Bytes::from_static(b"subscription"))
+        .assignment(Bytes::from_static(b"assignment"))
+        .build()
+        .unwrap();
+    let value = GroupMetadataValue::builder()
+        .protocol_type(StrBytes::from_str("consumer"))
+        .generation(7)
+        .protocol(Some(StrBytes::from_str("range")))
+        .leader(Some(StrBytes::from_str("member-1")))
+        .members(vec![member])
+        .build()
+        .unwrap();
+
+    for value_version in 0..=4 {
+        let mut expected = value.clone();
+        expected.normalize_to(value_version);
+        let decoded = ConsumerOffsetsRecord::decode(
+            versioned(&key, 2),
+            Some(versioned(&expected, value_version)),
+        )
+        .unwrap();
+        assert_eq!(
+            decoded,
+            ConsumerOffsetsRecord::GroupMetadata {
+                key: key.clone(),
+                value: Some(expected),
+            }
+        );
+    }
+}
+
+#[test]
+fn unknown_and_unsupported_versions() {
+    let key = Bytes::from_static(&[0, 9, 1, 2, 3]);
+    let decoded = ConsumerOffsetsRecord::decode(key, None).unwrap();
+    assert_eq!(
+        decoded,
+        ConsumerOffsetsRecord::Unknown {
+            version: 9,
+            key: Bytes::from_static(&[1, 2, 3]),
+            value: None,
+        }
+    );
+    assert!(decoded.is_tombstone());
+
+    let mut value = BytesMut::new();
+    value.put_i16(100);
+    let err =
+        ConsumerOffsetsRecord::decode(versioned(&offset_commit_key(), 1), Some(value.freeze()))
+            .unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::UnsupportedVersion);
+
+    let mut keyless = record(Bytes::new(), None);
+    keyless.key = None;
+    let err = ConsumerOffsetsRecord::decode_record(&keyless).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::InvalidValue);
+}
+
+#[test]
+fn transaction_log() {
+    let key = TransactionLogKey::builder()
+        .transactional_id(StrBytes::from_str("txn"))
+        .build()
+        .unwrap();
+    let value = TransactionLogValue::builder()
+        .producer_id(ProducerId(1000))
+        .producer_epoch(2)
+        .transaction_timeout_ms(60_000)
+        .transaction_status(1)
+        .build()
+        .unwrap();
+
+    let decoded = TransactionStateRecord::decode_record(&record(
+        versioned(&key, 0),
+        Some(versioned(&value, 1)),
+    ))
+    .unwrap();
+    assert_eq!(
+        decoded,
+        TransactionStateRecord::TransactionLog {
+            key: key.clone(),
+            value: Some(value),
+        }
+    );
+
+    let tombstone = TransactionStateRecord::decode(versioned(&key, 0), None).unwrap();
+    assert!(tombstone.is_tombstone());
+}
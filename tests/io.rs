@@ -0,0 +1,65 @@
+#![cfg(feature = "client-core")]
+
+use std::io::{Cursor, ErrorKind};
+
+use kafka_protocol::frame::{read_frame, write_frame};
+use kafka_protocol::messages::{ApiKey, MetadataRequest, RequestHeader, TopicName};
+use kafka_protocol::protocol::{
+    Builder, Decodable, DecodeError, DecodeErrorKind, Encodable, HeaderVersion, StrBytes,
+};
+
+#[test]
+fn write_and_read_frames() {
+    let header = RequestHeader::builder()
+        .request_api_key(ApiKey::MetadataKey as i16)
+        .request_api_version(12)
+        .correlation_id(3)
+        .build()
+        .unwrap();
+    let mut request = MetadataRequest::default();
+    request.topics = Some(vec![]);
+
+    let header_version = MetadataRequest::header_version(12);
+    let mut body = Vec::new();
+    header.write_to(&mut body, header_version).unwrap();
+    let header_len = body.len();
+    request.write_to(&mut body, 12).unwrap();
+    let mut stream = Vec::new();
+    write_frame(&mut stream, &body).unwrap();
+    write_frame(&mut stream, &body).unwrap();
+
+    let mut reader = Cursor::new(stream);
+    for _ in 0..2 {
+        let frame = read_frame(&mut reader, 1024).unwrap();
+        let mut frame = Cursor::new(frame);
+        let decoded_header =
+            RequestHeader::read_from(&mut frame, header_len, header_version).unwrap();
+        assert_eq!(decoded_header, header);
+        let len = frame.get_ref().len() - header_len;
+        let decoded = MetadataRequest::read_from(&mut frame, len, 12).unwrap();
+        assert_eq!(decoded, request);
+    }
+    let err = read_frame(&mut reader, 1024).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn io_errors_wrap_codec_errors() {
+    let err = read_frame(&mut Cursor::new([0x7f, 0xff, 0xff, 0xff]), 1024).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let inner = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<DecodeError>()
+        .unwrap();
+    assert_eq!(inner.kind(), DecodeErrorKind::LimitExceeded);
+
+    let mut request = MetadataRequest::default();
+    request.topics = Some(vec![Default::default()]);
+    request.topics.as_mut().unwrap()[0].name = Some(TopicName(StrBytes::from_str("events")));
+    let mut encoded = Vec::new();
+    request.write_to(&mut encoded, 4).unwrap();
+    let err = MetadataRequest::read_from(&mut Cursor::new(&encoded[..]), encoded.len() - 1, 4)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,114 @@
+#![cfg(all(feature = "json", feature = "admin", feature = "client-core"))]
+
+use bytes::Bytes;
+use kafka_protocol::json::RecordsJson;
+use kafka_protocol::messages::create_topics_response::CreatableTopicResult;
+use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
+use kafka_protocol::messages::{CreateTopicsResponse, ProduceRequest, TopicName};
+use kafka_protocol::protocol::{Builder, StrBytes};
+use serde_json::json;
+use uuid::Uuid;
+
+fn produce_request() -> ProduceRequest {
+    let partition = PartitionProduceData::builder()
+        .index(2)
+        .records(Some(Bytes::from_static(b"abc")))
+        .build()
+        .unwrap();
+    let topic = TopicProduceData::builder()
+        .partition_data(vec![partition])
+        .build()
+        .unwrap();
+    let mut request = ProduceRequest::builder()
+        .acks(-1)
+        .timeout_ms(1000)
+        .build()
+        .unwrap();
+    request
+        .topic_data
+        .insert(TopicName(StrBytes::from_str("events")), topic);
+    request
+}
+
+#[test]
+fn matches_json_converter() {
+    let request = produce_request();
+    assert_eq!(
+        request.to_json(9),
+        json!({
+            "transactionalId": null,
+            "acks": -1,
+            "timeoutMs": 1000,
+            "topicData": [{
+                "name": "events",
+                "partitionData": [{ "index": 2, "recordsSizeInBytes": 3 }]
+            }]
+        })
+    );
+    // transactionalId was only added in version 3
+    assert!(request.to_json(2).get("transactionalId").is_none());
+}
+
+#[test]
+fn writes_records_as_asked() {
+    let request = produce_request();
+    let records = |how| request.to_json_with(9, how)["topicData"][0]["partitionData"][0].clone();
+    assert_eq!(
+        records(RecordsJson::SizeInBytes),
+        json!({ "index": 2, "recordsSizeInBytes": 3 })
+    );
+    assert_eq!(
+        records(RecordsJson::Empty),
+        json!({ "index": 2, "records": "" })
+    );
+    assert_eq!(
+        records(RecordsJson::Base64),
+        json!({ "index": 2, "records": "YWJj" })
+    );
+
+    let json = request.to_json_with(9, RecordsJson::Base64);
+    assert_eq!(ProduceRequest::from_json(&json, 9).unwrap(), request);
+}
+
+#[test]
+fn uuids_and_tagged_fields() {
+    let topic_id = Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+    let mut response = CreateTopicsResponse::default();
+    response.topics.insert(
+        TopicName(StrBytes::from_str("events")),
+        CreatableTopicResult::builder()
+            .topic_id(topic_id)
+            .build()
+            .unwrap(),
+    );
+
+    let value = response.to_json(7);
+    let topic = &value["topics"][0];
+    assert_eq!(topic["topicId"], json!("ASNFZ4mrze8BI0VniavN7w"));
+    // Tagged fields are only written when set
+    assert!(topic.get("topicConfigErrorCode").is_none());
+    assert_eq!(
+        CreateTopicsResponse::from_json(&value, 7).unwrap(),
+        response
+    );
+}
+
+#[test]
+fn reports_missing_mandatory_fields() {
+    let err = ProduceRequest::from_json(
+        &json!({ "transactionalId": null, "acks": 1, "topicData": [] }),
+        9,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "ProduceRequest: unable to locate field 'timeoutMs', which is mandatory in version 9"
+    );
+
+    let err =
+        ProduceRequest::from_json(&json!({ "transactionalId": null, "acks": "x" }), 9).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "ProduceRequest.acks: expected an integer, but got STRING"
+    );
+}
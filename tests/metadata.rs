@@ -0,0 +1,118 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use indexmap::IndexMap;
+use kafka_protocol::messages::{BrokerId, TopicName};
+use kafka_protocol::metadata::{
+    ApiMessageAndVersion, MetadataRecord, MetadataRecordType, PartitionChangeRecord, TopicRecord,
+};
+use kafka_protocol::protocol::{Builder, DecodeErrorKind, StrBytes};
+use kafka_protocol::records::{Record, TimestampType};
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+fn record(value: Bytes) -> Record {
+    Record {
+        transactional: false,
+        control: false,
+        partition_leader_epoch: 0,
+        producer_id: -1,
+        producer_epoch: -1,
+        timestamp_type: TimestampType::Creation,
+        offset: 10,
+        sequence: -1,
+        timestamp: 0,
+        key: None,
+        value: Some(value),
+        headers: IndexMap::new(),
+    }
+}
+
+#[test]
+fn decode_topic_record() {
+    let topic_id = Uuid::from_u128(0x1234);
+    let mut buf = BytesMut::new();
+    // Frame version, api key and record version
+    buf.put_slice(&[1, 2, 0]);
+    // Compact string "events"
+    buf.put_u8(7);
+    buf.put_slice(b"events");
+    buf.put_slice(topic_id.as_bytes());
+    // No tagged fields
+    buf.put_u8(0);
+
+    let frame = ApiMessageAndVersion::decode_record(&record(buf.freeze())).unwrap();
+    assert_eq!(frame.version, 0);
+    assert_eq!(frame.message.record_type(), MetadataRecordType::TopicRecord);
+    match frame.message {
+        MetadataRecord::TopicRecord(topic) => {
+            assert_eq!(topic.name, TopicName(StrBytes::from_str("events")));
+            assert_eq!(topic.topic_id, topic_id);
+        }
+        other => panic!("unexpected record {:?}", other),
+    }
+}
+
+#[test]
+fn round_trip_tagged_fields() {
+    let change = PartitionChangeRecord::builder()
+        .partition_id(3)
+        .topic_id(Uuid::from_u128(7))
+        .isr(Some(vec![BrokerId(1), BrokerId(2)]))
+        .leader(BrokerId(2))
+        .build()
+        .unwrap();
+    let frame = ApiMessageAndVersion::new(change, 0);
+
+    let mut buf = BytesMut::new();
+    frame.encode(&mut buf).unwrap();
+    assert_eq!(buf.len(), frame.compute_size().unwrap());
+    assert_eq!(&buf[..3], &[1, 5, 0]);
+
+    let decoded = ApiMessageAndVersion::decode(&mut buf.freeze()).unwrap();
+    assert_eq!(decoded, frame);
+    match decoded.message {
+        MetadataRecord::PartitionChangeRecord(change) => {
+            assert_eq!(change.replicas, None);
+            assert_eq!(change.leader_recovery_state, -1);
+        }
+        other => panic!("unexpected record {:?}", other),
+    }
+}
+
+#[test]
+fn reject_unknown_frames() {
+    let unknown_frame_version = Bytes::from_static(&[2, 2, 0]);
+    let err = ApiMessageAndVersion::decode(&mut unknown_frame_version.clone()).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::InvalidValue);
+
+    let unknown_record_type = Bytes::from_static(&[1, 100, 0]);
+    let err = ApiMessageAndVersion::decode(&mut unknown_record_type.clone()).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::InvalidValue);
+
+    let unsupported_version = Bytes::from_static(&[1, 2, 5, 0]);
+    let err = ApiMessageAndVersion::decode(&mut unsupported_version.clone()).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::UnsupportedVersion);
+
+    let mut trailing = BytesMut::new();
+    ApiMessageAndVersion::new(TopicRecord::default(), 0)
+        .encode(&mut trailing)
+        .unwrap();
+    trailing.put_u8(0);
+    let err = ApiMessageAndVersion::decode_record(&record(trailing.freeze())).unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::InvalidValue);
+}
+
+#[test]
+fn record_types() {
+    for record_type in MetadataRecordType::all() {
+        assert_eq!(
+            MetadataRecordType::try_from(*record_type as i16),
+            Ok(*record_type)
+        );
+    }
+    assert_eq!(MetadataRecordType::TopicRecord.name(), "TopicRecord");
+    assert_eq!(
+        MetadataRecordType::try_from(13),
+        Err(()),
+        "removed record types are not known"
+    );
+}
@@ -0,0 +1,74 @@
+#![cfg(all(feature = "serde", feature = "client-core"))]
+
+use bytes::Bytes;
+use indexmap::IndexMap;
+use kafka_protocol::error::ResponseError;
+use kafka_protocol::messages::metadata_request::MetadataRequestTopic;
+use kafka_protocol::messages::{MetadataRequest, TopicName};
+use kafka_protocol::protocol::{Builder, StrBytes};
+use kafka_protocol::records::{Record, TimestampType};
+use serde_json::json;
+
+#[test]
+fn message_round_trip() {
+    let topic = MetadataRequestTopic::builder()
+        .name(Some(TopicName(StrBytes::from_str("events"))))
+        .build()
+        .unwrap();
+    let request = MetadataRequest::builder()
+        .topics(Some(vec![topic]))
+        .allow_auto_topic_creation(true)
+        .build()
+        .unwrap();
+
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(value["topics"][0]["name"], json!("events"));
+    assert_eq!(
+        serde_json::from_value::<MetadataRequest>(value).unwrap(),
+        request
+    );
+
+    // Missing fields take their default values
+    let request: MetadataRequest =
+        serde_json::from_value(json!({ "allow_auto_topic_creation": true })).unwrap();
+    assert_eq!(request.topics, Some(vec![]));
+    assert!(request.allow_auto_topic_creation);
+}
+
+#[test]
+fn record_round_trip() {
+    let mut headers = IndexMap::new();
+    headers.insert(
+        StrBytes::from_str("trace"),
+        Some(Bytes::from_static(b"abc")),
+    );
+    let record = Record {
+        transactional: false,
+        control: false,
+        partition_leader_epoch: 0,
+        producer_id: -1,
+        producer_epoch: -1,
+        timestamp_type: TimestampType::Creation,
+        offset: 4,
+        sequence: -1,
+        timestamp: 1000,
+        key: None,
+        value: Some(Bytes::from_static(b"hello")),
+        headers,
+    };
+
+    let json = serde_json::to_string(&record).unwrap();
+    let decoded: Record = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.offset, 4);
+    assert_eq!(decoded.value, record.value);
+    assert_eq!(decoded.headers, record.headers);
+}
+
+#[test]
+fn response_error_round_trip() {
+    let json = serde_json::to_string(&ResponseError::UnknownTopicOrPartition).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ResponseError>(&json).unwrap(),
+        ResponseError::UnknownTopicOrPartition
+    );
+}
@@ -0,0 +1,107 @@
+#![cfg(all(feature = "admin", feature = "client-core"))]
+
+use bytes::{Bytes, BytesMut};
+use kafka_protocol::messages::describe_log_dirs_response::DescribeLogDirsResult;
+use kafka_protocol::messages::metadata_response::{
+    MetadataResponsePartition, MetadataResponseTopic,
+};
+use kafka_protocol::messages::{BrokerId, DescribeLogDirsResponse, MetadataResponse, TopicName};
+use kafka_protocol::protocol::{Builder, Encodable, EncodeErrorKind, StrBytes};
+use string::TryFrom;
+
+fn str_bytes(s: String) -> StrBytes {
+    StrBytes::try_from(Bytes::from(s)).unwrap()
+}
+
+fn topic(i: i32) -> (TopicName, MetadataResponseTopic) {
+    let partitions = (0..4)
+        .map(|p| {
+            MetadataResponsePartition::builder()
+                .partition_index(p)
+                .leader_id(BrokerId(i))
+                .replica_nodes(vec![BrokerId(i)])
+                .build()
+                .unwrap()
+        })
+        .collect();
+    let name = TopicName(str_bytes(format!("topic-{}", i)));
+    let topic = MetadataResponseTopic::builder()
+        .partitions(partitions)
+        .build()
+        .unwrap();
+    (name, topic)
+}
+
+#[test]
+fn streamed_map_matches_encode() {
+    let mut response = MetadataResponse::builder()
+        .controller_id(BrokerId(1))
+        .build()
+        .unwrap();
+    response.topics = (0..10).map(topic).collect();
+
+    let mut skeleton = response.clone();
+    skeleton.topics.clear();
+    for version in [0, 4, 9, 12] {
+        let mut expected = BytesMut::new();
+        response.encode(&mut expected, version).unwrap();
+        let mut streamed = BytesMut::new();
+        skeleton
+            .encode_with_topics(&mut streamed, version, (0..10).map(topic))
+            .unwrap();
+        assert_eq!(streamed, expected);
+    }
+}
+
+#[test]
+fn streamed_array_matches_encode() {
+    let result = |i: i32| {
+        DescribeLogDirsResult::builder()
+            .log_dir(str_bytes(format!("/data/{}", i)))
+            .total_bytes(1 << 30)
+            .build()
+            .unwrap()
+    };
+    let mut response = DescribeLogDirsResponse::default();
+    response.results = (0..3).map(result).collect();
+
+    let skeleton = DescribeLogDirsResponse::default();
+    for version in [0, 2, 4] {
+        let mut expected = BytesMut::new();
+        response.encode(&mut expected, version).unwrap();
+        let mut streamed = BytesMut::new();
+        skeleton
+            .encode_with_results(&mut streamed, version, (0..3).map(result))
+            .unwrap();
+        assert_eq!(streamed, expected);
+    }
+}
+
+/// An iterator which produces fewer elements than it claims to.
+struct Lying(std::ops::Range<i32>);
+
+impl Iterator for Lying {
+    type Item = (TopicName, MetadataResponseTopic);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(topic)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (5, Some(5))
+    }
+}
+
+impl ExactSizeIterator for Lying {}
+
+#[test]
+fn iterators_must_produce_their_length() {
+    let mut buf = BytesMut::new();
+    let err = MetadataResponse::default()
+        .encode_with_topics(&mut buf, 12, Lying(0..3))
+        .unwrap_err();
+    assert_eq!(err.kind(), EncodeErrorKind::InvalidValue);
+    assert!(err.to_string().contains("topics"));
+    // The elements produced before the mismatch was detected have already been written
+    assert!(!buf.is_empty());
+}
@@ -0,0 +1,59 @@
+use bytes::{Bytes, BytesMut};
+use kafka_protocol::messages::ApiVersionsRequest;
+use kafka_protocol::protocol::tagged_fields::UnknownTaggedFields;
+use kafka_protocol::protocol::{Decodable, Encodable, StrBytes};
+
+const REQUEST: [u8; 31] = [
+    // Client software name: apache-kafka-java
+    0x12, 0x61, 0x70, 0x61, 0x63, 0x68, 0x65, 0x2d, 0x6b, 0x61, 0x66, 0x6b, 0x61, 0x2d, 0x6a, 0x61,
+    0x76, 0x61, // Client software version: 2.8.0
+    0x06, 0x32, 0x2e, 0x38, 0x2e, 0x30, // Number of tagged fields
+    0x01, // Tag
+    0x05, // Size
+    0x04, // Value
+    0x00, 0x00, 0x00, 0x2a,
+];
+
+#[test]
+fn unknown_tagged_fields_round_trip() {
+    let buf = Bytes::from_static(&REQUEST);
+    let request = ApiVersionsRequest::decode(&mut buf.clone(), 3).unwrap();
+    assert_eq!(
+        request.client_software_name,
+        StrBytes::from_str("apache-kafka-java")
+    );
+    assert_eq!(request.unknown_tagged_fields.len(), 1);
+    assert_eq!(
+        request.unknown_tagged_fields.get(5),
+        Some(&Bytes::from_static(&[0, 0, 0, 42]))
+    );
+    assert_eq!(
+        request.unknown_tagged_fields.get_as::<i32>(5, 3),
+        Ok(Some(42))
+    );
+    assert_eq!(request.unknown_tagged_fields.get_as::<i32>(6, 3), Ok(None));
+
+    let mut encoded = BytesMut::new();
+    request.encode(&mut encoded, 3).unwrap();
+    assert_eq!(encoded.freeze(), buf);
+}
+
+#[test]
+fn typed_accessors() {
+    let mut fields = UnknownTaggedFields::new();
+    fields
+        .insert_as(9, &Some(StrBytes::from_str("rack-1")), 0)
+        .unwrap();
+    fields.insert_as(2, &true, 0).unwrap();
+    let tags: Vec<i32> = fields.iter().map(|(tag, _)| tag).collect();
+    assert_eq!(tags, vec![2, 9]);
+    assert_eq!(
+        fields.get_as::<Option<StrBytes>>(9, 0).unwrap(),
+        Some(Some(StrBytes::from_str("rack-1")))
+    );
+    assert!(fields.get_as::<i64>(2, 0).is_err());
+    assert_eq!(fields.range(3..).count(), 1);
+
+    assert!(fields.remove(2).is_some());
+    assert_eq!(fields.len(), 1);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "client-core")]
+
+use kafka_protocol::messages::fetch_request::{FetchPartition, FetchTopic};
+use kafka_protocol::messages::{FetchRequest, TopicName};
+use kafka_protocol::protocol::{Builder, Encodable, StrBytes};
+
+fn fetch_request() -> FetchRequest {
+    let partitions = (0..2)
+        .map(|partition| {
+            FetchPartition::builder()
+                .partition(partition)
+                .current_leader_epoch(partition + 10)
+                .build()
+                .unwrap()
+        })
+        .collect();
+    let topic = FetchTopic::builder()
+        .topic(TopicName(StrBytes::from_str("events")))
+        .partitions(partitions)
+        .build()
+        .unwrap();
+    FetchRequest::builder()
+        .replica_id((-1).into())
+        .session_id(3)
+        .topics(vec![topic])
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn validate_lists_invalid_fields() {
+    let request = fetch_request();
+    assert!(request.validate(9).is_empty());
+    assert_eq!(
+        request.validate(6),
+        vec![
+            "session_id",
+            "topics[0].partitions[0].current_leader_epoch",
+            "topics[0].partitions[1].current_leader_epoch",
+        ]
+    );
+}
+
+#[test]
+fn normalize_to_resets_invalid_fields() {
+    let mut request = fetch_request();
+    request.normalize_to(6);
+    assert!(request.validate(6).is_empty());
+    assert_eq!(request.session_id, 0);
+    assert_eq!(request.topics[0].partitions[1].partition, 1);
+    assert_eq!(request.topics[0].partitions[1].current_leader_epoch, -1);
+    request.encode(&mut bytes::BytesMut::new(), 6).unwrap();
+
+    // Fields valid in the target version are left untouched
+    let mut request = fetch_request();
+    request.normalize_to(9);
+    assert_eq!(request, fetch_request());
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "client-core")]
+
+use bytes::{Bytes, BytesMut};
+use indexmap::IndexMap;
+use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
+use kafka_protocol::messages::{ProduceRequest, TopicName};
+use kafka_protocol::protocol::buf::BytesChain;
+use kafka_protocol::protocol::{Builder, Decodable, Encodable, StrBytes};
+use kafka_protocol::records::{
+    Compression, Record, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions, TimestampType,
+};
+
+fn concat(chunks: &[Bytes]) -> Bytes {
+    chunks
+        .iter()
+        .flat_map(|chunk| chunk.iter().copied())
+        .collect()
+}
+
+#[test]
+fn produce_request_records_are_not_copied() {
+    let records = Bytes::from(vec![7; 64 * 1024]);
+    let partition = PartitionProduceData::builder()
+        .index(0)
+        .records(Some(records.clone()))
+        .build()
+        .unwrap();
+    let mut request = ProduceRequest::builder().acks(-1).build().unwrap();
+    request.topic_data.insert(
+        TopicName(StrBytes::from_str("events")),
+        TopicProduceData::builder()
+            .partition_data(vec![partition])
+            .build()
+            .unwrap(),
+    );
+
+    for version in [3, 9] {
+        let chunks = request.encode_to_chunks(version).unwrap();
+        assert_eq!(chunks[1].as_ptr(), records.as_ptr());
+        assert_eq!(chunks[1].len(), records.len());
+        let encoded = concat(&chunks);
+        assert_eq!(encoded, request.encode_to_bytes(version).unwrap());
+        let decoded = ProduceRequest::decode(&mut encoded.clone(), version).unwrap();
+        assert_eq!(decoded, request);
+    }
+}
+
+#[test]
+fn record_batch_gaps_spanning_shared_chunks() {
+    let record = Record {
+        transactional: false,
+        control: false,
+        partition_leader_epoch: 0,
+        producer_id: -1,
+        producer_epoch: -1,
+        timestamp_type: TimestampType::Creation,
+        offset: 0,
+        sequence: -1,
+        timestamp: 1000,
+        key: None,
+        value: Some(Bytes::from(vec![1; 1024])),
+        headers: IndexMap::new(),
+    };
+    for version in [0, 1, 2] {
+        let options = RecordEncodeOptions {
+            version,
+            compression: Compression::None,
+        };
+        let mut expected = BytesMut::new();
+        RecordBatchEncoder::encode(&mut expected, std::iter::once(&record), &options).unwrap();
+        let mut chain = BytesChain::new();
+        RecordBatchEncoder::encode(&mut chain, std::iter::once(&record), &options).unwrap();
+        let mut encoded = concat(&chain.into_chunks());
+        assert_eq!(encoded, expected.freeze());
+        let decoded = RecordBatchDecoder::decode(&mut encoded).unwrap();
+        assert_eq!(decoded[0].value, record.value);
+    }
+}
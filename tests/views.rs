@@ -0,0 +1,139 @@
+#![cfg(feature = "client-core")]
+
+use bytes::{Bytes, BytesMut};
+use kafka_protocol::messages::metadata_response::{
+    MetadataResponseBroker, MetadataResponsePartition, MetadataResponseTopic, MetadataResponseView,
+};
+use kafka_protocol::messages::produce_request::{
+    PartitionProduceData, ProduceRequestView, TopicProduceData,
+};
+use kafka_protocol::messages::{BrokerId, MetadataResponse, ProduceRequest, TopicName};
+use kafka_protocol::protocol::{Builder, DecodeErrorKind, Encodable, StrBytes};
+
+fn encode<T: Encodable>(message: &T, version: i16) -> Bytes {
+    let mut buf = BytesMut::new();
+    message.encode(&mut buf, version).unwrap();
+    buf.freeze()
+}
+
+#[test]
+fn produce_request_records_borrow_the_buffer() {
+    let partition = PartitionProduceData::builder()
+        .index(3)
+        .records(Some(Bytes::from_static(b"record batch")))
+        .build()
+        .unwrap();
+    let mut request = ProduceRequest::builder().acks(-1).build().unwrap();
+    request.topic_data.insert(
+        TopicName(StrBytes::from_str("events")),
+        TopicProduceData::builder()
+            .partition_data(vec![partition])
+            .build()
+            .unwrap(),
+    );
+
+    for version in [3, 9] {
+        let encoded = encode(&request, version);
+        let mut buf = encoded.clone();
+        let view = ProduceRequestView::decode(&mut buf, version).unwrap();
+        assert!(buf.is_empty());
+        assert_eq!(view.acks, -1);
+        assert_eq!(view.transactional_id, None);
+        assert_eq!(view.topic_data.len(), 1);
+
+        let topic = view.topic_data.iter().next().unwrap().unwrap();
+        assert_eq!(topic.name, TopicName(StrBytes::from_str("events")));
+        let partition = topic.partition_data.iter().next().unwrap().unwrap();
+        assert_eq!(partition.index, 3);
+        let records = partition.records.unwrap();
+        assert_eq!(records, Bytes::from_static(b"record batch"));
+        let offset = records.as_ptr() as usize - encoded.as_ptr() as usize;
+        assert!(offset + records.len() <= encoded.len());
+    }
+}
+
+#[test]
+fn metadata_response_nested_arrays() {
+    let mut response = MetadataResponse::default();
+    response.brokers.insert(
+        BrokerId(1),
+        MetadataResponseBroker::builder()
+            .host(StrBytes::from_str("localhost"))
+            .port(9092)
+            .build()
+            .unwrap(),
+    );
+    let partitions = (0..3)
+        .map(|i| {
+            MetadataResponsePartition::builder()
+                .partition_index(i)
+                .leader_id(BrokerId(1))
+                .replica_nodes(vec![BrokerId(1), BrokerId(2)])
+                .build()
+                .unwrap()
+        })
+        .collect();
+    response.topics.insert(
+        TopicName(StrBytes::from_str("events")),
+        MetadataResponseTopic::builder()
+            .partitions(partitions)
+            .build()
+            .unwrap(),
+    );
+
+    let mut buf = encode(&response, 12);
+    let view = MetadataResponseView::decode(&mut buf, 12).unwrap();
+    let broker = view.brokers.iter().next().unwrap().unwrap();
+    assert_eq!(broker.node_id, 1);
+    assert_eq!(broker.host, StrBytes::from_str("localhost"));
+
+    let topic = view.topics.iter().next().unwrap().unwrap();
+    assert_eq!(topic.partitions.len(), 3);
+    for (i, partition) in topic.partitions.iter().enumerate() {
+        let partition = partition.unwrap();
+        assert_eq!(partition.partition_index, i as i32);
+        let replicas: Vec<BrokerId> = partition.replica_nodes.iter().map(Result::unwrap).collect();
+        assert_eq!(replicas, vec![BrokerId(1), BrokerId(2)]);
+    }
+    assert!(view.tagged_fields.is_empty());
+}
+
+#[test]
+fn truncated_views_fail_to_decode() {
+    let request = ProduceRequest::default();
+    let encoded = encode(&request, 9);
+    let mut buf = encoded.slice(..encoded.len() - 1);
+    let err = ProduceRequestView::decode(&mut buf, 9).unwrap_err();
+    assert_eq!(err.message_type(), Some("ProduceRequest"));
+}
+
+#[test]
+fn malformed_elements_fail_when_reached() {
+    let mut request = ProduceRequest::default();
+    for name in ["events", "orders"] {
+        request.topic_data.insert(
+            TopicName(StrBytes::from_str(name)),
+            TopicProduceData::default(),
+        );
+    }
+    let mut encoded = BytesMut::new();
+    request.encode(&mut encoded, 9).unwrap();
+    let offset = encoded
+        .windows(6)
+        .position(|window| window == b"orders")
+        .unwrap();
+    encoded[offset] = 0xff;
+
+    // Only the lengths of the topics are read when decoding the view
+    let view = ProduceRequestView::decode(&mut encoded.freeze(), 9).unwrap();
+    assert_eq!(view.topic_data.len(), 2);
+    let mut topics = view.topic_data.iter();
+    assert_eq!(
+        topics.next().unwrap().unwrap().name,
+        TopicName(StrBytes::from_str("events"))
+    );
+    let err = topics.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), DecodeErrorKind::InvalidUtf8);
+    assert!(err.to_string().contains("[1]"));
+    assert!(topics.next().is_none());
+}